@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+/// Field name of [`Caption`] in post message content.
+pub const CAPTION_FIELD: &str = "org.social.caption";
+
 /// Caption data for media posts.
 /// Field name: `org.social.caption`
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -11,4 +14,8 @@ pub struct Caption {
     /// Formatted caption (HTML)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub formatted_text: Option<String>,
+
+    /// Description of an image for people who can't see it, e.g., read out by screen readers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<String>,
 }
@@ -0,0 +1,21 @@
+use ruma::events::macros::EventContent;
+use serde::{Deserialize, Serialize};
+
+/// Check-in at an event, sent by an attendee once the event has started.
+/// Event type: `org.social.checkin`
+///
+/// This is a timeline event rather than state so that guests, who cannot
+/// send state events in event rooms, can check in. Only the latest check-in
+/// of each user counts.
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "org.social.checkin", kind = MessageLike)]
+#[serde(deny_unknown_fields)]
+pub struct SocialCheckinEventContent {
+    /// Optional short message (e.g., "Found a seat near the stage!")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Optional photo MXC URI
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub photo: Option<ruma::OwnedMxcUri>,
+}
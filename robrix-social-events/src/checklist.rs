@@ -0,0 +1,92 @@
+use ruma::events::macros::EventContent;
+use ruma::{OwnedUserId, UserId};
+use serde::{Deserialize, Serialize};
+
+/// Event type of [`SocialChecklistItemEventContent`].
+pub const CHECKLIST_ITEM_EVENT_TYPE: &str = "org.social.checklist_item";
+
+/// Event type of [`SocialChecklistClaimEventContent`].
+pub const CHECKLIST_CLAIM_EVENT_TYPE: &str = "org.social.checklist_claim";
+
+/// An item on an event's shared checklist, e.g., something to bring or a task.
+/// Event type: `org.social.checklist_item`
+///
+/// The state_key is the item's ID. Like other state in event rooms, only hosts
+/// can send this event; attendees claim items with
+/// [`SocialChecklistClaimEventContent`] instead. Items can't be deleted from
+/// room state, so removed items are marked with `removed` instead.
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "org.social.checklist_item", kind = State, state_key_type = String)]
+#[serde(deny_unknown_fields)]
+pub struct SocialChecklistItemEventContent {
+    /// What to bring or do (e.g., "Potato salad")
+    pub title: String,
+
+    /// Whether this is something to bring or a task
+    pub kind: ChecklistItemKind,
+
+    /// How many are needed; always 1 for tasks
+    #[serde(default = "default_quantity")]
+    pub quantity: u32,
+
+    /// Whether a host checked the item off
+    #[serde(default)]
+    pub done: bool,
+
+    /// Who checked the item off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done_by: Option<OwnedUserId>,
+
+    /// Whether the item has been removed from the checklist
+    #[serde(default)]
+    pub removed: bool,
+}
+
+/// An attendee's claim on a checklist item.
+/// Event type: `org.social.checklist_claim`
+///
+/// Each attendee has their own claim on an item, so that attendees claiming
+/// the same item at once don't overwrite each other's claims. For tasks,
+/// the single claim is the assignment. Guests are allowed to send this event.
+///
+/// The state_key is given by [`checklist_claim_state_key()`].
+///
+/// SECURITY: Any guest can send a claim with any state key. Clients MUST
+/// ignore claims whose state_key doesn't match their `item_id` and `user_id`,
+/// and claims sent by anyone other than `user_id` or a host.
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "org.social.checklist_claim", kind = State, state_key_type = String)]
+#[serde(deny_unknown_fields)]
+pub struct SocialChecklistClaimEventContent {
+    /// The ID of the claimed item
+    pub item_id: String,
+
+    /// The attendee bringing or doing the item
+    pub user_id: OwnedUserId,
+
+    /// How many of the item they're bringing; 0 if they withdrew their claim
+    #[serde(default = "default_quantity")]
+    pub quantity: u32,
+
+    /// Whether the attendee checked off their part
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// Get the state key of a user's claim on a checklist item.
+pub fn checklist_claim_state_key(item_id: &str, user_id: &UserId) -> String {
+    format!("{item_id}_{user_id}")
+}
+
+fn default_quantity() -> u32 {
+    1
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecklistItemKind {
+    /// Something attendees bring, e.g., food or drinks
+    Bring,
+    /// Something that needs doing, e.g., setting up chairs
+    Task,
+}
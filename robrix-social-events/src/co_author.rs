@@ -0,0 +1,46 @@
+use ruma::events::macros::EventContent;
+use ruma::{OwnedEventId, OwnedUserId};
+use serde::{Deserialize, Serialize};
+
+/// Field name of [`CoAuthorship`] in post message content.
+pub const CO_AUTHOR_FIELD: &str = "org.social.co_author";
+
+/// The authors of a collaborative post.
+/// Field name: `org.social.co_author`
+///
+/// The post is sent to the author's feeds, and once the co-author accepts,
+/// to the co-author's public feed too. Every copy carries the same
+/// correlation ID, so that clients can recognize them as the same post.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CoAuthorship {
+    /// The user who wrote the post and invited the co-author.
+    pub author: OwnedUserId,
+
+    /// The user invited to co-author the post.
+    pub co_author: OwnedUserId,
+
+    /// ID shared by all copies of the post
+    pub correlation_id: String,
+}
+
+/// Answer to an invitation to co-author a post.
+/// Event type: `org.social.co_author_response`
+///
+/// Sent by the co-author to the feed room of the original post.
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "org.social.co_author_response", kind = MessageLike)]
+#[serde(deny_unknown_fields)]
+pub struct SocialCoAuthorResponseEventContent {
+    /// Event ID of the original post
+    pub post: OwnedEventId,
+
+    /// Correlation ID of the post (see [`CoAuthorship::correlation_id`])
+    pub correlation_id: String,
+
+    /// Whether the co-author accepted
+    pub accepted: bool,
+
+    /// Event ID of the copy of the post in the co-author's public feed, if accepted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cross_post: Option<OwnedEventId>,
+}
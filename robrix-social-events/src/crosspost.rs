@@ -0,0 +1,8 @@
+/// Field name of a post's cross-post ID in post message content.
+/// Field name: `org.social.crosspost`
+///
+/// The value is a string shared by all copies of a post that was sent to
+/// several feeds at once, e.g., to both the Public and Friends feeds.
+/// Clients show such a post only once, preferring the copy in the most
+/// private feed.
+pub const CROSSPOST_FIELD: &str = "org.social.crosspost";
@@ -36,6 +36,18 @@ pub struct SocialEventEventContent {
     /// RSVP deadline (Unix timestamp in milliseconds)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rsvp_deadline: Option<u64>,
+
+    /// Whether attendees can offer and request rides (see `org.social.ride`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub logistics: bool,
+
+    /// Poll attendees vote in to pick the start time (see `SchedulingPollService`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduling_poll: Option<ruma::OwnedEventId>,
+
+    /// What the host did with the event room after the event ended
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archival: Option<EventArchival>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -59,3 +71,22 @@ pub enum EventVisibility {
     Public,
     Private,
 }
+
+/// What the host did with an event room after the event ended.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventArchival {
+    /// The room stays open for guests to chat in
+    KeptOpen,
+    /// The room is read-only for guests
+    Archived,
+    /// The room is read-only and tombstoned, pointing to another room
+    Closed,
+}
+
+impl EventArchival {
+    /// Whether attendees should list the event among their past events.
+    pub fn is_past(&self) -> bool {
+        matches!(self, Self::Archived | Self::Closed)
+    }
+}
@@ -0,0 +1,78 @@
+use ruma::events::macros::EventContent;
+use ruma::events::EmptyStateKey;
+use serde::{Deserialize, Serialize};
+
+/// Configuration of a feed room, set by the feed's admins.
+/// Event type: `org.social.feed_config`
+#[derive(Clone, Debug, Default, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "org.social.feed_config", kind = State, state_key_type = EmptyStateKey)]
+#[serde(deny_unknown_fields)]
+pub struct SocialFeedConfigEventContent {
+    /// Emoji that may be used to react to posts, in the order they are offered.
+    /// If absent, any emoji may be used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_reactions: Option<Vec<String>>,
+    /// Kinds of media that may be attached to posts.
+    /// If absent, any kind of media may be attached.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_media: Option<Vec<MediaKind>>,
+    /// Maximum size of the media attached to a post, in bytes.
+    /// If absent, media of any size may be attached.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_media_size: Option<u64>,
+}
+
+/// A kind of media that can be attached to a post.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+    /// A photo or other image (`m.image`).
+    Image,
+    /// A video (`m.video`).
+    Video,
+    /// An audio clip, e.g., a voice note (`m.audio`).
+    Audio,
+}
+
+impl MediaKind {
+    /// Get the media kind of a message type, e.g., `m.image`,
+    /// or `None` if it isn't a media message type.
+    pub fn from_msgtype(msgtype: &str) -> Option<Self> {
+        match msgtype {
+            "m.image" => Some(Self::Image),
+            "m.video" => Some(Self::Video),
+            "m.audio" => Some(Self::Audio),
+            _ => None,
+        }
+    }
+
+    /// Get the message type of messages with media of this kind.
+    pub fn msgtype(self) -> &'static str {
+        match self {
+            Self::Image => "m.image",
+            Self::Video => "m.video",
+            Self::Audio => "m.audio",
+        }
+    }
+}
+
+impl SocialFeedConfigEventContent {
+    /// Whether `emoji` may be used to react to posts in the feed.
+    pub fn allows_reaction(&self, emoji: &str) -> bool {
+        self.allowed_reactions
+            .as_ref()
+            .is_none_or(|allowed| allowed.iter().any(|e| e == emoji))
+    }
+
+    /// Whether media of the given kind may be attached to posts in the feed.
+    pub fn allows_media_kind(&self, kind: MediaKind) -> bool {
+        self.allowed_media
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(&kind))
+    }
+
+    /// Whether media of `size` bytes may be attached to posts in the feed.
+    pub fn allows_media_size(&self, size: u64) -> bool {
+        self.max_media_size.is_none_or(|max_size| size <= max_size)
+    }
+}
@@ -0,0 +1,30 @@
+use std::collections::BTreeMap;
+
+use ruma::events::macros::EventContent;
+use ruma::{MilliSecondsSinceUnixEpoch, OwnedRoomId, OwnedUserId};
+use serde::{Deserialize, Serialize};
+
+/// The users whose public feeds the owner follows.
+/// Event type: `org.social.following`
+///
+/// Stored as global account data, so it is private to the owner and
+/// syncs across their devices. Joined feed rooms are the source of truth
+/// for what is shown; this records when and how each follow happened.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "org.social.following", kind = GlobalAccountData)]
+#[serde(deny_unknown_fields)]
+pub struct SocialFollowingEventContent {
+    /// Followed users, keyed by user ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub following: BTreeMap<OwnedUserId, FollowedFeed>,
+}
+
+/// A followed user's public feed.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FollowedFeed {
+    /// Room ID of the followed public feed
+    pub room_id: OwnedRoomId,
+
+    /// When the user was followed
+    pub since: MilliSecondsSinceUnixEpoch,
+}
@@ -7,3 +7,18 @@ pub mod event;
 pub mod rsvp;
 pub mod link_preview;
 pub mod caption;
+pub mod retention;
+pub mod checkin;
+pub mod migration;
+pub mod follow;
+pub mod settings;
+pub mod feed_config;
+pub mod license;
+pub mod co_author;
+pub mod checklist;
+pub mod ride;
+pub mod visibility;
+pub mod location;
+pub mod mute;
+pub mod crosspost;
+pub mod version;
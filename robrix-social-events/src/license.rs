@@ -0,0 +1,86 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Field name of [`ContentLicense`] in post message content.
+pub const LICENSE_FIELD: &str = "org.social.license";
+
+/// License under which a post's content is shared.
+/// Field name: `org.social.license`
+///
+/// Serialized as the license's SPDX identifier, or `all-rights-reserved`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum ContentLicense {
+    /// No rights are granted to others.
+    #[serde(rename = "all-rights-reserved")]
+    AllRightsReserved,
+    /// Public domain dedication.
+    #[serde(rename = "CC0-1.0")]
+    Cc0,
+    /// Creative Commons Attribution.
+    #[serde(rename = "CC-BY-4.0")]
+    CcBy,
+    /// Creative Commons Attribution-ShareAlike.
+    #[serde(rename = "CC-BY-SA-4.0")]
+    CcBySa,
+    /// Creative Commons Attribution-NonCommercial.
+    #[serde(rename = "CC-BY-NC-4.0")]
+    CcByNc,
+    /// Creative Commons Attribution-NonCommercial-ShareAlike.
+    #[serde(rename = "CC-BY-NC-SA-4.0")]
+    CcByNcSa,
+    /// Creative Commons Attribution-NoDerivatives.
+    #[serde(rename = "CC-BY-ND-4.0")]
+    CcByNd,
+    /// Creative Commons Attribution-NonCommercial-NoDerivatives.
+    #[serde(rename = "CC-BY-NC-ND-4.0")]
+    CcByNcNd,
+}
+
+impl ContentLicense {
+    /// All licenses, in the order they are offered.
+    pub const ALL: [Self; 8] = [
+        Self::AllRightsReserved,
+        Self::Cc0,
+        Self::CcBy,
+        Self::CcBySa,
+        Self::CcByNc,
+        Self::CcByNcSa,
+        Self::CcByNd,
+        Self::CcByNcNd,
+    ];
+
+    /// Short name of the license, e.g., "CC BY 4.0".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::AllRightsReserved => "All rights reserved",
+            Self::Cc0 => "CC0 1.0",
+            Self::CcBy => "CC BY 4.0",
+            Self::CcBySa => "CC BY-SA 4.0",
+            Self::CcByNc => "CC BY-NC 4.0",
+            Self::CcByNcSa => "CC BY-NC-SA 4.0",
+            Self::CcByNd => "CC BY-ND 4.0",
+            Self::CcByNcNd => "CC BY-NC-ND 4.0",
+        }
+    }
+
+    /// Web page with the license's terms, if it has one.
+    pub fn url(&self) -> Option<&'static str> {
+        match self {
+            Self::AllRightsReserved => None,
+            Self::Cc0 => Some("https://creativecommons.org/publicdomain/zero/1.0/"),
+            Self::CcBy => Some("https://creativecommons.org/licenses/by/4.0/"),
+            Self::CcBySa => Some("https://creativecommons.org/licenses/by-sa/4.0/"),
+            Self::CcByNc => Some("https://creativecommons.org/licenses/by-nc/4.0/"),
+            Self::CcByNcSa => Some("https://creativecommons.org/licenses/by-nc-sa/4.0/"),
+            Self::CcByNd => Some("https://creativecommons.org/licenses/by-nd/4.0/"),
+            Self::CcByNcNd => Some("https://creativecommons.org/licenses/by-nc-nd/4.0/"),
+        }
+    }
+}
+
+impl fmt::Display for ContentLicense {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -0,0 +1,7 @@
+/// Field name of a post's location in post message content.
+/// Field name: `org.social.location`
+///
+/// The value is an [`EventLocation`](crate::event::EventLocation): the place
+/// the post was made at or is about, in the same format as an event's location.
+/// Clients show a map of the location if it has coordinates.
+pub const LOCATION_FIELD: &str = "org.social.location";
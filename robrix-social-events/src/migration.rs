@@ -0,0 +1,26 @@
+use ruma::{MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId};
+use serde::{Deserialize, Serialize};
+
+/// Field name of [`MigratedFrom`] in migrated message content.
+pub const MIGRATED_FROM_FIELD: &str = "org.social.migrated_from";
+
+/// Origin of a post that was re-posted while migrating to another account.
+/// Field name: `org.social.migrated_from`
+///
+/// Clients should display `origin_server_ts` as the post's time
+/// instead of the time it was re-posted.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MigratedFrom {
+    /// Event ID of the original post
+    pub event_id: OwnedEventId,
+
+    /// Room the original post was sent to
+    pub room_id: OwnedRoomId,
+
+    /// Account that sent the original post
+    pub sender: OwnedUserId,
+
+    /// When the original post was sent
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+}
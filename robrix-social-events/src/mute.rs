@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+
+use ruma::events::macros::EventContent;
+use ruma::{MilliSecondsSinceUnixEpoch, OwnedUserId};
+use serde::{Deserialize, Serialize};
+
+/// The authors whose posts the owner has muted in their newsfeed.
+/// Event type: `org.social.mutes`
+///
+/// Stored as global account data, so it is private to the owner and
+/// syncs across their devices. Unlike blocking, muting only hides posts;
+/// muted users can still follow the owner and see their feeds.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "org.social.mutes", kind = GlobalAccountData)]
+#[serde(deny_unknown_fields)]
+pub struct SocialMutesEventContent {
+    /// Muted authors, keyed by user ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub authors: BTreeMap<OwnedUserId, MutedAuthor>,
+}
+
+/// A muted author.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MutedAuthor {
+    /// When the author was muted
+    pub since: MilliSecondsSinceUnixEpoch,
+
+    /// When the mute expires (Unix timestamp in milliseconds).
+    /// If absent, the author stays muted until unmuted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+impl MutedAuthor {
+    /// Returns whether the mute is still in effect at the given time
+    /// (Unix timestamp in milliseconds).
+    pub fn is_active_at(&self, now_ms: u64) -> bool {
+        self.expires_at.is_none_or(|expires_at| now_ms < expires_at)
+    }
+}
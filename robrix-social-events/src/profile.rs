@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 /// Custom profile data stored as room state in a user's profile room.
 /// Event type: `org.social.profile`
-#[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, EventContent)]
 #[ruma_event(type = "org.social.profile", kind = State, state_key_type = EmptyStateKey)]
 #[serde(deny_unknown_fields)]
 pub struct SocialProfileEventContent {
@@ -16,6 +16,14 @@ pub struct SocialProfileEventContent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub location: Option<String>,
 
+    /// User's pronouns, e.g., "she/her"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pronouns: Option<String>,
+
+    /// User's birthday as an ISO 8601 date, e.g., "1990-04-23"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub birthday: Option<String>,
+
     /// User's website URL
     #[serde(skip_serializing_if = "Option::is_none")]
     pub website: Option<url::Url>,
@@ -24,7 +32,32 @@ pub struct SocialProfileEventContent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cover_image: Option<ruma::OwnedMxcUri>,
 
+    /// Profile avatar MXC URI, shown instead of the account avatar
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<ruma::OwnedMxcUri>,
+
+    /// Which social counts are hidden from other users
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counts_privacy: Option<ProfileCountsPrivacy>,
+
     /// Additional custom fields (for extensibility)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom: Option<serde_json::Value>,
 }
+
+/// Per-user settings for hiding follower, following, and friend counts
+/// on their profile. The owner always sees their own counts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ProfileCountsPrivacy {
+    /// Hide the number of followers from others
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hide_followers: bool,
+
+    /// Hide the number of followed users from others
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hide_following: bool,
+
+    /// Hide the number of friends from others
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hide_friends: bool,
+}
@@ -0,0 +1,24 @@
+use std::collections::BTreeSet;
+
+use ruma::events::macros::EventContent;
+use ruma::OwnedEventId;
+use serde::{Deserialize, Serialize};
+
+/// Retention policy for the owner's posts in one of their feed rooms.
+/// Event type: `org.social.retention`
+///
+/// Stored as room account data on the feed room, so it is private to the
+/// feed owner and never visible to followers.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "org.social.retention", kind = RoomAccountData)]
+#[serde(deny_unknown_fields)]
+pub struct SocialRetentionEventContent {
+    /// Posts older than this many months are redacted.
+    /// If absent, posts are kept forever.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age_months: Option<u32>,
+
+    /// Posts that are never redacted by the retention policy
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub pinned: BTreeSet<OwnedEventId>,
+}
@@ -0,0 +1,42 @@
+use ruma::events::macros::EventContent;
+use serde::{Deserialize, Serialize};
+
+/// Event type of [`SocialRideEventContent`].
+pub const RIDE_EVENT_TYPE: &str = "org.social.ride";
+
+/// An attendee's ride offer or request for an event.
+/// Event type: `org.social.ride`
+///
+/// Only used in events with logistics enabled. Like checklist claims,
+/// guests are allowed to send this event.
+///
+/// SECURITY: The state_key MUST equal the sender's user ID.
+/// Clients MUST validate this and ignore events where they don't match.
+#[derive(Clone, Debug, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "org.social.ride", kind = State, state_key_type = ruma::OwnedUserId)]
+#[serde(deny_unknown_fields)]
+pub struct SocialRideEventContent {
+    /// Whether the attendee offers or needs a ride
+    pub kind: RideKind,
+
+    /// Free seats offered, or seats needed
+    pub seats: u32,
+
+    /// Where the attendee is coming from (e.g., "Downtown")
+    pub area: String,
+
+    /// Optional note (e.g., "Leaving at 6pm")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+
+    /// Whether the attendee withdrew their offer or request
+    #[serde(default)]
+    pub withdrawn: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RideKind {
+    Offer,
+    Request,
+}
@@ -0,0 +1,123 @@
+use ruma::events::macros::EventContent;
+use serde::{Deserialize, Serialize};
+
+use crate::license::ContentLicense;
+
+/// Per-account settings for social features.
+/// Event type: `org.social.settings`
+///
+/// Stored as global account data. Server administrators can also set it
+/// on a user's behalf to turn social features on or off for that account.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, EventContent)]
+#[ruma_event(type = "org.social.settings", kind = GlobalAccountData)]
+#[serde(deny_unknown_fields)]
+pub struct SocialSettingsEventContent {
+    /// Whether social features are enabled for this account.
+    /// If absent, the homeserver's default applies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// License preselected for new posts.
+    /// If absent, posts are made without a license.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_license: Option<ContentLicense>,
+
+    /// Whether to avoid fetching anything from third-party services,
+    /// e.g., weather forecasts for events.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub privacy_mode: bool,
+
+    /// Whether to show a placeholder instead of fetching map images
+    /// for event and post locations. Privacy mode also stops map fetches.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub remote_maps_disabled: bool,
+
+    /// The newsfeed filter, as saved by the client.
+    /// Kept as raw JSON, as the available filters differ between clients.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_filter: Option<serde_json::Value>,
+
+    /// How the newsfeed groups bursts of posts from one author, as saved by the client.
+    /// Kept as raw JSON, like the newsfeed filter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_grouping: Option<serde_json::Value>,
+
+    /// Name of the ranking algorithm used for the newsfeed.
+    /// If absent, or unknown to the client, the client's default sort order applies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_ranker: Option<String>,
+
+    /// Whether to show developer diagnostics over the newsfeed,
+    /// e.g., how long each feed room took to load.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub feed_diagnostics: bool,
+
+    /// How much space the client may use for caching social data.
+    #[serde(default, skip_serializing_if = "CacheLimits::is_unlimited")]
+    pub cache_limits: CacheLimits,
+
+    /// How densely the client lays out posts in the newsfeed.
+    #[serde(default, skip_serializing_if = "FeedDensity::is_default")]
+    pub feed_density: FeedDensity,
+
+    /// Whether to hide the card with friends' upcoming birthdays in the newsfeed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub birthdays_hidden: bool,
+
+    /// The service posts are translated with.
+    /// If absent, posts aren't translated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translation: Option<TranslationSettings>,
+}
+
+/// A LibreTranslate-compatible service that posts are translated with.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TranslationSettings {
+    /// The base URL of the service, e.g., `https://libretranslate.example.org/`.
+    pub endpoint: String,
+
+    /// The API key, if the service requires one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+/// How densely posts are laid out in the newsfeed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedDensity {
+    /// Roomy posts with large avatars and media.
+    #[default]
+    Comfortable,
+    /// Tighter posts with smaller avatars and media, to fit more on screen.
+    Compact,
+}
+
+impl FeedDensity {
+    /// Whether this is the default density.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Limits on the size of the data a client caches for social features.
+///
+/// Clients drop the oldest cached data once a cache grows past its limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CacheLimits {
+    /// Maximum size of cached media, e.g., map images, in bytes.
+    /// If absent, media is cached without a size limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_media_bytes: Option<u64>,
+
+    /// Maximum size of the cached newsfeed, in bytes.
+    /// If absent, the newsfeed is cached without a size limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_feed_cache_bytes: Option<u64>,
+}
+
+impl CacheLimits {
+    /// Whether no cache has a size limit.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_media_bytes.is_none() && self.max_feed_cache_bytes.is_none()
+    }
+}
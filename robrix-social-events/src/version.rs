@@ -0,0 +1,11 @@
+/// The newest version of the social event schemas this crate supports.
+///
+/// Events sent without a version field are of version 1.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The field of a social event's content holding the version of the schema
+/// it was sent with, if newer than 1.
+///
+/// Clients that only support older versions may not be able to show the event,
+/// and should suggest updating instead.
+pub const SCHEMA_VERSION_FIELD: &str = "org.social.version";
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// Field name of [`PostVisibility`] in post message content.
+pub const VISIBILITY_FIELD: &str = "org.social.visibility";
+
+/// The audience a post is intended for.
+/// Field name: `org.social.visibility`
+///
+/// Rooms have their own privacy, but a post can end up in a room with a wider
+/// audience than intended, e.g., when it is shared or cross-posted. Clients
+/// MUST NOT show a post in a room whose audience is wider than the post's
+/// intended audience, and MUST NOT share it to such a room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostVisibility {
+    /// Anyone can see the post.
+    Public,
+    /// Only the author's friends.
+    Friends,
+    /// Only the author's close friends.
+    CloseFriends,
+    /// Only the members of the room the post was sent to.
+    Private,
+}
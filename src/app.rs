@@ -183,6 +183,15 @@ live_design! {
 
 app_main!(App);
 
+/// Whether the app uses Makepad's desktop dark theme instead of its light one.
+///
+/// The dark theme is chosen at startup by setting the `ROBRIX_THEME`
+/// environment variable to `dark`; any other value, or none, keeps the light theme.
+/// Social widgets follow it, see `App::live_register()`.
+fn use_dark_theme() -> bool {
+    std::env::var("ROBRIX_THEME").is_ok_and(|theme| theme.eq_ignore_ascii_case("dark"))
+}
+
 #[derive(Live)]
 pub struct App {
     #[live]
@@ -204,8 +213,15 @@ impl LiveRegister for App {
         // then `shared`` widgets (in which styles are defined),
         // then other modules widgets.
         makepad_widgets::live_design(cx);
-        // Override Makepad's default desktop dark theme with the desktop light theme.
-        cx.link(id!(theme), id!(theme_desktop_light));
+        // Override Makepad's default desktop dark theme with the desktop light theme,
+        // unless the dark theme is chosen with `ROBRIX_THEME`, see `use_dark_theme()`.
+        let use_dark_theme = use_dark_theme();
+        let theme = if use_dark_theme {
+            id!(theme_desktop_dark)
+        } else {
+            id!(theme_desktop_light)
+        };
+        cx.link(id!(theme), theme);
         crate::shared::live_design(cx);
 
         // If the `tsp` cargo feature is enabled, we create a new "tsp_link" DSL namespace
@@ -223,6 +239,26 @@ impl LiveRegister for App {
             cx.link(id!(tsp_link), id!(tsp_disabled));
         }
 
+        // Same as above, for the "social_link" DSL namespace and the `social` feature.
+        #[cfg(feature = "social")]
+        {
+            crate::social::live_design(cx);
+            cx.link(id!(social_link), id!(social_enabled));
+            // Social widgets take their colors from the "social_theme" DSL namespace,
+            // which follows the app theme above.
+            let social_theme = if use_dark_theme {
+                id!(social_theme_dark)
+            } else {
+                id!(social_theme_light)
+            };
+            cx.link(id!(social_theme), social_theme);
+        }
+        #[cfg(not(feature = "social"))]
+        {
+            crate::social_dummy::live_design(cx);
+            cx.link(id!(social_link), id!(social_disabled));
+        }
+
         crate::settings::live_design(cx);
         crate::room::live_design(cx);
         crate::join_leave_room_modal::live_design(cx);
@@ -287,6 +323,12 @@ impl MatchEvent for App {
 
         self.update_login_visibility(cx);
 
+        // Robrix may have been launched by the system to open a profile link,
+        // which is opened once the user is logged in. This only covers links
+        // passed as arguments, i.e., on Linux and Windows.
+        #[cfg(feature = "social")]
+        crate::social::profile_links::record_launch_profile_link(std::env::args().skip(1));
+
         log!("App::Startup: starting matrix sdk loop");
         let _tokio_rt_handle = crate::sliding_sync::start_matrix_tokio().unwrap();
 
@@ -346,6 +388,10 @@ impl MatchEvent for App {
                 log!("Received LoginAction::LoginSuccess, hiding login view.");
                 self.app_state.logged_in = true;
                 self.update_login_visibility(cx);
+                #[cfg(feature = "social")]
+                if let Some(user_id) = crate::social::profile_links::take_launch_profile_link() {
+                    cx.action(NavigationBarAction::GoToSocialProfile { user_id });
+                }
                 self.ui.redraw(cx);
                 continue;
             }
@@ -660,6 +706,10 @@ impl AppMain for App {
             }
         }
 
+        // Drive the shared ticker used by social widgets for relative times.
+        #[cfg(feature = "social")]
+        crate::social::ticker::handle_event(cx, event);
+
         // Forward events to the MatchEvent trait implementation.
         self.match_event(cx, event);
         let scope = &mut Scope::with_data(&mut self.app_state);
@@ -771,9 +821,15 @@ impl App {
             room_to_close,
         );
 
-        // Before we navigate to the room, if the AddRoom tab is currently shown,
-        // then we programmatically navigate to the Home tab to show the actual room.
-        if matches!(self.app_state.selected_tab, SelectedTab::AddRoom) {
+        // Before we navigate to the room, if the AddRoom tab, the settings or a social page
+        // is currently shown, then we programmatically navigate to the Home tab to show the actual room.
+        if matches!(
+            self.app_state.selected_tab,
+            SelectedTab::AddRoom
+                | SelectedTab::Settings
+                | SelectedTab::SocialProfile { .. }
+                | SelectedTab::Newsfeed
+        ) {
             cx.action(NavigationBarAction::GoToHome);
         }
         cx.widget_action(
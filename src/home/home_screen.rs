@@ -5,6 +5,12 @@ use crate::{
     home::navigation_tab_bar::{NavigationBarAction, SelectedTab},
     settings::settings_screen::SettingsScreenWidgetRefExt,
 };
+#[cfg(feature = "social")]
+use crate::social::widgets::{
+    main_feed_page::SocialMainFeedPageWidgetRefExt, profile_page::SocialProfilePageWidgetRefExt,
+};
+#[cfg(not(feature = "social"))]
+use crate::social_dummy::{SocialMainFeedPageWidgetRefExt, SocialProfilePageWidgetRefExt};
 
 live_design! {
     use link::theme::*;
@@ -21,6 +27,9 @@ live_design! {
     use crate::shared::room_filter_input_bar::RoomFilterInputBar;
     use crate::home::main_desktop_ui::MainDesktopUI;
     use crate::settings::settings_screen::SettingsScreen;
+    use link::social_link::SocialProfilePage;
+    use link::social_link::SocialMainFeedPage;
+    use link::social_link::SocialGate;
 
     // Defines the total height of the StackNavigationView's header.
     // This has to be set in multiple places because of how StackNavigation
@@ -148,6 +157,26 @@ live_design! {
                             add_room_screen = <AddRoomScreen> {}
                         }
                     }
+
+                    social_profile_page = <View> {
+                        width: Fill, height: Fill
+
+                        <SocialGate> {
+                            <CachedWidget> {
+                                social_profile_screen = <SocialProfilePage> {}
+                            }
+                        }
+                    }
+
+                    newsfeed_page = <View> {
+                        width: Fill, height: Fill
+
+                        <SocialGate> {
+                            <CachedWidget> {
+                                main_feed_screen = <SocialMainFeedPage> {}
+                            }
+                        }
+                    }
                 }
             }
 
@@ -200,6 +229,28 @@ live_design! {
                                         add_room_screen = <AddRoomScreen> {}
                                     }
                                 }
+
+                                social_profile_page = <View> {
+                                    width: Fill, height: Fill
+                                    padding: {top: 20}
+
+                                    <SocialGate> {
+                                        <CachedWidget> {
+                                            social_profile_screen = <SocialProfilePage> {}
+                                        }
+                                    }
+                                }
+
+                                newsfeed_page = <View> {
+                                    width: Fill, height: Fill
+                                    padding: {top: 20}
+
+                                    <SocialGate> {
+                                        <CachedWidget> {
+                                            main_feed_screen = <SocialMainFeedPage> {}
+                                        }
+                                    }
+                                }
                             }
 
                             // Show the SpacesBar right above the navigation tab bar.
@@ -457,6 +508,47 @@ impl Widget for HomeScreen {
                             self.view.redraw(cx);
                         }
                     }
+                    Some(NavigationBarAction::GoToSocialProfile { user_id }) => {
+                        let new_selection = SelectedTab::SocialProfile {
+                            user_id: user_id.clone(),
+                        };
+                        if app_state.selected_tab != new_selection {
+                            self.previous_selection = app_state.selected_tab.clone();
+                            app_state.selected_tab = new_selection;
+                            cx.action(NavigationBarAction::TabSelected(
+                                app_state.selected_tab.clone(),
+                            ));
+                        }
+                        if let Some(social_profile_page) =
+                            self.update_active_page_from_selection(cx, app_state)
+                        {
+                            social_profile_page
+                                .social_profile_page(ids!(social_profile_screen))
+                                .show_user(cx, user_id.clone());
+                            self.view.redraw(cx);
+                        } else {
+                            error!("BUG: failed to set active page to show social profile page.");
+                        }
+                    }
+                    Some(NavigationBarAction::GoToNewsfeed) => {
+                        if !matches!(app_state.selected_tab, SelectedTab::Newsfeed) {
+                            self.previous_selection = app_state.selected_tab.clone();
+                            app_state.selected_tab = SelectedTab::Newsfeed;
+                            cx.action(NavigationBarAction::TabSelected(
+                                app_state.selected_tab.clone(),
+                            ));
+                            if let Some(newsfeed_page) =
+                                self.update_active_page_from_selection(cx, app_state)
+                            {
+                                newsfeed_page
+                                    .social_main_feed_page(ids!(main_feed_screen))
+                                    .show(cx);
+                                self.view.redraw(cx);
+                            } else {
+                                error!("BUG: failed to set active page to show the newsfeed.");
+                            }
+                        }
+                    }
                     // Only open the settings screen if it is not currently open.
                     Some(NavigationBarAction::OpenSettings) => {
                         if !matches!(app_state.selected_tab, SelectedTab::Settings) {
@@ -528,6 +620,8 @@ impl HomeScreen {
                     SelectedTab::Space { .. } | SelectedTab::Home => id!(home_page),
                     SelectedTab::Settings => id!(settings_page),
                     SelectedTab::AddRoom => id!(add_room_page),
+                    SelectedTab::SocialProfile { .. } => id!(social_profile_page),
+                    SelectedTab::Newsfeed => id!(newsfeed_page),
                 },
             )
     }
@@ -6,6 +6,8 @@
 //!
 //! Their order in Mobile view (horizontally from left to right) is:
 //! 1. Home (house icon): the main view that shows all rooms across all spaces.
+//!    * Followed by the Newsfeed (list icon), which shows the posts of all joined
+//!      social feeds. It's only shown while social features are enabled.
 //! 2. Add Room (plus sign icon): a separate view that allows adding (joining) existing rooms,
 //!    exploring public rooms, or creating new rooms/spaces.
 //! 3. Spaces: a button that toggles the `SpacesBar` (shows/hides it).
@@ -20,6 +22,7 @@
 //!
 //! The order in Desktop view (vertically from top to bottom) is:
 //! 1. Home
+//!    * Newsfeed
 //! 2. Add/Join
 //! 3. ----- separator -----
 //!      SpacesBar content
@@ -29,6 +32,7 @@
 //!
 
 use makepad_widgets::*;
+use matrix_sdk::ruma::OwnedUserId;
 use crate::{
     avatar_cache::{self, AvatarCacheEntry},
     login::login_screen::LoginAction,
@@ -58,6 +62,9 @@ live_design! {
     use crate::shared::avatar::*;
     use crate::shared::icon_button::*;
     use crate::home::spaces_bar::*;
+    use link::social_link::SocialGate;
+
+    ICON_NEWSFEED = dep("crate://self/resources/icons/menu.svg")
 
     // A RadioButton styled to fit within our NavigationTabBar.
     pub NavigationTabButton = <RadioButton> {
@@ -193,6 +200,19 @@ live_design! {
         animator: { active = { default: on } }
     }
 
+    NewsfeedButton = <NavigationTabButton> {
+        draw_icon: { svg_file: (ICON_NEWSFEED) }
+    }
+
+    // The newsfeed button, only shown while social features are enabled.
+    SocialNewsfeedButton = <SocialGate> {
+        width: Fill, height: Fit
+
+        <CachedWidget> {
+            newsfeed_button = <NewsfeedButton> {}
+        }
+    }
+
     ToggleSpacesBarButton = <RobrixIconButton> {
         width: Fill,
         padding: 16
@@ -239,6 +259,8 @@ live_design! {
                 home_button = <HomeButton> {}
             }
 
+            <SocialNewsfeedButton> {}
+
             <CachedWidget> {
                 add_room_button = <AddRoomButton> {}
             }
@@ -272,6 +294,8 @@ live_design! {
                 home_button = <HomeButton> {}
             }
 
+            <SocialNewsfeedButton> {}
+
             <CachedWidget> {
                 add_room_button = <AddRoomButton> {}
             }
@@ -453,11 +477,13 @@ impl Widget for NavigationTabBar {
                 home_button,
                 add_room_button,
                 settings_button,
+                newsfeed_button,
             ));
             match radio_button_set.selected(cx, actions) {
                 Some(0) => cx.action(NavigationBarAction::GoToHome),
                 Some(1) => cx.action(NavigationBarAction::GoToAddRoom),
                 Some(2) => cx.action(NavigationBarAction::OpenSettings),
+                Some(3) => cx.action(NavigationBarAction::GoToNewsfeed),
                 _ => {}
             }
 
@@ -486,7 +512,12 @@ impl Widget for NavigationTabBar {
                             .view
                             .radio_button(ids!(settings_button))
                             .select(cx, scope),
-                        SelectedTab::Space { .. } => {
+                        SelectedTab::Newsfeed => self
+                            .view
+                            .radio_button(ids!(newsfeed_button))
+                            .select(cx, scope),
+                        // Spaces and social profiles don't have a tab button of their own.
+                        SelectedTab::Space { .. } | SelectedTab::SocialProfile { .. } => {
                             for rb in radio_button_set.iter() {
                                 if let Some(mut rb_inner) = rb.borrow_mut() {
                                     rb_inner.animator_play(cx, ids!(active.off));
@@ -512,10 +543,16 @@ pub enum SelectedTab {
     Home,
     AddRoom,
     Settings,
+    /// The main newsfeed, with the posts of all joined social feeds.
+    Newsfeed,
     // AlertsInbox,
     Space {
         space_name_id: RoomNameId,
     },
+    /// The social profile page of the given user.
+    SocialProfile {
+        user_id: OwnedUserId,
+    },
 }
 
 /// Actions for navigating through the top-level views of the app,
@@ -538,7 +575,8 @@ pub enum SelectedTab {
 /// There are 3 kinds of actions within this one enum:
 /// 1. "Leading-edge" ("request") actions emitted by the NavigationTabBar
 ///    when the user selects a particular button/space.
-///    * Includes `GoToHome`, `GoToAddRoom`, `GoToSpace`, `OpenSettings`, `CloseSettings`.
+///    * Includes `GoToHome`, `GoToAddRoom`, `GoToSpace`, `GoToSocialProfile`,
+///      `GoToNewsfeed`, `OpenSettings`, `CloseSettings`.
 /// 2. "Trailing-edge" ("response") actions that are emitted by the `HomeScreen` widget
 ///    in response to a leading-edge action.
 ///    * This includes only the `TabSelected` variant.
@@ -558,6 +596,11 @@ pub enum NavigationBarAction {
     CloseSettings,
     /// Go the space screen for the given space.
     GoToSpace { space_name_id: RoomNameId },
+    /// Go to the social profile page of the given user,
+    /// e.g., from the chat-side user profile pane.
+    GoToSocialProfile { user_id: OwnedUserId },
+    /// Go to the main newsfeed.
+    GoToNewsfeed,
 
     // TODO: add GoToAlertsInbox, once we add that button/screen
     /// The given tab was selected as the active top-level view.
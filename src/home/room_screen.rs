@@ -1754,6 +1754,18 @@ impl RoomScreen {
         action: &Action,
         pane: &UserProfileSlidingPaneRef,
     ) -> bool {
+        // Links that open a profile in Robrix go straight to the social profile page.
+        #[cfg(feature = "social")]
+        if let HtmlLinkAction::Clicked { url, .. } = action.as_widget_action().cast()
+            && url.starts_with(crate::social::PROFILE_LINK_PREFIX)
+            && let Some(user_id) = crate::social::parse_profile_link(&url)
+        {
+            cx.action(
+                crate::home::navigation_tab_bar::NavigationBarAction::GoToSocialProfile { user_id },
+            );
+            return true;
+        }
+
         // A closure that handles both MatrixToUri and MatrixUri links,
         // and returns whether the link was handled.
         let mut handle_matrix_link = |id: &MatrixId, _via: &[OwnedServerName]| -> bool {
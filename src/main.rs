@@ -10,5 +10,11 @@
 )]
 
 fn main() {
+    // Run a headless subcommand instead of the app, e.g., `robrix social post "Hello"`.
+    #[cfg(feature = "cli")]
+    if let Some(exit_code) = robrix::social::cli::run_from_args() {
+        std::process::exit(exit_code);
+    }
+
     robrix::app::app_main()
 }
@@ -11,6 +11,7 @@ use matrix_sdk::{
 };
 use crate::{
     avatar_cache::{self, AvatarCacheEntry},
+    home::navigation_tab_bar::NavigationBarAction,
     shared::{
         avatar::{AvatarState, AvatarWidgetExt},
         popup_list::{PopupItem, PopupKind, enqueue_popup_notification},
@@ -230,6 +231,21 @@ live_design! {
                 text: "Copy Link to User"
             }
 
+            // Only shown if social features are enabled, see draw_walk()
+            view_social_profile_button = <RobrixIconButton> {
+                visible: false,
+                padding: {top: 10, bottom: 10, left: 12, right: 15}
+                margin: 0,
+                draw_bg: {
+                    color: (COLOR_SECONDARY)
+                }
+                draw_icon: {
+                    svg_file: (ICON_EXTERNAL_LINK)
+                }
+                icon_walk: {width: 16, height: 16, margin: {left: -1, right: -1} }
+                text: "View Social Profile"
+            }
+
             jump_to_read_receipt_button = <RobrixIconButton> {
                 enabled: false, // TODO: support this button
                 padding: {top: 10, bottom: 10, left: 12, right: 15}
@@ -557,6 +573,18 @@ impl Widget for UserProfileSlidingPane {
                 });
             }
 
+            if self
+                .button(ids!(view_social_profile_button))
+                .clicked(actions)
+            {
+                cx.action(NavigationBarAction::GoToSocialProfile {
+                    user_id: info.user_id.clone(),
+                });
+                self.animator_play(cx, ids!(panel.hide));
+                self.redraw(cx);
+                return;
+            }
+
             // TODO: implement the third button: `jump_to_read_receipt_button`,
             //       which involves calling `Timeline::latest_user_read_receipt()`
             //       or `Room::load_user_receipt()`, which are async functions.
@@ -614,6 +642,7 @@ impl Widget for UserProfileSlidingPane {
         // * `direct_message_button` is disabled if the user is the same as the account user,
         //    since you cannot direct message yourself.
         // * `copy_link_to_user_button` is always enabled with the same text.
+        // * `view_social_profile_button` is only shown if social features are enabled.
         // * `jump_to_read_receipt_button` is always enabled with the same text.
         // * `ignore_user_button` is disabled if the user is not a member of the room,
         //    or if the user is the same as the account user, since you cannot ignore yourself.
@@ -627,6 +656,13 @@ impl Widget for UserProfileSlidingPane {
         // TODO: uncomment the line below once the `direct_message_button` logic is implemented.
         // self.button(ids!(direct_message_button)).set_enabled(!is_pane_showing_current_account);
 
+        #[cfg(feature = "social")]
+        let is_social_enabled = crate::social::is_social_enabled();
+        #[cfg(not(feature = "social"))]
+        let is_social_enabled = false;
+        self.button(ids!(view_social_profile_button))
+            .set_visible(cx, is_social_enabled);
+
         let ignore_user_button = self.button(ids!(ignore_user_button));
         ignore_user_button.set_enabled(
             cx,
@@ -727,6 +763,9 @@ impl UserProfileSlidingPane {
         self.view
             .button(ids!(copy_link_to_user_button))
             .reset_hover(cx);
+        self.view
+            .button(ids!(view_social_profile_button))
+            .reset_hover(cx);
         self.view
             .button(ids!(jump_to_read_receipt_button))
             .reset_hover(cx);
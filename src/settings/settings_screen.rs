@@ -5,6 +5,10 @@ use crate::{
     profile::user_profile::UserProfile,
     settings::account_settings::AccountSettingsWidgetExt,
 };
+#[cfg(feature = "social")]
+use crate::social::widgets::social_settings::SocialSettingsScreenWidgetExt;
+#[cfg(not(feature = "social"))]
+use crate::social_dummy::SocialSettingsScreenWidgetExt;
 
 live_design! {
     use link::theme::*;
@@ -19,6 +23,8 @@ live_design! {
     use link::tsp_link::TspSettingsScreen;
     use link::tsp_link::CreateWalletModal;
     use link::tsp_link::CreateDidModal;
+    use link::social_link::SocialGate;
+    use link::social_link::SocialSettingsScreen;
 
     // The main, top-level settings screen widget.
     pub SettingsScreen = {{SettingsScreen}} {
@@ -84,6 +90,14 @@ live_design! {
 
                 <LineH> { width: 400, padding: 10, margin: {top: 20, bottom: 5} }
 
+                // The social settings section, hidden while social features are disabled.
+                <SocialGate> {
+                    height: Fit
+                    social_settings_screen = <SocialSettingsScreen> {}
+                }
+
+                <LineH> { width: 400, padding: 10, margin: {top: 20, bottom: 5} }
+
                 // Add other settings sections here as needed.
                 // Don't forget to add a `show()` fn to those settings sections
                 // and call them in `SettingsScreen::show()`.
@@ -223,6 +237,9 @@ impl SettingsScreen {
         self.view
             .account_settings(ids!(account_settings))
             .populate(cx, profile);
+        self.view
+            .social_settings_screen(ids!(social_settings_screen))
+            .refresh(cx);
         self.view.button(ids!(close_button)).reset_hover(cx);
         cx.set_key_focus(self.view.area());
         self.redraw(cx);
@@ -13,7 +13,7 @@ use matrix_sdk::{
     ruma::{EventId, OwnedRoomId, OwnedUserId, RoomId, UserId},
 };
 use matrix_sdk_ui::timeline::{Profile, TimelineDetails};
-use ruma::OwnedMxcUri;
+use ruma::{MxcUri, OwnedMxcUri};
 
 use crate::{
     avatar_cache::{self, AvatarCacheEntry},
@@ -258,6 +258,38 @@ impl Avatar {
         }
     }
 
+    /// Shows the avatar image at the given Matrix URI, fetching its thumbnail
+    /// into the avatar cache if it isn't already cached.
+    ///
+    /// The first letter of `username` is shown as a placeholder while the image is
+    /// being fetched, and instead of the image if there's no `avatar_uri`
+    /// or the image couldn't be fetched.
+    ///
+    /// Returns `true` if the avatar has been completely drawn, or `false` if its image
+    /// is still being fetched, in which case this should be called again once
+    /// [`avatar_cache::process_avatar_updates()`] has been called.
+    pub fn show_avatar_uri<T: AsRef<str>>(
+        &mut self,
+        cx: &mut Cx,
+        username: T,
+        avatar_uri: Option<&MxcUri>,
+    ) -> bool {
+        let entry = avatar_uri.map(|uri| avatar_cache::get_or_fetch_avatar(cx, uri.to_owned()));
+        let (data, drawn) = match entry {
+            Some(AvatarCacheEntry::Loaded(data)) => (Some(data), true),
+            Some(AvatarCacheEntry::Requested) => (None, false),
+            Some(AvatarCacheEntry::Failed) | None => (None, true),
+        };
+        let image_shown = data.is_some_and(|data| {
+            self.show_image(cx, None, |cx, img| utils::load_png_or_jpg(&img, cx, &data))
+                .is_ok()
+        });
+        if !image_shown {
+            self.show_text(cx, None, None, username);
+        }
+        drawn
+    }
+
     /// Sets the given avatar and returns a displayable username based on the
     /// given profile and user ID of the sender of the event with the given event ID.
     ///
@@ -430,6 +462,20 @@ impl AvatarRef {
         }
     }
 
+    /// See [`Avatar::show_avatar_uri()`].
+    pub fn show_avatar_uri<T: AsRef<str>>(
+        &self,
+        cx: &mut Cx,
+        username: T,
+        avatar_uri: Option<&MxcUri>,
+    ) -> bool {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_avatar_uri(cx, username, avatar_uri)
+        } else {
+            true
+        }
+    }
+
     /// See [`Avatar::set_avatar_and_get_username()`].
     pub fn set_avatar_and_get_username(
         &self,
@@ -573,6 +573,840 @@ pub enum MatrixRequest {
         destination: Arc<Mutex<crate::home::link_preview::TimestampedCacheEntry>>,
         update_sender: Option<crossbeam_channel::Sender<TimelineUpdate>>,
     },
+    /// Request to save edits to the current user's social profile.
+    ///
+    /// If `display_name` is `Some`, the account's display name is changed too
+    /// (an empty name removes it).
+    /// Emits a [`SocialProfileEditorAction::Saved`] or
+    /// [`SocialProfileEditorAction::SaveFailed`] when the update has completed,
+    /// and publishes a [`SocialEvent::ProfileChanged`] once saved.
+    ///
+    /// [`SocialProfileEditorAction::Saved`]: crate::social::widgets::profile_editor::SocialProfileEditorAction::Saved
+    /// [`SocialProfileEditorAction::SaveFailed`]: crate::social::widgets::profile_editor::SocialProfileEditorAction::SaveFailed
+    /// [`SocialEvent::ProfileChanged`]: crate::social::SocialEvent::ProfileChanged
+    #[cfg(feature = "social")]
+    UpdateSocialProfile {
+        room_id: OwnedRoomId,
+        display_name: Option<String>,
+        profile: robrix_social_events::profile::SocialProfileEventContent,
+    },
+    /// Request to check whether a photo attached in the post composer
+    /// was already posted recently by the current user.
+    ///
+    /// Emits a [`SocialPostComposerAction::DuplicateImage`] if it was.
+    ///
+    /// [`SocialPostComposerAction::DuplicateImage`]: crate::social::widgets::post_composer::SocialPostComposerAction::DuplicateImage
+    #[cfg(feature = "social")]
+    FindDuplicateImage { path: std::path::PathBuf },
+    /// Request to fetch the configuration of a feed the post composer posts to,
+    /// to check the attached media against the feed's media restrictions.
+    ///
+    /// Emits a [`SocialPostComposerAction::FeedConfigLoaded`] once it's fetched.
+    ///
+    /// [`SocialPostComposerAction::FeedConfigLoaded`]: crate::social::widgets::post_composer::SocialPostComposerAction::FeedConfigLoaded
+    #[cfg(feature = "social")]
+    FetchFeedConfig { room_id: OwnedRoomId },
+    /// Request to move the `m.fully_read` marker of a feed room
+    /// to a post that was scrolled into view in the newsfeed.
+    ///
+    /// Unlike [`MatrixRequest::FullyReadReceipt`], this doesn't need
+    /// the room's timeline to be open.
+    #[cfg(feature = "social")]
+    SendFeedReadMarker {
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+    },
+    /// Request to fetch the weather forecast for an event's location and date.
+    ///
+    /// The forecast is stored in the forecast cache and the UI is signaled,
+    /// see [`get_or_fetch_forecast()`].
+    ///
+    /// [`get_or_fetch_forecast()`]: crate::social::events::weather::get_or_fetch_forecast
+    #[cfg(feature = "social")]
+    FetchEventForecast {
+        location: robrix_social_events::event::EventLocation,
+        date: chrono::NaiveDate,
+    },
+    /// Request to fetch the map thumbnail centered on a location.
+    ///
+    /// The image is stored in the map cache and the UI is signaled,
+    /// see [`get_or_fetch_map_thumbnail()`].
+    ///
+    /// [`get_or_fetch_map_thumbnail()`]: crate::social::maps::get_or_fetch_map_thumbnail
+    #[cfg(feature = "social")]
+    FetchMapThumbnail { center: crate::social::maps::GeoPoint },
+    /// Request to queue a post, with its media if any, in the outbox
+    /// to be sent to its target feed rooms.
+    ///
+    /// The post is retried until it's sent, see [`spawn_outbox_worker()`].
+    /// Emits an [`OutboxAction::Failed`] if it couldn't be queued or sent.
+    ///
+    /// [`spawn_outbox_worker()`]: crate::social::outbox::spawn_outbox_worker
+    /// [`OutboxAction::Failed`]: crate::social::outbox::OutboxAction::Failed
+    #[cfg(feature = "social")]
+    QueuePost {
+        post: crate::social::post::Post,
+        media: Option<crate::social::outbox::OutboxMedia>,
+        transaction_id: matrix_sdk::ruma::OwnedTransactionId,
+    },
+    /// Request to try sending a queued post that failed to send again.
+    #[cfg(feature = "social")]
+    RetryQueuedPost {
+        transaction_id: matrix_sdk::ruma::OwnedTransactionId,
+    },
+    /// Request to load a user's display name and social profile,
+    /// to show them on the social profile page.
+    ///
+    /// Emits a [`SocialProfileAction::Loaded`] once loaded. The social profile
+    /// is only loaded if the user's profile room is known to the client.
+    ///
+    /// [`SocialProfileAction::Loaded`]: crate::social::widgets::profile_page::SocialProfileAction::Loaded
+    #[cfg(feature = "social")]
+    LoadSocialProfile { user_id: OwnedUserId },
+    /// Request to vote for the given answers of a poll post,
+    /// replacing the current user's earlier vote.
+    ///
+    /// Emits a [`SocialPostCardAction::PollResultsLoaded`] with the poll's votes
+    /// once voted, or with its votes as they were if the vote failed.
+    ///
+    /// [`SocialPostCardAction::PollResultsLoaded`]: crate::social::widgets::post_card::SocialPostCardAction::PollResultsLoaded
+    #[cfg(feature = "social")]
+    VotePoll {
+        room_id: OwnedRoomId,
+        poll_id: OwnedEventId,
+        answers: Vec<String>,
+    },
+    /// Request to end a poll post of the current user.
+    ///
+    /// Emits a [`SocialPostCardAction::PollResultsLoaded`] with the poll's final votes
+    /// once ended.
+    ///
+    /// [`SocialPostCardAction::PollResultsLoaded`]: crate::social::widgets::post_card::SocialPostCardAction::PollResultsLoaded
+    #[cfg(feature = "social")]
+    EndPoll {
+        room_id: OwnedRoomId,
+        poll_id: OwnedEventId,
+    },
+    /// Request to load the current votes of a poll post.
+    ///
+    /// Emits a [`SocialPostCardAction::PollResultsLoaded`] once loaded.
+    ///
+    /// [`SocialPostCardAction::PollResultsLoaded`]: crate::social::widgets::post_card::SocialPostCardAction::PollResultsLoaded
+    #[cfg(feature = "social")]
+    LoadPollResults {
+        room_id: OwnedRoomId,
+        poll_id: OwnedEventId,
+    },
+    /// Request to load the rooms posts can be sent to with "Send to chat".
+    ///
+    /// Emits a [`ShareToChatAction::TargetsLoaded`] once loaded.
+    ///
+    /// [`ShareToChatAction::TargetsLoaded`]: crate::social::share_to_chat::ShareToChatAction::TargetsLoaded
+    #[cfg(feature = "social")]
+    LoadChatTargets,
+    /// Request to forward a post into a regular room, once the sharing guard allows it.
+    ///
+    /// Forwards that need the user's confirmation are only sent if `confirmed` is true.
+    /// Emits a [`ShareToChatAction`] with the outcome.
+    ///
+    /// [`ShareToChatAction`]: crate::social::share_to_chat::ShareToChatAction
+    #[cfg(feature = "social")]
+    SendPostToChat {
+        room_id: OwnedRoomId,
+        post_id: OwnedEventId,
+        target_room_id: OwnedRoomId,
+        confirmed: bool,
+    },
+    /// Request to measure how much local storage social features use.
+    ///
+    /// Emits a [`StorageUsageAction::Loaded`] once measured.
+    ///
+    /// [`StorageUsageAction::Loaded`]: crate::social::storage_usage::StorageUsageAction::Loaded
+    #[cfg(feature = "social")]
+    LoadStorageUsage,
+    /// Request to clear a category of locally stored social data.
+    ///
+    /// Emits a [`StorageUsageAction`] with the storage usage afterwards.
+    ///
+    /// [`StorageUsageAction`]: crate::social::storage_usage::StorageUsageAction
+    #[cfg(feature = "social")]
+    ClearSocialStorage(crate::social::storage_usage::StorageCategory),
+    /// Request to set the size limits of the social caches, and trim them to the new limits.
+    ///
+    /// Emits a [`StorageUsageAction`] with the storage usage afterwards.
+    ///
+    /// [`StorageUsageAction`]: crate::social::storage_usage::StorageUsageAction
+    #[cfg(feature = "social")]
+    SetCacheLimits(robrix_social_events::settings::CacheLimits),
+    /// Request to load what the current user may do to moderate a feed room,
+    /// and the room's pinned posts.
+    ///
+    /// Emits a [`FeedModerationAction::PermissionsLoaded`] once loaded.
+    ///
+    /// [`FeedModerationAction::PermissionsLoaded`]: crate::social::feed_moderation::FeedModerationAction::PermissionsLoaded
+    #[cfg(feature = "social")]
+    LoadModerationPermissions { room_id: OwnedRoomId },
+    /// Request to remove someone's post from a feed room the current user moderates.
+    ///
+    /// Emits a [`FeedModerationAction`] with the outcome.
+    ///
+    /// [`FeedModerationAction`]: crate::social::feed_moderation::FeedModerationAction
+    #[cfg(feature = "social")]
+    RemoveFeedPost {
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+    },
+    /// Request to ban a commenter from a feed room the current user moderates.
+    ///
+    /// Emits a [`FeedModerationAction`] with the outcome.
+    ///
+    /// [`FeedModerationAction`]: crate::social::feed_moderation::FeedModerationAction
+    #[cfg(feature = "social")]
+    BanFeedCommenter {
+        room_id: OwnedRoomId,
+        user_id: OwnedUserId,
+    },
+    /// Request to pin a post to a feed room, or to unpin it if `pinned` is false.
+    ///
+    /// Emits a [`FeedModerationAction`] with the outcome.
+    ///
+    /// [`FeedModerationAction`]: crate::social::feed_moderation::FeedModerationAction
+    #[cfg(feature = "social")]
+    PinFeedPost {
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+        pinned: bool,
+    },
+    /// Request to load which categories of social push notifications are turned on.
+    ///
+    /// Emits a [`SocialPushRulesAction::Loaded`] once loaded.
+    ///
+    /// [`SocialPushRulesAction::Loaded`]: crate::social::push_rules::SocialPushRulesAction::Loaded
+    #[cfg(feature = "social")]
+    LoadSocialPushRules,
+    /// Request to turn push notifications for a category of social interactions on or off.
+    ///
+    /// Emits a [`SocialPushRulesAction`] with the enabled categories afterwards.
+    ///
+    /// [`SocialPushRulesAction`]: crate::social::push_rules::SocialPushRulesAction
+    #[cfg(feature = "social")]
+    SetSocialPushCategory {
+        category: crate::social::push_rules::SocialPushCategory,
+        enabled: bool,
+    },
+    /// Request to translate a post's text into the user's language.
+    ///
+    /// Emits a [`TranslationAction`] with the translation, see [`translate_post()`].
+    ///
+    /// [`TranslationAction`]: crate::social::translation::TranslationAction
+    /// [`translate_post()`]: crate::social::translation::translate_post
+    #[cfg(feature = "social")]
+    TranslatePost {
+        event_id: OwnedEventId,
+        text: String,
+    },
+    /// Request to set how densely posts are laid out in the newsfeed.
+    ///
+    /// Emits a [`FeedDensityAction::Changed`] once the density changed.
+    ///
+    /// [`FeedDensityAction::Changed`]: crate::social::newsfeed::FeedDensityAction::Changed
+    #[cfg(feature = "social")]
+    SetFeedDensity(robrix_social_events::settings::FeedDensity),
+    /// Request to unfriend a user on both sides of the friendship.
+    ///
+    /// See [`FriendsSpaceService::unfriend()`].
+    ///
+    /// [`FriendsSpaceService::unfriend()`]: crate::social::friends::FriendsSpaceService::unfriend
+    #[cfg(feature = "social")]
+    Unfriend(OwnedUserId),
+    /// Request to look up whether a user's identity is verified by the current user.
+    ///
+    /// Emits a [`UserVerificationAction::Checked`] with the result.
+    ///
+    /// [`UserVerificationAction::Checked`]: crate::social::user_verification::UserVerificationAction::Checked
+    #[cfg(feature = "social")]
+    CheckUserVerification(OwnedUserId),
+    /// Request to start verifying another user's identity.
+    ///
+    /// The verification then proceeds in the verification modal,
+    /// see [`request_user_verification()`].
+    ///
+    /// [`request_user_verification()`]: crate::verification::request_user_verification
+    #[cfg(feature = "social")]
+    VerifyUser(OwnedUserId),
+    /// Request to fetch the presence of the given users.
+    ///
+    /// Emits a [`PresenceAction::Loaded`] for each user, see [`load_presence()`].
+    ///
+    /// [`PresenceAction::Loaded`]: crate::social::presence::PresenceAction::Loaded
+    /// [`load_presence()`]: crate::social::presence::load_presence
+    #[cfg(feature = "social")]
+    LoadPresence(Vec<OwnedUserId>),
+    /// Request to load the birthdays of the current user's friends in the coming week.
+    ///
+    /// Emits a [`BirthdaysAction::Loaded`] with the birthdays, soonest first.
+    ///
+    /// [`BirthdaysAction::Loaded`]: crate::social::newsfeed::BirthdaysAction::Loaded
+    #[cfg(feature = "social")]
+    LoadBirthdays,
+    /// Request to hide or show the upcoming birthdays card in the newsfeed.
+    ///
+    /// See [`set_birthdays_hidden()`].
+    ///
+    /// [`set_birthdays_hidden()`]: crate::social::newsfeed::set_birthdays_hidden
+    #[cfg(feature = "social")]
+    SetBirthdaysHidden(bool),
+    /// Request to load a user's media posts for the Media tab of their profile.
+    ///
+    /// Emits a [`ProfileTabAction::MediaLoaded`] with the posts, see [`load_profile_media()`].
+    ///
+    /// [`ProfileTabAction::MediaLoaded`]: crate::social::profile_tabs::ProfileTabAction::MediaLoaded
+    /// [`load_profile_media()`]: crate::social::profile_tabs::load_profile_media
+    #[cfg(feature = "social")]
+    LoadProfileMedia { user_id: OwnedUserId },
+    /// Request to load the posts the current user reacted to,
+    /// for the Likes tab of their profile.
+    ///
+    /// Emits a [`ProfileTabAction::LikesLoaded`] with the posts, see [`load_liked_posts()`].
+    ///
+    /// [`ProfileTabAction::LikesLoaded`]: crate::social::profile_tabs::ProfileTabAction::LikesLoaded
+    /// [`load_liked_posts()`]: crate::social::profile_tabs::load_liked_posts
+    #[cfg(feature = "social")]
+    LoadLikedPosts,
+    /// Request to load a user's posts for the Posts tab of their profile.
+    ///
+    /// Emits a [`ProfileTabAction::PostsLoaded`] with the posts, see [`load_profile_posts()`].
+    ///
+    /// [`ProfileTabAction::PostsLoaded`]: crate::social::profile_tabs::ProfileTabAction::PostsLoaded
+    /// [`load_profile_posts()`]: crate::social::profile_tabs::load_profile_posts
+    #[cfg(feature = "social")]
+    LoadProfilePosts { user_id: OwnedUserId },
+    /// Request to load the friends the current user shares with another user,
+    /// shown on their profile.
+    ///
+    /// Emits a [`MutualFriendsAction::Loaded`] with the shared friends,
+    /// see [`FriendSuggestionEngine::mutual_friends()`].
+    ///
+    /// [`MutualFriendsAction::Loaded`]: crate::social::discovery::MutualFriendsAction::Loaded
+    /// [`FriendSuggestionEngine::mutual_friends()`]: crate::social::discovery::FriendSuggestionEngine::mutual_friends
+    #[cfg(feature = "social")]
+    LoadMutualFriends { user_id: OwnedUserId },
+    /// Request to follow a user, see [`FollowService::follow()`].
+    ///
+    /// Emits a [`SocialProfileAction::FollowChanged`] with whether the user
+    /// is followed afterwards.
+    ///
+    /// [`FollowService::follow()`]: crate::social::FollowService::follow
+    /// [`SocialProfileAction::FollowChanged`]: crate::social::widgets::profile_page::SocialProfileAction::FollowChanged
+    #[cfg(feature = "social")]
+    Follow(OwnedUserId),
+    /// Request to unfollow a user, see [`FollowService::unfollow()`].
+    ///
+    /// Emits a [`SocialProfileAction::FollowChanged`] with whether the user
+    /// is followed afterwards.
+    ///
+    /// [`FollowService::unfollow()`]: crate::social::FollowService::unfollow
+    /// [`SocialProfileAction::FollowChanged`]: crate::social::widgets::profile_page::SocialProfileAction::FollowChanged
+    #[cfg(feature = "social")]
+    Unfollow(OwnedUserId),
+    /// Request to remove a follower from the current user's public feed,
+    /// see [`FollowerModerationService::remove_follower()`].
+    ///
+    /// Emits a [`SocialProfileAction::FollowerRemoved`] once removed.
+    ///
+    /// [`FollowerModerationService::remove_follower()`]: crate::social::FollowerModerationService::remove_follower
+    /// [`SocialProfileAction::FollowerRemoved`]: crate::social::widgets::profile_page::SocialProfileAction::FollowerRemoved
+    #[cfg(feature = "social")]
+    RemoveFollower(OwnedUserId),
+    /// Request to ban a user from all of the current user's feeds,
+    /// see [`FollowerModerationService::ban_from_feeds()`].
+    ///
+    /// Emits a [`SocialProfileAction::FollowerRemoved`] once banned from every feed.
+    ///
+    /// [`FollowerModerationService::ban_from_feeds()`]: crate::social::FollowerModerationService::ban_from_feeds
+    /// [`SocialProfileAction::FollowerRemoved`]: crate::social::widgets::profile_page::SocialProfileAction::FollowerRemoved
+    #[cfg(feature = "social")]
+    BanFromFeeds(OwnedUserId),
+    /// Request to upload a cover photo or avatar and set it on the current user's
+    /// social profile, see [`ProfileRoomService::set_cover_photo()`]
+    /// and [`ProfileRoomService::set_profile_avatar()`].
+    ///
+    /// Shows an error notification if the image couldn't be saved.
+    ///
+    /// [`ProfileRoomService::set_cover_photo()`]: crate::social::ProfileRoomService::set_cover_photo
+    /// [`ProfileRoomService::set_profile_avatar()`]: crate::social::ProfileRoomService::set_profile_avatar
+    #[cfg(feature = "social")]
+    SaveProfileImage {
+        room_id: OwnedRoomId,
+        kind: crate::social::ProfileImageKind,
+        data: std::sync::Arc<[u8]>,
+    },
+    /// Request to add the current user's reaction to a post, or to redact it
+    /// if `added` is false, with the given transaction ID.
+    ///
+    /// Emits an [`OptimisticAction::Confirmed`] once sent, or an
+    /// [`OptimisticAction::Failed`] if it couldn't be sent.
+    ///
+    /// [`OptimisticAction::Confirmed`]: crate::social::optimistic::OptimisticAction::Confirmed
+    /// [`OptimisticAction::Failed`]: crate::social::optimistic::OptimisticAction::Failed
+    #[cfg(feature = "social")]
+    TogglePostReaction {
+        room_id: OwnedRoomId,
+        post_id: OwnedEventId,
+        emoji: String,
+        added: bool,
+        transaction_id: matrix_sdk::ruma::OwnedTransactionId,
+    },
+    /// Request to load older posts of a user for the Posts tab of their profile,
+    /// up to `limit` posts in all.
+    ///
+    /// Emits a [`ProfileTabAction::MorePostsLoaded`] with the posts, see [`load_profile_posts()`].
+    ///
+    /// [`ProfileTabAction::MorePostsLoaded`]: crate::social::profile_tabs::ProfileTabAction::MorePostsLoaded
+    /// [`load_profile_posts()`]: crate::social::profile_tabs::load_profile_posts
+    #[cfg(feature = "social")]
+    LoadMoreProfilePosts { user_id: OwnedUserId, limit: usize },
+    /// Request to set the service posts are translated with,
+    /// or `None` to turn translation off.
+    ///
+    /// See [`set_translation_settings()`].
+    ///
+    /// [`set_translation_settings()`]: crate::social::translation::set_translation_settings
+    #[cfg(feature = "social")]
+    SetTranslationSettings(Option<robrix_social_events::settings::TranslationSettings>),
+    /// Request to save a sender the user marked as not spamming,
+    /// so that their posts are never flagged as spam again.
+    ///
+    /// See [`save_not_spam_sender()`].
+    ///
+    /// [`save_not_spam_sender()`]: crate::social::newsfeed::save_not_spam_sender
+    #[cfg(feature = "social")]
+    SaveNotSpamSender(OwnedUserId),
+    /// Request to load up to `limit` posts of the main newsfeed.
+    ///
+    /// Emits a [`MainFeedAction::Loaded`] with the posts, see [`load_main_feed()`].
+    ///
+    /// [`MainFeedAction::Loaded`]: crate::social::main_feed::MainFeedAction::Loaded
+    /// [`load_main_feed()`]: crate::social::main_feed::load_main_feed
+    #[cfg(feature = "social")]
+    LoadMainFeed { limit: usize },
+    /// Request to save the newsfeed filter.
+    ///
+    /// See [`save_feed_filter()`].
+    ///
+    /// [`save_feed_filter()`]: crate::social::newsfeed::save_feed_filter
+    #[cfg(feature = "social")]
+    SaveFeedFilter(crate::social::newsfeed::FeedFilterSettings),
+    /// Request to load the retention policy of one of the user's feeds.
+    ///
+    /// Emits a [`RetentionJobAction::PolicyLoaded`] with the policy,
+    /// see [`RetentionService::get_policy()`].
+    ///
+    /// [`RetentionJobAction::PolicyLoaded`]: crate::social::retention::RetentionJobAction::PolicyLoaded
+    /// [`RetentionService::get_policy()`]: crate::social::retention::RetentionService::get_policy
+    #[cfg(feature = "social")]
+    LoadRetentionPolicy(crate::social::feed_room::FeedPrivacy),
+    /// Request to set the maximum age of the posts in one of the user's feeds,
+    /// or `None` to keep them forever.
+    ///
+    /// See [`RetentionService::set_max_age()`].
+    ///
+    /// [`RetentionService::set_max_age()`]: crate::social::retention::RetentionService::set_max_age
+    #[cfg(feature = "social")]
+    SetRetentionMaxAge {
+        feed: crate::social::feed_room::FeedPrivacy,
+        max_age_months: Option<u32>,
+    },
+    /// Request to keep one of the user's posts regardless of its feed's retention policy.
+    ///
+    /// See [`RetentionService::set_pinned()`].
+    ///
+    /// [`RetentionService::set_pinned()`]: crate::social::retention::RetentionService::set_pinned
+    #[cfg(feature = "social")]
+    PinRetainedPost {
+        feed: crate::social::feed_room::FeedPrivacy,
+        event_id: OwnedEventId,
+    },
+    /// Request to preview which posts the retention policy of one of the user's
+    /// feeds would delete, or if `dry_run` is false, to delete them now.
+    ///
+    /// Emits a [`RetentionJobAction::Completed`] with the report,
+    /// see [`RetentionService::preview()`] and [`RetentionService::apply()`].
+    ///
+    /// [`RetentionJobAction::Completed`]: crate::social::retention::RetentionJobAction::Completed
+    /// [`RetentionService::preview()`]: crate::social::retention::RetentionService::preview
+    /// [`RetentionService::apply()`]: crate::social::retention::RetentionService::apply
+    #[cfg(feature = "social")]
+    RunRetentionPolicy {
+        feed: crate::social::feed_room::FeedPrivacy,
+        dry_run: bool,
+    },
+    /// Request to list the current user's followers.
+    ///
+    /// Emits a [`FollowListsAction::FollowersLoaded`] with the followers,
+    /// see [`FollowService::list_followers()`].
+    ///
+    /// [`FollowListsAction::FollowersLoaded`]: crate::social::follow::FollowListsAction::FollowersLoaded
+    /// [`FollowService::list_followers()`]: crate::social::FollowService::list_followers
+    #[cfg(feature = "social")]
+    LoadFollowers,
+    /// Request to list the users the current user follows.
+    ///
+    /// Emits a [`FollowListsAction::FollowingLoaded`] with the followed users,
+    /// see [`FollowService::list_following()`].
+    ///
+    /// [`FollowListsAction::FollowingLoaded`]: crate::social::follow::FollowListsAction::FollowingLoaded
+    /// [`FollowService::list_following()`]: crate::social::FollowService::list_following
+    #[cfg(feature = "social")]
+    LoadFollowing,
+    /// Request to count the reactions to a post by how long after `posted_at`
+    /// they arrived.
+    ///
+    /// Emits a [`PostDetailAction::ReactionTimingLoaded`] with the counts,
+    /// see [`load_reaction_timestamps()`] and [`reaction_timing()`].
+    ///
+    /// [`PostDetailAction::ReactionTimingLoaded`]: crate::social::widgets::post_detail::PostDetailAction::ReactionTimingLoaded
+    /// [`load_reaction_timestamps()`]: crate::social::reactions::load_reaction_timestamps
+    /// [`reaction_timing()`]: crate::social::reactions::reaction_timing
+    #[cfg(feature = "social")]
+    LoadReactionTiming {
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+        posted_at: MilliSecondsSinceUnixEpoch,
+    },
+    /// Request to load the current user's activity in the given range.
+    ///
+    /// Emits an [`ActivityLogAction::Loaded`] with the activity,
+    /// see [`ActivityLogService::load()`].
+    ///
+    /// [`ActivityLogAction::Loaded`]: crate::social::activity_log::ActivityLogAction::Loaded
+    /// [`ActivityLogService::load()`]: crate::social::ActivityLogService::load
+    #[cfg(feature = "social")]
+    LoadActivityLog(crate::social::activity_log::ActivityRange),
+    /// Request to undo some of the current user's reactions and follows.
+    ///
+    /// Emits an [`ActivityLogAction::Undone`] with the outcome,
+    /// see [`ActivityLogService::undo()`].
+    ///
+    /// [`ActivityLogAction::Undone`]: crate::social::activity_log::ActivityLogAction::Undone
+    /// [`ActivityLogService::undo()`]: crate::social::ActivityLogService::undo
+    #[cfg(feature = "social")]
+    UndoActivities(Vec<crate::social::activity_log::ActivityEntry>),
+    /// Request to find the events the current user joined or was invited to.
+    ///
+    /// Emits a [`ProfileTabAction::EventsLoaded`] with the events,
+    /// see [`EventDiscoveryService::discover_events()`].
+    ///
+    /// [`ProfileTabAction::EventsLoaded`]: crate::social::profile_tabs::ProfileTabAction::EventsLoaded
+    /// [`EventDiscoveryService::discover_events()`]: crate::social::discovery::EventDiscoveryService::discover_events
+    #[cfg(feature = "social")]
+    LoadEventsCalendar,
+    /// Request to list the current user's friends to invite to an event,
+    /// along with the users who already joined or were invited to it.
+    ///
+    /// Emits an [`EventInvitesAction::FriendsLoaded`] with the friends,
+    /// see [`FriendSuggestionEngine::own_friend_feeds()`] and
+    /// [`EventRoomService::invited_or_joined()`].
+    ///
+    /// [`EventInvitesAction::FriendsLoaded`]: crate::social::widgets::event_invite_sheet::EventInvitesAction::FriendsLoaded
+    /// [`FriendSuggestionEngine::own_friend_feeds()`]: crate::social::discovery::FriendSuggestionEngine::own_friend_feeds
+    /// [`EventRoomService::invited_or_joined()`]: crate::social::events::EventRoomService::invited_or_joined
+    #[cfg(feature = "social")]
+    LoadEventInviteFriends { room_id: OwnedRoomId },
+    /// Request to invite several guests to an event.
+    ///
+    /// Emits an [`EventInvitesAction::Sent`] with the result of each invite,
+    /// see [`EventRoomService::invite_guests()`].
+    ///
+    /// [`EventInvitesAction::Sent`]: crate::social::widgets::event_invite_sheet::EventInvitesAction::Sent
+    /// [`EventRoomService::invite_guests()`]: crate::social::events::EventRoomService::invite_guests
+    #[cfg(feature = "social")]
+    InviteEventGuests {
+        room_id: OwnedRoomId,
+        user_ids: Vec<OwnedUserId>,
+    },
+    /// Request to find the current user's feed rooms, which the post composer posts to.
+    ///
+    /// Emits a [`SocialPostComposerAction::FeedsLoaded`] with the feeds,
+    /// see [`FeedRoomService::get_own_feeds()`].
+    ///
+    /// [`SocialPostComposerAction::FeedsLoaded`]: crate::social::widgets::post_composer::SocialPostComposerAction::FeedsLoaded
+    /// [`FeedRoomService::get_own_feeds()`]: crate::social::FeedRoomService::get_own_feeds
+    #[cfg(feature = "social")]
+    LoadOwnFeeds,
+    /// Request to list the current user's friends and their pending friend requests.
+    ///
+    /// Emits a [`FriendListAction::Loaded`] with them, see
+    /// [`FriendSuggestionEngine::own_friend_feeds()`],
+    /// [`FriendRequestService::get_pending_requests()`] and
+    /// [`FriendRequestService::get_outgoing_requests()`].
+    ///
+    /// [`FriendListAction::Loaded`]: crate::social::widgets::friend_list::FriendListAction::Loaded
+    /// [`FriendSuggestionEngine::own_friend_feeds()`]: crate::social::discovery::FriendSuggestionEngine::own_friend_feeds
+    /// [`FriendRequestService::get_pending_requests()`]: crate::social::friends::FriendRequestService::get_pending_requests
+    /// [`FriendRequestService::get_outgoing_requests()`]: crate::social::friends::FriendRequestService::get_outgoing_requests
+    #[cfg(feature = "social")]
+    LoadFriendList,
+    /// Request to accept or decline a friend request sent to the current user.
+    ///
+    /// See [`FriendRequestService::accept_friend_request()`] and
+    /// [`FriendRequestService::decline_friend_request()`].
+    ///
+    /// [`FriendRequestService::accept_friend_request()`]: crate::social::friends::FriendRequestService::accept_friend_request
+    /// [`FriendRequestService::decline_friend_request()`]: crate::social::friends::FriendRequestService::decline_friend_request
+    #[cfg(feature = "social")]
+    AnswerFriendRequest { user_id: OwnedUserId, accept: bool },
+    /// Request to cancel a friend request the current user sent to the
+    /// friends-only feed with the given ID.
+    ///
+    /// See [`FriendRequestService::cancel_friend_request()`].
+    ///
+    /// [`FriendRequestService::cancel_friend_request()`]: crate::social::friends::FriendRequestService::cancel_friend_request
+    #[cfg(feature = "social")]
+    CancelFriendRequest { room_id: OwnedRoomId },
+    /// Request to load an event to show in the event detail view.
+    ///
+    /// Emits an [`EventDetailLoadAction::Loaded`] with the event, followed by
+    /// an [`EventDetailLoadAction::AttendQuietly`] with whether the current
+    /// user attends it quietly, an [`EventDetailLoadAction::Checkins`] with its
+    /// check-ins, an [`EventDetailLoadAction::Checklist`] with its checklist,
+    /// an [`EventDetailLoadAction::RideBoard`] if it coordinates rides,
+    /// an [`EventDetailLoadAction::SchedulingPoll`] with its scheduling poll, and for hosts, an [`EventDetailLoadAction::Insights`]
+    /// (see [`EventInsightsService::get_insights()`]) and an [`EventDetailLoadAction::Roles`].
+    ///
+    /// [`EventDetailLoadAction::Checkins`]: crate::social::widgets::event_detail::EventDetailLoadAction::Checkins
+    /// [`EventDetailLoadAction::Checklist`]: crate::social::widgets::event_detail::EventDetailLoadAction::Checklist
+    /// [`EventDetailLoadAction::RideBoard`]: crate::social::widgets::event_detail::EventDetailLoadAction::RideBoard
+    /// [`EventDetailLoadAction::SchedulingPoll`]: crate::social::widgets::event_detail::EventDetailLoadAction::SchedulingPoll
+    /// [`EventDetailLoadAction::Loaded`]: crate::social::widgets::event_detail::EventDetailLoadAction::Loaded
+    /// [`EventDetailLoadAction::Insights`]: crate::social::widgets::event_detail::EventDetailLoadAction::Insights
+    /// [`EventDetailLoadAction::Roles`]: crate::social::widgets::event_detail::EventDetailLoadAction::Roles
+    /// [`EventInsightsService::get_insights()`]: crate::social::events::EventInsightsService::get_insights
+    /// [`EventDetailLoadAction::AttendQuietly`]: crate::social::widgets::event_detail::EventDetailLoadAction::AttendQuietly
+    #[cfg(feature = "social")]
+    LoadEventDetail { room_id: OwnedRoomId },
+    /// Request to mute or unmute an event room's chat without changing one's RSVP.
+    ///
+    /// If that fails, emits an [`EventDetailLoadAction::AttendQuietly`]
+    /// to restore the toggle, see [`QuietAttendanceService::set_attend_quietly()`].
+    ///
+    /// [`EventDetailLoadAction::AttendQuietly`]: crate::social::widgets::event_detail::EventDetailLoadAction::AttendQuietly
+    /// [`QuietAttendanceService::set_attend_quietly()`]: crate::social::events::QuietAttendanceService::set_attend_quietly
+    #[cfg(feature = "social")]
+    SetAttendQuietly { room_id: OwnedRoomId, quiet: bool },
+    /// Request to promote a guest of an event to co-host.
+    ///
+    /// Emits an [`EventDetailLoadAction::Roles`] with the updated roles,
+    /// see [`EventRoomService::add_cohost()`].
+    ///
+    /// [`EventDetailLoadAction::Roles`]: crate::social::widgets::event_detail::EventDetailLoadAction::Roles
+    /// [`EventRoomService::add_cohost()`]: crate::social::events::EventRoomService::add_cohost
+    #[cfg(feature = "social")]
+    AddEventCohost {
+        room_id: OwnedRoomId,
+        user_id: OwnedUserId,
+    },
+    /// Request to demote a co-host of an event back to guest.
+    ///
+    /// Emits an [`EventDetailLoadAction::Roles`] with the updated roles,
+    /// see [`EventRoomService::remove_cohost()`].
+    ///
+    /// [`EventDetailLoadAction::Roles`]: crate::social::widgets::event_detail::EventDetailLoadAction::Roles
+    /// [`EventRoomService::remove_cohost()`]: crate::social::events::EventRoomService::remove_cohost
+    #[cfg(feature = "social")]
+    RemoveEventCohost {
+        room_id: OwnedRoomId,
+        user_id: OwnedUserId,
+    },
+    /// Request to check the current user in to an event, with an optional message.
+    ///
+    /// Emits an [`EventDetailLoadAction::Checkins`] with the updated check-ins,
+    /// see [`EventCheckinService::check_in()`].
+    ///
+    /// [`EventDetailLoadAction::Checkins`]: crate::social::widgets::event_detail::EventDetailLoadAction::Checkins
+    /// [`EventCheckinService::check_in()`]: crate::social::events::EventCheckinService::check_in
+    #[cfg(feature = "social")]
+    CheckInToEvent {
+        room_id: OwnedRoomId,
+        message: Option<String>,
+    },
+    /// Request to add an item to an event's checklist.
+    ///
+    /// Emits an [`EventDetailLoadAction::Checklist`] with the updated checklist,
+    /// see [`ChecklistService::add_item()`].
+    ///
+    /// [`EventDetailLoadAction::Checklist`]: crate::social::widgets::event_detail::EventDetailLoadAction::Checklist
+    /// [`ChecklistService::add_item()`]: crate::social::events::ChecklistService::add_item
+    #[cfg(feature = "social")]
+    AddChecklistItem {
+        room_id: OwnedRoomId,
+        title: String,
+        kind: robrix_social_events::checklist::ChecklistItemKind,
+        quantity: u32,
+    },
+    /// Request to set how many of a checklist item the current user is bringing.
+    ///
+    /// Emits an [`EventDetailLoadAction::Checklist`] with the updated checklist,
+    /// see [`ChecklistService::set_claim()`].
+    ///
+    /// [`EventDetailLoadAction::Checklist`]: crate::social::widgets::event_detail::EventDetailLoadAction::Checklist
+    /// [`ChecklistService::set_claim()`]: crate::social::events::ChecklistService::set_claim
+    #[cfg(feature = "social")]
+    SetChecklistClaim {
+        room_id: OwnedRoomId,
+        item_id: String,
+        quantity: u32,
+    },
+    /// Request to check a checklist item off, or undo that.
+    ///
+    /// Emits an [`EventDetailLoadAction::Checklist`] with the updated checklist,
+    /// see [`ChecklistService::set_done()`].
+    ///
+    /// [`EventDetailLoadAction::Checklist`]: crate::social::widgets::event_detail::EventDetailLoadAction::Checklist
+    /// [`ChecklistService::set_done()`]: crate::social::events::ChecklistService::set_done
+    #[cfg(feature = "social")]
+    SetChecklistItemDone {
+        room_id: OwnedRoomId,
+        item_id: String,
+        done: bool,
+    },
+    /// Request to offer free seats to the other attendees of an event.
+    ///
+    /// Emits an [`EventDetailLoadAction::RideBoard`] with the updated rides,
+    /// see [`LogisticsService::offer_ride()`].
+    ///
+    /// [`EventDetailLoadAction::RideBoard`]: crate::social::widgets::event_detail::EventDetailLoadAction::RideBoard
+    /// [`LogisticsService::offer_ride()`]: crate::social::events::LogisticsService::offer_ride
+    #[cfg(feature = "social")]
+    OfferRide {
+        room_id: OwnedRoomId,
+        seats: u32,
+        area: String,
+    },
+    /// Request to ask the other attendees of an event for a ride.
+    ///
+    /// Emits an [`EventDetailLoadAction::RideBoard`] with the updated rides,
+    /// see [`LogisticsService::request_ride()`].
+    ///
+    /// [`EventDetailLoadAction::RideBoard`]: crate::social::widgets::event_detail::EventDetailLoadAction::RideBoard
+    /// [`LogisticsService::request_ride()`]: crate::social::events::LogisticsService::request_ride
+    #[cfg(feature = "social")]
+    RequestRide {
+        room_id: OwnedRoomId,
+        seats: u32,
+        area: String,
+    },
+    /// Request to withdraw the current user's ride offer or request for an event.
+    ///
+    /// Emits an [`EventDetailLoadAction::RideBoard`] with the updated rides,
+    /// see [`LogisticsService::withdraw()`].
+    ///
+    /// [`EventDetailLoadAction::RideBoard`]: crate::social::widgets::event_detail::EventDetailLoadAction::RideBoard
+    /// [`LogisticsService::withdraw()`]: crate::social::events::LogisticsService::withdraw
+    #[cfg(feature = "social")]
+    WithdrawRide { room_id: OwnedRoomId },
+    /// Request to vote for the given dates in an event's scheduling poll,
+    /// replacing the current user's earlier vote.
+    ///
+    /// Emits an [`EventDetailLoadAction::SchedulingPoll`] with the updated poll,
+    /// see [`SchedulingPollService::vote()`].
+    ///
+    /// [`EventDetailLoadAction::SchedulingPoll`]: crate::social::widgets::event_detail::EventDetailLoadAction::SchedulingPoll
+    /// [`SchedulingPollService::vote()`]: crate::social::events::SchedulingPollService::vote
+    #[cfg(feature = "social")]
+    VoteSchedulingPoll {
+        room_id: OwnedRoomId,
+        start_times: Vec<u64>,
+    },
+    /// Request to move an event to the date with the most votes in its scheduling poll.
+    ///
+    /// Reloads the event with a [`MatrixRequest::LoadEventDetail`] once moved,
+    /// see [`SchedulingPollService::finalize()`].
+    ///
+    /// [`SchedulingPollService::finalize()`]: crate::social::events::SchedulingPollService::finalize
+    #[cfg(feature = "social")]
+    FinalizeSchedulingPoll { room_id: OwnedRoomId },
+    /// Request to keep an event room open for guests after the event ended,
+    /// see [`EventRoomService::keep_event_open()`].
+    ///
+    /// Reloads the event with a [`MatrixRequest::LoadEventDetail`] if this fails,
+    /// so its creator is asked again.
+    ///
+    /// [`EventRoomService::keep_event_open()`]: crate::social::events::EventRoomService::keep_event_open
+    #[cfg(feature = "social")]
+    KeepEventOpen { room_id: OwnedRoomId },
+    /// Request to make an event room read-only for guests after the event ended,
+    /// see [`EventRoomService::archive_event()`].
+    ///
+    /// Reloads the event with a [`MatrixRequest::LoadEventDetail`] if this fails,
+    /// so its creator is asked again.
+    ///
+    /// [`EventRoomService::archive_event()`]: crate::social::events::EventRoomService::archive_event
+    #[cfg(feature = "social")]
+    ArchiveEvent { room_id: OwnedRoomId },
+    /// Request to close an event room for good after the event ended,
+    /// pointing its attendees to the current user's public feed room,
+    /// see [`EventRoomService::close_event()`].
+    ///
+    /// Reloads the event with a [`MatrixRequest::LoadEventDetail`] if this fails,
+    /// so its creator is asked again.
+    ///
+    /// [`EventRoomService::close_event()`]: crate::social::events::EventRoomService::close_event
+    #[cfg(feature = "social")]
+    CloseEvent { room_id: OwnedRoomId },
+    /// Request to queue a reply to a post, with its media if any, in the outbox
+    /// to be sent to the post's thread.
+    ///
+    /// The reply is retried until it's sent, see [`PostService::queue_reply()`].
+    ///
+    /// [`PostService::queue_reply()`]: crate::social::outbox::PostService::queue_reply
+    #[cfg(feature = "social")]
+    QueuePostReply {
+        room_id: OwnedRoomId,
+        post_id: OwnedEventId,
+        text: String,
+        media: Option<crate::social::outbox::OutboxMedia>,
+    },
+    /// Request to load friends of friends to suggest to the current user.
+    ///
+    /// Emits a [`FriendSuggestionsAction::Loaded`] with up to
+    /// [`MAX_FRIEND_SUGGESTIONS`] suggestions,
+    /// see [`FriendSuggestionEngine::get_friend_suggestions()`].
+    ///
+    /// [`FriendSuggestionsAction::Loaded`]: crate::social::discovery::FriendSuggestionsAction::Loaded
+    /// [`MAX_FRIEND_SUGGESTIONS`]: crate::social::discovery::MAX_FRIEND_SUGGESTIONS
+    /// [`FriendSuggestionEngine::get_friend_suggestions()`]: crate::social::discovery::FriendSuggestionEngine::get_friend_suggestions
+    #[cfg(feature = "social")]
+    LoadFriendSuggestions,
+    /// Request to search the homeserver's user directory for people.
+    ///
+    /// Emits a [`UserSearchAction::Loaded`] with up to
+    /// [`MAX_USER_SEARCH_RESULTS`] results,
+    /// see [`UserDiscoveryService::search_users()`].
+    ///
+    /// [`UserSearchAction::Loaded`]: crate::social::discovery::UserSearchAction::Loaded
+    /// [`MAX_USER_SEARCH_RESULTS`]: crate::social::discovery::MAX_USER_SEARCH_RESULTS
+    /// [`UserDiscoveryService::search_users()`]: crate::social::discovery::UserDiscoveryService::search_users
+    #[cfg(feature = "social")]
+    SearchUsers { query: String },
+    /// Request to load the posts and hashtags trending in the user's joined public feeds.
+    ///
+    /// Emits a [`TrendingAction::Loaded`] with up to [`MAX_TRENDING_POSTS`] posts
+    /// and [`MAX_TRENDING_TOPICS`] hashtags, see [`TrendingService::get_trending()`].
+    ///
+    /// [`TrendingAction::Loaded`]: crate::social::discovery::TrendingAction::Loaded
+    /// [`MAX_TRENDING_POSTS`]: crate::social::discovery::MAX_TRENDING_POSTS
+    /// [`MAX_TRENDING_TOPICS`]: crate::social::discovery::MAX_TRENDING_TOPICS
+    /// [`TrendingService::get_trending()`]: crate::social::discovery::TrendingService::get_trending
+    #[cfg(feature = "social")]
+    LoadTrending,
+    /// Request to save how the newsfeed groups bursts of posts from one author.
+    ///
+    /// See [`save_feed_grouping()`].
+    ///
+    /// [`save_feed_grouping()`]: crate::social::newsfeed::save_feed_grouping
+    #[cfg(feature = "social")]
+    SaveFeedGrouping(crate::social::newsfeed::FeedGroupingSettings),
 }
 
 /// Submits a request to the worker thread to be executed asynchronously.
@@ -1884,18 +2718,1930 @@ async fn matrix_worker_task(
                     SignalToUI::set_ui_signal();
                 });
             }
-        }
-    }
 
-    error!("matrix_worker_task task ended unexpectedly");
-    bail!("matrix_worker_task task ended unexpectedly")
-}
+            #[cfg(feature = "social")]
+            MatrixRequest::UpdateSocialProfile {
+                room_id,
+                display_name,
+                profile,
+            } => {
+                use crate::social::{
+                    publish_social_event, widgets::profile_editor::SocialProfileEditorAction,
+                    ProfileRoomService, SocialEvent,
+                };
 
-/// The single global Tokio runtime that is used by all async tasks.
-static TOKIO_RUNTIME: Mutex<Option<tokio::runtime::Runtime>> = Mutex::new(None);
+                let Some(client) = get_client() else { continue };
+                let _update_profile_task = Handle::current().spawn(async move {
+                    if let Some(name) = display_name.as_deref() {
+                        let new_name = (!name.is_empty()).then_some(name);
+                        if let Err(e) = client.account().set_display_name(new_name).await {
+                            error!("Failed to set display name to {new_name:?}: {e:?}");
+                            Cx::post_action(SocialProfileEditorAction::SaveFailed(format!(
+                                "Failed to update your display name: {e}"
+                            )));
+                            return;
+                        }
+                    }
+                    match ProfileRoomService::new(client.clone())
+                        .update_profile(&room_id, profile.clone())
+                        .await
+                    {
+                        Ok(()) => {
+                            if let Some(user_id) = client.user_id() {
+                                publish_social_event(SocialEvent::ProfileChanged {
+                                    user_id: user_id.to_owned(),
+                                    display_name: display_name.clone(),
+                                    profile: profile.clone(),
+                                });
+                            }
+                            Cx::post_action(SocialProfileEditorAction::Saved {
+                                display_name,
+                                profile,
+                            });
+                        }
+                        Err(e) => {
+                            error!("Failed to update social profile in room {room_id}: {e:?}");
+                            Cx::post_action(SocialProfileEditorAction::SaveFailed(format!(
+                                "Failed to save your profile: {e}"
+                            )));
+                        }
+                    }
+                });
+            }
 
-/// The sender used by [`submit_async_request`] to send requests to the async worker thread.
-/// Currently there is only one, but it can be cloned if we need more concurrent senders.
+            #[cfg(feature = "social")]
+            MatrixRequest::FindDuplicateImage { path } => {
+                use crate::social::{
+                    duplicate_media::find_duplicate_upload,
+                    widgets::post_composer::SocialPostComposerAction,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let Some(user_id) = client.user_id().map(ToOwned::to_owned) else {
+                    continue;
+                };
+                // Decoding the photo is CPU-bound, so keep it off the async worker threads
+                let _find_duplicate_task = Handle::current().spawn_blocking(move || {
+                    match find_duplicate_upload(&user_id, &path) {
+                        Ok(Some(previous)) => {
+                            Cx::post_action(SocialPostComposerAction::DuplicateImage {
+                                path,
+                                previous,
+                            });
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            warning!(
+                                "Failed to check {} for duplicate uploads: {e}",
+                                path.display()
+                            );
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::FetchFeedConfig { room_id } => {
+                use crate::social::{widgets::post_composer::SocialPostComposerAction, FeedRoomService};
+
+                let Some(client) = get_client() else { continue };
+                let _fetch_feed_config_task = Handle::current().spawn(async move {
+                    match FeedRoomService::new(client).feed_config(&room_id).await {
+                        Ok(config) => {
+                            Cx::post_action(SocialPostComposerAction::FeedConfigLoaded {
+                                room_id,
+                                config,
+                            });
+                        }
+                        Err(e) => {
+                            warning!("Failed to fetch the config of feed {room_id}: {e}");
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SendFeedReadMarker { room_id, event_id } => {
+                use crate::social::FeedReadMarkerService;
+
+                let Some(client) = get_client() else { continue };
+                let _send_feed_marker_task = Handle::current().spawn(async move {
+                    if let Err(e) = FeedReadMarkerService::new(client)
+                        .send_fully_read(&room_id, event_id.clone())
+                        .await
+                    {
+                        error!(
+                            "Failed to send fully read marker to feed room {room_id} for event {event_id}: {e:?}"
+                        );
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::FetchEventForecast { location, date } => {
+                let _forecast_task = Handle::current().spawn(
+                    crate::social::events::weather::fetch_forecast(location, date),
+                );
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::FetchMapThumbnail { center } => {
+                let _map_task =
+                    Handle::current().spawn(crate::social::maps::fetch_map_thumbnail(center));
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::QueuePost {
+                post,
+                media,
+                transaction_id,
+            } => {
+                use crate::social::outbox::{OutboxAction, PostService};
+
+                let Some(client) = get_client() else { continue };
+                let service = PostService::new(client);
+                if let Err(e) = service.queue(&post, media, transaction_id.clone()) {
+                    error!("Failed to queue post {transaction_id}: {e}");
+                    Cx::post_action(OutboxAction::Failed {
+                        transaction_id,
+                        error: e.to_string(),
+                    });
+                }
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::RetryQueuedPost { transaction_id } => {
+                use crate::social::outbox::PostService;
+
+                let Some(client) = get_client() else { continue };
+                if let Err(e) = PostService::new(client).retry(&transaction_id) {
+                    error!("Failed to retry queued post {transaction_id}: {e}");
+                }
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadSocialProfile { user_id } => {
+                use crate::social::{
+                    widgets::profile_page::{LoadedProfile, SocialProfileAction},
+                    ProfileRoomService,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let _load_profile_task = Handle::current().spawn(async move {
+                    let display_name = match client.account().fetch_user_profile_of(&user_id).await
+                    {
+                        Ok(response) => response.get_static::<DisplayName>().ok().flatten(),
+                        Err(e) => {
+                            warning!("Failed to fetch the display name of {user_id}: {e:?}");
+                            None
+                        }
+                    };
+                    let service = ProfileRoomService::new(client);
+                    let room_id = match service.find_profile_room(&user_id).await {
+                        Ok(room_id) => room_id,
+                        Err(e) => {
+                            warning!("Failed to find the profile room of {user_id}: {e:?}");
+                            None
+                        }
+                    };
+                    let social_profile = match room_id.as_deref() {
+                        Some(room_id) => service.get_profile(room_id).await.unwrap_or_else(|e| {
+                            warning!("Failed to load the social profile of {user_id}: {e:?}");
+                            None
+                        }),
+                        None => None,
+                    };
+                    Cx::post_action(SocialProfileAction::Loaded(LoadedProfile {
+                        user_id,
+                        room_id,
+                        display_name,
+                        social_profile,
+                        avatar_data: None,
+                        cover_data: None,
+                    }));
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::VotePoll {
+                room_id,
+                poll_id,
+                answers,
+            } => {
+                use crate::social::{poll::PollService, widgets::post_card::SocialPostCardAction};
+
+                let Some(client) = get_client() else { continue };
+                let _vote_poll_task = Handle::current().spawn(async move {
+                    let service = PollService::new(client);
+                    let results = match service.vote(&room_id, &poll_id, answers).await {
+                        Ok(results) => results,
+                        Err(e) => {
+                            error!("Failed to vote in poll {poll_id} in room {room_id}: {e}");
+                            // Undo the vote shown before it was sent
+                            match service.get_results(&room_id, &poll_id).await {
+                                Ok(results) => results,
+                                Err(_) => return,
+                            }
+                        }
+                    };
+                    Cx::post_action(SocialPostCardAction::PollResultsLoaded(results));
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::EndPoll { room_id, poll_id } => {
+                use crate::social::{poll::PollService, widgets::post_card::SocialPostCardAction};
+
+                let Some(client) = get_client() else { continue };
+                let _end_poll_task = Handle::current().spawn(async move {
+                    match PollService::new(client).end_poll(&room_id, &poll_id).await {
+                        Ok(results) => {
+                            Cx::post_action(SocialPostCardAction::PollResultsLoaded(results))
+                        }
+                        Err(e) => error!("Failed to end poll {poll_id} in room {room_id}: {e}"),
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadPollResults { room_id, poll_id } => {
+                use crate::social::{poll::PollService, widgets::post_card::SocialPostCardAction};
+
+                let Some(client) = get_client() else { continue };
+                let _load_poll_task = Handle::current().spawn(async move {
+                    match PollService::new(client)
+                        .get_results(&room_id, &poll_id)
+                        .await
+                    {
+                        Ok(results) => {
+                            Cx::post_action(SocialPostCardAction::PollResultsLoaded(results))
+                        }
+                        Err(e) => {
+                            warning!(
+                                "Failed to load the votes of poll {poll_id} in room {room_id}: {e}"
+                            )
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadChatTargets => {
+                use crate::social::share_to_chat::{ShareToChatAction, ShareToChatService};
+
+                let Some(client) = get_client() else { continue };
+                let _load_chat_targets_task = Handle::current().spawn(async move {
+                    let targets = ShareToChatService::new(client).chat_targets().await;
+                    Cx::post_action(ShareToChatAction::TargetsLoaded(targets));
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SendPostToChat {
+                room_id,
+                post_id,
+                target_room_id,
+                confirmed,
+            } => {
+                use crate::social::share_to_chat::{
+                    ShareToChatAction, ShareToChatOutcome, ShareToChatService,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let _send_post_to_chat_task = Handle::current().spawn(async move {
+                    let result = ShareToChatService::new(client)
+                        .send_to_chat(&room_id, &post_id, &target_room_id, confirmed)
+                        .await;
+                    let action = match result {
+                        Ok(ShareToChatOutcome::Sent) => ShareToChatAction::Sent(target_room_id),
+                        Ok(ShareToChatOutcome::NeedsConfirmation(warning)) => {
+                            ShareToChatAction::NeedsConfirmation {
+                                room_id: target_room_id,
+                                warning,
+                            }
+                        }
+                        Err(e) => {
+                            warning!("Failed to send post {post_id} to room {target_room_id}: {e}");
+                            ShareToChatAction::Failed {
+                                room_id: target_room_id,
+                                error: e.to_string(),
+                            }
+                        }
+                    };
+                    Cx::post_action(action);
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadStorageUsage => {
+                use crate::social::storage_usage::{cache_limits, storage_usage, StorageUsageAction};
+
+                let Some(client) = get_client() else { continue };
+                let Some(user_id) = client.user_id().map(ToOwned::to_owned) else {
+                    continue;
+                };
+                let _load_storage_usage_task = Handle::current().spawn_blocking(move || {
+                    Cx::post_action(StorageUsageAction::Loaded {
+                        usage: storage_usage(&user_id),
+                        limits: cache_limits(),
+                    });
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::ClearSocialStorage(category) => {
+                use crate::social::storage_usage::{
+                    cache_limits, clear_storage, storage_usage, StorageUsageAction,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let Some(user_id) = client.user_id().map(ToOwned::to_owned) else {
+                    continue;
+                };
+                let _clear_storage_task = Handle::current().spawn_blocking(move || {
+                    if let Err(e) = clear_storage(&user_id, category) {
+                        warning!("Failed to clear {}: {e}", category.label());
+                        Cx::post_action(StorageUsageAction::Failed(e.to_string()));
+                    }
+                    Cx::post_action(StorageUsageAction::Loaded {
+                        usage: storage_usage(&user_id),
+                        limits: cache_limits(),
+                    });
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SetCacheLimits(limits) => {
+                use crate::social::storage_usage::{
+                    cache_limits, set_cache_limits, storage_usage, StorageUsageAction,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let Some(user_id) = client.user_id().map(ToOwned::to_owned) else {
+                    continue;
+                };
+                let _set_cache_limits_task = Handle::current().spawn(async move {
+                    if let Err(e) = set_cache_limits(&client, limits).await {
+                        warning!("Failed to set the social cache limits: {e}");
+                        Cx::post_action(StorageUsageAction::Failed(e.to_string()));
+                    }
+                    Cx::post_action(StorageUsageAction::Loaded {
+                        usage: storage_usage(&user_id),
+                        limits: cache_limits(),
+                    });
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadModerationPermissions { room_id } => {
+                use crate::social::feed_moderation::{FeedModerationAction, FeedModerationService};
+
+                let Some(client) = get_client() else { continue };
+                let _load_moderation_permissions_task = Handle::current().spawn(async move {
+                    let service = FeedModerationService::new(client);
+                    let permissions = match service.permissions(&room_id).await {
+                        Ok(permissions) => permissions,
+                        Err(e) => {
+                            warning!("Failed to load moderation permissions in {room_id}: {e}");
+                            return;
+                        }
+                    };
+                    let pinned_posts = service.pinned_posts(&room_id).await.unwrap_or_default();
+                    Cx::post_action(FeedModerationAction::PermissionsLoaded {
+                        room_id,
+                        permissions,
+                        pinned_posts,
+                    });
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::RemoveFeedPost { room_id, event_id } => {
+                use crate::social::feed_moderation::{FeedModerationAction, FeedModerationService};
+
+                let Some(client) = get_client() else { continue };
+                let _remove_feed_post_task = Handle::current().spawn(async move {
+                    let result = FeedModerationService::new(client)
+                        .remove_post(&room_id, &event_id)
+                        .await;
+                    match result {
+                        Ok(()) => {
+                            Cx::post_action(FeedModerationAction::PostRemoved { room_id, event_id })
+                        }
+                        Err(e) => {
+                            warning!("Failed to remove post {event_id} from {room_id}: {e}");
+                            Cx::post_action(FeedModerationAction::Failed(format!(
+                                "Failed to remove the post: {e}"
+                            )));
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::BanFeedCommenter { room_id, user_id } => {
+                use crate::social::feed_moderation::{FeedModerationAction, FeedModerationService};
+
+                let Some(client) = get_client() else { continue };
+                let _ban_feed_commenter_task = Handle::current().spawn(async move {
+                    let result = FeedModerationService::new(client)
+                        .ban_commenter(&room_id, &user_id, None)
+                        .await;
+                    match result {
+                        Ok(()) => {
+                            Cx::post_action(FeedModerationAction::UserBanned { room_id, user_id })
+                        }
+                        Err(e) => {
+                            warning!("Failed to ban {user_id} from {room_id}: {e}");
+                            Cx::post_action(FeedModerationAction::Failed(format!(
+                                "Failed to ban {user_id}: {e}"
+                            )));
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::PinFeedPost {
+                room_id,
+                event_id,
+                pinned,
+            } => {
+                use crate::social::feed_moderation::{FeedModerationAction, FeedModerationService};
+
+                let Some(client) = get_client() else { continue };
+                let _pin_feed_post_task = Handle::current().spawn(async move {
+                    let result = FeedModerationService::new(client)
+                        .pin_post(&room_id, &event_id, pinned)
+                        .await;
+                    match result {
+                        Ok(pinned_posts) => {
+                            Cx::post_action(FeedModerationAction::PinnedPostsChanged {
+                                room_id,
+                                pinned_posts,
+                            })
+                        }
+                        Err(e) => {
+                            warning!("Failed to pin post {event_id} in {room_id}: {e}");
+                            Cx::post_action(FeedModerationAction::Failed(format!(
+                                "Failed to pin the post: {e}"
+                            )));
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadSocialPushRules => {
+                use crate::social::push_rules::{PushRuleService, SocialPushRulesAction};
+
+                let Some(client) = get_client() else { continue };
+                let _load_social_push_rules_task = Handle::current().spawn(async move {
+                    match PushRuleService::new(client).enabled_categories().await {
+                        Ok(enabled) => Cx::post_action(SocialPushRulesAction::Loaded(enabled)),
+                        Err(e) => {
+                            warning!("Failed to load the social push rules: {e}");
+                            Cx::post_action(SocialPushRulesAction::Failed(format!(
+                                "Failed to load notification settings: {e}"
+                            )));
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SetSocialPushCategory { category, enabled } => {
+                use crate::social::push_rules::{PushRuleService, SocialPushRulesAction};
+
+                let Some(client) = get_client() else { continue };
+                let _set_social_push_category_task = Handle::current().spawn(async move {
+                    let service = PushRuleService::new(client);
+                    if let Err(e) = service.set_category_enabled(category, enabled).await {
+                        warning!("Failed to set the {category:?} push rules: {e}");
+                        Cx::post_action(SocialPushRulesAction::Failed(format!(
+                            "Failed to change notification settings: {e}"
+                        )));
+                    }
+                    if let Ok(enabled) = service.enabled_categories().await {
+                        Cx::post_action(SocialPushRulesAction::Loaded(enabled));
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::TranslatePost { event_id, text } => {
+                let _translate_post_task = Handle::current()
+                    .spawn(crate::social::translation::translate_post(event_id, text));
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SetFeedDensity(density) => {
+                use crate::social::newsfeed::set_feed_density;
+
+                let Some(client) = get_client() else { continue };
+                let _set_feed_density_task = Handle::current().spawn(async move {
+                    if let Err(e) = set_feed_density(&client, density).await {
+                        warning!("Failed to set the newsfeed density: {e}");
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::Unfriend(user_id) => {
+                use crate::social::friends::FriendsSpaceService;
+
+                let Some(client) = get_client() else { continue };
+                let _unfriend_task = Handle::current().spawn(async move {
+                    if let Err(e) = FriendsSpaceService::new(client).unfriend(&user_id).await {
+                        warning!("Failed to unfriend {user_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to unfriend {user_id}: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::CheckUserVerification(user_id) => {
+                use crate::social::user_verification::check_user_verification;
+
+                let Some(client) = get_client() else { continue };
+                let _check_user_verification_task = Handle::current().spawn(async move {
+                    if let Err(e) = check_user_verification(&client, &user_id).await {
+                        warning!("Failed to look up whether {user_id} is verified: {e}");
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::VerifyUser(user_id) => {
+                use crate::verification::request_user_verification;
+
+                let Some(client) = get_client() else { continue };
+                let _verify_user_task = Handle::current().spawn(async move {
+                    if let Err(e) = request_user_verification(client, user_id.clone()).await {
+                        warning!("Failed to request verification of {user_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to verify {user_id}: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadPresence(user_ids) => {
+                let Some(client) = get_client() else { continue };
+                let _load_presence_task = Handle::current()
+                    .spawn(crate::social::presence::load_presence(client, user_ids));
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadBirthdays => {
+                use crate::social::newsfeed::{load_birthdays_this_week, BirthdaysAction};
+
+                let Some(client) = get_client() else { continue };
+                let _load_birthdays_task = Handle::current().spawn(async move {
+                    match load_birthdays_this_week(&client).await {
+                        Ok(birthdays) => Cx::post_action(BirthdaysAction::Loaded(birthdays)),
+                        Err(e) => warning!("Failed to load friends' birthdays: {e}"),
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SetBirthdaysHidden(hidden) => {
+                use crate::social::newsfeed::set_birthdays_hidden;
+
+                let Some(client) = get_client() else { continue };
+                let _set_birthdays_hidden_task = Handle::current().spawn(async move {
+                    if let Err(e) = set_birthdays_hidden(&client, hidden).await {
+                        warning!("Failed to save the birthdays card setting: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to save the birthdays card setting: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadProfileMedia { user_id } => {
+                use crate::social::profile_tabs::{load_profile_media, ProfileTab, ProfileTabAction};
+
+                let Some(client) = get_client() else { continue };
+                let _load_profile_media_task = Handle::current().spawn(async move {
+                    match load_profile_media(&client, &user_id).await {
+                        Ok(posts) => {
+                            Cx::post_action(ProfileTabAction::MediaLoaded { user_id, posts })
+                        }
+                        Err(e) => {
+                            warning!("Failed to load the media posts of {user_id}: {e}");
+                            Cx::post_action(ProfileTabAction::Failed {
+                                tab: ProfileTab::Media,
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadLikedPosts => {
+                use crate::social::profile_tabs::{load_liked_posts, ProfileTab, ProfileTabAction};
+
+                let Some(client) = get_client() else { continue };
+                let _load_liked_posts_task = Handle::current().spawn(async move {
+                    match load_liked_posts(&client).await {
+                        Ok(posts) => Cx::post_action(ProfileTabAction::LikesLoaded(posts)),
+                        Err(e) => {
+                            warning!("Failed to load the posts you reacted to: {e}");
+                            Cx::post_action(ProfileTabAction::Failed {
+                                tab: ProfileTab::Likes,
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadProfilePosts { user_id } => {
+                use crate::social::profile_tabs::{
+                    load_profile_posts, ProfileTab, ProfileTabAction, MAX_PROFILE_POSTS,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let _load_profile_posts_task = Handle::current().spawn(async move {
+                    match load_profile_posts(&client, &user_id, MAX_PROFILE_POSTS).await {
+                        Ok(posts) => {
+                            Cx::post_action(ProfileTabAction::PostsLoaded { user_id, posts })
+                        }
+                        Err(e) => {
+                            warning!("Failed to load the posts of {user_id}: {e}");
+                            Cx::post_action(ProfileTabAction::Failed {
+                                tab: ProfileTab::Posts,
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadMutualFriends { user_id } => {
+                use crate::social::discovery::{FriendSuggestionEngine, MutualFriendsAction};
+
+                let Some(client) = get_client() else { continue };
+                let _load_mutual_friends_task = Handle::current().spawn(async move {
+                    let engine = FriendSuggestionEngine::new(client);
+                    let mutual_friends =
+                        engine.mutual_friends(&user_id).await.unwrap_or_else(|e| {
+                            warning!("Failed to load the friends shared with {user_id}: {e}");
+                            None
+                        });
+                    Cx::post_action(MutualFriendsAction::Loaded {
+                        user_id,
+                        mutual_friends,
+                    });
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::Follow(user_id) => {
+                use crate::social::{widgets::profile_page::SocialProfileAction, FollowService};
+
+                let Some(client) = get_client() else { continue };
+                let _follow_task = Handle::current().spawn(async move {
+                    let following = match FollowService::new(client).follow(&user_id).await {
+                        Ok(_) => true,
+                        Err(e) => {
+                            warning!("Failed to follow {user_id}: {e}");
+                            enqueue_popup_notification(PopupItem {
+                                message: format!("Failed to follow {user_id}: {e}"),
+                                kind: PopupKind::Error,
+                                auto_dismissal_duration: None,
+                            });
+                            false
+                        }
+                    };
+                    Cx::post_action(SocialProfileAction::FollowChanged { user_id, following });
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::Unfollow(user_id) => {
+                use crate::social::{widgets::profile_page::SocialProfileAction, FollowService};
+
+                let Some(client) = get_client() else { continue };
+                let _unfollow_task = Handle::current().spawn(async move {
+                    let following = match FollowService::new(client).unfollow(&user_id).await {
+                        Ok(()) => false,
+                        Err(e) => {
+                            warning!("Failed to unfollow {user_id}: {e}");
+                            enqueue_popup_notification(PopupItem {
+                                message: format!("Failed to unfollow {user_id}: {e}"),
+                                kind: PopupKind::Error,
+                                auto_dismissal_duration: None,
+                            });
+                            true
+                        }
+                    };
+                    Cx::post_action(SocialProfileAction::FollowChanged { user_id, following });
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::RemoveFollower(user_id) => {
+                use crate::social::{
+                    widgets::profile_page::SocialProfileAction, FollowerModerationService,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let _remove_follower_task = Handle::current().spawn(async move {
+                    let service = FollowerModerationService::new(client);
+                    match service.remove_follower(&user_id, None).await {
+                        Ok(()) => Cx::post_action(SocialProfileAction::FollowerRemoved(user_id)),
+                        Err(e) => {
+                            warning!("Failed to remove follower {user_id}: {e}");
+                            enqueue_popup_notification(PopupItem {
+                                message: format!("Failed to remove {user_id}: {e}"),
+                                kind: PopupKind::Error,
+                                auto_dismissal_duration: None,
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::BanFromFeeds(user_id) => {
+                use crate::social::{
+                    widgets::profile_page::SocialProfileAction, FollowerModerationService,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let _ban_from_feeds_task = Handle::current().spawn(async move {
+                    let service = FollowerModerationService::new(client);
+                    match service.ban_from_feeds(&user_id, None).await {
+                        Ok(()) => Cx::post_action(SocialProfileAction::FollowerRemoved(user_id)),
+                        Err(e) => {
+                            warning!("Failed to ban {user_id} from feeds: {e}");
+                            enqueue_popup_notification(PopupItem {
+                                message: format!("Failed to ban {user_id}: {e}"),
+                                kind: PopupKind::Error,
+                                auto_dismissal_duration: None,
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SaveProfileImage {
+                room_id,
+                kind,
+                data,
+            } => {
+                use crate::social::{ProfileImageKind, ProfileRoomService};
+
+                let Some(client) = get_client() else { continue };
+                let _save_profile_image_task = Handle::current().spawn(async move {
+                    let service = ProfileRoomService::new(client);
+                    let result = match kind {
+                        ProfileImageKind::CoverPhoto => {
+                            service.set_cover_photo(&room_id, data.to_vec()).await
+                        }
+                        ProfileImageKind::Avatar => {
+                            service.set_profile_avatar(&room_id, data.to_vec()).await
+                        }
+                    };
+                    if let Err(e) = result {
+                        warning!("Failed to save the {kind:?} of profile room {room_id}: {e}");
+                        let image = match kind {
+                            ProfileImageKind::CoverPhoto => "cover photo",
+                            ProfileImageKind::Avatar => "profile photo",
+                        };
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to save your {image}: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::TogglePostReaction {
+                room_id,
+                post_id,
+                emoji,
+                added,
+                transaction_id,
+            } => {
+                use crate::social::{optimistic::OptimisticAction, reactions::send_own_reaction};
+
+                let Some(client) = get_client() else { continue };
+                let _toggle_reaction_task = Handle::current().spawn(async move {
+                    let result = match client.get_room(&room_id) {
+                        Some(room) => send_own_reaction(
+                            &room,
+                            &post_id,
+                            &emoji,
+                            added,
+                            transaction_id.clone(),
+                        )
+                        .await
+                        .map_err(|e| e.to_string()),
+                        None => Err(format!("Room {room_id} not found")),
+                    };
+                    let action = match result {
+                        Ok(event_id) => OptimisticAction::Confirmed {
+                            transaction_id,
+                            // With no reaction left to redact, the change is already in effect
+                            event_id: event_id.unwrap_or(post_id),
+                        },
+                        Err(error) => {
+                            warning!("Failed to toggle reaction {emoji} on {post_id}: {error}");
+                            OptimisticAction::Failed {
+                                transaction_id,
+                                error,
+                            }
+                        }
+                    };
+                    Cx::post_action(action);
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadMoreProfilePosts { user_id, limit } => {
+                use crate::social::profile_tabs::{load_profile_posts, ProfileTab, ProfileTabAction};
+
+                let Some(client) = get_client() else { continue };
+                let _load_more_profile_posts_task = Handle::current().spawn(async move {
+                    match load_profile_posts(&client, &user_id, limit).await {
+                        Ok(posts) => {
+                            Cx::post_action(ProfileTabAction::MorePostsLoaded { user_id, posts })
+                        }
+                        Err(e) => {
+                            warning!("Failed to load more posts of {user_id}: {e}");
+                            Cx::post_action(ProfileTabAction::Failed {
+                                tab: ProfileTab::Posts,
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SetTranslationSettings(settings) => {
+                use crate::social::translation::set_translation_settings;
+
+                let Some(client) = get_client() else { continue };
+                let _set_translation_settings_task = Handle::current().spawn(async move {
+                    if let Err(e) = set_translation_settings(&client, settings).await {
+                        warning!("Failed to save the translation service: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to save the translation service: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SaveNotSpamSender(sender) => {
+                use crate::social::newsfeed::save_not_spam_sender;
+
+                let Some(client) = get_client() else { continue };
+                let _save_not_spam_sender_task = Handle::current().spawn(async move {
+                    if let Err(e) = save_not_spam_sender(&client, sender).await {
+                        warning!("Failed to save the sender marked as not spam: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to remember that the post isn't spam: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadMainFeed { limit } => {
+                use crate::social::main_feed::{load_main_feed, MainFeedAction};
+
+                let Some(client) = get_client() else { continue };
+                let _load_main_feed_task = Handle::current().spawn(async move {
+                    match load_main_feed(&client, limit).await {
+                        Ok(posts) => Cx::post_action(MainFeedAction::Loaded { limit, posts }),
+                        Err(e) => {
+                            warning!("Failed to load the newsfeed: {e}");
+                            Cx::post_action(MainFeedAction::Failed(e.to_string()));
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SaveFeedFilter(filter) => {
+                use crate::social::newsfeed::save_feed_filter;
+
+                let Some(client) = get_client() else { continue };
+                let _save_feed_filter_task = Handle::current().spawn(async move {
+                    if let Err(e) = save_feed_filter(&client, &filter).await {
+                        warning!("Failed to save the newsfeed filter: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to save the newsfeed filters: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadRetentionPolicy(feed) => {
+                use crate::social::retention::{RetentionJobAction, RetentionService};
+
+                let Some(client) = get_client() else { continue };
+                let _load_retention_policy_task = Handle::current().spawn(async move {
+                    match RetentionService::new(client).get_policy(feed).await {
+                        Ok(policy) => {
+                            Cx::post_action(RetentionJobAction::PolicyLoaded { feed, policy })
+                        }
+                        // The feed may not have been created yet
+                        Err(e) => {
+                            warning!("Failed to load the retention policy of the {feed} feed: {e}")
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SetRetentionMaxAge {
+                feed,
+                max_age_months,
+            } => {
+                use crate::social::retention::RetentionService;
+
+                let Some(client) = get_client() else { continue };
+                let _set_retention_max_age_task = Handle::current().spawn(async move {
+                    if let Err(e) = RetentionService::new(client)
+                        .set_max_age(feed, max_age_months)
+                        .await
+                    {
+                        warning!("Failed to set the retention policy of the {feed} feed: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to save the retention policy: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::PinRetainedPost { feed, event_id } => {
+                use crate::social::retention::RetentionService;
+
+                let Some(client) = get_client() else { continue };
+                let _pin_retained_post_task = Handle::current().spawn(async move {
+                    if let Err(e) = RetentionService::new(client)
+                        .set_pinned(feed, event_id, true)
+                        .await
+                    {
+                        warning!("Failed to keep a post of the {feed} feed: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to keep the post: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::RunRetentionPolicy { feed, dry_run } => {
+                use crate::social::retention::{RetentionJobAction, RetentionService};
+
+                let Some(client) = get_client() else { continue };
+                let _run_retention_policy_task = Handle::current().spawn(async move {
+                    let service = RetentionService::new(client);
+                    let result = if dry_run {
+                        service.preview(feed).await
+                    } else {
+                        service.apply(feed).await
+                    };
+                    match result {
+                        Ok(report) => Cx::post_action(RetentionJobAction::Completed(report)),
+                        Err(e) => {
+                            warning!("Failed to run the retention policy of the {feed} feed: {e}");
+                            enqueue_popup_notification(PopupItem {
+                                message: format!("Failed to find your old posts: {e}"),
+                                kind: PopupKind::Error,
+                                auto_dismissal_duration: None,
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadFollowers => {
+                use crate::social::{follow::FollowListsAction, FollowService};
+
+                let Some(client) = get_client() else { continue };
+                let _load_followers_task = Handle::current().spawn(async move {
+                    match FollowService::new(client).list_followers().await {
+                        Ok(users) => Cx::post_action(FollowListsAction::FollowersLoaded(users)),
+                        Err(e) => {
+                            warning!("Failed to list your followers: {e}");
+                            Cx::post_action(FollowListsAction::Failed(format!(
+                                "Failed to list your followers: {e}"
+                            )));
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadFollowing => {
+                use crate::social::{follow::FollowListsAction, FollowService};
+
+                let Some(client) = get_client() else { continue };
+                match FollowService::new(client).list_following() {
+                    Ok(users) => Cx::post_action(FollowListsAction::FollowingLoaded(users)),
+                    Err(e) => {
+                        warning!("Failed to list the users you follow: {e}");
+                        Cx::post_action(FollowListsAction::Failed(format!(
+                            "Failed to list the users you follow: {e}"
+                        )));
+                    }
+                }
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadReactionTiming {
+                room_id,
+                event_id,
+                posted_at,
+            } => {
+                use crate::social::reactions::{load_reaction_timestamps, reaction_timing};
+                use crate::social::widgets::post_detail::PostDetailAction;
+
+                let Some(client) = get_client() else { continue };
+                let _load_reaction_timing_task = Handle::current().spawn(async move {
+                    let result = match client.get_room(&room_id) {
+                        Some(room) => load_reaction_timestamps(&room, &event_id)
+                            .await
+                            .map_err(|e| e.to_string()),
+                        None => Err(format!("Room {room_id} not found")),
+                    };
+                    match result {
+                        Ok(timestamps) => Cx::post_action(PostDetailAction::ReactionTimingLoaded {
+                            buckets: reaction_timing(posted_at.get().into(), timestamps),
+                            event_id,
+                        }),
+                        Err(error) => {
+                            warning!("Failed to load the reactions to {event_id}: {error}");
+                            enqueue_popup_notification(PopupItem {
+                                message: format!(
+                                    "Failed to load the reactions to this post: {error}"
+                                ),
+                                kind: PopupKind::Error,
+                                auto_dismissal_duration: None,
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadActivityLog(range) => {
+                use crate::social::{activity_log::ActivityLogAction, ActivityLogService};
+
+                let Some(client) = get_client() else { continue };
+                let _load_activity_log_task = Handle::current().spawn(async move {
+                    match ActivityLogService::new(client).load(range).await {
+                        Ok(entries) => Cx::post_action(ActivityLogAction::Loaded(entries)),
+                        Err(e) => {
+                            warning!("Failed to load the activity log: {e}");
+                            Cx::post_action(ActivityLogAction::Failed(e.to_string()));
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::UndoActivities(entries) => {
+                use crate::social::{activity_log::ActivityLogAction, ActivityLogService};
+
+                let Some(client) = get_client() else { continue };
+                let _undo_activities_task = Handle::current().spawn(async move {
+                    let report = ActivityLogService::new(client).undo(&entries).await;
+                    for (entry, error) in &report.failed {
+                        warning!("Failed to undo activity {entry:?}: {error}");
+                    }
+                    Cx::post_action(ActivityLogAction::Undone(report));
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadEventsCalendar => {
+                use crate::social::discovery::EventDiscoveryService;
+                use crate::social::profile_tabs::{ProfileTab, ProfileTabAction};
+
+                let Some(client) = get_client() else { continue };
+                let _load_events_calendar_task = Handle::current().spawn(async move {
+                    match EventDiscoveryService::new(client).discover_events().await {
+                        Ok(events) => Cx::post_action(ProfileTabAction::EventsLoaded(events)),
+                        Err(e) => {
+                            warning!("Failed to find your events: {e}");
+                            Cx::post_action(ProfileTabAction::Failed {
+                                tab: ProfileTab::Events,
+                                error: e.to_string(),
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadEventInviteFriends { room_id } => {
+                use crate::social::discovery::FriendSuggestionEngine;
+                use crate::social::events::EventRoomService;
+                use crate::social::widgets::event_invite_sheet::EventInvitesAction;
+                use crate::social::widgets::friend_list::FriendInfo;
+
+                let Some(client) = get_client() else { continue };
+                let _load_event_invite_friends_task = Handle::current().spawn(async move {
+                    let friend_feeds = match FriendSuggestionEngine::new(client.clone())
+                        .own_friend_feeds()
+                        .await
+                    {
+                        Ok(friend_feeds) => friend_feeds,
+                        Err(e) => {
+                            warning!("Failed to list your friends: {e}");
+                            Cx::post_action(EventInvitesAction::Failed(format!(
+                                "Failed to list your friends: {e}"
+                            )));
+                            return;
+                        }
+                    };
+                    let already_invited = match EventRoomService::new(client)
+                        .invited_or_joined(&room_id)
+                        .await
+                    {
+                        Ok(already_invited) => already_invited,
+                        Err(e) => {
+                            warning!("Failed to load the guests of event {room_id}: {e}");
+                            Cx::post_action(EventInvitesAction::Failed(format!(
+                                "Failed to load the event's guests: {e}"
+                            )));
+                            return;
+                        }
+                    };
+
+                    let mut friends = Vec::with_capacity(friend_feeds.len());
+                    for (user_id, feed) in friend_feeds {
+                        friends.push(FriendInfo::from_friend_feed(user_id, &feed).await);
+                    }
+                    Cx::post_action(EventInvitesAction::FriendsLoaded {
+                        room_id,
+                        friends,
+                        already_invited,
+                    });
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::InviteEventGuests { room_id, user_ids } => {
+                use crate::social::events::EventRoomService;
+                use crate::social::widgets::event_invite_sheet::EventInvitesAction;
+
+                let Some(client) = get_client() else { continue };
+                let _invite_event_guests_task = Handle::current().spawn(async move {
+                    match EventRoomService::new(client)
+                        .invite_guests(&room_id, &user_ids)
+                        .await
+                    {
+                        Ok(results) => {
+                            let results = results
+                                .into_iter()
+                                .map(|invite| {
+                                    if let Err(e) = &invite.result {
+                                        warning!(
+                                            "Failed to invite {} to {room_id}: {e}",
+                                            invite.user_id
+                                        );
+                                    }
+                                    (invite.user_id, invite.result.map_err(|e| e.to_string()))
+                                })
+                                .collect();
+                            Cx::post_action(EventInvitesAction::Sent { room_id, results });
+                        }
+                        Err(e) => {
+                            warning!("Failed to invite guests to {room_id}: {e}");
+                            Cx::post_action(EventInvitesAction::Failed(format!(
+                                "Failed to send the invites: {e}"
+                            )));
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadOwnFeeds => {
+                use crate::social::{widgets::post_composer::SocialPostComposerAction, FeedRoomService};
+
+                let Some(client) = get_client() else { continue };
+                let _load_own_feeds_task = Handle::current().spawn(async move {
+                    match FeedRoomService::new(client).get_own_feeds().await {
+                        Ok(feeds) => Cx::post_action(SocialPostComposerAction::FeedsLoaded(feeds)),
+                        Err(e) => warning!("Failed to find the user's feeds: {e}"),
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadFriendList => {
+                use crate::social::discovery::FriendSuggestionEngine;
+                use crate::social::friends::FriendRequestService;
+                use crate::social::widgets::friend_list::{FriendInfo, FriendListAction};
+
+                let Some(client) = get_client() else { continue };
+                let _load_friend_list_task = Handle::current().spawn(async move {
+                    let friend_feeds = match FriendSuggestionEngine::new(client.clone())
+                        .own_friend_feeds()
+                        .await
+                    {
+                        Ok(friend_feeds) => friend_feeds,
+                        Err(e) => {
+                            warning!("Failed to list your friends: {e}");
+                            Cx::post_action(FriendListAction::Failed(format!(
+                                "Failed to list your friends: {e}"
+                            )));
+                            return;
+                        }
+                    };
+                    let requests = FriendRequestService::new(client);
+                    let (pending_requests, outgoing_requests) = match (
+                        requests.get_pending_requests().await,
+                        requests.get_outgoing_requests().await,
+                    ) {
+                        (Ok(pending), Ok(outgoing)) => (pending, outgoing),
+                        (Err(e), _) | (_, Err(e)) => {
+                            warning!("Failed to list your friend requests: {e}");
+                            Cx::post_action(FriendListAction::Failed(format!(
+                                "Failed to list your friend requests: {e}"
+                            )));
+                            return;
+                        }
+                    };
+
+                    let mut friends = Vec::with_capacity(friend_feeds.len());
+                    for (user_id, feed) in friend_feeds {
+                        friends.push(FriendInfo::from_friend_feed(user_id, &feed).await);
+                    }
+                    Cx::post_action(FriendListAction::Loaded {
+                        friends,
+                        pending_requests: pending_requests.into_iter().map(Into::into).collect(),
+                        outgoing_requests: outgoing_requests.into_iter().map(Into::into).collect(),
+                    });
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::AnswerFriendRequest { user_id, accept } => {
+                use crate::social::feed_room::{FeedPrivacy, FeedRoomService};
+                use crate::social::friends::FriendRequestService;
+
+                let Some(client) = get_client() else { continue };
+                let _answer_friend_request_task = Handle::current().spawn(async move {
+                    let verb = if accept { "accept" } else { "decline" };
+                    let Some(own_user_id) = client.user_id() else {
+                        return;
+                    };
+                    let Some(feed) = FeedRoomService::new(client.clone())
+                        .find_joined_feed(own_user_id, FeedPrivacy::Friends)
+                    else {
+                        warning!("No friends feed to {verb} the request from {user_id}");
+                        return;
+                    };
+                    let requests = FriendRequestService::new(client.clone());
+                    let result = if accept {
+                        requests
+                            .accept_friend_request(&user_id, feed.room_id())
+                            .await
+                    } else {
+                        requests
+                            .decline_friend_request(&user_id, feed.room_id())
+                            .await
+                    };
+                    if let Err(e) = result {
+                        warning!("Failed to {verb} the friend request from {user_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!(
+                                "Failed to {verb} the friend request from {user_id}: {e}"
+                            ),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::CancelFriendRequest { room_id } => {
+                use crate::social::friends::FriendRequestService;
+
+                let Some(client) = get_client() else { continue };
+                let _cancel_friend_request_task = Handle::current().spawn(async move {
+                    if let Err(e) = FriendRequestService::new(client)
+                        .cancel_friend_request(&room_id)
+                        .await
+                    {
+                        warning!("Failed to cancel the friend request to {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to cancel your friend request: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadEventDetail { room_id } => {
+                use crate::social::events::{
+                    EventInsightsService, EventRoomService, QuietAttendanceService, RsvpCounts,
+                    RsvpService,
+                };
+                use crate::social::widgets::event_card::LoadedEvent;
+                use crate::social::widgets::event_detail::EventDetailLoadAction;
+
+                let Some(client) = get_client() else { continue };
+                let _load_event_detail_task = Handle::current().spawn(async move {
+                    let rooms = EventRoomService::new(client.clone());
+                    let loaded = match (
+                        rooms.get_event(&room_id).await,
+                        RsvpService::new(client.clone()).get_rsvps(&room_id).await,
+                    ) {
+                        (Ok(event), Ok(rsvps)) => LoadedEvent {
+                            event,
+                            rsvp_counts: RsvpCounts::tally(&rsvps),
+                            user_rsvp: rsvps
+                                .iter()
+                                .find(|rsvp| Some(&*rsvp.user_id) == client.user_id())
+                                .map(|rsvp| rsvp.status),
+                            // The card shows the RSVPs without the invite count
+                            invited_count: rooms.invited_count(&room_id).await.unwrap_or(0),
+                            cover_data: None,
+                            room_id: room_id.clone(),
+                        },
+                        (Err(e), _) => {
+                            warning!("Failed to load event {room_id}: {e}");
+                            Cx::post_action(EventDetailLoadAction::Failed {
+                                room_id,
+                                error: format!("Failed to load the event: {e}"),
+                            });
+                            return;
+                        }
+                        (_, Err(e)) => {
+                            warning!("Failed to load the RSVPs of event {room_id}: {e}");
+                            Cx::post_action(EventDetailLoadAction::Failed {
+                                room_id,
+                                error: format!("Failed to load the event's RSVPs: {e}"),
+                            });
+                            return;
+                        }
+                    };
+                    let event = loaded.event.clone();
+                    Cx::post_action(EventDetailLoadAction::Loaded(loaded));
+
+                    let quiet = QuietAttendanceService::new(client.clone())
+                        .is_attending_quietly(&room_id)
+                        .await;
+                    Cx::post_action(EventDetailLoadAction::AttendQuietly {
+                        room_id: room_id.clone(),
+                        quiet,
+                    });
+                    load_event_checkins(&client, room_id.clone()).await;
+                    load_event_checklist(&client, room_id.clone()).await;
+                    if event.logistics {
+                        load_event_ride_board(&client, room_id.clone()).await;
+                    }
+                    load_event_scheduling_poll(&client, room_id.clone()).await;
+
+                    // Only hosts are shown the dashboard and the members' roles
+                    let insights = EventInsightsService::new(client.clone());
+                    if !insights.is_host(&room_id).await.unwrap_or(false) {
+                        return;
+                    }
+                    match insights.get_insights(&room_id).await {
+                        Ok(insights) => Cx::post_action(EventDetailLoadAction::Insights {
+                            summary: insights.summary_text(&event),
+                            insights,
+                            room_id: room_id.clone(),
+                        }),
+                        Err(e) => warning!("Failed to load the insights of event {room_id}: {e}"),
+                    }
+                    load_event_roles(&client, room_id).await;
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SetAttendQuietly { room_id, quiet } => {
+                use crate::social::events::QuietAttendanceService;
+                use crate::social::widgets::event_detail::EventDetailLoadAction;
+
+                let Some(client) = get_client() else { continue };
+                let _set_attend_quietly_task = Handle::current().spawn(async move {
+                    if let Err(e) = QuietAttendanceService::new(client)
+                        .set_attend_quietly(&room_id, quiet)
+                        .await
+                    {
+                        warning!("Failed to change quiet attendance of event {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!(
+                                "Failed to change your notifications for the event: {e}"
+                            ),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                        Cx::post_action(EventDetailLoadAction::AttendQuietly {
+                            room_id,
+                            quiet: !quiet,
+                        });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::AddEventCohost { room_id, user_id } => {
+                use crate::social::events::EventRoomService;
+
+                let Some(client) = get_client() else { continue };
+                let _add_event_cohost_task = Handle::current().spawn(async move {
+                    if let Err(e) = EventRoomService::new(client.clone())
+                        .add_cohost(&room_id, &user_id)
+                        .await
+                    {
+                        warning!("Failed to make {user_id} a co-host of event {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to make {user_id} a co-host: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                    load_event_roles(&client, room_id).await;
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::RemoveEventCohost { room_id, user_id } => {
+                use crate::social::events::EventRoomService;
+
+                let Some(client) = get_client() else { continue };
+                let _remove_event_cohost_task = Handle::current().spawn(async move {
+                    if let Err(e) = EventRoomService::new(client.clone())
+                        .remove_cohost(&room_id, &user_id)
+                        .await
+                    {
+                        warning!("Failed to remove {user_id} as co-host of event {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to remove {user_id} as co-host: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                    load_event_roles(&client, room_id).await;
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::CheckInToEvent { room_id, message } => {
+                use crate::social::events::EventCheckinService;
+
+                let Some(client) = get_client() else { continue };
+                let _check_in_to_event_task = Handle::current().spawn(async move {
+                    match EventCheckinService::new(client.clone())
+                        .check_in(&room_id, message, None)
+                        .await
+                    {
+                        Ok(_) => load_event_checkins(&client, room_id).await,
+                        Err(e) => {
+                            warning!("Failed to check in to event {room_id}: {e}");
+                            enqueue_popup_notification(PopupItem {
+                                message: format!("Failed to check in: {e}"),
+                                kind: PopupKind::Error,
+                                auto_dismissal_duration: None,
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::AddChecklistItem {
+                room_id,
+                title,
+                kind,
+                quantity,
+            } => {
+                use crate::social::events::ChecklistService;
+
+                let Some(client) = get_client() else { continue };
+                let _add_checklist_item_task = Handle::current().spawn(async move {
+                    if let Err(e) = ChecklistService::new(client.clone())
+                        .add_item(&room_id, &title, kind, quantity)
+                        .await
+                    {
+                        warning!("Failed to add a checklist item to event {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to add the checklist item: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                    load_event_checklist(&client, room_id).await;
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SetChecklistClaim {
+                room_id,
+                item_id,
+                quantity,
+            } => {
+                use crate::social::events::ChecklistService;
+
+                let Some(client) = get_client() else { continue };
+                let _set_checklist_claim_task = Handle::current().spawn(async move {
+                    if let Err(e) = ChecklistService::new(client.clone())
+                        .set_claim(&room_id, &item_id, quantity)
+                        .await
+                    {
+                        warning!(
+                            "Failed to claim checklist item {item_id} of event {room_id}: {e}"
+                        );
+                        enqueue_popup_notification(PopupItem {
+                            message: format!(
+                                "Failed to update your claim on the checklist item: {e}"
+                            ),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                    load_event_checklist(&client, room_id).await;
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SetChecklistItemDone {
+                room_id,
+                item_id,
+                done,
+            } => {
+                use crate::social::events::ChecklistService;
+
+                let Some(client) = get_client() else { continue };
+                let _set_checklist_item_done_task = Handle::current().spawn(async move {
+                    if let Err(e) = ChecklistService::new(client.clone())
+                        .set_done(&room_id, &item_id, done)
+                        .await
+                    {
+                        warning!(
+                            "Failed to check off checklist item {item_id} of event {room_id}: {e}"
+                        );
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to check off the checklist item: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                    load_event_checklist(&client, room_id).await;
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::OfferRide {
+                room_id,
+                seats,
+                area,
+            } => {
+                use crate::social::events::LogisticsService;
+
+                let Some(client) = get_client() else { continue };
+                let _offer_ride_task = Handle::current().spawn(async move {
+                    if let Err(e) = LogisticsService::new(client.clone())
+                        .offer_ride(&room_id, seats, &area, None)
+                        .await
+                    {
+                        warning!("Failed to offer a ride to event {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to offer a ride: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                    load_event_ride_board(&client, room_id).await;
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::RequestRide {
+                room_id,
+                seats,
+                area,
+            } => {
+                use crate::social::events::LogisticsService;
+
+                let Some(client) = get_client() else { continue };
+                let _request_ride_task = Handle::current().spawn(async move {
+                    if let Err(e) = LogisticsService::new(client.clone())
+                        .request_ride(&room_id, seats, &area, None)
+                        .await
+                    {
+                        warning!("Failed to request a ride to event {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to ask for a ride: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                    load_event_ride_board(&client, room_id).await;
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::WithdrawRide { room_id } => {
+                use crate::social::events::LogisticsService;
+
+                let Some(client) = get_client() else { continue };
+                let _withdraw_ride_task = Handle::current().spawn(async move {
+                    if let Err(e) = LogisticsService::new(client.clone())
+                        .withdraw(&room_id)
+                        .await
+                    {
+                        warning!("Failed to withdraw the ride to event {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to withdraw your ride: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                    load_event_ride_board(&client, room_id).await;
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::VoteSchedulingPoll {
+                room_id,
+                start_times,
+            } => {
+                use crate::social::events::SchedulingPollService;
+
+                let Some(client) = get_client() else { continue };
+                let _vote_scheduling_poll_task = Handle::current().spawn(async move {
+                    if let Err(e) = SchedulingPollService::new(client.clone())
+                        .vote(&room_id, &start_times)
+                        .await
+                    {
+                        warning!("Failed to vote in the scheduling poll of event {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to send your vote: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                    load_event_scheduling_poll(&client, room_id).await;
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::FinalizeSchedulingPoll { room_id } => {
+                use crate::social::events::SchedulingPollService;
+
+                let Some(client) = get_client() else { continue };
+                let _finalize_scheduling_poll_task = Handle::current().spawn(async move {
+                    match SchedulingPollService::new(client).finalize(&room_id).await {
+                        Ok(_) => submit_async_request(MatrixRequest::LoadEventDetail { room_id }),
+                        Err(e) => {
+                            warning!(
+                                "Failed to finalize the scheduling poll of event {room_id}: {e}"
+                            );
+                            enqueue_popup_notification(PopupItem {
+                                message: format!(
+                                    "Failed to move the event to the chosen date: {e}"
+                                ),
+                                kind: PopupKind::Error,
+                                auto_dismissal_duration: None,
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::KeepEventOpen { room_id } => {
+                use crate::social::events::EventRoomService;
+
+                let Some(client) = get_client() else { continue };
+                let _keep_event_open_task = Handle::current().spawn(async move {
+                    if let Err(e) = EventRoomService::new(client)
+                        .keep_event_open(&room_id)
+                        .await
+                    {
+                        warning!("Failed to keep event {room_id} open: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to keep the event room open: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                        submit_async_request(MatrixRequest::LoadEventDetail { room_id });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::ArchiveEvent { room_id } => {
+                use crate::social::events::EventRoomService;
+
+                let Some(client) = get_client() else { continue };
+                let _archive_event_task = Handle::current().spawn(async move {
+                    if let Err(e) = EventRoomService::new(client).archive_event(&room_id).await {
+                        warning!("Failed to archive event {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to archive the event room: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                        submit_async_request(MatrixRequest::LoadEventDetail { room_id });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::CloseEvent { room_id } => {
+                use crate::social::events::EventRoomService;
+                use crate::social::feed_room::{FeedPrivacy, FeedRoomService};
+
+                let Some(client) = get_client() else { continue };
+                let _close_event_task = Handle::current().spawn(async move {
+                    let Some(own_user_id) = client.user_id() else {
+                        return;
+                    };
+                    let result = match FeedRoomService::new(client.clone())
+                        .find_joined_feed(own_user_id, FeedPrivacy::Public)
+                    {
+                        Some(feed) => EventRoomService::new(client.clone())
+                            .close_event(&room_id, feed.room_id())
+                            .await
+                            .map_err(|e| e.to_string()),
+                        None => Err(String::from(
+                            "you have no public feed to point attendees to",
+                        )),
+                    };
+                    if let Err(e) = result {
+                        warning!("Failed to close event {room_id}: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to close the event room: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                        submit_async_request(MatrixRequest::LoadEventDetail { room_id });
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::QueuePostReply {
+                room_id,
+                post_id,
+                text,
+                media,
+            } => {
+                use crate::social::{outbox::PostService, post::Post};
+
+                let Some(client) = get_client() else { continue };
+                let content = Post::markdown(text).content;
+                if let Err(e) =
+                    PostService::new(client).queue_reply(room_id, post_id.clone(), &content, media)
+                {
+                    error!("Failed to queue the reply to post {post_id}: {e}");
+                    enqueue_popup_notification(PopupItem {
+                        message: format!("Failed to send your reply: {e}"),
+                        kind: PopupKind::Error,
+                        auto_dismissal_duration: None,
+                    });
+                }
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadFriendSuggestions => {
+                use crate::social::discovery::{
+                    FriendSuggestionEngine, FriendSuggestionsAction, MAX_FRIEND_SUGGESTIONS,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let _load_friend_suggestions_task = Handle::current().spawn(async move {
+                    match FriendSuggestionEngine::new(client)
+                        .get_friend_suggestions(MAX_FRIEND_SUGGESTIONS)
+                        .await
+                    {
+                        Ok(suggestions) => {
+                            Cx::post_action(FriendSuggestionsAction::Loaded(suggestions))
+                        }
+                        Err(e) => warning!("Failed to load friend suggestions: {e}"),
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SearchUsers { query } => {
+                use crate::social::discovery::{
+                    UserDiscoveryService, UserSearchAction, MAX_USER_SEARCH_RESULTS,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let _search_users_task = Handle::current().spawn(async move {
+                    match UserDiscoveryService::new(client)
+                        .search_users(&query, MAX_USER_SEARCH_RESULTS)
+                        .await
+                    {
+                        Ok(results) => Cx::post_action(UserSearchAction::Loaded { query, results }),
+                        Err(e) => {
+                            warning!("Failed to search for people: {e}");
+                            enqueue_popup_notification(PopupItem {
+                                message: format!("Failed to search for people: {e}"),
+                                kind: PopupKind::Error,
+                                auto_dismissal_duration: None,
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::LoadTrending => {
+                use crate::social::discovery::{
+                    TrendingAction, TrendingService, MAX_TRENDING_POSTS, MAX_TRENDING_TOPICS,
+                };
+
+                let Some(client) = get_client() else { continue };
+                let _load_trending_task = Handle::current().spawn(async move {
+                    match TrendingService::new(client)
+                        .get_trending(MAX_TRENDING_POSTS, MAX_TRENDING_TOPICS)
+                        .await
+                    {
+                        Ok(trending) => Cx::post_action(TrendingAction::Loaded(trending)),
+                        Err(e) => {
+                            warning!("Failed to load trending posts: {e}");
+                            enqueue_popup_notification(PopupItem {
+                                message: format!("Failed to load trending posts: {e}"),
+                                kind: PopupKind::Error,
+                                auto_dismissal_duration: None,
+                            });
+                        }
+                    }
+                });
+            }
+
+            #[cfg(feature = "social")]
+            MatrixRequest::SaveFeedGrouping(grouping) => {
+                use crate::social::newsfeed::save_feed_grouping;
+
+                let Some(client) = get_client() else { continue };
+                let _save_feed_grouping_task = Handle::current().spawn(async move {
+                    if let Err(e) = save_feed_grouping(&client, &grouping).await {
+                        warning!("Failed to save the newsfeed grouping: {e}");
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to save the newsfeed grouping: {e}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    }
+                });
+            }
+        }
+    }
+
+    error!("matrix_worker_task task ended unexpectedly");
+    bail!("matrix_worker_task task ended unexpectedly")
+}
+
+/// The single global Tokio runtime that is used by all async tasks.
+static TOKIO_RUNTIME: Mutex<Option<tokio::runtime::Runtime>> = Mutex::new(None);
+
+/// The sender used by [`submit_async_request`] to send requests to the async worker thread.
+/// Currently there is only one, but it can be cloned if we need more concurrent senders.
 static REQUEST_SENDER: Mutex<Option<UnboundedSender<MatrixRequest>>> = Mutex::new(None);
 
 /// A client object that is proactively created during initialization
@@ -1905,6 +4651,120 @@ static DEFAULT_SSO_CLIENT: Mutex<Option<(Client, ClientSessionPersisted)>> = Mut
 static DEFAULT_SSO_CLIENT_NOTIFIER: LazyLock<Arc<Notify>> =
     LazyLock::new(|| Arc::new(Notify::new()));
 
+/// Load the roles of an event's members and emit them
+/// in an [`EventDetailLoadAction::Roles`], along with the current user's role.
+///
+/// [`EventDetailLoadAction::Roles`]: crate::social::widgets::event_detail::EventDetailLoadAction::Roles
+#[cfg(feature = "social")]
+async fn load_event_roles(client: &Client, room_id: OwnedRoomId) {
+    use crate::social::events::{EventRole, EventRoomService};
+    use crate::social::widgets::event_detail::EventDetailLoadAction;
+
+    match EventRoomService::new(client.clone())
+        .list_roles(&room_id)
+        .await
+    {
+        Ok(roles) => {
+            let viewer_role = roles
+                .iter()
+                .find(|(user_id, _)| Some(&**user_id) == client.user_id())
+                .map_or(EventRole::Guest, |(_, role)| *role);
+            Cx::post_action(EventDetailLoadAction::Roles {
+                room_id,
+                roles,
+                viewer_role,
+            });
+        }
+        Err(e) => warning!("Failed to load the roles of event {room_id}'s members: {e}"),
+    }
+}
+
+/// Load the check-ins of an event and emit them in an [`EventDetailLoadAction::Checkins`].
+///
+/// [`EventDetailLoadAction::Checkins`]: crate::social::widgets::event_detail::EventDetailLoadAction::Checkins
+#[cfg(feature = "social")]
+async fn load_event_checkins(client: &Client, room_id: OwnedRoomId) {
+    use crate::social::events::EventCheckinService;
+    use crate::social::widgets::event_detail::EventDetailLoadAction;
+
+    match EventCheckinService::new(client.clone())
+        .get_checkins(&room_id)
+        .await
+    {
+        Ok(checkins) => Cx::post_action(EventDetailLoadAction::Checkins { room_id, checkins }),
+        Err(e) => warning!("Failed to load the check-ins of event {room_id}: {e}"),
+    }
+}
+
+/// Load the checklist of an event and emit it in an [`EventDetailLoadAction::Checklist`],
+/// along with whether the current user can edit it.
+///
+/// [`EventDetailLoadAction::Checklist`]: crate::social::widgets::event_detail::EventDetailLoadAction::Checklist
+#[cfg(feature = "social")]
+async fn load_event_checklist(client: &Client, room_id: OwnedRoomId) {
+    use crate::social::events::ChecklistService;
+    use crate::social::widgets::event_detail::EventDetailLoadAction;
+
+    let checklist = ChecklistService::new(client.clone());
+    match checklist.get_items(&room_id).await {
+        Ok(items) => {
+            let is_host = checklist.can_edit_items(&room_id).await.unwrap_or(false);
+            Cx::post_action(EventDetailLoadAction::Checklist {
+                room_id,
+                items,
+                is_host,
+            });
+        }
+        Err(e) => warning!("Failed to load the checklist of event {room_id}: {e}"),
+    }
+}
+
+/// Load the rides offered and requested for an event
+/// and emit them in an [`EventDetailLoadAction::RideBoard`].
+///
+/// [`EventDetailLoadAction::RideBoard`]: crate::social::widgets::event_detail::EventDetailLoadAction::RideBoard
+#[cfg(feature = "social")]
+async fn load_event_ride_board(client: &Client, room_id: OwnedRoomId) {
+    use crate::social::events::LogisticsService;
+    use crate::social::widgets::event_detail::EventDetailLoadAction;
+
+    match LogisticsService::new(client.clone())
+        .get_ride_board(&room_id)
+        .await
+    {
+        Ok(board) => Cx::post_action(EventDetailLoadAction::RideBoard { room_id, board }),
+        Err(e) => warning!("Failed to load the rides of event {room_id}: {e}"),
+    }
+}
+
+/// Load the scheduling poll of an event and emit it in an
+/// [`EventDetailLoadAction::SchedulingPoll`], along with whether the current user is a host.
+///
+/// [`EventDetailLoadAction::SchedulingPoll`]: crate::social::widgets::event_detail::EventDetailLoadAction::SchedulingPoll
+#[cfg(feature = "social")]
+async fn load_event_scheduling_poll(client: &Client, room_id: OwnedRoomId) {
+    use crate::social::events::{EventInsightsService, SchedulingPollService};
+    use crate::social::widgets::event_detail::EventDetailLoadAction;
+
+    match SchedulingPollService::new(client.clone())
+        .get_poll(&room_id)
+        .await
+    {
+        Ok(poll) => {
+            let is_host = EventInsightsService::new(client.clone())
+                .is_host(&room_id)
+                .await
+                .unwrap_or(false);
+            Cx::post_action(EventDetailLoadAction::SchedulingPoll {
+                room_id,
+                poll,
+                is_host,
+            });
+        }
+        Err(e) => warning!("Failed to load the scheduling poll of event {room_id}: {e}"),
+    }
+}
+
 /// Blocks the current thread until the given future completes.
 ///
 /// ## Warning
@@ -2299,6 +5159,26 @@ async fn start_matrix_client_login_and_sync(rt: Handle) {
     // Listen for updates to the ignored user list.
     handle_ignore_user_list_subscriber(client.clone());
 
+    // Decide whether social features are enabled for this account,
+    // sync the feed rooms separately while they are,
+    // then periodically apply the user's post retention policies to their own feeds,
+    // listen for new comments on the posts the user watches,
+    // index the user's own social activity,
+    // confirm the user's pending changes to posts as the server echoes them,
+    // send the posts queued in the user's outbox,
+    // and drop redacted and edited posts from the newsfeed cache.
+    #[cfg(feature = "social")]
+    {
+        crate::social::availability::spawn_availability_watcher(client.clone());
+        crate::social::availability::spawn_feed_sync(client.clone());
+        crate::social::retention::spawn_retention_job(client.clone());
+        crate::social::post_watch::spawn_post_watcher(client.clone());
+        crate::social::activity_log::spawn_activity_indexer(client.clone());
+        crate::social::optimistic::spawn_optimistic_echo_watcher(client.clone());
+        crate::social::outbox::spawn_outbox_worker(client.clone());
+        crate::social::main_feed::spawn_main_feed_cache_invalidator(client.clone());
+    }
+
     let sync_service = match SyncService::builder(client.clone())
         .with_offline_mode()
         .build()
@@ -4050,6 +6930,8 @@ pub async fn clear_app_state(config: &LogoutConfig) -> Result<()> {
     REQUEST_SENDER.lock().unwrap().take();
     IGNORED_USERS.lock().unwrap().clear();
     ALL_JOINED_ROOMS.lock().unwrap().clear();
+    #[cfg(feature = "social")]
+    crate::social::availability::reset_social_enabled();
 
     let on_clear_appstate = Arc::new(Notify::new());
     Cx::post_action(LogoutAction::ClearAppState {
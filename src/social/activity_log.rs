@@ -0,0 +1,847 @@
+//! A log of the current user's own social activity.
+//!
+//! The log lists the user's posts, comments, reactions, RSVPs and follows,
+//! newest first, so that they can review what they did in a date range and
+//! undo reactions and follows in bulk, or jump back to the post an activity
+//! was about.
+//!
+//! Posts, comments and reactions are kept in a local [`ActivityIndex`],
+//! built from the events the user sends as they sync (see
+//! [`spawn_activity_indexer()`]). The history of a feed room is only read
+//! once, for the activity from before the room was indexed. RSVPs are read
+//! from the state of the joined event rooms, and follows from the follow
+//! records Robrix keeps in the account data (see [`FollowService`]), as they
+//! leave no trace in any room the user can read. Nothing is sent to the
+//! homeserver except to undo activities.
+//!
+//! The log is loaded and undone through the Matrix worker
+//! (see [`MatrixRequest::LoadActivityLog`] and [`MatrixRequest::UndoActivities`]),
+//! which posts an [`ActivityLogAction`] with the result.
+//!
+//! [`MatrixRequest::LoadActivityLog`]: crate::sliding_sync::MatrixRequest::LoadActivityLog
+//! [`MatrixRequest::UndoActivities`]: crate::sliding_sync::MatrixRequest::UndoActivities
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    deserialized_responses::SyncOrStrippedState,
+    room::{MessagesOptions, Room},
+    ruma::{
+        events::{
+            room::{message::Relation, redaction::OriginalSyncRoomRedactionEvent},
+            AnySyncMessageLikeEvent, AnySyncTimelineEvent, SyncMessageLikeEvent, SyncStateEvent,
+        },
+        EventId, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId,
+        UserId,
+    },
+    Client,
+};
+use robrix_social_events::rsvp::{RsvpStatus, SocialRsvpEventContent};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+    time::Duration,
+};
+use tokio::runtime::Handle;
+
+use crate::persistence::persistent_state_dir;
+use crate::social::{
+    feed_room::is_feed_room,
+    follow::{FollowError, FollowService},
+};
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Delay between redactions, to stay clear of homeserver rate limits.
+const REDACTION_DELAY: Duration = Duration::from_millis(250);
+
+/// Reason attached to reactions removed from the activity log.
+const REDACTION_REASON: &str = "Removed from activity log";
+
+/// The name of the file, in the user's persistent state directory, holding the activity index.
+pub(crate) const ACTIVITY_INDEX_FILE_NAME: &str = "social_activity_index.json";
+
+/// The maximum number of activities kept in the index; the oldest are forgotten first.
+pub const MAX_INDEXED_ACTIVITIES: usize = 10_000;
+
+/// Held while the activity index file is loaded, changed and saved,
+/// as it's updated both by the indexer and when loading the log.
+static ACTIVITY_INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// Something the current user did.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Activity {
+    /// The user made a post.
+    Post,
+    /// The user commented on a post.
+    Comment { on: OwnedEventId },
+    /// The user reacted to a post or comment.
+    Reaction { on: OwnedEventId, key: String },
+    /// The user responded to an event.
+    Rsvp(RsvpStatus),
+    /// The user followed another user's public feed.
+    Follow(OwnedUserId),
+}
+
+/// An entry in the activity log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    /// What the user did.
+    pub activity: Activity,
+    /// The room the activity took place in.
+    pub room_id: OwnedRoomId,
+    /// The name of that room, if it has one.
+    pub room_name: Option<String>,
+    /// The event recording the activity. Follows have none.
+    pub event_id: Option<OwnedEventId>,
+    /// When the user did it.
+    pub timestamp: MilliSecondsSinceUnixEpoch,
+}
+
+impl ActivityEntry {
+    /// Whether this activity can be undone from the activity log.
+    ///
+    /// Only reactions and follows can be undone; posts and comments
+    /// can be deleted and RSVPs changed where they were made.
+    pub fn can_undo(&self) -> bool {
+        matches!(
+            self.activity,
+            Activity::Reaction { .. } | Activity::Follow(_)
+        )
+    }
+
+    /// Get the post the activity was about, to jump back to it.
+    ///
+    /// This is the post itself, or the post or comment that was commented on
+    /// or reacted to. RSVPs and follows aren't about a post.
+    pub fn post_id(&self) -> Option<&EventId> {
+        match &self.activity {
+            Activity::Post => self.event_id.as_deref(),
+            Activity::Comment { on } | Activity::Reaction { on, .. } => Some(on),
+            Activity::Rsvp(_) | Activity::Follow(_) => None,
+        }
+    }
+
+    /// Get a one-line description of the activity, e.g., "You reacted 👍 in Alice's feed".
+    pub fn summary(&self) -> String {
+        let place = self.room_name.as_deref().unwrap_or("a room");
+        match &self.activity {
+            Activity::Post => format!("You posted in {place}"),
+            Activity::Comment { .. } => format!("You commented in {place}"),
+            Activity::Reaction { key, .. } => format!("You reacted {key} in {place}"),
+            Activity::Rsvp(RsvpStatus::Going) => format!("You're going to {place}"),
+            Activity::Rsvp(RsvpStatus::Interested) => format!("You're interested in {place}"),
+            Activity::Rsvp(RsvpStatus::NotGoing) => format!("You can't go to {place}"),
+            Activity::Follow(user_id) => format!("You followed {user_id}"),
+        }
+    }
+}
+
+/// The dates to show activity from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActivityRange {
+    /// The earliest activity to include (Unix timestamp in milliseconds).
+    pub since_ms: u64,
+    /// The latest activity to include (Unix timestamp in milliseconds),
+    /// or `None` to include everything up to now.
+    pub until_ms: Option<u64>,
+}
+
+impl ActivityRange {
+    /// The range covering the last `days` days before `now_ms`.
+    pub fn last_days(days: u64, now_ms: u64) -> Self {
+        Self {
+            since_ms: now_ms.saturating_sub(days * DAY_MS),
+            until_ms: None,
+        }
+    }
+
+    /// Whether an activity at the given time falls in this range.
+    pub fn contains(&self, timestamp: MilliSecondsSinceUnixEpoch) -> bool {
+        let timestamp = u64::from(timestamp.get());
+        timestamp >= self.since_ms && self.until_ms.is_none_or(|until| timestamp <= until)
+    }
+}
+
+/// The outcome of undoing activities in bulk.
+#[derive(Clone, Debug, Default)]
+pub struct UndoReport {
+    /// Activities that were undone.
+    pub undone: Vec<ActivityEntry>,
+    /// Activities that could not be undone, with the error message.
+    pub failed: Vec<(ActivityEntry, String)>,
+}
+
+/// Actions emitted when the activity log was loaded or activities were undone.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum ActivityLogAction {
+    /// The activity in the requested range was loaded, newest first.
+    Loaded(Vec<ActivityEntry>),
+    /// Undoing activities completed, with the outcome of each.
+    Undone(UndoReport),
+    /// Loading the activity log failed.
+    Failed(String),
+    /// No action.
+    None,
+}
+
+/// A local index of the activities the user's events record, newest first.
+///
+/// Follows aren't indexed, as they're recorded in the account data instead.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityIndex {
+    /// Indexed activities, newest first.
+    entries: Vec<ActivityEntry>,
+    /// For each feed room whose history was indexed,
+    /// the time since which it was (Unix timestamp in milliseconds).
+    indexed_since: BTreeMap<OwnedRoomId, u64>,
+}
+
+impl ActivityIndex {
+    /// Load the index of the given user, or an empty index if none was saved yet.
+    ///
+    /// # Errors
+    /// Returns an error if the index file cannot be read or parsed.
+    pub fn load(user_id: &UserId) -> Result<Self, ActivityLogError> {
+        let path = persistent_state_dir(user_id).join(ACTIVITY_INDEX_FILE_NAME);
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save the index of the given user.
+    ///
+    /// # Errors
+    /// Returns an error if the index file cannot be written.
+    pub fn save(&self, user_id: &UserId) -> Result<(), ActivityLogError> {
+        let path = persistent_state_dir(user_id).join(ACTIVITY_INDEX_FILE_NAME);
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Add an activity to the index, forgetting the oldest ones if it's full.
+    ///
+    /// An activity whose event is indexed already replaces it, and an RSVP
+    /// replaces the user's earlier RSVP in the same room. Activities without
+    /// an event, i.e., follows, aren't indexed.
+    pub fn record(&mut self, entry: ActivityEntry) {
+        if entry.event_id.is_none() {
+            return;
+        }
+        let is_rsvp = |entry: &ActivityEntry| matches!(entry.activity, Activity::Rsvp(_));
+        self.entries.retain(|indexed| {
+            indexed.event_id != entry.event_id
+                && !(is_rsvp(indexed) && is_rsvp(&entry) && indexed.room_id == entry.room_id)
+        });
+        let position = self
+            .entries
+            .partition_point(|indexed| indexed.timestamp > entry.timestamp);
+        self.entries.insert(position, entry);
+
+        if self.entries.len() > MAX_INDEXED_ACTIVITIES {
+            self.entries.truncate(MAX_INDEXED_ACTIVITIES);
+            // The history before the oldest kept activity is no longer indexed
+            let oldest = self
+                .entries
+                .last()
+                .map_or(0, |entry| u64::from(entry.timestamp.get()));
+            for since in self.indexed_since.values_mut() {
+                *since = (*since).max(oldest);
+            }
+        }
+    }
+
+    /// Remove the activity recorded by the given event, e.g., once it was redacted.
+    ///
+    /// Returns whether it was indexed.
+    pub fn remove(&mut self, event_id: &EventId) -> bool {
+        let len = self.entries.len();
+        self.entries
+            .retain(|entry| entry.event_id.as_deref() != Some(event_id));
+        self.entries.len() != len
+    }
+
+    /// Get the indexed activities in the given range, newest first.
+    pub fn entries_in(&self, range: ActivityRange) -> Vec<ActivityEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| range.contains(entry.timestamp))
+            .cloned()
+            .collect()
+    }
+
+    /// Get the posts and comments the user reacted to, with the room they're in,
+    /// most recently reacted to first.
+    pub fn reacted_posts(&self) -> Vec<(OwnedRoomId, OwnedEventId)> {
+        let mut seen = BTreeSet::new();
+        self.entries
+            .iter()
+            .filter_map(|entry| match &entry.activity {
+                Activity::Reaction { on, .. } if seen.insert(on.clone()) => {
+                    Some((entry.room_id.clone(), on.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the time since which the history of a feed room was indexed, if it was.
+    pub fn indexed_since(&self, room_id: &RoomId) -> Option<u64> {
+        self.indexed_since.get(room_id).copied()
+    }
+
+    /// Record that the history of a feed room was indexed since the given time.
+    pub fn set_indexed_since(&mut self, room_id: OwnedRoomId, since_ms: u64) {
+        let since = self.indexed_since.entry(room_id).or_insert(since_ms);
+        *since = (*since).min(since_ms);
+    }
+}
+
+/// Load the given user's activity index, waiting for any change to it to be saved first.
+///
+/// # Errors
+/// Returns an error if the index file cannot be read or parsed.
+pub fn load_activity_index(user_id: &UserId) -> Result<ActivityIndex, ActivityLogError> {
+    let _lock = ACTIVITY_INDEX_LOCK.lock().unwrap();
+    ActivityIndex::load(user_id)
+}
+
+/// Load the given user's activity index, change it and save it again.
+///
+/// Returns the changed index.
+fn update_activity_index(
+    user_id: &UserId,
+    update: impl FnOnce(&mut ActivityIndex),
+) -> Result<ActivityIndex, ActivityLogError> {
+    let _lock = ACTIVITY_INDEX_LOCK.lock().unwrap();
+    let mut index = ActivityIndex::load(user_id)?;
+    update(&mut index);
+    index.save(user_id)?;
+    Ok(index)
+}
+
+/// Keep the logged-in user's activity index up to date with the events they send.
+///
+/// Must be called from within the Tokio runtime after logging in.
+/// The posts, comments and reactions the user sends to feed rooms, from any
+/// device, are added to the index as they sync, and removed once redacted.
+pub fn spawn_activity_indexer(client: Client) {
+    let Some(user_id) = client.user_id().map(ToOwned::to_owned) else {
+        return;
+    };
+    Handle::current().spawn(async move {
+        let own_user_id = user_id.clone();
+        client.add_event_handler(move |event: AnySyncMessageLikeEvent, room: Room| {
+            let own_user_id = own_user_id.clone();
+            async move {
+                if event.sender() != own_user_id || !is_feed_room(&room) {
+                    return;
+                }
+                let Some(activity) = activity_of_event(&event) else {
+                    return;
+                };
+                let entry = ActivityEntry {
+                    activity,
+                    room_id: room.room_id().to_owned(),
+                    room_name: room.name(),
+                    event_id: Some(event.event_id().to_owned()),
+                    timestamp: event.origin_server_ts(),
+                };
+                if let Err(e) = update_activity_index(&own_user_id, |index| index.record(entry)) {
+                    error!("Failed to index the activity of {own_user_id}: {e}");
+                }
+            }
+        });
+
+        client.add_event_handler(move |redaction: OriginalSyncRoomRedactionEvent| {
+            let own_user_id = user_id.clone();
+            async move {
+                if redaction.sender != own_user_id {
+                    return;
+                }
+                let Some(redacted) = redaction.content.redacts.or(redaction.redacts) else {
+                    return;
+                };
+                if let Err(e) = update_activity_index(&own_user_id, |index| {
+                    index.remove(&redacted);
+                }) {
+                    error!("Failed to update the activity index of {own_user_id}: {e}");
+                }
+            }
+        });
+    });
+}
+
+/// Get the activity a message-like event sent by the user records, if any.
+///
+/// Edits and other relations aren't activities of their own.
+pub fn activity_of_event(event: &AnySyncMessageLikeEvent) -> Option<Activity> {
+    match event {
+        AnySyncMessageLikeEvent::Reaction(SyncMessageLikeEvent::Original(reaction)) => {
+            Some(Activity::Reaction {
+                on: reaction.content.relates_to.event_id.clone(),
+                key: reaction.content.relates_to.key.clone(),
+            })
+        }
+        AnySyncMessageLikeEvent::RoomMessage(SyncMessageLikeEvent::Original(message)) => {
+            match &message.content.relates_to {
+                None => Some(Activity::Post),
+                Some(Relation::Reply { in_reply_to }) => Some(Activity::Comment {
+                    on: in_reply_to.event_id.clone(),
+                }),
+                Some(Relation::Thread(thread)) => Some(Activity::Comment {
+                    on: thread.event_id.clone(),
+                }),
+                Some(_) => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Service for reviewing and undoing the current user's social activity.
+pub struct ActivityLogService {
+    client: Client,
+}
+
+impl ActivityLogService {
+    /// Create a new ActivityLogService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Load the user's activity in the given range, newest first.
+    ///
+    /// The activity is read from the [`ActivityIndex`]. The history of feed
+    /// rooms that wasn't indexed for the range yet is read and indexed first.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, or room history,
+    /// room state, the index or the follow records cannot be loaded.
+    pub async fn load(&self, range: ActivityRange) -> Result<Vec<ActivityEntry>, ActivityLogError> {
+        let user_id = self.client.user_id().ok_or(ActivityLogError::NotLoggedIn)?;
+        let index = ActivityIndex::load(user_id)?;
+        let mut backfilled = Vec::new();
+        let mut rsvps = Vec::new();
+
+        for room in self.client.joined_rooms() {
+            let needs_backfill = index
+                .indexed_since(room.room_id())
+                .is_none_or(|since| since > range.since_ms);
+            if needs_backfill && is_feed_room(&room) {
+                let (entries, since_ms) = load_room_activity(&room, user_id, range).await?;
+                backfilled.push((room.room_id().to_owned(), since_ms, entries));
+            }
+            if let Some(entry) = load_rsvp_activity(&room, user_id).await? {
+                rsvps.push(entry);
+            }
+        }
+
+        let index = update_activity_index(user_id, |index| {
+            for (room_id, since_ms, entries) in backfilled {
+                for entry in entries {
+                    index.record(entry);
+                }
+                index.set_indexed_since(room_id, since_ms);
+            }
+            for entry in rsvps {
+                index.record(entry);
+            }
+        })?;
+        let mut entries = index.entries_in(range);
+
+        let following = FollowService::new(self.client.clone()).following().await?;
+        for (followed, feed) in following.following {
+            entries.push(ActivityEntry {
+                activity: Activity::Follow(followed),
+                room_name: self
+                    .client
+                    .get_room(&feed.room_id)
+                    .and_then(|room| room.name()),
+                room_id: feed.room_id,
+                event_id: None,
+                timestamp: feed.since,
+            });
+        }
+
+        entries.retain(|entry| range.contains(entry.timestamp));
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(entries)
+    }
+
+    /// Undo reactions and follows in bulk.
+    ///
+    /// Reactions are redacted and followed users unfollowed. Activities that
+    /// can't be undone (see [`ActivityEntry::can_undo()`]) are skipped, and
+    /// failures to undo individual activities are recorded in the report
+    /// rather than aborting the rest.
+    pub async fn undo(&self, entries: &[ActivityEntry]) -> UndoReport {
+        let mut report = UndoReport::default();
+        for entry in entries.iter().filter(|entry| entry.can_undo()) {
+            match self.undo_one(entry).await {
+                Ok(()) => report.undone.push(entry.clone()),
+                Err(e) => report.failed.push((entry.clone(), e.to_string())),
+            }
+        }
+
+        // Don't wait for the redactions to sync back to update the index
+        if let Some(user_id) = self.client.user_id() {
+            let result = update_activity_index(user_id, |index| {
+                for event_id in report
+                    .undone
+                    .iter()
+                    .filter_map(|entry| entry.event_id.as_deref())
+                {
+                    index.remove(event_id);
+                }
+            });
+            if let Err(e) = result {
+                error!("Failed to update the activity index of {user_id}: {e}");
+            }
+        }
+        report
+    }
+
+    /// Undo a single reaction or follow.
+    async fn undo_one(&self, entry: &ActivityEntry) -> Result<(), ActivityLogError> {
+        match (&entry.activity, &entry.event_id) {
+            (Activity::Reaction { .. }, Some(event_id)) => {
+                let room = self
+                    .client
+                    .get_room(&entry.room_id)
+                    .ok_or_else(|| ActivityLogError::RoomNotFound(entry.room_id.clone()))?;
+                room.redact(event_id, Some(REDACTION_REASON), None)
+                    .await
+                    .map_err(|e| ActivityLogError::MatrixError(e.into()))?;
+                tokio::time::sleep(REDACTION_DELAY).await;
+            }
+            (Activity::Follow(user_id), _) => {
+                FollowService::new(self.client.clone())
+                    .unfollow(user_id)
+                    .await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Load the user's posts, comments and reactions in a feed room,
+/// going back to the start of the range.
+///
+/// Returns them along with the time since which the room's history was read,
+/// which is 0 if it was read from its very beginning.
+async fn load_room_activity(
+    room: &Room,
+    user_id: &UserId,
+    range: ActivityRange,
+) -> Result<(Vec<ActivityEntry>, u64), ActivityLogError> {
+    let room_name = room.name();
+    let mut entries = Vec::new();
+    let mut since_ms = 0;
+    let mut from = None;
+    'paginate: loop {
+        let mut options = MessagesOptions::backward();
+        options.from = from.take();
+        let response = room.messages(options).await?;
+
+        for event in &response.chunk {
+            let Ok(AnySyncTimelineEvent::MessageLike(event)) = event.raw().deserialize() else {
+                continue;
+            };
+            if u64::from(event.origin_server_ts().get()) < range.since_ms {
+                since_ms = range.since_ms;
+                break 'paginate;
+            }
+            if event.sender() != user_id {
+                continue;
+            }
+            if let Some(activity) = activity_of_event(&event) {
+                entries.push(ActivityEntry {
+                    activity,
+                    room_id: room.room_id().to_owned(),
+                    room_name: room_name.clone(),
+                    event_id: Some(event.event_id().to_owned()),
+                    timestamp: event.origin_server_ts(),
+                });
+            }
+        }
+
+        match response.end {
+            Some(end) if !response.chunk.is_empty() => from = Some(end),
+            _ => break,
+        }
+    }
+    Ok((entries, since_ms))
+}
+
+/// Load the user's current RSVP in a room, if they responded to an event there.
+async fn load_rsvp_activity(
+    room: &Room,
+    user_id: &UserId,
+) -> Result<Option<ActivityEntry>, ActivityLogError> {
+    let Some(raw_event) = room
+        .get_state_event_static_for_key::<SocialRsvpEventContent, _>(user_id)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let Ok(SyncOrStrippedState::Sync(SyncStateEvent::Original(event))) = raw_event.deserialize()
+    else {
+        return Ok(None);
+    };
+    // SECURITY: the state_key must match the sender, see `validate_rsvp_event()`.
+    if event.sender != user_id {
+        return Ok(None);
+    }
+    Ok(Some(ActivityEntry {
+        activity: Activity::Rsvp(event.content.status),
+        room_id: room.room_id().to_owned(),
+        room_name: room.name(),
+        event_id: Some(event.event_id),
+        timestamp: event.origin_server_ts,
+    }))
+}
+
+/// Errors that can occur when loading or undoing activity.
+#[derive(Debug, thiserror::Error)]
+pub enum ActivityLogError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The room an activity took place in is no longer joined.
+    #[error("Room not found: {0}")]
+    RoomNotFound(OwnedRoomId),
+
+    /// Loading or undoing follows failed.
+    #[error("Follow error: {0}")]
+    Follow(#[from] FollowError),
+
+    /// The activity index could not be read or written.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The activity index could not be parsed or serialized.
+    #[error("Invalid activity index: {0}")]
+    InvalidIndex(#[from] serde_json::Error),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message_like(content: serde_json::Value, event_type: &str) -> AnySyncMessageLikeEvent {
+        serde_json::from_value(json!({
+            "type": event_type,
+            "event_id": "$own:example.org",
+            "sender": "@me:example.org",
+            "origin_server_ts": 1_700_000_000_000u64,
+            "content": content,
+        }))
+        .unwrap()
+    }
+
+    fn entry(activity: Activity) -> ActivityEntry {
+        ActivityEntry {
+            activity,
+            room_id: "!feed:example.org".try_into().unwrap(),
+            room_name: Some("Alice's feed".to_string()),
+            event_id: None,
+            timestamp: MilliSecondsSinceUnixEpoch(1_700_000_000_000u64.try_into().unwrap()),
+        }
+    }
+
+    fn sent(activity: Activity, event_id: &str, timestamp: u64) -> ActivityEntry {
+        ActivityEntry {
+            event_id: Some(event_id.try_into().unwrap()),
+            timestamp: MilliSecondsSinceUnixEpoch(timestamp.try_into().unwrap()),
+            ..entry(activity)
+        }
+    }
+
+    #[test]
+    fn test_activity_of_event() {
+        let post = message_like(
+            json!({ "msgtype": "m.text", "body": "Hello" }),
+            "m.room.message",
+        );
+        assert_eq!(activity_of_event(&post), Some(Activity::Post));
+
+        let comment = message_like(
+            json!({
+                "msgtype": "m.text",
+                "body": "Nice!",
+                "m.relates_to": { "m.in_reply_to": { "event_id": "$post:example.org" } },
+            }),
+            "m.room.message",
+        );
+        assert_eq!(
+            activity_of_event(&comment),
+            Some(Activity::Comment {
+                on: "$post:example.org".try_into().unwrap()
+            })
+        );
+
+        let reaction = message_like(
+            json!({
+                "m.relates_to": {
+                    "rel_type": "m.annotation",
+                    "event_id": "$post:example.org",
+                    "key": "👍",
+                },
+            }),
+            "m.reaction",
+        );
+        assert_eq!(
+            activity_of_event(&reaction),
+            Some(Activity::Reaction {
+                on: "$post:example.org".try_into().unwrap(),
+                key: "👍".to_string()
+            })
+        );
+
+        let edit = message_like(
+            json!({
+                "msgtype": "m.text",
+                "body": "* Hello",
+                "m.new_content": { "msgtype": "m.text", "body": "Hello" },
+                "m.relates_to": { "rel_type": "m.replace", "event_id": "$post:example.org" },
+            }),
+            "m.room.message",
+        );
+        assert_eq!(activity_of_event(&edit), None);
+    }
+
+    #[test]
+    fn test_activity_range() {
+        let now = 1_700_000_000_000;
+        let range = ActivityRange::last_days(7, now);
+        let at = |ms: u64| MilliSecondsSinceUnixEpoch(ms.try_into().unwrap());
+        assert!(range.contains(at(now)));
+        assert!(range.contains(at(now - 7 * DAY_MS)));
+        assert!(!range.contains(at(now - 7 * DAY_MS - 1)));
+
+        let bounded = ActivityRange {
+            until_ms: Some(now - DAY_MS),
+            ..range
+        };
+        assert!(!bounded.contains(at(now)));
+    }
+
+    #[test]
+    fn test_only_reactions_and_follows_can_be_undone() {
+        let reaction = entry(Activity::Reaction {
+            on: "$post:example.org".try_into().unwrap(),
+            key: "❤️".to_string(),
+        });
+        assert!(reaction.can_undo());
+        assert_eq!(reaction.summary(), "You reacted ❤️ in Alice's feed");
+        assert!(entry(Activity::Follow("@alice:example.org".try_into().unwrap())).can_undo());
+        assert!(!entry(Activity::Post).can_undo());
+        assert!(!entry(Activity::Rsvp(RsvpStatus::Going)).can_undo());
+    }
+
+    #[test]
+    fn test_activity_index() {
+        let mut index = ActivityIndex::default();
+        index.record(sent(Activity::Post, "$post:example.org", 2_000));
+        index.record(sent(
+            Activity::Rsvp(RsvpStatus::Going),
+            "$rsvp1:example.org",
+            1_000,
+        ));
+        index.record(sent(Activity::Post, "$post:example.org", 2_000));
+        // Follows are kept in the account data instead
+        index.record(entry(Activity::Follow(
+            "@alice:example.org".try_into().unwrap(),
+        )));
+
+        let all = ActivityRange {
+            since_ms: 0,
+            until_ms: None,
+        };
+        let entries = index.entries_in(all);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].activity, Activity::Post);
+
+        // A newer RSVP in the same room replaces the earlier one
+        index.record(sent(
+            Activity::Rsvp(RsvpStatus::NotGoing),
+            "$rsvp2:example.org",
+            3_000,
+        ));
+        let entries = index.entries_in(all);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].activity, Activity::Rsvp(RsvpStatus::NotGoing));
+
+        assert!(index.remove(&OwnedEventId::try_from("$post:example.org").unwrap()));
+        assert_eq!(index.entries_in(all).len(), 1);
+    }
+
+    #[test]
+    fn test_indexed_since_only_grows_backwards() {
+        let room_id: OwnedRoomId = "!feed:example.org".try_into().unwrap();
+        let mut index = ActivityIndex::default();
+        assert_eq!(index.indexed_since(&room_id), None);
+        index.set_indexed_since(room_id.clone(), 5_000);
+        index.set_indexed_since(room_id.clone(), 9_000);
+        assert_eq!(index.indexed_since(&room_id), Some(5_000));
+        index.set_indexed_since(room_id.clone(), 0);
+        assert_eq!(index.indexed_since(&room_id), Some(0));
+    }
+
+    #[test]
+    fn test_reacted_posts() {
+        let reaction = |on: &str, key: &str| Activity::Reaction {
+            on: on.try_into().unwrap(),
+            key: key.to_string(),
+        };
+        let mut index = ActivityIndex::default();
+        index.record(sent(
+            reaction("$first:example.org", "👍"),
+            "$r1:example.org",
+            1_000,
+        ));
+        index.record(sent(Activity::Post, "$post:example.org", 2_000));
+        index.record(sent(
+            reaction("$second:example.org", "👍"),
+            "$r2:example.org",
+            3_000,
+        ));
+        index.record(sent(
+            reaction("$first:example.org", "❤️"),
+            "$r3:example.org",
+            4_000,
+        ));
+
+        let reacted: Vec<_> = index
+            .reacted_posts()
+            .into_iter()
+            .map(|(_, event_id)| event_id.to_string())
+            .collect();
+        assert_eq!(reacted, ["$first:example.org", "$second:example.org"]);
+    }
+
+    #[test]
+    fn test_post_id() {
+        let post = sent(Activity::Post, "$post:example.org", 1_000);
+        assert_eq!(post.post_id(), post.event_id.as_deref());
+        let comment = sent(
+            Activity::Comment {
+                on: "$post:example.org".try_into().unwrap(),
+            },
+            "$comment:example.org",
+            2_000,
+        );
+        assert_eq!(comment.post_id().unwrap(), "$post:example.org");
+        assert_eq!(entry(Activity::Rsvp(RsvpStatus::Going)).post_id(), None);
+    }
+}
@@ -0,0 +1,237 @@
+//! Audio clip posts, e.g., voice notes.
+//!
+//! Audio posts are `m.audio` messages, so that other Matrix clients can play them.
+//! Like the voice messages of other clients, they carry their duration and a
+//! waveform to show before the clip is played in the `org.matrix.msc1767.audio`
+//! field of their content ([MSC3246]), and are marked as voice notes with
+//! the `org.matrix.msc3245.voice` field ([MSC3245]).
+//!
+//! The waveform of WAV recordings is computed when they're attached to a post
+//! (see [`analyze_wav()`]); clips in other formats are posted without one.
+//!
+//! [MSC3246]: https://github.com/matrix-org/matrix-spec-proposals/pull/3246
+//! [MSC3245]: https://github.com/matrix-org/matrix-spec-proposals/pull/3245
+
+use serde::{Deserialize, Serialize};
+
+/// The field of an audio message's content holding its duration and waveform.
+pub const AUDIO_DETAILS_FIELD: &str = "org.matrix.msc1767.audio";
+/// The field marking an audio message as a voice note.
+pub const VOICE_FIELD: &str = "org.matrix.msc3245.voice";
+/// The number of amplitudes in the waveform computed for a clip.
+pub const WAVEFORM_LENGTH: usize = 64;
+/// The amplitude of the loudest parts of a clip in its waveform.
+pub const MAX_WAVEFORM_AMPLITUDE: u16 = 1024;
+
+/// The duration and waveform of an audio clip.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioDetails {
+    /// Duration of the clip in milliseconds, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+    /// Amplitudes of the clip over time, from 0 to [`MAX_WAVEFORM_AMPLITUDE`].
+    /// Empty if the waveform isn't known.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waveform: Vec<u16>,
+}
+
+impl AudioDetails {
+    /// Get the duration of the clip as shown to users, e.g., "1:05".
+    pub fn duration_text(&self) -> Option<String> {
+        self.duration.map(format_duration)
+    }
+
+    /// Get the levels of `bars` bars to draw the waveform with, from 0.0 to 1.0.
+    ///
+    /// Each bar shows the loudest amplitude of its part of the waveform.
+    /// All bars are silent if the waveform isn't known.
+    pub fn levels(&self, bars: usize) -> Vec<f32> {
+        if self.waveform.is_empty() {
+            return vec![0.0; bars];
+        }
+        (0..bars)
+            .map(|bar| {
+                let start = bar * self.waveform.len() / bars;
+                let end = ((bar + 1) * self.waveform.len() / bars).max(start + 1);
+                let peak = self.waveform[start..end.min(self.waveform.len())]
+                    .iter()
+                    .max()
+                    .copied()
+                    .unwrap_or_default();
+                f32::from(peak.min(MAX_WAVEFORM_AMPLITUDE)) / f32::from(MAX_WAVEFORM_AMPLITUDE)
+            })
+            .collect()
+    }
+
+    /// Add the details to the raw content of an audio message, marking it as a voice note.
+    pub fn insert_into(&self, content: &mut serde_json::Map<String, serde_json::Value>) {
+        if let Ok(details) = serde_json::to_value(self) {
+            content.insert(AUDIO_DETAILS_FIELD.to_string(), details);
+        }
+        content.insert(VOICE_FIELD.to_string(), serde_json::json!({}));
+    }
+}
+
+/// Get the duration and waveform of an audio post from its raw message content.
+///
+/// The duration is taken from the message's `info` if the post has no details of its own,
+/// e.g., if it was sent by a client without voice notes.
+/// Returns `None` if the post isn't an audio message.
+pub fn audio_details_from_content(content: &serde_json::Value) -> Option<AudioDetails> {
+    if content.get("msgtype")?.as_str()? != "m.audio" {
+        return None;
+    }
+    let mut details: AudioDetails = content
+        .get(AUDIO_DETAILS_FIELD)
+        .and_then(|details| serde_json::from_value(details.clone()).ok())
+        .unwrap_or_default();
+    if details.duration.is_none() {
+        details.duration = content
+            .get("info")
+            .and_then(|info| info.get("duration"))
+            .and_then(serde_json::Value::as_u64);
+    }
+    Some(details)
+}
+
+/// Format a duration in milliseconds as minutes and seconds, e.g., "1:05".
+pub fn format_duration(duration_ms: u64) -> String {
+    let seconds = duration_ms / 1000;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Compute the waveform of a clip from its samples, from -1.0 to 1.0.
+///
+/// The waveform has up to [`WAVEFORM_LENGTH`] amplitudes, each the loudest
+/// sample of its part of the clip.
+pub fn waveform_from_samples(samples: &[f32]) -> Vec<u16> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    let chunk_size = samples.len().div_ceil(WAVEFORM_LENGTH);
+    samples
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let peak = chunk
+                .iter()
+                .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+            (peak.min(1.0) * f32::from(MAX_WAVEFORM_AMPLITUDE)).round() as u16
+        })
+        .collect()
+}
+
+/// Get the duration and waveform of a WAV recording with 16-bit PCM samples.
+///
+/// Only the first channel is used for the waveform.
+/// Returns `None` if the data isn't such a recording.
+pub fn analyze_wav(data: &[u8]) -> Option<AudioDetails> {
+    if data.get(0..4)? != b"RIFF" || data.get(8..12)? != b"WAVE" {
+        return None;
+    }
+    let mut format = None;
+    let mut samples = None;
+    let mut offset = 12;
+    while let Some(header) = data.get(offset..offset + 8) {
+        let size = u32::from_le_bytes(header[4..8].try_into().ok()?) as usize;
+        let body = data.get(offset + 8..)?;
+        let body = &body[..size.min(body.len())];
+        match &header[0..4] {
+            b"fmt " if body.len() >= 16 => {
+                let audio_format = u16::from_le_bytes([body[0], body[1]]);
+                let channels = u16::from_le_bytes([body[2], body[3]]);
+                let sample_rate = u32::from_le_bytes(body[4..8].try_into().ok()?);
+                let bits_per_sample = u16::from_le_bytes([body[14], body[15]]);
+                if audio_format != 1 || bits_per_sample != 16 || channels == 0 || sample_rate == 0 {
+                    return None;
+                }
+                format = Some((usize::from(channels), u64::from(sample_rate)));
+            }
+            b"data" => samples = Some(body),
+            _ => {}
+        }
+        // Chunks are padded to an even size
+        offset += 8 + size + size % 2;
+    }
+
+    let (channels, sample_rate) = format?;
+    let frames: Vec<f32> = samples?
+        .chunks_exact(2 * channels)
+        .map(|frame| f32::from(i16::from_le_bytes([frame[0], frame[1]])) / f32::from(i16::MAX))
+        .collect();
+    Some(AudioDetails {
+        duration: Some(frames.len() as u64 * 1000 / sample_rate),
+        waveform: waveform_from_samples(&frames),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Encode mono 16-bit samples as a WAV file.
+    fn wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+        let data_size = (samples.len() * 2) as u32;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16_u32.to_le_bytes());
+        wav.extend_from_slice(&1_u16.to_le_bytes());
+        wav.extend_from_slice(&1_u16.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2_u16.to_le_bytes());
+        wav.extend_from_slice(&16_u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_size.to_le_bytes());
+        for sample in samples {
+            wav.extend_from_slice(&sample.to_le_bytes());
+        }
+        wav
+    }
+
+    #[test]
+    fn test_analyze_wav() {
+        // Two seconds of silence, then two seconds at full volume
+        let mut samples = vec![0_i16; 16_000];
+        samples.extend(std::iter::repeat_n(i16::MAX, 16_000));
+        let details = analyze_wav(&wav(&samples, 8_000)).unwrap();
+
+        assert_eq!(details.duration, Some(4_000));
+        assert_eq!(details.duration_text().as_deref(), Some("0:04"));
+        assert_eq!(details.waveform.len(), WAVEFORM_LENGTH);
+        assert_eq!(details.waveform[0], 0);
+        assert_eq!(
+            details.waveform[WAVEFORM_LENGTH - 1],
+            MAX_WAVEFORM_AMPLITUDE
+        );
+        assert_eq!(details.levels(2), [0.0, 1.0]);
+
+        assert_eq!(analyze_wav(b"ID3 not a wav file"), None);
+    }
+
+    #[test]
+    fn test_audio_details_from_content() {
+        let mut content = serde_json::Map::new();
+        content.insert("msgtype".to_string(), "m.audio".into());
+        let details = AudioDetails {
+            duration: Some(65_000),
+            waveform: vec![0, 512, 1024],
+        };
+        details.insert_into(&mut content);
+        let content = serde_json::Value::Object(content);
+        assert_eq!(audio_details_from_content(&content), Some(details));
+        assert_eq!(content[VOICE_FIELD], json!({}));
+
+        // Audio from other clients only has its duration in its info
+        let content =
+            json!({ "msgtype": "m.audio", "body": "song.mp3", "info": { "duration": 5_000 } });
+        let details = audio_details_from_content(&content).unwrap();
+        assert_eq!(details.duration_text().as_deref(), Some("0:05"));
+        assert_eq!(details.levels(3), [0.0; 3]);
+
+        let content = json!({ "msgtype": "m.image", "body": "sunset.jpg" });
+        assert_eq!(audio_details_from_content(&content), None);
+    }
+}
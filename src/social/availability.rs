@@ -0,0 +1,269 @@
+//! Runtime switch for social features.
+//!
+//! Social features are compiled in with the `social` cargo feature, but can
+//! also be turned off per account at runtime, so that one build can be
+//! deployed with social features on for some users and off for others.
+//! Whether they are enabled is decided, in order of precedence, by:
+//! 1. the account's `org.social.settings` account data, and
+//! 2. the `org.social` hint in the homeserver's `/.well-known/matrix/client`,
+//!    e.g., `{ "org.social": { "enabled": false } }`.
+//!
+//! If neither is set, social features are enabled. Until the account is
+//! resolved after login, they are treated as disabled.
+//! While disabled, social UI is hidden (see
+//! [`SocialGate`](crate::social::widgets::social_gate::SocialGate)),
+//! social background jobs don't run, and no feed sync filter is provided
+//! (see [`feed_sync_filter()`]), so feed rooms aren't synced separately
+//! (see [`spawn_feed_sync()`]). They are disabled again on logout.
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    config::SyncSettings,
+    ruma::{
+        api::client::{filter::FilterDefinition, sync::sync_events::v3::Filter},
+        events::GlobalAccountDataEvent,
+        OwnedRoomId,
+    },
+    Client,
+};
+use robrix_social_events::settings::SocialSettingsEventContent;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use tokio::runtime::Handle;
+
+use crate::sliding_sync::get_client;
+use crate::social::{
+    feed_room::is_feed_room,
+    maps::update_remote_maps_disabled,
+    newsfeed::{
+        birthdays::update_birthdays_hidden, create_feed_sync_filter, density::update_feed_density,
+        feed_filter::update_feed_filter, feed_grouping::update_feed_grouping,
+    },
+    privacy::privacy_mode::update_privacy_mode,
+    storage_usage::update_cache_limits,
+    translation::update_translation_provider,
+};
+
+/// The key of the social features hint in the homeserver's well-known file.
+pub const WELL_KNOWN_KEY: &str = "org.social";
+
+/// How long the server may hold a feed sync request open while waiting for new events.
+const FEED_SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long to wait before syncing the feed rooms again after there was
+/// nothing to sync or syncing failed.
+const FEED_SYNC_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Whether social features are enabled for the logged-in account.
+static SOCIAL_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Actions emitted when social features are turned on or off.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialAvailabilityAction {
+    /// Social features were enabled (`true`) or disabled (`false`).
+    Changed(bool),
+    /// No action.
+    None,
+}
+
+/// Whether social features are currently enabled for the logged-in account.
+pub fn is_social_enabled() -> bool {
+    SOCIAL_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Get the sync filter for feed rooms, or `None` if social features are
+/// disabled, in which case feed rooms shouldn't be synced separately.
+pub fn feed_sync_filter() -> Option<FilterDefinition> {
+    is_social_enabled().then(create_feed_sync_filter)
+}
+
+/// Keep the user's joined feed rooms synced with [`feed_sync_filter()`],
+/// separately from the room list, while social features are enabled,
+/// so that new posts reach the newsfeed without syncing everything else
+/// that happens in feed rooms.
+///
+/// Must be called from within the Tokio runtime after logging in.
+/// Stops once the user is logged out.
+pub fn spawn_feed_sync(client: Client) {
+    Handle::current().spawn(async move {
+        let mut settings = SyncSettings::default().timeout(FEED_SYNC_TIMEOUT);
+        while get_client().is_some_and(|current| current.user_id() == client.user_id()) {
+            let feed_rooms: Vec<OwnedRoomId> = client
+                .joined_rooms()
+                .into_iter()
+                .filter(is_feed_room)
+                .map(|room| room.room_id().to_owned())
+                .collect();
+            let Some(mut filter) = feed_sync_filter().filter(|_| !feed_rooms.is_empty()) else {
+                tokio::time::sleep(FEED_SYNC_RETRY_DELAY).await;
+                continue;
+            };
+            filter.room.rooms = Some(feed_rooms);
+            match client
+                .sync_once(settings.clone().filter(Filter::FilterDefinition(filter)))
+                .await
+            {
+                Ok(response) => settings = settings.token(response.next_batch),
+                Err(e) => {
+                    warning!("Failed to sync the feed rooms: {e}");
+                    tokio::time::sleep(FEED_SYNC_RETRY_DELAY).await;
+                }
+            }
+        }
+    });
+}
+
+/// Disable social features until the next account is resolved after login.
+///
+/// Must be called when the user logs out.
+pub fn reset_social_enabled() {
+    set_social_enabled(false);
+}
+
+/// Enable or disable social features, notifying the UI if that changed.
+fn set_social_enabled(enabled: bool) {
+    if SOCIAL_ENABLED.swap(enabled, Ordering::Relaxed) != enabled {
+        log!(
+            "Social features are now {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        Cx::post_action(SocialAvailabilityAction::Changed(enabled));
+    }
+}
+
+/// Decide whether social features are enabled from the account's setting
+/// and the homeserver's hint, where the account's setting wins.
+pub fn resolve_enabled(account_setting: Option<bool>, server_hint: Option<bool>) -> bool {
+    account_setting.or(server_hint).unwrap_or(true)
+}
+
+/// Get the social features hint from the contents of a homeserver's
+/// `/.well-known/matrix/client` file.
+pub fn parse_well_known_hint(well_known: &serde_json::Value) -> Option<bool> {
+    well_known.get(WELL_KNOWN_KEY)?.get("enabled")?.as_bool()
+}
+
+/// Fetch the social features hint from the logged-in user's homeserver.
+///
+/// Returns `None` if the server has no well-known file or no hint in it.
+async fn fetch_server_hint(client: &Client) -> Option<bool> {
+    let server_name = client.user_id()?.server_name();
+    let url = format!("https://{server_name}/.well-known/matrix/client");
+    let response = client.http_client().get(&url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let well_known = serde_json::from_str(&response.text().await.ok()?).ok()?;
+    parse_well_known_hint(&well_known)
+}
+
+/// Load the account's social settings from its account data.
+///
+/// Accounts without settings, or with settings that can't be parsed, get the default settings.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded.
+pub(crate) async fn load_settings(
+    client: &Client,
+) -> Result<SocialSettingsEventContent, matrix_sdk::Error> {
+    let raw = client
+        .account()
+        .account_data::<SocialSettingsEventContent>()
+        .await?;
+    Ok(raw
+        .and_then(|raw| raw.deserialize().ok())
+        .unwrap_or_default())
+}
+
+/// Turn social features on or off for the logged-in account.
+///
+/// Passing `None` removes the account's own setting, so the homeserver's
+/// default applies again. The change takes effect once it has synced back.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn set_account_setting(
+    client: &Client,
+    enabled: Option<bool>,
+) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.enabled = enabled;
+    client.account().set_account_data(settings).await?;
+    Ok(())
+}
+
+/// Decide whether social features are enabled for the logged-in account,
+/// and keep that up to date as the account's setting changes.
+///
+/// This also keeps the account's [privacy mode](crate::social::privacy::privacy_mode),
+/// [remote maps setting](crate::social::maps::set_remote_maps_disabled),
+/// [cache limits](crate::social::storage_usage::cache_limits),
+/// [newsfeed density](crate::social::newsfeed::feed_density),
+/// [newsfeed filter](crate::social::newsfeed::feed_filter()),
+/// [newsfeed grouping](crate::social::newsfeed::feed_grouping()) and
+/// [translation service](crate::social::translation::translation_settings) up to date.
+///
+/// Must be called from within the Tokio runtime after logging in.
+/// A [`SocialAvailabilityAction::Changed`] action is posted whenever
+/// social features are turned on or off.
+pub fn spawn_availability_watcher(client: Client) {
+    Handle::current().spawn(async move {
+        let server_hint = fetch_server_hint(&client).await;
+        let settings = load_settings(&client).await.unwrap_or_default();
+        set_social_enabled(resolve_enabled(settings.enabled, server_hint));
+        update_privacy_mode(settings.privacy_mode);
+        update_remote_maps_disabled(settings.remote_maps_disabled);
+        update_cache_limits(settings.cache_limits);
+        update_feed_density(settings.feed_density);
+        update_feed_filter(settings.feed_filter);
+        update_feed_grouping(settings.feed_grouping);
+        update_birthdays_hidden(settings.birthdays_hidden);
+        update_translation_provider(settings.translation);
+
+        client.add_event_handler(
+            move |event: GlobalAccountDataEvent<SocialSettingsEventContent>| async move {
+                set_social_enabled(resolve_enabled(event.content.enabled, server_hint));
+                update_privacy_mode(event.content.privacy_mode);
+                update_remote_maps_disabled(event.content.remote_maps_disabled);
+                update_cache_limits(event.content.cache_limits);
+                update_feed_density(event.content.feed_density);
+                update_feed_filter(event.content.feed_filter);
+                update_feed_grouping(event.content.feed_grouping);
+                update_birthdays_hidden(event.content.birthdays_hidden);
+                update_translation_provider(event.content.translation);
+            },
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_setting_overrides_server_hint() {
+        assert!(resolve_enabled(None, None));
+        assert!(!resolve_enabled(None, Some(false)));
+        assert!(resolve_enabled(Some(true), Some(false)));
+        assert!(!resolve_enabled(Some(false), Some(true)));
+    }
+
+    #[test]
+    fn test_parse_well_known_hint() {
+        let well_known = serde_json::json!({
+            "m.homeserver": { "base_url": "https://matrix.example.org" },
+            "org.social": { "enabled": false },
+        });
+        assert_eq!(parse_well_known_hint(&well_known), Some(false));
+
+        let without_hint = serde_json::json!({
+            "m.homeserver": { "base_url": "https://matrix.example.org" },
+        });
+        assert_eq!(parse_well_known_hint(&without_hint), None);
+        assert_eq!(
+            parse_well_known_hint(&serde_json::json!({ "org.social": { "enabled": "no" } })),
+            None
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! The current user's list of blocked users.
+//!
+//! Blocked users are stored in the standard `m.ignored_user_list` account
+//! data, so blocking someone in Robrix also hides them in other Matrix
+//! clients. On top of ignoring, blocking bans the user from all of the
+//! current user's feeds, so they can no longer see or follow them.
+//!
+//! The newsfeed and friend list hide blocked users automatically,
+//! see [`is_blocked()`].
+
+use matrix_sdk::{
+    ruma::{events::ignored_user_list::IgnoredUserListEventContent, OwnedUserId, UserId},
+    Client,
+};
+use std::collections::BTreeSet;
+
+use crate::{
+    sliding_sync::is_user_ignored,
+    social::follower_moderation::{FollowerModerationError, FollowerModerationService},
+};
+
+/// Returns whether the given user is blocked by the current user.
+///
+/// This reads the ignored user list kept up to date by sync,
+/// so it is cheap enough to call when filtering lists of users or posts.
+pub fn is_blocked(user_id: &UserId) -> bool {
+    is_user_ignored(user_id)
+}
+
+/// Service for blocking and unblocking users.
+pub struct BlockListService {
+    client: Client,
+}
+
+impl BlockListService {
+    /// Create a new BlockListService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Block a user.
+    ///
+    /// The user is added to the ignored user list and banned from all of
+    /// the current user's feeds.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, tries to block
+    /// themselves, or the Matrix API calls fail.
+    pub async fn block(
+        &self,
+        user_id: &UserId,
+        reason: Option<&str>,
+    ) -> Result<(), BlockListError> {
+        if self.client.user_id().ok_or(BlockListError::NotLoggedIn)? == user_id {
+            return Err(BlockListError::CannotBlockSelf);
+        }
+
+        if !self.blocked_users().await?.contains(user_id) {
+            self.client.account().ignore_user(user_id).await?;
+        }
+        self.ban_from_feeds(user_id, reason).await
+    }
+
+    /// Unblock a user, lifting their bans from the current user's feeds.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or the Matrix API calls fail.
+    pub async fn unblock(&self, user_id: &UserId) -> Result<(), BlockListError> {
+        if self.blocked_users().await?.contains(user_id) {
+            self.client.account().unignore_user(user_id).await?;
+        }
+        FollowerModerationService::new(self.client.clone())
+            .unban_from_feeds(user_id)
+            .await?;
+        Ok(())
+    }
+
+    /// Get the users blocked by the current user.
+    ///
+    /// # Errors
+    /// Returns an error if the account data cannot be loaded.
+    pub async fn blocked_users(&self) -> Result<BTreeSet<OwnedUserId>, BlockListError> {
+        let Some(raw) = self
+            .client
+            .account()
+            .account_data::<IgnoredUserListEventContent>()
+            .await?
+        else {
+            return Ok(BTreeSet::new());
+        };
+        let content = raw.deserialize().map_err(matrix_sdk::Error::from)?;
+        Ok(content.ignored_users.into_keys().collect())
+    }
+
+    /// Ban every blocked user from all of the current user's feeds.
+    ///
+    /// Call this after creating a new feed room, so that users blocked
+    /// earlier are banned from it too.
+    ///
+    /// # Errors
+    /// Returns an error if the account data cannot be loaded or any ban fails.
+    pub async fn apply_to_feeds(&self) -> Result<(), BlockListError> {
+        for user_id in self.blocked_users().await? {
+            self.ban_from_feeds(&user_id, Some("User blocked")).await?;
+        }
+        Ok(())
+    }
+
+    /// Ban a user from all of the current user's feeds, if they have any.
+    async fn ban_from_feeds(
+        &self,
+        user_id: &UserId,
+        reason: Option<&str>,
+    ) -> Result<(), BlockListError> {
+        match FollowerModerationService::new(self.client.clone())
+            .ban_from_feeds(user_id, reason)
+            .await
+        {
+            Ok(()) | Err(FollowerModerationError::FeedNotFound) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Errors that can occur when blocking users.
+#[derive(Debug, thiserror::Error)]
+pub enum BlockListError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// Users can't block themselves.
+    #[error("Cannot block yourself")]
+    CannotBlockSelf,
+
+    /// Banning the user from the current user's feeds failed.
+    #[error("Feed moderation error: {0}")]
+    Moderation(#[from] FollowerModerationError),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
@@ -0,0 +1,277 @@
+//! Headless `robrix social` subcommands, for scripting social features.
+//!
+//! These reuse the social services on the session saved by the app, without
+//! starting the UI, e.g.:
+//! ```text
+//! robrix social post "Off to the mountains!" --audience friends --media hike.jpg
+//! robrix social export-feed --output backup.json
+//! ```
+//! The user must have logged in with the app before. Only available with
+//! the `cli` feature.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use matrix_sdk::{
+    ruma::{OwnedUserId, UserId},
+    Client,
+};
+use std::path::PathBuf;
+
+use crate::persistence;
+use crate::social::{
+    feed_room::{FeedPrivacy, FeedRoomService},
+    migration::{MigrationError, MigrationService},
+    outbox::{OutboxError, OutboxMedia, PostService},
+    post::Post,
+};
+
+/// Robrix's command line, when running headless subcommands.
+#[derive(Parser, Debug)]
+#[command(name = "robrix")]
+pub struct RobrixCli {
+    #[command(subcommand)]
+    pub command: RobrixCommand,
+}
+
+/// Headless subcommands of Robrix.
+#[derive(Subcommand, Debug)]
+pub enum RobrixCommand {
+    /// Use social features without the UI.
+    Social {
+        /// The user to act as. Defaults to the most recently logged-in user.
+        #[arg(long, global = true)]
+        user_id: Option<String>,
+
+        #[command(subcommand)]
+        command: SocialCommand,
+    },
+}
+
+/// Social subcommands.
+#[derive(Subcommand, Debug)]
+pub enum SocialCommand {
+    /// Post to your feeds, printing the event ID of each copy sent.
+    Post {
+        /// The text of the post, or the caption of its media.
+        text: String,
+
+        /// Who the post is for. Repeat to post to several feeds.
+        #[arg(long, value_enum, default_value = "public")]
+        audience: Vec<Audience>,
+
+        /// A photo, video or audio clip to attach.
+        #[arg(long)]
+        media: Option<PathBuf>,
+    },
+    /// Export your social profile, friends and own posts as JSON.
+    ExportFeed {
+        /// The file to write the export to. Defaults to standard output.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Who a post from the command line is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Audience {
+    Public,
+    Friends,
+    CloseFriends,
+}
+
+impl From<Audience> for FeedPrivacy {
+    fn from(audience: Audience) -> Self {
+        match audience {
+            Audience::Public => FeedPrivacy::Public,
+            Audience::Friends => FeedPrivacy::Friends,
+            Audience::CloseFriends => FeedPrivacy::CloseFriends,
+        }
+    }
+}
+
+/// Run a headless subcommand if one was given on the command line.
+///
+/// Returns the exit code once the subcommand has completed,
+/// or `None` if the app should be started instead.
+pub fn run_from_args() -> Option<i32> {
+    if std::env::args().nth(1).as_deref() != Some("social") {
+        return None;
+    }
+    // Prints the usage and exits if the arguments are invalid
+    let RobrixCommand::Social { user_id, command } = RobrixCli::parse().command;
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Error: failed to start the async runtime: {e}");
+            return Some(1);
+        }
+    };
+    match runtime.block_on(run(user_id, command)) {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            Some(1)
+        }
+    }
+}
+
+/// Run a social subcommand as the given user.
+///
+/// # Errors
+/// Returns an error if the user's session cannot be restored or the subcommand fails.
+pub async fn run(user_id: Option<String>, command: SocialCommand) -> Result<(), CliError> {
+    let user_id = user_id
+        .map(|user_id| UserId::parse(&user_id).map_err(|_| CliError::InvalidUserId(user_id)))
+        .transpose()?;
+    let client = restore_client(user_id).await?;
+    match command {
+        SocialCommand::Post {
+            text,
+            audience,
+            media,
+        } => post(client, text, audience, media).await,
+        SocialCommand::ExportFeed { output } => export_feed(client, output).await,
+    }
+}
+
+/// Restore the saved session of the given user, or of the most recently logged-in user.
+async fn restore_client(user_id: Option<OwnedUserId>) -> Result<Client, CliError> {
+    let (client, _sync_token) = persistence::restore_session(user_id)
+        .await
+        .map_err(CliError::Session)?;
+    Ok(client)
+}
+
+/// Post to the user's feeds for the given audiences.
+async fn post(
+    client: Client,
+    text: String,
+    audiences: Vec<Audience>,
+    media: Option<PathBuf>,
+) -> Result<(), CliError> {
+    let user_id = client.user_id().ok_or(CliError::NotLoggedIn)?.to_owned();
+    let feed_service = FeedRoomService::new(client.clone());
+    let privacy_levels: Vec<FeedPrivacy> = audiences.into_iter().map(Into::into).collect();
+    let targets = privacy_levels
+        .iter()
+        .map(|privacy| {
+            feed_service
+                .find_joined_feed(&user_id, *privacy)
+                .map(|room| room.room_id().to_owned())
+                .ok_or(CliError::FeedNotFound(*privacy))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let post = Post::text(text)
+        .with_privacy(privacy_levels)
+        .with_targets(targets);
+    let media = media.map(OutboxMedia::from_path);
+    let event_ids = PostService::new(client).send_now(&post, media).await?;
+    for event_id in event_ids {
+        println!("{event_id}");
+    }
+    Ok(())
+}
+
+/// Export the user's social profile, friends and own posts as JSON.
+async fn export_feed(client: Client, output: Option<PathBuf>) -> Result<(), CliError> {
+    let export = MigrationService::new(client).export().await?;
+    let json = export.to_json()?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, json)?;
+            eprintln!(
+                "Exported {} posts to {}",
+                export.post_count(),
+                path.display()
+            );
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+/// Errors that can occur when running headless subcommands.
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    /// The given user ID is invalid.
+    #[error("Invalid user ID: {0}")]
+    InvalidUserId(String),
+
+    /// No saved session could be restored.
+    #[error("Could not restore a session, please log in with Robrix first: {0}")]
+    Session(anyhow::Error),
+
+    /// User is not logged in.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The user has no feed for one of the audiences.
+    #[error("You have no {0}")]
+    FeedNotFound(FeedPrivacy),
+
+    /// The post could not be sent.
+    #[error(transparent)]
+    Post(#[from] OutboxError),
+
+    /// The export could not be created.
+    #[error(transparent)]
+    Migration(#[from] MigrationError),
+
+    /// The export could not be written.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_post() {
+        let cli = RobrixCli::try_parse_from([
+            "robrix",
+            "social",
+            "post",
+            "Hello",
+            "--audience",
+            "friends",
+            "--audience",
+            "close-friends",
+        ])
+        .unwrap();
+        let RobrixCommand::Social { user_id, command } = cli.command;
+        assert_eq!(user_id, None);
+        let SocialCommand::Post {
+            text,
+            audience,
+            media,
+        } = command
+        else {
+            panic!("expected a post command");
+        };
+        assert_eq!(text, "Hello");
+        assert_eq!(audience, vec![Audience::Friends, Audience::CloseFriends]);
+        assert_eq!(media, None);
+    }
+
+    #[test]
+    fn test_parse_export_feed() {
+        let cli = RobrixCli::try_parse_from([
+            "robrix",
+            "social",
+            "--user-id",
+            "@me:example.org",
+            "export-feed",
+            "-o",
+            "backup.json",
+        ])
+        .unwrap();
+        let RobrixCommand::Social { user_id, command } = cli.command;
+        assert_eq!(user_id.as_deref(), Some("@me:example.org"));
+        assert!(matches!(
+            command,
+            SocialCommand::ExportFeed { output: Some(path) } if path == PathBuf::from("backup.json")
+        ));
+    }
+}
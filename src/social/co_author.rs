@@ -0,0 +1,556 @@
+//! Collaborative posts written by two authors.
+//!
+//! The author invites a co-author by sending a post to their own feeds with
+//! an `org.social.co_author` field naming both authors (see [`CoAuthorship`]),
+//! and mentioning the co-author so that they're notified. The co-author finds
+//! the invitation in the author's feed, which they've joined as a follower or
+//! friend. Accepting cross-posts the same content, with the same correlation
+//! ID, to the co-author's own public feed and answers with an
+//! `org.social.co_author_response` event in the author's feed; declining only
+//! answers. Both authors are shown on a post once the co-author has accepted.
+
+use matrix_sdk::{
+    room::{MessagesOptions, Room},
+    ruma::{
+        events::{AnySyncTimelineEvent, OriginalSyncMessageLikeEvent, SyncMessageLikeEvent},
+        serde::Raw,
+        EventId, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId, TransactionId, UserId,
+    },
+    Client,
+};
+use robrix_social_events::{
+    co_author::{CoAuthorship, SocialCoAuthorResponseEventContent, CO_AUTHOR_FIELD},
+    visibility::PostVisibility,
+};
+use std::collections::{HashMap, HashSet};
+
+use crate::social::{
+    events::timing::now_ms,
+    feed_room::{feed_of_room, find_feed, FeedPrivacy, FeedRoomError},
+    post::{visibility_from_content, Post, PostContent},
+};
+
+/// How far back to look for invitations to co-author a post.
+pub const CO_AUTHOR_INVITE_LOOKBACK_MS: u64 = 14 * 24 * 60 * 60 * 1000;
+
+/// Event type of [`SocialCoAuthorResponseEventContent`].
+const CO_AUTHOR_RESPONSE_TYPE: &str = "org.social.co_author_response";
+
+/// An invitation to co-author a post, sent to the current user.
+#[derive(Clone, Debug)]
+pub struct CoAuthorInvite {
+    /// The author's feed room the post was sent to.
+    pub room_id: OwnedRoomId,
+    /// Event ID of the post.
+    pub event_id: OwnedEventId,
+    /// The authors of the post.
+    pub co_authorship: CoAuthorship,
+    /// Raw message content of the post, which is cross-posted as is when accepted.
+    pub content: serde_json::Value,
+}
+
+/// Service for writing posts together with another user.
+pub struct CoAuthorService {
+    client: Client,
+}
+
+impl CoAuthorService {
+    /// Create a new CoAuthorService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Send a post to its target feeds, inviting `co_author` to co-author it.
+    ///
+    /// # Returns
+    /// The event IDs of the post in each of its targets.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, invites themselves,
+    /// the post has no targets, or a target feed room is not found.
+    pub async fn post_with_co_author(
+        &self,
+        post: Post,
+        co_author: &UserId,
+    ) -> Result<Vec<OwnedEventId>, CoAuthorError> {
+        let own_user_id = self.client.user_id().ok_or(CoAuthorError::NotLoggedIn)?;
+        if co_author == own_user_id {
+            return Err(CoAuthorError::CannotCoAuthorWithSelf);
+        }
+        if post.targets.is_empty() {
+            return Err(CoAuthorError::NoTargets);
+        }
+
+        let post = invite_co_author(post, own_user_id, co_author);
+        let mut content = post.into_raw_content()?;
+        add_mention(&mut content, co_author);
+
+        let mut event_ids = Vec::with_capacity(post.targets.len());
+        for room_id in &post.targets {
+            let room = self
+                .client
+                .get_room(room_id)
+                .ok_or_else(|| CoAuthorError::RoomNotFound(room_id.clone()))?;
+            let response = room.send_raw("m.room.message", content.clone()).await?;
+            event_ids.push(response.event_id);
+        }
+        Ok(event_ids)
+    }
+
+    /// Find the invitations to co-author a post that the current user hasn't answered yet.
+    ///
+    /// Invitations are looked for in the other users' feeds the current user
+    /// has joined, going back [`CO_AUTHOR_INVITE_LOOKBACK_MS`].
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or messages cannot be loaded.
+    pub async fn pending_invites(&self) -> Result<Vec<CoAuthorInvite>, CoAuthorError> {
+        let own_user_id = self.client.user_id().ok_or(CoAuthorError::NotLoggedIn)?;
+        let since_ms = now_ms().saturating_sub(CO_AUTHOR_INVITE_LOOKBACK_MS);
+
+        let mut invites = Vec::new();
+        for room in self.client.joined_rooms() {
+            let is_others_feed = feed_of_room(&room).is_some_and(|(_, owner)| owner != own_user_id);
+            if !is_others_feed {
+                continue;
+            }
+
+            let (posts, answered) = load_invites(&room, own_user_id, since_ms).await?;
+            invites.extend(
+                posts
+                    .into_iter()
+                    .filter(|invite| !answered.contains(&invite.co_authorship.correlation_id)),
+            );
+        }
+        Ok(invites)
+    }
+
+    /// Accept an invitation to co-author a post,
+    /// cross-posting it to the current user's public feed.
+    ///
+    /// # Returns
+    /// The event ID of the copy of the post in the current user's public feed.
+    ///
+    /// # Errors
+    /// Returns an error if the post is intended for a narrower audience than
+    /// the public, the user has no public feed, or sending fails.
+    pub async fn accept(&self, invite: &CoAuthorInvite) -> Result<OwnedEventId, CoAuthorError> {
+        let own_user_id = self.client.user_id().ok_or(CoAuthorError::NotLoggedIn)?;
+        let rooms = self.client.joined_rooms().into_iter();
+        let public_feed = cross_post_feed(
+            &invite.content,
+            own_user_id,
+            rooms.map(|room| (feed_of_room(&room), room)),
+        )?;
+
+        let response = public_feed
+            .send_raw("m.room.message", invite.content.clone())
+            .await?;
+        self.respond(invite, Some(response.event_id.clone()))
+            .await?;
+        Ok(response.event_id)
+    }
+
+    /// Decline an invitation to co-author a post.
+    ///
+    /// # Errors
+    /// Returns an error if the author's feed room is not found or sending fails.
+    pub async fn decline(&self, invite: &CoAuthorInvite) -> Result<(), CoAuthorError> {
+        self.respond(invite, None).await
+    }
+
+    /// Answer an invitation in the author's feed room,
+    /// accepting it if the post was cross-posted as `cross_post`.
+    async fn respond(
+        &self,
+        invite: &CoAuthorInvite,
+        cross_post: Option<OwnedEventId>,
+    ) -> Result<(), CoAuthorError> {
+        let room = self
+            .client
+            .get_room(&invite.room_id)
+            .ok_or_else(|| CoAuthorError::RoomNotFound(invite.room_id.clone()))?;
+        room.send(SocialCoAuthorResponseEventContent {
+            post: invite.event_id.clone(),
+            correlation_id: invite.co_authorship.correlation_id.clone(),
+            accepted: cross_post.is_some(),
+            cross_post,
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Find the collaborative posts in a feed room whose co-author accepted.
+    ///
+    /// This loads the room's whole history, so the result should be kept
+    /// rather than loaded again for every post.
+    ///
+    /// # Returns
+    /// The users who accepted, keyed by the event ID of the post they accepted.
+    /// Whether they were actually invited to that post should be checked with
+    /// [`displayed_co_author()`].
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or messages cannot be loaded.
+    pub async fn accepted_co_authors(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<HashMap<OwnedEventId, OwnedUserId>, CoAuthorError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or_else(|| CoAuthorError::RoomNotFound(room_id.to_owned()))?;
+
+        let mut accepted = HashMap::new();
+        let mut from = None;
+        loop {
+            let mut options = MessagesOptions::backward();
+            options.from = from.take();
+            let response = room.messages(options).await?;
+
+            for timeline_event in &response.chunk {
+                if let Some(response) = parse_response(timeline_event.raw()) {
+                    if response.content.accepted {
+                        accepted
+                            .entry(response.content.post)
+                            .or_insert(response.sender);
+                    }
+                }
+            }
+
+            match response.end {
+                Some(end) if !response.chunk.is_empty() => from = Some(end),
+                _ => break,
+            }
+        }
+        Ok(accepted)
+    }
+}
+
+/// Get the feed to cross-post a collaborative post to when accepting it,
+/// i.e., the co-author's own public feed among their joined rooms, identified
+/// with [`feed_of_room()`].
+///
+/// # Errors
+/// Returns an error if the post is intended for a narrower audience than
+/// the public, or the co-author has no public feed.
+fn cross_post_feed<R>(
+    content: &serde_json::Value,
+    own_user_id: &UserId,
+    rooms: impl IntoIterator<Item = (Option<(FeedPrivacy, OwnedUserId)>, R)>,
+) -> Result<R, CoAuthorError> {
+    if visibility_from_content(content)
+        .is_some_and(|visibility| visibility != PostVisibility::Public)
+    {
+        return Err(CoAuthorError::NotPublic);
+    }
+    find_feed(rooms, own_user_id, FeedPrivacy::Public)
+        .ok_or(CoAuthorError::Feed(FeedRoomError::FeedNotFound))
+}
+
+/// Make a post invite `co_author` to co-author it with `author`,
+/// mentioning them in a text post so that they're notified.
+///
+/// [`CoAuthorService::post_with_co_author()`] sends such a post at once;
+/// it can also be queued in the [outbox](crate::social::outbox) like any other post.
+pub fn invite_co_author(mut post: Post, author: &UserId, co_author: &UserId) -> Post {
+    if let PostContent::Text { mentions, .. } = &mut post.content {
+        mentions.insert(co_author.to_owned());
+    }
+    post.with_co_authorship(Some(CoAuthorship {
+        author: author.to_owned(),
+        co_author: co_author.to_owned(),
+        correlation_id: TransactionId::new().to_string(),
+    }))
+}
+
+/// Get the author to show next to the sender of a collaborative post, if any.
+///
+/// The co-author's copy of the post shows the original author. The author's
+/// copy shows the co-author only once they've accepted, as found with
+/// [`CoAuthorService::accepted_co_authors()`]. Posts from neither of
+/// the authors named in `co_authorship` show nobody else.
+pub fn displayed_co_author(
+    sender: &UserId,
+    event_id: &EventId,
+    co_authorship: &CoAuthorship,
+    accepted: &HashMap<OwnedEventId, OwnedUserId>,
+) -> Option<OwnedUserId> {
+    if co_authorship.co_author == sender {
+        Some(co_authorship.author.clone())
+    } else if co_authorship.author == sender
+        && accepted.get(event_id) == Some(&co_authorship.co_author)
+    {
+        Some(co_authorship.co_author.clone())
+    } else {
+        None
+    }
+}
+
+/// Get the authors of a collaborative post from its raw message content.
+pub fn co_authorship_from_content(content: &serde_json::Value) -> Option<CoAuthorship> {
+    serde_json::from_value(content.get(CO_AUTHOR_FIELD)?.clone()).ok()
+}
+
+/// Add a user to the mentions of raw message content, so that they're notified.
+fn add_mention(content: &mut serde_json::Value, user_id: &UserId) {
+    let Some(object) = content.as_object_mut() else {
+        return;
+    };
+    let mentions = object
+        .entry("m.mentions")
+        .or_insert_with(|| serde_json::json!({}));
+    let Some(mentions) = mentions.as_object_mut() else {
+        return;
+    };
+    let user_ids = mentions
+        .entry("user_ids")
+        .or_insert_with(|| serde_json::json!([]));
+    if let Some(user_ids) = user_ids.as_array_mut() {
+        let user_id = serde_json::Value::from(user_id.as_str());
+        if !user_ids.contains(&user_id) {
+            user_ids.push(user_id);
+        }
+    }
+}
+
+/// Parse a timeline event as an answer to an invitation to co-author a post.
+fn parse_response(
+    raw: &Raw<AnySyncTimelineEvent>,
+) -> Option<OriginalSyncMessageLikeEvent<SocialCoAuthorResponseEventContent>> {
+    if raw.get_field::<String>("type").ok().flatten().as_deref() != Some(CO_AUTHOR_RESPONSE_TYPE) {
+        return None;
+    }
+    match raw.deserialize_as::<SyncMessageLikeEvent<SocialCoAuthorResponseEventContent>>() {
+        Ok(SyncMessageLikeEvent::Original(event)) => Some(event),
+        _ => None,
+    }
+}
+
+/// Load the invitations to `user_id` in a feed room sent since `since_ms`,
+/// along with the correlation IDs of the invitations they've answered.
+async fn load_invites(
+    room: &Room,
+    user_id: &UserId,
+    since_ms: u64,
+) -> Result<(Vec<CoAuthorInvite>, HashSet<String>), CoAuthorError> {
+    let mut invites = Vec::new();
+    let mut answered = HashSet::new();
+    let mut from = None;
+    'paginate: loop {
+        let mut options = MessagesOptions::backward();
+        options.from = from.take();
+        let response = room.messages(options).await?;
+
+        for timeline_event in &response.chunk {
+            let raw = timeline_event.raw();
+            if raw
+                .get_field::<u64>("origin_server_ts")
+                .ok()
+                .flatten()
+                .is_some_and(|ts| ts < since_ms)
+            {
+                break 'paginate;
+            }
+
+            if let Some(response) = parse_response(raw) {
+                if response.sender == user_id {
+                    answered.insert(response.content.correlation_id);
+                }
+                continue;
+            }
+
+            let Ok(Some(content)) = raw.get_field::<serde_json::Value>("content") else {
+                continue;
+            };
+            let Some(co_authorship) = co_authorship_from_content(&content) else {
+                continue;
+            };
+            let sender = raw.get_field::<OwnedUserId>("sender").ok().flatten();
+            let event_id = raw.get_field::<OwnedEventId>("event_id").ok().flatten();
+            // Only the author named in the post can invite a co-author to it
+            if let (Some(sender), Some(event_id)) = (sender, event_id) {
+                if co_authorship.co_author == user_id && co_authorship.author == sender {
+                    invites.push(CoAuthorInvite {
+                        room_id: room.room_id().to_owned(),
+                        event_id,
+                        co_authorship,
+                        content,
+                    });
+                }
+            }
+        }
+
+        match response.end {
+            Some(end) if !response.chunk.is_empty() => from = Some(end),
+            _ => break,
+        }
+    }
+    Ok((invites, answered))
+}
+
+/// Errors that can occur when co-authoring posts.
+#[derive(Debug, thiserror::Error)]
+pub enum CoAuthorError {
+    /// User is not logged in.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// User tried to invite themselves to co-author a post.
+    #[error("Cannot co-author a post with yourself")]
+    CannotCoAuthorWithSelf,
+
+    /// The post has no feeds to be sent to.
+    #[error("The post has no target feeds")]
+    NoTargets,
+
+    /// The post is intended for a narrower audience,
+    /// so it can't be cross-posted to the co-author's public feed.
+    #[error("The post is not public")]
+    NotPublic,
+
+    /// The specified room was not found.
+    #[error("Room not found: {0}")]
+    RoomNotFound(OwnedRoomId),
+
+    /// An error occurred while finding the user's feeds.
+    #[error("Feed error: {0}")]
+    Feed(#[from] FeedRoomError),
+
+    /// The post could not be converted to message content.
+    #[error("Invalid post content: {0}")]
+    InvalidContent(#[from] serde_json::Error),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{event_id, owned_event_id, owned_user_id, user_id};
+
+    fn co_authorship() -> CoAuthorship {
+        CoAuthorship {
+            author: owned_user_id!("@alice:example.org"),
+            co_author: owned_user_id!("@bob:example.org"),
+            correlation_id: "abc123".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_co_authors_copy_shows_the_author() {
+        assert_eq!(
+            displayed_co_author(
+                user_id!("@bob:example.org"),
+                event_id!("$copy:example.org"),
+                &co_authorship(),
+                &HashMap::new(),
+            ),
+            Some(owned_user_id!("@alice:example.org"))
+        );
+    }
+
+    #[test]
+    fn test_authors_copy_shows_the_co_author_once_accepted() {
+        let alice = user_id!("@alice:example.org");
+        let post = event_id!("$post:example.org");
+        assert_eq!(
+            displayed_co_author(alice, post, &co_authorship(), &HashMap::new()),
+            None
+        );
+
+        // Accepting a post someone wasn't invited to doesn't count
+        let accepted_by_mallory = HashMap::from([(
+            owned_event_id!("$post:example.org"),
+            owned_user_id!("@mallory:example.org"),
+        )]);
+        assert_eq!(
+            displayed_co_author(alice, post, &co_authorship(), &accepted_by_mallory),
+            None
+        );
+
+        let accepted = HashMap::from([(
+            owned_event_id!("$post:example.org"),
+            owned_user_id!("@bob:example.org"),
+        )]);
+        assert_eq!(
+            displayed_co_author(alice, post, &co_authorship(), &accepted),
+            Some(owned_user_id!("@bob:example.org"))
+        );
+    }
+
+    #[test]
+    fn test_invited_co_author_is_mentioned() {
+        let alice = user_id!("@alice:example.org");
+        let bob = user_id!("@bob:example.org");
+        let post = invite_co_author(Post::text("Our trip"), alice, bob);
+        let co_authorship = post.co_authorship.as_ref().unwrap();
+        assert_eq!(co_authorship.author, alice);
+        assert_eq!(co_authorship.co_author, bob);
+        let PostContent::Text { mentions, .. } = &post.content else {
+            panic!("expected a text post");
+        };
+        assert!(mentions.contains(bob));
+
+        let content = post.into_raw_content().unwrap();
+        assert_eq!(
+            co_authorship_from_content(&content).as_ref(),
+            Some(co_authorship)
+        );
+    }
+
+    #[test]
+    fn test_accepted_posts_are_cross_posted_to_own_public_feed() {
+        let bob = user_id!("@bob:example.org");
+        let rooms = || {
+            [
+                (
+                    Some((FeedPrivacy::Public, owned_user_id!("@alice:example.org"))),
+                    "alice_public",
+                ),
+                (None, "chat"),
+                (Some((FeedPrivacy::Friends, bob.to_owned())), "bob_friends"),
+                (Some((FeedPrivacy::Public, bob.to_owned())), "bob_public"),
+            ]
+        };
+        let content = Post::text("Our trip").into_raw_content().unwrap();
+        assert_eq!(
+            cross_post_feed(&content, bob, rooms()).unwrap(),
+            "bob_public"
+        );
+
+        // Without a public feed of their own, there's nowhere to cross-post to
+        assert!(matches!(
+            cross_post_feed(&content, bob, rooms().into_iter().take(3)),
+            Err(CoAuthorError::Feed(FeedRoomError::FeedNotFound))
+        ));
+
+        let friends_only = Post::text("Our trip")
+            .with_privacy(vec![FeedPrivacy::Friends])
+            .into_raw_content()
+            .unwrap();
+        assert!(matches!(
+            cross_post_feed(&friends_only, bob, rooms()),
+            Err(CoAuthorError::NotPublic)
+        ));
+    }
+
+    #[test]
+    fn test_co_authorship_round_trips_through_raw_content() {
+        let post = Post::text("Our trip").with_co_authorship(Some(co_authorship()));
+        let mut content = post.into_raw_content().unwrap();
+        assert_eq!(co_authorship_from_content(&content), Some(co_authorship()));
+
+        add_mention(&mut content, user_id!("@bob:example.org"));
+        add_mention(&mut content, user_id!("@bob:example.org"));
+        assert_eq!(
+            content["m.mentions"]["user_ids"],
+            serde_json::json!(["@bob:example.org"])
+        );
+
+        let solo = Post::text("Just me").into_raw_content().unwrap();
+        assert_eq!(co_authorship_from_content(&solo), None);
+    }
+}
@@ -0,0 +1,238 @@
+//! Discovery of event rooms the user is part of.
+//!
+//! Aggregates event rooms the user has joined or been invited to,
+//! and groups them by day or week for calendar display.
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, TimeZone};
+use matrix_sdk::{ruma::OwnedRoomId, Client};
+use robrix_social_events::event::SocialEventEventContent;
+use std::collections::BTreeMap;
+
+use crate::social::events::{
+    archival::is_past_event,
+    event_room::{load_event_details, EventRoomError},
+    EventTiming,
+};
+
+/// How the user is related to a discovered event room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventMembership {
+    /// The user has joined the event room.
+    Joined,
+    /// The user has a pending invite to the event room.
+    Invited,
+}
+
+/// An event room found by the discovery service.
+#[derive(Clone, Debug)]
+pub struct DiscoveredEvent {
+    /// Room ID of the event.
+    pub room_id: OwnedRoomId,
+    /// Event details from room state.
+    pub event: SocialEventEventContent,
+    /// The user's membership in the event room.
+    pub membership: EventMembership,
+}
+
+impl DiscoveredEvent {
+    /// Get the timing of the event relative to the given time.
+    pub fn timing(&self, now_ms: u64) -> EventTiming {
+        EventTiming::at(self.event.start_time, self.event.end_time, now_ms)
+    }
+
+    /// Check whether the event has not yet ended at the given time.
+    pub fn is_upcoming(&self, now_ms: u64) -> bool {
+        self.timing(now_ms) != EventTiming::Ended
+    }
+
+    /// Check whether the event's room was archived or closed by its host,
+    /// so it belongs among the user's past events.
+    pub fn is_past(&self) -> bool {
+        is_past_event(&self.event)
+    }
+
+    /// Get the local calendar day on which the event starts.
+    pub fn start_day<Tz: TimeZone>(&self, tz: &Tz) -> Option<NaiveDate> {
+        DateTime::from_timestamp_millis(self.event.start_time as i64)
+            .map(|start| start.with_timezone(tz).date_naive())
+    }
+}
+
+/// Service for finding event rooms the user has joined or been invited to.
+pub struct EventDiscoveryService {
+    client: Client,
+}
+
+impl EventDiscoveryService {
+    /// Create a new EventDiscoveryService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Find all event rooms the user has joined or been invited to.
+    ///
+    /// Events are sorted by start time. Invites whose event details were not
+    /// included in the invite state are skipped.
+    ///
+    /// # Errors
+    /// Returns an error if room state cannot be read.
+    pub async fn discover_events(&self) -> Result<Vec<DiscoveredEvent>, EventRoomError> {
+        let rooms = self
+            .client
+            .joined_rooms()
+            .into_iter()
+            .map(|room| (room, EventMembership::Joined))
+            .chain(
+                self.client
+                    .invited_rooms()
+                    .into_iter()
+                    .map(|room| (room, EventMembership::Invited)),
+            );
+
+        let mut events = Vec::new();
+        for (room, membership) in rooms {
+            if let Some(event) = load_event_details(&room)
+                .await
+                .map_err(EventRoomError::MatrixError)?
+            {
+                events.push(DiscoveredEvent {
+                    room_id: room.room_id().to_owned(),
+                    event,
+                    membership,
+                });
+            }
+        }
+
+        events.sort_by_key(|e| e.event.start_time);
+        Ok(events)
+    }
+
+    /// Find all event rooms whose event has not yet ended,
+    /// and that weren't archived or closed by their host.
+    ///
+    /// # Errors
+    /// Returns an error if room state cannot be read.
+    pub async fn upcoming_events(
+        &self,
+        now_ms: u64,
+    ) -> Result<Vec<DiscoveredEvent>, EventRoomError> {
+        let mut events = self.discover_events().await?;
+        events.retain(|e| e.is_upcoming(now_ms) && !e.is_past());
+        Ok(events)
+    }
+}
+
+/// Group events by the local calendar day on which they start.
+pub fn group_events_by_day<'a, Tz: TimeZone>(
+    events: &'a [DiscoveredEvent],
+    tz: &Tz,
+) -> BTreeMap<NaiveDate, Vec<&'a DiscoveredEvent>> {
+    let mut days: BTreeMap<NaiveDate, Vec<&DiscoveredEvent>> = BTreeMap::new();
+    for event in events {
+        if let Some(day) = event.start_day(tz) {
+            days.entry(day).or_default().push(event);
+        }
+    }
+    days
+}
+
+/// Group events by the local calendar week in which they start.
+///
+/// Weeks start on Monday and are keyed by the date of that Monday.
+pub fn group_events_by_week<'a, Tz: TimeZone>(
+    events: &'a [DiscoveredEvent],
+    tz: &Tz,
+) -> BTreeMap<NaiveDate, Vec<&'a DiscoveredEvent>> {
+    let mut weeks: BTreeMap<NaiveDate, Vec<&DiscoveredEvent>> = BTreeMap::new();
+    for event in events {
+        if let Some(day) = event.start_day(tz) {
+            weeks.entry(week_start(day)).or_default().push(event);
+        }
+    }
+    weeks
+}
+
+/// Get the Monday of the week containing the given day.
+pub fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Days::new(day.weekday().num_days_from_monday() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+    use robrix_social_events::event::EventVisibility;
+
+    const HOUR_MS: u64 = 60 * 60 * 1000;
+
+    fn event(title: &str, start_time: u64) -> DiscoveredEvent {
+        DiscoveredEvent {
+            room_id: "!event:example.org".try_into().unwrap(),
+            event: SocialEventEventContent {
+                title: title.to_string(),
+                description: None,
+                start_time,
+                end_time: None,
+                location: None,
+                cover_image: None,
+                visibility: EventVisibility::Private,
+                rsvp_deadline: None,
+                logistics: false,
+                scheduling_poll: None,
+                archival: None,
+            },
+            membership: EventMembership::Joined,
+        }
+    }
+
+    #[test]
+    fn test_group_by_day() {
+        // 2024-01-01 is a Monday
+        let monday = 1_704_067_200_000;
+        let events = vec![
+            event("Breakfast", monday + 8 * HOUR_MS),
+            event("Dinner", monday + 19 * HOUR_MS),
+            event("Lunch", monday + 36 * HOUR_MS),
+        ];
+
+        let days = group_events_by_day(&events, &Utc);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[&NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()].len(), 2);
+    }
+
+    #[test]
+    fn test_group_by_day_uses_timezone() {
+        let monday = 1_704_067_200_000;
+        let events = vec![event("Late dinner", monday + 23 * HOUR_MS)];
+
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let days = group_events_by_day(&events, &tz);
+        assert!(days.contains_key(&NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()));
+    }
+
+    #[test]
+    fn test_group_by_week() {
+        let monday = 1_704_067_200_000;
+        let events = vec![
+            event("Monday", monday),
+            event("Sunday", monday + 6 * 24 * HOUR_MS),
+            event("Next Monday", monday + 7 * 24 * HOUR_MS),
+        ];
+
+        let weeks = group_events_by_week(&events, &Utc);
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(
+            weeks[&NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()].len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_is_upcoming() {
+        let mut e = event("Party", 1000);
+        e.event.end_time = Some(3000);
+        assert!(e.is_upcoming(500));
+        assert!(e.is_upcoming(2000));
+        assert!(!e.is_upcoming(3000));
+    }
+}
@@ -1 +1,31 @@
-//! Profile and event discovery (placeholder).
+//! Profile and event discovery.
+//!
+//! User discovery finds other people through the homeserver's user
+//! directory, and friend suggestions come from the friends space graph.
+//! Trending posts and hashtags are ranked from recent engagement in the
+//! public feeds the user has joined.
+//! Event discovery aggregates the event rooms a user has joined or been
+//! invited to.
+
+pub mod events;
+pub mod suggestions;
+pub mod trending;
+pub mod users;
+
+pub use events::{
+    group_events_by_day, group_events_by_week, DiscoveredEvent, EventDiscoveryService,
+    EventMembership,
+};
+pub use suggestions::{
+    rank_friends_of_friends, shared_friends, FriendSuggestionEngine, FriendSuggestionsAction,
+    MutualFriendsAction, SuggestedUser, MAX_FRIEND_SUGGESTIONS,
+};
+pub use trending::{
+    engagement_velocity, extract_hashtags, rank_trending_posts, rank_trending_topics, Trending,
+    TrendingAction, TrendingError, TrendingPost, TrendingService, TrendingTopic,
+    MAX_TRENDING_POSTS, MAX_TRENDING_TOPICS, TRENDING_WINDOW_MS,
+};
+pub use users::{
+    DiscoveredUser, UserDiscoveryError, UserDiscoveryService, UserSearchAction,
+    MAX_USER_SEARCH_RESULTS,
+};
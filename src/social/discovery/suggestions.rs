@@ -0,0 +1,384 @@
+//! Suggestions of people the current user may know.
+//!
+//! Friends of friends are found by walking the friends space graph. For each
+//! friend, their friends are the members of their friends-only feed, along
+//! with the owners of the feeds in their own friends space when the current
+//! user can see it. Candidates are scored by how many friends they share with
+//! the current user; existing friends and blocked users are never suggested.
+//!
+//! The same graph gives the friends the current user shares with anyone whose
+//! friends they can see, shown as "N mutual friends" on profiles
+//! (see [`FriendSuggestionEngine::mutual_friends()`]).
+
+use makepad_widgets::{warning, DefaultNone};
+use matrix_sdk::{
+    ruma::{OwnedRoomId, OwnedUserId, UserId},
+    Client, Room, RoomMemberships,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    sliding_sync::get_ignored_users,
+    social::{
+        feed_room::{feed_of_room, FeedPrivacy, FeedRoomService},
+        follower_moderation::FollowerModerationService,
+        friends::{friends_space_children, friends_space_name, FriendsSpaceService},
+    },
+};
+
+use super::UserDiscoveryError;
+
+/// A user suggested as a friend of friends.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuggestedUser {
+    /// The user's ID.
+    pub user_id: OwnedUserId,
+    /// The current user's friends who are friends with this user.
+    pub mutual_friends: Vec<OwnedUserId>,
+}
+
+/// The most friends of friends suggested at once.
+pub const MAX_FRIEND_SUGGESTIONS: usize = 20;
+
+/// Actions emitted about the people suggested to the current user.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum FriendSuggestionsAction {
+    /// Friends of friends were loaded, with those sharing the most friends first,
+    /// see [`FriendSuggestionEngine::get_friend_suggestions()`].
+    Loaded(Vec<SuggestedUser>),
+    /// No action.
+    None,
+}
+
+/// Actions emitted about the friends the current user shares with someone.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum MutualFriendsAction {
+    /// The friends shared with a user were loaded, see
+    /// [`FriendSuggestionEngine::mutual_friends()`].
+    Loaded {
+        user_id: OwnedUserId,
+        /// The shared friends, or `None` if the user's friends aren't visible.
+        mutual_friends: Option<Vec<OwnedUserId>>,
+    },
+    /// No action.
+    None,
+}
+
+/// Engine for suggesting friends of friends.
+pub struct FriendSuggestionEngine {
+    client: Client,
+}
+
+impl FriendSuggestionEngine {
+    /// Create a new FriendSuggestionEngine.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Get up to `limit` suggested friends of friends, with those sharing the most friends first.
+    ///
+    /// Users the current user has ignored or banned from their feeds are excluded.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in,
+    /// or the friends space or feed members cannot be loaded.
+    pub async fn get_friend_suggestions(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<SuggestedUser>, UserDiscoveryError> {
+        let own_user_id = self
+            .client
+            .user_id()
+            .ok_or(UserDiscoveryError::NotLoggedIn)?
+            .to_owned();
+
+        let mut friends_of_friends = Vec::new();
+        for (friend, room) in self.own_friend_feeds().await? {
+            let their_friends = self.friends_of(&friend, &room).await?;
+            friends_of_friends.push((friend, their_friends.into_iter().collect()));
+        }
+
+        let mut excluded = get_ignored_users();
+        excluded.extend(
+            FollowerModerationService::new(self.client.clone())
+                .banned_users()
+                .await?,
+        );
+
+        let mut suggestions = rank_friends_of_friends(&own_user_id, friends_of_friends, &excluded);
+        suggestions.truncate(limit);
+        Ok(suggestions)
+    }
+
+    /// Get the friends the current user shares with another user, sorted by user ID.
+    ///
+    /// The other user's friends are the members of their friends-only feed if the
+    /// current user has joined it, along with the owners of the feeds in their
+    /// friends space if it's shared with the current user.
+    /// Returns `None` if neither is visible.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in,
+    /// or the friends space or feed members cannot be loaded.
+    pub async fn mutual_friends(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<Vec<OwnedUserId>>, UserDiscoveryError> {
+        let own_user_id = self
+            .client
+            .user_id()
+            .ok_or(UserDiscoveryError::NotLoggedIn)?
+            .to_owned();
+
+        let feeds = FeedRoomService::new(self.client.clone());
+        let their_friends = match feeds.find_joined_feed(user_id, FeedPrivacy::Friends) {
+            Some(feed) => self.friends_of(user_id, &feed).await?,
+            None => {
+                let Some(space) = self.find_visible_friends_space(user_id) else {
+                    return Ok(None);
+                };
+                let children = friends_space_children(&space)
+                    .await
+                    .map_err(UserDiscoveryError::MatrixError)?;
+                self.owners_of_known_feeds(children).into_iter().collect()
+            }
+        };
+        let own_friends = self
+            .own_friend_feeds()
+            .await?
+            .into_iter()
+            .map(|(friend, _)| friend)
+            .collect();
+        Ok(Some(shared_friends(
+            &own_user_id,
+            user_id,
+            &own_friends,
+            &their_friends,
+        )))
+    }
+
+    /// Get the current user's friends along with their friends-only feeds.
+    ///
+    /// Friends whose feeds haven't been joined yet are left out,
+    /// since their feeds can't be inspected.
+    pub async fn own_friend_feeds(&self) -> Result<Vec<(OwnedUserId, Room)>, UserDiscoveryError> {
+        let mut friends_space = FriendsSpaceService::new(self.client.clone());
+        if friends_space.find_existing_friends_space().await?.is_none() {
+            return Ok(Vec::new());
+        }
+
+        Ok(friends_space
+            .get_friends()
+            .await?
+            .into_iter()
+            .filter_map(|feed_room_id| {
+                let room = self.client.get_room(&feed_room_id)?;
+                Some((feed_owner(&room)?, room))
+            })
+            .collect())
+    }
+
+    /// Get a friend's own friends, given their friends-only feed.
+    async fn friends_of(
+        &self,
+        friend: &UserId,
+        feed: &Room,
+    ) -> Result<BTreeSet<OwnedUserId>, UserDiscoveryError> {
+        let members = feed
+            .members(RoomMemberships::JOIN)
+            .await
+            .map_err(UserDiscoveryError::MatrixError)?;
+        let mut their_friends: BTreeSet<_> = members
+            .iter()
+            .map(|member| member.user_id().to_owned())
+            .collect();
+
+        if let Some(space) = self.find_visible_friends_space(friend) {
+            // The friend's space can only be read if it's shared with us;
+            // otherwise their feed members are all we know about
+            match friends_space_children(&space).await {
+                Ok(children) => their_friends.extend(self.owners_of_known_feeds(children)),
+                Err(e) => warning!("Failed to read the friends space of {friend}: {e}"),
+            }
+        }
+        Ok(their_friends)
+    }
+
+    /// Find a friend's friends space among the rooms the current user has joined.
+    fn find_visible_friends_space(&self, friend: &UserId) -> Option<Room> {
+        let name = friends_space_name(friend);
+        self.client
+            .joined_rooms()
+            .into_iter()
+            .find(|room| room.is_space() && room.name().as_deref() == Some(&name))
+    }
+
+    /// Map feed rooms to their owners, skipping rooms the current user doesn't know.
+    fn owners_of_known_feeds(&self, feeds: Vec<OwnedRoomId>) -> Vec<OwnedUserId> {
+        feeds
+            .into_iter()
+            .filter_map(|room_id| feed_owner(&self.client.get_room(&room_id)?))
+            .collect()
+    }
+}
+
+/// Get the owner of a feed room.
+fn feed_owner(room: &Room) -> Option<OwnedUserId> {
+    feed_of_room(room).map(|(_, owner)| owner)
+}
+
+/// Get the friends `own_user_id` shares with `user_id`, sorted by user ID,
+/// given each of their friends.
+///
+/// Neither of the two users is counted as a friend they share.
+pub fn shared_friends(
+    own_user_id: &UserId,
+    user_id: &UserId,
+    own_friends: &BTreeSet<OwnedUserId>,
+    their_friends: &BTreeSet<OwnedUserId>,
+) -> Vec<OwnedUserId> {
+    own_friends
+        .intersection(their_friends)
+        .filter(|friend| *friend != own_user_id && *friend != user_id)
+        .cloned()
+        .collect()
+}
+
+/// Rank friends of friends by how many friends they share with `own_user_id`.
+///
+/// `friends` holds each friend along with their own friends. Users in
+/// `excluded`, e.g., blocked users, are never suggested. Ties are
+/// broken by user ID so that suggestions are shown in a stable order.
+pub fn rank_friends_of_friends(
+    own_user_id: &UserId,
+    friends: Vec<(OwnedUserId, Vec<OwnedUserId>)>,
+    excluded: &BTreeSet<OwnedUserId>,
+) -> Vec<SuggestedUser> {
+    let own_friends: BTreeSet<_> = friends.iter().map(|(friend, _)| friend.clone()).collect();
+    let mut mutual_friends: BTreeMap<OwnedUserId, BTreeSet<OwnedUserId>> = BTreeMap::new();
+    for (friend, their_friends) in friends {
+        for user_id in their_friends {
+            if user_id != own_user_id
+                && user_id != friend
+                && !own_friends.contains(&user_id)
+                && !excluded.contains(&user_id)
+            {
+                mutual_friends
+                    .entry(user_id)
+                    .or_default()
+                    .insert(friend.clone());
+            }
+        }
+    }
+
+    let mut suggestions: Vec<_> = mutual_friends
+        .into_iter()
+        .map(|(user_id, mutual_friends)| SuggestedUser {
+            user_id,
+            mutual_friends: mutual_friends.into_iter().collect(),
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.mutual_friends
+            .len()
+            .cmp(&a.mutual_friends.len())
+            .then_with(|| a.user_id.cmp(&b.user_id))
+    });
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{owned_user_id, user_id};
+
+    #[test]
+    fn test_friends_of_friends_are_ranked_by_mutual_friends() {
+        let me = user_id!("@me:example.org");
+        let bob = owned_user_id!("@bob:example.org");
+        let carol = owned_user_id!("@carol:example.org");
+        let dave = owned_user_id!("@dave:example.org");
+        let erin = owned_user_id!("@erin:example.org");
+
+        let suggestions = rank_friends_of_friends(
+            me,
+            vec![
+                (
+                    bob.clone(),
+                    vec![bob.clone(), me.to_owned(), carol.clone(), dave.clone()],
+                ),
+                (
+                    carol.clone(),
+                    vec![carol.clone(), bob.clone(), erin.clone(), dave.clone()],
+                ),
+            ],
+            &BTreeSet::new(),
+        );
+        assert_eq!(
+            suggestions,
+            vec![
+                SuggestedUser {
+                    user_id: dave,
+                    mutual_friends: vec![bob, carol.clone()],
+                },
+                SuggestedUser {
+                    user_id: erin,
+                    mutual_friends: vec![carol],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_excluded_users_are_not_suggested() {
+        let me = user_id!("@me:example.org");
+        let bob = owned_user_id!("@bob:example.org");
+        let mallory = owned_user_id!("@mallory:example.org");
+        let erin = owned_user_id!("@erin:example.org");
+
+        let suggestions = rank_friends_of_friends(
+            me,
+            vec![(bob.clone(), vec![mallory.clone(), erin.clone()])],
+            &BTreeSet::from([mallory]),
+        );
+        assert_eq!(
+            suggestions,
+            vec![SuggestedUser {
+                user_id: erin,
+                mutual_friends: vec![bob],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_shared_friends() {
+        let me = user_id!("@me:example.org");
+        let bob = user_id!("@bob:example.org");
+        let carol = owned_user_id!("@carol:example.org");
+        let dave = owned_user_id!("@dave:example.org");
+        let erin = owned_user_id!("@erin:example.org");
+
+        let own_friends = BTreeSet::from([bob.to_owned(), carol.clone(), dave.clone()]);
+        // A friends-only feed's members include its owner and the current user
+        let their_friends = BTreeSet::from([
+            bob.to_owned(),
+            me.to_owned(),
+            dave.clone(),
+            carol.clone(),
+            erin,
+        ]);
+        assert_eq!(
+            shared_friends(me, bob, &own_friends, &their_friends),
+            vec![carol, dave]
+        );
+        assert!(shared_friends(me, bob, &BTreeSet::new(), &their_friends).is_empty());
+    }
+
+    #[test]
+    fn test_no_friends_means_no_suggestions() {
+        assert!(
+            rank_friends_of_friends(user_id!("@me:example.org"), Vec::new(), &BTreeSet::new())
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! Trending posts and topics across followed public feeds.
+//!
+//! Recent posts in the public feeds the user has joined are ranked by
+//! engagement velocity: reactions and comments per hour since posting, so
+//! that a new post picking up engagement quickly outranks an older one that
+//! slowly gathered more. Hashtags used in those posts are ranked as topics.
+
+use makepad_widgets::DefaultNone;
+use matrix_sdk::{
+    room::{MessagesOptions, Room},
+    ruma::{
+        events::{
+            room::message::Relation, AnySyncMessageLikeEvent, AnySyncTimelineEvent,
+            SyncMessageLikeEvent,
+        },
+        MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId,
+    },
+    Client,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::social::{
+    events::timing::now_ms,
+    feed_room::{FeedRoomError, FeedRoomService},
+};
+
+/// How far back to look for trending posts.
+pub const TRENDING_WINDOW_MS: u64 = 48 * 60 * 60 * 1000;
+
+const HOUR_MS: f64 = 60.0 * 60.0 * 1000.0;
+
+/// The most trending posts shown at once.
+pub const MAX_TRENDING_POSTS: usize = 20;
+
+/// The most trending hashtags shown at once.
+pub const MAX_TRENDING_TOPICS: usize = 5;
+
+/// Actions emitted about what's trending.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum TrendingAction {
+    /// Trending posts and topics were loaded, see [`TrendingService::get_trending()`].
+    Loaded(Trending),
+    /// No action.
+    None,
+}
+
+/// A recent post ranked by how quickly it is gathering engagement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrendingPost {
+    /// The feed room the post is in.
+    pub room_id: OwnedRoomId,
+    /// Event ID of the post.
+    pub event_id: OwnedEventId,
+    /// Author of the post.
+    pub sender: OwnedUserId,
+    /// When the post was sent.
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+    /// Text of the post.
+    pub text: String,
+    /// Number of reactions to the post.
+    pub reactions: u32,
+    /// Number of comments on the post.
+    pub comments: u32,
+    /// Reactions and comments per hour since the post was sent.
+    pub velocity: f64,
+}
+
+impl TrendingPost {
+    /// Get the total engagement with this post.
+    pub fn engagement(&self) -> u32 {
+        self.reactions + self.comments
+    }
+
+    /// Get the hashtags used in this post.
+    pub fn hashtags(&self) -> BTreeSet<String> {
+        extract_hashtags(&self.text)
+    }
+}
+
+/// A hashtag used in trending posts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrendingTopic {
+    /// The hashtag, lowercased and without the leading `#`.
+    pub hashtag: String,
+    /// Number of recent posts using the hashtag.
+    pub post_count: u32,
+    /// Total engagement with those posts.
+    pub engagement: u32,
+}
+
+/// Trending posts and topics, best first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trending {
+    /// Trending posts.
+    pub posts: Vec<TrendingPost>,
+    /// Trending hashtags.
+    pub topics: Vec<TrendingTopic>,
+}
+
+/// Service for finding trending posts and topics.
+pub struct TrendingService {
+    client: Client,
+}
+
+impl TrendingService {
+    /// Create a new TrendingService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Get up to `post_limit` trending posts and `topic_limit` trending topics
+    /// from the last [`TRENDING_WINDOW_MS`] in the public feeds the user has joined.
+    ///
+    /// Topics are taken from all recent posts, not only the returned ones.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or messages cannot be loaded.
+    pub async fn get_trending(
+        &self,
+        post_limit: usize,
+        topic_limit: usize,
+    ) -> Result<Trending, TrendingError> {
+        let now = now_ms();
+        let since_ms = now.saturating_sub(TRENDING_WINDOW_MS);
+
+        let mut posts = Vec::new();
+        for (_, room) in FeedRoomService::new(self.client.clone()).joined_public_feeds()? {
+            posts.extend(load_recent_posts(&room, since_ms, now).await?);
+        }
+
+        let topics = rank_trending_topics(&posts, topic_limit);
+        let mut posts = rank_trending_posts(posts);
+        posts.truncate(post_limit);
+        Ok(Trending { posts, topics })
+    }
+}
+
+/// Get the engagement per hour of a post sent at `posted_at_ms`.
+///
+/// Posts younger than an hour are treated as an hour old,
+/// so that a single early reaction doesn't make a post trend.
+pub fn engagement_velocity(engagement: u32, posted_at_ms: u64, now_ms: u64) -> f64 {
+    let age_hours = now_ms.saturating_sub(posted_at_ms) as f64 / HOUR_MS;
+    engagement as f64 / age_hours.max(1.0)
+}
+
+/// Sort posts by engagement velocity, dropping those without any engagement.
+///
+/// Ties are broken by recency.
+pub fn rank_trending_posts(mut posts: Vec<TrendingPost>) -> Vec<TrendingPost> {
+    posts.retain(|post| post.engagement() > 0);
+    posts.sort_by(|a, b| {
+        b.velocity
+            .total_cmp(&a.velocity)
+            .then_with(|| b.origin_server_ts.cmp(&a.origin_server_ts))
+    });
+    posts
+}
+
+/// Rank the hashtags used in `posts` by how many posts use them,
+/// then by the engagement with those posts, returning up to `limit` topics.
+pub fn rank_trending_topics(posts: &[TrendingPost], limit: usize) -> Vec<TrendingTopic> {
+    let mut topics: BTreeMap<String, TrendingTopic> = BTreeMap::new();
+    for post in posts {
+        for hashtag in post.hashtags() {
+            let topic = topics
+                .entry(hashtag.clone())
+                .or_insert_with(|| TrendingTopic {
+                    hashtag,
+                    post_count: 0,
+                    engagement: 0,
+                });
+            topic.post_count += 1;
+            topic.engagement += post.engagement();
+        }
+    }
+
+    // Topics are already sorted by hashtag, which a stable sort keeps for ties
+    let mut topics: Vec<_> = topics.into_values().collect();
+    topics.sort_by(|a, b| {
+        b.post_count
+            .cmp(&a.post_count)
+            .then_with(|| b.engagement.cmp(&a.engagement))
+    });
+    topics.truncate(limit);
+    topics
+}
+
+/// Extract the hashtags from a post's text, lowercased and without the leading `#`.
+///
+/// A hashtag is a `#` at the start of a word followed by letters, digits or
+/// underscores, at least one of which is not a digit (so "#1" isn't a hashtag).
+pub fn extract_hashtags(text: &str) -> BTreeSet<String> {
+    text.split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .map(|tag| {
+            tag.chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|tag| tag.chars().any(|c| !c.is_ascii_digit()))
+        .collect()
+}
+
+/// Load the posts in a feed room sent since `since_ms`, with their engagement.
+async fn load_recent_posts(
+    room: &Room,
+    since_ms: u64,
+    now_ms: u64,
+) -> Result<Vec<TrendingPost>, TrendingError> {
+    let mut posts = Vec::new();
+    // Reactions and comments per post, as (reactions, comments)
+    let mut engagement: HashMap<OwnedEventId, (u32, u32)> = HashMap::new();
+    let mut from = None;
+    'paginate: loop {
+        let mut options = MessagesOptions::backward();
+        options.from = from.take();
+        let response = room
+            .messages(options)
+            .await
+            .map_err(TrendingError::MatrixError)?;
+
+        for event in &response.chunk {
+            let Ok(AnySyncTimelineEvent::MessageLike(event)) = event.raw().deserialize() else {
+                continue;
+            };
+            let timestamp: u64 = event.origin_server_ts().get().into();
+            if timestamp < since_ms {
+                break 'paginate;
+            }
+
+            match event {
+                AnySyncMessageLikeEvent::Reaction(SyncMessageLikeEvent::Original(reaction)) => {
+                    engagement
+                        .entry(reaction.content.relates_to.event_id)
+                        .or_default()
+                        .0 += 1;
+                }
+                AnySyncMessageLikeEvent::RoomMessage(SyncMessageLikeEvent::Original(message)) => {
+                    match message.content.relates_to {
+                        None => posts.push(TrendingPost {
+                            room_id: room.room_id().to_owned(),
+                            event_id: message.event_id,
+                            sender: message.sender,
+                            origin_server_ts: message.origin_server_ts,
+                            text: message.content.body().to_owned(),
+                            reactions: 0,
+                            comments: 0,
+                            velocity: 0.0,
+                        }),
+                        Some(Relation::Reply { in_reply_to }) => {
+                            engagement.entry(in_reply_to.event_id).or_default().1 += 1;
+                        }
+                        Some(Relation::Thread(thread)) => {
+                            engagement.entry(thread.event_id).or_default().1 += 1;
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        match response.end {
+            Some(end) if !response.chunk.is_empty() => from = Some(end),
+            _ => break,
+        }
+    }
+
+    for post in &mut posts {
+        let (reactions, comments) = engagement.get(&post.event_id).copied().unwrap_or_default();
+        post.reactions = reactions;
+        post.comments = comments;
+        post.velocity = engagement_velocity(
+            post.engagement(),
+            post.origin_server_ts.get().into(),
+            now_ms,
+        );
+    }
+    Ok(posts)
+}
+
+/// Errors that can occur when finding trending posts.
+#[derive(Debug, thiserror::Error)]
+pub enum TrendingError {
+    /// An error occurred while finding the joined public feeds.
+    #[error("Feed error: {0}")]
+    Feed(#[from] FeedRoomError),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{owned_event_id, owned_room_id, owned_user_id, UInt};
+
+    const NOW: u64 = 1_700_000_000_000;
+    const HOUR: u64 = 60 * 60 * 1000;
+
+    fn post(id: &str, text: &str, reactions: u32, hours_ago: u64) -> TrendingPost {
+        let posted_at = NOW - hours_ago * HOUR;
+        TrendingPost {
+            room_id: owned_room_id!("!feed:example.org"),
+            event_id: format!("${id}:example.org").try_into().unwrap(),
+            sender: owned_user_id!("@bob:example.org"),
+            origin_server_ts: MilliSecondsSinceUnixEpoch(UInt::new(posted_at).unwrap()),
+            text: text.to_string(),
+            reactions,
+            comments: 0,
+            velocity: engagement_velocity(reactions, posted_at, NOW),
+        }
+    }
+
+    #[test]
+    fn test_extract_hashtags() {
+        assert_eq!(
+            extract_hashtags("Sunset at the #Beach! #beach #photo_of_the_day #1 mid#word"),
+            BTreeSet::from(["beach".to_string(), "photo_of_the_day".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_posts_are_ranked_by_velocity() {
+        let ranked = rank_trending_posts(vec![
+            post("old", "Slow burner", 20, 40),
+            post("new", "Quick hit", 6, 2),
+            post("quiet", "Nobody cares", 0, 1),
+        ]);
+        let ids: Vec<_> = ranked.iter().map(|p| p.event_id.clone()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                owned_event_id!("$new:example.org"),
+                owned_event_id!("$old:example.org")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_young_posts_are_treated_as_an_hour_old() {
+        assert_eq!(engagement_velocity(3, NOW - 60_000, NOW), 3.0);
+        assert_eq!(engagement_velocity(3, NOW - 3 * HOUR, NOW), 1.0);
+    }
+
+    #[test]
+    fn test_topics_are_ranked_by_post_count_then_engagement() {
+        let posts = [
+            post("a", "#rust is great", 1, 1),
+            post("b", "More #rust and #matrix", 5, 1),
+            post("c", "#matrix rocks", 10, 1),
+            post("d", "#cats", 50, 1),
+        ];
+        let topics = rank_trending_topics(&posts, 2);
+        assert_eq!(
+            topics,
+            vec![
+                TrendingTopic {
+                    hashtag: "matrix".to_string(),
+                    post_count: 2,
+                    engagement: 15,
+                },
+                TrendingTopic {
+                    hashtag: "rust".to_string(),
+                    post_count: 2,
+                    engagement: 6,
+                },
+            ]
+        );
+    }
+}
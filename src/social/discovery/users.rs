@@ -0,0 +1,155 @@
+//! Discovery of other users and their social profiles.
+//!
+//! People can be found by searching the homeserver's user directory.
+//! Suggestions of people the user may know are made by
+//! [`FriendSuggestionEngine`](super::FriendSuggestionEngine).
+
+use futures_util::future::join_all;
+use makepad_widgets::{warning, DefaultNone};
+use matrix_sdk::{
+    ruma::{api::client::error::ErrorKind, OwnedMxcUri, OwnedRoomId, OwnedUserId, UserId},
+    Client,
+};
+
+use crate::social::{
+    feed_room::FeedPrivacy, follower_moderation::FollowerModerationError, friends::FriendsError,
+    profile_room::ProfileRoomService,
+};
+
+/// The most results of a search of the user directory shown at once.
+pub const MAX_USER_SEARCH_RESULTS: u64 = 20;
+
+/// Actions emitted about searches of the user directory.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum UserSearchAction {
+    /// The users matching `query` were found,
+    /// see [`UserDiscoveryService::search_users()`].
+    Loaded {
+        query: String,
+        results: Vec<DiscoveredUser>,
+    },
+    /// No action.
+    None,
+}
+
+/// A user found by searching the user directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiscoveredUser {
+    /// The user's ID.
+    pub user_id: OwnedUserId,
+    /// The user's display name.
+    pub display_name: Option<String>,
+    /// The user's avatar.
+    pub avatar_url: Option<OwnedMxcUri>,
+    /// The user's profile room, if they have one.
+    pub profile_room: Option<OwnedRoomId>,
+    /// The user's public feed, if they have one.
+    pub public_feed: Option<OwnedRoomId>,
+}
+
+impl DiscoveredUser {
+    /// Whether the user has set up social features, i.e., has a profile room or public feed.
+    pub fn has_social_profile(&self) -> bool {
+        self.profile_room.is_some() || self.public_feed.is_some()
+    }
+}
+
+/// Service for finding other users to connect with.
+pub struct UserDiscoveryService {
+    client: Client,
+}
+
+impl UserDiscoveryService {
+    /// Create a new UserDiscoveryService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Search the homeserver's user directory.
+    ///
+    /// Each result is resolved to its profile room and public feed,
+    /// and results with a social profile are listed first.
+    ///
+    /// # Errors
+    /// Returns an error if the user directory cannot be searched.
+    pub async fn search_users(
+        &self,
+        search_term: &str,
+        limit: u64,
+    ) -> Result<Vec<DiscoveredUser>, UserDiscoveryError> {
+        let response = self
+            .client
+            .search_users(search_term, limit)
+            .await
+            .map_err(|e| UserDiscoveryError::MatrixError(e.into()))?;
+
+        let own_user_id = self.client.user_id();
+        let mut users: Vec<_> = join_all(
+            response
+                .results
+                .into_iter()
+                .filter(|user| Some(&*user.user_id) != own_user_id)
+                .map(|user| async move {
+                    let (profile_room, public_feed) =
+                        self.resolve_social_rooms(&user.user_id).await;
+                    DiscoveredUser {
+                        user_id: user.user_id,
+                        display_name: user.display_name,
+                        avatar_url: user.avatar_url,
+                        profile_room,
+                        public_feed,
+                    }
+                }),
+        )
+        .await;
+
+        // A stable sort keeps the directory's ranking otherwise
+        users.sort_by_key(|user| !user.has_social_profile());
+        Ok(users)
+    }
+
+    /// Find a user's profile room and public feed.
+    ///
+    /// Rooms that cannot be resolved, e.g., due to a network error, are treated as missing.
+    pub async fn resolve_social_rooms(
+        &self,
+        user_id: &UserId,
+    ) -> (Option<OwnedRoomId>, Option<OwnedRoomId>) {
+        let profile_room = ProfileRoomService::new(self.client.clone())
+            .find_profile_room(user_id)
+            .await
+            .unwrap_or_default();
+        let public_feed = match FeedPrivacy::Public.room_alias(user_id) {
+            Some(alias) => match self.client.resolve_room_alias(&alias).await {
+                Ok(response) => Some(response.room_id),
+                Err(e) if e.client_api_error_kind() == Some(&ErrorKind::NotFound) => None,
+                Err(e) => {
+                    warning!("Failed to resolve public feed alias {alias}: {e}");
+                    None
+                }
+            },
+            None => None,
+        };
+        (profile_room, public_feed)
+    }
+}
+
+/// Errors that can occur when discovering users.
+#[derive(Debug, thiserror::Error)]
+pub enum UserDiscoveryError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// An error occurred while reading the friends space.
+    #[error("Friends error: {0}")]
+    Friends(#[from] FriendsError),
+
+    /// An error occurred while listing users banned from the current user's feeds.
+    #[error("Moderation error: {0}")]
+    Moderation(#[from] FollowerModerationError),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
@@ -0,0 +1,350 @@
+//! Detection of photos that the user has already posted.
+//!
+//! Each photo the user posts is reduced to a 64-bit perceptual hash
+//! ("pHash"), which stays the same when an image is re-encoded, resized,
+//! or slightly brightened. The hashes of recent uploads are kept in a small
+//! local index, so the composer can warn before the same photo is uploaded
+//! again and offer to link to the earlier post instead.
+
+use matrix_sdk::ruma::{MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, UserId};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::path::Path;
+
+use crate::persistence::persistent_state_dir;
+use crate::shared::image_viewer::get_png_or_jpg_image_buffer;
+
+/// The name of the file, in the user's persistent state directory, holding the index.
+pub(crate) const UPLOAD_HASH_INDEX_FILE_NAME: &str = "social_upload_hashes.json";
+/// The width and height of the grayscale thumbnail that images are hashed from.
+const THUMBNAIL_SIZE: usize = 32;
+/// The width and height of the block of low frequencies that make up the hash.
+const HASH_SIZE: usize = 8;
+
+/// Hashes that differ in at most this many bits are considered the same image.
+pub const MAX_DUPLICATE_DISTANCE: u32 = 6;
+/// How long an upload is remembered for, in milliseconds.
+pub const RECENT_UPLOAD_PERIOD_MS: u64 = 90 * 24 * 60 * 60 * 1000;
+/// The maximum number of uploads kept in the index.
+pub const MAX_INDEXED_UPLOADS: usize = 1000;
+
+/// A perceptual hash of an image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ImageHash(pub u64);
+
+impl ImageHash {
+    /// Hash an image from its row-major ARGB pixels.
+    ///
+    /// Returns `None` if the image is empty.
+    pub fn from_pixels(pixels: &[u32], width: usize, height: usize) -> Option<Self> {
+        if width == 0 || height == 0 || pixels.len() < width * height {
+            return None;
+        }
+        let thumbnail = grayscale_thumbnail(pixels, width, height);
+
+        // Keep the lowest frequencies of the thumbnail's discrete cosine transform
+        let cosines: Vec<Vec<f64>> = (0..HASH_SIZE)
+            .map(|u| {
+                (0..THUMBNAIL_SIZE)
+                    .map(|x| {
+                        ((2 * x + 1) as f64 * u as f64 * PI / (2 * THUMBNAIL_SIZE) as f64).cos()
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut coefficients = Vec::with_capacity(HASH_SIZE * HASH_SIZE);
+        for v in 0..HASH_SIZE {
+            for u in 0..HASH_SIZE {
+                let mut sum = 0.0;
+                for y in 0..THUMBNAIL_SIZE {
+                    for x in 0..THUMBNAIL_SIZE {
+                        sum += thumbnail[y * THUMBNAIL_SIZE + x] * cosines[u][x] * cosines[v][y];
+                    }
+                }
+                coefficients.push(sum);
+            }
+        }
+
+        // Each bit says whether a frequency is above the median, ignoring the average brightness
+        let mut without_average = coefficients[1..].to_vec();
+        without_average.sort_by(f64::total_cmp);
+        let median = without_average[without_average.len() / 2];
+        let hash = coefficients
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c > median)
+            .fold(0u64, |hash, (i, _)| hash | (1 << i));
+        Some(Self(hash))
+    }
+
+    /// Hash a PNG or JPEG image.
+    ///
+    /// This decodes the whole image, so it should not be called on the UI thread.
+    ///
+    /// # Errors
+    /// Returns an error if the image is not a valid PNG or JPEG.
+    pub fn from_image_data(data: Vec<u8>) -> Result<Self, DuplicateImageError> {
+        let buffer =
+            get_png_or_jpg_image_buffer(data).map_err(|_| DuplicateImageError::InvalidImage)?;
+        Self::from_pixels(&buffer.data, buffer.width, buffer.height)
+            .ok_or(DuplicateImageError::InvalidImage)
+    }
+
+    /// Get the number of bits in which two hashes differ.
+    pub fn distance(self, other: Self) -> u32 {
+        (self.0 ^ other.0).count_ones()
+    }
+
+    /// Whether two hashes are close enough to be of the same image.
+    pub fn is_duplicate_of(self, other: Self) -> bool {
+        self.distance(other) <= MAX_DUPLICATE_DISTANCE
+    }
+}
+
+/// Average the brightness of an image over a square grid of cells.
+fn grayscale_thumbnail(pixels: &[u32], width: usize, height: usize) -> Vec<f64> {
+    let mut thumbnail = Vec::with_capacity(THUMBNAIL_SIZE * THUMBNAIL_SIZE);
+    for cell_y in 0..THUMBNAIL_SIZE {
+        let y_start = cell_y * height / THUMBNAIL_SIZE;
+        let y_end = ((cell_y + 1) * height / THUMBNAIL_SIZE).max(y_start + 1);
+        for cell_x in 0..THUMBNAIL_SIZE {
+            let x_start = cell_x * width / THUMBNAIL_SIZE;
+            let x_end = ((cell_x + 1) * width / THUMBNAIL_SIZE).max(x_start + 1);
+            let mut sum = 0.0;
+            for y in y_start..y_end {
+                for pixel in &pixels[y * width + x_start..y * width + x_end] {
+                    let [_, r, g, b] = pixel.to_be_bytes();
+                    sum += 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+                }
+            }
+            thumbnail.push(sum / ((y_end - y_start) * (x_end - x_start)) as f64);
+        }
+    }
+    thumbnail
+}
+
+/// A photo the user posted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadedImage {
+    /// The perceptual hash of the photo.
+    pub hash: ImageHash,
+    /// The feed room the photo was posted to.
+    pub room_id: OwnedRoomId,
+    /// The post containing the photo.
+    pub event_id: OwnedEventId,
+    /// When the photo was posted.
+    pub uploaded_at: MilliSecondsSinceUnixEpoch,
+}
+
+/// A local index of the hashes of photos the user recently posted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadHashIndex {
+    /// Indexed uploads, oldest first.
+    uploads: Vec<UploadedImage>,
+}
+
+impl UploadHashIndex {
+    /// Load the index of the given user, or an empty index if none was saved yet.
+    ///
+    /// # Errors
+    /// Returns an error if the index file cannot be read or parsed.
+    pub fn load(user_id: &UserId) -> Result<Self, DuplicateImageError> {
+        let path = persistent_state_dir(user_id).join(UPLOAD_HASH_INDEX_FILE_NAME);
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save the index of the given user.
+    ///
+    /// # Errors
+    /// Returns an error if the index file cannot be written.
+    pub fn save(&self, user_id: &UserId) -> Result<(), DuplicateImageError> {
+        let path = persistent_state_dir(user_id).join(UPLOAD_HASH_INDEX_FILE_NAME);
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Add a posted photo to the index, forgetting the oldest ones if it's full.
+    pub fn record(&mut self, upload: UploadedImage) {
+        self.uploads.push(upload);
+        self.uploads.sort_by_key(|upload| upload.uploaded_at);
+        let excess = self.uploads.len().saturating_sub(MAX_INDEXED_UPLOADS);
+        self.uploads.drain(..excess);
+    }
+
+    /// Find the most recent upload of the same photo within [`RECENT_UPLOAD_PERIOD_MS`] of `now`.
+    pub fn find_duplicate(
+        &self,
+        hash: ImageHash,
+        now: MilliSecondsSinceUnixEpoch,
+    ) -> Option<&UploadedImage> {
+        let now: u64 = now.get().into();
+        self.uploads.iter().rev().find(|upload| {
+            let uploaded_at: u64 = upload.uploaded_at.get().into();
+            now.saturating_sub(uploaded_at) <= RECENT_UPLOAD_PERIOD_MS
+                && upload.hash.is_duplicate_of(hash)
+        })
+    }
+}
+
+/// Check whether the photo at `path` was recently posted by the given user.
+///
+/// This decodes the whole image, so it should not be called on the UI thread.
+///
+/// # Errors
+/// Returns an error if the photo cannot be read or decoded,
+/// or the user's index cannot be loaded.
+pub fn find_duplicate_upload(
+    user_id: &UserId,
+    path: &Path,
+) -> Result<Option<UploadedImage>, DuplicateImageError> {
+    let hash = ImageHash::from_image_data(std::fs::read(path)?)?;
+    let index = UploadHashIndex::load(user_id)?;
+    Ok(index
+        .find_duplicate(hash, MilliSecondsSinceUnixEpoch::now())
+        .cloned())
+}
+
+/// Remember that the given user posted a photo, so it can be recognized later.
+///
+/// This is called by the outbox once a photo post has been sent, see
+/// [`OutboxMedia::uploaded_image()`](crate::social::outbox::OutboxMedia::uploaded_image).
+///
+/// # Errors
+/// Returns an error if the user's index cannot be loaded or saved.
+pub fn record_upload(user_id: &UserId, upload: UploadedImage) -> Result<(), DuplicateImageError> {
+    let mut index = UploadHashIndex::load(user_id)?;
+    index.record(upload);
+    index.save(user_id)
+}
+
+/// Errors that can occur when detecting duplicate photos.
+#[derive(Debug, thiserror::Error)]
+pub enum DuplicateImageError {
+    /// The image is not a valid PNG or JPEG.
+    #[error("Invalid or unsupported image")]
+    InvalidImage,
+
+    /// The image or the index could not be read or written.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The index could not be parsed or serialized.
+    #[error("Invalid upload index: {0}")]
+    InvalidIndex(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{owned_event_id, owned_room_id, UInt};
+
+    const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+    /// Render an image from a function of the relative position to an RGB color.
+    fn render(width: usize, height: usize, color: impl Fn(f64, f64) -> [f64; 3]) -> Vec<u32> {
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let [r, g, b] = color(x as f64 / width as f64, y as f64 / height as f64)
+                    .map(|c| c.clamp(0.0, 255.0) as u8);
+                pixels.push(u32::from_be_bytes([0xff, r, g, b]));
+            }
+        }
+        pixels
+    }
+
+    /// A bright disk in front of a horizontal gradient.
+    fn scene(x: f64, y: f64) -> [f64; 3] {
+        let v = if (x - 0.3).hypot(y - 0.4) < 0.2 {
+            200.0
+        } else {
+            40.0 + 120.0 * x
+        };
+        [v, v * 0.8, 255.0 - v]
+    }
+
+    /// A larger disk in front of a vertical gradient.
+    fn other_scene(x: f64, y: f64) -> [f64; 3] {
+        let v = if (x - 0.7).hypot(y - 0.7) < 0.25 {
+            220.0
+        } else {
+            200.0 - 150.0 * y
+        };
+        [v * 0.5, v, v * 0.3]
+    }
+
+    fn hash(width: usize, height: usize, color: impl Fn(f64, f64) -> [f64; 3]) -> ImageHash {
+        ImageHash::from_pixels(&render(width, height, color), width, height).unwrap()
+    }
+
+    fn at_day(day: u64) -> MilliSecondsSinceUnixEpoch {
+        MilliSecondsSinceUnixEpoch(UInt::new(day * DAY_MS).unwrap())
+    }
+
+    #[test]
+    fn test_same_photo_is_recognized() {
+        let original = hash(64, 48, scene);
+        assert_eq!(original, hash(64, 48, scene));
+        assert!(original.is_duplicate_of(hash(160, 120, scene)));
+        let brighter = hash(64, 48, |x, y| scene(x, y).map(|c| c + 20.0));
+        assert!(original.is_duplicate_of(brighter));
+    }
+
+    #[test]
+    fn test_different_photo_is_not_recognized() {
+        let original = hash(64, 48, scene);
+        assert!(!original.is_duplicate_of(hash(64, 48, other_scene)));
+        assert_eq!(ImageHash::from_pixels(&[], 0, 0), None);
+        // Images smaller than the thumbnail can still be hashed
+        assert!(ImageHash::from_pixels(&render(4, 3, scene), 4, 3).is_some());
+    }
+
+    #[test]
+    fn test_index_finds_recent_duplicates() {
+        let mut index = UploadHashIndex::default();
+        let photo = hash(64, 48, scene);
+        index.record(UploadedImage {
+            hash: photo,
+            room_id: owned_room_id!("!feed:example.org"),
+            event_id: owned_event_id!("$post:example.org"),
+            uploaded_at: at_day(10),
+        });
+
+        let duplicate = index
+            .find_duplicate(hash(160, 120, scene), at_day(20))
+            .unwrap();
+        assert_eq!(duplicate.event_id, owned_event_id!("$post:example.org"));
+        assert!(
+            index
+                .find_duplicate(hash(64, 48, other_scene), at_day(20))
+                .is_none()
+        );
+        assert!(index.find_duplicate(photo, at_day(200)).is_none());
+    }
+
+    #[test]
+    fn test_index_forgets_oldest_uploads() {
+        let mut index = UploadHashIndex::default();
+        for i in 0..=MAX_INDEXED_UPLOADS as u64 {
+            index.record(UploadedImage {
+                hash: ImageHash(i),
+                room_id: owned_room_id!("!feed:example.org"),
+                event_id: format!("$post{i}:example.org").try_into().unwrap(),
+                uploaded_at: at_day(i),
+            });
+        }
+        assert_eq!(index.uploads.len(), MAX_INDEXED_UPLOADS);
+        assert_eq!(index.uploads[0].hash, ImageHash(1));
+
+        let json = serde_json::to_string(&index).unwrap();
+        assert_eq!(
+            serde_json::from_str::<UploadHashIndex>(&json).unwrap(),
+            index
+        );
+    }
+}
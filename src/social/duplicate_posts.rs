@@ -0,0 +1,246 @@
+//! Detection of posts whose text the user has already posted.
+//!
+//! The text of each post the user sends is kept for a day in a small local
+//! index, so the composer can ask "You already posted this" before the same
+//! post is sent twice, e.g., after an accidental double submit or when it's
+//! pasted again. Texts match if they're the same after normalizing case,
+//! punctuation and whitespace, or differ in only a few characters.
+
+use matrix_sdk::ruma::{MilliSecondsSinceUnixEpoch, UserId};
+use serde::{Deserialize, Serialize};
+
+use crate::persistence::persistent_state_dir;
+
+/// The name of the file, in the user's persistent state directory, holding the index.
+pub(crate) const RECENT_POSTS_FILE_NAME: &str = "social_recent_posts.json";
+
+/// How long a post is remembered for, in milliseconds.
+pub const RECENT_POST_PERIOD_MS: u64 = 24 * 60 * 60 * 1000;
+/// The maximum number of posts kept in the index.
+pub const MAX_INDEXED_POSTS: usize = 200;
+/// Normalized texts at least this similar, from 0 to 1, are considered the same post.
+pub const NEAR_DUPLICATE_SIMILARITY: f64 = 0.9;
+
+/// A post the user sent.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentPost {
+    /// The text of the post, normalized with [`normalize_post_text()`].
+    pub text: String,
+    /// When the post was sent.
+    pub posted_at: MilliSecondsSinceUnixEpoch,
+}
+
+/// A local index of the texts of posts the user recently sent.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentPostIndex {
+    /// Indexed posts, oldest first.
+    posts: Vec<RecentPost>,
+}
+
+impl RecentPostIndex {
+    /// Load the index of the given user, or an empty index if none was saved yet.
+    ///
+    /// # Errors
+    /// Returns an error if the index file cannot be read or parsed.
+    pub fn load(user_id: &UserId) -> Result<Self, DuplicatePostError> {
+        let path = persistent_state_dir(user_id).join(RECENT_POSTS_FILE_NAME);
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save the index of the given user.
+    ///
+    /// # Errors
+    /// Returns an error if the index file cannot be written.
+    pub fn save(&self, user_id: &UserId) -> Result<(), DuplicatePostError> {
+        let path = persistent_state_dir(user_id).join(RECENT_POSTS_FILE_NAME);
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Add the text of a sent post to the index,
+    /// forgetting posts older than [`RECENT_POST_PERIOD_MS`] and the oldest ones if it's full.
+    ///
+    /// Posts without any text, e.g., a photo on its own, aren't indexed.
+    pub fn record(&mut self, text: &str, posted_at: MilliSecondsSinceUnixEpoch) {
+        let text = normalize_post_text(text);
+        if text.is_empty() {
+            return;
+        }
+        self.posts.push(RecentPost { text, posted_at });
+        self.posts.sort_by_key(|post| post.posted_at);
+
+        let now: u64 = posted_at.get().into();
+        self.posts.retain(|post| {
+            let posted_at: u64 = post.posted_at.get().into();
+            now.saturating_sub(posted_at) <= RECENT_POST_PERIOD_MS
+        });
+        let excess = self.posts.len().saturating_sub(MAX_INDEXED_POSTS);
+        self.posts.drain(..excess);
+    }
+
+    /// Find the most recent post with the same or nearly the same text
+    /// within [`RECENT_POST_PERIOD_MS`] of `now`.
+    pub fn find_duplicate(
+        &self,
+        text: &str,
+        now: MilliSecondsSinceUnixEpoch,
+    ) -> Option<&RecentPost> {
+        let text = normalize_post_text(text);
+        if text.is_empty() {
+            return None;
+        }
+        let now: u64 = now.get().into();
+        self.posts.iter().rev().find(|post| {
+            let posted_at: u64 = post.posted_at.get().into();
+            now.saturating_sub(posted_at) <= RECENT_POST_PERIOD_MS
+                && text_similarity(&post.text, &text) >= NEAR_DUPLICATE_SIMILARITY
+        })
+    }
+}
+
+/// Normalize the text of a post for comparing it with others,
+/// ignoring case, punctuation and differences in whitespace.
+pub fn normalize_post_text(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Get how similar two texts are, from 0 (nothing in common) to 1 (the same),
+/// from the number of characters that must be changed to turn one into the other.
+fn text_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    // Levenshtein distance, keeping a single row of the table
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    1.0 - row[b.len()] as f64 / longest as f64
+}
+
+/// Remember the text of a post the given user sent, so it can be recognized later.
+///
+/// This should be called after a post has been sent.
+///
+/// # Errors
+/// Returns an error if the user's index cannot be loaded or saved.
+pub fn record_post(user_id: &UserId, text: &str) -> Result<(), DuplicatePostError> {
+    let mut index = RecentPostIndex::load(user_id)?;
+    index.record(text, MilliSecondsSinceUnixEpoch::now());
+    index.save(user_id)
+}
+
+/// Errors that can occur when detecting duplicate posts.
+#[derive(Debug, thiserror::Error)]
+pub enum DuplicatePostError {
+    /// The index could not be read or written.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The index could not be parsed or serialized.
+    #[error("Invalid recent post index: {0}")]
+    InvalidIndex(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::UInt;
+
+    const HOUR_MS: u64 = 60 * 60 * 1000;
+
+    fn at_hour(hour: u64) -> MilliSecondsSinceUnixEpoch {
+        MilliSecondsSinceUnixEpoch(UInt::new(hour * HOUR_MS).unwrap())
+    }
+
+    #[test]
+    fn test_normalize_post_text() {
+        assert_eq!(
+            normalize_post_text("  Hello,   World!\nSee you\ttomorrow. "),
+            "hello world see you tomorrow"
+        );
+        assert_eq!(normalize_post_text("!!! ..."), "");
+    }
+
+    #[test]
+    fn test_index_finds_recent_duplicates() {
+        let mut index = RecentPostIndex::default();
+        index.record("Our bake sale starts at 10am on Saturday!", at_hour(100));
+
+        // Exact, normalized and near duplicates are recognized
+        let now = at_hour(110);
+        assert!(
+            index
+                .find_duplicate("Our bake sale starts at 10am on Saturday!", now)
+                .is_some()
+        );
+        assert!(
+            index
+                .find_duplicate("our bake sale starts at 10am on saturday", now)
+                .is_some()
+        );
+        assert!(
+            index
+                .find_duplicate("Our bake sale starts at 10am on Saturday!!", now)
+                .is_some()
+        );
+        assert!(
+            index
+                .find_duplicate("Our bake sale starts at 11am on Saturdays", now)
+                .is_some()
+        );
+
+        // Different posts and posts from more than a day ago aren't
+        assert!(
+            index
+                .find_duplicate("The bake sale was a success, thank you!", now)
+                .is_none()
+        );
+        assert!(index.find_duplicate("", now).is_none());
+        assert!(
+            index
+                .find_duplicate("Our bake sale starts at 10am on Saturday!", at_hour(125))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_index_forgets_old_posts() {
+        let mut index = RecentPostIndex::default();
+        index.record("First", at_hour(0));
+        index.record("", at_hour(1));
+        assert_eq!(index.posts.len(), 1);
+        index.record("Second", at_hour(30));
+        assert_eq!(index.posts.len(), 1);
+        assert_eq!(index.posts[0].text, "second");
+
+        for i in 0..=MAX_INDEXED_POSTS as u64 {
+            index.record(&format!("Post {i}"), at_hour(31));
+        }
+        assert_eq!(index.posts.len(), MAX_INDEXED_POSTS);
+
+        let json = serde_json::to_string(&index).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RecentPostIndex>(&json).unwrap(),
+            index
+        );
+    }
+}
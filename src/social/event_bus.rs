@@ -0,0 +1,222 @@
+//! An in-memory publish/subscribe bus for social updates.
+//!
+//! Services publish [`SocialEvent`]s to the [`SocialEventBus`], each under the
+//! [`SocialTopic`] it's about, and widgets subscribe to the topics they show.
+//! This way, several independent views of the same data stay up to date
+//! without the services knowing about them.
+//!
+//! The bus only keeps weak handles to subscriptions, so a widget unsubscribes
+//! by dropping its [`SocialSubscription`]. Subscribers are woken up with a UI
+//! signal and should drain their subscription when handling it, e.g.:
+//! ```rust,ignore
+//! if let Event::Signal = event {
+//!     for event in self.subscription.drain() {
+//!         // ...
+//!     }
+//! }
+//! ```
+
+use crossbeam_queue::SegQueue;
+use makepad_widgets::SignalToUI;
+use matrix_sdk::ruma::{OwnedEventId, OwnedRoomId, OwnedUserId};
+use robrix_social_events::profile::SocialProfileEventContent;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, LazyLock, Mutex, Weak},
+};
+
+use crate::social::mentions::CommentMention;
+use crate::social::newsfeed::FeedItem;
+use crate::social::post_watch::WatchedPostComment;
+
+/// The bus that services publish to with [`publish_social_event()`].
+static SOCIAL_EVENT_BUS: LazyLock<SocialEventBus> = LazyLock::new(SocialEventBus::default);
+
+/// What a [`SocialEvent`] is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SocialTopic {
+    /// The aggregated newsfeed.
+    FeedUpdates,
+    /// Friends and friend requests.
+    FriendGraph,
+    /// Things the user should be told about, e.g., comments on watched posts
+    /// or mentions in comments.
+    Notifications,
+    /// Users' social profiles.
+    ProfileChanges,
+}
+
+/// An update published by the social services.
+#[derive(Clone, Debug)]
+pub enum SocialEvent {
+    /// The feed was refreshed in the background, sorted and limited
+    /// (see [`FeedAggregator::get_cached_feed()`](crate::social::FeedAggregator::get_cached_feed)).
+    FeedRefreshed(Vec<FeedItem>),
+    /// The feed could not be refreshed, with the error message.
+    FeedRefreshFailed(String),
+    /// Someone voted in a poll post, or its author ended it.
+    PollUpdated {
+        /// The feed room the poll was posted in.
+        room_id: OwnedRoomId,
+        /// The poll's start event.
+        poll_id: OwnedEventId,
+    },
+    /// The user accepted a friend request from the given user.
+    FriendRequestAccepted(OwnedUserId),
+    /// The user declined a friend request from the given user.
+    FriendRequestDeclined(OwnedUserId),
+    /// A friend's feed room was added to the user's friends space.
+    FriendAdded(OwnedRoomId),
+    /// A friend's feed room was removed from the user's friends space.
+    FriendRemoved(OwnedRoomId),
+    /// Someone commented on a watched post.
+    NewComment(WatchedPostComment),
+    /// Someone mentioned the user in a comment on a post.
+    CommentMention(CommentMention),
+    /// A user's social profile was saved.
+    ProfileChanged {
+        /// The user whose profile changed.
+        user_id: OwnedUserId,
+        /// The new display name, if it was changed.
+        display_name: Option<String>,
+        /// The saved profile.
+        profile: SocialProfileEventContent,
+    },
+}
+
+impl SocialEvent {
+    /// Get the topic this event is published under.
+    pub fn topic(&self) -> SocialTopic {
+        match self {
+            Self::FeedRefreshed(_) | Self::FeedRefreshFailed(_) | Self::PollUpdated { .. } => {
+                SocialTopic::FeedUpdates
+            }
+            Self::FriendRequestAccepted(_)
+            | Self::FriendRequestDeclined(_)
+            | Self::FriendAdded(_)
+            | Self::FriendRemoved(_) => SocialTopic::FriendGraph,
+            Self::NewComment(_) | Self::CommentMention(_) => SocialTopic::Notifications,
+            Self::ProfileChanged { .. } => SocialTopic::ProfileChanges,
+        }
+    }
+}
+
+/// A subscription to some topics of a [`SocialEventBus`].
+///
+/// Events published under the subscribed topics are queued until drained.
+/// Dropping the subscription unsubscribes from all of its topics.
+#[derive(Debug)]
+pub struct SocialSubscription {
+    queue: Arc<SegQueue<SocialEvent>>,
+}
+
+impl SocialSubscription {
+    /// Take the events published since the last drain, oldest first.
+    pub fn drain(&self) -> Vec<SocialEvent> {
+        std::iter::from_fn(|| self.queue.pop()).collect()
+    }
+}
+
+/// Routes published events to the subscriptions of their topics.
+#[derive(Debug, Default)]
+pub struct SocialEventBus {
+    /// Weak handles to the queues of the subscriptions to each topic.
+    subscribers: Mutex<BTreeMap<SocialTopic, Vec<Weak<SegQueue<SocialEvent>>>>>,
+}
+
+impl SocialEventBus {
+    /// Subscribe to the given topics.
+    pub fn subscribe(&self, topics: &[SocialTopic]) -> SocialSubscription {
+        let queue = Arc::new(SegQueue::new());
+        let mut subscribers = self.subscribers.lock().unwrap();
+        for topic in topics {
+            subscribers
+                .entry(*topic)
+                .or_default()
+                .push(Arc::downgrade(&queue));
+        }
+        SocialSubscription { queue }
+    }
+
+    /// Queue the event for every live subscription to its topic,
+    /// forgetting the subscriptions that were dropped.
+    ///
+    /// Returns the number of subscriptions the event was queued for.
+    pub fn publish(&self, event: SocialEvent) -> usize {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let Some(queues) = subscribers.get_mut(&event.topic()) else {
+            return 0;
+        };
+        queues.retain(|queue| queue.strong_count() > 0);
+        for queue in queues.iter().filter_map(Weak::upgrade) {
+            queue.push(event.clone());
+        }
+        queues.len()
+    }
+
+    /// Get the number of live subscriptions to the given topic.
+    pub fn subscriber_count(&self, topic: SocialTopic) -> usize {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .get(&topic)
+            .map_or(0, |queues| {
+                queues.iter().filter(|q| q.strong_count() > 0).count()
+            })
+    }
+}
+
+/// Publish an event to the app's social event bus and wake up the UI to handle it.
+///
+/// Can be called from any thread.
+pub fn publish_social_event(event: SocialEvent) {
+    if SOCIAL_EVENT_BUS.publish(event) > 0 {
+        SignalToUI::set_ui_signal();
+    }
+}
+
+/// Subscribe to the given topics of the app's social event bus.
+pub fn subscribe_social_events(topics: &[SocialTopic]) -> SocialSubscription {
+    SOCIAL_EVENT_BUS.subscribe(topics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{owned_room_id, owned_user_id};
+
+    #[test]
+    fn test_publish_to_topic_subscribers() {
+        let bus = SocialEventBus::default();
+        let feed_view = bus.subscribe(&[SocialTopic::FeedUpdates]);
+        let friend_list = bus.subscribe(&[SocialTopic::FriendGraph]);
+        let other_friend_list = bus.subscribe(&[SocialTopic::FriendGraph]);
+
+        let delivered = bus.publish(SocialEvent::FriendAdded(owned_room_id!(
+            "!feed:example.org"
+        )));
+        assert_eq!(delivered, 2);
+        assert!(feed_view.drain().is_empty());
+        assert!(matches!(
+            friend_list.drain().as_slice(),
+            [SocialEvent::FriendAdded(_)]
+        ));
+        assert_eq!(other_friend_list.drain().len(), 1);
+        // Draining empties the queue
+        assert!(friend_list.drain().is_empty());
+    }
+
+    #[test]
+    fn test_dropped_subscriptions_are_forgotten() {
+        let bus = SocialEventBus::default();
+        let subscription = bus.subscribe(&[SocialTopic::FriendGraph, SocialTopic::Notifications]);
+        assert_eq!(bus.subscriber_count(SocialTopic::FriendGraph), 1);
+
+        drop(subscription);
+        assert_eq!(bus.subscriber_count(SocialTopic::FriendGraph), 0);
+        let delivered = bus.publish(SocialEvent::FriendRequestAccepted(owned_user_id!(
+            "@friend:example.org"
+        )));
+        assert_eq!(delivered, 0);
+    }
+}
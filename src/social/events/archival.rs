@@ -0,0 +1,111 @@
+//! What happens to event rooms after their event ends.
+//!
+//! [`ARCHIVAL_DELAY_DAYS`] days after an event ends, its creator is asked what
+//! to do with the event room:
+//! * keep it open, e.g., for guests to keep sharing photos
+//!   (see [`EventRoomService::keep_event_open()`]),
+//! * archive it, making it read-only for guests
+//!   (see [`EventRoomService::archive_event()`]),
+//! * or close it for good, which also tombstones it
+//!   (see [`EventRoomService::close_event()`]).
+//!
+//! The choice is stored in the event details, so the creator is only asked once,
+//! and attendees' clients list archived and closed events among their past events
+//! instead of their upcoming ones.
+//!
+//! [`EventRoomService::keep_event_open()`]: crate::social::events::EventRoomService::keep_event_open
+//! [`EventRoomService::archive_event()`]: crate::social::events::EventRoomService::archive_event
+//! [`EventRoomService::close_event()`]: crate::social::events::EventRoomService::close_event
+
+use matrix_sdk::ruma::events::room::power_levels::RoomPowerLevels;
+use robrix_social_events::event::SocialEventEventContent;
+
+use crate::social::events::event_room::{EventRole, GUEST_STATE_EVENT_TYPES};
+use crate::social::events::timing::DEFAULT_EVENT_DURATION_MS;
+
+/// The number of days after an event ends before its creator is asked
+/// what to do with the event room.
+pub const ARCHIVAL_DELAY_DAYS: u64 = 3;
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Get when an event ends, as a Unix timestamp in milliseconds.
+///
+/// Events without an end time are assumed to last [`DEFAULT_EVENT_DURATION_MS`].
+pub fn event_end_ms(event: &SocialEventEventContent) -> u64 {
+    event
+        .end_time
+        .unwrap_or(event.start_time.saturating_add(DEFAULT_EVENT_DURATION_MS))
+}
+
+/// Check whether the creator of an event should be asked what to do with its room,
+/// i.e., whether it ended [`ARCHIVAL_DELAY_DAYS`] days ago and nothing was decided yet.
+pub fn is_archival_due(event: &SocialEventEventContent, now_ms: u64) -> bool {
+    event.archival.is_none()
+        && now_ms >= event_end_ms(event).saturating_add(ARCHIVAL_DELAY_DAYS * DAY_MS)
+}
+
+/// Check whether an event should be listed among past events,
+/// i.e., whether its room was archived or closed.
+pub fn is_past_event(event: &SocialEventEventContent) -> bool {
+    event.archival.is_some_and(|archival| archival.is_past())
+}
+
+/// Make an event room read-only for guests.
+///
+/// Hosts can still post, e.g., a recap of the event.
+pub(crate) fn make_read_only(power_levels: &mut RoomPowerLevels) {
+    let host_level = EventRole::CoHost.power_level();
+    power_levels.events_default = host_level;
+    power_levels.invite = host_level;
+    for event_type in GUEST_STATE_EVENT_TYPES {
+        power_levels.events.insert(event_type.into(), host_level);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use robrix_social_events::event::{EventArchival, EventVisibility};
+
+    fn event(end_time: Option<u64>, archival: Option<EventArchival>) -> SocialEventEventContent {
+        SocialEventEventContent {
+            title: "Picnic".to_string(),
+            description: None,
+            start_time: 0,
+            end_time,
+            location: None,
+            cover_image: None,
+            visibility: EventVisibility::Private,
+            rsvp_deadline: None,
+            logistics: false,
+            scheduling_poll: None,
+            archival,
+        }
+    }
+
+    #[test]
+    fn test_archival_is_due_days_after_the_end() {
+        let delay = ARCHIVAL_DELAY_DAYS * DAY_MS;
+        let ended = event(Some(1000), None);
+        assert!(!is_archival_due(&ended, 1000 + delay - 1));
+        assert!(is_archival_due(&ended, 1000 + delay));
+
+        // Without an end time, the event lasts the default duration
+        let no_end = event(None, None);
+        assert!(!is_archival_due(&no_end, delay));
+        assert!(is_archival_due(&no_end, DEFAULT_EVENT_DURATION_MS + delay));
+
+        // The creator is only asked once
+        let kept_open = event(Some(1000), Some(EventArchival::KeptOpen));
+        assert!(!is_archival_due(&kept_open, 1000 + delay));
+    }
+
+    #[test]
+    fn test_past_events() {
+        assert!(!is_past_event(&event(None, None)));
+        assert!(!is_past_event(&event(None, Some(EventArchival::KeptOpen))));
+        assert!(is_past_event(&event(None, Some(EventArchival::Archived))));
+        assert!(is_past_event(&event(None, Some(EventArchival::Closed))));
+    }
+}
@@ -0,0 +1,344 @@
+//! Attendee check-ins for events.
+//!
+//! Once an event has started, attendees can check in with an optional short
+//! message and photo. Check-ins are `org.social.checkin` timeline events in
+//! the event room (see [`SocialCheckinEventContent`]), so the UI can show a
+//! live count and list of who is there.
+
+use makepad_widgets::{Cx, DefaultNone};
+use matrix_sdk::{
+    event_handler::EventHandlerHandle,
+    room::{MessagesOptions, Room},
+    ruma::{
+        events::{OriginalSyncMessageLikeEvent, SyncMessageLikeEvent},
+        MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedMxcUri, OwnedRoomId, OwnedUserId, RoomId,
+    },
+    Client,
+};
+use robrix_social_events::{checkin::SocialCheckinEventContent, event::SocialEventEventContent};
+
+use crate::social::events::{
+    event_room::load_event_details,
+    timing::{now_ms, EventTiming},
+};
+
+/// How early before the start of an event attendees may check in.
+pub const CHECKIN_EARLY_WINDOW_MS: u64 = 15 * 60 * 1000;
+
+/// Maximum length of a check-in message, in characters.
+pub const MAX_CHECKIN_MESSAGE_CHARS: usize = 140;
+
+/// A single attendee's check-in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckIn {
+    /// The user who checked in.
+    pub user_id: OwnedUserId,
+    /// Optional short message.
+    pub message: Option<String>,
+    /// Optional photo.
+    pub photo: Option<OwnedMxcUri>,
+    /// When the user checked in.
+    pub timestamp: MilliSecondsSinceUnixEpoch,
+}
+
+impl CheckIn {
+    fn from_event(event: OriginalSyncMessageLikeEvent<SocialCheckinEventContent>) -> Self {
+        Self {
+            user_id: event.sender,
+            message: event.content.message,
+            photo: event.content.photo,
+            timestamp: event.origin_server_ts,
+        }
+    }
+}
+
+/// Actions posted by the live check-in subscription.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum EventCheckinAction {
+    /// A user checked in to an event.
+    CheckedIn {
+        room_id: OwnedRoomId,
+        checkin: CheckIn,
+    },
+    /// No action.
+    None,
+}
+
+/// Check whether attendees can check in to an event at the given time.
+///
+/// Check-in opens [`CHECKIN_EARLY_WINDOW_MS`] before the event starts
+/// and closes when it ends.
+pub fn can_check_in(event: &SocialEventEventContent, now_ms: u64) -> bool {
+    let opens_at = event.start_time.saturating_sub(CHECKIN_EARLY_WINDOW_MS);
+    now_ms >= opens_at
+        && EventTiming::at(event.start_time, event.end_time, now_ms) != EventTiming::Ended
+}
+
+/// Add a check-in to a list, keeping only the latest check-in of each user.
+///
+/// A user's position in the list is kept when they check in again, so that
+/// the list stays in order of arrival.
+pub fn merge_checkin(checkins: &mut Vec<CheckIn>, checkin: CheckIn) {
+    match checkins.iter_mut().find(|c| c.user_id == checkin.user_id) {
+        Some(existing) if existing.timestamp <= checkin.timestamp => {
+            existing.message = checkin.message;
+            existing.photo = checkin.photo;
+        }
+        Some(_) => {}
+        None => checkins.push(checkin),
+    }
+}
+
+/// Service for checking in to events.
+pub struct EventCheckinService {
+    client: Client,
+}
+
+impl EventCheckinService {
+    /// Create a new EventCheckinService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Check the current user in to an event.
+    ///
+    /// # Arguments
+    /// * `room_id` - The event room ID
+    /// * `message` - Optional short message, at most [`MAX_CHECKIN_MESSAGE_CHARS`] characters
+    /// * `photo` - Optional PNG or JPEG image data
+    ///
+    /// # Errors
+    /// Returns an error if the room is not an event room, check-in is not
+    /// open, the message is too long, the photo format is not supported,
+    /// or the upload or send fails.
+    pub async fn check_in(
+        &self,
+        room_id: &RoomId,
+        message: Option<String>,
+        photo: Option<Vec<u8>>,
+    ) -> Result<OwnedEventId, CheckinError> {
+        let room = self.room(room_id)?;
+        let event = load_event_details(&room)
+            .await
+            .map_err(CheckinError::MatrixError)?
+            .ok_or(CheckinError::EventDetailsNotFound)?;
+        if !can_check_in(&event, now_ms()) {
+            return Err(CheckinError::NotOpen);
+        }
+
+        let message = message
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty());
+        if message
+            .as_ref()
+            .is_some_and(|m| m.chars().count() > MAX_CHECKIN_MESSAGE_CHARS)
+        {
+            return Err(CheckinError::MessageTooLong);
+        }
+
+        let photo = match photo {
+            Some(image) => {
+                let content_type = match imghdr::from_bytes(&image) {
+                    Some(imghdr::Type::Png) => mime::IMAGE_PNG,
+                    Some(imghdr::Type::Jpeg) => mime::IMAGE_JPEG,
+                    _ => return Err(CheckinError::UnsupportedImageFormat),
+                };
+                let response = self
+                    .client
+                    .media()
+                    .upload(&content_type, image, None)
+                    .await
+                    .map_err(CheckinError::MatrixError)?;
+                Some(response.content_uri)
+            }
+            None => None,
+        };
+
+        let response = room
+            .send(SocialCheckinEventContent { message, photo })
+            .await
+            .map_err(CheckinError::MatrixError)?;
+
+        Ok(response.event_id)
+    }
+
+    /// Get the check-ins for an event, in order of arrival.
+    ///
+    /// Only the latest check-in of each user is returned.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not an event room or messages cannot be loaded.
+    pub async fn get_checkins(&self, room_id: &RoomId) -> Result<Vec<CheckIn>, CheckinError> {
+        let room = self.room(room_id)?;
+        let event = load_event_details(&room)
+            .await
+            .map_err(CheckinError::MatrixError)?
+            .ok_or(CheckinError::EventDetailsNotFound)?;
+        let opens_at = event.start_time.saturating_sub(CHECKIN_EARLY_WINDOW_MS);
+
+        let mut events = Vec::new();
+        let mut from = None;
+        'paginate: loop {
+            let mut options = MessagesOptions::backward();
+            options.from = from.take();
+            let response = room
+                .messages(options)
+                .await
+                .map_err(CheckinError::MatrixError)?;
+
+            for timeline_event in &response.chunk {
+                let raw = timeline_event.raw();
+                // No check-ins can be sent before check-in opens
+                if raw
+                    .get_field::<u64>("origin_server_ts")
+                    .ok()
+                    .flatten()
+                    .is_some_and(|ts| ts < opens_at)
+                {
+                    break 'paginate;
+                }
+                if raw.get_field::<String>("type").ok().flatten().as_deref()
+                    != Some("org.social.checkin")
+                {
+                    continue;
+                }
+                if let Ok(SyncMessageLikeEvent::Original(event)) =
+                    raw.deserialize_as::<SyncMessageLikeEvent<SocialCheckinEventContent>>()
+                {
+                    events.push(event);
+                }
+            }
+
+            match response.end {
+                Some(end) if !response.chunk.is_empty() => from = Some(end),
+                _ => break,
+            }
+        }
+
+        // Events were loaded newest first
+        let mut checkins = Vec::new();
+        for event in events.into_iter().rev() {
+            merge_checkin(&mut checkins, CheckIn::from_event(event));
+        }
+        Ok(checkins)
+    }
+
+    /// Subscribe to new check-ins in an event room.
+    ///
+    /// An [`EventCheckinAction::CheckedIn`] action is posted for every
+    /// check-in received through sync. The subscription lasts until the
+    /// returned handle is passed to [`Client::remove_event_handler()`].
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found.
+    pub fn subscribe(&self, room_id: &RoomId) -> Result<EventHandlerHandle, CheckinError> {
+        let room = self.room(room_id)?;
+        let room_id = room_id.to_owned();
+        Ok(room.add_event_handler(
+            move |event: OriginalSyncMessageLikeEvent<SocialCheckinEventContent>| {
+                let room_id = room_id.clone();
+                async move {
+                    Cx::post_action(EventCheckinAction::CheckedIn {
+                        room_id,
+                        checkin: CheckIn::from_event(event),
+                    });
+                }
+            },
+        ))
+    }
+
+    fn room(&self, room_id: &RoomId) -> Result<Room, CheckinError> {
+        self.client
+            .get_room(room_id)
+            .ok_or(CheckinError::RoomNotFound)
+    }
+}
+
+/// Errors that can occur when checking in to events.
+#[derive(Debug, thiserror::Error)]
+pub enum CheckinError {
+    /// The requested room was not found.
+    #[error("Room not found")]
+    RoomNotFound,
+
+    /// The room has no event details.
+    #[error("Event details not found")]
+    EventDetailsNotFound,
+
+    /// The event has not started yet or is already over.
+    #[error("Check-in is not open for this event")]
+    NotOpen,
+
+    /// The check-in message is longer than [`MAX_CHECKIN_MESSAGE_CHARS`].
+    #[error("Check-in message is too long")]
+    MessageTooLong,
+
+    /// The photo is not a PNG or JPEG image.
+    #[error("Unsupported image format")]
+    UnsupportedImageFormat,
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{uint, user_id};
+    use robrix_social_events::event::EventVisibility;
+
+    const HOUR_MS: u64 = 60 * 60 * 1000;
+
+    fn event(start_time: u64, end_time: Option<u64>) -> SocialEventEventContent {
+        SocialEventEventContent {
+            title: "Picnic".to_string(),
+            description: None,
+            start_time,
+            end_time,
+            location: None,
+            cover_image: None,
+            visibility: EventVisibility::Public,
+            rsvp_deadline: None,
+            logistics: false,
+            scheduling_poll: None,
+            archival: None,
+        }
+    }
+
+    fn checkin(user: &str, message: &str, ts: u32) -> CheckIn {
+        CheckIn {
+            user_id: user.try_into().unwrap(),
+            message: Some(message.to_string()),
+            photo: None,
+            timestamp: MilliSecondsSinceUnixEpoch(ts.into()),
+        }
+    }
+
+    #[test]
+    fn test_checkin_window() {
+        let event = event(10 * HOUR_MS, Some(12 * HOUR_MS));
+        assert!(!can_check_in(&event, 9 * HOUR_MS));
+        assert!(can_check_in(&event, 10 * HOUR_MS - CHECKIN_EARLY_WINDOW_MS));
+        assert!(can_check_in(&event, 11 * HOUR_MS));
+        assert!(!can_check_in(&event, 12 * HOUR_MS));
+    }
+
+    #[test]
+    fn test_merge_keeps_latest_checkin_per_user() {
+        let mut checkins = Vec::new();
+        merge_checkin(&mut checkins, checkin("@alice:example.org", "here", 1));
+        merge_checkin(&mut checkins, checkin("@bob:example.org", "hi", 2));
+        merge_checkin(
+            &mut checkins,
+            checkin("@alice:example.org", "by the stage", 3),
+        );
+        merge_checkin(&mut checkins, checkin("@bob:example.org", "stale", 1));
+
+        assert_eq!(checkins.len(), 2);
+        assert_eq!(checkins[0].user_id, user_id!("@alice:example.org"));
+        assert_eq!(checkins[0].message.as_deref(), Some("by the stage"));
+        assert_eq!(checkins[0].timestamp.0, uint!(1));
+        assert_eq!(checkins[1].message.as_deref(), Some("hi"));
+    }
+}
@@ -0,0 +1,651 @@
+//! Shared checklists for events.
+//!
+//! Event rooms have a checklist attendees use to coordinate what they're
+//! bringing and who is doing which task. Each item is an
+//! `org.social.checklist_item` state event keyed by the item's ID
+//! (see [`SocialChecklistItemEventContent`]), holding the quantity needed
+//! and whether a host checked it off. Only hosts can add, edit and remove items.
+//!
+//! Attendees claim items with their own `org.social.checklist_claim` state
+//! event per item (see [`SocialChecklistClaimEventContent`]), and check off
+//! their part of an item there. Attendees claiming the same item at once
+//! don't overwrite each other's claims, though together they can claim more
+//! than is needed; the UI should reload the checklist when room state changes.
+
+use matrix_sdk::{
+    deserialized_responses::SyncOrStrippedState,
+    room::Room,
+    ruma::{events::SyncStateEvent, OwnedUserId, RoomId, TransactionId, UserId},
+    Client,
+};
+use robrix_social_events::checklist::{
+    checklist_claim_state_key, ChecklistItemKind, SocialChecklistClaimEventContent,
+    SocialChecklistItemEventContent,
+};
+
+use crate::social::events::event_room::EventRole;
+
+/// Maximum length of a checklist item's title, in characters.
+pub const MAX_CHECKLIST_TITLE_CHARS: usize = 100;
+
+/// An attendee's claim on a checklist item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChecklistClaim {
+    /// The attendee bringing or doing the item.
+    pub user_id: OwnedUserId,
+    /// How many of the item they're bringing.
+    pub quantity: u32,
+    /// Whether they checked off their part.
+    pub done: bool,
+}
+
+/// An item on an event's checklist.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChecklistItem {
+    /// The item's ID, i.e., the state key of its state event.
+    pub id: String,
+    /// What to bring or do.
+    pub title: String,
+    /// Whether this is something to bring or a task.
+    pub kind: ChecklistItemKind,
+    /// How many are needed.
+    pub quantity: u32,
+    /// Attendees bringing or doing the item.
+    pub claims: Vec<ChecklistClaim>,
+    /// Whether a host checked the item off.
+    pub done: bool,
+    /// Which host checked the item off.
+    pub done_by: Option<OwnedUserId>,
+}
+
+impl ChecklistItem {
+    fn from_content(id: String, content: SocialChecklistItemEventContent) -> Self {
+        Self {
+            id,
+            title: content.title,
+            kind: content.kind,
+            quantity: content.quantity,
+            claims: Vec::new(),
+            done: content.done,
+            done_by: content.done_by,
+        }
+    }
+
+    fn to_content(&self) -> SocialChecklistItemEventContent {
+        SocialChecklistItemEventContent {
+            title: self.title.clone(),
+            kind: self.kind,
+            quantity: self.quantity,
+            done: self.done,
+            done_by: self.done_by.clone(),
+            removed: false,
+        }
+    }
+
+    /// Get the claim event of the given user on this item,
+    /// withdrawing their claim if they have none.
+    fn claim_content(&self, user_id: &UserId) -> SocialChecklistClaimEventContent {
+        let claim = self.claim(user_id);
+        SocialChecklistClaimEventContent {
+            item_id: self.id.clone(),
+            user_id: user_id.to_owned(),
+            quantity: claim.map_or(0, |claim| claim.quantity),
+            done: claim.is_some_and(|claim| claim.done),
+        }
+    }
+
+    /// Check whether the item is done: either a host checked it off, or all
+    /// that's needed is claimed and every claimant checked off their part.
+    pub fn is_done(&self) -> bool {
+        self.done
+            || (!self.claims.is_empty()
+                && self.remaining() == 0
+                && self.claims.iter().all(|claim| claim.done))
+    }
+
+    /// Get the total quantity claimed by attendees.
+    pub fn claimed(&self) -> u32 {
+        self.claims.iter().map(|claim| claim.quantity).sum()
+    }
+
+    /// Get the quantity nobody has claimed yet.
+    pub fn remaining(&self) -> u32 {
+        self.quantity.saturating_sub(self.claimed())
+    }
+
+    /// Get the claim of the given user, if they have one.
+    pub fn claim(&self, user_id: &UserId) -> Option<&ChecklistClaim> {
+        self.claims.iter().find(|claim| claim.user_id == user_id)
+    }
+
+    /// Get the quantity claimed by the given user.
+    pub fn claim_of(&self, user_id: &UserId) -> u32 {
+        self.claim(user_id).map_or(0, |claim| claim.quantity)
+    }
+
+    /// Set the quantity the given user claims, replacing any earlier claim.
+    ///
+    /// A quantity of 0 withdraws the user's claim.
+    ///
+    /// # Errors
+    /// Returns [`ChecklistError::QuantityUnavailable`] if the user claims more
+    /// than is left, counting what they already claimed.
+    pub fn set_claim(&mut self, user_id: &UserId, quantity: u32) -> Result<(), ChecklistError> {
+        let available = self.remaining() + self.claim_of(user_id);
+        if quantity > available {
+            return Err(ChecklistError::QuantityUnavailable { available });
+        }
+
+        let done = self.claim(user_id).is_some_and(|claim| claim.done);
+        self.claims.retain(|claim| claim.user_id != user_id);
+        if quantity > 0 {
+            self.claims.push(ChecklistClaim {
+                user_id: user_id.to_owned(),
+                quantity,
+                done,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check off the given user's part of the item, or undo that.
+    ///
+    /// # Errors
+    /// Returns [`ChecklistError::NotClaimed`] if the user hasn't claimed the item.
+    pub fn set_claim_done(&mut self, user_id: &UserId, done: bool) -> Result<(), ChecklistError> {
+        let claim = self
+            .claims
+            .iter_mut()
+            .find(|claim| claim.user_id == user_id)
+            .ok_or(ChecklistError::NotClaimed)?;
+        claim.done = done;
+        Ok(())
+    }
+
+    /// Check the item off on behalf of a host, or undo that.
+    pub fn set_done(&mut self, user_id: &UserId, done: bool) {
+        self.done = done;
+        self.done_by = done.then(|| user_id.to_owned());
+    }
+}
+
+/// Sort checklist items with open items first, then by title.
+pub fn sort_checklist(items: &mut [ChecklistItem]) {
+    items.sort_by(|a, b| {
+        a.is_done()
+            .cmp(&b.is_done())
+            .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+}
+
+/// Add claims to the items they're on.
+///
+/// Each claim is given with its state key and sender. Claims are ignored if
+/// their state key doesn't match their item and user (see
+/// [`checklist_claim_state_key()`]), or if they weren't sent by the claimant
+/// or a host, according to `is_host`. Withdrawn claims are skipped.
+pub fn apply_claims(
+    items: &mut [ChecklistItem],
+    claims: impl IntoIterator<Item = (String, OwnedUserId, SocialChecklistClaimEventContent)>,
+    is_host: impl Fn(&UserId) -> bool,
+) {
+    for (state_key, sender, claim) in claims {
+        if claim.quantity == 0
+            || state_key != checklist_claim_state_key(&claim.item_id, &claim.user_id)
+            || (sender != claim.user_id && !is_host(&sender))
+        {
+            continue;
+        }
+        if let Some(item) = items.iter_mut().find(|item| item.id == claim.item_id) {
+            item.claims.push(ChecklistClaim {
+                user_id: claim.user_id,
+                quantity: claim.quantity,
+                done: claim.done,
+            });
+        }
+    }
+}
+
+/// Service for managing event checklists.
+pub struct ChecklistService {
+    client: Client,
+}
+
+impl ChecklistService {
+    /// Create a new ChecklistService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Check whether the current user is a host of an event,
+    /// and can thus add, edit and remove checklist items.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, the room is not found,
+    /// or the room's power levels cannot be read.
+    pub async fn can_edit_items(&self, room_id: &RoomId) -> Result<bool, ChecklistError> {
+        let user_id = self.client.user_id().ok_or(ChecklistError::NotLoggedIn)?;
+        is_host(&self.room(room_id)?, user_id).await
+    }
+
+    /// Add an item to an event's checklist.
+    ///
+    /// The quantity of tasks is always 1.
+    ///
+    /// # Returns
+    /// The ID of the new item.
+    ///
+    /// # Errors
+    /// Returns an error if the title is empty or too long, the quantity is 0,
+    /// the room is not found, the current user is not a host,
+    /// or the Matrix API call fails.
+    pub async fn add_item(
+        &self,
+        room_id: &RoomId,
+        title: &str,
+        kind: ChecklistItemKind,
+        quantity: u32,
+    ) -> Result<String, ChecklistError> {
+        let title = title.trim();
+        if title.is_empty() || title.chars().count() > MAX_CHECKLIST_TITLE_CHARS {
+            return Err(ChecklistError::InvalidTitle);
+        }
+        if quantity == 0 {
+            return Err(ChecklistError::InvalidQuantity);
+        }
+
+        let room = self.host_room(room_id).await?;
+        let item = ChecklistItem {
+            id: TransactionId::new().to_string(),
+            title: title.to_string(),
+            kind,
+            quantity: match kind {
+                ChecklistItemKind::Bring => quantity,
+                ChecklistItemKind::Task => 1,
+            },
+            claims: Vec::new(),
+            done: false,
+            done_by: None,
+        };
+        room.send_state_event_for_key(item.id.as_str(), item.to_content())
+            .await
+            .map_err(ChecklistError::MatrixError)?;
+
+        Ok(item.id)
+    }
+
+    /// Get the items on an event's checklist with their claims,
+    /// sorted with [`sort_checklist()`].
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or room state cannot be read.
+    pub async fn get_items(&self, room_id: &RoomId) -> Result<Vec<ChecklistItem>, ChecklistError> {
+        let room = self.room(room_id)?;
+        let mut items = load_items(&room).await?;
+        sort_checklist(&mut items);
+        Ok(items)
+    }
+
+    /// Set how many of an item the current user is bringing.
+    ///
+    /// For tasks, a quantity of 1 takes the task on and 0 gives it up.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, the item is not found,
+    /// more is claimed than is left, or the Matrix API call fails.
+    pub async fn set_claim(
+        &self,
+        room_id: &RoomId,
+        item_id: &str,
+        quantity: u32,
+    ) -> Result<(), ChecklistError> {
+        let user_id = self.client.user_id().ok_or(ChecklistError::NotLoggedIn)?;
+        let room = self.room(room_id)?;
+        let mut item = load_item(&room, item_id).await?;
+        item.set_claim(user_id, quantity)?;
+        send_claim(&room, &item, user_id).await
+    }
+
+    /// Assign a task to an attendee, withdrawing any earlier assignment.
+    ///
+    /// # Errors
+    /// Returns an error if the current user is not a host, the item is not
+    /// found, is not a task, or the Matrix API call fails.
+    pub async fn assign(
+        &self,
+        room_id: &RoomId,
+        item_id: &str,
+        user_id: &UserId,
+    ) -> Result<(), ChecklistError> {
+        let room = self.host_room(room_id).await?;
+        let mut item = load_item(&room, item_id).await?;
+        if item.kind != ChecklistItemKind::Task {
+            return Err(ChecklistError::NotATask);
+        }
+
+        let assignees: Vec<OwnedUserId> = item
+            .claims
+            .drain(..)
+            .map(|claim| claim.user_id)
+            .filter(|assignee| assignee != user_id)
+            .collect();
+        for assignee in assignees {
+            send_claim(&room, &item, &assignee).await?;
+        }
+        item.set_claim(user_id, 1)?;
+        send_claim(&room, &item, user_id).await
+    }
+
+    /// Check an item off, or undo that.
+    ///
+    /// Hosts check off the whole item. Other attendees check off
+    /// their own part of an item they claimed.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, the item is not found,
+    /// the user is neither a host nor claimed the item,
+    /// or the Matrix API call fails.
+    pub async fn set_done(
+        &self,
+        room_id: &RoomId,
+        item_id: &str,
+        done: bool,
+    ) -> Result<(), ChecklistError> {
+        let user_id = self.client.user_id().ok_or(ChecklistError::NotLoggedIn)?;
+        let room = self.room(room_id)?;
+        let mut item = load_item(&room, item_id).await?;
+        if is_host(&room, user_id).await? {
+            item.set_done(user_id, done);
+            room.send_state_event_for_key(item_id, item.to_content())
+                .await
+                .map_err(ChecklistError::MatrixError)?;
+            Ok(())
+        } else {
+            item.set_claim_done(user_id, done)?;
+            send_claim(&room, &item, user_id).await
+        }
+    }
+
+    /// Remove an item from an event's checklist.
+    ///
+    /// # Errors
+    /// Returns an error if the current user is not a host, the item is not
+    /// found, or the Matrix API call fails.
+    pub async fn remove_item(&self, room_id: &RoomId, item_id: &str) -> Result<(), ChecklistError> {
+        let room = self.host_room(room_id).await?;
+        let mut content = load_item(&room, item_id).await?.to_content();
+        content.removed = true;
+        room.send_state_event_for_key(item_id, content)
+            .await
+            .map_err(ChecklistError::MatrixError)?;
+        Ok(())
+    }
+
+    fn room(&self, room_id: &RoomId) -> Result<Room, ChecklistError> {
+        self.client
+            .get_room(room_id)
+            .ok_or(ChecklistError::RoomNotFound)
+    }
+
+    /// Get an event room whose checklist items the current user can edit.
+    async fn host_room(&self, room_id: &RoomId) -> Result<Room, ChecklistError> {
+        let user_id = self.client.user_id().ok_or(ChecklistError::NotLoggedIn)?;
+        let room = self.room(room_id)?;
+        if !is_host(&room, user_id).await? {
+            return Err(ChecklistError::NotAllowed);
+        }
+        Ok(room)
+    }
+}
+
+/// Check whether a user is a host of an event room.
+async fn is_host(room: &Room, user_id: &UserId) -> Result<bool, ChecklistError> {
+    let power_levels = room
+        .power_levels()
+        .await
+        .map_err(ChecklistError::MatrixError)?;
+    Ok(power_levels.for_user(user_id) >= EventRole::CoHost.power_level())
+}
+
+/// Send a user's claim on an item, as set on the item.
+async fn send_claim(
+    room: &Room,
+    item: &ChecklistItem,
+    user_id: &UserId,
+) -> Result<(), ChecklistError> {
+    room.send_state_event_for_key(
+        &checklist_claim_state_key(&item.id, user_id),
+        item.claim_content(user_id),
+    )
+    .await
+    .map_err(ChecklistError::MatrixError)?;
+    Ok(())
+}
+
+/// Load the checklist items that haven't been removed, with their valid claims.
+async fn load_items(room: &Room) -> Result<Vec<ChecklistItem>, ChecklistError> {
+    let raw_items = room
+        .get_state_events_static::<SocialChecklistItemEventContent>()
+        .await
+        .map_err(ChecklistError::MatrixError)?;
+    let mut items: Vec<ChecklistItem> = raw_items
+        .into_iter()
+        .filter_map(|raw_event| match raw_event.deserialize() {
+            Ok(SyncOrStrippedState::Sync(SyncStateEvent::Original(event))) => Some(event),
+            _ => None,
+        })
+        .filter(|event| !event.content.removed)
+        .map(|event| ChecklistItem::from_content(event.state_key, event.content))
+        .collect();
+
+    let raw_claims = room
+        .get_state_events_static::<SocialChecklistClaimEventContent>()
+        .await
+        .map_err(ChecklistError::MatrixError)?;
+    let claims = raw_claims
+        .into_iter()
+        .filter_map(|raw_event| match raw_event.deserialize() {
+            Ok(SyncOrStrippedState::Sync(SyncStateEvent::Original(event))) => {
+                Some((event.state_key, event.sender, event.content))
+            }
+            _ => None,
+        });
+    let power_levels = room
+        .power_levels()
+        .await
+        .map_err(ChecklistError::MatrixError)?;
+    let host_level = EventRole::CoHost.power_level();
+    apply_claims(&mut items, claims, |user_id| {
+        power_levels.for_user(user_id) >= host_level
+    });
+
+    Ok(items)
+}
+
+/// Load a single checklist item that hasn't been removed, with its valid claims.
+async fn load_item(room: &Room, item_id: &str) -> Result<ChecklistItem, ChecklistError> {
+    load_items(room)
+        .await?
+        .into_iter()
+        .find(|item| item.id == item_id)
+        .ok_or(ChecklistError::ItemNotFound)
+}
+
+/// Errors that can occur when working with event checklists.
+#[derive(Debug, thiserror::Error)]
+pub enum ChecklistError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The requested room was not found.
+    #[error("Room not found")]
+    RoomNotFound,
+
+    /// The checklist item was not found or has been removed.
+    #[error("Checklist item not found")]
+    ItemNotFound,
+
+    /// The title is empty or too long.
+    #[error("Title must be between 1 and {MAX_CHECKLIST_TITLE_CHARS} characters")]
+    InvalidTitle,
+
+    /// The quantity needed is 0.
+    #[error("Quantity must be at least 1")]
+    InvalidQuantity,
+
+    /// More was claimed than is left.
+    #[error("Only {available} left to claim")]
+    QuantityUnavailable {
+        /// How many the user can claim, including what they already claimed.
+        available: u32,
+    },
+
+    /// Only hosts can add, edit and remove checklist items.
+    #[error("Only hosts can edit the checklist")]
+    NotAllowed,
+
+    /// The user hasn't claimed the item they checked off.
+    #[error("Claim the item before checking it off")]
+    NotClaimed,
+
+    /// Only tasks can be assigned.
+    #[error("Only tasks can be assigned")]
+    NotATask,
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::user_id;
+
+    fn item(id: &str, title: &str, quantity: u32) -> ChecklistItem {
+        ChecklistItem {
+            id: id.to_string(),
+            title: title.to_string(),
+            kind: ChecklistItemKind::Bring,
+            quantity,
+            claims: Vec::new(),
+            done: false,
+            done_by: None,
+        }
+    }
+
+    #[test]
+    fn test_claims_cannot_exceed_quantity() {
+        let alice = user_id!("@alice:example.org");
+        let bob = user_id!("@bob:example.org");
+        let mut chips = item("chips", "Chips", 3);
+
+        chips.set_claim(alice, 2).unwrap();
+        assert!(matches!(
+            chips.set_claim(bob, 2),
+            Err(ChecklistError::QuantityUnavailable { available: 1 })
+        ));
+        chips.set_claim(bob, 1).unwrap();
+        assert_eq!(chips.remaining(), 0);
+
+        // Changing a claim counts the user's own claim as available
+        chips.set_claim(alice, 1).unwrap();
+        assert_eq!(chips.claim_of(alice), 1);
+        assert_eq!(chips.remaining(), 1);
+
+        chips.set_claim(alice, 0).unwrap();
+        assert_eq!(chips.claims.len(), 1);
+        assert_eq!(chips.claimed(), 1);
+    }
+
+    #[test]
+    fn test_set_done_records_who_checked_off() {
+        let alice = user_id!("@alice:example.org");
+        let mut chairs = item("chairs", "Set up chairs", 1);
+
+        chairs.set_done(alice, true);
+        assert_eq!(chairs.done_by.as_deref(), Some(alice));
+        chairs.set_done(alice, false);
+        assert!(!chairs.done);
+        assert_eq!(chairs.done_by, None);
+    }
+
+    #[test]
+    fn test_claimants_check_off_their_part() {
+        let alice = user_id!("@alice:example.org");
+        let bob = user_id!("@bob:example.org");
+        let mut chips = item("chips", "Chips", 2);
+
+        chips.set_claim(alice, 1).unwrap();
+        chips.set_claim_done(alice, true).unwrap();
+        assert!(!chips.is_done());
+        assert!(matches!(
+            chips.set_claim_done(bob, true),
+            Err(ChecklistError::NotClaimed)
+        ));
+
+        chips.set_claim(bob, 1).unwrap();
+        assert!(!chips.is_done());
+        chips.set_claim_done(bob, true).unwrap();
+        assert!(chips.is_done());
+
+        // Changing a claim keeps it checked off
+        chips.set_claim(alice, 1).unwrap();
+        assert!(chips.claim(alice).unwrap().done);
+    }
+
+    #[test]
+    fn test_only_valid_claims_are_applied() {
+        let alice = user_id!("@alice:example.org");
+        let bob = user_id!("@bob:example.org");
+        let host = user_id!("@host:example.org");
+        let claim = |sender: &UserId, user_id: &UserId, quantity: u32| {
+            (
+                checklist_claim_state_key("chips", user_id),
+                sender.to_owned(),
+                SocialChecklistClaimEventContent {
+                    item_id: "chips".to_string(),
+                    user_id: user_id.to_owned(),
+                    quantity,
+                    done: false,
+                },
+            )
+        };
+        let mut misplaced = claim(bob, bob, 1);
+        misplaced.0 = checklist_claim_state_key("cake", bob);
+
+        let mut items = vec![item("chips", "Chips", 5)];
+        apply_claims(
+            &mut items,
+            [
+                claim(alice, alice, 2),
+                // Claims on behalf of someone else are only valid from hosts
+                claim(alice, bob, 1),
+                claim(host, bob, 1),
+                misplaced,
+                claim(host, host, 0),
+            ],
+            |user_id| user_id == host,
+        );
+
+        let claims: Vec<_> = items[0]
+            .claims
+            .iter()
+            .map(|claim| (claim.user_id.as_ref(), claim.quantity))
+            .collect();
+        assert_eq!(claims, [(alice, 2), (bob, 1)]);
+    }
+
+    #[test]
+    fn test_open_items_sort_first() {
+        let mut done = item("a", "Apples", 1);
+        done.done = true;
+        let mut items = vec![done, item("b", "napkins", 10), item("c", "Cake", 1)];
+        sort_checklist(&mut items);
+
+        let ids: Vec<_> = items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, ["c", "b", "a"]);
+    }
+}
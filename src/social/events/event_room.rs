@@ -1,18 +1,35 @@
 //! Event/gathering room management.
 //!
 //! This module provides services for creating and managing event rooms,
-//! including power level configuration for different event roles.
+//! including power level configuration for different event roles,
+//! and archiving event rooms after the event (see [`archival`](super::archival)).
 
 use matrix_sdk::{
-    room::power_levels::RoomPowerLevelChanges,
+    deserialized_responses::RawSyncOrStrippedState,
+    room::{power_levels::RoomPowerLevelChanges, Room},
     ruma::{
         api::client::room::create_room::v3::Request as CreateRoomRequest,
-        events::room::join_rules::{JoinRule, RoomJoinRulesEventContent},
-        Int, OwnedRoomId, RoomId, UserId,
+        events::room::{
+            join_rules::{JoinRule, RoomJoinRulesEventContent},
+            power_levels::{RoomPowerLevelsEventContent, UserPowerLevel},
+            tombstone::RoomTombstoneEventContent,
+        },
+        Int, OwnedMxcUri, OwnedRoomId, OwnedUserId, RoomId, UserId,
     },
-    Client,
+    Client, RoomMemberships,
 };
-use robrix_social_events::event::{EventVisibility, SocialEventEventContent};
+use robrix_social_events::{
+    checklist::CHECKLIST_CLAIM_EVENT_TYPE,
+    event::{EventArchival, EventVisibility, SocialEventEventContent},
+    ride::RIDE_EVENT_TYPE,
+};
+use std::collections::HashSet;
+
+use crate::social::events::archival::make_read_only;
+
+/// State event types guests can send in event rooms,
+/// despite state events otherwise requiring co-host level.
+pub const GUEST_STATE_EVENT_TYPES: [&str; 2] = [CHECKLIST_CLAIM_EVENT_TYPE, RIDE_EVENT_TYPE];
 
 /// Power level roles for events.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -21,7 +38,7 @@ pub enum EventRole {
     Creator,
     /// Can edit event, moderate (PL 50)
     CoHost,
-    /// Can chat, RSVP, potentially invite (PL 0)
+    /// Can chat, RSVP, claim checklist items, share rides, potentially invite (PL 0)
     Guest,
 }
 
@@ -35,6 +52,20 @@ impl EventRole {
         }
     }
 
+    /// Get the role that corresponds to the given power level.
+    ///
+    /// Power levels between the predefined roles map to the highest role
+    /// they reach.
+    pub fn from_power_level(power_level: Int) -> Self {
+        if power_level >= Self::Creator.power_level() {
+            Self::Creator
+        } else if power_level >= Self::CoHost.power_level() {
+            Self::CoHost
+        } else {
+            Self::Guest
+        }
+    }
+
     /// Get the power level for this role as i64.
     pub fn power_level_i64(&self) -> i64 {
         match self {
@@ -75,6 +106,31 @@ pub fn event_room_power_levels(guests_can_invite: bool) -> RoomPowerLevelChanges
     changes
 }
 
+/// Load the event details from a room's `org.social.event` state.
+///
+/// Works for both joined rooms and invites that carry the event state.
+/// Returns `None` if the room is not an event room, or if its event state
+/// is redacted or malformed.
+pub(crate) async fn load_event_details(
+    room: &Room,
+) -> Result<Option<SocialEventEventContent>, matrix_sdk::Error> {
+    let Some(raw_event) = room
+        .get_state_event_static::<SocialEventEventContent>()
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    // Redacted event state has an empty content, which fails to deserialize.
+    let content = match raw_event {
+        RawSyncOrStrippedState::Sync(raw) => raw.get_field::<SocialEventEventContent>("content"),
+        RawSyncOrStrippedState::Stripped(raw) => {
+            raw.get_field::<SocialEventEventContent>("content")
+        }
+    };
+    Ok(content.ok().flatten())
+}
+
 /// Service for managing event rooms.
 pub struct EventRoomService {
     client: Client,
@@ -140,6 +196,20 @@ impl EventRoomService {
                 .await
                 .map_err(EventRoomError::MatrixError)?;
 
+            // Let guests send the state events they coordinate with
+            let mut power_levels = room
+                .power_levels()
+                .await
+                .map_err(EventRoomError::MatrixError)?;
+            for event_type in GUEST_STATE_EVENT_TYPES {
+                power_levels
+                    .events
+                    .insert(event_type.into(), EventRole::Guest.power_level());
+            }
+            room.send_state_event(RoomPowerLevelsEventContent::from(power_levels))
+                .await
+                .map_err(EventRoomError::MatrixError)?;
+
             // Send event details state event
             room.send_state_event(event_details)
                 .await
@@ -172,6 +242,119 @@ impl EventRoomService {
         Ok(())
     }
 
+    /// Upload a cover photo and set it as the event's cover image.
+    ///
+    /// The image must be a PNG or JPEG. The uploaded MXC URI is stored in the
+    /// event's `cover_image` field, keeping all other event details unchanged.
+    ///
+    /// # Returns
+    /// The MXC URI of the uploaded image.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the image format is not
+    /// supported, the room has no event details, or the upload or state
+    /// update fails.
+    pub async fn set_cover_photo(
+        &self,
+        room_id: &RoomId,
+        image: Vec<u8>,
+    ) -> Result<OwnedMxcUri, EventRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(EventRoomError::RoomNotFound)?;
+
+        let content_type = match imghdr::from_bytes(&image) {
+            Some(imghdr::Type::Png) => mime::IMAGE_PNG,
+            Some(imghdr::Type::Jpeg) => mime::IMAGE_JPEG,
+            _ => return Err(EventRoomError::UnsupportedImageFormat),
+        };
+
+        let mut event_details = load_event_details(&room)
+            .await
+            .map_err(EventRoomError::MatrixError)?
+            .ok_or(EventRoomError::EventDetailsNotFound)?;
+
+        let response = self
+            .client
+            .media()
+            .upload(&content_type, image, None)
+            .await
+            .map_err(EventRoomError::MatrixError)?;
+
+        event_details.cover_image = Some(response.content_uri.clone());
+        room.send_state_event(event_details)
+            .await
+            .map_err(EventRoomError::MatrixError)?;
+
+        Ok(response.content_uri)
+    }
+
+    /// Keep an event room open for guests after the event ended.
+    ///
+    /// The creator won't be asked what to do with the room again.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the room has no event details,
+    /// or the state update fails.
+    pub async fn keep_event_open(&self, room_id: &RoomId) -> Result<(), EventRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(EventRoomError::RoomNotFound)?;
+
+        set_archival(&room, EventArchival::KeptOpen).await
+    }
+
+    /// Archive an event room after the event ended, making it read-only for guests.
+    ///
+    /// Hosts can still post in the room, e.g., a recap of the event.
+    /// Attendees' clients list the event among their past events.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the room has no event details,
+    /// or the power levels or state update fails.
+    pub async fn archive_event(&self, room_id: &RoomId) -> Result<(), EventRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(EventRoomError::RoomNotFound)?;
+
+        set_archival(&room, EventArchival::Archived).await?;
+        make_room_read_only(&room).await
+    }
+
+    /// Close an event room for good after the event ended.
+    ///
+    /// The room is made read-only for guests, like an archived one, then tombstoned
+    /// with a pointer to `replacement_room`, e.g., the creator's public feed room,
+    /// where attendees can keep following them.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the room has no event details,
+    /// or any of the state updates fails.
+    pub async fn close_event(
+        &self,
+        room_id: &RoomId,
+        replacement_room: &RoomId,
+    ) -> Result<(), EventRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(EventRoomError::RoomNotFound)?;
+
+        set_archival(&room, EventArchival::Closed).await?;
+        make_room_read_only(&room).await?;
+        room.send_state_event(RoomTombstoneEventContent::new(
+            "This event is over and its room was closed.".to_string(),
+            replacement_room.to_owned(),
+        ))
+        .await
+        .map_err(EventRoomError::MatrixError)?;
+
+        Ok(())
+    }
+
     /// Invite a guest to an event.
     ///
     /// # Errors
@@ -193,6 +376,103 @@ impl EventRoomService {
         Ok(())
     }
 
+    /// Invite several guests to an event.
+    ///
+    /// Each invite is sent independently, so one failure does not prevent
+    /// the remaining guests from being invited.
+    ///
+    /// # Returns
+    /// The result of each invite, in the same order as `guests`.
+    ///
+    /// # Errors
+    /// Returns an error only if the room is not found.
+    pub async fn invite_guests(
+        &self,
+        room_id: &RoomId,
+        guests: &[OwnedUserId],
+    ) -> Result<Vec<GuestInviteResult>, EventRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(EventRoomError::RoomNotFound)?;
+
+        let mut results = Vec::with_capacity(guests.len());
+        for guest in guests {
+            let result = room
+                .invite_user_by_id(guest)
+                .await
+                .map_err(EventRoomError::MatrixError);
+            results.push(GuestInviteResult {
+                user_id: guest.clone(),
+                result,
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Get the details of an event from its room's state.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the room has no event details,
+    /// or room state cannot be read.
+    pub async fn get_event(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<SocialEventEventContent, EventRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(EventRoomError::RoomNotFound)?;
+
+        load_event_details(&room)
+            .await
+            .map_err(EventRoomError::MatrixError)?
+            .ok_or(EventRoomError::EventDetailsNotFound)
+    }
+
+    /// Get the number of users with a pending invite to an event.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or its members cannot be loaded.
+    pub async fn invited_count(&self, room_id: &RoomId) -> Result<u32, EventRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(EventRoomError::RoomNotFound)?;
+
+        let invited = room
+            .members(RoomMemberships::INVITE)
+            .await
+            .map_err(EventRoomError::MatrixError)?;
+
+        Ok(invited.len() as u32)
+    }
+
+    /// Get the users who have joined an event or have a pending invite to it.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or its members cannot be loaded.
+    pub async fn invited_or_joined(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<HashSet<OwnedUserId>, EventRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(EventRoomError::RoomNotFound)?;
+
+        let members = room
+            .members(RoomMemberships::JOIN | RoomMemberships::INVITE)
+            .await
+            .map_err(EventRoomError::MatrixError)?;
+
+        Ok(members
+            .iter()
+            .map(|member| member.user_id().to_owned())
+            .collect())
+    }
+
     /// Add a co-host to an event.
     ///
     /// Promotes a user to co-host power level (50), allowing them to
@@ -217,6 +497,110 @@ impl EventRoomService {
 
         Ok(())
     }
+
+    /// Remove a co-host from an event.
+    ///
+    /// Demotes the user back to guest power level (0). They remain in the room.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or power level update fails.
+    pub async fn remove_cohost(
+        &self,
+        room_id: &RoomId,
+        cohost: &UserId,
+    ) -> Result<(), EventRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(EventRoomError::RoomNotFound)?;
+
+        room.update_power_levels(vec![(cohost, EventRole::Guest.power_level())])
+            .await
+            .map_err(EventRoomError::MatrixError)?;
+
+        Ok(())
+    }
+
+    /// List the role of every joined member of an event.
+    ///
+    /// Roles are derived from the room's power levels, and room creators with
+    /// infinite power are hosts too. The list is sorted with the creator first,
+    /// then co-hosts, then guests.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or room state cannot be read.
+    pub async fn list_roles(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<Vec<(OwnedUserId, EventRole)>, EventRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(EventRoomError::RoomNotFound)?;
+
+        let power_levels = room
+            .power_levels()
+            .await
+            .map_err(EventRoomError::MatrixError)?;
+        let members = room
+            .members(RoomMemberships::JOIN)
+            .await
+            .map_err(EventRoomError::MatrixError)?;
+
+        let mut roles: Vec<(OwnedUserId, EventRole)> = members
+            .iter()
+            .map(|member| {
+                let user_id = member.user_id();
+                let role = match power_levels.for_user(user_id) {
+                    // Room creators have infinite power since room version 12
+                    UserPowerLevel::Infinite => EventRole::Creator,
+                    UserPowerLevel::Int(power_level) => EventRole::from_power_level(power_level),
+                };
+                (user_id.to_owned(), role)
+            })
+            .collect();
+        roles.sort_by_key(|(_, role)| std::cmp::Reverse(role.power_level_i64()));
+
+        Ok(roles)
+    }
+}
+
+/// Record what the host did with an event room after the event ended.
+async fn set_archival(room: &Room, archival: EventArchival) -> Result<(), EventRoomError> {
+    let mut event_details = load_event_details(room)
+        .await
+        .map_err(EventRoomError::MatrixError)?
+        .ok_or(EventRoomError::EventDetailsNotFound)?;
+
+    event_details.archival = Some(archival);
+    room.send_state_event(event_details)
+        .await
+        .map_err(EventRoomError::MatrixError)?;
+
+    Ok(())
+}
+
+/// Make an event room read-only for guests (see [`make_read_only()`]).
+async fn make_room_read_only(room: &Room) -> Result<(), EventRoomError> {
+    let mut power_levels = room
+        .power_levels()
+        .await
+        .map_err(EventRoomError::MatrixError)?;
+    make_read_only(&mut power_levels);
+    room.send_state_event(RoomPowerLevelsEventContent::from(power_levels))
+        .await
+        .map_err(EventRoomError::MatrixError)?;
+
+    Ok(())
+}
+
+/// The outcome of inviting a single guest.
+#[derive(Debug)]
+pub struct GuestInviteResult {
+    /// The user who was invited.
+    pub user_id: OwnedUserId,
+    /// Whether the invite succeeded.
+    pub result: Result<(), EventRoomError>,
 }
 
 /// Errors that can occur when working with event rooms.
@@ -230,7 +614,29 @@ pub enum EventRoomError {
     #[error("Room not found")]
     RoomNotFound,
 
+    /// The room has no event details state event.
+    #[error("Event details not found")]
+    EventDetailsNotFound,
+
+    /// The image is not a PNG or JPEG.
+    #[error("Unsupported image format")]
+    UnsupportedImageFormat,
+
     /// An error occurred in the Matrix SDK.
     #[error("Matrix error: {0}")]
     MatrixError(#[from] matrix_sdk::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_from_power_level() {
+        assert_eq!(EventRole::from_power_level(Int::new(100).unwrap()), EventRole::Creator);
+        assert_eq!(EventRole::from_power_level(Int::new(75).unwrap()), EventRole::CoHost);
+        assert_eq!(EventRole::from_power_level(Int::new(50).unwrap()), EventRole::CoHost);
+        assert_eq!(EventRole::from_power_level(Int::new(10).unwrap()), EventRole::Guest);
+        assert_eq!(EventRole::from_power_level(Int::new(-1).unwrap()), EventRole::Guest);
+    }
+}
@@ -0,0 +1,305 @@
+//! Host analytics for event rooms.
+//!
+//! Provides a snapshot of the current RSVPs by when they were made, invite
+//! conversion, and a shareable plain-text summary of an event. All insights
+//! are computed locally from room state; nothing is sent to the homeserver.
+//!
+//! Room state only keeps each attendee's latest RSVP, so the snapshot counts
+//! every current RSVP from the day it was last changed. Earlier RSVPs that
+//! were since changed aren't counted, so it isn't a history of the RSVPs.
+
+use matrix_sdk::{
+    ruma::{MilliSecondsSinceUnixEpoch, OwnedUserId, RoomId},
+    Client, RoomMemberships,
+};
+use robrix_social_events::{event::SocialEventEventContent, rsvp::RsvpStatus};
+use std::collections::BTreeMap;
+
+use super::{
+    event_room::EventRole,
+    rsvp::{load_validated_rsvps, RsvpCounts, RsvpError},
+};
+
+/// Length of one day in milliseconds, the default snapshot bucket size.
+pub const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// A single timestamped RSVP.
+#[derive(Clone, Debug)]
+pub struct RsvpChange {
+    /// The user who changed their RSVP.
+    pub user_id: OwnedUserId,
+    /// The new RSVP status.
+    pub status: RsvpStatus,
+    /// Number of guests (including the user).
+    pub guests: u32,
+    /// When the RSVP was last changed (Unix timestamp in milliseconds).
+    pub timestamp_ms: u64,
+}
+
+/// Counts of the RSVPs last changed before the end of a time bucket.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RsvpSnapshotPoint {
+    /// Start of the bucket (Unix timestamp in milliseconds).
+    pub bucket_start_ms: u64,
+    /// Number of users going at the end of this bucket.
+    pub going: u32,
+    /// Number of users interested at the end of this bucket.
+    pub interested: u32,
+    /// Number of users not going at the end of this bucket.
+    pub not_going: u32,
+}
+
+/// How many invited users actually joined and RSVPed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InviteConversion {
+    /// Users with a pending invite.
+    pub pending_invites: u32,
+    /// Users who have joined the event room.
+    pub joined: u32,
+    /// Joined users who RSVPed "Going".
+    pub going: u32,
+}
+
+impl InviteConversion {
+    /// Fraction of all invited users that joined the event room.
+    pub fn join_rate(&self) -> f32 {
+        let invited = self.pending_invites + self.joined;
+        if invited == 0 {
+            0.0
+        } else {
+            self.joined as f32 / invited as f32
+        }
+    }
+
+    /// Fraction of joined users that RSVPed "Going".
+    pub fn going_rate(&self) -> f32 {
+        if self.joined == 0 {
+            0.0
+        } else {
+            self.going as f32 / self.joined as f32
+        }
+    }
+}
+
+/// Aggregated host insights for an event.
+#[derive(Clone, Debug, Default)]
+pub struct EventInsights {
+    /// Current RSVP counts.
+    pub counts: RsvpCounts,
+    /// The current RSVPs by when they were last changed, one point per bucket,
+    /// see [`compute_rsvp_snapshot()`].
+    pub rsvp_snapshot: Vec<RsvpSnapshotPoint>,
+    /// Invite conversion statistics.
+    pub conversion: InviteConversion,
+}
+
+impl EventInsights {
+    /// Build a shareable plain-text summary of the event and its attendance.
+    pub fn summary_text(&self, event: &SocialEventEventContent) -> String {
+        let mut summary = format!("📅 {}\n", event.title);
+        if let Some(location) = &event.location {
+            summary.push_str(&format!("📍 {}\n", location.name));
+        }
+        summary.push_str(&format!(
+            "✅ {} going ({} guests total) · ⭐ {} interested",
+            self.counts.going, self.counts.total_guests, self.counts.interested,
+        ));
+        summary
+    }
+}
+
+/// Count the given RSVPs cumulatively by when they were last changed.
+///
+/// Changes are replayed in chronological order, and each user's most recent
+/// status at the end of each bucket is counted. Buckets are aligned to
+/// multiples of `bucket_ms` and span from the first to the last change.
+///
+/// Given the current RSVPs from room state, this is a snapshot of them
+/// rather than their history, see the [module docs](self).
+pub fn compute_rsvp_snapshot(changes: &[RsvpChange], bucket_ms: u64) -> Vec<RsvpSnapshotPoint> {
+    let bucket_ms = bucket_ms.max(1);
+    let mut sorted: Vec<&RsvpChange> = changes.iter().collect();
+    sorted.sort_by_key(|c| c.timestamp_ms);
+
+    let (Some(first), Some(last)) = (sorted.first(), sorted.last()) else {
+        return Vec::new();
+    };
+    let first_bucket = first.timestamp_ms / bucket_ms * bucket_ms;
+    let last_bucket = last.timestamp_ms / bucket_ms * bucket_ms;
+
+    let mut latest: BTreeMap<&OwnedUserId, RsvpStatus> = BTreeMap::new();
+    let mut remaining = sorted.into_iter().peekable();
+    let mut snapshot = Vec::new();
+
+    let mut bucket_start = first_bucket;
+    while bucket_start <= last_bucket {
+        let bucket_end = bucket_start + bucket_ms;
+        while let Some(change) = remaining.next_if(|c| c.timestamp_ms < bucket_end) {
+            latest.insert(&change.user_id, change.status);
+        }
+
+        let mut point = RsvpSnapshotPoint {
+            bucket_start_ms: bucket_start,
+            going: 0,
+            interested: 0,
+            not_going: 0,
+        };
+        for status in latest.values() {
+            match status {
+                RsvpStatus::Going => point.going += 1,
+                RsvpStatus::Interested => point.interested += 1,
+                RsvpStatus::NotGoing => point.not_going += 1,
+            }
+        }
+        snapshot.push(point);
+        bucket_start = bucket_end;
+    }
+
+    snapshot
+}
+
+/// Service for computing host-only event insights.
+pub struct EventInsightsService {
+    client: Client,
+}
+
+impl EventInsightsService {
+    /// Create a new EventInsightsService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Check whether the current user is a host (creator or co-host) of the event.
+    pub async fn is_host(&self, room_id: &RoomId) -> Result<bool, RsvpError> {
+        let user_id = self.client.user_id().ok_or(RsvpError::NotLoggedIn)?;
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(RsvpError::RoomNotFound)?;
+
+        let power_levels = room.power_levels().await.map_err(RsvpError::MatrixError)?;
+        Ok(power_levels.for_user(user_id) >= EventRole::CoHost.power_level())
+    }
+
+    /// Compute insights for an event room, bucketing the RSVP snapshot by day.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or room state cannot be read.
+    pub async fn get_insights(&self, room_id: &RoomId) -> Result<EventInsights, RsvpError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(RsvpError::RoomNotFound)?;
+
+        let rsvps = load_validated_rsvps(&room).await?;
+
+        let mut counts = RsvpCounts::default();
+        let mut changes = Vec::with_capacity(rsvps.len());
+        for (rsvp, timestamp) in &rsvps {
+            match rsvp.status {
+                RsvpStatus::Going => {
+                    counts.going += 1;
+                    counts.total_guests += rsvp.guests;
+                }
+                RsvpStatus::Interested => counts.interested += 1,
+                RsvpStatus::NotGoing => counts.not_going += 1,
+            }
+            changes.push(RsvpChange {
+                user_id: rsvp.user_id.clone(),
+                status: rsvp.status,
+                guests: rsvp.guests,
+                timestamp_ms: timestamp_ms(*timestamp),
+            });
+        }
+
+        let joined = room
+            .members(RoomMemberships::JOIN)
+            .await
+            .map_err(RsvpError::MatrixError)?;
+        let pending_invites = room
+            .members(RoomMemberships::INVITE)
+            .await
+            .map_err(RsvpError::MatrixError)?;
+        let going = joined
+            .iter()
+            .filter(|member| {
+                rsvps.iter().any(|(rsvp, _)| {
+                    rsvp.user_id == member.user_id() && rsvp.status == RsvpStatus::Going
+                })
+            })
+            .count();
+
+        Ok(EventInsights {
+            counts,
+            rsvp_snapshot: compute_rsvp_snapshot(&changes, DAY_MS),
+            conversion: InviteConversion {
+                pending_invites: pending_invites.len() as u32,
+                joined: joined.len() as u32,
+                going: going as u32,
+            },
+        })
+    }
+}
+
+fn timestamp_ms(ts: MilliSecondsSinceUnixEpoch) -> u64 {
+    ts.get().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(user: &str, status: RsvpStatus, timestamp_ms: u64) -> RsvpChange {
+        RsvpChange {
+            user_id: format!("@{}:example.org", user).try_into().unwrap(),
+            status,
+            guests: 1,
+            timestamp_ms,
+        }
+    }
+
+    #[test]
+    fn test_empty_snapshot() {
+        assert!(compute_rsvp_snapshot(&[], DAY_MS).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_is_cumulative() {
+        let changes = vec![
+            change("alice", RsvpStatus::Going, 10),
+            change("bob", RsvpStatus::Interested, DAY_MS + 10),
+            change("carol", RsvpStatus::Going, 2 * DAY_MS + 10),
+        ];
+
+        let snapshot = compute_rsvp_snapshot(&changes, DAY_MS);
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!((snapshot[0].going, snapshot[0].interested), (1, 0));
+        assert_eq!((snapshot[1].going, snapshot[1].interested), (1, 1));
+        assert_eq!((snapshot[2].going, snapshot[2].interested), (2, 1));
+    }
+
+    #[test]
+    fn test_snapshot_uses_latest_status_per_user() {
+        let changes = vec![
+            change("alice", RsvpStatus::NotGoing, DAY_MS + 5),
+            change("alice", RsvpStatus::Going, 5),
+        ];
+
+        let snapshot = compute_rsvp_snapshot(&changes, DAY_MS);
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!((snapshot[0].going, snapshot[0].not_going), (1, 0));
+        assert_eq!((snapshot[1].going, snapshot[1].not_going), (0, 1));
+    }
+
+    #[test]
+    fn test_invite_conversion_rates() {
+        let conversion = InviteConversion {
+            pending_invites: 2,
+            joined: 6,
+            going: 3,
+        };
+        assert_eq!(conversion.join_rate(), 0.75);
+        assert_eq!(conversion.going_rate(), 0.5);
+        assert_eq!(InviteConversion::default().join_rate(), 0.0);
+    }
+}
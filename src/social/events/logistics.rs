@@ -0,0 +1,419 @@
+//! Carpool coordination for events.
+//!
+//! When the host enables logistics for an event, attendees can offer free
+//! seats in their car or ask for a ride. Each attendee has at most one
+//! `org.social.ride` state event, keyed by their user ID
+//! (see [`SocialRideEventContent`]). Requests are matched with offers from
+//! the same area, so that matched attendees can message each other to
+//! arrange the details.
+
+use matrix_sdk::{
+    deserialized_responses::SyncOrStrippedState,
+    room::Room,
+    ruma::{events::SyncStateEvent, MilliSecondsSinceUnixEpoch, OwnedUserId, RoomId, UserId},
+    Client,
+};
+use robrix_social_events::ride::{RideKind, SocialRideEventContent};
+
+use crate::social::events::event_room::load_event_details;
+
+/// Maximum length of a ride's area, in characters.
+pub const MAX_RIDE_AREA_CHARS: usize = 60;
+
+/// Maximum number of seats in a ride offer or request.
+pub const MAX_RIDE_SEATS: u32 = 8;
+
+/// An attendee's ride offer or request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ride {
+    /// The attendee offering or requesting the ride.
+    pub user_id: OwnedUserId,
+    /// Whether this is an offer or a request.
+    pub kind: RideKind,
+    /// Free seats offered, or seats needed.
+    pub seats: u32,
+    /// Where the attendee is coming from.
+    pub area: String,
+    /// Optional note.
+    pub note: Option<String>,
+    /// When the attendee last changed their offer or request.
+    pub timestamp: MilliSecondsSinceUnixEpoch,
+}
+
+/// A ride request matched with an offer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RideMatch {
+    /// The attendee driving.
+    pub driver: OwnedUserId,
+    /// The attendee getting a ride.
+    pub passenger: OwnedUserId,
+    /// Seats taken by the passenger.
+    pub seats: u32,
+    /// The area both are coming from, as the driver wrote it.
+    pub area: String,
+}
+
+impl RideMatch {
+    /// Get the other attendee of a match the given user is part of.
+    pub fn counterpart(&self, user_id: &UserId) -> Option<&UserId> {
+        if self.driver == user_id {
+            Some(&*self.passenger)
+        } else if self.passenger == user_id {
+            Some(&*self.driver)
+        } else {
+            None
+        }
+    }
+}
+
+/// The rides of an event, with requests matched to offers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RideBoard {
+    /// Requests matched with an offer.
+    pub matches: Vec<RideMatch>,
+    /// Offers with seats still free, with `seats` reduced to the free seats.
+    pub open_offers: Vec<Ride>,
+    /// Requests no offer could take.
+    pub open_requests: Vec<Ride>,
+}
+
+impl RideBoard {
+    /// Get the matches the given user is part of.
+    pub fn matches_for<'a>(&'a self, user_id: &'a UserId) -> impl Iterator<Item = &'a RideMatch> {
+        self.matches
+            .iter()
+            .filter(move |ride_match| ride_match.counterpart(user_id).is_some())
+    }
+
+    /// Returns whether there are no rides at all.
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty() && self.open_offers.is_empty() && self.open_requests.is_empty()
+    }
+}
+
+/// Normalize an area for matching, ignoring case and extra whitespace.
+fn normalize_area(area: &str) -> String {
+    area.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Match ride requests with offers from the same area.
+///
+/// Requests are matched first come, first served, each with the earliest
+/// offer from the same area that still has enough free seats. Requests are
+/// never split across several cars.
+pub fn match_rides(rides: &[Ride]) -> RideBoard {
+    let mut rides: Vec<&Ride> = rides.iter().collect();
+    rides.sort_by_key(|ride| ride.timestamp);
+
+    let mut offers: Vec<Ride> = rides
+        .iter()
+        .filter(|ride| ride.kind == RideKind::Offer)
+        .map(|ride| (*ride).clone())
+        .collect();
+
+    let mut board = RideBoard::default();
+    for request in rides.iter().filter(|ride| ride.kind == RideKind::Request) {
+        let area = normalize_area(&request.area);
+        let offer = offers.iter_mut().find(|offer| {
+            offer.user_id != request.user_id
+                && offer.seats >= request.seats
+                && normalize_area(&offer.area) == area
+        });
+        match offer {
+            Some(offer) => {
+                offer.seats -= request.seats;
+                board.matches.push(RideMatch {
+                    driver: offer.user_id.clone(),
+                    passenger: request.user_id.clone(),
+                    seats: request.seats,
+                    area: offer.area.clone(),
+                });
+            }
+            None => board.open_requests.push((*request).clone()),
+        }
+    }
+
+    offers.retain(|offer| offer.seats > 0);
+    board.open_offers = offers;
+    board
+}
+
+/// Service for coordinating rides to events.
+pub struct LogisticsService {
+    client: Client,
+}
+
+impl LogisticsService {
+    /// Create a new LogisticsService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Enable or disable ride coordination for an event.
+    ///
+    /// Only the creator and co-hosts can change this.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, it is not an event room,
+    /// or the Matrix API call fails.
+    pub async fn set_enabled(&self, room_id: &RoomId, enabled: bool) -> Result<(), LogisticsError> {
+        let room = self.room(room_id)?;
+        let mut event_details = load_event_details(&room)
+            .await?
+            .ok_or(LogisticsError::EventDetailsNotFound)?;
+        event_details.logistics = enabled;
+        room.send_state_event(event_details).await?;
+        Ok(())
+    }
+
+    /// Offer free seats to other attendees, replacing any earlier offer or request.
+    ///
+    /// # Errors
+    /// Returns an error if logistics are disabled for the event,
+    /// the seats or area are invalid, or the Matrix API call fails.
+    pub async fn offer_ride(
+        &self,
+        room_id: &RoomId,
+        seats: u32,
+        area: &str,
+        note: Option<String>,
+    ) -> Result<(), LogisticsError> {
+        self.set_ride(room_id, RideKind::Offer, seats, area, note)
+            .await
+    }
+
+    /// Ask other attendees for a ride, replacing any earlier offer or request.
+    ///
+    /// # Errors
+    /// Returns an error if logistics are disabled for the event,
+    /// the seats or area are invalid, or the Matrix API call fails.
+    pub async fn request_ride(
+        &self,
+        room_id: &RoomId,
+        seats: u32,
+        area: &str,
+        note: Option<String>,
+    ) -> Result<(), LogisticsError> {
+        self.set_ride(room_id, RideKind::Request, seats, area, note)
+            .await
+    }
+
+    /// Withdraw the current user's ride offer or request, if any.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, the room is not found,
+    /// or the Matrix API call fails.
+    pub async fn withdraw(&self, room_id: &RoomId) -> Result<(), LogisticsError> {
+        let user_id = self.client.user_id().ok_or(LogisticsError::NotLoggedIn)?;
+        let room = self.room(room_id)?;
+        let Some(ride) = load_rides(&room)
+            .await?
+            .into_iter()
+            .find(|ride| ride.user_id == user_id)
+        else {
+            return Ok(());
+        };
+
+        let content = SocialRideEventContent {
+            kind: ride.kind,
+            seats: ride.seats,
+            area: ride.area,
+            note: ride.note,
+            withdrawn: true,
+        };
+        room.send_state_event_for_key(user_id, content).await?;
+        Ok(())
+    }
+
+    /// Get the rides of an event, with requests matched to offers.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or room state cannot be read.
+    pub async fn get_ride_board(&self, room_id: &RoomId) -> Result<RideBoard, LogisticsError> {
+        let room = self.room(room_id)?;
+        Ok(match_rides(&load_rides(&room).await?))
+    }
+
+    async fn set_ride(
+        &self,
+        room_id: &RoomId,
+        kind: RideKind,
+        seats: u32,
+        area: &str,
+        note: Option<String>,
+    ) -> Result<(), LogisticsError> {
+        let user_id = self.client.user_id().ok_or(LogisticsError::NotLoggedIn)?;
+        if seats == 0 || seats > MAX_RIDE_SEATS {
+            return Err(LogisticsError::InvalidSeats);
+        }
+        let area = area.trim();
+        if area.is_empty() || area.chars().count() > MAX_RIDE_AREA_CHARS {
+            return Err(LogisticsError::InvalidArea);
+        }
+
+        let room = self.room(room_id)?;
+        let logistics_enabled = load_event_details(&room)
+            .await?
+            .is_some_and(|event_details| event_details.logistics);
+        if !logistics_enabled {
+            return Err(LogisticsError::LogisticsDisabled);
+        }
+
+        let content = SocialRideEventContent {
+            kind,
+            seats,
+            area: area.to_string(),
+            note,
+            withdrawn: false,
+        };
+        room.send_state_event_for_key(user_id, content).await?;
+        Ok(())
+    }
+
+    fn room(&self, room_id: &RoomId) -> Result<Room, LogisticsError> {
+        self.client
+            .get_room(room_id)
+            .ok_or(LogisticsError::RoomNotFound)
+    }
+}
+
+/// Load the current rides of an event, dropping withdrawn and invalid ones.
+///
+/// Events whose `state_key` doesn't match the sender are ignored.
+async fn load_rides(room: &Room) -> Result<Vec<Ride>, LogisticsError> {
+    let raw_events = room
+        .get_state_events_static::<SocialRideEventContent>()
+        .await?;
+
+    let mut rides = Vec::with_capacity(raw_events.len());
+    for raw_event in raw_events {
+        let Ok(SyncOrStrippedState::Sync(SyncStateEvent::Original(event))) = raw_event.deserialize()
+        else {
+            continue;
+        };
+
+        // SECURITY: the state_key must match the sender.
+        if event.state_key != event.sender || event.content.withdrawn {
+            continue;
+        }
+
+        rides.push(Ride {
+            user_id: event.sender,
+            kind: event.content.kind,
+            seats: event.content.seats,
+            area: event.content.area,
+            note: event.content.note,
+            timestamp: event.origin_server_ts,
+        });
+    }
+
+    Ok(rides)
+}
+
+/// Errors that can occur when coordinating rides.
+#[derive(Debug, thiserror::Error)]
+pub enum LogisticsError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The requested room was not found.
+    #[error("Room not found")]
+    RoomNotFound,
+
+    /// The room has no event details state event.
+    #[error("Event details not found")]
+    EventDetailsNotFound,
+
+    /// The host hasn't enabled ride coordination for the event.
+    #[error("Rides are not enabled for this event")]
+    LogisticsDisabled,
+
+    /// The number of seats is 0 or too large.
+    #[error("Seats must be between 1 and {MAX_RIDE_SEATS}")]
+    InvalidSeats,
+
+    /// The area is empty or too long.
+    #[error("Area must be between 1 and {MAX_RIDE_AREA_CHARS} characters")]
+    InvalidArea,
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::user_id;
+
+    fn ride(user: &str, kind: RideKind, seats: u32, area: &str, ts: u32) -> Ride {
+        Ride {
+            user_id: user.try_into().unwrap(),
+            kind,
+            seats,
+            area: area.to_string(),
+            note: None,
+            timestamp: MilliSecondsSinceUnixEpoch(ts.into()),
+        }
+    }
+
+    #[test]
+    fn test_requests_match_offers_from_the_same_area() {
+        let board = match_rides(&[
+            ride("@dan:example.org", RideKind::Request, 1, "uptown", 4),
+            ride("@alice:example.org", RideKind::Offer, 3, "Downtown", 1),
+            ride("@bob:example.org", RideKind::Request, 2, "  downtown ", 2),
+        ]);
+
+        assert_eq!(
+            board.matches,
+            vec![RideMatch {
+                driver: "@alice:example.org".try_into().unwrap(),
+                passenger: "@bob:example.org".try_into().unwrap(),
+                seats: 2,
+                area: "Downtown".to_string(),
+            }]
+        );
+        assert_eq!(board.open_offers.len(), 1);
+        assert_eq!(board.open_offers[0].seats, 1);
+        assert_eq!(board.open_requests.len(), 1);
+        assert_eq!(board.open_requests[0].area, "uptown");
+    }
+
+    #[test]
+    fn test_requests_are_matched_first_come_first_served() {
+        let board = match_rides(&[
+            ride("@alice:example.org", RideKind::Offer, 2, "Downtown", 1),
+            ride("@carol:example.org", RideKind::Request, 2, "Downtown", 3),
+            ride("@bob:example.org", RideKind::Request, 2, "Downtown", 2),
+        ]);
+
+        let bob = user_id!("@bob:example.org");
+        let carol = user_id!("@carol:example.org");
+        assert_eq!(board.matches.len(), 1);
+        assert_eq!(board.matches[0].passenger, bob);
+        assert_eq!(board.matches_for(bob).count(), 1);
+        assert_eq!(board.matches_for(carol).count(), 0);
+        assert_eq!(board.open_requests[0].user_id, carol);
+        assert!(board.open_offers.is_empty());
+    }
+
+    #[test]
+    fn test_counterpart() {
+        let alice = user_id!("@alice:example.org");
+        let bob = user_id!("@bob:example.org");
+        let ride_match = RideMatch {
+            driver: alice.to_owned(),
+            passenger: bob.to_owned(),
+            seats: 1,
+            area: "Downtown".to_string(),
+        };
+        assert_eq!(ride_match.counterpart(alice), Some(bob));
+        assert_eq!(ride_match.counterpart(bob), Some(alice));
+        assert_eq!(ride_match.counterpart(user_id!("@carol:example.org")), None);
+    }
+}
@@ -1,10 +1,36 @@
 //! Event gatherings module for Robrix social features.
 //!
 //! This module provides services for creating and managing event rooms,
-//! handling RSVPs, and coordinating event-related functionality.
+//! handling RSVPs, shared checklists, carpools and scheduling polls, weather
+//! forecasts, archiving event rooms after the event, and coordinating
+//! event-related functionality.
 
+pub mod archival;
+pub mod checkin;
+pub mod checklist;
 pub mod event_room;
+pub mod insights;
+pub mod logistics;
+pub mod quiet_attendance;
 pub mod rsvp;
+pub mod scheduling_poll;
+pub mod timezone_watcher;
+pub mod timing;
+pub mod weather;
 
-pub use event_room::{EventRole, EventRoomError, EventRoomService, event_room_power_levels};
+pub use archival::{ARCHIVAL_DELAY_DAYS, is_archival_due, is_past_event};
+pub use checkin::{CheckIn, CheckinError, EventCheckinAction, EventCheckinService};
+pub use checklist::{ChecklistError, ChecklistItem, ChecklistService};
+pub use event_room::{
+    EventRole, EventRoomError, EventRoomService, GuestInviteResult, event_room_power_levels,
+};
+pub use insights::{EventInsights, EventInsightsService, InviteConversion, RsvpSnapshotPoint};
+pub use logistics::{LogisticsError, LogisticsService, Ride, RideBoard, RideMatch};
+pub use quiet_attendance::{QuietAttendanceError, QuietAttendanceService};
 pub use rsvp::{RsvpCounts, RsvpError, RsvpService, RsvpValidation, ValidatedRsvp, validate_rsvp_event};
+pub use scheduling_poll::{DateOption, SchedulingPoll, SchedulingPollError, SchedulingPollService};
+pub use timezone_watcher::{ShiftedEvent, TimezoneChange, TimezoneWatcher};
+pub use timing::{DEFAULT_EVENT_DURATION_MS, EventTiming};
+pub use weather::{
+    Forecast, ForecastStatus, WeatherCondition, WeatherError, WeatherProvider, set_weather_provider,
+};
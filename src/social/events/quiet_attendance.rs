@@ -0,0 +1,75 @@
+//! "Attend quietly" support for event rooms.
+//!
+//! Event rooms double as chat rooms, which can get noisy for attendees who
+//! only care about the event itself. Attending quietly keeps the user's RSVP
+//! untouched and mutes the room via a room-level push rule override.
+
+use matrix_sdk::{
+    notification_settings::{NotificationSettingsError, RoomNotificationMode},
+    ruma::RoomId,
+    Client,
+};
+
+/// Service for muting and unmuting event room chat without affecting RSVPs.
+pub struct QuietAttendanceService {
+    client: Client,
+}
+
+impl QuietAttendanceService {
+    /// Create a new QuietAttendanceService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Enable or disable quiet attendance for an event room.
+    ///
+    /// Enabling installs a room-level `Mute` override push rule.
+    /// Disabling removes the user-defined rules for the room, restoring the
+    /// user's default notification behavior.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or the push rules cannot be updated.
+    pub async fn set_attend_quietly(
+        &self,
+        room_id: &RoomId,
+        quiet: bool,
+    ) -> Result<(), QuietAttendanceError> {
+        if self.client.get_room(room_id).is_none() {
+            return Err(QuietAttendanceError::RoomNotFound);
+        }
+
+        let settings = self.client.notification_settings().await;
+        if quiet {
+            settings
+                .set_room_notification_mode(room_id, RoomNotificationMode::Mute)
+                .await?;
+        } else {
+            settings.delete_user_defined_room_rules(room_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the user is attending an event room quietly.
+    ///
+    /// Returns `true` only if the user has explicitly muted the room.
+    pub async fn is_attending_quietly(&self, room_id: &RoomId) -> bool {
+        let settings = self.client.notification_settings().await;
+        matches!(
+            settings.get_user_defined_room_notification_mode(room_id).await,
+            Some(RoomNotificationMode::Mute)
+        )
+    }
+}
+
+/// Errors that can occur when changing quiet attendance.
+#[derive(Debug, thiserror::Error)]
+pub enum QuietAttendanceError {
+    /// The requested room was not found.
+    #[error("Room not found")]
+    RoomNotFound,
+
+    /// Failed to update the room's push rules.
+    #[error("Notification settings error: {0}")]
+    NotificationSettings(#[from] NotificationSettingsError),
+}
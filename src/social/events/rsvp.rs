@@ -4,7 +4,12 @@
 //! RSVP spoofing attacks.
 
 use matrix_sdk::{
-    ruma::{events::AnySyncStateEvent, OwnedEventId, OwnedUserId, RoomId, UserId},
+    deserialized_responses::SyncOrStrippedState,
+    room::Room,
+    ruma::{
+        events::{AnySyncStateEvent, SyncStateEvent},
+        MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedUserId, RoomId, UserId,
+    },
     Client,
 };
 use robrix_social_events::rsvp::{RsvpStatus, SocialRsvpEventContent};
@@ -129,20 +134,16 @@ impl RsvpService {
     /// # Errors
     /// Returns an error if the room is not found.
     pub async fn get_rsvps(&self, room_id: &RoomId) -> Result<Vec<ValidatedRsvp>, RsvpError> {
-        let _room = self
+        let room = self
             .client
             .get_room(room_id)
             .ok_or(RsvpError::RoomNotFound)?;
 
-        // TODO: Implement RSVP retrieval with validation
-        // This would involve:
-        // 1. Fetching all org.social.rsvp state events from the room
-        // 2. Validating each event (state_key == sender)
-        // 3. Filtering out invalid events
-        // 4. Converting valid events to ValidatedRsvp structs
-        //
-        // For now, return empty list until state event retrieval is implemented
-        Ok(Vec::new())
+        Ok(load_validated_rsvps(&room)
+            .await?
+            .into_iter()
+            .map(|(rsvp, _)| rsvp)
+            .collect())
     }
 
     /// Get aggregated RSVP counts.
@@ -151,21 +152,47 @@ impl RsvpService {
     /// Returns an error if RSVP retrieval fails.
     pub async fn get_rsvp_counts(&self, room_id: &RoomId) -> Result<RsvpCounts, RsvpError> {
         let rsvps = self.get_rsvps(room_id).await?;
+        Ok(RsvpCounts::tally(&rsvps))
+    }
+}
 
-        let mut counts = RsvpCounts::default();
-        for rsvp in rsvps {
-            match rsvp.status {
-                RsvpStatus::Going => {
-                    counts.going += 1;
-                    counts.total_guests += rsvp.guests;
-                }
-                RsvpStatus::Interested => counts.interested += 1,
-                RsvpStatus::NotGoing => counts.not_going += 1,
-            }
+/// Load all RSVP state events from an event room, dropping invalid ones.
+///
+/// Each RSVP is returned alongside the timestamp of the state event,
+/// i.e., when the user last changed their RSVP.
+/// Events whose `state_key` doesn't match the sender are ignored (see [`validate_rsvp_event()`]).
+pub(crate) async fn load_validated_rsvps(
+    room: &Room,
+) -> Result<Vec<(ValidatedRsvp, MilliSecondsSinceUnixEpoch)>, RsvpError> {
+    let raw_events = room
+        .get_state_events_static::<SocialRsvpEventContent>()
+        .await
+        .map_err(RsvpError::MatrixError)?;
+
+    let mut rsvps = Vec::with_capacity(raw_events.len());
+    for raw_event in raw_events {
+        let Ok(SyncOrStrippedState::Sync(SyncStateEvent::Original(event))) = raw_event.deserialize()
+        else {
+            continue;
+        };
+
+        // SECURITY: the state_key must match the sender.
+        if event.state_key != event.sender {
+            continue;
         }
 
-        Ok(counts)
+        rsvps.push((
+            ValidatedRsvp {
+                user_id: event.sender,
+                status: event.content.status,
+                guests: event.content.guests,
+                note: event.content.note,
+            },
+            event.origin_server_ts,
+        ));
     }
+
+    Ok(rsvps)
 }
 
 /// A validated RSVP (passed security checks).
@@ -194,6 +221,24 @@ pub struct RsvpCounts {
     pub total_guests: u32,
 }
 
+impl RsvpCounts {
+    /// Count the given RSVPs.
+    pub fn tally(rsvps: &[ValidatedRsvp]) -> Self {
+        let mut counts = Self::default();
+        for rsvp in rsvps {
+            match rsvp.status {
+                RsvpStatus::Going => {
+                    counts.going += 1;
+                    counts.total_guests += rsvp.guests;
+                }
+                RsvpStatus::Interested => counts.interested += 1,
+                RsvpStatus::NotGoing => counts.not_going += 1,
+            }
+        }
+        counts
+    }
+}
+
 /// Errors that can occur when working with RSVPs.
 #[derive(Debug, thiserror::Error)]
 pub enum RsvpError {
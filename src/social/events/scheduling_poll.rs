@@ -0,0 +1,402 @@
+//! Scheduling polls for events.
+//!
+//! Before settling on a date, hosts can attach a poll with several candidate
+//! start times to an event. Attendees vote for every date that works for
+//! them, and the host then moves the event to the winning date in one step.
+//!
+//! Scheduling polls are regular Matrix polls (MSC3381), so they also show up
+//! in the event room's timeline like any other poll. Each answer's ID is the
+//! candidate start time in milliseconds, and the poll's event ID is stored in
+//! the `scheduling_poll` field of the event details.
+
+use matrix_sdk::{
+    room::Room,
+    ruma::{
+        events::poll::{
+            start::PollKind,
+            unstable_end::UnstablePollEndEventContent,
+            unstable_response::UnstablePollResponseEventContent,
+            unstable_start::{
+                NewUnstablePollStartEventContent, UnstablePollAnswer,
+                UnstablePollStartContentBlock, UnstablePollStartEventContent,
+            },
+        },
+        MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedUserId, RoomId, UInt, UserId,
+    },
+    Client,
+};
+
+use crate::social::events::{event_room::load_event_details, timing::now_ms};
+use crate::social::poll::{latest_votes, load_poll_events, PollVote};
+
+/// Maximum number of dates in a scheduling poll.
+pub const MAX_SCHEDULING_POLL_OPTIONS: usize = 20;
+
+/// A candidate date in a scheduling poll.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DateOption {
+    /// The candidate start time (Unix timestamp in milliseconds).
+    pub start_time: u64,
+    /// Attendees who voted for this date.
+    pub voters: Vec<OwnedUserId>,
+}
+
+/// A scheduling poll with its current votes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchedulingPoll {
+    /// The poll's start event.
+    pub poll_event_id: OwnedEventId,
+    /// The candidate dates, earliest first.
+    pub options: Vec<DateOption>,
+    /// Whether the host already picked a date.
+    pub ended: bool,
+}
+
+impl SchedulingPoll {
+    /// Get the date with the most votes, preferring the earliest on ties.
+    ///
+    /// Returns `None` if nobody voted yet.
+    pub fn winner(&self) -> Option<&DateOption> {
+        self.options
+            .iter()
+            .filter(|option| !option.voters.is_empty())
+            // Options are sorted by date, and `max_by_key` keeps the last maximum
+            .rev()
+            .max_by_key(|option| option.voters.len())
+    }
+
+    /// Get the dates the given user voted for.
+    pub fn votes_of(&self, user_id: &UserId) -> Vec<u64> {
+        self.options
+            .iter()
+            .filter(|option| option.voters.iter().any(|voter| voter == user_id))
+            .map(|option| option.start_time)
+            .collect()
+    }
+}
+
+/// Count the votes for each candidate date.
+///
+/// Following MSC3381, only each user's latest vote counts, votes sent after
+/// the poll ended are ignored, and a vote without any valid answer withdraws
+/// the user's earlier vote (see [`latest_votes()`]).
+pub fn tally_votes(
+    start_times: &[u64],
+    votes: &[PollVote],
+    ended_at: Option<MilliSecondsSinceUnixEpoch>,
+) -> Vec<DateOption> {
+    let mut options: Vec<DateOption> = start_times
+        .iter()
+        .map(|start_time| DateOption {
+            start_time: *start_time,
+            voters: Vec::new(),
+        })
+        .collect();
+    options.sort_by_key(|option| option.start_time);
+    options.dedup_by_key(|option| option.start_time);
+
+    for vote in latest_votes(votes, ended_at) {
+        for option in options.iter_mut() {
+            let voted = vote
+                .answers
+                .iter()
+                .any(|answer| answer.parse::<u64>().ok() == Some(option.start_time));
+            if voted {
+                option.voters.push(vote.user_id.clone());
+            }
+        }
+    }
+    options
+}
+
+/// Format a candidate date for display in the device's local timezone.
+fn format_date_option(start_time: u64) -> String {
+    use chrono::{DateTime, Local, Utc};
+
+    DateTime::from_timestamp_millis(start_time as i64)
+        .unwrap_or_else(Utc::now)
+        .with_timezone(&Local)
+        .format("%a, %b %d at %I:%M %p")
+        .to_string()
+}
+
+/// Service for picking an event's date with a poll.
+pub struct SchedulingPollService {
+    client: Client,
+}
+
+impl SchedulingPollService {
+    /// Create a new SchedulingPollService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Attach a scheduling poll with the given candidate start times to an event.
+    ///
+    /// Attendees can vote for as many dates as they like. Any earlier
+    /// scheduling poll is replaced. Only the creator and co-hosts can do this.
+    ///
+    /// # Returns
+    /// The event ID of the poll.
+    ///
+    /// # Errors
+    /// Returns an error if there are too few or too many dates, a date is in
+    /// the past, the room is not an event room, or the Matrix API call fails.
+    pub async fn create_poll(
+        &self,
+        room_id: &RoomId,
+        start_times: &[u64],
+    ) -> Result<OwnedEventId, SchedulingPollError> {
+        let mut start_times = start_times.to_vec();
+        start_times.sort_unstable();
+        start_times.dedup();
+        let now = now_ms();
+        if !(2..=MAX_SCHEDULING_POLL_OPTIONS).contains(&start_times.len())
+            || start_times.iter().any(|start_time| *start_time <= now)
+        {
+            return Err(SchedulingPollError::InvalidOptions);
+        }
+
+        let room = self.room(room_id)?;
+        let mut event_details = load_event_details(&room)
+            .await?
+            .ok_or(SchedulingPollError::EventDetailsNotFound)?;
+
+        let answers = start_times
+            .iter()
+            .map(|start_time| {
+                UnstablePollAnswer::new(start_time.to_string(), format_date_option(*start_time))
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| SchedulingPollError::InvalidOptions)?;
+        let question = format!("When should {} take place?", event_details.title);
+        let mut poll_start = UnstablePollStartContentBlock::new(question.clone(), answers);
+        poll_start.kind = PollKind::Disclosed;
+        poll_start.max_selections = UInt::from(start_times.len() as u32);
+        let content = NewUnstablePollStartEventContent::plain_text(question, poll_start);
+
+        let response = room
+            .send(UnstablePollStartEventContent::New(content))
+            .await?;
+
+        event_details.scheduling_poll = Some(response.event_id.clone());
+        room.send_state_event(event_details).await?;
+        Ok(response.event_id)
+    }
+
+    /// Vote for the dates that work for the current user, replacing any earlier vote.
+    ///
+    /// Voting for no dates withdraws the user's vote.
+    ///
+    /// # Errors
+    /// Returns an error if the event has no open scheduling poll
+    /// or the Matrix API call fails.
+    pub async fn vote(
+        &self,
+        room_id: &RoomId,
+        start_times: &[u64],
+    ) -> Result<(), SchedulingPollError> {
+        let room = self.room(room_id)?;
+        let poll = load_poll(&room)
+            .await?
+            .ok_or(SchedulingPollError::PollNotFound)?;
+        if poll.ended {
+            return Err(SchedulingPollError::PollEnded);
+        }
+
+        let answers = start_times
+            .iter()
+            .map(|start_time| start_time.to_string())
+            .collect();
+        room.send(UnstablePollResponseEventContent::new(
+            answers,
+            poll.poll_event_id,
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Get the event's scheduling poll with its current votes, if any.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or messages cannot be loaded.
+    pub async fn get_poll(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<Option<SchedulingPoll>, SchedulingPollError> {
+        let room = self.room(room_id)?;
+        load_poll(&room).await
+    }
+
+    /// Move the event to the date with the most votes and close the poll.
+    ///
+    /// The event keeps its duration. Only the creator and co-hosts can do this.
+    ///
+    /// # Returns
+    /// The event's new start time.
+    ///
+    /// # Errors
+    /// Returns an error if the event has no scheduling poll, nobody voted yet,
+    /// or the Matrix API call fails.
+    pub async fn finalize(&self, room_id: &RoomId) -> Result<u64, SchedulingPollError> {
+        let room = self.room(room_id)?;
+        let mut event_details = load_event_details(&room)
+            .await?
+            .ok_or(SchedulingPollError::EventDetailsNotFound)?;
+        let poll = load_poll(&room)
+            .await?
+            .ok_or(SchedulingPollError::PollNotFound)?;
+        let start_time = poll
+            .winner()
+            .ok_or(SchedulingPollError::NoVotes)?
+            .start_time;
+
+        event_details.end_time = event_details
+            .end_time
+            .map(|end_time| start_time + end_time.saturating_sub(event_details.start_time));
+        event_details.start_time = start_time;
+        room.send_state_event(event_details).await?;
+
+        if !poll.ended {
+            let text = format!("The event is on {}", format_date_option(start_time));
+            room.send(UnstablePollEndEventContent::new(text, poll.poll_event_id))
+                .await?;
+        }
+        Ok(start_time)
+    }
+
+    fn room(&self, room_id: &RoomId) -> Result<Room, SchedulingPollError> {
+        self.client
+            .get_room(room_id)
+            .ok_or(SchedulingPollError::RoomNotFound)
+    }
+}
+
+/// Load the event's scheduling poll, if any, with its current votes.
+///
+/// Messages are loaded back to the poll's start event.
+async fn load_poll(room: &Room) -> Result<Option<SchedulingPoll>, SchedulingPollError> {
+    let Some(poll_event_id) = load_event_details(room)
+        .await?
+        .and_then(|event_details| event_details.scheduling_poll)
+    else {
+        return Ok(None);
+    };
+
+    let Some(poll) = load_poll_events(room, &poll_event_id).await? else {
+        return Ok(None);
+    };
+    let start_times: Vec<u64> = poll
+        .start
+        .poll_start
+        .answers
+        .iter()
+        .filter_map(|answer| answer.id.parse().ok())
+        .collect();
+    Ok(Some(SchedulingPoll {
+        poll_event_id,
+        options: tally_votes(&start_times, &poll.votes, poll.ended_at),
+        ended: poll.ended_at.is_some(),
+    }))
+}
+
+/// Errors that can occur with scheduling polls.
+#[derive(Debug, thiserror::Error)]
+pub enum SchedulingPollError {
+    /// The requested room was not found.
+    #[error("Room not found")]
+    RoomNotFound,
+
+    /// The room has no event details state event.
+    #[error("Event details not found")]
+    EventDetailsNotFound,
+
+    /// The event has no scheduling poll.
+    #[error("Scheduling poll not found")]
+    PollNotFound,
+
+    /// The host already picked a date.
+    #[error("Scheduling poll has ended")]
+    PollEnded,
+
+    /// Nobody voted for any date yet.
+    #[error("No votes yet")]
+    NoVotes,
+
+    /// Too few or too many dates, or a date is in the past.
+    #[error("A scheduling poll needs between 2 and {MAX_SCHEDULING_POLL_OPTIONS} future dates")]
+    InvalidOptions,
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::user_id;
+
+    fn vote(user: &str, answers: &[u64], ts: u32) -> PollVote {
+        PollVote {
+            user_id: user.try_into().unwrap(),
+            answers: answers.iter().map(|answer| answer.to_string()).collect(),
+            timestamp: MilliSecondsSinceUnixEpoch(ts.into()),
+        }
+    }
+
+    #[test]
+    fn test_only_latest_vote_before_end_counts() {
+        let options = tally_votes(
+            &[2000, 1000],
+            &[
+                vote("@alice:example.org", &[1000], 3),
+                vote("@alice:example.org", &[1000, 2000], 1),
+                vote("@bob:example.org", &[2000, 3000], 2),
+                vote("@bob:example.org", &[1000], 9),
+            ],
+            Some(MilliSecondsSinceUnixEpoch(5u32.into())),
+        );
+
+        let alice = user_id!("@alice:example.org");
+        let bob = user_id!("@bob:example.org");
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0].start_time, 1000);
+        assert_eq!(options[0].voters, vec![alice.to_owned()]);
+        assert_eq!(options[1].start_time, 2000);
+        assert_eq!(options[1].voters, vec![bob.to_owned()]);
+    }
+
+    #[test]
+    fn test_winner_prefers_earliest_on_ties() {
+        let poll = SchedulingPoll {
+            poll_event_id: "$poll:example.org".try_into().unwrap(),
+            options: tally_votes(
+                &[1000, 2000, 3000],
+                &[
+                    vote("@alice:example.org", &[2000, 3000], 1),
+                    vote("@bob:example.org", &[3000, 2000], 2),
+                ],
+                None,
+            ),
+            ended: false,
+        };
+
+        assert_eq!(poll.winner().map(|option| option.start_time), Some(2000));
+        assert_eq!(
+            poll.votes_of(user_id!("@bob:example.org")),
+            vec![2000, 3000]
+        );
+    }
+
+    #[test]
+    fn test_no_winner_without_votes() {
+        let poll = SchedulingPoll {
+            poll_event_id: "$poll:example.org".try_into().unwrap(),
+            options: tally_votes(&[1000, 2000], &[vote("@alice:example.org", &[], 1)], None),
+            ended: false,
+        };
+        assert_eq!(poll.winner(), None);
+    }
+}
@@ -0,0 +1,234 @@
+//! Detection of device timezone changes.
+//!
+//! Event times are stored in UTC, so when the user travels across timezones
+//! the wall-clock time of their upcoming events changes. The watcher detects
+//! the change, by the name of the device's IANA timezone, so that event times
+//! can be re-rendered and the user can be told which of their events now start
+//! at a noticeably different local time. Daylight saving time changes within
+//! a timezone aren't reported, since they don't move any event's local time.
+
+use chrono::{DateTime, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use matrix_sdk::ruma::OwnedRoomId;
+
+use crate::social::discovery::DiscoveredEvent;
+
+/// Minimum wall-clock shift, in minutes, for an event to be worth an alert.
+pub const SIGNIFICANT_SHIFT_MINUTES: i64 = 60;
+
+/// A change of the device's timezone.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimezoneChange {
+    /// The IANA name of the timezone before the change, e.g., "Europe/Paris".
+    pub old_zone: String,
+    /// The IANA name of the timezone after the change.
+    pub new_zone: String,
+}
+
+impl TimezoneChange {
+    /// Get the timezone before the change,
+    /// or `None` if its name isn't in the timezone database.
+    pub fn old_tz(&self) -> Option<Tz> {
+        self.old_zone.parse().ok()
+    }
+}
+
+/// Watches the device's timezone for changes.
+#[derive(Clone, Debug)]
+pub struct TimezoneWatcher {
+    /// The IANA name of the last seen timezone, if it could be told.
+    zone: Option<String>,
+}
+
+impl Default for TimezoneWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimezoneWatcher {
+    /// Create a watcher for the current timezone.
+    pub fn new() -> Self {
+        Self {
+            zone: current_zone(),
+        }
+    }
+
+    /// Create a watcher starting from the timezone of the given IANA name.
+    pub fn with_zone(zone: impl Into<String>) -> Self {
+        Self {
+            zone: Some(zone.into()),
+        }
+    }
+
+    /// Check whether the timezone has changed since the last check.
+    pub fn check(&mut self) -> Option<TimezoneChange> {
+        self.check_zone(current_zone()?)
+    }
+
+    /// Record the timezone of the given IANA name,
+    /// returning the change if it differs from the last one.
+    pub fn check_zone(&mut self, zone: String) -> Option<TimezoneChange> {
+        let old_zone = self.zone.replace(zone.clone())?;
+        (old_zone != zone).then_some(TimezoneChange {
+            old_zone,
+            new_zone: zone,
+        })
+    }
+}
+
+/// An upcoming event whose local start time moved after a timezone change.
+#[derive(Clone, Debug)]
+pub struct ShiftedEvent {
+    /// Room ID of the event.
+    pub room_id: OwnedRoomId,
+    /// Title of the event.
+    pub title: String,
+    /// Local start time in the old timezone.
+    pub old_start: NaiveDateTime,
+    /// Local start time in the new timezone.
+    pub new_start: NaiveDateTime,
+}
+
+/// Find upcoming events whose local start time shifted significantly
+/// between two timezones.
+///
+/// Each event's start is converted to both timezones at its own date, so
+/// that events after a daylight saving time change in either timezone are
+/// only reported if their wall-clock time actually differs.
+/// Only events that have not yet ended at `now_ms` are considered.
+pub fn shifted_events<Old: TimeZone, New: TimeZone>(
+    events: &[DiscoveredEvent],
+    old_tz: &Old,
+    new_tz: &New,
+    now_ms: u64,
+) -> Vec<ShiftedEvent> {
+    events
+        .iter()
+        .filter(|event| event.is_upcoming(now_ms))
+        .filter_map(|event| {
+            let start = DateTime::from_timestamp_millis(event.event.start_time as i64)?;
+            let old_start = start.with_timezone(old_tz).naive_local();
+            let new_start = start.with_timezone(new_tz).naive_local();
+            let shift = (new_start - old_start).num_minutes().abs();
+            (shift >= SIGNIFICANT_SHIFT_MINUTES).then(|| ShiftedEvent {
+                room_id: event.room_id.clone(),
+                title: event.event.title.clone(),
+                old_start,
+                new_start,
+            })
+        })
+        .collect()
+}
+
+/// Build a user-facing alert message for events whose local time shifted.
+///
+/// Returns `None` if no events shifted.
+pub fn shift_alert_message(shifted: &[ShiftedEvent]) -> Option<String> {
+    let first = shifted.first()?;
+    let times = |e: &ShiftedEvent| {
+        format!(
+            "\"{}\" now starts at {} (was {})",
+            e.title,
+            e.new_start.format("%a %-I:%M %p"),
+            e.old_start.format("%a %-I:%M %p"),
+        )
+    };
+    Some(match shifted.len() {
+        1 => format!("Your timezone changed. {}.", times(first)),
+        n => format!(
+            "Your timezone changed, so {} upcoming events moved. {}, and {} more.",
+            n,
+            times(first),
+            n - 1,
+        ),
+    })
+}
+
+/// Get the IANA name of the device's current timezone, if it can be told.
+fn current_zone() -> Option<String> {
+    iana_time_zone::get_timezone().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::discovery::EventMembership;
+    use chrono_tz::{Africa, America, Asia, Europe};
+    use robrix_social_events::event::{EventVisibility, SocialEventEventContent};
+
+    fn event(start_time: u64) -> DiscoveredEvent {
+        DiscoveredEvent {
+            room_id: "!event:example.org".try_into().unwrap(),
+            event: SocialEventEventContent {
+                title: "Dinner".to_string(),
+                description: None,
+                start_time,
+                end_time: None,
+                location: None,
+                cover_image: None,
+                visibility: EventVisibility::Private,
+                rsvp_deadline: None,
+                logistics: false,
+                scheduling_poll: None,
+                archival: None,
+            },
+            membership: EventMembership::Joined,
+        }
+    }
+
+    #[test]
+    fn test_watcher_detects_change_once() {
+        let mut watcher = TimezoneWatcher::with_zone("Europe/Paris");
+        assert_eq!(watcher.check_zone("Europe/Paris".to_string()), None);
+
+        let change = watcher.check_zone("America/New_York".to_string()).unwrap();
+        assert_eq!(change.old_zone, "Europe/Paris");
+        assert_eq!(change.old_tz(), Some(Europe::Paris));
+        assert_eq!(watcher.check_zone("America/New_York".to_string()), None);
+    }
+
+    #[test]
+    fn test_shifted_events() {
+        // 2024-01-01T18:00:00Z
+        let start = 1_704_132_000_000;
+        let events = vec![event(start), event(1000)];
+
+        let shifted = shifted_events(&events, &Europe::Paris, &America::New_York, start - 1000);
+        assert_eq!(shifted.len(), 1);
+        assert_eq!(shifted[0].old_start.format("%H:%M").to_string(), "19:00");
+        assert_eq!(shifted[0].new_start.format("%H:%M").to_string(), "13:00");
+        assert!(shift_alert_message(&shifted).is_some());
+    }
+
+    #[test]
+    fn test_events_are_compared_at_their_own_date() {
+        // Paris and Lagos share their offset in winter, but not while Paris
+        // is on daylight saving time
+        let winter = 1_704_132_000_000; // 2024-01-01T18:00:00Z
+        let summer = 1_719_853_200_000; // 2024-07-01T17:00:00Z
+        let events = vec![event(winter), event(summer)];
+
+        let shifted = shifted_events(&events, &Europe::Paris, &Africa::Lagos, 0);
+        assert_eq!(shifted.len(), 1);
+        assert_eq!(
+            shifted[0].old_start.format("%m-%d %H:%M").to_string(),
+            "07-01 19:00"
+        );
+        assert_eq!(
+            shifted[0].new_start.format("%m-%d %H:%M").to_string(),
+            "07-01 18:00"
+        );
+    }
+
+    #[test]
+    fn test_small_shift_is_ignored() {
+        let start = 1_704_132_000_000;
+        let events = vec![event(start)];
+
+        // Kolkata is 30 minutes ahead of Karachi
+        let shifted = shifted_events(&events, &Asia::Karachi, &Asia::Kolkata, 0);
+        assert!(shifted.is_empty());
+        assert_eq!(shift_alert_message(&shifted), None);
+    }
+}
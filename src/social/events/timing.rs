@@ -0,0 +1,121 @@
+//! Event timing helpers.
+//!
+//! Determines whether an event is upcoming, happening now, or over,
+//! and formats countdowns like "in 2 days" or "starting in 20 min".
+
+/// Assumed duration of events that have no end time.
+pub const DEFAULT_EVENT_DURATION_MS: u64 = 2 * HOUR_MS;
+
+const MINUTE_MS: u64 = 60 * 1000;
+const HOUR_MS: u64 = 60 * MINUTE_MS;
+const DAY_MS: u64 = 24 * HOUR_MS;
+
+/// Where an event is in time relative to now.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventTiming {
+    /// The event has not started yet.
+    Upcoming {
+        /// Time until the event starts, in milliseconds.
+        starts_in_ms: u64,
+    },
+    /// The event is happening right now.
+    Live,
+    /// The event is over.
+    Ended,
+}
+
+impl EventTiming {
+    /// Determine the timing of an event at the given time.
+    ///
+    /// Events without an end time are assumed to last
+    /// [`DEFAULT_EVENT_DURATION_MS`].
+    pub fn at(start_ms: u64, end_ms: Option<u64>, now_ms: u64) -> Self {
+        let end_ms = end_ms.unwrap_or(start_ms.saturating_add(DEFAULT_EVENT_DURATION_MS));
+        if now_ms < start_ms {
+            Self::Upcoming {
+                starts_in_ms: start_ms - now_ms,
+            }
+        } else if now_ms < end_ms {
+            Self::Live
+        } else {
+            Self::Ended
+        }
+    }
+
+    /// Check whether the event is happening right now.
+    pub fn is_live(&self) -> bool {
+        matches!(self, Self::Live)
+    }
+
+    /// Get a short countdown text for display on a chip.
+    ///
+    /// Returns `None` for live events, which show a "Happening now"
+    /// indicator instead.
+    pub fn countdown_text(&self) -> Option<String> {
+        match *self {
+            Self::Upcoming { starts_in_ms } if starts_in_ms < MINUTE_MS => {
+                Some("starting now".to_string())
+            }
+            Self::Upcoming { starts_in_ms } if starts_in_ms < HOUR_MS => {
+                Some(format!("starting in {} min", starts_in_ms / MINUTE_MS))
+            }
+            Self::Upcoming { starts_in_ms } if starts_in_ms < DAY_MS => {
+                let hours = starts_in_ms / HOUR_MS;
+                Some(format!("in {} hour{}", hours, plural(hours)))
+            }
+            Self::Upcoming { starts_in_ms } => {
+                let days = starts_in_ms / DAY_MS;
+                Some(format!("in {} day{}", days, plural(days)))
+            }
+            Self::Live => None,
+            Self::Ended => Some("ended".to_string()),
+        }
+    }
+}
+
+/// Get the current time as a Unix timestamp in milliseconds.
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn plural(n: u64) -> &'static str {
+    if n == 1 { "" } else { "s" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing() {
+        assert_eq!(
+            EventTiming::at(1000, Some(2000), 400),
+            EventTiming::Upcoming { starts_in_ms: 600 }
+        );
+        assert_eq!(EventTiming::at(1000, Some(2000), 1500), EventTiming::Live);
+        assert_eq!(EventTiming::at(1000, Some(2000), 2000), EventTiming::Ended);
+    }
+
+    #[test]
+    fn test_default_duration() {
+        assert!(EventTiming::at(0, None, HOUR_MS).is_live());
+        assert_eq!(EventTiming::at(0, None, 3 * HOUR_MS), EventTiming::Ended);
+    }
+
+    #[test]
+    fn test_countdown_text() {
+        let text = |ms| {
+            EventTiming::Upcoming { starts_in_ms: ms }
+                .countdown_text()
+                .unwrap()
+        };
+        assert_eq!(text(30 * 1000), "starting now");
+        assert_eq!(text(20 * MINUTE_MS), "starting in 20 min");
+        assert_eq!(text(HOUR_MS + 5 * MINUTE_MS), "in 1 hour");
+        assert_eq!(text(2 * DAY_MS + HOUR_MS), "in 2 days");
+        assert_eq!(EventTiming::Live.countdown_text(), None);
+    }
+}
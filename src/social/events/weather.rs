@@ -0,0 +1,316 @@
+//! Weather forecasts for upcoming events.
+//!
+//! Events with a physical location that start within the next
+//! [`FORECAST_HORIZON_DAYS`] days show a small forecast chip on their card.
+//! Forecasts come from a pluggable [`WeatherProvider`], which the app sets
+//! with [`set_weather_provider()`]; without one, no forecasts are shown.
+//!
+//! Forecasts are cached per location and date, and fetched through the
+//! Matrix worker (see [`MatrixRequest::FetchEventForecast`]), which signals
+//! the UI once a forecast is available. Nothing is fetched while
+//! [privacy mode](crate::social::privacy::privacy_mode) is on.
+//!
+//! [`MatrixRequest::FetchEventForecast`]: crate::sliding_sync::MatrixRequest::FetchEventForecast
+
+use chrono::{DateTime, Local, NaiveDate};
+use futures_util::future::BoxFuture;
+use makepad_widgets::{error, SignalToUI};
+use robrix_social_events::event::EventLocation;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    sliding_sync::{submit_async_request, MatrixRequest},
+    social::{events::timing::now_ms, privacy::is_privacy_mode},
+};
+
+/// How many days ahead forecasts are shown for.
+pub const FORECAST_HORIZON_DAYS: u64 = 10;
+
+/// How long a fetched forecast is reused before fetching it again.
+const FORECAST_TTL_MS: u64 = 3 * 60 * 60 * 1000;
+
+/// How long to wait before retrying a failed fetch.
+const FORECAST_RETRY_MS: u64 = 30 * 60 * 1000;
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// The overall weather expected for a day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeatherCondition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Rain,
+    Snow,
+    Thunderstorm,
+}
+
+impl WeatherCondition {
+    /// Get an emoji depicting this condition.
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Self::Clear => "☀️",
+            Self::PartlyCloudy => "⛅",
+            Self::Cloudy => "☁️",
+            Self::Fog => "🌫️",
+            Self::Rain => "🌧️",
+            Self::Snow => "❄️",
+            Self::Thunderstorm => "⛈️",
+        }
+    }
+}
+
+/// The forecast for an event's location on the day of the event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forecast {
+    /// The overall weather expected.
+    pub condition: WeatherCondition,
+    /// Highest temperature of the day, in degrees Celsius.
+    pub high_celsius: f32,
+    /// Lowest temperature of the day, in degrees Celsius.
+    pub low_celsius: f32,
+    /// Chance of precipitation, in percent, if the provider has it.
+    pub precipitation_chance: Option<u8>,
+}
+
+impl Forecast {
+    /// Get the short text shown on an event card, e.g., "🌧️ 18° / 12° · 80%".
+    pub fn chip_text(&self) -> String {
+        let mut text = format!(
+            "{} {:.0}° / {:.0}°",
+            self.condition.emoji(),
+            self.high_celsius,
+            self.low_celsius
+        );
+        if let Some(chance) = self.precipitation_chance.filter(|chance| *chance > 0) {
+            text.push_str(&format!(" · {chance}%"));
+        }
+        text
+    }
+}
+
+/// A source of weather forecasts.
+///
+/// Implementations typically query a remote weather service. They are only
+/// called for physical locations (see [`location_key()`]) and dates within
+/// the next [`FORECAST_HORIZON_DAYS`] days.
+pub trait WeatherProvider: Send + Sync {
+    /// Get the forecast for the given location on the given local date.
+    ///
+    /// Returns `Ok(None)` if the provider has no forecast for that location or date.
+    fn forecast<'a>(
+        &'a self,
+        location: &'a EventLocation,
+        date: NaiveDate,
+    ) -> BoxFuture<'a, Result<Option<Forecast>, WeatherError>>;
+}
+
+/// The provider forecasts are fetched from, if any.
+static WEATHER_PROVIDER: Mutex<Option<Arc<dyn WeatherProvider>>> = Mutex::new(None);
+
+/// Set the provider forecasts are fetched from, or `None` to stop showing forecasts.
+///
+/// Cached forecasts are dropped, as they came from the previous provider.
+pub fn set_weather_provider(provider: Option<Arc<dyn WeatherProvider>>) {
+    *WEATHER_PROVIDER.lock().unwrap() = provider;
+    FORECAST_CACHE.lock().unwrap().clear();
+}
+
+/// An entry in the forecast cache.
+#[derive(Clone, Debug)]
+enum CacheEntry {
+    /// The forecast is being fetched.
+    Requested,
+    /// The forecast was fetched at the given time (Unix timestamp in milliseconds).
+    Fetched {
+        forecast: Option<Forecast>,
+        fetched_at: u64,
+    },
+    /// Fetching the forecast failed at the given time (Unix timestamp in milliseconds).
+    Failed { failed_at: u64 },
+}
+
+/// Forecasts by location key and date.
+static FORECAST_CACHE: Mutex<BTreeMap<(String, NaiveDate), CacheEntry>> =
+    Mutex::new(BTreeMap::new());
+
+/// The forecast to show for an event.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ForecastStatus {
+    /// No forecast will be shown for this event.
+    Unavailable,
+    /// The forecast is being fetched; the UI will be signaled once it is.
+    Pending,
+    /// The forecast for the event.
+    Ready(Forecast),
+}
+
+/// Get the key forecasts for a location are cached under,
+/// or `None` if the location isn't a physical place.
+///
+/// Locations with just a name (e.g., "Online" or "My place") can't be
+/// looked up, so only locations with coordinates or an address have a key.
+pub fn location_key(location: &EventLocation) -> Option<String> {
+    let key = location.geo.as_deref().or(location.address.as_deref())?;
+    let key = key.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!key.is_empty()).then(|| key.to_lowercase())
+}
+
+/// Get the local date to show the forecast for, or `None` if an event
+/// is over or starts more than [`FORECAST_HORIZON_DAYS`] days from now.
+pub fn forecast_date(start_ms: u64, end_ms: Option<u64>, now_ms: u64) -> Option<NaiveDate> {
+    let over = end_ms.unwrap_or(start_ms) < now_ms;
+    if over || start_ms > now_ms + FORECAST_HORIZON_DAYS * DAY_MS {
+        return None;
+    }
+    let start = DateTime::from_timestamp_millis(start_ms as i64)?;
+    Some(start.with_timezone(&Local).date_naive())
+}
+
+/// Get the forecast for an event, requesting it if it isn't cached yet.
+///
+/// This is cheap to call on every redraw: each location and date is only
+/// fetched once per [`FORECAST_TTL_MS`].
+pub fn get_or_fetch_forecast(
+    location: &EventLocation,
+    start_ms: u64,
+    end_ms: Option<u64>,
+) -> ForecastStatus {
+    if is_privacy_mode() || WEATHER_PROVIDER.lock().unwrap().is_none() {
+        return ForecastStatus::Unavailable;
+    }
+    let now = now_ms();
+    let (Some(key), Some(date)) = (location_key(location), forecast_date(start_ms, end_ms, now))
+    else {
+        return ForecastStatus::Unavailable;
+    };
+
+    let mut cache = FORECAST_CACHE.lock().unwrap();
+    match cache.get(&(key.clone(), date)) {
+        Some(CacheEntry::Requested) => return ForecastStatus::Pending,
+        Some(CacheEntry::Fetched {
+            forecast,
+            fetched_at,
+        }) if now < fetched_at + FORECAST_TTL_MS => {
+            return forecast
+                .clone()
+                .map_or(ForecastStatus::Unavailable, ForecastStatus::Ready);
+        }
+        Some(CacheEntry::Failed { failed_at }) if now < failed_at + FORECAST_RETRY_MS => {
+            return ForecastStatus::Unavailable;
+        }
+        _ => {}
+    }
+
+    cache.insert((key, date), CacheEntry::Requested);
+    submit_async_request(MatrixRequest::FetchEventForecast {
+        location: location.clone(),
+        date,
+    });
+    ForecastStatus::Pending
+}
+
+/// Fetch the forecast for a location and date from the current provider,
+/// store it in the cache, and signal the UI.
+///
+/// This is run by the Matrix worker for [`MatrixRequest::FetchEventForecast`].
+pub async fn fetch_forecast(location: EventLocation, date: NaiveDate) {
+    let Some(key) = location_key(&location) else {
+        return;
+    };
+    let provider = WEATHER_PROVIDER.lock().unwrap().clone();
+    let result = match provider {
+        // Privacy mode may have been turned on since the request was made
+        Some(provider) if !is_privacy_mode() => provider.forecast(&location, date).await,
+        _ => Ok(None),
+    };
+
+    let entry = match result {
+        Ok(forecast) => CacheEntry::Fetched {
+            forecast,
+            fetched_at: now_ms(),
+        },
+        Err(e) => {
+            error!("Failed to fetch the forecast for {key} on {date}: {e}");
+            CacheEntry::Failed {
+                failed_at: now_ms(),
+            }
+        }
+    };
+    FORECAST_CACHE.lock().unwrap().insert((key, date), entry);
+    SignalToUI::set_ui_signal();
+}
+
+/// Errors that can occur when fetching forecasts.
+#[derive(Debug, thiserror::Error)]
+pub enum WeatherError {
+    /// The weather service couldn't be reached.
+    #[error("Network error: {0}")]
+    Network(String),
+
+    /// The weather service returned something unexpected.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(address: Option<&str>, geo: Option<&str>) -> EventLocation {
+        EventLocation {
+            name: "Central Park".to_string(),
+            address: address.map(str::to_string),
+            geo: geo.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_location_key() {
+        assert_eq!(location_key(&location(None, None)), None);
+        assert_eq!(
+            location_key(&location(Some("  New York,   NY "), None)).as_deref(),
+            Some("new york, ny")
+        );
+        assert_eq!(
+            location_key(&location(
+                Some("New York, NY"),
+                Some("geo:40.7829,-73.9654")
+            ))
+            .as_deref(),
+            Some("geo:40.7829,-73.9654")
+        );
+    }
+
+    #[test]
+    fn test_forecast_date_window() {
+        let now = 1_700_000_000_000;
+        assert!(forecast_date(now + DAY_MS, None, now).is_some());
+        assert!(forecast_date(now + FORECAST_HORIZON_DAYS * DAY_MS + 1, None, now).is_none());
+        assert!(forecast_date(now - DAY_MS, None, now).is_none());
+        // Events that are still going on keep their forecast
+        assert!(forecast_date(now - 1000, Some(now + 1000), now).is_some());
+    }
+
+    #[test]
+    fn test_chip_text() {
+        let forecast = Forecast {
+            condition: WeatherCondition::Rain,
+            high_celsius: 18.4,
+            low_celsius: 11.6,
+            precipitation_chance: Some(80),
+        };
+        assert_eq!(forecast.chip_text(), "🌧️ 18° / 12° · 80%");
+
+        let forecast = Forecast {
+            condition: WeatherCondition::Clear,
+            precipitation_chance: Some(0),
+            ..forecast
+        };
+        assert_eq!(forecast.chip_text(), "☀️ 18° / 12°");
+    }
+}
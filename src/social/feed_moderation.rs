@@ -0,0 +1,312 @@
+//! Moderation of posts and commenters in feed rooms.
+//!
+//! Users with a high enough power level in a feed room, e.g., the feed's
+//! owner, can moderate it from a post's options:
+//! - Removing a post redacts it from the feed.
+//! - Banning a commenter removes them from the feed and prevents them from
+//!   rejoining, so they can no longer comment on its posts.
+//! - Pinning a post adds it to the room's `m.room.pinned_events`. Up to
+//!   [`MAX_PINNED_POSTS`] posts can be pinned to a feed, which are shown
+//!   first when viewing its owner's feed.
+//!
+//! What the current user may do in a feed is described by
+//! [`ModerationPermissions`], loaded with [`FeedModerationService::permissions()`].
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    deserialized_responses::RawSyncOrStrippedState,
+    ruma::{
+        events::{room::pinned_events::RoomPinnedEventsEventContent, StateEventType},
+        EventId, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId, UserId,
+    },
+    Client, Room,
+};
+
+/// Reason given for posts removed by a feed's moderator.
+const REMOVAL_REASON: &str = "Removed by a feed moderator";
+
+/// The maximum number of posts that can be pinned to a feed.
+pub const MAX_PINNED_POSTS: usize = 3;
+
+/// What the current user may do to moderate a feed room.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ModerationPermissions {
+    /// Whether the user can remove other people's posts.
+    pub can_remove_posts: bool,
+    /// Whether the user can ban people from the feed.
+    pub can_ban: bool,
+    /// Whether the user can pin posts to the feed.
+    pub can_pin: bool,
+}
+
+impl ModerationPermissions {
+    /// Whether the user can moderate the feed at all.
+    pub fn is_moderator(&self) -> bool {
+        self.can_remove_posts || self.can_ban || self.can_pin
+    }
+}
+
+/// Actions emitted when moderating a feed room.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum FeedModerationAction {
+    /// What the current user may do in a feed room was loaded,
+    /// along with the room's pinned posts.
+    PermissionsLoaded {
+        room_id: OwnedRoomId,
+        permissions: ModerationPermissions,
+        pinned_posts: Vec<OwnedEventId>,
+    },
+    /// A post was removed from a feed room.
+    PostRemoved {
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+    },
+    /// A user was banned from a feed room.
+    UserBanned {
+        room_id: OwnedRoomId,
+        user_id: OwnedUserId,
+    },
+    /// The pinned posts of a feed room changed.
+    PinnedPostsChanged {
+        room_id: OwnedRoomId,
+        pinned_posts: Vec<OwnedEventId>,
+    },
+    /// A moderation action failed.
+    Failed(String),
+    /// No action.
+    None,
+}
+
+/// Get the pinned posts after pinning or unpinning a post.
+///
+/// Pinned posts are added at the end, so the order they were pinned in is kept.
+pub fn pinned_posts_with(
+    pinned_posts: &[OwnedEventId],
+    event_id: &EventId,
+    pinned: bool,
+) -> Vec<OwnedEventId> {
+    let mut pinned_posts: Vec<_> = pinned_posts
+        .iter()
+        .filter(|pinned_id| *pinned_id != event_id)
+        .cloned()
+        .collect();
+    if pinned {
+        pinned_posts.push(event_id.to_owned());
+    }
+    pinned_posts
+}
+
+/// Get the pinned posts that are shown first in a feed, given all posts pinned in it.
+///
+/// Only the [`MAX_PINNED_POSTS`] most recently pinned posts are shown,
+/// e.g., if more were pinned from another client.
+pub fn shown_pinned_posts(pinned_posts: &[OwnedEventId]) -> &[OwnedEventId] {
+    &pinned_posts[pinned_posts.len().saturating_sub(MAX_PINNED_POSTS)..]
+}
+
+/// Get the posts pinned in a room, in the order they were pinned.
+///
+/// # Errors
+/// Returns an error if the room's state cannot be loaded.
+pub async fn room_pinned_posts(room: &Room) -> Result<Vec<OwnedEventId>, matrix_sdk::Error> {
+    let Some(raw_event) = room
+        .get_state_event_static::<RoomPinnedEventsEventContent>()
+        .await?
+    else {
+        return Ok(Vec::new());
+    };
+
+    // Redacted event state has an empty content, which fails to deserialize.
+    let content = match raw_event {
+        RawSyncOrStrippedState::Sync(raw) => {
+            raw.get_field::<RoomPinnedEventsEventContent>("content")
+        }
+        RawSyncOrStrippedState::Stripped(raw) => {
+            raw.get_field::<RoomPinnedEventsEventContent>("content")
+        }
+    };
+    Ok(content
+        .ok()
+        .flatten()
+        .map(|content| content.pinned)
+        .unwrap_or_default())
+}
+
+/// Service for moderating feed rooms.
+pub struct FeedModerationService {
+    client: Client,
+}
+
+impl FeedModerationService {
+    /// Create a new FeedModerationService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Get what the current user may do to moderate a feed room,
+    /// according to the room's power levels.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, the room is not found,
+    /// or its power levels cannot be loaded.
+    pub async fn permissions(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<ModerationPermissions, FeedModerationError> {
+        let user_id = self
+            .client
+            .user_id()
+            .ok_or(FeedModerationError::NotLoggedIn)?;
+        let power_levels = self.room(room_id)?.power_levels().await?;
+        Ok(ModerationPermissions {
+            can_remove_posts: power_levels.user_can_redact_event_of_other(user_id),
+            can_ban: power_levels.user_can_ban(user_id),
+            can_pin: power_levels.user_can_send_state(user_id, StateEventType::RoomPinnedEvents),
+        })
+    }
+
+    /// Get the posts pinned in a feed room, in the order they were pinned.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or its state cannot be loaded.
+    pub async fn pinned_posts(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<Vec<OwnedEventId>, FeedModerationError> {
+        Ok(room_pinned_posts(&self.room(room_id)?).await?)
+    }
+
+    /// Remove someone's post from a feed room by redacting it.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the user may not remove
+    /// posts from it, or the redaction fails.
+    pub async fn remove_post(
+        &self,
+        room_id: &RoomId,
+        event_id: &EventId,
+    ) -> Result<(), FeedModerationError> {
+        if !self.permissions(room_id).await?.can_remove_posts {
+            return Err(FeedModerationError::NotPermitted);
+        }
+        self.room(room_id)?
+            .redact(event_id, Some(REMOVAL_REASON), None)
+            .await
+            .map_err(|e| FeedModerationError::MatrixError(e.into()))?;
+        Ok(())
+    }
+
+    /// Ban a commenter from a feed room, so they can't comment on its posts anymore.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the user may not ban
+    /// people from it, or the ban fails.
+    pub async fn ban_commenter(
+        &self,
+        room_id: &RoomId,
+        user_id: &UserId,
+        reason: Option<&str>,
+    ) -> Result<(), FeedModerationError> {
+        if !self.permissions(room_id).await?.can_ban {
+            return Err(FeedModerationError::NotPermitted);
+        }
+        self.room(room_id)?.ban_user(user_id, reason).await?;
+        Ok(())
+    }
+
+    /// Pin a post to a feed room, or unpin it if `pinned` is false.
+    ///
+    /// Returns the room's pinned posts afterwards.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the user may not pin
+    /// posts to it, [`MAX_PINNED_POSTS`] posts are pinned already,
+    /// or its pinned events cannot be updated.
+    pub async fn pin_post(
+        &self,
+        room_id: &RoomId,
+        event_id: &EventId,
+        pinned: bool,
+    ) -> Result<Vec<OwnedEventId>, FeedModerationError> {
+        if !self.permissions(room_id).await?.can_pin {
+            return Err(FeedModerationError::NotPermitted);
+        }
+        let current = self.pinned_posts(room_id).await?;
+        let is_new_pin = pinned && !current.iter().any(|pinned_id| pinned_id == event_id);
+        if is_new_pin && current.len() >= MAX_PINNED_POSTS {
+            return Err(FeedModerationError::TooManyPinnedPosts);
+        }
+        let pinned_posts = pinned_posts_with(&current, event_id, pinned);
+        self.room(room_id)?
+            .send_state_event(RoomPinnedEventsEventContent::new(pinned_posts.clone()))
+            .await?;
+        Ok(pinned_posts)
+    }
+
+    /// Get a joined feed room.
+    fn room(&self, room_id: &RoomId) -> Result<Room, FeedModerationError> {
+        self.client
+            .get_room(room_id)
+            .ok_or(FeedModerationError::RoomNotFound)
+    }
+}
+
+/// Errors that can occur when moderating a feed room.
+#[derive(Debug, thiserror::Error)]
+pub enum FeedModerationError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The feed room was not found.
+    #[error("Feed room not found")]
+    RoomNotFound,
+
+    /// The user's power level in the feed room is too low.
+    #[error("You don't have permission to do this in this feed")]
+    NotPermitted,
+
+    /// The feed has as many pinned posts as it can have.
+    #[error("Only {} posts can be pinned, unpin one first", MAX_PINNED_POSTS)]
+    TooManyPinnedPosts,
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{event_id, owned_event_id};
+
+    #[test]
+    fn test_pinned_posts_with() {
+        let pinned = vec![
+            owned_event_id!("$a:example.org"),
+            owned_event_id!("$b:example.org"),
+        ];
+
+        let added = pinned_posts_with(&pinned, event_id!("$c:example.org"), true);
+        assert_eq!(added.len(), 3);
+        assert_eq!(added[2], "$c:example.org");
+
+        // Pinning a pinned post again moves it to the end
+        let repinned = pinned_posts_with(&pinned, event_id!("$a:example.org"), true);
+        assert_eq!(repinned, vec![pinned[1].clone(), pinned[0].clone()]);
+
+        let removed = pinned_posts_with(&pinned, event_id!("$a:example.org"), false);
+        assert_eq!(removed, vec![pinned[1].clone()]);
+        assert!(pinned_posts_with(&[], event_id!("$a:example.org"), false).is_empty());
+    }
+
+    #[test]
+    fn test_shown_pinned_posts() {
+        let pinned: Vec<_> = (1..=5)
+            .map(|i| OwnedEventId::try_from(format!("${i}:example.org")).unwrap())
+            .collect();
+        assert_eq!(shown_pinned_posts(&pinned), &pinned[2..]);
+        assert_eq!(shown_pinned_posts(&pinned[..2]), &pinned[..2]);
+        assert!(shown_pinned_posts(&[]).is_empty());
+    }
+}
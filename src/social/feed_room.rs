@@ -4,23 +4,62 @@
 //! - Public feed: Anyone can read
 //! - Friends feed: Only friends can read (restricted join)
 //! - Close friends feed: Invite-only
+//!
+//! Friends and close friends feeds can be end-to-end encrypted when they're
+//! created (see [`FeedRoomService::create_feed_room()`]).
+//!
+//! A feed room is marked by its room type (see [`FeedPrivacy::room_type()`]),
+//! and its owner is the room's creator (see [`feed_of_room()`]). Both are set
+//! in the room's `m.room.create` event, which can't be changed afterwards.
+//! The topic is only display text: any member allowed to change it could
+//! claim someone else's feed, or a different privacy level.
+//!
+//! Feed admins can configure a feed with the `org.social.feed_config`
+//! state event, e.g., to restrict reactions to a curated set of emoji, or
+//! the kinds and size of the media attached to posts.
 
 use matrix_sdk::{
+    deserialized_responses::RawSyncOrStrippedState,
     ruma::{
-        api::client::room::create_room::v3::Request as CreateRoomRequest,
-        events::room::{
-            history_visibility::{HistoryVisibility, RoomHistoryVisibilityEventContent},
-            join_rules::{JoinRule, RoomJoinRulesEventContent},
+        api::client::{
+            error::ErrorKind,
+            room::create_room::v3::{CreationContent, Request as CreateRoomRequest},
+        },
+        events::{
+            room::{
+                encryption::RoomEncryptionEventContent,
+                history_visibility::{HistoryVisibility, RoomHistoryVisibilityEventContent},
+                join_rules::{JoinRule, RoomJoinRulesEventContent},
+            },
+            InitialStateEvent,
         },
-        OwnedRoomId, RoomId, UserId,
+        room::RoomType,
+        serde::Raw,
+        OwnedRoomAliasId, OwnedRoomId, OwnedUserId, RoomAliasId, RoomId, UserId,
     },
-    Client,
+    Client, Room,
 };
+use robrix_social_events::feed_config::{MediaKind, SocialFeedConfigEventContent};
+use serde::{Deserialize, Serialize};
+
+/// The prefix of the room types of feed rooms, followed by their privacy level,
+/// e.g., `org.social.feed.friends`.
+pub const FEED_ROOM_TYPE_PREFIX: &str = "org.social.feed.";
+
+/// All feed privacy levels, from the most public to the most private.
+const ALL_PRIVACY_LEVELS: [FeedPrivacy; 3] = [
+    FeedPrivacy::Public,
+    FeedPrivacy::Friends,
+    FeedPrivacy::CloseFriends,
+];
 
 /// Feed privacy level.
 ///
 /// Determines who can read posts in a feed room and how users can join.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
 pub enum FeedPrivacy {
     /// Anyone can read, public room directory.
     #[default]
@@ -59,6 +98,14 @@ impl FeedPrivacy {
         }
     }
 
+    /// Check whether feed rooms of this privacy level can be end-to-end encrypted.
+    ///
+    /// Public feeds can't be, since anyone must be able to read them,
+    /// including people who haven't joined them.
+    pub fn can_be_encrypted(&self) -> bool {
+        matches!(self, Self::Friends | Self::CloseFriends)
+    }
+
     /// Get the feed type name for display and room naming.
     pub fn feed_name(&self) -> &'static str {
         match self {
@@ -76,6 +123,101 @@ impl FeedPrivacy {
             Self::CloseFriends => "_close",
         }
     }
+
+    /// Get the room alias of the feed of this type owned by `owner`,
+    /// e.g., `#alice_public:example.org`.
+    pub fn room_alias(&self, owner: &UserId) -> Option<OwnedRoomAliasId> {
+        format!(
+            "#{}{}:{}",
+            owner.localpart(),
+            self.alias_suffix(),
+            owner.server_name()
+        )
+        .try_into()
+        .ok()
+    }
+
+    /// Get the topic shown for a feed room of this type owned by `owner`.
+    ///
+    /// The topic is only display text; feed rooms are identified with [`feed_of_room()`].
+    pub fn room_topic(&self, owner: &UserId) -> String {
+        format!("Social feed room ({}) for {}", self, owner)
+    }
+
+    /// Get the room type of feed rooms of this privacy level,
+    /// set in their `m.room.create` event when they're created.
+    pub fn room_type(&self) -> RoomType {
+        let name = match self {
+            Self::Public => "public",
+            Self::Friends => "friends",
+            Self::CloseFriends => "close_friends",
+        };
+        RoomType::from(format!("{FEED_ROOM_TYPE_PREFIX}{name}"))
+    }
+
+    /// Get the privacy level of feed rooms of the given room type,
+    /// or `None` if it isn't a feed room type.
+    pub fn from_room_type(room_type: &RoomType) -> Option<Self> {
+        ALL_PRIVACY_LEVELS
+            .into_iter()
+            .find(|privacy| privacy.room_type() == *room_type)
+    }
+}
+
+/// Identify a feed room from its creator, room type and canonical alias.
+///
+/// Returns the feed's privacy level and owner, or `None` if the room isn't a feed.
+/// The owner is always the room's creator. The privacy level is given by the room
+/// type; feeds created before they had a room type are recognized by their
+/// canonical alias instead, if it's the creator's alias for a feed of that
+/// privacy level (see [`FeedPrivacy::room_alias()`]), since servers only let
+/// rooms use aliases that point to them.
+pub fn identify_feed(
+    creator: &UserId,
+    room_type: Option<&RoomType>,
+    canonical_alias: Option<&RoomAliasId>,
+) -> Option<(FeedPrivacy, OwnedUserId)> {
+    let privacy = match room_type {
+        Some(room_type) => FeedPrivacy::from_room_type(room_type)?,
+        None => {
+            let alias = canonical_alias?;
+            ALL_PRIVACY_LEVELS
+                .into_iter()
+                .find(|privacy| privacy.room_alias(creator).as_deref() == Some(alias))?
+        }
+    };
+    Some((privacy, creator.to_owned()))
+}
+
+/// Get the privacy level and owner of a feed room,
+/// or `None` if the room isn't a feed or its creation event isn't known yet.
+///
+/// See [`identify_feed()`] for how feed rooms are recognized.
+pub fn feed_of_room(room: &Room) -> Option<(FeedPrivacy, OwnedUserId)> {
+    let create = room.create_content()?;
+    identify_feed(
+        &create.creator,
+        create.room_type.as_ref(),
+        room.canonical_alias().as_deref(),
+    )
+}
+
+/// Find the feed of the given privacy level owned by `owner` among rooms
+/// identified with [`feed_of_room()`].
+pub fn find_feed<R>(
+    rooms: impl IntoIterator<Item = (Option<(FeedPrivacy, OwnedUserId)>, R)>,
+    owner: &UserId,
+    privacy: FeedPrivacy,
+) -> Option<R> {
+    rooms.into_iter().find_map(|(feed, room)| {
+        feed.is_some_and(|(p, o)| p == privacy && o == owner)
+            .then_some(room)
+    })
+}
+
+/// Check whether a room is a feed room (see [`feed_of_room()`]).
+pub fn is_feed_room(room: &Room) -> bool {
+    feed_of_room(room).is_some()
 }
 
 impl std::fmt::Display for FeedPrivacy {
@@ -110,6 +252,31 @@ impl UserFeeds {
         }
     }
 
+    /// Collect the feeds owned by `owner` among rooms identified with [`feed_of_room()`].
+    ///
+    /// If `owner` has several feeds of the same privacy level, the first one is kept.
+    pub fn find(
+        rooms: impl IntoIterator<Item = (Option<(FeedPrivacy, OwnedUserId)>, OwnedRoomId)>,
+        owner: &UserId,
+    ) -> Self {
+        let mut feeds = Self::default();
+        for (feed, room_id) in rooms {
+            let Some((privacy, feed_owner)) = feed else {
+                continue;
+            };
+            if feed_owner != owner {
+                continue;
+            }
+            let feed = match privacy {
+                FeedPrivacy::Public => &mut feeds.public,
+                FeedPrivacy::Friends => &mut feeds.friends,
+                FeedPrivacy::CloseFriends => &mut feeds.close_friends,
+            };
+            feed.get_or_insert(room_id);
+        }
+        feeds
+    }
+
     /// Get all existing feed room IDs.
     pub fn all(&self) -> Vec<&OwnedRoomId> {
         [&self.public, &self.friends, &self.close_friends]
@@ -117,6 +284,24 @@ impl UserFeeds {
             .flatten()
             .collect()
     }
+
+    /// Get the feed room IDs for the given privacy levels, e.g., to cross-post to them.
+    ///
+    /// # Errors
+    /// Returns [`FeedRoomError::FeedNotFound`] if any of the feeds doesn't exist.
+    pub fn targets(
+        &self,
+        privacy_levels: &[FeedPrivacy],
+    ) -> Result<Vec<OwnedRoomId>, FeedRoomError> {
+        privacy_levels
+            .iter()
+            .map(|privacy| {
+                self.get(*privacy)
+                    .cloned()
+                    .ok_or(FeedRoomError::FeedNotFound)
+            })
+            .collect()
+    }
 }
 
 /// Service for managing feed rooms.
@@ -138,21 +323,57 @@ impl FeedRoomService {
     /// # Arguments
     /// * `privacy` - The privacy level for the feed room
     /// * `friends_space_id` - The user's friends space ID (required for Friends privacy)
+    /// * `encrypted` - Whether to enable end-to-end encryption in the room
+    ///   (see [`FeedPrivacy::can_be_encrypted()`])
     ///
     /// # Errors
     /// Returns an error if the user is not logged in, if a feed room with
-    /// the same privacy level already exists, or if the Matrix API call fails.
+    /// the same privacy level already exists, if encryption is requested for
+    /// a public feed, or if the Matrix API call fails.
     pub async fn create_feed_room(
         &self,
         privacy: FeedPrivacy,
         friends_space_id: Option<&RoomId>,
+        encrypted: bool,
     ) -> Result<OwnedRoomId, FeedRoomError> {
         let user_id = self.client.user_id().ok_or(FeedRoomError::NotLoggedIn)?;
+        if encrypted && !privacy.can_be_encrypted() {
+            return Err(FeedRoomError::InvalidConfiguration(format!(
+                "{privacy} can't be encrypted"
+            )));
+        }
 
         // Build room creation request with appropriate settings
         let mut request = CreateRoomRequest::new();
         request.name = Some(format!("{}'s {}", user_id.localpart(), privacy.feed_name()));
-        request.topic = Some(format!("Social feed room ({}) for {}", privacy, user_id));
+        request.topic = Some(privacy.room_topic(user_id));
+        let mut creation_content = CreationContent::new();
+        creation_content.room_type = Some(privacy.room_type());
+        request.creation_content = Some(
+            Raw::new(&creation_content)
+                .map_err(|e| FeedRoomError::InvalidConfiguration(e.to_string()))?,
+        );
+        // Others find the public feed by its alias in order to follow it
+        if privacy == FeedPrivacy::Public {
+            request.room_alias_name =
+                Some(format!("{}{}", user_id.localpart(), privacy.alias_suffix()));
+        }
+
+        // Set the join rules, history visibility and encryption in the creation request,
+        // so that nothing is ever posted to the room before they're in place
+        let join_rules = RoomJoinRulesEventContent::new(privacy.join_rule(friends_space_id));
+        let history_visibility =
+            RoomHistoryVisibilityEventContent::new(privacy.history_visibility());
+        request.initial_state = vec![
+            InitialStateEvent::new(join_rules).to_raw_any(),
+            InitialStateEvent::new(history_visibility).to_raw_any(),
+        ];
+        if encrypted {
+            let encryption = RoomEncryptionEventContent::with_recommended_defaults();
+            request
+                .initial_state
+                .push(InitialStateEvent::new(encryption).to_raw_any());
+        }
 
         // Create the room
         let response = self
@@ -161,40 +382,35 @@ impl FeedRoomService {
             .await
             .map_err(FeedRoomError::MatrixError)?;
 
-        let room_id = response.room_id().to_owned();
-
-        // Get the room and configure it
-        if let Some(room) = self.client.get_room(&room_id) {
-            // Set join rules based on privacy level
-            let join_rules = RoomJoinRulesEventContent::new(privacy.join_rule(friends_space_id));
-            room.send_state_event(join_rules)
-                .await
-                .map_err(FeedRoomError::MatrixError)?;
-
-            // Set history visibility
-            let history_visibility =
-                RoomHistoryVisibilityEventContent::new(privacy.history_visibility());
-            room.send_state_event(history_visibility)
-                .await
-                .map_err(FeedRoomError::MatrixError)?;
-        }
-
-        Ok(room_id)
+        Ok(response.room_id().to_owned())
     }
 
     /// Get all feed rooms for a user.
     ///
-    /// This discovers feed rooms by looking for rooms with specific
-    /// naming conventions or tags.
+    /// Feeds are found among the joined rooms (see [`UserFeeds::find()`]).
+    /// A public feed that isn't joined is found by its alias
+    /// (see [`FeedPrivacy::room_alias()`]), since anyone can read it;
+    /// friends and close friends feeds can only be found once they're joined.
     ///
-    /// # Note
-    /// This is a placeholder implementation. Full discovery would involve
-    /// checking room state events or maintaining a mapping in the user's
-    /// profile room.
-    pub async fn get_user_feeds(&self, _user_id: &UserId) -> Result<UserFeeds, FeedRoomError> {
-        // TODO: Implement feed discovery by checking room state or profile room
-        // For now, return empty feeds
-        Ok(UserFeeds::default())
+    /// # Errors
+    /// Returns an error if the public feed's alias cannot be resolved,
+    /// other than because it doesn't exist.
+    pub async fn get_user_feeds(&self, user_id: &UserId) -> Result<UserFeeds, FeedRoomError> {
+        let rooms = self.client.joined_rooms().into_iter();
+        let mut feeds = UserFeeds::find(
+            rooms.map(|room| (feed_of_room(&room), room.room_id().to_owned())),
+            user_id,
+        );
+        if feeds.public.is_none()
+            && let Some(alias) = FeedPrivacy::Public.room_alias(user_id)
+        {
+            feeds.public = match self.client.resolve_room_alias(&alias).await {
+                Ok(response) => Some(response.room_id),
+                Err(e) if e.client_api_error_kind() == Some(&ErrorKind::NotFound) => None,
+                Err(e) => return Err(FeedRoomError::MatrixError(e.into())),
+            };
+        }
+        Ok(feeds)
     }
 
     /// Get the current user's feed rooms.
@@ -203,6 +419,32 @@ impl FeedRoomService {
         self.get_user_feeds(user_id).await
     }
 
+    /// Find a joined feed room of the given privacy level owned by `owner`.
+    pub fn find_joined_feed(&self, owner: &UserId, privacy: FeedPrivacy) -> Option<Room> {
+        let rooms = self.client.joined_rooms().into_iter();
+        find_feed(
+            rooms.map(|room| (feed_of_room(&room), room)),
+            owner,
+            privacy,
+        )
+    }
+
+    /// Get all joined public feed rooms owned by other users, with their owners.
+    ///
+    /// These are the feeds the current user follows.
+    pub fn joined_public_feeds(&self) -> Result<Vec<(OwnedUserId, Room)>, FeedRoomError> {
+        let user_id = self.client.user_id().ok_or(FeedRoomError::NotLoggedIn)?;
+        Ok(self
+            .client
+            .joined_rooms()
+            .into_iter()
+            .filter_map(|room| {
+                let (privacy, owner) = feed_of_room(&room)?;
+                (privacy == FeedPrivacy::Public && owner != user_id).then_some((owner, room))
+            })
+            .collect())
+    }
+
     /// Join a user's feed room.
     ///
     /// # Arguments
@@ -228,6 +470,96 @@ impl FeedRoomService {
         }
         Ok(())
     }
+
+    /// Get a feed's configuration.
+    ///
+    /// Feeds without a configuration, or with a redacted one, get the default configuration.
+    ///
+    /// # Errors
+    /// Returns an error if the feed is not found or its state cannot be read.
+    pub async fn feed_config(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<SocialFeedConfigEventContent, FeedRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(FeedRoomError::FeedNotFound)?;
+        let Some(raw_event) = room
+            .get_state_event_static::<SocialFeedConfigEventContent>()
+            .await
+            .map_err(FeedRoomError::MatrixError)?
+        else {
+            return Ok(SocialFeedConfigEventContent::default());
+        };
+
+        // Redacted event state has an empty content, which fails to deserialize.
+        let content = match raw_event {
+            RawSyncOrStrippedState::Sync(raw) => raw.get_field("content"),
+            RawSyncOrStrippedState::Stripped(raw) => raw.get_field("content"),
+        };
+        Ok(content.ok().flatten().unwrap_or_default())
+    }
+
+    /// Restrict reactions to posts in a feed to the given emoji,
+    /// or allow any emoji if `allowed_reactions` is `None`.
+    ///
+    /// # Errors
+    /// Returns an error if the feed is not found, or the current user
+    /// is not allowed to change the feed's configuration.
+    pub async fn set_allowed_reactions(
+        &self,
+        room_id: &RoomId,
+        allowed_reactions: Option<Vec<String>>,
+    ) -> Result<(), FeedRoomError> {
+        self.update_feed_config(room_id, |config| {
+            config.allowed_reactions = allowed_reactions;
+        })
+        .await
+    }
+
+    /// Restrict the media attached to posts in a feed to the given kinds and
+    /// maximum size in bytes. `None` lifts the respective restriction.
+    ///
+    /// Posts that break the restrictions anyway are annotated for the feed's
+    /// moderators in the newsfeed (see [`FeedItem::media_violation`](crate::social::FeedItem::media_violation)).
+    ///
+    /// # Errors
+    /// Returns an error if the feed is not found, or the current user
+    /// is not allowed to change the feed's configuration.
+    pub async fn set_media_restrictions(
+        &self,
+        room_id: &RoomId,
+        allowed_media: Option<Vec<MediaKind>>,
+        max_media_size: Option<u64>,
+    ) -> Result<(), FeedRoomError> {
+        self.update_feed_config(room_id, |config| {
+            config.allowed_media = allowed_media;
+            config.max_media_size = max_media_size;
+        })
+        .await
+    }
+
+    /// Change a feed's configuration and send it to the feed.
+    async fn update_feed_config(
+        &self,
+        room_id: &RoomId,
+        update: impl FnOnce(&mut SocialFeedConfigEventContent),
+    ) -> Result<(), FeedRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(FeedRoomError::FeedNotFound)?;
+        let mut config = self.feed_config(room_id).await?;
+        update(&mut config);
+        match room.send_state_event(config).await {
+            Ok(_) => Ok(()),
+            Err(e) if matches!(e.client_api_error_kind(), Some(ErrorKind::Forbidden { .. })) => {
+                Err(FeedRoomError::AccessDenied)
+            }
+            Err(e) => Err(FeedRoomError::MatrixError(e)),
+        }
+    }
 }
 
 /// Errors that can occur when working with feed rooms.
@@ -275,6 +607,57 @@ mod tests {
         assert_eq!(FeedPrivacy::CloseFriends.to_string(), "Close Friends Feed");
     }
 
+    #[test]
+    fn test_room_type_round_trip() {
+        for privacy in ALL_PRIVACY_LEVELS {
+            assert_eq!(
+                FeedPrivacy::from_room_type(&privacy.room_type()),
+                Some(privacy)
+            );
+        }
+        assert_eq!(
+            FeedPrivacy::Friends.room_type().as_str(),
+            "org.social.feed.friends"
+        );
+        assert_eq!(FeedPrivacy::from_room_type(&RoomType::Space), None);
+    }
+
+    #[test]
+    fn test_identify_feed() {
+        let alice: OwnedUserId = "@alice:example.org".try_into().unwrap();
+        let mallory: OwnedUserId = "@mallory:example.org".try_into().unwrap();
+        let close_friends = FeedPrivacy::CloseFriends.room_type();
+
+        // The owner is the creator, whatever else the room claims
+        assert_eq!(
+            identify_feed(&alice, Some(&close_friends), None),
+            Some((FeedPrivacy::CloseFriends, alice.clone()))
+        );
+        assert_eq!(
+            identify_feed(&mallory, Some(&close_friends), None),
+            Some((FeedPrivacy::CloseFriends, mallory.clone()))
+        );
+        assert_eq!(identify_feed(&alice, Some(&RoomType::Space), None), None);
+
+        // Feeds without a room type are recognized by their creator's feed alias
+        let alias = FeedPrivacy::Public.room_alias(&alice).unwrap();
+        assert_eq!(
+            identify_feed(&alice, None, Some(&alias)),
+            Some((FeedPrivacy::Public, alice.clone()))
+        );
+        assert_eq!(identify_feed(&mallory, None, Some(&alias)), None);
+        assert_eq!(identify_feed(&alice, None, None), None);
+    }
+
+    #[test]
+    fn test_room_alias() {
+        let owner: OwnedUserId = "@alice:example.org".try_into().unwrap();
+        assert_eq!(
+            FeedPrivacy::Public.room_alias(&owner).unwrap().as_str(),
+            "#alice_public:example.org"
+        );
+    }
+
     #[test]
     fn test_user_feeds_has_any() {
         let empty = UserFeeds::default();
@@ -286,4 +669,46 @@ mod tests {
         };
         assert!(with_public.has_any());
     }
+
+    #[test]
+    fn test_user_feeds_find() {
+        let alice: OwnedUserId = "@alice:example.org".try_into().unwrap();
+        let bob: OwnedUserId = "@bob:example.org".try_into().unwrap();
+        let room = |id: &str| -> OwnedRoomId { format!("!{id}:example.org").try_into().unwrap() };
+        let feeds = UserFeeds::find(
+            [
+                (None, room("chat")),
+                (Some((FeedPrivacy::Public, bob.clone())), room("bob_public")),
+                (Some((FeedPrivacy::Friends, alice.clone())), room("friends")),
+                (Some((FeedPrivacy::Public, alice.clone())), room("public")),
+                (Some((FeedPrivacy::Friends, alice.clone())), room("other")),
+            ],
+            &alice,
+        );
+        assert_eq!(feeds.public, Some(room("public")));
+        assert_eq!(feeds.friends, Some(room("friends")));
+        assert_eq!(feeds.close_friends, None);
+        assert!(!UserFeeds::find([], &alice).has_any());
+    }
+
+    #[test]
+    fn test_user_feeds_targets() {
+        let public: OwnedRoomId = "!public:example.org".try_into().unwrap();
+        let friends: OwnedRoomId = "!friends:example.org".try_into().unwrap();
+        let feeds = UserFeeds {
+            public: Some(public.clone()),
+            friends: Some(friends.clone()),
+            close_friends: None,
+        };
+        assert_eq!(
+            feeds
+                .targets(&[FeedPrivacy::Public, FeedPrivacy::Friends])
+                .unwrap(),
+            vec![public, friends]
+        );
+        assert!(matches!(
+            feeds.targets(&[FeedPrivacy::Public, FeedPrivacy::CloseFriends]),
+            Err(FeedRoomError::FeedNotFound)
+        ));
+    }
 }
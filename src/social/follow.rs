@@ -0,0 +1,272 @@
+//! Following other users' public feeds.
+//!
+//! Following is a one-way subscription, unlike friendship: the follower
+//! joins the followed user's public feed room, found by its alias (see
+//! [`FeedPrivacy::room_alias()`]), and the follow is recorded in the
+//! follower's `org.social.following` account data. Joined public feeds
+//! remain the source of truth for who is followed, so follows made
+//! on other clients are still recognized.
+//!
+//! The current user's followers and followed users are listed through the
+//! Matrix worker (see [`MatrixRequest::LoadFollowers`] and
+//! [`MatrixRequest::LoadFollowing`]), which posts a [`FollowListsAction`].
+//!
+//! [`MatrixRequest::LoadFollowers`]: crate::sliding_sync::MatrixRequest::LoadFollowers
+//! [`MatrixRequest::LoadFollowing`]: crate::sliding_sync::MatrixRequest::LoadFollowing
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    ruma::{
+        api::client::error::ErrorKind, MilliSecondsSinceUnixEpoch, OwnedRoomId, OwnedUserId, UserId,
+    },
+    Client,
+};
+use robrix_social_events::follow::{FollowedFeed, SocialFollowingEventContent};
+
+use crate::social::{
+    feed_room::{FeedPrivacy, FeedRoomError, FeedRoomService},
+    profile_stats::{ProfileStatsError, ProfileStatsService},
+};
+
+/// A user in the current user's followers or following list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FollowListUser {
+    /// The user's ID.
+    pub user_id: OwnedUserId,
+    /// Whether the current user follows this user.
+    pub is_followed: bool,
+}
+
+/// Actions emitted when the current user's followers or following list was loaded.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum FollowListsAction {
+    /// The current user's followers were loaded.
+    FollowersLoaded(Vec<FollowListUser>),
+    /// The users the current user follows were loaded.
+    FollowingLoaded(Vec<FollowListUser>),
+    /// Loading one of the lists failed.
+    Failed(String),
+    /// No action.
+    None,
+}
+
+/// Service for following and unfollowing other users.
+pub struct FollowService {
+    client: Client,
+}
+
+impl FollowService {
+    /// Create a new FollowService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Whether the current user follows `user_id`, i.e., has joined their public feed.
+    pub fn is_following(&self, user_id: &UserId) -> bool {
+        FeedRoomService::new(self.client.clone())
+            .find_joined_feed(user_id, FeedPrivacy::Public)
+            .is_some()
+    }
+
+    /// List the members of the current user's public feed, i.e., their followers,
+    /// along with whether the current user follows each of them back.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or room members cannot be loaded.
+    pub async fn list_followers(&self) -> Result<Vec<FollowListUser>, FollowError> {
+        let followers = ProfileStatsService::new(self.client.clone())
+            .list_followers()
+            .await?;
+        Ok(followers
+            .into_iter()
+            .map(|user_id| FollowListUser {
+                is_followed: self.is_following(&user_id),
+                user_id,
+            })
+            .collect())
+    }
+
+    /// List the owners of the public feeds the current user has joined.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in.
+    pub fn list_following(&self) -> Result<Vec<FollowListUser>, FollowError> {
+        let following = ProfileStatsService::new(self.client.clone()).list_following()?;
+        Ok(following
+            .into_iter()
+            .map(|user_id| FollowListUser {
+                user_id,
+                is_followed: true,
+            })
+            .collect())
+    }
+
+    /// Get the follows recorded in the current user's account data.
+    ///
+    /// # Errors
+    /// Returns an error if the account data cannot be loaded or parsed.
+    pub async fn following(&self) -> Result<SocialFollowingEventContent, FollowError> {
+        let Some(raw) = self
+            .client
+            .account()
+            .account_data::<SocialFollowingEventContent>()
+            .await
+            .map_err(FollowError::MatrixError)?
+        else {
+            return Ok(SocialFollowingEventContent::default());
+        };
+        Ok(raw.deserialize()?)
+    }
+
+    /// Follow a user by joining their public feed.
+    ///
+    /// Following a user whose public feed is already joined only records the follow.
+    ///
+    /// # Returns
+    /// The room ID of the followed public feed.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, tries to follow
+    /// themselves, or the followed user has no public feed.
+    pub async fn follow(&self, user_id: &UserId) -> Result<OwnedRoomId, FollowError> {
+        let own_user_id = self.client.user_id().ok_or(FollowError::NotLoggedIn)?;
+        if user_id == own_user_id {
+            return Err(FollowError::CannotFollowSelf);
+        }
+
+        let feed_service = FeedRoomService::new(self.client.clone());
+        let room_id = match feed_service.find_joined_feed(user_id, FeedPrivacy::Public) {
+            Some(room) => room.room_id().to_owned(),
+            None => {
+                let alias = FeedPrivacy::Public
+                    .room_alias(user_id)
+                    .ok_or(FollowError::FeedNotFound)?;
+                match self
+                    .client
+                    .join_room_by_id_or_alias((&*alias).into(), &[])
+                    .await
+                {
+                    Ok(room) => room.room_id().to_owned(),
+                    Err(e) if e.client_api_error_kind() == Some(&ErrorKind::NotFound) => {
+                        return Err(FollowError::FeedNotFound);
+                    }
+                    Err(e) => return Err(FollowError::MatrixError(e)),
+                }
+            }
+        };
+
+        let mut following = self.following().await?;
+        following.following.insert(
+            user_id.to_owned(),
+            FollowedFeed {
+                room_id: room_id.clone(),
+                since: MilliSecondsSinceUnixEpoch::now(),
+            },
+        );
+        self.save_following(following).await?;
+        Ok(room_id)
+    }
+
+    /// Unfollow a user by leaving their public feed.
+    ///
+    /// Unfollowing a user who isn't followed does nothing.
+    ///
+    /// # Errors
+    /// Returns an error if the feed cannot be left or the account data cannot be updated.
+    pub async fn unfollow(&self, user_id: &UserId) -> Result<(), FollowError> {
+        let mut following = self.following().await?;
+        let record = following.following.remove(user_id);
+
+        let feed_service = FeedRoomService::new(self.client.clone());
+        let room_id = feed_service
+            .find_joined_feed(user_id, FeedPrivacy::Public)
+            .map(|room| room.room_id().to_owned())
+            .or_else(|| record.as_ref().map(|r| r.room_id.clone()));
+        if let Some(room_id) = room_id {
+            feed_service.leave_feed(&room_id).await?;
+        }
+
+        if record.is_some() {
+            self.save_following(following).await?;
+        }
+        Ok(())
+    }
+
+    /// Save the follows to the current user's account data.
+    async fn save_following(
+        &self,
+        following: SocialFollowingEventContent,
+    ) -> Result<(), FollowError> {
+        self.client
+            .account()
+            .set_account_data(following)
+            .await
+            .map_err(FollowError::MatrixError)?;
+        Ok(())
+    }
+}
+
+/// Errors that can occur when following or unfollowing users.
+#[derive(Debug, thiserror::Error)]
+pub enum FollowError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// Users can't follow themselves.
+    #[error("You can't follow yourself")]
+    CannotFollowSelf,
+
+    /// The user to follow has no public feed.
+    #[error("This user has no public feed")]
+    FeedNotFound,
+
+    /// The stored follows could not be parsed.
+    #[error("Invalid following list: {0}")]
+    InvalidFollowing(#[from] serde_json::Error),
+
+    /// An error occurred while leaving a feed room.
+    #[error("Feed error: {0}")]
+    Feed(#[from] FeedRoomError),
+
+    /// An error occurred while listing followers or followed users.
+    #[error("Stats error: {0}")]
+    Stats(#[from] ProfileStatsError),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{owned_room_id, owned_user_id, UInt};
+
+    #[test]
+    fn test_empty_following_serializes_to_empty_object() {
+        let following = SocialFollowingEventContent::default();
+        assert_eq!(serde_json::to_string(&following).unwrap(), "{}");
+        let parsed: SocialFollowingEventContent = serde_json::from_str("{}").unwrap();
+        assert!(parsed.following.is_empty());
+    }
+
+    #[test]
+    fn test_following_round_trips() {
+        let mut following = SocialFollowingEventContent::default();
+        following.following.insert(
+            owned_user_id!("@bob:example.org"),
+            FollowedFeed {
+                room_id: owned_room_id!("!feed:example.org"),
+                since: MilliSecondsSinceUnixEpoch(UInt::new(1_700_000_000_000).unwrap()),
+            },
+        );
+        let json = serde_json::to_value(&following).unwrap();
+        assert_eq!(
+            json["following"]["@bob:example.org"]["room_id"],
+            "!feed:example.org"
+        );
+        let parsed: SocialFollowingEventContent = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.following, following.following);
+    }
+}
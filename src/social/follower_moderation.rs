@@ -0,0 +1,168 @@
+//! Moderation of followers of the current user's feeds.
+//!
+//! Offers two strengths of moderation:
+//! - Removing a follower kicks them from the public feed. This is a soft
+//!   block: they can follow again later.
+//! - Banning a user removes them from all of the user's feeds and prevents
+//!   them from rejoining until unbanned.
+
+use matrix_sdk::{
+    ruma::{OwnedRoomId, OwnedUserId, UserId},
+    Client, Room, RoomMemberships,
+};
+use std::collections::BTreeSet;
+
+use crate::social::feed_room::{FeedPrivacy, FeedRoomService};
+
+/// All feed privacy levels, in the order they are moderated.
+const ALL_FEEDS: [FeedPrivacy; 3] = [
+    FeedPrivacy::Public,
+    FeedPrivacy::Friends,
+    FeedPrivacy::CloseFriends,
+];
+
+/// Service for removing and banning followers from the current user's feeds.
+pub struct FollowerModerationService {
+    client: Client,
+}
+
+impl FollowerModerationService {
+    /// Create a new FollowerModerationService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Remove a follower from the current user's public feed.
+    ///
+    /// The follower is kicked, not banned, so they can follow again.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, has no public feed,
+    /// or the kick fails.
+    pub async fn remove_follower(
+        &self,
+        follower: &UserId,
+        reason: Option<&str>,
+    ) -> Result<(), FollowerModerationError> {
+        let feed = self
+            .own_feed(FeedPrivacy::Public)?
+            .ok_or(FollowerModerationError::FeedNotFound)?;
+
+        feed.kick_user(follower, reason)
+            .await
+            .map_err(FollowerModerationError::MatrixError)?;
+
+        Ok(())
+    }
+
+    /// Ban a user from all of the current user's feeds.
+    ///
+    /// The user is removed from every feed they are in and cannot rejoin
+    /// any of them until [`unban_from_feeds()`](Self::unban_from_feeds) is called.
+    /// Every feed is tried even if banning from one of them fails.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or has no feeds,
+    /// or [`FollowerModerationError::BanFailed`] with the feeds whose ban failed.
+    pub async fn ban_from_feeds(
+        &self,
+        user_id: &UserId,
+        reason: Option<&str>,
+    ) -> Result<(), FollowerModerationError> {
+        let feeds = self.own_feeds()?;
+        if feeds.is_empty() {
+            return Err(FollowerModerationError::FeedNotFound);
+        }
+
+        let mut failures = Vec::new();
+        for feed in feeds {
+            if let Err(e) = feed.ban_user(user_id, reason).await {
+                failures.push((feed.room_id().to_owned(), e));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(FollowerModerationError::BanFailed(failures))
+        }
+    }
+
+    /// Lift a ban from all of the current user's feeds.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or any unban fails.
+    pub async fn unban_from_feeds(&self, user_id: &UserId) -> Result<(), FollowerModerationError> {
+        for feed in self.own_feeds()? {
+            feed.unban_user(user_id, None)
+                .await
+                .map_err(FollowerModerationError::MatrixError)?;
+        }
+
+        Ok(())
+    }
+
+    /// List the users banned from any of the current user's feeds.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or room members cannot be loaded.
+    pub async fn banned_users(&self) -> Result<BTreeSet<OwnedUserId>, FollowerModerationError> {
+        let mut banned = BTreeSet::new();
+        for feed in self.own_feeds()? {
+            let members = feed
+                .members(RoomMemberships::BAN)
+                .await
+                .map_err(FollowerModerationError::MatrixError)?;
+            banned.extend(members.iter().map(|member| member.user_id().to_owned()));
+        }
+        Ok(banned)
+    }
+
+    /// Get the current user's joined feed room of the given privacy level.
+    fn own_feed(&self, privacy: FeedPrivacy) -> Result<Option<Room>, FollowerModerationError> {
+        let user_id = self
+            .client
+            .user_id()
+            .ok_or(FollowerModerationError::NotLoggedIn)?;
+        Ok(FeedRoomService::new(self.client.clone()).find_joined_feed(user_id, privacy))
+    }
+
+    /// Get all of the current user's joined feed rooms.
+    fn own_feeds(&self) -> Result<Vec<Room>, FollowerModerationError> {
+        let mut feeds = Vec::new();
+        for privacy in ALL_FEEDS {
+            feeds.extend(self.own_feed(privacy)?);
+        }
+        Ok(feeds)
+    }
+}
+
+/// Errors that can occur when moderating followers.
+#[derive(Debug, thiserror::Error)]
+pub enum FollowerModerationError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The user has no feed room to moderate.
+    #[error("Feed room not found")]
+    FeedNotFound,
+
+    /// Banning a user failed in some of the feeds, with the error of each.
+    /// The user is still banned from the other feeds.
+    #[error("Failed to ban from {} feed(s): {}", .0.len(), describe_failures(.0))]
+    BanFailed(Vec<(OwnedRoomId, matrix_sdk::Error)>),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+/// Describe the feeds an operation failed in, with their errors.
+fn describe_failures(failures: &[(OwnedRoomId, matrix_sdk::Error)]) -> String {
+    failures
+        .iter()
+        .map(|(room_id, error)| format!("{room_id}: {error}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
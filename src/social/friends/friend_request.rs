@@ -6,12 +6,20 @@
 //! - Declining a request = rejecting the knock (kick from knock state)
 
 use matrix_sdk::{
+    deserialized_responses::MemberEvent,
     ruma::{
-        MilliSecondsSinceUnixEpoch, OwnedRoomId, OwnedRoomOrAliasId, OwnedUserId, RoomId, UserId,
+        api::client::profile::{AvatarUrl, DisplayName},
+        events::SyncStateEvent,
+        MilliSecondsSinceUnixEpoch, OwnedMxcUri, OwnedRoomId, OwnedRoomOrAliasId, OwnedUserId,
+        RoomAliasId, RoomId, UserId,
     },
-    Client,
+    Client, RoomMemberships, RoomState,
 };
 
+use crate::social::block_list::{is_blocked, BlockListError, BlockListService};
+use crate::social::event_bus::{publish_social_event, SocialEvent};
+use crate::social::feed_room::{feed_of_room, FeedPrivacy, FeedRoomService};
+
 /// Friend request state between two users.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FriendRequestState {
@@ -40,6 +48,63 @@ pub struct PendingFriendRequest {
     pub display_name: Option<String>,
     /// Requester's avatar URL (if available)
     pub avatar_url: Option<String>,
+    /// The intro message sent with the request (if any),
+    /// see [`FriendRequestService::send_friend_request_with_message()`]
+    pub message: Option<String>,
+}
+
+/// A friend request we sent that hasn't been answered yet.
+#[derive(Clone, Debug)]
+pub struct OutgoingFriendRequest {
+    /// The user the request was sent to
+    pub target: OwnedUserId,
+    /// The room we knocked on (their friends-only feed)
+    pub room_id: OwnedRoomId,
+    /// Target's display name (if available)
+    pub display_name: Option<String>,
+    /// Target's avatar URL (if available)
+    pub avatar_url: Option<OwnedMxcUri>,
+}
+
+/// Get the owner of a friends-only feed room from the feed it was identified as
+/// (see [`feed_of_room()`]) or, if the room's creation event isn't known,
+/// e.g., before the room has been joined, from its alias.
+///
+/// Returns `None` if the room isn't a friends-only feed.
+fn friends_feed_owner(
+    feed: Option<(FeedPrivacy, OwnedUserId)>,
+    alias: Option<&RoomAliasId>,
+) -> Option<OwnedUserId> {
+    if let Some((privacy, owner)) = feed {
+        return (privacy == FeedPrivacy::Friends).then_some(owner);
+    }
+    let alias = alias?;
+    let localpart = alias
+        .alias()
+        .strip_suffix(FeedPrivacy::Friends.alias_suffix())?;
+    UserId::parse(format!("@{localpart}:{}", alias.server_name())).ok()
+}
+
+/// Get the intro message of a friend request from its knock's reason.
+///
+/// Blank reasons are treated as no message.
+fn knock_message(reason: Option<&str>) -> Option<String> {
+    reason
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .map(str::to_owned)
+}
+
+/// Get when a knock was sent, if known, and its reason.
+fn knock_details(event: &MemberEvent) -> (Option<MilliSecondsSinceUnixEpoch>, Option<&str>) {
+    match event {
+        MemberEvent::Sync(SyncStateEvent::Original(event)) => (
+            Some(event.origin_server_ts),
+            event.content.reason.as_deref(),
+        ),
+        MemberEvent::Sync(SyncStateEvent::Redacted(_)) => (None, None),
+        MemberEvent::Stripped(event) => (None, event.content.reason.as_deref()),
+    }
 }
 
 /// Service for handling friend requests.
@@ -106,6 +171,10 @@ impl FriendRequestService {
         requester: &UserId,
         our_friends_feed: &RoomId,
     ) -> Result<(), FriendRequestError> {
+        if is_blocked(requester) {
+            return Err(FriendRequestError::UserBlocked);
+        }
+
         let room = self
             .client
             .get_room(our_friends_feed)
@@ -116,6 +185,7 @@ impl FriendRequestService {
             .await
             .map_err(FriendRequestError::MatrixError)?;
 
+        publish_social_event(SocialEvent::FriendRequestAccepted(requester.to_owned()));
         Ok(())
     }
 
@@ -138,26 +208,85 @@ impl FriendRequestService {
             .await
             .map_err(FriendRequestError::MatrixError)?;
 
+        publish_social_event(SocialEvent::FriendRequestDeclined(requester.to_owned()));
         Ok(())
     }
 
     /// Get pending incoming friend requests.
     ///
     /// Returns a list of users who have knocked on our friends-only feed
-    /// rooms and are waiting for a response.
+    /// rooms and are waiting for a response, with the intro messages
+    /// they sent along.
     pub async fn get_pending_requests(
         &self,
     ) -> Result<Vec<PendingFriendRequest>, FriendRequestError> {
-        let pending = Vec::new();
-
-        // TODO: Implement full knock state retrieval
-        // This requires:
-        // 1. Iterating through our friends-only feed rooms
-        // 2. Fetching room members for each room
-        // 3. Filtering for members in MembershipState::Knock
-        // 4. Building PendingFriendRequest structs with profile info
+        let user_id = self
+            .client
+            .user_id()
+            .ok_or(FriendRequestError::NotLoggedIn)?;
+        let Some(room) = FeedRoomService::new(self.client.clone())
+            .find_joined_feed(user_id, FeedPrivacy::Friends)
+        else {
+            return Ok(Vec::new());
+        };
+
+        let knocking = room
+            .members(RoomMemberships::KNOCK)
+            .await
+            .map_err(FriendRequestError::MatrixError)?;
+        Ok(knocking
+            .into_iter()
+            .filter(|member| !is_blocked(member.user_id()))
+            .map(|member| {
+                let (timestamp, reason) = knock_details(member.event());
+                PendingFriendRequest {
+                    requester: member.user_id().to_owned(),
+                    room_id: room.room_id().to_owned(),
+                    // Knocks only known from stripped state have no timestamp
+                    timestamp: timestamp.unwrap_or_else(MilliSecondsSinceUnixEpoch::now),
+                    display_name: member.display_name().map(str::to_owned),
+                    avatar_url: member.avatar_url().map(ToString::to_string),
+                    message: knock_message(reason),
+                }
+            })
+            .collect())
+    }
 
-        Ok(pending)
+    /// Get our pending outgoing friend requests.
+    ///
+    /// Returns the friends-only feed rooms we have knocked on and are
+    /// waiting to be let into, with the profiles of their owners.
+    pub async fn get_outgoing_requests(
+        &self,
+    ) -> Result<Vec<OutgoingFriendRequest>, FriendRequestError> {
+        let mut outgoing = Vec::new();
+        for room in self.client.rooms() {
+            if room.state() != RoomState::Knocked {
+                continue;
+            }
+            let alias = room.canonical_alias();
+            let Some(target) = friends_feed_owner(feed_of_room(&room), alias.as_deref()) else {
+                continue;
+            };
+            // The profile is only shown, so the request is listed even without it
+            let profile = self
+                .client
+                .account()
+                .fetch_user_profile_of(&target)
+                .await
+                .ok();
+            outgoing.push(OutgoingFriendRequest {
+                display_name: profile
+                    .as_ref()
+                    .and_then(|profile| profile.get_static::<DisplayName>().ok().flatten()),
+                avatar_url: profile
+                    .as_ref()
+                    .and_then(|profile| profile.get_static::<AvatarUrl>().ok().flatten()),
+                target,
+                room_id: room.room_id().to_owned(),
+            });
+        }
+        Ok(outgoing)
     }
 
     /// Get the friend request state with a specific user.
@@ -202,7 +331,9 @@ impl FriendRequestService {
     /// Block a user (prevents future friend requests).
     ///
     /// Blocking prevents the user from sending friend requests and
-    /// removes any existing friendship.
+    /// removes any existing friendship. The user is also added to the
+    /// block list and banned from all of our other feeds,
+    /// see [`BlockListService::block()`].
     pub async fn block_user(
         &self,
         user_id: &UserId,
@@ -218,6 +349,10 @@ impl FriendRequestService {
             .await
             .map_err(FriendRequestError::MatrixError)?;
 
+        BlockListService::new(self.client.clone())
+            .block(user_id, Some("User blocked"))
+            .await?;
+
         Ok(())
     }
 
@@ -239,6 +374,10 @@ impl FriendRequestService {
             .await
             .map_err(FriendRequestError::MatrixError)?;
 
+        BlockListService::new(self.client.clone())
+            .unblock(user_id)
+            .await?;
+
         Ok(())
     }
 }
@@ -270,7 +409,56 @@ pub enum FriendRequestError {
     #[error("Cannot send friend request to yourself")]
     CannotFriendSelf,
 
+    /// Updating the block list failed.
+    #[error("Block list error: {0}")]
+    BlockList(#[from] BlockListError),
+
     /// An error occurred in the Matrix SDK.
     #[error("Matrix error: {0}")]
     MatrixError(#[from] matrix_sdk::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{room_alias_id, user_id};
+
+    #[test]
+    fn test_knock_message() {
+        assert_eq!(
+            knock_message(Some("  Hi, we met at the conference! ")).as_deref(),
+            Some("Hi, we met at the conference!")
+        );
+        assert_eq!(knock_message(Some("   ")), None);
+        assert_eq!(knock_message(None), None);
+    }
+
+    #[test]
+    fn test_friends_feed_owner() {
+        let alice = user_id!("@alice:example.org");
+        let feed = |privacy: FeedPrivacy| Some((privacy, alice.to_owned()));
+
+        assert_eq!(
+            friends_feed_owner(feed(FeedPrivacy::Friends), None).as_deref(),
+            Some(alice)
+        );
+        assert_eq!(friends_feed_owner(feed(FeedPrivacy::Public), None), None);
+        // The identified feed takes precedence over the alias
+        assert_eq!(
+            friends_feed_owner(
+                feed(FeedPrivacy::Public),
+                Some(room_alias_id!("#alice_friends:example.org"))
+            ),
+            None
+        );
+        assert_eq!(
+            friends_feed_owner(None, Some(room_alias_id!("#alice_friends:example.org"))).as_deref(),
+            Some(alice)
+        );
+        assert_eq!(
+            friends_feed_owner(None, Some(room_alias_id!("#alice_public:example.org"))),
+            None
+        );
+        assert_eq!(friends_feed_owner(None, None), None);
+    }
+}
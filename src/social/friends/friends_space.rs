@@ -5,20 +5,70 @@
 //! friends-only visibility feed tier.
 
 use matrix_sdk::{
+    deserialized_responses::SyncOrStrippedState,
     ruma::{
         api::client::room::create_room::v3::Request as CreateRoomRequest,
         events::{
             room::{
                 history_visibility::{HistoryVisibility, RoomHistoryVisibilityEventContent},
                 join_rules::{JoinRule, RoomJoinRulesEventContent},
+                member::MembershipState,
             },
             space::child::SpaceChildEventContent,
+            SyncStateEvent,
         },
         OwnedRoomId, RoomId, UserId,
     },
-    Client,
+    Client, Room,
 };
 
+use crate::social::event_bus::{publish_social_event, SocialEvent};
+use crate::social::feed_room::{FeedPrivacy, FeedRoomService};
+
+/// Get the name of a user's friends space, by which it is recognized.
+pub fn friends_space_name(user_id: &UserId) -> String {
+    format!("{}'s Friends", user_id.localpart())
+}
+
+/// Get the friend feed rooms in a friends space.
+///
+/// Works for any friends space whose state is visible to the current user.
+pub async fn friends_space_children(
+    space: &Room,
+) -> Result<Vec<OwnedRoomId>, matrix_sdk::Error> {
+    let raw_events = space
+        .get_state_events_static::<SpaceChildEventContent>()
+        .await?;
+
+    // Removed children have an empty `via` list
+    Ok(raw_events
+        .into_iter()
+        .filter_map(|raw_event| match raw_event.deserialize().ok()? {
+            SyncOrStrippedState::Sync(SyncStateEvent::Original(event))
+                if !event.content.via.is_empty() =>
+            {
+                Some(event.state_key)
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+/// Kick a user from one of our feeds, if they're in it, invited to it or knocking on it.
+async fn remove_from_feed(room: &Room, user_id: &UserId) -> Result<(), matrix_sdk::Error> {
+    let membership = room
+        .get_member_no_sync(user_id)
+        .await?
+        .map(|member| member.membership().clone());
+    if matches!(
+        membership,
+        Some(MembershipState::Join | MembershipState::Invite | MembershipState::Knock)
+    ) {
+        room.kick_user(user_id, Some("Unfriended")).await?;
+    }
+    Ok(())
+}
+
 /// Service for managing the friends space
 pub struct FriendsSpaceService {
     client: Client,
@@ -57,6 +107,16 @@ impl FriendsSpaceService {
         Ok(id)
     }
 
+    /// Get the user's friends space if it already exists, without creating one.
+    pub async fn find_existing_friends_space(
+        &mut self,
+    ) -> Result<Option<OwnedRoomId>, FriendsError> {
+        if self.space_id.is_none() {
+            self.space_id = self.find_friends_space().await?;
+        }
+        Ok(self.space_id.clone())
+    }
+
     /// Add a friend's feed room to the space.
     ///
     /// This creates a space child relationship between the friends space
@@ -68,13 +128,19 @@ impl FriendsSpaceService {
             .get_room(&space_id)
             .ok_or(FriendsError::SpaceNotFound)?;
 
-        // Add as space child with empty server list (Matrix handles routing)
-        let content = SpaceChildEventContent::new(vec![]);
+        // A space child needs at least one server to join it through;
+        // an empty `via` list marks it as removed.
+        let server = friend_feed_room
+            .server_name()
+            .or_else(|| self.client.user_id().map(|u| u.server_name()))
+            .ok_or(FriendsError::NotLoggedIn)?;
+        let content = SpaceChildEventContent::new(vec![server.to_owned()]);
         space
             .send_state_event_for_key(friend_feed_room, content)
             .await
             .map_err(FriendsError::MatrixError)?;
 
+        publish_social_event(SocialEvent::FriendAdded(friend_feed_room.to_owned()));
         Ok(())
     }
 
@@ -101,31 +167,69 @@ impl FriendsSpaceService {
             .await
             .map_err(FriendsError::MatrixError)?;
 
+        publish_social_event(SocialEvent::FriendRemoved(friend_feed_room.to_owned()));
         Ok(())
     }
 
+    /// Unfriend a user on both sides of the friendship.
+    ///
+    /// Unlike [`remove_friend()`](Self::remove_friend), this also leaves
+    /// the friend's friends feed and kicks them from all our own feeds that
+    /// aren't public, revoking their access to our friends-only and
+    /// close-friends posts. Every step is attempted even if an earlier one fails.
+    ///
+    /// # Errors
+    /// Returns [`FriendsError::NotFriend`] if we share no friends feed
+    /// with the user, or [`FriendsError::Incomplete`] with the errors of
+    /// the steps that failed.
+    pub async fn unfriend(&mut self, friend: &UserId) -> Result<(), FriendsError> {
+        let user_id = self.client.user_id().ok_or(FriendsError::NotLoggedIn)?;
+        let feeds = FeedRoomService::new(self.client.clone());
+        let their_feed = feeds.find_joined_feed(friend, FeedPrivacy::Friends);
+        let our_feeds: Vec<Room> = [FeedPrivacy::Friends, FeedPrivacy::CloseFriends]
+            .into_iter()
+            .filter_map(|privacy| feeds.find_joined_feed(user_id, privacy))
+            .collect();
+        if their_feed.is_none() && our_feeds.is_empty() {
+            return Err(FriendsError::NotFriend);
+        }
+
+        let mut errors = Vec::new();
+        if let Some(room) = their_feed {
+            if let Err(e) = self.remove_friend(room.room_id()).await {
+                errors.push(e);
+            }
+            if let Err(e) = room.leave().await {
+                errors.push(FriendsError::MatrixError(e));
+            }
+        }
+        for room in our_feeds {
+            if let Err(e) = remove_from_feed(&room, friend).await {
+                errors.push(FriendsError::MatrixError(e));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(FriendsError::Incomplete(errors))
+        }
+    }
+
     /// Get list of friends (feed room IDs in the space).
     ///
     /// Returns the room IDs of all friend feed rooms in the friends space.
     pub async fn get_friends(&self) -> Result<Vec<OwnedRoomId>, FriendsError> {
         let space_id = self.space_id.as_ref().ok_or(FriendsError::SpaceNotFound)?;
 
-        let _space = self
+        let space = self
             .client
             .get_room(space_id)
             .ok_or(FriendsError::SpaceNotFound)?;
 
-        // Get space children from the room's space info
-        // For now, return an empty list - full implementation requires
-        // iterating over m.space.child state events
-        let friends = Vec::new();
-
-        // TODO: Implement full space children retrieval using:
-        // space.get_state_events_static::<SpaceChildEventContent>()
-        // This would iterate through m.space.child state events
-        // and extract the room IDs from the state keys
-
-        Ok(friends)
+        friends_space_children(&space)
+            .await
+            .map_err(FriendsError::MatrixError)
     }
 
     /// Check if a user is a friend (bidirectional membership check).
@@ -165,8 +269,7 @@ impl FriendsSpaceService {
 
             // Check if the room name matches our convention
             let room_name = room.name();
-            let expected_name = format!("{}'s Friends", user_id.localpart());
-            if room_name.as_deref() == Some(&expected_name) {
+            if room_name.as_deref() == Some(&friends_space_name(user_id)) {
                 return Ok(Some(room.room_id().to_owned()));
             }
 
@@ -183,7 +286,7 @@ impl FriendsSpaceService {
 
         // Create private space request
         let mut request = CreateRoomRequest::new();
-        request.name = Some(format!("{}'s Friends", user_id.localpart()));
+        request.name = Some(friends_space_name(user_id));
         request.topic = Some("Friends space for organizing connections".to_string());
 
         // Mark as a space by setting the room type
@@ -251,7 +354,20 @@ pub enum FriendsError {
     #[error("User is not a friend")]
     NotFriend,
 
+    /// Some steps of unfriending a user failed, while the others went through.
+    #[error("Failed to fully unfriend the user: {}", join_errors(.0))]
+    Incomplete(Vec<FriendsError>),
+
     /// An error occurred in the Matrix SDK.
     #[error("Matrix error: {0}")]
     MatrixError(#[from] matrix_sdk::Error),
 }
+
+/// Join the messages of several errors into one.
+fn join_errors(errors: &[FriendsError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
@@ -35,6 +35,9 @@ pub mod friend_request;
 pub mod friends_space;
 
 pub use friend_request::{
-    FriendRequestError, FriendRequestService, FriendRequestState, PendingFriendRequest,
+    FriendRequestError, FriendRequestService, FriendRequestState, OutgoingFriendRequest,
+    PendingFriendRequest,
+};
+pub use friends_space::{
+    friends_space_children, friends_space_name, FriendsError, FriendsSpaceService,
 };
-pub use friends_space::{FriendsError, FriendsSpaceService};
@@ -0,0 +1,229 @@
+//! The main newsfeed, with the posts of all the feeds the user has joined.
+//!
+//! Its posts are filtered with the newsfeed filter saved in the account's
+//! social settings (see [`load_feed_filter()`]), along with the account's
+//! mutes, and likely spam is moved to the end of the feed and hidden behind
+//! an interstitial, as strictly as the filter asks (see [`SpamScorer`]).
+//!
+//! It's loaded through the Matrix worker (see [`MatrixRequest::LoadMainFeed`]),
+//! which first posts a [`MainFeedAction::CachedLoaded`] with the posts kept in
+//! the [feed cache](crate::social::newsfeed::FeedCache) since the last load,
+//! so that they're shown at once, then a [`MainFeedAction::Loaded`] with the
+//! refreshed posts. Cached posts that are redacted or edited are dropped from
+//! the cache as the events arrive (see [`spawn_main_feed_cache_invalidator()`]).
+//! The first load also posts a [`MainFeedAction::ReadMarkersLoaded`] with the
+//! feed rooms' read markers, to count the posts sent since the last visit.
+//! Each load posts a [`MainFeedAction::DiagnosticsLoaded`] with how the posts
+//! were aggregated, if the `feed_diagnostics` developer setting is on, and a
+//! [`MainFeedAction::CapabilitiesLoaded`] with the post types that need a newer
+//! client to be shown.
+//!
+//! [`MatrixRequest::LoadMainFeed`]: crate::sliding_sync::MatrixRequest::LoadMainFeed
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    ruma::{events::AnySyncTimelineEvent, serde::Raw, OwnedUserId},
+    Client, Room,
+};
+use std::sync::Mutex;
+use tokio::runtime::Handle;
+
+use crate::social::feed_room::{feed_of_room, is_feed_room};
+use crate::social::newsfeed::{
+    load_feed_diagnostics_enabled, load_feed_filter, CapabilityTracker, FeedAggregator, FeedCache,
+    FeedDiagnostics, FeedError, FeedFilterSettings, FeedReadMarkerService, FeedReadMarkers,
+    ScoredFeedItem, SpamAssessment, SpamScorer, SpamVerdict,
+};
+use crate::social::profile_tabs::load_post_card;
+use crate::social::reactions::ReactionSummary;
+use crate::social::widgets::post_card::PostCardData;
+
+/// The number of posts first shown in the main newsfeed, and the number of
+/// older posts loaded each time the user scrolls to the end of them.
+pub const MAX_MAIN_FEED_POSTS: usize = 50;
+
+/// The aggregator of the main newsfeed and the user it belongs to,
+/// kept so that every load shares the feed cache.
+static MAIN_FEED: Mutex<Option<(OwnedUserId, FeedAggregator)>> = Mutex::new(None);
+
+/// Actions emitted when the main newsfeed was loaded.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum MainFeedAction {
+    /// Up to `limit` cached posts of the main newsfeed, without their reactions,
+    /// shown until the newsfeed is refreshed.
+    CachedLoaded {
+        limit: usize,
+        posts: Vec<PostCardData>,
+    },
+    /// Up to `limit` posts of the main newsfeed were loaded.
+    Loaded {
+        limit: usize,
+        posts: Vec<PostCardData>,
+    },
+    /// The read markers of the newsfeed's rooms were loaded,
+    /// when its first posts were loaded.
+    ReadMarkersLoaded(FeedReadMarkers),
+    /// How the newsfeed's posts were aggregated, or `None` if the
+    /// `feed_diagnostics` developer setting is off.
+    DiagnosticsLoaded(Option<FeedDiagnostics>),
+    /// The post types that needed a newer client to be shown in the newsfeed so far.
+    CapabilitiesLoaded(CapabilityTracker),
+    /// Loading the main newsfeed failed.
+    Failed(String),
+    /// No action.
+    None,
+}
+
+/// Get the aggregator of the main newsfeed, without any rooms,
+/// loading the feed cache the first time.
+async fn main_feed_aggregator(client: &Client) -> FeedAggregator {
+    let Some(user_id) = client.user_id().map(ToOwned::to_owned) else {
+        return FeedAggregator::new(client.clone());
+    };
+    let loaded = MAIN_FEED
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|(owner, _)| *owner == user_id)
+        .map(|(_, aggregator)| aggregator.clone());
+    if let Some(aggregator) = loaded {
+        return aggregator;
+    }
+
+    let cache_owner = user_id.clone();
+    let cache = Handle::current()
+        .spawn_blocking(move || FeedCache::load(&cache_owner))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result.map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            warning!("Failed to load the feed cache: {e}");
+            FeedCache::default()
+        });
+    let mut aggregator = FeedAggregator::new(client.clone());
+    aggregator.set_cache(cache);
+    *MAIN_FEED.lock().unwrap() = Some((user_id, aggregator.clone()));
+    aggregator
+}
+
+/// Empty the main newsfeed's cache in memory, e.g., as its file is deleted,
+/// so that it isn't saved again.
+pub fn clear_main_feed_cache() {
+    if let Some((_, aggregator)) = MAIN_FEED.lock().unwrap().as_ref() {
+        *aggregator.cache().lock().unwrap() = FeedCache::default();
+    }
+}
+
+/// Load up to `limit` posts of the main newsfeed, with their reactions.
+///
+/// Before the feeds are read, a [`MainFeedAction::CachedLoaded`] is posted
+/// with the cached posts, if there are any, and unless older posts are loaded,
+/// a [`MainFeedAction::ReadMarkersLoaded`] with the feed rooms' read markers.
+/// Once they're read, a [`MainFeedAction::DiagnosticsLoaded`] and
+/// a [`MainFeedAction::CapabilitiesLoaded`] are posted.
+/// If the saved newsfeed filter can't be loaded, the posts are shown unfiltered.
+///
+/// # Errors
+/// Returns an error if the feeds cannot be read.
+pub async fn load_main_feed(client: &Client, limit: usize) -> Result<Vec<PostCardData>, FeedError> {
+    let filter = load_feed_filter(client).await.unwrap_or_else(|e| {
+        warning!("Failed to load the newsfeed filter: {e}");
+        FeedFilterSettings::new()
+    });
+
+    let mut aggregator = main_feed_aggregator(client).await;
+    let room_ids: Vec<_> = client
+        .joined_rooms()
+        .into_iter()
+        .filter(|room| feed_of_room(room).is_some())
+        .map(|room| room.room_id().to_owned())
+        .collect();
+    for room_id in &room_ids {
+        aggregator.add_feed_room(room_id.clone());
+    }
+    if aggregator.room_count() == 0 {
+        return Ok(Vec::new());
+    }
+    aggregator.set_filter(filter.clone());
+
+    // The filter may have changed since the posts were cached
+    let cached_items = filter.apply(aggregator.cached_feed(limit));
+    if !cached_items.is_empty() {
+        let scorer = SpamScorer::new().with_not_spam_senders(filter.not_spam_senders.clone());
+        let posts = scorer
+            .apply(cached_items, filter.spam_strictness)
+            .into_iter()
+            .map(|ScoredFeedItem { item, spam }| {
+                let post =
+                    PostCardData::from_feed_item(item, ReactionSummary::new(), client.user_id());
+                hide_if_spam(post, spam)
+            })
+            .collect();
+        Cx::post_action(MainFeedAction::CachedLoaded { limit, posts });
+    }
+
+    if limit <= MAX_MAIN_FEED_POSTS {
+        match FeedReadMarkerService::new(client.clone())
+            .load_markers(&room_ids)
+            .await
+        {
+            Ok(markers) => Cx::post_action(MainFeedAction::ReadMarkersLoaded(markers)),
+            Err(e) => warning!("Failed to load the read markers of the newsfeed: {e}"),
+        }
+    }
+
+    let items = aggregator.get_aggregated_feed(limit).await?;
+    let diagnostics_enabled = load_feed_diagnostics_enabled(client)
+        .await
+        .unwrap_or_else(|e| {
+            warning!("Failed to load the feed diagnostics setting: {e}");
+            false
+        });
+    Cx::post_action(MainFeedAction::DiagnosticsLoaded(
+        diagnostics_enabled.then(|| aggregator.diagnostics()),
+    ));
+    Cx::post_action(MainFeedAction::CapabilitiesLoaded(
+        aggregator.capabilities(),
+    ));
+    let mut scorer = SpamScorer::new().with_not_spam_senders(filter.not_spam_senders);
+    scorer.load_join_times(client, &items).await;
+    let mut posts = Vec::new();
+    for ScoredFeedItem { item, spam } in scorer.apply(items, filter.spam_strictness) {
+        let post = load_post_card(client, item).await;
+        posts.push(hide_if_spam(post, spam));
+    }
+    Ok(posts)
+}
+
+/// Hide a post behind an interstitial if it's likely spam.
+fn hide_if_spam(mut post: PostCardData, spam: SpamAssessment) -> PostCardData {
+    if spam.verdict == SpamVerdict::Hide {
+        post.hidden_as_spam = spam.signals;
+    }
+    post
+}
+
+/// Drop the cached posts of the main newsfeed as they're redacted or edited,
+/// so that they're fetched again on its next load.
+///
+/// Must be called from within the Tokio runtime after logging in.
+pub fn spawn_main_feed_cache_invalidator(client: Client) {
+    Handle::current().spawn(async move {
+        client.add_event_handler(
+            |event: Raw<AnySyncTimelineEvent>, room: Room, client: Client| async move {
+                if !is_feed_room(&room) {
+                    return;
+                }
+                let aggregator = main_feed_aggregator(&client).await;
+                let invalidated = aggregator
+                    .cache()
+                    .lock()
+                    .unwrap()
+                    .invalidate_for_event(room.room_id(), &event);
+                if invalidated.is_some() {
+                    aggregator.save_cache();
+                }
+            },
+        );
+    });
+}
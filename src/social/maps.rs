@@ -0,0 +1,373 @@
+//! Map thumbnails for event and post locations.
+//!
+//! Locations with coordinates show a small static map, which users can tap
+//! to open the full map view. Map images come from a pluggable
+//! [`MapProvider`], which the app sets with [`set_map_provider()`].
+//! Whenever no map can be fetched, e.g., without a provider or while
+//! offline, a placeholder is shown instead.
+//!
+//! Map images are cached per location, and fetched through the Matrix
+//! worker (see [`MatrixRequest::FetchMapThumbnail`]), which signals the UI
+//! once an image is available. Map providers learn which places the user
+//! looks at, so nothing is fetched if the user turned remote maps off
+//! (see [`set_remote_maps_disabled()`]) or while
+//! [privacy mode](crate::social::privacy::privacy_mode) is on. The cached
+//! images count as cached media in the
+//! [storage usage](crate::social::storage_usage), and are trimmed to its limit.
+//!
+//! [`MatrixRequest::FetchMapThumbnail`]: crate::sliding_sync::MatrixRequest::FetchMapThumbnail
+
+use futures_util::future::BoxFuture;
+use makepad_widgets::{error, SignalToUI};
+use matrix_sdk::Client;
+use robrix_social_events::event::EventLocation;
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::{
+    sliding_sync::{submit_async_request, MatrixRequest},
+    social::{
+        availability::load_settings, events::timing::now_ms, privacy::is_privacy_mode,
+        storage_usage::cache_limits,
+    },
+};
+
+/// Width of a map thumbnail, in pixels.
+pub const THUMBNAIL_WIDTH: u32 = 600;
+
+/// Height of a map thumbnail, in pixels.
+pub const THUMBNAIL_HEIGHT: u32 = 240;
+
+/// Zoom level of map thumbnails, roughly showing a neighborhood.
+pub const THUMBNAIL_ZOOM: u8 = 15;
+
+/// How many map images are kept in memory.
+const MAX_CACHED_MAPS: usize = 100;
+
+/// How long to wait before retrying a failed fetch.
+const MAP_RETRY_MS: u64 = 5 * 60 * 1000;
+
+/// A point on the map, in degrees.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    /// Parse a `geo:` URI (RFC 5870), e.g., "geo:40.7829,-73.9654;u=35".
+    ///
+    /// The altitude and any parameters are ignored.
+    /// Returns `None` if the URI isn't a valid `geo:` URI.
+    pub fn from_geo_uri(uri: &str) -> Option<Self> {
+        let uri = uri.trim();
+        let (scheme, rest) = uri.split_once(':')?;
+        if !scheme.eq_ignore_ascii_case("geo") {
+            return None;
+        }
+        let coordinates = rest.split(';').next()?;
+        let mut parts = coordinates.split(',');
+        let latitude: f64 = parts.next()?.trim().parse().ok()?;
+        let longitude: f64 = parts.next()?.trim().parse().ok()?;
+        let in_range = (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude);
+        in_range.then_some(Self {
+            latitude,
+            longitude,
+        })
+    }
+
+    /// Get the point a location is at, or `None` if it has no valid coordinates.
+    pub fn of_location(location: &EventLocation) -> Option<Self> {
+        location.geo.as_deref().and_then(Self::from_geo_uri)
+    }
+
+    /// Get the key map images of this point are cached under.
+    ///
+    /// Points within about a meter of each other share a key.
+    fn cache_key(&self) -> String {
+        format!("{:.5},{:.5}", self.latitude, self.longitude)
+    }
+}
+
+/// A source of static map images.
+///
+/// Implementations typically query a remote tile or static map service.
+pub trait MapProvider: Send + Sync {
+    /// Get a PNG or JPEG image of the map centered on the given point.
+    fn static_map<'a>(
+        &'a self,
+        center: GeoPoint,
+        zoom: u8,
+        width: u32,
+        height: u32,
+    ) -> BoxFuture<'a, Result<Vec<u8>, MapError>>;
+}
+
+/// The provider map images are fetched from, if any.
+static MAP_PROVIDER: Mutex<Option<Arc<dyn MapProvider>>> = Mutex::new(None);
+
+/// Set the provider map images are fetched from, or `None` to only show placeholders.
+///
+/// Cached map images are dropped, as they came from the previous provider.
+pub fn set_map_provider(provider: Option<Arc<dyn MapProvider>>) {
+    *MAP_PROVIDER.lock().unwrap() = provider;
+    MAP_CACHE.lock().unwrap().clear();
+}
+
+/// Whether the logged-in account turned off remote map fetches.
+static REMOTE_MAPS_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Whether map images may currently be fetched from the map provider.
+///
+/// This is `false` if the user turned remote maps off or privacy mode is on.
+pub fn are_remote_maps_enabled() -> bool {
+    !REMOTE_MAPS_DISABLED.load(Ordering::Relaxed) && !is_privacy_mode()
+}
+
+/// Update the in-memory remote maps setting from the account's settings.
+pub(crate) fn update_remote_maps_disabled(disabled: bool) {
+    REMOTE_MAPS_DISABLED.store(disabled, Ordering::Relaxed);
+}
+
+/// Turn remote map fetches off or on for the logged-in account.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn set_remote_maps_disabled(
+    client: &Client,
+    disabled: bool,
+) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.remote_maps_disabled = disabled;
+    client.account().set_account_data(settings).await?;
+    // Take effect right away rather than once the settings have synced back
+    update_remote_maps_disabled(disabled);
+    Ok(())
+}
+
+/// An entry in the map image cache.
+#[derive(Clone, Debug)]
+enum CacheEntry {
+    /// The map image is being fetched.
+    Requested,
+    /// The map image was fetched.
+    Fetched(Arc<[u8]>),
+    /// Fetching the map image failed at the given time (Unix timestamp in milliseconds).
+    Failed { failed_at: u64 },
+}
+
+/// Map images by [`GeoPoint::cache_key()`].
+static MAP_CACHE: Mutex<BTreeMap<String, CacheEntry>> = Mutex::new(BTreeMap::new());
+
+/// The map thumbnail to show for a location.
+#[derive(Clone, Debug)]
+pub enum MapThumbnailStatus {
+    /// No map image will be shown; show the placeholder instead.
+    Placeholder,
+    /// The map image is being fetched; the UI will be signaled once it is.
+    Pending,
+    /// The map image, as PNG or JPEG data.
+    Ready(Arc<[u8]>),
+}
+
+/// Get the map thumbnail for a location, requesting it if it isn't cached yet.
+///
+/// This is cheap to call on every redraw: each location is only fetched once,
+/// and failed fetches are retried after [`MAP_RETRY_MS`].
+pub fn get_or_fetch_map_thumbnail(location: &EventLocation) -> MapThumbnailStatus {
+    if !are_remote_maps_enabled() || MAP_PROVIDER.lock().unwrap().is_none() {
+        return MapThumbnailStatus::Placeholder;
+    }
+    let Some(center) = GeoPoint::of_location(location) else {
+        return MapThumbnailStatus::Placeholder;
+    };
+    let key = center.cache_key();
+
+    let mut cache = MAP_CACHE.lock().unwrap();
+    match cache.get(&key) {
+        Some(CacheEntry::Requested) => return MapThumbnailStatus::Pending,
+        Some(CacheEntry::Fetched(data)) => return MapThumbnailStatus::Ready(data.clone()),
+        Some(CacheEntry::Failed { failed_at }) if now_ms() < failed_at + MAP_RETRY_MS => {
+            return MapThumbnailStatus::Placeholder;
+        }
+        _ => {}
+    }
+
+    // Make room by dropping what's done, keeping fetches that are still in flight
+    if cache.len() >= MAX_CACHED_MAPS {
+        cache.retain(|_, entry| matches!(entry, CacheEntry::Requested));
+    }
+    cache.insert(key, CacheEntry::Requested);
+    submit_async_request(MatrixRequest::FetchMapThumbnail { center });
+    MapThumbnailStatus::Pending
+}
+
+/// Fetch the map thumbnail centered on a point from the current provider,
+/// store it in the cache, and signal the UI.
+///
+/// This is run by the Matrix worker for [`MatrixRequest::FetchMapThumbnail`].
+pub async fn fetch_map_thumbnail(center: GeoPoint) {
+    let key = center.cache_key();
+    let provider = MAP_PROVIDER.lock().unwrap().clone();
+    let entry = match provider {
+        // Remote maps may have been turned off since the request was made
+        Some(provider) if are_remote_maps_enabled() => match provider
+            .static_map(center, THUMBNAIL_ZOOM, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)
+            .await
+        {
+            Ok(data) => CacheEntry::Fetched(data.into()),
+            Err(e) => {
+                error!("Failed to fetch the map thumbnail for {key}: {e}");
+                CacheEntry::Failed {
+                    failed_at: now_ms(),
+                }
+            }
+        },
+        _ => CacheEntry::Failed {
+            failed_at: now_ms(),
+        },
+    };
+    let mut cache = MAP_CACHE.lock().unwrap();
+    cache.insert(key.clone(), entry);
+    if let Some(max_bytes) = cache_limits().max_media_bytes {
+        trim_map_cache(&mut cache, max_bytes, &key);
+    }
+    drop(cache);
+    SignalToUI::set_ui_signal();
+}
+
+/// Get the total size of the cached map images, in bytes.
+pub fn map_cache_size() -> u64 {
+    cached_bytes(&MAP_CACHE.lock().unwrap())
+}
+
+/// Drop all cached map images, keeping fetches that are still in flight.
+pub fn clear_map_cache() {
+    MAP_CACHE
+        .lock()
+        .unwrap()
+        .retain(|_, entry| matches!(entry, CacheEntry::Requested));
+}
+
+/// Drop cached map images until they take up at most `max_bytes`,
+/// keeping fetches that are still in flight.
+pub(crate) fn limit_map_cache(max_bytes: u64) {
+    trim_map_cache(&mut MAP_CACHE.lock().unwrap(), max_bytes, "");
+}
+
+/// Get the total size of the map images in a cache, in bytes.
+fn cached_bytes(cache: &BTreeMap<String, CacheEntry>) -> u64 {
+    cache
+        .values()
+        .map(|entry| match entry {
+            CacheEntry::Fetched(data) => data.len() as u64,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Drop map images from a cache until they take up at most `max_bytes`,
+/// keeping the image cached under `keep` if it fits on its own.
+fn trim_map_cache(cache: &mut BTreeMap<String, CacheEntry>, max_bytes: u64, keep: &str) {
+    let mut size = cached_bytes(cache);
+    if size <= max_bytes {
+        return;
+    }
+    let mut keys: Vec<String> = cache
+        .iter()
+        .filter(|(key, entry)| matches!(entry, CacheEntry::Fetched(_)) && key.as_str() != keep)
+        .map(|(key, _)| key.clone())
+        .collect();
+    // Drop the image being kept last
+    if cache.contains_key(keep) {
+        keys.push(keep.to_string());
+    }
+    for key in keys {
+        if size <= max_bytes {
+            break;
+        }
+        if let Some(CacheEntry::Fetched(data)) = cache.remove(&key) {
+            size -= data.len() as u64;
+        }
+    }
+}
+
+/// Errors that can occur when fetching map images.
+#[derive(Debug, thiserror::Error)]
+pub enum MapError {
+    /// The map service couldn't be reached.
+    #[error("Network error: {0}")]
+    Network(String),
+
+    /// The map service returned something unexpected.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_geo_uri() {
+        assert_eq!(
+            GeoPoint::from_geo_uri("geo:40.7829,-73.9654"),
+            Some(GeoPoint {
+                latitude: 40.7829,
+                longitude: -73.9654
+            })
+        );
+        // Altitude and parameters are ignored
+        assert_eq!(
+            GeoPoint::from_geo_uri("GEO:48.2010,16.3695,183;u=35"),
+            Some(GeoPoint {
+                latitude: 48.201,
+                longitude: 16.3695
+            })
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_geo_uri() {
+        assert_eq!(GeoPoint::from_geo_uri("40.7829,-73.9654"), None);
+        assert_eq!(GeoPoint::from_geo_uri("https://example.org"), None);
+        assert_eq!(GeoPoint::from_geo_uri("geo:40.7829"), None);
+        assert_eq!(GeoPoint::from_geo_uri("geo:91,0"), None);
+        assert_eq!(GeoPoint::from_geo_uri("geo:0,-181"), None);
+    }
+
+    #[test]
+    fn test_trim_map_cache() {
+        let image = |size: usize| CacheEntry::Fetched(vec![0; size].into());
+        let mut cache = BTreeMap::from([
+            ("a".to_string(), image(40)),
+            ("b".to_string(), CacheEntry::Requested),
+            ("c".to_string(), image(40)),
+            ("d".to_string(), image(40)),
+        ]);
+        trim_map_cache(&mut cache, 100, "a");
+        assert_eq!(cached_bytes(&cache), 80);
+        // The newly fetched image and fetches in flight are kept
+        assert!(cache.contains_key("a"));
+        assert!(cache.contains_key("b"));
+        assert!(!cache.contains_key("c"));
+
+        trim_map_cache(&mut cache, 10, "a");
+        assert_eq!(cached_bytes(&cache), 0);
+        assert!(cache.contains_key("b"));
+    }
+
+    #[test]
+    fn test_nearby_points_share_cache_key() {
+        let a = GeoPoint::from_geo_uri("geo:40.782900,-73.965400").unwrap();
+        let b = GeoPoint::from_geo_uri("geo:40.782901,-73.965401").unwrap();
+        let c = GeoPoint::from_geo_uri("geo:40.7830,-73.9654").unwrap();
+        assert_eq!(a.cache_key(), b.cache_key());
+        assert_ne!(a.cache_key(), c.cache_key());
+    }
+}
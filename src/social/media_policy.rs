@@ -0,0 +1,155 @@
+//! Restrictions on the media attached to posts in a feed.
+//!
+//! A feed's owner can restrict the kinds of media attached to posts in the
+//! feed, and their size, in the feed's configuration
+//! (see [`FeedRoomService::set_media_restrictions()`](crate::social::FeedRoomService::set_media_restrictions)).
+//! The post composer checks attachments against the configuration of each feed
+//! it posts to before uploading them, and the newsfeed annotates posts that
+//! break their feed's restrictions for the feed's moderators
+//! (see [`FeedItem::media_violation`](crate::social::FeedItem::media_violation)).
+
+use bytesize::ByteSize;
+use robrix_social_events::feed_config::{MediaKind, SocialFeedConfigEventContent};
+use serde::{Deserialize, Serialize};
+
+use crate::social::post::PostContent;
+
+/// How attached media breaks a feed's media restrictions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum MediaViolation {
+    /// The feed doesn't allow media of this kind.
+    #[error("{} aren't allowed", kind_name(*.0))]
+    KindNotAllowed(MediaKind),
+    /// The media is larger than the feed allows.
+    #[error("{} is over the limit of {}", ByteSize::b(*.size), ByteSize::b(*.max_size))]
+    TooLarge {
+        /// The size of the media, in bytes.
+        size: u64,
+        /// The largest size the feed allows, in bytes.
+        max_size: u64,
+    },
+}
+
+/// Get the plural name of a media kind, as shown to users.
+fn kind_name(kind: MediaKind) -> &'static str {
+    match kind {
+        MediaKind::Image => "Photos",
+        MediaKind::Video => "Videos",
+        MediaKind::Audio => "Audio clips",
+    }
+}
+
+/// Check media of the given kind and size, if known, against a feed's configuration.
+///
+/// Returns how the media breaks the feed's restrictions, or `None` if it doesn't.
+/// Media of unknown size is only checked for its kind.
+pub fn check_media(
+    config: &SocialFeedConfigEventContent,
+    kind: MediaKind,
+    size: Option<u64>,
+) -> Option<MediaViolation> {
+    if !config.allows_media_kind(kind) {
+        return Some(MediaViolation::KindNotAllowed(kind));
+    }
+    let size = size?;
+    let max_size = config.max_media_size?;
+    (!config.allows_media_size(size)).then_some(MediaViolation::TooLarge { size, max_size })
+}
+
+/// Check the media of a post against the configuration of the feed it's in.
+///
+/// Posts without media never break the restrictions.
+pub fn check_post_media(
+    config: &SocialFeedConfigEventContent,
+    content: &PostContent,
+) -> Option<MediaViolation> {
+    let (kind, size) = match content {
+        PostContent::Image { size, .. } => (MediaKind::Image, *size),
+        PostContent::Video { size, .. } => (MediaKind::Video, *size),
+        PostContent::Audio { size, .. } => (MediaKind::Audio, *size),
+        PostContent::Text { .. } | PostContent::Link { .. } | PostContent::Poll { .. } => {
+            return None;
+        }
+    };
+    check_media(config, kind, size)
+}
+
+/// Get the kind and size, if recorded, of the media of a post from its raw message content.
+///
+/// Returns `None` if the post has no media.
+pub fn media_from_content(content: &serde_json::Value) -> Option<(MediaKind, Option<u64>)> {
+    let kind = MediaKind::from_msgtype(content.get("msgtype")?.as_str()?)?;
+    let size = content
+        .get("info")
+        .and_then(|info| info.get("size"))
+        .and_then(serde_json::Value::as_u64);
+    Some((kind, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(
+        allowed_media: Option<Vec<MediaKind>>,
+        max_media_size: Option<u64>,
+    ) -> SocialFeedConfigEventContent {
+        SocialFeedConfigEventContent {
+            allowed_media,
+            max_media_size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unrestricted_feed_allows_any_media() {
+        let config = config(None, None);
+        assert_eq!(check_media(&config, MediaKind::Video, Some(u64::MAX)), None);
+    }
+
+    #[test]
+    fn test_media_kind_and_size_restrictions() {
+        let config = config(Some(vec![MediaKind::Image]), Some(1_000_000));
+
+        assert_eq!(
+            check_media(&config, MediaKind::Image, Some(1_000_000)),
+            None
+        );
+        assert_eq!(
+            check_media(&config, MediaKind::Image, Some(1_000_001)),
+            Some(MediaViolation::TooLarge {
+                size: 1_000_001,
+                max_size: 1_000_000,
+            })
+        );
+        // Media of unknown size can only be checked for its kind
+        assert_eq!(check_media(&config, MediaKind::Image, None), None);
+        assert_eq!(
+            check_media(&config, MediaKind::Video, Some(10)),
+            Some(MediaViolation::KindNotAllowed(MediaKind::Video))
+        );
+        assert_eq!(
+            MediaViolation::KindNotAllowed(MediaKind::Video).to_string(),
+            "Videos aren't allowed"
+        );
+    }
+
+    #[test]
+    fn test_media_from_content() {
+        let content = json!({
+            "msgtype": "m.video",
+            "body": "hike.mp4",
+            "url": "mxc://example.org/hike",
+            "info": { "size": 2048 },
+        });
+        assert_eq!(
+            media_from_content(&content),
+            Some((MediaKind::Video, Some(2048)))
+        );
+        let content = json!({ "msgtype": "m.image", "body": "sunset.jpg" });
+        assert_eq!(media_from_content(&content), Some((MediaKind::Image, None)));
+        let content = json!({ "msgtype": "m.text", "body": "Hello" });
+        assert_eq!(media_from_content(&content), None);
+    }
+}
@@ -0,0 +1,220 @@
+//! Notifications about the user being mentioned in comments.
+//!
+//! The post watcher (see [`spawn_post_watcher()`](crate::social::post_watch::spawn_post_watcher))
+//! publishes a [`SocialEvent::CommentMention`](crate::social::SocialEvent::CommentMention)
+//! for every comment in a feed room that mentions the user. Each mention comes
+//! with a short snippet of the comment around the mention, and a [`CommentLink`]
+//! to open the comment in the thread view of its post, where it's highlighted
+//! when it arrives (see [`SocialCommentThread::show_comment()`]).
+//!
+//! [`SocialCommentThread::show_comment()`]: crate::social::widgets::comment_thread::SocialCommentThread::show_comment
+
+use matrix_sdk::ruma::{
+    events::room::message::{OriginalSyncRoomMessageEvent, Relation},
+    MatrixToUri, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId, UserId,
+};
+
+/// The maximum number of characters of a comment shown in a mention notification.
+pub const MENTION_SNIPPET_LENGTH: usize = 120;
+
+/// A link to a comment, opened in the thread view of the post it's on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommentLink {
+    /// The feed room the post is in.
+    pub room_id: OwnedRoomId,
+    /// The post the comment is on.
+    pub post_id: OwnedEventId,
+    /// The comment.
+    pub comment_id: OwnedEventId,
+}
+
+impl CommentLink {
+    /// Get a `matrix.to` permalink to the comment, e.g., to share it.
+    pub fn permalink(&self) -> MatrixToUri {
+        self.room_id.matrix_to_event_uri(self.comment_id.clone())
+    }
+}
+
+/// A comment that mentions the user.
+#[derive(Clone, Debug)]
+pub struct CommentMention {
+    /// Where to find the comment.
+    pub link: CommentLink,
+    /// Author of the comment.
+    pub sender: OwnedUserId,
+    /// The part of the comment around the mention (see [`mention_snippet()`]).
+    pub snippet: String,
+}
+
+impl CommentMention {
+    /// Get the text of a notification about the mention,
+    /// given the name to show for its author.
+    pub fn notification_text(&self, sender_name: &str) -> String {
+        format!(
+            "{sender_name} mentioned you in a comment: “{}”",
+            self.snippet
+        )
+    }
+}
+
+/// Get the mention of the user that a message in a feed room is, if it's a comment
+/// on a post that mentions them.
+///
+/// The user is mentioned if they're among the comment's intentional mentions,
+/// or if their user ID is in its text. The user's own comments are ignored.
+pub fn comment_mention(
+    room_id: &RoomId,
+    message: &OriginalSyncRoomMessageEvent,
+    own_user_id: &UserId,
+) -> Option<CommentMention> {
+    if message.sender == own_user_id {
+        return None;
+    }
+    let post_id = match message.content.relates_to.as_ref()? {
+        Relation::Reply { in_reply_to } => &in_reply_to.event_id,
+        Relation::Thread(thread) => &thread.event_id,
+        _ => return None,
+    };
+    let body = message.content.body();
+    let mentioned = message
+        .content
+        .mentions
+        .as_ref()
+        .is_some_and(|mentions| mentions.user_ids.contains(own_user_id))
+        || body.contains(own_user_id.as_str());
+    mentioned.then(|| CommentMention {
+        link: CommentLink {
+            room_id: room_id.to_owned(),
+            post_id: post_id.clone(),
+            comment_id: message.event_id.clone(),
+        },
+        sender: message.sender.clone(),
+        snippet: mention_snippet(body, own_user_id),
+    })
+}
+
+/// Get up to [`MENTION_SNIPPET_LENGTH`] characters of a comment around the first
+/// mention of the user, on a single line, with an ellipsis where it was cut.
+///
+/// The snippet starts at the beginning of the comment if the user isn't
+/// mentioned by their user ID in its text, e.g., if it mentions their display name.
+pub fn mention_snippet(body: &str, user_id: &UserId) -> String {
+    let text = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= MENTION_SNIPPET_LENGTH {
+        return text;
+    }
+    // Keep some of the text before the mention for context
+    let mention_start = text
+        .find(&format!("@{}", user_id.localpart()))
+        .map_or(0, |byte_index| text[..byte_index].chars().count());
+    let start = mention_start
+        .saturating_sub(MENTION_SNIPPET_LENGTH / 3)
+        .min(chars.len() - MENTION_SNIPPET_LENGTH);
+    let end = start + MENTION_SNIPPET_LENGTH;
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.extend(&chars[start..end]);
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{room_id, user_id};
+    use serde_json::json;
+
+    fn comment(sender: &str, content: serde_json::Value) -> OriginalSyncRoomMessageEvent {
+        serde_json::from_value(json!({
+            "type": "m.room.message",
+            "event_id": "$comment:example.org",
+            "sender": sender,
+            "origin_server_ts": 1,
+            "content": content,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mentions_in_comments() {
+        let room_id = room_id!("!feed:example.org");
+        let own_user_id = user_id!("@me:example.org");
+        let thread = json!({
+            "rel_type": "m.thread",
+            "event_id": "$post:example.org",
+            "is_falling_back": true,
+            "m.in_reply_to": { "event_id": "$post:example.org" },
+        });
+
+        let intentional = comment(
+            "@friend:example.org",
+            json!({
+                "msgtype": "m.text",
+                "body": "Me, you should see this",
+                "m.mentions": { "user_ids": ["@me:example.org"] },
+                "m.relates_to": thread.clone(),
+            }),
+        );
+        let mention = comment_mention(room_id, &intentional, own_user_id).unwrap();
+        assert_eq!(mention.link.post_id, "$post:example.org");
+        assert_eq!(mention.link.comment_id, "$comment:example.org");
+        assert_eq!(mention.snippet, "Me, you should see this");
+
+        let by_user_id = comment(
+            "@friend:example.org",
+            json!({
+                "msgtype": "m.text",
+                "body": "cc @me:example.org",
+                "m.relates_to": { "m.in_reply_to": { "event_id": "$post:example.org" } },
+            }),
+        );
+        assert!(comment_mention(room_id, &by_user_id, own_user_id).is_some());
+
+        // Comments without a mention, and mentions outside of comments, are ignored
+        let unrelated = comment(
+            "@friend:example.org",
+            json!({ "msgtype": "m.text", "body": "Nice!", "m.relates_to": thread.clone() }),
+        );
+        assert!(comment_mention(room_id, &unrelated, own_user_id).is_none());
+        let post = comment(
+            "@friend:example.org",
+            json!({ "msgtype": "m.text", "body": "Hi @me:example.org" }),
+        );
+        assert!(comment_mention(room_id, &post, own_user_id).is_none());
+        let own_comment = comment(
+            "@me:example.org",
+            json!({
+                "msgtype": "m.text",
+                "body": "@me:example.org",
+                "m.relates_to": thread.clone(),
+            }),
+        );
+        assert!(comment_mention(room_id, &own_comment, own_user_id).is_none());
+    }
+
+    #[test]
+    fn test_mention_snippet_keeps_context() {
+        let user_id = user_id!("@me:example.org");
+        assert_eq!(
+            mention_snippet("Hey\n\n@me:example.org  look", user_id),
+            "Hey @me:example.org look"
+        );
+
+        let before = "a".repeat(200);
+        let after = "b".repeat(200);
+        let snippet = mention_snippet(&format!("{before} @me:example.org {after}"), user_id);
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert_eq!(snippet.chars().count(), MENTION_SNIPPET_LENGTH + 2);
+        assert!(snippet.contains("aaaa @me:example.org bbbb"));
+
+        // Without the user ID in the text, the snippet starts at the beginning
+        let snippet = mention_snippet(&after, user_id);
+        assert!(snippet.starts_with('b') && snippet.ends_with('…'));
+    }
+}
@@ -0,0 +1,827 @@
+//! Backup and restore of a user's social rooms to another account.
+//!
+//! Migration happens in two steps, so the old and new accounts can live on
+//! different homeservers and don't need to be logged in at the same time:
+//! 1. [`MigrationService::export()`] on the old account collects the social
+//!    profile, the friends space and the user's own posts in each feed into a
+//!    [`SocialExport`], which can be saved as JSON.
+//! 2. [`MigrationService::restore()`] on the new account re-creates the
+//!    profile room, friends space and feeds, and re-posts the owned posts
+//!    and polls. Each re-posted message carries its original event ID and
+//!    timestamp in [`MigratedFrom`] metadata, and media is re-uploaded to the
+//!    new homeserver where possible, encrypted if the new feed is encrypted.
+//!
+//! The media of posts in encrypted feeds is exported with the keys to decrypt
+//! it, so an export of encrypted feeds must be kept as private as the feeds.
+//!
+//! Restoring produces a [`MigrationReport`] that maps old room and event IDs
+//! to their new counterparts and lists anything that could not be migrated.
+
+use matrix_sdk::{
+    media::{MediaFormat, MediaRequestParameters},
+    room::{MessagesOptions, Room},
+    ruma::{
+        events::{
+            poll::unstable_start::{
+                NewUnstablePollStartEventContent, UnstablePollStartEventContent,
+            },
+            room::{
+                message::{MessageType, Relation, RoomMessageEventContent},
+                MediaSource,
+            },
+            AnySyncMessageLikeEvent, AnySyncTimelineEvent, SyncMessageLikeEvent,
+        },
+        EventId, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId,
+        UserId,
+    },
+    Client,
+};
+use robrix_social_events::{
+    license::{ContentLicense, LICENSE_FIELD},
+    migration::{MigratedFrom, MIGRATED_FROM_FIELD},
+    profile::SocialProfileEventContent,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::social::{
+    feed_room::{FeedPrivacy, FeedRoomError, FeedRoomService},
+    friends::{FriendsError, FriendsSpaceService},
+    poll::POLL_START_EVENT_TYPE,
+    post::license_from_content,
+    profile_room::{ProfileRoomError, ProfileRoomService},
+};
+
+/// Version of the [`SocialExport`] format.
+///
+/// Version 2 added polls (see [`ExportedContent::Poll`]).
+const EXPORT_VERSION: u32 = 2;
+
+/// Delay between re-posted messages, to avoid hitting rate limits.
+const REPOST_DELAY: Duration = Duration::from_millis(250);
+
+/// All feed privacy levels, in the order they are migrated.
+const ALL_FEEDS: [FeedPrivacy; 3] = [
+    FeedPrivacy::Public,
+    FeedPrivacy::Friends,
+    FeedPrivacy::CloseFriends,
+];
+
+/// A backup of a user's social rooms, as produced by [`MigrationService::export()`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SocialExport {
+    /// Version of the export format.
+    pub version: u32,
+    /// The account that was exported.
+    pub user_id: OwnedUserId,
+    /// When the export was made.
+    pub exported_at: MilliSecondsSinceUnixEpoch,
+    /// The user's social profile, if they have a profile room.
+    pub profile: Option<ExportedProfile>,
+    /// The user's friends space, if they have one.
+    pub friends_space: Option<OwnedRoomId>,
+    /// Feed rooms of the user's friends, i.e., the children of the friends space.
+    pub friends: Vec<OwnedRoomId>,
+    /// The user's own feeds.
+    pub feeds: Vec<ExportedFeed>,
+}
+
+impl SocialExport {
+    /// Serialize this export to JSON.
+    ///
+    /// # Errors
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, MigrationError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse an export from JSON.
+    ///
+    /// # Errors
+    /// Returns an error if the JSON is malformed or was written by a newer
+    /// version of the export format.
+    pub fn from_json(json: &str) -> Result<Self, MigrationError> {
+        let export: Self = serde_json::from_str(json)?;
+        if export.version > EXPORT_VERSION {
+            return Err(MigrationError::UnsupportedVersion(export.version));
+        }
+        Ok(export)
+    }
+
+    /// Get the total number of exported posts across all feeds.
+    pub fn post_count(&self) -> usize {
+        self.feeds.iter().map(|feed| feed.posts.len()).sum()
+    }
+}
+
+/// An exported social profile.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExportedProfile {
+    /// The old profile room.
+    pub room_id: OwnedRoomId,
+    /// The profile content.
+    pub content: SocialProfileEventContent,
+}
+
+/// An exported feed with the user's own posts.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExportedFeed {
+    /// Privacy level of the feed.
+    pub privacy: FeedPrivacy,
+    /// The old feed room.
+    pub room_id: OwnedRoomId,
+    /// Whether the old feed room was end-to-end encrypted, so the new one is too.
+    #[serde(default)]
+    pub encrypted: bool,
+    /// The user's posts and polls in the feed, oldest first.
+    pub posts: Vec<ExportedPost>,
+}
+
+/// An exported post.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExportedPost {
+    /// Event ID of the original post.
+    pub event_id: OwnedEventId,
+    /// When the original post was sent.
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+    /// Content of the post.
+    pub content: ExportedContent,
+    /// License the post's content was shared under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<ContentLicense>,
+}
+
+/// The content of an exported post.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ExportedContent {
+    /// A message, e.g., a text or media post.
+    Message(RoomMessageEventContent),
+    /// The start of a poll.
+    Poll(NewUnstablePollStartEventContent),
+}
+
+/// The kind of a migrated room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigratedRoomKind {
+    /// The profile room.
+    Profile,
+    /// The friends space.
+    FriendsSpace,
+    /// A feed room.
+    Feed(FeedPrivacy),
+}
+
+/// A room on the old account and the room that replaces it on the new account.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RoomMapping {
+    /// The kind of room.
+    pub kind: MigratedRoomKind,
+    /// The room on the old account.
+    pub old_room_id: OwnedRoomId,
+    /// The room on the new account.
+    pub new_room_id: OwnedRoomId,
+}
+
+/// A post on the old account and its re-posted copy on the new account.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PostMapping {
+    /// Event ID of the original post.
+    pub old_event_id: OwnedEventId,
+    /// Event ID of the re-posted copy.
+    pub new_event_id: OwnedEventId,
+    /// When the original post was sent.
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+}
+
+/// Something that could not be migrated.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MigrationFailure {
+    /// What failed to migrate, e.g., "Post $abc".
+    pub item: String,
+    /// Why it failed.
+    pub error: String,
+}
+
+/// The outcome of restoring a [`SocialExport`] on a new account.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MigrationReport {
+    /// The account that was exported.
+    pub old_user_id: OwnedUserId,
+    /// The account the export was restored on.
+    pub new_user_id: OwnedUserId,
+    /// Re-created rooms.
+    pub rooms: Vec<RoomMapping>,
+    /// Re-posted posts.
+    pub posts: Vec<PostMapping>,
+    /// Number of friends added to the new friends space.
+    pub friends_restored: usize,
+    /// Things that could not be migrated.
+    pub failures: Vec<MigrationFailure>,
+}
+
+impl MigrationReport {
+    fn new(old_user_id: OwnedUserId, new_user_id: OwnedUserId) -> Self {
+        Self {
+            old_user_id,
+            new_user_id,
+            rooms: Vec::new(),
+            posts: Vec::new(),
+            friends_restored: 0,
+            failures: Vec::new(),
+        }
+    }
+
+    fn fail(&mut self, item: impl Into<String>, error: impl std::fmt::Display) {
+        self.failures.push(MigrationFailure {
+            item: item.into(),
+            error: error.to_string(),
+        });
+    }
+
+    /// Get the new room that replaces a room of the old account.
+    pub fn new_room_id(&self, old_room_id: &RoomId) -> Option<&OwnedRoomId> {
+        self.rooms
+            .iter()
+            .find(|mapping| *mapping.old_room_id == *old_room_id)
+            .map(|mapping| &mapping.new_room_id)
+    }
+
+    /// Get the re-posted copy of a post of the old account.
+    pub fn new_event_id(&self, old_event_id: &EventId) -> Option<&OwnedEventId> {
+        self.posts
+            .iter()
+            .find(|mapping| *mapping.old_event_id == *old_event_id)
+            .map(|mapping| &mapping.new_event_id)
+    }
+
+    /// Get a short human-readable summary of the migration.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Migrated {} rooms, {} posts and {} friends from {} to {}.",
+            self.rooms.len(),
+            self.posts.len(),
+            self.friends_restored,
+            self.old_user_id,
+            self.new_user_id,
+        );
+        if !self.failures.is_empty() {
+            summary.push_str(&format!(
+                " {} items could not be migrated.",
+                self.failures.len()
+            ));
+        }
+        summary
+    }
+
+    /// Serialize this report to JSON.
+    ///
+    /// # Errors
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, MigrationError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Service for exporting social rooms from one account and restoring them on another.
+pub struct MigrationService {
+    client: Client,
+}
+
+impl MigrationService {
+    /// Create a new MigrationService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Export the current user's social profile, friends space and own posts and polls.
+    ///
+    /// Edits are not exported separately; posts are exported with their
+    /// original content.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or room state or
+    /// messages cannot be loaded.
+    pub async fn export(&self) -> Result<SocialExport, MigrationError> {
+        let user_id = self
+            .client
+            .user_id()
+            .ok_or(MigrationError::NotLoggedIn)?
+            .to_owned();
+
+        let profile_service = ProfileRoomService::new(self.client.clone());
+        let profile = match profile_service.find_profile_room(&user_id).await? {
+            Some(room_id) => profile_service
+                .get_profile(&room_id)
+                .await?
+                .map(|content| ExportedProfile { room_id, content }),
+            None => None,
+        };
+
+        let mut friends_space = FriendsSpaceService::new(self.client.clone());
+        let friends_space_id = friends_space.find_existing_friends_space().await?;
+        let friends = if friends_space_id.is_some() {
+            friends_space.get_friends().await?
+        } else {
+            Vec::new()
+        };
+
+        let feed_service = FeedRoomService::new(self.client.clone());
+        let mut feeds = Vec::new();
+        for privacy in ALL_FEEDS {
+            let Some(room) = feed_service.find_joined_feed(&user_id, privacy) else {
+                continue;
+            };
+            feeds.push(ExportedFeed {
+                privacy,
+                room_id: room.room_id().to_owned(),
+                encrypted: room.encryption_state().is_encrypted(),
+                posts: export_own_posts(&room, &user_id).await?,
+            });
+        }
+
+        Ok(SocialExport {
+            version: EXPORT_VERSION,
+            user_id,
+            exported_at: MilliSecondsSinceUnixEpoch::now(),
+            profile,
+            friends_space: friends_space_id,
+            friends,
+            feeds,
+        })
+    }
+
+    /// Restore an export on the current user's account.
+    ///
+    /// Existing social rooms of the current user are reused, so a restore
+    /// that was interrupted can be retried, although posts that were
+    /// already re-posted will be posted again. Failures of individual rooms,
+    /// friends or posts don't stop the migration; they are listed in the
+    /// returned report instead.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or is the exported account.
+    pub async fn restore(&self, export: &SocialExport) -> Result<MigrationReport, MigrationError> {
+        let user_id = self
+            .client
+            .user_id()
+            .ok_or(MigrationError::NotLoggedIn)?
+            .to_owned();
+        if user_id == export.user_id {
+            return Err(MigrationError::SameAccount);
+        }
+        let mut report = MigrationReport::new(export.user_id.clone(), user_id.clone());
+
+        if let Some(profile) = &export.profile {
+            match self
+                .restore_profile(&user_id, profile.content.clone())
+                .await
+            {
+                Ok(new_room_id) => report.rooms.push(RoomMapping {
+                    kind: MigratedRoomKind::Profile,
+                    old_room_id: profile.room_id.clone(),
+                    new_room_id,
+                }),
+                Err(e) => report.fail(format!("Profile room {}", profile.room_id), e),
+            }
+        }
+
+        let mut friends_space = FriendsSpaceService::new(self.client.clone());
+        let friends_space_id = match friends_space.get_or_create_friends_space().await {
+            Ok(new_room_id) => {
+                if let Some(old_room_id) = &export.friends_space {
+                    report.rooms.push(RoomMapping {
+                        kind: MigratedRoomKind::FriendsSpace,
+                        old_room_id: old_room_id.clone(),
+                        new_room_id: new_room_id.clone(),
+                    });
+                }
+                Some(new_room_id)
+            }
+            Err(e) => {
+                report.fail("Friends space", e);
+                None
+            }
+        };
+        if friends_space_id.is_some() {
+            for friend in &export.friends {
+                match friends_space.add_friend(friend).await {
+                    Ok(()) => report.friends_restored += 1,
+                    Err(e) => report.fail(format!("Friend {}", friend), e),
+                }
+            }
+        }
+
+        for feed in &export.feeds {
+            let room = match self
+                .restore_feed(
+                    &user_id,
+                    feed.privacy,
+                    friends_space_id.as_deref(),
+                    feed.encrypted,
+                )
+                .await
+            {
+                Ok(room) => room,
+                Err(e) => {
+                    report.fail(format!("{} {}", feed.privacy, feed.room_id), e);
+                    continue;
+                }
+            };
+            report.rooms.push(RoomMapping {
+                kind: MigratedRoomKind::Feed(feed.privacy),
+                old_room_id: feed.room_id.clone(),
+                new_room_id: room.room_id().to_owned(),
+            });
+
+            for post in &feed.posts {
+                let migrated_from = MigratedFrom {
+                    event_id: post.event_id.clone(),
+                    room_id: feed.room_id.clone(),
+                    sender: export.user_id.clone(),
+                    origin_server_ts: post.origin_server_ts,
+                };
+                match self.repost(&room, post, migrated_from, &mut report).await {
+                    Ok(new_event_id) => report.posts.push(PostMapping {
+                        old_event_id: post.event_id.clone(),
+                        new_event_id,
+                        origin_server_ts: post.origin_server_ts,
+                    }),
+                    Err(e) => report.fail(format!("Post {}", post.event_id), e),
+                }
+                tokio::time::sleep(REPOST_DELAY).await;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Update the current user's profile room, creating it if needed.
+    async fn restore_profile(
+        &self,
+        user_id: &UserId,
+        content: SocialProfileEventContent,
+    ) -> Result<OwnedRoomId, MigrationError> {
+        let service = ProfileRoomService::new(self.client.clone());
+        match service.find_profile_room(user_id).await? {
+            Some(room_id) => {
+                service.update_profile(&room_id, content).await?;
+                Ok(room_id)
+            }
+            None => Ok(service.create_profile_room(content).await?),
+        }
+    }
+
+    /// Get the current user's feed room of the given privacy level, creating it if needed.
+    async fn restore_feed(
+        &self,
+        user_id: &UserId,
+        privacy: FeedPrivacy,
+        friends_space_id: Option<&RoomId>,
+        encrypted: bool,
+    ) -> Result<Room, MigrationError> {
+        let service = FeedRoomService::new(self.client.clone());
+        if let Some(room) = service.find_joined_feed(user_id, privacy) {
+            return Ok(room);
+        }
+        let room_id = service
+            .create_feed_room(privacy, friends_space_id, encrypted)
+            .await?;
+        self.client
+            .get_room(&room_id)
+            .ok_or(MigrationError::Feed(FeedRoomError::FeedNotFound))
+    }
+
+    /// Re-post a post to a feed room with its origin attached.
+    ///
+    /// Relations to other posts are dropped, since they point to events of
+    /// the old account. Media that cannot be re-uploaded keeps its original
+    /// source and is listed in the report.
+    async fn repost(
+        &self,
+        room: &Room,
+        post: &ExportedPost,
+        migrated_from: MigratedFrom,
+        report: &mut MigrationReport,
+    ) -> Result<OwnedEventId, MigrationError> {
+        let (event_type, mut json) = match &post.content {
+            ExportedContent::Message(content) => {
+                let mut content = content.clone();
+                content.relates_to = None;
+                let encrypted = room.encryption_state().is_encrypted();
+                if let Err(e) = self.rehost_media(&mut content.msgtype, encrypted).await {
+                    report.fail(format!("Media of post {}", post.event_id), e);
+                }
+                ("m.room.message", serde_json::to_value(&content)?)
+            }
+            ExportedContent::Poll(content) => {
+                (POLL_START_EVENT_TYPE, serde_json::to_value(content)?)
+            }
+        };
+        if let Some(object) = json.as_object_mut() {
+            object.remove("m.relates_to");
+            object.insert(
+                MIGRATED_FROM_FIELD.to_string(),
+                serde_json::to_value(&migrated_from)?,
+            );
+            if let Some(license) = post.license {
+                object.insert(LICENSE_FIELD.to_string(), serde_json::to_value(license)?);
+            }
+        }
+
+        let response = room
+            .send_raw(event_type, json)
+            .await
+            .map_err(MigrationError::MatrixError)?;
+        Ok(response.event_id)
+    }
+
+    /// Download a message's media and upload it to the current user's homeserver,
+    /// encrypting it if it's re-posted to an encrypted feed.
+    ///
+    /// Media of encrypted feeds is decrypted with the keys in its exported source.
+    async fn rehost_media(
+        &self,
+        msgtype: &mut MessageType,
+        encrypted: bool,
+    ) -> Result<(), MigrationError> {
+        let (source, mimetype) = match msgtype {
+            MessageType::Image(c) => (
+                &mut c.source,
+                c.info.as_ref().and_then(|i| i.mimetype.clone()),
+            ),
+            MessageType::Video(c) => (
+                &mut c.source,
+                c.info.as_ref().and_then(|i| i.mimetype.clone()),
+            ),
+            MessageType::Audio(c) => (
+                &mut c.source,
+                c.info.as_ref().and_then(|i| i.mimetype.clone()),
+            ),
+            MessageType::File(c) => (
+                &mut c.source,
+                c.info.as_ref().and_then(|i| i.mimetype.clone()),
+            ),
+            _ => return Ok(()),
+        };
+        let request = MediaRequestParameters {
+            source: source.clone(),
+            format: MediaFormat::File,
+        };
+        let data = self
+            .client
+            .media()
+            .get_media_content(&request, false)
+            .await
+            .map_err(MigrationError::MatrixError)?;
+
+        if encrypted {
+            // The server only gets opaque bytes, so there's no content type to give it
+            let file = self
+                .client
+                .upload_encrypted_file(&mut data.as_slice())
+                .await
+                .map_err(MigrationError::MatrixError)?;
+            *source = MediaSource::Encrypted(file.into());
+            return Ok(());
+        }
+        let content_type = mimetype
+            .and_then(|m| m.parse::<mime::Mime>().ok())
+            .unwrap_or(mime::APPLICATION_OCTET_STREAM);
+        let response = self
+            .client
+            .media()
+            .upload(&content_type, data, None)
+            .await
+            .map_err(MigrationError::MatrixError)?;
+
+        *source = MediaSource::Plain(response.content_uri);
+        Ok(())
+    }
+}
+
+/// Load the user's own posts and polls in a feed room, oldest first.
+///
+/// Edits and redacted posts are skipped.
+async fn export_own_posts(
+    room: &Room,
+    user_id: &UserId,
+) -> Result<Vec<ExportedPost>, MigrationError> {
+    let mut posts = Vec::new();
+    let mut from = None;
+    loop {
+        let mut options = MessagesOptions::backward();
+        options.from = from.take();
+        let response = room
+            .messages(options)
+            .await
+            .map_err(MigrationError::MatrixError)?;
+
+        for event in &response.chunk {
+            let Ok(AnySyncTimelineEvent::MessageLike(message_like)) = event.raw().deserialize()
+            else {
+                continue;
+            };
+            if message_like.sender() != user_id {
+                continue;
+            }
+            let (event_id, origin_server_ts, content) = match message_like {
+                AnySyncMessageLikeEvent::RoomMessage(SyncMessageLikeEvent::Original(message))
+                    if !matches!(message.content.relates_to, Some(Relation::Replacement(_))) =>
+                {
+                    (
+                        message.event_id,
+                        message.origin_server_ts,
+                        ExportedContent::Message(message.content),
+                    )
+                }
+                AnySyncMessageLikeEvent::UnstablePollStart(SyncMessageLikeEvent::Original(
+                    poll,
+                )) => match poll.content {
+                    UnstablePollStartEventContent::New(content) => (
+                        poll.event_id,
+                        poll.origin_server_ts,
+                        ExportedContent::Poll(content),
+                    ),
+                    UnstablePollStartEventContent::Replacement(_) => continue,
+                },
+                _ => continue,
+            };
+            // The license is a custom field, which the typed content doesn't keep
+            let license = event
+                .raw()
+                .get_field::<serde_json::Value>("content")
+                .ok()
+                .flatten()
+                .and_then(|content| license_from_content(&content));
+            posts.push(ExportedPost {
+                event_id,
+                origin_server_ts,
+                content,
+                license,
+            });
+        }
+
+        match response.end {
+            Some(end) if !response.chunk.is_empty() => from = Some(end),
+            _ => break,
+        }
+    }
+
+    // Messages were loaded newest first
+    posts.reverse();
+    Ok(posts)
+}
+
+/// Errors that can occur when migrating social rooms.
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The export belongs to the current account.
+    #[error("Cannot restore an export on the account it was made from")]
+    SameAccount,
+
+    /// The export was made by a newer version of the app.
+    #[error("Unsupported export version: {0}")]
+    UnsupportedVersion(u32),
+
+    /// The export could not be (de)serialized.
+    #[error("Invalid export: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The profile room could not be read or re-created.
+    #[error("Profile error: {0}")]
+    Profile(#[from] ProfileRoomError),
+
+    /// A feed room could not be read or re-created.
+    #[error("Feed error: {0}")]
+    Feed(#[from] FeedRoomError),
+
+    /// The friends space could not be read or re-created.
+    #[error("Friends error: {0}")]
+    Friends(#[from] FriendsError),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::poll::{poll_start_content, PollAnswer};
+    use matrix_sdk::ruma::{event_id, owned_event_id, owned_room_id, owned_user_id, uint};
+
+    fn export() -> SocialExport {
+        SocialExport {
+            version: EXPORT_VERSION,
+            user_id: owned_user_id!("@alice:old.example"),
+            exported_at: MilliSecondsSinceUnixEpoch(uint!(1_700_000_000_000)),
+            profile: None,
+            friends_space: None,
+            friends: vec![owned_room_id!("!bobfeed:example.org")],
+            feeds: vec![ExportedFeed {
+                privacy: FeedPrivacy::Public,
+                room_id: owned_room_id!("!feed:old.example"),
+                encrypted: false,
+                posts: vec![ExportedPost {
+                    event_id: owned_event_id!("$post:old.example"),
+                    origin_server_ts: MilliSecondsSinceUnixEpoch(uint!(1_600_000_000_000)),
+                    content: ExportedContent::Message(RoomMessageEventContent::text_plain("Hello")),
+                    license: Some(ContentLicense::CcBy),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_export_json_round_trip() {
+        let json = export().to_json().unwrap();
+        let parsed = SocialExport::from_json(&json).unwrap();
+        assert_eq!(parsed.user_id, "@alice:old.example");
+        assert_eq!(parsed.feeds[0].privacy, FeedPrivacy::Public);
+        assert_eq!(parsed.post_count(), 1);
+        assert!(matches!(
+            &parsed.feeds[0].posts[0].content,
+            ExportedContent::Message(content) if content.body() == "Hello"
+        ));
+        assert_eq!(parsed.feeds[0].posts[0].license, Some(ContentLicense::CcBy));
+    }
+
+    #[test]
+    fn test_exported_polls_round_trip() {
+        let answers: Vec<PollAnswer> = ["Tea", "Coffee"]
+            .iter()
+            .enumerate()
+            .map(|(index, text)| PollAnswer {
+                id: (index + 1).to_string(),
+                text: text.to_string(),
+            })
+            .collect();
+        let mut export = export();
+        export.feeds[0].posts.push(ExportedPost {
+            event_id: owned_event_id!("$poll:old.example"),
+            origin_server_ts: MilliSecondsSinceUnixEpoch(uint!(1_600_000_001_000)),
+            content: ExportedContent::Poll(
+                poll_start_content("Tea or coffee?", &answers, 1).unwrap(),
+            ),
+            license: None,
+        });
+
+        let parsed = SocialExport::from_json(&export.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.post_count(), 2);
+        assert!(matches!(
+            &parsed.feeds[0].posts[0].content,
+            ExportedContent::Message(_)
+        ));
+        assert!(matches!(
+            &parsed.feeds[0].posts[1].content,
+            ExportedContent::Poll(content) if content.poll_start.question.text == "Tea or coffee?"
+        ));
+    }
+
+    #[test]
+    fn test_newer_export_version_is_rejected() {
+        let mut export = export();
+        export.version = EXPORT_VERSION + 1;
+        let json = export.to_json().unwrap();
+        assert!(matches!(
+            SocialExport::from_json(&json),
+            Err(MigrationError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_report_maps_old_ids_to_new() {
+        let mut report = MigrationReport::new(
+            owned_user_id!("@alice:old.example"),
+            owned_user_id!("@alice:new.example"),
+        );
+        report.rooms.push(RoomMapping {
+            kind: MigratedRoomKind::Feed(FeedPrivacy::Public),
+            old_room_id: owned_room_id!("!feed:old.example"),
+            new_room_id: owned_room_id!("!feed:new.example"),
+        });
+        report.posts.push(PostMapping {
+            old_event_id: owned_event_id!("$post:old.example"),
+            new_event_id: owned_event_id!("$post:new.example"),
+            origin_server_ts: MilliSecondsSinceUnixEpoch(uint!(1_600_000_000_000)),
+        });
+
+        assert_eq!(
+            report
+                .new_room_id(&owned_room_id!("!feed:old.example"))
+                .map(|id| id.as_str()),
+            Some("!feed:new.example")
+        );
+        assert_eq!(
+            report.new_event_id(event_id!("$post:old.example")),
+            Some(&owned_event_id!("$post:new.example"))
+        );
+        assert!(report.summary().starts_with("Migrated 1 rooms, 1 posts"));
+    }
+}
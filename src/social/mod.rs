@@ -5,15 +5,52 @@
 
 use makepad_widgets::*;
 
+pub mod activity_log;
+pub mod audio_clip;
+pub mod availability;
+pub mod block_list;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod co_author;
 pub mod discovery;
+pub mod duplicate_media;
+pub mod duplicate_posts;
+pub mod event_bus;
 pub mod events;
+pub mod feed_moderation;
 pub mod feed_room;
+pub mod follow;
+pub mod follower_moderation;
 pub mod friends;
+pub mod main_feed;
+pub mod maps;
+pub mod media_policy;
+pub mod mentions;
+pub mod migration;
 pub mod newsfeed;
+pub mod optimistic;
+pub mod outbox;
+pub mod poll;
 pub mod post;
+pub mod post_insights;
+pub mod post_links;
+pub mod post_watch;
+pub mod presence;
 pub mod privacy;
+pub mod push_rules;
+pub mod profile_edit;
+pub mod profile_links;
+pub mod profile_media;
 pub mod profile_room;
+pub mod profile_stats;
+pub mod profile_tabs;
 pub mod reactions;
+pub mod retention;
+pub mod share_to_chat;
+pub mod storage_usage;
+pub mod ticker;
+pub mod translation;
+pub mod user_verification;
 pub mod widgets;
 
 mod actions;
@@ -22,20 +59,94 @@ mod requests;
 // Note: actions and requests modules are placeholders for future use.
 // Re-exports will be added when the modules have public items.
 
+// Re-export runtime availability of social features
+pub use availability::{is_social_enabled, SocialAvailabilityAction};
+pub use widgets::social_gate::SocialGate;
+
 // Re-export core types from profile_room (Phase 2)
+pub use profile_edit::{ProfileDraft, ProfileField, ProfileFieldError};
+pub use profile_links::{
+    parse_profile_link, profile_qr_code, ProfileLinkError, ProfileLinkKind, QrCodeImage,
+    PROFILE_LINK_PREFIX,
+};
+pub use profile_media::{prepare_profile_image, ProfileImageError, ProfileImageKind};
 pub use profile_room::{ProfileRoomConfig, ProfileRoomError, ProfileRoomService};
+pub use profile_stats::{ProfileCounts, ProfileStatsError, ProfileStatsService};
 
 // Re-export profile page widgets (Phase 2)
+pub use widgets::profile_editor::{SocialProfileEditor, SocialProfileEditorAction};
 pub use widgets::profile_page::{LoadedProfile, SocialProfileAction, SocialProfilePage};
 
 // Re-export feed room types (Phase 3)
 pub use feed_room::{FeedPrivacy, FeedRoomError, FeedRoomService, UserFeeds};
+pub use media_policy::{check_media, check_post_media, media_from_content, MediaViolation};
+pub use follow::{FollowError, FollowListUser, FollowListsAction, FollowService};
+pub use follower_moderation::{FollowerModerationError, FollowerModerationService};
+pub use feed_moderation::{
+    FeedModerationAction, FeedModerationError, FeedModerationService, ModerationPermissions,
+};
+pub use block_list::{is_blocked, BlockListError, BlockListService};
 
 // Re-export post types (Phase 3)
-pub use post::{FeedPost, Post, PostContent, PostError, PostMetadata};
+pub use co_author::{
+    co_authorship_from_content, displayed_co_author, invite_co_author, CoAuthorError,
+    CoAuthorInvite, CoAuthorService,
+};
+pub use post::{
+    alt_text_from_content, crosspost_id_from_content, formatted_body_from_content,
+    license_from_content, load_default_license, location_from_content, markdown_to_html,
+    post_event_type, save_default_license, visibility_from_content, FeedPost, Post, PostContent,
+    PostError, PostMetadata,
+};
+pub use post_links::{post_text_html, PostLink, HASHTAG_LINK_PREFIX};
+pub use audio_clip::{
+    analyze_wav, audio_details_from_content, format_duration, waveform_from_samples, AudioDetails,
+    AUDIO_DETAILS_FIELD, MAX_WAVEFORM_AMPLITUDE, VOICE_FIELD, WAVEFORM_LENGTH,
+};
+pub use poll::{
+    latest_votes, poll_fallback_text, poll_from_content, poll_start_content, tally_poll,
+    AnswerResult, PollAnswer, PollError, PollResults, PollService, PollVote, MAX_POLL_ANSWERS,
+    POLL_END_EVENT_TYPE, POLL_RESPONSE_EVENT_TYPE, POLL_START_EVENT_TYPE,
+};
+pub use duplicate_media::{DuplicateImageError, ImageHash, UploadHashIndex, UploadedImage};
+pub use duplicate_posts::{
+    normalize_post_text, record_post, DuplicatePostError, RecentPost, RecentPostIndex,
+};
+pub use mentions::{
+    comment_mention, mention_snippet, CommentLink, CommentMention, MENTION_SNIPPET_LENGTH,
+};
+pub use post_watch::{
+    is_post_watched, unwatch_post, watch_post, PostWatchError, PostWatchList, WatchedPost,
+    WatchedPostComment,
+};
+pub use optimistic::{
+    local_event_id, OptimisticAction, OptimisticUpdates, PendingChange, LIKE_REACTION,
+};
+pub use outbox::{
+    spawn_outbox_worker, Outbox, OutboxAction, OutboxEntry, OutboxError, OutboxMedia, PostService,
+};
+
+// Re-export event bus types
+pub use event_bus::{
+    publish_social_event, subscribe_social_events, SocialEvent, SocialEventBus, SocialSubscription,
+    SocialTopic,
+};
+
+// Re-export activity log types
+pub use activity_log::{
+    load_activity_index, spawn_activity_indexer, Activity, ActivityEntry, ActivityIndex,
+    ActivityLogAction, ActivityLogError, ActivityLogService, ActivityRange, UndoReport,
+};
+
+// Re-export post insights types
+pub use post_insights::{ActiveWindow, PostInsightsError, PostInsightsService};
 
 // Re-export reactions types (Phase 3)
-pub use reactions::{common_emojis, reactions_for_display, ReactionDisplay, ReactionSummary};
+pub use reactions::{
+    common_emojis, load_comment_reactions, load_reaction_summary, quick_reactions,
+    reactions_for_display, CommentReactions, ReactionDisplay, ReactionSummary,
+    OTHER_REACTIONS_LABEL,
+};
 
 // Re-export widget types (Phase 3)
 pub use widgets::feed_view::{FeedState, SocialFeedView, SocialFeedViewAction};
@@ -44,12 +155,62 @@ pub use widgets::post_composer::{AttachedMedia, SocialPostComposer, SocialPostCo
 
 // Re-export newsfeed types (Phase 4)
 pub use newsfeed::{
-    create_feed_sync_filter, ContentFilter, FeedAggregator, FeedError, FeedFilterSettings,
-    FeedItem, FeedSortOrder,
+    are_birthdays_hidden, create_feed_sync_filter, deduplicate_cross_posts, detect_language,
+    feed_density, feed_filter, feed_order, feed_ranker, find_anchor_row, group_feed_items,
+    load_author_affinity, load_birthdays_this_week, load_feed_diagnostics_enabled, load_feed_filter,
+    load_feed_ranker, merge_feed_items, placeholder_feed_item, rank_items, register_feed_ranker,
+    save_feed_diagnostics_enabled, save_feed_filter, save_feed_ranker, save_not_spam_sender,
+    schema_version, set_birthdays_hidden, set_feed_density, undecryptable_feed_item,
+    unsupported_feed_item, unsupported_post, AffinityRanker, AuthorAffinity, BirthdaysAction,
+    CapabilityTracker, ChronologicalWithBoostsRanker, ContentFilter, DegradedFeature,
+    FeedAggregator, FeedCache, FeedCacheError, FeedDensityAction, FeedDiagnostics, FeedEntry,
+    FeedError, FeedFilterSettings, FeedGroupingSettings, FeedItem, FeedRanker, FeedReadMarker,
+    FeedReadMarkerService, FeedReadMarkers, FeedRowKey, FeedSortOrder, ForYouRanker, ForYouWeights,
+    Language, MuteDuration, MuteError, MuteService, MutedPattern, OrderedFeedItem, RankingContext,
+    RoomAggregationStats, ScoredFeedItem, SpamAssessment, SpamScorer, SpamSignal, SpamStrictness,
+    SpamVerdict, UndecryptablePost, UnsupportedPost, UpcomingBirthday, AFFINITY_RANKER,
+    CHRONOLOGICAL_WITH_BOOSTS_RANKER, FOR_YOU_RANKER, MAX_CACHED_FEED_ITEMS,
+    SOCIAL_EVENT_TYPE_PREFIX,
+};
+
+// Re-export migration types
+pub use migration::{MigrationError, MigrationReport, MigrationService, SocialExport};
+
+// Re-export retention types
+pub use retention::{RetentionError, RetentionJobAction, RetentionReport, RetentionService};
+
+// Re-export map thumbnail types
+pub use maps::{
+    are_remote_maps_enabled, set_map_provider, set_remote_maps_disabled, GeoPoint, MapError,
+    MapProvider, MapThumbnailStatus,
+};
+pub use translation::{
+    is_translation_available, set_translation_provider, set_translation_settings,
+    translation_settings, LibreTranslateProvider, Translation, TranslationAction,
+    TranslationError, TranslationProvider,
 };
+pub use main_feed::{MainFeedAction, MAX_MAIN_FEED_POSTS};
+pub use presence::{OnlineStatus, PresenceAction, UserPresence};
+pub use profile_tabs::{ProfileTab, ProfileTabAction, ProfileTabError};
+pub use user_verification::{refresh_user_verification, user_verification, UserVerificationAction};
 
 // Re-export privacy types (Phase 7)
 pub use privacy::{PrivacyLevel, ShareValidation, SharingGuard};
+pub use share_to_chat::{
+    forwarded_content, mentioned_users, ChatTarget, ShareToChatAction, ShareToChatError,
+    ShareToChatOutcome, ShareToChatService,
+};
+
+// Re-export push rule types
+pub use push_rules::{
+    PushRuleError, PushRuleService, SocialPushCategory, SocialPushRulesAction, RULE_ID_PREFIX,
+};
+
+// Re-export storage usage types
+pub use storage_usage::{
+    cache_limits, clear_storage, set_cache_limits, storage_usage, StorageCategory, StorageUsage,
+    StorageUsageAction, StorageUsageError,
+};
 
 /// Register all social feature UI components.
 pub fn live_design(cx: &mut Cx) {
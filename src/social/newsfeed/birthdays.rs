@@ -0,0 +1,202 @@
+//! Friends' upcoming birthdays, shown in a "Birthdays this week" card in the newsfeed.
+//!
+//! Birthdays come from the `birthday` field of friends' social profiles
+//! (see [`SocialProfileEventContent`]). They are loaded through the Matrix
+//! worker (see [`MatrixRequest::LoadBirthdays`]), which posts a
+//! [`BirthdaysAction::Loaded`] with the birthdays of the coming week.
+//!
+//! Users can hide the card, which is stored in the account's `org.social.settings`
+//! account data (see [`set_birthdays_hidden()`]).
+//!
+//! [`MatrixRequest::LoadBirthdays`]: crate::sliding_sync::MatrixRequest::LoadBirthdays
+
+use chrono::{Datelike, Local, NaiveDate};
+use makepad_widgets::*;
+use matrix_sdk::{
+    ruma::{OwnedUserId, UserId},
+    Client,
+};
+use robrix_social_events::profile::SocialProfileEventContent;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::social::availability::load_settings;
+use crate::social::feed_room::{feed_of_room, FeedPrivacy};
+use crate::social::profile_room::{ProfileRoomError, ProfileRoomService};
+
+/// How many days ahead birthdays are shown, including today.
+pub const BIRTHDAY_WINDOW_DAYS: u64 = 7;
+
+/// Whether the logged-in account hid the birthdays card.
+static BIRTHDAYS_HIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// A friend's birthday in the coming week.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpcomingBirthday {
+    /// The friend's user ID.
+    pub user_id: OwnedUserId,
+    /// The friend's display name, if known.
+    pub display_name: Option<String>,
+    /// The day of the birthday this year, or next year if it has passed.
+    pub date: NaiveDate,
+}
+
+impl UpcomingBirthday {
+    /// Get the day of the birthday as shown in the card,
+    /// e.g., "Today", "Tomorrow" or "Friday".
+    pub fn day_label(&self, today: NaiveDate) -> String {
+        match (self.date - today).num_days() {
+            0 => "Today".to_owned(),
+            1 => "Tomorrow".to_owned(),
+            _ => self.date.format("%A").to_string(),
+        }
+    }
+}
+
+/// Actions emitted about friends' upcoming birthdays.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum BirthdaysAction {
+    /// Friends' birthdays in the coming week were loaded, soonest first.
+    Loaded(Vec<UpcomingBirthday>),
+    /// The user hid or showed the birthdays card, e.g., on another device.
+    HiddenChanged(bool),
+    /// No action.
+    None,
+}
+
+/// Whether the user hid the birthdays card.
+pub fn are_birthdays_hidden() -> bool {
+    BIRTHDAYS_HIDDEN.load(Ordering::Relaxed)
+}
+
+/// Update the in-memory birthdays setting from the account's settings,
+/// posting a [`BirthdaysAction::HiddenChanged`] if it changed.
+pub(crate) fn update_birthdays_hidden(hidden: bool) {
+    if BIRTHDAYS_HIDDEN.swap(hidden, Ordering::Relaxed) != hidden {
+        Cx::post_action(BirthdaysAction::HiddenChanged(hidden));
+    }
+}
+
+/// Hide or show the birthdays card for the logged-in account.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn set_birthdays_hidden(client: &Client, hidden: bool) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.birthdays_hidden = hidden;
+    client.account().set_account_data(settings).await?;
+    // Take effect right away rather than once the settings have synced back
+    update_birthdays_hidden(hidden);
+    Ok(())
+}
+
+/// Get the next occurrence of a birthday on or after `today`.
+///
+/// Birthdays are ISO 8601 dates, e.g., "1990-04-23". Birthdays on February 29
+/// fall on February 28 in other years. Returns `None` for invalid dates.
+pub fn next_birthday(birthday: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let date = NaiveDate::parse_from_str(birthday.trim(), "%Y-%m-%d").ok()?;
+    [today.year(), today.year() + 1]
+        .into_iter()
+        .filter_map(|year| {
+            NaiveDate::from_ymd_opt(year, date.month(), date.day())
+                .or_else(|| NaiveDate::from_ymd_opt(year, date.month(), date.day() - 1))
+        })
+        .find(|occurrence| *occurrence >= today)
+}
+
+/// Whether a birthday falls within [`BIRTHDAY_WINDOW_DAYS`] from `today`.
+fn is_this_week(date: NaiveDate, today: NaiveDate) -> bool {
+    (date - today).num_days() < BIRTHDAY_WINDOW_DAYS as i64
+}
+
+/// Load the birthdays of the current user's friends in the coming week, soonest first.
+///
+/// Friends are the owners of the friends-only feeds the user has joined.
+/// Friends without a birthday in their profile, or whose profile can't be
+/// loaded, are left out.
+///
+/// # Errors
+/// Returns an error if the user is not logged in.
+pub async fn load_birthdays_this_week(
+    client: &Client,
+) -> Result<Vec<UpcomingBirthday>, ProfileRoomError> {
+    let user_id = client.user_id().ok_or(ProfileRoomError::NotLoggedIn)?;
+    let today = Local::now().date_naive();
+    let service = ProfileRoomService::new(client.clone());
+
+    let mut birthdays = Vec::new();
+    for room in client.joined_rooms() {
+        let Some((FeedPrivacy::Friends, friend)) = feed_of_room(&room) else {
+            continue;
+        };
+        if friend == user_id {
+            continue;
+        }
+        let Some(date) = friend_birthday(&service, &friend)
+            .await
+            .and_then(|birthday| next_birthday(&birthday, today))
+            .filter(|date| is_this_week(*date, today))
+        else {
+            continue;
+        };
+        let display_name = match room.get_member_no_sync(&friend).await {
+            Ok(member) => member.and_then(|member| member.display_name().map(ToOwned::to_owned)),
+            Err(_) => None,
+        };
+        birthdays.push(UpcomingBirthday {
+            user_id: friend,
+            display_name,
+            date,
+        });
+    }
+    birthdays.sort_by_key(|birthday| birthday.date);
+    Ok(birthdays)
+}
+
+/// Get a friend's birthday from their social profile, if they set one.
+async fn friend_birthday(service: &ProfileRoomService, friend: &UserId) -> Option<String> {
+    let room_id = service.find_profile_room(friend).await.ok()??;
+    let profile: SocialProfileEventContent = service.get_profile(&room_id).await.ok()??;
+    profile.birthday
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_next_birthday() {
+        let today = date(2026, 6, 10);
+        assert_eq!(next_birthday("1990-06-12", today), Some(date(2026, 6, 12)));
+        assert_eq!(next_birthday("1990-06-10", today), Some(today));
+        // Birthdays that have passed this year come next year
+        assert_eq!(next_birthday("1990-01-05", today), Some(date(2027, 1, 5)));
+        assert_eq!(next_birthday("2000-02-29", today), Some(date(2027, 2, 28)));
+        assert_eq!(next_birthday("not a date", today), None);
+    }
+
+    #[test]
+    fn test_birthdays_this_week() {
+        let today = date(2026, 12, 28);
+        assert!(is_this_week(date(2026, 12, 28), today));
+        assert!(is_this_week(date(2027, 1, 3), today));
+        assert!(!is_this_week(date(2027, 1, 4), today));
+    }
+
+    #[test]
+    fn test_day_label() {
+        let today = date(2026, 10, 16);
+        let birthday = |date| UpcomingBirthday {
+            user_id: "@alice:example.org".try_into().unwrap(),
+            display_name: None,
+            date,
+        };
+        assert_eq!(birthday(today).day_label(today), "Today");
+        assert_eq!(birthday(date(2026, 10, 17)).day_label(today), "Tomorrow");
+        assert_eq!(birthday(date(2026, 10, 20)).day_label(today), "Tuesday");
+    }
+}
@@ -0,0 +1,136 @@
+//! Tracking of the post types this version of Robrix is too old to show.
+//!
+//! Posts sent with a newer schema version than this client supports are shown
+//! as "Unsupported post" cards (see [`unsupported_post()`](super::unsupported_post)).
+//! The [`CapabilityTracker`] remembers which event types were degraded this way,
+//! so that the newsfeed can suggest updating Robrix without getting in the way.
+
+use matrix_sdk::ruma::OwnedEventId;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::feed_aggregator::FeedItem;
+
+/// An event type whose newer posts this client can't show.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DegradedFeature {
+    /// The raw type of the events, e.g., `org.social.checkin`.
+    pub event_type: String,
+    /// The newest schema version seen for the event type.
+    pub newest_version: u32,
+    /// The posts that couldn't be shown.
+    pub post_ids: BTreeSet<OwnedEventId>,
+}
+
+/// Tracks which features were degraded because posts need a newer client.
+#[derive(Clone, Debug, Default)]
+pub struct CapabilityTracker {
+    /// The degraded features, keyed by event type.
+    degraded: BTreeMap<String, DegradedFeature>,
+}
+
+impl CapabilityTracker {
+    /// Record the feed items that need a newer client to be shown.
+    ///
+    /// Returns `true` if a feature was newly degraded.
+    pub fn record_items(&mut self, items: &[FeedItem]) -> bool {
+        let mut newly_degraded = false;
+        for item in items {
+            let Some(unsupported) = &item.unsupported else {
+                continue;
+            };
+            let Some(version) = unsupported.schema_version else {
+                continue;
+            };
+            let feature = self
+                .degraded
+                .entry(unsupported.event_type.clone())
+                .or_insert_with(|| {
+                    newly_degraded = true;
+                    DegradedFeature {
+                        event_type: unsupported.event_type.clone(),
+                        newest_version: version,
+                        post_ids: BTreeSet::new(),
+                    }
+                });
+            feature.newest_version = feature.newest_version.max(version);
+            feature.post_ids.insert(item.event_id.clone());
+        }
+        newly_degraded
+    }
+
+    /// Check whether any posts needed a newer client to be shown.
+    pub fn is_degraded(&self) -> bool {
+        !self.degraded.is_empty()
+    }
+
+    /// Check whether posts of the given event type needed a newer client to be shown.
+    pub fn is_type_degraded(&self, event_type: &str) -> bool {
+        self.degraded.contains_key(event_type)
+    }
+
+    /// Get the degraded features, ordered by event type.
+    pub fn degraded_features(&self) -> impl Iterator<Item = &DegradedFeature> {
+        self.degraded.values()
+    }
+
+    /// Get the number of degraded features.
+    pub fn degraded_feature_count(&self) -> usize {
+        self.degraded.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::newsfeed::UnsupportedPost;
+    use matrix_sdk::ruma::{owned_event_id, owned_room_id, owned_user_id, MilliSecondsSinceUnixEpoch};
+
+    fn item(event_id: OwnedEventId, event_type: &str, schema_version: Option<u32>) -> FeedItem {
+        FeedItem::unsupported(
+            owned_room_id!("!feed:example.org"),
+            event_id,
+            owned_user_id!("@friend:example.org"),
+            MilliSecondsSinceUnixEpoch::now(),
+            UnsupportedPost {
+                event_type: event_type.to_string(),
+                reason: "Unsupported".to_string(),
+                schema_version,
+            },
+        )
+    }
+
+    #[test]
+    fn test_tracks_degraded_features() {
+        let mut tracker = CapabilityTracker::default();
+        // Malformed posts don't need a newer client
+        assert!(!tracker.record_items(&[item(
+            owned_event_id!("$malformed:example.org"),
+            "org.social.checkin",
+            None,
+        )]));
+        assert!(!tracker.is_degraded());
+
+        let items = [
+            item(
+                owned_event_id!("$a:example.org"),
+                "org.social.checkin",
+                Some(2),
+            ),
+            item(
+                owned_event_id!("$b:example.org"),
+                "org.social.checkin",
+                Some(3),
+            ),
+        ];
+        assert!(tracker.record_items(&items));
+        // Refreshing the feed doesn't count the same posts again
+        assert!(!tracker.record_items(&items));
+
+        assert!(tracker.is_type_degraded("org.social.checkin"));
+        assert!(!tracker.is_type_degraded("m.room.message"));
+        let feature = tracker.degraded_features().next().unwrap();
+        assert_eq!(feature.newest_version, 3);
+        assert_eq!(feature.post_ids.len(), 2);
+        assert_eq!(tracker.degraded_feature_count(), 1);
+    }
+}
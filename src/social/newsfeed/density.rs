@@ -0,0 +1,56 @@
+//! Display density of the newsfeed.
+//!
+//! Posts are laid out either comfortably or compactly (see [`FeedDensity`]).
+//! The setting is stored in the account's `org.social.settings` account data,
+//! so it follows the user across devices. Whenever it changes, a
+//! [`FeedDensityAction::Changed`] is posted so shown posts are laid out
+//! again right away.
+
+use makepad_widgets::*;
+use matrix_sdk::Client;
+use robrix_social_events::settings::FeedDensity;
+use std::sync::Mutex;
+
+use crate::social::availability::load_settings;
+
+/// The newsfeed density of the logged-in account.
+static FEED_DENSITY: Mutex<FeedDensity> = Mutex::new(FeedDensity::Comfortable);
+
+/// Actions emitted when the newsfeed density changes.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum FeedDensityAction {
+    /// The newsfeed density changed, e.g., on another device.
+    Changed(FeedDensity),
+    /// No action.
+    None,
+}
+
+/// Get the current newsfeed density.
+pub fn feed_density() -> FeedDensity {
+    *FEED_DENSITY.lock().unwrap()
+}
+
+/// Update the in-memory newsfeed density from the account's settings,
+/// posting a [`FeedDensityAction::Changed`] if it changed.
+pub(crate) fn update_feed_density(density: FeedDensity) {
+    let previous = std::mem::replace(&mut *FEED_DENSITY.lock().unwrap(), density);
+    if previous != density {
+        Cx::post_action(FeedDensityAction::Changed(density));
+    }
+}
+
+/// Set the newsfeed density for the logged-in account.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn set_feed_density(
+    client: &Client,
+    density: FeedDensity,
+) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.feed_density = density;
+    client.account().set_account_data(settings).await?;
+    // Take effect right away rather than once the settings have synced back
+    update_feed_density(density);
+    Ok(())
+}
@@ -0,0 +1,180 @@
+//! Diagnostics of how the newsfeed was aggregated.
+//!
+//! When the newsfeed is slow or stale, it's hard to tell which feed room is
+//! at fault. Each refresh of the [`FeedAggregator`](super::FeedAggregator)
+//! records how long every feed room took to load, how many posts it had,
+//! how many of them were already cached, and when it was last refreshed.
+//! With the `feed_diagnostics` developer setting on (see
+//! [`load_feed_diagnostics_enabled()`]), these are shown over the newsfeed.
+
+use matrix_sdk::{
+    ruma::{MilliSecondsSinceUnixEpoch, OwnedRoomId},
+    Client,
+};
+use std::{fmt, time::Duration};
+
+use crate::social::availability::load_settings;
+
+/// How a single feed room was aggregated in the last refresh.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoomAggregationStats {
+    /// The feed room.
+    pub room_id: OwnedRoomId,
+    /// How long it took to load the room's posts.
+    pub duration: Duration,
+    /// How many posts were loaded.
+    pub item_count: usize,
+    /// How many of the loaded posts were already cached.
+    pub cached_item_count: usize,
+    /// When the room was last refreshed.
+    pub refreshed_at: MilliSecondsSinceUnixEpoch,
+}
+
+/// How the newsfeed was aggregated in the last refresh.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeedDiagnostics {
+    /// Per-room statistics, in the order the rooms were loaded.
+    pub rooms: Vec<RoomAggregationStats>,
+    /// How long the whole refresh took, including sorting and caching.
+    pub total_duration: Duration,
+    /// Feed rooms that couldn't be loaded, e.g., because the user left them.
+    pub missing_rooms: Vec<OwnedRoomId>,
+    /// Feed rooms left out of the refresh because of an error, with the error.
+    pub failed_rooms: Vec<(OwnedRoomId, String)>,
+}
+
+impl FeedDiagnostics {
+    /// Get the total number of posts loaded.
+    pub fn item_count(&self) -> usize {
+        self.rooms.iter().map(|room| room.item_count).sum()
+    }
+
+    /// Get the share of loaded posts that were already cached, from 0 to 1,
+    /// or `None` if no posts were loaded.
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        let item_count = self.item_count();
+        let cached: usize = self.rooms.iter().map(|room| room.cached_item_count).sum();
+        (item_count > 0).then(|| cached as f64 / item_count as f64)
+    }
+
+    /// Get the room that took the longest to load, if any.
+    pub fn slowest_room(&self) -> Option<&RoomAggregationStats> {
+        self.rooms.iter().max_by_key(|room| room.duration)
+    }
+}
+
+impl fmt::Display for FeedDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rooms, {} posts in {} ms",
+            self.rooms.len(),
+            self.item_count(),
+            self.total_duration.as_millis()
+        )?;
+        if let Some(hit_rate) = self.cache_hit_rate() {
+            write!(f, ", {:.0}% cached", hit_rate * 100.0)?;
+        }
+        for room in &self.rooms {
+            let refreshed_at: u64 = room.refreshed_at.get().into();
+            let refreshed_at = chrono::DateTime::from_timestamp_millis(refreshed_at as i64)
+                .map(|dt| {
+                    dt.with_timezone(&chrono::Local)
+                        .format("%H:%M:%S")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            write!(
+                f,
+                "\n{}: {} posts ({} cached) in {} ms, refreshed at {refreshed_at}",
+                room.room_id,
+                room.item_count,
+                room.cached_item_count,
+                room.duration.as_millis()
+            )?;
+        }
+        for room_id in &self.missing_rooms {
+            write!(f, "\n{room_id}: not found")?;
+        }
+        for (room_id, error) in &self.failed_rooms {
+            write!(f, "\n{room_id}: failed: {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check whether the account's social settings turn on the newsfeed diagnostics.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded.
+pub async fn load_feed_diagnostics_enabled(client: &Client) -> Result<bool, matrix_sdk::Error> {
+    Ok(load_settings(client).await?.feed_diagnostics)
+}
+
+/// Turn the newsfeed diagnostics on or off in the account's social settings.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn save_feed_diagnostics_enabled(
+    client: &Client,
+    enabled: bool,
+) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.feed_diagnostics = enabled;
+    client.account().set_account_data(settings).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::owned_room_id;
+
+    fn stats(
+        room_id: OwnedRoomId,
+        millis: u64,
+        items: usize,
+        cached: usize,
+    ) -> RoomAggregationStats {
+        RoomAggregationStats {
+            room_id,
+            duration: Duration::from_millis(millis),
+            item_count: items,
+            cached_item_count: cached,
+            refreshed_at: MilliSecondsSinceUnixEpoch::now(),
+        }
+    }
+
+    #[test]
+    fn test_cache_hit_rate() {
+        let mut diagnostics = FeedDiagnostics::default();
+        assert_eq!(diagnostics.cache_hit_rate(), None);
+
+        diagnostics.rooms = vec![
+            stats(owned_room_id!("!a:a.org"), 120, 6, 3),
+            stats(owned_room_id!("!b:a.org"), 450, 2, 1),
+        ];
+        assert_eq!(diagnostics.item_count(), 8);
+        assert_eq!(diagnostics.cache_hit_rate(), Some(0.5));
+        assert_eq!(
+            diagnostics.slowest_room().unwrap().room_id,
+            owned_room_id!("!b:a.org")
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let diagnostics = FeedDiagnostics {
+            rooms: vec![stats(owned_room_id!("!a:a.org"), 120, 4, 1)],
+            total_duration: Duration::from_millis(150),
+            missing_rooms: vec![owned_room_id!("!gone:a.org")],
+            failed_rooms: vec![(owned_room_id!("!bad:a.org"), "timed out".to_owned())],
+        };
+        let text = diagnostics.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "1 rooms, 4 posts in 150 ms, 25% cached");
+        assert!(lines[1].starts_with("!a:a.org: 4 posts (1 cached) in 120 ms, refreshed at "));
+        assert_eq!(lines[2], "!gone:a.org: not found");
+        assert_eq!(lines[3], "!bad:a.org: failed: timed out");
+    }
+}
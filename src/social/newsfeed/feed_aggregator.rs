@@ -1,8 +1,35 @@
 //! Newsfeed aggregation across multiple feed rooms.
 //!
 //! The newsfeed is the union of all joined feed rooms, sorted
-//! chronologically or by engagement.
+//! chronologically, by engagement, or by the "For You" ranking
+//! (see [`ranking`](super::ranking)), or by a ranker picked in the settings
+//! (see [`FeedAggregator::set_ranker()`]).
+//!
+//! Posts intended for a more private audience than the feed room they turn
+//! up in are left out. Posts cross-posted to several feed rooms are shown
+//! once, from the most private room (see [`deduplicate_cross_posts()`]).
+//! Posts that don't pass the feed's filter (see [`FeedAggregator::set_filter()`])
+//! or whose authors the user muted are left out too, and each refresh sweeps
+//! expired author mutes from the account data (see [`MuteService::active_mutes()`]).
+//!
+//! Each refresh updates the local [`FeedCache`](super::FeedCache), from which
+//! the newsfeed can be shown at once when opened
+//! (see [`FeedAggregator::get_cached_feed()`]). Posts in end-to-end encrypted
+//! feeds, or in feeds whose encryption state isn't known yet, aren't cached,
+//! so that they aren't stored decrypted on disk.
+//!
+//! How each refresh went is kept as [`FeedDiagnostics`] for the developer
+//! overlay of the newsfeed (see [`FeedAggregator::diagnostics()`]), including
+//! the feed rooms that failed to load and were skipped. The post types that
+//! need a newer client to be shown are tracked in a [`CapabilityTracker`]
+//! (see [`FeedAggregator::capabilities()`]).
+//!
+//! In feeds the user moderates, posts whose media breaks the feed's media
+//! restrictions are annotated with their [`FeedItem::media_violation`].
+//! When all aggregated rooms are the feeds of a single user, e.g., on their
+//! profile, the posts they pinned are shown first (see [`surface_pinned_items()`]).
 
+use makepad_widgets::warning;
 use matrix_sdk::{
     room::Room,
     ruma::{
@@ -12,18 +39,49 @@ use matrix_sdk::{
     },
     Client,
 };
-use std::collections::BTreeMap;
-
-use crate::social::post::PostContent;
+use robrix_social_events::{feed_config::SocialFeedConfigEventContent, visibility::PostVisibility};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use tokio::runtime::Handle;
+
+use super::capabilities::CapabilityTracker;
+use super::diagnostics::{FeedDiagnostics, RoomAggregationStats};
+use super::feed_cache::FeedCache;
+
+use super::language::{detect_language, Language};
+use super::feed_filter::FeedFilterSettings;
+use super::mutes::MuteService;
+use super::ranking::{rank_items, AuthorAffinity, FeedRanker, ForYouRanker, RankingContext};
+use super::read_markers::{FeedReadMarker, FeedReadMarkers};
+use super::undecryptable::UndecryptablePost;
+use super::unsupported::UnsupportedPost;
+use crate::social::event_bus::{publish_social_event, SocialEvent};
+use crate::social::feed_moderation::{room_pinned_posts, shown_pinned_posts};
+use crate::social::feed_room::{feed_of_room, FeedPrivacy, FeedRoomService};
+use crate::social::media_policy::{check_post_media, MediaViolation};
+use crate::social::poll::{POLL_END_EVENT_TYPE, POLL_RESPONSE_EVENT_TYPE, POLL_START_EVENT_TYPE};
+use crate::social::storage_usage::cache_limits;
+use crate::social::{events::timing::now_ms, post::PostContent, privacy::PrivacyLevel};
+
+/// How far apart copies of a post without a cross-post ID may have been sent
+/// to still count as the same post, in milliseconds.
+pub const CROSS_POST_WINDOW_MS: u64 = 5 * 60 * 1000;
 
 /// Sync filter optimized for feed rooms.
 ///
-/// Creates a filter that fetches only message events, reactions, and redactions
+/// Creates a filter that fetches only message events, polls, reactions, and redactions
 /// for efficient feed synchronization.
 pub fn create_feed_sync_filter() -> FilterDefinition {
     let mut timeline_filter = RoomEventFilter::default();
     timeline_filter.types = Some(vec![
         TimelineEventType::RoomMessage.to_string(),
+        POLL_START_EVENT_TYPE.to_string(),
+        POLL_RESPONSE_EVENT_TYPE.to_string(),
+        POLL_END_EVENT_TYPE.to_string(),
         TimelineEventType::Reaction.to_string(),
         TimelineEventType::RoomRedaction.to_string(),
     ]);
@@ -55,12 +113,15 @@ pub enum FeedSortOrder {
     Engagement,
     /// Grouped by author, then by time within each author.
     GroupedByAuthor,
+    /// Ranked by recency, affinity to the author, and engagement
+    /// (see [`ForYouRanker`]).
+    ForYou,
 }
 
 /// An aggregated feed item from any feed room.
 ///
 /// Represents a single post with its metadata and engagement metrics.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FeedItem {
     /// Source room ID where this post lives.
     pub room_id: OwnedRoomId,
@@ -76,14 +137,158 @@ pub struct FeedItem {
     pub reactions: BTreeMap<String, u32>,
     /// Number of comments/replies to this post.
     pub comment_count: u32,
+    /// Number of reactions to the comments on this post.
+    pub comment_reaction_count: u32,
+    /// Detected language of the post text, if it could be determined.
+    pub language: Option<Language>,
+    /// The audience the post is intended for, if it records one.
+    pub visibility: Option<PostVisibility>,
+    /// ID shared by all copies of a cross-posted post, if it records one.
+    pub crosspost_id: Option<String>,
+    /// Why the post can't be shown, if it couldn't be deserialized,
+    /// in which case its content is empty (see [`FeedItem::unsupported()`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unsupported: Option<UnsupportedPost>,
+    /// Why the post can't be shown, if it couldn't be decrypted,
+    /// in which case its content is empty (see [`FeedItem::undecryptable()`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undecryptable: Option<UndecryptablePost>,
+    /// How the post's media breaks the restrictions of the feed it's in, if it does.
+    ///
+    /// Only set for posts in feeds the current user moderates, so that
+    /// they can take it down (see [`check_post_media()`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_violation: Option<MediaViolation>,
+    /// Whether the post is pinned to its feed by the feed's owner.
+    ///
+    /// Only set when viewing a single user's feeds, in which case pinned posts are shown first.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_pinned: bool,
 }
 
 impl FeedItem {
+    /// Create a new feed item with no engagement yet.
+    ///
+    /// The language of the post is detected from its text.
+    pub fn new(
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+        sender: OwnedUserId,
+        origin_server_ts: MilliSecondsSinceUnixEpoch,
+        content: PostContent,
+    ) -> Self {
+        let language = content.text().and_then(detect_language);
+        Self {
+            room_id,
+            event_id,
+            sender,
+            origin_server_ts,
+            content,
+            reactions: BTreeMap::new(),
+            comment_count: 0,
+            comment_reaction_count: 0,
+            language,
+            visibility: None,
+            crosspost_id: None,
+            unsupported: None,
+            undecryptable: None,
+            media_violation: None,
+            is_pinned: false,
+        }
+    }
+
+    /// Create a placeholder feed item for a post that can't be shown,
+    /// e.g., one sent by a newer client (see [`unsupported_feed_item()`](super::unsupported_feed_item)).
+    pub fn unsupported(
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+        sender: OwnedUserId,
+        origin_server_ts: MilliSecondsSinceUnixEpoch,
+        unsupported: UnsupportedPost,
+    ) -> Self {
+        let content = PostContent::Text {
+            body: String::new(),
+            formatted_body: None,
+            mentions: Default::default(),
+        };
+        Self {
+            unsupported: Some(unsupported),
+            ..Self::new(room_id, event_id, sender, origin_server_ts, content)
+        }
+    }
+
+    /// Create a placeholder feed item for a post in an encrypted feed that couldn't be decrypted,
+    /// e.g., because its keys haven't arrived yet (see [`placeholder_feed_item()`](super::placeholder_feed_item)).
+    pub fn undecryptable(
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+        sender: OwnedUserId,
+        origin_server_ts: MilliSecondsSinceUnixEpoch,
+        undecryptable: UndecryptablePost,
+    ) -> Self {
+        let content = PostContent::Text {
+            body: String::new(),
+            formatted_body: None,
+            mentions: Default::default(),
+        };
+        Self {
+            undecryptable: Some(undecryptable),
+            ..Self::new(room_id, event_id, sender, origin_server_ts, content)
+        }
+    }
+
+    /// Check whether the item is a placeholder for a post that can't be shown.
+    pub fn is_placeholder(&self) -> bool {
+        self.unsupported.is_some() || self.undecryptable.is_some()
+    }
+
+    /// Set the audience the post is intended for.
+    pub fn with_visibility(mut self, visibility: Option<PostVisibility>) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    /// Set the ID shared by all copies of the post.
+    pub fn with_crosspost_id(mut self, crosspost_id: Option<String>) -> Self {
+        self.crosspost_id = crosspost_id;
+        self
+    }
+
+    /// Check whether this item and another are copies of the same cross-posted post.
+    ///
+    /// Copies with cross-post IDs match if their IDs do. Otherwise, copies by the
+    /// same sender with the same content, sent within [`CROSS_POST_WINDOW_MS`]
+    /// of each other, match. Placeholders for posts that can't be shown have
+    /// no content to compare, so they only match by their cross-post IDs.
+    pub fn is_cross_post_of(&self, other: &FeedItem) -> bool {
+        if self.sender != other.sender || self.room_id == other.room_id {
+            return false;
+        }
+        match (&self.crosspost_id, &other.crosspost_id) {
+            (Some(id), Some(other_id)) => id == other_id,
+            _ if self.is_placeholder() || other.is_placeholder() => false,
+            _ => {
+                let sent_at = u64::from(self.origin_server_ts.get());
+                let other_sent_at = u64::from(other.origin_server_ts.get());
+                sent_at.abs_diff(other_sent_at) <= CROSS_POST_WINDOW_MS
+                    && self.content.has_same_content(&other.content)
+            }
+        }
+    }
+
+    /// Check whether this item may be shown in a room with the given privacy level,
+    /// i.e., the room's audience is no wider than the post's intended audience.
+    pub fn is_visible_in(&self, room_privacy: PrivacyLevel) -> bool {
+        self.visibility
+            .is_none_or(|visibility| PrivacyLevel::from(visibility) <= room_privacy)
+    }
+
     /// Calculate the total engagement for this item.
     ///
-    /// Engagement is the sum of all reaction counts plus comment count.
+    /// Engagement is the sum of all reaction counts plus comment count,
+    /// plus the reactions to those comments.
     pub fn engagement(&self) -> u32 {
-        self.reactions.values().sum::<u32>() + self.comment_count
+        self.reactions.values().sum::<u32>() + self.comment_count + self.comment_reaction_count
     }
 }
 
@@ -91,12 +296,31 @@ impl FeedItem {
 ///
 /// The FeedAggregator maintains a list of feed rooms to watch and provides
 /// methods to fetch a unified, sorted feed from all of them.
+///
+/// Clones share the same feed cache, diagnostics and capability tracker.
+#[derive(Clone)]
 pub struct FeedAggregator {
     client: Client,
     /// IDs of feed rooms to aggregate.
     feed_rooms: Vec<OwnedRoomId>,
     /// Current sort order.
     sort_order: FeedSortOrder,
+    /// Ranking used for [`FeedSortOrder::ForYou`].
+    for_you_ranker: ForYouRanker,
+    /// How often the user interacts with each author, for ranked sort orders.
+    affinity: AuthorAffinity,
+    /// Ranking used instead of the sort order, if any.
+    ranker: Option<Arc<dyn FeedRanker>>,
+    /// The filter posts must pass to be shown, along with the account's mutes.
+    filter: FeedFilterSettings,
+    /// Items of the last refresh, shown while the next one is in progress.
+    cache: Arc<Mutex<FeedCache>>,
+    /// The newest post the user has seen in each feed room.
+    read_markers: FeedReadMarkers,
+    /// How the last refresh went.
+    diagnostics: Arc<Mutex<FeedDiagnostics>>,
+    /// The post types that needed a newer client to be shown.
+    capabilities: Arc<Mutex<CapabilityTracker>>,
 }
 
 impl FeedAggregator {
@@ -109,6 +333,14 @@ impl FeedAggregator {
             client,
             feed_rooms: Vec::new(),
             sort_order: FeedSortOrder::default(),
+            for_you_ranker: ForYouRanker::default(),
+            affinity: AuthorAffinity::default(),
+            ranker: None,
+            filter: FeedFilterSettings::default(),
+            cache: Arc::default(),
+            read_markers: FeedReadMarkers::default(),
+            diagnostics: Arc::default(),
+            capabilities: Arc::default(),
         }
     }
 
@@ -155,11 +387,96 @@ impl FeedAggregator {
         self.sort_order = order;
     }
 
+    /// Set the ranking used for [`FeedSortOrder::ForYou`], e.g., with custom weights.
+    pub fn set_for_you_ranker(&mut self, ranker: ForYouRanker) {
+        self.for_you_ranker = ranker;
+    }
+
+    /// Rank the feed with the given ranker instead of the sort order,
+    /// e.g., as picked in the settings with [`load_feed_ranker()`](super::load_feed_ranker).
+    ///
+    /// Pass `None` to go back to the sort order.
+    pub fn set_ranker(&mut self, ranker: Option<Arc<dyn FeedRanker>>) {
+        self.ranker = ranker;
+    }
+
+    /// Set how often the user interacts with each author, e.g., as loaded with
+    /// [`load_author_affinity()`](super::load_author_affinity).
+    pub fn set_author_affinity(&mut self, affinity: AuthorAffinity) {
+        self.affinity = affinity;
+    }
+
+    /// Only show the posts that pass the given filter, e.g., as saved in the
+    /// settings and loaded with [`load_feed_filter()`](super::load_feed_filter).
+    ///
+    /// The filter's muted authors are replaced with the account's mutes on each refresh.
+    pub fn set_filter(&mut self, filter: FeedFilterSettings) {
+        self.filter = filter;
+    }
+
+    /// Set the items to show until the feed is refreshed,
+    /// e.g., as loaded with [`FeedCache::load()`].
+    pub fn set_cache(&mut self, cache: FeedCache) {
+        *self.cache.lock().unwrap() = cache;
+    }
+
+    /// Get the feed cache, e.g., to invalidate items that were redacted or edited.
+    pub fn cache(&self) -> Arc<Mutex<FeedCache>> {
+        self.cache.clone()
+    }
+
+    /// Set the read markers of the feed rooms, e.g., as loaded with
+    /// [`FeedReadMarkerService::load_markers()`](super::FeedReadMarkerService::load_markers).
+    pub fn set_read_markers(&mut self, markers: FeedReadMarkers) {
+        self.read_markers = markers;
+    }
+
+    /// Get the read markers of the feed rooms.
+    pub fn read_markers(&self) -> &FeedReadMarkers {
+        &self.read_markers
+    }
+
+    /// Mark a post as seen, moving its room's read marker forward.
+    ///
+    /// Returns whether the marker moved, i.e., whether it should be sent to the server.
+    pub fn mark_read(&mut self, item: &FeedItem) -> bool {
+        self.read_markers.mark_read(
+            &item.room_id,
+            FeedReadMarker {
+                event_id: item.event_id.clone(),
+                origin_server_ts: item.origin_server_ts,
+            },
+        )
+    }
+
+    /// Count the posts among the given items that were sent since the user's last visit.
+    pub fn new_post_count(&self, items: &[FeedItem]) -> usize {
+        self.read_markers.new_post_count(items)
+    }
+
+    /// Get how the last refresh went, e.g., to show in the newsfeed's diagnostics overlay.
+    pub fn diagnostics(&self) -> FeedDiagnostics {
+        self.diagnostics.lock().unwrap().clone()
+    }
+
+    /// Get the post types that needed a newer client to be shown since the aggregator
+    /// was created, e.g., to suggest updating Robrix in the newsfeed.
+    pub fn capabilities(&self) -> CapabilityTracker {
+        self.capabilities.lock().unwrap().clone()
+    }
+
     /// Get aggregated feed items from all feed rooms.
     ///
     /// Fetches recent items from all tracked feed rooms, combines them,
     /// sorts them according to the current sort order, and returns up to
-    /// `limit` items.
+    /// `limit` items. Posts that don't pass the [filter](Self::set_filter) are
+    /// left out, and so are posts by muted authors, unless all rooms
+    /// are the feeds of a single user, e.g., on their profile. Expired mutes
+    /// are swept first (see [`MuteService::active_mutes()`]),
+    /// and the [`diagnostics()`](Self::diagnostics) and
+    /// [`capabilities()`](Self::capabilities) are updated.
+    /// Rooms whose privacy level can't be determined are skipped,
+    /// and recorded in the diagnostics.
     ///
     /// # Arguments
     /// * `limit` - Maximum number of items to return.
@@ -167,25 +484,204 @@ impl FeedAggregator {
     /// # Errors
     /// Returns an error if there's a problem fetching room data.
     pub async fn get_aggregated_feed(&self, limit: usize) -> Result<Vec<FeedItem>, FeedError> {
+        let refresh_started = Instant::now();
+        let mut diagnostics = FeedDiagnostics::default();
         let mut all_items = Vec::new();
+        let mut encrypted_rooms = BTreeSet::new();
+        let is_single_user_feed = self.feed_owner().is_some();
+
+        // A user's own feeds are still shown in full after muting them
+        let mut filter = self.filter.clone();
+        if !is_single_user_feed {
+            match MuteService::new(self.client.clone()).active_mutes().await {
+                Ok(mutes) => filter = filter.with_mutes(&mutes),
+                Err(e) => warning!("Failed to load the muted authors: {e}"),
+            }
+        }
 
         for room_id in &self.feed_rooms {
-            if let Some(room) = self.client.get_room(room_id) {
-                // Fetch recent timeline items from this room
-                let items = self.fetch_room_items(&room, limit).await?;
-                all_items.extend(items);
+            let Some(room) = self.client.get_room(room_id) else {
+                diagnostics.missing_rooms.push(room_id.clone());
+                continue;
+            };
+            // Rooms whose encryption state isn't known yet may be encrypted
+            let encryption = room.encryption_state();
+            if encryption.is_encrypted() || encryption.is_unknown() {
+                encrypted_rooms.insert(room_id.clone());
             }
+            let room_started = Instant::now();
+            // Without its privacy level, the room's posts can't be safely shown
+            let room_privacy = match PrivacyLevel::infer_from_room(&room).await {
+                Ok(room_privacy) => room_privacy,
+                Err(e) => {
+                    warning!("Failed to get the privacy level of feed {room_id}: {e}");
+                    let failure = (room_id.clone(), e.to_string());
+                    diagnostics.failed_rooms.push(failure);
+                    continue;
+                }
+            };
+            // Fetch recent timeline items from this room
+            let mut items = self.fetch_room_items(&room, limit).await?;
+            if let Some(config) = self.moderated_feed_config(&room).await {
+                for item in &mut items {
+                    item.media_violation = check_post_media(&config, &item.content);
+                }
+            }
+            if is_single_user_feed {
+                let pinned_posts = room_pinned_posts(&room).await.unwrap_or_else(|e| {
+                    warning!("Failed to get the pinned posts of feed {room_id}: {e}");
+                    Vec::new()
+                });
+                let shown_pinned_posts = shown_pinned_posts(&pinned_posts);
+                for item in &mut items {
+                    item.is_pinned = shown_pinned_posts.contains(&item.event_id);
+                }
+            }
+            let cached_item_count = {
+                let cache = self.cache.lock().unwrap();
+                items
+                    .iter()
+                    .filter(|item| cache.get(room_id, &item.event_id).is_some())
+                    .count()
+            };
+            diagnostics.rooms.push(RoomAggregationStats {
+                room_id: room_id.clone(),
+                duration: room_started.elapsed(),
+                item_count: items.len(),
+                cached_item_count,
+                refreshed_at: MilliSecondsSinceUnixEpoch::now(),
+            });
+            all_items.extend(
+                items
+                    .into_iter()
+                    .filter(|item| item.is_visible_in(room_privacy) && filter.matches(item))
+                    .map(|item| (item, room_privacy)),
+            );
         }
 
+        let mut all_items = deduplicate_cross_posts(all_items);
+        self.update_cache(&all_items, &encrypted_rooms);
+        self.capabilities.lock().unwrap().record_items(&all_items);
+
         // Sort according to current order
         self.sort_items(&mut all_items);
+        if is_single_user_feed {
+            surface_pinned_items(&mut all_items);
+        }
 
         // Limit total results
         all_items.truncate(limit);
 
+        diagnostics.total_duration = refresh_started.elapsed();
+        *self.diagnostics.lock().unwrap() = diagnostics;
+
         Ok(all_items)
     }
 
+    /// Get the cached feed at once, and refresh it in the background.
+    ///
+    /// Must be called from within the Tokio runtime. A
+    /// [`SocialEvent::FeedRefreshed`] event with the refreshed feed is
+    /// published once the refresh completes, or a
+    /// [`SocialEvent::FeedRefreshFailed`] event if it fails.
+    pub fn get_cached_feed(&self, limit: usize) -> Vec<FeedItem> {
+        let aggregator = self.clone();
+        Handle::current().spawn(async move {
+            match aggregator.get_aggregated_feed(limit).await {
+                Ok(items) => publish_social_event(SocialEvent::FeedRefreshed(items)),
+                Err(e) => publish_social_event(SocialEvent::FeedRefreshFailed(e.to_string())),
+            }
+        });
+        self.cached_feed(limit)
+    }
+
+    /// Get the cached items of the aggregated rooms, sorted and limited
+    /// like [`Self::get_aggregated_feed()`].
+    pub fn cached_feed(&self, limit: usize) -> Vec<FeedItem> {
+        let mut items = self.cache.lock().unwrap().items_in(&self.feed_rooms);
+        self.sort_items(&mut items);
+        if self.feed_owner().is_some() {
+            surface_pinned_items(&mut items);
+        } else {
+            // Posts are only shown as pinned in their owner's feed
+            for item in &mut items {
+                item.is_pinned = false;
+            }
+        }
+        items.truncate(limit);
+        items
+    }
+
+    /// Get the user whose feeds are aggregated, if all aggregated rooms are feeds of one user.
+    fn feed_owner(&self) -> Option<OwnedUserId> {
+        let mut owners = self.feed_rooms.iter().map(|room_id| {
+            let room = self.client.get_room(room_id)?;
+            feed_of_room(&room).map(|(_, owner)| owner)
+        });
+        let owner = owners.next()??;
+        owners
+            .all(|other| other.as_ref() == Some(&owner))
+            .then_some(owner)
+    }
+
+    /// Replace the cached items of the aggregated rooms with freshly fetched ones,
+    /// leaving out the items of encrypted rooms, and save the cache
+    /// within the feed cache's [size limit](crate::social::storage_usage::cache_limits).
+    fn update_cache(&self, items: &[FeedItem], encrypted_rooms: &BTreeSet<OwnedRoomId>) {
+        let cached_items = items
+            .iter()
+            .filter(|item| !encrypted_rooms.contains(&item.room_id))
+            .cloned()
+            .collect();
+        {
+            let mut cache = self.cache.lock().unwrap();
+            cache.replace_rooms(&self.feed_rooms, cached_items);
+            if let Some(max_bytes) = cache_limits().max_feed_cache_bytes {
+                cache.trim_to_size(max_bytes);
+            }
+        }
+        self.save_cache();
+    }
+
+    /// Save the feed cache in the background, e.g., after invalidating some of its items.
+    ///
+    /// Must be called from within the Tokio runtime.
+    pub fn save_cache(&self) {
+        let Some(user_id) = self.client.user_id().map(ToOwned::to_owned) else {
+            return;
+        };
+        let cache = self.cache.lock().unwrap().clone();
+        Handle::current().spawn_blocking(move || {
+            // The cache only speeds up opening the feed, so failing to save it isn't fatal
+            if let Err(e) = cache.save(&user_id) {
+                warning!("Failed to save the feed cache: {e}");
+            }
+        });
+    }
+
+    /// Get the configuration of a feed room the current user moderates,
+    /// to annotate its posts that break its media restrictions.
+    ///
+    /// Returns `None` if the user can't remove others' posts from the room,
+    /// or if its configuration can't be read.
+    async fn moderated_feed_config(&self, room: &Room) -> Option<SocialFeedConfigEventContent> {
+        let user_id = self.client.user_id()?;
+        let power_levels = room.power_levels().await.ok()?;
+        if !power_levels.user_can_redact_event_of_other(user_id) {
+            return None;
+        }
+        match FeedRoomService::new(self.client.clone())
+            .feed_config(room.room_id())
+            .await
+        {
+            Ok(config) => Some(config),
+            Err(e) => {
+                warning!("Failed to get the config of feed {}: {e}", room.room_id());
+                None
+            }
+        }
+    }
+
     /// Fetch items from a single room.
     ///
     /// This is a placeholder implementation that will need to be expanded
@@ -198,14 +694,25 @@ impl FeedAggregator {
         // TODO: Implement actual timeline fetching
         // This would involve:
         // 1. Getting the room timeline
-        // 2. Filtering for message events
-        // 3. Collecting reactions for each message
-        // 4. Converting to FeedItem format
+        // 2. Filtering for message and poll start events
+        // 3. Collecting reactions for each message, and votes for each poll
+        //    (see `PollService::get_results()`)
+        // 4. Converting to FeedItem format, with the size of its media from
+        //    `media_from_content()`, or to a placeholder with
+        //    `placeholder_feed_item()` if the event can't be decrypted or deserialized
         Ok(Vec::new())
     }
 
-    /// Sort items according to the current sort order.
+    /// Sort items with the current ranker, or according to the current sort order.
     fn sort_items(&self, items: &mut [FeedItem]) {
+        if let Some(ranker) = &self.ranker {
+            let context = RankingContext {
+                now_ms: now_ms(),
+                affinity: &self.affinity,
+            };
+            rank_items(ranker.as_ref(), items, &context);
+            return;
+        }
         match self.sort_order {
             FeedSortOrder::Chronological => {
                 items.sort_by(|a, b| b.origin_server_ts.cmp(&a.origin_server_ts));
@@ -224,8 +731,45 @@ impl FeedAggregator {
                         .then_with(|| b.origin_server_ts.cmp(&a.origin_server_ts))
                 });
             }
+            FeedSortOrder::ForYou => {
+                let context = RankingContext {
+                    now_ms: now_ms(),
+                    affinity: &self.affinity,
+                };
+                rank_items(&self.for_you_ranker, items, &context);
+            }
+        }
+    }
+}
+
+/// Remove all but one copy of each cross-posted post,
+/// given the items along with the privacy level of the room each was found in.
+///
+/// The copy from the most private room is kept, so that its engagement and
+/// comments stay within the smallest audience. The order of the kept items is
+/// otherwise preserved.
+pub fn deduplicate_cross_posts(
+    items: impl IntoIterator<Item = (FeedItem, PrivacyLevel)>,
+) -> Vec<FeedItem> {
+    let mut kept: Vec<(FeedItem, PrivacyLevel)> = Vec::new();
+    for (item, room_privacy) in items {
+        match kept
+            .iter_mut()
+            .find(|(other, _)| item.is_cross_post_of(other))
+        {
+            Some(copy) if room_privacy > copy.1 => *copy = (item, room_privacy),
+            Some(_) => {}
+            None => kept.push((item, room_privacy)),
         }
     }
+    kept.into_iter().map(|(item, _)| item).collect()
+}
+
+/// Move the pinned items to the top of a sorted feed,
+/// keeping the order of the pinned and of the other items.
+pub fn surface_pinned_items(items: &mut [FeedItem]) {
+    // The sort is stable, so the items keep their order otherwise
+    items.sort_by_key(|item| !item.is_pinned);
 }
 
 /// Errors that can occur when working with the feed aggregator.
@@ -264,6 +808,7 @@ mod tests {
         assert!(filter.room.timeline.types.is_some());
         let types = filter.room.timeline.types.unwrap();
         assert!(types.contains(&TimelineEventType::RoomMessage.to_string()));
+        assert!(types.contains(&POLL_START_EVENT_TYPE.to_string()));
         assert!(types.contains(&TimelineEventType::Reaction.to_string()));
     }
 
@@ -285,8 +830,122 @@ mod tests {
             },
             reactions,
             comment_count: 2,
+            comment_reaction_count: 4,
+            language: None,
+            visibility: None,
+            crosspost_id: None,
+            unsupported: None,
+            undecryptable: None,
+            media_violation: None,
+            is_pinned: false,
         };
 
-        assert_eq!(item.engagement(), 10); // 5 + 3 + 2
+        assert_eq!(item.engagement(), 14); // 5 + 3 + 2 + 4
+    }
+
+    #[test]
+    fn test_feed_item_new_detects_language() {
+        let item = FeedItem::new(
+            "!room:example.org".try_into().unwrap(),
+            "$event:example.org".try_into().unwrap(),
+            "@user:example.org".try_into().unwrap(),
+            MilliSecondsSinceUnixEpoch(0u64.try_into().unwrap()),
+            PostContent::Text {
+                body: "Ich gehe heute mit meinen Freunden in die Stadt".to_string(),
+                formatted_body: None,
+                mentions: std::collections::BTreeSet::new(),
+            },
+        );
+
+        assert_eq!(item.language, Some(Language::German));
+        assert_eq!(item.engagement(), 0);
+    }
+
+    #[test]
+    fn test_posts_are_hidden_in_rooms_wider_than_their_audience() {
+        let item = FeedItem::new(
+            "!room:example.org".try_into().unwrap(),
+            "$event:example.org".try_into().unwrap(),
+            "@user:example.org".try_into().unwrap(),
+            MilliSecondsSinceUnixEpoch(0u64.try_into().unwrap()),
+            PostContent::Text {
+                body: "Just for you".to_string(),
+                formatted_body: None,
+                mentions: std::collections::BTreeSet::new(),
+            },
+        );
+        assert!(item.is_visible_in(PrivacyLevel::Public));
+
+        let item = item.with_visibility(Some(PostVisibility::CloseFriends));
+        assert!(!item.is_visible_in(PrivacyLevel::Friends));
+        assert!(item.is_visible_in(PrivacyLevel::CloseFriends));
+        assert!(item.is_visible_in(PrivacyLevel::Private));
+    }
+
+    fn make_copy(room_id: &str, event_id: &str, sent_at: u64, body: &str) -> FeedItem {
+        FeedItem::new(
+            room_id.try_into().unwrap(),
+            event_id.try_into().unwrap(),
+            "@user:example.org".try_into().unwrap(),
+            MilliSecondsSinceUnixEpoch(sent_at.try_into().unwrap()),
+            PostContent::Text {
+                body: body.to_string(),
+                formatted_body: None,
+                mentions: std::collections::BTreeSet::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_cross_posts_keep_the_most_private_copy() {
+        let public = make_copy("!public:a.org", "$public:a.org", 1_000, "Hi all")
+            .with_crosspost_id(Some("abc".to_string()));
+        let friends = make_copy("!friends:a.org", "$friends:a.org", 2_000, "Hi all")
+            .with_crosspost_id(Some("abc".to_string()));
+        let other = make_copy("!public:a.org", "$other:a.org", 3_000, "Hi all")
+            .with_crosspost_id(Some("def".to_string()));
+
+        let items = deduplicate_cross_posts([
+            (public, PrivacyLevel::Public),
+            (friends, PrivacyLevel::Friends),
+            (other, PrivacyLevel::Public),
+        ]);
+        let event_ids: Vec<_> = items.iter().map(|item| item.event_id.as_str()).collect();
+        assert_eq!(event_ids, ["$friends:a.org", "$other:a.org"]);
+    }
+
+    #[test]
+    fn test_cross_posts_without_id_match_by_content_and_time() {
+        let public = make_copy("!public:a.org", "$public:a.org", 0, "Hi all");
+        let friends = make_copy("!friends:a.org", "$friends:a.org", 1_000, "Hi all");
+        let later = make_copy(
+            "!friends:a.org",
+            "$later:a.org",
+            CROSS_POST_WINDOW_MS + 1,
+            "Hi all",
+        );
+        let different = make_copy("!friends:a.org", "$different:a.org", 0, "Bye");
+
+        assert!(friends.is_cross_post_of(&public));
+        assert!(!later.is_cross_post_of(&public));
+        assert!(!different.is_cross_post_of(&public));
+        // Repeated posts in the same room aren't cross-posts
+        assert!(!later.is_cross_post_of(&friends));
+    }
+
+    #[test]
+    fn test_pinned_items_are_surfaced_first() {
+        let mut items: Vec<_> = (1..=4)
+            .map(|i| {
+                let body = format!("Post {i}");
+                make_copy("!feed:a.org", &format!("${i}:a.org"), i, &body)
+            })
+            .collect();
+        items[1].is_pinned = true;
+        items[3].is_pinned = true;
+
+        surface_pinned_items(&mut items);
+        let order: Vec<_> = items.iter().map(|item| item.event_id.as_str()).collect();
+        assert_eq!(order, ["$2:a.org", "$4:a.org", "$1:a.org", "$3:a.org"]);
     }
 }
@@ -0,0 +1,370 @@
+//! Local cache of newsfeed items.
+//!
+//! Without a cache, every time the newsfeed is opened all feed rooms are
+//! fetched again. The [`FeedCache`] keeps the items of the last refresh,
+//! keyed by room and event, in a file in the user's persistent state
+//! directory, so that the main newsfeed can show them at once while it's
+//! refreshed in the background (see [`crate::social::main_feed`]).
+//! Cached posts that are redacted or edited are dropped, so that they're
+//! fetched again (see [`FeedCache::invalidate_for_event()`]).
+//!
+//! The file is plain JSON and isn't encrypted at rest. Posts of end-to-end
+//! encrypted feeds are left out when the cache is updated (see
+//! [`FeedAggregator::get_aggregated_feed()`](super::FeedAggregator::get_aggregated_feed)),
+//! but the file may still hold decrypted posts of a feed that turned
+//! encryption on after they were cached, until that feed is refreshed.
+//! Posts of private feeds that aren't encrypted are stored as they are.
+
+use matrix_sdk::ruma::{
+    events::AnySyncTimelineEvent, serde::Raw, EventId, OwnedEventId, OwnedRoomId, RoomId, UserId,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::feed_aggregator::FeedItem;
+use crate::persistence::persistent_state_dir;
+
+/// The name of the file, in the user's persistent state directory, holding the cache.
+pub(crate) const FEED_CACHE_FILE_NAME: &str = "social_feed_cache.json";
+
+/// The maximum number of items kept in the cache.
+pub const MAX_CACHED_FEED_ITEMS: usize = 1000;
+
+/// A local cache of newsfeed items, keyed by room and event.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<FeedItem>", into = "Vec<FeedItem>")]
+pub struct FeedCache {
+    items: HashMap<(OwnedRoomId, OwnedEventId), FeedItem>,
+}
+
+impl From<Vec<FeedItem>> for FeedCache {
+    fn from(items: Vec<FeedItem>) -> Self {
+        let mut cache = Self::default();
+        cache.extend(items);
+        cache
+    }
+}
+
+impl From<FeedCache> for Vec<FeedItem> {
+    fn from(cache: FeedCache) -> Self {
+        cache.items.into_values().collect()
+    }
+}
+
+impl FeedCache {
+    /// Load the cache of the given user, or an empty cache if none was saved yet.
+    ///
+    /// # Errors
+    /// Returns an error if the cache file cannot be read or parsed.
+    pub fn load(user_id: &UserId) -> Result<Self, FeedCacheError> {
+        let path = persistent_state_dir(user_id).join(FEED_CACHE_FILE_NAME);
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save the cache of the given user.
+    ///
+    /// # Errors
+    /// Returns an error if the cache file cannot be written.
+    pub fn save(&self, user_id: &UserId) -> Result<(), FeedCacheError> {
+        let path = persistent_state_dir(user_id).join(FEED_CACHE_FILE_NAME);
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Get the number of cached items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check whether no items are cached.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Get a cached item.
+    pub fn get(&self, room_id: &RoomId, event_id: &EventId) -> Option<&FeedItem> {
+        self.items.get(&(room_id.to_owned(), event_id.to_owned()))
+    }
+
+    /// Add items to the cache, replacing cached copies of the same events,
+    /// and forgetting the oldest items if it's full.
+    pub fn extend(&mut self, items: impl IntoIterator<Item = FeedItem>) {
+        for item in items {
+            self.items
+                .insert((item.room_id.clone(), item.event_id.clone()), item);
+        }
+        let excess = self.items.len().saturating_sub(MAX_CACHED_FEED_ITEMS);
+        if excess > 0 {
+            let mut keys: Vec<_> = self
+                .items
+                .iter()
+                .map(|(key, item)| (item.origin_server_ts, key.clone()))
+                .collect();
+            keys.sort();
+            for (_, key) in keys.into_iter().take(excess) {
+                self.items.remove(&key);
+            }
+        }
+    }
+
+    /// Forget the oldest items until the saved cache would take up at most `max_bytes`.
+    pub fn trim_to_size(&mut self, max_bytes: u64) {
+        let mut items: Vec<_> = self
+            .items
+            .iter()
+            .map(|(key, item)| {
+                let size = serde_json::to_vec(item).map_or(0, |json| json.len() as u64);
+                (item.origin_server_ts, key.clone(), size)
+            })
+            .collect();
+        // Keep the newest items that fit, counting the list's brackets
+        // and the commas between items
+        items.sort_by(|a, b| b.0.cmp(&a.0));
+        let mut size = 1;
+        for (_, key, item_size) in items {
+            size += item_size + 1;
+            if size > max_bytes {
+                self.items.remove(&key);
+            }
+        }
+    }
+
+    /// Replace all cached items of the given rooms with freshly fetched ones.
+    ///
+    /// Items that are no longer in a room, e.g., because they were deleted,
+    /// are dropped from the cache.
+    pub fn replace_rooms(&mut self, room_ids: &[OwnedRoomId], items: Vec<FeedItem>) {
+        self.items
+            .retain(|(room_id, _), _| !room_ids.contains(room_id));
+        self.extend(items);
+    }
+
+    /// Get the cached items of the given rooms, in no particular order.
+    pub fn items_in(&self, room_ids: &[OwnedRoomId]) -> Vec<FeedItem> {
+        self.items
+            .values()
+            .filter(|item| room_ids.contains(&item.room_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Drop a cached item, returning it if it was cached.
+    pub fn invalidate(&mut self, room_id: &RoomId, event_id: &EventId) -> Option<FeedItem> {
+        self.items
+            .remove(&(room_id.to_owned(), event_id.to_owned()))
+    }
+
+    /// Drop all cached items of a room, e.g., after leaving it.
+    pub fn invalidate_room(&mut self, room_id: &RoomId) {
+        self.items
+            .retain(|(cached_room_id, _), _| cached_room_id != room_id);
+    }
+
+    /// Drop the cached item that the given event in a room redacts or edits, if any,
+    /// returning it if it was cached.
+    pub fn invalidate_for_event(
+        &mut self,
+        room_id: &RoomId,
+        event: &Raw<AnySyncTimelineEvent>,
+    ) -> Option<FeedItem> {
+        let event_id = redacted_or_edited_event(event)?;
+        self.invalidate(room_id, &event_id)
+    }
+}
+
+/// Get the ID of the event that the given event redacts or edits, if any.
+fn redacted_or_edited_event(event: &Raw<AnySyncTimelineEvent>) -> Option<OwnedEventId> {
+    let event_type = event.get_field::<String>("type").ok().flatten()?;
+    let content = event
+        .get_field::<serde_json::Value>("content")
+        .ok()
+        .flatten()?;
+    let event_id = match event_type.as_str() {
+        // Before room version 11, the redacted event is outside of the content
+        "m.room.redaction" => match content.get("redacts") {
+            Some(redacts) => redacts.clone(),
+            None => event
+                .get_field::<serde_json::Value>("redacts")
+                .ok()
+                .flatten()?,
+        },
+        "m.room.message" => {
+            let relation = content.get("m.relates_to")?;
+            if relation.get("rel_type")?.as_str()? != "m.replace" {
+                return None;
+            }
+            relation.get("event_id")?.clone()
+        }
+        _ => return None,
+    };
+    serde_json::from_value(event_id).ok()
+}
+
+/// Errors that can occur when loading or saving the feed cache.
+#[derive(Debug, thiserror::Error)]
+pub enum FeedCacheError {
+    /// The cache file could not be read or written.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The cache could not be parsed or serialized.
+    #[error("Invalid feed cache: {0}")]
+    InvalidCache(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::post::PostContent;
+    use matrix_sdk::ruma::{owned_event_id, owned_room_id, MilliSecondsSinceUnixEpoch};
+    use serde_json::json;
+
+    fn item(room_id: &str, event_id: &str, sent_at: u64) -> FeedItem {
+        FeedItem::new(
+            room_id.try_into().unwrap(),
+            event_id.try_into().unwrap(),
+            "@user:example.org".try_into().unwrap(),
+            MilliSecondsSinceUnixEpoch(sent_at.try_into().unwrap()),
+            PostContent::Text {
+                body: "Hello".to_string(),
+                formatted_body: None,
+                mentions: Default::default(),
+            },
+        )
+    }
+
+    fn event(json: serde_json::Value) -> Raw<AnySyncTimelineEvent> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let mut cache = FeedCache::default();
+        cache.extend([
+            item("!a:a.org", "$1:a.org", 1),
+            item("!b:a.org", "$2:a.org", 2),
+        ]);
+
+        let json = serde_json::to_string(&cache).unwrap();
+        let loaded: FeedCache = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.len(), 2);
+        let cached = loaded
+            .get(&owned_room_id!("!b:a.org"), &owned_event_id!("$2:a.org"))
+            .unwrap();
+        assert_eq!(cached.content.text(), Some("Hello"));
+    }
+
+    #[test]
+    fn test_cache_forgets_oldest_items() {
+        let mut cache = FeedCache::default();
+        cache.extend(
+            (0..MAX_CACHED_FEED_ITEMS as u64 + 2)
+                .map(|i| item("!a:a.org", &format!("${i}:a.org"), i)),
+        );
+        assert_eq!(cache.len(), MAX_CACHED_FEED_ITEMS);
+        let room_id = owned_room_id!("!a:a.org");
+        assert!(cache.get(&room_id, &owned_event_id!("$1:a.org")).is_none());
+        assert!(cache.get(&room_id, &owned_event_id!("$2:a.org")).is_some());
+    }
+
+    #[test]
+    fn test_trim_to_size_keeps_newest_items() {
+        let mut cache = FeedCache::default();
+        cache.extend((1..=10).map(|i| item("!a:a.org", &format!("${i}:a.org"), i)));
+        let full_size = serde_json::to_vec(&cache).unwrap().len() as u64;
+        cache.trim_to_size(full_size);
+        assert_eq!(cache.len(), 10);
+
+        cache.trim_to_size(full_size / 2);
+        assert!(serde_json::to_vec(&cache).unwrap().len() as u64 <= full_size / 2);
+        assert!(!cache.is_empty());
+        let room_id = owned_room_id!("!a:a.org");
+        assert!(cache.get(&room_id, &owned_event_id!("$10:a.org")).is_some());
+        assert!(cache.get(&room_id, &owned_event_id!("$1:a.org")).is_none());
+
+        cache.trim_to_size(0);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_replace_rooms_drops_deleted_items() {
+        let mut cache = FeedCache::default();
+        cache.extend([
+            item("!a:a.org", "$1:a.org", 1),
+            item("!b:a.org", "$2:a.org", 2),
+        ]);
+
+        let room_ids = [owned_room_id!("!a:a.org")];
+        cache.replace_rooms(&room_ids, vec![item("!a:a.org", "$3:a.org", 3)]);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.items_in(&room_ids)[0].event_id, "$3:a.org");
+    }
+
+    #[test]
+    fn test_redactions_and_edits_invalidate_items() {
+        let room_id = owned_room_id!("!a:a.org");
+        let mut cache = FeedCache::default();
+        cache.extend([
+            item("!a:a.org", "$1:a.org", 1),
+            item("!a:a.org", "$2:a.org", 2),
+            item("!a:a.org", "$3:a.org", 3),
+            item("!a:a.org", "$4:a.org", 4),
+        ]);
+
+        let redaction = event(json!({
+            "type": "m.room.redaction",
+            "event_id": "$r1:a.org",
+            "sender": "@user:example.org",
+            "origin_server_ts": 5,
+            "content": { "redacts": "$1:a.org" },
+        }));
+        assert!(cache.invalidate_for_event(&room_id, &redaction).is_some());
+
+        let old_redaction = event(json!({
+            "type": "m.room.redaction",
+            "event_id": "$r2:a.org",
+            "sender": "@user:example.org",
+            "origin_server_ts": 6,
+            "redacts": "$2:a.org",
+            "content": {},
+        }));
+        assert!(
+            cache
+                .invalidate_for_event(&room_id, &old_redaction)
+                .is_some()
+        );
+
+        let edit = event(json!({
+            "type": "m.room.message",
+            "event_id": "$e:a.org",
+            "sender": "@user:example.org",
+            "origin_server_ts": 7,
+            "content": {
+                "msgtype": "m.text",
+                "body": "* Hi",
+                "m.new_content": { "msgtype": "m.text", "body": "Hi" },
+                "m.relates_to": { "rel_type": "m.replace", "event_id": "$3:a.org" },
+            },
+        }));
+        assert!(cache.invalidate_for_event(&room_id, &edit).is_some());
+
+        let reply = event(json!({
+            "type": "m.room.message",
+            "event_id": "$c:a.org",
+            "sender": "@user:example.org",
+            "origin_server_ts": 8,
+            "content": {
+                "msgtype": "m.text",
+                "body": "Nice",
+                "m.relates_to": { "m.in_reply_to": { "event_id": "$4:a.org" } },
+            },
+        }));
+        assert!(cache.invalidate_for_event(&room_id, &reply).is_none());
+
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -1,24 +1,49 @@
 //! Feed filtering for the aggregated newsfeed.
 //!
 //! Provides filtering capabilities to refine what content appears in a user's
-//! newsfeed based on content type, author, and other criteria.
-
-use matrix_sdk::ruma::{OwnedUserId, UserId};
-use std::collections::HashSet;
+//! newsfeed based on content type, author, language, and other criteria.
+//! Authors can be muted for a limited time; the feed aggregator hides the
+//! posts of the authors muted in the account's persistent mutes with
+//! [`FeedFilterSettings::with_mutes()`]. Topics can be
+//! muted with keywords or regular expressions matched against post text.
+//!
+//! The other filters are saved in the account's social settings, see
+//! [`load_feed_filter()`] and [`save_feed_filter()`], and applied to the
+//! newsfeed with [`FeedAggregator::set_filter()`](super::FeedAggregator::set_filter).
+//! The filter last synced is kept in memory for the settings screen
+//! (see [`feed_filter()`]).
+
+use matrix_sdk::{
+    ruma::{OwnedUserId, UserId},
+    Client,
+};
+use regex::{Regex, RegexBuilder};
+use robrix_social_events::mute::SocialMutesEventContent;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
 
 use super::feed_aggregator::FeedItem;
+use super::language::Language;
+use super::mutes::MuteDuration;
+use super::spam::SpamStrictness;
+use crate::social::{availability::load_settings, block_list::is_blocked, events::timing::now_ms};
+
+/// The newsfeed filter of the logged-in account, once its settings are loaded.
+static FEED_FILTER: Mutex<Option<FeedFilterSettings>> = Mutex::new(None);
 
 /// Content type filter for feed items.
 ///
 /// Allows filtering the feed to show only certain types of content.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ContentFilter {
     /// Show all content types.
     #[default]
     All,
     /// Show only text posts.
     TextOnly,
-    /// Show only media posts (images and videos).
+    /// Show only media posts (images, videos and audio clips).
     MediaOnly,
     /// Show only posts with links.
     LinksOnly,
@@ -35,7 +60,9 @@ impl ContentFilter {
             Self::MediaOnly => {
                 matches!(
                     item.content,
-                    PostContent::Image { .. } | PostContent::Video { .. }
+                    PostContent::Image { .. }
+                        | PostContent::Video { .. }
+                        | PostContent::Audio { .. }
                 )
             }
             Self::LinksOnly => matches!(item.content, PostContent::Link { .. }),
@@ -43,21 +70,145 @@ impl ContentFilter {
     }
 }
 
+/// Maximum compiled size of a muted pattern, in bytes.
+const MAX_PATTERN_SIZE: usize = 1 << 20;
+
+/// A regular expression hiding the posts whose text it matches.
+///
+/// Patterns are matched case-insensitively, and are saved as their source text.
+/// Saved patterns that fail to compile are skipped when the filter is loaded.
+#[derive(Clone, Debug, Serialize)]
+#[serde(into = "String")]
+pub struct MutedPattern {
+    regex: Regex,
+}
+
+impl MutedPattern {
+    /// Compile a muted pattern.
+    ///
+    /// # Errors
+    /// Returns an error if the pattern is not a valid regular expression,
+    /// or is too large.
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .size_limit(MAX_PATTERN_SIZE)
+            .build()?;
+        Ok(Self { regex })
+    }
+
+    /// Get the source text of this pattern.
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// Check if this pattern matches anywhere in the given text.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl PartialEq for MutedPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for MutedPattern {}
+
+impl From<MutedPattern> for String {
+    fn from(pattern: MutedPattern) -> Self {
+        pattern.regex.as_str().to_owned()
+    }
+}
+
+/// Deserialize saved muted patterns, skipping the ones that are not valid
+/// rather than failing to load the whole filter.
+fn deserialize_muted_patterns<'de, D>(deserializer: D) -> Result<Vec<MutedPattern>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let patterns = Vec::<String>::deserialize(deserializer)?;
+    Ok(patterns
+        .iter()
+        .filter_map(|pattern| MutedPattern::new(pattern).ok())
+        .collect())
+}
+
+/// Deserialize saved muted keywords, trimmed and lowercased
+/// like [`FeedFilterSettings::mute_keyword()`] does.
+///
+/// Keywords saved by other clients may not be lowercase,
+/// which [`contains_keyword()`] expects.
+fn deserialize_muted_keywords<'de, D>(deserializer: D) -> Result<HashSet<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let keywords = HashSet::<String>::deserialize(deserializer)?;
+    Ok(keywords
+        .iter()
+        .map(|keyword| keyword.trim().to_lowercase())
+        .filter(|keyword| !keyword.is_empty())
+        .collect())
+}
+
+/// Check if `text` contains `keyword` as a whole word or phrase.
+///
+/// Both must already be lowercase.
+fn contains_keyword(text: &str, keyword: &str) -> bool {
+    text.match_indices(keyword).any(|(start, _)| {
+        let end = start + keyword.len();
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
 /// Settings for filtering the newsfeed.
 ///
 /// Combines multiple filter criteria that can be applied to feed items.
-#[derive(Clone, Debug, Default)]
+/// Missing fields are deserialized with their default, so settings saved
+/// by older versions still load.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FeedFilterSettings {
     /// Filter by content type.
     pub content_filter: ContentFilter,
     /// Show only posts from these users (empty = show all).
     pub authors: HashSet<OwnedUserId>,
-    /// Hide posts from these users.
-    pub muted_authors: HashSet<OwnedUserId>,
+    /// Hide posts from these users, until the given time
+    /// (Unix timestamp in milliseconds) or forever if `None`.
+    ///
+    /// Posts from blocked users are always hidden,
+    /// see [`BlockListService`](crate::social::BlockListService).
+    ///
+    /// Not saved with the other filters, as mutes have their own account data.
+    #[serde(skip)]
+    pub muted_authors: HashMap<OwnedUserId, Option<u64>>,
     /// Minimum engagement threshold (0 = no minimum).
     pub min_engagement: u32,
     /// Only show posts newer than this many seconds (0 = no limit).
     pub max_age_seconds: u64,
+    /// Show only posts in these languages (empty = show all).
+    ///
+    /// Posts whose language could not be detected are always shown.
+    pub languages: HashSet<Language>,
+    /// Hide posts in these languages.
+    pub hidden_languages: HashSet<Language>,
+    /// How strictly likely spam is down-ranked or hidden,
+    /// see [`SpamScorer::apply()`](super::SpamScorer::apply).
+    pub spam_strictness: SpamStrictness,
+    /// Hide posts whose text contains any of these words or phrases (lowercase).
+    ///
+    /// Keywords only match whole words, so "war" doesn't hide posts about "software".
+    #[serde(deserialize_with = "deserialize_muted_keywords")]
+    pub muted_keywords: HashSet<String>,
+    /// Hide posts whose text matches any of these patterns.
+    #[serde(deserialize_with = "deserialize_muted_patterns")]
+    pub muted_patterns: Vec<MutedPattern>,
+    /// Senders the user marked as not spamming, whose posts are never
+    /// flagged as spam, see [`save_not_spam_sender()`](super::save_not_spam_sender).
+    pub not_spam_senders: HashSet<OwnedUserId>,
 }
 
 impl FeedFilterSettings {
@@ -85,18 +236,42 @@ impl FeedFilterSettings {
 
     /// Mute posts from specific users.
     pub fn with_muted_authors(mut self, authors: impl IntoIterator<Item = OwnedUserId>) -> Self {
-        self.muted_authors = authors.into_iter().collect();
+        self.muted_authors = authors.into_iter().map(|author| (author, None)).collect();
+        self
+    }
+
+    /// Mute the authors muted in the account's persistent mutes,
+    /// see [`MuteService`](super::MuteService).
+    pub fn with_mutes(mut self, mutes: &SocialMutesEventContent) -> Self {
+        self.muted_authors = mutes
+            .authors
+            .iter()
+            .map(|(author, muted)| (author.clone(), muted.expires_at))
+            .collect();
         self
     }
 
     /// Mute a specific user.
     pub fn mute_author(&mut self, author: OwnedUserId) {
-        self.muted_authors.insert(author);
+        self.muted_authors.insert(author, None);
+    }
+
+    /// Mute a specific user for the given duration, starting now.
+    pub fn mute_author_for(&mut self, author: OwnedUserId, duration: MuteDuration) {
+        self.muted_authors
+            .insert(author, duration.expires_at(now_ms()));
     }
 
     /// Unmute a specific user.
     pub fn unmute_author(&mut self, author: &UserId) {
-        self.muted_authors.retain(|a| a != author);
+        self.muted_authors.remove(author);
+    }
+
+    /// Check if a user is muted at the given time (Unix timestamp in milliseconds).
+    pub fn is_muted_at(&self, author: &UserId, now_ms: u64) -> bool {
+        self.muted_authors
+            .get(author)
+            .is_some_and(|expires_at| expires_at.is_none_or(|expires_at| now_ms < expires_at))
     }
 
     /// Set minimum engagement threshold.
@@ -111,6 +286,76 @@ impl FeedFilterSettings {
         self
     }
 
+    /// Filter to show only posts in specific languages.
+    pub fn with_languages(mut self, languages: impl IntoIterator<Item = Language>) -> Self {
+        self.languages = languages.into_iter().collect();
+        self
+    }
+
+    /// Hide posts in a specific language.
+    pub fn hide_language(&mut self, language: Language) {
+        self.hidden_languages.insert(language);
+    }
+
+    /// Stop hiding posts in a specific language.
+    pub fn unhide_language(&mut self, language: Language) {
+        self.hidden_languages.remove(&language);
+    }
+
+    /// Set how strictly likely spam is down-ranked or hidden.
+    pub fn with_spam_strictness(mut self, strictness: SpamStrictness) -> Self {
+        self.spam_strictness = strictness;
+        self
+    }
+
+    /// Mute posts containing a word or phrase, ignoring case.
+    pub fn mute_keyword(&mut self, keyword: &str) {
+        let keyword = keyword.trim().to_lowercase();
+        if !keyword.is_empty() {
+            self.muted_keywords.insert(keyword);
+        }
+    }
+
+    /// Stop muting posts containing a word or phrase.
+    pub fn unmute_keyword(&mut self, keyword: &str) {
+        self.muted_keywords.remove(&keyword.trim().to_lowercase());
+    }
+
+    /// Mute posts whose text matches a regular expression, ignoring case.
+    ///
+    /// # Errors
+    /// Returns an error if the pattern is not a valid regular expression.
+    pub fn mute_pattern(&mut self, pattern: &str) -> Result<(), regex::Error> {
+        let pattern = MutedPattern::new(pattern)?;
+        if !self.muted_patterns.contains(&pattern) {
+            self.muted_patterns.push(pattern);
+        }
+        Ok(())
+    }
+
+    /// Stop muting posts matching a regular expression.
+    pub fn unmute_pattern(&mut self, pattern: &str) {
+        self.muted_patterns
+            .retain(|muted| muted.as_str() != pattern);
+    }
+
+    /// Check if a post's text contains a muted keyword or matches a muted pattern.
+    pub fn is_text_muted(&self, text: &str) -> bool {
+        if !self.muted_keywords.is_empty() {
+            let text = text.to_lowercase();
+            if self
+                .muted_keywords
+                .iter()
+                .any(|keyword| contains_keyword(&text, keyword))
+            {
+                return true;
+            }
+        }
+        self.muted_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(text))
+    }
+
     /// Check if a feed item passes all filters.
     pub fn matches(&self, item: &FeedItem) -> bool {
         // Check content type filter
@@ -123,19 +368,44 @@ impl FeedFilterSettings {
             return false;
         }
 
-        // Check muted authors
-        if self.muted_authors.contains(&item.sender) {
+        // Check muted and blocked authors (expired mutes no longer apply)
+        let now = now_ms();
+        if self.is_muted_at(&item.sender, now) || is_blocked(&item.sender) {
             return false;
         }
 
+        // Check maximum age
+        if self.max_age_seconds > 0 {
+            let sent_at: u64 = item.origin_server_ts.get().into();
+            if now.saturating_sub(sent_at) > self.max_age_seconds.saturating_mul(1000) {
+                return false;
+            }
+        }
+
+        // Check muted keywords and patterns against the body or caption
+        if item
+            .content
+            .text()
+            .is_some_and(|text| self.is_text_muted(text))
+        {
+            return false;
+        }
+
+        // Check language filters (posts with an unknown language always pass)
+        if let Some(language) = item.language {
+            if !self.languages.is_empty() && !self.languages.contains(&language) {
+                return false;
+            }
+            if self.hidden_languages.contains(&language) {
+                return false;
+            }
+        }
+
         // Check minimum engagement
         if self.min_engagement > 0 && item.engagement() < self.min_engagement {
             return false;
         }
 
-        // Note: max_age_seconds check would require current time comparison
-        // which is deferred to the caller or a method with time parameter
-
         true
     }
 
@@ -158,6 +428,10 @@ impl FeedFilterSettings {
             || !self.muted_authors.is_empty()
             || self.min_engagement > 0
             || self.max_age_seconds > 0
+            || !self.languages.is_empty()
+            || !self.hidden_languages.is_empty()
+            || !self.muted_keywords.is_empty()
+            || !self.muted_patterns.is_empty()
     }
 
     /// Reset all filters to default.
@@ -166,6 +440,54 @@ impl FeedFilterSettings {
     }
 }
 
+/// Load the newsfeed filter saved in the account's social settings.
+///
+/// Accounts without a saved filter, or with one that can't be parsed, get the
+/// default filter. Mutes aren't included; apply them with
+/// [`FeedFilterSettings::with_mutes()`].
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded.
+pub async fn load_feed_filter(client: &Client) -> Result<FeedFilterSettings, matrix_sdk::Error> {
+    Ok(parse_feed_filter(load_settings(client).await?.feed_filter))
+}
+
+/// Parse the newsfeed filter saved in the account's social settings,
+/// falling back to the default filter.
+fn parse_feed_filter(value: Option<serde_json::Value>) -> FeedFilterSettings {
+    value
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Get the newsfeed filter of the logged-in account, e.g., to show it in the settings.
+pub fn feed_filter() -> FeedFilterSettings {
+    FEED_FILTER.lock().unwrap().clone().unwrap_or_default()
+}
+
+/// Update the in-memory newsfeed filter from the account's settings.
+pub(crate) fn update_feed_filter(value: Option<serde_json::Value>) {
+    *FEED_FILTER.lock().unwrap() = Some(parse_feed_filter(value));
+}
+
+/// Save the newsfeed filter in the account's social settings.
+///
+/// Mutes aren't saved here, see [`MuteService`](super::MuteService).
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn save_feed_filter(
+    client: &Client,
+    filter: &FeedFilterSettings,
+) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.feed_filter = Some(serde_json::to_value(filter)?);
+    client.account().set_account_data(settings).await?;
+    // Take effect right away rather than once the settings have synced back
+    *FEED_FILTER.lock().unwrap() = Some(filter.clone());
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +514,21 @@ mod tests {
                 r
             },
             comment_count: 0,
+            comment_reaction_count: 0,
+            language: None,
+            visibility: None,
+            crosspost_id: None,
+            unsupported: None,
+            undecryptable: None,
+            media_violation: None,
+            is_pinned: false,
+        }
+    }
+
+    fn make_item_in(language: Option<Language>) -> FeedItem {
+        FeedItem {
+            language,
+            ..make_text_item("@user:example.org", 0)
         }
     }
 
@@ -221,6 +558,21 @@ mod tests {
         assert!(settings.matches(&normal_item));
     }
 
+    #[test]
+    fn test_temporary_mute_expires() {
+        let muted_user: OwnedUserId = "@muted:example.org".try_into().unwrap();
+        let mut settings = FeedFilterSettings::new();
+        settings.mute_author_for(muted_user.clone(), MuteDuration::Day);
+        let now = now_ms();
+
+        assert!(!settings.matches(&make_text_item("@muted:example.org", 0)));
+        assert!(settings.is_muted_at(&muted_user, now));
+        assert!(!settings.is_muted_at(&muted_user, now + 25 * 60 * 60 * 1000));
+
+        settings.mute_author(muted_user.clone());
+        assert!(settings.is_muted_at(&muted_user, u64::MAX));
+    }
+
     #[test]
     fn test_filter_settings_min_engagement() {
         let settings = FeedFilterSettings::new().with_min_engagement(5);
@@ -232,6 +584,19 @@ mod tests {
         assert!(settings.matches(&high_engagement));
     }
 
+    #[test]
+    fn test_filter_settings_max_age() {
+        let settings = FeedFilterSettings::new().with_max_age(60 * 60);
+        let recent = FeedItem {
+            origin_server_ts: MilliSecondsSinceUnixEpoch::now(),
+            ..make_text_item("@user:example.org", 0)
+        };
+
+        assert!(settings.matches(&recent));
+        assert!(!settings.matches(&make_text_item("@user:example.org", 0)));
+        assert!(FeedFilterSettings::new().matches(&make_text_item("@user:example.org", 0)));
+    }
+
     #[test]
     fn test_filter_settings_apply() {
         let muted: OwnedUserId = "@muted:example.org".try_into().unwrap();
@@ -247,6 +612,139 @@ mod tests {
         assert_eq!(filtered.len(), 2);
     }
 
+    #[test]
+    fn test_filter_settings_languages() {
+        let settings = FeedFilterSettings::new().with_languages([Language::English]);
+
+        assert!(settings.matches(&make_item_in(Some(Language::English))));
+        assert!(!settings.matches(&make_item_in(Some(Language::French))));
+        assert!(settings.matches(&make_item_in(None)));
+    }
+
+    #[test]
+    fn test_filter_settings_hidden_language() {
+        let mut settings = FeedFilterSettings::new();
+        settings.hide_language(Language::German);
+
+        assert!(!settings.matches(&make_item_in(Some(Language::German))));
+        assert!(settings.matches(&make_item_in(Some(Language::English))));
+        assert!(settings.has_active_filters());
+
+        settings.unhide_language(Language::German);
+        assert!(settings.matches(&make_item_in(Some(Language::German))));
+        assert!(!settings.has_active_filters());
+    }
+
+    fn make_item_with_text(body: &str) -> FeedItem {
+        FeedItem {
+            content: PostContent::Text {
+                body: body.to_string(),
+                formatted_body: None,
+                mentions: std::collections::BTreeSet::new(),
+            },
+            ..make_text_item("@user:example.org", 0)
+        }
+    }
+
+    #[test]
+    fn test_filter_settings_muted_keyword() {
+        let mut settings = FeedFilterSettings::new();
+        settings.mute_keyword("  Spoilers ");
+        settings.mute_keyword("the finale");
+
+        assert!(!settings.matches(&make_item_with_text("No SPOILERS, promise!")));
+        assert!(!settings.matches(&make_item_with_text("What did you think of the finale?")));
+        // Keywords only match whole words
+        assert!(settings.matches(&make_item_with_text("Spoilersaurus is a dinosaur")));
+        assert!(settings.has_active_filters());
+
+        settings.unmute_keyword("spoilers");
+        assert!(settings.matches(&make_item_with_text("No SPOILERS, promise!")));
+    }
+
+    #[test]
+    fn test_filter_settings_muted_pattern() {
+        let mut settings = FeedFilterSettings::new();
+        assert!(settings.mute_pattern("(").is_err());
+        settings.mute_pattern(r"\belection(s)?\b").unwrap();
+
+        assert!(!settings.matches(&make_item_with_text("Election results are in")));
+        assert!(settings.matches(&make_item_with_text("Nothing to see here")));
+
+        let caption_item = FeedItem {
+            content: PostContent::Image {
+                mxc_uri: "mxc://example.org/image".into(),
+                caption: Some("Watching the elections tonight".to_string()),
+                thumbnail_uri: None,
+                width: 800,
+                height: 600,
+                size: None,
+                alt_text: None,
+            },
+            ..make_text_item("@user:example.org", 0)
+        };
+        assert!(!settings.matches(&caption_item));
+
+        settings.unmute_pattern(r"\belection(s)?\b");
+        assert!(settings.matches(&caption_item));
+    }
+
+    #[test]
+    fn test_filter_settings_serde_round_trip() {
+        let muted: OwnedUserId = "@muted:example.org".try_into().unwrap();
+        let mut settings = FeedFilterSettings::new()
+            .with_content_filter(ContentFilter::MediaOnly)
+            .with_min_engagement(3)
+            .with_languages([Language::English])
+            .with_spam_strictness(SpamStrictness::Strict)
+            .with_muted_authors([muted]);
+        settings.hide_language(Language::German);
+        settings.mute_keyword("spoilers");
+        settings.mute_pattern("^breaking").unwrap();
+
+        let value = serde_json::to_value(&settings).unwrap();
+        assert_eq!(value["content_filter"], "media_only");
+        assert_eq!(value["spam_strictness"], "strict");
+
+        let restored: FeedFilterSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(restored.content_filter, ContentFilter::MediaOnly);
+        assert_eq!(restored.min_engagement, 3);
+        assert_eq!(restored.languages, settings.languages);
+        assert_eq!(restored.hidden_languages, settings.hidden_languages);
+        assert_eq!(restored.spam_strictness, SpamStrictness::Strict);
+        assert_eq!(restored.muted_keywords, settings.muted_keywords);
+        assert_eq!(restored.muted_patterns, settings.muted_patterns);
+        // Mutes are saved in their own account data
+        assert!(restored.muted_authors.is_empty());
+    }
+
+    #[test]
+    fn test_filter_settings_missing_fields_default() {
+        let restored: FeedFilterSettings =
+            serde_json::from_value(serde_json::json!({ "min_engagement": 2 })).unwrap();
+        assert_eq!(restored.min_engagement, 2);
+        assert_eq!(restored.content_filter, ContentFilter::All);
+        assert_eq!(restored.spam_strictness, SpamStrictness::Balanced);
+    }
+
+    #[test]
+    fn test_filter_settings_skip_invalid_saved_patterns() {
+        let restored = parse_feed_filter(Some(serde_json::json!({
+            "min_engagement": 2,
+            "muted_keywords": ["  Spoilers "],
+            "muted_patterns": ["(unclosed", "^breaking"],
+        })));
+        assert_eq!(restored.min_engagement, 2);
+        assert_eq!(
+            restored.muted_keywords,
+            HashSet::from(["spoilers".to_owned()])
+        );
+        assert_eq!(
+            restored.muted_patterns,
+            vec![MutedPattern::new("^breaking").unwrap()]
+        );
+    }
+
     #[test]
     fn test_has_active_filters() {
         let default = FeedFilterSettings::new();
@@ -0,0 +1,313 @@
+//! Grouping of bursts of posts from the same author.
+//!
+//! When one author posts many times in a short window, their posts are
+//! collapsed into a single expandable group ("Alice posted 5 times") so
+//! they don't dominate the feed.
+//!
+//! The grouping settings, including the authors whose posts are always
+//! expanded, are stored in the account's `org.social.settings` account data
+//! (see [`save_feed_grouping()`]), so they follow the user across devices.
+
+use matrix_sdk::{
+    ruma::{OwnedUserId, UserId},
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use super::feed_aggregator::FeedItem;
+use crate::social::availability::load_settings;
+
+/// The newsfeed grouping settings of the logged-in account, once its settings are loaded.
+static FEED_GROUPING: Mutex<Option<FeedGroupingSettings>> = Mutex::new(None);
+
+/// Default minimum number of posts needed to form a group.
+pub const DEFAULT_GROUP_THRESHOLD: usize = 3;
+
+/// Default time window for grouping posts, in seconds.
+pub const DEFAULT_GROUP_WINDOW_SECONDS: u64 = 60 * 60;
+
+/// Settings controlling how posts are grouped in the feed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedGroupingSettings {
+    /// Whether grouping is enabled at all.
+    pub enabled: bool,
+    /// Minimum number of posts from one author within the window to form a group.
+    pub threshold: usize,
+    /// Maximum time between the first and last post of a group, in seconds.
+    pub window_seconds: u64,
+    /// Authors whose posts are never grouped.
+    pub always_expand: HashSet<OwnedUserId>,
+}
+
+impl Default for FeedGroupingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: DEFAULT_GROUP_THRESHOLD,
+            window_seconds: DEFAULT_GROUP_WINDOW_SECONDS,
+            always_expand: HashSet::new(),
+        }
+    }
+}
+
+impl FeedGroupingSettings {
+    /// Create grouping settings with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable grouping.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Set the minimum number of posts needed to form a group.
+    ///
+    /// Values below 2 are clamped to 2, since a single post is never a group.
+    pub fn with_threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold.max(2);
+        self
+    }
+
+    /// Set the grouping time window in seconds.
+    pub fn with_window(mut self, seconds: u64) -> Self {
+        self.window_seconds = seconds;
+        self
+    }
+
+    /// Never group posts from the given author.
+    pub fn always_expand_author(&mut self, author: OwnedUserId) {
+        self.always_expand.insert(author);
+    }
+
+    /// Allow posts from the given author to be grouped again.
+    pub fn allow_grouping_author(&mut self, author: &UserId) {
+        self.always_expand.retain(|a| a != author);
+    }
+}
+
+/// Parse the grouping settings saved in the account's social settings,
+/// falling back to the default settings.
+fn parse_feed_grouping(value: Option<serde_json::Value>) -> FeedGroupingSettings {
+    value
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Get the newsfeed grouping settings of the logged-in account, e.g., to apply them
+/// with [`SocialFeedView::set_grouping()`](crate::social::widgets::feed_view::SocialFeedView::set_grouping).
+pub fn feed_grouping() -> FeedGroupingSettings {
+    FEED_GROUPING.lock().unwrap().clone().unwrap_or_default()
+}
+
+/// Update the in-memory newsfeed grouping settings from the account's settings.
+pub(crate) fn update_feed_grouping(value: Option<serde_json::Value>) {
+    *FEED_GROUPING.lock().unwrap() = Some(parse_feed_grouping(value));
+}
+
+/// Save the newsfeed grouping settings in the account's social settings.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn save_feed_grouping(
+    client: &Client,
+    grouping: &FeedGroupingSettings,
+) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.feed_grouping = Some(serde_json::to_value(grouping)?);
+    client.account().set_account_data(settings).await?;
+    // Take effect right away rather than once the settings have synced back
+    *FEED_GROUPING.lock().unwrap() = Some(grouping.clone());
+    Ok(())
+}
+
+/// An item that can be grouped by author and time.
+pub trait GroupableItem {
+    /// The author of the item.
+    fn author(&self) -> &UserId;
+    /// When the item was posted (Unix timestamp in milliseconds).
+    fn timestamp_ms(&self) -> u64;
+}
+
+impl GroupableItem for FeedItem {
+    fn author(&self) -> &UserId {
+        &self.sender
+    }
+
+    fn timestamp_ms(&self) -> u64 {
+        self.origin_server_ts.get().into()
+    }
+}
+
+/// A single entry in a grouped feed, referring to items by index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FeedEntry {
+    /// A single item shown on its own.
+    Single(usize),
+    /// Several items from the same author, shown as one collapsible group.
+    Group {
+        /// The author of all items in the group.
+        author: OwnedUserId,
+        /// Indices of the grouped items, in feed order.
+        indices: Vec<usize>,
+    },
+}
+
+/// Group bursts of items from the same author.
+///
+/// Items keep their original order. A group is placed at the position of
+/// its first item and contains every later item by the same author that was
+/// posted within `window_seconds` of it, as long as there are at least
+/// `threshold` such items.
+pub fn group_feed_items<T: GroupableItem>(
+    items: &[T],
+    settings: &FeedGroupingSettings,
+) -> Vec<FeedEntry> {
+    if !settings.enabled {
+        return (0..items.len()).map(FeedEntry::Single).collect();
+    }
+
+    let window_ms = settings.window_seconds.saturating_mul(1000);
+    let mut assigned = vec![false; items.len()];
+    let mut entries = Vec::new();
+
+    for (i, item) in items.iter().enumerate() {
+        if assigned[i] {
+            continue;
+        }
+        assigned[i] = true;
+
+        if settings.always_expand.contains(item.author()) {
+            entries.push(FeedEntry::Single(i));
+            continue;
+        }
+
+        let indices: Vec<usize> = std::iter::once(i)
+            .chain((i + 1..items.len()).filter(|&j| {
+                !assigned[j]
+                    && items[j].author() == item.author()
+                    && items[j].timestamp_ms().abs_diff(item.timestamp_ms()) <= window_ms
+            }))
+            .collect();
+
+        if indices.len() >= settings.threshold {
+            for &j in &indices {
+                assigned[j] = true;
+            }
+            entries.push(FeedEntry::Group {
+                author: item.author().to_owned(),
+                indices,
+            });
+        } else {
+            entries.push(FeedEntry::Single(i));
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::post::PostContent;
+    use matrix_sdk::ruma::MilliSecondsSinceUnixEpoch;
+
+    fn item(sender: &str, seconds: u64) -> FeedItem {
+        FeedItem::new(
+            "!room:example.org".try_into().unwrap(),
+            "$event:example.org".try_into().unwrap(),
+            sender.try_into().unwrap(),
+            MilliSecondsSinceUnixEpoch((seconds * 1000).try_into().unwrap()),
+            PostContent::Text {
+                body: "Test".to_string(),
+                formatted_body: None,
+                mentions: std::collections::BTreeSet::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_burst_is_grouped() {
+        let items = vec![
+            item("@alice:example.org", 500),
+            item("@bob:example.org", 450),
+            item("@alice:example.org", 400),
+            item("@alice:example.org", 300),
+        ];
+
+        let entries = group_feed_items(&items, &FeedGroupingSettings::new());
+        assert_eq!(
+            entries,
+            vec![
+                FeedEntry::Group {
+                    author: "@alice:example.org".try_into().unwrap(),
+                    indices: vec![0, 2, 3],
+                },
+                FeedEntry::Single(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_below_threshold_is_not_grouped() {
+        let items = vec![
+            item("@alice:example.org", 500),
+            item("@alice:example.org", 400),
+        ];
+
+        let entries = group_feed_items(&items, &FeedGroupingSettings::new());
+        assert_eq!(entries, vec![FeedEntry::Single(0), FeedEntry::Single(1)]);
+    }
+
+    #[test]
+    fn test_posts_outside_window_are_not_grouped() {
+        let items = vec![
+            item("@alice:example.org", 10_000),
+            item("@alice:example.org", 5_000),
+            item("@alice:example.org", 100),
+        ];
+
+        let settings = FeedGroupingSettings::new().with_window(60);
+        let entries = group_feed_items(&items, &settings);
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn test_always_expand_author() {
+        let items = vec![
+            item("@alice:example.org", 500),
+            item("@alice:example.org", 400),
+            item("@alice:example.org", 300),
+        ];
+
+        let mut settings = FeedGroupingSettings::new();
+        settings.always_expand_author("@alice:example.org".try_into().unwrap());
+        assert_eq!(group_feed_items(&items, &settings).len(), 3);
+
+        settings.allow_grouping_author("@alice:example.org".try_into().unwrap());
+        assert_eq!(group_feed_items(&items, &settings).len(), 1);
+    }
+
+    #[test]
+    fn test_saved_grouping_is_restored() {
+        let mut settings = FeedGroupingSettings::new().with_window(60);
+        settings.always_expand_author("@alice:example.org".try_into().unwrap());
+
+        let saved = serde_json::to_value(&settings).unwrap();
+        assert_eq!(parse_feed_grouping(Some(saved)), settings);
+        // Missing or invalid settings fall back to the defaults
+        let partial = serde_json::json!({ "enabled": false });
+        assert_eq!(
+            parse_feed_grouping(Some(partial)),
+            FeedGroupingSettings::new().with_enabled(false)
+        );
+        assert_eq!(
+            parse_feed_grouping(Some(serde_json::json!("invalid"))),
+            FeedGroupingSettings::new()
+        );
+    }
+}
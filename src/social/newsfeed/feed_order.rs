@@ -0,0 +1,220 @@
+//! Deterministic ordering of posts that arrive in the newsfeed one batch at a time.
+//!
+//! Posts from different feed rooms can arrive out of order, e.g., when one room
+//! is paginated after another. Adding them with [`merge_feed_items()`] keeps the
+//! posts already shown in place, and inserts each new post before the first shown
+//! post that comes after it in [`feed_order()`]. This guarantees that:
+//! * posts already shown never move relative to each other,
+//! * a new post lands in the same place relative to the shown posts whichever
+//!   order the new posts arrive in,
+//! * and while the shown posts are in feed order, e.g., when all of them were
+//!   added this way, they stay in feed order.
+//!
+//! It doesn't reorder posts shown in another order, e.g., ranked ones, so new
+//! posts merged into them aren't in feed order either. And a post that arrives
+//! again replaces the shown one in place, so its latest copy is shown.
+//!
+//! Rows of the feed are identified by a [`FeedRowKey`] derived from the event ID
+//! of their first post, so that the row at the top of the view can be kept in
+//! place with [`find_anchor_row()`] when rows are inserted above it.
+
+use matrix_sdk::ruma::{EventId, OwnedEventId};
+use std::cmp::Ordering;
+
+use super::feed_aggregator::FeedItem;
+use super::feed_grouping::GroupableItem;
+
+/// An item that can be shown in the feed, identified by its event ID.
+pub trait OrderedFeedItem: GroupableItem {
+    /// The event ID of the item.
+    fn event_id(&self) -> &EventId;
+}
+
+impl OrderedFeedItem for FeedItem {
+    fn event_id(&self) -> &EventId {
+        &self.event_id
+    }
+}
+
+/// Compare two items in feed order: newest first, with ties broken by event ID.
+pub fn feed_order<T: OrderedFeedItem>(a: &T, b: &T) -> Ordering {
+    b.timestamp_ms()
+        .cmp(&a.timestamp_ms())
+        .then_with(|| a.event_id().cmp(b.event_id()))
+}
+
+/// Merge newly arrived items into the shown items.
+///
+/// Items that are already shown are replaced in place by their newly arrived copy.
+/// Each new item is inserted before the first shown item that comes after it in
+/// [`feed_order()`], or at the end, so that shown items never move relative to
+/// each other (see the [module docs](self) for what this guarantees).
+///
+/// Returns the number of new items inserted.
+pub fn merge_feed_items<T: OrderedFeedItem>(
+    shown: &mut Vec<T>,
+    incoming: impl IntoIterator<Item = T>,
+) -> usize {
+    let mut incoming: Vec<T> = incoming.into_iter().collect();
+    incoming.sort_by(feed_order);
+
+    let mut inserted = 0;
+    for item in incoming {
+        if let Some(existing) = shown
+            .iter_mut()
+            .find(|shown_item| shown_item.event_id() == item.event_id())
+        {
+            *existing = item;
+            continue;
+        }
+        let position = shown
+            .iter()
+            .position(|shown_item| feed_order(&item, shown_item) == Ordering::Less)
+            .unwrap_or(shown.len());
+        shown.insert(position, item);
+        inserted += 1;
+    }
+    inserted
+}
+
+/// A stable ID for a row of the feed, which doesn't change when rows are
+/// inserted or removed around it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FeedRowKey {
+    /// A row showing one or more posts, by the event ID of its first post.
+    Post(OwnedEventId),
+    /// The "People you may know" card.
+    Suggestions,
+    /// The "Birthdays this week" card.
+    Birthdays,
+}
+
+/// Find the new index of the row that was at `first_index` of the old rows,
+/// to keep it at the top of the view after the rows changed.
+///
+/// If that row is gone, the next old row that's still there is used instead.
+/// Returns `None` if none of the rows from `first_index` on are left.
+pub fn find_anchor_row(
+    old_keys: &[FeedRowKey],
+    first_index: usize,
+    new_keys: &[FeedRowKey],
+) -> Option<usize> {
+    old_keys
+        .iter()
+        .skip(first_index)
+        .find_map(|key| new_keys.iter().position(|new_key| new_key == key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{owned_user_id, OwnedUserId, UserId};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Post {
+        event_id: OwnedEventId,
+        author: OwnedUserId,
+        timestamp_ms: u64,
+    }
+
+    impl GroupableItem for Post {
+        fn author(&self) -> &UserId {
+            &self.author
+        }
+
+        fn timestamp_ms(&self) -> u64 {
+            self.timestamp_ms
+        }
+    }
+
+    impl OrderedFeedItem for Post {
+        fn event_id(&self) -> &EventId {
+            &self.event_id
+        }
+    }
+
+    fn post(id: &str, timestamp_ms: u64) -> Post {
+        Post {
+            event_id: EventId::parse(format!("${id}:example.org")).unwrap(),
+            author: owned_user_id!("@friend:example.org"),
+            timestamp_ms,
+        }
+    }
+
+    fn ids(posts: &[Post]) -> Vec<&str> {
+        posts.iter().map(|p| p.event_id.localpart()).collect()
+    }
+
+    #[test]
+    fn test_out_of_order_arrival_converges() {
+        // The same posts from two rooms, arriving in different orders
+        let room_a = vec![post("a5", 5), post("a2", 2)];
+        let room_b = vec![post("b4", 4), post("b1", 1), post("b5", 5)];
+
+        let mut a_first = Vec::new();
+        merge_feed_items(&mut a_first, room_a.clone());
+        merge_feed_items(&mut a_first, room_b.clone());
+
+        let mut b_first = Vec::new();
+        merge_feed_items(&mut b_first, room_b);
+        merge_feed_items(&mut b_first, room_a);
+
+        assert_eq!(ids(&a_first), ["a5", "b5", "b4", "a2", "b1"]);
+        assert_eq!(a_first, b_first);
+    }
+
+    #[test]
+    fn test_shown_items_stay_in_place() {
+        // Shown in a ranked order rather than by time
+        let mut shown = vec![post("p3", 3), post("p9", 9), post("p1", 1)];
+        let inserted =
+            merge_feed_items(&mut shown, [post("p2", 2), post("p9", 9), post("p10", 10)]);
+        assert_eq!(inserted, 2);
+        assert_eq!(ids(&shown), ["p10", "p3", "p9", "p2", "p1"]);
+    }
+
+    #[test]
+    fn test_new_posts_land_in_place_whatever_their_arrival_order() {
+        // Shown in a ranked order, which merging doesn't turn into feed order
+        let shown = vec![post("p3", 3), post("p9", 9), post("p1", 1)];
+        let batches = [vec![post("p2", 2)], vec![post("p10", 10), post("p5", 5)]];
+
+        let mut in_order = shown.clone();
+        for batch in batches.clone() {
+            merge_feed_items(&mut in_order, batch);
+        }
+        let mut reversed = shown;
+        for batch in batches.into_iter().rev() {
+            merge_feed_items(&mut reversed, batch);
+        }
+
+        assert_eq!(ids(&in_order), ["p10", "p5", "p3", "p9", "p2", "p1"]);
+        assert_eq!(in_order, reversed);
+    }
+
+    #[test]
+    fn test_latest_copy_of_a_post_is_shown() {
+        let mut shown = vec![post("p2", 2), post("p1", 1)];
+        let mut edited = post("p2", 2);
+        edited.author = owned_user_id!("@co-author:example.org");
+
+        assert_eq!(merge_feed_items(&mut shown, [edited.clone()]), 0);
+        assert_eq!(shown[0], edited);
+        // A stale copy arriving later replaces it again
+        merge_feed_items(&mut shown, [post("p2", 2)]);
+        assert_eq!(shown[0], post("p2", 2));
+    }
+
+    #[test]
+    fn test_find_anchor_row() {
+        let key =
+            |id: &str| FeedRowKey::Post(EventId::parse(format!("${id}:example.org")).unwrap());
+        let old_keys = [key("a"), key("b"), FeedRowKey::Suggestions, key("c")];
+        let new_keys = [key("new"), key("a"), FeedRowKey::Suggestions, key("c")];
+
+        assert_eq!(find_anchor_row(&old_keys, 0, &new_keys), Some(1));
+        // The removed row is replaced by the next row that's still there
+        assert_eq!(find_anchor_row(&old_keys, 1, &new_keys), Some(2));
+        assert_eq!(find_anchor_row(&old_keys, 4, &new_keys), None);
+    }
+}
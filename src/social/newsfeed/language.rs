@@ -0,0 +1,321 @@
+//! Lightweight language detection for post text.
+//!
+//! Non-Latin scripts are classified by their dominant Unicode script.
+//! Latin-script text is scored against small per-language profiles of the
+//! most common character trigrams. This is intentionally tiny and fully local;
+//! it only needs to be good enough to power per-language feed filters.
+
+use serde::{Deserialize, Serialize};
+
+/// Minimum number of letters required before attempting detection.
+const MIN_LETTERS: usize = 12;
+
+/// A language that can be detected in post text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    Spanish,
+    French,
+    German,
+    Portuguese,
+    Italian,
+    Dutch,
+    Russian,
+    Chinese,
+    Japanese,
+    Korean,
+    Arabic,
+    Greek,
+    Hebrew,
+    Hindi,
+}
+
+impl Language {
+    /// All languages that can be detected, in declaration order.
+    pub const ALL: [Self; 15] = [
+        Self::English,
+        Self::Spanish,
+        Self::French,
+        Self::German,
+        Self::Portuguese,
+        Self::Italian,
+        Self::Dutch,
+        Self::Russian,
+        Self::Chinese,
+        Self::Japanese,
+        Self::Korean,
+        Self::Arabic,
+        Self::Greek,
+        Self::Hebrew,
+        Self::Hindi,
+    ];
+
+    /// Get the ISO 639-1 code for this language.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+            Self::French => "fr",
+            Self::German => "de",
+            Self::Portuguese => "pt",
+            Self::Italian => "it",
+            Self::Dutch => "nl",
+            Self::Russian => "ru",
+            Self::Chinese => "zh",
+            Self::Japanese => "ja",
+            Self::Korean => "ko",
+            Self::Arabic => "ar",
+            Self::Greek => "el",
+            Self::Hebrew => "he",
+            Self::Hindi => "hi",
+        }
+    }
+
+    /// Get the English display name of this language.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::Spanish => "Spanish",
+            Self::French => "French",
+            Self::German => "German",
+            Self::Portuguese => "Portuguese",
+            Self::Italian => "Italian",
+            Self::Dutch => "Dutch",
+            Self::Russian => "Russian",
+            Self::Chinese => "Chinese",
+            Self::Japanese => "Japanese",
+            Self::Korean => "Korean",
+            Self::Arabic => "Arabic",
+            Self::Greek => "Greek",
+            Self::Hebrew => "Hebrew",
+            Self::Hindi => "Hindi",
+        }
+    }
+
+    /// Parse a language from an ISO 639-1 code or a locale string like `en_US.UTF-8`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code
+            .split(['_', '-', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Self::ALL.into_iter().find(|lang| lang.code() == code)
+    }
+
+    /// Get the user's preferred language based on the system locale.
+    ///
+    /// Falls back to English if the locale is unset or unsupported.
+    pub fn system() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find_map(|locale| Self::from_code(&locale))
+            .unwrap_or(Self::English)
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Most common trigrams for each Latin-script language, most frequent first.
+///
+/// Words are padded with a single space on each side before extracting trigrams.
+#[rustfmt::skip]
+const LATIN_PROFILES: &[(Language, &[&str])] = &[
+    (Language::English, &[
+        " th", "the", "he ", "and", " an", "nd ", "ing", "ng ", " to", "to ",
+        " of", "of ", "ion", " in", "in ", "is ", " is", "hat", "tha", "you",
+        "ou ", "er ", "ed ", " wa", "for", "re ", "ere", " be", "ly ", " it",
+    ]),
+    (Language::Spanish, &[
+        " de", "de ", " la", "la ", "os ", " qu", "que", "ue ", " el", "el ",
+        "es ", " en", "en ", "as ", "ent", "ión", "ado", " lo", " co", "con",
+        "ara", "par", " y ", "ien", "los", " es", "ar ", "ra ", "una", " un",
+    ]),
+    (Language::French, &[
+        " de", "de ", "es ", " le", "le ", "ent", " la", "la ", "les", " et",
+        "et ", "nt ", "ion", "re ", " un", " qu", "que", "ue ", " pa", "est",
+        "our", "ais", "eur", " du", "ait", "ell", "ons", " je", "je ", "ous",
+    ]),
+    (Language::German, &[
+        "en ", "er ", " de", "der", "ie ", "ich", "ch ", "die", " di", "ein",
+        " ei", "und", " un", "nd ", "sch", "ung", "den", "cht", " da", "das",
+        "ist", " is", "nde", " zu", "auf", "ine", "gen", " ic", "te ", "ht ",
+    ]),
+    (Language::Portuguese, &[
+        " de", "de ", "os ", " qu", "que", "ue ", " do", "do ", "da ", " da",
+        "ão ", "ção", " co", "com", "ent", " e ", "as ", "es ", " a ", "nte",
+        " pa", "par", "ara", "em ", " em", "não", " nã", "um ", "uma", " um",
+    ]),
+    (Language::Italian, &[
+        " di", "di ", "che", " ch", "he ", " il", "il ", "la ", " la", "to ",
+        "re ", "ell", "lla", "one", "ent", " de", "del", "per", " pe", " co",
+        "ato", "ere", "no ", "zio", " e ", "gli", " un", "son", "ono", "ta ",
+    ]),
+    (Language::Dutch, &[
+        "en ", " de", "de ", "het", " he", "et ", "een", " ee", "van", " va",
+        "an ", "ij ", " in", "in ", "er ", "nde", "ver", " ve", "ing", "oor",
+        "dat", " da", "ijk", "aar", " zi", "ik ", " ik", "nie", "iet", "je ",
+    ]),
+];
+
+/// Detect the language of the given text.
+///
+/// Returns `None` if the text is too short or no language scores clearly.
+pub fn detect_language(text: &str) -> Option<Language> {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() < MIN_LETTERS {
+        return None;
+    }
+
+    if let Some(language) = detect_by_script(&letters) {
+        return Some(language);
+    }
+
+    let trigrams = trigrams(text);
+    let mut best: Option<(Language, usize)> = None;
+    for (language, profile) in LATIN_PROFILES {
+        let score: usize = trigrams
+            .iter()
+            .filter_map(|t| profile.iter().position(|p| p == t))
+            .map(|rank| profile.len() - rank)
+            .sum();
+        if score > 0 && best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((*language, score));
+        }
+    }
+    best.map(|(language, _)| language)
+}
+
+/// Classify text written in a non-Latin script.
+///
+/// Returns `None` if the majority of letters are not in a recognized script.
+fn detect_by_script(letters: &[char]) -> Option<Language> {
+    let mut counts = [0usize; 9];
+    for c in letters {
+        let index = match *c as u32 {
+            0x0400..=0x04FF => 0,                   // Cyrillic
+            0x0370..=0x03FF => 1,                   // Greek
+            0x0590..=0x05FF => 2,                   // Hebrew
+            0x0600..=0x06FF => 3,                   // Arabic
+            0x0900..=0x097F => 4,                   // Devanagari
+            0x3040..=0x30FF => 5,                   // Hiragana and Katakana
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => 6, // Hangul
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => 7, // CJK ideographs
+            _ => 8,
+        };
+        counts[index] += 1;
+    }
+
+    let non_latin: usize = counts[..8].iter().sum();
+    if non_latin * 2 < letters.len() {
+        return None;
+    }
+
+    // Japanese mixes kana with ideographs, so any kana means Japanese.
+    if counts[5] > 0 {
+        return Some(Language::Japanese);
+    }
+
+    let (index, _) = counts[..8]
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, count)| **count)?;
+    Some(match index {
+        0 => Language::Russian,
+        1 => Language::Greek,
+        2 => Language::Hebrew,
+        3 => Language::Arabic,
+        4 => Language::Hindi,
+        6 => Language::Korean,
+        _ => Language::Chinese,
+    })
+}
+
+/// Extract space-padded, lowercased character trigrams from each word.
+fn trigrams(text: &str) -> Vec<String> {
+    let mut trigrams = Vec::new();
+    for word in text.split_whitespace() {
+        // Skip links and mentions, which carry no language signal.
+        if word.contains("://") || word.starts_with('@') || word.starts_with('#') {
+            continue;
+        }
+        let letters: String = word
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(char::to_lowercase)
+            .collect();
+        if letters.is_empty() {
+            continue;
+        }
+        let padded: Vec<char> = format!(" {} ", letters).chars().collect();
+        trigrams.extend(padded.windows(3).map(|w| w.iter().collect::<String>()));
+    }
+    trigrams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_latin_languages() {
+        let cases = [
+            (
+                "The weather is lovely today and I am going to the beach with my friends",
+                Language::English,
+            ),
+            (
+                "Hoy vamos a la playa con los amigos de mi hermana, que es muy bonita",
+                Language::Spanish,
+            ),
+            (
+                "Je suis très content de vous voir ce soir, nous allons au restaurant",
+                Language::French,
+            ),
+            (
+                "Ich gehe heute mit meinen Freunden in die Stadt und wir essen zusammen",
+                Language::German,
+            ),
+            (
+                "Oggi andiamo al mare con gli amici di mia sorella, che bella giornata",
+                Language::Italian,
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(detect_language(text), Some(expected), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_by_script() {
+        assert_eq!(
+            detect_language("Привет, как у тебя дела сегодня?"),
+            Some(Language::Russian)
+        );
+        assert_eq!(
+            detect_language("今日はとても良い天気ですね、散歩に行きましょう"),
+            Some(Language::Japanese)
+        );
+        assert_eq!(
+            detect_language("오늘 날씨가 정말 좋네요 산책하러 갈까요"),
+            Some(Language::Korean)
+        );
+    }
+
+    #[test]
+    fn test_short_text_is_undetected() {
+        assert_eq!(detect_language("Hello world"), None);
+        assert_eq!(detect_language("👍👍👍"), None);
+    }
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(Language::from_code("en_US.UTF-8"), Some(Language::English));
+        assert_eq!(Language::from_code("pt-BR"), Some(Language::Portuguese));
+        assert_eq!(Language::from_code("xx"), None);
+    }
+}
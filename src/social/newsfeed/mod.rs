@@ -4,8 +4,62 @@
 //! feed rooms into a single unified newsfeed, with sorting and filtering
 //! capabilities.
 
+pub mod birthdays;
+pub mod capabilities;
+pub mod density;
+pub mod diagnostics;
 pub mod feed_aggregator;
+pub mod feed_cache;
 pub mod feed_filter;
+pub mod feed_grouping;
+pub mod feed_order;
+pub mod language;
+pub mod mutes;
+pub mod ranking;
+pub mod read_markers;
+pub mod spam;
+pub mod undecryptable;
+pub mod unsupported;
 
-pub use feed_aggregator::{create_feed_sync_filter, FeedAggregator, FeedError, FeedItem, FeedSortOrder};
-pub use feed_filter::{ContentFilter, FeedFilterSettings};
+pub use birthdays::{
+    are_birthdays_hidden, load_birthdays_this_week, set_birthdays_hidden, BirthdaysAction,
+    UpcomingBirthday,
+};
+pub use capabilities::{CapabilityTracker, DegradedFeature};
+pub use density::{feed_density, set_feed_density, FeedDensityAction};
+pub use diagnostics::{
+    load_feed_diagnostics_enabled, save_feed_diagnostics_enabled, FeedDiagnostics,
+    RoomAggregationStats,
+};
+pub use feed_aggregator::{
+    create_feed_sync_filter, deduplicate_cross_posts, surface_pinned_items, FeedAggregator,
+    FeedError, FeedItem, FeedSortOrder,
+};
+pub use feed_cache::{FeedCache, FeedCacheError, MAX_CACHED_FEED_ITEMS};
+pub use feed_filter::{
+    feed_filter, load_feed_filter, save_feed_filter, ContentFilter, FeedFilterSettings,
+    MutedPattern,
+};
+pub use feed_grouping::{
+    feed_grouping, group_feed_items, save_feed_grouping, FeedEntry, FeedGroupingSettings,
+    GroupableItem,
+};
+pub use feed_order::{feed_order, find_anchor_row, merge_feed_items, FeedRowKey, OrderedFeedItem};
+pub use language::{detect_language, Language};
+pub use mutes::{MuteDuration, MuteError, MuteService};
+pub use ranking::{
+    feed_ranker, load_author_affinity, load_feed_ranker, rank_items, register_feed_ranker,
+    save_feed_ranker, AffinityRanker, AuthorAffinity, ChronologicalWithBoostsRanker, FeedRanker,
+    ForYouRanker, ForYouWeights, RankingContext, AFFINITY_RANKER, CHRONOLOGICAL_WITH_BOOSTS_RANKER,
+    FOR_YOU_RANKER,
+};
+pub use read_markers::{FeedReadMarker, FeedReadMarkerService, FeedReadMarkers};
+pub use spam::{
+    save_not_spam_sender, ScoredFeedItem, SpamAssessment, SpamScorer, SpamSignal, SpamStrictness,
+    SpamVerdict,
+};
+pub use undecryptable::{placeholder_feed_item, undecryptable_feed_item, UndecryptablePost};
+pub use unsupported::{
+    schema_version, unsupported_feed_item, unsupported_post, UnsupportedPost,
+    SOCIAL_EVENT_TYPE_PREFIX,
+};
@@ -0,0 +1,220 @@
+//! Persistent, optionally temporary mutes of newsfeed authors.
+//!
+//! Mutes are stored in the account's `org.social.mutes` account data, so
+//! they survive restarts and follow the user across devices. A mute can
+//! last for a [`MuteDuration`] or until the author is unmuted. Expired mutes
+//! stop hiding posts right away. The feed aggregator hides the posts of muted
+//! authors from the newsfeed, and removes expired mutes from the account data
+//! as it does (see
+//! [`FeedAggregator::get_aggregated_feed()`](super::FeedAggregator::get_aggregated_feed)).
+
+use matrix_sdk::{
+    ruma::{MilliSecondsSinceUnixEpoch, OwnedUserId, UserId},
+    Client,
+};
+use robrix_social_events::mute::{MutedAuthor, SocialMutesEventContent};
+
+use crate::social::events::timing::now_ms;
+
+const HOUR_MS: u64 = 60 * 60 * 1000;
+
+/// How long to mute an author for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MuteDuration {
+    /// Mute for 24 hours.
+    Day,
+    /// Mute for 7 days.
+    Week,
+    /// Mute until unmuted.
+    Forever,
+}
+
+impl MuteDuration {
+    /// Get the time a mute starting at `now_ms` expires (Unix timestamp in
+    /// milliseconds), or `None` if it doesn't.
+    pub fn expires_at(&self, now_ms: u64) -> Option<u64> {
+        match self {
+            Self::Day => Some(now_ms + 24 * HOUR_MS),
+            Self::Week => Some(now_ms + 7 * 24 * HOUR_MS),
+            Self::Forever => None,
+        }
+    }
+
+    /// Get the label shown for this duration in the mute menu.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Day => "Mute for 24 hours",
+            Self::Week => "Mute for 7 days",
+            Self::Forever => "Mute forever",
+        }
+    }
+}
+
+/// Remove the mutes that have expired at the given time
+/// (Unix timestamp in milliseconds), returning the unmuted authors.
+pub fn remove_expired_mutes(mutes: &mut SocialMutesEventContent, now_ms: u64) -> Vec<OwnedUserId> {
+    let expired: Vec<_> = mutes
+        .authors
+        .iter()
+        .filter(|(_, muted)| !muted.is_active_at(now_ms))
+        .map(|(user_id, _)| user_id.clone())
+        .collect();
+    for user_id in &expired {
+        mutes.authors.remove(user_id);
+    }
+    expired
+}
+
+/// Service for muting and unmuting newsfeed authors.
+pub struct MuteService {
+    client: Client,
+}
+
+impl MuteService {
+    /// Create a new MuteService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Get the current user's mutes, including any that have expired
+    /// but haven't been swept yet.
+    ///
+    /// # Errors
+    /// Returns an error if the account data cannot be loaded.
+    pub async fn mutes(&self) -> Result<SocialMutesEventContent, MuteError> {
+        let raw = self
+            .client
+            .account()
+            .account_data::<SocialMutesEventContent>()
+            .await?;
+        Ok(raw
+            .and_then(|raw| raw.deserialize().ok())
+            .unwrap_or_default())
+    }
+
+    /// Mute an author for the given duration.
+    ///
+    /// Muting an author who is already muted replaces their mute.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, tries to mute
+    /// themselves, or the account data cannot be loaded or saved.
+    pub async fn mute(&self, user_id: &UserId, duration: MuteDuration) -> Result<(), MuteError> {
+        if self.client.user_id().ok_or(MuteError::NotLoggedIn)? == user_id {
+            return Err(MuteError::CannotMuteSelf);
+        }
+
+        let mut mutes = self.mutes().await?;
+        mutes.authors.insert(
+            user_id.to_owned(),
+            MutedAuthor {
+                since: MilliSecondsSinceUnixEpoch::now(),
+                expires_at: duration.expires_at(now_ms()),
+            },
+        );
+        self.client.account().set_account_data(mutes).await?;
+        Ok(())
+    }
+
+    /// Unmute an author.
+    ///
+    /// # Errors
+    /// Returns an error if the account data cannot be loaded or saved.
+    pub async fn unmute(&self, user_id: &UserId) -> Result<(), MuteError> {
+        let mut mutes = self.mutes().await?;
+        if mutes.authors.remove(user_id).is_some() {
+            self.client.account().set_account_data(mutes).await?;
+        }
+        Ok(())
+    }
+
+    /// Get the current user's mutes that haven't expired, removing the expired
+    /// ones from the account data like [`sweep_expired()`](Self::sweep_expired).
+    ///
+    /// # Errors
+    /// Returns an error if the account data cannot be loaded or saved.
+    pub async fn active_mutes(&self) -> Result<SocialMutesEventContent, MuteError> {
+        let mut mutes = self.mutes().await?;
+        if !remove_expired_mutes(&mut mutes, now_ms()).is_empty() {
+            self.client
+                .account()
+                .set_account_data(mutes.clone())
+                .await?;
+        }
+        Ok(mutes)
+    }
+
+    /// Remove expired mutes from the account data, returning the unmuted authors.
+    ///
+    /// The account data is only saved if any mute expired.
+    ///
+    /// # Errors
+    /// Returns an error if the account data cannot be loaded or saved.
+    pub async fn sweep_expired(&self) -> Result<Vec<OwnedUserId>, MuteError> {
+        let mut mutes = self.mutes().await?;
+        let expired = remove_expired_mutes(&mut mutes, now_ms());
+        if !expired.is_empty() {
+            self.client.account().set_account_data(mutes).await?;
+        }
+        Ok(expired)
+    }
+}
+
+/// Errors that can occur when muting authors.
+#[derive(Debug, thiserror::Error)]
+pub enum MuteError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// Users can't mute themselves.
+    #[error("Cannot mute yourself")]
+    CannotMuteSelf,
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn muted(expires_at: Option<u64>) -> MutedAuthor {
+        MutedAuthor {
+            since: MilliSecondsSinceUnixEpoch(0u64.try_into().unwrap()),
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_mute_duration_expiry() {
+        let now = 1_700_000_000_000;
+        assert_eq!(MuteDuration::Day.expires_at(now), Some(now + 24 * HOUR_MS));
+        assert_eq!(
+            MuteDuration::Week.expires_at(now),
+            Some(now + 7 * 24 * HOUR_MS)
+        );
+        assert_eq!(MuteDuration::Forever.expires_at(now), None);
+    }
+
+    #[test]
+    fn test_remove_expired_mutes() {
+        let now = 1_700_000_000_000;
+        let expired: OwnedUserId = "@expired:example.org".try_into().unwrap();
+        let active: OwnedUserId = "@active:example.org".try_into().unwrap();
+        let forever: OwnedUserId = "@forever:example.org".try_into().unwrap();
+
+        let mut mutes = SocialMutesEventContent::default();
+        mutes.authors.insert(expired.clone(), muted(Some(now)));
+        mutes.authors.insert(active.clone(), muted(Some(now + 1)));
+        mutes.authors.insert(forever.clone(), muted(None));
+
+        assert_eq!(remove_expired_mutes(&mut mutes, now), vec![expired]);
+        assert_eq!(
+            mutes.authors.keys().cloned().collect::<Vec<_>>(),
+            vec![active, forever]
+        );
+        assert!(remove_expired_mutes(&mut mutes, now).is_empty());
+    }
+}
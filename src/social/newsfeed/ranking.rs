@@ -0,0 +1,480 @@
+//! Ranking of newsfeed items.
+//!
+//! Rankers score each feed item, and items are shown highest score first
+//! (see [`rank_items()`]). The built-in [`ForYouRanker`] combines how recent
+//! a post is, how often the user interacts with its author, and how much
+//! engagement it has. Other ranking algorithms can implement [`FeedRanker`],
+//! e.g., to compare rankings in tests.
+//!
+//! Rankers are known by name (see [`feed_ranker()`]), so that the account's
+//! social settings can pick one (see [`load_feed_ranker()`]) and different
+//! rankings can be tried out without changing the
+//! [`FeedAggregator`](super::FeedAggregator). Besides the built-in rankers,
+//! the app can add its own with [`register_feed_ranker()`].
+//!
+//! Author affinity is computed on the device from the user's own reactions
+//! and comments in their feed rooms (see [`load_author_affinity()`]).
+
+use matrix_sdk::{
+    room::MessagesOptions,
+    ruma::{events::AnySyncTimelineEvent, OwnedEventId, OwnedUserId, UserId},
+    Client,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use super::feed_aggregator::FeedItem;
+use crate::social::{
+    activity_log::{activity_of_event, Activity},
+    availability::load_settings,
+    feed_room::is_feed_room,
+};
+
+const HOUR_MS: f64 = 60.0 * 60.0 * 1000.0;
+
+/// Name of [`ForYouRanker`] with the default weights.
+pub const FOR_YOU_RANKER: &str = "for_you";
+
+/// Name of [`ChronologicalWithBoostsRanker`] with the default boost.
+pub const CHRONOLOGICAL_WITH_BOOSTS_RANKER: &str = "chronological_with_boosts";
+
+/// Name of [`AffinityRanker`].
+pub const AFFINITY_RANKER: &str = "friend_affinity";
+
+/// What a ranker knows besides the items being ranked.
+#[derive(Clone, Copy, Debug)]
+pub struct RankingContext<'a> {
+    /// The current time (Unix timestamp in milliseconds).
+    pub now_ms: u64,
+    /// How often the user interacts with each author.
+    pub affinity: &'a AuthorAffinity,
+}
+
+/// An algorithm for ranking newsfeed items.
+pub trait FeedRanker: Send + Sync {
+    /// Score a feed item; items with higher scores are shown first.
+    fn score(&self, item: &FeedItem, context: &RankingContext) -> f64;
+}
+
+/// Sort feed items by their score from the given ranker, highest first.
+///
+/// Items with equal scores are shown newest first.
+pub fn rank_items(ranker: &dyn FeedRanker, items: &mut [FeedItem], context: &RankingContext) {
+    let mut scored: Vec<(f64, FeedItem)> = items
+        .iter()
+        .map(|item| (ranker.score(item, context), item.clone()))
+        .collect();
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .total_cmp(a_score)
+            .then_with(|| b.origin_server_ts.cmp(&a.origin_server_ts))
+    });
+    for (slot, (_, item)) in items.iter_mut().zip(scored) {
+        *slot = item;
+    }
+}
+
+/// How often the user interacts with each author, by reacting to or
+/// commenting on their posts.
+#[derive(Clone, Debug, Default)]
+pub struct AuthorAffinity {
+    /// Number of interactions with each author.
+    interactions: HashMap<OwnedUserId, u32>,
+    /// The highest number of interactions with any author.
+    max_interactions: u32,
+}
+
+impl AuthorAffinity {
+    /// Create an empty affinity, where all authors score 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an interaction with an author.
+    pub fn record_interaction(&mut self, author: OwnedUserId) {
+        let count = self.interactions.entry(author).or_default();
+        *count += 1;
+        self.max_interactions = self.max_interactions.max(*count);
+    }
+
+    /// Get the number of recorded interactions with an author.
+    pub fn interactions(&self, author: &UserId) -> u32 {
+        self.interactions.get(author).copied().unwrap_or(0)
+    }
+
+    /// Get the affinity to an author, from 0 (no interactions) to 1
+    /// (the author the user interacts with most).
+    ///
+    /// The scale is logarithmic, so that a few interactions already count.
+    pub fn score(&self, author: &UserId) -> f64 {
+        if self.max_interactions == 0 {
+            return 0.0;
+        }
+        let interactions = self.interactions(author) as f64;
+        (1.0 + interactions).ln() / (1.0 + self.max_interactions as f64).ln()
+    }
+}
+
+/// Weights of the "For You" ranking.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ForYouWeights {
+    /// After how many hours a post's score is halved.
+    pub recency_half_life_hours: f64,
+    /// How much author affinity boosts a post.
+    pub affinity: f64,
+    /// How much engagement boosts a post.
+    pub engagement: f64,
+}
+
+impl Default for ForYouWeights {
+    fn default() -> Self {
+        Self {
+            recency_half_life_hours: 24.0,
+            affinity: 2.0,
+            engagement: 1.0,
+        }
+    }
+}
+
+/// The "For You" ranking: recent posts by authors the user interacts with,
+/// and posts with a lot of engagement, are shown first.
+///
+/// A post's score is its boost from affinity and engagement, decayed by its age.
+/// Engagement counts logarithmically, so that popular posts don't drown out
+/// posts from close friends.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ForYouRanker {
+    pub weights: ForYouWeights,
+}
+
+impl ForYouRanker {
+    /// Create a "For You" ranker with the given weights.
+    pub fn new(weights: ForYouWeights) -> Self {
+        Self { weights }
+    }
+}
+
+impl FeedRanker for ForYouRanker {
+    fn score(&self, item: &FeedItem, context: &RankingContext) -> f64 {
+        let sent_at = u64::from(item.origin_server_ts.get());
+        let age_hours = context.now_ms.saturating_sub(sent_at) as f64 / HOUR_MS;
+        let decay = 0.5f64.powf(age_hours / self.weights.recency_half_life_hours.max(f64::EPSILON));
+
+        let affinity = context.affinity.score(&item.sender);
+        let engagement = (1.0 + item.engagement() as f64).ln();
+        decay * (1.0 + self.weights.affinity * affinity + self.weights.engagement * engagement)
+    }
+}
+
+/// Newest posts first, with posts by authors the user interacts with
+/// moved up as if they were sent a few hours later.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChronologicalWithBoostsRanker {
+    /// How many hours posts by the author the user interacts with most are moved up.
+    pub max_boost_hours: f64,
+}
+
+impl Default for ChronologicalWithBoostsRanker {
+    fn default() -> Self {
+        Self {
+            max_boost_hours: 6.0,
+        }
+    }
+}
+
+impl FeedRanker for ChronologicalWithBoostsRanker {
+    fn score(&self, item: &FeedItem, context: &RankingContext) -> f64 {
+        let sent_at = u64::from(item.origin_server_ts.get());
+        let age_hours = context.now_ms.saturating_sub(sent_at) as f64 / HOUR_MS;
+        self.max_boost_hours * context.affinity.score(&item.sender) - age_hours
+    }
+}
+
+/// Posts by the authors the user interacts with most first,
+/// each author's posts newest first.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AffinityRanker;
+
+impl FeedRanker for AffinityRanker {
+    fn score(&self, item: &FeedItem, context: &RankingContext) -> f64 {
+        context.affinity.score(&item.sender)
+    }
+}
+
+/// Rankers added by the app, by name.
+static CUSTOM_RANKERS: Mutex<BTreeMap<String, Arc<dyn FeedRanker>>> = Mutex::new(BTreeMap::new());
+
+/// Make a ranker available under the given name, replacing any ranker
+/// registered under that name before.
+///
+/// Built-in rankers can't be replaced.
+pub fn register_feed_ranker(name: impl Into<String>, ranker: Arc<dyn FeedRanker>) {
+    CUSTOM_RANKERS.lock().unwrap().insert(name.into(), ranker);
+}
+
+/// Get the ranker with the given name, either built in or registered with
+/// [`register_feed_ranker()`].
+pub fn feed_ranker(name: &str) -> Option<Arc<dyn FeedRanker>> {
+    match name {
+        FOR_YOU_RANKER => Some(Arc::new(ForYouRanker::default())),
+        CHRONOLOGICAL_WITH_BOOSTS_RANKER => {
+            Some(Arc::new(ChronologicalWithBoostsRanker::default()))
+        }
+        AFFINITY_RANKER => Some(Arc::new(AffinityRanker)),
+        _ => CUSTOM_RANKERS.lock().unwrap().get(name).cloned(),
+    }
+}
+
+/// Get the ranker picked in the account's social settings, if any.
+///
+/// Rankers this version of the app doesn't know are ignored,
+/// so that the aggregator's sort order applies instead.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded.
+pub async fn load_feed_ranker(
+    client: &Client,
+) -> Result<Option<Arc<dyn FeedRanker>>, matrix_sdk::Error> {
+    Ok(load_settings(client)
+        .await?
+        .feed_ranker
+        .and_then(|name| feed_ranker(&name)))
+}
+
+/// Pick the ranker with the given name in the account's social settings,
+/// or `None` to use the aggregator's sort order.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn save_feed_ranker(
+    client: &Client,
+    name: Option<&str>,
+) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.feed_ranker = name.map(str::to_owned);
+    client.account().set_account_data(settings).await?;
+    Ok(())
+}
+
+/// Load how often the user interacted with each author in their feed rooms
+/// since `since_ms` (Unix timestamp in milliseconds).
+///
+/// Every reaction to and comment on another user's post counts as an interaction.
+///
+/// # Errors
+/// Returns an error if room history cannot be loaded.
+pub async fn load_author_affinity(
+    client: &Client,
+    since_ms: u64,
+) -> Result<AuthorAffinity, matrix_sdk::Error> {
+    let mut affinity = AuthorAffinity::new();
+    let Some(user_id) = client.user_id() else {
+        return Ok(affinity);
+    };
+
+    for room in client.joined_rooms() {
+        if !is_feed_room(&room) {
+            continue;
+        }
+
+        // Reactions and comments are newer than what they're about, so they're
+        // found before their post when paginating backwards
+        let mut senders: HashMap<OwnedEventId, OwnedUserId> = HashMap::new();
+        let mut interacted_with: Vec<OwnedEventId> = Vec::new();
+        let mut from = None;
+        'paginate: loop {
+            let mut options = MessagesOptions::backward();
+            options.from = from.take();
+            let response = room.messages(options).await?;
+
+            for event in &response.chunk {
+                let Ok(AnySyncTimelineEvent::MessageLike(event)) = event.raw().deserialize() else {
+                    continue;
+                };
+                if u64::from(event.origin_server_ts().get()) < since_ms {
+                    break 'paginate;
+                }
+                if event.sender() != user_id {
+                    senders.insert(event.event_id().to_owned(), event.sender().to_owned());
+                    continue;
+                }
+                if let Some(Activity::Reaction { on, .. } | Activity::Comment { on }) =
+                    activity_of_event(&event)
+                {
+                    interacted_with.push(on);
+                }
+            }
+
+            match response.end {
+                Some(end) if !response.chunk.is_empty() => from = Some(end),
+                _ => break,
+            }
+        }
+
+        for event_id in interacted_with {
+            if let Some(author) = senders.get(&event_id) {
+                affinity.record_interaction(author.clone());
+            }
+        }
+    }
+    Ok(affinity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::post::PostContent;
+    use matrix_sdk::ruma::MilliSecondsSinceUnixEpoch;
+    use std::collections::BTreeMap;
+
+    const NOW: u64 = 1_700_000_000_000;
+
+    fn item(id: &str, sender: &str, hours_ago: u64, reactions: u32) -> FeedItem {
+        let mut item = FeedItem::new(
+            "!room:example.org".try_into().unwrap(),
+            format!("${id}:example.org").try_into().unwrap(),
+            sender.try_into().unwrap(),
+            MilliSecondsSinceUnixEpoch((NOW - hours_ago * HOUR_MS as u64).try_into().unwrap()),
+            PostContent::Text {
+                body: "Hello".to_string(),
+                formatted_body: None,
+                mentions: Default::default(),
+            },
+        );
+        item.reactions = BTreeMap::from([("👍".to_string(), reactions)]);
+        item
+    }
+
+    fn ids(items: &[FeedItem]) -> Vec<&str> {
+        items.iter().map(|item| item.event_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_author_affinity_score() {
+        let friend: OwnedUserId = "@friend:example.org".try_into().unwrap();
+        let acquaintance: OwnedUserId = "@acquaintance:example.org".try_into().unwrap();
+        let stranger: OwnedUserId = "@stranger:example.org".try_into().unwrap();
+
+        let mut affinity = AuthorAffinity::new();
+        assert_eq!(affinity.score(&friend), 0.0);
+        for _ in 0..9 {
+            affinity.record_interaction(friend.clone());
+        }
+        affinity.record_interaction(acquaintance.clone());
+
+        assert_eq!(affinity.interactions(&friend), 9);
+        assert_eq!(affinity.score(&friend), 1.0);
+        assert!(affinity.score(&acquaintance) > 0.0);
+        assert!(affinity.score(&acquaintance) < 1.0);
+        assert_eq!(affinity.score(&stranger), 0.0);
+    }
+
+    #[test]
+    fn test_for_you_prefers_recent_and_close_authors() {
+        let mut affinity = AuthorAffinity::new();
+        affinity.record_interaction("@friend:example.org".try_into().unwrap());
+        let context = RankingContext {
+            now_ms: NOW,
+            affinity: &affinity,
+        };
+
+        let mut items = vec![
+            item("old", "@stranger:example.org", 72, 0),
+            item("stranger", "@stranger:example.org", 1, 0),
+            item("friend", "@friend:example.org", 1, 0),
+            item("popular", "@stranger:example.org", 1, 50),
+        ];
+        rank_items(&ForYouRanker::default(), &mut items, &context);
+        assert_eq!(ids(&items), ["popular", "friend", "stranger", "old"]);
+
+        // Without the engagement boost, closeness wins
+        let ranker = ForYouRanker::new(ForYouWeights {
+            engagement: 0.0,
+            ..Default::default()
+        });
+        rank_items(&ranker, &mut items, &context);
+        assert_eq!(ids(&items)[0], "friend");
+    }
+
+    #[test]
+    fn test_custom_ranker() {
+        /// Ranks the least engaged posts first.
+        struct Underdog;
+        impl FeedRanker for Underdog {
+            fn score(&self, item: &FeedItem, _context: &RankingContext) -> f64 {
+                -(item.engagement() as f64)
+            }
+        }
+
+        let affinity = AuthorAffinity::new();
+        let context = RankingContext {
+            now_ms: NOW,
+            affinity: &affinity,
+        };
+        let mut items = vec![
+            item("a", "@a:example.org", 1, 5),
+            item("b", "@b:example.org", 2, 1),
+            item("c", "@c:example.org", 1, 1),
+        ];
+        rank_items(&Underdog, &mut items, &context);
+        // Ties are broken newest first
+        assert_eq!(ids(&items), ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn test_alternative_rankers() {
+        let mut affinity = AuthorAffinity::new();
+        affinity.record_interaction("@friend:example.org".try_into().unwrap());
+        let context = RankingContext {
+            now_ms: NOW,
+            affinity: &affinity,
+        };
+        let mut items = vec![
+            item("new", "@stranger:example.org", 1, 0),
+            item("friend", "@friend:example.org", 4, 0),
+            item("old_friend", "@friend:example.org", 12, 0),
+        ];
+
+        let ranker = feed_ranker(CHRONOLOGICAL_WITH_BOOSTS_RANKER).unwrap();
+        rank_items(ranker.as_ref(), &mut items, &context);
+        assert_eq!(ids(&items), ["friend", "new", "old_friend"]);
+
+        let ranker = feed_ranker(AFFINITY_RANKER).unwrap();
+        rank_items(ranker.as_ref(), &mut items, &context);
+        assert_eq!(ids(&items), ["friend", "old_friend", "new"]);
+    }
+
+    #[test]
+    fn test_register_feed_ranker() {
+        struct Oldest;
+        impl FeedRanker for Oldest {
+            fn score(&self, item: &FeedItem, _context: &RankingContext) -> f64 {
+                -(u64::from(item.origin_server_ts.get()) as f64)
+            }
+        }
+
+        assert!(feed_ranker("oldest_first").is_none());
+        register_feed_ranker("oldest_first", Arc::new(Oldest));
+        assert!(feed_ranker("oldest_first").is_some());
+
+        // Built-in rankers can't be replaced
+        register_feed_ranker(FOR_YOU_RANKER, Arc::new(Oldest));
+        let affinity = AuthorAffinity::new();
+        let context = RankingContext {
+            now_ms: NOW,
+            affinity: &affinity,
+        };
+        let mut items = vec![
+            item("old", "@a:example.org", 10, 0),
+            item("new", "@b:example.org", 1, 0),
+        ];
+        rank_items(
+            feed_ranker(FOR_YOU_RANKER).unwrap().as_ref(),
+            &mut items,
+            &context,
+        );
+        assert_eq!(ids(&items), ["new", "old"]);
+    }
+}
@@ -0,0 +1,208 @@
+//! Tracking which newsfeed posts the user has already seen.
+//!
+//! Each feed room's `m.fully_read` marker records the newest post the user
+//! has scrolled to in the newsfeed. Posts after a room's marker count as new,
+//! so the newsfeed can offer to jump to "N new posts" since the last visit
+//! (see [`FeedReadMarkers::new_post_count()`]). As posts scroll into view,
+//! the markers are moved forward (see [`FeedReadMarkers::mark_read()`]) and
+//! sent to the server with [`FeedReadMarkerService::send_fully_read()`].
+
+use matrix_sdk::{
+    ruma::{
+        api::client::receipt::create_receipt::v3::ReceiptType,
+        events::{fully_read::FullyReadEventContent, receipt::ReceiptThread},
+        MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, RoomId,
+    },
+    Client,
+};
+use std::collections::HashMap;
+
+use super::feed_aggregator::{FeedError, FeedItem};
+
+/// The newest post the user has seen in a feed room.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeedReadMarker {
+    /// Event ID of the post.
+    pub event_id: OwnedEventId,
+    /// When the post was sent.
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+}
+
+/// The read markers of the user's feed rooms.
+#[derive(Clone, Debug, Default)]
+pub struct FeedReadMarkers {
+    markers: HashMap<OwnedRoomId, FeedReadMarker>,
+}
+
+impl FeedReadMarkers {
+    /// Get the read marker of a feed room, if the user has read any of its posts.
+    pub fn marker(&self, room_id: &RoomId) -> Option<&FeedReadMarker> {
+        self.markers.get(room_id)
+    }
+
+    /// Move the read marker of a feed room to the given post,
+    /// unless it's already at a newer one.
+    ///
+    /// Returns whether the marker moved, i.e., whether it should be sent to the server.
+    pub fn mark_read(&mut self, room_id: &RoomId, marker: FeedReadMarker) -> bool {
+        if self
+            .markers
+            .get(room_id)
+            .is_some_and(|current| current.origin_server_ts >= marker.origin_server_ts)
+        {
+            return false;
+        }
+        self.markers.insert(room_id.to_owned(), marker);
+        true
+    }
+
+    /// Check whether a post sent at the given time in a feed room is new,
+    /// i.e., sent after the room's read marker.
+    ///
+    /// All posts in rooms without a read marker are new.
+    pub fn is_unread(
+        &self,
+        room_id: &RoomId,
+        origin_server_ts: MilliSecondsSinceUnixEpoch,
+    ) -> bool {
+        self.markers
+            .get(room_id)
+            .is_none_or(|marker| origin_server_ts > marker.origin_server_ts)
+    }
+
+    /// Count the new posts among the given items.
+    pub fn new_post_count(&self, items: &[FeedItem]) -> usize {
+        items
+            .iter()
+            .filter(|item| self.is_unread(&item.room_id, item.origin_server_ts))
+            .count()
+    }
+}
+
+/// Service for loading and sending the read markers of feed rooms.
+pub struct FeedReadMarkerService {
+    client: Client,
+}
+
+impl FeedReadMarkerService {
+    /// Create a new FeedReadMarkerService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Load the read markers of the given feed rooms.
+    ///
+    /// Rooms without a marker, or whose marked post can't be found,
+    /// are left out, so all of their posts count as new.
+    ///
+    /// # Errors
+    /// Returns an error if a room's account data cannot be loaded.
+    pub async fn load_markers(
+        &self,
+        room_ids: &[OwnedRoomId],
+    ) -> Result<FeedReadMarkers, FeedError> {
+        let mut markers = FeedReadMarkers::default();
+        for room_id in room_ids {
+            let Some(room) = self.client.get_room(room_id) else {
+                continue;
+            };
+            let Some(raw) = room.account_data_static::<FullyReadEventContent>().await? else {
+                continue;
+            };
+            let Ok(fully_read) = raw.deserialize() else {
+                continue;
+            };
+            let event_id = fully_read.content.event_id;
+            let Ok(event) = room.load_or_fetch_event(&event_id, None).await else {
+                continue;
+            };
+            let Some(origin_server_ts) = event
+                .raw()
+                .get_field::<MilliSecondsSinceUnixEpoch>("origin_server_ts")
+                .ok()
+                .flatten()
+            else {
+                continue;
+            };
+            markers.mark_read(
+                room_id,
+                FeedReadMarker {
+                    event_id,
+                    origin_server_ts,
+                },
+            );
+        }
+        Ok(markers)
+    }
+
+    /// Move the `m.fully_read` marker of a feed room to the given post.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found or the marker cannot be sent.
+    pub async fn send_fully_read(
+        &self,
+        room_id: &RoomId,
+        event_id: OwnedEventId,
+    ) -> Result<(), FeedError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or_else(|| FeedError::RoomNotFound(room_id.to_owned()))?;
+        room.send_single_receipt(ReceiptType::FullyRead, ReceiptThread::Unthreaded, event_id)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::post::PostContent;
+    use matrix_sdk::ruma::owned_room_id;
+
+    fn marker(event_id: &str, sent_at: u64) -> FeedReadMarker {
+        FeedReadMarker {
+            event_id: event_id.try_into().unwrap(),
+            origin_server_ts: MilliSecondsSinceUnixEpoch(sent_at.try_into().unwrap()),
+        }
+    }
+
+    fn item(room_id: &str, sent_at: u64) -> FeedItem {
+        FeedItem::new(
+            room_id.try_into().unwrap(),
+            format!("${sent_at}:a.org").try_into().unwrap(),
+            "@user:example.org".try_into().unwrap(),
+            MilliSecondsSinceUnixEpoch(sent_at.try_into().unwrap()),
+            PostContent::Text {
+                body: "Hello".to_string(),
+                formatted_body: None,
+                mentions: Default::default(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_markers_only_move_forward() {
+        let room_id = owned_room_id!("!a:a.org");
+        let mut markers = FeedReadMarkers::default();
+        assert!(markers.mark_read(&room_id, marker("$2:a.org", 2)));
+        assert!(!markers.mark_read(&room_id, marker("$1:a.org", 1)));
+        assert!(!markers.mark_read(&room_id, marker("$2:a.org", 2)));
+        assert!(markers.mark_read(&room_id, marker("$3:a.org", 3)));
+        assert_eq!(markers.marker(&room_id).unwrap().event_id, "$3:a.org");
+    }
+
+    #[test]
+    fn test_new_post_count() {
+        let mut markers = FeedReadMarkers::default();
+        markers.mark_read(&owned_room_id!("!a:a.org"), marker("$2:a.org", 2));
+        let items = [
+            item("!a:a.org", 1),
+            item("!a:a.org", 2),
+            item("!a:a.org", 3),
+            // Nothing was read in this room yet
+            item("!b:a.org", 1),
+        ];
+        assert_eq!(markers.new_post_count(&items), 2);
+    }
+}
@@ -0,0 +1,518 @@
+//! Local spam detection for the aggregated newsfeed.
+//!
+//! Posts are scored on the device with a few simple heuristics, without
+//! sending anything to a server:
+//! - link-only posts from accounts that only recently joined the feed they
+//!   posted in (see [`SpamScorer::load_join_times()`]),
+//! - identical content posted to several feeds, and
+//! - posts that mention many users at once ("mention bombs").
+//!
+//! Depending on the user's [`SpamStrictness`], likely spam is moved to the
+//! end of the feed or hidden behind a "show anyway" interstitial.
+//! Posts and senders the user marks as not spam are never flagged again.
+//! Senders marked as not spam are saved with the newsfeed filter (see
+//! [`save_not_spam_sender()`]), and should be passed to new scorers with
+//! [`SpamScorer::with_not_spam_senders()`].
+
+use makepad_widgets::warning;
+use matrix_sdk::{
+    deserialized_responses::MemberEvent,
+    ruma::{
+        events::SyncStateEvent, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId,
+        RoomId, UserId,
+    },
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::feed_aggregator::FeedItem;
+use super::feed_filter::{load_feed_filter, save_feed_filter};
+use crate::social::post::PostContent;
+
+/// How long after joining the feed it posted in an account counts as new, in milliseconds.
+pub const NEW_ACCOUNT_PERIOD_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+/// The number of mentions in a single post that makes it a mention bomb.
+pub const MENTION_BOMB_THRESHOLD: usize = 5;
+/// Posts shorter than this many characters are never treated as repeated content,
+/// so that short replies like "congrats!" aren't flagged.
+pub const MIN_REPEATED_CONTENT_CHARS: usize = 20;
+
+/// How strictly likely spam is down-ranked or hidden.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpamStrictness {
+    /// Never down-rank or hide posts.
+    Off,
+    /// Only act on posts with several spam signals.
+    Lenient,
+    /// Down-rank posts with one strong signal, hide those with several.
+    #[default]
+    Balanced,
+    /// Act on any spam signal.
+    Strict,
+}
+
+impl SpamStrictness {
+    /// Get the minimum scores at which posts are down-ranked and hidden,
+    /// or `None` if spam detection is off.
+    fn thresholds(self) -> Option<(u32, u32)> {
+        match self {
+            Self::Off => None,
+            Self::Lenient => Some((60, 90)),
+            Self::Balanced => Some((40, 70)),
+            Self::Strict => Some((20, 40)),
+        }
+    }
+}
+
+/// A reason why a post looks like spam.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpamSignal {
+    /// The post is just a link, from an account that only recently joined the feed.
+    LinkOnlyFromNewAccount,
+    /// The same content was posted to several feeds.
+    RepeatedContent {
+        /// The number of feeds the content was posted to.
+        feeds: usize,
+    },
+    /// The post mentions many users.
+    MentionBomb {
+        /// The number of users mentioned.
+        mentions: usize,
+    },
+}
+
+impl SpamSignal {
+    /// Get how strongly this signal indicates spam.
+    fn weight(&self) -> u32 {
+        match self {
+            Self::LinkOnlyFromNewAccount => 40,
+            Self::RepeatedContent { feeds } => (*feeds as u32 - 1).saturating_mul(20).min(60),
+            Self::MentionBomb { mentions } => {
+                let extra = (mentions - MENTION_BOMB_THRESHOLD) as u32;
+                extra.saturating_mul(5).saturating_add(30).min(60)
+            }
+        }
+    }
+}
+
+impl fmt::Display for SpamSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LinkOnlyFromNewAccount => write!(f, "Link-only post from a new account"),
+            Self::RepeatedContent { feeds } => write!(f, "Same post shared to {feeds} feeds"),
+            Self::MentionBomb { mentions } => write!(f, "Mentions {mentions} people"),
+        }
+    }
+}
+
+/// What to do with a post based on its spam score.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpamVerdict {
+    /// Show the post normally.
+    #[default]
+    Show,
+    /// Move the post to the end of the feed.
+    DownRank,
+    /// Move the post to the end of the feed and hide it behind an interstitial.
+    Hide,
+}
+
+/// The spam score of a single post.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpamAssessment {
+    /// The combined weight of all signals.
+    pub score: u32,
+    /// The reasons why the post looks like spam.
+    pub signals: Vec<SpamSignal>,
+    /// What to do with the post.
+    pub verdict: SpamVerdict,
+}
+
+/// A feed item along with its spam score.
+#[derive(Clone, Debug)]
+pub struct ScoredFeedItem {
+    /// The feed item.
+    pub item: FeedItem,
+    /// The item's spam score.
+    pub spam: SpamAssessment,
+}
+
+/// Scores feed items for likely spam.
+///
+/// A scorer should be kept for as long as the feed is shown,
+/// since it remembers when each sender joined the feeds they posted in
+/// and which posts the user marked as not spam.
+#[derive(Clone, Debug, Default)]
+pub struct SpamScorer {
+    /// When each sender joined each feed they posted in,
+    /// from the timestamp of their membership event.
+    joined_at: HashMap<(OwnedRoomId, OwnedUserId), MilliSecondsSinceUnixEpoch>,
+    /// Posts the user marked as not spam.
+    not_spam_events: HashSet<OwnedEventId>,
+    /// Senders the user marked as not spamming.
+    not_spam_senders: HashSet<OwnedUserId>,
+}
+
+impl SpamScorer {
+    /// Create a new scorer that hasn't seen any posts yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Never flag posts from the given senders, e.g., the senders saved in
+    /// [`FeedFilterSettings::not_spam_senders`](super::FeedFilterSettings::not_spam_senders).
+    pub fn with_not_spam_senders(mut self, senders: impl IntoIterator<Item = OwnedUserId>) -> Self {
+        self.not_spam_senders.extend(senders);
+        self
+    }
+
+    /// Mark a post that was flagged as spam as a false positive.
+    ///
+    /// Neither the post nor any other post from its sender will be flagged again.
+    /// The sender should also be saved with [`save_not_spam_sender()`],
+    /// so that they aren't flagged after a restart.
+    pub fn mark_not_spam(&mut self, event_id: OwnedEventId, sender: OwnedUserId) {
+        self.not_spam_events.insert(event_id);
+        self.not_spam_senders.insert(sender);
+    }
+
+    /// Get the senders the user marked as not spamming, e.g., to save them.
+    pub fn not_spam_senders(&self) -> &HashSet<OwnedUserId> {
+        &self.not_spam_senders
+    }
+
+    /// Record when a sender joined a feed, from the timestamp of their membership event.
+    pub fn record_joined_at(
+        &mut self,
+        room_id: OwnedRoomId,
+        sender: OwnedUserId,
+        joined_at: MilliSecondsSinceUnixEpoch,
+    ) {
+        self.joined_at.insert((room_id, sender), joined_at);
+    }
+
+    /// Load when the senders of the given items joined the feeds they posted in,
+    /// from the membership events in the client's store.
+    ///
+    /// Senders whose membership event isn't known, e.g., because it was redacted,
+    /// aren't treated as new accounts.
+    pub async fn load_join_times(&mut self, client: &Client, items: &[FeedItem]) {
+        for item in items {
+            let key = (item.room_id.clone(), item.sender.clone());
+            if self.joined_at.contains_key(&key) {
+                continue;
+            }
+            let Some(room) = client.get_room(&item.room_id) else {
+                continue;
+            };
+            let member = match room.get_member_no_sync(&item.sender).await {
+                Ok(member) => member,
+                Err(e) => {
+                    warning!(
+                        "Failed to load the membership of {} in {}: {e}",
+                        item.sender,
+                        item.room_id
+                    );
+                    continue;
+                }
+            };
+            if let Some(MemberEvent::Sync(SyncStateEvent::Original(event))) =
+                member.as_ref().map(|member| &**member.event())
+            {
+                self.joined_at.insert(key, event.origin_server_ts);
+            }
+        }
+    }
+
+    /// Whether the sender joined the room within [`NEW_ACCOUNT_PERIOD_MS`] before `at`.
+    fn is_new_account(
+        &self,
+        room_id: &RoomId,
+        sender: &UserId,
+        at: MilliSecondsSinceUnixEpoch,
+    ) -> bool {
+        let Some(joined_at) = self.joined_at.get(&(room_id.to_owned(), sender.to_owned())) else {
+            return false;
+        };
+        let joined_at: u64 = joined_at.get().into();
+        let at: u64 = at.get().into();
+        at.saturating_sub(joined_at) < NEW_ACCOUNT_PERIOD_MS
+    }
+
+    /// Score a single item against the other items of the same feed.
+    fn assess(
+        &self,
+        item: &FeedItem,
+        repeated: &HashMap<String, HashSet<&RoomId>>,
+        strictness: SpamStrictness,
+    ) -> SpamAssessment {
+        let Some((down_rank_at, hide_at)) = strictness.thresholds() else {
+            return SpamAssessment::default();
+        };
+        if self.not_spam_events.contains(&item.event_id)
+            || self.not_spam_senders.contains(&item.sender)
+        {
+            return SpamAssessment::default();
+        }
+
+        let mut signals = Vec::new();
+        if is_link_only(&item.content)
+            && self.is_new_account(&item.room_id, &item.sender, item.origin_server_ts)
+        {
+            signals.push(SpamSignal::LinkOnlyFromNewAccount);
+        }
+        if let Some(feeds) = normalized_text(&item.content)
+            .and_then(|text| repeated.get(&text))
+            .map(HashSet::len)
+            .filter(|feeds| *feeds > 1)
+        {
+            signals.push(SpamSignal::RepeatedContent { feeds });
+        }
+        let mentions = mention_count(&item.content);
+        if mentions >= MENTION_BOMB_THRESHOLD {
+            signals.push(SpamSignal::MentionBomb { mentions });
+        }
+
+        let score = signals.iter().map(SpamSignal::weight).sum();
+        let verdict = if score >= hide_at {
+            SpamVerdict::Hide
+        } else if score >= down_rank_at {
+            SpamVerdict::DownRank
+        } else {
+            SpamVerdict::Show
+        };
+        SpamAssessment {
+            score,
+            signals,
+            verdict,
+        }
+    }
+
+    /// Score the items of a feed for spam and move likely spam to the end.
+    ///
+    /// The order of items is otherwise kept. Items with a
+    /// [`SpamVerdict::Hide`] verdict should be shown behind an interstitial.
+    /// Load the senders' join times with [`SpamScorer::load_join_times()`] first.
+    pub fn apply(&self, items: Vec<FeedItem>, strictness: SpamStrictness) -> Vec<ScoredFeedItem> {
+        let mut repeated: HashMap<String, HashSet<&RoomId>> = HashMap::new();
+        for item in &items {
+            if let Some(text) = normalized_text(&item.content) {
+                repeated.entry(text).or_default().insert(&item.room_id);
+            }
+        }
+        let assessments: Vec<_> = items
+            .iter()
+            .map(|item| self.assess(item, &repeated, strictness))
+            .collect();
+
+        let mut scored: Vec<_> = items
+            .into_iter()
+            .zip(assessments)
+            .map(|(item, spam)| ScoredFeedItem { item, spam })
+            .collect();
+        // A stable sort keeps the feed's order within shown and down-ranked items
+        scored.sort_by_key(|scored| scored.spam.verdict != SpamVerdict::Show);
+        scored
+    }
+}
+
+/// Save a sender the user marked as not spamming with the newsfeed filter,
+/// see [`SpamScorer::mark_not_spam()`].
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn save_not_spam_sender(
+    client: &Client,
+    sender: OwnedUserId,
+) -> Result<(), matrix_sdk::Error> {
+    let mut filter = load_feed_filter(client).await?;
+    if filter.not_spam_senders.insert(sender) {
+        save_feed_filter(client, &filter).await?;
+    }
+    Ok(())
+}
+
+/// Whether a post consists of nothing but a link.
+fn is_link_only(content: &PostContent) -> bool {
+    match content {
+        PostContent::Link { comment, .. } => comment.as_deref().is_none_or(|c| c.trim().is_empty()),
+        PostContent::Text { body, .. } => {
+            let body = body.trim();
+            !body.contains(char::is_whitespace)
+                && (body.starts_with("https://") || body.starts_with("http://"))
+        }
+        PostContent::Image { .. }
+        | PostContent::Video { .. }
+        | PostContent::Audio { .. }
+        | PostContent::Poll { .. } => false,
+    }
+}
+
+/// Get the text of a post for comparing it to other posts, ignoring case and whitespace,
+/// or `None` if it's too short to be compared.
+fn normalized_text(content: &PostContent) -> Option<String> {
+    let text = match content {
+        PostContent::Link { url, comment, .. } => {
+            format!("{} {}", comment.as_deref().unwrap_or_default(), url)
+        }
+        _ => content.text()?.to_string(),
+    };
+    let normalized = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    (normalized.chars().count() >= MIN_REPEATED_CONTENT_CHARS).then_some(normalized)
+}
+
+/// Count the users mentioned in a post, either explicitly or as `@user:server` in its text.
+fn mention_count(content: &PostContent) -> usize {
+    let in_text = content.text().map_or(0, |text| {
+        text.split_whitespace()
+            .filter(|word| UserId::parse(word.trim_end_matches([',', '.', '!', '?'])).is_ok())
+            .collect::<HashSet<_>>()
+            .len()
+    });
+    match content {
+        PostContent::Text { mentions, .. } => mentions.len().max(in_text),
+        _ => in_text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{OwnedRoomId, UInt};
+    use std::collections::BTreeSet;
+
+    const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+    fn make_item(room: &str, event: &str, sender: &str, day: u64, body: &str) -> FeedItem {
+        let room_id: OwnedRoomId = format!("!{room}:example.org").try_into().unwrap();
+        FeedItem::new(
+            room_id,
+            format!("${event}:example.org").try_into().unwrap(),
+            format!("@{sender}:example.org").try_into().unwrap(),
+            MilliSecondsSinceUnixEpoch(UInt::new(day * DAY_MS).unwrap()),
+            PostContent::Text {
+                body: body.to_string(),
+                formatted_body: None,
+                mentions: BTreeSet::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_link_only_post_from_new_account() {
+        let mut scorer = SpamScorer::new();
+        let established = make_item("a", "old", "alice", 1, "Hello everyone, glad to be here");
+        let new_link = make_item("b", "link", "bob", 30, "https://spam.example.com/offer");
+        let old_link = make_item("a", "link2", "alice", 30, "https://example.org/blog");
+        let at_day = |day: u64| MilliSecondsSinceUnixEpoch(UInt::new(day * DAY_MS).unwrap());
+        scorer.record_joined_at(old_link.room_id.clone(), old_link.sender.clone(), at_day(0));
+        scorer.record_joined_at(
+            new_link.room_id.clone(),
+            new_link.sender.clone(),
+            at_day(29),
+        );
+
+        let scored = scorer.apply(
+            vec![established, new_link, old_link],
+            SpamStrictness::Balanced,
+        );
+        let bob = scored
+            .iter()
+            .find(|s| s.item.sender.localpart() == "bob")
+            .unwrap();
+        assert_eq!(bob.spam.signals, vec![SpamSignal::LinkOnlyFromNewAccount]);
+        assert_eq!(bob.spam.verdict, SpamVerdict::DownRank);
+        assert!(
+            scored
+                .iter()
+                .any(|s| s.item.event_id.as_str() == "$link2:example.org"
+                    && s.spam.signals.is_empty())
+        );
+        // Down-ranked posts are moved to the end
+        assert_eq!(scored.last().unwrap().item.sender.localpart(), "bob");
+    }
+
+    #[test]
+    fn test_repeated_content_across_feeds() {
+        let body = "Buy cheap followers now, limited time only!!";
+        let items = vec![
+            make_item("a", "1", "carol", 1, body),
+            make_item("b", "2", "carol", 1, &body.to_uppercase()),
+            make_item("c", "3", "carol", 1, body),
+            make_item("c", "4", "dave", 1, "A normal post about gardening"),
+        ];
+        let scored = SpamScorer::new().apply(items, SpamStrictness::Balanced);
+        assert_eq!(scored[0].item.sender.localpart(), "dave");
+        for spam in &scored[1..] {
+            assert_eq!(
+                spam.spam.signals,
+                vec![SpamSignal::RepeatedContent { feeds: 3 }]
+            );
+            assert_eq!(spam.spam.verdict, SpamVerdict::DownRank);
+        }
+    }
+
+    #[test]
+    fn test_mention_bomb_and_strictness() {
+        let body = "@a:x.org @b:x.org @c:x.org @d:x.org @e:x.org @f:x.org @g:x.org check this out";
+        let item = make_item("a", "1", "eve", 1, body);
+
+        let scored = SpamScorer::new().apply(vec![item.clone()], SpamStrictness::Balanced);
+        assert_eq!(
+            scored[0].spam.signals,
+            vec![SpamSignal::MentionBomb { mentions: 7 }]
+        );
+        assert_eq!(scored[0].spam.verdict, SpamVerdict::DownRank);
+
+        let scored = SpamScorer::new().apply(vec![item.clone()], SpamStrictness::Strict);
+        assert_eq!(scored[0].spam.verdict, SpamVerdict::Hide);
+
+        let scored = SpamScorer::new().apply(vec![item.clone()], SpamStrictness::Lenient);
+        assert_eq!(scored[0].spam.verdict, SpamVerdict::Show);
+
+        let scored = SpamScorer::new().apply(vec![item], SpamStrictness::Off);
+        assert!(scored[0].spam.signals.is_empty());
+    }
+
+    #[test]
+    fn test_link_only_post_from_unknown_member() {
+        let item = make_item("a", "1", "grace", 1, "https://example.org/my-new-project");
+        let scored = SpamScorer::new().apply(vec![item], SpamStrictness::Strict);
+        assert!(scored[0].spam.signals.is_empty());
+    }
+
+    #[test]
+    fn test_marking_not_spam() {
+        let mut scorer = SpamScorer::new();
+        let item = make_item("a", "1", "frank", 1, "https://example.org/my-new-project");
+        scorer.record_joined_at(
+            item.room_id.clone(),
+            item.sender.clone(),
+            item.origin_server_ts,
+        );
+        let scored = scorer.apply(vec![item.clone()], SpamStrictness::Strict);
+        assert_eq!(scored[0].spam.verdict, SpamVerdict::Hide);
+
+        scorer.mark_not_spam(item.event_id.clone(), item.sender.clone());
+        let other = make_item("a", "2", "frank", 2, "https://example.org/another-one");
+        let scored = scorer.apply(vec![item.clone(), other.clone()], SpamStrictness::Strict);
+        assert!(scored.iter().all(|s| s.spam.verdict == SpamVerdict::Show));
+
+        // Saved senders aren't flagged by new scorers either
+        let mut scorer = SpamScorer::new().with_not_spam_senders([item.sender.clone()]);
+        scorer.record_joined_at(
+            other.room_id.clone(),
+            other.sender.clone(),
+            item.origin_server_ts,
+        );
+        let scored = scorer.apply(vec![other], SpamStrictness::Strict);
+        assert_eq!(scored[0].spam.verdict, SpamVerdict::Show);
+    }
+}
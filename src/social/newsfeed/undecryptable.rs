@@ -0,0 +1,128 @@
+//! Placeholders for posts in encrypted feeds that couldn't be decrypted.
+//!
+//! Friends and close friends feeds can be end-to-end encrypted (see
+//! [`FeedRoomService::create_feed_room()`](crate::social::feed_room::FeedRoomService::create_feed_room)).
+//! Their posts are decrypted by the SDK as they're fetched, and are then
+//! turned into feed items like any other post. Posts whose keys haven't
+//! arrived yet are turned into placeholder feed items by
+//! [`placeholder_feed_item()`], shown as a "Waiting for this post" card
+//! until a later refresh decrypts them.
+
+use matrix_sdk::{
+    deserialized_responses::{TimelineEvent, TimelineEventKind},
+    ruma::{
+        events::AnySyncTimelineEvent, serde::Raw, MilliSecondsSinceUnixEpoch, OwnedEventId,
+        OwnedRoomId, OwnedUserId,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::feed_aggregator::FeedItem;
+use super::unsupported::unsupported_feed_item;
+
+/// A post that couldn't be decrypted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndecryptablePost {
+    /// The ID of the Megolm session the post was encrypted with, if known.
+    pub session_id: Option<String>,
+    /// Why the post couldn't be decrypted, for developers.
+    pub reason: String,
+}
+
+impl fmt::Display for UndecryptablePost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.session_id {
+            Some(session_id) => write!(f, "{} (session {session_id})", self.reason),
+            None => write!(f, "{}", self.reason),
+        }
+    }
+}
+
+/// Turn a timeline event fetched from a feed room that can't be shown into
+/// a placeholder feed item.
+///
+/// Events that couldn't be decrypted become [`UndecryptablePost`] placeholders,
+/// and decrypted or plain events that can't be deserialized become unsupported
+/// posts (see [`unsupported_feed_item()`]). Returns `None` for events that can
+/// be shown or aren't posts.
+pub fn placeholder_feed_item(room_id: OwnedRoomId, event: &TimelineEvent) -> Option<FeedItem> {
+    match &event.kind {
+        TimelineEventKind::UnableToDecrypt {
+            event: raw,
+            utd_info,
+        } => undecryptable_feed_item(
+            room_id,
+            raw,
+            UndecryptablePost {
+                session_id: utd_info.session_id.clone(),
+                reason: format!("{:?}", utd_info.reason),
+            },
+        ),
+        _ => unsupported_feed_item(room_id, event.raw()),
+    }
+}
+
+/// Turn an encrypted timeline event into a placeholder feed item.
+///
+/// Returns `None` if the event lacks the fields every event has, e.g., its sender.
+pub fn undecryptable_feed_item(
+    room_id: OwnedRoomId,
+    raw: &Raw<AnySyncTimelineEvent>,
+    undecryptable: UndecryptablePost,
+) -> Option<FeedItem> {
+    let event_id = raw.get_field::<OwnedEventId>("event_id").ok().flatten()?;
+    let sender = raw.get_field::<OwnedUserId>("sender").ok().flatten()?;
+    let origin_server_ts = raw
+        .get_field::<MilliSecondsSinceUnixEpoch>("origin_server_ts")
+        .ok()
+        .flatten()?;
+    Some(FeedItem::undecryptable(
+        room_id,
+        event_id,
+        sender,
+        origin_server_ts,
+        undecryptable,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::owned_room_id;
+    use serde_json::json;
+
+    #[test]
+    fn test_undecryptable_feed_item() {
+        let raw: Raw<AnySyncTimelineEvent> = serde_json::from_value(json!({
+            "type": "m.room.encrypted",
+            "event_id": "$post:example.org",
+            "sender": "@friend:example.org",
+            "origin_server_ts": 1,
+            "content": {
+                "algorithm": "m.megolm.v1.aes-sha2",
+                "ciphertext": "AwgA",
+                "session_id": "session",
+            },
+        }))
+        .unwrap();
+        let undecryptable = UndecryptablePost {
+            session_id: Some("session".to_string()),
+            reason: "MissingMegolmSession".to_string(),
+        };
+        let item = undecryptable_feed_item(
+            owned_room_id!("!feed:example.org"),
+            &raw,
+            undecryptable.clone(),
+        )
+        .unwrap();
+        assert_eq!(item.event_id, "$post:example.org");
+        assert_eq!(item.sender, "@friend:example.org");
+        assert_eq!(item.undecryptable, Some(undecryptable));
+        assert_eq!(item.unsupported, None);
+        assert_eq!(
+            item.undecryptable.unwrap().to_string(),
+            "MissingMegolmSession (session session)"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Placeholders for feed posts this version of Robrix can't show.
+//!
+//! Social events are strict about their content, so a post sent by a newer
+//! client, e.g., with a field added to its event type, or of a post type
+//! added since, fails to deserialize here. Rather than dropping such posts
+//! from the newsfeed, [`unsupported_feed_item()`] turns them into feed items
+//! shown as an "Unsupported post" card, so users know there's something
+//! they're missing. Posts declaring a schema version newer than this client
+//! supports (see [`SCHEMA_VERSION_FIELD`]) are unsupported too, and tracked
+//! by the [`CapabilityTracker`](super::CapabilityTracker).
+
+use matrix_sdk::ruma::{
+    events::{
+        poll::unstable_start::SyncUnstablePollStartEvent, room::message::SyncRoomMessageEvent,
+        AnySyncTimelineEvent, SyncMessageLikeEvent,
+    },
+    serde::Raw,
+    MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId,
+};
+use robrix_social_events::{
+    checkin::SocialCheckinEventContent,
+    co_author::SocialCoAuthorResponseEventContent,
+    version::{SCHEMA_VERSION, SCHEMA_VERSION_FIELD},
+};
+use serde::{Deserialize, Serialize};
+
+use super::feed_aggregator::FeedItem;
+use crate::social::poll::POLL_START_EVENT_TYPE;
+
+/// The prefix of the types of all social events.
+pub const SOCIAL_EVENT_TYPE_PREFIX: &str = "org.social.";
+
+/// A post that couldn't be deserialized.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsupportedPost {
+    /// The raw type of the event, e.g., `org.social.poll`.
+    pub event_type: String,
+    /// Why the event couldn't be shown, for developers.
+    pub reason: String,
+    /// The schema version the event was sent with,
+    /// if it's newer than the one this client supports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+}
+
+impl UnsupportedPost {
+    /// Check whether the post could be shown by updating Robrix.
+    pub fn needs_newer_client(&self) -> bool {
+        self.schema_version.is_some()
+    }
+}
+
+/// Check whether a timeline event in a feed room is a post that can't be shown.
+///
+/// Room messages, poll posts and social events that fail to deserialize are unsupported,
+/// as are social events of unknown types and posts of newer schema versions.
+/// State events and other kinds of events, e.g., reactions, aren't posts,
+/// so `None` is returned for them.
+pub fn unsupported_post(raw: &Raw<AnySyncTimelineEvent>) -> Option<UnsupportedPost> {
+    let event_type = raw.get_field::<String>("type").ok().flatten()?;
+    if matches!(raw.get_field::<String>("state_key"), Ok(Some(_))) {
+        return None;
+    }
+
+    if let Some(schema_version) = schema_version(raw) {
+        if schema_version > SCHEMA_VERSION
+            && (event_type == "m.room.message"
+                || event_type == POLL_START_EVENT_TYPE
+                || event_type.starts_with(SOCIAL_EVENT_TYPE_PREFIX))
+        {
+            return Some(UnsupportedPost {
+                event_type,
+                reason: format!(
+                    "Schema version {schema_version} is newer than supported version {SCHEMA_VERSION}"
+                ),
+                schema_version: Some(schema_version),
+            });
+        }
+    }
+
+    let result = match event_type.as_str() {
+        "m.room.message" => raw.deserialize_as::<SyncRoomMessageEvent>().map(drop),
+        POLL_START_EVENT_TYPE => raw.deserialize_as::<SyncUnstablePollStartEvent>().map(drop),
+        "org.social.checkin" => raw
+            .deserialize_as::<SyncMessageLikeEvent<SocialCheckinEventContent>>()
+            .map(drop),
+        "org.social.co_author_response" => raw
+            .deserialize_as::<SyncMessageLikeEvent<SocialCoAuthorResponseEventContent>>()
+            .map(drop),
+        social_type if social_type.starts_with(SOCIAL_EVENT_TYPE_PREFIX) => {
+            return Some(UnsupportedPost {
+                event_type,
+                reason: "Unknown event type".to_string(),
+                schema_version: None,
+            });
+        }
+        _ => return None,
+    };
+    result.err().map(|e| UnsupportedPost {
+        event_type,
+        reason: e.to_string(),
+        schema_version: None,
+    })
+}
+
+/// Get the schema version a timeline event's content declares, if any
+/// (see [`SCHEMA_VERSION_FIELD`]).
+pub fn schema_version(raw: &Raw<AnySyncTimelineEvent>) -> Option<u32> {
+    let content = raw
+        .get_field::<serde_json::Value>("content")
+        .ok()
+        .flatten()?;
+    let version = content.get(SCHEMA_VERSION_FIELD)?.as_u64()?;
+    version.try_into().ok()
+}
+
+/// Turn a timeline event in a feed room that can't be shown into a placeholder feed item.
+///
+/// Returns `None` if the event can be shown or isn't a post (see [`unsupported_post()`]),
+/// or if it lacks the fields every event has, e.g., its sender.
+pub fn unsupported_feed_item(
+    room_id: OwnedRoomId,
+    raw: &Raw<AnySyncTimelineEvent>,
+) -> Option<FeedItem> {
+    let unsupported = unsupported_post(raw)?;
+    let event_id = raw.get_field::<OwnedEventId>("event_id").ok().flatten()?;
+    let sender = raw.get_field::<OwnedUserId>("sender").ok().flatten()?;
+    let origin_server_ts = raw
+        .get_field::<MilliSecondsSinceUnixEpoch>("origin_server_ts")
+        .ok()
+        .flatten()?;
+    Some(FeedItem::unsupported(
+        room_id,
+        event_id,
+        sender,
+        origin_server_ts,
+        unsupported,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::owned_room_id;
+    use serde_json::json;
+
+    fn event(event_type: &str, content: serde_json::Value) -> Raw<AnySyncTimelineEvent> {
+        serde_json::from_value(json!({
+            "type": event_type,
+            "event_id": "$post:example.org",
+            "sender": "@user:example.org",
+            "origin_server_ts": 1,
+            "content": content,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_supported_events_are_not_placeholders() {
+        let message = event(
+            "m.room.message",
+            json!({ "msgtype": "m.text", "body": "Hi" }),
+        );
+        assert_eq!(unsupported_post(&message), None);
+        let checkin = event("org.social.checkin", json!({ "message": "Here!" }));
+        assert_eq!(unsupported_post(&checkin), None);
+        let reaction = event(
+            "m.reaction",
+            json!({ "m.relates_to": { "rel_type": "m.annotation" } }),
+        );
+        assert_eq!(unsupported_post(&reaction), None);
+    }
+
+    #[test]
+    fn test_unsupported_posts() {
+        // A field added by a newer client
+        let checkin = event(
+            "org.social.checkin",
+            json!({ "message": "Here!", "mood": "happy" }),
+        );
+        assert_eq!(
+            unsupported_post(&checkin).unwrap().event_type,
+            "org.social.checkin"
+        );
+
+        let malformed = event("m.room.message", json!({ "msgtype": 5 }));
+        assert!(unsupported_post(&malformed).is_some());
+        let malformed = event(POLL_START_EVENT_TYPE, json!({ "question": "Pizza?" }));
+        assert!(unsupported_post(&malformed).is_some());
+
+        let poll = event("org.social.poll", json!({ "question": "Pizza?" }));
+        let item = unsupported_feed_item(owned_room_id!("!feed:example.org"), &poll).unwrap();
+        assert_eq!(item.event_id, "$post:example.org");
+        assert_eq!(
+            item.unsupported,
+            Some(UnsupportedPost {
+                event_type: "org.social.poll".to_string(),
+                reason: "Unknown event type".to_string(),
+                schema_version: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_newer_schema_versions_are_unsupported() {
+        let message = event(
+            "m.room.message",
+            json!({ "msgtype": "m.text", "body": "Hi", "org.social.version": 2 }),
+        );
+        let unsupported = unsupported_post(&message).unwrap();
+        assert_eq!(unsupported.schema_version, Some(2));
+        assert!(unsupported.needs_newer_client());
+
+        let current = event(
+            "m.room.message",
+            json!({ "msgtype": "m.text", "body": "Hi", "org.social.version": SCHEMA_VERSION }),
+        );
+        assert_eq!(schema_version(&current), Some(SCHEMA_VERSION));
+        assert_eq!(unsupported_post(&current), None);
+    }
+}
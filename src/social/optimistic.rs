@@ -0,0 +1,469 @@
+//! Optimistic updates of the newsfeed while the user's changes are being sent.
+//!
+//! When the user likes or reacts to a post, or sends a post, the newsfeed
+//! shows the change right away, marked as pending, instead of waiting for
+//! the server. Each change gets a transaction ID it must be sent with. Once
+//! the server echoes an event with that transaction ID back via sync,
+//! [`spawn_optimistic_echo_watcher()`] posts an [`OptimisticAction::Confirmed`]
+//! and the pending marker is cleared (see [`OptimisticUpdates::confirm()`]).
+//! If sending fails, the sender should post an [`OptimisticAction::Failed`],
+//! and the change is rolled back (see [`OptimisticUpdates::roll_back()`]).
+//! Posts sent through the [`Outbox`](crate::social::Outbox) are retried
+//! instead, and only marked as failed once the outbox gives up on them
+//! (see [`OptimisticUpdates::mark_post_failed()`]).
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    ruma::{
+        events::{
+            reaction::OriginalSyncReactionEvent,
+            room::{
+                message::OriginalSyncRoomMessageEvent, redaction::OriginalSyncRoomRedactionEvent,
+            },
+        },
+        EventId, OwnedEventId, OwnedRoomId, OwnedTransactionId, TransactionId, UserId,
+    },
+    Client,
+};
+use std::collections::HashMap;
+use tokio::runtime::Handle;
+
+use crate::social::reactions::common_emojis;
+use crate::social::widgets::post_card::PostCardData;
+
+/// The reaction a like is sent as.
+pub const LIKE_REACTION: &str = common_emojis::LOVE;
+
+/// A change the user made that the server hasn't confirmed yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PendingChange {
+    /// The user added or removed a reaction to a post. Likes are [`LIKE_REACTION`]s.
+    Reaction {
+        /// The post reacted to.
+        post_id: OwnedEventId,
+        /// The reaction emoji.
+        emoji: String,
+        /// Whether the reaction was added, or removed.
+        added: bool,
+    },
+    /// The user sent a post, shown under a local event ID until its echo arrives.
+    Post {
+        /// The feed room the post is sent to.
+        room_id: OwnedRoomId,
+        /// The local event ID the post is shown under.
+        local_id: OwnedEventId,
+    },
+}
+
+/// Actions emitted about the user's pending changes.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum OptimisticAction {
+    /// The server echoed the event sent with the given transaction ID back.
+    Confirmed {
+        transaction_id: OwnedTransactionId,
+        event_id: OwnedEventId,
+    },
+    /// The event with the given transaction ID could not be sent.
+    Failed {
+        transaction_id: OwnedTransactionId,
+        error: String,
+    },
+    /// No action.
+    None,
+}
+
+/// The user's changes to the posts in a feed that the server hasn't confirmed yet,
+/// keyed by the transaction ID they're sent with.
+#[derive(Clone, Debug, Default)]
+pub struct OptimisticUpdates {
+    pending: HashMap<OwnedTransactionId, PendingChange>,
+}
+
+impl OptimisticUpdates {
+    /// Add or remove the user's reaction to a post at once, marking the post as pending.
+    ///
+    /// Returns the transaction ID the reaction (or its redaction) must be sent with,
+    /// and whether the reaction was added.
+    pub fn toggle_reaction(
+        &mut self,
+        post: &mut PostCardData,
+        emoji: &str,
+        own_user_id: &UserId,
+    ) -> (OwnedTransactionId, bool) {
+        let transaction_id = TransactionId::new();
+        let added = toggle_own_reaction(post, emoji, own_user_id, &transaction_id);
+        post.is_pending = true;
+        self.pending.insert(
+            transaction_id.clone(),
+            PendingChange::Reaction {
+                post_id: post.event_id.clone(),
+                emoji: emoji.to_string(),
+                added,
+            },
+        );
+        (transaction_id, added)
+    }
+
+    /// Start tracking a post the user is sending, to be shown at once.
+    ///
+    /// The post is given a local event ID and marked as pending.
+    /// Returns the transaction ID the post must be sent with.
+    pub fn add_post(&mut self, post: &mut PostCardData) -> OwnedTransactionId {
+        let transaction_id = TransactionId::new();
+        post.event_id = local_event_id(&transaction_id);
+        post.is_pending = true;
+        self.pending.insert(
+            transaction_id.clone(),
+            PendingChange::Post {
+                room_id: post.room_id.clone(),
+                local_id: post.event_id.clone(),
+            },
+        );
+        transaction_id
+    }
+
+    /// Check whether any change to a post is still pending.
+    pub fn is_pending(&self, event_id: &EventId) -> bool {
+        self.pending.values().any(|change| match change {
+            PendingChange::Reaction { post_id, .. } => post_id == event_id,
+            PendingChange::Post { local_id, .. } => local_id == event_id,
+        })
+    }
+
+    /// Get the number of pending changes.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check whether no changes are pending.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Confirm a change once the server echoed it back as the given event.
+    ///
+    /// A sent post takes the ID of its event, and posts without other
+    /// pending changes lose their pending marker. Returns the confirmed
+    /// change, or `None` if the transaction isn't pending, e.g., because it
+    /// was sent from another feed view.
+    pub fn confirm(
+        &mut self,
+        transaction_id: &TransactionId,
+        event_id: &EventId,
+        posts: &mut [PostCardData],
+    ) -> Option<PendingChange> {
+        let change = self.pending.remove(transaction_id)?;
+        if let PendingChange::Post { local_id, .. } = &change {
+            if let Some(post) = posts.iter_mut().find(|post| &post.event_id == local_id) {
+                post.event_id = event_id.to_owned();
+                post.send_failed = false;
+            }
+        }
+        self.update_pending_markers(posts);
+        Some(change)
+    }
+
+    /// Roll a change back after it failed to send.
+    ///
+    /// A reaction is toggled back, and a sent post is removed.
+    /// Returns the rolled back change, or `None` if the transaction isn't pending.
+    pub fn roll_back(
+        &mut self,
+        transaction_id: &TransactionId,
+        posts: &mut Vec<PostCardData>,
+        own_user_id: &UserId,
+    ) -> Option<PendingChange> {
+        let change = self.pending.remove(transaction_id)?;
+        match &change {
+            PendingChange::Reaction { post_id, emoji, .. } => {
+                if let Some(post) = posts.iter_mut().find(|post| &post.event_id == post_id) {
+                    toggle_own_reaction(post, emoji, own_user_id, transaction_id);
+                }
+            }
+            PendingChange::Post { local_id, .. } => {
+                posts.retain(|post| &post.event_id != local_id);
+            }
+        }
+        self.update_pending_markers(posts);
+        Some(change)
+    }
+
+    /// Mark a post the outbox gave up sending as failed, so that the user can retry it.
+    ///
+    /// The post stays pending, to be confirmed once it's finally sent.
+    /// Returns `false` if the transaction isn't a pending post.
+    pub fn mark_post_failed(
+        &self,
+        transaction_id: &TransactionId,
+        posts: &mut [PostCardData],
+    ) -> bool {
+        let Some(PendingChange::Post { local_id, .. }) = self.pending.get(transaction_id) else {
+            return false;
+        };
+        let Some(post) = posts.iter_mut().find(|post| &post.event_id == local_id) else {
+            return false;
+        };
+        post.send_failed = true;
+        true
+    }
+
+    /// Clear the failed marker of a post the user retries sending.
+    ///
+    /// Returns the transaction ID the post is sent with,
+    /// or `None` if the post isn't a pending post.
+    pub fn retry_post(
+        &self,
+        local_id: &EventId,
+        posts: &mut [PostCardData],
+    ) -> Option<OwnedTransactionId> {
+        let transaction_id = self.pending.iter().find_map(|(transaction_id, change)| {
+            matches!(change, PendingChange::Post { local_id: id, .. } if id == local_id)
+                .then(|| transaction_id.clone())
+        })?;
+        if let Some(post) = posts.iter_mut().find(|post| post.event_id == local_id) {
+            post.send_failed = false;
+        }
+        Some(transaction_id)
+    }
+
+    /// Mark exactly the posts with pending changes as pending.
+    fn update_pending_markers(&self, posts: &mut [PostCardData]) {
+        for post in posts {
+            post.is_pending = self.is_pending(&post.event_id);
+        }
+    }
+}
+
+/// Get the local event ID a post sent with the given transaction ID is shown under.
+pub fn local_event_id(transaction_id: &TransactionId) -> OwnedEventId {
+    EventId::parse(format!("$local-{transaction_id}"))
+        .expect("transaction IDs are valid in event IDs")
+}
+
+/// Add the user's reaction to a post if they haven't reacted with this emoji yet,
+/// or remove it, updating whether the post is liked.
+///
+/// Returns whether the reaction was added.
+fn toggle_own_reaction(
+    post: &mut PostCardData,
+    emoji: &str,
+    own_user_id: &UserId,
+    transaction_id: &TransactionId,
+) -> bool {
+    let own_user_id = own_user_id.to_owned();
+    let added = !post.reactions.has_user_reacted(emoji, &own_user_id);
+    if added {
+        post.reactions
+            .add_reaction(emoji, own_user_id, local_event_id(transaction_id));
+    } else {
+        post.reactions.remove_reaction(emoji, &own_user_id);
+    }
+    if emoji == LIKE_REACTION {
+        post.is_liked = added;
+    }
+    added
+}
+
+/// Listen for the server's echoes of the events the user sends.
+///
+/// Must be called from within the Tokio runtime after logging in.
+/// An [`OptimisticAction::Confirmed`] action is posted for every message,
+/// reaction or redaction the user sent with a transaction ID.
+pub fn spawn_optimistic_echo_watcher(client: Client) {
+    let Some(user_id) = client.user_id().map(ToOwned::to_owned) else {
+        return;
+    };
+    Handle::current().spawn(async move {
+        let own_user_id = user_id.clone();
+        client.add_event_handler(move |message: OriginalSyncRoomMessageEvent| {
+            let own_user_id = own_user_id.clone();
+            async move {
+                if message.sender == own_user_id {
+                    post_confirmed(message.unsigned.transaction_id, message.event_id);
+                }
+            }
+        });
+        let own_user_id = user_id.clone();
+        client.add_event_handler(move |reaction: OriginalSyncReactionEvent| {
+            let own_user_id = own_user_id.clone();
+            async move {
+                if reaction.sender == own_user_id {
+                    post_confirmed(reaction.unsigned.transaction_id, reaction.event_id);
+                }
+            }
+        });
+        client.add_event_handler(move |redaction: OriginalSyncRoomRedactionEvent| {
+            let own_user_id = user_id.clone();
+            async move {
+                if redaction.sender == own_user_id {
+                    post_confirmed(redaction.unsigned.transaction_id, redaction.event_id);
+                }
+            }
+        });
+    });
+}
+
+/// Post an [`OptimisticAction::Confirmed`] for an echoed event, if it was sent with a transaction ID.
+fn post_confirmed(transaction_id: Option<OwnedTransactionId>, event_id: OwnedEventId) {
+    if let Some(transaction_id) = transaction_id {
+        Cx::post_action(OptimisticAction::Confirmed {
+            transaction_id,
+            event_id,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::reactions::ReactionSummary;
+    use matrix_sdk::ruma::{owned_event_id, owned_room_id, owned_user_id, user_id};
+    use robrix_social_events::feed_config::SocialFeedConfigEventContent;
+
+    fn post(event_id: OwnedEventId) -> PostCardData {
+        PostCardData {
+            event_id,
+            room_id: owned_room_id!("!feed:example.org"),
+            author_id: owned_user_id!("@friend:example.org"),
+            author_name: None,
+            author_avatar_url: None,
+            co_author: None,
+            co_author_avatar_url: None,
+            timestamp: matrix_sdk::ruma::MilliSecondsSinceUnixEpoch::now(),
+            text: "Hello".to_string(),
+            formatted_text: None,
+            is_edited: false,
+            media_url: None,
+            media_alt_text: None,
+            audio: None,
+            poll: None,
+            link_preview: None,
+            reactions: ReactionSummary::new(),
+            feed_config: SocialFeedConfigEventContent::default(),
+            license: None,
+            location: None,
+            comment_count: 0,
+            share_count: 0,
+            is_liked: false,
+            is_bookmarked: false,
+            is_watched: false,
+            is_own_post: false,
+            language: None,
+            hidden_as_spam: Vec::new(),
+            unsupported: None,
+            undecryptable: None,
+            media_violation: None,
+            is_pending: false,
+            send_failed: false,
+            moderation: Default::default(),
+            is_pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_like_is_confirmed() {
+        let own_user_id = user_id!("@me:example.org");
+        let mut updates = OptimisticUpdates::default();
+        let mut posts = vec![post(owned_event_id!("$post:example.org"))];
+
+        let (transaction_id, added) =
+            updates.toggle_reaction(&mut posts[0], LIKE_REACTION, own_user_id);
+        assert!(added);
+        assert!(posts[0].is_liked && posts[0].is_pending);
+        assert_eq!(posts[0].reactions.count(LIKE_REACTION), 1);
+
+        let confirmed = updates.confirm(
+            &transaction_id,
+            &owned_event_id!("$reaction:example.org"),
+            &mut posts,
+        );
+        assert!(matches!(
+            confirmed,
+            Some(PendingChange::Reaction { added: true, .. })
+        ));
+        assert!(posts[0].is_liked && !posts[0].is_pending);
+        assert!(updates.is_empty());
+    }
+
+    #[test]
+    fn test_failed_reaction_is_rolled_back() {
+        let own_user_id = user_id!("@me:example.org");
+        let mut updates = OptimisticUpdates::default();
+        let mut posts = vec![post(owned_event_id!("$post:example.org"))];
+
+        let (first, _) = updates.toggle_reaction(&mut posts[0], "🎉", own_user_id);
+        let (second, added) = updates.toggle_reaction(&mut posts[0], "👍", own_user_id);
+        assert!(added);
+        assert_eq!(posts[0].reactions.total(), 2);
+
+        updates.roll_back(&second, &mut posts, own_user_id).unwrap();
+        assert_eq!(posts[0].reactions.count("👍"), 0);
+        assert_eq!(posts[0].reactions.count("🎉"), 1);
+        // The other reaction is still pending
+        assert!(posts[0].is_pending);
+
+        assert!(
+            updates
+                .roll_back(&second, &mut posts, own_user_id)
+                .is_none()
+        );
+        updates.roll_back(&first, &mut posts, own_user_id).unwrap();
+        assert!(posts[0].reactions.is_empty() && !posts[0].is_pending);
+    }
+
+    #[test]
+    fn test_sent_post() {
+        let own_user_id = user_id!("@me:example.org");
+        let mut updates = OptimisticUpdates::default();
+
+        let mut sent = post(owned_event_id!("$unsent:example.org"));
+        let transaction_id = updates.add_post(&mut sent);
+        assert_eq!(sent.event_id, local_event_id(&transaction_id));
+        assert!(sent.is_pending);
+        let mut posts = vec![sent];
+        updates.confirm(
+            &transaction_id,
+            &owned_event_id!("$sent:example.org"),
+            &mut posts,
+        );
+        assert_eq!(posts[0].event_id, "$sent:example.org");
+        assert!(!posts[0].is_pending);
+
+        let mut failed = post(owned_event_id!("$unsent:example.org"));
+        let transaction_id = updates.add_post(&mut failed);
+        posts.insert(0, failed);
+        updates.roll_back(&transaction_id, &mut posts, own_user_id);
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].event_id, "$sent:example.org");
+    }
+
+    #[test]
+    fn test_failed_post_is_retried() {
+        let mut updates = OptimisticUpdates::default();
+        let mut sent = post(owned_event_id!("$unsent:example.org"));
+        let transaction_id = updates.add_post(&mut sent);
+        let local_id = sent.event_id.clone();
+        let mut posts = vec![sent];
+
+        assert!(updates.mark_post_failed(&transaction_id, &mut posts));
+        assert!(posts[0].send_failed && posts[0].is_pending);
+
+        assert_eq!(
+            updates.retry_post(&local_id, &mut posts),
+            Some(transaction_id.clone())
+        );
+        assert!(!posts[0].send_failed);
+        assert_eq!(
+            updates.retry_post(&owned_event_id!("$post:example.org"), &mut posts),
+            None
+        );
+
+        updates.mark_post_failed(&transaction_id, &mut posts);
+        updates.confirm(
+            &transaction_id,
+            &owned_event_id!("$sent:example.org"),
+            &mut posts,
+        );
+        assert!(!posts[0].send_failed && !posts[0].is_pending);
+    }
+}
@@ -0,0 +1,1008 @@
+//! A persistent outbox of posts waiting to be sent.
+//!
+//! Posts aren't sent directly, but queued in an outbox kept in the user's
+//! persistent state directory, together with the local paths of their media.
+//! [`spawn_outbox_worker()`] sends the queued posts, uploading their media
+//! first, and retries the ones that fail with exponential backoff (see
+//! [`retry_delay_ms()`]), so posts made while the network is down are sent
+//! once it's back, even after a restart. After [`MAX_AUTOMATIC_ATTEMPTS`]
+//! failed attempts, a post is only retried when the user asks to, and an
+//! [`OutboxAction::Failed`] is posted so that its post card can offer to.
+//!
+//! Each post is sent with the transaction ID it was queued with, so that
+//! its echo confirms the post shown while it's being sent (see
+//! [`OptimisticUpdates`](crate::social::OptimisticUpdates)).
+//!
+//! Media sent to an encrypted feed is encrypted before it's uploaded,
+//! and attached to the post as an encrypted `file` rather than a `url`.
+//! Photos are remembered once they're posted, so that the composer can warn
+//! before the same photo is posted again (see [`duplicate_media`](crate::social::duplicate_media)).
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    ruma::{
+        events::room::{EncryptedFile, MediaSource},
+        MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedMxcUri, OwnedRoomId, OwnedTransactionId,
+        RoomId, TransactionId, UserId,
+    },
+    Client,
+};
+use mime::Mime;
+use robrix_social_events::{
+    caption::{Caption, CAPTION_FIELD},
+    feed_config::MediaKind,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+use tokio::{runtime::Handle, sync::Notify};
+
+use crate::persistence::persistent_state_dir;
+use crate::social::{
+    audio_clip::analyze_wav,
+    duplicate_media::{record_upload, DuplicateImageError, ImageHash, UploadedImage},
+    optimistic::OptimisticAction,
+    post::{post_event_type, Post, PostContent, PostError},
+};
+
+/// The name of the file, in the user's persistent state directory, holding the outbox.
+const OUTBOX_FILE_NAME: &str = "social_outbox.json";
+
+/// How long to wait before retrying a post the first time, in milliseconds.
+pub const RETRY_BASE_DELAY_MS: u64 = 2_000;
+/// The longest time to wait before retrying a post, in milliseconds.
+pub const MAX_RETRY_DELAY_MS: u64 = 5 * 60 * 1000;
+/// How many times a post is tried before waiting for the user to retry it.
+pub const MAX_AUTOMATIC_ATTEMPTS: u32 = 8;
+
+/// The outbox of the logged-in user, as loaded by [`spawn_outbox_worker()`].
+static OUTBOX: Mutex<Outbox> = Mutex::new(Outbox {
+    entries: Vec::new(),
+});
+
+/// Wakes the outbox worker up when a post is queued or retried.
+static OUTBOX_WAKER: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Media attached to a queued post.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboxMedia {
+    /// Local path of the file to upload.
+    pub path: PathBuf,
+    /// Whether the file is a video, rather than a photo.
+    pub is_video: bool,
+    /// Whether the file is an audio clip, e.g., a voice note, rather than a photo.
+    #[serde(default)]
+    pub is_audio: bool,
+    /// MXC URI of the file once it's uploaded, so it isn't uploaded again on retries.
+    pub mxc_uri: Option<OwnedMxcUri>,
+    /// The encrypted file (see [`EncryptedFile`]) once the file is uploaded encrypted
+    /// for an encrypted feed, so it isn't uploaded again on retries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_file: Option<serde_json::Value>,
+    /// Description of a photo for people who can't see it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<String>,
+}
+
+/// A post waiting in the outbox to be sent to one feed room.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    /// The transaction ID the post is sent with.
+    pub transaction_id: OwnedTransactionId,
+    /// The feed room to send the post to.
+    pub room_id: OwnedRoomId,
+    /// The raw message content of the post (see [`Post::into_raw_content()`]).
+    pub content: serde_json::Value,
+    /// Media to upload and attach to the post, if any.
+    pub media: Option<OutboxMedia>,
+    /// How many times sending the post failed.
+    pub attempts: u32,
+    /// When to try sending the post next.
+    pub next_attempt_at: MilliSecondsSinceUnixEpoch,
+    /// Why sending the post failed the last time, if it did.
+    pub last_error: Option<String>,
+}
+
+impl OutboxMedia {
+    /// Create media to attach from a local file, which is a video or an audio clip
+    /// if its extension is one of a video or an audio file, or a photo otherwise.
+    pub fn from_path(path: PathBuf) -> Self {
+        Self {
+            is_video: video_content_type(&path).is_some(),
+            is_audio: audio_content_type(&path).is_some(),
+            path,
+            mxc_uri: None,
+            encrypted_file: None,
+            alt_text: None,
+        }
+    }
+
+    /// Describe the photo for people who can't see it.
+    /// Videos and audio clips have no alt text, so this is ignored for them.
+    pub fn with_alt_text(mut self, alt_text: Option<String>) -> Self {
+        if self.kind() == MediaKind::Image {
+            self.alt_text = alt_text;
+        }
+        self
+    }
+
+    /// Get the uploaded file to attach to a post sent to an encrypted
+    /// or an unencrypted room, or `None` if it hasn't been uploaded for it yet.
+    pub fn uploaded(&self, encrypted: bool) -> Option<MediaSource> {
+        if encrypted {
+            let file = serde_json::from_value::<EncryptedFile>(self.encrypted_file.clone()?);
+            file.ok().map(|file| MediaSource::Encrypted(Box::new(file)))
+        } else {
+            self.mxc_uri.clone().map(MediaSource::Plain)
+        }
+    }
+
+    /// Remember an uploaded file (see [`OutboxMedia::uploaded()`]).
+    pub fn set_uploaded(&mut self, source: MediaSource) {
+        match source {
+            MediaSource::Plain(mxc_uri) => self.mxc_uri = Some(mxc_uri),
+            MediaSource::Encrypted(file) => {
+                self.encrypted_file = serde_json::to_value(file).ok();
+            }
+        }
+    }
+
+    /// Get the photo to remember as posted in the given post,
+    /// or `None` if the media is a video or an audio clip.
+    ///
+    /// This decodes the whole image, so it should not be called on the UI thread.
+    ///
+    /// # Errors
+    /// Returns an error if the photo cannot be read or decoded.
+    pub fn uploaded_image(
+        &self,
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+    ) -> Result<Option<UploadedImage>, DuplicateImageError> {
+        if self.kind() != MediaKind::Image {
+            return Ok(None);
+        }
+        let hash = ImageHash::from_image_data(std::fs::read(&self.path)?)?;
+        Ok(Some(UploadedImage {
+            hash,
+            room_id,
+            event_id,
+            uploaded_at: MilliSecondsSinceUnixEpoch::now(),
+        }))
+    }
+
+    /// Get the kind of the media.
+    pub fn kind(&self) -> MediaKind {
+        if self.is_video {
+            MediaKind::Video
+        } else if self.is_audio {
+            MediaKind::Audio
+        } else {
+            MediaKind::Image
+        }
+    }
+}
+
+impl OutboxEntry {
+    /// Check whether the post is only retried when the user asks to.
+    pub fn has_failed(&self) -> bool {
+        self.attempts >= MAX_AUTOMATIC_ATTEMPTS
+    }
+
+    /// Check whether the post should be tried now.
+    pub fn is_due(&self, now: MilliSecondsSinceUnixEpoch) -> bool {
+        !self.has_failed() && self.next_attempt_at <= now
+    }
+}
+
+/// Actions emitted by the outbox worker.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum OutboxAction {
+    /// A post failed to send [`MAX_AUTOMATIC_ATTEMPTS`] times,
+    /// and is only retried when the user asks to (see [`PostService::retry()`]).
+    Failed {
+        transaction_id: OwnedTransactionId,
+        error: String,
+    },
+    /// No action.
+    None,
+}
+
+/// The posts waiting to be sent, oldest first.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Outbox {
+    entries: Vec<OutboxEntry>,
+}
+
+impl Outbox {
+    /// Load the outbox of the given user, or an empty outbox if none was saved yet.
+    ///
+    /// # Errors
+    /// Returns an error if the outbox file cannot be read or parsed.
+    pub fn load(user_id: &UserId) -> Result<Self, OutboxError> {
+        let path = persistent_state_dir(user_id).join(OUTBOX_FILE_NAME);
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save the outbox of the given user.
+    ///
+    /// # Errors
+    /// Returns an error if the outbox file cannot be written.
+    pub fn save(&self, user_id: &UserId) -> Result<(), OutboxError> {
+        let path = persistent_state_dir(user_id).join(OUTBOX_FILE_NAME);
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Get the posts waiting to be sent, oldest first.
+    pub fn entries(&self) -> &[OutboxEntry] {
+        &self.entries
+    }
+
+    /// Get a queued post.
+    pub fn get(&self, transaction_id: &TransactionId) -> Option<&OutboxEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.transaction_id == transaction_id)
+    }
+
+    /// Queue a post to be sent at once.
+    pub fn enqueue(
+        &mut self,
+        transaction_id: OwnedTransactionId,
+        room_id: OwnedRoomId,
+        content: serde_json::Value,
+        media: Option<OutboxMedia>,
+        now: MilliSecondsSinceUnixEpoch,
+    ) {
+        self.entries.push(OutboxEntry {
+            transaction_id,
+            room_id,
+            content,
+            media,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+        });
+    }
+
+    /// Get the oldest post that should be tried now, if any.
+    pub fn next_due(&self, now: MilliSecondsSinceUnixEpoch) -> Option<&OutboxEntry> {
+        self.entries.iter().find(|entry| entry.is_due(now))
+    }
+
+    /// Get when the next post should be tried, if any will be without the user asking.
+    pub fn next_attempt_at(&self) -> Option<MilliSecondsSinceUnixEpoch> {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.has_failed())
+            .map(|entry| entry.next_attempt_at)
+            .min()
+    }
+
+    /// Remember the uploaded media of a queued post.
+    pub fn set_media_uploaded(&mut self, transaction_id: &TransactionId, source: MediaSource) {
+        if let Some(media) = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.transaction_id == transaction_id)
+            .and_then(|entry| entry.media.as_mut())
+        {
+            media.set_uploaded(source);
+        }
+    }
+
+    /// Record that a post failed to send, scheduling its next attempt.
+    ///
+    /// Returns the post, or `None` if it isn't queued.
+    pub fn record_failure(
+        &mut self,
+        transaction_id: &TransactionId,
+        error: String,
+        now: MilliSecondsSinceUnixEpoch,
+    ) -> Option<&OutboxEntry> {
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.transaction_id == transaction_id)?;
+        entry.attempts += 1;
+        entry.last_error = Some(error);
+        let next_attempt_at = u64::from(now.get()) + retry_delay_ms(entry.attempts);
+        if let Ok(next_attempt_at) = next_attempt_at.try_into() {
+            entry.next_attempt_at = MilliSecondsSinceUnixEpoch(next_attempt_at);
+        }
+        Some(entry)
+    }
+
+    /// Try a post again at once, starting its attempts over.
+    ///
+    /// Returns `false` if the post isn't queued.
+    pub fn retry(
+        &mut self,
+        transaction_id: &TransactionId,
+        now: MilliSecondsSinceUnixEpoch,
+    ) -> bool {
+        let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.transaction_id == transaction_id)
+        else {
+            return false;
+        };
+        entry.attempts = 0;
+        entry.next_attempt_at = now;
+        true
+    }
+
+    /// Remove a post from the outbox, e.g., once it was sent.
+    pub fn remove(&mut self, transaction_id: &TransactionId) -> Option<OutboxEntry> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.transaction_id == transaction_id)?;
+        Some(self.entries.remove(index))
+    }
+}
+
+/// Get how long to wait before trying a post again after the given number of failed attempts,
+/// doubling from [`RETRY_BASE_DELAY_MS`] up to [`MAX_RETRY_DELAY_MS`].
+pub fn retry_delay_ms(attempts: u32) -> u64 {
+    let doublings = attempts.saturating_sub(1).min(31);
+    RETRY_BASE_DELAY_MS
+        .saturating_mul(1 << doublings)
+        .min(MAX_RETRY_DELAY_MS)
+}
+
+/// Service for sending posts through the outbox.
+pub struct PostService {
+    client: Client,
+}
+
+impl PostService {
+    /// Create a new PostService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Queue a post to be sent to each of its targets, with its media, if any.
+    ///
+    /// The copy sent to the first target is sent with `transaction_id`, e.g., the one
+    /// [`SocialFeedView::add_pending_post()`](crate::social::SocialFeedView::add_pending_post)
+    /// returned; the other copies get their own transaction IDs.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, or the post cannot be
+    /// serialized or the outbox saved.
+    pub fn queue(
+        &self,
+        post: &Post,
+        media: Option<OutboxMedia>,
+        transaction_id: OwnedTransactionId,
+    ) -> Result<(), OutboxError> {
+        let user_id = self.client.user_id().ok_or(PostError::NotLoggedIn)?;
+        let content = post.into_raw_content()?;
+        let now = MilliSecondsSinceUnixEpoch::now();
+        let mut outbox = OUTBOX.lock().unwrap();
+        let mut transaction_id = Some(transaction_id);
+        for room_id in &post.targets {
+            let transaction_id = transaction_id.take().unwrap_or_else(TransactionId::new);
+            outbox.enqueue(
+                transaction_id,
+                room_id.clone(),
+                content.clone(),
+                media.clone(),
+                now,
+            );
+        }
+        outbox.save(user_id)?;
+        OUTBOX_WAKER.notify_one();
+        Ok(())
+    }
+
+    /// Queue a reply to a post, sent to the post's room in the post's thread
+    /// (see [`PostContent::into_thread_reply()`]), with its media, if any.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, or the reply cannot be
+    /// serialized or the outbox saved.
+    pub fn queue_reply(
+        &self,
+        room_id: OwnedRoomId,
+        post_id: OwnedEventId,
+        content: &PostContent,
+        media: Option<OutboxMedia>,
+    ) -> Result<(), OutboxError> {
+        let user_id = self.client.user_id().ok_or(PostError::NotLoggedIn)?;
+        let content = serde_json::to_value(content.into_thread_reply(post_id))?;
+        let mut outbox = OUTBOX.lock().unwrap();
+        outbox.enqueue(
+            TransactionId::new(),
+            room_id,
+            content,
+            media,
+            MilliSecondsSinceUnixEpoch::now(),
+        );
+        outbox.save(user_id)?;
+        OUTBOX_WAKER.notify_one();
+        Ok(())
+    }
+
+    /// Try a post that failed to send again at once.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or the outbox cannot be saved.
+    pub fn retry(&self, transaction_id: &TransactionId) -> Result<(), OutboxError> {
+        let user_id = self.client.user_id().ok_or(PostError::NotLoggedIn)?;
+        let mut outbox = OUTBOX.lock().unwrap();
+        if outbox.retry(transaction_id, MilliSecondsSinceUnixEpoch::now()) {
+            outbox.save(user_id)?;
+            OUTBOX_WAKER.notify_one();
+        }
+        Ok(())
+    }
+
+    /// Send a post to each of its targets at once, without queueing it,
+    /// e.g., from the command line (see [`cli`](crate::social::cli)).
+    ///
+    /// Returns the event IDs of the copies sent, in the order of the targets.
+    ///
+    /// # Errors
+    /// Returns an error as soon as the media or a copy of the post fails to send.
+    pub async fn send_now(
+        &self,
+        post: &Post,
+        mut media: Option<OutboxMedia>,
+    ) -> Result<Vec<OwnedEventId>, OutboxError> {
+        let content = post.into_raw_content()?;
+        // Upload the media once for all unencrypted copies and once for all encrypted ones
+        if let Some(media) = media.as_mut() {
+            for room_id in &post.targets {
+                let encrypted = self.is_encrypted(room_id);
+                if media.uploaded(encrypted).is_none() {
+                    let source = self.upload(media, encrypted).await?;
+                    media.set_uploaded(source);
+                }
+            }
+        }
+        let mut event_ids = Vec::with_capacity(post.targets.len());
+        for room_id in &post.targets {
+            let entry = OutboxEntry {
+                transaction_id: TransactionId::new(),
+                room_id: room_id.clone(),
+                content: content.clone(),
+                media: media.clone(),
+                attempts: 0,
+                next_attempt_at: MilliSecondsSinceUnixEpoch::now(),
+                last_error: None,
+            };
+            event_ids.push(self.send(&entry).await?);
+        }
+        Ok(event_ids)
+    }
+
+    /// Try to send a queued post once, uploading its media first if needed.
+    async fn send(&self, entry: &OutboxEntry) -> Result<OwnedEventId, OutboxError> {
+        let room = self
+            .client
+            .get_room(&entry.room_id)
+            .ok_or_else(|| PostError::RoomNotFound(entry.room_id.clone()))?;
+
+        let mut content = entry.content.clone();
+        if let Some(media) = &entry.media {
+            let encrypted = room.encryption_state().is_encrypted();
+            let source = match media.uploaded(encrypted) {
+                Some(source) => source,
+                None => {
+                    let source = self.upload(media, encrypted).await?;
+                    OUTBOX
+                        .lock()
+                        .unwrap()
+                        .set_media_uploaded(&entry.transaction_id, source.clone());
+                    source
+                }
+            };
+            attach_media(&mut content, media, source);
+        }
+
+        let response = room
+            .send_raw(post_event_type(&content), content)
+            .with_transaction_id(entry.transaction_id.clone())
+            .await?;
+        Cx::post_action(OptimisticAction::Confirmed {
+            transaction_id: entry.transaction_id.clone(),
+            event_id: response.event_id.clone(),
+        });
+        if let Some(media) = &entry.media {
+            self.record_sent_photo(media, entry.room_id.clone(), response.event_id.clone());
+        }
+        Ok(response.event_id)
+    }
+
+    /// Remember a photo that was just posted in the user's index of posted photos,
+    /// in the background since the whole image is decoded.
+    fn record_sent_photo(&self, media: &OutboxMedia, room_id: OwnedRoomId, event_id: OwnedEventId) {
+        let Some(user_id) = self.client.user_id().map(ToOwned::to_owned) else {
+            return;
+        };
+        if media.kind() != MediaKind::Image {
+            return;
+        }
+        let media = media.clone();
+        Handle::current().spawn_blocking(move || {
+            let result = media
+                .uploaded_image(room_id, event_id)
+                .and_then(|upload| upload.map_or(Ok(()), |upload| record_upload(&user_id, upload)));
+            if let Err(e) = result {
+                warning!(
+                    "Failed to remember the posted photo {}: {e}",
+                    media.path.display()
+                );
+            }
+        });
+    }
+
+    /// Check whether a room is encrypted, so that media sent to it must be encrypted too.
+    fn is_encrypted(&self, room_id: &RoomId) -> bool {
+        self.client
+            .get_room(room_id)
+            .is_some_and(|room| room.encryption_state().is_encrypted())
+    }
+
+    /// Upload the media of a queued post, encrypting it first if the post
+    /// is sent to an encrypted room.
+    async fn upload(
+        &self,
+        media: &OutboxMedia,
+        encrypted: bool,
+    ) -> Result<MediaSource, OutboxError> {
+        let data = std::fs::read(&media.path)?;
+        if encrypted {
+            // The server only gets opaque bytes, so there's no content type to give it
+            let file = self
+                .client
+                .upload_encrypted_file(&mut data.as_slice())
+                .await
+                .map_err(|e| PostError::MediaUploadFailed(e.to_string()))?;
+            return Ok(MediaSource::Encrypted(file.into()));
+        }
+        let content_type = match media.kind() {
+            MediaKind::Video => {
+                video_content_type(&media.path).unwrap_or(mime::APPLICATION_OCTET_STREAM)
+            }
+            MediaKind::Audio => {
+                audio_content_type(&media.path).unwrap_or(mime::APPLICATION_OCTET_STREAM)
+            }
+            MediaKind::Image => match imghdr::from_bytes(&data) {
+                Some(imghdr::Type::Png) => mime::IMAGE_PNG,
+                Some(imghdr::Type::Jpeg) => mime::IMAGE_JPEG,
+                Some(imghdr::Type::Gif) => mime::IMAGE_GIF,
+                _ => mime::APPLICATION_OCTET_STREAM,
+            },
+        };
+        let response = self
+            .client
+            .media()
+            .upload(&content_type, data, None)
+            .await
+            .map_err(|e| PostError::MediaUploadFailed(e.to_string()))?;
+        Ok(MediaSource::Plain(response.content_uri))
+    }
+}
+
+/// Get the content type of a video file from its extension,
+/// or `None` if it isn't a video.
+fn video_content_type(path: &Path) -> Option<Mime> {
+    let content_type = match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "mp4" | "m4v" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        _ => return None,
+    };
+    content_type.parse().ok()
+}
+
+/// Get the content type of an audio file from its extension,
+/// or `None` if it isn't an audio file.
+fn audio_content_type(path: &Path) -> Option<Mime> {
+    let content_type = match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "ogg" | "oga" | "opus" => "audio/ogg",
+        "m4a" => "audio/mp4",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        _ => return None,
+    };
+    content_type.parse().ok()
+}
+
+/// Turn the raw message content of a post into a photo, video or audio message with
+/// its caption, the alt text of a photo, and the duration and waveform of a WAV recording
+/// (see [`analyze_wav()`]).
+///
+/// The file is attached as a `url` if it was uploaded as is, or as a `file`
+/// if it was encrypted. The size of the file is recorded in the message's `info`,
+/// if it can be read, so that it can be checked against the feed's media restrictions
+/// (see [`media_policy`](crate::social::media_policy)).
+fn attach_media(content: &mut serde_json::Value, media: &OutboxMedia, source: MediaSource) {
+    let Some(object) = content.as_object_mut() else {
+        return;
+    };
+    let msgtype = media.kind().msgtype();
+    let file_name = media
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let caption = object
+        .get("body")
+        .and_then(|body| body.as_str())
+        .filter(|body| !body.is_empty())
+        .map(str::to_owned);
+    object.insert("msgtype".to_string(), msgtype.into());
+    match source {
+        MediaSource::Plain(mxc_uri) => {
+            object.insert("url".to_string(), mxc_uri.to_string().into());
+        }
+        MediaSource::Encrypted(file) => {
+            if let Ok(file) = serde_json::to_value(file) {
+                object.insert("file".to_string(), file);
+            }
+        }
+    }
+    if let Ok(metadata) = std::fs::metadata(&media.path) {
+        let info = serde_json::json!({ "size": metadata.len() });
+        object.insert("info".to_string(), info);
+    }
+    if media.kind() == MediaKind::Audio {
+        let details = std::fs::read(&media.path)
+            .ok()
+            .and_then(|data| analyze_wav(&data));
+        if let Some(details) = details {
+            if let (Some(duration), Some(info)) = (details.duration, object.get_mut("info")) {
+                info["duration"] = duration.into();
+            }
+            details.insert_into(object);
+        }
+    }
+    if let Some(alt_text) = &media.alt_text {
+        let caption = Caption {
+            text: caption.clone().unwrap_or_default(),
+            formatted_text: None,
+            alt_text: Some(alt_text.clone()),
+        };
+        if let Ok(caption) = serde_json::to_value(caption) {
+            object.insert(CAPTION_FIELD.to_string(), caption);
+        }
+    }
+    match caption {
+        Some(caption) if caption != file_name => {
+            object.insert("filename".to_string(), file_name.into());
+            object.insert("body".to_string(), caption.into());
+        }
+        _ => {
+            object.insert("body".to_string(), file_name.into());
+        }
+    }
+}
+
+/// Load the logged-in user's outbox and keep sending the posts queued in it.
+///
+/// Must be called from within the Tokio runtime after logging in.
+/// Posts left in the outbox by a previous session are sent too.
+pub fn spawn_outbox_worker(client: Client) {
+    let Some(user_id) = client.user_id().map(ToOwned::to_owned) else {
+        return;
+    };
+    Handle::current().spawn(async move {
+        match Outbox::load(&user_id) {
+            Ok(outbox) => *OUTBOX.lock().unwrap() = outbox,
+            Err(e) => error!("Failed to load the outbox of {user_id}: {e}"),
+        }
+
+        let service = PostService::new(client);
+        loop {
+            let now = MilliSecondsSinceUnixEpoch::now();
+            let (next_due, next_attempt_at) = {
+                let outbox = OUTBOX.lock().unwrap();
+                (outbox.next_due(now).cloned(), outbox.next_attempt_at())
+            };
+            let Some(entry) = next_due else {
+                // Sleep until the next retry, or until a post is queued or retried
+                let wait_ms = next_attempt_at
+                    .map(|at| u64::from(at.get()).saturating_sub(u64::from(now.get())));
+                match wait_ms {
+                    Some(wait_ms) => {
+                        let _ = tokio::time::timeout(
+                            Duration::from_millis(wait_ms),
+                            OUTBOX_WAKER.notified(),
+                        )
+                        .await;
+                    }
+                    None => OUTBOX_WAKER.notified().await,
+                }
+                continue;
+            };
+
+            let result = service.send(&entry).await;
+            let mut outbox = OUTBOX.lock().unwrap();
+            match result {
+                Ok(_) => {
+                    outbox.remove(&entry.transaction_id);
+                }
+                Err(e) => {
+                    warning!("Failed to send queued post to {}: {e}", entry.room_id);
+                    let failed = outbox.record_failure(
+                        &entry.transaction_id,
+                        e.to_string(),
+                        MilliSecondsSinceUnixEpoch::now(),
+                    );
+                    if failed.is_some_and(OutboxEntry::has_failed) {
+                        Cx::post_action(OutboxAction::Failed {
+                            transaction_id: entry.transaction_id.clone(),
+                            error: e.to_string(),
+                        });
+                    }
+                }
+            }
+            if let Err(e) = outbox.save(&user_id) {
+                error!("Failed to save the outbox of {user_id}: {e}");
+            }
+        }
+    });
+}
+
+/// Errors that can occur when queueing or sending posts.
+#[derive(Debug, thiserror::Error)]
+pub enum OutboxError {
+    /// The post could not be sent.
+    #[error(transparent)]
+    Post(#[from] PostError),
+
+    /// The outbox or a media file could not be read or written.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The outbox or a post could not be parsed or serialized.
+    #[error("Invalid outbox: {0}")]
+    InvalidOutbox(#[from] serde_json::Error),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{owned_event_id, owned_room_id, UInt};
+    use serde_json::json;
+
+    fn at(ms: u64) -> MilliSecondsSinceUnixEpoch {
+        MilliSecondsSinceUnixEpoch(UInt::new(ms).unwrap())
+    }
+
+    fn sunset() -> MediaSource {
+        MediaSource::Plain("mxc://example.org/sunset".into())
+    }
+
+    fn encrypted_sunset() -> MediaSource {
+        let file = json!({
+            "url": "mxc://example.org/encrypted_sunset",
+            "key": {
+                "kty": "oct",
+                "key_ops": ["encrypt", "decrypt"],
+                "alg": "A256CTR",
+                "k": "qcHVMSgYg-71CauWBezXI5qkaRb0LuIy-Wx5kIaHMIA",
+                "ext": true,
+            },
+            "iv": "X85+XgHN+HEAAAAAAAAAAA",
+            "hashes": { "sha256": "5qG4fFnbbVdlAB1Q72JDKwCagV6Dbkx9uds4rSak37c" },
+            "v": "v2",
+        });
+        MediaSource::Encrypted(Box::new(serde_json::from_value(file).unwrap()))
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_max() {
+        assert_eq!(retry_delay_ms(1), RETRY_BASE_DELAY_MS);
+        assert_eq!(retry_delay_ms(2), 2 * RETRY_BASE_DELAY_MS);
+        assert_eq!(retry_delay_ms(4), 8 * RETRY_BASE_DELAY_MS);
+        assert_eq!(retry_delay_ms(20), MAX_RETRY_DELAY_MS);
+        assert_eq!(retry_delay_ms(u32::MAX), MAX_RETRY_DELAY_MS);
+    }
+
+    #[test]
+    fn test_failed_posts_are_retried_with_backoff() {
+        let transaction_id = TransactionId::new();
+        let mut outbox = Outbox::default();
+        outbox.enqueue(
+            transaction_id.clone(),
+            owned_room_id!("!feed:example.org"),
+            json!({ "msgtype": "m.text", "body": "Hello" }),
+            None,
+            at(1_000),
+        );
+        assert!(outbox.next_due(at(1_000)).is_some());
+
+        outbox.record_failure(&transaction_id, "offline".to_string(), at(1_000));
+        assert!(outbox.next_due(at(2_000)).is_none());
+        assert_eq!(
+            outbox.next_attempt_at(),
+            Some(at(1_000 + RETRY_BASE_DELAY_MS))
+        );
+        assert!(outbox.next_due(at(1_000 + RETRY_BASE_DELAY_MS)).is_some());
+
+        // After too many attempts, posts wait for the user to retry them
+        for _ in 1..MAX_AUTOMATIC_ATTEMPTS {
+            outbox.record_failure(&transaction_id, "offline".to_string(), at(1_000));
+        }
+        assert!(outbox.get(&transaction_id).unwrap().has_failed());
+        assert!(outbox.next_due(at(u64::from(u32::MAX))).is_none());
+        assert_eq!(outbox.next_attempt_at(), None);
+
+        assert!(outbox.retry(&transaction_id, at(5_000)));
+        assert!(outbox.next_due(at(5_000)).is_some());
+        assert!(outbox.remove(&transaction_id).is_some());
+        assert!(outbox.entries().is_empty());
+    }
+
+    #[test]
+    fn test_outbox_round_trip() {
+        let transaction_id = TransactionId::new();
+        let mut outbox = Outbox::default();
+        outbox.enqueue(
+            transaction_id.clone(),
+            owned_room_id!("!feed:example.org"),
+            json!({ "msgtype": "m.text", "body": "Sunset" }),
+            Some(OutboxMedia {
+                path: PathBuf::from("/photos/sunset.jpg"),
+                is_video: false,
+                is_audio: false,
+                mxc_uri: None,
+                encrypted_file: None,
+                alt_text: None,
+            }),
+            at(1_000),
+        );
+        outbox.set_media_uploaded(
+            &transaction_id,
+            MediaSource::Plain("mxc://example.org/sunset".into()),
+        );
+
+        let json = serde_json::to_string(&outbox).unwrap();
+        let loaded: Outbox = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, outbox);
+        assert!(
+            loaded.entries()[0]
+                .media
+                .as_ref()
+                .unwrap()
+                .mxc_uri
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_media_from_path() {
+        assert!(OutboxMedia::from_path(PathBuf::from("/videos/hike.MP4")).is_video);
+        assert!(!OutboxMedia::from_path(PathBuf::from("/photos/sunset.jpg")).is_video);
+        assert_eq!(
+            OutboxMedia::from_path(PathBuf::from("/photos/sunset")).kind(),
+            MediaKind::Image
+        );
+        assert_eq!(
+            OutboxMedia::from_path(PathBuf::from("/voice/note.ogg")).kind(),
+            MediaKind::Audio
+        );
+    }
+
+    #[test]
+    fn test_sent_photos_are_recognized_when_posted_again() {
+        use crate::social::duplicate_media::UploadHashIndex;
+
+        let path = PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/resources/img/default_image.png"
+        ));
+        let media = OutboxMedia::from_path(path.clone());
+        let upload = media
+            .uploaded_image(
+                owned_room_id!("!feed:example.org"),
+                owned_event_id!("$post:example.org"),
+            )
+            .unwrap()
+            .unwrap();
+        let mut index = UploadHashIndex::default();
+        index.record(upload);
+
+        let hash = ImageHash::from_image_data(std::fs::read(&path).unwrap()).unwrap();
+        let duplicate = index
+            .find_duplicate(hash, MilliSecondsSinceUnixEpoch::now())
+            .unwrap();
+        assert_eq!(duplicate.event_id, owned_event_id!("$post:example.org"));
+
+        // Videos aren't remembered, so they aren't even read
+        let video = OutboxMedia::from_path(PathBuf::from("/videos/hike.mp4"));
+        assert!(
+            video
+                .uploaded_image(
+                    owned_room_id!("!feed:example.org"),
+                    owned_event_id!("$post:example.org"),
+                )
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_attach_media() {
+        let media = OutboxMedia {
+            path: PathBuf::from("/photos/sunset.jpg"),
+            is_video: false,
+            is_audio: false,
+            mxc_uri: None,
+            encrypted_file: None,
+            alt_text: None,
+        };
+        let mut content = json!({ "msgtype": "m.text", "body": "What a view" });
+        attach_media(&mut content, &media, sunset());
+        assert_eq!(
+            content,
+            json!({
+                "msgtype": "m.image",
+                "body": "What a view",
+                "filename": "sunset.jpg",
+                "url": "mxc://example.org/sunset",
+            })
+        );
+
+        let mut content = json!({ "msgtype": "m.text", "body": "" });
+        attach_media(&mut content, &media, sunset());
+        assert_eq!(content["body"], "sunset.jpg");
+
+        let media = media.with_alt_text(Some("The sun setting over the sea".to_string()));
+        let mut content = json!({ "msgtype": "m.text", "body": "What a view" });
+        attach_media(&mut content, &media, sunset());
+        assert_eq!(
+            crate::social::post::alt_text_from_content(&content).as_deref(),
+            Some("The sun setting over the sea")
+        );
+
+        let media = OutboxMedia::from_path(PathBuf::from("/voice/note.ogg"));
+        let mut content = json!({ "msgtype": "m.text", "body": "" });
+        attach_media(
+            &mut content,
+            &media,
+            MediaSource::Plain("mxc://example.org/note".into()),
+        );
+        assert_eq!(content["msgtype"], "m.audio");
+        assert_eq!(content["body"], "note.ogg");
+    }
+
+    #[test]
+    fn test_encrypted_media() {
+        let mut media = OutboxMedia::from_path(PathBuf::from("/photos/sunset.jpg"));
+        media.set_uploaded(sunset());
+        // Media uploaded for an unencrypted feed isn't reused for an encrypted one
+        assert!(media.uploaded(false).is_some());
+        assert!(media.uploaded(true).is_none());
+
+        media.set_uploaded(encrypted_sunset());
+        let Some(MediaSource::Encrypted(file)) = media.uploaded(true) else {
+            panic!("the encrypted file wasn't remembered");
+        };
+        assert_eq!(file.url, "mxc://example.org/encrypted_sunset");
+
+        let mut content = json!({ "msgtype": "m.text", "body": "What a view" });
+        attach_media(&mut content, &media, encrypted_sunset());
+        assert_eq!(content["msgtype"], "m.image");
+        assert_eq!(content["file"]["url"], "mxc://example.org/encrypted_sunset");
+        assert!(content.get("url").is_none());
+    }
+}
@@ -0,0 +1,754 @@
+//! Poll posts.
+//!
+//! Polls are sent as regular Matrix polls ([MSC3381]) rather than
+//! `m.room.message` events, so that other Matrix clients can show them and
+//! vote in them. Like other posts, their content carries the social extension
+//! fields, e.g., their intended audience (see [`Post::into_raw_content()`]).
+//!
+//! Votes are poll response events referencing the poll, and only each voter's
+//! latest vote counts (see [`latest_votes()`]). The author closes the poll
+//! with a poll end event, after which new votes are ignored. Only ends sent by
+//! the author, or by someone allowed to remove others' posts, count
+//! (see [`poll_ended_at()`]). Post cards show
+//! the current votes of each answer, which the newsfeed reloads whenever the
+//! post watcher sees a vote or the end of a poll
+//! (see [`SocialEvent::PollUpdated`](crate::social::SocialEvent::PollUpdated)).
+//!
+//! [MSC3381]: https://github.com/matrix-org/matrix-spec-proposals/pull/3381
+//! [`Post::into_raw_content()`]: crate::social::Post::into_raw_content
+
+use matrix_sdk::{
+    room::{MessagesOptions, Room},
+    ruma::{
+        events::poll::{
+            start::PollKind,
+            unstable_end::UnstablePollEndEventContent,
+            unstable_response::UnstablePollResponseEventContent,
+            unstable_start::{
+                NewUnstablePollStartEventContent, UnstablePollAnswer, UnstablePollStartContentBlock,
+            },
+        },
+        EventId, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedUserId, RoomId, UInt, UserId,
+    },
+    Client,
+};
+use serde::{Deserialize, Serialize};
+
+/// Event type of poll start events, which poll posts are sent as.
+pub const POLL_START_EVENT_TYPE: &str = "org.matrix.msc3381.poll.start";
+
+/// Event type of poll response events, i.e., votes.
+pub const POLL_RESPONSE_EVENT_TYPE: &str = "org.matrix.msc3381.poll.response";
+
+/// Event type of poll end events.
+pub const POLL_END_EVENT_TYPE: &str = "org.matrix.msc3381.poll.end";
+
+/// Maximum number of answers in a poll.
+pub const MAX_POLL_ANSWERS: usize = 20;
+
+/// A possible answer to a poll.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollAnswer {
+    /// The ID votes refer to the answer by.
+    pub id: String,
+    /// The answer as shown to voters.
+    pub text: String,
+}
+
+/// A single poll response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PollVote {
+    /// The user who voted.
+    pub user_id: OwnedUserId,
+    /// The IDs of the answers they picked.
+    pub answers: Vec<String>,
+    /// When they voted.
+    pub timestamp: MilliSecondsSinceUnixEpoch,
+}
+
+/// A poll end event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PollEnd {
+    /// The user who ended the poll.
+    pub sender: OwnedUserId,
+    /// When they ended it.
+    pub timestamp: MilliSecondsSinceUnixEpoch,
+}
+
+/// An answer to a poll with the users who picked it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnswerResult {
+    /// The answer.
+    pub answer: PollAnswer,
+    /// Users who picked this answer.
+    pub voters: Vec<OwnedUserId>,
+}
+
+/// A poll post with its current votes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PollResults {
+    /// The poll's start event, i.e., the post.
+    pub poll_id: OwnedEventId,
+    /// The user who started the poll, the only one who can end it here.
+    pub author: OwnedUserId,
+    /// The question asked.
+    pub question: String,
+    /// The possible answers with their votes, in the order they're shown.
+    pub answers: Vec<AnswerResult>,
+    /// How many answers each voter can pick.
+    pub max_selections: u32,
+    /// Whether the author ended the poll.
+    pub ended: bool,
+}
+
+impl PollResults {
+    /// Create the results of a poll nobody voted in yet,
+    /// e.g., to show the poll until its votes are loaded.
+    pub fn new(
+        poll_id: OwnedEventId,
+        author: OwnedUserId,
+        question: String,
+        answers: Vec<PollAnswer>,
+        max_selections: u32,
+    ) -> Self {
+        Self {
+            poll_id,
+            author,
+            question,
+            answers: tally_poll(&answers, max_selections, &[], None),
+            max_selections,
+            ended: false,
+        }
+    }
+
+    /// Get the number of users whose vote counts.
+    pub fn voter_count(&self) -> usize {
+        let mut voters: Vec<&OwnedUserId> = self
+            .answers
+            .iter()
+            .flat_map(|answer| &answer.voters)
+            .collect();
+        voters.sort();
+        voters.dedup();
+        voters.len()
+    }
+
+    /// Get the share of voters who picked the given answer, in percent.
+    pub fn percentage(&self, answer: &AnswerResult) -> u32 {
+        let voter_count = self.voter_count();
+        if voter_count == 0 {
+            return 0;
+        }
+        (answer.voters.len() * 100 / voter_count) as u32
+    }
+
+    /// Get the IDs of the answers the given user picked.
+    pub fn votes_of(&self, user_id: &UserId) -> Vec<String> {
+        self.answers
+            .iter()
+            .filter(|answer| answer.voters.iter().any(|voter| voter == user_id))
+            .map(|answer| answer.answer.id.clone())
+            .collect()
+    }
+
+    /// Get the answers with the most votes, or none if nobody voted yet.
+    pub fn winners(&self) -> Vec<&PollAnswer> {
+        let most_votes = self
+            .answers
+            .iter()
+            .map(|answer| answer.voters.len())
+            .max()
+            .unwrap_or_default();
+        if most_votes == 0 {
+            return Vec::new();
+        }
+        self.answers
+            .iter()
+            .filter(|answer| answer.voters.len() == most_votes)
+            .map(|answer| &answer.answer)
+            .collect()
+    }
+
+    /// Count the given answers as the vote of the given user, replacing their earlier vote,
+    /// e.g., to show a vote before it's sent.
+    ///
+    /// Answers the poll doesn't have, and any beyond the number each voter can pick, are ignored.
+    pub fn set_votes(&mut self, user_id: &UserId, answer_ids: &[String]) {
+        for answer in &mut self.answers {
+            answer.voters.retain(|voter| voter != user_id);
+        }
+        for answer_id in answer_ids.iter().take(self.max_selections.max(1) as usize) {
+            let answer = self
+                .answers
+                .iter_mut()
+                .find(|answer| &answer.answer.id == answer_id);
+            if let Some(answer) = answer {
+                answer.voters.push(user_id.to_owned());
+            }
+        }
+    }
+
+    /// Get the answers the given user picks by tapping an answer.
+    ///
+    /// In a single choice poll, tapping an answer picks it instead of the user's
+    /// earlier answer. Otherwise, it's added to or removed from the user's answers,
+    /// unless they already picked as many as they can. Tapping the only answer the
+    /// user picked withdraws their vote, i.e., returns no answers.
+    pub fn toggle_vote(&self, user_id: &UserId, answer_id: &str) -> Vec<String> {
+        let mut votes = self.votes_of(user_id);
+        if let Some(index) = votes.iter().position(|id| id == answer_id) {
+            votes.remove(index);
+        } else if self.max_selections <= 1 {
+            votes = vec![answer_id.to_string()];
+        } else if votes.len() < self.max_selections as usize {
+            votes.push(answer_id.to_string());
+        }
+        votes
+    }
+}
+
+/// Get the text shown for a poll by clients that don't support polls,
+/// i.e., its question followed by its numbered answers.
+pub fn poll_fallback_text(question: &str, answers: &[PollAnswer]) -> String {
+    let mut text = question.to_string();
+    for (index, answer) in answers.iter().enumerate() {
+        text.push_str(&format!("\n{}. {}", index + 1, answer.text));
+    }
+    text
+}
+
+/// Build the content of the start event of a poll post.
+///
+/// # Errors
+/// Returns an error if there are fewer than 2 answers or more than [`MAX_POLL_ANSWERS`].
+pub fn poll_start_content(
+    question: &str,
+    answers: &[PollAnswer],
+    max_selections: u32,
+) -> Result<NewUnstablePollStartEventContent, PollError> {
+    if !(2..=MAX_POLL_ANSWERS).contains(&answers.len()) {
+        return Err(PollError::InvalidAnswers);
+    }
+    let poll_answers = answers
+        .iter()
+        .map(|answer| UnstablePollAnswer::new(answer.id.clone(), answer.text.clone()))
+        .collect::<Vec<_>>()
+        .try_into()
+        .map_err(|_| PollError::InvalidAnswers)?;
+    let mut poll_start = UnstablePollStartContentBlock::new(question, poll_answers);
+    poll_start.kind = PollKind::Disclosed;
+    poll_start.max_selections = UInt::from(max_selections.max(1));
+    Ok(NewUnstablePollStartEventContent::plain_text(
+        poll_fallback_text(question, answers),
+        poll_start,
+    ))
+}
+
+/// Get the question, answers and number of answers each voter can pick
+/// of a poll post from the raw content of its start event.
+///
+/// Returns `None` if the content isn't the start of a poll.
+pub fn poll_from_content(content: &serde_json::Value) -> Option<(String, Vec<PollAnswer>, u32)> {
+    let content: NewUnstablePollStartEventContent = serde_json::from_value(content.clone()).ok()?;
+    let answers = content
+        .poll_start
+        .answers
+        .iter()
+        .map(|answer| PollAnswer {
+            id: answer.id.clone(),
+            text: answer.text.clone(),
+        })
+        .collect();
+    let max_selections = u32::try_from(u64::from(content.poll_start.max_selections)).ok()?;
+    Some((content.poll_start.question.text, answers, max_selections))
+}
+
+/// Get each user's vote that counts.
+///
+/// Following MSC3381, only each user's latest vote counts,
+/// and votes sent after the poll ended are ignored.
+pub fn latest_votes(
+    votes: &[PollVote],
+    ended_at: Option<MilliSecondsSinceUnixEpoch>,
+) -> Vec<&PollVote> {
+    let mut latest_votes: Vec<&PollVote> = Vec::new();
+    for vote in votes {
+        if ended_at.is_some_and(|ended_at| vote.timestamp > ended_at) {
+            continue;
+        }
+        match latest_votes
+            .iter_mut()
+            .find(|latest| latest.user_id == vote.user_id)
+        {
+            Some(latest) if latest.timestamp <= vote.timestamp => *latest = vote,
+            Some(_) => {}
+            None => latest_votes.push(vote),
+        }
+    }
+    latest_votes
+}
+
+/// Get when a poll was first ended by its author, or by a user for whom
+/// `can_redact` is true, i.e., who may remove others' posts.
+///
+/// Following MSC3381, ends sent by anyone else are ignored.
+pub fn poll_ended_at(
+    ends: &[PollEnd],
+    author: &UserId,
+    can_redact: impl Fn(&UserId) -> bool,
+) -> Option<MilliSecondsSinceUnixEpoch> {
+    ends.iter()
+        .filter(|end| &*end.sender == author || can_redact(&end.sender))
+        .map(|end| end.timestamp)
+        .min()
+}
+
+/// Count the votes for each answer of a poll.
+///
+/// Following MSC3381, only the first `max_selections` answers of a vote count,
+/// answers the poll doesn't have are ignored, and a vote without any valid
+/// answer withdraws the user's earlier vote (see [`latest_votes()`]).
+pub fn tally_poll(
+    answers: &[PollAnswer],
+    max_selections: u32,
+    votes: &[PollVote],
+    ended_at: Option<MilliSecondsSinceUnixEpoch>,
+) -> Vec<AnswerResult> {
+    let mut results: Vec<AnswerResult> = answers
+        .iter()
+        .map(|answer| AnswerResult {
+            answer: answer.clone(),
+            voters: Vec::new(),
+        })
+        .collect();
+    for vote in latest_votes(votes, ended_at) {
+        for answer_id in vote.answers.iter().take(max_selections.max(1) as usize) {
+            let result = results
+                .iter_mut()
+                .find(|result| &result.answer.id == answer_id);
+            if let Some(result) = result {
+                if !result.voters.contains(&vote.user_id) {
+                    result.voters.push(vote.user_id.clone());
+                }
+            }
+        }
+    }
+    results
+}
+
+/// The events of a poll, as loaded from its room.
+pub(crate) struct PollEvents {
+    /// The content of the poll's start event.
+    pub start: NewUnstablePollStartEventContent,
+    /// The user who started the poll.
+    pub author: OwnedUserId,
+    /// The votes in the poll, newest first.
+    pub votes: Vec<PollVote>,
+    /// When the poll was first ended by someone allowed to, if it was
+    /// (see [`poll_ended_at()`]).
+    pub ended_at: Option<MilliSecondsSinceUnixEpoch>,
+}
+
+/// Load a poll's start event, votes and end from its room.
+///
+/// Messages are loaded back to the poll's start event.
+/// Returns `None` if the poll's start event isn't found.
+pub(crate) async fn load_poll_events(
+    room: &Room,
+    poll_id: &EventId,
+) -> Result<Option<PollEvents>, matrix_sdk::Error> {
+    let mut votes = Vec::new();
+    let mut ends = Vec::new();
+    let mut from = None;
+    loop {
+        let mut options = MessagesOptions::backward();
+        options.from = from.take();
+        let response = room.messages(options).await?;
+
+        for timeline_event in &response.chunk {
+            let raw = timeline_event.raw();
+            let event_type = raw.get_field::<String>("type").ok().flatten();
+            match event_type.as_deref() {
+                Some(POLL_START_EVENT_TYPE)
+                    if timeline_event.event_id().as_deref() == Some(poll_id) =>
+                {
+                    let start = raw
+                        .get_field::<NewUnstablePollStartEventContent>("content")
+                        .ok()
+                        .flatten();
+                    let author = raw.get_field::<OwnedUserId>("sender").ok().flatten();
+                    let (Some(start), Some(author)) = (start, author) else {
+                        return Ok(None);
+                    };
+                    // Only look up who may remove others' posts if someone else ended the poll
+                    let power_levels = if ends.iter().any(|end: &PollEnd| end.sender != author) {
+                        Some(room.power_levels().await?)
+                    } else {
+                        None
+                    };
+                    let ended_at = poll_ended_at(&ends, &author, |user_id| {
+                        power_levels
+                            .as_ref()
+                            .is_some_and(|levels| levels.user_can_redact_event_of_other(user_id))
+                    });
+                    return Ok(Some(PollEvents {
+                        start,
+                        author,
+                        votes,
+                        ended_at,
+                    }));
+                }
+                Some(POLL_RESPONSE_EVENT_TYPE) => {
+                    let Ok(Some(content)) =
+                        raw.get_field::<UnstablePollResponseEventContent>("content")
+                    else {
+                        continue;
+                    };
+                    let (Ok(Some(user_id)), Ok(Some(timestamp))) = (
+                        raw.get_field::<OwnedUserId>("sender"),
+                        raw.get_field::<MilliSecondsSinceUnixEpoch>("origin_server_ts"),
+                    ) else {
+                        continue;
+                    };
+                    if content.relates_to.event_id == poll_id {
+                        votes.push(PollVote {
+                            user_id,
+                            answers: content.poll_response.answers,
+                            timestamp,
+                        });
+                    }
+                }
+                Some(POLL_END_EVENT_TYPE) => {
+                    let Ok(Some(content)) = raw.get_field::<UnstablePollEndEventContent>("content")
+                    else {
+                        continue;
+                    };
+                    let (Ok(Some(sender)), Ok(Some(timestamp))) = (
+                        raw.get_field::<OwnedUserId>("sender"),
+                        raw.get_field::<MilliSecondsSinceUnixEpoch>("origin_server_ts"),
+                    ) else {
+                        continue;
+                    };
+                    if content.relates_to.event_id == poll_id {
+                        ends.push(PollEnd { sender, timestamp });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        match response.end {
+            Some(end) if !response.chunk.is_empty() => from = Some(end),
+            _ => return Ok(None),
+        }
+    }
+}
+
+/// Service for voting in and ending poll posts.
+pub struct PollService {
+    client: Client,
+}
+
+impl PollService {
+    /// Create a new PollService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Get a poll post with its current votes.
+    ///
+    /// # Errors
+    /// Returns an error if the poll is not found or messages cannot be loaded.
+    pub async fn get_results(
+        &self,
+        room_id: &RoomId,
+        poll_id: &EventId,
+    ) -> Result<PollResults, PollError> {
+        let room = self.room(room_id)?;
+        let poll = load_poll_events(&room, poll_id)
+            .await?
+            .ok_or(PollError::PollNotFound)?;
+        let answers: Vec<PollAnswer> = poll
+            .start
+            .poll_start
+            .answers
+            .iter()
+            .map(|answer| PollAnswer {
+                id: answer.id.clone(),
+                text: answer.text.clone(),
+            })
+            .collect();
+        let max_selections =
+            u32::try_from(u64::from(poll.start.poll_start.max_selections)).unwrap_or(u32::MAX);
+        Ok(PollResults {
+            poll_id: poll_id.to_owned(),
+            author: poll.author,
+            question: poll.start.poll_start.question.text,
+            answers: tally_poll(&answers, max_selections, &poll.votes, poll.ended_at),
+            max_selections,
+            ended: poll.ended_at.is_some(),
+        })
+    }
+
+    /// Vote for the given answers, replacing any earlier vote of the current user.
+    ///
+    /// Voting for no answers withdraws the user's vote.
+    ///
+    /// # Returns
+    /// The poll with the user's vote counted.
+    ///
+    /// # Errors
+    /// Returns an error if the poll is not found, has ended,
+    /// or the Matrix API call fails.
+    pub async fn vote(
+        &self,
+        room_id: &RoomId,
+        poll_id: &EventId,
+        answer_ids: Vec<String>,
+    ) -> Result<PollResults, PollError> {
+        let user_id = self.client.user_id().ok_or(PollError::NotLoggedIn)?;
+        let mut results = self.get_results(room_id, poll_id).await?;
+        if results.ended {
+            return Err(PollError::PollEnded);
+        }
+
+        let room = self.room(room_id)?;
+        room.send(UnstablePollResponseEventContent::new(
+            answer_ids.clone(),
+            poll_id.to_owned(),
+        ))
+        .await?;
+
+        // Count the vote at once rather than waiting for it to be loaded back
+        results.set_votes(user_id, &answer_ids);
+        Ok(results)
+    }
+
+    /// End a poll, after which new votes are ignored.
+    ///
+    /// Only the poll's author can end it. The end event's fallback text
+    /// announces the answers with the most votes.
+    ///
+    /// # Returns
+    /// The poll's final results.
+    ///
+    /// # Errors
+    /// Returns an error if the poll is not found, the current user isn't its
+    /// author, or the Matrix API call fails.
+    pub async fn end_poll(
+        &self,
+        room_id: &RoomId,
+        poll_id: &EventId,
+    ) -> Result<PollResults, PollError> {
+        let user_id = self.client.user_id().ok_or(PollError::NotLoggedIn)?;
+        let mut results = self.get_results(room_id, poll_id).await?;
+        if results.author != user_id {
+            return Err(PollError::NotAuthor);
+        }
+        if results.ended {
+            return Ok(results);
+        }
+
+        let winners: Vec<&str> = results
+            .winners()
+            .into_iter()
+            .map(|answer| answer.text.as_str())
+            .collect();
+        let text = if winners.is_empty() {
+            "The poll has ended with no votes".to_string()
+        } else {
+            format!("The poll has ended. Top answer: {}", winners.join(", "))
+        };
+        let room = self.room(room_id)?;
+        room.send(UnstablePollEndEventContent::new(text, poll_id.to_owned()))
+            .await?;
+        results.ended = true;
+        Ok(results)
+    }
+
+    fn room(&self, room_id: &RoomId) -> Result<Room, PollError> {
+        self.client.get_room(room_id).ok_or(PollError::RoomNotFound)
+    }
+}
+
+/// Errors that can occur with poll posts.
+#[derive(Debug, thiserror::Error)]
+pub enum PollError {
+    /// The requested room was not found.
+    #[error("Room not found")]
+    RoomNotFound,
+
+    /// User is not logged in.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The poll's start event was not found.
+    #[error("Poll not found")]
+    PollNotFound,
+
+    /// The poll has ended, so votes no longer count.
+    #[error("The poll has ended")]
+    PollEnded,
+
+    /// Only the poll's author can end it.
+    #[error("Only the author of a poll can end it")]
+    NotAuthor,
+
+    /// Too few or too many answers.
+    #[error("A poll needs between 2 and {MAX_POLL_ANSWERS} answers")]
+    InvalidAnswers,
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::user_id;
+
+    fn answers() -> Vec<PollAnswer> {
+        ["Tea", "Coffee", "Water"]
+            .iter()
+            .enumerate()
+            .map(|(index, text)| PollAnswer {
+                id: (index + 1).to_string(),
+                text: text.to_string(),
+            })
+            .collect()
+    }
+
+    fn vote(user: &str, answers: &[&str], ts: u32) -> PollVote {
+        PollVote {
+            user_id: user.try_into().unwrap(),
+            answers: answers.iter().map(|answer| answer.to_string()).collect(),
+            timestamp: MilliSecondsSinceUnixEpoch(ts.into()),
+        }
+    }
+
+    fn results(max_selections: u32, votes: &[PollVote]) -> PollResults {
+        let mut results = PollResults::new(
+            "$poll:example.org".try_into().unwrap(),
+            "@alice:example.org".try_into().unwrap(),
+            "What do you drink?".to_string(),
+            answers(),
+            max_selections,
+        );
+        results.answers = tally_poll(&answers(), max_selections, votes, None);
+        results
+    }
+
+    #[test]
+    fn test_tally_counts_latest_valid_votes() {
+        let tallied = tally_poll(
+            &answers(),
+            1,
+            &[
+                vote("@alice:example.org", &["1"], 1),
+                vote("@alice:example.org", &["2"], 2),
+                // Only the first answer counts in a single choice poll
+                vote("@bob:example.org", &["2", "3"], 3),
+                // Unknown answers are ignored
+                vote("@carol:example.org", &["42"], 4),
+                // Votes after the end don't count
+                vote("@dave:example.org", &["3"], 9),
+            ],
+            Some(MilliSecondsSinceUnixEpoch(5u32.into())),
+        );
+        let counts: Vec<usize> = tallied.iter().map(|answer| answer.voters.len()).collect();
+        assert_eq!(counts, [0, 2, 0]);
+    }
+
+    #[test]
+    fn test_only_allowed_users_end_polls() {
+        let end = |sender: &str, ts: u32| PollEnd {
+            sender: sender.try_into().unwrap(),
+            timestamp: MilliSecondsSinceUnixEpoch(ts.into()),
+        };
+        let author = user_id!("@alice:example.org");
+        let moderator = user_id!("@mod:example.org");
+        let ends = [
+            end("@alice:example.org", 9),
+            end("@mod:example.org", 7),
+            end("@mallory:example.org", 3),
+        ];
+        let can_redact = |user_id: &UserId| user_id == moderator;
+
+        // Newest first, as loaded, but the earliest allowed end counts
+        assert_eq!(
+            poll_ended_at(&ends, author, can_redact),
+            Some(MilliSecondsSinceUnixEpoch(7u32.into()))
+        );
+        assert_eq!(
+            poll_ended_at(&ends, author, |_| false),
+            Some(MilliSecondsSinceUnixEpoch(9u32.into()))
+        );
+        assert_eq!(poll_ended_at(&ends[2..], author, can_redact), None);
+    }
+
+    #[test]
+    fn test_percentages_and_winners() {
+        let results = results(
+            2,
+            &[
+                vote("@alice:example.org", &["1", "2"], 1),
+                vote("@bob:example.org", &["2"], 2),
+            ],
+        );
+        assert_eq!(results.voter_count(), 2);
+        assert_eq!(results.percentage(&results.answers[0]), 50);
+        assert_eq!(results.percentage(&results.answers[1]), 100);
+        assert_eq!(results.percentage(&results.answers[2]), 0);
+        let winners: Vec<&str> = results.winners().iter().map(|a| a.text.as_str()).collect();
+        assert_eq!(winners, ["Coffee"]);
+
+        assert!(results(1, &[]).winners().is_empty());
+    }
+
+    #[test]
+    fn test_toggle_vote() {
+        let alice = user_id!("@alice:example.org");
+        let single = results(1, &[vote("@alice:example.org", &["1"], 1)]);
+        assert_eq!(single.toggle_vote(alice, "2"), ["2"]);
+        assert!(single.toggle_vote(alice, "1").is_empty());
+
+        let multiple = results(2, &[vote("@alice:example.org", &["1"], 1)]);
+        assert_eq!(multiple.toggle_vote(alice, "2"), ["1", "2"]);
+        let full = results(2, &[vote("@alice:example.org", &["1", "2"], 1)]);
+        assert_eq!(full.toggle_vote(alice, "3"), ["1", "2"]);
+        assert_eq!(full.toggle_vote(alice, "1"), ["2"]);
+    }
+
+    #[test]
+    fn test_set_votes_replaces_earlier_vote() {
+        let alice = user_id!("@alice:example.org");
+        let mut results = results(1, &[vote("@alice:example.org", &["1"], 1)]);
+        results.set_votes(alice, &["2".to_string(), "3".to_string()]);
+        assert_eq!(results.votes_of(alice), ["2"]);
+        assert_eq!(results.voter_count(), 1);
+
+        results.set_votes(alice, &[]);
+        assert_eq!(results.voter_count(), 0);
+    }
+
+    #[test]
+    fn test_poll_content_round_trip() {
+        let content = poll_start_content("What do you drink?", &answers(), 1).unwrap();
+        let json = serde_json::to_value(&content).unwrap();
+        let (question, parsed_answers, max_selections) = poll_from_content(&json).unwrap();
+        assert_eq!(question, "What do you drink?");
+        assert_eq!(parsed_answers, answers());
+        assert_eq!(max_selections, 1);
+        assert_eq!(
+            content.text.as_deref(),
+            Some("What do you drink?\n1. Tea\n2. Coffee\n3. Water")
+        );
+
+        assert!(matches!(
+            poll_start_content("Anything?", &answers()[..1], 1),
+            Err(PollError::InvalidAnswers)
+        ));
+        assert!(poll_from_content(&serde_json::json!({ "msgtype": "m.text" })).is_none());
+    }
+}
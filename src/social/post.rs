@@ -2,18 +2,57 @@
 //!
 //! Posts are standard Matrix messages with optional social extensions.
 //! This module provides types for creating posts with various content types
-//! (text, images, videos, links) and converting them to Matrix message events.
+//! (text, images, videos, audio clips, links) and converting them to Matrix message events.
+//! A post can carry the license its content is shared under, which is stored
+//! in the message content's `org.social.license` field, and the authors of
+//! a collaborative post, stored in its `org.social.co_author` field
+//! (see [`CoAuthorService`](crate::social::CoAuthorService)). Every post
+//! records its intended audience in its `org.social.visibility` field, so that
+//! it isn't shown or shared beyond that audience. A post can also be tagged
+//! with a location, stored in its `org.social.location` field, which post
+//! cards show as a map thumbnail. Posts sent to several feeds at once share
+//! an ID in their `org.social.crosspost` field, so that the newsfeed shows
+//! them only once. Image posts can carry a description of the image for
+//! people who can't see it, stored in their `org.social.caption` field.
+//! Audio posts, e.g., voice notes, are sent as `m.audio` messages with their
+//! duration and waveform (see [`audio_clip`](crate::social::audio_clip)).
+//! Poll posts are sent as Matrix poll start events instead of messages
+//! (see [`poll`](crate::social::poll) and [`post_event_type()`]).
+//! Text posts written in Markdown are sent with an HTML-formatted body
+//! (see [`Post::markdown()`]), so bold, italic, strikethrough, code and
+//! lists show up in other Matrix clients too. Text between `||` markers is
+//! sent as a spoiler, which is hidden until it's tapped.
 
 use matrix_sdk::ruma::{
-    events::room::message::{
-        ImageMessageEventContent, MessageType, RoomMessageEventContent, VideoMessageEventContent,
+    events::{
+        relation::Thread,
+        room::message::{
+            AudioInfo, AudioMessageEventContent, FormattedBody, ImageMessageEventContent,
+            MessageType, Relation, RoomMessageEventContent, VideoMessageEventContent,
+        },
     },
-    MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedMxcUri, OwnedRoomId, OwnedUserId,
+    MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedMxcUri, OwnedRoomId, OwnedUserId, TransactionId,
 };
-use robrix_social_events::link_preview::LinkPreview;
-use std::collections::BTreeSet;
+use matrix_sdk::Client;
+use robrix_social_events::{
+    caption::{Caption, CAPTION_FIELD},
+    co_author::{CoAuthorship, CO_AUTHOR_FIELD},
+    crosspost::CROSSPOST_FIELD,
+    event::EventLocation,
+    license::{ContentLicense, LICENSE_FIELD},
+    link_preview::LinkPreview,
+    location::LOCATION_FIELD,
+    visibility::{PostVisibility, VISIBILITY_FIELD},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, time::Duration};
 
-use crate::social::feed_room::FeedPrivacy;
+use crate::social::{
+    audio_clip::AudioDetails,
+    availability::load_settings,
+    feed_room::FeedPrivacy,
+    poll::{poll_fallback_text, poll_start_content, PollAnswer, POLL_START_EVENT_TYPE},
+};
 
 /// A social media post ready to be sent to feed rooms.
 #[derive(Clone, Debug)]
@@ -24,6 +63,14 @@ pub struct Post {
     pub targets: Vec<OwnedRoomId>,
     /// Privacy levels this post is intended for.
     pub privacy_levels: Vec<FeedPrivacy>,
+    /// License the post's content is shared under.
+    pub license: Option<ContentLicense>,
+    /// Authors of the post, if it's a collaborative post.
+    pub co_authorship: Option<CoAuthorship>,
+    /// The place the post was made at or is about.
+    pub location: Option<EventLocation>,
+    /// ID shared by all copies of the post, if it's sent to several feeds.
+    pub crosspost_id: Option<String>,
 }
 
 impl Post {
@@ -37,7 +84,25 @@ impl Post {
             },
             targets: Vec::new(),
             privacy_levels: vec![FeedPrivacy::Public],
+            license: None,
+            co_authorship: None,
+            location: None,
+            crosspost_id: None,
+        }
+    }
+
+    /// Create a new text post from Markdown.
+    ///
+    /// If the text uses any formatting, it's sent with an HTML-formatted body
+    /// (see [`markdown_to_html()`]); the Markdown is kept as the plain body.
+    pub fn markdown(body: impl Into<String>) -> Self {
+        let body = body.into();
+        let html = markdown_to_html(&body);
+        let mut post = Self::text(body);
+        if let PostContent::Text { formatted_body, .. } = &mut post.content {
+            *formatted_body = html;
         }
+        post
     }
 
     /// Create a new post with an image.
@@ -49,9 +114,15 @@ impl Post {
                 thumbnail_uri: None,
                 width,
                 height,
+                size: None,
+                alt_text: None,
             },
             targets: Vec::new(),
             privacy_levels: vec![FeedPrivacy::Public],
+            license: None,
+            co_authorship: None,
+            location: None,
+            crosspost_id: None,
         }
     }
 
@@ -63,9 +134,33 @@ impl Post {
                 caption: None,
                 thumbnail_uri: None,
                 duration_ms: None,
+                size: None,
             },
             targets: Vec::new(),
             privacy_levels: vec![FeedPrivacy::Public],
+            license: None,
+            co_authorship: None,
+            location: None,
+            crosspost_id: None,
+        }
+    }
+
+    /// Create a new post with an audio clip, e.g., a voice note.
+    pub fn audio(mxc_uri: OwnedMxcUri, details: AudioDetails) -> Self {
+        Self {
+            content: PostContent::Audio {
+                mxc_uri,
+                caption: None,
+                duration_ms: details.duration,
+                waveform: details.waveform,
+                size: None,
+            },
+            targets: Vec::new(),
+            privacy_levels: vec![FeedPrivacy::Public],
+            license: None,
+            co_authorship: None,
+            location: None,
+            crosspost_id: None,
         }
     }
 
@@ -79,6 +174,42 @@ impl Post {
             },
             targets: Vec::new(),
             privacy_levels: vec![FeedPrivacy::Public],
+            license: None,
+            co_authorship: None,
+            location: None,
+            crosspost_id: None,
+        }
+    }
+
+    /// Create a new poll post asking the given question.
+    ///
+    /// Voters pick one of the answers, or as many as they like if `allow_multiple` is set.
+    pub fn poll(question: impl Into<String>, answers: Vec<String>, allow_multiple: bool) -> Self {
+        let answers: Vec<PollAnswer> = answers
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| PollAnswer {
+                id: (index + 1).to_string(),
+                text,
+            })
+            .collect();
+        let max_selections = if allow_multiple {
+            answers.len() as u32
+        } else {
+            1
+        };
+        Self {
+            content: PostContent::Poll {
+                question: question.into(),
+                answers,
+                max_selections,
+            },
+            targets: Vec::new(),
+            privacy_levels: vec![FeedPrivacy::Public],
+            license: None,
+            co_authorship: None,
+            location: None,
+            crosspost_id: None,
         }
     }
 
@@ -89,12 +220,42 @@ impl Post {
     }
 
     /// Set the privacy levels for this post.
+    ///
+    /// A post for several privacy levels is cross-posted to several feeds,
+    /// so it gets a cross-post ID if it doesn't have one yet.
     pub fn with_privacy(mut self, privacy_levels: Vec<FeedPrivacy>) -> Self {
+        if privacy_levels.len() > 1 && self.crosspost_id.is_none() {
+            self.crosspost_id = Some(TransactionId::new().to_string());
+        }
         self.privacy_levels = privacy_levels;
         self
     }
 
-    /// Add a caption to image or video content.
+    /// Set the ID shared by all copies of the post.
+    pub fn with_crosspost_id(mut self, crosspost_id: Option<String>) -> Self {
+        self.crosspost_id = crosspost_id;
+        self
+    }
+
+    /// Set the license the post's content is shared under.
+    pub fn with_license(mut self, license: Option<ContentLicense>) -> Self {
+        self.license = license;
+        self
+    }
+
+    /// Set the authors of a collaborative post.
+    pub fn with_co_authorship(mut self, co_authorship: Option<CoAuthorship>) -> Self {
+        self.co_authorship = co_authorship;
+        self
+    }
+
+    /// Tag the post with a location.
+    pub fn with_location(mut self, location: Option<EventLocation>) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// Add a caption to image, video or audio content.
     pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
         let caption_str = caption.into();
         match &mut self.content {
@@ -104,26 +265,250 @@ impl Post {
             PostContent::Video { caption, .. } => {
                 *caption = Some(caption_str);
             }
+            PostContent::Audio { caption, .. } => {
+                *caption = Some(caption_str);
+            }
             PostContent::Link { comment, .. } => {
                 *comment = Some(caption_str);
             }
-            PostContent::Text { .. } => {
-                // Text posts don't have captions, ignore
+            PostContent::Text { .. } | PostContent::Poll { .. } => {
+                // Text and poll posts don't have captions, ignore
             }
         }
         self
     }
 
+    /// Describe the image of an image post for people who can't see it,
+    /// or remove the description if `None`.
+    ///
+    /// Other posts don't have images, so this is ignored for them.
+    pub fn with_alt_text(mut self, alt_text: Option<String>) -> Self {
+        if let PostContent::Image {
+            alt_text: image_alt_text,
+            ..
+        } = &mut self.content
+        {
+            *image_alt_text = alt_text;
+        }
+        self
+    }
+
+    /// Get the audience the post is intended for,
+    /// i.e., the most private of its privacy levels.
+    pub fn visibility(&self) -> Option<PostVisibility> {
+        self.privacy_levels
+            .iter()
+            .max()
+            .map(|&privacy| privacy.into())
+    }
+
     /// Convert the post content to a Matrix message.
     pub fn into_room_message(&self) -> RoomMessageEventContent {
         self.content.into_room_message()
     }
+
+    /// Convert the post to raw Matrix message content, including its license,
+    /// co-authors, intended audience, location, cross-post ID, the
+    /// alt text of its image and the waveform of its audio clip.
+    ///
+    /// The content should be sent as an event of the type given by
+    /// [`post_event_type()`], e.g., with [`Room::send_raw()`](matrix_sdk::Room::send_raw).
+    pub fn into_raw_content(&self) -> Result<serde_json::Value, serde_json::Error> {
+        let mut json = if let PostContent::Poll {
+            question,
+            answers,
+            max_selections,
+        } = &self.content
+        {
+            let content = poll_start_content(question, answers, *max_selections)
+                .map_err(<serde_json::Error as serde::ser::Error>::custom)?;
+            serde_json::to_value(content)?
+        } else {
+            serde_json::to_value(self.into_room_message())?
+        };
+        if let Some(object) = json.as_object_mut() {
+            if let Some(license) = self.license {
+                object.insert(LICENSE_FIELD.to_string(), serde_json::to_value(license)?);
+            }
+            if let Some(co_authorship) = &self.co_authorship {
+                object.insert(
+                    CO_AUTHOR_FIELD.to_string(),
+                    serde_json::to_value(co_authorship)?,
+                );
+            }
+            if let Some(visibility) = self.visibility() {
+                object.insert(
+                    VISIBILITY_FIELD.to_string(),
+                    serde_json::to_value(visibility)?,
+                );
+            }
+            if let Some(location) = &self.location {
+                object.insert(LOCATION_FIELD.to_string(), serde_json::to_value(location)?);
+            }
+            if let Some(crosspost_id) = &self.crosspost_id {
+                object.insert(CROSSPOST_FIELD.to_string(), crosspost_id.as_str().into());
+            }
+            if let PostContent::Image {
+                caption,
+                alt_text: Some(alt_text),
+                ..
+            } = &self.content
+            {
+                let caption = Caption {
+                    text: caption.clone().unwrap_or_default(),
+                    formatted_text: None,
+                    alt_text: Some(alt_text.clone()),
+                };
+                object.insert(CAPTION_FIELD.to_string(), serde_json::to_value(caption)?);
+            }
+            if let PostContent::Audio {
+                duration_ms,
+                waveform,
+                ..
+            } = &self.content
+            {
+                let details = AudioDetails {
+                    duration: *duration_ms,
+                    waveform: waveform.clone(),
+                };
+                details.insert_into(object);
+            }
+        }
+        Ok(json)
+    }
+}
+
+impl From<FeedPrivacy> for PostVisibility {
+    fn from(privacy: FeedPrivacy) -> Self {
+        match privacy {
+            FeedPrivacy::Public => Self::Public,
+            FeedPrivacy::Friends => Self::Friends,
+            FeedPrivacy::CloseFriends => Self::CloseFriends,
+        }
+    }
+}
+
+/// The format of the HTML-formatted bodies of messages.
+const HTML_FORMAT: &str = "org.matrix.custom.html";
+
+/// The Markdown marker around spoilers in a post's text, e.g., `||hidden||`.
+const SPOILER_MARKER: &str = "||";
+
+/// Get the type of the event a post with the given raw content is sent as,
+/// i.e., a poll start event for poll posts and `m.room.message` otherwise.
+pub fn post_event_type(content: &serde_json::Value) -> &'static str {
+    if content.get(POLL_START_EVENT_TYPE).is_some() {
+        POLL_START_EVENT_TYPE
+    } else {
+        "m.room.message"
+    }
+}
+
+/// Get the license of a post from its raw message content.
+///
+/// Licenses this version of the app doesn't know are treated as missing.
+pub fn license_from_content(content: &serde_json::Value) -> Option<ContentLicense> {
+    serde_json::from_value(content.get(LICENSE_FIELD)?.clone()).ok()
+}
+
+/// Get the intended audience of a post from its raw message content.
+///
+/// Posts from before audiences were recorded have none.
+pub fn visibility_from_content(content: &serde_json::Value) -> Option<PostVisibility> {
+    serde_json::from_value(content.get(VISIBILITY_FIELD)?.clone()).ok()
+}
+
+/// Get the location a post is tagged with from its raw message content.
+pub fn location_from_content(content: &serde_json::Value) -> Option<EventLocation> {
+    serde_json::from_value(content.get(LOCATION_FIELD)?.clone()).ok()
+}
+
+/// Get the description of the image of a post from its raw message content, if it has one.
+pub fn alt_text_from_content(content: &serde_json::Value) -> Option<String> {
+    serde_json::from_value::<Caption>(content.get(CAPTION_FIELD)?.clone())
+        .ok()?
+        .alt_text
+}
+
+/// Get the ID shared by all copies of a cross-posted post from its raw message content.
+pub fn crosspost_id_from_content(content: &serde_json::Value) -> Option<String> {
+    content.get(CROSSPOST_FIELD)?.as_str().map(str::to_owned)
+}
+
+/// Convert the Markdown text of a post to HTML, if it uses any formatting.
+///
+/// Text between `||` markers becomes a spoiler (see [`spoilers_to_html()`]).
+/// Returns `None` for plain text, which needs no formatted body.
+pub fn markdown_to_html(text: &str) -> Option<String> {
+    FormattedBody::markdown(spoilers_to_html(text)).map(|formatted| formatted.body)
+}
+
+/// Replace the `||` markers around spoilers with Matrix spoiler spans,
+/// leaving code and unpaired markers as they are.
+fn spoilers_to_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    for (i, segment) in text.split('`').enumerate() {
+        if i > 0 {
+            html.push('`');
+        }
+        // Odd segments are inside code spans or blocks
+        if i % 2 == 1 {
+            html.push_str(segment);
+            continue;
+        }
+        let parts: Vec<&str> = segment.split(SPOILER_MARKER).collect();
+        for (j, part) in parts.iter().enumerate() {
+            if j % 2 == 1 && j == parts.len() - 1 {
+                html.push_str(SPOILER_MARKER);
+            } else if j % 2 == 1 {
+                html.push_str("<span data-mx-spoiler>");
+            } else if j > 0 {
+                html.push_str("</span>");
+            }
+            html.push_str(part);
+        }
+    }
+    html
+}
+
+/// Get the HTML-formatted body of a post from its raw message content, if it has one.
+pub fn formatted_body_from_content(content: &serde_json::Value) -> Option<String> {
+    if content.get("format")?.as_str()? != HTML_FORMAT {
+        return None;
+    }
+    content.get("formatted_body")?.as_str().map(str::to_owned)
+}
+
+/// Get the license the current user preselects for new posts, from their social settings.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded.
+pub async fn load_default_license(
+    client: &Client,
+) -> Result<Option<ContentLicense>, matrix_sdk::Error> {
+    Ok(load_settings(client).await?.default_license)
+}
+
+/// Set the license the current user preselects for new posts,
+/// or `None` to make posts without a license by default.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn save_default_license(
+    client: &Client,
+    license: Option<ContentLicense>,
+) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.default_license = license;
+    client.account().set_account_data(settings).await?;
+    Ok(())
 }
 
 /// Post content types.
 ///
 /// Different types of content that can be included in a social post.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum PostContent {
     /// Text-only post, optionally with HTML formatting and mentions.
     Text {
@@ -146,6 +531,12 @@ pub enum PostContent {
         width: u32,
         /// Image height in pixels.
         height: u32,
+        /// Size of the image file in bytes, if known.
+        #[serde(default)]
+        size: Option<u64>,
+        /// Description of the image for people who can't see it, e.g., read out by screen readers.
+        #[serde(default)]
+        alt_text: Option<String>,
     },
     /// Video post with optional caption.
     Video {
@@ -157,6 +548,25 @@ pub enum PostContent {
         thumbnail_uri: Option<OwnedMxcUri>,
         /// Duration in milliseconds.
         duration_ms: Option<u64>,
+        /// Size of the video file in bytes, if known.
+        #[serde(default)]
+        size: Option<u64>,
+    },
+    /// Audio clip post, e.g., a voice note, with optional caption.
+    Audio {
+        /// MXC URI of the uploaded audio clip.
+        mxc_uri: OwnedMxcUri,
+        /// Optional caption for the audio clip.
+        caption: Option<String>,
+        /// Duration in milliseconds.
+        duration_ms: Option<u64>,
+        /// Amplitudes of the clip over time, shown before it's played
+        /// (see [`AudioDetails::waveform`]).
+        #[serde(default)]
+        waveform: Vec<u16>,
+        /// Size of the audio file in bytes, if known.
+        #[serde(default)]
+        size: Option<u64>,
     },
     /// Link share with optional preview.
     Link {
@@ -167,9 +577,60 @@ pub enum PostContent {
         /// Optional rich link preview data (boxed to reduce enum size).
         preview: Box<Option<LinkPreview>>,
     },
+    /// Poll asking followers a question.
+    Poll {
+        /// The question asked.
+        question: String,
+        /// The possible answers, in the order they're shown.
+        answers: Vec<PollAnswer>,
+        /// How many answers each voter can pick.
+        max_selections: u32,
+    },
 }
 
 impl PostContent {
+    /// Get the user-written text of this post, if any.
+    ///
+    /// This is the body of a text post, the caption of a media post,
+    /// the comment on a shared link, or the question of a poll.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::Text { body, .. } => Some(body),
+            Self::Image { caption, .. }
+            | Self::Video { caption, .. }
+            | Self::Audio { caption, .. } => caption.as_deref(),
+            Self::Link { comment, .. } => comment.as_deref(),
+            Self::Poll { question, .. } => Some(question),
+        }
+    }
+
+    /// Check whether this content is the same as another's,
+    /// e.g., to recognize a post sent to several feeds.
+    ///
+    /// Formatting and mentions are ignored; media must be the same upload.
+    pub fn has_same_content(&self, other: &PostContent) -> bool {
+        let same_kind = match (self, other) {
+            (Self::Text { .. }, Self::Text { .. }) => true,
+            (Self::Image { mxc_uri: a, .. }, Self::Image { mxc_uri: b, .. })
+            | (Self::Video { mxc_uri: a, .. }, Self::Video { mxc_uri: b, .. })
+            | (Self::Audio { mxc_uri: a, .. }, Self::Audio { mxc_uri: b, .. }) => a == b,
+            (Self::Link { url: a, .. }, Self::Link { url: b, .. }) => a == b,
+            (Self::Poll { answers: a, .. }, Self::Poll { answers: b, .. }) => a == b,
+            _ => false,
+        };
+        same_kind && self.text() == other.text()
+    }
+
+    /// Convert this post content to a reply to a post, sent in the post's thread.
+    ///
+    /// Unlike a comment, the reply is explicitly addressed to the post,
+    /// so clients without threads show it as a reply quoting the post.
+    pub fn into_thread_reply(&self, post_id: OwnedEventId) -> RoomMessageEventContent {
+        let mut content = self.into_room_message();
+        content.relates_to = Some(Relation::Thread(Thread::reply(post_id.clone(), post_id)));
+        content
+    }
+
     /// Convert this post content to a Matrix room message.
     pub fn into_room_message(&self) -> RoomMessageEventContent {
         match self {
@@ -199,6 +660,8 @@ impl PostContent {
                 thumbnail_uri: _,
                 width: _,
                 height: _,
+                size: _,
+                alt_text: _,
             } => {
                 let body = caption.clone().unwrap_or_else(|| "Image".to_string());
                 let content = ImageMessageEventContent::plain(body, mxc_uri.clone());
@@ -211,12 +674,28 @@ impl PostContent {
                 caption,
                 thumbnail_uri: _,
                 duration_ms: _,
+                size: _,
             } => {
                 let body = caption.clone().unwrap_or_else(|| "Video".to_string());
                 let content = VideoMessageEventContent::plain(body, mxc_uri.clone());
                 // Note: Thumbnail info and duration could be added here
                 RoomMessageEventContent::new(MessageType::Video(content))
             }
+            Self::Audio {
+                mxc_uri,
+                caption,
+                duration_ms,
+                waveform: _,
+                size: _,
+            } => {
+                let body = caption.clone().unwrap_or_else(|| "Voice note".to_string());
+                let mut info = AudioInfo::new();
+                info.duration = duration_ms.map(Duration::from_millis);
+                let content =
+                    AudioMessageEventContent::plain(body, mxc_uri.clone()).info(Box::new(info));
+                // The waveform is added to the raw content, see `Post::into_raw_content()`
+                RoomMessageEventContent::new(MessageType::Audio(content))
+            }
             Self::Link {
                 url,
                 comment,
@@ -276,6 +755,12 @@ impl PostContent {
                     RoomMessageEventContent::text_plain(body)
                 }
             }
+            Self::Poll {
+                question, answers, ..
+            } => {
+                // Poll posts are sent as poll start events, see `Post::into_raw_content()`
+                RoomMessageEventContent::text_plain(poll_fallback_text(question, answers))
+            }
         }
     }
 }
@@ -342,12 +827,24 @@ pub struct FeedPost {
     pub content: PostContent,
     /// Post metadata (timestamps, author, etc.).
     pub metadata: PostMetadata,
+    /// License the post's content is shared under.
+    pub license: Option<ContentLicense>,
 }
 
 impl FeedPost {
     /// Create a new feed post from content and metadata.
     pub fn new(content: PostContent, metadata: PostMetadata) -> Self {
-        Self { content, metadata }
+        Self {
+            content,
+            metadata,
+            license: None,
+        }
+    }
+
+    /// Set the license the post's content is shared under.
+    pub fn with_license(mut self, license: Option<ContentLicense>) -> Self {
+        self.license = license;
+        self
     }
 
     /// Get the event ID of this post.
@@ -427,10 +924,184 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_alt_text_round_trips_through_raw_content() {
+        let mxc: OwnedMxcUri = "mxc://example.org/abc123".into();
+        let post = Post::image(mxc, 800, 600)
+            .with_caption("A nice photo")
+            .with_alt_text(Some("A sunset over the sea".to_string()));
+        let content = post.into_raw_content().unwrap();
+        assert_eq!(content[CAPTION_FIELD]["text"], "A nice photo");
+        assert_eq!(
+            alt_text_from_content(&content).as_deref(),
+            Some("A sunset over the sea")
+        );
+
+        // Only images have alt text
+        let post = Post::text("Hello").with_alt_text(Some("Ignored".to_string()));
+        let content = post.into_raw_content().unwrap();
+        assert!(content.get(CAPTION_FIELD).is_none());
+    }
+
+    #[test]
+    fn test_audio_post_is_a_voice_note() {
+        let mxc: OwnedMxcUri = "mxc://example.org/voice".into();
+        let details = AudioDetails {
+            duration: Some(12_000),
+            waveform: vec![100, 800, 300],
+        };
+        let post = Post::audio(mxc, details.clone());
+        let msg = post.into_room_message();
+        let MessageType::Audio(audio) = &msg.msgtype else {
+            panic!("expected an audio message");
+        };
+        assert_eq!(audio.body, "Voice note");
+        assert_eq!(
+            audio.info.as_ref().and_then(|info| info.duration),
+            Some(Duration::from_secs(12))
+        );
+
+        let content = post.into_raw_content().unwrap();
+        assert_eq!(
+            crate::social::audio_clip::audio_details_from_content(&content),
+            Some(details)
+        );
+    }
+
+    #[test]
+    fn test_poll_post_is_a_poll_start_event() {
+        let answers = vec!["Tea".to_string(), "Coffee".to_string()];
+        let post = Post::poll("What do you drink?", answers, true)
+            .with_privacy(vec![FeedPrivacy::Friends]);
+        let content = post.into_raw_content().unwrap();
+        assert_eq!(post_event_type(&content), POLL_START_EVENT_TYPE);
+        assert_eq!(content[VISIBILITY_FIELD], "friends");
+        let (question, answers, max_selections) =
+            crate::social::poll::poll_from_content(&content).unwrap();
+        assert_eq!(question, "What do you drink?");
+        assert_eq!(answers.len(), 2);
+        assert_eq!(max_selections, 2);
+
+        let text = Post::text("Hello").into_raw_content().unwrap();
+        assert_eq!(post_event_type(&text), "m.room.message");
+
+        // A poll needs at least two answers
+        let post = Post::poll("Anything?", vec!["Yes".to_string()], false);
+        assert!(post.into_raw_content().is_err());
+    }
+
     #[test]
     fn test_text_to_room_message() {
         let post = Post::text("Hello");
         let msg = post.into_room_message();
         assert!(matches!(msg.msgtype, MessageType::Text(_)));
     }
+
+    #[test]
+    fn test_markdown_posts_are_formatted() {
+        let html = markdown_to_html("**Bold**, _italic_, ~~gone~~ and `code`").unwrap();
+        assert!(html.contains("<strong>Bold</strong>"));
+        assert!(html.contains("<em>italic</em>"));
+        assert!(html.contains("<del>gone</del>"));
+        assert!(html.contains("<code>code</code>"));
+        let list = markdown_to_html("- Tea\n- Coffee").unwrap();
+        assert!(list.contains("<ul>"));
+        assert_eq!(markdown_to_html("Just text"), None);
+
+        let content = Post::markdown("**Hello**").into_raw_content().unwrap();
+        assert_eq!(content["body"], "**Hello**");
+        let html = formatted_body_from_content(&content).unwrap();
+        assert!(html.contains("<strong>Hello</strong>"));
+        let plain = Post::markdown("Hello").into_raw_content().unwrap();
+        assert_eq!(formatted_body_from_content(&plain), None);
+    }
+
+    #[test]
+    fn test_spoilers_are_formatted() {
+        assert_eq!(
+            spoilers_to_html("It was ||the butler|| all along"),
+            "It was <span data-mx-spoiler>the butler</span> all along"
+        );
+        // Code and unpaired markers are left as they are
+        assert_eq!(spoilers_to_html("`a || b || c`"), "`a || b || c`");
+        assert_eq!(spoilers_to_html("a || b"), "a || b");
+        assert_eq!(
+            spoilers_to_html("||a|| and ||b"),
+            "<span data-mx-spoiler>a</span> and ||b"
+        );
+
+        let html = markdown_to_html("||**Hidden**||").unwrap();
+        assert!(html.contains("<span data-mx-spoiler><strong>Hidden</strong></span>"));
+    }
+
+    #[test]
+    fn test_thread_reply_replies_to_the_post() {
+        let post_id: OwnedEventId = "$post:example.org".try_into().unwrap();
+        let post = Post::text("Agreed!");
+        let msg = post.content.into_thread_reply(post_id.clone());
+        let Some(Relation::Thread(thread)) = msg.relates_to else {
+            panic!("not a thread reply");
+        };
+        assert_eq!(thread.event_id, post_id);
+        assert_eq!(thread.in_reply_to.unwrap().event_id, post_id);
+        assert!(!thread.is_falling_back);
+    }
+
+    #[test]
+    fn test_license_round_trips_through_raw_content() {
+        let post = Post::text("Hello").with_license(Some(ContentLicense::CcBySa));
+        let content = post.into_raw_content().unwrap();
+        assert_eq!(content[LICENSE_FIELD], "CC-BY-SA-4.0");
+        assert_eq!(license_from_content(&content), Some(ContentLicense::CcBySa));
+
+        let unlicensed = Post::text("Hello").into_raw_content().unwrap();
+        assert!(unlicensed.get(LICENSE_FIELD).is_none());
+        assert_eq!(license_from_content(&unlicensed), None);
+    }
+
+    #[test]
+    fn test_visibility_is_the_most_private_audience() {
+        let post =
+            Post::text("Hello").with_privacy(vec![FeedPrivacy::Friends, FeedPrivacy::Public]);
+        let content = post.into_raw_content().unwrap();
+        assert_eq!(content[VISIBILITY_FIELD], "friends");
+        assert_eq!(
+            visibility_from_content(&content),
+            Some(PostVisibility::Friends)
+        );
+
+        let unmarked = Post::text("Hello").with_privacy(Vec::new());
+        assert_eq!(unmarked.visibility(), None);
+    }
+
+    #[test]
+    fn test_location_round_trips_through_raw_content() {
+        let location = EventLocation {
+            name: "Central Park".to_string(),
+            address: None,
+            geo: Some("geo:40.7829,-73.9654".to_string()),
+        };
+        let post = Post::text("Hello").with_location(Some(location));
+        let content = post.into_raw_content().unwrap();
+        assert_eq!(content[LOCATION_FIELD]["name"], "Central Park");
+        let parsed = location_from_content(&content).unwrap();
+        assert_eq!(parsed.geo.as_deref(), Some("geo:40.7829,-73.9654"));
+
+        let untagged = Post::text("Hello").into_raw_content().unwrap();
+        assert!(location_from_content(&untagged).is_none());
+    }
+
+    #[test]
+    fn test_cross_posts_share_an_id() {
+        let post =
+            Post::text("Hello").with_privacy(vec![FeedPrivacy::Public, FeedPrivacy::Friends]);
+        let crosspost_id = post.crosspost_id.clone().unwrap();
+        let content = post.into_raw_content().unwrap();
+        assert_eq!(crosspost_id_from_content(&content), Some(crosspost_id));
+
+        let single = Post::text("Hello").with_privacy(vec![FeedPrivacy::Friends]);
+        assert!(single.crosspost_id.is_none());
+        let content = single.into_raw_content().unwrap();
+        assert!(crosspost_id_from_content(&content).is_none());
+    }
 }
@@ -0,0 +1,304 @@
+//! Audience insights for the user's own posts.
+//!
+//! Finds the time of day when the user's audience usually interacts with
+//! their posts, based on the timestamps of reactions and comments from
+//! other users, so the composer can suggest a good time to post.
+//! All insights are computed locally from the user's feed rooms; nothing
+//! is sent to the homeserver.
+
+use chrono::{DateTime, Local, Timelike};
+use matrix_sdk::{
+    room::{MessagesOptions, Room},
+    ruma::{
+        events::{
+            room::message::Relation, AnySyncMessageLikeEvent, AnySyncTimelineEvent,
+            SyncMessageLikeEvent,
+        },
+        OwnedEventId, UserId,
+    },
+    Client,
+};
+use std::collections::HashSet;
+
+use crate::social::{
+    events::timing::now_ms,
+    feed_room::{FeedPrivacy, FeedRoomService},
+};
+
+/// How far back to look for interactions with the user's posts.
+pub const ACTIVITY_LOOKBACK_MS: u64 = 90 * 24 * 60 * 60 * 1000;
+
+/// Minimum number of interactions needed before suggesting a time to post.
+pub const MIN_ACTIVITY_SAMPLES: u32 = 10;
+
+/// Length of the suggested posting window, in hours.
+pub const ACTIVE_WINDOW_HOURS: u32 = 2;
+
+/// Number of interactions per local hour of the day.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HourlyActivity {
+    counts: [u32; 24],
+}
+
+impl HourlyActivity {
+    /// Record an interaction at the given local hour (0-23).
+    pub fn add(&mut self, hour: u32) {
+        if let Some(count) = self.counts.get_mut(hour as usize) {
+            *count += 1;
+        }
+    }
+
+    /// Get the total number of recorded interactions.
+    pub fn total(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+
+    /// Find the `hours`-long window of the day with the most interactions.
+    ///
+    /// Windows may wrap around midnight. Returns `None` if fewer than
+    /// [`MIN_ACTIVITY_SAMPLES`] interactions were recorded.
+    pub fn best_window(&self, hours: u32) -> Option<ActiveWindow> {
+        let total = self.total();
+        if total < MIN_ACTIVITY_SAMPLES || hours == 0 || hours > 24 {
+            return None;
+        }
+
+        let window_count = |start: u32| -> u32 {
+            (start..start + hours)
+                .map(|hour| self.counts[(hour % 24) as usize])
+                .sum()
+        };
+        // The earliest start hour wins ties
+        let start_hour = (0..24).rev().max_by_key(|&start| window_count(start))?;
+        Some(ActiveWindow {
+            start_hour,
+            end_hour: (start_hour + hours) % 24,
+            share: window_count(start_hour) as f32 / total as f32,
+        })
+    }
+}
+
+/// A time window of the day in which the user's audience is most active.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActiveWindow {
+    /// First local hour of the window (0-23).
+    pub start_hour: u32,
+    /// Local hour at which the window ends, exclusive (0-23).
+    pub end_hour: u32,
+    /// Fraction of all interactions that happened in this window.
+    pub share: f32,
+}
+
+impl ActiveWindow {
+    /// Get a short hint for the composer, e.g.,
+    /// "Your friends are usually active around 7–9pm".
+    pub fn hint_text(&self) -> String {
+        format!(
+            "Your friends are usually active around {}",
+            format_hour_range(self.start_hour, self.end_hour)
+        )
+    }
+}
+
+/// Format a range of hours in 12-hour time, e.g., "7–9pm" or "11am–1pm".
+fn format_hour_range(start_hour: u32, end_hour: u32) -> String {
+    let hour_12 = |hour: u32| -> (u32, &'static str) {
+        let suffix = if hour % 24 < 12 { "am" } else { "pm" };
+        match hour % 12 {
+            0 => (12, suffix),
+            h => (h, suffix),
+        }
+    };
+    let (start, start_suffix) = hour_12(start_hour);
+    let (end, end_suffix) = hour_12(end_hour);
+    if start_suffix == end_suffix {
+        format!("{}–{}{}", start, end, end_suffix)
+    } else {
+        format!("{}{}–{}{}", start, start_suffix, end, end_suffix)
+    }
+}
+
+/// Service for computing insights about the user's own posts.
+pub struct PostInsightsService {
+    client: Client,
+}
+
+impl PostInsightsService {
+    /// Create a new PostInsightsService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Count the interactions with the user's recent posts by local hour.
+    ///
+    /// Reactions and comments by other users on the user's posts of the last
+    /// [`ACTIVITY_LOOKBACK_MS`] in all of their feeds are counted.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or messages cannot be loaded.
+    pub async fn audience_activity(&self) -> Result<HourlyActivity, PostInsightsError> {
+        let user_id = self
+            .client
+            .user_id()
+            .ok_or(PostInsightsError::NotLoggedIn)?;
+        let since_ms = now_ms().saturating_sub(ACTIVITY_LOOKBACK_MS);
+        let feed_service = FeedRoomService::new(self.client.clone());
+
+        let mut activity = HourlyActivity::default();
+        for privacy in [
+            FeedPrivacy::Public,
+            FeedPrivacy::Friends,
+            FeedPrivacy::CloseFriends,
+        ] {
+            let Some(room) = feed_service.find_joined_feed(user_id, privacy) else {
+                continue;
+            };
+            for timestamp in load_interaction_timestamps(&room, user_id, since_ms).await? {
+                let local_hour = DateTime::from_timestamp_millis(timestamp as i64)
+                    .map(|dt| dt.with_timezone(&Local).hour());
+                if let Some(hour) = local_hour {
+                    activity.add(hour);
+                }
+            }
+        }
+        Ok(activity)
+    }
+
+    /// Suggest a time of day to post, based on when the user's audience is active.
+    ///
+    /// Returns `None` if there isn't enough activity to make a suggestion.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or messages cannot be loaded.
+    pub async fn best_time_to_post(&self) -> Result<Option<ActiveWindow>, PostInsightsError> {
+        Ok(self
+            .audience_activity()
+            .await?
+            .best_window(ACTIVE_WINDOW_HOURS))
+    }
+}
+
+/// Load the timestamps of reactions and comments by other users on the
+/// user's posts in a feed room, going back to `since_ms`.
+async fn load_interaction_timestamps(
+    room: &Room,
+    user_id: &UserId,
+    since_ms: u64,
+) -> Result<Vec<u64>, PostInsightsError> {
+    let mut own_posts: HashSet<OwnedEventId> = HashSet::new();
+    let mut interactions: Vec<(OwnedEventId, u64)> = Vec::new();
+    let mut from = None;
+    'paginate: loop {
+        let mut options = MessagesOptions::backward();
+        options.from = from.take();
+        let response = room
+            .messages(options)
+            .await
+            .map_err(PostInsightsError::MatrixError)?;
+
+        for event in &response.chunk {
+            let Ok(AnySyncTimelineEvent::MessageLike(event)) = event.raw().deserialize() else {
+                continue;
+            };
+            let timestamp: u64 = event.origin_server_ts().get().into();
+            if timestamp < since_ms {
+                break 'paginate;
+            }
+            let from_other = event.sender() != user_id;
+
+            match event {
+                AnySyncMessageLikeEvent::Reaction(SyncMessageLikeEvent::Original(reaction))
+                    if from_other =>
+                {
+                    interactions.push((reaction.content.relates_to.event_id, timestamp));
+                }
+                AnySyncMessageLikeEvent::RoomMessage(SyncMessageLikeEvent::Original(message)) => {
+                    match (from_other, message.content.relates_to) {
+                        (false, None) => {
+                            own_posts.insert(message.event_id);
+                        }
+                        (true, Some(Relation::Reply { in_reply_to })) => {
+                            interactions.push((in_reply_to.event_id, timestamp));
+                        }
+                        (true, Some(Relation::Thread(thread))) => {
+                            interactions.push((thread.event_id, timestamp));
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        match response.end {
+            Some(end) if !response.chunk.is_empty() => from = Some(end),
+            _ => break,
+        }
+    }
+
+    Ok(interactions
+        .into_iter()
+        .filter(|(target, _)| own_posts.contains(target))
+        .map(|(_, timestamp)| timestamp)
+        .collect())
+}
+
+/// Errors that can occur when computing post insights.
+#[derive(Debug, thiserror::Error)]
+pub enum PostInsightsError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(hours: &[(u32, u32)]) -> HourlyActivity {
+        let mut activity = HourlyActivity::default();
+        for &(hour, count) in hours {
+            for _ in 0..count {
+                activity.add(hour);
+            }
+        }
+        activity
+    }
+
+    #[test]
+    fn test_no_suggestion_without_enough_activity() {
+        let activity = activity(&[(19, MIN_ACTIVITY_SAMPLES - 1)]);
+        assert_eq!(activity.best_window(ACTIVE_WINDOW_HOURS), None);
+    }
+
+    #[test]
+    fn test_best_window_finds_busiest_hours() {
+        let activity = activity(&[(8, 3), (19, 6), (20, 5), (21, 1)]);
+        let window = activity.best_window(2).unwrap();
+        assert_eq!((window.start_hour, window.end_hour), (19, 21));
+        assert_eq!(window.share, 11.0 / 15.0);
+        assert_eq!(
+            window.hint_text(),
+            "Your friends are usually active around 7–9pm"
+        );
+    }
+
+    #[test]
+    fn test_best_window_wraps_around_midnight() {
+        let activity = activity(&[(23, 6), (0, 6), (12, 5)]);
+        let window = activity.best_window(2).unwrap();
+        assert_eq!((window.start_hour, window.end_hour), (23, 1));
+    }
+
+    #[test]
+    fn test_format_hour_range() {
+        assert_eq!(format_hour_range(19, 21), "7–9pm");
+        assert_eq!(format_hour_range(11, 13), "11am–1pm");
+        assert_eq!(format_hour_range(23, 1), "11pm–1am");
+        assert_eq!(format_hour_range(0, 2), "12–2am");
+    }
+}
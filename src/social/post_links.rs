@@ -0,0 +1,263 @@
+//! Tappable links, mentions and hashtags in the text of posts.
+//!
+//! Before a post's text is shown, [`post_text_html()`] turns the bare URLs,
+//! user IDs (e.g., `@alice:example.org`) and hashtags (e.g., `#sunset`) in it
+//! into links. Mentions link to the user's `matrix.to` URI, so they're shown
+//! as pills, and hashtags link to a [`HASHTAG_LINK_PREFIX`] URI. When a link
+//! is tapped, [`PostLink::from_url()`] tells which kind of link it was.
+//!
+//! Text inside existing links and code is left as it is.
+
+use matrix_sdk::ruma::{OwnedUserId, UserId};
+use std::fmt::Write;
+
+use crate::social::post::markdown_to_html;
+use crate::social::profile_links::parse_profile_link;
+use crate::utils;
+
+/// The prefix of the URIs that hashtags in a post's text link to,
+/// followed by the lowercased hashtag without its `#`.
+pub const HASHTAG_LINK_PREFIX: &str = "org.social.hashtag:";
+
+/// HTML elements whose text isn't linkified.
+const UNLINKED_ELEMENTS: [&str; 3] = ["a", "code", "pre"];
+
+/// A link tapped in a post's text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PostLink {
+    /// A link to a web page or any other URL.
+    Url(String),
+    /// A mention of a user, or a link to their profile.
+    Mention(OwnedUserId),
+    /// A hashtag, lowercased and without its leading `#`.
+    Hashtag(String),
+}
+
+impl PostLink {
+    /// Get the kind of link that a link in a post's text points to.
+    pub fn from_url(url: &str) -> Self {
+        if let Some(hashtag) = url.strip_prefix(HASHTAG_LINK_PREFIX) {
+            return Self::Hashtag(hashtag.to_owned());
+        }
+        match parse_profile_link(url) {
+            Some(user_id) => Self::Mention(user_id),
+            None => Self::Url(url.to_owned()),
+        }
+    }
+}
+
+/// Get the HTML to show for a post's text, with its links, mentions and hashtags made tappable.
+///
+/// The post's `formatted_text` is used if it has one; otherwise its text is
+/// rendered as Markdown (see [`markdown_to_html()`]), with any HTML in it shown
+/// as text rather than rendered, since only formatted bodies are sanitized.
+/// Returns `None` if the text has neither formatting nor anything to link,
+/// so it can be shown as plain text.
+pub fn post_text_html(text: &str, formatted_text: Option<&str>) -> Option<String> {
+    let formatted = formatted_text
+        .map(str::to_owned)
+        .or_else(|| markdown_to_html(&escape_html_outside_code(text)));
+    let (html, is_formatted) = match formatted {
+        Some(html) => (html, true),
+        None => (htmlize::escape_text(text).replace('\n', "<br>"), false),
+    };
+    let linkified = linkify_mentions_and_hashtags(&utils::linkify(&html, true));
+    (is_formatted || linkified != html).then_some(linkified)
+}
+
+/// Escape the characters that start HTML tags and entities in Markdown text,
+/// except in code, whose text Markdown already escapes.
+///
+/// Like spoilers (see [`markdown_to_html()`]), code is told apart by its backticks.
+fn escape_html_outside_code(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (i, segment) in text.split('`').enumerate() {
+        if i > 0 {
+            escaped.push('`');
+        }
+        // Odd segments are inside code spans or blocks
+        if i % 2 == 1 {
+            escaped.push_str(segment);
+        } else {
+            escaped.push_str(&segment.replace('&', "&amp;").replace('<', "&lt;"));
+        }
+    }
+    escaped
+}
+
+/// Turn the user IDs and hashtags in the text of some HTML into links,
+/// except within existing links and code.
+fn linkify_mentions_and_hashtags(html: &str) -> String {
+    let mut linkified = String::with_capacity(html.len());
+    // How many elements whose text isn't linkified the text is in
+    let mut unlinked_depth = 0usize;
+    let mut rest = html;
+    while let Some(tag_start) = rest.find('<') {
+        let (text, tail) = rest.split_at(tag_start);
+        if unlinked_depth == 0 {
+            linkify_text(text, &mut linkified);
+        } else {
+            linkified.push_str(text);
+        }
+
+        let tag_end = tail.find('>').map_or(tail.len(), |index| index + 1);
+        let tag = &tail[..tag_end];
+        let is_closing = tag.starts_with("</");
+        let name = tag
+            .trim_start_matches(['<', '/'])
+            .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .next()
+            .unwrap_or_default();
+        if UNLINKED_ELEMENTS
+            .iter()
+            .any(|element| name.eq_ignore_ascii_case(element))
+        {
+            unlinked_depth = if is_closing {
+                unlinked_depth.saturating_sub(1)
+            } else {
+                unlinked_depth + 1
+            };
+        }
+        linkified.push_str(tag);
+        rest = &tail[tag_end..];
+    }
+    if unlinked_depth == 0 {
+        linkify_text(rest, &mut linkified);
+    } else {
+        linkified.push_str(rest);
+    }
+    linkified
+}
+
+/// Append `text` to `html`, with the user IDs and hashtags at the start of its words as links.
+fn linkify_text(text: &str, html: &mut String) {
+    let mut last_end = 0;
+    let mut is_word_start = true;
+    for (index, c) in text.char_indices() {
+        let starts_word = is_word_start;
+        is_word_start = c.is_whitespace();
+        if !starts_word || index < last_end {
+            continue;
+        }
+        let link = match c {
+            '#' => hashtag_at(&text[index..]),
+            '@' => mention_at(&text[index..]),
+            _ => None,
+        };
+        if let Some((len, href)) = link {
+            let end = index + len;
+            let _ = write!(
+                html,
+                "{}<a href=\"{}\">{}</a>",
+                &text[last_end..index],
+                htmlize::escape_attribute(&href),
+                &text[index..end],
+            );
+            last_end = end;
+        }
+    }
+    html.push_str(&text[last_end..]);
+}
+
+/// Get the length of the hashtag that `text` starts with and the URI it links to, if any.
+///
+/// Hashtags are recognized the same way as for trending topics (see
+/// [`extract_hashtags()`](crate::social::discovery::trending::extract_hashtags)).
+fn hashtag_at(text: &str) -> Option<(usize, String)> {
+    let tag = text.strip_prefix('#')?;
+    let len = tag
+        .char_indices()
+        .find(|(_, c)| !c.is_alphanumeric() && *c != '_')
+        .map_or(tag.len(), |(index, _)| index);
+    let tag = &tag[..len];
+    tag.chars().any(|c| !c.is_ascii_digit()).then(|| {
+        (
+            1 + len,
+            format!("{HASHTAG_LINK_PREFIX}{}", tag.to_lowercase()),
+        )
+    })
+}
+
+/// Get the length of the user ID that `text` starts with and the URI it links to, if any.
+///
+/// Punctuation right after the user ID, e.g., a full stop, isn't part of it.
+fn mention_at(text: &str) -> Option<(usize, String)> {
+    let word = text
+        .split(|c: char| c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(|c: char| !c.is_alphanumeric());
+    let user_id = UserId::parse(word).ok()?;
+    Some((word.len(), user_id.matrix_to_uri().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::user_id;
+
+    #[test]
+    fn test_plain_text_is_not_linkified() {
+        assert_eq!(post_text_html("Just text, #1 and me@home", None), None);
+    }
+
+    #[test]
+    fn test_mentions_and_hashtags_are_linkified() {
+        let html = post_text_html("Hi @alice:example.org! #Sunset at the beach", None).unwrap();
+        let mention_uri = user_id!("@alice:example.org").matrix_to_uri().to_string();
+        assert!(html.contains(&format!(
+            "<a href=\"{mention_uri}\">@alice:example.org</a>!"
+        )));
+        assert!(html.contains("<a href=\"org.social.hashtag:sunset\">#Sunset</a> at"));
+
+        let html = post_text_html("See https://example.org", None).unwrap();
+        assert!(html.contains("<a href=\"https://example.org\">"));
+    }
+
+    #[test]
+    fn test_html_in_plain_text_is_escaped() {
+        let html = post_text_html("<img src=x onerror=alert(1)> **Hi** & bye", None).unwrap();
+        assert!(!html.contains("<img"));
+        assert!(html.contains("&lt;img src=x onerror=alert(1)&gt;"));
+        assert!(html.contains("<strong>Hi</strong> &amp; bye"));
+
+        let html = post_text_html("`a < b` and ||<b>spoiler</b>||", None).unwrap();
+        assert!(html.contains("<code>a &lt; b</code>"));
+        assert!(html.contains("<span data-mx-spoiler>&lt;b&gt;spoiler&lt;/b&gt;</span>"));
+
+        // Formatted bodies are sanitized when they're shown, not here
+        let html = post_text_html("Hi", Some("<b>Hi</b>")).unwrap();
+        assert_eq!(html, "<b>Hi</b>");
+    }
+
+    #[test]
+    fn test_code_and_links_are_not_linkified() {
+        let html = "<code>#include</code> <a href=\"https://example.org\">#home</a> #tag";
+        assert_eq!(
+            linkify_mentions_and_hashtags(html),
+            "<code>#include</code> <a href=\"https://example.org\">#home</a> \
+             <a href=\"org.social.hashtag:tag\">#tag</a>"
+        );
+    }
+
+    #[test]
+    fn test_post_link_from_url() {
+        assert_eq!(
+            PostLink::from_url("org.social.hashtag:sunset"),
+            PostLink::Hashtag("sunset".to_owned())
+        );
+        let alice = user_id!("@alice:example.org");
+        assert_eq!(
+            PostLink::from_url(&alice.matrix_to_uri().to_string()),
+            PostLink::Mention(alice.to_owned())
+        );
+        assert_eq!(
+            PostLink::from_url("robrix://profile/@alice:example.org"),
+            PostLink::Mention(alice.to_owned())
+        );
+        assert_eq!(
+            PostLink::from_url("https://example.org"),
+            PostLink::Url("https://example.org".to_owned())
+        );
+    }
+}
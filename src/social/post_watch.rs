@@ -0,0 +1,392 @@
+//! "Watch this post": notifications about new comments on other users' posts.
+//!
+//! The author of a post is notified about comments on it by the homeserver,
+//! but other users only are if they're mentioned. Users can watch a post
+//! they care about to be notified about its new comments too. Watched posts
+//! are kept in a local watch list in the user's persistent state directory,
+//! and [`spawn_post_watcher()`] publishes a [`SocialEvent::NewComment`] for
+//! every comment from someone else on a watched post, to be shown as a
+//! notification. Comments in feed rooms that mention the user are published
+//! as a [`SocialEvent::CommentMention`] instead (see [`mentions`](crate::social::mentions)).
+//! The watcher also publishes a [`SocialEvent::PollUpdated`] for every vote in,
+//! or end of, a poll in a feed room, so that post cards show live vote counts.
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    room::Room,
+    ruma::{
+        events::{
+            poll::{
+                unstable_end::OriginalSyncUnstablePollEndEvent,
+                unstable_response::OriginalSyncUnstablePollResponseEvent,
+            },
+            room::message::{OriginalSyncRoomMessageEvent, Relation},
+        },
+        EventId, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId,
+        UserId,
+    },
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tokio::runtime::Handle;
+
+use crate::persistence::persistent_state_dir;
+use crate::social::availability::is_social_enabled;
+use crate::social::event_bus::{publish_social_event, SocialEvent};
+use crate::social::feed_room::is_feed_room;
+use crate::social::mentions::comment_mention;
+
+/// The name of the file, in the user's persistent state directory, holding the watch list.
+const WATCH_LIST_FILE_NAME: &str = "social_watched_posts.json";
+
+/// The maximum number of posts that can be watched at once.
+pub const MAX_WATCHED_POSTS: usize = 500;
+
+/// The watch list of the logged-in user, as loaded by [`spawn_post_watcher()`].
+static WATCHED_POSTS: Mutex<PostWatchList> = Mutex::new(PostWatchList { posts: Vec::new() });
+
+/// A post the user watches for new comments.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchedPost {
+    /// The feed room the post is in.
+    pub room_id: OwnedRoomId,
+    /// The watched post.
+    pub event_id: OwnedEventId,
+    /// When the user started watching the post.
+    pub watched_at: MilliSecondsSinceUnixEpoch,
+}
+
+/// A new comment on a watched post.
+#[derive(Clone, Debug)]
+pub struct WatchedPostComment {
+    /// The feed room the post is in.
+    pub room_id: OwnedRoomId,
+    /// The watched post.
+    pub post_id: OwnedEventId,
+    /// The comment.
+    pub comment_id: OwnedEventId,
+    /// Author of the comment.
+    pub sender: OwnedUserId,
+    /// Text of the comment.
+    pub body: String,
+}
+
+/// A local list of the posts the user watches, oldest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostWatchList {
+    posts: Vec<WatchedPost>,
+}
+
+impl PostWatchList {
+    /// Load the watch list of the given user, or an empty list if none was saved yet.
+    ///
+    /// # Errors
+    /// Returns an error if the watch list file cannot be read or parsed.
+    pub fn load(user_id: &UserId) -> Result<Self, PostWatchError> {
+        let path = persistent_state_dir(user_id).join(WATCH_LIST_FILE_NAME);
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save the watch list of the given user.
+    ///
+    /// # Errors
+    /// Returns an error if the watch list file cannot be written.
+    pub fn save(&self, user_id: &UserId) -> Result<(), PostWatchError> {
+        let path = persistent_state_dir(user_id).join(WATCH_LIST_FILE_NAME);
+        std::fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Get the watched posts, oldest first.
+    pub fn posts(&self) -> &[WatchedPost] {
+        &self.posts
+    }
+
+    /// Check whether a post is watched.
+    pub fn is_watched(&self, room_id: &RoomId, event_id: &EventId) -> bool {
+        self.posts
+            .iter()
+            .any(|post| post.room_id == room_id && post.event_id == event_id)
+    }
+
+    /// Start watching a post, forgetting the oldest watched post if the list is full.
+    ///
+    /// Returns `false` if the post was already watched.
+    pub fn watch(&mut self, post: WatchedPost) -> bool {
+        if self.is_watched(&post.room_id, &post.event_id) {
+            return false;
+        }
+        self.posts.push(post);
+        let excess = self.posts.len().saturating_sub(MAX_WATCHED_POSTS);
+        self.posts.drain(..excess);
+        true
+    }
+
+    /// Stop watching a post.
+    ///
+    /// Returns `false` if the post wasn't watched.
+    pub fn unwatch(&mut self, room_id: &RoomId, event_id: &EventId) -> bool {
+        let len = self.posts.len();
+        self.posts
+            .retain(|post| post.room_id != room_id || post.event_id != event_id);
+        self.posts.len() != len
+    }
+
+    /// Get the new comment on a watched post that a message in a room is, if any.
+    ///
+    /// The user's own comments are ignored.
+    pub fn comment_on_watched_post(
+        &self,
+        room_id: &RoomId,
+        message: &OriginalSyncRoomMessageEvent,
+        own_user_id: &UserId,
+    ) -> Option<WatchedPostComment> {
+        if message.sender == own_user_id {
+            return None;
+        }
+        let post_id = match message.content.relates_to.as_ref()? {
+            Relation::Reply { in_reply_to } => &in_reply_to.event_id,
+            Relation::Thread(thread) => &thread.event_id,
+            _ => return None,
+        };
+        self.is_watched(room_id, post_id)
+            .then(|| WatchedPostComment {
+                room_id: room_id.to_owned(),
+                post_id: post_id.clone(),
+                comment_id: message.event_id.clone(),
+                sender: message.sender.clone(),
+                body: message.content.body().to_string(),
+            })
+    }
+}
+
+/// Check whether the logged-in user watches a post.
+pub fn is_post_watched(room_id: &RoomId, event_id: &EventId) -> bool {
+    WATCHED_POSTS.lock().unwrap().is_watched(room_id, event_id)
+}
+
+/// Start watching a post for the given user, saving their watch list.
+///
+/// # Errors
+/// Returns an error if the watch list cannot be saved.
+pub fn watch_post(
+    user_id: &UserId,
+    room_id: OwnedRoomId,
+    event_id: OwnedEventId,
+) -> Result<(), PostWatchError> {
+    let mut watched_posts = WATCHED_POSTS.lock().unwrap();
+    let post = WatchedPost {
+        room_id,
+        event_id,
+        watched_at: MilliSecondsSinceUnixEpoch::now(),
+    };
+    if watched_posts.watch(post) {
+        watched_posts.save(user_id)?;
+    }
+    Ok(())
+}
+
+/// Stop watching a post for the given user, saving their watch list.
+///
+/// # Errors
+/// Returns an error if the watch list cannot be saved.
+pub fn unwatch_post(
+    user_id: &UserId,
+    room_id: &RoomId,
+    event_id: &EventId,
+) -> Result<(), PostWatchError> {
+    let mut watched_posts = WATCHED_POSTS.lock().unwrap();
+    if watched_posts.unwatch(room_id, event_id) {
+        watched_posts.save(user_id)?;
+    }
+    Ok(())
+}
+
+/// Load the logged-in user's watch list and listen for new comments on watched posts,
+/// for comments that mention the user, and for votes in polls.
+///
+/// Must be called from within the Tokio runtime after logging in.
+/// While social features are enabled, a [`SocialEvent::CommentMention`] event is
+/// published for every new comment in a feed room that mentions the user, and a
+/// [`SocialEvent::NewComment`] event for every other new comment from someone else
+/// on a watched post. A [`SocialEvent::PollUpdated`] event is published for every
+/// vote in, or end of, a poll in a feed room.
+pub fn spawn_post_watcher(client: Client) {
+    let Some(user_id) = client.user_id().map(ToOwned::to_owned) else {
+        return;
+    };
+    Handle::current().spawn(async move {
+        match PostWatchList::load(&user_id) {
+            Ok(watch_list) => *WATCHED_POSTS.lock().unwrap() = watch_list,
+            Err(e) => error!("Failed to load the watched posts of {user_id}: {e}"),
+        }
+
+        client.add_event_handler(move |message: OriginalSyncRoomMessageEvent, room: Room| {
+            let user_id = user_id.clone();
+            async move {
+                if !is_social_enabled() {
+                    return;
+                }
+                if is_feed_room(&room) {
+                    if let Some(mention) = comment_mention(room.room_id(), &message, &user_id) {
+                        publish_social_event(SocialEvent::CommentMention(mention));
+                        return;
+                    }
+                }
+                let comment = WATCHED_POSTS.lock().unwrap().comment_on_watched_post(
+                    room.room_id(),
+                    &message,
+                    &user_id,
+                );
+                if let Some(comment) = comment {
+                    publish_social_event(SocialEvent::NewComment(comment));
+                }
+            }
+        });
+
+        client.add_event_handler(
+            |vote: OriginalSyncUnstablePollResponseEvent, room: Room| async move {
+                if is_social_enabled() && is_feed_room(&room) {
+                    publish_social_event(SocialEvent::PollUpdated {
+                        room_id: room.room_id().to_owned(),
+                        poll_id: vote.content.relates_to.event_id,
+                    });
+                }
+            },
+        );
+        client.add_event_handler(
+            |end: OriginalSyncUnstablePollEndEvent, room: Room| async move {
+                if is_social_enabled() && is_feed_room(&room) {
+                    publish_social_event(SocialEvent::PollUpdated {
+                        room_id: room.room_id().to_owned(),
+                        poll_id: end.content.relates_to.event_id,
+                    });
+                }
+            },
+        );
+    });
+}
+
+/// Errors that can occur when managing watched posts.
+#[derive(Debug, thiserror::Error)]
+pub enum PostWatchError {
+    /// The watch list could not be read or written.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The watch list could not be parsed or serialized.
+    #[error("Invalid watch list: {0}")]
+    InvalidWatchList(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::{event_id, owned_event_id, owned_room_id, room_id, user_id};
+    use serde_json::json;
+
+    fn watched(event_id: OwnedEventId) -> WatchedPost {
+        WatchedPost {
+            room_id: owned_room_id!("!feed:example.org"),
+            event_id,
+            watched_at: MilliSecondsSinceUnixEpoch::now(),
+        }
+    }
+
+    fn message(sender: &str, relates_to: serde_json::Value) -> OriginalSyncRoomMessageEvent {
+        serde_json::from_value(json!({
+            "type": "m.room.message",
+            "event_id": "$comment:example.org",
+            "sender": sender,
+            "origin_server_ts": 1,
+            "content": {
+                "msgtype": "m.text",
+                "body": "Great photo!",
+                "m.relates_to": relates_to,
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_watch_and_unwatch() {
+        let room_id = room_id!("!feed:example.org");
+        let mut watch_list = PostWatchList::default();
+        assert!(watch_list.watch(watched(owned_event_id!("$post:example.org"))));
+        assert!(!watch_list.watch(watched(owned_event_id!("$post:example.org"))));
+        assert!(watch_list.is_watched(room_id, event_id!("$post:example.org")));
+        assert!(!watch_list.is_watched(room_id, event_id!("$other:example.org")));
+
+        assert!(watch_list.unwatch(room_id, event_id!("$post:example.org")));
+        assert!(!watch_list.unwatch(room_id, event_id!("$post:example.org")));
+        assert!(watch_list.posts().is_empty());
+    }
+
+    #[test]
+    fn test_watch_list_forgets_oldest_posts() {
+        let mut watch_list = PostWatchList::default();
+        for i in 0..=MAX_WATCHED_POSTS {
+            watch_list.watch(watched(format!("$post{i}:example.org").try_into().unwrap()));
+        }
+        assert_eq!(watch_list.posts().len(), MAX_WATCHED_POSTS);
+        assert_eq!(watch_list.posts()[0].event_id, "$post1:example.org");
+    }
+
+    #[test]
+    fn test_comments_on_watched_posts() {
+        let room_id = room_id!("!feed:example.org");
+        let own_user_id = user_id!("@me:example.org");
+        let mut watch_list = PostWatchList::default();
+        watch_list.watch(watched(owned_event_id!("$post:example.org")));
+
+        let thread_comment = message(
+            "@friend:example.org",
+            json!({
+                "rel_type": "m.thread",
+                "event_id": "$post:example.org",
+                "is_falling_back": true,
+                "m.in_reply_to": { "event_id": "$post:example.org" },
+            }),
+        );
+        let comment = watch_list
+            .comment_on_watched_post(room_id, &thread_comment, own_user_id)
+            .unwrap();
+        assert_eq!(comment.post_id, "$post:example.org");
+        assert_eq!(comment.body, "Great photo!");
+
+        let reply = message(
+            "@friend:example.org",
+            json!({ "m.in_reply_to": { "event_id": "$post:example.org" } }),
+        );
+        assert!(
+            watch_list
+                .comment_on_watched_post(room_id, &reply, own_user_id)
+                .is_some()
+        );
+
+        // The user's own comments and comments on other posts are ignored
+        let own_reply = message(
+            "@me:example.org",
+            json!({ "m.in_reply_to": { "event_id": "$post:example.org" } }),
+        );
+        assert!(
+            watch_list
+                .comment_on_watched_post(room_id, &own_reply, own_user_id)
+                .is_none()
+        );
+        let other_reply = message(
+            "@friend:example.org",
+            json!({ "m.in_reply_to": { "event_id": "$other:example.org" } }),
+        );
+        assert!(
+            watch_list
+                .comment_on_watched_post(room_id, &other_reply, own_user_id)
+                .is_none()
+        );
+    }
+}
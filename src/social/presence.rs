@@ -0,0 +1,171 @@
+//! Online status of friends.
+//!
+//! Sliding sync doesn't deliver presence, so it is fetched from the homeserver
+//! through the Matrix worker (see [`MatrixRequest::LoadPresence`]), which posts
+//! a [`PresenceAction::Loaded`] for each user. The friend list does so when its
+//! friends are set and again on every [`SocialTickerAction::Tick`], keeping
+//! their status up to date while it's shown.
+//!
+//! [`MatrixRequest::LoadPresence`]: crate::sliding_sync::MatrixRequest::LoadPresence
+//! [`SocialTickerAction::Tick`]: crate::social::ticker::SocialTickerAction::Tick
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    ruma::{api::client::presence::get_presence, presence::PresenceState, OwnedUserId, UserId},
+    Client,
+};
+use std::time::{Duration, SystemTime};
+
+/// Whether a user is currently online.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnlineStatus {
+    /// The user is online and active.
+    Online,
+    /// The user is online but away, e.g., idle.
+    Idle,
+    /// The user is offline, or their presence is unknown.
+    Offline,
+}
+
+impl From<&PresenceState> for OnlineStatus {
+    fn from(state: &PresenceState) -> Self {
+        match state {
+            PresenceState::Online => Self::Online,
+            PresenceState::Unavailable => Self::Idle,
+            _ => Self::Offline,
+        }
+    }
+}
+
+/// A user's presence, as shown in the friend list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UserPresence {
+    /// Whether the user is online.
+    pub status: OnlineStatus,
+    /// When the user was last active, if known.
+    pub last_active: Option<SystemTime>,
+}
+
+impl UserPresence {
+    /// Describe the presence, e.g., "Online" or "Last active 5m ago".
+    pub fn description(&self) -> String {
+        let elapsed = self
+            .last_active
+            .map(|last_active| last_active.elapsed().unwrap_or_default());
+        match (self.status, elapsed) {
+            (OnlineStatus::Online, _) => "Online".to_owned(),
+            (OnlineStatus::Idle, Some(elapsed)) => {
+                format!("Idle · active {}", format_elapsed(elapsed))
+            }
+            (OnlineStatus::Idle, None) => "Idle".to_owned(),
+            (OnlineStatus::Offline, Some(elapsed)) => {
+                format!("Last active {}", format_elapsed(elapsed))
+            }
+            (OnlineStatus::Offline, None) => "Offline".to_owned(),
+        }
+    }
+}
+
+/// Format how long ago something happened, e.g., "5m ago".
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs < 60 {
+        "just now".to_owned()
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h ago", secs / 3600)
+    } else if secs < 604800 {
+        format!("{}d ago", secs / 86400)
+    } else {
+        "over a week ago".to_owned()
+    }
+}
+
+/// Actions emitted when users' presence was fetched.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum PresenceAction {
+    /// A user's presence was fetched.
+    Loaded {
+        user_id: OwnedUserId,
+        presence: UserPresence,
+    },
+    /// No action.
+    None,
+}
+
+/// Fetch a user's presence from the homeserver.
+///
+/// # Errors
+/// Returns an error if the request fails, e.g., if the homeserver
+/// doesn't share the user's presence.
+pub async fn fetch_presence(
+    client: &Client,
+    user_id: &UserId,
+) -> Result<UserPresence, matrix_sdk::HttpError> {
+    let response = client
+        .send(get_presence::v3::Request::new(user_id.to_owned()))
+        .await?;
+    let status = match OnlineStatus::from(&response.presence) {
+        // Users that are online but not currently active count as idle
+        OnlineStatus::Online if response.currently_active == Some(false) => OnlineStatus::Idle,
+        status => status,
+    };
+    Ok(UserPresence {
+        status,
+        last_active: response
+            .last_active_ago
+            .and_then(|ago| SystemTime::now().checked_sub(ago)),
+    })
+}
+
+/// Fetch the presence of the given users, posting a [`PresenceAction::Loaded`] for each.
+///
+/// This is run by the Matrix worker for
+/// [`MatrixRequest::LoadPresence`](crate::sliding_sync::MatrixRequest::LoadPresence).
+pub async fn load_presence(client: Client, user_ids: Vec<OwnedUserId>) {
+    for user_id in user_ids {
+        match fetch_presence(&client, &user_id).await {
+            Ok(presence) => Cx::post_action(PresenceAction::Loaded { user_id, presence }),
+            Err(e) => warning!("Failed to fetch the presence of {user_id}: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_secs(30)), "just now");
+        assert_eq!(format_elapsed(Duration::from_secs(5 * 60)), "5m ago");
+        assert_eq!(format_elapsed(Duration::from_secs(3 * 3600)), "3h ago");
+        assert_eq!(format_elapsed(Duration::from_secs(2 * 86400)), "2d ago");
+        assert_eq!(
+            format_elapsed(Duration::from_secs(30 * 86400)),
+            "over a week ago"
+        );
+    }
+
+    #[test]
+    fn test_presence_description() {
+        let online = UserPresence {
+            status: OnlineStatus::Online,
+            last_active: Some(SystemTime::now()),
+        };
+        assert_eq!(online.description(), "Online");
+
+        let offline = UserPresence {
+            status: OnlineStatus::Offline,
+            last_active: SystemTime::now().checked_sub(Duration::from_secs(2 * 3600 + 5)),
+        };
+        assert_eq!(offline.description(), "Last active 2h ago");
+
+        let unknown = UserPresence {
+            status: OnlineStatus::Offline,
+            last_active: None,
+        };
+        assert_eq!(unknown.description(), "Offline");
+    }
+}
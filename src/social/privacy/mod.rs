@@ -1,12 +1,15 @@
 //! Privacy and security module.
 //!
 //! This module provides privacy safeguards to prevent content from being
-//! shared to audiences with less restrictive visibility than intended.
+//! shared to audiences with less restrictive visibility than intended,
+//! and the privacy mode that turns off requests to third-party services.
 
+pub mod privacy_mode;
 pub mod sharing_guard;
 
 mod validation;
 
+pub use privacy_mode::{is_privacy_mode, set_privacy_mode};
 pub use sharing_guard::*;
 
 /// Maximum allowed sizes for various content types
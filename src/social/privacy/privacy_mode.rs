@@ -0,0 +1,40 @@
+//! Privacy mode.
+//!
+//! Some social features rely on third-party services, e.g., weather
+//! forecasts for events. Those services learn where and when the user's
+//! events take place, so users can turn them all off at once with privacy
+//! mode. The setting is stored in the account's `org.social.settings`
+//! account data, so it follows the user across devices.
+
+use matrix_sdk::Client;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::social::availability::load_settings;
+
+/// Whether privacy mode is on for the logged-in account.
+static PRIVACY_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Whether privacy mode is currently on for the logged-in account.
+///
+/// While it is on, nothing may be fetched from third-party services.
+pub fn is_privacy_mode() -> bool {
+    PRIVACY_MODE.load(Ordering::Relaxed)
+}
+
+/// Update the in-memory privacy mode from the account's settings.
+pub(crate) fn update_privacy_mode(enabled: bool) {
+    PRIVACY_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// Turn privacy mode on or off for the logged-in account.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn set_privacy_mode(client: &Client, enabled: bool) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.privacy_mode = enabled;
+    client.account().set_account_data(settings).await?;
+    // Take effect right away rather than once the settings have synced back
+    update_privacy_mode(enabled);
+    Ok(())
+}
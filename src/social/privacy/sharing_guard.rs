@@ -1,9 +1,30 @@
 //! Privacy safeguards for cross-posting and sharing.
 //!
 //! This module prevents accidental privacy leaks when sharing
-//! content from private rooms to public rooms.
+//! content from private rooms to public rooms, or when cross-posting
+//! the same post to feeds with different audiences.
+//!
+//! The privacy level of a room is inferred from its state with
+//! [`PrivacyLevel::infer_from_room()`]. Posts can also carry their intended
+//! audience, which is respected when it is more private than their room.
+
+use std::collections::HashSet;
+
+use matrix_sdk::{
+    deserialized_responses::RawSyncOrStrippedState,
+    ruma::{
+        events::room::{
+            history_visibility::{HistoryVisibility, RoomHistoryVisibilityEventContent},
+            join_rules::{JoinRule, RoomJoinRulesEventContent},
+        },
+        OwnedUserId, RoomId,
+    },
+    Client, Room, RoomMemberships,
+};
 
-use matrix_sdk::ruma::{OwnedUserId, RoomId};
+use robrix_social_events::visibility::PostVisibility;
+
+use crate::social::feed_room::{feed_of_room, FeedPrivacy};
 
 /// Privacy level of content
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -12,9 +33,9 @@ pub enum PrivacyLevel {
     Public = 0,
     /// Friends only (restricted join)
     Friends = 1,
-    /// Close friends (invite only)
+    /// Close friends (close friends feeds)
     CloseFriends = 2,
-    /// Private/DM
+    /// Private (DMs and invite-only chats)
     Private = 3,
 }
 
@@ -24,6 +45,118 @@ impl PrivacyLevel {
         // Can only share to equal or more private levels
         target >= *self
     }
+
+    /// Get the privacy level of a post in a room with the given privacy level.
+    ///
+    /// A post intended for a more private audience than its room's keeps
+    /// that audience's level.
+    pub fn for_post(room_privacy: PrivacyLevel, visibility: Option<PostVisibility>) -> Self {
+        visibility.map_or(room_privacy, |visibility| {
+            room_privacy.max(visibility.into())
+        })
+    }
+
+    /// Infer the privacy level of a room from its state.
+    ///
+    /// See [`PrivacyLevel::from_room_state()`] for how rooms are classified.
+    ///
+    /// # Errors
+    /// Returns an error if the room's state cannot be read.
+    pub async fn infer_from_room(room: &Room) -> Result<Self, matrix_sdk::Error> {
+        let feed = feed_of_room(room).map(|(privacy, _)| privacy);
+        let is_direct = room.is_direct().await?;
+        let join_rule = match room
+            .get_state_event_static::<RoomJoinRulesEventContent>()
+            .await?
+        {
+            Some(RawSyncOrStrippedState::Sync(raw)) => {
+                raw.get_field::<RoomJoinRulesEventContent>("content")
+            }
+            Some(RawSyncOrStrippedState::Stripped(raw)) => {
+                raw.get_field::<RoomJoinRulesEventContent>("content")
+            }
+            None => Ok(None),
+        }
+        .ok()
+        .flatten()
+        .map(|content| content.join_rule);
+        let history_visibility = match room
+            .get_state_event_static::<RoomHistoryVisibilityEventContent>()
+            .await?
+        {
+            Some(RawSyncOrStrippedState::Sync(raw)) => {
+                raw.get_field::<RoomHistoryVisibilityEventContent>("content")
+            }
+            Some(RawSyncOrStrippedState::Stripped(raw)) => {
+                raw.get_field::<RoomHistoryVisibilityEventContent>("content")
+            }
+            None => Ok(None),
+        }
+        .ok()
+        .flatten()
+        .map(|content| content.history_visibility);
+
+        Ok(Self::from_room_state(
+            feed,
+            is_direct,
+            join_rule.as_ref(),
+            history_visibility.as_ref(),
+        ))
+    }
+
+    /// Classify a room from its feed's privacy level, whether it's a direct chat,
+    /// its join rule and its history visibility.
+    ///
+    /// - Direct chats are private.
+    /// - Rooms anyone can read or join are public.
+    /// - Restricted rooms are friends-only, since members of another room
+    ///   (e.g., a friends space) can join them.
+    /// - Other rooms, e.g., invite-only group chats, are private.
+    ///
+    /// Friends and close friends feeds use the same join rules, so feed rooms get
+    /// the level of their feed, unless their state makes them more public:
+    /// the feed only tells who the room is meant for, while the state tells
+    /// who can actually read it.
+    pub fn from_room_state(
+        feed: Option<FeedPrivacy>,
+        is_direct: bool,
+        join_rule: Option<&JoinRule>,
+        history_visibility: Option<&HistoryVisibility>,
+    ) -> Self {
+        let state_privacy = if is_direct {
+            Self::Private
+        } else if history_visibility == Some(&HistoryVisibility::WorldReadable) {
+            Self::Public
+        } else {
+            match join_rule {
+                Some(JoinRule::Public) => Self::Public,
+                Some(JoinRule::Restricted(_) | JoinRule::KnockRestricted(_)) => Self::Friends,
+                _ => Self::Private,
+            }
+        };
+        feed.map_or(state_privacy, |privacy| state_privacy.min(privacy.into()))
+    }
+}
+
+impl From<FeedPrivacy> for PrivacyLevel {
+    fn from(privacy: FeedPrivacy) -> Self {
+        match privacy {
+            FeedPrivacy::Public => Self::Public,
+            FeedPrivacy::Friends => Self::Friends,
+            FeedPrivacy::CloseFriends => Self::CloseFriends,
+        }
+    }
+}
+
+impl From<PostVisibility> for PrivacyLevel {
+    fn from(visibility: PostVisibility) -> Self {
+        match visibility {
+            PostVisibility::Public => Self::Public,
+            PostVisibility::Friends => Self::Friends,
+            PostVisibility::CloseFriends => Self::CloseFriends,
+            PostVisibility::Private => Self::Private,
+        }
+    }
 }
 
 /// Result of share validation
@@ -97,6 +230,149 @@ impl SharingGuard {
         ShareValidation::Allowed
     }
 
+    /// Validate a share/cross-post action, looking up the target room's members.
+    ///
+    /// This applies the same rules as [`SharingGuard::validate_share()`], but
+    /// fetches the joined members of the target room instead of taking them
+    /// from the caller. The members are loaded in a single request, and only
+    /// if the privacy checks pass and there are mentions to check.
+    ///
+    /// # Errors
+    /// Returns an error if the target room is not found or its members
+    /// cannot be loaded.
+    pub async fn validate_share_with_rooms(
+        client: &Client,
+        source_room: &RoomId,
+        source_privacy: PrivacyLevel,
+        target_room: &RoomId,
+        target_privacy: PrivacyLevel,
+        mentioned_users: &[OwnedUserId],
+    ) -> Result<ShareValidation, SharingGuardError> {
+        let validation = Self::validate_share(
+            source_room,
+            source_privacy,
+            target_room,
+            target_privacy,
+            &[],
+            &[],
+        );
+        if !matches!(validation, ShareValidation::Allowed) || mentioned_users.is_empty() {
+            return Ok(validation);
+        }
+
+        let room = client
+            .get_room(target_room)
+            .ok_or(SharingGuardError::RoomNotFound)?;
+        let members: HashSet<OwnedUserId> = room
+            .members(RoomMemberships::JOIN)
+            .await?
+            .iter()
+            .map(|member| member.user_id().to_owned())
+            .collect();
+
+        let mut missing: Vec<OwnedUserId> = Vec::new();
+        for user_id in mentioned_users {
+            if !members.contains(user_id) && !missing.contains(user_id) {
+                missing.push(user_id.clone());
+            }
+        }
+        if !missing.is_empty() {
+            return Ok(ShareValidation::MissingMentions {
+                missing_users: missing,
+            });
+        }
+
+        Ok(ShareValidation::Allowed)
+    }
+
+    /// Validate sharing content from one room to another,
+    /// inferring both rooms' privacy levels with [`PrivacyLevel::infer_from_room()`].
+    ///
+    /// # Errors
+    /// Returns an error if either room is not found, or its state or the
+    /// target room's members cannot be loaded.
+    pub async fn validate_room_share(
+        client: &Client,
+        source_room: &RoomId,
+        target_room: &RoomId,
+        mentioned_users: &[OwnedUserId],
+    ) -> Result<ShareValidation, SharingGuardError> {
+        Self::validate_post_share(client, source_room, None, target_room, mentioned_users).await
+    }
+
+    /// Validate sharing a post from one room to another, like
+    /// [`SharingGuard::validate_room_share()`], but treating the post as at
+    /// least as private as its intended audience (see [`PrivacyLevel::for_post()`]).
+    ///
+    /// The post's visibility can be read from its content with
+    /// [`visibility_from_content()`](crate::social::visibility_from_content).
+    ///
+    /// # Errors
+    /// Returns an error if either room is not found, or its state or the
+    /// target room's members cannot be loaded.
+    pub async fn validate_post_share(
+        client: &Client,
+        source_room: &RoomId,
+        visibility: Option<PostVisibility>,
+        target_room: &RoomId,
+        mentioned_users: &[OwnedUserId],
+    ) -> Result<ShareValidation, SharingGuardError> {
+        let source = client
+            .get_room(source_room)
+            .ok_or(SharingGuardError::RoomNotFound)?;
+        let target = client
+            .get_room(target_room)
+            .ok_or(SharingGuardError::RoomNotFound)?;
+        let source_privacy =
+            PrivacyLevel::for_post(PrivacyLevel::infer_from_room(&source).await?, visibility);
+        let target_privacy = PrivacyLevel::infer_from_room(&target).await?;
+
+        Self::validate_share_with_rooms(
+            client,
+            source_room,
+            source_privacy,
+            target_room,
+            target_privacy,
+            mentioned_users,
+        )
+        .await
+    }
+
+    /// Validate cross-posting the same content to several rooms at once.
+    ///
+    /// The content is treated as written for the most private of the targets,
+    /// and posting it to each of the others is validated as a share from there.
+    /// A blocked share takes precedence over one requiring confirmation.
+    pub fn validate_cross_post(targets: &[(&RoomId, PrivacyLevel)]) -> ShareValidation {
+        let Some(&(source_room, source_privacy)) =
+            targets.iter().max_by_key(|(_, privacy)| *privacy)
+        else {
+            return ShareValidation::Allowed;
+        };
+
+        let mut result = ShareValidation::Allowed;
+        for &(target_room, target_privacy) in targets {
+            // Mentions are the same in every target, so they're checked separately
+            match Self::validate_share(
+                source_room,
+                source_privacy,
+                target_room,
+                target_privacy,
+                &[],
+                &[],
+            ) {
+                ShareValidation::Allowed => {}
+                blocked @ ShareValidation::BlockedPrivacyLeak { .. } => return blocked,
+                other => {
+                    if matches!(result, ShareValidation::Allowed) {
+                        result = other;
+                    }
+                }
+            }
+        }
+        result
+    }
+
     /// Check if a quote/reply leaks private content
     pub fn validate_quote(
         original_room_privacy: PrivacyLevel,
@@ -114,6 +390,18 @@ impl SharingGuard {
     }
 }
 
+/// Errors that can occur when validating a share against room state.
+#[derive(Debug, thiserror::Error)]
+pub enum SharingGuardError {
+    /// The target room was not found.
+    #[error("Room not found")]
+    RoomNotFound,
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
 fn privacy_level_name(level: PrivacyLevel) -> &'static str {
     match level {
         PrivacyLevel::Public => "public",
@@ -0,0 +1,275 @@
+//! Validation of edits to a social profile.
+//!
+//! The profile editor collects the raw text of each field into a
+//! [`ProfileDraft`], which is validated and turned into the
+//! [`SocialProfileEventContent`] to be saved with
+//! [`ProfileRoomService::update_profile()`](crate::social::ProfileRoomService::update_profile).
+
+use chrono::{Local, NaiveDate};
+use robrix_social_events::profile::SocialProfileEventContent;
+use url::Url;
+
+/// Maximum length of a display name, in characters.
+pub const MAX_DISPLAY_NAME_CHARS: usize = 100;
+/// Maximum length of a bio, in characters.
+pub const MAX_BIO_CHARS: usize = 500;
+/// Maximum length of a location, in characters.
+pub const MAX_LOCATION_CHARS: usize = 100;
+/// Maximum length of pronouns, in characters.
+pub const MAX_PRONOUNS_CHARS: usize = 40;
+
+/// A field of the profile editor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileField {
+    /// The account's display name.
+    DisplayName,
+    /// The biography text.
+    Bio,
+    /// The location.
+    Location,
+    /// The website address.
+    Website,
+    /// The pronouns.
+    Pronouns,
+    /// The birthday.
+    Birthday,
+}
+
+/// A validation error for a single profile field.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ProfileFieldError {
+    /// The field is longer than its maximum length.
+    #[error("Must be at most {max} characters")]
+    TooLong { field: ProfileField, max: usize },
+
+    /// The website is not a valid http(s) URL.
+    #[error("Enter a valid web address, e.g., https://example.org")]
+    InvalidWebsite,
+
+    /// The birthday is not a valid date.
+    #[error("Enter a date as YYYY-MM-DD")]
+    InvalidBirthday,
+
+    /// The birthday is in the future.
+    #[error("Birthday can't be in the future")]
+    BirthdayInFuture,
+}
+
+impl ProfileFieldError {
+    /// Get the field this error is about.
+    pub fn field(&self) -> ProfileField {
+        match self {
+            Self::TooLong { field, .. } => *field,
+            Self::InvalidWebsite => ProfileField::Website,
+            Self::InvalidBirthday | Self::BirthdayInFuture => ProfileField::Birthday,
+        }
+    }
+}
+
+/// The raw contents of the profile editor's fields.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProfileDraft {
+    /// The account's display name.
+    pub display_name: String,
+    /// The biography text.
+    pub bio: String,
+    /// The location.
+    pub location: String,
+    /// The website address, with or without a scheme.
+    pub website: String,
+    /// The pronouns.
+    pub pronouns: String,
+    /// The birthday as YYYY-MM-DD.
+    pub birthday: String,
+}
+
+impl ProfileDraft {
+    /// Create a draft from the current display name and profile.
+    pub fn new(display_name: Option<&str>, profile: Option<&SocialProfileEventContent>) -> Self {
+        let field = |value: Option<&String>| value.cloned().unwrap_or_default();
+        Self {
+            display_name: display_name.unwrap_or_default().to_string(),
+            bio: field(profile.and_then(|p| p.bio.as_ref())),
+            location: field(profile.and_then(|p| p.location.as_ref())),
+            website: profile
+                .and_then(|p| p.website.as_ref())
+                .map(|url| url.to_string())
+                .unwrap_or_default(),
+            pronouns: field(profile.and_then(|p| p.pronouns.as_ref())),
+            birthday: field(profile.and_then(|p| p.birthday.as_ref())),
+        }
+    }
+
+    /// Validate the draft and apply it to the given profile.
+    ///
+    /// Fields that aren't part of the editor (images, count privacy, and
+    /// custom fields) are kept from `profile`. Empty fields are removed.
+    ///
+    /// # Errors
+    /// Returns the errors of all invalid fields.
+    pub fn apply_to(
+        &self,
+        mut profile: SocialProfileEventContent,
+        today: NaiveDate,
+    ) -> Result<SocialProfileEventContent, Vec<ProfileFieldError>> {
+        let mut errors = Vec::new();
+        let mut text = |value: &str, field: ProfileField, max: usize| -> Option<String> {
+            let value = value.trim();
+            if value.chars().count() > max {
+                errors.push(ProfileFieldError::TooLong { field, max });
+            }
+            (!value.is_empty()).then(|| value.to_string())
+        };
+
+        // The display name belongs to the account, not to the profile
+        let _ = text(
+            &self.display_name,
+            ProfileField::DisplayName,
+            MAX_DISPLAY_NAME_CHARS,
+        );
+        profile.bio = text(&self.bio, ProfileField::Bio, MAX_BIO_CHARS);
+        profile.location = text(&self.location, ProfileField::Location, MAX_LOCATION_CHARS);
+        profile.pronouns = text(&self.pronouns, ProfileField::Pronouns, MAX_PRONOUNS_CHARS);
+
+        profile.website = match parse_website(&self.website) {
+            Ok(website) => website,
+            Err(e) => {
+                errors.push(e);
+                None
+            }
+        };
+
+        let birthday = self.birthday.trim();
+        profile.birthday = if birthday.is_empty() {
+            None
+        } else {
+            match NaiveDate::parse_from_str(birthday, "%Y-%m-%d") {
+                Ok(date) if date > today => {
+                    errors.push(ProfileFieldError::BirthdayInFuture);
+                    None
+                }
+                Ok(date) => Some(date.format("%Y-%m-%d").to_string()),
+                Err(_) => {
+                    errors.push(ProfileFieldError::InvalidBirthday);
+                    None
+                }
+            }
+        };
+
+        if errors.is_empty() {
+            Ok(profile)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Like [`apply_to()`](Self::apply_to), using the local date as today.
+    pub fn apply_to_now(
+        &self,
+        profile: SocialProfileEventContent,
+    ) -> Result<SocialProfileEventContent, Vec<ProfileFieldError>> {
+        self.apply_to(profile, Local::now().date_naive())
+    }
+}
+
+/// Parse a website address, adding `https://` if no scheme was given.
+fn parse_website(website: &str) -> Result<Option<Url>, ProfileFieldError> {
+    let website = website.trim();
+    if website.is_empty() {
+        return Ok(None);
+    }
+    let url = if website.contains("://") {
+        Url::parse(website)
+    } else {
+        Url::parse(&format!("https://{}", website))
+    }
+    .map_err(|_| ProfileFieldError::InvalidWebsite)?;
+
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none_or(|h| !h.contains('.'))
+    {
+        return Err(ProfileFieldError::InvalidWebsite);
+    }
+    Ok(Some(url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+    }
+
+    #[test]
+    fn test_valid_draft_is_applied() {
+        let draft = ProfileDraft {
+            display_name: "Alice".to_string(),
+            bio: "  Gardener  ".to_string(),
+            location: String::new(),
+            website: "example.org/alice".to_string(),
+            pronouns: "she/her".to_string(),
+            birthday: "1990-04-23".to_string(),
+        };
+        let profile = draft
+            .apply_to(SocialProfileEventContent::default(), today())
+            .unwrap();
+        assert_eq!(profile.bio.as_deref(), Some("Gardener"));
+        assert_eq!(profile.location, None);
+        assert_eq!(
+            profile.website.as_ref().map(Url::as_str),
+            Some("https://example.org/alice")
+        );
+        assert_eq!(profile.pronouns.as_deref(), Some("she/her"));
+        assert_eq!(profile.birthday.as_deref(), Some("1990-04-23"));
+    }
+
+    #[test]
+    fn test_all_invalid_fields_are_reported() {
+        let draft = ProfileDraft {
+            bio: "a".repeat(MAX_BIO_CHARS + 1),
+            website: "ftp://example.org".to_string(),
+            birthday: "2030-01-01".to_string(),
+            ..Default::default()
+        };
+        let errors = draft
+            .apply_to(SocialProfileEventContent::default(), today())
+            .unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                ProfileFieldError::TooLong {
+                    field: ProfileField::Bio,
+                    max: MAX_BIO_CHARS
+                },
+                ProfileFieldError::InvalidWebsite,
+                ProfileFieldError::BirthdayInFuture,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_website() {
+        assert!(parse_website("not a url").is_err());
+        assert!(parse_website("localhost").is_err());
+        assert_eq!(parse_website(" ").unwrap(), None);
+        assert_eq!(
+            parse_website("http://example.org")
+                .unwrap()
+                .unwrap()
+                .as_str(),
+            "http://example.org/"
+        );
+    }
+
+    #[test]
+    fn test_draft_round_trips_profile() {
+        let mut profile = SocialProfileEventContent::default();
+        profile.bio = Some("Hi".to_string());
+        profile.birthday = Some("2000-02-29".to_string());
+        let draft = ProfileDraft::new(Some("Bob"), Some(&profile));
+        assert_eq!(draft.display_name, "Bob");
+        let applied = draft.apply_to(profile.clone(), today()).unwrap();
+        assert_eq!(applied.bio, profile.bio);
+        assert_eq!(applied.birthday, profile.birthday);
+    }
+}
@@ -0,0 +1,211 @@
+//! Links and QR codes for sharing social profiles.
+//!
+//! A profile can be shared with its owner's `matrix.to` link, which any Matrix
+//! client or web browser can open, or with a [`PROFILE_LINK_PREFIX`] link,
+//! which opens the profile straight in Robrix (see [`ProfileLinkKind`]).
+//! Either link can be shown as a QR code (see [`profile_qr_code()`]),
+//! so that it can be scanned from another device.
+//!
+//! [`parse_profile_link()`] gets the user back out of either kind of link,
+//! as well as out of `matrix:` URIs.
+//!
+//! The `robrix` and `matrix` schemes are registered with the system through
+//! the `deep_link_protocols` of the packager metadata in `Cargo.toml`, which
+//! end up in `packaging/Info.plist` on macOS and in the `MimeType` and `%u`
+//! argument of the Linux `.desktop` file. Only links passed as a command-line
+//! argument when the system launches Robrix are opened, i.e., on Linux and
+//! Windows: the link is recorded at startup with [`record_launch_profile_link()`]
+//! and the profile is opened after login with [`take_launch_profile_link()`].
+//! Links that macOS delivers as Apple Events, links opened on mobile, and links
+//! opened while Robrix is already running aren't handled, since Makepad doesn't
+//! pass them on to the app.
+
+use matrix_sdk::ruma::{matrix_uri::MatrixId, MatrixToUri, MatrixUri, OwnedUserId, UserId};
+use qrcode::{types::QrError, Color, QrCode};
+use std::sync::Mutex;
+
+/// The prefix of the links that open a profile in Robrix,
+/// followed by the profile owner's user ID.
+pub const PROFILE_LINK_PREFIX: &str = "robrix://profile/";
+
+/// The number of light modules around a QR code, which scanners need to find it.
+const QR_QUIET_ZONE: usize = 4;
+
+/// The width and height in pixels of a single QR code module.
+///
+/// The code is drawn scaled up so that it stays sharp when shown larger.
+pub const QR_MODULE_SIZE: usize = 8;
+
+/// The user whose profile Robrix was launched to open, until it's opened.
+static LAUNCH_PROFILE_LINK: Mutex<Option<OwnedUserId>> = Mutex::new(None);
+
+/// The kind of link a profile is shared with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProfileLinkKind {
+    /// A `matrix.to` link, which any Matrix client or web browser can open.
+    #[default]
+    MatrixTo,
+    /// A [`PROFILE_LINK_PREFIX`] link, which opens the profile in Robrix.
+    Robrix,
+}
+
+impl ProfileLinkKind {
+    /// Get the link of this kind to the given user's profile.
+    pub fn link(&self, user_id: &UserId) -> String {
+        match self {
+            Self::MatrixTo => user_id.matrix_to_uri().to_string(),
+            Self::Robrix => format!("{PROFILE_LINK_PREFIX}{user_id}"),
+        }
+    }
+}
+
+/// Get the user whose profile a link points to.
+///
+/// Accepts [`PROFILE_LINK_PREFIX`] links, as well as `matrix.to` links
+/// and `matrix:` URIs to users. Returns `None` for any other link.
+pub fn parse_profile_link(link: &str) -> Option<OwnedUserId> {
+    let link = link.trim();
+    if let Some(user_id) = link.strip_prefix(PROFILE_LINK_PREFIX) {
+        return UserId::parse(user_id.trim_end_matches('/')).ok();
+    }
+    let id = MatrixToUri::parse(link)
+        .map(|uri| uri.id().clone())
+        .or_else(|_| MatrixUri::parse(link).map(|uri| uri.id().clone()))
+        .ok()?;
+    match id {
+        MatrixId::User(user_id) => Some(user_id),
+        _ => None,
+    }
+}
+
+/// A QR code drawn as an RGBA image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QrCodeImage {
+    /// The image's pixels, four bytes per pixel, row by row.
+    pub rgba: Vec<u8>,
+    /// The width and height of the square image in pixels.
+    pub size: usize,
+}
+
+/// Draw a QR code of the given link, with dark modules on a light background
+/// and a quiet zone around it.
+///
+/// Each module is [`QR_MODULE_SIZE`] pixels wide.
+///
+/// # Errors
+/// Returns an error if the link is too long to fit in a QR code.
+pub fn profile_qr_code(link: &str) -> Result<QrCodeImage, ProfileLinkError> {
+    let code = QrCode::new(link.as_bytes())?;
+    let modules = code.width();
+    let colors = code.to_colors();
+
+    let size = (modules + 2 * QR_QUIET_ZONE) * QR_MODULE_SIZE;
+    let mut rgba = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let module = (
+                (x / QR_MODULE_SIZE).checked_sub(QR_QUIET_ZONE),
+                (y / QR_MODULE_SIZE).checked_sub(QR_QUIET_ZONE),
+            );
+            let is_dark = match module {
+                (Some(column), Some(row)) if column < modules && row < modules => {
+                    colors[row * modules + column] == Color::Dark
+                }
+                _ => false,
+            };
+            let value = if is_dark { 0x00 } else { 0xFF };
+            rgba.extend_from_slice(&[value, value, value, 0xFF]);
+        }
+    }
+    Ok(QrCodeImage { rgba, size })
+}
+
+/// Remember the first profile link among the arguments Robrix was launched with,
+/// so that the profile can be opened once the user is logged in.
+pub fn record_launch_profile_link(args: impl IntoIterator<Item = String>) {
+    let Some(user_id) = args.into_iter().find_map(|arg| parse_profile_link(&arg)) else {
+        return;
+    };
+    if let Ok(mut link) = LAUNCH_PROFILE_LINK.lock() {
+        *link = Some(user_id);
+    }
+}
+
+/// Take the user whose profile Robrix was launched to open, if any.
+pub fn take_launch_profile_link() -> Option<OwnedUserId> {
+    LAUNCH_PROFILE_LINK.lock().ok()?.take()
+}
+
+/// Errors that can occur when sharing a profile.
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileLinkError {
+    /// The link could not be encoded as a QR code.
+    #[error("Cannot draw a QR code of the link: {0}")]
+    QrCode(#[from] QrError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alice() -> OwnedUserId {
+        "@alice:example.org".try_into().unwrap()
+    }
+
+    #[test]
+    fn test_profile_links_round_trip() {
+        for kind in [ProfileLinkKind::MatrixTo, ProfileLinkKind::Robrix] {
+            assert_eq!(parse_profile_link(&kind.link(&alice())), Some(alice()));
+        }
+        assert_eq!(
+            ProfileLinkKind::Robrix.link(&alice()),
+            "robrix://profile/@alice:example.org"
+        );
+    }
+
+    #[test]
+    fn test_parse_profile_link() {
+        assert_eq!(
+            parse_profile_link("robrix://profile/@alice:example.org/"),
+            Some(alice())
+        );
+        assert_eq!(
+            parse_profile_link("matrix:u/alice:example.org"),
+            Some(alice())
+        );
+        assert_eq!(parse_profile_link("robrix://profile/alice"), None);
+        assert_eq!(
+            parse_profile_link("https://matrix.to/#/!room:example.org"),
+            None
+        );
+        assert_eq!(parse_profile_link("https://example.org"), None);
+    }
+
+    #[test]
+    fn test_qr_code_has_quiet_zone() {
+        let image = profile_qr_code(&ProfileLinkKind::Robrix.link(&alice())).unwrap();
+        assert_eq!(image.size % QR_MODULE_SIZE, 0);
+        assert_eq!(image.rgba.len(), image.size * image.size * 4);
+
+        let pixel = |x: usize, y: usize| &image.rgba[(y * image.size + x) * 4..][..4];
+        // The quiet zone is light
+        assert_eq!(pixel(0, 0), [0xFF, 0xFF, 0xFF, 0xFF]);
+        // The top-left finder pattern starts right after it
+        let start = QR_QUIET_ZONE * QR_MODULE_SIZE;
+        assert_eq!(pixel(start, start), [0x00, 0x00, 0x00, 0xFF]);
+        assert_eq!(pixel(start - 1, start), [0xFF, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn test_launch_profile_link() {
+        record_launch_profile_link(["robrix".to_string(), "--flag".to_string()]);
+        assert_eq!(take_launch_profile_link(), None);
+
+        record_launch_profile_link([
+            "robrix".to_string(),
+            "robrix://profile/@alice:example.org".to_string(),
+        ]);
+        assert_eq!(take_launch_profile_link(), Some(alice()));
+        assert_eq!(take_launch_profile_link(), None);
+    }
+}
@@ -0,0 +1,193 @@
+//! Image preparation for profile cover photos and avatars.
+//!
+//! Images picked by the user are center-cropped to the shape they are shown
+//! in on the profile page and scaled down to a reasonable size before being
+//! uploaded, so that the preview shown while editing matches what others see.
+
+use makepad_widgets::image_cache::ImageBuffer;
+
+use crate::shared::image_viewer::get_png_or_jpg_image_buffer;
+
+/// The kind of image shown on a profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileImageKind {
+    /// The wide banner at the top of the profile page.
+    CoverPhoto,
+    /// The round profile picture.
+    Avatar,
+}
+
+impl ProfileImageKind {
+    /// Width divided by height of the image after cropping.
+    pub fn aspect_ratio(&self) -> f64 {
+        match self {
+            Self::CoverPhoto => 3.0,
+            Self::Avatar => 1.0,
+        }
+    }
+
+    /// Maximum width of the image after cropping, in pixels.
+    pub fn max_width(&self) -> usize {
+        match self {
+            Self::CoverPhoto => 1500,
+            Self::Avatar => 512,
+        }
+    }
+}
+
+/// A crop rectangle in pixels, along with the size of the output image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CropPlan {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    output_width: usize,
+    output_height: usize,
+}
+
+impl CropPlan {
+    /// Plan a centered crop of a `width`x`height` image for the given kind.
+    fn new(width: usize, height: usize, kind: ProfileImageKind) -> Self {
+        let aspect_ratio = kind.aspect_ratio();
+        let (crop_width, crop_height) = if width as f64 / height as f64 > aspect_ratio {
+            ((height as f64 * aspect_ratio).round() as usize, height)
+        } else {
+            (width, (width as f64 / aspect_ratio).round() as usize)
+        };
+        let crop_width = crop_width.clamp(1, width);
+        let crop_height = crop_height.clamp(1, height);
+
+        let output_width = crop_width.min(kind.max_width());
+        let output_height = ((output_width as f64 / aspect_ratio).round() as usize).max(1);
+        Self {
+            x: (width - crop_width) / 2,
+            y: (height - crop_height) / 2,
+            width: crop_width,
+            height: crop_height,
+            output_width,
+            output_height,
+        }
+    }
+
+    /// Whether applying this plan would leave the image unchanged.
+    fn is_noop(&self, width: usize, height: usize) -> bool {
+        self.width.abs_diff(width) <= 1
+            && self.height.abs_diff(height) <= 1
+            && self.output_width == self.width
+    }
+
+    /// Crop and scale the given row-major pixels, using nearest-neighbor sampling.
+    fn apply(&self, pixels: &[u32], image_width: usize) -> Vec<u32> {
+        let mut output = Vec::with_capacity(self.output_width * self.output_height);
+        for out_y in 0..self.output_height {
+            let y = self.y + (out_y * self.height / self.output_height).min(self.height - 1);
+            for out_x in 0..self.output_width {
+                let x = self.x + (out_x * self.width / self.output_width).min(self.width - 1);
+                output.push(pixels[y * image_width + x]);
+            }
+        }
+        output
+    }
+}
+
+/// Crop and scale an image for use as a profile cover photo or avatar.
+///
+/// The image is center-cropped to the kind's aspect ratio and scaled down
+/// to at most its maximum width. Images that already have the right shape
+/// and size are returned unchanged; otherwise, the result is a PNG.
+///
+/// This decodes the whole image, so it should not be called on the UI thread.
+///
+/// # Errors
+/// Returns an error if the image is not a valid PNG or JPEG,
+/// or if the cropped image cannot be encoded.
+pub fn prepare_profile_image(
+    data: Vec<u8>,
+    kind: ProfileImageKind,
+) -> Result<Vec<u8>, ProfileImageError> {
+    let buffer: ImageBuffer =
+        get_png_or_jpg_image_buffer(data.clone()).map_err(|_| ProfileImageError::InvalidImage)?;
+    if buffer.width == 0 || buffer.height == 0 {
+        return Err(ProfileImageError::InvalidImage);
+    }
+
+    let plan = CropPlan::new(buffer.width, buffer.height, kind);
+    if plan.is_noop(buffer.width, buffer.height) {
+        return Ok(data);
+    }
+    let pixels = plan.apply(&buffer.data, buffer.width);
+    encode_png(&pixels, plan.output_width, plan.output_height)
+}
+
+/// Encode ARGB pixels as an RGBA PNG.
+fn encode_png(pixels: &[u32], width: usize, height: usize) -> Result<Vec<u8>, ProfileImageError> {
+    let rgba: Vec<u8> = pixels
+        .iter()
+        .flat_map(|pixel| {
+            let [a, r, g, b] = pixel.to_be_bytes();
+            [r, g, b, a]
+        })
+        .collect();
+
+    let mut png_data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png_data, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&rgba)?;
+    writer.finish()?;
+    Ok(png_data)
+}
+
+/// Errors that can occur when preparing a profile image.
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileImageError {
+    /// The image is not a valid PNG or JPEG.
+    #[error("Invalid or unsupported image")]
+    InvalidImage,
+
+    /// The cropped image could not be encoded.
+    #[error("Failed to encode image: {0}")]
+    Encoding(#[from] png::EncodingError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cover_photo_crop_is_centered() {
+        let plan = CropPlan::new(1200, 900, ProfileImageKind::CoverPhoto);
+        assert_eq!(
+            (plan.x, plan.y, plan.width, plan.height),
+            (0, 250, 1200, 400)
+        );
+        assert_eq!((plan.output_width, plan.output_height), (1200, 400));
+        assert!(!plan.is_noop(1200, 900));
+    }
+
+    #[test]
+    fn test_avatar_crop_is_scaled_down() {
+        let plan = CropPlan::new(4000, 3000, ProfileImageKind::Avatar);
+        assert_eq!(
+            (plan.x, plan.y, plan.width, plan.height),
+            (500, 0, 3000, 3000)
+        );
+        assert_eq!((plan.output_width, plan.output_height), (512, 512));
+    }
+
+    #[test]
+    fn test_fitting_image_is_unchanged() {
+        let plan = CropPlan::new(300, 300, ProfileImageKind::Avatar);
+        assert!(plan.is_noop(300, 300));
+    }
+
+    #[test]
+    fn test_apply_crops_pixels() {
+        // A 4x2 image cropped to a 2x2 square keeps the middle columns
+        let pixels = [0, 1, 2, 3, 10, 11, 12, 13];
+        let plan = CropPlan::new(4, 2, ProfileImageKind::Avatar);
+        assert_eq!(plan.apply(&pixels, 4), vec![1, 2, 11, 12]);
+    }
+}
@@ -5,18 +5,25 @@
 //! manages the creation, discovery, and updating of user profile rooms.
 
 use matrix_sdk::{
+    deserialized_responses::RawSyncOrStrippedState,
     ruma::{
-        api::client::room::create_room::v3::Request as CreateRoomRequest,
-        events::room::{
-            join_rules::{JoinRule, RoomJoinRulesEventContent},
-            history_visibility::{HistoryVisibility, RoomHistoryVisibilityEventContent},
+        api::client::{error::ErrorKind, room::create_room::v3::Request as CreateRoomRequest},
+        events::{
+            room::{
+                canonical_alias::RoomCanonicalAliasEventContent,
+                join_rules::{JoinRule, RoomJoinRulesEventContent},
+                history_visibility::{HistoryVisibility, RoomHistoryVisibilityEventContent},
+            },
+            SyncStateEvent,
         },
-        OwnedRoomAliasId, OwnedRoomId, RoomId, UserId,
+        OwnedMxcUri, OwnedRoomAliasId, OwnedRoomId, RoomId, UserId,
     },
     Client,
 };
 use robrix_social_events::profile::SocialProfileEventContent;
 
+use crate::social::profile_media::{prepare_profile_image, ProfileImageError, ProfileImageKind};
+
 /// Profile room configuration
 pub struct ProfileRoomConfig {
     /// Room alias format: #profile_{localpart}:{server}
@@ -61,17 +68,21 @@ impl ProfileRoomService {
     ///
     /// This creates a new Matrix room configured as a profile room,
     /// with the initial profile content set as a state event.
+    /// The room is registered under the user's profile alias (see
+    /// [`find_profile_room()`](Self::find_profile_room)), which is also
+    /// set as its canonical alias.
     ///
     /// # Errors
     /// Returns an error if the user is not logged in, if a profile room
-    /// already exists, or if the Matrix API call fails.
+    /// already exists, if the profile alias is taken by another room,
+    /// or if the Matrix API call fails.
     pub async fn create_profile_room(
         &self,
         initial_profile: SocialProfileEventContent,
     ) -> Result<OwnedRoomId, ProfileRoomError> {
         let user_id = self.client.user_id().ok_or(ProfileRoomError::NotLoggedIn)?;
 
-        let _alias = self.profile_alias_for_user(user_id)?;
+        let alias = self.profile_alias_for_user(user_id)?;
 
         // Check if room already exists
         if let Some(room_id) = self.find_profile_room(user_id).await? {
@@ -82,20 +93,31 @@ impl ProfileRoomService {
         let mut request = CreateRoomRequest::new();
         request.name = Some(format!("{}'s Profile", user_id.localpart()));
         request.topic = Some("Social profile room".to_string());
+        request.room_alias_name = Some(format!(
+            "{}{}",
+            self.config.alias_prefix,
+            user_id.localpart()
+        ));
 
-        // Set initial state events for join rules and history visibility
+        // Join rules and history visibility, sent once the room exists
         let join_rules = RoomJoinRulesEventContent::new(self.config.default_join_rule.clone());
         let history_visibility =
             RoomHistoryVisibilityEventContent::new(self.config.default_history_visibility.clone());
 
-        // Note: Setting initial state events and room alias requires
-        // building the full request with proper event serialization.
-        // For now, we create a basic room and update it.
-        let response = self
-            .client
-            .create_room(request)
-            .await
-            .map_err(ProfileRoomError::MatrixError)?;
+        let response = match self.client.create_room(request).await {
+            Ok(response) => response,
+            // The alias was registered between our lookup and the creation,
+            // either by a concurrent profile room creation or by someone else.
+            Err(e) if e.client_api_error_kind() == Some(&ErrorKind::RoomInUse) => {
+                return Err(match self.find_profile_room(user_id).await? {
+                    Some(room_id) if self.client.get_room(&room_id).is_some() => {
+                        ProfileRoomError::AlreadyExists(room_id)
+                    }
+                    _ => ProfileRoomError::AliasTaken(alias),
+                });
+            }
+            Err(e) => return Err(ProfileRoomError::MatrixError(e)),
+        };
 
         let room_id = response.room_id().to_owned();
 
@@ -115,6 +137,16 @@ impl ProfileRoomService {
             room.send_state_event(initial_profile)
                 .await
                 .map_err(ProfileRoomError::MatrixError)?;
+
+            // Servers should set the canonical alias when creating a room
+            // with an alias, but not all do.
+            if room.canonical_alias().as_ref() != Some(&alias) {
+                let mut canonical_alias = RoomCanonicalAliasEventContent::new();
+                canonical_alias.alias = Some(alias);
+                room.send_state_event(canonical_alias)
+                    .await
+                    .map_err(ProfileRoomError::MatrixError)?;
+            }
         }
 
         Ok(room_id)
@@ -164,25 +196,129 @@ impl ProfileRoomService {
         Ok(())
     }
 
+    /// Upload a cover photo and set it as the profile's cover image.
+    ///
+    /// The image must be a PNG or JPEG. It is cropped to the cover photo's
+    /// shape (see [`prepare_profile_image()`]) before being uploaded, and the
+    /// uploaded MXC URI is stored in the profile's `cover_image` field,
+    /// keeping all other profile fields unchanged.
+    ///
+    /// # Returns
+    /// The MXC URI of the uploaded image.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the room has no profile,
+    /// the image is invalid, or the upload or state update fails.
+    pub async fn set_cover_photo(
+        &self,
+        room_id: &RoomId,
+        image: Vec<u8>,
+    ) -> Result<OwnedMxcUri, ProfileRoomError> {
+        self.set_profile_image(room_id, image, ProfileImageKind::CoverPhoto)
+            .await
+    }
+
+    /// Upload an avatar and set it as the profile's avatar.
+    ///
+    /// The image must be a PNG or JPEG. It is cropped to a square
+    /// (see [`prepare_profile_image()`]) before being uploaded, and the
+    /// uploaded MXC URI is stored in the profile's `avatar` field,
+    /// keeping all other profile fields unchanged.
+    ///
+    /// # Returns
+    /// The MXC URI of the uploaded image.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the room has no profile,
+    /// the image is invalid, or the upload or state update fails.
+    pub async fn set_profile_avatar(
+        &self,
+        room_id: &RoomId,
+        image: Vec<u8>,
+    ) -> Result<OwnedMxcUri, ProfileRoomError> {
+        self.set_profile_image(room_id, image, ProfileImageKind::Avatar)
+            .await
+    }
+
+    /// Crop and upload a profile image, then store its URI in the profile.
+    async fn set_profile_image(
+        &self,
+        room_id: &RoomId,
+        image: Vec<u8>,
+        kind: ProfileImageKind,
+    ) -> Result<OwnedMxcUri, ProfileRoomError> {
+        let room = self
+            .client
+            .get_room(room_id)
+            .ok_or(ProfileRoomError::RoomNotFound)?;
+        let mut profile = self
+            .get_profile(room_id)
+            .await?
+            .ok_or(ProfileRoomError::ProfileNotFound)?;
+
+        let image = prepare_profile_image(image, kind)?;
+        let content_type = match imghdr::from_bytes(&image) {
+            Some(imghdr::Type::Png) => mime::IMAGE_PNG,
+            Some(imghdr::Type::Jpeg) => mime::IMAGE_JPEG,
+            _ => return Err(ProfileRoomError::Image(ProfileImageError::InvalidImage)),
+        };
+
+        let response = self
+            .client
+            .media()
+            .upload(&content_type, image, None)
+            .await
+            .map_err(ProfileRoomError::MatrixError)?;
+
+        match kind {
+            ProfileImageKind::CoverPhoto => {
+                profile.cover_image = Some(response.content_uri.clone());
+            }
+            ProfileImageKind::Avatar => profile.avatar = Some(response.content_uri.clone()),
+        }
+        room.send_state_event(profile)
+            .await
+            .map_err(ProfileRoomError::MatrixError)?;
+
+        Ok(response.content_uri)
+    }
+
     /// Get the profile from a profile room.
     ///
-    /// Retrieves the current profile state event from the given room.
-    /// Note: This is a placeholder implementation for Phase 2.
-    /// Full state event retrieval will be implemented in a later phase.
+    /// Retrieves the current `org.social.profile` state event from the given room.
+    /// Returns `None` if the room has no profile or the profile was redacted.
+    ///
+    /// # Errors
+    /// Returns an error if the room is not found, the state cannot be loaded,
+    /// or the profile content is malformed.
     pub async fn get_profile(
         &self,
         room_id: &RoomId,
     ) -> Result<Option<SocialProfileEventContent>, ProfileRoomError> {
-        let _room = self
+        let room = self
             .client
             .get_room(room_id)
             .ok_or(ProfileRoomError::RoomNotFound)?;
 
-        // TODO: Implement state event retrieval once we have the proper
-        // ruma event types wired up. For now, return None.
-        // The full implementation would use:
-        // room.get_state_event_static::<SocialProfileEventContent>()
-        Ok(None)
+        let Some(raw_event) = room
+            .get_state_event_static::<SocialProfileEventContent>()
+            .await
+            .map_err(ProfileRoomError::MatrixError)?
+        else {
+            return Ok(None);
+        };
+
+        match raw_event {
+            RawSyncOrStrippedState::Sync(raw) => match raw.deserialize() {
+                Ok(SyncStateEvent::Original(event)) => Ok(Some(event.content)),
+                Ok(SyncStateEvent::Redacted(_)) => Ok(None),
+                Err(e) => Err(ProfileRoomError::InvalidProfile(e)),
+            },
+            // Stripped state (e.g., of an invite) carries the content only
+            RawSyncOrStrippedState::Stripped(raw) => raw
+                .get_field::<SocialProfileEventContent>("content")
+                .map_err(ProfileRoomError::InvalidProfile),
+        }
     }
 
     /// Get profile alias for a user.
@@ -236,6 +372,22 @@ pub enum ProfileRoomError {
     #[error("Invalid room alias")]
     InvalidAlias,
 
+    /// The profile alias is already used by a room that isn't ours.
+    #[error("Profile alias {0} is already taken")]
+    AliasTaken(OwnedRoomAliasId),
+
+    /// The profile state event could not be deserialized.
+    #[error("Invalid profile: {0}")]
+    InvalidProfile(#[source] serde_json::Error),
+
+    /// The profile room has no profile state event.
+    #[error("Profile not found")]
+    ProfileNotFound,
+
+    /// A cover photo or avatar could not be prepared for upload.
+    #[error("Profile image error: {0}")]
+    Image(#[from] ProfileImageError),
+
     /// An error occurred in the Matrix SDK.
     #[error("Matrix error: {0}")]
     MatrixError(#[from] matrix_sdk::Error),
@@ -0,0 +1,215 @@
+//! Follower, following, and friend counts for social profiles.
+//!
+//! Counts are computed locally from room memberships: followers are the
+//! members of a user's public feed, following are the public feeds the
+//! current user has joined, and friends are the children of the friends
+//! space. Profile owners can hide each count from others via
+//! [`ProfileCountsPrivacy`].
+
+use matrix_sdk::{
+    ruma::{OwnedUserId, UserId},
+    Client, RoomMemberships,
+};
+use robrix_social_events::profile::ProfileCountsPrivacy;
+
+use crate::social::{
+    feed_room::{FeedPrivacy, FeedRoomError, FeedRoomService},
+    friends::{FriendsError, FriendsSpaceService},
+};
+
+/// Social counts shown on a profile.
+///
+/// A count is `None` if it is hidden by the owner or cannot be computed
+/// by the current user, e.g., another user's following count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProfileCounts {
+    /// Number of users following the profile owner's public feed.
+    pub followers: Option<u32>,
+    /// Number of public feeds the profile owner follows.
+    pub following: Option<u32>,
+    /// Number of friends of the profile owner.
+    pub friends: Option<u32>,
+}
+
+impl ProfileCounts {
+    /// Apply the owner's privacy settings to these counts.
+    ///
+    /// Counts are never hidden from the owner themselves.
+    pub fn with_privacy(
+        self,
+        privacy: Option<&ProfileCountsPrivacy>,
+        is_own_profile: bool,
+    ) -> Self {
+        let Some(privacy) = privacy.filter(|_| !is_own_profile) else {
+            return self;
+        };
+        Self {
+            followers: self.followers.filter(|_| !privacy.hide_followers),
+            following: self.following.filter(|_| !privacy.hide_following),
+            friends: self.friends.filter(|_| !privacy.hide_friends),
+        }
+    }
+}
+
+/// Service for computing profile counts and follower lists.
+pub struct ProfileStatsService {
+    client: Client,
+}
+
+impl ProfileStatsService {
+    /// Create a new ProfileStatsService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Compute the counts to show on a user's profile.
+    ///
+    /// The owner's `privacy` settings (from their social profile) are applied
+    /// unless `user_id` is the current user.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or room members cannot be loaded.
+    pub async fn get_counts(
+        &self,
+        user_id: &UserId,
+        privacy: Option<&ProfileCountsPrivacy>,
+    ) -> Result<ProfileCounts, ProfileStatsError> {
+        let own_user_id = self
+            .client
+            .user_id()
+            .ok_or(ProfileStatsError::NotLoggedIn)?;
+        let is_own_profile = user_id == own_user_id;
+
+        let followers = self.followers_of(user_id).await?.map(|f| f.len() as u32);
+        let (following, friends) = if is_own_profile {
+            let following = self.list_following()?.len() as u32;
+            let mut friends_space = FriendsSpaceService::new(self.client.clone());
+            let friends = if friends_space.find_existing_friends_space().await?.is_some() {
+                friends_space.get_friends().await?.len() as u32
+            } else {
+                0
+            };
+            (Some(following), Some(friends))
+        } else {
+            // Other users' joined rooms and friends space are not visible to us
+            (None, None)
+        };
+
+        Ok(ProfileCounts {
+            followers,
+            following,
+            friends,
+        }
+        .with_privacy(privacy, is_own_profile))
+    }
+
+    /// List the users following the current user's public feed.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in or room members cannot be loaded.
+    pub async fn list_followers(&self) -> Result<Vec<OwnedUserId>, ProfileStatsError> {
+        let user_id = self
+            .client
+            .user_id()
+            .ok_or(ProfileStatsError::NotLoggedIn)?;
+        Ok(self.followers_of(user_id).await?.unwrap_or_default())
+    }
+
+    /// List the owners of the public feeds the current user follows.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in.
+    pub fn list_following(&self) -> Result<Vec<OwnedUserId>, ProfileStatsError> {
+        let mut following: Vec<OwnedUserId> = FeedRoomService::new(self.client.clone())
+            .joined_public_feeds()?
+            .into_iter()
+            .map(|(owner, _)| owner)
+            .collect();
+        following.sort();
+        following.dedup();
+        Ok(following)
+    }
+
+    /// Get the members of a user's public feed, excluding the owner.
+    ///
+    /// Returns `None` if the current user has not joined that feed.
+    async fn followers_of(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<Vec<OwnedUserId>>, ProfileStatsError> {
+        let Some(feed) = FeedRoomService::new(self.client.clone())
+            .find_joined_feed(user_id, FeedPrivacy::Public)
+        else {
+            return Ok(None);
+        };
+
+        let members = feed
+            .members(RoomMemberships::JOIN)
+            .await
+            .map_err(ProfileStatsError::MatrixError)?;
+        Ok(Some(
+            members
+                .iter()
+                .map(|member| member.user_id().to_owned())
+                .filter(|member| member != user_id)
+                .collect(),
+        ))
+    }
+}
+
+/// Errors that can occur when computing profile counts.
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileStatsError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// Feed rooms could not be read.
+    #[error("Feed error: {0}")]
+    Feed(#[from] FeedRoomError),
+
+    /// The friends space could not be read.
+    #[error("Friends error: {0}")]
+    Friends(#[from] FriendsError),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTS: ProfileCounts = ProfileCounts {
+        followers: Some(10),
+        following: Some(20),
+        friends: Some(5),
+    };
+
+    #[test]
+    fn test_privacy_hides_counts_from_others() {
+        let privacy = ProfileCountsPrivacy {
+            hide_followers: true,
+            hide_following: false,
+            hide_friends: true,
+        };
+
+        let counts = COUNTS.with_privacy(Some(&privacy), false);
+        assert_eq!(counts.followers, None);
+        assert_eq!(counts.following, Some(20));
+        assert_eq!(counts.friends, None);
+    }
+
+    #[test]
+    fn test_privacy_never_hides_own_counts() {
+        let privacy = ProfileCountsPrivacy {
+            hide_followers: true,
+            hide_following: true,
+            hide_friends: true,
+        };
+
+        assert_eq!(COUNTS.with_privacy(Some(&privacy), true), COUNTS);
+        assert_eq!(COUNTS.with_privacy(None, false), COUNTS);
+    }
+}
@@ -0,0 +1,270 @@
+//! Posts shown in the tabs of a social profile.
+//!
+//! The Posts tab shows the profile owner's posts in their feeds that the
+//! current user has joined, pinned posts first, as full post cards.
+//! The Media tab shows the image, video and audio posts the profile owner made
+//! in their feeds that the current user has joined, picked out with
+//! [`ContentFilter::MediaOnly`]. The Likes tab, only shown on the user's own
+//! profile, lists the posts they reacted to, as recorded in their
+//! [`ActivityIndex`](crate::social::activity_log::ActivityIndex).
+//! The Events tab, also only shown on the user's own profile, shows the events
+//! they joined or were invited to in a calendar (see [`EventDiscoveryService`]).
+//!
+//! All are loaded through the Matrix worker (see [`MatrixRequest::LoadProfilePosts`],
+//! [`MatrixRequest::LoadProfileMedia`], [`MatrixRequest::LoadLikedPosts`] and
+//! [`MatrixRequest::LoadEventsCalendar`]), which posts a [`ProfileTabAction`]
+//! with the posts or events.
+//!
+//! [`EventDiscoveryService`]: crate::social::discovery::EventDiscoveryService
+//! [`MatrixRequest::LoadProfilePosts`]: crate::sliding_sync::MatrixRequest::LoadProfilePosts
+//! [`MatrixRequest::LoadProfileMedia`]: crate::sliding_sync::MatrixRequest::LoadProfileMedia
+//! [`MatrixRequest::LoadLikedPosts`]: crate::sliding_sync::MatrixRequest::LoadLikedPosts
+//! [`MatrixRequest::LoadEventsCalendar`]: crate::sliding_sync::MatrixRequest::LoadEventsCalendar
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    ruma::{OwnedEventId, OwnedRoomId, OwnedUserId, UserId},
+    Client,
+};
+use std::collections::HashMap;
+
+use crate::social::activity_log::{load_activity_index, ActivityLogError};
+use crate::social::discovery::DiscoveredEvent;
+use crate::social::feed_room::{FeedPrivacy, FeedRoomService};
+use crate::social::newsfeed::{ContentFilter, FeedAggregator, FeedError, FeedFilterSettings, FeedItem};
+use crate::social::reactions::{load_reaction_summary, ReactionSummary};
+use crate::social::widgets::post_card::PostCardData;
+
+/// The maximum number of recent posts read from the feeds shown in a profile tab.
+pub const MAX_PROFILE_TAB_POSTS: usize = 500;
+
+/// The number of posts first shown in the Posts tab, and the number of older
+/// posts loaded each time the user scrolls to the end of them.
+/// The reactions to the posts are loaded one post at a time.
+pub const MAX_PROFILE_POSTS: usize = 50;
+
+/// A tab of the profile page.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProfileTab {
+    /// The owner's posts.
+    #[default]
+    Posts,
+    /// The owner's image, video and audio posts.
+    Media,
+    /// The posts the current user reacted to, on their own profile.
+    Likes,
+    /// The events the current user joined or was invited to, on their own profile.
+    Events,
+}
+
+/// Actions emitted when the posts of a profile tab were loaded.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum ProfileTabAction {
+    /// The posts of a user were loaded, pinned posts first and then newest first.
+    PostsLoaded {
+        user_id: OwnedUserId,
+        posts: Vec<PostCardData>,
+    },
+    /// More posts of a user were loaded after scrolling to the end of the Posts tab,
+    /// including the ones already shown.
+    MorePostsLoaded {
+        user_id: OwnedUserId,
+        posts: Vec<PostCardData>,
+    },
+    /// The media posts of a user were loaded, newest first.
+    MediaLoaded {
+        user_id: OwnedUserId,
+        posts: Vec<FeedItem>,
+    },
+    /// The posts the current user reacted to were loaded, most recently reacted to first.
+    LikesLoaded(Vec<FeedItem>),
+    /// The events the current user joined or was invited to were loaded.
+    EventsLoaded(Vec<DiscoveredEvent>),
+    /// Loading the posts or events of a tab failed.
+    Failed { tab: ProfileTab, error: String },
+    /// No action.
+    None,
+}
+
+/// Get an aggregator of a user's feeds that the current user has joined,
+/// i.e., the feeds whose posts they can read.
+fn joined_feeds_of(client: &Client, user_id: &UserId) -> FeedAggregator {
+    let feeds = FeedRoomService::new(client.clone());
+    let mut aggregator = FeedAggregator::new(client.clone());
+    for privacy in [
+        FeedPrivacy::Public,
+        FeedPrivacy::Friends,
+        FeedPrivacy::CloseFriends,
+    ] {
+        if let Some(room) = feeds.find_joined_feed(user_id, privacy) {
+            aggregator.add_feed_room(room.room_id().to_owned());
+        }
+    }
+    aggregator
+}
+
+/// Load up to `limit` posts a user made in their feeds that the current user
+/// has joined, with their reactions, pinned posts first and then newest first.
+///
+/// Posts whose reactions can't be loaded are shown without them.
+///
+/// # Errors
+/// Returns an error if the feeds cannot be read.
+pub async fn load_profile_posts(
+    client: &Client,
+    user_id: &UserId,
+    limit: usize,
+) -> Result<Vec<PostCardData>, FeedError> {
+    let aggregator = joined_feeds_of(client, user_id);
+    if aggregator.room_count() == 0 {
+        return Ok(Vec::new());
+    }
+
+    let items = aggregator.get_aggregated_feed(limit).await?;
+    let filter = FeedFilterSettings::new().with_authors([user_id.to_owned()]);
+    let mut posts = Vec::new();
+    for item in filter.apply(items) {
+        posts.push(load_post_card(client, item).await);
+    }
+    Ok(posts)
+}
+
+/// Get the post card of a feed item, with the reactions to the post.
+///
+/// A post whose reactions can't be loaded is shown without them.
+pub async fn load_post_card(client: &Client, item: FeedItem) -> PostCardData {
+    let reactions = match client.get_room(&item.room_id) {
+        Some(room) => load_reaction_summary(&room, &item.event_id)
+            .await
+            .unwrap_or_else(|e| {
+                warning!(
+                    "Failed to load the reactions to post {}: {e}",
+                    item.event_id
+                );
+                ReactionSummary::new()
+            }),
+        None => ReactionSummary::new(),
+    };
+    PostCardData::from_feed_item(item, reactions, client.user_id())
+}
+
+/// Load the media posts a user made in their feeds that the current user has joined,
+/// newest first.
+///
+/// # Errors
+/// Returns an error if the feeds cannot be read.
+pub async fn load_profile_media(
+    client: &Client,
+    user_id: &UserId,
+) -> Result<Vec<FeedItem>, FeedError> {
+    let aggregator = joined_feeds_of(client, user_id);
+    if aggregator.room_count() == 0 {
+        return Ok(Vec::new());
+    }
+
+    let posts = aggregator
+        .get_aggregated_feed(MAX_PROFILE_TAB_POSTS)
+        .await?;
+    let filter = FeedFilterSettings::new()
+        .with_content_filter(ContentFilter::MediaOnly)
+        .with_authors([user_id.to_owned()]);
+    Ok(filter.apply(posts))
+}
+
+/// Load the posts the current user reacted to, most recently reacted to first.
+///
+/// Posts that are no longer among the recent posts of their feed,
+/// or whose feed the user left, are left out.
+///
+/// # Errors
+/// Returns an error if the user is not logged in, or if the activity index
+/// or the feeds cannot be read.
+pub async fn load_liked_posts(client: &Client) -> Result<Vec<FeedItem>, ProfileTabError> {
+    let user_id = client.user_id().ok_or(ProfileTabError::NotLoggedIn)?;
+    let liked = load_activity_index(user_id)?.reacted_posts();
+
+    let mut aggregator = FeedAggregator::new(client.clone());
+    for (room_id, _) in &liked {
+        if !aggregator.contains_room(room_id) && client.get_room(room_id).is_some() {
+            aggregator.add_feed_room(room_id.clone());
+        }
+    }
+    if aggregator.room_count() == 0 {
+        return Ok(Vec::new());
+    }
+
+    let posts = aggregator
+        .get_aggregated_feed(MAX_PROFILE_TAB_POSTS)
+        .await?;
+    Ok(order_liked_posts(&liked, posts))
+}
+
+/// Pick the liked posts out of the given posts, in the order they were liked.
+fn order_liked_posts(liked: &[(OwnedRoomId, OwnedEventId)], posts: Vec<FeedItem>) -> Vec<FeedItem> {
+    let mut posts: HashMap<OwnedEventId, FeedItem> = posts
+        .into_iter()
+        .map(|post| (post.event_id.clone(), post))
+        .collect();
+    liked
+        .iter()
+        .filter_map(|(_, event_id)| posts.remove(event_id))
+        .collect()
+}
+
+/// Errors that can occur when loading the posts of a profile tab.
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileTabError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The activity index could not be read.
+    #[error("Activity log error: {0}")]
+    ActivityLog(#[from] ActivityLogError),
+
+    /// The feeds could not be read.
+    #[error("Feed error: {0}")]
+    Feed(#[from] FeedError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::post::PostContent;
+    use matrix_sdk::ruma::MilliSecondsSinceUnixEpoch;
+
+    fn post(event_id: &str) -> FeedItem {
+        FeedItem::new(
+            "!feed:example.org".try_into().unwrap(),
+            event_id.try_into().unwrap(),
+            "@alice:example.org".try_into().unwrap(),
+            MilliSecondsSinceUnixEpoch(0u64.try_into().unwrap()),
+            PostContent::Text {
+                body: "Hello".to_string(),
+                formatted_body: None,
+                mentions: std::collections::BTreeSet::new(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_liked_posts_keep_the_order_they_were_liked() {
+        let room_id: OwnedRoomId = "!feed:example.org".try_into().unwrap();
+        let liked = [
+            (room_id.clone(), "$second:example.org".try_into().unwrap()),
+            (room_id.clone(), "$gone:example.org".try_into().unwrap()),
+            (room_id, "$first:example.org".try_into().unwrap()),
+        ];
+        let posts = vec![
+            post("$first:example.org"),
+            post("$second:example.org"),
+            post("$other:example.org"),
+        ];
+
+        let event_ids: Vec<String> = order_liked_posts(&liked, posts)
+            .into_iter()
+            .map(|post| post.event_id.to_string())
+            .collect();
+        assert_eq!(event_ids, ["$second:example.org", "$first:example.org"]);
+    }
+}
@@ -0,0 +1,304 @@
+//! Push rules for social interactions.
+//!
+//! The server's default push rules don't notify about most social
+//! interactions, so mobile push notifications wouldn't fire for them.
+//! Each [`SocialPushCategory`] can be turned on by installing override push
+//! rules for it in the user's account, and turned off by removing them:
+//! - RSVPs to events (`org.social.rsvp` state events),
+//! - knocks on the user's friends feed, i.e., friend requests,
+//! - reactions in the user's own feeds, i.e., to their posts and to the comments on them.
+//!
+//! Rules are identified by the [`RULE_ID_PREFIX`] and their category's name,
+//! so they're recognized again on any device.
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    ruma::{
+        api::client::push::{delete_pushrule, get_pushrules_all, set_pushrule},
+        push::{Action, NewConditionalPushRule, NewPushRule, PushCondition, RuleKind, Tweak},
+        OwnedRoomId,
+    },
+    Client,
+};
+use std::collections::BTreeSet;
+
+use crate::social::feed_room::{FeedPrivacy, FeedRoomService};
+
+/// The prefix of the IDs of the push rules installed for social interactions.
+pub const RULE_ID_PREFIX: &str = "org.social.push.";
+
+/// The event type of RSVPs, see [`SocialRsvpEventContent`].
+///
+/// [`SocialRsvpEventContent`]: robrix_social_events::rsvp::SocialRsvpEventContent
+const RSVP_EVENT_TYPE: &str = "org.social.rsvp";
+
+/// All feed privacy levels, in the order their rules are installed.
+const ALL_FEEDS: [FeedPrivacy; 3] = [
+    FeedPrivacy::Public,
+    FeedPrivacy::Friends,
+    FeedPrivacy::CloseFriends,
+];
+
+/// A kind of social interaction that can trigger push notifications.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SocialPushCategory {
+    /// Someone RSVPed to an event.
+    Rsvps,
+    /// Someone knocked on the user's friends feed, asking to be friends.
+    FriendKnocks,
+    /// Someone reacted to a post or comment in the user's feeds.
+    Reactions,
+}
+
+impl SocialPushCategory {
+    /// All categories, in the order they're shown.
+    pub const ALL: [SocialPushCategory; 3] = [Self::Rsvps, Self::FriendKnocks, Self::Reactions];
+
+    /// Get the name of this category, as used in the IDs of its push rules.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rsvps => "rsvp",
+            Self::FriendKnocks => "friend_knock",
+            Self::Reactions => "reaction",
+        }
+    }
+
+    /// Get the category of a push rule, if it's one installed for social interactions.
+    pub fn from_rule_id(rule_id: &str) -> Option<Self> {
+        let name = rule_id.strip_prefix(RULE_ID_PREFIX)?;
+        let name = name.split_once('.').map_or(name, |(name, _)| name);
+        Self::ALL
+            .into_iter()
+            .find(|category| category.name() == name)
+    }
+
+    /// Get the push rules to install for this category, given the user's own feed rooms.
+    ///
+    /// Rules that apply to a feed room are only returned for the feeds the user has.
+    pub fn rules(self, own_feeds: &[(FeedPrivacy, OwnedRoomId)]) -> Vec<NewConditionalPushRule> {
+        let event_type = |event_type: &str| PushCondition::EventMatch {
+            key: "type".to_owned(),
+            pattern: event_type.to_owned(),
+        };
+        let in_room = |room_id: &OwnedRoomId| PushCondition::EventMatch {
+            key: "room_id".to_owned(),
+            pattern: room_id.to_string(),
+        };
+        let rule = |suffix: Option<&str>, conditions| {
+            let rule_id = match suffix {
+                Some(suffix) => format!("{RULE_ID_PREFIX}{}.{suffix}", self.name()),
+                None => format!("{RULE_ID_PREFIX}{}", self.name()),
+            };
+            NewConditionalPushRule::new(rule_id, conditions, notify_actions())
+        };
+
+        match self {
+            Self::Rsvps => vec![rule(None, vec![event_type(RSVP_EVENT_TYPE)])],
+            Self::FriendKnocks => own_feeds
+                .iter()
+                .filter(|(privacy, _)| *privacy == FeedPrivacy::Friends)
+                .map(|(_, room_id)| {
+                    let conditions = vec![
+                        event_type("m.room.member"),
+                        PushCondition::EventMatch {
+                            key: "content.membership".to_owned(),
+                            pattern: "knock".to_owned(),
+                        },
+                        in_room(room_id),
+                    ];
+                    rule(None, conditions)
+                })
+                .collect(),
+            Self::Reactions => own_feeds
+                .iter()
+                .map(|(privacy, room_id)| {
+                    let conditions = vec![event_type("m.reaction"), in_room(room_id)];
+                    rule(
+                        Some(privacy.alias_suffix().trim_start_matches('_')),
+                        conditions,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Actions emitted when loading or changing the social push rules.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialPushRulesAction {
+    /// The categories whose push rules are installed were loaded,
+    /// e.g., after turning a category on or off.
+    Loaded(BTreeSet<SocialPushCategory>),
+    /// Turning a category on or off failed.
+    Failed(String),
+    /// No action.
+    None,
+}
+
+/// The actions of the installed rules: notify with the default sound.
+fn notify_actions() -> Vec<Action> {
+    vec![
+        Action::Notify,
+        Action::SetTweak(Tweak::Sound("default".to_owned())),
+    ]
+}
+
+/// Service for installing and removing the push rules for social interactions.
+pub struct PushRuleService {
+    client: Client,
+}
+
+impl PushRuleService {
+    /// Create a new PushRuleService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Get the categories whose push rules are installed in the user's account.
+    ///
+    /// # Errors
+    /// Returns an error if the push rules cannot be loaded.
+    pub async fn enabled_categories(&self) -> Result<BTreeSet<SocialPushCategory>, PushRuleError> {
+        Ok(self
+            .installed_rule_ids()
+            .await?
+            .iter()
+            .filter_map(|rule_id| SocialPushCategory::from_rule_id(rule_id))
+            .collect())
+    }
+
+    /// Turn push notifications for a category on or off,
+    /// by installing or removing its push rules.
+    ///
+    /// # Errors
+    /// Returns an error if the user is not logged in, has no feed the category
+    /// applies to, or the push rules cannot be changed.
+    pub async fn set_category_enabled(
+        &self,
+        category: SocialPushCategory,
+        enabled: bool,
+    ) -> Result<(), PushRuleError> {
+        // Remove the category's rules first, e.g., ones for feeds the user has left
+        for rule_id in self.installed_rule_ids().await? {
+            if SocialPushCategory::from_rule_id(&rule_id) != Some(category) {
+                continue;
+            }
+            let request = delete_pushrule::v3::Request::new(RuleKind::Override, rule_id);
+            self.client
+                .send(request)
+                .await
+                .map_err(|e| PushRuleError::MatrixError(e.into()))?;
+        }
+        if !enabled {
+            return Ok(());
+        }
+
+        let rules = category.rules(&self.own_feeds()?);
+        if rules.is_empty() {
+            return Err(PushRuleError::FeedNotFound);
+        }
+        for rule in rules {
+            let request = set_pushrule::v3::Request::new(NewPushRule::Override(rule));
+            self.client
+                .send(request)
+                .await
+                .map_err(|e| PushRuleError::MatrixError(e.into()))?;
+        }
+        Ok(())
+    }
+
+    /// Get the IDs of the installed push rules for social interactions.
+    async fn installed_rule_ids(&self) -> Result<Vec<String>, PushRuleError> {
+        let response = self
+            .client
+            .send(get_pushrules_all::v3::Request::new())
+            .await
+            .map_err(|e| PushRuleError::MatrixError(e.into()))?;
+        Ok(response
+            .global
+            .override_
+            .iter()
+            .map(|rule| rule.rule_id.clone())
+            .filter(|rule_id| rule_id.starts_with(RULE_ID_PREFIX))
+            .collect())
+    }
+
+    /// Get the current user's joined feed rooms.
+    fn own_feeds(&self) -> Result<Vec<(FeedPrivacy, OwnedRoomId)>, PushRuleError> {
+        let user_id = self.client.user_id().ok_or(PushRuleError::NotLoggedIn)?;
+        let feed_rooms = FeedRoomService::new(self.client.clone());
+        Ok(ALL_FEEDS
+            .into_iter()
+            .filter_map(|privacy| {
+                let room = feed_rooms.find_joined_feed(user_id, privacy)?;
+                Some((privacy, room.room_id().to_owned()))
+            })
+            .collect())
+    }
+}
+
+/// Errors that can occur when changing the social push rules.
+#[derive(Debug, thiserror::Error)]
+pub enum PushRuleError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The user has no feed room the category applies to.
+    #[error("Feed room not found")]
+    FeedNotFound,
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::owned_room_id;
+
+    #[test]
+    fn test_rules_are_recognized_by_their_id() {
+        let own_feeds = [
+            (FeedPrivacy::Public, owned_room_id!("!public:example.org")),
+            (FeedPrivacy::Friends, owned_room_id!("!friends:example.org")),
+        ];
+        for category in SocialPushCategory::ALL {
+            for rule in category.rules(&own_feeds) {
+                assert_eq!(
+                    SocialPushCategory::from_rule_id(&rule.rule_id),
+                    Some(category)
+                );
+            }
+        }
+        assert_eq!(SocialPushCategory::from_rule_id(".m.rule.master"), None);
+        assert_eq!(
+            SocialPushCategory::from_rule_id("org.social.push.unknown"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_feed_rules_need_feeds() {
+        let own_feeds = [(FeedPrivacy::Public, owned_room_id!("!public:example.org"))];
+        assert_eq!(SocialPushCategory::Rsvps.rules(&[]).len(), 1);
+        // Only the friends feed can be knocked on
+        assert!(
+            SocialPushCategory::FriendKnocks
+                .rules(&own_feeds)
+                .is_empty()
+        );
+
+        let reactions = SocialPushCategory::Reactions.rules(&own_feeds);
+        assert_eq!(reactions.len(), 1);
+        assert!(
+            reactions[0]
+                .conditions
+                .contains(&PushCondition::EventMatch {
+                    key: "room_id".to_owned(),
+                    pattern: "!public:example.org".to_owned(),
+                })
+        );
+    }
+}
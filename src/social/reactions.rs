@@ -3,8 +3,30 @@
 //! This module provides types and utilities for aggregating reactions
 //! (emoji responses) from Matrix timeline events. Reactions are a key
 //! social feature that allows users to express quick responses to posts.
-
-use matrix_sdk::ruma::{OwnedEventId, OwnedUserId};
+//! Reaction timestamps can also be bucketed by how long after posting they
+//! arrived, showing authors when their audience is active.
+//!
+//! Feeds can restrict reactions to a curated set of emoji (see
+//! [`SocialFeedConfigEventContent`]); reactions outside of the set are
+//! grouped together as "other" reactions.
+//!
+//! Comments can be reacted to just like posts. Their reactions are kept per
+//! comment in [`CommentReactions`], and count towards the engagement with
+//! the post they comment on.
+
+use matrix_sdk::{
+    room::{IncludeRelations, RelationsOptions, Room},
+    ruma::{
+        api::Direction,
+        events::{
+            reaction::{OriginalSyncReactionEvent, ReactionEventContent},
+            relation::{Annotation, RelationType},
+            AnySyncMessageLikeEvent, AnySyncTimelineEvent, SyncMessageLikeEvent,
+        },
+        EventId, OwnedEventId, OwnedTransactionId, OwnedUserId,
+    },
+};
+use robrix_social_events::feed_config::SocialFeedConfigEventContent;
 use std::collections::{BTreeMap, BTreeSet};
 
 /// Summary of reactions on a post.
@@ -151,6 +173,249 @@ impl ReactionSummary {
     }
 }
 
+/// Reactions to the comments on a post, by comment.
+#[derive(Clone, Debug, Default)]
+pub struct CommentReactions {
+    /// Reaction summary of each comment with reactions, by the comment's event ID.
+    by_comment: BTreeMap<OwnedEventId, ReactionSummary>,
+}
+
+impl CommentReactions {
+    /// Create a new empty set of comment reactions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a reaction to a comment.
+    ///
+    /// See [`ReactionSummary::add_reaction()`].
+    pub fn add_reaction(
+        &mut self,
+        comment_id: OwnedEventId,
+        emoji: impl Into<String>,
+        user_id: OwnedUserId,
+        event_id: OwnedEventId,
+    ) {
+        self.by_comment
+            .entry(comment_id)
+            .or_default()
+            .add_reaction(emoji, user_id, event_id);
+    }
+
+    /// Remove a reaction from a comment.
+    ///
+    /// # Returns
+    /// The event ID of the removed reaction, if it existed.
+    pub fn remove_reaction(
+        &mut self,
+        comment_id: &EventId,
+        emoji: &str,
+        user_id: &OwnedUserId,
+    ) -> Option<OwnedEventId> {
+        let summary = self.by_comment.get_mut(comment_id)?;
+        let removed = summary.remove_reaction(emoji, user_id);
+        if summary.is_empty() {
+            self.by_comment.remove(comment_id);
+        }
+        removed
+    }
+
+    /// Set the reactions to a comment, e.g., as loaded with [`load_reaction_summary()`].
+    pub fn set_summary(&mut self, comment_id: OwnedEventId, summary: ReactionSummary) {
+        if summary.is_empty() {
+            self.by_comment.remove(&comment_id);
+        } else {
+            self.by_comment.insert(comment_id, summary);
+        }
+    }
+
+    /// Get the reactions to a comment, if it has any.
+    pub fn summary(&self, comment_id: &EventId) -> Option<&ReactionSummary> {
+        self.by_comment.get(comment_id)
+    }
+
+    /// Get the total number of reactions to all comments.
+    pub fn total(&self) -> u32 {
+        self.by_comment.values().map(ReactionSummary::total).sum()
+    }
+
+    /// Check if no comment has reactions.
+    pub fn is_empty(&self) -> bool {
+        self.by_comment.is_empty()
+    }
+}
+
+const HOUR_MS: u64 = 60 * 60 * 1000;
+
+/// Buckets of time after posting used by [`reaction_timing()`],
+/// as labels and exclusive upper bounds in milliseconds.
+pub const REACTION_TIMING_BUCKETS: &[(&str, u64)] = &[
+    ("<1h", HOUR_MS),
+    ("1-3h", 3 * HOUR_MS),
+    ("3-6h", 6 * HOUR_MS),
+    ("6-12h", 12 * HOUR_MS),
+    ("12-24h", 24 * HOUR_MS),
+    ("1-3d", 72 * HOUR_MS),
+    ("3-7d", 168 * HOUR_MS),
+    (">7d", u64::MAX),
+];
+
+/// Number of reactions that arrived within one time bucket after posting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReactionTimingBucket {
+    /// Short label of the bucket, e.g., "1-3h".
+    pub label: &'static str,
+    /// Number of reactions in the bucket.
+    pub count: u32,
+}
+
+/// Count reactions by how long after the post they arrived.
+///
+/// Returns one entry per [`REACTION_TIMING_BUCKETS`] bucket, in order.
+/// Reactions timestamped before the post (due to clock skew between
+/// homeservers) are counted in the first bucket.
+pub fn reaction_timing(
+    posted_at_ms: u64,
+    reaction_timestamps_ms: impl IntoIterator<Item = u64>,
+) -> Vec<ReactionTimingBucket> {
+    let mut buckets: Vec<_> = REACTION_TIMING_BUCKETS
+        .iter()
+        .map(|(label, _)| ReactionTimingBucket { label, count: 0 })
+        .collect();
+    for timestamp in reaction_timestamps_ms {
+        let delay = timestamp.saturating_sub(posted_at_ms);
+        let index = REACTION_TIMING_BUCKETS
+            .iter()
+            .position(|(_, upper)| delay < *upper)
+            .unwrap_or(REACTION_TIMING_BUCKETS.len() - 1);
+        buckets[index].count += 1;
+    }
+    buckets
+}
+
+/// Load all reactions to a post or comment.
+///
+/// Redacted reactions are not included.
+async fn load_reactions(
+    room: &Room,
+    event_id: &EventId,
+) -> Result<Vec<OriginalSyncReactionEvent>, matrix_sdk::Error> {
+    let mut reactions = Vec::new();
+    let mut from = None;
+    loop {
+        let options = RelationsOptions {
+            from: from.take(),
+            dir: Direction::Backward,
+            include_relations: IncludeRelations::RelationsOfType(RelationType::Annotation),
+            ..Default::default()
+        };
+        let relations = room.relations(event_id.to_owned(), options).await?;
+
+        for event in &relations.chunk {
+            if let Ok(AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::Reaction(
+                SyncMessageLikeEvent::Original(reaction),
+            ))) = event.raw().deserialize()
+            {
+                reactions.push(reaction);
+            }
+        }
+
+        match relations.next_batch_token {
+            Some(token) if !relations.chunk.is_empty() => from = Some(token),
+            _ => break,
+        }
+    }
+    Ok(reactions)
+}
+
+/// Load the timestamps of all reactions to a post, in milliseconds.
+///
+/// Redacted reactions are not included.
+pub async fn load_reaction_timestamps(
+    room: &Room,
+    event_id: &EventId,
+) -> Result<Vec<u64>, matrix_sdk::Error> {
+    Ok(load_reactions(room, event_id)
+        .await?
+        .into_iter()
+        .map(|reaction| reaction.origin_server_ts.get().into())
+        .collect())
+}
+
+/// Load the reactions to a post or comment.
+///
+/// Redacted reactions are not included.
+pub async fn load_reaction_summary(
+    room: &Room,
+    event_id: &EventId,
+) -> Result<ReactionSummary, matrix_sdk::Error> {
+    let mut summary = ReactionSummary::new();
+    for reaction in load_reactions(room, event_id).await? {
+        summary.add_reaction(
+            reaction.content.relates_to.key,
+            reaction.sender,
+            reaction.event_id,
+        );
+    }
+    Ok(summary)
+}
+
+/// Add the current user's reaction to a post, or redact it if `added` is false,
+/// sending it with the given transaction ID so that its echo confirms the
+/// change shown while it's being sent.
+///
+/// # Returns
+/// The event ID of the reaction or of its redaction, or `None` if there was
+/// no reaction of the user's to redact.
+///
+/// # Errors
+/// Returns an error if the user's reactions cannot be loaded or sending fails.
+pub async fn send_own_reaction(
+    room: &Room,
+    post_id: &EventId,
+    emoji: &str,
+    added: bool,
+    transaction_id: OwnedTransactionId,
+) -> Result<Option<OwnedEventId>, matrix_sdk::Error> {
+    if added {
+        let content =
+            ReactionEventContent::new(Annotation::new(post_id.to_owned(), emoji.to_owned()));
+        let response = room
+            .send(content)
+            .with_transaction_id(transaction_id)
+            .await?;
+        return Ok(Some(response.event_id));
+    }
+
+    let Some(own_user_id) = room.client().user_id().map(ToOwned::to_owned) else {
+        return Ok(None);
+    };
+    let summary = load_reaction_summary(room, post_id).await?;
+    let Some(reaction_id) = summary.get_event_id(&own_user_id, emoji) else {
+        return Ok(None);
+    };
+    let response = room
+        .redact(reaction_id, None, Some(transaction_id))
+        .await
+        .map_err(matrix_sdk::Error::from)?;
+    Ok(Some(response.event_id))
+}
+
+/// Load the reactions to each of the given comments.
+///
+/// Redacted reactions are not included.
+pub async fn load_comment_reactions(
+    room: &Room,
+    comment_ids: impl IntoIterator<Item = OwnedEventId>,
+) -> Result<CommentReactions, matrix_sdk::Error> {
+    let mut reactions = CommentReactions::new();
+    for comment_id in comment_ids {
+        let summary = load_reaction_summary(room, &comment_id).await?;
+        reactions.set_summary(comment_id, summary);
+    }
+    Ok(reactions)
+}
+
 /// Common emoji reactions used in social contexts.
 pub mod common_emojis {
     /// Like/thumbs up reaction.
@@ -178,6 +443,17 @@ pub mod common_emojis {
     pub const QUICK_REACTIONS: &[&str] = &[LIKE, LOVE, LAUGH, WOW, SAD, ANGRY];
 }
 
+/// Get the quick reaction options for a feed with the given configuration.
+///
+/// Feeds that restrict reactions offer their allowed emoji, in order;
+/// other feeds offer [`common_emojis::QUICK_REACTIONS`].
+pub fn quick_reactions(config: &SocialFeedConfigEventContent) -> Vec<&str> {
+    match &config.allowed_reactions {
+        Some(allowed) => allowed.iter().map(String::as_str).collect(),
+        None => common_emojis::QUICK_REACTIONS.to_vec(),
+    }
+}
+
 /// A single reaction entry for display purposes.
 #[derive(Clone, Debug)]
 pub struct ReactionDisplay {
@@ -187,6 +463,9 @@ pub struct ReactionDisplay {
     pub count: u32,
     /// Whether the current user has used this reaction.
     pub is_selected: bool,
+    /// Whether this entry groups reactions the feed doesn't allow,
+    /// in which case `emoji` is only a label.
+    pub is_other: bool,
 }
 
 impl ReactionDisplay {
@@ -196,24 +475,52 @@ impl ReactionDisplay {
             emoji: emoji.into(),
             count,
             is_selected,
+            is_other: false,
+        }
+    }
+
+    /// Create a display entry grouping reactions the feed doesn't allow.
+    pub fn other(count: u32, is_selected: bool) -> Self {
+        Self {
+            emoji: OTHER_REACTIONS_LABEL.to_string(),
+            count,
+            is_selected,
+            is_other: true,
         }
     }
 }
 
+/// Label of the display entry grouping reactions a feed doesn't allow.
+pub const OTHER_REACTIONS_LABEL: &str = "other";
+
 /// Convert a reaction summary to display entries for a specific user.
+///
+/// Reactions not allowed by the feed's `config` are grouped into a
+/// single entry at the end (see [`ReactionDisplay::other()`]).
 pub fn reactions_for_display(
     summary: &ReactionSummary,
     current_user: Option<&OwnedUserId>,
+    config: &SocialFeedConfigEventContent,
 ) -> Vec<ReactionDisplay> {
-    summary
-        .top_reactions(10)
+    let has_user_reacted =
+        |emoji: &str| current_user.is_some_and(|user_id| summary.has_user_reacted(emoji, user_id));
+    let (allowed, other): (Vec<_>, Vec<_>) = summary
+        .top_reactions(usize::MAX)
+        .into_iter()
+        .partition(|(emoji, _)| config.allows_reaction(emoji));
+
+    let mut reactions: Vec<_> = allowed
         .into_iter()
-        .map(|(emoji, count)| {
-            let is_selected =
-                current_user.is_some_and(|user_id| summary.has_user_reacted(emoji, user_id));
-            ReactionDisplay::new(emoji.clone(), count, is_selected)
-        })
-        .collect()
+        .take(10)
+        .map(|(emoji, count)| ReactionDisplay::new(emoji.clone(), count, has_user_reacted(emoji)))
+        .collect();
+    if !other.is_empty() {
+        reactions.push(ReactionDisplay::other(
+            other.iter().map(|(_, count)| count).sum(),
+            other.iter().any(|(emoji, _)| has_user_reacted(emoji)),
+        ));
+    }
+    reactions
 }
 
 #[cfg(test)]
@@ -290,19 +597,85 @@ mod tests {
         assert_eq!(top[1].1, 2);
     }
 
+    #[test]
+    fn test_reaction_timing_buckets() {
+        let posted_at = 1_000 * HOUR_MS;
+        let buckets = reaction_timing(
+            posted_at,
+            [
+                posted_at - 1000,
+                posted_at + 10,
+                posted_at + HOUR_MS,
+                posted_at + 2 * HOUR_MS,
+                posted_at + 30 * 24 * HOUR_MS,
+            ],
+        );
+
+        assert_eq!(buckets.len(), REACTION_TIMING_BUCKETS.len());
+        assert_eq!(buckets[0], ReactionTimingBucket { label: "<1h", count: 2 });
+        assert_eq!(buckets[1], ReactionTimingBucket { label: "1-3h", count: 2 });
+        assert_eq!(buckets.last().unwrap().count, 1);
+        assert_eq!(buckets.iter().map(|b| b.count).sum::<u32>(), 5);
+    }
+
     #[test]
     fn test_reactions_for_display() {
         let mut summary = ReactionSummary::new();
         summary.add_reaction("👍", user_id("alice"), event_id("1"));
         summary.add_reaction("👍", user_id("bob"), event_id("2"));
 
-        let display = reactions_for_display(&summary, Some(&user_id("alice")));
+        let config = SocialFeedConfigEventContent::default();
+        let display = reactions_for_display(&summary, Some(&user_id("alice")), &config);
         assert_eq!(display.len(), 1);
         assert_eq!(display[0].emoji, "👍");
         assert_eq!(display[0].count, 2);
         assert!(display[0].is_selected);
 
-        let display_bob = reactions_for_display(&summary, Some(&user_id("charlie")));
+        let display_bob = reactions_for_display(&summary, Some(&user_id("charlie")), &config);
         assert!(!display_bob[0].is_selected);
     }
+
+    #[test]
+    fn test_disallowed_reactions_are_grouped_as_other() {
+        let mut summary = ReactionSummary::new();
+        summary.add_reaction("👍", user_id("alice"), event_id("1"));
+        summary.add_reaction("🍕", user_id("bob"), event_id("2"));
+        summary.add_reaction("🌮", user_id("alice"), event_id("3"));
+        let config = SocialFeedConfigEventContent {
+            allowed_reactions: Some(vec!["👍".to_string(), "❤️".to_string()]),
+            ..Default::default()
+        };
+
+        let reactions = reactions_for_display(&summary, Some(&user_id("bob")), &config);
+        assert_eq!(reactions.len(), 2);
+        assert_eq!(reactions[0].emoji, "👍");
+        assert!(!reactions[0].is_other);
+        assert!(reactions[1].is_other);
+        assert_eq!(reactions[1].count, 2);
+        assert!(reactions[1].is_selected);
+
+        assert_eq!(quick_reactions(&config), ["👍", "❤️"]);
+        assert_eq!(
+            quick_reactions(&SocialFeedConfigEventContent::default()),
+            common_emojis::QUICK_REACTIONS
+        );
+    }
+
+    #[test]
+    fn test_comment_reactions() {
+        let mut reactions = CommentReactions::new();
+        reactions.add_reaction(event_id("c1"), "👍", user_id("alice"), event_id("1"));
+        reactions.add_reaction(event_id("c1"), "👍", user_id("bob"), event_id("2"));
+        reactions.add_reaction(event_id("c2"), "❤️", user_id("alice"), event_id("3"));
+
+        assert_eq!(reactions.summary(&event_id("c1")).unwrap().count("👍"), 2);
+        assert_eq!(reactions.total(), 3);
+        assert!(reactions.summary(&event_id("c3")).is_none());
+
+        let removed = reactions.remove_reaction(&event_id("c2"), "❤️", &user_id("alice"));
+        assert_eq!(removed, Some(event_id("3")));
+        // Comments without reactions left are dropped
+        assert!(reactions.summary(&event_id("c2")).is_none());
+        assert_eq!(reactions.total(), 2);
+    }
 }
@@ -0,0 +1,432 @@
+//! Automatic deletion of the user's own old posts.
+//!
+//! A feed owner can choose to have their posts in a feed redacted once they
+//! are older than a number of months, e.g., only in their public feed.
+//! Individual posts can be pinned to keep them forever. Policies are stored
+//! privately as room account data on each feed room (see
+//! [`SocialRetentionEventContent`]) and applied by a background job;
+//! a dry-run preview shows which posts would be deleted. Polls count as
+//! posts. Policies are edited, previewed and applied from the social settings
+//! (see [`SocialRetentionSettings`](crate::social::widgets::retention_settings::SocialRetentionSettings)).
+
+use chrono::{DateTime, Months};
+use makepad_widgets::{error, Cx, DefaultNone};
+use matrix_sdk::{
+    room::{MessagesOptions, Room},
+    ruma::{
+        events::{
+            poll::unstable_start::UnstablePollStartEventContent, AnySyncMessageLikeEvent,
+            AnySyncTimelineEvent, SyncMessageLikeEvent,
+        },
+        MilliSecondsSinceUnixEpoch, OwnedEventId, UserId,
+    },
+    Client,
+};
+use robrix_social_events::retention::SocialRetentionEventContent;
+use std::time::Duration;
+use tokio::runtime::Handle;
+
+use crate::social::{
+    availability::is_social_enabled,
+    events::timing::now_ms,
+    feed_room::{FeedPrivacy, FeedRoomService},
+};
+
+/// How long the background job waits after login before its first run,
+/// giving the initial sync time to load the user's feed rooms.
+const JOB_INITIAL_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// How often the background job applies retention policies.
+const JOB_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Delay between redactions, to stay clear of homeserver rate limits.
+const REDACTION_DELAY: Duration = Duration::from_millis(250);
+
+/// Reason attached to redactions made by the retention job.
+const REDACTION_REASON: &str = "Automatically deleted by retention policy";
+
+/// Maximum length of a post preview in a retention report.
+const PREVIEW_MAX_CHARS: usize = 80;
+
+/// One of the user's own posts, as considered by the retention policy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnPost {
+    /// Event ID of the post.
+    pub event_id: OwnedEventId,
+    /// When the post was created.
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+    /// Short plain-text preview of the post.
+    pub preview: String,
+}
+
+/// The outcome of applying (or previewing) a retention policy to a feed.
+#[derive(Clone, Debug)]
+pub struct RetentionReport {
+    /// The feed the policy was applied to.
+    pub feed: FeedPrivacy,
+    /// Whether this was a dry run, in which case nothing was redacted.
+    pub dry_run: bool,
+    /// Posts that are past the retention period and not pinned.
+    pub expired: Vec<OwnPost>,
+    /// Posts that were redacted.
+    pub redacted: Vec<OwnedEventId>,
+    /// Posts that could not be redacted, with the error message.
+    pub failed: Vec<(OwnedEventId, String)>,
+}
+
+/// Actions posted by the background retention job, and when a retention
+/// policy is loaded or run from the retention settings.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum RetentionJobAction {
+    /// The retention policy of a feed was loaded.
+    PolicyLoaded {
+        feed: FeedPrivacy,
+        policy: SocialRetentionEventContent,
+    },
+    /// The job deleted or failed to delete posts in a feed,
+    /// or a policy was previewed or applied from the settings.
+    Completed(RetentionReport),
+    /// No action.
+    None,
+}
+
+/// Get the timestamp before which posts are expired, in milliseconds.
+///
+/// Returns `None` if the policy keeps posts forever.
+pub fn retention_cutoff_ms(policy: &SocialRetentionEventContent, now_ms: u64) -> Option<u64> {
+    let months = policy.max_age_months?;
+    let now = DateTime::from_timestamp_millis(now_ms as i64)?;
+    let cutoff = now.checked_sub_months(Months::new(months))?;
+    Some(cutoff.timestamp_millis().max(0) as u64)
+}
+
+/// Select the posts that the policy would delete.
+///
+/// Posts are expired if they are older than the retention period
+/// and not pinned.
+pub fn expired_posts(
+    posts: &[OwnPost],
+    policy: &SocialRetentionEventContent,
+    now_ms: u64,
+) -> Vec<OwnPost> {
+    let Some(cutoff) = retention_cutoff_ms(policy, now_ms) else {
+        return Vec::new();
+    };
+    posts
+        .iter()
+        .filter(|post| u64::from(post.origin_server_ts.get()) < cutoff)
+        .filter(|post| !policy.pinned.contains(&post.event_id))
+        .cloned()
+        .collect()
+}
+
+/// Service for managing and applying retention policies to the user's own feeds.
+pub struct RetentionService {
+    client: Client,
+}
+
+impl RetentionService {
+    /// Create a new RetentionService.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Get the retention policy of one of the user's feeds.
+    ///
+    /// Returns the default policy (keep forever, nothing pinned) if none is set.
+    ///
+    /// # Errors
+    /// Returns an error if the feed is not found or the stored policy is invalid.
+    pub async fn get_policy(
+        &self,
+        feed: FeedPrivacy,
+    ) -> Result<SocialRetentionEventContent, RetentionError> {
+        let room = self.own_feed(feed)?;
+        let Some(raw) = room
+            .account_data_static::<SocialRetentionEventContent>()
+            .await
+            .map_err(RetentionError::MatrixError)?
+        else {
+            return Ok(SocialRetentionEventContent::default());
+        };
+        Ok(raw.deserialize()?.content)
+    }
+
+    /// Set the retention policy of one of the user's feeds.
+    ///
+    /// # Errors
+    /// Returns an error if the feed is not found or the policy cannot be saved.
+    pub async fn set_policy(
+        &self,
+        feed: FeedPrivacy,
+        policy: SocialRetentionEventContent,
+    ) -> Result<(), RetentionError> {
+        let room = self.own_feed(feed)?;
+        room.set_account_data(policy)
+            .await
+            .map_err(RetentionError::MatrixError)?;
+        Ok(())
+    }
+
+    /// Set the maximum age of posts in a feed, keeping pinned posts unchanged.
+    ///
+    /// Passing `None` disables automatic deletion for the feed.
+    ///
+    /// # Errors
+    /// Returns an error if the feed is not found or the policy cannot be saved.
+    pub async fn set_max_age(
+        &self,
+        feed: FeedPrivacy,
+        max_age_months: Option<u32>,
+    ) -> Result<(), RetentionError> {
+        let mut policy = self.get_policy(feed).await?;
+        policy.max_age_months = max_age_months;
+        self.set_policy(feed, policy).await
+    }
+
+    /// Pin or unpin a post, exempting it from automatic deletion.
+    ///
+    /// # Errors
+    /// Returns an error if the feed is not found or the policy cannot be saved.
+    pub async fn set_pinned(
+        &self,
+        feed: FeedPrivacy,
+        event_id: OwnedEventId,
+        pinned: bool,
+    ) -> Result<(), RetentionError> {
+        let mut policy = self.get_policy(feed).await?;
+        if pinned {
+            policy.pinned.insert(event_id);
+        } else {
+            policy.pinned.remove(&event_id);
+        }
+        self.set_policy(feed, policy).await
+    }
+
+    /// Preview which posts the feed's policy would delete, without deleting anything.
+    ///
+    /// # Errors
+    /// Returns an error if the feed is not found or its history cannot be loaded.
+    pub async fn preview(&self, feed: FeedPrivacy) -> Result<RetentionReport, RetentionError> {
+        self.run(feed, true).await
+    }
+
+    /// Apply the feed's policy, redacting all expired posts.
+    ///
+    /// Failures to redact individual posts are recorded in the report
+    /// rather than aborting the run.
+    ///
+    /// # Errors
+    /// Returns an error if the feed is not found or its history cannot be loaded.
+    pub async fn apply(&self, feed: FeedPrivacy) -> Result<RetentionReport, RetentionError> {
+        self.run(feed, false).await
+    }
+
+    /// Find the feed's expired posts and, unless `dry_run` is set, redact them.
+    async fn run(
+        &self,
+        feed: FeedPrivacy,
+        dry_run: bool,
+    ) -> Result<RetentionReport, RetentionError> {
+        let user_id = self.client.user_id().ok_or(RetentionError::NotLoggedIn)?;
+        let policy = self.get_policy(feed).await?;
+        let mut report = RetentionReport {
+            feed,
+            dry_run,
+            expired: Vec::new(),
+            redacted: Vec::new(),
+            failed: Vec::new(),
+        };
+        if policy.max_age_months.is_none() {
+            return Ok(report);
+        }
+
+        let room = self.own_feed(feed)?;
+        let posts = load_own_posts(&room, user_id).await?;
+        report.expired = expired_posts(&posts, &policy, now_ms());
+        if dry_run {
+            return Ok(report);
+        }
+
+        for post in &report.expired {
+            match room
+                .redact(&post.event_id, Some(REDACTION_REASON), None)
+                .await
+            {
+                Ok(_) => report.redacted.push(post.event_id.clone()),
+                Err(e) => report.failed.push((post.event_id.clone(), e.to_string())),
+            }
+            tokio::time::sleep(REDACTION_DELAY).await;
+        }
+
+        Ok(report)
+    }
+
+    /// Get the user's own joined feed room of the given privacy level.
+    fn own_feed(&self, feed: FeedPrivacy) -> Result<Room, RetentionError> {
+        let user_id = self.client.user_id().ok_or(RetentionError::NotLoggedIn)?;
+        FeedRoomService::new(self.client.clone())
+            .find_joined_feed(user_id, feed)
+            .ok_or(RetentionError::FeedNotFound(feed))
+    }
+}
+
+/// Load all of the user's own, non-redacted posts in a feed room, including polls.
+async fn load_own_posts(room: &Room, user_id: &UserId) -> Result<Vec<OwnPost>, RetentionError> {
+    let mut posts = Vec::new();
+    let mut from = None;
+    loop {
+        let mut options = MessagesOptions::backward();
+        options.from = from.take();
+        let response = room
+            .messages(options)
+            .await
+            .map_err(RetentionError::MatrixError)?;
+
+        for event in &response.chunk {
+            let Ok(AnySyncTimelineEvent::MessageLike(event)) = event.raw().deserialize() else {
+                continue;
+            };
+            if event.sender() != user_id {
+                continue;
+            }
+            let (event_id, origin_server_ts, text) = match event {
+                AnySyncMessageLikeEvent::RoomMessage(SyncMessageLikeEvent::Original(message)) => {
+                    let body = message.content.body().to_owned();
+                    (message.event_id, message.origin_server_ts, body)
+                }
+                // A poll is previewed by its question
+                AnySyncMessageLikeEvent::UnstablePollStart(SyncMessageLikeEvent::Original(
+                    poll,
+                )) => match poll.content {
+                    UnstablePollStartEventContent::New(content) => (
+                        poll.event_id,
+                        poll.origin_server_ts,
+                        content.poll_start.question.text,
+                    ),
+                    UnstablePollStartEventContent::Replacement(_) => continue,
+                },
+                _ => continue,
+            };
+            posts.push(OwnPost {
+                event_id,
+                origin_server_ts,
+                preview: text.chars().take(PREVIEW_MAX_CHARS).collect(),
+            });
+        }
+
+        match response.end {
+            Some(end) if !response.chunk.is_empty() => from = Some(end),
+            _ => break,
+        }
+    }
+    Ok(posts)
+}
+
+/// Spawn the background job that periodically applies retention policies
+/// to all of the user's feeds.
+///
+/// Must be called from within the Tokio runtime. A
+/// [`RetentionJobAction::Completed`] action is posted for every feed
+/// in which posts were deleted or failed to be deleted.
+pub fn spawn_retention_job(client: Client) {
+    Handle::current().spawn(async move {
+        tokio::time::sleep(JOB_INITIAL_DELAY).await;
+        let service = RetentionService::new(client);
+        loop {
+            // Nothing is deleted while social features are disabled for the account
+            if is_social_enabled() {
+                for feed in [
+                    FeedPrivacy::Public,
+                    FeedPrivacy::Friends,
+                    FeedPrivacy::CloseFriends,
+                ] {
+                    match service.apply(feed).await {
+                        Ok(report) if !report.redacted.is_empty() || !report.failed.is_empty() => {
+                            Cx::post_action(RetentionJobAction::Completed(report));
+                        }
+                        Ok(_) | Err(RetentionError::FeedNotFound(_)) => {}
+                        Err(e) => error!("Failed to apply retention policy to {feed}: {e}"),
+                    }
+                }
+            }
+            tokio::time::sleep(JOB_INTERVAL).await;
+        }
+    });
+}
+
+/// Errors that can occur when managing retention policies.
+#[derive(Debug, thiserror::Error)]
+pub enum RetentionError {
+    /// User is not logged in to the Matrix client.
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    /// The user has no joined feed room of this privacy level.
+    #[error("Feed room not found: {0}")]
+    FeedNotFound(FeedPrivacy),
+
+    /// The stored retention policy could not be parsed.
+    #[error("Invalid retention policy: {0}")]
+    InvalidPolicy(#[from] serde_json::Error),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+    fn post(event_id: &str, ts: u64) -> OwnPost {
+        OwnPost {
+            event_id: event_id.try_into().unwrap(),
+            origin_server_ts: MilliSecondsSinceUnixEpoch(ts.try_into().unwrap()),
+            preview: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_no_max_age_keeps_everything() {
+        let posts = vec![post("$old:example.org", 0)];
+        let policy = SocialRetentionEventContent::default();
+        assert!(expired_posts(&posts, &policy, 1_000 * DAY_MS).is_empty());
+    }
+
+    #[test]
+    fn test_expired_posts_respect_age_and_pins() {
+        // 2024-07-01T00:00:00Z
+        let now = 1_719_792_000_000;
+        let posts = vec![
+            post("$recent:example.org", now - 30 * DAY_MS),
+            post("$old:example.org", now - 200 * DAY_MS),
+            post("$pinned:example.org", now - 300 * DAY_MS),
+        ];
+        let mut policy = SocialRetentionEventContent {
+            max_age_months: Some(6),
+            ..Default::default()
+        };
+        policy
+            .pinned
+            .insert("$pinned:example.org".try_into().unwrap());
+
+        let expired = expired_posts(&posts, &policy, now);
+        assert_eq!(expired, vec![posts[1].clone()]);
+    }
+
+    #[test]
+    fn test_cutoff_uses_calendar_months() {
+        // 2024-07-01T00:00:00Z minus 6 months is 2024-01-01T00:00:00Z
+        let policy = SocialRetentionEventContent {
+            max_age_months: Some(6),
+            ..Default::default()
+        };
+        assert_eq!(
+            retention_cutoff_ms(&policy, 1_719_792_000_000),
+            Some(1_704_067_200_000)
+        );
+    }
+}
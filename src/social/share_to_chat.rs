@@ -0,0 +1,314 @@
+//! Forwarding posts into regular Matrix rooms ("Send to chat").
+//!
+//! A post can be sent from its feed room into any ordinary room the user has
+//! joined, e.g., a group chat or a direct message (see
+//! [`ShareToChatService::chat_targets()`]). The forwarded message is the post's
+//! own message, with its text, media and link preview, minus the fields that
+//! only make sense in a feed (see [`forwarded_content()`]). Poll posts are
+//! forwarded as their fallback text, since votes can only be cast in the feed.
+//!
+//! Every forward is validated by [`SharingGuard`] first: forwarding a post to a
+//! wider audience than it was meant for is blocked, and forwards that need the
+//! user's confirmation are only sent once they confirm.
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    ruma::{
+        events::room::message::RoomMessageEventContent, EventId, OwnedRoomId, OwnedUserId, RoomId,
+    },
+    Client,
+};
+use robrix_social_events::{
+    co_author::CO_AUTHOR_FIELD, crosspost::CROSSPOST_FIELD, visibility::VISIBILITY_FIELD,
+};
+
+use crate::social::{
+    feed_room::is_feed_room,
+    poll::{poll_fallback_text, poll_from_content, POLL_START_EVENT_TYPE},
+    post::visibility_from_content,
+    privacy::{ShareValidation, SharingGuard, SharingGuardError},
+};
+
+/// Fields of a post's content that aren't forwarded.
+///
+/// Relations and edits belong to the post's room, mentions would notify people
+/// again, and the post's audience, cross-post ID and co-authors only apply to feeds.
+const FEED_ONLY_FIELDS: [&str; 6] = [
+    "m.relates_to",
+    "m.new_content",
+    "m.mentions",
+    VISIBILITY_FIELD,
+    CROSSPOST_FIELD,
+    CO_AUTHOR_FIELD,
+];
+
+/// A room a post can be sent to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatTarget {
+    /// The room's ID.
+    pub room_id: OwnedRoomId,
+    /// The room's display name.
+    pub name: String,
+}
+
+/// Actions emitted while sending a post to a chat.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum ShareToChatAction {
+    /// The rooms the user can send posts to were loaded.
+    TargetsLoaded(Vec<ChatTarget>),
+    /// The post was sent to the given room.
+    Sent(OwnedRoomId),
+    /// Sending the post to the given room needs the user's confirmation first.
+    NeedsConfirmation {
+        room_id: OwnedRoomId,
+        warning: String,
+    },
+    /// Sending the post to the given room failed or was blocked.
+    Failed { room_id: OwnedRoomId, error: String },
+    /// No action.
+    None,
+}
+
+/// The result of asking to send a post to a chat.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShareToChatOutcome {
+    /// The post was sent.
+    Sent,
+    /// The post wasn't sent, as the user must confirm the given warning first.
+    NeedsConfirmation(String),
+}
+
+/// Get the content to forward a post with, given the raw content of the post's event.
+///
+/// The content is sent as an `m.room.message`: the fields in [`FEED_ONLY_FIELDS`]
+/// are removed, and poll posts are converted to their fallback text.
+/// Returns `None` if the content isn't a post that can be forwarded.
+pub fn forwarded_content(content: &serde_json::Value) -> Option<serde_json::Value> {
+    if content.get(POLL_START_EVENT_TYPE).is_some() {
+        let (question, answers, _) = poll_from_content(content)?;
+        let message = RoomMessageEventContent::text_plain(poll_fallback_text(&question, &answers));
+        return serde_json::to_value(message).ok();
+    }
+
+    let mut content = content.clone();
+    let object = content.as_object_mut()?;
+    if !object
+        .get("msgtype")
+        .is_some_and(serde_json::Value::is_string)
+    {
+        return None;
+    }
+    for field in FEED_ONLY_FIELDS {
+        object.remove(field);
+    }
+    Some(content)
+}
+
+/// Get the users intentionally mentioned in a post, given its raw content.
+pub fn mentioned_users(content: &serde_json::Value) -> Vec<OwnedUserId> {
+    content
+        .get("m.mentions")
+        .and_then(|mentions| mentions.get("user_ids"))
+        .and_then(|user_ids| serde_json::from_value(user_ids.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Service for sending posts to regular rooms.
+pub struct ShareToChatService {
+    client: Client,
+}
+
+impl ShareToChatService {
+    /// Create a new share-to-chat service.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Get the rooms posts can be sent to, sorted by name.
+    ///
+    /// These are the joined rooms that are neither feed rooms nor spaces.
+    pub async fn chat_targets(&self) -> Vec<ChatTarget> {
+        let mut targets = Vec::new();
+        for room in self.client.joined_rooms() {
+            if is_feed_room(&room) || room.is_space() {
+                continue;
+            }
+            let name = match room.display_name().await {
+                Ok(name) => name.to_string(),
+                Err(_) => room.room_id().to_string(),
+            };
+            targets.push(ChatTarget {
+                room_id: room.room_id().to_owned(),
+                name,
+            });
+        }
+        targets.sort_by_cached_key(|target| target.name.to_lowercase());
+        targets
+    }
+
+    /// Send a post to a room, once [`SharingGuard::validate_post_share()`] allows it.
+    ///
+    /// Forwards that need confirmation are only sent if `confirmed` is true;
+    /// otherwise, the warning to confirm is returned.
+    ///
+    /// # Errors
+    /// Returns an error if either room or the post is not found,
+    /// if the post can't be forwarded, if the forward is blocked,
+    /// or if sending the post fails.
+    pub async fn send_to_chat(
+        &self,
+        source_room: &RoomId,
+        post_id: &EventId,
+        target_room: &RoomId,
+        confirmed: bool,
+    ) -> Result<ShareToChatOutcome, ShareToChatError> {
+        let source = self
+            .client
+            .get_room(source_room)
+            .ok_or(ShareToChatError::RoomNotFound)?;
+        let target = self
+            .client
+            .get_room(target_room)
+            .ok_or(ShareToChatError::RoomNotFound)?;
+        let post = source
+            .load_or_fetch_event(post_id, None)
+            .await
+            .map_err(|_| ShareToChatError::PostNotFound)?;
+        let content = post
+            .raw()
+            .get_field::<serde_json::Value>("content")
+            .ok()
+            .flatten()
+            .ok_or(ShareToChatError::PostNotFound)?;
+        let forwarded = forwarded_content(&content).ok_or(ShareToChatError::UnsupportedPost)?;
+
+        let validation = SharingGuard::validate_post_share(
+            &self.client,
+            source_room,
+            visibility_from_content(&content),
+            target_room,
+            &mentioned_users(&content),
+        )
+        .await?;
+        match validation {
+            ShareValidation::Allowed => {}
+            ShareValidation::BlockedPrivacyLeak { message, .. } => {
+                return Err(ShareToChatError::Blocked(message));
+            }
+            ShareValidation::RequiresConfirmation { warning } if !confirmed => {
+                return Ok(ShareToChatOutcome::NeedsConfirmation(warning));
+            }
+            ShareValidation::MissingMentions { missing_users } if !confirmed => {
+                return Ok(ShareToChatOutcome::NeedsConfirmation(format!(
+                    "This post mentions {} people who aren't in this chat.",
+                    missing_users.len()
+                )));
+            }
+            ShareValidation::RequiresConfirmation { .. }
+            | ShareValidation::MissingMentions { .. } => {}
+        }
+
+        target.send_raw("m.room.message", forwarded).await?;
+        Ok(ShareToChatOutcome::Sent)
+    }
+}
+
+/// Errors that can occur when sending a post to a chat.
+#[derive(Debug, thiserror::Error)]
+pub enum ShareToChatError {
+    /// The post's room or the target room was not found.
+    #[error("Room not found")]
+    RoomNotFound,
+
+    /// The post was not found in its room.
+    #[error("Post not found")]
+    PostNotFound,
+
+    /// The post's content can't be forwarded.
+    #[error("This post can't be sent to a chat")]
+    UnsupportedPost,
+
+    /// Sending the post was blocked to protect its audience's privacy.
+    #[error("{0}")]
+    Blocked(String),
+
+    /// The rooms' privacy couldn't be checked.
+    #[error("Privacy check failed: {0}")]
+    SharingGuard(#[from] SharingGuardError),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::social::poll::{poll_start_content, PollAnswer};
+    use serde_json::json;
+
+    #[test]
+    fn test_forwarded_content_drops_feed_only_fields() {
+        let content = json!({
+            "msgtype": "m.text",
+            "body": "Look at this\n\nhttps://example.org",
+            "format": "org.matrix.custom.html",
+            "formatted_body": "<p>Look at this</p><blockquote>…</blockquote>",
+            "m.mentions": { "user_ids": ["@friend:example.org"] },
+            "m.relates_to": { "rel_type": "m.thread", "event_id": "$post:example.org" },
+            (VISIBILITY_FIELD): "friends",
+            (CROSSPOST_FIELD): "abc",
+        });
+        let forwarded = forwarded_content(&content).unwrap();
+        assert_eq!(
+            forwarded,
+            json!({
+                "msgtype": "m.text",
+                "body": "Look at this\n\nhttps://example.org",
+                "format": "org.matrix.custom.html",
+                "formatted_body": "<p>Look at this</p><blockquote>…</blockquote>",
+            })
+        );
+
+        // Media is forwarded as is, without uploading it again
+        let image = json!({
+            "msgtype": "m.image",
+            "body": "Sunset",
+            "url": "mxc://example.org/sunset",
+        });
+        assert_eq!(forwarded_content(&image), Some(image));
+
+        // Content that isn't a message can't be forwarded
+        assert_eq!(forwarded_content(&json!({ "membership": "join" })), None);
+    }
+
+    #[test]
+    fn test_polls_are_forwarded_as_text() {
+        let answers: Vec<PollAnswer> = ["Tea", "Coffee"]
+            .iter()
+            .enumerate()
+            .map(|(index, text)| PollAnswer {
+                id: (index + 1).to_string(),
+                text: text.to_string(),
+            })
+            .collect();
+        let poll = serde_json::to_value(poll_start_content("Tea or coffee?", &answers, 1).unwrap())
+            .unwrap();
+        let forwarded = forwarded_content(&poll).unwrap();
+        assert_eq!(forwarded["msgtype"], "m.text");
+        assert_eq!(forwarded["body"], "Tea or coffee?\n1. Tea\n2. Coffee");
+    }
+
+    #[test]
+    fn test_mentioned_users() {
+        let content = json!({
+            "msgtype": "m.text",
+            "body": "Hi",
+            "m.mentions": { "user_ids": ["@a:example.org", "@b:example.org"] },
+        });
+        let users = mentioned_users(&content);
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0], "@a:example.org");
+        assert!(mentioned_users(&json!({ "body": "Hi" })).is_empty());
+    }
+}
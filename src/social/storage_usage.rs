@@ -0,0 +1,278 @@
+//! Local storage used by social features, and limits on it.
+//!
+//! Social features keep some data on the device to be faster and work offline:
+//! * cached media, i.e., map images of event and post locations
+//!   (see [`maps`](crate::social::maps)),
+//! * the feed cache, i.e., the newsfeed items of the last refresh (see [`FeedCache`]),
+//! * and search indexes, i.e., the user's recent photos and posts, which are
+//!   looked up to warn before posting them again (see [`UploadHashIndex`]
+//!   and [`RecentPostIndex`]), and the user's activity (see [`ActivityIndex`]).
+//!
+//! [`storage_usage()`] measures how much space each category takes up, and
+//! [`clear_storage()`] clears one. The caches can also be limited in size
+//! with the [`CacheLimits`] in the account's `org.social.settings`: once a
+//! cache grows past its limit, its oldest data is dropped. The search indexes
+//! aren't limited, as they already only keep a bounded number of entries.
+//!
+//! [`ActivityIndex`]: crate::social::activity_log::ActivityIndex
+//! [`UploadHashIndex`]: crate::social::duplicate_media::UploadHashIndex
+//! [`RecentPostIndex`]: crate::social::duplicate_posts::RecentPostIndex
+
+use makepad_widgets::*;
+use matrix_sdk::{ruma::UserId, Client};
+use robrix_social_events::settings::CacheLimits;
+use std::{path::Path, sync::Mutex};
+
+use crate::persistence::persistent_state_dir;
+use crate::social::{
+    activity_log::ACTIVITY_INDEX_FILE_NAME,
+    availability::load_settings,
+    duplicate_media::UPLOAD_HASH_INDEX_FILE_NAME,
+    duplicate_posts::RECENT_POSTS_FILE_NAME,
+    main_feed::clear_main_feed_cache,
+    maps::{clear_map_cache, limit_map_cache, map_cache_size},
+    newsfeed::feed_cache::{FeedCache, FeedCacheError, FEED_CACHE_FILE_NAME},
+};
+
+/// The size limits of the caches, from the account's social settings.
+static CACHE_LIMITS: Mutex<CacheLimits> = Mutex::new(CacheLimits {
+    max_media_bytes: None,
+    max_feed_cache_bytes: None,
+});
+
+/// The names of the files, in the user's persistent state directory, holding the search indexes.
+const SEARCH_INDEX_FILE_NAMES: [&str; 3] = [
+    UPLOAD_HASH_INDEX_FILE_NAME,
+    RECENT_POSTS_FILE_NAME,
+    ACTIVITY_INDEX_FILE_NAME,
+];
+
+/// A category of locally stored social data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StorageCategory {
+    /// Cached media, e.g., map images.
+    Media,
+    /// The cached newsfeed.
+    FeedCache,
+    /// Indexes of the user's recent photos and posts, and of their activity.
+    SearchIndex,
+}
+
+impl StorageCategory {
+    /// All categories, in the order they're shown.
+    pub const ALL: [StorageCategory; 3] = [Self::Media, Self::FeedCache, Self::SearchIndex];
+
+    /// Get the name of this category, as shown to the user.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Media => "Cached media",
+            Self::FeedCache => "Feed cache",
+            Self::SearchIndex => "Search indexes",
+        }
+    }
+
+    /// Get the size limit of this category, in bytes, if it has one.
+    pub fn limit(self, limits: &CacheLimits) -> Option<u64> {
+        match self {
+            Self::Media => limits.max_media_bytes,
+            Self::FeedCache => limits.max_feed_cache_bytes,
+            Self::SearchIndex => None,
+        }
+    }
+}
+
+/// How much space each category of social data takes up, in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StorageUsage {
+    /// Size of the cached media.
+    pub media_bytes: u64,
+    /// Size of the cached newsfeed.
+    pub feed_cache_bytes: u64,
+    /// Size of the search indexes.
+    pub search_index_bytes: u64,
+}
+
+impl StorageUsage {
+    /// Get the size of a category, in bytes.
+    pub fn get(&self, category: StorageCategory) -> u64 {
+        match category {
+            StorageCategory::Media => self.media_bytes,
+            StorageCategory::FeedCache => self.feed_cache_bytes,
+            StorageCategory::SearchIndex => self.search_index_bytes,
+        }
+    }
+
+    /// Get the total size of all categories, in bytes.
+    pub fn total(&self) -> u64 {
+        StorageCategory::ALL
+            .iter()
+            .map(|&category| self.get(category))
+            .sum()
+    }
+}
+
+/// Actions emitted when storage usage was measured.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum StorageUsageAction {
+    /// The storage usage was measured, e.g., after clearing a category.
+    Loaded {
+        usage: StorageUsage,
+        limits: CacheLimits,
+    },
+    /// Clearing a category or changing the limits failed.
+    Failed(String),
+    /// No action.
+    None,
+}
+
+/// Get the current size limits of the caches.
+pub fn cache_limits() -> CacheLimits {
+    *CACHE_LIMITS.lock().unwrap()
+}
+
+/// Update the in-memory cache limits from the account's settings.
+pub(crate) fn update_cache_limits(limits: CacheLimits) {
+    *CACHE_LIMITS.lock().unwrap() = limits;
+}
+
+/// Set the size limits of the caches for the logged-in account,
+/// and trim the caches to the new limits at once.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved,
+/// or the feed cache cannot be trimmed.
+pub async fn set_cache_limits(
+    client: &Client,
+    limits: CacheLimits,
+) -> Result<(), StorageUsageError> {
+    let mut settings = load_settings(client).await?;
+    settings.cache_limits = limits;
+    client.account().set_account_data(settings).await?;
+    // Take effect right away rather than once the settings have synced back
+    update_cache_limits(limits);
+    if let Some(user_id) = client.user_id() {
+        enforce_cache_limits(user_id, limits)?;
+    }
+    Ok(())
+}
+
+/// Measure how much space each category of the given user's social data takes up.
+pub fn storage_usage(user_id: &UserId) -> StorageUsage {
+    let dir = persistent_state_dir(user_id);
+    StorageUsage {
+        media_bytes: map_cache_size(),
+        feed_cache_bytes: file_size(&dir.join(FEED_CACHE_FILE_NAME)),
+        search_index_bytes: SEARCH_INDEX_FILE_NAMES
+            .iter()
+            .map(|file_name| file_size(&dir.join(file_name)))
+            .sum(),
+    }
+}
+
+/// Clear a category of the given user's social data.
+///
+/// The caches fill up again as the user browses; clearing the search
+/// indexes means earlier photos and posts are no longer recognized as duplicates,
+/// and the activity log is read from the feeds' history again.
+///
+/// # Errors
+/// Returns an error if a file of the category cannot be deleted.
+pub fn clear_storage(user_id: &UserId, category: StorageCategory) -> Result<(), StorageUsageError> {
+    let dir = persistent_state_dir(user_id);
+    match category {
+        StorageCategory::Media => clear_map_cache(),
+        StorageCategory::FeedCache => {
+            clear_main_feed_cache();
+            remove_file(&dir.join(FEED_CACHE_FILE_NAME))?;
+        }
+        StorageCategory::SearchIndex => {
+            for file_name in SEARCH_INDEX_FILE_NAMES {
+                remove_file(&dir.join(file_name))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Trim the given user's caches to the given limits, dropping their oldest data.
+///
+/// The caches are also kept within the current limits as they're filled,
+/// see [`cache_limits()`].
+///
+/// # Errors
+/// Returns an error if the feed cache cannot be loaded or saved.
+pub fn enforce_cache_limits(
+    user_id: &UserId,
+    limits: CacheLimits,
+) -> Result<(), StorageUsageError> {
+    if let Some(max_bytes) = limits.max_media_bytes {
+        limit_map_cache(max_bytes);
+    }
+    if let Some(max_bytes) = limits.max_feed_cache_bytes {
+        let path = persistent_state_dir(user_id).join(FEED_CACHE_FILE_NAME);
+        if file_size(&path) > max_bytes {
+            let mut cache = FeedCache::load(user_id)?;
+            cache.trim_to_size(max_bytes);
+            cache.save(user_id)?;
+        }
+    }
+    Ok(())
+}
+
+/// Get the size of a file in bytes, or 0 if it doesn't exist.
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map_or(0, |metadata| metadata.len())
+}
+
+/// Delete a file, if it exists.
+fn remove_file(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Errors that can occur when clearing or limiting local social data.
+#[derive(Debug, thiserror::Error)]
+pub enum StorageUsageError {
+    /// A file could not be deleted.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The feed cache could not be trimmed.
+    #[error("Feed cache error: {0}")]
+    FeedCache(#[from] FeedCacheError),
+
+    /// An error occurred in the Matrix SDK.
+    #[error("Matrix error: {0}")]
+    MatrixError(#[from] matrix_sdk::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_usage_total() {
+        let usage = StorageUsage {
+            media_bytes: 1000,
+            feed_cache_bytes: 200,
+            search_index_bytes: 30,
+        };
+        assert_eq!(usage.total(), 1230);
+        assert_eq!(usage.get(StorageCategory::FeedCache), 200);
+    }
+
+    #[test]
+    fn test_only_caches_are_limited() {
+        let limits = CacheLimits {
+            max_media_bytes: Some(100),
+            max_feed_cache_bytes: Some(10),
+        };
+        assert_eq!(StorageCategory::Media.limit(&limits), Some(100));
+        assert_eq!(StorageCategory::FeedCache.limit(&limits), Some(10));
+        assert_eq!(StorageCategory::SearchIndex.limit(&limits), None);
+        assert!(CacheLimits::default().is_unlimited());
+        assert!(!limits.is_unlimited());
+    }
+}
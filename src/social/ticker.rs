@@ -0,0 +1,73 @@
+//! A shared, low-frequency UI ticker for social widgets.
+//!
+//! Widgets that display relative times (countdowns, "happening now" badges)
+//! listen for [`SocialTickerAction::Tick`] in their `handle_actions` instead of
+//! each running their own timer.
+//!
+//! On every tick the ticker also checks whether the device timezone changed,
+//! emitting [`SocialTickerAction::TimezoneChanged`] so event times can be
+//! re-rendered.
+
+use makepad_widgets::*;
+use std::cell::RefCell;
+
+use crate::social::availability::is_social_enabled;
+use crate::social::events::timezone_watcher::{TimezoneChange, TimezoneWatcher};
+
+/// Interval between ticks, in seconds.
+pub const TICK_INTERVAL_SECS: f64 = 30.0;
+
+thread_local! {
+    static TICK_TIMER: RefCell<Timer> = RefCell::new(Timer::empty());
+    static TIMEZONE_WATCHER: RefCell<TimezoneWatcher> = RefCell::new(TimezoneWatcher::new());
+}
+
+/// Actions emitted by the shared social ticker.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialTickerAction {
+    /// Time has passed; re-render any relative times.
+    Tick,
+    /// The device timezone changed; re-render any local times.
+    TimezoneChanged(TimezoneChange),
+    /// No action.
+    None,
+}
+
+/// Drive the shared ticker. Must be called from the app's `handle_event`.
+///
+/// Starts the ticker on startup and emits a [`SocialTickerAction::Tick`]
+/// every [`TICK_INTERVAL_SECS`] seconds while social features are enabled.
+pub fn handle_event(cx: &mut Cx, event: &Event) {
+    if let Event::Startup = event {
+        TICK_TIMER.with_borrow_mut(|timer| *timer = cx.start_interval(TICK_INTERVAL_SECS));
+        return;
+    }
+
+    let ticked = TICK_TIMER.with_borrow(|timer| timer.is_event(event).is_some());
+    if ticked && is_social_enabled() {
+        cx.action(SocialTickerAction::Tick);
+        if let Some(change) = TIMEZONE_WATCHER.with_borrow_mut(|watcher| watcher.check()) {
+            cx.action(SocialTickerAction::TimezoneChanged(change));
+        }
+    }
+}
+
+/// Get the timezone change contained in the given actions, if any.
+pub fn timezone_change(actions: &Actions) -> Option<TimezoneChange> {
+    actions
+        .iter()
+        .find_map(|action| match action.downcast_ref::<SocialTickerAction>() {
+            Some(SocialTickerAction::TimezoneChanged(change)) => Some(change.clone()),
+            _ => None,
+        })
+}
+
+/// Check whether the given actions contain a tick from the shared ticker.
+pub fn is_tick(actions: &Actions) -> bool {
+    actions.iter().any(|action| {
+        matches!(
+            action.downcast_ref::<SocialTickerAction>(),
+            Some(SocialTickerAction::Tick)
+        )
+    })
+}
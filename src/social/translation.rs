@@ -0,0 +1,329 @@
+//! Translation of posts into the user's language.
+//!
+//! Posts are translated by a pluggable [`TranslationProvider`], which the app
+//! sets with [`set_translation_provider()`]. [`LibreTranslateProvider`] works
+//! with any LibreTranslate-compatible service at a configurable endpoint.
+//! The endpoint and API key of the service are stored in the account's
+//! `org.social.settings` account data (see [`set_translation_settings()`]),
+//! and a provider for them is set once they're loaded after login.
+//!
+//! Translations are requested through the Matrix worker (see
+//! [`MatrixRequest::TranslatePost`]), which posts a [`TranslationAction`] once
+//! the translation arrives. Translation services learn what the user reads, so
+//! nothing is translated while [privacy mode](crate::social::privacy::privacy_mode) is on.
+//!
+//! [`MatrixRequest::TranslatePost`]: crate::sliding_sync::MatrixRequest::TranslatePost
+
+use futures_util::future::BoxFuture;
+use makepad_widgets::*;
+use matrix_sdk::{ruma::OwnedEventId, Client};
+use robrix_social_events::settings::TranslationSettings;
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+use crate::social::{availability::load_settings, newsfeed::Language, privacy::is_privacy_mode};
+
+/// A post translated into another language.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Translation {
+    /// The translated text.
+    pub text: String,
+    /// The ISO 639-1 code of the language the post was detected to be written in, if known.
+    pub source_language: Option<String>,
+}
+
+impl Translation {
+    /// Get the name of the language the post was written in, as shown to the user.
+    ///
+    /// Languages without a known name are shown by their code.
+    pub fn source_language_name(&self) -> Option<String> {
+        let code = self.source_language.as_deref()?;
+        Some(
+            Language::from_code(code)
+                .map_or_else(|| code.to_owned(), |language| language.to_string()),
+        )
+    }
+}
+
+/// A service that translates text.
+///
+/// Implementations typically query a remote translation service.
+pub trait TranslationProvider: Send + Sync {
+    /// Translate `text` into the `target` language, detecting the language it's written in.
+    fn translate<'a>(
+        &'a self,
+        text: &'a str,
+        target: Language,
+    ) -> BoxFuture<'a, Result<Translation, TranslationError>>;
+}
+
+/// The provider posts are translated by, if any.
+static TRANSLATION_PROVIDER: Mutex<Option<Arc<dyn TranslationProvider>>> = Mutex::new(None);
+
+/// The translation service set in the account's social settings, if any.
+static TRANSLATION_SETTINGS: Mutex<Option<TranslationSettings>> = Mutex::new(None);
+
+/// Set the provider posts are translated by, or `None` to turn translation off.
+pub fn set_translation_provider(provider: Option<Arc<dyn TranslationProvider>>) {
+    *TRANSLATION_PROVIDER.lock().unwrap() = provider;
+}
+
+/// Get the translation service set in the account's social settings, if any.
+pub fn translation_settings() -> Option<TranslationSettings> {
+    TRANSLATION_SETTINGS.lock().unwrap().clone()
+}
+
+/// Translate posts with the service set in the account's social settings,
+/// or turn translation off if none is set or its endpoint isn't a valid URL.
+pub(crate) fn update_translation_provider(settings: Option<TranslationSettings>) {
+    let provider = settings
+        .as_ref()
+        .and_then(|settings| match Url::parse(&settings.endpoint) {
+            Ok(endpoint) => Some(Arc::new(LibreTranslateProvider::new(
+                endpoint,
+                settings.api_key.clone(),
+            )) as Arc<dyn TranslationProvider>),
+            Err(e) => {
+                warning!("Invalid translation endpoint {}: {e}", settings.endpoint);
+                None
+            }
+        });
+    set_translation_provider(provider);
+    *TRANSLATION_SETTINGS.lock().unwrap() = settings;
+}
+
+/// Set the service posts are translated with for the logged-in account,
+/// or `None` to turn translation off.
+///
+/// # Errors
+/// Returns an error if the account data cannot be loaded or saved.
+pub async fn set_translation_settings(
+    client: &Client,
+    translation: Option<TranslationSettings>,
+) -> Result<(), matrix_sdk::Error> {
+    let mut settings = load_settings(client).await?;
+    settings.translation = translation.clone();
+    client.account().set_account_data(settings).await?;
+    // Take effect right away rather than once the settings have synced back
+    update_translation_provider(translation);
+    Ok(())
+}
+
+/// Whether posts can currently be translated.
+///
+/// This is `false` without a provider or while privacy mode is on.
+pub fn is_translation_available() -> bool {
+    !is_privacy_mode() && TRANSLATION_PROVIDER.lock().unwrap().is_some()
+}
+
+/// Actions emitted when a post was translated.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum TranslationAction {
+    /// A post was translated into the user's language.
+    Translated {
+        event_id: OwnedEventId,
+        translation: Translation,
+    },
+    /// Translating a post failed.
+    Failed {
+        event_id: OwnedEventId,
+        error: String,
+    },
+    /// No action.
+    None,
+}
+
+/// Translate a post's text into the user's language with the current provider,
+/// and post a [`TranslationAction`] with the result.
+///
+/// This is run by the Matrix worker for
+/// [`MatrixRequest::TranslatePost`](crate::sliding_sync::MatrixRequest::TranslatePost).
+pub async fn translate_post(event_id: OwnedEventId, text: String) {
+    let action = match translate(&text, Language::system()).await {
+        Ok(translation) => TranslationAction::Translated {
+            event_id,
+            translation,
+        },
+        Err(e) => {
+            error!("Failed to translate post {event_id}: {e}");
+            TranslationAction::Failed {
+                event_id,
+                error: e.to_string(),
+            }
+        }
+    };
+    Cx::post_action(action);
+}
+
+/// Translate `text` into the `target` language with the current provider.
+///
+/// # Errors
+/// Returns an error if no provider is set, privacy mode is on,
+/// or the provider fails to translate the text.
+pub async fn translate(text: &str, target: Language) -> Result<Translation, TranslationError> {
+    if is_privacy_mode() {
+        return Err(TranslationError::PrivacyMode);
+    }
+    let provider = TRANSLATION_PROVIDER
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or(TranslationError::NoProvider)?;
+    provider.translate(text, target).await
+}
+
+/// A provider for LibreTranslate-compatible translation services.
+pub struct LibreTranslateProvider {
+    /// The base URL of the service, e.g., `https://libretranslate.example.org/`.
+    endpoint: Url,
+    /// The API key, if the service requires one.
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl LibreTranslateProvider {
+    /// Create a provider for the service at `endpoint`.
+    pub fn new(endpoint: Url, api_key: Option<String>) -> Self {
+        Self {
+            endpoint,
+            api_key,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+/// A response of a LibreTranslate-compatible service's `/translate` endpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibreTranslateResponse {
+    translated_text: String,
+    detected_language: Option<DetectedLanguage>,
+}
+
+/// The language a LibreTranslate-compatible service detected the text to be written in.
+#[derive(Deserialize)]
+struct DetectedLanguage {
+    language: String,
+}
+
+impl TranslationProvider for LibreTranslateProvider {
+    fn translate<'a>(
+        &'a self,
+        text: &'a str,
+        target: Language,
+    ) -> BoxFuture<'a, Result<Translation, TranslationError>> {
+        Box::pin(async move {
+            let url = self
+                .endpoint
+                .join("translate")
+                .map_err(|e| TranslationError::InvalidEndpoint(e.to_string()))?;
+            let mut body = serde_json::json!({
+                "q": text,
+                "source": "auto",
+                "target": target.code(),
+                "format": "text",
+            });
+            if let Some(api_key) = &self.api_key {
+                body["api_key"] = api_key.clone().into();
+            }
+            let response = self
+                .client
+                .post(url)
+                .json(&body)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| TranslationError::Network(e.to_string()))?;
+            let response: LibreTranslateResponse = response
+                .json()
+                .await
+                .map_err(|e| TranslationError::InvalidResponse(e.to_string()))?;
+            Ok(Translation {
+                text: response.translated_text,
+                source_language: response.detected_language.map(|detected| detected.language),
+            })
+        })
+    }
+}
+
+/// Errors that can occur when translating a post.
+#[derive(Debug, thiserror::Error)]
+pub enum TranslationError {
+    /// No translation provider is set.
+    #[error("Translation isn't set up")]
+    NoProvider,
+
+    /// Privacy mode is on, so nothing is sent to translation services.
+    #[error("Translation is off in privacy mode")]
+    PrivacyMode,
+
+    /// The translation service's endpoint isn't a valid base URL.
+    #[error("Invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+
+    /// The translation service couldn't be reached.
+    #[error("Network error: {0}")]
+    Network(String),
+
+    /// The translation service returned something unexpected.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_language_name() {
+        let translation = |source_language: Option<&str>| Translation {
+            text: "Hello".to_owned(),
+            source_language: source_language.map(str::to_owned),
+        };
+        assert_eq!(
+            translation(Some("fr")).source_language_name().as_deref(),
+            Some("French")
+        );
+        assert_eq!(
+            translation(Some("sv")).source_language_name().as_deref(),
+            Some("sv")
+        );
+        assert_eq!(translation(None).source_language_name(), None);
+    }
+
+    #[test]
+    fn test_parse_libretranslate_response() {
+        let response: LibreTranslateResponse = serde_json::from_value(serde_json::json!({
+            "translatedText": "Hello",
+            "detectedLanguage": { "confidence": 90.0, "language": "fr" },
+        }))
+        .unwrap();
+        assert_eq!(response.translated_text, "Hello");
+        assert_eq!(response.detected_language.unwrap().language, "fr");
+    }
+
+    #[test]
+    fn test_translation_provider_follows_settings() {
+        let settings = |endpoint: &str| TranslationSettings {
+            endpoint: endpoint.to_owned(),
+            api_key: Some("secret".to_owned()),
+        };
+
+        update_translation_provider(Some(settings("https://libretranslate.example.org/")));
+        assert!(TRANSLATION_PROVIDER.lock().unwrap().is_some());
+        assert_eq!(
+            translation_settings(),
+            Some(settings("https://libretranslate.example.org/"))
+        );
+
+        // An invalid endpoint is kept, so that it can be corrected, but not used
+        update_translation_provider(Some(settings("not a url")));
+        assert!(TRANSLATION_PROVIDER.lock().unwrap().is_none());
+        assert_eq!(translation_settings(), Some(settings("not a url")));
+
+        update_translation_provider(None);
+        assert!(TRANSLATION_PROVIDER.lock().unwrap().is_none());
+        assert_eq!(translation_settings(), None);
+    }
+}
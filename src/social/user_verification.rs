@@ -0,0 +1,107 @@
+//! Verification badges for social profiles and posts.
+//!
+//! A user is shown as verified once the local user has verified their
+//! cross-signing identity. Identities are looked up in the crypto store
+//! through the Matrix worker (see [`MatrixRequest::CheckUserVerification`]),
+//! and the results are cached here so post cards can show a badge without
+//! waiting. Each lookup posts a [`UserVerificationAction::Checked`].
+//!
+//! [`MatrixRequest::CheckUserVerification`]: crate::sliding_sync::MatrixRequest::CheckUserVerification
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    ruma::{OwnedUserId, UserId},
+    Client,
+};
+use matrix_sdk_base::crypto::CryptoStoreError;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+
+/// The verification state of users whose identities were looked up.
+///
+/// Users being looked up are mapped to `None`.
+static VERIFIED_USERS: Mutex<BTreeMap<OwnedUserId, Option<bool>>> = Mutex::new(BTreeMap::new());
+
+/// Actions emitted when a user's verification state was looked up.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum UserVerificationAction {
+    /// Whether the user's cross-signing identity is verified by the local user.
+    Checked {
+        user_id: OwnedUserId,
+        verified: bool,
+    },
+    /// No action.
+    None,
+}
+
+/// Get whether a user's identity is verified by the local user.
+///
+/// Returns `None` if it hasn't been looked up yet, in which case it is,
+/// and a [`UserVerificationAction::Checked`] is posted once it's known.
+pub fn user_verification(user_id: &UserId) -> Option<bool> {
+    let mut verified_users = VERIFIED_USERS.lock().unwrap();
+    if let Some(verified) = verified_users.get(user_id) {
+        return *verified;
+    }
+    verified_users.insert(user_id.to_owned(), None);
+    submit_async_request(MatrixRequest::CheckUserVerification(user_id.to_owned()));
+    None
+}
+
+/// Look up a user's verification state again, e.g., after verifying them.
+pub fn refresh_user_verification(user_id: &UserId) {
+    VERIFIED_USERS.lock().unwrap().remove(user_id);
+    user_verification(user_id);
+}
+
+/// Remember a user's verification state.
+///
+/// Returns whether it changed from what was known before.
+fn record_user_verification(user_id: &UserId, verified: bool) -> bool {
+    VERIFIED_USERS
+        .lock()
+        .unwrap()
+        .insert(user_id.to_owned(), Some(verified))
+        != Some(Some(verified))
+}
+
+/// Look up whether a user's cross-signing identity is verified by the local user,
+/// and post a [`UserVerificationAction::Checked`] with the result.
+///
+/// Users without a known identity count as unverified.
+///
+/// # Errors
+/// Returns an error if the crypto store cannot be read.
+pub async fn check_user_verification(
+    client: &Client,
+    user_id: &UserId,
+) -> Result<bool, CryptoStoreError> {
+    let verified = client
+        .encryption()
+        .get_user_identity(user_id)
+        .await?
+        .is_some_and(|identity| identity.is_verified());
+    record_user_verification(user_id, verified);
+    Cx::post_action(UserVerificationAction::Checked {
+        user_id: user_id.to_owned(),
+        verified,
+    });
+    Ok(verified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matrix_sdk::ruma::user_id;
+
+    #[test]
+    fn test_record_user_verification() {
+        let user = user_id!("@verified:example.org");
+        assert!(record_user_verification(user, false));
+        assert!(!record_user_verification(user, false));
+        assert!(record_user_verification(user, true));
+        assert_eq!(user_verification(user), Some(true));
+    }
+}
@@ -0,0 +1,379 @@
+//! "Your activity" view for reviewing the user's own social activity.
+//!
+//! Lists the user's posts, comments, reactions, RSVPs and follows from a
+//! chosen date range, newest first. Reactions and follows can be selected
+//! and undone in bulk (see [`ActivityLogService`](crate::social::ActivityLogService)),
+//! and posts, comments and reactions lead back to the post they were about.
+//!
+//! It's shown at the end of the social settings, which open the feed room of
+//! the post an activity was about (see [`SocialSettingsScreen`]).
+//!
+//! [`SocialSettingsScreen`]: crate::social::widgets::social_settings::SocialSettingsScreen
+
+use chrono::{DateTime, Local};
+use makepad_widgets::*;
+use matrix_sdk::ruma::{OwnedEventId, OwnedRoomId};
+use std::collections::BTreeSet;
+
+use crate::social::activity_log::{ActivityEntry, ActivityRange, UndoReport};
+use crate::social::events::timing::now_ms;
+
+/// Date ranges offered in the range dropdown, in days, in the order of its labels.
+const RANGE_DAYS_OPTIONS: [u64; 3] = [7, 30, 365];
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+
+    /// An entry in the activity log.
+    ActivityItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 10, bottom: 10 },
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        info_column = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 2,
+
+            date_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                }
+            }
+
+            summary_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: #000,
+                    wrap: Word,
+                }
+            }
+        }
+
+        open_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "Open",
+        }
+
+        select_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "Select",
+        }
+    }
+
+    /// The user's own social activity, with bulk undo.
+    pub SocialActivityLogView = {{SocialActivityLogView}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: #f5f5f5
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            flow: Down,
+            spacing: 12,
+            show_bg: true,
+            draw_bg: {
+                color: #fff
+            }
+
+            title_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Your activity",
+                draw_text: {
+                    text_style: { font_size: 18.0 },
+                    color: #000,
+                }
+            }
+
+            range_row = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+                align: { y: 0.5 },
+
+                <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "Show activity from",
+                    draw_text: {
+                        text_style: { font_size: 14.0 },
+                        color: #000,
+                    }
+                }
+
+                range_dropdown = <DropDown> {
+                    width: Fit,
+                    height: Fit,
+                    labels: ["Last 7 days", "Last 30 days", "Last year"],
+                    selected_item: 1,
+                }
+            }
+
+            undo_button = <Button> {
+                width: Fill,
+                height: Fit,
+                padding: 10,
+                text: "Undo selected",
+                enabled: false,
+            }
+
+            summary_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                    wrap: Word,
+                }
+            }
+        }
+
+        activity_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            activity_item = <ActivityItem> {}
+        }
+    }
+}
+
+/// Actions that can be triggered from the activity log view.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialActivityLogViewAction {
+    /// User picked another date range; its activity should be loaded.
+    RangeSelected(ActivityRange),
+    /// User wants to undo the selected reactions and follows.
+    Undo(Vec<ActivityEntry>),
+    /// User wants to go back to the post an activity was about.
+    OpenPost {
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+    },
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialActivityLogView {
+    #[deref]
+    view: View,
+
+    /// The length of the shown date range, in days.
+    #[rust(30)]
+    range_days: u64,
+
+    /// The shown activity, newest first.
+    #[rust]
+    entries: Vec<ActivityEntry>,
+
+    /// Indices of the entries selected to be undone.
+    #[rust]
+    selected: BTreeSet<usize>,
+}
+
+impl Widget for SocialActivityLogView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, self.entries.len());
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let Some(entry) = self.entries.get(item_id) else {
+                    continue;
+                };
+                let item = list.item(cx, item_id, live_id!(activity_item));
+                self.populate_activity_item(cx, &item, item_id, entry);
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for SocialActivityLogView {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if let Some(selected) = self.drop_down(ids!(range_dropdown)).selected(actions) {
+            if let Some(days) = RANGE_DAYS_OPTIONS.get(selected) {
+                self.range_days = *days;
+                cx.action(SocialActivityLogViewAction::RangeSelected(self.range()));
+            }
+        }
+
+        if self.button(ids!(undo_button)).clicked(actions) && !self.selected.is_empty() {
+            let entries = self
+                .selected
+                .iter()
+                .filter_map(|index| self.entries.get(*index).cloned())
+                .collect();
+            cx.action(SocialActivityLogViewAction::Undo(entries));
+        }
+
+        let activity_list = self.portal_list(ids!(activity_list));
+        for (index, item) in activity_list.items_with_actions(actions) {
+            if item.button(ids!(open_button)).clicked(actions) {
+                let Some(entry) = self.entries.get(index) else {
+                    continue;
+                };
+                if let Some(event_id) = entry.post_id() {
+                    cx.action(SocialActivityLogViewAction::OpenPost {
+                        room_id: entry.room_id.clone(),
+                        event_id: event_id.to_owned(),
+                    });
+                }
+                continue;
+            }
+            if !item.button(ids!(select_button)).clicked(actions) {
+                continue;
+            }
+            if !self.selected.remove(&index) {
+                self.selected.insert(index);
+            }
+            self.update_undo_button(cx);
+            self.redraw(cx);
+        }
+    }
+}
+
+impl SocialActivityLogView {
+    /// Get the date range currently shown.
+    pub fn range(&self) -> ActivityRange {
+        ActivityRange::last_days(self.range_days, now_ms())
+    }
+
+    /// Show the activity loaded for the current range, newest first.
+    pub fn set_entries(&mut self, cx: &mut Cx, entries: Vec<ActivityEntry>) {
+        let summary = match entries.len() {
+            0 => "No activity in this period.".to_string(),
+            1 => "1 activity.".to_string(),
+            n => format!("{} activities.", n),
+        };
+        self.label(ids!(summary_label)).set_text(cx, &summary);
+        self.entries = entries;
+        self.selected.clear();
+        self.update_undo_button(cx);
+        self.redraw(cx);
+    }
+
+    /// Show the result of undoing the selected activities,
+    /// removing the undone ones from the list.
+    pub fn set_undo_report(&mut self, cx: &mut Cx, report: UndoReport) {
+        let summary = if report.failed.is_empty() {
+            format!("Undid {} activities.", report.undone.len())
+        } else {
+            format!(
+                "Undid {} activities, {} could not be undone.",
+                report.undone.len(),
+                report.failed.len()
+            )
+        };
+        self.label(ids!(summary_label)).set_text(cx, &summary);
+
+        self.entries.retain(|entry| !report.undone.contains(entry));
+        self.selected.clear();
+        self.update_undo_button(cx);
+        self.redraw(cx);
+    }
+
+    /// Enable the undo button only while something is selected.
+    fn update_undo_button(&mut self, cx: &mut Cx) {
+        let undo_button = self.button(ids!(undo_button));
+        undo_button.set_enabled(cx, !self.selected.is_empty());
+        let text = match self.selected.len() {
+            0 => "Undo selected".to_string(),
+            n => format!("Undo {} selected", n),
+        };
+        undo_button.set_text(cx, &text);
+    }
+
+    /// Fill in an activity row.
+    fn populate_activity_item(
+        &self,
+        cx: &mut Cx,
+        item: &WidgetRef,
+        index: usize,
+        entry: &ActivityEntry,
+    ) {
+        let date = DateTime::from_timestamp_millis(u64::from(entry.timestamp.get()) as i64)
+            .map(|dt| {
+                dt.with_timezone(&Local)
+                    .format("%b %-d, %Y %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        item.label(ids!(date_label)).set_text(cx, &date);
+        item.label(ids!(summary_label))
+            .set_text(cx, &entry.summary());
+
+        item.button(ids!(open_button))
+            .set_visible(cx, entry.post_id().is_some());
+
+        let select_button = item.button(ids!(select_button));
+        select_button.set_visible(cx, entry.can_undo());
+        let text = if self.selected.contains(&index) {
+            "Selected"
+        } else {
+            "Select"
+        };
+        select_button.set_text(cx, text);
+    }
+}
+
+impl SocialActivityLogViewRef {
+    /// See [`SocialActivityLogView::range()`].
+    pub fn range(&self) -> Option<ActivityRange> {
+        Some(self.borrow()?.range())
+    }
+
+    /// See [`SocialActivityLogView::set_entries()`].
+    pub fn set_entries(&self, cx: &mut Cx, entries: Vec<ActivityEntry>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_entries(cx, entries);
+        }
+    }
+
+    /// See [`SocialActivityLogView::set_undo_report()`].
+    pub fn set_undo_report(&self, cx: &mut Cx, report: UndoReport) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_undo_report(cx, report);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Waveform widget for audio clip posts.
+//!
+//! Draws the waveform of an audio clip as a row of bars, as many as fit in
+//! the widget's width (see [`AudioDetails::levels()`]). The part of the clip
+//! that was already played is drawn in a different color, see
+//! [`AudioWaveform::set_progress()`].
+
+use makepad_widgets::*;
+
+use crate::social::audio_clip::AudioDetails;
+
+live_design! {
+    use link::theme::*;
+    use link::widgets::*;
+
+    /// The waveform of an audio clip, e.g., of a voice note.
+    pub AudioWaveform = {{AudioWaveform}} {
+        width: Fill,
+        height: 28,
+        bar_width: 3.0,
+        bar_spacing: 2.0,
+        min_bar_height: 2.0,
+        color: #aab8c2,
+        played_color: #1d9bf0,
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct AudioWaveform {
+    #[redraw]
+    #[live]
+    draw_bar: DrawColor,
+
+    #[walk]
+    walk: Walk,
+
+    /// Width of each bar.
+    #[live]
+    bar_width: f64,
+
+    /// Space between bars.
+    #[live]
+    bar_spacing: f64,
+
+    /// Height of the bars of silent parts of the clip.
+    #[live]
+    min_bar_height: f64,
+
+    /// Color of the bars of the part of the clip that wasn't played yet.
+    #[live]
+    color: Vec4,
+
+    /// Color of the bars of the part of the clip that was already played.
+    #[live]
+    played_color: Vec4,
+
+    /// The clip being shown.
+    #[rust]
+    details: AudioDetails,
+
+    /// How much of the clip was played, from 0.0 to 1.0.
+    #[rust]
+    progress: f64,
+}
+
+impl Widget for AudioWaveform {
+    fn handle_event(&mut self, _cx: &mut Cx, _event: &Event, _scope: &mut Scope) {}
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle(walk);
+        let bar_step = self.bar_width + self.bar_spacing;
+        let bars = ((rect.size.x + self.bar_spacing) / bar_step).max(0.0) as usize;
+        let played_bars = (self.progress * bars as f64).round() as usize;
+        for (index, level) in self.details.levels(bars).into_iter().enumerate() {
+            let height = (level as f64 * rect.size.y).max(self.min_bar_height);
+            self.draw_bar.color = if index < played_bars {
+                self.played_color
+            } else {
+                self.color
+            };
+            self.draw_bar.draw_abs(
+                cx,
+                Rect {
+                    pos: dvec2(
+                        rect.pos.x + index as f64 * bar_step,
+                        rect.pos.y + (rect.size.y - height) / 2.0,
+                    ),
+                    size: dvec2(self.bar_width, height),
+                },
+            );
+        }
+        DrawStep::done()
+    }
+}
+
+impl AudioWaveform {
+    /// Show the waveform of the given clip, not played yet.
+    pub fn set_audio(&mut self, cx: &mut Cx, details: &AudioDetails) {
+        self.details = details.clone();
+        self.progress = 0.0;
+        self.redraw(cx);
+    }
+
+    /// Set how much of the clip was played, from 0.0 to 1.0.
+    pub fn set_progress(&mut self, cx: &mut Cx, progress: f64) {
+        self.progress = progress.clamp(0.0, 1.0);
+        self.redraw(cx);
+    }
+}
+
+impl AudioWaveformRef {
+    /// See [`AudioWaveform::set_audio()`].
+    pub fn set_audio(&self, cx: &mut Cx, details: &AudioDetails) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_audio(cx, details);
+        }
+    }
+
+    /// See [`AudioWaveform::set_progress()`].
+    pub fn set_progress(&self, cx: &mut Cx, progress: f64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_progress(cx, progress);
+        }
+    }
+}
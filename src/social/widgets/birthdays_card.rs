@@ -0,0 +1,270 @@
+//! "Birthdays this week" card widget and its show/hide setting.
+//!
+//! The card is shown at the top of the newsfeed with the friends whose
+//! birthday falls in the coming week, from
+//! [`load_birthdays_this_week()`]. Users can hide it from the card itself
+//! or with [`SocialBirthdaySettings`], which is saved in the account's
+//! social settings (see [`set_birthdays_hidden()`]).
+//!
+//! [`load_birthdays_this_week()`]: crate::social::newsfeed::load_birthdays_this_week
+//! [`set_birthdays_hidden()`]: crate::social::newsfeed::set_birthdays_hidden
+
+use chrono::Local;
+use makepad_widgets::*;
+use matrix_sdk::ruma::OwnedUserId;
+
+use crate::shared::avatar::AvatarWidgetExt;
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::social::newsfeed::{are_birthdays_hidden, BirthdaysAction, UpcomingBirthday};
+
+/// The rows of friends shown in the card, in order.
+const BIRTHDAY_ROWS: [&[LiveId]; 5] = [
+    ids!(birthday_0),
+    ids!(birthday_1),
+    ids!(birthday_2),
+    ids!(birthday_3),
+    ids!(birthday_4),
+];
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use link::social_theme::*;
+
+    use crate::shared::styles::*;
+    use crate::shared::avatar::Avatar;
+
+    /// A single friend's birthday in the card.
+    BirthdayRow = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        cursor: Hand,
+
+        avatar = <Avatar> {
+            width: 36,
+            height: 36,
+        }
+
+        name_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 13.0 },
+                color: (SOCIAL_TEXT_PRIMARY),
+            }
+        }
+
+        day_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 11.0 },
+                color: (SOCIAL_TEXT_SECONDARY),
+            }
+        }
+    }
+
+    /// Card listing friends' birthdays in the coming week within the feed.
+    pub SocialBirthdaysCard = {{SocialBirthdaysCard}} {
+        width: Fill,
+        height: Fit,
+        padding: 16,
+        flow: Down,
+        spacing: 12,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_BG)
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            align: { y: 0.5 },
+
+            title_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Birthdays this week",
+                draw_text: {
+                    text_style: { font_size: 15.0 },
+                    color: (SOCIAL_TEXT_PRIMARY),
+                }
+            }
+
+            hide_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Hide",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (SOCIAL_ACCENT),
+                    text_style: { font_size: 12.0 },
+                }
+            }
+        }
+
+        birthday_0 = <BirthdayRow> {}
+        birthday_1 = <BirthdayRow> {}
+        birthday_2 = <BirthdayRow> {}
+        birthday_3 = <BirthdayRow> {}
+        birthday_4 = <BirthdayRow> {}
+    }
+
+    /// Setting for whether the birthdays card is shown in the newsfeed.
+    pub SocialBirthdaySettings = {{SocialBirthdaySettings}} {
+        width: Fill,
+        height: Fit,
+        padding: 16,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_BG)
+        }
+
+        show_birthdays_toggle = <CheckBoxFlat> {
+            text: "Show friends' birthdays in the newsfeed",
+            active: true,
+        }
+    }
+}
+
+/// Actions that can be triggered from the birthdays card.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialBirthdaysCardAction {
+    /// User tapped a friend whose birthday is coming up.
+    OpenProfile(OwnedUserId),
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialBirthdaysCard {
+    #[deref]
+    view: View,
+
+    /// The friends shown in the card, one per row.
+    #[rust]
+    friends: Vec<OwnedUserId>,
+}
+
+impl Widget for SocialBirthdaysCard {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialBirthdaysCard {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.button(ids!(hide_button)).clicked(actions) {
+            submit_async_request(MatrixRequest::SetBirthdaysHidden(true));
+        }
+
+        for (row_ids, user_id) in BIRTHDAY_ROWS.iter().zip(&self.friends) {
+            if self.view(row_ids).finger_up(actions).is_some() {
+                cx.action(SocialBirthdaysCardAction::OpenProfile(user_id.clone()));
+            }
+        }
+    }
+}
+
+impl SocialBirthdaysCard {
+    /// Set the upcoming birthdays, of which only the first few are shown.
+    pub fn set_birthdays(&mut self, cx: &mut Cx, birthdays: &[UpcomingBirthday]) {
+        self.friends = birthdays
+            .iter()
+            .take(BIRTHDAY_ROWS.len())
+            .map(|birthday| birthday.user_id.clone())
+            .collect();
+
+        let today = Local::now().date_naive();
+        for (index, row_ids) in BIRTHDAY_ROWS.iter().enumerate() {
+            let row = self.view(row_ids);
+            let Some(birthday) = birthdays.get(index) else {
+                row.set_visible(cx, false);
+                continue;
+            };
+            row.set_visible(cx, true);
+            let name = birthday
+                .display_name
+                .as_deref()
+                .unwrap_or_else(|| birthday.user_id.localpart());
+            row.avatar(ids!(avatar)).set_text(cx, name);
+            row.label(ids!(name_label)).set_text(cx, name);
+            row.label(ids!(day_label))
+                .set_text(cx, &birthday.day_label(today));
+        }
+        self.redraw(cx);
+    }
+}
+
+impl SocialBirthdaysCardRef {
+    /// See [`SocialBirthdaysCard::set_birthdays()`].
+    pub fn set_birthdays(&self, cx: &mut Cx, birthdays: &[UpcomingBirthday]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_birthdays(cx, birthdays);
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialBirthdaySettings {
+    #[deref]
+    view: View,
+}
+
+impl Widget for SocialBirthdaySettings {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialBirthdaySettings {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if let Some(shown) = self.check_box(ids!(show_birthdays_toggle)).changed(actions) {
+            submit_async_request(MatrixRequest::SetBirthdaysHidden(!shown));
+        }
+
+        for action in actions {
+            // The card may also be hidden from the newsfeed or on another device
+            if let Some(BirthdaysAction::HiddenChanged(hidden)) = action.downcast_ref() {
+                self.check_box(ids!(show_birthdays_toggle))
+                    .set_active(cx, !hidden);
+            }
+        }
+    }
+}
+
+impl SocialBirthdaySettings {
+    /// Show whether the birthdays card is currently shown.
+    pub fn refresh(&mut self, cx: &mut Cx) {
+        self.check_box(ids!(show_birthdays_toggle))
+            .set_active(cx, !are_birthdays_hidden());
+    }
+}
+
+impl SocialBirthdaySettingsRef {
+    /// See [`SocialBirthdaySettings::refresh()`].
+    pub fn refresh(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.refresh(cx);
+        }
+    }
+}
@@ -0,0 +1,420 @@
+//! Comment thread widget for the comments on a post.
+//!
+//! Lists the comments on a post, oldest first, each with compact chips for
+//! its reactions. Tapping a chip toggles that reaction on the comment, using
+//! the same [`SocialPostCardAction::ToggleReaction`] as reactions to posts.
+//! A comment can be opened directly, e.g., from a mention notification
+//! (see [`CommentLink`](crate::social::CommentLink)), in which case the thread
+//! scrolls to it and highlights it once it arrives
+//! (see [`SocialCommentThread::show_comment()`]).
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::{MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedUserId};
+use robrix_social_events::feed_config::SocialFeedConfigEventContent;
+
+use crate::social::reactions::{reactions_for_display, CommentReactions, ReactionSummary};
+use crate::social::widgets::post_card::SocialReactionsRow;
+use crate::utils::relative_format;
+
+/// How long a comment opened directly stays highlighted, in seconds.
+const COMMENT_HIGHLIGHT_DURATION: f64 = 3.0;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use link::social_theme::*;
+
+    use crate::shared::styles::*;
+
+    /// Compact chip for a single reaction to a comment.
+    CommentReactionChip = <Button> {
+        width: Fit,
+        height: Fit,
+        padding: { top: 2, bottom: 2, left: 6, right: 6 },
+        margin: { right: 2 },
+
+        draw_bg: {
+            // 1.0 if the user has selected this reaction
+            instance selected: 0.0
+            border_radius: 9.0
+            border_size: 1.0
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(
+                    self.border_size,
+                    self.border_size,
+                    self.rect_size.x - self.border_size * 2.0,
+                    self.rect_size.y - self.border_size * 2.0,
+                    self.border_radius
+                );
+                sdf.fill_keep(mix((SOCIAL_SURFACE), (SOCIAL_ACCENT_BG), self.selected));
+                sdf.stroke(mix((SOCIAL_BORDER), (SOCIAL_ACCENT), self.selected), self.border_size);
+                return sdf.result;
+            }
+        }
+        draw_text: {
+            text_style: { font_size: 10.0 },
+            color: (SOCIAL_TEXT),
+        }
+    }
+
+    /// A single comment in the thread.
+    CommentItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 8, bottom: 8 },
+        flow: Down,
+        spacing: 4,
+        show_bg: true,
+        draw_bg: {
+            // 1.0 while the comment is highlighted, after being opened directly
+            instance highlighted: 0.0
+            fn pixel(self) -> vec4 {
+                return mix((SOCIAL_BG), (SOCIAL_ACCENT_BG), self.highlighted);
+            }
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 8,
+            align: { y: 0.5 },
+
+            author_label = <Label> {
+                width: Fit,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: <THEME_FONT_BOLD> { font_size: 12.0 },
+                    color: (SOCIAL_TEXT_PRIMARY),
+                }
+            }
+
+            time_label = <Label> {
+                width: Fit,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 11.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                }
+            }
+
+            <View> { width: Fill, height: Fit }
+
+            react_button = <Button> {
+                width: Fit,
+                height: Fit,
+                padding: { top: 2, bottom: 2, left: 6, right: 6 },
+                text: "☺+",
+                draw_text: {
+                    text_style: { font_size: 10.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                }
+            }
+        }
+
+        body_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 13.0 },
+                color: (SOCIAL_TEXT_PRIMARY),
+                wrap: Word,
+            }
+        }
+
+        reactions_row = {{SocialReactionsRow}} {
+            width: Fill,
+            height: Fit,
+            flow: RightWrap,
+            spacing: 2,
+
+            reaction_template: <CommentReactionChip> {}
+        }
+    }
+
+    /// The comments on a post.
+    pub SocialCommentThread = {{SocialCommentThread}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_SURFACE)
+        }
+
+        empty_label = <Label> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            text: "No comments yet.",
+            draw_text: {
+                text_style: { font_size: 13.0 },
+                color: (SOCIAL_TEXT_SECONDARY),
+            }
+        }
+
+        comment_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            comment_item = <CommentItem> {}
+        }
+    }
+}
+
+/// A comment to show in a comment thread.
+#[derive(Clone, Debug)]
+pub struct CommentData {
+    /// Event ID of the comment.
+    pub event_id: OwnedEventId,
+    /// Author's user ID.
+    pub author_id: OwnedUserId,
+    /// Author's display name.
+    pub author_name: Option<String>,
+    /// When the comment was sent.
+    pub timestamp: MilliSecondsSinceUnixEpoch,
+    /// Text of the comment.
+    pub body: String,
+}
+
+/// Actions that can be triggered from a comment thread.
+///
+/// Toggling an existing reaction is emitted as
+/// [`SocialPostCardAction::ToggleReaction`](crate::social::SocialPostCardAction::ToggleReaction)
+/// with the comment's event ID.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialCommentThreadAction {
+    /// User wants to pick a reaction for a comment.
+    ReactToComment(OwnedEventId),
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialCommentThread {
+    #[deref]
+    view: View,
+
+    /// The comments, oldest first.
+    #[rust]
+    comments: Vec<CommentData>,
+
+    /// Reactions to the comments.
+    #[rust]
+    reactions: CommentReactions,
+
+    /// Configuration of the feed the post is in, e.g., its allowed reactions.
+    #[rust]
+    feed_config: SocialFeedConfigEventContent,
+
+    /// The logged-in user, whose own reactions are highlighted.
+    #[rust]
+    current_user: Option<OwnedUserId>,
+
+    /// The comment opened directly, which is highlighted for a moment once it's shown.
+    #[rust]
+    highlighted_comment: Option<OwnedEventId>,
+
+    /// Whether the highlighted comment still has to be scrolled to, once it arrives.
+    #[rust]
+    highlight_pending: bool,
+
+    /// Timer to stop highlighting the highlighted comment.
+    #[rust]
+    highlight_timer: Timer,
+}
+
+impl Widget for SocialCommentThread {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if self.highlight_timer.is_event(event).is_some() {
+            self.highlighted_comment = None;
+            self.redraw(cx);
+        }
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, self.comments.len());
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let Some(comment) = self.comments.get(item_id) else {
+                    continue;
+                };
+                let item = list.item(cx, item_id, live_id!(comment_item));
+                self.populate_comment_item(cx, &item, comment);
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for SocialCommentThread {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        let comment_list = self.portal_list(ids!(comment_list));
+        for (index, item) in comment_list.items_with_actions(actions) {
+            if !item.button(ids!(react_button)).clicked(actions) {
+                continue;
+            }
+            if let Some(comment) = self.comments.get(index) {
+                cx.action(SocialCommentThreadAction::ReactToComment(
+                    comment.event_id.clone(),
+                ));
+            }
+        }
+    }
+}
+
+impl SocialCommentThread {
+    /// Show the comments on a post, oldest first, with their reactions.
+    pub fn set_comments(
+        &mut self,
+        cx: &mut Cx,
+        comments: Vec<CommentData>,
+        reactions: CommentReactions,
+        feed_config: SocialFeedConfigEventContent,
+        current_user: Option<OwnedUserId>,
+    ) {
+        self.label(ids!(empty_label))
+            .set_visible(cx, comments.is_empty());
+        self.comments = comments;
+        self.reactions = reactions;
+        self.feed_config = feed_config;
+        self.current_user = current_user;
+        self.scroll_to_highlighted_comment(cx);
+        self.redraw(cx);
+    }
+
+    /// Scroll to a comment and highlight it for a moment, e.g., when opened
+    /// from a mention notification.
+    ///
+    /// If the comment isn't shown yet, e.g., because the comments are still
+    /// being loaded, it's scrolled to and highlighted once it arrives
+    /// with [`Self::set_comments()`].
+    pub fn show_comment(&mut self, cx: &mut Cx, comment_id: OwnedEventId) {
+        self.highlighted_comment = Some(comment_id);
+        self.highlight_pending = true;
+        cx.stop_timer(self.highlight_timer);
+        self.scroll_to_highlighted_comment(cx);
+        self.redraw(cx);
+    }
+
+    /// Scroll to the highlighted comment and start the timer to stop highlighting it,
+    /// if it still has to be scrolled to and is shown now.
+    fn scroll_to_highlighted_comment(&mut self, cx: &mut Cx) {
+        if !self.highlight_pending {
+            return;
+        }
+        let Some(index) = self.highlighted_comment.as_ref().and_then(|comment_id| {
+            self.comments
+                .iter()
+                .position(|comment| &comment.event_id == comment_id)
+        }) else {
+            return;
+        };
+        self.highlight_pending = false;
+        self.portal_list(ids!(comment_list))
+            .set_first_id_and_scroll(index, 0.0);
+        self.highlight_timer = cx.start_timeout(COMMENT_HIGHLIGHT_DURATION);
+    }
+
+    /// Update the reactions to a single comment, e.g., after the user toggled one.
+    pub fn set_comment_reactions(
+        &mut self,
+        cx: &mut Cx,
+        comment_id: OwnedEventId,
+        summary: ReactionSummary,
+    ) {
+        self.reactions.set_summary(comment_id, summary);
+        self.redraw(cx);
+    }
+
+    /// Get the reactions to the shown comments.
+    pub fn reactions(&self) -> &CommentReactions {
+        &self.reactions
+    }
+
+    /// Fill in a comment row.
+    fn populate_comment_item(&self, cx: &mut Cx, item: &WidgetRef, comment: &CommentData) {
+        let author = comment
+            .author_name
+            .as_deref()
+            .unwrap_or(comment.author_id.as_str());
+        item.label(ids!(author_label)).set_text(cx, author);
+        let time = relative_format(comment.timestamp).unwrap_or_default();
+        item.label(ids!(time_label)).set_text(cx, &time);
+        item.label(ids!(body_label)).set_text(cx, &comment.body);
+        let highlighted = if self.highlighted_comment.as_ref() == Some(&comment.event_id) {
+            1.0
+        } else {
+            0.0
+        };
+        item.apply_over(cx, live! { draw_bg: { highlighted: (highlighted) } });
+
+        let reactions_row = item.widget(ids!(reactions_row));
+        let Some(mut row) = reactions_row.borrow_mut::<SocialReactionsRow>() else {
+            return;
+        };
+        match self.reactions.summary(&comment.event_id) {
+            Some(summary) => {
+                let display =
+                    reactions_for_display(summary, self.current_user.as_ref(), &self.feed_config);
+                row.set_reactions(cx, &display, comment.event_id.clone());
+            }
+            None => row.clear(),
+        }
+    }
+}
+
+impl SocialCommentThreadRef {
+    /// See [`SocialCommentThread::set_comments()`].
+    pub fn set_comments(
+        &self,
+        cx: &mut Cx,
+        comments: Vec<CommentData>,
+        reactions: CommentReactions,
+        feed_config: SocialFeedConfigEventContent,
+        current_user: Option<OwnedUserId>,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_comments(cx, comments, reactions, feed_config, current_user);
+        }
+    }
+
+    /// See [`SocialCommentThread::show_comment()`].
+    pub fn show_comment(&self, cx: &mut Cx, comment_id: OwnedEventId) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_comment(cx, comment_id);
+        }
+    }
+
+    /// See [`SocialCommentThread::set_comment_reactions()`].
+    pub fn set_comment_reactions(
+        &self,
+        cx: &mut Cx,
+        comment_id: OwnedEventId,
+        summary: ReactionSummary,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_comment_reactions(cx, comment_id, summary);
+        }
+    }
+
+    /// See [`SocialCommentThread::reactions()`].
+    pub fn reactions(&self) -> Option<CommentReactions> {
+        Some(self.borrow()?.reactions().clone())
+    }
+}
@@ -0,0 +1,497 @@
+//! Discovery widget.
+//!
+//! The People tab shows people the user may know, suggested from the
+//! friends space graph by [`FriendSuggestionEngine::get_friend_suggestions()`],
+//! and lets the user search the homeserver's user directory with
+//! [`UserDiscoveryService::search_users()`]. People can be opened or
+//! followed right from the list.
+//!
+//! The Trending tab shows a [`SocialTrendingView`](crate::social::widgets::trending_view::SocialTrendingView)
+//! of the posts and hashtags trending in followed public feeds.
+//!
+//! [`FriendSuggestionEngine::get_friend_suggestions()`]: crate::social::discovery::FriendSuggestionEngine::get_friend_suggestions
+//! [`UserDiscoveryService::search_users()`]: crate::social::discovery::UserDiscoveryService::search_users
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::{OwnedUserId, UserId};
+use std::collections::BTreeSet;
+
+use crate::shared::avatar::AvatarWidgetExt;
+use crate::social::discovery::{DiscoveredUser, SuggestedUser, Trending};
+use crate::social::widgets::trending_view::{SocialTrendingAction, SocialTrendingViewWidgetExt};
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use link::social_theme::*;
+    use crate::shared::avatar::Avatar;
+    use crate::social::widgets::trending_view::SocialTrendingView;
+
+    /// A tab button at the top of the discovery view.
+    DiscoverTabButton = <Button> {
+        width: Fill,
+        height: 40,
+        text: "",
+        draw_bg: {
+            color: (SOCIAL_BG),
+        }
+        draw_text: {
+            // 1.0 if this tab is shown
+            instance selected: 0.0
+            text_style: { font_size: 14.0 },
+
+            fn get_color(self) -> vec4 {
+                return mix((SOCIAL_TEXT_SECONDARY), (SOCIAL_ACCENT), self.selected);
+            }
+        }
+    }
+
+    /// A single person in the discovery list.
+    DiscoverPersonItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 10, bottom: 10 },
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        cursor: Hand,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_BG)
+        }
+
+        avatar = <Avatar> {
+            width: 40,
+            height: 40,
+        }
+
+        person_info = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 2,
+
+            name_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: (SOCIAL_TEXT_PRIMARY),
+                }
+            }
+
+            detail_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                }
+            }
+        }
+
+        follow_button = <Button> {
+            width: Fit,
+            height: 32,
+            text: "Follow",
+            draw_bg: {
+                color: (SOCIAL_ACCENT),
+                radius: 16.0,
+            }
+            draw_text: {
+                color: (SOCIAL_ON_ACCENT),
+                text_style: { font_size: 12.0 },
+            }
+        }
+    }
+
+    /// Shown in place of the list when there is nobody to show.
+    DiscoverEmpty = <View> {
+        width: Fill,
+        height: Fit,
+        padding: 32,
+        align: { x: 0.5 },
+
+        empty_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: (SOCIAL_TEXT_MUTED),
+            }
+        }
+    }
+
+    /// View for finding people to follow or befriend, and what's trending.
+    pub SocialDiscoverView = {{SocialDiscoverView}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_BG)
+        }
+
+        tabs = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+
+            people_tab_button = <DiscoverTabButton> {
+                text: "People",
+                draw_text: { selected: 1.0 }
+            }
+            trending_tab_button = <DiscoverTabButton> {
+                text: "Trending",
+            }
+        }
+
+        people_page = <View> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            header = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                spacing: 12,
+                padding: 16,
+
+                title_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "Discover people",
+                    draw_text: {
+                        text_style: { font_size: 20.0 },
+                        color: (SOCIAL_TEXT_PRIMARY),
+                    }
+                }
+
+                search_input = <SimpleTextInput> {
+                    empty_message: "Search by name or user ID"
+                }
+
+                section_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "People you may know",
+                    draw_text: {
+                        text_style: { font_size: 13.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                    }
+                }
+            }
+
+            people_list = <PortalList> {
+                width: Fill,
+                height: Fill,
+                flow: Down,
+
+                person_item = <DiscoverPersonItem> {}
+                empty_item = <DiscoverEmpty> {}
+            }
+        }
+
+        trending_page = <SocialTrendingView> {
+            visible: false,
+        }
+    }
+}
+
+/// Actions that can be triggered from the discovery view.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialDiscoverAction {
+    /// User searched for people.
+    /// The results should be set with [`SocialDiscoverView::set_search_results()`].
+    Search(String),
+    /// User tapped a person in the list.
+    OpenProfile(OwnedUserId),
+    /// User clicked Follow next to a person.
+    /// The follow should be made with
+    /// [`FollowService::follow()`](crate::social::FollowService::follow).
+    Follow(OwnedUserId),
+    /// No action
+    None,
+}
+
+/// A person shown in the discovery list.
+#[derive(Clone, Debug)]
+struct DiscoverPerson {
+    user_id: OwnedUserId,
+    display_name: Option<String>,
+    /// Why the person is shown, e.g., their mutual friends.
+    detail: String,
+}
+
+impl From<&SuggestedUser> for DiscoverPerson {
+    fn from(user: &SuggestedUser) -> Self {
+        let detail = match user.mutual_friends.as_slice() {
+            [] => String::new(),
+            [friend] => format!("Friends with {}", friend),
+            [friend, others @ ..] => format!("Friends with {} and {} more", friend, others.len()),
+        };
+        Self {
+            user_id: user.user_id.clone(),
+            display_name: None,
+            detail,
+        }
+    }
+}
+
+impl From<&DiscoveredUser> for DiscoverPerson {
+    fn from(user: &DiscoveredUser) -> Self {
+        let detail = if user.has_social_profile() {
+            user.user_id.to_string()
+        } else {
+            format!("{} · No social profile", user.user_id)
+        };
+        Self {
+            user_id: user.user_id.clone(),
+            display_name: user.display_name.clone(),
+            detail,
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialDiscoverView {
+    #[deref]
+    view: View,
+
+    /// Friends of friends, shown while not searching.
+    #[rust]
+    suggestions: Vec<DiscoverPerson>,
+
+    /// The current search query, or empty if not searching.
+    #[rust]
+    query: String,
+
+    /// The results of the current search, or `None` while it is in progress.
+    #[rust]
+    results: Option<Vec<DiscoverPerson>>,
+
+    /// People the user followed from this view.
+    #[rust]
+    followed: BTreeSet<OwnedUserId>,
+}
+
+impl Widget for SocialDiscoverView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        // Borrow the fields directly, since the view is drawn at the same time
+        let people = if self.query.is_empty() {
+            &self.suggestions[..]
+        } else {
+            self.results.as_deref().unwrap_or_default()
+        };
+        let empty_text = if self.query.is_empty() {
+            "No suggestions yet. Add some friends to see who they know."
+        } else if self.results.is_none() {
+            "Searching…"
+        } else {
+            "Nobody found."
+        };
+
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, people.len().max(1));
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let item = match people.get(item_id) {
+                    Some(person) => {
+                        let item = list.item(cx, item_id, live_id!(person_item));
+                        let name = person
+                            .display_name
+                            .as_deref()
+                            .unwrap_or_else(|| person.user_id.localpart());
+                        item.avatar(ids!(avatar)).set_text(cx, name);
+                        item.label(ids!(name_label)).set_text(cx, name);
+                        item.label(ids!(detail_label)).set_text(cx, &person.detail);
+                        let is_followed = self.followed.contains(&person.user_id);
+                        let follow_button = item.button(ids!(follow_button));
+                        follow_button
+                            .set_text(cx, if is_followed { "Following" } else { "Follow" });
+                        follow_button.set_enabled(cx, !is_followed);
+                        item
+                    }
+                    None => {
+                        let item = list.item(cx, item_id, live_id!(empty_item));
+                        item.label(ids!(empty_label)).set_text(cx, empty_text);
+                        item
+                    }
+                };
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for SocialDiscoverView {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.button(ids!(people_tab_button)).clicked(actions) {
+            self.show_trending_tab(cx, false);
+        }
+        if self.button(ids!(trending_tab_button)).clicked(actions) {
+            self.show_trending_tab(cx, true);
+            // Load what's trending the first time the tab is opened
+            if !self.social_trending_view(ids!(trending_page)).is_loaded() {
+                cx.action(SocialTrendingAction::Refresh);
+            }
+        }
+
+        let search_input = self.text_input(ids!(search_input));
+        if let Some(text) = search_input.changed(actions) {
+            if text.trim().is_empty() {
+                self.set_query(cx, String::new());
+            }
+        }
+        if let Some((text, _)) = search_input.returned(actions) {
+            let query = text.trim().to_string();
+            if !query.is_empty() && query != self.query {
+                cx.action(SocialDiscoverAction::Search(query.clone()));
+                self.set_query(cx, query);
+            }
+        }
+
+        let people_list = self.portal_list(ids!(people_list));
+        for (index, item) in people_list.items_with_actions(actions) {
+            let Some(user_id) = self.shown_people().get(index).map(|p| p.user_id.clone()) else {
+                continue;
+            };
+            if item.button(ids!(follow_button)).clicked(actions) {
+                cx.action(SocialDiscoverAction::Follow(user_id.clone()));
+                // Show the change right away; it's reverted with `set_followed()` on failure
+                self.followed.insert(user_id);
+                people_list.redraw(cx);
+            } else if item.as_view().finger_up(actions).is_some() {
+                cx.action(SocialDiscoverAction::OpenProfile(user_id));
+            }
+        }
+    }
+}
+
+impl SocialDiscoverView {
+    /// Switch between the People and Trending tabs.
+    fn show_trending_tab(&mut self, cx: &mut Cx, show_trending: bool) {
+        let (people_selected, trending_selected) = if show_trending {
+            (0.0, 1.0)
+        } else {
+            (1.0, 0.0)
+        };
+        self.button(ids!(people_tab_button))
+            .apply_over(cx, live! { draw_text: { selected: (people_selected) } });
+        self.button(ids!(trending_tab_button))
+            .apply_over(cx, live! { draw_text: { selected: (trending_selected) } });
+        self.view(ids!(people_page)).set_visible(cx, !show_trending);
+        self.view(ids!(trending_page))
+            .set_visible(cx, show_trending);
+        self.redraw(cx);
+    }
+
+    /// Get the people currently shown in the list.
+    fn shown_people(&self) -> &[DiscoverPerson] {
+        if self.query.is_empty() {
+            &self.suggestions
+        } else {
+            self.results.as_deref().unwrap_or_default()
+        }
+    }
+
+    /// Start showing the results for `query`, or the suggestions if it's empty.
+    fn set_query(&mut self, cx: &mut Cx, query: String) {
+        self.label(ids!(section_label)).set_text(
+            cx,
+            if query.is_empty() {
+                "People you may know"
+            } else {
+                "Search results"
+            },
+        );
+        self.query = query;
+        self.results = None;
+        self.redraw(cx);
+    }
+
+    /// Set the suggested friends of friends,
+    /// e.g., from [`FriendSuggestionEngine::get_friend_suggestions()`](crate::social::discovery::FriendSuggestionEngine::get_friend_suggestions).
+    pub fn set_suggestions(&mut self, cx: &mut Cx, suggestions: &[SuggestedUser]) {
+        self.suggestions = suggestions.iter().map(DiscoverPerson::from).collect();
+        self.redraw(cx);
+    }
+
+    /// Set the results of a search for `query`,
+    /// e.g., from [`UserDiscoveryService::search_users()`](crate::social::discovery::UserDiscoveryService::search_users).
+    ///
+    /// Results of a search other than the current one are ignored.
+    pub fn set_search_results(&mut self, cx: &mut Cx, query: &str, results: &[DiscoveredUser]) {
+        if query != self.query {
+            return;
+        }
+        self.results = Some(results.iter().map(DiscoverPerson::from).collect());
+        self.redraw(cx);
+    }
+
+    /// Set the trending posts and topics shown in the Trending tab.
+    ///
+    /// See [`SocialTrendingView::set_trending()`](crate::social::widgets::trending_view::SocialTrendingView::set_trending).
+    pub fn set_trending(&mut self, cx: &mut Cx, trending: &Trending) {
+        self.social_trending_view(ids!(trending_page))
+            .set_trending(cx, trending);
+    }
+
+    /// Set whether the current user follows someone in the list,
+    /// e.g., after following them failed.
+    pub fn set_followed(&mut self, cx: &mut Cx, user_id: &UserId, is_followed: bool) {
+        if is_followed {
+            self.followed.insert(user_id.to_owned());
+        } else {
+            self.followed.remove(user_id);
+        }
+        self.redraw(cx);
+    }
+}
+
+impl SocialDiscoverViewRef {
+    /// See [`SocialDiscoverView::set_suggestions()`].
+    pub fn set_suggestions(&self, cx: &mut Cx, suggestions: &[SuggestedUser]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_suggestions(cx, suggestions);
+        }
+    }
+
+    /// See [`SocialDiscoverView::set_search_results()`].
+    pub fn set_search_results(&self, cx: &mut Cx, query: &str, results: &[DiscoveredUser]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_search_results(cx, query, results);
+        }
+    }
+
+    /// See [`SocialDiscoverView::set_trending()`].
+    pub fn set_trending(&self, cx: &mut Cx, trending: &Trending) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_trending(cx, trending);
+        }
+    }
+
+    /// See [`SocialDiscoverView::set_followed()`].
+    pub fn set_followed(&self, cx: &mut Cx, user_id: &UserId, is_followed: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_followed(cx, user_id, is_followed);
+        }
+    }
+}
@@ -4,22 +4,71 @@
 //! RSVP counts, and action buttons for responding to events.
 
 use makepad_widgets::*;
+use matrix_sdk::media::MediaFormat;
 use matrix_sdk::ruma::OwnedRoomId;
 use robrix_social_events::event::{EventLocation, SocialEventEventContent};
 use robrix_social_events::rsvp::RsvpStatus;
 
-use crate::social::events::RsvpCounts;
+use crate::media_cache::{MediaCache, MediaCacheEntry};
+use crate::utils;
+use crate::social::events::timing::now_ms;
+use crate::social::events::weather::{get_or_fetch_forecast, ForecastStatus};
+use crate::social::events::{EventTiming, RsvpCounts};
+use crate::social::ticker;
+use crate::social::widgets::map_thumbnail::MapThumbnailWidgetExt;
 
 live_design! {
     use link::theme::*;
     use link::shaders::*;
     use link::widgets::*;
+    use link::social_theme::*;
 
     use crate::shared::styles::*;
+    use crate::social::widgets::map_thumbnail::MapThumbnail;
 
     // Default event cover image placeholder
     IMG_DEFAULT_EVENT_COVER = dep("crate://self/resources/img/default_avatar.png")
 
+    LIVE_COLOR = (SOCIAL_DANGER)
+
+    /// A red dot that pulses while an event is happening.
+    pub LiveIndicatorDot = {{LiveIndicatorDot}} {
+        width: 10,
+        height: 10,
+
+        show_bg: true,
+        draw_bg: {
+            color: (LIVE_COLOR),
+            uniform anim_time: 0.0,
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                let center = self.rect_size * 0.5;
+                let pulse = 0.5 + 0.5 * sin(self.anim_time * 2.0 * PI);
+                sdf.circle(center.x, center.y, center.x * (0.6 + 0.4 * pulse));
+                sdf.fill(mix(self.color, vec4(self.color.rgb, 0.4), pulse));
+                return sdf.result;
+            }
+        }
+
+        animator: {
+            pulse = {
+                default: off,
+                off = {
+                    from: {all: Forward {duration: 0.0}}
+                    apply: {
+                        draw_bg: {anim_time: 0.0}
+                    }
+                }
+                on = {
+                    from: {all: Loop {duration: 1.5, end: 1.0}}
+                    apply: {
+                        draw_bg: {anim_time: [{time: 0.0, value: 0.0}, {time: 1.0, value: 1.0}]}
+                    }
+                }
+            }
+        }
+    }
+
     /// Event card widget for displaying event information.
     pub EventCard = {{EventCard}} {
         width: Fill,
@@ -29,7 +78,7 @@ live_design! {
         margin: { bottom: 12 },
         show_bg: true,
         draw_bg: {
-            color: #fff,
+            color: (SOCIAL_BG),
             radius: 8.0,
         }
 
@@ -69,11 +118,62 @@ live_design! {
                 text: "Event Title",
                 draw_text: {
                     text_style: { font_size: 18.0 },
-                    color: #000,
+                    color: (SOCIAL_TEXT_PRIMARY),
                     font_scale: 1.0,
                 }
             }
 
+            // Countdown chip or "Happening now" indicator
+            timing_row = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                align: { y: 0.5 },
+
+                live_indicator = <View> {
+                    width: Fit,
+                    height: Fit,
+                    flow: Right,
+                    spacing: 6,
+                    align: { y: 0.5 },
+                    visible: false,
+
+                    live_dot = <LiveIndicatorDot> {}
+
+                    live_label = <Label> {
+                        width: Fit,
+                        height: Fit,
+                        text: "Happening now",
+                        draw_text: {
+                            text_style: { font_size: 12.0 },
+                            color: (LIVE_COLOR),
+                        }
+                    }
+                }
+
+                countdown_chip = <View> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 2, bottom: 2, left: 8, right: 8 },
+                    visible: false,
+                    show_bg: true,
+                    draw_bg: {
+                        color: (SOCIAL_ACCENT_BG),
+                        radius: 10.0,
+                    }
+
+                    countdown_label = <Label> {
+                        width: Fit,
+                        height: Fit,
+                        text: "",
+                        draw_text: {
+                            text_style: { font_size: 11.0 },
+                            color: (SOCIAL_ACCENT),
+                        }
+                    }
+                }
+            }
+
             // Date and time row
             datetime_row = <View> {
                 width: Fill,
@@ -87,7 +187,7 @@ live_design! {
                     text: "📅",
                     draw_text: {
                         text_style: { font_size: 14.0 },
-                        color: #666,
+                        color: (SOCIAL_TEXT_SECONDARY),
                     }
                 }
 
@@ -97,7 +197,7 @@ live_design! {
                     text: "",
                     draw_text: {
                         text_style: { font_size: 14.0 },
-                        color: #666,
+                        color: (SOCIAL_TEXT_SECONDARY),
                     }
                 }
             }
@@ -116,7 +216,7 @@ live_design! {
                     text: "📍",
                     draw_text: {
                         text_style: { font_size: 14.0 },
-                        color: #666,
+                        color: (SOCIAL_TEXT_SECONDARY),
                     }
                 }
 
@@ -126,11 +226,37 @@ live_design! {
                     text: "",
                     draw_text: {
                         text_style: { font_size: 14.0 },
-                        color: #666,
+                        color: (SOCIAL_TEXT_SECONDARY),
+                    }
+                }
+
+                // Weather forecast (only shown for upcoming events)
+                forecast_chip = <View> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 2, bottom: 2, left: 8, right: 8 },
+                    visible: false,
+                    show_bg: true,
+                    draw_bg: {
+                        color: (SOCIAL_SURFACE),
+                        radius: 10.0,
+                    }
+
+                    forecast_label = <Label> {
+                        width: Fit,
+                        height: Fit,
+                        text: "",
+                        draw_text: {
+                            text_style: { font_size: 11.0 },
+                            color: (SOCIAL_TEXT),
+                        }
                     }
                 }
             }
 
+            // Map of the location, tap to open the full map view
+            map_thumbnail = <MapThumbnail> {}
+
             // Description
             description_label = <Label> {
                 width: Fill,
@@ -138,7 +264,7 @@ live_design! {
                 text: "",
                 draw_text: {
                     text_style: { font_size: 14.0 },
-                    color: #333,
+                    color: (SOCIAL_TEXT),
                     wrap: Word,
                 }
             }
@@ -157,7 +283,7 @@ live_design! {
                     text: "0 Going",
                     draw_text: {
                         text_style: { font_size: 12.0 },
-                        color: #22c55e,
+                        color: (SOCIAL_SUCCESS),
                     }
                 }
 
@@ -167,7 +293,17 @@ live_design! {
                     text: "0 Interested",
                     draw_text: {
                         text_style: { font_size: 12.0 },
-                        color: #f59e0b,
+                        color: (SOCIAL_WARNING),
+                    }
+                }
+
+                invited_count = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Invited (0)",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
                     }
                 }
             }
@@ -179,7 +315,7 @@ live_design! {
                 margin: { top: 8 },
                 show_bg: true,
                 draw_bg: {
-                    color: #eee
+                    color: (SOCIAL_DIVIDER)
                 }
             }
 
@@ -196,11 +332,11 @@ live_design! {
                     height: Fit,
                     text: "Going",
                     draw_bg: {
-                        color: #22c55e,
+                        color: (SOCIAL_SUCCESS),
                         radius: 4.0,
                     }
                     draw_text: {
-                        color: #fff,
+                        color: (SOCIAL_ON_ACCENT),
                     }
                 }
 
@@ -209,13 +345,13 @@ live_design! {
                     height: Fit,
                     text: "Interested",
                     draw_bg: {
-                        color: #fff,
+                        color: (SOCIAL_BG),
                         border_width: 1.0,
-                        border_color: #f59e0b,
+                        border_color: (SOCIAL_WARNING),
                         radius: 4.0,
                     }
                     draw_text: {
-                        color: #f59e0b,
+                        color: (SOCIAL_WARNING),
                     }
                 }
 
@@ -224,13 +360,13 @@ live_design! {
                     height: Fit,
                     text: "Not Going",
                     draw_bg: {
-                        color: #fff,
+                        color: (SOCIAL_BG),
                         border_width: 1.0,
-                        border_color: #ccc,
+                        border_color: (SOCIAL_BORDER),
                         radius: 4.0,
                     }
                     draw_text: {
-                        color: #666,
+                        color: (SOCIAL_TEXT_SECONDARY),
                     }
                 }
             }
@@ -249,7 +385,12 @@ pub struct LoadedEvent {
     pub rsvp_counts: RsvpCounts,
     /// Current user's RSVP status.
     pub user_rsvp: Option<RsvpStatus>,
-    /// Cover image data.
+    /// Number of users with a pending invite to the event room.
+    pub invited_count: u32,
+    /// Cover image data, if already loaded.
+    ///
+    /// If this is `None` and the event has a cover image, the card fetches
+    /// it asynchronously through its media cache.
     pub cover_data: Option<std::sync::Arc<[u8]>>,
 }
 
@@ -282,10 +423,31 @@ pub struct EventCard {
     /// The loaded event data.
     #[rust]
     event: Option<LoadedEvent>,
+
+    /// Cache for fetching the event's cover image.
+    #[rust(MediaCache::new(None))]
+    media_cache: MediaCache,
+
+    /// Whether the cover image is still being fetched.
+    #[rust]
+    cover_pending: bool,
+
+    /// Whether the weather forecast is still being fetched.
+    #[rust]
+    forecast_pending: bool,
 }
 
 impl Widget for EventCard {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        // The media cache and forecast fetches signal the UI when they complete
+        if let Event::Signal = event {
+            if self.cover_pending {
+                self.load_cover(cx);
+            }
+            if self.forecast_pending {
+                self.load_forecast(cx);
+            }
+        }
         self.view.handle_event(cx, event, scope);
         self.widget_match_event(cx, event, scope);
     }
@@ -297,10 +459,28 @@ impl Widget for EventCard {
 
 impl WidgetMatchEvent for EventCard {
     fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if ticker::is_tick(actions) {
+            self.update_timing(cx);
+            self.load_forecast(cx);
+        }
+
+        // Event times are shown in local time, so re-render them after travel
+        if ticker::timezone_change(actions).is_some() {
+            if let Some(loaded) = &self.event {
+                let datetime_str =
+                    format_event_time(loaded.event.start_time, loaded.event.end_time);
+                self.label(ids!(datetime_label)).set_text(cx, &datetime_str);
+            }
+        }
+
         let going_button = self.button(ids!(going_button));
         let interested_button = self.button(ids!(interested_button));
         let not_going_button = self.button(ids!(not_going_button));
 
+        if let Some(location) = self.map_thumbnail(ids!(map_thumbnail)).clicked(actions) {
+            cx.action(EventCardAction::ViewLocation(location));
+        }
+
         if let Some(room_id) = &self.room_id {
             if going_button.clicked(actions) {
                 cx.action(EventCardAction::RsvpGoing(room_id.clone()));
@@ -338,6 +518,8 @@ impl EventCard {
         } else {
             self.view(ids!(location_row)).set_visible(cx, false);
         }
+        self.map_thumbnail(ids!(map_thumbnail))
+            .set_location(cx, event.event.location.as_ref());
 
         // Update description
         if let Some(ref desc) = event.event.description {
@@ -351,25 +533,127 @@ impl EventCard {
             .set_text(cx, &format!("{} Going", event.rsvp_counts.going));
         self.label(ids!(interested_count))
             .set_text(cx, &format!("{} Interested", event.rsvp_counts.interested));
+        self.label(ids!(invited_count))
+            .set_text(cx, &format!("Invited ({})", event.invited_count));
 
         // Highlight user's current RSVP (visual feedback)
         // This could be expanded to change button styles based on current RSVP
 
         self.event = Some(event);
+        self.load_cover(cx);
+        self.load_forecast(cx);
+        self.update_timing(cx);
+    }
+
+    /// Show the event's cover image, fetching it through the media cache if needed.
+    ///
+    /// While the image is being fetched, the default cover stays visible.
+    fn load_cover(&mut self, cx: &mut Cx) {
+        self.cover_pending = false;
+        let Some(loaded) = &self.event else {
+            return;
+        };
+
+        let data = match (&loaded.cover_data, &loaded.event.cover_image) {
+            (Some(data), _) => Some(data.clone()),
+            (None, Some(mxc_uri)) => {
+                match self
+                    .media_cache
+                    .try_get_media_or_fetch(mxc_uri.clone(), MediaFormat::File)
+                {
+                    (MediaCacheEntry::Loaded(data), _) => Some(data),
+                    (MediaCacheEntry::Requested, _) => {
+                        self.cover_pending = true;
+                        None
+                    }
+                    (MediaCacheEntry::Failed(status), _) => {
+                        error!("Failed to fetch event cover image {mxc_uri}: {status}");
+                        None
+                    }
+                }
+            }
+            (None, None) => None,
+        };
+
+        if let Some(data) = data {
+            let cover_image = self.image(ids!(cover_image));
+            if utils::load_png_or_jpg(&cover_image, cx, &data).is_ok() {
+                self.redraw(cx);
+            }
+        }
+    }
+
+    /// Show the weather forecast for the event's location and date, if there is one.
+    ///
+    /// Forecasts are only available for upcoming events at a physical location,
+    /// and never fetched in privacy mode (see [`get_or_fetch_forecast()`]).
+    fn load_forecast(&mut self, cx: &mut Cx) {
+        let status = match &self.event {
+            Some(loaded) => match &loaded.event.location {
+                Some(location) => {
+                    get_or_fetch_forecast(location, loaded.event.start_time, loaded.event.end_time)
+                }
+                None => ForecastStatus::Unavailable,
+            },
+            None => ForecastStatus::Unavailable,
+        };
+
+        self.forecast_pending = status == ForecastStatus::Pending;
+        if let ForecastStatus::Ready(forecast) = &status {
+            self.label(ids!(forecast_label))
+                .set_text(cx, &forecast.chip_text());
+        }
+        self.view(ids!(forecast_chip))
+            .set_visible(cx, matches!(status, ForecastStatus::Ready(_)));
+    }
+
+    /// Update the countdown chip and live indicator for the current time.
+    ///
+    /// This is called automatically on every tick of the shared social ticker.
+    pub fn update_timing(&mut self, cx: &mut Cx) {
+        let Some(loaded) = &self.event else {
+            return;
+        };
+        let timing = EventTiming::at(loaded.event.start_time, loaded.event.end_time, now_ms());
+
+        let countdown = timing.countdown_text();
+        if let Some(text) = &countdown {
+            self.label(ids!(countdown_label)).set_text(cx, text);
+        }
+        self.view(ids!(countdown_chip))
+            .set_visible(cx, countdown.is_some());
+
+        let live_dot = self.live_indicator_dot(ids!(live_dot));
+        if timing.is_live() {
+            live_dot.start_animation(cx);
+        } else {
+            live_dot.stop_animation(cx);
+        }
+        self.view(ids!(live_indicator))
+            .set_visible(cx, timing.is_live());
     }
 
     /// Clear the event data.
     pub fn clear(&mut self, cx: &mut Cx) {
         self.room_id = None;
         self.event = None;
+        self.cover_pending = false;
+        self.forecast_pending = false;
 
         self.label(ids!(title_label)).set_text(cx, "");
         self.label(ids!(datetime_label)).set_text(cx, "");
         self.label(ids!(description_label)).set_text(cx, "");
         self.view(ids!(location_row)).set_visible(cx, false);
+        self.view(ids!(forecast_chip)).set_visible(cx, false);
+        self.map_thumbnail(ids!(map_thumbnail))
+            .set_location(cx, None);
         self.label(ids!(going_count)).set_text(cx, "0 Going");
         self.label(ids!(interested_count))
             .set_text(cx, "0 Interested");
+        self.label(ids!(invited_count)).set_text(cx, "Invited (0)");
+        self.view(ids!(countdown_chip)).set_visible(cx, false);
+        self.view(ids!(live_indicator)).set_visible(cx, false);
+        self.live_indicator_dot(ids!(live_dot)).stop_animation(cx);
     }
 }
 
@@ -381,6 +665,13 @@ impl EventCardRef {
         }
     }
 
+    /// See [`EventCard::update_timing()`].
+    pub fn update_timing(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.update_timing(cx);
+        }
+    }
+
     /// See [`EventCard::clear()`].
     pub fn clear(&self, cx: &mut Cx) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -389,16 +680,56 @@ impl EventCardRef {
     }
 }
 
-/// Format event time for display.
-fn format_event_time(start_ms: u64, end_ms: Option<u64>) -> String {
-    use chrono::{DateTime, Utc};
+/// A dot that pulses to indicate that an event is happening now.
+#[derive(Live, LiveHook, Widget)]
+pub struct LiveIndicatorDot {
+    #[deref]
+    view: View,
+    #[animator]
+    animator: Animator,
+}
+
+impl Widget for LiveIndicatorDot {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.animator_handle_event(cx, event);
+        self.view.handle_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl LiveIndicatorDotRef {
+    /// Starts the pulsing animation.
+    pub fn start_animation(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.animator_play(cx, ids!(pulse.on));
+        }
+    }
+
+    /// Stops the pulsing animation.
+    pub fn stop_animation(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.animator_play(cx, ids!(pulse.off));
+        }
+    }
+}
+
+/// Format event time for display in the device's local timezone.
+pub(crate) fn format_event_time(start_ms: u64, end_ms: Option<u64>) -> String {
+    use chrono::{DateTime, Local, Utc};
 
-    let start = DateTime::from_timestamp_millis(start_ms as i64).unwrap_or_else(Utc::now);
+    let start = DateTime::from_timestamp_millis(start_ms as i64)
+        .unwrap_or_else(Utc::now)
+        .with_timezone(&Local);
 
     let start_str = start.format("%a, %b %d at %I:%M %p").to_string();
 
     if let Some(end) = end_ms {
-        let end_dt = DateTime::from_timestamp_millis(end as i64).unwrap_or_else(Utc::now);
+        let end_dt = DateTime::from_timestamp_millis(end as i64)
+            .unwrap_or_else(Utc::now)
+            .with_timezone(&Local);
 
         // If same day, just show end time
         if start.date_naive() == end_dt.date_naive() {
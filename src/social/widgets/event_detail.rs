@@ -0,0 +1,2365 @@
+//! Event detail view widget.
+//!
+//! This widget renders the full view of a single event: the event card
+//! with RSVP actions, live attendee check-ins, the shared checklist of what
+//! attendees are bringing, carpools, a host-only dashboard with
+//! RSVP insights and co-host management, and per-attendee settings for the
+//! event room. Some days after the event ends, the creator is asked whether
+//! to keep the event room open, archive it or close it (see
+//! [`archival`](crate::social::events::archival)).
+//!
+//! The view only emits [`EventDetailAction`]s; the page containing it loads
+//! the event with [`MatrixRequest::LoadEventDetail`] and turns those actions
+//! into requests to the Matrix worker.
+//!
+//! [`MatrixRequest::LoadEventDetail`]: crate::sliding_sync::MatrixRequest::LoadEventDetail
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::{OwnedRoomId, OwnedUserId, UserId};
+use robrix_social_events::{checklist::ChecklistItemKind, rsvp::RsvpStatus};
+
+use crate::sliding_sync::current_user_id;
+use crate::social::events::checkin::{can_check_in, merge_checkin, CheckIn, MAX_CHECKIN_MESSAGE_CHARS};
+use crate::social::events::archival::{is_archival_due, is_past_event, ARCHIVAL_DELAY_DAYS};
+use crate::social::events::checklist::{ChecklistItem, MAX_CHECKLIST_TITLE_CHARS};
+use crate::social::events::event_room::EventRole;
+use crate::social::events::insights::{EventInsights, RsvpSnapshotPoint};
+use crate::social::events::logistics::{Ride, RideBoard, MAX_RIDE_AREA_CHARS, MAX_RIDE_SEATS};
+use crate::social::events::scheduling_poll::SchedulingPoll;
+use crate::social::events::timing::now_ms;
+use crate::social::widgets::event_card::{format_event_time, EventCardWidgetExt, LoadedEvent};
+use crate::social::widgets::kiosk_view::KioskSlide;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use link::social_theme::*;
+    use crate::social::widgets::event_card::EventCard;
+
+    /// A single bar in the RSVP snapshot chart.
+    RsvpSnapshotBar = <View> {
+        width: 12,
+        height: 0,
+        margin: { right: 4 },
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_SUCCESS),
+            radius: 2.0,
+        }
+    }
+
+    /// A single member row in the host management panel.
+    HostRoleRow = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 8,
+        padding: { top: 4, bottom: 4 },
+        align: { y: 0.5 },
+
+        name_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 13.0 },
+                color: (SOCIAL_TEXT_PRIMARY),
+            }
+        }
+
+        role_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 12.0 },
+                color: (SOCIAL_TEXT_SECONDARY),
+            }
+        }
+
+        role_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_bg: {
+                color: #0000,
+            }
+            draw_text: {
+                color: (SOCIAL_ACCENT),
+                text_style: { font_size: 12.0 }
+            }
+        }
+    }
+
+    /// A single attendee in the check-in list.
+    CheckinRow = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Down,
+        spacing: 2,
+        padding: { top: 4, bottom: 4 },
+
+        name_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 13.0 },
+                color: (SOCIAL_TEXT_PRIMARY),
+            }
+        }
+
+        message_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 12.0 },
+                color: (SOCIAL_TEXT_SECONDARY),
+                wrap: Word,
+            }
+        }
+    }
+
+    /// A single item in the event checklist.
+    ChecklistRow = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 8,
+        padding: { top: 4, bottom: 4 },
+        align: { y: 0.5 },
+
+        item_info = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 2,
+
+            title_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: (SOCIAL_TEXT_PRIMARY),
+                    wrap: Word,
+                }
+            }
+
+            detail_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                    wrap: Word,
+                }
+            }
+        }
+
+        claim_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_bg: {
+                color: #0000,
+            }
+            draw_text: {
+                color: (SOCIAL_ACCENT),
+                text_style: { font_size: 12.0 }
+            }
+        }
+
+        done_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_bg: {
+                color: #0000,
+            }
+            draw_text: {
+                color: (SOCIAL_SUCCESS),
+                text_style: { font_size: 12.0 }
+            }
+        }
+    }
+
+    /// A single ride offer, request or match in the carpool list.
+    RideRow = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 8,
+        padding: { top: 4, bottom: 4 },
+        align: { y: 0.5 },
+
+        ride_info = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 2,
+
+            summary_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: (SOCIAL_TEXT_PRIMARY),
+                    wrap: Word,
+                }
+            }
+
+            note_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                    wrap: Word,
+                }
+            }
+        }
+
+        message_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "Message",
+            draw_bg: {
+                color: #0000,
+            }
+            draw_text: {
+                color: (SOCIAL_ACCENT),
+                text_style: { font_size: 12.0 }
+            }
+        }
+    }
+
+    /// A single candidate date in the scheduling poll.
+    DateOptionRow = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 8,
+        padding: { top: 4, bottom: 4 },
+        align: { y: 0.5 },
+
+        option_info = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 2,
+
+            date_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: (SOCIAL_TEXT_PRIMARY),
+                    wrap: Word,
+                }
+            }
+
+            votes_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                    wrap: Word,
+                }
+            }
+        }
+
+        vote_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_bg: {
+                color: #0000,
+            }
+            draw_text: {
+                color: (SOCIAL_ACCENT),
+                text_style: { font_size: 12.0 }
+            }
+        }
+
+        // Only shown to hosts, on the date with the most votes
+        use_date_button = <Button> {
+            width: Fit,
+            height: Fit,
+            visible: false,
+            text: "Use this date",
+            draw_bg: {
+                color: (SOCIAL_ACCENT),
+                radius: 4.0,
+            }
+            draw_text: {
+                color: (SOCIAL_ON_ACCENT),
+                text_style: { font_size: 12.0 }
+            }
+        }
+    }
+
+    /// Full detail view for a single event.
+    pub EventDetailView = {{EventDetailView}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_SURFACE)
+        }
+
+        content = <ScrollYView> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+            padding: 12,
+            spacing: 12,
+
+            event_card = <EventCard> {}
+
+            // Shown once the host archived or closed the event room
+            past_event_notice = <Label> {
+                width: Fill,
+                height: Fit,
+                visible: false,
+                text: "This event is over. Its chat was archived by the host and is now read-only.",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                    wrap: Word,
+                }
+            }
+
+            // Scheduling poll (only shown if the host attached one)
+            scheduling_poll_section = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                padding: 16,
+                spacing: 8,
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_BG),
+                    radius: 8.0,
+                }
+
+                scheduling_poll_title = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Pick a date",
+                    draw_text: {
+                        text_style: { font_size: 16.0 },
+                        color: (SOCIAL_TEXT_PRIMARY),
+                    }
+                }
+
+                scheduling_poll_hint = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "Vote for every date that works for you.",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        wrap: Word,
+                    }
+                }
+
+                date_options = {{DatePollList}} {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+
+                    row_template: <DateOptionRow> {}
+                }
+            }
+
+            // Check-ins (shown once check-in opens, or if anyone checked in)
+            checkin_section = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                padding: 16,
+                spacing: 8,
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_BG),
+                    radius: 8.0,
+                }
+
+                checkin_header = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Right,
+                    align: { y: 0.5 },
+
+                    checkin_title = <Label> {
+                        width: Fill,
+                        height: Fit,
+                        text: "Checked in",
+                        draw_text: {
+                            text_style: { font_size: 16.0 },
+                            color: (SOCIAL_TEXT_PRIMARY),
+                        }
+                    }
+
+                    // Only shown once someone shared a photo
+                    present_photos_button = <Button> {
+                        width: Fit,
+                        height: Fit,
+                        visible: false,
+                        text: "Show photos",
+                        draw_bg: {
+                            color: #0000,
+                        }
+                        draw_text: {
+                            color: (SOCIAL_ACCENT),
+                        }
+                    }
+                }
+
+                checkin_form = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+                    spacing: 8,
+                    visible: false,
+
+                    checkin_message_input = <TextInput> {
+                        width: Fill,
+                        height: Fit,
+                        empty_message: "Say something (optional)",
+                    }
+
+                    checkin_buttons = <View> {
+                        width: Fill,
+                        height: Fit,
+                        flow: Right,
+                        spacing: 8,
+
+                        checkin_photo_button = <Button> {
+                            width: Fit,
+                            height: Fit,
+                            text: "Add photo",
+                            draw_bg: {
+                                color: (SOCIAL_BG),
+                                border_width: 1.0,
+                                border_color: (SOCIAL_BORDER),
+                                radius: 4.0,
+                            }
+                            draw_text: {
+                                color: (SOCIAL_TEXT),
+                            }
+                        }
+
+                        checkin_button = <Button> {
+                            width: Fill,
+                            height: Fit,
+                            text: "Check in",
+                            draw_bg: {
+                                color: (SOCIAL_SUCCESS),
+                                radius: 4.0,
+                            }
+                            draw_text: {
+                                color: (SOCIAL_ON_ACCENT),
+                            }
+                        }
+                    }
+                }
+
+                checkin_list = {{CheckinList}} {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+
+                    row_template: <CheckinRow> {}
+                }
+            }
+
+            // Shared checklist of things to bring and tasks
+            checklist_section = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                padding: 16,
+                spacing: 8,
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_BG),
+                    radius: 8.0,
+                }
+
+                checklist_title = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Checklist",
+                    draw_text: {
+                        text_style: { font_size: 16.0 },
+                        color: (SOCIAL_TEXT_PRIMARY),
+                    }
+                }
+
+                checklist_empty_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "Nothing on the list yet. Add what's needed so attendees can sign up.",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        wrap: Word,
+                    }
+                }
+
+                checklist_items = {{ChecklistList}} {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+
+                    row_template: <ChecklistRow> {}
+                }
+
+                checklist_form = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Right,
+                    spacing: 8,
+                    align: { y: 0.5 },
+
+                    checklist_title_input = <TextInput> {
+                        width: Fill,
+                        height: Fit,
+                        empty_message: "Add an item or task",
+                    }
+
+                    checklist_quantity_input = <TextInput> {
+                        width: 48,
+                        height: Fit,
+                        empty_message: "Qty",
+                    }
+
+                    checklist_task_toggle = <CheckBoxFlat> {
+                        text: "Task",
+                        active: false,
+                    }
+
+                    add_checklist_item_button = <Button> {
+                        width: Fit,
+                        height: Fit,
+                        text: "Add",
+                        draw_bg: {
+                            color: (SOCIAL_ACCENT),
+                            radius: 4.0,
+                        }
+                        draw_text: {
+                            color: (SOCIAL_ON_ACCENT),
+                        }
+                    }
+                }
+            }
+
+            // Carpools (only shown if the host enabled logistics)
+            rides_section = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                padding: 16,
+                spacing: 8,
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_BG),
+                    radius: 8.0,
+                }
+
+                rides_title = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Rides",
+                    draw_text: {
+                        text_style: { font_size: 16.0 },
+                        color: (SOCIAL_TEXT_PRIMARY),
+                    }
+                }
+
+                rides_empty_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "No rides offered or requested yet.",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        wrap: Word,
+                    }
+                }
+
+                ride_list = {{RideList}} {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+
+                    row_template: <RideRow> {}
+                }
+
+                ride_form = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Right,
+                    spacing: 8,
+                    align: { y: 0.5 },
+
+                    ride_area_input = <TextInput> {
+                        width: Fill,
+                        height: Fit,
+                        empty_message: "Coming from (e.g., Downtown)",
+                    }
+
+                    ride_seats_input = <TextInput> {
+                        width: 48,
+                        height: Fit,
+                        empty_message: "Seats",
+                    }
+                }
+
+                ride_buttons = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Right,
+                    spacing: 8,
+
+                    offer_ride_button = <Button> {
+                        width: Fill,
+                        height: Fit,
+                        text: "Offer a ride",
+                        draw_bg: {
+                            color: (SOCIAL_ACCENT),
+                            radius: 4.0,
+                        }
+                        draw_text: {
+                            color: (SOCIAL_ON_ACCENT),
+                        }
+                    }
+
+                    request_ride_button = <Button> {
+                        width: Fill,
+                        height: Fit,
+                        text: "Need a ride",
+                        draw_bg: {
+                            color: (SOCIAL_BG),
+                            border_width: 1.0,
+                            border_color: (SOCIAL_BORDER),
+                            radius: 4.0,
+                        }
+                        draw_text: {
+                            color: (SOCIAL_TEXT),
+                        }
+                    }
+
+                    withdraw_ride_button = <Button> {
+                        width: Fit,
+                        height: Fit,
+                        visible: false,
+                        text: "Withdraw",
+                        draw_bg: {
+                            color: #0000,
+                        }
+                        draw_text: {
+                            color: (SOCIAL_DANGER),
+                        }
+                    }
+                }
+            }
+
+            // Host dashboard (only shown to the creator and co-hosts)
+            host_dashboard = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                padding: 16,
+                spacing: 8,
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_BG),
+                    radius: 8.0,
+                }
+
+                dashboard_title = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Host dashboard",
+                    draw_text: {
+                        text_style: { font_size: 16.0 },
+                        color: (SOCIAL_TEXT_PRIMARY),
+                    }
+                }
+
+                snapshot_hint = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "Current RSVPs, by the day they were last changed",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        wrap: Word,
+                    }
+                }
+
+                snapshot_chart = {{RsvpSnapshotChart}} {
+                    width: Fill,
+                    height: 80,
+                    flow: Right,
+                    align: { y: 1.0 },
+
+                    bar_template: <RsvpSnapshotBar> {}
+                }
+
+                conversion_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        wrap: Word,
+                    }
+                }
+
+                share_summary_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Copy event summary",
+                    draw_bg: {
+                        color: (SOCIAL_BG),
+                        border_width: 1.0,
+                        border_color: (SOCIAL_BORDER),
+                        radius: 4.0,
+                    }
+                    draw_text: {
+                        color: (SOCIAL_TEXT),
+                    }
+                }
+            }
+
+            // Host management (only shown to the creator and co-hosts)
+            host_management = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                padding: 16,
+                spacing: 8,
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_BG),
+                    radius: 8.0,
+                }
+
+                host_management_title = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Hosts and guests",
+                    draw_text: {
+                        text_style: { font_size: 16.0 },
+                        color: (SOCIAL_TEXT_PRIMARY),
+                    }
+                }
+
+                host_roles = {{HostRolesList}} {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+
+                    row_template: <HostRoleRow> {}
+                }
+            }
+
+            // Archival options (only shown to the creator, some days after the event ended)
+            archival_options = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                padding: 16,
+                spacing: 8,
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_BG),
+                    radius: 8.0,
+                }
+
+                archival_title = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "The event is over",
+                    draw_text: {
+                        text_style: { font_size: 16.0 },
+                        color: (SOCIAL_TEXT_PRIMARY),
+                    }
+                }
+
+                archival_hint = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        wrap: Word,
+                    }
+                }
+
+                archival_buttons = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Right,
+                    spacing: 8,
+
+                    keep_open_button = <Button> {
+                        width: Fit,
+                        height: Fit,
+                        text: "Keep open",
+                        draw_bg: {
+                            color: (SOCIAL_BG),
+                            border_width: 1.0,
+                            border_color: (SOCIAL_BORDER),
+                            radius: 4.0,
+                        }
+                        draw_text: {
+                            color: (SOCIAL_TEXT),
+                        }
+                    }
+
+                    archive_event_button = <Button> {
+                        width: Fit,
+                        height: Fit,
+                        text: "Archive",
+                        draw_bg: {
+                            color: (SOCIAL_BG),
+                            border_width: 1.0,
+                            border_color: (SOCIAL_BORDER),
+                            radius: 4.0,
+                        }
+                        draw_text: {
+                            color: (SOCIAL_TEXT),
+                        }
+                    }
+
+                    close_event_button = <Button> {
+                        width: Fit,
+                        height: Fit,
+                        text: "Close for good",
+                        draw_bg: {
+                            color: (SOCIAL_BG),
+                            border_width: 1.0,
+                            border_color: (SOCIAL_DANGER),
+                            radius: 4.0,
+                        }
+                        draw_text: {
+                            color: (SOCIAL_DANGER),
+                        }
+                    }
+                }
+            }
+
+            // Attendee settings (only shown once the user has RSVPed)
+            attendee_settings = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                padding: 16,
+                spacing: 4,
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_BG),
+                    radius: 8.0,
+                }
+
+                attend_quietly_toggle = <CheckBoxFlat> {
+                    text: "Attend quietly",
+                    active: false,
+                }
+
+                attend_quietly_hint = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "Keep your RSVP but mute notifications from this event's chat.",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        wrap: Word,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Actions that can be triggered from the event detail view.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum EventDetailAction {
+    /// User toggled "Attend quietly" for the event room.
+    SetAttendQuietly {
+        room_id: OwnedRoomId,
+        quiet: bool,
+    },
+    /// User wants to check in to the event.
+    ///
+    /// The photo attached via [`EventDetailAction::AttachCheckinPhoto`],
+    /// if any, should be sent along with the check-in.
+    CheckIn {
+        room_id: OwnedRoomId,
+        message: Option<String>,
+    },
+    /// User wants to pick a photo to attach to their check-in.
+    AttachCheckinPhoto(OwnedRoomId),
+    /// User wants to present the event's check-in photos full-screen, e.g.,
+    /// on a party screen. The slides should be passed to
+    /// [`SocialKioskView::start()`](crate::social::widgets::kiosk_view::SocialKioskView::start)
+    /// with [`KioskSettings::media_only`](crate::social::widgets::kiosk_view::KioskSettings::media_only)
+    /// set; new photos can be added as they arrive.
+    PresentPhotos {
+        room_id: OwnedRoomId,
+        slides: Vec<KioskSlide>,
+    },
+    /// User wants to add an item to the event's checklist.
+    AddChecklistItem {
+        room_id: OwnedRoomId,
+        title: String,
+        kind: ChecklistItemKind,
+        quantity: u32,
+    },
+    /// User wants to change how many of a checklist item they're bringing,
+    /// or take on or give up a task; 0 withdraws their claim.
+    SetChecklistClaim {
+        room_id: OwnedRoomId,
+        item_id: String,
+        quantity: u32,
+    },
+    /// User wants to check off a checklist item, or undo that.
+    ///
+    /// Hosts check off the whole item, other attendees their own part of it.
+    SetChecklistItemDone {
+        room_id: OwnedRoomId,
+        item_id: String,
+        done: bool,
+    },
+    /// User wants to offer free seats in their car to other attendees.
+    OfferRide {
+        room_id: OwnedRoomId,
+        seats: u32,
+        area: String,
+    },
+    /// User wants to ask other attendees for a ride.
+    RequestRide {
+        room_id: OwnedRoomId,
+        seats: u32,
+        area: String,
+    },
+    /// User wants to withdraw their ride offer or request.
+    WithdrawRide(OwnedRoomId),
+    /// User wants to message an attendee they were matched with for a ride.
+    MessageAttendee(OwnedUserId),
+    /// User wants to vote for the given dates in the scheduling poll,
+    /// replacing their earlier vote.
+    VoteSchedulingPoll {
+        room_id: OwnedRoomId,
+        start_times: Vec<u64>,
+    },
+    /// Host wants to move the event to the date with the most votes.
+    FinalizeSchedulingPoll(OwnedRoomId),
+    /// Host wants to share a plain-text summary of the event,
+    /// see [`EventInsights::summary_text()`]. It's copied to the clipboard.
+    ShareSummary(String),
+    /// Creator wants to promote a guest to co-host.
+    AddCohost {
+        room_id: OwnedRoomId,
+        user_id: OwnedUserId,
+    },
+    /// Creator wants to demote a co-host back to guest.
+    RemoveCohost {
+        room_id: OwnedRoomId,
+        user_id: OwnedUserId,
+    },
+    /// Creator wants to keep the event room open after the event ended.
+    /// This should be done with
+    /// [`EventRoomService::keep_event_open()`](crate::social::events::EventRoomService::keep_event_open).
+    KeepEventOpen(OwnedRoomId),
+    /// Creator wants to make the event room read-only after the event ended.
+    /// This should be done with
+    /// [`EventRoomService::archive_event()`](crate::social::events::EventRoomService::archive_event).
+    ArchiveEvent(OwnedRoomId),
+    /// Creator wants to close the event room for good after the event ended.
+    /// This should be done with
+    /// [`EventRoomService::close_event()`](crate::social::events::EventRoomService::close_event),
+    /// pointing attendees to the creator's public feed room.
+    CloseEvent(OwnedRoomId),
+    /// No action.
+    None,
+}
+
+/// Actions emitted when parts of the event shown in an [`EventDetailView`]
+/// were loaded or changed, after a [`MatrixRequest::LoadEventDetail`].
+///
+/// [`MatrixRequest::LoadEventDetail`]: crate::sliding_sync::MatrixRequest::LoadEventDetail
+#[derive(Clone, Debug, DefaultNone)]
+pub enum EventDetailLoadAction {
+    /// The event was loaded, see [`EventDetailView::set_event()`].
+    Loaded(LoadedEvent),
+    /// The host dashboard of the event was loaded, for hosts only,
+    /// see [`EventDetailView::set_insights()`].
+    Insights {
+        room_id: OwnedRoomId,
+        insights: EventInsights,
+        summary: String,
+    },
+    /// The roles of the event's members were loaded, for hosts only,
+    /// along with the current user's role, see [`EventDetailView::set_roles()`].
+    Roles {
+        room_id: OwnedRoomId,
+        roles: Vec<(OwnedUserId, EventRole)>,
+        viewer_role: EventRole,
+    },
+    /// Whether the current user attends the event quietly was loaded,
+    /// or changed back after failing to change it,
+    /// see [`EventDetailView::set_attend_quietly()`].
+    AttendQuietly { room_id: OwnedRoomId, quiet: bool },
+    /// The check-ins of the event were loaded, see [`EventDetailView::set_checkins()`].
+    Checkins {
+        room_id: OwnedRoomId,
+        checkins: Vec<CheckIn>,
+    },
+    /// The checklist of the event was loaded, along with whether the current user
+    /// can edit it, see [`EventDetailView::set_checklist()`].
+    Checklist {
+        room_id: OwnedRoomId,
+        items: Vec<ChecklistItem>,
+        is_host: bool,
+    },
+    /// The rides offered and requested by attendees were loaded,
+    /// see [`EventDetailView::set_ride_board()`].
+    RideBoard {
+        room_id: OwnedRoomId,
+        board: RideBoard,
+    },
+    /// The scheduling poll of the event was loaded, if it has one, along with
+    /// whether the current user is a host, see [`EventDetailView::set_scheduling_poll()`].
+    SchedulingPoll {
+        room_id: OwnedRoomId,
+        poll: Option<SchedulingPoll>,
+        is_host: bool,
+    },
+    /// Loading the event failed.
+    Failed { room_id: OwnedRoomId, error: String },
+    /// No action.
+    None,
+}
+
+/// Widget listing event members with their roles, for the host management panel.
+#[derive(Live, LiveHook, Widget)]
+pub struct HostRolesList {
+    #[redraw]
+    #[rust]
+    area: Area,
+
+    /// Template for a member row.
+    #[live]
+    row_template: Option<LivePtr>,
+
+    /// Created rows, with the user and role each one represents.
+    #[rust]
+    rows: Vec<(OwnedUserId, EventRole, WidgetRef)>,
+
+    /// Layout for the widget.
+    #[layout]
+    layout: Layout,
+
+    /// Walk for the widget.
+    #[walk]
+    walk: Walk,
+}
+
+impl Widget for HostRolesList {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        for (_, _, row) in &self.rows {
+            row.handle_event(cx, event, scope);
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        cx.begin_turtle(walk, self.layout);
+        for (_, _, row) in self.rows.iter_mut() {
+            let _ = row.draw(cx, scope);
+        }
+        cx.end_turtle_with_area(&mut self.area);
+        DrawStep::done()
+    }
+}
+
+impl HostRolesList {
+    /// Set the members and roles to display.
+    ///
+    /// If `can_manage` is true, co-hosts get a "Remove co-host" button
+    /// and guests get a "Make co-host" button.
+    pub fn set_roles(&mut self, cx: &mut Cx, roles: &[(OwnedUserId, EventRole)], can_manage: bool) {
+        self.rows.clear();
+        let Some(template) = self.row_template else {
+            return;
+        };
+
+        for (user_id, role) in roles {
+            let row = WidgetRef::new_from_ptr(cx, Some(template));
+            row.label(ids!(name_label)).set_text(cx, user_id.as_str());
+            let (role_text, button_text) = match role {
+                EventRole::Creator => ("Creator", None),
+                EventRole::CoHost => ("Co-host", Some("Remove co-host")),
+                EventRole::Guest => ("Guest", Some("Make co-host")),
+            };
+            row.label(ids!(role_label)).set_text(cx, role_text);
+            let button = row.button(ids!(role_button));
+            match button_text.filter(|_| can_manage) {
+                Some(text) => button.set_text(cx, text),
+                None => button.set_visible(cx, false),
+            }
+            self.rows.push((user_id.clone(), *role, row));
+        }
+        self.area.redraw(cx);
+    }
+
+    /// Get the member whose role button was clicked, along with their current role.
+    pub fn clicked_row(&self, actions: &Actions) -> Option<(OwnedUserId, EventRole)> {
+        self.rows
+            .iter()
+            .find(|(_, _, row)| row.button(ids!(role_button)).clicked(actions))
+            .map(|(user_id, role, _)| (user_id.clone(), *role))
+    }
+}
+
+/// Widget listing the attendees who checked in to an event.
+#[derive(Live, LiveHook, Widget)]
+pub struct CheckinList {
+    #[redraw]
+    #[rust]
+    area: Area,
+
+    /// Template for an attendee row.
+    #[live]
+    row_template: Option<LivePtr>,
+
+    /// Created rows, one per check-in.
+    #[rust]
+    rows: Vec<WidgetRef>,
+
+    /// Layout for the widget.
+    #[layout]
+    layout: Layout,
+
+    /// Walk for the widget.
+    #[walk]
+    walk: Walk,
+}
+
+impl Widget for CheckinList {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        cx.begin_turtle(walk, self.layout);
+        for row in self.rows.iter_mut() {
+            let _ = row.draw(cx, scope);
+        }
+        cx.end_turtle_with_area(&mut self.area);
+        DrawStep::done()
+    }
+}
+
+impl CheckinList {
+    /// Set the check-ins to display.
+    pub fn set_checkins(&mut self, cx: &mut Cx, checkins: &[CheckIn]) {
+        self.rows.clear();
+        let Some(template) = self.row_template else {
+            return;
+        };
+
+        for checkin in checkins {
+            let row = WidgetRef::new_from_ptr(cx, Some(template));
+            row.label(ids!(name_label))
+                .set_text(cx, checkin.user_id.as_str());
+            let message = match (&checkin.message, &checkin.photo) {
+                (Some(message), Some(_)) => format!("{} · shared a photo", message),
+                (Some(message), None) => message.clone(),
+                (None, Some(_)) => "Shared a photo".to_string(),
+                (None, None) => String::new(),
+            };
+            let message_label = row.label(ids!(message_label));
+            message_label.set_visible(cx, !message.is_empty());
+            message_label.set_text(cx, &message);
+            self.rows.push(row);
+        }
+        self.area.redraw(cx);
+    }
+}
+
+/// Widget listing the items on an event's checklist.
+#[derive(Live, LiveHook, Widget)]
+pub struct ChecklistList {
+    #[redraw]
+    #[rust]
+    area: Area,
+
+    /// Template for an item row.
+    #[live]
+    row_template: Option<LivePtr>,
+
+    /// Created rows, with the item each one represents.
+    #[rust]
+    rows: Vec<(ChecklistItem, WidgetRef)>,
+
+    /// Layout for the widget.
+    #[layout]
+    layout: Layout,
+
+    /// Walk for the widget.
+    #[walk]
+    walk: Walk,
+}
+
+impl Widget for ChecklistList {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        for (_, row) in &self.rows {
+            row.handle_event(cx, event, scope);
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        cx.begin_turtle(walk, self.layout);
+        for (_, row) in self.rows.iter_mut() {
+            let _ = row.draw(cx, scope);
+        }
+        cx.end_turtle_with_area(&mut self.area);
+        DrawStep::done()
+    }
+}
+
+impl ChecklistList {
+    /// Set the checklist items to display.
+    ///
+    /// The claim button lets the current user withdraw their claim,
+    /// or else sign up to bring one of an item or take on an unassigned task.
+    /// The done button checks off the whole item if `is_host`,
+    /// or else the current user's part of it, if they claimed it.
+    pub fn set_items(
+        &mut self,
+        cx: &mut Cx,
+        items: &[ChecklistItem],
+        own_user_id: Option<&UserId>,
+        is_host: bool,
+    ) {
+        self.rows.clear();
+        let Some(template) = self.row_template else {
+            return;
+        };
+
+        for item in items {
+            let row = WidgetRef::new_from_ptr(cx, Some(template));
+            let title = match item.kind {
+                ChecklistItemKind::Bring if item.quantity > 1 => {
+                    format!("{} × {}", item.title, item.quantity)
+                }
+                _ => item.title.clone(),
+            };
+            row.label(ids!(title_label)).set_text(cx, &title);
+            row.label(ids!(detail_label))
+                .set_text(cx, &checklist_item_details(item));
+
+            let own_claim = own_user_id.map_or(0, |user_id| item.claim_of(user_id));
+            let claim_button = row.button(ids!(claim_button));
+            match claim_button_text(item, own_claim).filter(|_| own_user_id.is_some()) {
+                Some(text) => claim_button.set_text(cx, text),
+                None => claim_button.set_visible(cx, false),
+            }
+            let done_button = row.button(ids!(done_button));
+            match checked_off_by_viewer(item, own_user_id, is_host) {
+                Some(done) => done_button.set_text(cx, if done { "Undo" } else { "Done" }),
+                None => done_button.set_visible(cx, false),
+            }
+            self.rows.push((item.clone(), row));
+        }
+        self.area.redraw(cx);
+    }
+
+    /// Get the item whose claim button was clicked.
+    pub fn clicked_claim(&self, actions: &Actions) -> Option<&ChecklistItem> {
+        self.rows
+            .iter()
+            .find(|(_, row)| row.button(ids!(claim_button)).clicked(actions))
+            .map(|(item, _)| item)
+    }
+
+    /// Get the item whose done button was clicked.
+    pub fn clicked_done(&self, actions: &Actions) -> Option<&ChecklistItem> {
+        self.rows
+            .iter()
+            .find(|(_, row)| row.button(ids!(done_button)).clicked(actions))
+            .map(|(item, _)| item)
+    }
+}
+
+/// Get whether a checklist item is checked off as far as the done button of
+/// the current user goes, or `None` if they can't check it off.
+///
+/// Mirrors [`ChecklistService::set_done()`](crate::social::events::ChecklistService::set_done).
+fn checked_off_by_viewer(
+    item: &ChecklistItem,
+    own_user_id: Option<&UserId>,
+    is_host: bool,
+) -> Option<bool> {
+    if is_host {
+        Some(item.done)
+    } else {
+        own_user_id
+            .and_then(|user_id| item.claim(user_id))
+            .map(|claim| claim.done)
+    }
+}
+
+/// Get the text of a checklist item's claim button, given how many of it the
+/// current user claimed, or `None` if there's nothing they can claim.
+fn claim_button_text(item: &ChecklistItem, own_claim: u32) -> Option<&'static str> {
+    match item.kind {
+        _ if item.is_done() => None,
+        ChecklistItemKind::Task if own_claim > 0 => Some("Give up"),
+        ChecklistItemKind::Task if item.claims.is_empty() => Some("I'll do it"),
+        ChecklistItemKind::Task => None,
+        ChecklistItemKind::Bring if own_claim > 0 => Some("Not bringing"),
+        ChecklistItemKind::Bring if item.remaining() > 0 => Some("I'll bring one"),
+        ChecklistItemKind::Bring => None,
+    }
+}
+
+/// Describe who is bringing or doing a checklist item.
+fn checklist_item_details(item: &ChecklistItem) -> String {
+    let claims = item
+        .claims
+        .iter()
+        .map(|claim| match claim.quantity {
+            1 => claim.user_id.localpart().to_string(),
+            quantity => format!("{} ({quantity})", claim.user_id.localpart()),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let status = match item.kind {
+        ChecklistItemKind::Task if claims.is_empty() => "Unassigned".to_string(),
+        ChecklistItemKind::Task => format!("Assigned to {claims}"),
+        ChecklistItemKind::Bring if claims.is_empty() => "Nobody signed up yet".to_string(),
+        ChecklistItemKind::Bring => {
+            format!("{} of {} covered · {claims}", item.claimed(), item.quantity)
+        }
+    };
+    match &item.done_by {
+        Some(done_by) if item.done => format!("Done by {} · {status}", done_by.localpart()),
+        _ if item.is_done() => format!("Done · {status}"),
+        _ => status,
+    }
+}
+
+/// Widget listing the ride matches, offers and requests of an event.
+#[derive(Live, LiveHook, Widget)]
+pub struct RideList {
+    #[redraw]
+    #[rust]
+    area: Area,
+
+    /// Template for a ride row.
+    #[live]
+    row_template: Option<LivePtr>,
+
+    /// Created rows, with the attendee each row's message button contacts.
+    #[rust]
+    rows: Vec<(Option<OwnedUserId>, WidgetRef)>,
+
+    /// Layout for the widget.
+    #[layout]
+    layout: Layout,
+
+    /// Walk for the widget.
+    #[walk]
+    walk: Walk,
+}
+
+impl Widget for RideList {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        for (_, row) in &self.rows {
+            row.handle_event(cx, event, scope);
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        cx.begin_turtle(walk, self.layout);
+        for (_, row) in self.rows.iter_mut() {
+            let _ = row.draw(cx, scope);
+        }
+        cx.end_turtle_with_area(&mut self.area);
+        DrawStep::done()
+    }
+}
+
+impl RideList {
+    /// Set the rides to display.
+    ///
+    /// The current user's matches come first, each with a button to message
+    /// the attendee they were matched with, followed by other matches,
+    /// then open offers and requests.
+    pub fn set_board(&mut self, cx: &mut Cx, board: &RideBoard, own_user_id: Option<&UserId>) {
+        self.rows.clear();
+        let Some(template) = self.row_template else {
+            return;
+        };
+
+        // Each row is the summary, an optional note and who to message
+        let mut rows: Vec<(String, Option<&str>, Option<OwnedUserId>)> = Vec::new();
+        let mut other_matches = Vec::new();
+        for ride_match in &board.matches {
+            let Some(counterpart) = own_user_id.and_then(|user_id| ride_match.counterpart(user_id))
+            else {
+                other_matches.push(ride_match);
+                continue;
+            };
+            let summary = if ride_match.driver == counterpart {
+                format!("{} is driving you", counterpart.localpart())
+            } else {
+                format!("You're driving {}", counterpart.localpart())
+            };
+            rows.push((
+                format!("{summary} · {}", seats_text(ride_match.seats)),
+                Some(ride_match.area.as_str()),
+                Some(counterpart.to_owned()),
+            ));
+        }
+        for ride_match in other_matches {
+            rows.push((
+                format!(
+                    "{} is driving {}",
+                    ride_match.driver.localpart(),
+                    ride_match.passenger.localpart()
+                ),
+                Some(ride_match.area.as_str()),
+                None,
+            ));
+        }
+        for ride in &board.open_offers {
+            rows.push((
+                format!(
+                    "{} has {} free from {}",
+                    ride.user_id.localpart(),
+                    seats_text(ride.seats),
+                    ride.area
+                ),
+                ride.note.as_deref(),
+                None,
+            ));
+        }
+        for ride in &board.open_requests {
+            rows.push((
+                format!(
+                    "{} needs {} from {}",
+                    ride.user_id.localpart(),
+                    seats_text(ride.seats),
+                    ride.area
+                ),
+                ride.note.as_deref(),
+                None,
+            ));
+        }
+
+        for (summary, note, contact) in rows {
+            let row = WidgetRef::new_from_ptr(cx, Some(template));
+            row.label(ids!(summary_label)).set_text(cx, &summary);
+            let note_label = row.label(ids!(note_label));
+            note_label.set_visible(cx, note.is_some());
+            note_label.set_text(cx, note.unwrap_or_default());
+            row.button(ids!(message_button))
+                .set_visible(cx, contact.is_some());
+            self.rows.push((contact, row));
+        }
+        self.area.redraw(cx);
+    }
+
+    /// Get the attendee whose message button was clicked.
+    pub fn clicked_contact(&self, actions: &Actions) -> Option<OwnedUserId> {
+        self.rows
+            .iter()
+            .find(|(_, row)| row.button(ids!(message_button)).clicked(actions))
+            .and_then(|(contact, _)| contact.clone())
+    }
+}
+
+/// Describe a number of seats, e.g., "1 seat" or "3 seats".
+fn seats_text(seats: u32) -> String {
+    match seats {
+        1 => "1 seat".to_string(),
+        seats => format!("{seats} seats"),
+    }
+}
+
+/// Returns whether the given user has a ride offer or request on the board.
+fn has_ride(board: &RideBoard, user_id: &UserId) -> bool {
+    let is_own = |ride: &Ride| ride.user_id == user_id;
+    board.matches_for(user_id).next().is_some()
+        || board.open_offers.iter().any(is_own)
+        || board.open_requests.iter().any(is_own)
+}
+
+/// Widget listing the candidate dates of a scheduling poll.
+#[derive(Live, LiveHook, Widget)]
+pub struct DatePollList {
+    #[redraw]
+    #[rust]
+    area: Area,
+
+    /// Template for a date row.
+    #[live]
+    row_template: Option<LivePtr>,
+
+    /// Created rows, with the start time each row is for.
+    #[rust]
+    rows: Vec<(u64, WidgetRef)>,
+
+    /// Layout for the widget.
+    #[layout]
+    layout: Layout,
+
+    /// Walk for the widget.
+    #[walk]
+    walk: Walk,
+}
+
+impl Widget for DatePollList {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        for (_, row) in &self.rows {
+            row.handle_event(cx, event, scope);
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        cx.begin_turtle(walk, self.layout);
+        for (_, row) in self.rows.iter_mut() {
+            let _ = row.draw(cx, scope);
+        }
+        cx.end_turtle_with_area(&mut self.area);
+        DrawStep::done()
+    }
+}
+
+impl DatePollList {
+    /// Set the poll to display.
+    ///
+    /// Voting is disabled once the poll has ended. Hosts get a button to
+    /// move the event to the date with the most votes.
+    pub fn set_poll(
+        &mut self,
+        cx: &mut Cx,
+        poll: &SchedulingPoll,
+        own_user_id: Option<&UserId>,
+        is_host: bool,
+    ) {
+        self.rows.clear();
+        let Some(template) = self.row_template else {
+            return;
+        };
+
+        let own_votes = own_user_id
+            .map(|user_id| poll.votes_of(user_id))
+            .unwrap_or_default();
+        let winner = poll.winner().map(|option| option.start_time);
+        for option in &poll.options {
+            let row = WidgetRef::new_from_ptr(cx, Some(template));
+            row.label(ids!(date_label))
+                .set_text(cx, &format_event_time(option.start_time, None));
+            row.label(ids!(votes_label))
+                .set_text(cx, &votes_text(option.voters.len()));
+            let vote_button = row.button(ids!(vote_button));
+            vote_button.set_visible(cx, !poll.ended && own_user_id.is_some());
+            vote_button.set_text(
+                cx,
+                if own_votes.contains(&option.start_time) {
+                    "Can't make it"
+                } else {
+                    "Works for me"
+                },
+            );
+            row.button(ids!(use_date_button)).set_visible(
+                cx,
+                is_host && !poll.ended && winner == Some(option.start_time),
+            );
+            self.rows.push((option.start_time, row));
+        }
+        self.area.redraw(cx);
+    }
+
+    /// Get the start time of the date whose vote button was clicked.
+    pub fn clicked_vote(&self, actions: &Actions) -> Option<u64> {
+        self.rows
+            .iter()
+            .find(|(_, row)| row.button(ids!(vote_button)).clicked(actions))
+            .map(|(start_time, _)| *start_time)
+    }
+
+    /// Returns whether the host clicked the button to use the winning date.
+    pub fn clicked_use_date(&self, actions: &Actions) -> bool {
+        self.rows
+            .iter()
+            .any(|(_, row)| row.button(ids!(use_date_button)).clicked(actions))
+    }
+}
+
+/// Describe a number of votes, e.g., "1 vote" or "3 votes".
+fn votes_text(votes: usize) -> String {
+    match votes {
+        1 => "1 vote".to_string(),
+        votes => format!("{votes} votes"),
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct EventDetailView {
+    #[deref]
+    view: View,
+
+    /// The room ID of the event being displayed.
+    #[rust]
+    room_id: Option<OwnedRoomId>,
+
+    /// Shareable summary text, available once host insights are loaded.
+    #[rust]
+    summary_text: Option<String>,
+
+    /// Whether check-in is open for the event.
+    #[rust]
+    checkin_open: bool,
+
+    /// Attendees who checked in, in order of arrival.
+    #[rust]
+    checkins: Vec<CheckIn>,
+
+    /// Items on the event's checklist.
+    #[rust]
+    checklist: Vec<ChecklistItem>,
+
+    /// Whether the current user can add, edit and remove checklist items.
+    #[rust]
+    checklist_host: bool,
+
+    /// The event's scheduling poll, if any.
+    #[rust]
+    scheduling_poll: Option<SchedulingPoll>,
+
+    /// Whether the creator should be asked what to do with the event room,
+    /// see [`is_archival_due()`].
+    #[rust]
+    archival_due: bool,
+}
+
+impl Widget for EventDetailView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for EventDetailView {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        let Some(room_id) = &self.room_id else {
+            return;
+        };
+
+        if let Some(quiet) = self.check_box(ids!(attend_quietly_toggle)).changed(actions) {
+            cx.action(EventDetailAction::SetAttendQuietly {
+                room_id: room_id.clone(),
+                quiet,
+            });
+        }
+
+        if let Some(list) = self
+            .view
+            .widget(ids!(date_options))
+            .borrow::<DatePollList>()
+        {
+            if let Some(start_time) = list.clicked_vote(actions) {
+                // Toggle the clicked date, keeping the user's other votes
+                let mut start_times = self
+                    .scheduling_poll
+                    .as_ref()
+                    .zip(current_user_id())
+                    .map(|(poll, user_id)| poll.votes_of(&user_id))
+                    .unwrap_or_default();
+                if let Some(index) = start_times.iter().position(|t| *t == start_time) {
+                    start_times.remove(index);
+                } else {
+                    start_times.push(start_time);
+                }
+                cx.action(EventDetailAction::VoteSchedulingPoll {
+                    room_id: room_id.clone(),
+                    start_times,
+                });
+            }
+            if list.clicked_use_date(actions) {
+                cx.action(EventDetailAction::FinalizeSchedulingPoll(room_id.clone()));
+            }
+        }
+
+        if self.button(ids!(checkin_photo_button)).clicked(actions) {
+            cx.action(EventDetailAction::AttachCheckinPhoto(room_id.clone()));
+        }
+
+        if self.button(ids!(present_photos_button)).clicked(actions) {
+            let slides = self
+                .checkins
+                .iter()
+                .filter(|c| c.photo.is_some())
+                .map(KioskSlide::from)
+                .collect();
+            cx.action(EventDetailAction::PresentPhotos {
+                room_id: room_id.clone(),
+                slides,
+            });
+        }
+
+        if self.button(ids!(checkin_button)).clicked(actions) {
+            let message: String = self
+                .text_input(ids!(checkin_message_input))
+                .text()
+                .trim()
+                .chars()
+                .take(MAX_CHECKIN_MESSAGE_CHARS)
+                .collect();
+            cx.action(EventDetailAction::CheckIn {
+                room_id: room_id.clone(),
+                message: (!message.is_empty()).then_some(message),
+            });
+        }
+
+        if self.button(ids!(add_checklist_item_button)).clicked(actions) {
+            let title_input = self.text_input(ids!(checklist_title_input));
+            let title: String = title_input
+                .text()
+                .trim()
+                .chars()
+                .take(MAX_CHECKLIST_TITLE_CHARS)
+                .collect();
+            if !title.is_empty() {
+                let kind = if self.check_box(ids!(checklist_task_toggle)).active(cx) {
+                    ChecklistItemKind::Task
+                } else {
+                    ChecklistItemKind::Bring
+                };
+                let quantity_input = self.text_input(ids!(checklist_quantity_input));
+                let quantity = match kind {
+                    ChecklistItemKind::Bring => quantity_input
+                        .text()
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|quantity| *quantity > 0)
+                        .unwrap_or(1),
+                    ChecklistItemKind::Task => 1,
+                };
+                cx.action(EventDetailAction::AddChecklistItem {
+                    room_id: room_id.clone(),
+                    title,
+                    kind,
+                    quantity,
+                });
+                title_input.set_text(cx, "");
+                quantity_input.set_text(cx, "");
+            }
+        }
+
+        if let Some(list) = self
+            .view
+            .widget(ids!(checklist_items))
+            .borrow::<ChecklistList>()
+        {
+            if let Some(item) = list.clicked_claim(actions) {
+                // Mirrors the button text set in `ChecklistList::set_items()`
+                let has_claim =
+                    current_user_id().is_some_and(|user_id| item.claim_of(&user_id) > 0);
+                cx.action(EventDetailAction::SetChecklistClaim {
+                    room_id: room_id.clone(),
+                    item_id: item.id.clone(),
+                    quantity: if has_claim { 0 } else { 1 },
+                });
+            }
+            if let Some(item) = list.clicked_done(actions) {
+                let own_user_id = current_user_id();
+                if let Some(done) =
+                    checked_off_by_viewer(item, own_user_id.as_deref(), self.checklist_host)
+                {
+                    cx.action(EventDetailAction::SetChecklistItemDone {
+                        room_id: room_id.clone(),
+                        item_id: item.id.clone(),
+                        done: !done,
+                    });
+                }
+            }
+        }
+
+        let offer_clicked = self.button(ids!(offer_ride_button)).clicked(actions);
+        let request_clicked = self.button(ids!(request_ride_button)).clicked(actions);
+        if offer_clicked || request_clicked {
+            let area_input = self.text_input(ids!(ride_area_input));
+            let area: String = area_input
+                .text()
+                .trim()
+                .chars()
+                .take(MAX_RIDE_AREA_CHARS)
+                .collect();
+            if !area.is_empty() {
+                let seats_input = self.text_input(ids!(ride_seats_input));
+                let seats = seats_input
+                    .text()
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|seats| (1..=MAX_RIDE_SEATS).contains(seats))
+                    .unwrap_or(1);
+                let room_id = room_id.clone();
+                cx.action(if offer_clicked {
+                    EventDetailAction::OfferRide {
+                        room_id,
+                        seats,
+                        area,
+                    }
+                } else {
+                    EventDetailAction::RequestRide {
+                        room_id,
+                        seats,
+                        area,
+                    }
+                });
+                area_input.set_text(cx, "");
+                seats_input.set_text(cx, "");
+            }
+        }
+
+        if self.button(ids!(withdraw_ride_button)).clicked(actions) {
+            cx.action(EventDetailAction::WithdrawRide(room_id.clone()));
+        }
+
+        let clicked_contact = self
+            .view
+            .widget(ids!(ride_list))
+            .borrow::<RideList>()
+            .and_then(|list| list.clicked_contact(actions));
+        if let Some(user_id) = clicked_contact {
+            cx.action(EventDetailAction::MessageAttendee(user_id));
+        }
+
+        if self.button(ids!(keep_open_button)).clicked(actions) {
+            cx.action(EventDetailAction::KeepEventOpen(room_id.clone()));
+            self.hide_archival_options(cx);
+            return;
+        }
+        if self.button(ids!(archive_event_button)).clicked(actions) {
+            cx.action(EventDetailAction::ArchiveEvent(room_id.clone()));
+            self.hide_archival_options(cx);
+            return;
+        }
+        if self.button(ids!(close_event_button)).clicked(actions) {
+            cx.action(EventDetailAction::CloseEvent(room_id.clone()));
+            self.hide_archival_options(cx);
+            return;
+        }
+
+        if self.button(ids!(share_summary_button)).clicked(actions) {
+            if let Some(summary) = &self.summary_text {
+                cx.action(EventDetailAction::ShareSummary(summary.clone()));
+            }
+        }
+
+        let clicked_role = self
+            .view
+            .widget(ids!(host_roles))
+            .borrow::<HostRolesList>()
+            .and_then(|list| list.clicked_row(actions));
+        match clicked_role {
+            Some((user_id, EventRole::CoHost)) => {
+                cx.action(EventDetailAction::RemoveCohost {
+                    room_id: room_id.clone(),
+                    user_id,
+                });
+            }
+            Some((user_id, EventRole::Guest)) => {
+                cx.action(EventDetailAction::AddCohost {
+                    room_id: room_id.clone(),
+                    user_id,
+                });
+            }
+            Some((_, EventRole::Creator)) | None => {}
+        }
+    }
+}
+
+impl EventDetailView {
+    /// Set the event to display.
+    pub fn set_event(&mut self, cx: &mut Cx, event: LoadedEvent) {
+        self.room_id = Some(event.room_id.clone());
+
+        // Quiet attendance only makes sense once the user has RSVPed
+        let has_rsvp = matches!(
+            event.user_rsvp,
+            Some(RsvpStatus::Going | RsvpStatus::Interested)
+        );
+        self.view(ids!(attendee_settings)).set_visible(cx, has_rsvp);
+
+        self.checkin_open = can_check_in(&event.event, now_ms());
+        self.update_checkins(cx);
+
+        self.view(ids!(rides_section))
+            .set_visible(cx, event.event.logistics);
+
+        self.archival_due = is_archival_due(&event.event, now_ms());
+        self.label(ids!(past_event_notice))
+            .set_visible(cx, is_past_event(&event.event));
+
+        self.event_card(ids!(event_card)).set_event(cx, event);
+        self.redraw(cx);
+    }
+
+    /// Update the "Attend quietly" toggle state.
+    ///
+    /// This should be called with the result of
+    /// [`QuietAttendanceService::is_attending_quietly()`](crate::social::events::QuietAttendanceService::is_attending_quietly).
+    pub fn set_attend_quietly(&mut self, cx: &mut Cx, quiet: bool) {
+        self.check_box(ids!(attend_quietly_toggle))
+            .set_active(cx, quiet);
+    }
+
+    /// Set the attendees who checked in to the event.
+    ///
+    /// This should be called with the result of
+    /// [`EventCheckinService::get_checkins()`](crate::social::events::EventCheckinService::get_checkins).
+    pub fn set_checkins(&mut self, cx: &mut Cx, checkins: Vec<CheckIn>) {
+        self.checkins = checkins;
+        self.update_checkins(cx);
+    }
+
+    /// Add a live check-in to the list.
+    ///
+    /// This should be called for each
+    /// [`EventCheckinAction::CheckedIn`](crate::social::events::EventCheckinAction::CheckedIn)
+    /// action for the displayed event.
+    pub fn add_checkin(&mut self, cx: &mut Cx, checkin: CheckIn) {
+        merge_checkin(&mut self.checkins, checkin);
+        self.update_checkins(cx);
+    }
+
+    /// Update the "Add photo" button to show whether a check-in photo is attached.
+    pub fn set_checkin_photo_attached(&mut self, cx: &mut Cx, attached: bool) {
+        self.button(ids!(checkin_photo_button))
+            .set_text(cx, if attached { "Photo attached" } else { "Add photo" });
+    }
+
+    /// Update the check-in count, list and form.
+    ///
+    /// The form is hidden once the current user has checked in.
+    fn update_checkins(&mut self, cx: &mut Cx) {
+        let own_user_id = current_user_id();
+        let checked_in = self
+            .checkins
+            .iter()
+            .any(|c| Some(&c.user_id) == own_user_id.as_ref());
+        if checked_in {
+            self.text_input(ids!(checkin_message_input)).set_text(cx, "");
+            self.set_checkin_photo_attached(cx, false);
+        }
+
+        self.label(ids!(checkin_title))
+            .set_text(cx, &format!("Checked in ({})", self.checkins.len()));
+        self.button(ids!(present_photos_button))
+            .set_visible(cx, self.checkins.iter().any(|c| c.photo.is_some()));
+        self.view(ids!(checkin_form))
+            .set_visible(cx, self.checkin_open && !checked_in);
+        self.view(ids!(checkin_section))
+            .set_visible(cx, self.checkin_open || !self.checkins.is_empty());
+        if let Some(mut list) = self
+            .view
+            .widget(ids!(checkin_list))
+            .borrow_mut::<CheckinList>()
+        {
+            list.set_checkins(cx, &self.checkins);
+        }
+        self.redraw(cx);
+    }
+
+    /// Set the items on the event's checklist and show the checklist.
+    ///
+    /// This should be called with the result of
+    /// [`ChecklistService::get_items()`](crate::social::events::ChecklistService::get_items),
+    /// and again whenever the event room's checklist state changes. `is_host`
+    /// should be the result of
+    /// [`ChecklistService::can_edit_items()`](crate::social::events::ChecklistService::can_edit_items);
+    /// only hosts are shown the form adding items.
+    pub fn set_checklist(&mut self, cx: &mut Cx, items: Vec<ChecklistItem>, is_host: bool) {
+        self.checklist = items;
+        self.checklist_host = is_host;
+        self.view(ids!(checklist_form)).set_visible(cx, is_host);
+        self.update_checklist(cx);
+        self.view(ids!(checklist_section)).set_visible(cx, true);
+    }
+
+    /// Update the checklist rows for the current user.
+    fn update_checklist(&mut self, cx: &mut Cx) {
+        self.label(ids!(checklist_empty_label))
+            .set_visible(cx, self.checklist.is_empty());
+        let own_user_id = current_user_id();
+        if let Some(mut list) = self
+            .view
+            .widget(ids!(checklist_items))
+            .borrow_mut::<ChecklistList>()
+        {
+            list.set_items(
+                cx,
+                &self.checklist,
+                own_user_id.as_deref(),
+                self.checklist_host,
+            );
+        }
+        self.redraw(cx);
+    }
+
+    /// Set the event's scheduling poll and show it, or hide it if there is none.
+    ///
+    /// This should be called with the result of
+    /// [`SchedulingPollService::get_poll()`](crate::social::events::SchedulingPollService::get_poll),
+    /// and again whenever someone votes. `is_host` should be the result of
+    /// [`EventInsightsService::is_host()`](crate::social::events::EventInsightsService::is_host).
+    pub fn set_scheduling_poll(
+        &mut self,
+        cx: &mut Cx,
+        poll: Option<SchedulingPoll>,
+        is_host: bool,
+    ) {
+        let own_user_id = current_user_id();
+        if let (Some(poll), Some(mut list)) = (
+            &poll,
+            self.view
+                .widget(ids!(date_options))
+                .borrow_mut::<DatePollList>(),
+        ) {
+            list.set_poll(cx, poll, own_user_id.as_deref(), is_host);
+        }
+        self.label(ids!(scheduling_poll_hint)).set_text(
+            cx,
+            if poll.as_ref().is_some_and(|poll| poll.ended) {
+                "The host picked a date."
+            } else {
+                "Vote for every date that works for you."
+            },
+        );
+        self.view(ids!(scheduling_poll_section))
+            .set_visible(cx, poll.is_some());
+        self.scheduling_poll = poll;
+        self.redraw(cx);
+    }
+
+    /// Set the rides offered and requested by attendees.
+    ///
+    /// This should be called with the result of
+    /// [`LogisticsService::get_ride_board()`](crate::social::events::LogisticsService::get_ride_board),
+    /// and again whenever the event room's ride state changes.
+    pub fn set_ride_board(&mut self, cx: &mut Cx, board: &RideBoard) {
+        let own_user_id = current_user_id();
+        self.label(ids!(rides_empty_label))
+            .set_visible(cx, board.is_empty());
+        self.button(ids!(withdraw_ride_button)).set_visible(
+            cx,
+            own_user_id
+                .as_deref()
+                .is_some_and(|user_id| has_ride(board, user_id)),
+        );
+        if let Some(mut list) = self
+            .view
+            .widget(ids!(ride_list))
+            .borrow_mut::<RideList>()
+        {
+            list.set_board(cx, board, own_user_id.as_deref());
+        }
+        self.redraw(cx);
+    }
+
+    /// Show the host dashboard with the given insights.
+    ///
+    /// Only call this for hosts, i.e., when
+    /// [`EventInsightsService::is_host()`](crate::social::events::EventInsightsService::is_host)
+    /// returned `true`.
+    pub fn set_insights(&mut self, cx: &mut Cx, insights: &EventInsights, summary: String) {
+        if let Some(mut chart) = self
+            .view
+            .widget(ids!(snapshot_chart))
+            .borrow_mut::<RsvpSnapshotChart>()
+        {
+            chart.set_snapshot(cx, &insights.rsvp_snapshot);
+        }
+
+        let conversion = &insights.conversion;
+        self.label(ids!(conversion_label)).set_text(
+            cx,
+            &format!(
+                "{} of {} invited joined ({:.0}%) · {:.0}% of attendees going",
+                conversion.joined,
+                conversion.joined + conversion.pending_invites,
+                conversion.join_rate() * 100.0,
+                conversion.going_rate() * 100.0,
+            ),
+        );
+        self.summary_text = Some(summary);
+        self.view(ids!(host_dashboard)).set_visible(cx, true);
+        self.redraw(cx);
+    }
+
+    /// Show the host management panel with the roles of the event's members.
+    ///
+    /// This should be called with the result of
+    /// [`EventRoomService::list_roles()`](crate::social::events::EventRoomService::list_roles),
+    /// and only for hosts. Only the creator can promote or demote co-hosts,
+    /// so the role buttons are hidden unless `viewer_role` is
+    /// [`EventRole::Creator`]. For the same reason, only the creator is asked
+    /// what to do with the event room once the event is long over.
+    pub fn set_roles(
+        &mut self,
+        cx: &mut Cx,
+        roles: &[(OwnedUserId, EventRole)],
+        viewer_role: EventRole,
+    ) {
+        if let Some(mut list) = self
+            .view
+            .widget(ids!(host_roles))
+            .borrow_mut::<HostRolesList>()
+        {
+            list.set_roles(cx, roles, viewer_role == EventRole::Creator);
+        }
+        self.view(ids!(host_management)).set_visible(cx, true);
+
+        // Only the creator can change the power levels of the event room
+        if self.archival_due && viewer_role == EventRole::Creator {
+            self.label(ids!(archival_hint)).set_text(
+                cx,
+                &format!(
+                    "It ended over {ARCHIVAL_DELAY_DAYS} days ago. Keep its chat open, \
+                     archive it so it's read-only for guests, \
+                     or close it for good and point guests to your feed."
+                ),
+            );
+            self.view(ids!(archival_options)).set_visible(cx, true);
+        }
+        self.redraw(cx);
+    }
+
+    /// Hide the archival options once the creator made their choice.
+    fn hide_archival_options(&mut self, cx: &mut Cx) {
+        self.archival_due = false;
+        self.view(ids!(archival_options)).set_visible(cx, false);
+        self.redraw(cx);
+    }
+
+    /// Clear the event data.
+    pub fn clear(&mut self, cx: &mut Cx) {
+        self.room_id = None;
+        self.summary_text = None;
+        self.checkin_open = false;
+        self.checkins.clear();
+        self.update_checkins(cx);
+        self.scheduling_poll = None;
+        self.view(ids!(scheduling_poll_section))
+            .set_visible(cx, false);
+        self.checklist.clear();
+        self.update_checklist(cx);
+        self.view(ids!(checklist_section)).set_visible(cx, false);
+        self.set_ride_board(cx, &RideBoard::default());
+        self.view(ids!(rides_section)).set_visible(cx, false);
+        self.view(ids!(host_dashboard)).set_visible(cx, false);
+        self.view(ids!(host_management)).set_visible(cx, false);
+        self.archival_due = false;
+        self.view(ids!(archival_options)).set_visible(cx, false);
+        self.label(ids!(past_event_notice)).set_visible(cx, false);
+        if let Some(mut list) = self
+            .view
+            .widget(ids!(host_roles))
+            .borrow_mut::<HostRolesList>()
+        {
+            list.set_roles(cx, &[], false);
+        }
+        self.event_card(ids!(event_card)).clear(cx);
+        self.view(ids!(attendee_settings)).set_visible(cx, false);
+        self.check_box(ids!(attend_quietly_toggle))
+            .set_active(cx, false);
+    }
+}
+
+impl EventDetailViewRef {
+    /// See [`EventDetailView::set_event()`].
+    pub fn set_event(&self, cx: &mut Cx, event: LoadedEvent) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_event(cx, event);
+        }
+    }
+
+    /// See [`EventDetailView::set_attend_quietly()`].
+    pub fn set_attend_quietly(&self, cx: &mut Cx, quiet: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_attend_quietly(cx, quiet);
+        }
+    }
+
+    /// See [`EventDetailView::set_checkins()`].
+    pub fn set_checkins(&self, cx: &mut Cx, checkins: Vec<CheckIn>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_checkins(cx, checkins);
+        }
+    }
+
+    /// See [`EventDetailView::add_checkin()`].
+    pub fn add_checkin(&self, cx: &mut Cx, checkin: CheckIn) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.add_checkin(cx, checkin);
+        }
+    }
+
+    /// See [`EventDetailView::set_checkin_photo_attached()`].
+    pub fn set_checkin_photo_attached(&self, cx: &mut Cx, attached: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_checkin_photo_attached(cx, attached);
+        }
+    }
+
+    /// See [`EventDetailView::set_checklist()`].
+    pub fn set_checklist(&self, cx: &mut Cx, items: Vec<ChecklistItem>, is_host: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_checklist(cx, items, is_host);
+        }
+    }
+
+    /// See [`EventDetailView::set_scheduling_poll()`].
+    pub fn set_scheduling_poll(&self, cx: &mut Cx, poll: Option<SchedulingPoll>, is_host: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_scheduling_poll(cx, poll, is_host);
+        }
+    }
+
+    /// See [`EventDetailView::set_ride_board()`].
+    pub fn set_ride_board(&self, cx: &mut Cx, board: &RideBoard) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_ride_board(cx, board);
+        }
+    }
+
+    /// See [`EventDetailView::set_insights()`].
+    pub fn set_insights(&self, cx: &mut Cx, insights: &EventInsights, summary: String) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_insights(cx, insights, summary);
+        }
+    }
+
+    /// See [`EventDetailView::set_roles()`].
+    pub fn set_roles(
+        &self,
+        cx: &mut Cx,
+        roles: &[(OwnedUserId, EventRole)],
+        viewer_role: EventRole,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_roles(cx, roles, viewer_role);
+        }
+    }
+
+    /// See [`EventDetailView::clear()`].
+    pub fn clear(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.clear(cx);
+        }
+    }
+}
+
+/// Maximum height of a bar in the RSVP snapshot chart.
+const SNAPSHOT_CHART_MAX_BAR_HEIGHT: f64 = 80.0;
+
+/// Widget for displaying the current RSVPs by when they were last changed
+/// as a simple bar chart, see [`compute_rsvp_snapshot()`].
+///
+/// [`compute_rsvp_snapshot()`]: crate::social::events::insights::compute_rsvp_snapshot
+#[derive(Live, LiveHook, Widget)]
+pub struct RsvpSnapshotChart {
+    #[redraw]
+    #[rust]
+    area: Area,
+
+    /// Template for the chart bars.
+    #[live]
+    bar_template: Option<LivePtr>,
+
+    /// Created bars, one per snapshot point.
+    #[rust]
+    bars: Vec<WidgetRef>,
+
+    /// Layout for the widget.
+    #[layout]
+    layout: Layout,
+
+    /// Walk for the widget.
+    #[walk]
+    walk: Walk,
+}
+
+impl Widget for RsvpSnapshotChart {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        cx.begin_turtle(walk, self.layout);
+        for bar in self.bars.iter_mut() {
+            let _ = bar.draw(cx, scope);
+        }
+        cx.end_turtle_with_area(&mut self.area);
+        DrawStep::done()
+    }
+}
+
+impl RsvpSnapshotChart {
+    /// Set the snapshot points to display.
+    ///
+    /// Each bar's height is proportional to the number of users
+    /// going or interested at the end of that bucket.
+    pub fn set_snapshot(&mut self, cx: &mut Cx, snapshot: &[RsvpSnapshotPoint]) {
+        self.bars.clear();
+        let Some(template) = self.bar_template else {
+            return;
+        };
+
+        let max = snapshot
+            .iter()
+            .map(|p| p.going + p.interested)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for point in snapshot {
+            let bar = WidgetRef::new_from_ptr(cx, Some(template));
+            let height = SNAPSHOT_CHART_MAX_BAR_HEIGHT * (point.going + point.interested) as f64
+                / max as f64;
+            bar.apply_over(cx, live! { height: (height) });
+            self.bars.push(bar);
+        }
+        self.area.redraw(cx);
+    }
+}
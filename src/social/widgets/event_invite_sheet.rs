@@ -0,0 +1,439 @@
+//! Invites sheet for inviting friends to an event.
+//!
+//! The sheet lists the user's friends with a checkbox each, so a host can
+//! invite several guests at once. After the invites are sent, each row shows
+//! whether that guest's invite succeeded.
+//!
+//! It's opened from the Invite button of an event in the calendar on one's
+//! own profile page. The friends are loaded through
+//! [`MatrixRequest::LoadEventInviteFriends`] and the invites sent through
+//! [`MatrixRequest::InviteEventGuests`], which post an [`EventInvitesAction`].
+//!
+//! [`MatrixRequest::LoadEventInviteFriends`]: crate::sliding_sync::MatrixRequest::LoadEventInviteFriends
+//! [`MatrixRequest::InviteEventGuests`]: crate::sliding_sync::MatrixRequest::InviteEventGuests
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::{OwnedRoomId, OwnedUserId};
+use std::collections::{HashMap, HashSet};
+
+use crate::shared::avatar::AvatarWidgetExt;
+use crate::social::widgets::friend_list::FriendInfo;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use crate::shared::avatar::Avatar;
+
+    INVITE_ACCENT_COLOR = #1d9bf0
+
+    /// A selectable friend in the invites sheet.
+    InviteFriendItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 10, bottom: 10 },
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        select_toggle = <CheckBoxFlat> {
+            text: "",
+            active: false,
+        }
+
+        avatar = <Avatar> {
+            width: 36,
+            height: 36,
+        }
+
+        info_column = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 2,
+
+            name_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: #000,
+                }
+            }
+
+            username_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                }
+            }
+        }
+
+        status_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 12.0 },
+                color: #666,
+            }
+        }
+    }
+
+    /// Sheet for inviting friends to an event.
+    pub EventInviteSheet = {{EventInviteSheet}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: #f5f5f5
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            flow: Right,
+            spacing: 8,
+            align: { y: 0.5 },
+            show_bg: true,
+            draw_bg: {
+                color: #fff
+            }
+
+            title_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Invite friends",
+                draw_text: {
+                    text_style: { font_size: 18.0 },
+                    color: #000,
+                }
+            }
+
+            close_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Close",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: #666,
+                }
+            }
+        }
+
+        friend_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            friend_item = <InviteFriendItem> {}
+
+            empty_item = <View> {
+                width: Fill,
+                height: Fit,
+                padding: 32,
+                align: { x: 0.5 },
+
+                <Label> {
+                    text: "No friends to invite yet.",
+                    draw_text: {
+                        text_style: { font_size: 14.0 },
+                        color: #666,
+                    }
+                }
+            }
+        }
+
+        footer = <View> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            show_bg: true,
+            draw_bg: {
+                color: #fff
+            }
+
+            send_button = <Button> {
+                width: Fill,
+                height: Fit,
+                padding: 12,
+                text: "Send invites",
+                draw_bg: {
+                    color: (INVITE_ACCENT_COLOR),
+                    radius: 4.0,
+                }
+                draw_text: {
+                    color: #fff,
+                }
+            }
+        }
+    }
+}
+
+/// The invite state of a single friend in the sheet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InviteStatus {
+    /// The friend is already invited to or has joined the event.
+    AlreadyInvited,
+    /// An invite was sent successfully.
+    Sent,
+    /// Sending the invite failed.
+    Failed(String),
+}
+
+/// Actions emitted when the friends that can be invited to an event were loaded,
+/// or invites to it were sent.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum EventInvitesAction {
+    /// The current user's friends were loaded, along with those of them
+    /// who already joined or were invited to the event.
+    FriendsLoaded {
+        room_id: OwnedRoomId,
+        friends: Vec<FriendInfo>,
+        already_invited: HashSet<OwnedUserId>,
+    },
+    /// Invites to the event were sent, with the result of each.
+    Sent {
+        room_id: OwnedRoomId,
+        results: Vec<(OwnedUserId, Result<(), String>)>,
+    },
+    /// Loading the friends or sending the invites failed altogether.
+    Failed(String),
+    /// No action.
+    None,
+}
+
+/// Actions that can be triggered from the invites sheet.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum EventInviteSheetAction {
+    /// User wants to invite the selected friends to the event.
+    SendInvites {
+        room_id: OwnedRoomId,
+        user_ids: Vec<OwnedUserId>,
+    },
+    /// User closed the sheet.
+    Close,
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct EventInviteSheet {
+    #[deref]
+    view: View,
+
+    /// Room ID of the event guests are invited to.
+    #[rust]
+    room_id: Option<OwnedRoomId>,
+
+    /// Friends that can be invited.
+    #[rust]
+    friends: Vec<FriendInfo>,
+
+    /// Friends currently selected for inviting.
+    #[rust]
+    selected: HashSet<OwnedUserId>,
+
+    /// Invite state of friends that can no longer be selected,
+    /// or whose invite failed.
+    #[rust]
+    statuses: HashMap<OwnedUserId, InviteStatus>,
+}
+
+impl Widget for EventInviteSheet {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, self.friends.len().max(1));
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let item = match self.friends.get(item_id) {
+                    Some(friend) => {
+                        let item = list.item(cx, item_id, live_id!(friend_item));
+                        self.populate_friend_item(cx, &item, friend);
+                        item
+                    }
+                    None => list.item(cx, item_id, live_id!(empty_item)),
+                };
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for EventInviteSheet {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.button(ids!(close_button)).clicked(actions) {
+            cx.action(EventInviteSheetAction::Close);
+        }
+
+        let friend_list = self.portal_list(ids!(friend_list));
+        for (index, item) in friend_list.items_with_actions(actions) {
+            let Some(selected) = item.check_box(ids!(select_toggle)).changed(actions) else {
+                continue;
+            };
+            let Some(friend) = self.friends.get(index) else {
+                continue;
+            };
+            // Toggling a friend whose invite failed clears the failure,
+            // so they can be picked again
+            if let Some(InviteStatus::Failed(_)) = self.statuses.get(&friend.user_id) {
+                self.statuses.remove(&friend.user_id);
+            }
+            if selected && !self.statuses.contains_key(&friend.user_id) {
+                self.selected.insert(friend.user_id.clone());
+            } else {
+                self.selected.remove(&friend.user_id);
+            }
+            self.update_send_button(cx);
+            self.redraw(cx);
+        }
+
+        if self.button(ids!(send_button)).clicked(actions) && !self.selected.is_empty() {
+            if let Some(room_id) = &self.room_id {
+                // Keep the sheet's friend order so results line up with the list
+                let user_ids = self
+                    .friends
+                    .iter()
+                    .filter(|f| self.selected.contains(&f.user_id))
+                    .map(|f| f.user_id.clone())
+                    .collect();
+                cx.action(EventInviteSheetAction::SendInvites {
+                    room_id: room_id.clone(),
+                    user_ids,
+                });
+            }
+        }
+    }
+}
+
+impl EventInviteSheet {
+    /// Set the event and the friends that can be invited to it.
+    ///
+    /// Friends in `already_invited` are shown but cannot be selected.
+    pub fn set_friends(
+        &mut self,
+        cx: &mut Cx,
+        room_id: OwnedRoomId,
+        friends: Vec<FriendInfo>,
+        already_invited: &HashSet<OwnedUserId>,
+    ) {
+        self.room_id = Some(room_id);
+        self.selected.clear();
+        self.statuses = friends
+            .iter()
+            .filter(|f| already_invited.contains(&f.user_id))
+            .map(|f| (f.user_id.clone(), InviteStatus::AlreadyInvited))
+            .collect();
+        self.friends = friends;
+        self.update_send_button(cx);
+        self.redraw(cx);
+    }
+
+    /// Show the per-guest results of sending invites.
+    ///
+    /// Successfully invited friends are deselected; failed ones stay selected
+    /// so the invite can be retried, and can be deselected and picked again.
+    pub fn set_results(&mut self, cx: &mut Cx, results: &[(OwnedUserId, Result<(), String>)]) {
+        for (user_id, result) in results {
+            match result {
+                Ok(()) => {
+                    self.selected.remove(user_id);
+                    self.statuses.insert(user_id.clone(), InviteStatus::Sent);
+                }
+                Err(error) => {
+                    self.statuses
+                        .insert(user_id.clone(), InviteStatus::Failed(error.clone()));
+                }
+            }
+        }
+        self.update_send_button(cx);
+        self.redraw(cx);
+    }
+
+    /// Get the friends currently selected for inviting.
+    pub fn selected(&self) -> &HashSet<OwnedUserId> {
+        &self.selected
+    }
+
+    /// Update the send button label with the number of selected friends.
+    fn update_send_button(&mut self, cx: &mut Cx) {
+        let text = match self.selected.len() {
+            0 => "Send invites".to_string(),
+            1 => "Send 1 invite".to_string(),
+            n => format!("Send {} invites", n),
+        };
+        self.button(ids!(send_button)).set_text(cx, &text);
+    }
+
+    /// Fill in a friend row from the friend's info and invite state.
+    fn populate_friend_item(&self, cx: &mut Cx, item: &WidgetRef, friend: &FriendInfo) {
+        let display_name = friend
+            .display_name
+            .as_deref()
+            .unwrap_or_else(|| friend.user_id.localpart());
+        item.avatar(ids!(avatar)).set_text(cx, display_name);
+        item.label(ids!(name_label)).set_text(cx, display_name);
+        item.label(ids!(username_label))
+            .set_text(cx, friend.user_id.as_str());
+
+        let status = self.statuses.get(&friend.user_id);
+        let status_text = match status {
+            Some(InviteStatus::AlreadyInvited) => "Already invited",
+            Some(InviteStatus::Sent) => "Invited",
+            Some(InviteStatus::Failed(_)) => "Failed, tap send to retry",
+            None => "",
+        };
+        item.label(ids!(status_label)).set_text(cx, status_text);
+
+        let toggle = item.check_box(ids!(select_toggle));
+        toggle.set_active(cx, self.selected.contains(&friend.user_id));
+        let selectable = matches!(status, None | Some(InviteStatus::Failed(_)));
+        toggle.set_visible(cx, selectable);
+    }
+}
+
+impl EventInviteSheetRef {
+    /// See [`EventInviteSheet::set_friends()`].
+    pub fn set_friends(
+        &self,
+        cx: &mut Cx,
+        room_id: OwnedRoomId,
+        friends: Vec<FriendInfo>,
+        already_invited: &HashSet<OwnedUserId>,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_friends(cx, room_id, friends, already_invited);
+        }
+    }
+
+    /// See [`EventInviteSheet::set_results()`].
+    pub fn set_results(&self, cx: &mut Cx, results: &[(OwnedUserId, Result<(), String>)]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_results(cx, results);
+        }
+    }
+}
@@ -0,0 +1,760 @@
+//! Calendar widget for upcoming events.
+//!
+//! This widget shows the events a user has joined or been invited to,
+//! either as a month grid with the selected day's events below it,
+//! or as a scrollable agenda grouped by day. Events whose room was archived
+//! or closed by their host are listed under "Past events" at the end of the agenda.
+//!
+//! It's shown in the Events tab of one's own profile page, which opens an
+//! event's room when it's tapped (see [`crate::social::profile_tabs`]).
+
+use chrono::{Datelike, Local, Months, NaiveDate};
+use makepad_widgets::*;
+use matrix_sdk::ruma::OwnedRoomId;
+use std::collections::BTreeSet;
+
+use crate::social::discovery::{group_events_by_day, DiscoveredEvent, EventMembership};
+use crate::shared::popup_list::{enqueue_popup_notification, PopupItem, PopupKind};
+use crate::social::events::timezone_watcher::{shift_alert_message, shifted_events};
+use crate::social::events::timing::now_ms;
+use crate::social::ticker;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use link::social_theme::*;
+
+    CALENDAR_CELL_SIZE = 40
+
+    /// A single day cell in the month grid.
+    CalendarDayCell = <Button> {
+        width: (CALENDAR_CELL_SIZE),
+        height: (CALENDAR_CELL_SIZE),
+        padding: 0,
+        text: "",
+        draw_bg: {
+            // 1.0 if this is the selected day
+            instance selected: 0.0
+            // 1.0 if this is today
+            instance today: 0.0
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                let radius = min(self.rect_size.x, self.rect_size.y) * 0.5;
+                sdf.circle(self.rect_size.x * 0.5, self.rect_size.y * 0.5, radius);
+                let today_color = mix(vec4(0.0, 0.0, 0.0, 0.0), (SOCIAL_ACCENT_BG), self.today);
+                sdf.fill(mix(today_color, (SOCIAL_ACCENT), self.selected));
+                return sdf.result;
+            }
+        }
+        draw_text: {
+            // 1.0 if this is the selected day
+            instance selected: 0.0
+            text_style: { font_size: 12.0 },
+
+            fn get_color(self) -> vec4 {
+                return mix((SOCIAL_TEXT), (SOCIAL_ON_ACCENT), self.selected);
+            }
+        }
+    }
+
+    /// A weekday name in the month grid header.
+    CalendarWeekday = <Label> {
+        width: (CALENDAR_CELL_SIZE),
+        height: Fit,
+        align: { x: 0.5 },
+        draw_text: {
+            text_style: { font_size: 11.0 },
+            color: (SOCIAL_TEXT_MUTED),
+        }
+    }
+
+    /// Calendar of upcoming events with month and agenda modes.
+    pub SocialEventsCalendar = {{SocialEventsCalendar}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        padding: 12,
+        spacing: 8,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_SURFACE)
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 8,
+            align: { y: 0.5 },
+
+            prev_month_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "‹",
+                draw_bg: { color: #0000 }
+                draw_text: { color: (SOCIAL_TEXT), text_style: { font_size: 16.0 } }
+            }
+
+            month_label = <Label> {
+                width: Fit,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 16.0 },
+                    color: (SOCIAL_TEXT_PRIMARY),
+                }
+            }
+
+            next_month_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "›",
+                draw_bg: { color: #0000 }
+                draw_text: { color: (SOCIAL_TEXT), text_style: { font_size: 16.0 } }
+            }
+
+            <View> { width: Fill, height: 1 }
+
+            month_mode_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Month",
+                draw_bg: { color: #0000 }
+                draw_text: { color: (SOCIAL_ACCENT) }
+            }
+
+            agenda_mode_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Agenda",
+                draw_bg: { color: #0000 }
+                draw_text: { color: (SOCIAL_ACCENT) }
+            }
+        }
+
+        month_section = <View> {
+            width: Fit,
+            height: Fit,
+            flow: Down,
+
+            weekday_header = <View> {
+                width: Fit,
+                height: Fit,
+                flow: Right,
+
+                <CalendarWeekday> { text: "Mo" }
+                <CalendarWeekday> { text: "Tu" }
+                <CalendarWeekday> { text: "We" }
+                <CalendarWeekday> { text: "Th" }
+                <CalendarWeekday> { text: "Fr" }
+                <CalendarWeekday> { text: "Sa" }
+                <CalendarWeekday> { text: "Su" }
+            }
+
+            month_grid = {{CalendarMonthGrid}} {
+                width: 280,
+                height: Fit,
+                flow: RightWrap,
+
+                day_template: <CalendarDayCell> {}
+            }
+        }
+
+        agenda_title = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: (SOCIAL_TEXT),
+            }
+        }
+
+        agenda_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            day_header = <View> {
+                width: Fill,
+                height: Fit,
+                padding: { top: 12, bottom: 4 },
+
+                date_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 13.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                    }
+                }
+            }
+
+            event_row = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 12,
+                padding: 12,
+                margin: { bottom: 4 },
+                cursor: Hand,
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_BG),
+                    radius: 8.0,
+                }
+
+                time_label = <Label> {
+                    width: 72,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_ACCENT),
+                    }
+                }
+
+                event_info = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+                    spacing: 2,
+
+                    title_label = <Label> {
+                        width: Fill,
+                        height: Fit,
+                        text: "",
+                        draw_text: {
+                            text_style: { font_size: 14.0 },
+                            color: (SOCIAL_TEXT_PRIMARY),
+                        }
+                    }
+
+                    location_label = <Label> {
+                        width: Fill,
+                        height: Fit,
+                        text: "",
+                        draw_text: {
+                            text_style: { font_size: 12.0 },
+                            color: (SOCIAL_TEXT_SECONDARY),
+                        }
+                    }
+                }
+
+                invited_badge = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Invited",
+                    draw_text: {
+                        text_style: { font_size: 11.0 },
+                        color: (SOCIAL_WARNING),
+                    }
+                }
+
+                // Only shown for upcoming events the user has joined
+                invite_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Invite",
+                    visible: false,
+                }
+            }
+
+            empty_agenda = <View> {
+                width: Fill,
+                height: Fit,
+                padding: 24,
+                align: { x: 0.5 },
+
+                <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "No events",
+                    draw_text: {
+                        text_style: { font_size: 14.0 },
+                        color: (SOCIAL_TEXT_MUTED),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How the events calendar is displayed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CalendarMode {
+    /// A month grid, with the selected day's events listed below.
+    #[default]
+    Month,
+    /// A scrollable list of all upcoming events, grouped by day,
+    /// followed by past events.
+    Agenda,
+}
+
+/// Actions that can be triggered from the events calendar.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialEventsCalendarAction {
+    /// User tapped an event to view its details.
+    OpenEvent(OwnedRoomId),
+    /// User tapped to invite friends to an event they joined.
+    InviteFriends(OwnedRoomId),
+    /// No action.
+    None,
+}
+
+/// A single row in the agenda list.
+#[derive(Clone, Debug)]
+enum AgendaRow {
+    /// Header for the events happening right now.
+    LiveHeader,
+    /// Header for the events on a given day.
+    Day(NaiveDate),
+    /// An event, by index into `events`.
+    Event(usize),
+    /// Header for the events whose room was archived or closed.
+    PastHeader,
+    /// A past event, by index into `events`.
+    PastEvent(usize),
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialEventsCalendar {
+    #[deref]
+    view: View,
+
+    /// Events to display, sorted by start time.
+    #[rust]
+    events: Vec<DiscoveredEvent>,
+
+    /// Current display mode.
+    #[rust]
+    mode: CalendarMode,
+
+    /// First day of the month shown in the month grid.
+    #[rust]
+    month: Option<NaiveDate>,
+
+    /// The day selected in the month grid, if any.
+    #[rust]
+    selected_day: Option<NaiveDate>,
+
+    /// Rows of the agenda list, computed from the events and mode.
+    #[rust]
+    rows: Vec<AgendaRow>,
+}
+
+impl Widget for SocialEventsCalendar {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, self.rows.len().max(1));
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let item = match self.rows.get(item_id) {
+                    Some(AgendaRow::LiveHeader) => {
+                        let item = list.item(cx, item_id, live_id!(day_header));
+                        item.label(ids!(date_label)).set_text(cx, "Happening now");
+                        item
+                    }
+                    Some(AgendaRow::Day(day)) => {
+                        let item = list.item(cx, item_id, live_id!(day_header));
+                        item.label(ids!(date_label))
+                            .set_text(cx, &day.format("%A, %B %-d").to_string());
+                        item
+                    }
+                    Some(AgendaRow::Event(index)) => {
+                        let item = list.item(cx, item_id, live_id!(event_row));
+                        if let Some(event) = self.events.get(*index) {
+                            populate_event_row(cx, &item, event);
+                        }
+                        item
+                    }
+                    Some(AgendaRow::PastHeader) => {
+                        let item = list.item(cx, item_id, live_id!(day_header));
+                        item.label(ids!(date_label)).set_text(cx, "Past events");
+                        item
+                    }
+                    Some(AgendaRow::PastEvent(index)) => {
+                        let item = list.item(cx, item_id, live_id!(event_row));
+                        if let Some(event) = self.events.get(*index) {
+                            populate_event_row(cx, &item, event);
+                            // Past events aren't grouped by day, so show their date instead
+                            if let Some(day) = event.start_day(&Local) {
+                                item.label(ids!(time_label))
+                                    .set_text(cx, &day.format("%b %-d, %Y").to_string());
+                            }
+                        }
+                        item
+                    }
+                    None => list.item(cx, item_id, live_id!(empty_agenda)),
+                };
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for SocialEventsCalendar {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        // Live events move to the top of the agenda as time passes
+        if ticker::is_tick(actions) && self.mode == CalendarMode::Agenda {
+            self.refresh(cx);
+        }
+
+        // Re-render local times after a timezone change, and tell the user
+        // about any upcoming events that now start at a different local time.
+        if let Some(change) = ticker::timezone_change(actions) {
+            let shifted = change.old_tz().map_or_else(Vec::new, |old_tz| {
+                shifted_events(&self.events, &old_tz, &Local, now_ms())
+            });
+            if let Some(message) = shift_alert_message(&shifted) {
+                enqueue_popup_notification(PopupItem {
+                    message,
+                    auto_dismissal_duration: None,
+                    kind: PopupKind::Info,
+                });
+            }
+            self.refresh(cx);
+        }
+
+        if self.button(ids!(month_mode_button)).clicked(actions) {
+            self.set_mode(cx, CalendarMode::Month);
+        }
+        if self.button(ids!(agenda_mode_button)).clicked(actions) {
+            self.set_mode(cx, CalendarMode::Agenda);
+        }
+
+        if self.button(ids!(prev_month_button)).clicked(actions) {
+            if let Some(month) = self.month.and_then(|m| m.checked_sub_months(Months::new(1))) {
+                self.show_month(cx, month);
+            }
+        }
+        if self.button(ids!(next_month_button)).clicked(actions) {
+            if let Some(month) = self.month.and_then(|m| m.checked_add_months(Months::new(1))) {
+                self.show_month(cx, month);
+            }
+        }
+
+        for action in actions {
+            if let Some(CalendarMonthGridAction::SelectDay(day)) =
+                action.downcast_ref::<CalendarMonthGridAction>()
+            {
+                // Tapping the selected day again shows the whole month
+                self.selected_day = (self.selected_day != Some(*day)).then_some(*day);
+                self.refresh(cx);
+            }
+        }
+
+        let agenda_list = self.portal_list(ids!(agenda_list));
+        for (index, item) in agenda_list.items_with_actions(actions) {
+            let Some(AgendaRow::Event(event_index) | AgendaRow::PastEvent(event_index)) =
+                self.rows.get(index)
+            else {
+                continue;
+            };
+            let Some(event) = self.events.get(*event_index) else {
+                continue;
+            };
+            if item.button(ids!(invite_button)).clicked(actions) {
+                cx.action(SocialEventsCalendarAction::InviteFriends(
+                    event.room_id.clone(),
+                ));
+            } else if item.as_view().finger_up(actions).is_some() {
+                cx.action(SocialEventsCalendarAction::OpenEvent(event.room_id.clone()));
+            }
+        }
+    }
+}
+
+impl SocialEventsCalendar {
+    /// Set the events to display.
+    ///
+    /// This should be called with the result of
+    /// [`EventDiscoveryService::discover_events()`](crate::social::discovery::EventDiscoveryService::discover_events).
+    pub fn set_events(&mut self, cx: &mut Cx, mut events: Vec<DiscoveredEvent>) {
+        events.sort_by_key(|e| e.event.start_time);
+        self.events = events;
+        if self.month.is_none() {
+            self.month = Some(first_of_month(Local::now().date_naive()));
+        }
+        self.refresh(cx);
+    }
+
+    /// Switch between the month grid and the agenda list.
+    pub fn set_mode(&mut self, cx: &mut Cx, mode: CalendarMode) {
+        self.mode = mode;
+        self.refresh(cx);
+    }
+
+    /// Show the month containing the given day in the month grid.
+    pub fn show_month(&mut self, cx: &mut Cx, day: NaiveDate) {
+        self.month = Some(first_of_month(day));
+        self.selected_day = None;
+        self.refresh(cx);
+    }
+
+    /// Recompute the month grid and agenda rows, then redraw.
+    fn refresh(&mut self, cx: &mut Cx) {
+        let today = Local::now().date_naive();
+        let month = *self.month.get_or_insert_with(|| first_of_month(today));
+        let is_month_mode = self.mode == CalendarMode::Month;
+
+        self.view(ids!(month_section)).set_visible(cx, is_month_mode);
+        self.button(ids!(prev_month_button))
+            .set_visible(cx, is_month_mode);
+        self.button(ids!(next_month_button))
+            .set_visible(cx, is_month_mode);
+        self.label(ids!(month_label))
+            .set_text(cx, &month.format("%B %Y").to_string());
+
+        if is_month_mode {
+            let event_days: BTreeSet<NaiveDate> =
+                group_events_by_day(&self.events, &Local).into_keys().collect();
+            if let Some(mut grid) = self
+                .view
+                .widget(ids!(month_grid))
+                .borrow_mut::<CalendarMonthGrid>()
+            {
+                grid.set_month(cx, month, today, self.selected_day, &event_days);
+            }
+        }
+
+        let title = match (self.mode, self.selected_day) {
+            (CalendarMode::Agenda, _) => "Upcoming".to_string(),
+            (CalendarMode::Month, Some(day)) => day.format("Events on %B %-d").to_string(),
+            (CalendarMode::Month, None) => month.format("Events in %B").to_string(),
+        };
+        self.label(ids!(agenda_title)).set_text(cx, &title);
+
+        self.rows.clear();
+        let now = now_ms();
+
+        // In the agenda, events that are happening now are listed first
+        if self.mode == CalendarMode::Agenda {
+            let live_events: Vec<usize> = self
+                .events
+                .iter()
+                .enumerate()
+                .filter(|(_, event)| event.timing(now).is_live() && !event.is_past())
+                .map(|(index, _)| index)
+                .collect();
+            if !live_events.is_empty() {
+                self.rows.push(AgendaRow::LiveHeader);
+                self.rows
+                    .extend(live_events.into_iter().map(AgendaRow::Event));
+            }
+        }
+
+        let mut current_day = None;
+        for (index, event) in self.events.iter().enumerate() {
+            let Some(day) = event.start_day(&Local) else {
+                continue;
+            };
+            let visible = match (self.mode, self.selected_day) {
+                (CalendarMode::Agenda, _) => {
+                    event.is_upcoming(now) && !event.timing(now).is_live() && !event.is_past()
+                }
+                (CalendarMode::Month, Some(selected)) => day == selected,
+                (CalendarMode::Month, None) => first_of_month(day) == month,
+            };
+            if !visible {
+                continue;
+            }
+            if current_day != Some(day) {
+                self.rows.push(AgendaRow::Day(day));
+                current_day = Some(day);
+            }
+            self.rows.push(AgendaRow::Event(index));
+        }
+
+        // Past events come last in the agenda, most recent first
+        if self.mode == CalendarMode::Agenda {
+            let past_events: Vec<usize> = self
+                .events
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(_, event)| event.is_past())
+                .map(|(index, _)| index)
+                .collect();
+            if !past_events.is_empty() {
+                self.rows.push(AgendaRow::PastHeader);
+                self.rows
+                    .extend(past_events.into_iter().map(AgendaRow::PastEvent));
+            }
+        }
+
+        self.redraw(cx);
+    }
+}
+
+impl SocialEventsCalendarRef {
+    /// See [`SocialEventsCalendar::set_events()`].
+    pub fn set_events(&self, cx: &mut Cx, events: Vec<DiscoveredEvent>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_events(cx, events);
+        }
+    }
+
+    /// See [`SocialEventsCalendar::set_mode()`].
+    pub fn set_mode(&self, cx: &mut Cx, mode: CalendarMode) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_mode(cx, mode);
+        }
+    }
+
+    /// See [`SocialEventsCalendar::show_month()`].
+    pub fn show_month(&self, cx: &mut Cx, day: NaiveDate) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_month(cx, day);
+        }
+    }
+}
+
+/// Fill an agenda event row with the given event's details.
+fn populate_event_row(cx: &mut Cx, item: &WidgetRef, event: &DiscoveredEvent) {
+    let time = chrono::DateTime::from_timestamp_millis(event.event.start_time as i64)
+        .map(|start| start.with_timezone(&Local).format("%-I:%M %p").to_string())
+        .unwrap_or_default();
+    item.label(ids!(time_label)).set_text(cx, &time);
+    item.label(ids!(title_label)).set_text(cx, &event.event.title);
+    item.label(ids!(location_label)).set_text(
+        cx,
+        event
+            .event
+            .location
+            .as_ref()
+            .map(|l| l.name.as_str())
+            .unwrap_or(""),
+    );
+    item.label(ids!(invited_badge))
+        .set_visible(cx, event.membership == EventMembership::Invited);
+    let can_invite = event.membership == EventMembership::Joined
+        && event.is_upcoming(now_ms())
+        && !event.is_past();
+    item.button(ids!(invite_button)).set_visible(cx, can_invite);
+}
+
+/// Get the first day of the month containing the given day.
+fn first_of_month(day: NaiveDate) -> NaiveDate {
+    day.with_day(1).unwrap_or(day)
+}
+
+/// Actions emitted by the month grid.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum CalendarMonthGridAction {
+    /// User tapped a day in the grid.
+    SelectDay(NaiveDate),
+    /// No action.
+    None,
+}
+
+/// Widget for displaying the days of a month as a 7-column grid.
+#[derive(Live, LiveHook, Widget)]
+pub struct CalendarMonthGrid {
+    #[redraw]
+    #[rust]
+    area: Area,
+
+    /// Template for day cells.
+    #[live]
+    day_template: Option<LivePtr>,
+
+    /// Created day cells, with the day each one represents.
+    /// Leading cells before the first of the month have no day.
+    #[rust]
+    cells: Vec<(ButtonRef, Option<NaiveDate>)>,
+
+    /// Layout for the widget.
+    #[layout]
+    layout: Layout,
+
+    /// Walk for the widget.
+    #[walk]
+    walk: Walk,
+}
+
+impl Widget for CalendarMonthGrid {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, _scope: &mut Scope) {
+        for (button, day) in &self.cells {
+            let Some(day) = day else {
+                continue;
+            };
+            if let Hit::FingerUp(fue) = event.hits(cx, button.area()) {
+                if fue.is_over && fue.is_primary_hit() && fue.was_tap() {
+                    cx.action(CalendarMonthGridAction::SelectDay(*day));
+                }
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        cx.begin_turtle(walk, self.layout);
+        for (button, _) in self.cells.iter_mut() {
+            let _ = button.draw(cx, scope);
+        }
+        cx.end_turtle_with_area(&mut self.area);
+        DrawStep::done()
+    }
+}
+
+impl CalendarMonthGrid {
+    /// Show the given month.
+    ///
+    /// Days in `event_days` are marked with a dot.
+    pub fn set_month(
+        &mut self,
+        cx: &mut Cx,
+        month: NaiveDate,
+        today: NaiveDate,
+        selected: Option<NaiveDate>,
+        event_days: &BTreeSet<NaiveDate>,
+    ) {
+        self.cells.clear();
+        let Some(template) = self.day_template else {
+            return;
+        };
+
+        let leading_blanks = month.weekday().num_days_from_monday();
+        for _ in 0..leading_blanks {
+            let cell = WidgetRef::new_from_ptr(cx, Some(template)).as_button();
+            self.cells.push((cell, None));
+        }
+
+        let days = month
+            .iter_days()
+            .take_while(|day| day.month() == month.month());
+        for day in days {
+            let cell = WidgetRef::new_from_ptr(cx, Some(template)).as_button();
+            let marker = if event_days.contains(&day) { " •" } else { "" };
+            cell.set_text(cx, &format!("{}{}", day.day(), marker));
+
+            let is_selected = if selected == Some(day) { 1.0 } else { 0.0 };
+            let is_today = if day == today { 1.0 } else { 0.0 };
+            cell.apply_over(
+                cx,
+                live! {
+                    draw_bg: { selected: (is_selected), today: (is_today) }
+                    draw_text: { selected: (is_selected) }
+                },
+            );
+            self.cells.push((cell, Some(day)));
+        }
+
+        self.area.redraw(cx);
+    }
+}
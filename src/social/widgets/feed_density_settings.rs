@@ -0,0 +1,115 @@
+//! Setting for how densely the newsfeed lays out posts.
+//!
+//! Choosing a density saves it in the account's social settings
+//! (see [`set_feed_density()`]), and the newsfeed lays out its posts
+//! for it right away, without restarting.
+//!
+//! [`set_feed_density()`]: crate::social::newsfeed::set_feed_density
+
+use makepad_widgets::*;
+use robrix_social_events::settings::FeedDensity;
+
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::social::newsfeed::{feed_density, FeedDensityAction};
+
+/// Densities offered in the density dropdown, in the order of its labels.
+const DENSITY_OPTIONS: [FeedDensity; 2] = [FeedDensity::Comfortable, FeedDensity::Compact];
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use link::social_theme::*;
+
+    use crate::shared::styles::*;
+
+    /// Newsfeed density setting.
+    pub SocialFeedDensitySettings = {{SocialFeedDensitySettings}} {
+        width: Fill,
+        height: Fit,
+        padding: 16,
+        flow: Right,
+        spacing: 8,
+        align: { y: 0.5 },
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_BG)
+        }
+
+        title_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "Feed density",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: (SOCIAL_TEXT_PRIMARY),
+            }
+        }
+
+        density_dropdown = <DropDown> {
+            width: Fit,
+            height: Fit,
+            labels: ["Comfortable", "Compact"],
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialFeedDensitySettings {
+    #[deref]
+    view: View,
+}
+
+impl Widget for SocialFeedDensitySettings {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialFeedDensitySettings {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if let Some(selected) = self.drop_down(ids!(density_dropdown)).selected(actions) {
+            if let Some(density) = DENSITY_OPTIONS.get(selected) {
+                submit_async_request(MatrixRequest::SetFeedDensity(*density));
+            }
+        }
+
+        for action in actions {
+            // The density may also change on another device
+            if let Some(FeedDensityAction::Changed(density)) = action.downcast_ref() {
+                self.show_density(cx, *density);
+            }
+        }
+    }
+}
+
+impl SocialFeedDensitySettings {
+    /// Show the current newsfeed density.
+    pub fn refresh(&mut self, cx: &mut Cx) {
+        self.show_density(cx, feed_density());
+    }
+
+    /// Select a density in the dropdown.
+    fn show_density(&mut self, cx: &mut Cx, density: FeedDensity) {
+        let index = DENSITY_OPTIONS
+            .iter()
+            .position(|option| *option == density)
+            .unwrap_or_default();
+        self.drop_down(ids!(density_dropdown))
+            .set_selected_item(cx, index);
+    }
+}
+
+impl SocialFeedDensitySettingsRef {
+    /// See [`SocialFeedDensitySettings::refresh()`].
+    pub fn refresh(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.refresh(cx);
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! Settings panel for the newsfeed filter.
+//!
+//! Lets the user choose which content type, engagement and age of posts
+//! the newsfeed shows, how strictly likely spam is handled, and which
+//! languages are hidden. Every change is emitted as a
+//! [`SocialFeedFilterSettingsAction::Changed`] with the complete filter,
+//! which the social settings screen saves with
+//! [`save_feed_filter()`](crate::social::save_feed_filter).
+
+use makepad_widgets::*;
+
+use crate::social::newsfeed::{
+    feed_filter, ContentFilter, FeedFilterSettings, Language, SpamStrictness,
+};
+
+/// Content filters offered in the content dropdown, in the order of its labels.
+const CONTENT_FILTER_OPTIONS: [ContentFilter; 4] = [
+    ContentFilter::All,
+    ContentFilter::TextOnly,
+    ContentFilter::MediaOnly,
+    ContentFilter::LinksOnly,
+];
+
+/// Spam strictness levels offered in the spam dropdown, in the order of its labels.
+const SPAM_STRICTNESS_OPTIONS: [SpamStrictness; 4] = [
+    SpamStrictness::Off,
+    SpamStrictness::Lenient,
+    SpamStrictness::Balanced,
+    SpamStrictness::Strict,
+];
+
+/// Minimum engagements offered in the engagement dropdown, in the order of its labels.
+const MIN_ENGAGEMENT_OPTIONS: [u32; 5] = [0, 1, 5, 10, 25];
+
+/// Maximum post ages offered in the age dropdown, in seconds, in the order of its labels.
+const MAX_AGE_OPTIONS: [u64; 4] = [0, 24 * 60 * 60, 7 * 24 * 60 * 60, 30 * 24 * 60 * 60];
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+
+    /// A row with a label and a dropdown.
+    FilterRow = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 8,
+        align: { y: 0.5 },
+
+        row_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: #000,
+            }
+        }
+    }
+
+    /// A language that can be hidden from the newsfeed.
+    LanguageItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 8, bottom: 8 },
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        language_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: #000,
+            }
+        }
+
+        hide_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "Hide",
+        }
+    }
+
+    /// Newsfeed filter settings.
+    pub SocialFeedFilterSettings = {{SocialFeedFilterSettings}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: #f5f5f5
+        }
+
+        settings = <View> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            flow: Down,
+            spacing: 12,
+            show_bg: true,
+            draw_bg: {
+                color: #fff
+            }
+
+            title_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Newsfeed filters",
+                draw_text: {
+                    text_style: { font_size: 18.0 },
+                    color: #000,
+                }
+            }
+
+            content_row = <FilterRow> {
+                row_label = { text: "Show" }
+
+                content_dropdown = <DropDown> {
+                    width: Fit,
+                    height: Fit,
+                    labels: ["All posts", "Text posts", "Photos and videos", "Links"],
+                }
+            }
+
+            engagement_row = <FilterRow> {
+                row_label = { text: "Minimum reactions and comments" }
+
+                engagement_dropdown = <DropDown> {
+                    width: Fit,
+                    height: Fit,
+                    labels: ["None", "1", "5", "10", "25"],
+                }
+            }
+
+            max_age_row = <FilterRow> {
+                row_label = { text: "Posts from" }
+
+                max_age_dropdown = <DropDown> {
+                    width: Fit,
+                    height: Fit,
+                    labels: ["Any time", "Last day", "Last week", "Last month"],
+                }
+            }
+
+            spam_row = <FilterRow> {
+                row_label = { text: "Spam filtering" }
+
+                spam_dropdown = <DropDown> {
+                    width: Fit,
+                    height: Fit,
+                    labels: ["Off", "Lenient", "Balanced", "Strict"],
+                    selected_item: 2,
+                }
+            }
+
+            reset_button = <Button> {
+                width: Fill,
+                height: Fit,
+                padding: 10,
+                text: "Reset filters",
+            }
+
+            languages_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Hidden languages",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: #000,
+                }
+            }
+        }
+
+        language_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            language_item = <LanguageItem> {}
+        }
+    }
+}
+
+/// Actions that can be triggered from the newsfeed filter settings.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialFeedFilterSettingsAction {
+    /// User changed the filter; it should be saved and the newsfeed refiltered.
+    Changed(FeedFilterSettings),
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialFeedFilterSettings {
+    #[deref]
+    view: View,
+
+    /// The filter being edited.
+    #[rust]
+    settings: FeedFilterSettings,
+}
+
+impl Widget for SocialFeedFilterSettings {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, Language::ALL.len());
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let Some(language) = Language::ALL.get(item_id) else {
+                    continue;
+                };
+                let item = list.item(cx, item_id, live_id!(language_item));
+                self.populate_language_item(cx, &item, *language);
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for SocialFeedFilterSettings {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        let mut changed = false;
+
+        if let Some(selected) = self.drop_down(ids!(content_dropdown)).selected(actions) {
+            if let Some(filter) = CONTENT_FILTER_OPTIONS.get(selected) {
+                self.settings.content_filter = *filter;
+                changed = true;
+            }
+        }
+
+        if let Some(selected) = self.drop_down(ids!(engagement_dropdown)).selected(actions) {
+            if let Some(min) = MIN_ENGAGEMENT_OPTIONS.get(selected) {
+                self.settings.min_engagement = *min;
+                changed = true;
+            }
+        }
+
+        if let Some(selected) = self.drop_down(ids!(max_age_dropdown)).selected(actions) {
+            if let Some(seconds) = MAX_AGE_OPTIONS.get(selected) {
+                self.settings.max_age_seconds = *seconds;
+                changed = true;
+            }
+        }
+
+        if let Some(selected) = self.drop_down(ids!(spam_dropdown)).selected(actions) {
+            if let Some(strictness) = SPAM_STRICTNESS_OPTIONS.get(selected) {
+                self.settings.spam_strictness = *strictness;
+                changed = true;
+            }
+        }
+
+        if self.button(ids!(reset_button)).clicked(actions) {
+            // Mutes are managed separately, so keep them
+            let muted_authors = std::mem::take(&mut self.settings.muted_authors);
+            self.settings.reset();
+            self.settings.muted_authors = muted_authors;
+            self.show_settings(cx);
+            changed = true;
+        }
+
+        let language_list = self.portal_list(ids!(language_list));
+        for (index, item) in language_list.items_with_actions(actions) {
+            if !item.button(ids!(hide_button)).clicked(actions) {
+                continue;
+            }
+            let Some(language) = Language::ALL.get(index).copied() else {
+                continue;
+            };
+            if self.settings.hidden_languages.contains(&language) {
+                self.settings.unhide_language(language);
+            } else {
+                self.settings.hide_language(language);
+            }
+            self.redraw(cx);
+            changed = true;
+        }
+
+        if changed {
+            cx.action(SocialFeedFilterSettingsAction::Changed(
+                self.settings.clone(),
+            ));
+        }
+    }
+}
+
+impl SocialFeedFilterSettings {
+    /// Show the given filter, e.g., as loaded with
+    /// [`load_feed_filter()`](crate::social::load_feed_filter).
+    pub fn set_settings(&mut self, cx: &mut Cx, settings: FeedFilterSettings) {
+        self.settings = settings;
+        self.show_settings(cx);
+    }
+
+    /// Get the filter as currently edited.
+    pub fn settings(&self) -> &FeedFilterSettings {
+        &self.settings
+    }
+
+    /// Show the account's current filter, see [`feed_filter()`].
+    pub fn refresh(&mut self, cx: &mut Cx) {
+        self.set_settings(cx, feed_filter());
+    }
+
+    /// Select the dropdown items matching the current filter.
+    ///
+    /// Values that aren't offered in a dropdown fall back to its first item.
+    fn show_settings(&mut self, cx: &mut Cx) {
+        let content_index = CONTENT_FILTER_OPTIONS
+            .iter()
+            .position(|filter| *filter == self.settings.content_filter)
+            .unwrap_or(0);
+        self.drop_down(ids!(content_dropdown))
+            .set_selected_item(cx, content_index);
+
+        let engagement_index = MIN_ENGAGEMENT_OPTIONS
+            .iter()
+            .position(|min| *min == self.settings.min_engagement)
+            .unwrap_or(0);
+        self.drop_down(ids!(engagement_dropdown))
+            .set_selected_item(cx, engagement_index);
+
+        let age_index = MAX_AGE_OPTIONS
+            .iter()
+            .position(|seconds| *seconds == self.settings.max_age_seconds)
+            .unwrap_or(0);
+        self.drop_down(ids!(max_age_dropdown))
+            .set_selected_item(cx, age_index);
+
+        let spam_index = SPAM_STRICTNESS_OPTIONS
+            .iter()
+            .position(|strictness| *strictness == self.settings.spam_strictness)
+            .unwrap_or(0);
+        self.drop_down(ids!(spam_dropdown))
+            .set_selected_item(cx, spam_index);
+
+        self.redraw(cx);
+    }
+
+    /// Fill in a language row.
+    fn populate_language_item(&self, cx: &mut Cx, item: &WidgetRef, language: Language) {
+        item.label(ids!(language_label))
+            .set_text(cx, language.name());
+        let hidden = self.settings.hidden_languages.contains(&language);
+        item.button(ids!(hide_button))
+            .set_text(cx, if hidden { "Hidden" } else { "Hide" });
+    }
+}
+
+impl SocialFeedFilterSettingsRef {
+    /// See [`SocialFeedFilterSettings::set_settings()`].
+    pub fn set_settings(&self, cx: &mut Cx, settings: FeedFilterSettings) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_settings(cx, settings);
+        }
+    }
+
+    /// See [`SocialFeedFilterSettings::settings()`].
+    pub fn settings(&self) -> Option<FeedFilterSettings> {
+        Some(self.borrow()?.settings().clone())
+    }
+
+    /// See [`SocialFeedFilterSettings::refresh()`].
+    pub fn refresh(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.refresh(cx);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Owner card shown above the posts of a single user's feed.
+//!
+//! When the feed view shows one user's feed instead of the aggregated
+//! newsfeed, this card introduces its owner with their avatar, name and the
+//! start of their bio, and lets the user follow them or send them a friend
+//! request right there, so that the feed reads like their profile timeline.
+//! Its buttons emit the same [`SocialProfileAction`]s as the profile page.
+
+use makepad_widgets::*;
+
+use crate::shared::avatar::AvatarWidgetExt;
+use crate::social::widgets::profile_page::{LoadedProfile, SocialProfileAction};
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::utils;
+
+/// The maximum number of characters of the owner's bio shown in the card.
+const BIO_EXCERPT_CHARS: usize = 140;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use crate::shared::avatar::Avatar;
+
+    /// Card introducing the owner of the shown feed.
+    pub SocialFeedOwnerCard = {{SocialFeedOwnerCard}} {
+        width: Fill,
+        height: Fit,
+        padding: 16,
+        flow: Down,
+        spacing: 12,
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        owner_row = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 12,
+            align: { y: 0.5 },
+            cursor: Hand,
+
+            avatar = <Avatar> {
+                width: 56,
+                height: 56,
+            }
+
+            owner_info = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                spacing: 2,
+
+                name_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: <THEME_FONT_BOLD> { font_size: 16.0 },
+                        color: #000,
+                    }
+                }
+
+                username_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: #657786,
+                    }
+                }
+            }
+        }
+
+        bio_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 13.0 },
+                color: #333,
+                wrap: Word,
+            }
+        }
+
+        action_row = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 8,
+
+            follow_button = <Button> {
+                width: Fit,
+                height: 32,
+                padding: { left: 16, right: 16 },
+                text: "Follow",
+                draw_bg: {
+                    color: #1d9bf0,
+                    radius: 16.0,
+                }
+                draw_text: {
+                    color: #fff,
+                    text_style: { font_size: 12.0 },
+                }
+            }
+
+            friend_request_button = <Button> {
+                width: Fit,
+                height: 32,
+                padding: { left: 16, right: 16 },
+                text: "Add Friend",
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialFeedOwnerCard {
+    #[deref]
+    view: View,
+
+    /// The owner of the shown feed.
+    #[rust]
+    profile: Option<LoadedProfile>,
+
+    /// Whether the current user follows the owner.
+    #[rust]
+    is_following: bool,
+}
+
+impl Widget for SocialFeedOwnerCard {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialFeedOwnerCard {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        let Some(user_id) = self.profile.as_ref().map(|profile| profile.user_id.clone()) else {
+            return;
+        };
+
+        for action in actions {
+            if let Some(SocialProfileAction::FollowChanged {
+                user_id: followed,
+                following,
+            }) = action.downcast_ref()
+            {
+                if *followed == user_id {
+                    self.set_following(cx, *following);
+                }
+            }
+        }
+
+        if self.button(ids!(follow_button)).clicked(actions) {
+            if self.is_following {
+                submit_async_request(MatrixRequest::Unfollow(user_id));
+            } else {
+                submit_async_request(MatrixRequest::Follow(user_id));
+            }
+            // Show the change right away; it's reverted if the request fails
+            self.set_following(cx, !self.is_following);
+        } else if self.button(ids!(friend_request_button)).clicked(actions) {
+            cx.action(SocialProfileAction::SendFriendRequest(user_id));
+            let friend_request_button = self.button(ids!(friend_request_button));
+            friend_request_button.set_text(cx, "Request Sent");
+            friend_request_button.set_enabled(cx, false);
+        } else if self.view(ids!(owner_row)).finger_up(actions).is_some() {
+            cx.action(SocialProfileAction::OpenProfile(user_id));
+        }
+    }
+}
+
+impl SocialFeedOwnerCard {
+    /// Show the owner of the feed.
+    ///
+    /// The follow and friend buttons are hidden on the user's own feed,
+    /// and the friend button is hidden if the owner already is a friend.
+    pub fn set_owner(
+        &mut self,
+        cx: &mut Cx,
+        profile: LoadedProfile,
+        is_own_feed: bool,
+        is_friend: bool,
+    ) {
+        let name = profile
+            .display_name
+            .clone()
+            .unwrap_or_else(|| profile.user_id.localpart().to_string());
+        self.label(ids!(name_label)).set_text(cx, &name);
+        self.label(ids!(username_label))
+            .set_text(cx, profile.user_id.as_str());
+
+        let bio = profile
+            .social_profile
+            .as_ref()
+            .and_then(|social| social.bio.as_deref())
+            .unwrap_or_default();
+        let bio_label = self.label(ids!(bio_label));
+        bio_label.set_text(cx, &bio_excerpt(bio));
+        bio_label.set_visible(cx, !bio.is_empty());
+
+        let avatar = self.avatar(ids!(avatar));
+        avatar.set_text(cx, &name);
+        if let Some(avatar_data) = profile.avatar_data.as_deref() {
+            let _ = avatar.show_image(cx, None, |cx, img| {
+                utils::load_png_or_jpg(&img, cx, avatar_data)
+            });
+        }
+
+        self.view(ids!(action_row)).set_visible(cx, !is_own_feed);
+        let friend_request_button = self.button(ids!(friend_request_button));
+        friend_request_button.set_visible(cx, !is_friend);
+        friend_request_button.set_text(cx, "Add Friend");
+        friend_request_button.set_enabled(cx, true);
+
+        self.profile = Some(profile);
+        self.redraw(cx);
+    }
+
+    /// Set whether the current user follows the owner,
+    /// e.g., from [`FollowService::is_following()`](crate::social::FollowService::is_following).
+    pub fn set_following(&mut self, cx: &mut Cx, following: bool) {
+        self.is_following = following;
+        self.button(ids!(follow_button))
+            .set_text(cx, if following { "Unfollow" } else { "Follow" });
+        self.redraw(cx);
+    }
+
+    /// Get the owner of the shown feed, if set.
+    pub fn owner(&self) -> Option<&LoadedProfile> {
+        self.profile.as_ref()
+    }
+}
+
+impl SocialFeedOwnerCardRef {
+    /// See [`SocialFeedOwnerCard::set_owner()`].
+    pub fn set_owner(
+        &self,
+        cx: &mut Cx,
+        profile: LoadedProfile,
+        is_own_feed: bool,
+        is_friend: bool,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_owner(cx, profile, is_own_feed, is_friend);
+        }
+    }
+
+    /// See [`SocialFeedOwnerCard::set_following()`].
+    pub fn set_following(&self, cx: &mut Cx, following: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_following(cx, following);
+        }
+    }
+}
+
+/// Shorten the owner's bio for the card.
+fn bio_excerpt(bio: &str) -> String {
+    if bio.chars().count() <= BIO_EXCERPT_CHARS {
+        return bio.to_string();
+    }
+    let mut excerpt: String = bio.chars().take(BIO_EXCERPT_CHARS).collect();
+    excerpt.push('…');
+    excerpt
+}
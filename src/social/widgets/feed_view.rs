@@ -1,12 +1,93 @@
 //! Feed view widget displaying a scrollable list of posts.
 //!
 //! This widget renders an aggregated feed of posts from multiple
-//! feed rooms, supporting infinite scroll and refresh.
+//! feed rooms, supporting infinite scroll and refresh. More posts are
+//! requested once the user scrolls within a few rows of the end, at most
+//! once every [`LOAD_MORE_DEBOUNCE`], until loading more brings no older posts.
+//! Only the rows in
+//! view are bound to list items, and a row is only filled in again after
+//! the posts change, so the feed can hold thousands of posts. When it shows a
+//! single user's feed instead, a card introducing that user is shown
+//! above the posts (see [`SocialFeedView::show_owner()`]).
+//! Posts arriving out of order from different rooms are merged in without
+//! moving the posts already shown (see [`merge_feed_items()`]), and the row at
+//! the top of the view stays in place when rows are inserted above it.
+//! Posts sent since the user's last visit are counted in a floating
+//! "New posts" pill, and each feed room's read marker is moved forward
+//! as its posts scroll into view (see [`SocialFeedView::set_read_markers()`]).
+//! Likes, reactions and posts by the user are shown at once, marked as
+//! pending until the server echoes them back, and rolled back if they fail
+//! to send (see [`OptimisticUpdates`]). Posts made with the composer, while
+//! it's shown, are queued in the [outbox](crate::social::outbox) so that
+//! they're retried until they're sent, as are replies to posts, which
+//! the composer quotes when the user replies to a post.
+//! Votes in poll posts are shown at once too, and the votes of the polls
+//! shown are reloaded whenever someone votes in them or their author ends them.
+//! Posts can be sent to a regular room from their options, picking the room
+//! in a sheet shown over the feed (see [`SocialShareToChatSheet`]).
+//! Moderators of a feed room can also remove, pin and ban the authors of its
+//! posts from their options, which are offered once the user's power level in
+//! the room was loaded (see [`FeedModerationService`]).
+//! With the `feed_diagnostics` developer setting on, how the feed was
+//! aggregated is shown over the posts (see [`SocialFeedView::set_diagnostics()`]).
+//! If some posts need a newer version of Robrix to be shown, a dismissable
+//! banner suggests updating (see [`SocialFeedView::set_capabilities()`]).
+//! Once the feed has keyboard focus, J/K or the arrow keys move between
+//! posts, highlighting the focused one, and Enter opens it, L likes it and
+//! R replies to it.
+//! Posts are laid out comfortably or compactly, following the account's
+//! newsfeed density (see [`feed_density()`]), which can be switched live.
+//! Friends' birthdays in the coming week are shown in a card above the posts,
+//! unless the user hid it (see [`SocialFeedView::set_birthdays()`]).
+//! The posts can be narrowed down to those with a hashtag, e.g., one tapped
+//! in a post (see [`SocialFeedView::show_hashtag()`]).
+//!
+//! [`SocialShareToChatSheet`]: crate::social::widgets::share_to_chat_sheet::SocialShareToChatSheet
+//! [`FeedModerationService`]: crate::social::feed_moderation::FeedModerationService
 
 use makepad_widgets::*;
-use matrix_sdk::ruma::OwnedEventId;
+use matrix_sdk::ruma::{
+    MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedTransactionId, TransactionId,
+};
+use rangemap::RangeSet;
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
 
+use crate::shared::popup_list::{enqueue_popup_notification, PopupItem, PopupKind};
+use crate::sliding_sync::{current_user_id, submit_async_request, MatrixRequest};
+use crate::social::co_author::invite_co_author;
+use crate::social::discovery::extract_hashtags;
+use crate::social::discovery::SuggestedUser;
+use crate::social::event_bus::{subscribe_social_events, SocialEvent, SocialSubscription, SocialTopic};
+use crate::social::feed_moderation::{FeedModerationAction, ModerationPermissions};
+use crate::social::newsfeed::{
+    are_birthdays_hidden, feed_density, find_anchor_row, group_feed_items, merge_feed_items,
+    BirthdaysAction, CapabilityTracker, FeedDensityAction, FeedDiagnostics, FeedEntry,
+    FeedGroupingSettings, FeedItem, FeedReadMarker, FeedReadMarkers, FeedRowKey, Language,
+    UpcomingBirthday,
+};
+use crate::social::optimistic::{
+    local_event_id, OptimisticAction, OptimisticUpdates, PendingChange, LIKE_REACTION,
+};
+use crate::social::outbox::{OutboxAction, OutboxMedia};
+use crate::social::poll::PollResults;
+use crate::social::post::Post;
+use crate::social::reactions::ReactionSummary;
+use crate::social::widgets::birthdays_card::SocialBirthdaysCard;
+use crate::social::widgets::feed_owner_card::SocialFeedOwnerCardWidgetExt;
+use crate::social::widgets::kiosk_view::KioskSlide;
+use crate::social::widgets::people_suggestions_card::SocialPeopleSuggestionsCard;
 use crate::social::widgets::post_card::{PostCardData, SocialPostCard, SocialPostCardAction};
+use crate::social::widgets::post_composer::{
+    ReplyTarget, SocialPostComposerAction, SocialPostComposerWidgetExt,
+};
+use crate::social::widgets::post_group_card::{SocialPostGroupAction, SocialPostGroupCard};
+use crate::social::widgets::profile_page::LoadedProfile;
+use crate::social::widgets::share_to_chat_sheet::{
+    SocialShareToChatSheetAction, SocialShareToChatSheetWidgetExt,
+};
 
 live_design! {
     use link::theme::*;
@@ -14,11 +95,14 @@ live_design! {
     use link::widgets::*;
 
     use crate::shared::styles::*;
+    use link::social_theme::*;
+    use crate::social::widgets::birthdays_card::SocialBirthdaysCard;
+    use crate::social::widgets::feed_owner_card::SocialFeedOwnerCard;
     use crate::social::widgets::post_card::SocialPostCard;
+    use crate::social::widgets::post_group_card::SocialPostGroupCard;
+    use crate::social::widgets::people_suggestions_card::SocialPeopleSuggestionsCard;
     use crate::social::widgets::post_composer::SocialPostComposer;
-
-    FEED_BG_COLOR = #f0f2f5
-    SPINNER_COLOR = #1d9bf0
+    use crate::social::widgets::share_to_chat_sheet::SocialShareToChatSheet;
 
     /// Feed view widget displaying a scrollable list of posts.
     pub SocialFeedView = {{SocialFeedView}} {
@@ -27,7 +111,17 @@ live_design! {
         flow: Down,
         show_bg: true,
         draw_bg: {
-            color: (FEED_BG_COLOR)
+            color: (SOCIAL_SURFACE)
+        }
+
+        // Owner of the feed, when showing a single user's feed
+        owner_section = <View> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            margin: { bottom: 8 },
+
+            owner_card = <SocialFeedOwnerCard> {}
         }
 
         // Composer at top (optional, can be hidden)
@@ -39,81 +133,258 @@ live_design! {
             composer = <SocialPostComposer> {}
         }
 
-        // Feed content
-        feed_scroll = <PortalList> {
+        // Feed-level actions
+        feed_toolbar = <View> {
             width: Fill,
-            height: Fill,
-            flow: Down,
+            height: Fit,
+            padding: { left: 16, right: 16, top: 4, bottom: 4 },
+            flow: Right,
+            align: { x: 1.0, y: 0.5 },
+
+            present_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Present",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (SOCIAL_ACCENT),
+                }
+            }
+        }
 
-            // Template for post items
-            post_item = <SocialPostCard> {
-                margin: { bottom: 8 }
+        // Suggests updating when posts need a newer client, see `set_capabilities()`
+        update_banner = <RoundedView> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            margin: { left: 16, right: 16, bottom: 8 },
+            padding: { left: 12, right: 4, top: 4, bottom: 4 },
+            flow: Right,
+            align: { y: 0.5 },
+            show_bg: true,
+            draw_bg: {
+                color: (SOCIAL_WARNING_BG),
+                border_radius: 6.0,
             }
 
-            // Loading indicator at bottom
-            loading_item = <View> {
+            update_banner_label = <Label> {
                 width: Fill,
-                height: 60,
-                align: { x: 0.5, y: 0.5 },
+                height: Fit,
+                text: "Update Robrix to see new post types",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_WARNING_TEXT),
+                    wrap: Word,
+                }
+            }
 
-                loading_spinner = <View> {
-                    width: 32,
-                    height: 32,
-                    show_bg: true,
-                    draw_bg: {
-                        color: (SPINNER_COLOR),
-                        fn pixel(self) -> vec4 {
-                            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
-                            let center = self.rect_size / 2.;
-                            let radius = min(center.x, center.y) - 2.;
-                            sdf.circle(center.x, center.y, radius);
-                            sdf.stroke(self.color, 3.);
-                            return sdf.result;
-                        }
-                    }
+            dismiss_update_banner_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "✕",
+                draw_bg: {
+                    color: #0000,
                 }
+                draw_text: {
+                    color: (SOCIAL_WARNING_TEXT),
+                }
+            }
+        }
+
+        // The hashtag the posts are narrowed down to, see `show_hashtag()`
+        hashtag_banner = <RoundedView> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            margin: { left: 16, right: 16, bottom: 8 },
+            padding: { left: 12, right: 4, top: 4, bottom: 4 },
+            flow: Right,
+            align: { y: 0.5 },
+            show_bg: true,
+            draw_bg: {
+                color: (SOCIAL_ACCENT_BG),
+                border_radius: 6.0,
             }
 
-            // Empty state
-            empty_state = <View> {
+            hashtag_label = <Label> {
+                width: Fill,
+                height: Fit,
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_ACCENT),
+                }
+            }
+
+            clear_hashtag_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "✕",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (SOCIAL_ACCENT),
+                }
+            }
+        }
+
+        // Feed content, with the "New posts" pill floating above it
+        feed_content = <View> {
+            width: Fill,
+            height: Fill,
+            flow: Overlay,
+
+            feed_scroll = <PortalList> {
+                keep_invisible: false,
                 width: Fill,
                 height: Fill,
-                align: { x: 0.5, y: 0.5 },
-                padding: 32,
                 flow: Down,
-                spacing: 16,
 
-                empty_icon = <Label> {
-                    width: Fit,
-                    height: Fit,
-                    text: "📭",
-                    draw_text: {
-                        text_style: { font_size: 48.0 },
-                        color: #999,
+                // Template for post items
+                post_item = <SocialPostCard> {
+                    margin: { bottom: 8 }
+                }
+
+                // Template for collapsed groups of posts from one author
+                group_item = <SocialPostGroupCard> {
+                    margin: { bottom: 8 }
+                }
+
+                // Template for the "People you may know" card
+                suggestions_item = <SocialPeopleSuggestionsCard> {
+                    margin: { bottom: 8 }
+                }
+
+                // Template for the "Birthdays this week" card
+                birthdays_item = <SocialBirthdaysCard> {
+                    margin: { bottom: 8 }
+                }
+
+                // Loading indicator at bottom
+                loading_item = <View> {
+                    width: Fill,
+                    height: 60,
+                    align: { x: 0.5, y: 0.5 },
+
+                    loading_spinner = <View> {
+                        width: 32,
+                        height: 32,
+                        show_bg: true,
+                        draw_bg: {
+                            color: (SOCIAL_ACCENT),
+                            fn pixel(self) -> vec4 {
+                                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                                let center = self.rect_size / 2.;
+                                let radius = min(center.x, center.y) - 2.;
+                                sdf.circle(center.x, center.y, radius);
+                                sdf.stroke(self.color, 3.);
+                                return sdf.result;
+                            }
+                        }
                     }
                 }
 
-                empty_title = <Label> {
+                // Empty state
+                empty_state = <View> {
+                    width: Fill,
+                    height: Fill,
+                    align: { x: 0.5, y: 0.5 },
+                    padding: 32,
+                    flow: Down,
+                    spacing: 16,
+
+                    empty_icon = <Label> {
+                        width: Fit,
+                        height: Fit,
+                        text: "📭",
+                        draw_text: {
+                            text_style: { font_size: 48.0 },
+                            color: (SOCIAL_TEXT_MUTED),
+                        }
+                    }
+
+                    empty_title = <Label> {
+                        width: Fit,
+                        height: Fit,
+                        text: "No posts yet",
+                        draw_text: {
+                            text_style: { font_size: 18.0 },
+                            color: (SOCIAL_TEXT),
+                        }
+                    }
+
+                    empty_subtitle = <Label> {
+                        width: Fit,
+                        height: Fit,
+                        text: "Follow some people to see their posts here",
+                        draw_text: {
+                            text_style: { font_size: 14.0 },
+                            color: (SOCIAL_TEXT_SECONDARY),
+                            wrap: Word,
+                        }
+                    }
+                }
+            }
+
+            new_posts_pill_container = <View> {
+                width: Fill,
+                height: Fit,
+                padding: { top: 12 },
+                align: { x: 0.5 },
+
+                new_posts_pill = <Button> {
                     width: Fit,
-                    height: Fit,
-                    text: "No posts yet",
+                    height: 32,
+                    padding: { left: 16, right: 16 },
+                    visible: false,
+                    text: "New posts",
+                    draw_bg: {
+                        color: (SOCIAL_ACCENT),
+                        radius: 16.0,
+                    }
                     draw_text: {
-                        text_style: { font_size: 18.0 },
-                        color: #333,
+                        color: (SOCIAL_ON_ACCENT),
+                        text_style: <THEME_FONT_BOLD> { font_size: 12.0 },
                     }
                 }
+            }
 
-                empty_subtitle = <Label> {
+            // Developer diagnostics of the last refresh, see `set_diagnostics()`
+            diagnostics_container = <View> {
+                width: Fill,
+                height: Fill,
+                padding: 8,
+                align: { x: 1.0, y: 1.0 },
+
+                diagnostics_overlay = <RoundedView> {
                     width: Fit,
                     height: Fit,
-                    text: "Follow some people to see their posts here",
-                    draw_text: {
-                        text_style: { font_size: 14.0 },
-                        color: #666,
-                        wrap: Word,
+                    padding: 8,
+                    visible: false,
+                    show_bg: true,
+                    draw_bg: {
+                        color: #000000b0,
+                        border_radius: 4.0,
+                    }
+
+                    diagnostics_label = <Label> {
+                        width: Fit,
+                        height: Fit,
+                        text: "",
+                        draw_text: {
+                            text_style: <THEME_FONT_REGULAR> { font_size: 9.0 },
+                            color: #fff,
+                        }
                     }
                 }
             }
+
+            // Room picker for sending a post to a chat, see `SocialPostCardAction::SendToChat`
+            share_to_chat_sheet = <SocialShareToChatSheet> {
+                visible: false,
+            }
         }
 
         // Pull-to-refresh indicator (for mobile)
@@ -129,7 +400,7 @@ live_design! {
                 text: "⟳",
                 draw_text: {
                     text_style: { font_size: 24.0 },
-                    color: (SPINNER_COLOR),
+                    color: (SOCIAL_ACCENT),
                 }
             }
         }
@@ -159,10 +430,22 @@ pub enum FeedState {
 pub enum SocialFeedViewAction {
     /// User wants to refresh the feed.
     Refresh,
-    /// User scrolled to the bottom, load more posts.
+    /// User scrolled near the bottom, load more posts.
+    ///
+    /// The older posts should be added with [`SocialFeedView::append_posts()`],
+    /// or the state set back to [`FeedState::Loaded`] if they can't be loaded.
     LoadMore,
     /// User interacted with a post (delegated from PostCard).
+    ///
+    /// Likes and reactions are sent by the feed view itself instead.
     PostAction(SocialPostCardAction),
+    /// The feed grouping settings were changed from within the feed,
+    /// e.g., an author was set to always expand.
+    GroupingChanged(FeedGroupingSettings),
+    /// User wants to present the feed full-screen, e.g., on a lobby display.
+    /// The slides should be passed to
+    /// [`SocialKioskView::start()`](crate::social::widgets::kiosk_view::SocialKioskView::start).
+    Present(Vec<KioskSlide>),
     /// No action.
     None,
 }
@@ -183,17 +466,117 @@ pub struct SocialFeedView {
     /// Whether the composer should be shown.
     #[rust]
     show_composer: bool,
+
+    /// Settings for grouping bursts of posts from one author.
+    #[rust]
+    grouping: FeedGroupingSettings,
+
+    /// Groups the user has expanded, keyed by the event ID of their first post.
+    #[rust]
+    expanded_groups: HashSet<OwnedEventId>,
+
+    /// Friends of friends to suggest in a "People you may know" card.
+    #[rust]
+    suggestions: Vec<SuggestedUser>,
+
+    /// Friends' birthdays in the coming week, shown in a "Birthdays this week" card.
+    #[rust]
+    birthdays: Vec<UpcomingBirthday>,
+
+    /// Rows to display, computed from `posts`, the grouping settings, the suggestions
+    /// and the birthdays.
+    #[rust]
+    rows: Vec<FeedRow>,
+
+    /// The stable key of each row in `rows`, to find rows again after they're rebuilt.
+    #[rust]
+    row_keys: Vec<FeedRowKey>,
+
+    /// The newest post the user has seen in each feed room.
+    #[rust]
+    read_markers: FeedReadMarkers,
+
+    /// Rows whose list items were filled in since the rows last changed,
+    /// which don't need to be filled in again when they're redrawn.
+    #[rust]
+    rows_drawn_since_last_update: RangeSet<usize>,
+
+    /// When more posts were last requested, to debounce [`SocialFeedViewAction::LoadMore`].
+    #[rust]
+    last_load_more: Option<Instant>,
+
+    /// Whether loading more posts last brought no older posts,
+    /// so that no more are requested until the posts are set again.
+    #[rust]
+    reached_end: bool,
+
+    /// Whether developer diagnostics are shown, see [`SocialFeedView::set_diagnostics()`].
+    #[rust]
+    show_diagnostics: bool,
+
+    /// How many features were degraded because posts need a newer client,
+    /// see [`SocialFeedView::set_capabilities()`].
+    #[rust]
+    degraded_feature_count: usize,
+
+    /// How many features were degraded when the user dismissed the update banner.
+    #[rust]
+    update_banner_dismissed_at: usize,
+
+    /// The user's changes to the posts that the server hasn't confirmed yet.
+    #[rust]
+    optimistic: OptimisticUpdates,
+
+    /// Votes in and ends of polls, whose votes are reloaded if they're shown.
+    #[rust(subscribe_social_events(&[SocialTopic::FeedUpdates]))]
+    feed_updates: SocialSubscription,
+
+    /// The post focused with the keyboard, by the event ID of its row's first post.
+    #[rust]
+    focused_post: Option<OwnedEventId>,
+
+    /// The hashtag the posts are narrowed down to, lowercased and without its `#`.
+    #[rust]
+    hashtag: Option<String>,
 }
 
+/// A single row in the feed list.
+#[derive(Clone, Debug)]
+enum FeedRow {
+    /// A single post, by index into `posts`.
+    Post(usize),
+    /// A collapsed group of posts, by indices into `posts`.
+    Group(Vec<usize>),
+    /// The "People you may know" card.
+    Suggestions,
+    /// The "Birthdays this week" card.
+    Birthdays,
+}
+
+/// How many rows come before the "People you may know" card.
+const SUGGESTIONS_ROW_INDEX: usize = 3;
+
+/// More posts are requested once the last visible row is this close to the end of the feed.
+pub const LOAD_MORE_THRESHOLD_ROWS: usize = 5;
+
+/// The minimum time between two requests for more posts.
+pub const LOAD_MORE_DEBOUNCE: Duration = Duration::from_secs(1);
+
 impl Widget for SocialFeedView {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
-        self.view.handle_event(cx, event, scope);
-        self.widget_match_event(cx, event, scope);
+        if let Event::Signal = event {
+            self.reload_updated_polls();
+        }
 
-        // Handle scroll events for infinite scroll
-        if let Event::Scroll(scroll) = event {
-            self.handle_scroll(cx, scroll);
+        // Take keyboard focus when tapped, before the text inputs within the feed may take it
+        let area = self.view.area();
+        match event.hits_with_capture_overload(cx, area, true) {
+            Hit::FingerDown(_) => cx.set_key_focus(area),
+            Hit::KeyDown(key_event) => self.handle_key_down(cx, &key_event),
+            _ => {}
         }
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
@@ -208,14 +591,14 @@ impl Widget for SocialFeedView {
             };
 
             // Calculate total item count based on state
-            let post_count = self.posts.len();
+            let row_count = self.rows.len();
             let total_count = match self.state {
-                FeedState::Empty => 1,                    // Just empty state
-                FeedState::Loading => 1,                  // Just loading indicator
-                FeedState::Loaded => post_count,          // Posts only
-                FeedState::LoadingMore => post_count + 1, // Posts + loading at bottom
-                FeedState::Refreshing => post_count,      // Posts (refresh indicator separate)
-                FeedState::Error => 1,                    // Error state
+                FeedState::Empty => 1,                   // Just empty state
+                FeedState::Loading => 1,                 // Just loading indicator
+                FeedState::Loaded => row_count,          // Posts only
+                FeedState::LoadingMore => row_count + 1, // Posts + loading at bottom
+                FeedState::Refreshing => row_count,      // Posts (refresh indicator separate)
+                FeedState::Error => 1,                   // Error state
             };
 
             list.set_item_range(cx, 0, total_count);
@@ -227,30 +610,15 @@ impl Widget for SocialFeedView {
                     // Loading state
                     FeedState::Loading => list.item(cx, item_id, live_id!(loading_item)),
                     // Normal loaded state with posts
-                    FeedState::Loaded | FeedState::Refreshing => {
-                        if let Some(post_data) = self.posts.get(item_id) {
-                            let item = list.item(cx, item_id, live_id!(post_item));
-                            if let Some(mut inner) = item.borrow_mut::<SocialPostCard>() {
-                                inner.set_post(cx, post_data);
-                            }
-                            item
-                        } else {
-                            // Fallback to empty view for out-of-bounds
-                            list.item(cx, item_id, live_id!(empty_state))
-                        }
-                    }
+                    FeedState::Loaded | FeedState::Refreshing => self
+                        .row_item(cx, &mut list, item_id)
+                        // Fallback to empty view for out-of-bounds
+                        .unwrap_or_else(|| list.item(cx, item_id, live_id!(empty_state))),
                     // Loading more - posts + loading indicator at bottom
                     FeedState::LoadingMore => {
-                        if item_id < post_count {
-                            if let Some(post_data) = self.posts.get(item_id) {
-                                let item = list.item(cx, item_id, live_id!(post_item));
-                                if let Some(mut inner) = item.borrow_mut::<SocialPostCard>() {
-                                    inner.set_post(cx, post_data);
-                                }
-                                item
-                            } else {
-                                list.item(cx, item_id, live_id!(empty_state))
-                            }
+                        if item_id < row_count {
+                            self.row_item(cx, &mut list, item_id)
+                                .unwrap_or_else(|| list.item(cx, item_id, live_id!(empty_state)))
                         } else {
                             // Loading indicator at the bottom
                             list.item(cx, item_id, live_id!(loading_item))
@@ -270,16 +638,297 @@ impl Widget for SocialFeedView {
 
 impl WidgetMatchEvent for SocialFeedView {
     fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
-        // Forward post card actions
+        if self.button(ids!(present_button)).clicked(actions) && !self.posts.is_empty() {
+            let slides = self.posts.iter().map(KioskSlide::from).collect();
+            cx.action(SocialFeedViewAction::Present(slides));
+        }
+
+        if self
+            .button(ids!(dismiss_update_banner_button))
+            .clicked(actions)
+        {
+            self.update_banner_dismissed_at = self.degraded_feature_count;
+            self.view(ids!(update_banner)).set_visible(cx, false);
+            self.redraw(cx);
+        }
+
+        if self.button(ids!(clear_hashtag_button)).clicked(actions) {
+            self.show_hashtag(cx, None);
+        }
+
+        let feed_scroll = self.portal_list(ids!(feed_scroll));
+        if self.button(ids!(new_posts_pill)).clicked(actions) {
+            if let Some(row) = self.first_unread_row() {
+                feed_scroll.set_first_id_and_scroll(row, 0.0);
+            }
+            self.button(ids!(new_posts_pill)).set_visible(cx, false);
+            self.redraw(cx);
+        } else if feed_scroll.scrolled(actions) {
+            let first_row = feed_scroll.first_id();
+            let visible_rows = feed_scroll.visible_items();
+            self.mark_rows_read(cx, first_row, visible_rows);
+            if self.should_load_more(first_row, visible_rows) {
+                self.state = FeedState::LoadingMore;
+                self.last_load_more = Some(Instant::now());
+                cx.action(SocialFeedViewAction::LoadMore);
+                self.redraw(cx);
+            }
+        }
+
+        // Forward post card actions, showing likes and reactions at once
         for action in actions {
             if let Some(post_action) = action.downcast_ref::<SocialPostCardAction>() {
                 match post_action {
                     SocialPostCardAction::None => {}
+                    SocialPostCardAction::Like(event_id)
+                    | SocialPostCardAction::Unlike(event_id)
+                        if self.toggle_reaction(cx, event_id, LIKE_REACTION) => {}
+                    SocialPostCardAction::ToggleReaction { event_id, emoji }
+                        if self.toggle_reaction(cx, event_id, emoji) => {}
+                    SocialPostCardAction::RetrySend(event_id) => self.retry_send(cx, event_id),
+                    SocialPostCardAction::VotePoll { event_id, answers } => {
+                        self.vote_poll(cx, event_id, answers)
+                    }
+                    SocialPostCardAction::EndPoll(event_id) => {
+                        if let Some(post) =
+                            self.posts.iter().find(|post| &post.event_id == event_id)
+                        {
+                            submit_async_request(MatrixRequest::EndPoll {
+                                room_id: post.room_id.clone(),
+                                poll_id: event_id.clone(),
+                            });
+                        }
+                    }
+                    SocialPostCardAction::PollResultsLoaded(results) => {
+                        self.set_poll_results(cx, results)
+                    }
+                    SocialPostCardAction::SendToChat(event_id) => {
+                        self.open_share_to_chat(cx, event_id)
+                    }
+                    SocialPostCardAction::ShowMoreOptions(event_id) => {
+                        // Load the moderation options the user has in the post's feed
+                        if let Some(post) = self.post(event_id) {
+                            submit_async_request(MatrixRequest::LoadModerationPermissions {
+                                room_id: post.room_id.clone(),
+                            });
+                        }
+                        cx.action(SocialFeedViewAction::PostAction(post_action.clone()));
+                    }
+                    SocialPostCardAction::RemovePost(event_id) => {
+                        if let Some(post) = self.post(event_id) {
+                            submit_async_request(MatrixRequest::RemoveFeedPost {
+                                room_id: post.room_id.clone(),
+                                event_id: event_id.clone(),
+                            });
+                        }
+                    }
+                    SocialPostCardAction::BanAuthor(event_id) => {
+                        if let Some(post) = self.post(event_id) {
+                            submit_async_request(MatrixRequest::BanFeedCommenter {
+                                room_id: post.room_id.clone(),
+                                user_id: post.author_id.clone(),
+                            });
+                        }
+                    }
+                    SocialPostCardAction::ReportNotSpam { sender, .. } => {
+                        submit_async_request(MatrixRequest::SaveNotSpamSender(sender.clone()));
+                        cx.action(SocialFeedViewAction::PostAction(post_action.clone()));
+                    }
+                    SocialPostCardAction::Reply(event_id) => self.reply_to_post(cx, event_id),
+                    SocialPostCardAction::PinPost { event_id, pinned } => {
+                        if let Some(post) = self.post(event_id) {
+                            submit_async_request(MatrixRequest::PinFeedPost {
+                                room_id: post.room_id.clone(),
+                                event_id: event_id.clone(),
+                                pinned: *pinned,
+                            });
+                        }
+                    }
                     _ => {
                         cx.action(SocialFeedViewAction::PostAction(post_action.clone()));
                     }
                 }
             }
+
+            match action.downcast_ref::<FeedModerationAction>() {
+                Some(FeedModerationAction::PermissionsLoaded {
+                    room_id,
+                    permissions,
+                    pinned_posts,
+                }) => self.set_moderation(cx, room_id, *permissions, pinned_posts),
+                Some(FeedModerationAction::PinnedPostsChanged {
+                    room_id,
+                    pinned_posts,
+                }) => {
+                    let permissions = self
+                        .posts
+                        .iter()
+                        .find(|post| &post.room_id == room_id)
+                        .map(|post| post.moderation)
+                        .unwrap_or_default();
+                    self.set_moderation(cx, room_id, permissions, pinned_posts);
+                }
+                Some(FeedModerationAction::PostRemoved { event_id, .. }) => {
+                    self.posts.retain(|post| &post.event_id != event_id);
+                    self.rebuild_rows();
+                    self.redraw(cx);
+                }
+                Some(FeedModerationAction::UserBanned { user_id, .. }) => {
+                    enqueue_popup_notification(PopupItem {
+                        message: format!("Banned {user_id} from the feed."),
+                        auto_dismissal_duration: Some(3.0),
+                        kind: PopupKind::Success,
+                    });
+                }
+                Some(FeedModerationAction::Failed(error)) => {
+                    enqueue_popup_notification(PopupItem {
+                        message: error.clone(),
+                        auto_dismissal_duration: Some(5.0),
+                        kind: PopupKind::Error,
+                    });
+                }
+                _ => {}
+            }
+
+            if let Some(SocialShareToChatSheetAction::Close) = action.downcast_ref() {
+                self.widget(ids!(share_to_chat_sheet))
+                    .set_visible(cx, false);
+                self.redraw(cx);
+            }
+
+            // Reconcile or roll back the user's pending changes
+            match action.downcast_ref::<OptimisticAction>() {
+                Some(OptimisticAction::Confirmed {
+                    transaction_id,
+                    event_id,
+                }) => {
+                    let confirmed =
+                        self.optimistic
+                            .confirm(transaction_id, event_id, &mut self.posts);
+                    if confirmed.is_some() {
+                        self.rebuild_rows();
+                        self.redraw(cx);
+                    }
+                }
+                Some(OptimisticAction::Failed {
+                    transaction_id,
+                    error,
+                }) => {
+                    let Some(own_user_id) = current_user_id() else {
+                        continue;
+                    };
+                    let Some(change) =
+                        self.optimistic
+                            .roll_back(transaction_id, &mut self.posts, &own_user_id)
+                    else {
+                        continue;
+                    };
+                    let message = match change {
+                        PendingChange::Reaction { .. } => {
+                            format!("Failed to send your reaction: {error}")
+                        }
+                        PendingChange::Post { .. } => format!("Failed to send your post: {error}"),
+                    };
+                    enqueue_popup_notification(PopupItem {
+                        message,
+                        auto_dismissal_duration: Some(5.0),
+                        kind: PopupKind::Error,
+                    });
+                    self.rebuild_rows();
+                    self.update_new_posts_pill(cx);
+                }
+                _ => {}
+            }
+
+            // Send the posts made with the composer, unless it's hidden
+            if self.view(ids!(composer_section)).visible() {
+                match action.downcast_ref() {
+                    Some(SocialPostComposerAction::SubmitPost {
+                        text,
+                        targets,
+                        privacy_levels,
+                        media,
+                        license,
+                        co_author,
+                    }) => {
+                        let mut post = Post::markdown(text.as_str())
+                            .with_targets(targets.clone())
+                            .with_privacy(privacy_levels.clone())
+                            .with_license(*license);
+                        if let (Some(co_author), Some(own_user_id)) = (co_author, current_user_id())
+                        {
+                            post = invite_co_author(post, &own_user_id, co_author);
+                        }
+                        self.queue_post(cx, post, media.as_ref().map(OutboxMedia::from));
+                    }
+                    Some(SocialPostComposerAction::SubmitPoll {
+                        question,
+                        answers,
+                        allow_multiple,
+                        targets,
+                        privacy_levels,
+                        license,
+                    }) => {
+                        let post = Post::poll(question.as_str(), answers.clone(), *allow_multiple)
+                            .with_targets(targets.clone())
+                            .with_privacy(privacy_levels.clone())
+                            .with_license(*license);
+                        self.queue_post(cx, post, None);
+                    }
+                    Some(SocialPostComposerAction::SubmitReply {
+                        text,
+                        room_id,
+                        post_id,
+                        media,
+                    }) => {
+                        submit_async_request(MatrixRequest::QueuePostReply {
+                            room_id: room_id.clone(),
+                            post_id: post_id.clone(),
+                            text: text.clone(),
+                            media: media.as_ref().map(OutboxMedia::from),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            // Offer to retry posts the outbox gave up sending
+            if let Some(OutboxAction::Failed { transaction_id, .. }) = action.downcast_ref() {
+                if self
+                    .optimistic
+                    .mark_post_failed(transaction_id, &mut self.posts)
+                {
+                    self.rows_drawn_since_last_update.clear();
+                    self.redraw(cx);
+                }
+            }
+
+            // Show or hide the birthdays card, e.g., once it was hidden from the card
+            if let Some(BirthdaysAction::HiddenChanged(_)) = action.downcast_ref() {
+                self.rebuild_rows();
+                self.redraw(cx);
+            }
+
+            // Lay out the posts shown again for the new density
+            if let Some(FeedDensityAction::Changed(_)) = action.downcast_ref() {
+                self.redraw(cx);
+            }
+
+            // Handle post group expansion
+            match action.downcast_ref::<SocialPostGroupAction>() {
+                Some(SocialPostGroupAction::Expand(event_id)) => {
+                    self.expanded_groups.insert(event_id.clone());
+                    self.rebuild_rows();
+                    self.redraw(cx);
+                }
+                Some(SocialPostGroupAction::AlwaysExpand(author_id)) => {
+                    self.grouping.always_expand_author(author_id.clone());
+                    self.rebuild_rows();
+                    self.redraw(cx);
+                    cx.action(SocialFeedViewAction::GroupingChanged(self.grouping.clone()));
+                }
+                _ => {}
+            }
         }
     }
 }
@@ -288,36 +937,211 @@ impl SocialFeedView {
     /// Set the posts to display in the feed.
     pub fn set_posts(&mut self, cx: &mut Cx, posts: Vec<PostCardData>) {
         self.posts = posts;
+        self.reached_end = false;
+        self.rebuild_rows();
         self.state = if self.posts.is_empty() {
             FeedState::Empty
         } else {
             FeedState::Loaded
         };
-        self.redraw(cx);
+        self.update_new_posts_pill(cx);
     }
 
-    /// Append more posts to the feed.
+    /// Add older posts to the feed, e.g., once more were loaded.
+    ///
+    /// The posts are merged in with [`merge_feed_items()`],
+    /// so the posts already shown stay in place. If none of the posts are new,
+    /// the end of the feed was reached and no more posts are requested.
     pub fn append_posts(&mut self, cx: &mut Cx, posts: Vec<PostCardData>) {
-        self.posts.extend(posts);
+        let post_count = self.posts.len();
+        merge_feed_items(&mut self.posts, posts);
+        self.reached_end = self.posts.len() == post_count;
+        self.rebuild_rows();
         self.state = FeedState::Loaded;
-        self.redraw(cx);
+        self.update_new_posts_pill(cx);
     }
 
-    /// Prepend new posts to the feed (for refresh).
+    /// Add new posts to the feed (for refresh).
+    ///
+    /// The posts are merged in with [`merge_feed_items()`], so the posts already
+    /// shown stay in place and each new post is put before the first shown post
+    /// that comes after it in [`feed_order()`](crate::social::newsfeed::feed_order()).
     pub fn prepend_posts(&mut self, cx: &mut Cx, posts: Vec<PostCardData>) {
-        let mut new_posts = posts;
-        new_posts.append(&mut self.posts);
-        self.posts = new_posts;
+        merge_feed_items(&mut self.posts, posts);
+        self.rebuild_rows();
         self.state = FeedState::Loaded;
+        self.update_new_posts_pill(cx);
+    }
+
+    /// Show a post the user is sending at the top of the feed at once, marked as pending.
+    ///
+    /// Returns the transaction ID the post must be queued with (see
+    /// [`MatrixRequest::QueuePost`]), so that its echo confirms it.
+    pub fn add_pending_post(&mut self, cx: &mut Cx, mut post: PostCardData) -> OwnedTransactionId {
+        let transaction_id = self.optimistic.add_post(&mut post);
+        self.posts.insert(0, post);
+        self.rebuild_rows();
+        // Show the user their new post rather than keeping the view in place
+        self.portal_list(ids!(feed_scroll))
+            .set_first_id_and_scroll(0, 0.0);
+        if self.state == FeedState::Empty {
+            self.state = FeedState::Loaded;
+        }
         self.redraw(cx);
+        transaction_id
+    }
+
+    /// Show a post the user made at the top of the feed and queue it in the outbox,
+    /// which sends it to each of its targets.
+    fn queue_post(&mut self, cx: &mut Cx, post: Post, media: Option<OutboxMedia>) {
+        let (Some(own_user_id), Some(room_id)) = (current_user_id(), post.targets.first()) else {
+            return;
+        };
+        // The local event ID is replaced by `add_pending_post()`
+        let item = FeedItem::new(
+            room_id.clone(),
+            local_event_id(&TransactionId::new()),
+            own_user_id.clone(),
+            MilliSecondsSinceUnixEpoch::now(),
+            post.content.clone(),
+        );
+        let pending =
+            PostCardData::from_feed_item(item, ReactionSummary::new(), Some(&*own_user_id));
+        let transaction_id = self.add_pending_post(cx, pending);
+        submit_async_request(MatrixRequest::QueuePost {
+            post,
+            media,
+            transaction_id,
+        });
     }
 
     /// Set the feed state.
     pub fn set_state(&mut self, cx: &mut Cx, state: FeedState) {
         self.state = state;
+        // The list items may switch to or from the loading and empty templates
+        self.rows_drawn_since_last_update.clear();
+        self.redraw(cx);
+    }
+
+    /// Set how bursts of posts from one author are grouped.
+    pub fn set_grouping(&mut self, cx: &mut Cx, grouping: FeedGroupingSettings) {
+        self.grouping = grouping;
+        self.rebuild_rows();
+        self.redraw(cx);
+    }
+
+    /// Set the friends of friends to suggest in a "People you may know" card,
+    /// e.g., from [`FriendSuggestionEngine::get_friend_suggestions()`](crate::social::discovery::FriendSuggestionEngine::get_friend_suggestions).
+    ///
+    /// The card is hidden if there are no suggestions.
+    pub fn set_friend_suggestions(&mut self, cx: &mut Cx, suggestions: Vec<SuggestedUser>) {
+        self.suggestions = suggestions;
+        self.rebuild_rows();
+        self.redraw(cx);
+    }
+
+    /// Set the friends' birthdays to show in a "Birthdays this week" card,
+    /// e.g., from a [`BirthdaysAction::Loaded`] after submitting
+    /// [`MatrixRequest::LoadBirthdays`].
+    ///
+    /// The card is hidden if there are no birthdays or the user hid it.
+    pub fn set_birthdays(&mut self, cx: &mut Cx, birthdays: Vec<UpcomingBirthday>) {
+        self.birthdays = birthdays;
+        self.rebuild_rows();
+        self.redraw(cx);
+    }
+
+    /// Show a card introducing the owner above the posts,
+    /// when showing a single user's feed rather than the aggregated newsfeed.
+    ///
+    /// See [`SocialFeedOwnerCard::set_owner()`](crate::social::widgets::feed_owner_card::SocialFeedOwnerCard::set_owner).
+    pub fn show_owner(
+        &mut self,
+        cx: &mut Cx,
+        profile: LoadedProfile,
+        is_own_feed: bool,
+        is_friend: bool,
+    ) {
+        let owner_card = self.social_feed_owner_card(ids!(owner_card));
+        owner_card.set_owner(cx, profile, is_own_feed, is_friend);
+        self.view(ids!(owner_section)).set_visible(cx, true);
+        self.redraw(cx);
+    }
+
+    /// Set whether the current user follows the owner shown above the posts.
+    pub fn set_owner_following(&mut self, cx: &mut Cx, following: bool) {
+        self.social_feed_owner_card(ids!(owner_card))
+            .set_following(cx, following);
+    }
+
+    /// Hide the owner card, e.g., when going back to the aggregated newsfeed.
+    pub fn hide_owner(&mut self, cx: &mut Cx) {
+        self.view(ids!(owner_section)).set_visible(cx, false);
+        self.redraw(cx);
+    }
+
+    /// Set the read markers of the feed rooms, e.g., as loaded with
+    /// [`FeedReadMarkerService::load_markers()`](crate::social::newsfeed::FeedReadMarkerService::load_markers),
+    /// to count the posts sent since the user's last visit.
+    pub fn set_read_markers(&mut self, cx: &mut Cx, markers: FeedReadMarkers) {
+        self.read_markers = markers;
+        self.update_new_posts_pill(cx);
+    }
+
+    /// Show how the feed was aggregated over the posts,
+    /// e.g., from [`FeedAggregator::diagnostics()`](crate::social::newsfeed::FeedAggregator::diagnostics)
+    /// when the `feed_diagnostics` developer setting is on, or hide it if `None`.
+    ///
+    /// While shown, unsupported posts also show their raw event type.
+    pub fn set_diagnostics(&mut self, cx: &mut Cx, diagnostics: Option<&FeedDiagnostics>) {
+        if self.show_diagnostics != diagnostics.is_some() {
+            self.show_diagnostics = diagnostics.is_some();
+            self.rows_drawn_since_last_update.clear();
+        }
+        let overlay = self.view(ids!(diagnostics_overlay));
+        overlay.set_visible(cx, diagnostics.is_some());
+        if let Some(diagnostics) = diagnostics {
+            self.label(ids!(diagnostics_label))
+                .set_text(cx, &diagnostics.to_string());
+        }
+        self.redraw(cx);
+    }
+
+    /// Suggest updating Robrix if some posts need a newer client to be shown,
+    /// e.g., as tracked by [`FeedAggregator::capabilities()`](crate::social::newsfeed::FeedAggregator::capabilities).
+    ///
+    /// Once dismissed, the banner is only shown again if more features are degraded.
+    pub fn set_capabilities(&mut self, cx: &mut Cx, capabilities: &CapabilityTracker) {
+        self.degraded_feature_count = capabilities.degraded_feature_count();
+        self.view(ids!(update_banner)).set_visible(
+            cx,
+            self.degraded_feature_count > self.update_banner_dismissed_at,
+        );
         self.redraw(cx);
     }
 
+    /// Only show the posts with the given hashtag, lowercased and without its `#`
+    /// (see [`extract_hashtags()`]), or all posts again if `None`.
+    pub fn show_hashtag(&mut self, cx: &mut Cx, hashtag: Option<String>) {
+        if let Some(hashtag) = &hashtag {
+            self.label(ids!(hashtag_label))
+                .set_text(cx, &format!("Posts with #{hashtag}"));
+        }
+        self.view(ids!(hashtag_banner))
+            .set_visible(cx, hashtag.is_some());
+        self.hashtag = hashtag;
+        self.rebuild_rows();
+        self.redraw(cx);
+    }
+
+    /// Get the number of posts sent since the user's last visit.
+    pub fn new_post_count(&self) -> usize {
+        self.posts
+            .iter()
+            .filter(|post| self.read_markers.is_unread(&post.room_id, post.timestamp))
+            .count()
+    }
+
     /// Show or hide the composer.
     pub fn set_show_composer(&mut self, cx: &mut Cx, show: bool) {
         self.show_composer = show;
@@ -327,8 +1151,16 @@ impl SocialFeedView {
     /// Clear all posts.
     pub fn clear(&mut self, cx: &mut Cx) {
         self.posts.clear();
+        self.rows.clear();
+        self.row_keys.clear();
+        self.rows_drawn_since_last_update.clear();
+        self.expanded_groups.clear();
         self.state = FeedState::Empty;
-        self.redraw(cx);
+        self.last_load_more = None;
+        self.reached_end = false;
+        self.hashtag = None;
+        self.view(ids!(hashtag_banner)).set_visible(cx, false);
+        self.update_new_posts_pill(cx);
     }
 
     /// Get the number of posts.
@@ -350,6 +1182,7 @@ impl SocialFeedView {
     pub fn update_post(&mut self, cx: &mut Cx, event_id: &OwnedEventId, data: PostCardData) {
         if let Some(post) = self.posts.iter_mut().find(|p| &p.event_id == event_id) {
             *post = data;
+            self.rebuild_rows();
             self.redraw(cx);
         }
     }
@@ -357,23 +1190,414 @@ impl SocialFeedView {
     /// Remove a post by event ID.
     pub fn remove_post(&mut self, cx: &mut Cx, event_id: &OwnedEventId) {
         self.posts.retain(|p| &p.event_id != event_id);
+        self.rebuild_rows();
         if self.posts.is_empty() {
             self.state = FeedState::Empty;
         }
+        self.update_new_posts_pill(cx);
+    }
+
+    /// Remove the posts written in the given language, e.g., once the user hid it
+    /// with [`FeedFilterSettings::hide_language()`](crate::social::newsfeed::FeedFilterSettings::hide_language).
+    pub fn remove_posts_in_language(&mut self, cx: &mut Cx, language: Language) {
+        self.posts.retain(|p| p.language != Some(language));
+        self.rebuild_rows();
+        if self.posts.is_empty() {
+            self.state = FeedState::Empty;
+        }
+        self.update_new_posts_pill(cx);
+    }
+
+    /// Mark the posts in `count` rows from `first_row` on as seen,
+    /// sending the read markers of the feed rooms whose markers moved forward.
+    fn mark_rows_read(&mut self, cx: &mut Cx, first_row: usize, count: usize) {
+        let mut moved: HashMap<OwnedRoomId, OwnedEventId> = HashMap::new();
+        for row in self.rows.iter().skip(first_row).take(count) {
+            let indices = match row {
+                FeedRow::Post(index) => std::slice::from_ref(index),
+                FeedRow::Group(indices) => indices.as_slice(),
+                FeedRow::Suggestions | FeedRow::Birthdays => continue,
+            };
+            for post in indices.iter().filter_map(|i| self.posts.get(*i)) {
+                let marker = FeedReadMarker {
+                    event_id: post.event_id.clone(),
+                    origin_server_ts: post.timestamp,
+                };
+                if self.read_markers.mark_read(&post.room_id, marker) {
+                    moved.insert(post.room_id.clone(), post.event_id.clone());
+                }
+            }
+        }
+        if moved.is_empty() {
+            return;
+        }
+        for (room_id, event_id) in moved {
+            submit_async_request(MatrixRequest::SendFeedReadMarker { room_id, event_id });
+        }
+        self.update_new_posts_pill(cx);
+    }
+
+    /// Check whether more posts should be requested, given the rows in view:
+    /// the feed must be loaded and not at its end, the last visible row within
+    /// [`LOAD_MORE_THRESHOLD_ROWS`] of the end, and no more posts requested in the
+    /// last [`LOAD_MORE_DEBOUNCE`].
+    fn should_load_more(&self, first_row: usize, visible_rows: usize) -> bool {
+        self.state == FeedState::Loaded
+            && !self.reached_end
+            && first_row + visible_rows + LOAD_MORE_THRESHOLD_ROWS >= self.rows.len()
+            && self
+                .last_load_more
+                .is_none_or(|requested_at| requested_at.elapsed() >= LOAD_MORE_DEBOUNCE)
+    }
+
+    /// Add or remove the user's reaction to a post at once and send the change,
+    /// which is confirmed or rolled back with an [`OptimisticAction`].
+    ///
+    /// Returns `false` if the post isn't in the feed or no user is logged in.
+    fn toggle_reaction(&mut self, cx: &mut Cx, event_id: &OwnedEventId, emoji: &str) -> bool {
+        let Some(own_user_id) = current_user_id() else {
+            return false;
+        };
+        let Some(post) = self
+            .posts
+            .iter_mut()
+            .find(|post| &post.event_id == event_id)
+        else {
+            return false;
+        };
+        let (transaction_id, added) = self.optimistic.toggle_reaction(post, emoji, &own_user_id);
+        submit_async_request(MatrixRequest::TogglePostReaction {
+            room_id: post.room_id.clone(),
+            post_id: event_id.clone(),
+            emoji: emoji.to_string(),
+            added,
+            transaction_id,
+        });
+        self.rows_drawn_since_last_update.clear();
         self.redraw(cx);
+        true
     }
 
-    /// Handle scroll events for infinite scrolling.
-    fn handle_scroll(&mut self, cx: &mut Cx, _scroll: &event::ScrollEvent) {
-        // Check if we're near the bottom and should load more
-        // This is a simplified check - real implementation would use
-        // the scroll position and content height
-        if self.state == FeedState::Loaded && self.posts.len() >= 10 {
-            // Trigger load more when near bottom
-            // (actual scroll position check would go here)
-            // cx.action(SocialFeedViewAction::LoadMore);
+    /// Try sending a post that failed to send again, marking it as pending.
+    fn retry_send(&mut self, cx: &mut Cx, local_id: &OwnedEventId) {
+        let Some(transaction_id) = self.optimistic.retry_post(local_id, &mut self.posts) else {
+            return;
+        };
+        submit_async_request(MatrixRequest::RetryQueuedPost { transaction_id });
+        self.rows_drawn_since_last_update.clear();
+        self.redraw(cx);
+    }
+
+    /// Show the user's vote in a poll at once and send it.
+    fn vote_poll(&mut self, cx: &mut Cx, event_id: &OwnedEventId, answers: &[String]) {
+        let Some(own_user_id) = current_user_id() else {
+            return;
+        };
+        let Some(post) = self
+            .posts
+            .iter_mut()
+            .find(|post| &post.event_id == event_id)
+        else {
+            return;
+        };
+        let Some(poll) = &mut post.poll else {
+            return;
+        };
+        poll.set_votes(&own_user_id, answers);
+        submit_async_request(MatrixRequest::VotePoll {
+            room_id: post.room_id.clone(),
+            poll_id: event_id.clone(),
+            answers: answers.to_vec(),
+        });
+        self.rows_drawn_since_last_update.clear();
+        self.redraw(cx);
+    }
+
+    /// Show the loaded votes of a poll, if its post is in the feed.
+    fn set_poll_results(&mut self, cx: &mut Cx, results: &PollResults) {
+        let Some(post) = self
+            .posts
+            .iter_mut()
+            .find(|post| post.event_id == results.poll_id)
+        else {
+            return;
+        };
+        post.poll = Some(results.clone());
+        self.rows_drawn_since_last_update.clear();
+        self.redraw(cx);
+    }
+
+    /// Handle a key pressed while the feed has keyboard focus.
+    fn handle_key_down(&mut self, cx: &mut Cx, key_event: &KeyEvent) {
+        let modifiers = &key_event.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.logo {
+            return;
+        }
+        match key_event.key_code {
+            KeyCode::KeyJ | KeyCode::ArrowDown => self.move_focus(cx, true),
+            KeyCode::KeyK | KeyCode::ArrowUp => self.move_focus(cx, false),
+            KeyCode::Escape => {
+                self.focused_post = None;
+                self.redraw(cx);
+            }
+            KeyCode::ReturnKey | KeyCode::KeyL | KeyCode::KeyR => {
+                let Some(event_id) = self.focused_post.clone() else {
+                    return;
+                };
+                match key_event.key_code {
+                    KeyCode::ReturnKey => cx.action(SocialFeedViewAction::PostAction(
+                        SocialPostCardAction::ViewPost(event_id),
+                    )),
+                    KeyCode::KeyL => {
+                        self.toggle_reaction(cx, &event_id, LIKE_REACTION);
+                    }
+                    _ => self.reply_to_post(cx, &event_id),
+                }
+            }
+            _ => {}
         }
-        let _ = cx;
+    }
+
+    /// Move the keyboard focus to the next or previous post, scrolling it into view.
+    ///
+    /// Without a focused post, the first post in view is focused.
+    fn move_focus(&mut self, cx: &mut Cx, forward: bool) {
+        let feed_scroll = self.portal_list(ids!(feed_scroll));
+        let first_row = feed_scroll.first_id();
+        let focused_row = self.focused_post.as_ref().and_then(|focused| {
+            let key = FeedRowKey::Post(focused.clone());
+            self.row_keys.iter().position(|row_key| *row_key == key)
+        });
+        let is_post = |row: &usize| matches!(self.rows[*row], FeedRow::Post(_));
+        let next_row = match focused_row {
+            Some(row) if forward => (row + 1..self.rows.len()).find(is_post),
+            Some(row) => (0..row).rev().find(is_post),
+            None => (first_row..self.rows.len()).find(is_post),
+        };
+        let Some(row) = next_row else {
+            return;
+        };
+        let FeedRowKey::Post(event_id) = &self.row_keys[row] else {
+            return;
+        };
+        self.focused_post = Some(event_id.clone());
+
+        // Keep the focused post fully in view
+        let last_full_row = first_row + feed_scroll.visible_items().saturating_sub(1);
+        if row < first_row || row >= last_full_row {
+            feed_scroll.set_first_id_and_scroll(row, 0.0);
+        }
+        self.redraw(cx);
+    }
+
+    /// Reply to a post from the composer, quoting it, or let the page containing
+    /// the feed handle the reply if the composer is hidden.
+    fn reply_to_post(&mut self, cx: &mut Cx, event_id: &OwnedEventId) {
+        if !self.view(ids!(composer_section)).visible() {
+            cx.action(SocialFeedViewAction::PostAction(
+                SocialPostCardAction::Reply(event_id.clone()),
+            ));
+            return;
+        }
+        if let Some(post) = self.post(event_id) {
+            let target = ReplyTarget::from(post);
+            self.social_post_composer(ids!(composer))
+                .start_reply(cx, target);
+        }
+    }
+
+    /// Get a post in the feed.
+    fn post(&self, event_id: &OwnedEventId) -> Option<&PostCardData> {
+        self.posts.iter().find(|post| &post.event_id == event_id)
+    }
+
+    /// Offer the moderation options the user has in a feed room on its posts,
+    /// and mark which of them are pinned.
+    fn set_moderation(
+        &mut self,
+        cx: &mut Cx,
+        room_id: &OwnedRoomId,
+        permissions: ModerationPermissions,
+        pinned_posts: &[OwnedEventId],
+    ) {
+        for post in &mut self.posts {
+            if &post.room_id == room_id {
+                post.moderation = permissions;
+                post.is_pinned = pinned_posts.contains(&post.event_id);
+            }
+        }
+        self.rows_drawn_since_last_update.clear();
+        self.redraw(cx);
+    }
+
+    /// Show the room picker for sending a post in the feed to a chat.
+    fn open_share_to_chat(&mut self, cx: &mut Cx, event_id: &OwnedEventId) {
+        let Some(post) = self.posts.iter().find(|post| &post.event_id == event_id) else {
+            return;
+        };
+        let sheet = self.social_share_to_chat_sheet(ids!(share_to_chat_sheet));
+        sheet.open(cx, post.room_id.clone(), event_id.clone());
+        sheet.set_visible(cx, true);
+        self.redraw(cx);
+    }
+
+    /// Reload the votes of the polls in the feed that someone voted in or ended.
+    fn reload_updated_polls(&mut self) {
+        for event in self.feed_updates.drain() {
+            let SocialEvent::PollUpdated { room_id, poll_id } = event else {
+                continue;
+            };
+            let is_shown = self
+                .posts
+                .iter()
+                .any(|post| post.event_id == poll_id && post.poll.is_some());
+            if is_shown {
+                submit_async_request(MatrixRequest::LoadPollResults { room_id, poll_id });
+            }
+        }
+    }
+
+    /// Get the index of the first row showing a post sent since the user's last visit.
+    fn first_unread_row(&self) -> Option<usize> {
+        self.rows.iter().position(|row| {
+            let indices = match row {
+                FeedRow::Post(index) => std::slice::from_ref(index),
+                FeedRow::Group(indices) => indices.as_slice(),
+                FeedRow::Suggestions | FeedRow::Birthdays => return false,
+            };
+            indices
+                .iter()
+                .filter_map(|i| self.posts.get(*i))
+                .any(|post| self.read_markers.is_unread(&post.room_id, post.timestamp))
+        })
+    }
+
+    /// Show the number of posts sent since the user's last visit in the "New posts" pill,
+    /// or hide the pill if there are none.
+    fn update_new_posts_pill(&mut self, cx: &mut Cx) {
+        let count = self.new_post_count();
+        let pill = self.button(ids!(new_posts_pill));
+        pill.set_visible(cx, count > 0);
+        match count {
+            0 => {}
+            1 => pill.set_text(cx, "1 new post"),
+            n => pill.set_text(cx, &format!("{n} new posts")),
+        }
+        self.redraw(cx);
+    }
+
+    /// Recompute the displayed rows from the posts, grouping settings, suggestions and birthdays.
+    ///
+    /// The row that was at the top of the view is kept there,
+    /// even if rows were inserted or removed above it.
+    fn rebuild_rows(&mut self) {
+        let mut rows = Vec::new();
+        if let Some(hashtag) = &self.hashtag {
+            // Only a few posts have the hashtag, so they aren't grouped
+            rows.extend(
+                (0..self.posts.len())
+                    .filter(|index| extract_hashtags(&self.posts[*index].text).contains(hashtag))
+                    .map(FeedRow::Post),
+            );
+        } else {
+            for entry in group_feed_items(&self.posts, &self.grouping) {
+                match entry {
+                    FeedEntry::Single(index) => rows.push(FeedRow::Post(index)),
+                    FeedEntry::Group { indices, .. }
+                        if self.expanded_groups.contains(&self.posts[indices[0]].event_id) =>
+                    {
+                        rows.extend(indices.into_iter().map(FeedRow::Post));
+                    }
+                    FeedEntry::Group { indices, .. } => rows.push(FeedRow::Group(indices)),
+                }
+            }
+            if !self.suggestions.is_empty() && !rows.is_empty() {
+                rows.insert(SUGGESTIONS_ROW_INDEX.min(rows.len()), FeedRow::Suggestions);
+            }
+            if !self.birthdays.is_empty() && !are_birthdays_hidden() && !rows.is_empty() {
+                rows.insert(0, FeedRow::Birthdays);
+            }
+        }
+        let row_keys: Vec<FeedRowKey> = rows
+            .iter()
+            .map(|row| match row {
+                FeedRow::Post(index) => FeedRowKey::Post(self.posts[*index].event_id.clone()),
+                FeedRow::Group(indices) => {
+                    FeedRowKey::Post(self.posts[indices[0]].event_id.clone())
+                }
+                FeedRow::Suggestions => FeedRowKey::Suggestions,
+                FeedRow::Birthdays => FeedRowKey::Birthdays,
+            })
+            .collect();
+
+        let feed_scroll = self.portal_list(ids!(feed_scroll));
+        let first_row = feed_scroll.first_id();
+        if let Some(new_first_row) = find_anchor_row(&self.row_keys, first_row, &row_keys) {
+            if new_first_row != first_row {
+                feed_scroll.set_first_id_and_scroll(new_first_row, feed_scroll.scroll_position());
+            }
+        }
+
+        self.rows = rows;
+        self.row_keys = row_keys;
+        self.rows_drawn_since_last_update.clear();
+    }
+
+    /// Get the list item for the row at the given index, populated with its data
+    /// unless it was already filled in since the rows last changed.
+    fn row_item(
+        &mut self,
+        cx: &mut Cx2d,
+        list: &mut PortalList,
+        item_id: usize,
+    ) -> Option<WidgetRef> {
+        let template = match self.rows.get(item_id)? {
+            FeedRow::Post(_) => live_id!(post_item),
+            FeedRow::Group(_) => live_id!(group_item),
+            FeedRow::Suggestions => live_id!(suggestions_item),
+            FeedRow::Birthdays => live_id!(birthdays_item),
+        };
+        let (item, existed) = list.item_with_existed(cx, item_id, template);
+        if let Some(mut inner) = item.borrow_mut::<SocialPostCard>() {
+            let is_focused = self.focused_post.as_ref().is_some_and(|focused| {
+                self.row_keys.get(item_id) == Some(&FeedRowKey::Post(focused.clone()))
+            });
+            inner.set_focused(cx, is_focused);
+            inner.set_density(cx, feed_density());
+        }
+        if existed && self.rows_drawn_since_last_update.contains(&item_id) {
+            return Some(item);
+        }
+
+        match &self.rows[item_id] {
+            FeedRow::Post(index) => {
+                let post_data = self.posts.get(*index)?;
+                if let Some(mut inner) = item.borrow_mut::<SocialPostCard>() {
+                    inner.set_show_developer_details(self.show_diagnostics);
+                    inner.set_post(cx, post_data);
+                }
+            }
+            FeedRow::Group(indices) => {
+                let posts: Vec<&PostCardData> =
+                    indices.iter().filter_map(|i| self.posts.get(*i)).collect();
+                if let Some(mut inner) = item.borrow_mut::<SocialPostGroupCard>() {
+                    inner.set_posts(cx, &posts);
+                }
+            }
+            FeedRow::Suggestions => {
+                if let Some(mut inner) = item.borrow_mut::<SocialPeopleSuggestionsCard>() {
+                    inner.set_suggestions(cx, &self.suggestions);
+                }
+            }
+            FeedRow::Birthdays => {
+                if let Some(mut inner) = item.borrow_mut::<SocialBirthdaysCard>() {
+                    inner.set_birthdays(cx, &self.birthdays);
+                }
+            }
+        }
+        self.rows_drawn_since_last_update
+            .insert(item_id..item_id + 1);
+        Some(item)
     }
 
     /// Update visibility of UI elements based on current state.
@@ -415,6 +1639,12 @@ impl SocialFeedViewRef {
         }
     }
 
+    /// See [`SocialFeedView::add_pending_post()`].
+    pub fn add_pending_post(&self, cx: &mut Cx, post: PostCardData) -> Option<OwnedTransactionId> {
+        self.borrow_mut()
+            .map(|mut inner| inner.add_pending_post(cx, post))
+    }
+
     /// See [`SocialFeedView::set_state()`].
     pub fn set_state(&self, cx: &mut Cx, state: FeedState) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -422,6 +1652,89 @@ impl SocialFeedViewRef {
         }
     }
 
+    /// See [`SocialFeedView::set_grouping()`].
+    pub fn set_grouping(&self, cx: &mut Cx, grouping: FeedGroupingSettings) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_grouping(cx, grouping);
+        }
+    }
+
+    /// See [`SocialFeedView::set_friend_suggestions()`].
+    pub fn set_friend_suggestions(&self, cx: &mut Cx, suggestions: Vec<SuggestedUser>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_friend_suggestions(cx, suggestions);
+        }
+    }
+
+    /// See [`SocialFeedView::remove_posts_in_language()`].
+    pub fn remove_posts_in_language(&self, cx: &mut Cx, language: Language) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.remove_posts_in_language(cx, language);
+        }
+    }
+
+    /// See [`SocialFeedView::set_birthdays()`].
+    pub fn set_birthdays(&self, cx: &mut Cx, birthdays: Vec<UpcomingBirthday>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_birthdays(cx, birthdays);
+        }
+    }
+
+    /// See [`SocialFeedView::show_owner()`].
+    pub fn show_owner(
+        &self,
+        cx: &mut Cx,
+        profile: LoadedProfile,
+        is_own_feed: bool,
+        is_friend: bool,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_owner(cx, profile, is_own_feed, is_friend);
+        }
+    }
+
+    /// See [`SocialFeedView::set_owner_following()`].
+    pub fn set_owner_following(&self, cx: &mut Cx, following: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_owner_following(cx, following);
+        }
+    }
+
+    /// See [`SocialFeedView::hide_owner()`].
+    pub fn hide_owner(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.hide_owner(cx);
+        }
+    }
+
+    /// See [`SocialFeedView::set_read_markers()`].
+    pub fn set_read_markers(&self, cx: &mut Cx, markers: FeedReadMarkers) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_read_markers(cx, markers);
+        }
+    }
+
+    /// See [`SocialFeedView::show_hashtag()`].
+    pub fn show_hashtag(&self, cx: &mut Cx, hashtag: Option<String>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_hashtag(cx, hashtag);
+        }
+    }
+
+    /// See [`SocialFeedView::set_capabilities()`].
+    pub fn set_capabilities(&self, cx: &mut Cx, capabilities: &CapabilityTracker) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_capabilities(cx, capabilities);
+        }
+    }
+
+    /// See [`SocialFeedView::set_diagnostics()`].
+    pub fn set_diagnostics(&self, cx: &mut Cx, diagnostics: Option<&FeedDiagnostics>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_diagnostics(cx, diagnostics);
+        }
+    }
+
     /// See [`SocialFeedView::set_show_composer()`].
     pub fn set_show_composer(&self, cx: &mut Cx, show: bool) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -440,4 +1753,24 @@ impl SocialFeedViewRef {
     pub fn post_count(&self) -> usize {
         self.borrow().map(|inner| inner.post_count()).unwrap_or(0)
     }
+
+    /// See [`SocialFeedView::new_post_count()`].
+    pub fn new_post_count(&self) -> usize {
+        self.borrow()
+            .map(|inner| inner.new_post_count())
+            .unwrap_or(0)
+    }
+
+    /// See [`SocialFeedView::find_post()`].
+    pub fn find_post(&self, event_id: &OwnedEventId) -> Option<PostCardData> {
+        self.borrow()
+            .and_then(|inner| inner.find_post(event_id).cloned())
+    }
+
+    /// See [`SocialFeedView::update_post()`].
+    pub fn update_post(&self, cx: &mut Cx, event_id: &OwnedEventId, data: PostCardData) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.update_post(cx, event_id, data);
+        }
+    }
 }
@@ -0,0 +1,446 @@
+//! Followers and following list widgets.
+//!
+//! [`FollowersListView`] shows the members of the current user's public feed,
+//! and [`FollowingListView`] shows the owners of the public feeds they have
+//! joined. Both let the user open a profile by tapping it and follow or
+//! unfollow people inline, and followers can also be removed or banned.
+//! Their contents come from
+//! [`FollowService::list_followers()`](crate::social::FollowService::list_followers)
+//! and [`FollowService::list_following()`](crate::social::FollowService::list_following).
+//!
+//! Both are shown on one's own profile page, in place of its tabs,
+//! when tapping the follower or following count.
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::{OwnedUserId, UserId};
+
+use crate::shared::avatar::AvatarWidgetExt;
+use crate::social::follow::FollowListUser;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use crate::shared::avatar::Avatar;
+
+    /// A single user in a followers or following list.
+    FollowListItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 10, bottom: 10 },
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        cursor: Hand,
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        avatar = <Avatar> {
+            width: 40,
+            height: 40,
+        }
+
+        user_id_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: #000,
+            }
+        }
+
+        follow_button = <Button> {
+            width: Fit,
+            height: 32,
+            text: "Follow",
+            draw_bg: {
+                color: #1d9bf0,
+                radius: 16.0,
+            }
+            draw_text: {
+                color: #fff,
+                text_style: { font_size: 12.0 },
+            }
+        }
+
+        // Follower moderation (followers list only)
+        moderation = <View> {
+            width: Fit,
+            height: Fit,
+            flow: Right,
+            spacing: 8,
+            visible: false,
+
+            remove_follower_button = <Button> {
+                width: Fit,
+                height: 32,
+                text: "Remove",
+                draw_text: {
+                    color: #333,
+                    text_style: { font_size: 12.0 },
+                }
+            }
+
+            ban_follower_button = <Button> {
+                width: Fit,
+                height: 32,
+                text: "Ban",
+                draw_text: {
+                    color: #e0245e,
+                    text_style: { font_size: 12.0 },
+                }
+            }
+        }
+    }
+
+    /// Shown in place of the list when it is empty.
+    FollowListEmpty = <View> {
+        width: Fill,
+        height: Fit,
+        padding: 32,
+        align: { x: 0.5 },
+
+        empty_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: #999,
+            }
+        }
+    }
+
+    /// Header of a followers or following list.
+    FollowListHeader = <View> {
+        width: Fill,
+        height: Fit,
+        padding: 16,
+        flow: Right,
+        align: { y: 0.5 },
+
+        title_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 20.0 },
+                color: #000,
+            }
+        }
+
+        close_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "Close",
+            draw_bg: {
+                color: #0000,
+            }
+            draw_text: {
+                color: #666,
+            }
+        }
+    }
+
+    /// List of users following the current user.
+    pub FollowersListView = {{FollowersListView}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        header = <FollowListHeader> {
+            title_label = { text: "Followers" }
+        }
+
+        users_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            user_item = <FollowListItem> {
+                moderation = { visible: true }
+            }
+            empty_item = <FollowListEmpty> {
+                empty_label = { text: "Nobody follows you yet." }
+            }
+        }
+    }
+
+    /// List of users the current user follows.
+    pub FollowingListView = {{FollowingListView}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        header = <FollowListHeader> {
+            title_label = { text: "Following" }
+        }
+
+        users_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            user_item = <FollowListItem> {}
+            empty_item = <FollowListEmpty> {
+                empty_label = { text: "You're not following anyone yet." }
+            }
+        }
+    }
+}
+
+/// Actions that can be triggered from the followers and following lists.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum FollowListAction {
+    /// User tapped someone in the list
+    OpenProfile(OwnedUserId),
+    /// User clicked Follow next to someone.
+    /// The follow should be made with
+    /// [`FollowService::follow()`](crate::social::FollowService::follow).
+    Follow(OwnedUserId),
+    /// User clicked Unfollow next to someone.
+    /// The follow should be undone with
+    /// [`FollowService::unfollow()`](crate::social::FollowService::unfollow).
+    Unfollow(OwnedUserId),
+    /// User clicked Remove next to a follower.
+    /// The follower should be removed with
+    /// [`FollowerModerationService::remove_follower()`](crate::social::FollowerModerationService::remove_follower).
+    RemoveFollower(OwnedUserId),
+    /// User clicked Ban next to a follower.
+    /// The follower should be banned with
+    /// [`FollowerModerationService::ban_from_feeds()`](crate::social::FollowerModerationService::ban_from_feeds).
+    BanFromFeeds(OwnedUserId),
+    /// User closed the list.
+    Close,
+    /// No action
+    None,
+}
+
+/// The users shown in a followers or following list, shared by both widgets.
+#[derive(Default)]
+struct FollowList {
+    users: Vec<FollowListUser>,
+}
+
+impl FollowList {
+    /// Draw the list's items, or its empty state if there are no users.
+    fn draw_walk(&mut self, view: &mut View, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) {
+        while let Some(widget_to_draw) = view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, self.users.len().max(1));
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let item = match self.users.get(item_id) {
+                    Some(user) => {
+                        let item = list.item(cx, item_id, live_id!(user_item));
+                        item.avatar(ids!(avatar))
+                            .set_text(cx, user.user_id.localpart());
+                        item.label(ids!(user_id_label))
+                            .set_text(cx, user.user_id.as_str());
+                        item.button(ids!(follow_button)).set_text(
+                            cx,
+                            if user.is_followed {
+                                "Unfollow"
+                            } else {
+                                "Follow"
+                            },
+                        );
+                        item
+                    }
+                    None => list.item(cx, item_id, live_id!(empty_item)),
+                };
+                item.draw_all(cx, scope);
+            }
+        }
+    }
+
+    /// Handle taps on users and their buttons, and on the close button.
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, view: &View) {
+        if view.button(ids!(close_button)).clicked(actions) {
+            cx.action(FollowListAction::Close);
+        }
+
+        let users_list = view.portal_list(ids!(users_list));
+        for (index, item) in users_list.items_with_actions(actions) {
+            let Some(user) = self.users.get_mut(index) else {
+                continue;
+            };
+            if item.button(ids!(follow_button)).clicked(actions) {
+                let user_id = user.user_id.clone();
+                if user.is_followed {
+                    cx.action(FollowListAction::Unfollow(user_id));
+                } else {
+                    cx.action(FollowListAction::Follow(user_id));
+                }
+                // Show the change right away; it's reverted with `set_followed()` on failure
+                user.is_followed = !user.is_followed;
+                users_list.redraw(cx);
+            } else if item.button(ids!(remove_follower_button)).clicked(actions) {
+                cx.action(FollowListAction::RemoveFollower(user.user_id.clone()));
+            } else if item.button(ids!(ban_follower_button)).clicked(actions) {
+                cx.action(FollowListAction::BanFromFeeds(user.user_id.clone()));
+            } else if item.as_view().finger_up(actions).is_some() {
+                cx.action(FollowListAction::OpenProfile(user.user_id.clone()));
+            }
+        }
+    }
+
+    /// Set whether the current user follows the given user.
+    fn set_followed(&mut self, user_id: &UserId, is_followed: bool) {
+        for user in self.users.iter_mut().filter(|u| u.user_id == user_id) {
+            user.is_followed = is_followed;
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct FollowersListView {
+    #[deref]
+    view: View,
+
+    /// The users following the current user.
+    #[rust]
+    list: FollowList,
+}
+
+impl Widget for FollowersListView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.list.draw_walk(&mut self.view, cx, scope, walk);
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for FollowersListView {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        self.list.handle_actions(cx, actions, &self.view);
+    }
+}
+
+impl FollowersListView {
+    /// Set the followers to display,
+    /// e.g., from [`FollowService::list_followers()`](crate::social::FollowService::list_followers).
+    pub fn set_users(&mut self, cx: &mut Cx, users: Vec<FollowListUser>) {
+        self.list.users = users;
+        self.redraw(cx);
+    }
+
+    /// Set whether the current user follows one of the followers back,
+    /// e.g., after following them failed.
+    pub fn set_followed(&mut self, cx: &mut Cx, user_id: &UserId, is_followed: bool) {
+        self.list.set_followed(user_id, is_followed);
+        self.redraw(cx);
+    }
+
+    /// Remove a user from the list, e.g., after they were removed as a follower.
+    pub fn remove_user(&mut self, cx: &mut Cx, user_id: &UserId) {
+        self.list.users.retain(|u| u.user_id != user_id);
+        self.redraw(cx);
+    }
+}
+
+impl FollowersListViewRef {
+    /// See [`FollowersListView::set_users()`].
+    pub fn set_users(&self, cx: &mut Cx, users: Vec<FollowListUser>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_users(cx, users);
+        }
+    }
+
+    /// See [`FollowersListView::set_followed()`].
+    pub fn set_followed(&self, cx: &mut Cx, user_id: &UserId, is_followed: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_followed(cx, user_id, is_followed);
+        }
+    }
+
+    /// See [`FollowersListView::remove_user()`].
+    pub fn remove_user(&self, cx: &mut Cx, user_id: &UserId) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.remove_user(cx, user_id);
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct FollowingListView {
+    #[deref]
+    view: View,
+
+    /// The users the current user follows.
+    #[rust]
+    list: FollowList,
+}
+
+impl Widget for FollowingListView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.list.draw_walk(&mut self.view, cx, scope, walk);
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for FollowingListView {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        self.list.handle_actions(cx, actions, &self.view);
+    }
+}
+
+impl FollowingListView {
+    /// Set the followed users to display,
+    /// e.g., from [`FollowService::list_following()`](crate::social::FollowService::list_following).
+    ///
+    /// Unfollowed users stay in the list until it is refreshed,
+    /// so they can be followed again.
+    pub fn set_users(&mut self, cx: &mut Cx, users: Vec<FollowListUser>) {
+        self.list.users = users;
+        self.redraw(cx);
+    }
+
+    /// Set whether the current user follows one of the listed users,
+    /// e.g., after unfollowing them failed.
+    pub fn set_followed(&mut self, cx: &mut Cx, user_id: &UserId, is_followed: bool) {
+        self.list.set_followed(user_id, is_followed);
+        self.redraw(cx);
+    }
+}
+
+impl FollowingListViewRef {
+    /// See [`FollowingListView::set_users()`].
+    pub fn set_users(&self, cx: &mut Cx, users: Vec<FollowListUser>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_users(cx, users);
+        }
+    }
+
+    /// See [`FollowingListView::set_followed()`].
+    pub fn set_followed(&self, cx: &mut Cx, user_id: &UserId, is_followed: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_followed(cx, user_id, is_followed);
+        }
+    }
+}
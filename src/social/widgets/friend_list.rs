@@ -1,19 +1,45 @@
 //! Friend list widget for displaying and managing friends.
 //!
 //! This widget renders the user's friend list with options to view profiles,
-//! send messages, and remove friends. It also displays pending friend requests.
+//! send messages, and remove friends. It also displays pending friend requests:
+//! the ones received, which can be accepted or declined, and the ones sent,
+//! which can be cancelled.
+//!
+//! Friends show whether they're online, idle or offline, and when they were
+//! last active, see [`presence`](crate::social::presence). Their presence is
+//! fetched again on every [`SocialTickerAction::Tick`] while they're listed.
+//!
+//! Unfriending someone, answering a request and cancelling a sent request
+//! are sent to the Matrix worker from the list itself.
+//! The list is loaded with [`MatrixRequest::LoadFriendList`].
 
 use makepad_widgets::*;
-use matrix_sdk::ruma::OwnedUserId;
+use matrix_sdk::{
+    ruma::{MxcUri, OwnedMxcUri, OwnedRoomId, OwnedUserId, RoomId, UserId},
+    Room,
+};
 use std::sync::Arc;
 
+use crate::avatar_cache;
+use crate::shared::avatar::{AvatarRef, AvatarWidgetExt};
+use crate::shared::confirmation_modal::{ConfirmationModalContent, ConfirmationModalWidgetExt};
+use crate::social::block_list::is_blocked;
+use crate::social::event_bus::{subscribe_social_events, SocialEvent, SocialSubscription, SocialTopic};
+use crate::social::friends::{OutgoingFriendRequest, PendingFriendRequest};
+use crate::social::presence::{OnlineStatus, PresenceAction, UserPresence};
+use crate::social::ticker::SocialTickerAction;
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::utils;
+
 live_design! {
     use link::theme::*;
     use link::shaders::*;
     use link::widgets::*;
+    use link::social_theme::*;
 
     use crate::shared::styles::*;
     use crate::shared::avatar::Avatar;
+    use crate::shared::confirmation_modal::*;
 
     /// Individual friend item in the list.
     FriendItem = <View> {
@@ -24,13 +50,47 @@ live_design! {
         spacing: 12,
         show_bg: true,
         draw_bg: {
-            color: #fff
+            color: (SOCIAL_BG)
         }
 
-        // Friend's avatar
-        avatar = <Avatar> {
+        // Friend's avatar, with their presence at its bottom right
+        avatar_container = <View> {
             width: 48,
             height: 48,
+            flow: Overlay,
+            align: { x: 1.0, y: 1.0 },
+
+            avatar = <Avatar> {
+                width: 48,
+                height: 48,
+            }
+
+            // Shown once the friend's presence is known
+            presence_dot = <View> {
+                width: 14,
+                height: 14,
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    // 1.0 if the friend is online
+                    instance online: 0.0
+                    // 1.0 if the friend is idle
+                    instance idle: 0.0
+
+                    fn pixel(self) -> vec4 {
+                        let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                        let radius = self.rect_size.x * 0.5;
+                        sdf.circle(radius, radius, radius - 1.0);
+                        sdf.fill_keep(mix(
+                            mix((SOCIAL_PRESENCE_OFFLINE), (SOCIAL_PRESENCE_IDLE), self.idle),
+                            (SOCIAL_PRESENCE_ONLINE),
+                            self.online
+                        ));
+                        sdf.stroke((SOCIAL_BG), 2.0);
+                        return sdf.result;
+                    }
+                }
+            }
         }
 
         // Friend info column
@@ -46,7 +106,7 @@ live_design! {
                 text: "",
                 draw_text: {
                     text_style: { font_size: 14.0 },
-                    color: #000,
+                    color: (SOCIAL_TEXT_PRIMARY),
                 }
             }
 
@@ -56,7 +116,7 @@ live_design! {
                 text: "",
                 draw_text: {
                     text_style: { font_size: 12.0 },
-                    color: #666,
+                    color: (SOCIAL_TEXT_SECONDARY),
                 }
             }
 
@@ -66,7 +126,7 @@ live_design! {
                 text: "",
                 draw_text: {
                     text_style: { font_size: 11.0 },
-                    color: #999,
+                    color: (SOCIAL_TEXT_MUTED),
                 }
             }
         }
@@ -84,11 +144,11 @@ live_design! {
                 height: 36,
                 text: "Msg",
                 draw_bg: {
-                    color: #f0f0f0,
+                    color: (SOCIAL_SURFACE),
                     radius: 18.0,
                 }
                 draw_text: {
-                    color: #333,
+                    color: (SOCIAL_TEXT),
                     text_style: { font_size: 10.0 },
                 }
             }
@@ -98,11 +158,11 @@ live_design! {
                 height: 36,
                 text: "X",
                 draw_bg: {
-                    color: #fff0f0,
+                    color: (SOCIAL_DANGER_BG),
                     radius: 18.0,
                 }
                 draw_text: {
-                    color: #c00,
+                    color: (SOCIAL_DANGER),
                     text_style: { font_size: 12.0 },
                 }
             }
@@ -118,7 +178,7 @@ live_design! {
         spacing: 12,
         show_bg: true,
         draw_bg: {
-            color: #fffef0
+            color: (SOCIAL_WARNING_BG)
         }
 
         // Requester's avatar
@@ -140,7 +200,7 @@ live_design! {
                 text: "",
                 draw_text: {
                     text_style: { font_size: 14.0 },
-                    color: #000,
+                    color: (SOCIAL_TEXT_PRIMARY),
                 }
             }
 
@@ -150,7 +210,7 @@ live_design! {
                 text: "",
                 draw_text: {
                     text_style: { font_size: 12.0 },
-                    color: #666,
+                    color: (SOCIAL_TEXT_SECONDARY),
                 }
             }
 
@@ -160,7 +220,20 @@ live_design! {
                 text: "Wants to be your friend",
                 draw_text: {
                     text_style: { font_size: 11.0 },
-                    color: #996600,
+                    color: (SOCIAL_WARNING_TEXT),
+                }
+            }
+
+            // The intro message sent with the request, if any
+            message_label = <Label> {
+                width: Fill,
+                height: Fit,
+                visible: false,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: (SOCIAL_TEXT),
+                    wrap: Word,
                 }
             }
         }
@@ -178,11 +251,11 @@ live_design! {
                 height: 32,
                 text: "Accept",
                 draw_bg: {
-                    color: #1d9bf0,
+                    color: (SOCIAL_ACCENT),
                     radius: 16.0,
                 }
                 draw_text: {
-                    color: #fff,
+                    color: (SOCIAL_ON_ACCENT),
                     text_style: { font_size: 12.0 },
                 }
             }
@@ -192,11 +265,90 @@ live_design! {
                 height: 32,
                 text: "Decline",
                 draw_bg: {
-                    color: #f0f0f0,
+                    color: (SOCIAL_SURFACE),
+                    radius: 16.0,
+                }
+                draw_text: {
+                    color: (SOCIAL_TEXT_SECONDARY),
+                    text_style: { font_size: 12.0 },
+                }
+            }
+        }
+    }
+
+    /// Friend request sent by the user, waiting to be answered.
+    OutgoingRequestItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 12, bottom: 12 },
+        flow: Right,
+        spacing: 12,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_BG)
+        }
+
+        // Recipient's avatar
+        avatar = <Avatar> {
+            width: 48,
+            height: 48,
+        }
+
+        // Request info column
+        info_column = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 4,
+
+            name_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: (SOCIAL_TEXT_PRIMARY),
+                }
+            }
+
+            username_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                }
+            }
+
+            request_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Request sent",
+                draw_text: {
+                    text_style: { font_size: 11.0 },
+                    color: (SOCIAL_TEXT_MUTED),
+                }
+            }
+        }
+
+        // Cancel button
+        actions_row = <View> {
+            width: Fit,
+            height: Fit,
+            flow: Right,
+            align: { x: 1.0, y: 0.5 },
+
+            cancel_button = <Button> {
+                width: Fit,
+                height: 32,
+                text: "Cancel",
+                draw_bg: {
+                    color: (SOCIAL_SURFACE),
                     radius: 16.0,
                 }
                 draw_text: {
-                    color: #666,
+                    color: (SOCIAL_TEXT_SECONDARY),
                     text_style: { font_size: 12.0 },
                 }
             }
@@ -210,7 +362,7 @@ live_design! {
         padding: { left: 16, right: 16, top: 16, bottom: 8 },
         show_bg: true,
         draw_bg: {
-            color: #f8f8f8
+            color: (SOCIAL_SURFACE)
         }
 
         section_label = <Label> {
@@ -219,7 +371,7 @@ live_design! {
             text: "",
             draw_text: {
                 text_style: { font_size: 12.0 },
-                color: #666,
+                color: (SOCIAL_TEXT_SECONDARY),
             }
         }
     }
@@ -231,7 +383,7 @@ live_design! {
         flow: Down,
         show_bg: true,
         draw_bg: {
-            color: #fff
+            color: (SOCIAL_BG)
         }
 
         // Header
@@ -242,7 +394,7 @@ live_design! {
             flow: Right,
             show_bg: true,
             draw_bg: {
-                color: #fff
+                color: (SOCIAL_BG)
             }
 
             title_label = <Label> {
@@ -251,7 +403,7 @@ live_design! {
                 text: "Friends",
                 draw_text: {
                     text_style: { font_size: 20.0 },
-                    color: #000,
+                    color: (SOCIAL_TEXT_PRIMARY),
                 }
             }
 
@@ -260,11 +412,11 @@ live_design! {
                 height: Fit,
                 text: "Add Friend",
                 draw_bg: {
-                    color: #1d9bf0,
+                    color: (SOCIAL_ACCENT),
                     radius: 16.0,
                 }
                 draw_text: {
-                    color: #fff,
+                    color: (SOCIAL_ON_ACCENT),
                 }
             }
         }
@@ -275,61 +427,42 @@ live_design! {
             height: 1,
             show_bg: true,
             draw_bg: {
-                color: #eee
+                color: (SOCIAL_DIVIDER)
             }
         }
 
-        // Scrollable content
-        content = <View> {
+        // Scrollable content: pending requests, outgoing requests, then friends
+        content = <PortalList> {
             width: Fill,
             height: Fill,
             flow: Down,
 
-            // Pending requests section (shown when there are requests)
-            requests_section = <View> {
-                width: Fill,
-                height: Fit,
-                flow: Down,
-                visible: false,
-
-                requests_header = <FriendListSection> {
-                    section_label = {
-                        text: "Friend Requests"
-                    }
-                }
-
-                requests_list = <View> {
-                    width: Fill,
-                    height: Fit,
-                    flow: Down,
+            requests_header = <FriendListSection> {
+                section_label = {
+                    text: "Friend Requests"
                 }
             }
+            request_item = <FriendRequestItem> {}
 
-            // Friends section
-            friends_section = <View> {
-                width: Fill,
-                height: Fit,
-                flow: Down,
-
-                friends_header = <FriendListSection> {
-                    section_label = {
-                        text: "All Friends"
-                    }
+            outgoing_header = <FriendListSection> {
+                section_label = {
+                    text: "Outgoing Requests"
                 }
+            }
+            outgoing_item = <OutgoingRequestItem> {}
 
-                friends_list = <View> {
-                    width: Fill,
-                    height: Fit,
-                    flow: Down,
+            friends_header = <FriendListSection> {
+                section_label = {
+                    text: "All Friends"
                 }
             }
+            friend_item = <FriendItem> {}
 
             // Empty state
             empty_state = <View> {
                 width: Fill,
                 height: 200,
                 align: { x: 0.5, y: 0.5 },
-                visible: true,
 
                 empty_label = <Label> {
                     width: Fit,
@@ -337,11 +470,18 @@ live_design! {
                     text: "No friends yet. Add some friends to get started!",
                     draw_text: {
                         text_style: { font_size: 14.0 },
-                        color: #999,
+                        color: (SOCIAL_TEXT_MUTED),
                     }
                 }
             }
         }
+
+        // Confirmation for unfriending someone
+        unfriend_modal = <Modal> {
+            content: {
+                unfriend_modal_inner = <NegativeConfirmationModal> {}
+            }
+        }
     }
 }
 
@@ -352,10 +492,31 @@ pub struct FriendInfo {
     pub user_id: OwnedUserId,
     /// Display name
     pub display_name: Option<String>,
-    /// Online status (if available)
-    pub status: Option<String>,
+    /// Online status, once it's been fetched (see [`FriendListView::set_presence()`])
+    pub presence: Option<UserPresence>,
     /// Avatar image data
     pub avatar_data: Option<Arc<[u8]>>,
+    /// Avatar image URI, fetched if there's no avatar image data
+    pub avatar_url: Option<OwnedMxcUri>,
+}
+
+impl FriendInfo {
+    /// Get a friend's info from their friends-only feed,
+    /// naming them as they are in that feed.
+    pub async fn from_friend_feed(user_id: OwnedUserId, feed: &Room) -> Self {
+        let member = feed.get_member_no_sync(&user_id).await.ok().flatten();
+        Self {
+            display_name: member
+                .as_ref()
+                .and_then(|m| m.display_name().map(ToOwned::to_owned)),
+            avatar_url: member
+                .as_ref()
+                .and_then(|m| m.avatar_url().map(ToOwned::to_owned)),
+            user_id,
+            presence: None,
+            avatar_data: None,
+        }
+    }
 }
 
 /// Information about a pending friend request.
@@ -367,10 +528,48 @@ pub struct FriendRequestInfo {
     pub display_name: Option<String>,
     /// Avatar image data
     pub avatar_data: Option<Arc<[u8]>>,
+    /// Avatar image URI, fetched if there's no avatar image data
+    pub avatar_url: Option<OwnedMxcUri>,
     /// Request message (if any)
     pub message: Option<String>,
 }
 
+impl From<PendingFriendRequest> for FriendRequestInfo {
+    fn from(request: PendingFriendRequest) -> Self {
+        Self {
+            user_id: request.requester,
+            display_name: request.display_name,
+            avatar_data: None,
+            avatar_url: request.avatar_url.map(Into::into),
+            message: request.message,
+        }
+    }
+}
+
+/// Information about a friend request sent by the user.
+#[derive(Clone, Debug)]
+pub struct OutgoingRequestInfo {
+    /// The recipient's user ID
+    pub user_id: OwnedUserId,
+    /// The room the request was sent to (the recipient's friends-only feed)
+    pub room_id: OwnedRoomId,
+    /// Display name
+    pub display_name: Option<String>,
+    /// Avatar image URI
+    pub avatar_url: Option<OwnedMxcUri>,
+}
+
+impl From<OutgoingFriendRequest> for OutgoingRequestInfo {
+    fn from(request: OutgoingFriendRequest) -> Self {
+        Self {
+            user_id: request.target,
+            room_id: request.room_id,
+            display_name: request.display_name,
+            avatar_url: request.avatar_url,
+        }
+    }
+}
+
 /// Actions that can be triggered from the friend list.
 #[derive(Clone, Debug, DefaultNone)]
 pub enum FriendListAction {
@@ -380,16 +579,46 @@ pub enum FriendListAction {
     ViewProfile(OwnedUserId),
     /// User clicked to message a friend
     MessageFriend(OwnedUserId),
-    /// User clicked to remove a friend
+    /// User confirmed unfriending someone, and a [`MatrixRequest::Unfriend`] was sent.
     RemoveFriend(OwnedUserId),
-    /// User accepted a friend request
+    /// User accepted a friend request, and a [`MatrixRequest::AnswerFriendRequest`] was sent.
     AcceptRequest(OwnedUserId),
-    /// User declined a friend request
+    /// User declined a friend request, and a [`MatrixRequest::AnswerFriendRequest`] was sent.
     DeclineRequest(OwnedUserId),
+    /// User cancelled a friend request they sent,
+    /// and a [`MatrixRequest::CancelFriendRequest`] was sent.
+    CancelRequest {
+        user_id: OwnedUserId,
+        room_id: OwnedRoomId,
+    },
+    /// The current user's friends and friend requests were loaded
+    /// after a [`MatrixRequest::LoadFriendList`].
+    Loaded {
+        friends: Vec<FriendInfo>,
+        pending_requests: Vec<FriendRequestInfo>,
+        outgoing_requests: Vec<OutgoingRequestInfo>,
+    },
+    /// Loading the friend list failed.
+    Failed(String),
     /// No action
     None,
 }
 
+/// A row of the friend list.
+#[derive(Clone, Copy, Debug)]
+enum FriendListRow {
+    RequestsHeader,
+    /// The pending request at the given index.
+    Request(usize),
+    OutgoingHeader,
+    /// The outgoing request at the given index.
+    Outgoing(usize),
+    FriendsHeader,
+    /// The friend at the given index.
+    Friend(usize),
+    Empty,
+}
+
 #[derive(Live, LiveHook, Widget)]
 pub struct FriendListView {
     #[deref]
@@ -402,17 +631,173 @@ pub struct FriendListView {
     /// List of pending friend requests.
     #[rust]
     pending_requests: Vec<FriendRequestInfo>,
+
+    /// List of friend requests sent by the user.
+    #[rust]
+    outgoing_requests: Vec<OutgoingRequestInfo>,
+
+    /// The rows of the list, rebuilt whenever the friends or requests change.
+    #[rust]
+    rows: Vec<FriendListRow>,
+
+    /// Whether any avatar images drawn are still being fetched.
+    #[rust]
+    avatars_pending: bool,
+
+    /// Changes to the friend graph, to drop requests once they've been answered.
+    #[rust(subscribe_social_events(&[SocialTopic::FriendGraph]))]
+    friend_graph_changes: SocialSubscription,
 }
 
 impl Widget for FriendListView {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if let Event::Signal = event {
+            self.apply_friend_graph_changes(cx);
+            // Redraw to show avatar images once they've been fetched
+            if self.avatars_pending {
+                avatar_cache::process_avatar_updates(cx);
+                self.redraw(cx);
+            }
+        }
         self.view.handle_event(cx, event, scope);
         self.widget_match_event(cx, event, scope);
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
-        self.view.draw_walk(cx, scope, walk)
+        let mut avatars_pending = false;
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, self.rows.len().max(1));
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let row = self.rows.get(item_id).copied();
+                // The list is empty until the friends or requests are first set
+                let item = match row.unwrap_or(FriendListRow::Empty) {
+                    FriendListRow::RequestsHeader => {
+                        list.item(cx, item_id, live_id!(requests_header))
+                    }
+                    FriendListRow::Request(index) => {
+                        let request = &self.pending_requests[index];
+                        let item = list.item(cx, item_id, live_id!(request_item));
+                        let name = request
+                            .display_name
+                            .as_deref()
+                            .unwrap_or_else(|| request.user_id.localpart());
+                        avatars_pending |= !show_avatar(
+                            cx,
+                            &item.avatar(ids!(avatar)),
+                            name,
+                            request.avatar_data.as_ref(),
+                            request.avatar_url.as_deref(),
+                        );
+                        item.label(ids!(name_label)).set_text(cx, name);
+                        item.label(ids!(username_label))
+                            .set_text(cx, request.user_id.as_str());
+                        let message_label = item.label(ids!(message_label));
+                        message_label.set_visible(cx, request.message.is_some());
+                        if let Some(message) = &request.message {
+                            message_label.set_text(cx, &format!("“{message}”"));
+                        }
+                        item
+                    }
+                    FriendListRow::OutgoingHeader => {
+                        list.item(cx, item_id, live_id!(outgoing_header))
+                    }
+                    FriendListRow::Outgoing(index) => {
+                        let request = &self.outgoing_requests[index];
+                        let item = list.item(cx, item_id, live_id!(outgoing_item));
+                        let name = request
+                            .display_name
+                            .as_deref()
+                            .unwrap_or_else(|| request.user_id.localpart());
+                        avatars_pending |= !show_avatar(
+                            cx,
+                            &item.avatar(ids!(avatar)),
+                            name,
+                            None,
+                            request.avatar_url.as_deref(),
+                        );
+                        item.label(ids!(name_label)).set_text(cx, name);
+                        item.label(ids!(username_label))
+                            .set_text(cx, request.user_id.as_str());
+                        item
+                    }
+                    FriendListRow::FriendsHeader => {
+                        list.item(cx, item_id, live_id!(friends_header))
+                    }
+                    FriendListRow::Friend(index) => {
+                        let friend = &self.friends[index];
+                        let item = list.item(cx, item_id, live_id!(friend_item));
+                        let name = friend
+                            .display_name
+                            .as_deref()
+                            .unwrap_or_else(|| friend.user_id.localpart());
+                        avatars_pending |= !show_avatar(
+                            cx,
+                            &item.avatar(ids!(avatar)),
+                            name,
+                            friend.avatar_data.as_ref(),
+                            friend.avatar_url.as_deref(),
+                        );
+                        item.label(ids!(name_label)).set_text(cx, name);
+                        item.label(ids!(username_label))
+                            .set_text(cx, friend.user_id.as_str());
+                        let presence_dot = item.view(ids!(presence_dot));
+                        presence_dot.set_visible(cx, friend.presence.is_some());
+                        if let Some(presence) = &friend.presence {
+                            let (online, idle) = match presence.status {
+                                OnlineStatus::Online => (1.0, 0.0),
+                                OnlineStatus::Idle => (0.0, 1.0),
+                                OnlineStatus::Offline => (0.0, 0.0),
+                            };
+                            presence_dot.apply_over(
+                                cx,
+                                live! {
+                                    draw_bg: { online: (online), idle: (idle) }
+                                },
+                            );
+                        }
+                        let status = friend
+                            .presence
+                            .as_ref()
+                            .map(UserPresence::description)
+                            .unwrap_or_default();
+                        item.label(ids!(status_label)).set_text(cx, &status);
+                        item
+                    }
+                    FriendListRow::Empty => list.item(cx, item_id, live_id!(empty_state)),
+                };
+                item.draw_all(cx, scope);
+            }
+        }
+        self.avatars_pending = avatars_pending;
+        DrawStep::done()
+    }
+}
+
+/// Show the given avatar image data, or else the avatar image at the given URI,
+/// with the first letter of `name` as a placeholder while the image is being fetched.
+///
+/// Returns `false` if the image is still being fetched.
+fn show_avatar(
+    cx: &mut Cx,
+    avatar: &AvatarRef,
+    name: &str,
+    avatar_data: Option<&Arc<[u8]>>,
+    avatar_url: Option<&MxcUri>,
+) -> bool {
+    if let Some(data) = avatar_data {
+        let shown = avatar
+            .show_image(cx, None, |cx, img| utils::load_png_or_jpg(&img, cx, data))
+            .is_ok();
+        if shown {
+            return true;
+        }
     }
+    avatar.show_avatar_uri(cx, name, avatar_url)
 }
 
 impl WidgetMatchEvent for FriendListView {
@@ -423,27 +808,183 @@ impl WidgetMatchEvent for FriendListView {
             cx.action(FriendListAction::AddFriend);
         }
 
-        // Individual friend item actions would be handled here
-        // when we implement the dynamic list rendering
+        let content = self.portal_list(ids!(content));
+        for (item_id, item) in content.items_with_actions(actions) {
+            match self.rows.get(item_id) {
+                Some(FriendListRow::Request(index)) => {
+                    let user_id = self.pending_requests[*index].user_id.clone();
+                    if item.button(ids!(accept_button)).clicked(actions) {
+                        submit_async_request(MatrixRequest::AnswerFriendRequest {
+                            user_id: user_id.clone(),
+                            accept: true,
+                        });
+                        cx.action(FriendListAction::AcceptRequest(user_id));
+                    } else if item.button(ids!(decline_button)).clicked(actions) {
+                        submit_async_request(MatrixRequest::AnswerFriendRequest {
+                            user_id: user_id.clone(),
+                            accept: false,
+                        });
+                        cx.action(FriendListAction::DeclineRequest(user_id));
+                    } else if item.as_view().finger_up(actions).is_some() {
+                        cx.action(FriendListAction::ViewProfile(user_id));
+                    }
+                }
+                Some(FriendListRow::Outgoing(index)) => {
+                    let request = &self.outgoing_requests[*index];
+                    if item.button(ids!(cancel_button)).clicked(actions) {
+                        let room_id = request.room_id.clone();
+                        submit_async_request(MatrixRequest::CancelFriendRequest {
+                            room_id: room_id.clone(),
+                        });
+                        cx.action(FriendListAction::CancelRequest {
+                            user_id: request.user_id.clone(),
+                            room_id: room_id.clone(),
+                        });
+                        self.remove_outgoing_request(cx, &room_id);
+                    } else if item.as_view().finger_up(actions).is_some() {
+                        cx.action(FriendListAction::ViewProfile(request.user_id.clone()));
+                    }
+                }
+                Some(FriendListRow::Friend(index)) => {
+                    let friend = &self.friends[*index];
+                    let user_id = friend.user_id.clone();
+                    if item.button(ids!(message_button)).clicked(actions) {
+                        cx.action(FriendListAction::MessageFriend(user_id));
+                    } else if item.button(ids!(remove_button)).clicked(actions) {
+                        let name = friend
+                            .display_name
+                            .clone()
+                            .unwrap_or_else(|| user_id.to_string());
+                        self.confirm_unfriend(
+                            cx,
+                            ConfirmationModalContent {
+                                title_text: "Unfriend".into(),
+                                body_text: format!(
+                                    "Unfriend {name}? You will leave each other's friends feeds \
+                                    and no longer see each other's friends-only posts."
+                                )
+                                .into(),
+                                accept_button_text: Some("Unfriend".into()),
+                                on_accept_clicked: Some(Box::new(move |cx| {
+                                    submit_async_request(MatrixRequest::Unfriend(user_id.clone()));
+                                    cx.action(FriendListAction::RemoveFriend(user_id));
+                                })),
+                                ..Default::default()
+                            },
+                        );
+                    } else if item.as_view().finger_up(actions).is_some() {
+                        cx.action(FriendListAction::ViewProfile(user_id));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if self
+            .confirmation_modal(ids!(unfriend_modal_inner))
+            .closed(actions)
+            .is_some()
+        {
+            self.modal(ids!(unfriend_modal)).close(cx);
+        }
+
+        for action in actions {
+            if let Some(FriendListAction::RemoveFriend(user_id)) = action.downcast_ref() {
+                self.remove_friend(cx, user_id);
+            }
+            if let Some(PresenceAction::Loaded { user_id, presence }) = action.downcast_ref() {
+                self.set_presence(cx, user_id, presence.clone());
+            }
+            // Keep presence and "last active" times up to date
+            if let Some(SocialTickerAction::Tick) = action.downcast_ref() {
+                self.load_presence();
+                self.redraw(cx);
+            }
+        }
     }
 }
 
 impl FriendListView {
+    /// Show a confirmation dialog before unfriending someone.
+    fn confirm_unfriend(&mut self, cx: &mut Cx, content: ConfirmationModalContent) {
+        self.confirmation_modal(ids!(unfriend_modal_inner))
+            .show(cx, content);
+        self.modal(ids!(unfriend_modal)).open(cx);
+    }
+
     /// Set the list of friends to display.
+    ///
+    /// Blocked users are left out.
     pub fn set_friends(&mut self, cx: &mut Cx, friends: Vec<FriendInfo>) {
         self.friends = friends;
-        self.update_display(cx);
+        self.remove_blocked(cx);
+        self.load_presence();
+    }
+
+    /// Fetch the presence of the listed friends in the background.
+    ///
+    /// It's shown once it arrives, see [`Self::set_presence()`].
+    fn load_presence(&self) {
+        if self.friends.is_empty() {
+            return;
+        }
+        let user_ids = self
+            .friends
+            .iter()
+            .map(|friend| friend.user_id.clone())
+            .collect();
+        submit_async_request(MatrixRequest::LoadPresence(user_ids));
+    }
+
+    /// Show a friend's presence.
+    pub fn set_presence(&mut self, cx: &mut Cx, user_id: &UserId, presence: UserPresence) {
+        let Some(friend) = self
+            .friends
+            .iter_mut()
+            .find(|friend| friend.user_id == user_id)
+        else {
+            return;
+        };
+        friend.presence = Some(presence);
+        self.redraw(cx);
     }
 
     /// Set the list of pending friend requests.
+    ///
+    /// Requests from blocked users are left out.
     pub fn set_pending_requests(&mut self, cx: &mut Cx, requests: Vec<FriendRequestInfo>) {
         self.pending_requests = requests;
+        self.remove_blocked(cx);
+    }
+
+    /// Set the list of friend requests sent by the user.
+    pub fn set_outgoing_requests(&mut self, cx: &mut Cx, requests: Vec<OutgoingRequestInfo>) {
+        self.outgoing_requests = requests;
         self.update_display(cx);
     }
 
-    /// Add a friend to the list.
+    /// Remove a friend request sent by the user, e.g., once it has been cancelled.
+    pub fn remove_outgoing_request(&mut self, cx: &mut Cx, room_id: &RoomId) {
+        self.outgoing_requests
+            .retain(|request| request.room_id != room_id);
+        self.update_display(cx);
+    }
+
+    /// Add a friend to the list, unless they are blocked.
     pub fn add_friend(&mut self, cx: &mut Cx, friend: FriendInfo) {
-        self.friends.push(friend);
+        if !is_blocked(&friend.user_id) {
+            self.friends.push(friend);
+        }
+        self.update_display(cx);
+    }
+
+    /// Remove blocked users from the friends and requests lists.
+    ///
+    /// This should be called whenever the block list changes.
+    pub fn remove_blocked(&mut self, cx: &mut Cx) {
+        self.friends.retain(|f| !is_blocked(&f.user_id));
+        self.pending_requests.retain(|r| !is_blocked(&r.user_id));
+        self.outgoing_requests.retain(|r| !is_blocked(&r.user_id));
         self.update_display(cx);
     }
 
@@ -457,30 +998,50 @@ impl FriendListView {
     pub fn clear(&mut self, cx: &mut Cx) {
         self.friends.clear();
         self.pending_requests.clear();
+        self.outgoing_requests.clear();
         self.update_display(cx);
     }
 
-    /// Update the display based on current data.
-    fn update_display(&mut self, cx: &mut Cx) {
-        let has_requests = !self.pending_requests.is_empty();
-        let has_friends = !self.friends.is_empty();
-
-        // Show/hide requests section
-        self.view(ids!(requests_section))
-            .set_visible(cx, has_requests);
-
-        // Show/hide empty state
-        self.view(ids!(empty_state))
-            .set_visible(cx, !has_friends && !has_requests);
+    /// Drop the pending requests that were accepted or declined.
+    fn apply_friend_graph_changes(&mut self, cx: &mut Cx) {
+        let answered: Vec<OwnedUserId> = self
+            .friend_graph_changes
+            .drain()
+            .into_iter()
+            .filter_map(|event| match event {
+                SocialEvent::FriendRequestAccepted(user_id)
+                | SocialEvent::FriendRequestDeclined(user_id) => Some(user_id),
+                _ => None,
+            })
+            .collect();
+        if answered.is_empty() {
+            return;
+        }
+        self.pending_requests
+            .retain(|request| !answered.contains(&request.user_id));
+        self.update_display(cx);
+    }
 
-        // Note: Dynamic list item creation requires PortalList or similar
-        // For now, we update the visibility based on data state
-        // Full implementation would:
-        // 1. Clear existing list items
-        // 2. Create FriendItem widgets for each friend
-        // 3. Create FriendRequestItem widgets for each pending request
-        // 4. Update section headers with counts
-        let _ = has_friends;
+    /// Rebuild the rows of the list from the current data.
+    fn update_display(&mut self, cx: &mut Cx) {
+        let mut rows = Vec::new();
+        if !self.pending_requests.is_empty() {
+            rows.push(FriendListRow::RequestsHeader);
+            rows.extend((0..self.pending_requests.len()).map(FriendListRow::Request));
+        }
+        if !self.outgoing_requests.is_empty() {
+            rows.push(FriendListRow::OutgoingHeader);
+            rows.extend((0..self.outgoing_requests.len()).map(FriendListRow::Outgoing));
+        }
+        if !self.friends.is_empty() {
+            rows.push(FriendListRow::FriendsHeader);
+            rows.extend((0..self.friends.len()).map(FriendListRow::Friend));
+        }
+        if rows.is_empty() {
+            rows.push(FriendListRow::Empty);
+        }
+        self.rows = rows;
+        self.redraw(cx);
     }
 
     /// Get the number of friends.
@@ -492,6 +1053,11 @@ impl FriendListView {
     pub fn pending_request_count(&self) -> usize {
         self.pending_requests.len()
     }
+
+    /// Get the number of friend requests sent by the user.
+    pub fn outgoing_request_count(&self) -> usize {
+        self.outgoing_requests.len()
+    }
 }
 
 impl FriendListViewRef {
@@ -502,6 +1068,13 @@ impl FriendListViewRef {
         }
     }
 
+    /// See [`FriendListView::set_presence()`].
+    pub fn set_presence(&self, cx: &mut Cx, user_id: &UserId, presence: UserPresence) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_presence(cx, user_id, presence);
+        }
+    }
+
     /// See [`FriendListView::set_pending_requests()`].
     pub fn set_pending_requests(&self, cx: &mut Cx, requests: Vec<FriendRequestInfo>) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -509,6 +1082,20 @@ impl FriendListViewRef {
         }
     }
 
+    /// See [`FriendListView::set_outgoing_requests()`].
+    pub fn set_outgoing_requests(&self, cx: &mut Cx, requests: Vec<OutgoingRequestInfo>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_outgoing_requests(cx, requests);
+        }
+    }
+
+    /// See [`FriendListView::remove_outgoing_request()`].
+    pub fn remove_outgoing_request(&self, cx: &mut Cx, room_id: &RoomId) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.remove_outgoing_request(cx, room_id);
+        }
+    }
+
     /// See [`FriendListView::add_friend()`].
     pub fn add_friend(&self, cx: &mut Cx, friend: FriendInfo) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -523,6 +1110,13 @@ impl FriendListViewRef {
         }
     }
 
+    /// See [`FriendListView::remove_blocked()`].
+    pub fn remove_blocked(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.remove_blocked(cx);
+        }
+    }
+
     /// See [`FriendListView::clear()`].
     pub fn clear(&self, cx: &mut Cx) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -0,0 +1,532 @@
+//! Full-screen presentation mode for a feed.
+//!
+//! This widget shows one post at a time in large type, advancing
+//! automatically like a slideshow. It is meant for a lobby display or a
+//! party screen showing an event's photos, so it needs no keyboard: it can
+//! be driven by a remote's arrow, select, and back keys, or by the large
+//! on-screen buttons.
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::{MilliSecondsSinceUnixEpoch, OwnedUserId};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::shared::avatar::AvatarWidgetExt;
+use crate::social::events::checkin::CheckIn;
+use crate::social::widgets::post_card::PostCardData;
+use crate::utils;
+
+/// How long the on-screen controls stay visible after the last input, in seconds.
+const SHOW_CONTROLS_DURATION: f64 = 4.0;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use crate::shared::avatar::Avatar;
+
+    KIOSK_BG_COLOR = #000
+    KIOSK_TEXT_COLOR = #fff
+    KIOSK_MUTED_COLOR = #aaa
+
+    /// A large, remote-friendly control button.
+    KioskButton = <Button> {
+        width: Fit,
+        height: Fit,
+        padding: { top: 16, bottom: 16, left: 28, right: 28 },
+        text: "",
+        draw_bg: {
+            color: #ffffff22,
+            radius: 8.0,
+        }
+        draw_text: {
+            text_style: { font_size: 20.0 },
+            color: (KIOSK_TEXT_COLOR),
+        }
+    }
+
+    /// Full-screen, auto-advancing presentation of a feed.
+    pub SocialKioskView = {{SocialKioskView}} {
+        width: Fill,
+        height: Fill,
+        flow: Overlay,
+        show_bg: true,
+        draw_bg: {
+            color: (KIOSK_BG_COLOR)
+        }
+
+        slide = <View> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+            padding: 48,
+            spacing: 24,
+            align: { x: 0.5, y: 0.5 },
+
+            slide_media = <Image> {
+                width: Fill,
+                height: Fill,
+                fit: Smallest,
+                visible: false,
+            }
+
+            slide_text = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 32.0 },
+                    color: (KIOSK_TEXT_COLOR),
+                    wrap: Word,
+                }
+            }
+
+            author_row = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 16,
+                align: { y: 0.5 },
+
+                author_avatar = <Avatar> {
+                    width: 64,
+                    height: 64,
+                }
+
+                author_info = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Down,
+                    spacing: 4,
+
+                    author_name = <Label> {
+                        width: Fill,
+                        height: Fit,
+                        text: "",
+                        draw_text: {
+                            text_style: { font_size: 22.0 },
+                            color: (KIOSK_TEXT_COLOR),
+                        }
+                    }
+
+                    timestamp_label = <Label> {
+                        width: Fill,
+                        height: Fit,
+                        text: "",
+                        draw_text: {
+                            text_style: { font_size: 16.0 },
+                            color: (KIOSK_MUTED_COLOR),
+                        }
+                    }
+                }
+
+                position_label = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 16.0 },
+                        color: (KIOSK_MUTED_COLOR),
+                    }
+                }
+            }
+        }
+
+        empty_state = <View> {
+            width: Fill,
+            height: Fill,
+            align: { x: 0.5, y: 0.5 },
+            visible: false,
+
+            <Label> {
+                width: Fit,
+                height: Fit,
+                text: "Waiting for posts…",
+                draw_text: {
+                    text_style: { font_size: 28.0 },
+                    color: (KIOSK_MUTED_COLOR),
+                }
+            }
+        }
+
+        // On-screen controls, hidden after a few seconds without input
+        controls_overlay = <View> {
+            width: Fill,
+            height: Fill,
+            padding: { bottom: 32 },
+            align: { x: 0.5, y: 1.0 },
+
+            controls = <View> {
+                width: Fit,
+                height: Fit,
+                flow: Right,
+                spacing: 16,
+                padding: 12,
+                show_bg: true,
+                draw_bg: {
+                    color: #000000aa,
+                    radius: 12.0,
+                }
+
+                previous_button = <KioskButton> { text: "◀ Back" }
+                pause_button = <KioskButton> { text: "Pause" }
+                next_button = <KioskButton> { text: "Next ▶" }
+                exit_button = <KioskButton> { text: "Exit" }
+            }
+        }
+    }
+}
+
+/// A single slide in the presentation.
+#[derive(Clone, Debug)]
+pub struct KioskSlide {
+    /// The user who posted this.
+    pub author_id: OwnedUserId,
+    /// The author's display name, if known.
+    pub author_name: Option<String>,
+    /// Text of the post, or the caption of its media.
+    pub text: String,
+    /// MXC URI of the post's image, if any.
+    pub media_url: Option<String>,
+    /// When this was posted.
+    pub timestamp: MilliSecondsSinceUnixEpoch,
+}
+
+impl From<&PostCardData> for KioskSlide {
+    fn from(post: &PostCardData) -> Self {
+        Self {
+            author_id: post.author_id.clone(),
+            author_name: post.author_name.clone(),
+            text: post.text.clone(),
+            media_url: post.media_url.clone(),
+            timestamp: post.timestamp,
+        }
+    }
+}
+
+impl From<&CheckIn> for KioskSlide {
+    fn from(checkin: &CheckIn) -> Self {
+        Self {
+            author_id: checkin.user_id.clone(),
+            author_name: None,
+            text: checkin.message.clone().unwrap_or_default(),
+            media_url: checkin.photo.as_ref().map(|uri| uri.to_string()),
+            timestamp: checkin.timestamp,
+        }
+    }
+}
+
+/// Settings for a presentation.
+#[derive(Clone, Copy, Debug)]
+pub struct KioskSettings {
+    /// How long each slide is shown before advancing, in seconds.
+    pub slide_duration_secs: f64,
+    /// Only show slides with media, e.g., for an event's photo album.
+    pub media_only: bool,
+}
+
+impl Default for KioskSettings {
+    fn default() -> Self {
+        Self {
+            slide_duration_secs: 8.0,
+            media_only: false,
+        }
+    }
+}
+
+/// Actions emitted by the kiosk view.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialKioskAction {
+    /// The image of an upcoming slide should be downloaded and passed to
+    /// [`SocialKioskView::set_media()`].
+    FetchMedia(String),
+    /// The presentation wrapped around to the first slide;
+    /// new posts can be added with [`SocialKioskView::add_slides()`].
+    Refresh,
+    /// User wants to leave the presentation.
+    Exit,
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialKioskView {
+    #[deref]
+    view: View,
+
+    /// Slides in presentation order.
+    #[rust]
+    slides: Vec<KioskSlide>,
+
+    /// Index of the slide being shown.
+    #[rust]
+    current: usize,
+
+    /// Downloaded images, keyed by their MXC URI.
+    #[rust]
+    media: HashMap<String, Arc<[u8]>>,
+
+    /// Images that were requested with [`SocialKioskAction::FetchMedia`].
+    #[rust]
+    requested_media: HashSet<String>,
+
+    #[rust]
+    settings: KioskSettings,
+
+    /// Whether a presentation is running.
+    #[rust]
+    running: bool,
+
+    /// Whether auto-advancing is paused.
+    #[rust]
+    paused: bool,
+
+    #[rust]
+    advance_timer: Timer,
+
+    #[rust]
+    hide_controls_timer: Timer,
+}
+
+impl Widget for SocialKioskView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        if !self.running {
+            return;
+        }
+        self.widget_match_event(cx, event, scope);
+
+        if self.advance_timer.is_event(event).is_some() {
+            self.advance(cx, true);
+        }
+        if self.hide_controls_timer.is_event(event).is_some() {
+            self.view(ids!(controls_overlay)).set_visible(cx, false);
+            self.redraw(cx);
+        }
+
+        if event.back_pressed() {
+            self.exit(cx);
+            return;
+        }
+        match event {
+            Event::KeyDown(KeyEvent { key_code, .. }) => {
+                match key_code {
+                    KeyCode::ArrowRight | KeyCode::ArrowDown => self.advance(cx, true),
+                    KeyCode::ArrowLeft | KeyCode::ArrowUp => self.advance(cx, false),
+                    KeyCode::Space | KeyCode::ReturnKey => self.set_paused(cx, !self.paused),
+                    KeyCode::Escape => {
+                        self.exit(cx);
+                        return;
+                    }
+                    _ => {}
+                }
+                self.show_controls(cx);
+            }
+            Event::MouseMove(_) | Event::TouchUpdate(_) => self.show_controls(cx),
+            _ => {}
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialKioskView {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.button(ids!(previous_button)).clicked(actions) {
+            self.advance(cx, false);
+        }
+        if self.button(ids!(next_button)).clicked(actions) {
+            self.advance(cx, true);
+        }
+        if self.button(ids!(pause_button)).clicked(actions) {
+            self.set_paused(cx, !self.paused);
+        }
+        if self.button(ids!(exit_button)).clicked(actions) {
+            self.exit(cx);
+        }
+    }
+}
+
+impl SocialKioskView {
+    /// Start presenting the given slides from the first one.
+    ///
+    /// For a feed, slides can be created from its posts; for an event's
+    /// photo album, from its check-ins with [`KioskSettings::media_only`] set.
+    pub fn start(&mut self, cx: &mut Cx, slides: Vec<KioskSlide>, settings: KioskSettings) {
+        self.settings = settings;
+        self.slides.clear();
+        self.media.clear();
+        self.requested_media.clear();
+        self.current = 0;
+        self.running = true;
+        self.paused = false;
+        self.button(ids!(pause_button)).set_text(cx, "Pause");
+        self.add_slides(cx, slides);
+        self.show_current(cx);
+        self.show_controls(cx);
+        cx.set_key_focus(self.view.area());
+    }
+
+    /// Add new slides at the end of the presentation, e.g., photos
+    /// posted while it is running. The current slide is kept.
+    pub fn add_slides(&mut self, cx: &mut Cx, slides: Vec<KioskSlide>) {
+        let was_empty = self.slides.is_empty();
+        let media_only = self.settings.media_only;
+        self.slides.extend(
+            slides
+                .into_iter()
+                .filter(|s| !media_only || s.media_url.is_some()),
+        );
+        if was_empty && self.running {
+            self.show_current(cx);
+        }
+    }
+
+    /// Provide the downloaded image for a slide's media URI.
+    pub fn set_media(&mut self, cx: &mut Cx, media_url: &str, data: Arc<[u8]>) {
+        self.media.insert(media_url.to_string(), data);
+        let is_current = self
+            .slides
+            .get(self.current)
+            .is_some_and(|s| s.media_url.as_deref() == Some(media_url));
+        if is_current {
+            self.show_current(cx);
+        }
+    }
+
+    /// Stop the presentation.
+    pub fn stop(&mut self, cx: &mut Cx) {
+        self.running = false;
+        cx.stop_timer(self.advance_timer);
+        cx.stop_timer(self.hide_controls_timer);
+        self.slides.clear();
+        self.media.clear();
+        self.requested_media.clear();
+    }
+
+    fn exit(&mut self, cx: &mut Cx) {
+        self.stop(cx);
+        cx.action(SocialKioskAction::Exit);
+    }
+
+    /// Move to the next or previous slide, wrapping around at either end.
+    fn advance(&mut self, cx: &mut Cx, forward: bool) {
+        let count = self.slides.len();
+        if count > 0 {
+            self.current = if forward {
+                (self.current + 1) % count
+            } else {
+                (self.current + count - 1) % count
+            };
+            if forward && self.current == 0 {
+                cx.action(SocialKioskAction::Refresh);
+            }
+        }
+        self.show_current(cx);
+    }
+
+    fn set_paused(&mut self, cx: &mut Cx, paused: bool) {
+        self.paused = paused;
+        self.button(ids!(pause_button))
+            .set_text(cx, if paused { "Play" } else { "Pause" });
+        self.restart_advance_timer(cx);
+    }
+
+    fn restart_advance_timer(&mut self, cx: &mut Cx) {
+        cx.stop_timer(self.advance_timer);
+        if self.running && !self.paused && self.slides.len() > 1 {
+            self.advance_timer = cx.start_timeout(self.settings.slide_duration_secs);
+        }
+    }
+
+    fn show_controls(&mut self, cx: &mut Cx) {
+        self.view(ids!(controls_overlay)).set_visible(cx, true);
+        cx.stop_timer(self.hide_controls_timer);
+        self.hide_controls_timer = cx.start_timeout(SHOW_CONTROLS_DURATION);
+        self.redraw(cx);
+    }
+
+    /// Display the current slide and request the media of the next one.
+    fn show_current(&mut self, cx: &mut Cx) {
+        self.restart_advance_timer(cx);
+        let Some(slide) = self.slides.get(self.current).cloned() else {
+            self.view(ids!(slide)).set_visible(cx, false);
+            self.view(ids!(empty_state)).set_visible(cx, true);
+            self.redraw(cx);
+            return;
+        };
+        self.view(ids!(empty_state)).set_visible(cx, false);
+        self.view(ids!(slide)).set_visible(cx, true);
+
+        let slide_media = self.image(ids!(slide_media));
+        let media_shown = slide
+            .media_url
+            .as_ref()
+            .and_then(|url| self.media.get(url))
+            .is_some_and(|data| utils::load_png_or_jpg(&slide_media, cx, data).is_ok());
+        slide_media.set_visible(cx, media_shown);
+
+        let slide_text = self.label(ids!(slide_text));
+        slide_text.set_text(cx, &slide.text);
+        slide_text.set_visible(cx, !slide.text.is_empty());
+
+        let name = slide
+            .author_name
+            .clone()
+            .unwrap_or_else(|| slide.author_id.localpart().to_string());
+        self.avatar(ids!(author_avatar)).set_text(cx, &name);
+        self.label(ids!(author_name)).set_text(cx, &name);
+        self.label(ids!(timestamp_label)).set_text(
+            cx,
+            &utils::relative_format(slide.timestamp).unwrap_or_default(),
+        );
+        self.label(ids!(position_label))
+            .set_text(cx, &format!("{} / {}", self.current + 1, self.slides.len()));
+
+        // Fetch the media of this and the next slide ahead of time
+        let next = (self.current + 1) % self.slides.len();
+        for index in [self.current, next] {
+            let Some(url) = self.slides[index].media_url.clone() else {
+                continue;
+            };
+            if !self.media.contains_key(&url) && self.requested_media.insert(url.clone()) {
+                cx.action(SocialKioskAction::FetchMedia(url));
+            }
+        }
+        self.redraw(cx);
+    }
+}
+
+impl SocialKioskViewRef {
+    /// See [`SocialKioskView::start()`].
+    pub fn start(&self, cx: &mut Cx, slides: Vec<KioskSlide>, settings: KioskSettings) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.start(cx, slides, settings);
+        }
+    }
+
+    /// See [`SocialKioskView::add_slides()`].
+    pub fn add_slides(&self, cx: &mut Cx, slides: Vec<KioskSlide>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.add_slides(cx, slides);
+        }
+    }
+
+    /// See [`SocialKioskView::set_media()`].
+    pub fn set_media(&self, cx: &mut Cx, media_url: &str, data: Arc<[u8]>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_media(cx, media_url, data);
+        }
+    }
+
+    /// See [`SocialKioskView::stop()`].
+    pub fn stop(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.stop(cx);
+        }
+    }
+}
@@ -0,0 +1,474 @@
+//! The page showing the main newsfeed.
+//!
+//! It's opened from the newsfeed button of the navigation tab bar, and shows
+//! the posts of all joined feeds in a [`SocialFeedView`]
+//! (see [`crate::social::main_feed`]), below the composer the user posts to
+//! their own feeds with. The newsfeed is loaded again each time
+//! the page is shown, so that changes to the newsfeed filter take effect.
+//! Until it's first loaded, the posts cached since the last time are shown.
+//! Friends' birthdays in the coming week and friends of friends the user
+//! may know are shown among the posts.
+//! Tapping a link in a post opens it in the browser, tapping a mention or
+//! an author opens their profile, and tapping a hashtag narrows the newsfeed
+//! down to the posts with it. Hiding a post's language adds it to the
+//! newsfeed filter, removing the posts in that language right away.
+//! Watching a post for new comments is saved in the user's watch list.
+//!
+//! Presenting the newsfeed shows its posts in a [`SocialKioskView`] over the
+//! page, which gets the posts sent since as it wraps around to its first slide.
+//!
+//! The Discover button above the posts, or "See all" on the suggestions card,
+//! opens a [`SocialDiscoverView`] in place of the newsfeed, to find people
+//! through the user directory and friends of friends.
+//! Its Trending tab lists the posts and hashtags trending in the user's
+//! public feeds. Trending posts are opened in their feed room's timeline,
+//! and trending hashtags narrow the newsfeed down to the posts with them.
+//!
+//! [`SocialKioskView`]: crate::social::widgets::kiosk_view::SocialKioskView
+//! [`SocialDiscoverView`]: crate::social::widgets::discover_view::SocialDiscoverView
+
+use makepad_widgets::*;
+use matrix_sdk::media::MediaFormat;
+use matrix_sdk::ruma::{MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedMxcUri};
+
+use crate::app::AppStateAction;
+use crate::home::navigation_tab_bar::NavigationBarAction;
+use crate::media_cache::{MediaCache, MediaCacheEntry};
+use crate::room::BasicRoomDetails;
+use crate::shared::popup_list::{enqueue_popup_notification, PopupItem, PopupKind};
+use crate::sliding_sync::{current_user_id, submit_async_request, MatrixRequest};
+use crate::social::discovery::{FriendSuggestionsAction, TrendingAction, UserSearchAction};
+use crate::social::main_feed::{MainFeedAction, MAX_MAIN_FEED_POSTS};
+use crate::social::newsfeed::{feed_filter, feed_grouping, BirthdaysAction};
+use crate::social::post_watch::{unwatch_post, watch_post};
+use crate::social::widgets::discover_view::{SocialDiscoverAction, SocialDiscoverViewWidgetExt};
+use crate::social::widgets::feed_view::{FeedState, SocialFeedViewAction, SocialFeedViewWidgetExt};
+use crate::social::widgets::kiosk_view::{
+    KioskSettings, KioskSlide, SocialKioskAction, SocialKioskViewWidgetExt,
+};
+use crate::social::widgets::people_suggestions_card::SocialPeopleSuggestionsAction;
+use crate::social::widgets::post_card::SocialPostCardAction;
+use crate::social::widgets::profile_page::SocialProfileAction;
+use crate::social::widgets::trending_view::SocialTrendingAction;
+use crate::utils::RoomNameId;
+
+live_design! {
+    link social_enabled
+
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use link::social_theme::*;
+
+    use crate::social::widgets::discover_view::SocialDiscoverView;
+    use crate::social::widgets::feed_view::SocialFeedView;
+    use crate::social::widgets::kiosk_view::SocialKioskView;
+
+    /// Page with the main newsfeed.
+    pub SocialMainFeedPage = {{SocialMainFeedPage}} {
+        width: Fill,
+        height: Fill,
+        flow: Overlay,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_BG)
+        }
+
+        main_feed = <SocialFeedView> {
+            feed_toolbar = {
+                discover_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Discover",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (SOCIAL_ACCENT),
+                    }
+                }
+            }
+        }
+
+        // Shown in place of the newsfeed, see `show_discover()`
+        discover_page = <View> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+            visible: false,
+            show_bg: true,
+            draw_bg: {
+                color: (SOCIAL_BG)
+            }
+
+            close_discover_button = <Button> {
+                width: Fit,
+                height: Fit,
+                margin: { left: 8, top: 4, bottom: 4 },
+                text: "← Newsfeed",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (SOCIAL_ACCENT),
+                }
+            }
+
+            discover_view = <SocialDiscoverView> {}
+        }
+
+        // The newsfeed presented one post at a time, see `present()`
+        kiosk_view = <SocialKioskView> {
+            visible: false,
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialMainFeedPage {
+    #[deref]
+    view: View,
+
+    /// The number of posts last requested, while they're loading.
+    #[rust]
+    loading_limit: Option<usize>,
+
+    /// When the newest post presented was sent, while the newsfeed is presented.
+    #[rust]
+    presented_until: Option<MilliSecondsSinceUnixEpoch>,
+
+    /// Images of the presented posts that are being fetched, by their MXC URI.
+    #[rust]
+    pending_media: Vec<String>,
+
+    #[rust(MediaCache::new(None))]
+    media_cache: MediaCache,
+}
+
+impl Widget for SocialMainFeedPage {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        // The media cache signals the UI when a fetch completes
+        if let Event::Signal = event {
+            if !self.pending_media.is_empty() {
+                self.fetch_presented_media(cx);
+            }
+        }
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialMainFeedPage {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        let feed = self.social_feed_view(ids!(main_feed));
+        if self.button(ids!(discover_button)).clicked(actions) {
+            self.show_discover(cx, true);
+        }
+        if self.button(ids!(close_discover_button)).clicked(actions) {
+            self.show_discover(cx, false);
+        }
+
+        let discover_view = self.social_discover_view(ids!(discover_view));
+        for action in actions {
+            match action.downcast_ref() {
+                Some(SocialFeedViewAction::Refresh) => self.show(cx),
+                Some(SocialFeedViewAction::LoadMore) => {
+                    let limit = feed.post_count() + MAX_MAIN_FEED_POSTS;
+                    self.loading_limit = Some(limit);
+                    submit_async_request(MatrixRequest::LoadMainFeed { limit });
+                }
+                Some(SocialFeedViewAction::PostAction(post_action)) => {
+                    self.handle_post_action(cx, post_action);
+                }
+                Some(SocialFeedViewAction::Present(slides)) => self.present(cx, slides.clone()),
+                Some(SocialFeedViewAction::GroupingChanged(grouping)) => {
+                    submit_async_request(MatrixRequest::SaveFeedGrouping(grouping.clone()));
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(SocialKioskAction::FetchMedia(media_url)) => {
+                    self.pending_media.push(media_url.clone());
+                    self.fetch_presented_media(cx);
+                }
+                // Load the newsfeed again to present the posts sent since
+                Some(SocialKioskAction::Refresh) => self.show(cx),
+                Some(SocialKioskAction::Exit) => {
+                    self.presented_until = None;
+                    self.pending_media.clear();
+                    self.view(ids!(kiosk_view)).set_visible(cx, false);
+                    self.redraw(cx);
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                // Cached posts are only shown until the first load completes
+                Some(MainFeedAction::CachedLoaded { limit, posts })
+                    if self.loading_limit == Some(*limit) && feed.post_count() == 0 =>
+                {
+                    feed.set_posts(cx, posts.clone());
+                    self.redraw(cx);
+                }
+                Some(MainFeedAction::Loaded { limit, posts })
+                    if self.loading_limit == Some(*limit) =>
+                {
+                    self.loading_limit = None;
+                    if *limit > MAX_MAIN_FEED_POSTS {
+                        feed.append_posts(cx, posts.clone());
+                    } else {
+                        feed.set_posts(cx, posts.clone());
+                    }
+                    if let Some(presented_until) = self.presented_until {
+                        let new_slides: Vec<KioskSlide> = posts
+                            .iter()
+                            .filter(|post| post.timestamp > presented_until)
+                            .map(KioskSlide::from)
+                            .collect();
+                        self.add_presented_slides(cx, new_slides);
+                    }
+                    self.redraw(cx);
+                }
+                Some(MainFeedAction::ReadMarkersLoaded(markers)) => {
+                    feed.set_read_markers(cx, markers.clone());
+                }
+                Some(MainFeedAction::DiagnosticsLoaded(diagnostics)) => {
+                    feed.set_diagnostics(cx, diagnostics.as_ref());
+                }
+                Some(MainFeedAction::CapabilitiesLoaded(capabilities)) => {
+                    feed.set_capabilities(cx, capabilities);
+                }
+                Some(MainFeedAction::Failed(error)) if self.loading_limit.is_some() => {
+                    self.loading_limit = None;
+                    // Keep showing the posts if only loading older ones failed
+                    if feed.post_count() > 0 {
+                        feed.set_state(cx, FeedState::Loaded);
+                        enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to load the newsfeed: {error}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        });
+                    } else {
+                        feed.set_state(cx, FeedState::Error);
+                    }
+                }
+                _ => {}
+            }
+
+            if let Some(BirthdaysAction::Loaded(birthdays)) = action.downcast_ref() {
+                feed.set_birthdays(cx, birthdays.clone());
+            }
+            if let Some(FriendSuggestionsAction::Loaded(suggestions)) = action.downcast_ref() {
+                feed.set_friend_suggestions(cx, suggestions.clone());
+                discover_view.set_suggestions(cx, suggestions);
+            }
+            if let Some(UserSearchAction::Loaded { query, results }) = action.downcast_ref() {
+                discover_view.set_search_results(cx, query, results);
+            }
+            if let Some(TrendingAction::Loaded(trending)) = action.downcast_ref() {
+                discover_view.set_trending(cx, trending);
+            }
+            if let Some(SocialProfileAction::FollowChanged { user_id, following }) =
+                action.downcast_ref()
+            {
+                discover_view.set_followed(cx, user_id, *following);
+            }
+
+            match action.downcast_ref() {
+                Some(SocialPeopleSuggestionsAction::OpenProfile(user_id)) => {
+                    cx.action(NavigationBarAction::GoToSocialProfile {
+                        user_id: user_id.clone(),
+                    });
+                }
+                Some(SocialPeopleSuggestionsAction::Follow(user_id)) => {
+                    submit_async_request(MatrixRequest::Follow(user_id.clone()));
+                }
+                Some(SocialPeopleSuggestionsAction::SeeAll) => self.show_discover(cx, true),
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(SocialDiscoverAction::OpenProfile(user_id)) => {
+                    cx.action(NavigationBarAction::GoToSocialProfile {
+                        user_id: user_id.clone(),
+                    });
+                }
+                Some(SocialDiscoverAction::Follow(user_id)) => {
+                    submit_async_request(MatrixRequest::Follow(user_id.clone()));
+                }
+                Some(SocialDiscoverAction::Search(query)) => {
+                    submit_async_request(MatrixRequest::SearchUsers {
+                        query: query.clone(),
+                    });
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(SocialTrendingAction::Refresh) => {
+                    submit_async_request(MatrixRequest::LoadTrending);
+                }
+                // Posts are shown in their feed room's timeline
+                Some(SocialTrendingAction::OpenPost { room_id, .. }) => {
+                    cx.action(AppStateAction::NavigateToRoom {
+                        room_to_close: None,
+                        destination_room: BasicRoomDetails::RoomId(RoomNameId::empty(
+                            room_id.clone(),
+                        )),
+                    });
+                }
+                Some(SocialTrendingAction::OpenHashtag(hashtag)) => {
+                    self.show_discover(cx, false);
+                    feed.show_hashtag(cx, Some(hashtag.clone()));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl SocialMainFeedPage {
+    /// Show the discovery view in place of the newsfeed, or the newsfeed again.
+    fn show_discover(&mut self, cx: &mut Cx, show: bool) {
+        self.view(ids!(discover_page)).set_visible(cx, show);
+        self.widget(ids!(main_feed)).set_visible(cx, !show);
+        self.redraw(cx);
+    }
+
+    /// Present the given posts of the newsfeed one at a time over the page.
+    fn present(&mut self, cx: &mut Cx, slides: Vec<KioskSlide>) {
+        self.presented_until = slides.iter().map(|slide| slide.timestamp).max();
+        self.pending_media.clear();
+        self.view(ids!(kiosk_view)).set_visible(cx, true);
+        self.social_kiosk_view(ids!(kiosk_view))
+            .start(cx, slides, KioskSettings::default());
+        self.redraw(cx);
+    }
+
+    /// Add newly loaded posts to the presentation, oldest first.
+    fn add_presented_slides(&mut self, cx: &mut Cx, mut slides: Vec<KioskSlide>) {
+        if slides.is_empty() {
+            return;
+        }
+        slides.sort_by_key(|slide| slide.timestamp);
+        self.presented_until = slides.last().map(|slide| slide.timestamp);
+        self.social_kiosk_view(ids!(kiosk_view))
+            .add_slides(cx, slides);
+    }
+
+    /// Pass the images of the presented posts that were fetched to the kiosk view.
+    fn fetch_presented_media(&mut self, cx: &mut Cx) {
+        let kiosk_view = self.social_kiosk_view(ids!(kiosk_view));
+        let media_cache = &mut self.media_cache;
+        self.pending_media.retain(|media_url| {
+            let mxc_uri = OwnedMxcUri::from(media_url.clone());
+            match media_cache.try_get_media_or_fetch(mxc_uri, MediaFormat::File) {
+                (MediaCacheEntry::Loaded(data), _) => {
+                    kiosk_view.set_media(cx, media_url, data);
+                    false
+                }
+                (MediaCacheEntry::Requested, _) => true,
+                (MediaCacheEntry::Failed(status), _) => {
+                    error!("Failed to fetch presented media {media_url}: {status}");
+                    false
+                }
+            }
+        });
+    }
+
+    /// Handle an action on one of the posts that the feed view leaves to its page.
+    fn handle_post_action(&mut self, cx: &mut Cx, action: &SocialPostCardAction) {
+        match action {
+            SocialPostCardAction::ViewAuthorProfile(user_id) => {
+                cx.action(NavigationBarAction::GoToSocialProfile {
+                    user_id: user_id.clone(),
+                });
+            }
+            SocialPostCardAction::OpenLink(url) => {
+                if let Err(e) = robius_open::Uri::new(url).open() {
+                    error!("Failed to open URL {:?}. Error: {:?}", url, e);
+                    enqueue_popup_notification(PopupItem {
+                        message: format!("Could not open URL: {url}"),
+                        kind: PopupKind::Error,
+                        auto_dismissal_duration: None,
+                    });
+                }
+            }
+            SocialPostCardAction::ViewHashtag(hashtag) => {
+                self.social_feed_view(ids!(main_feed))
+                    .show_hashtag(cx, Some(hashtag.clone()));
+            }
+            SocialPostCardAction::HideLanguage(language) => {
+                let mut filter = feed_filter();
+                filter.hide_language(*language);
+                submit_async_request(MatrixRequest::SaveFeedFilter(filter));
+                self.social_feed_view(ids!(main_feed))
+                    .remove_posts_in_language(cx, *language);
+            }
+            SocialPostCardAction::Watch(event_id) => self.set_post_watched(cx, event_id, true),
+            SocialPostCardAction::Unwatch(event_id) => self.set_post_watched(cx, event_id, false),
+            _ => {}
+        }
+    }
+
+    /// Save whether the user watches one of the posts for new comments,
+    /// showing it on the post once saved.
+    fn set_post_watched(&mut self, cx: &mut Cx, event_id: &OwnedEventId, watched: bool) {
+        let feed = self.social_feed_view(ids!(main_feed));
+        let (Some(mut post), Some(user_id)) = (feed.find_post(event_id), current_user_id()) else {
+            return;
+        };
+        let result = if watched {
+            watch_post(&user_id, post.room_id.clone(), event_id.clone())
+        } else {
+            unwatch_post(&user_id, &post.room_id, event_id)
+        };
+        match result {
+            Ok(()) => {
+                post.is_watched = watched;
+                feed.update_post(cx, event_id, post);
+            }
+            Err(e) => {
+                error!("Failed to save the watched posts: {e}");
+                enqueue_popup_notification(PopupItem {
+                    message: format!("Failed to save the watched posts: {e}"),
+                    kind: PopupKind::Error,
+                    auto_dismissal_duration: None,
+                });
+            }
+        }
+    }
+
+    /// Load the newsfeed again, showing the posts already loaded until it's loaded.
+    pub fn show(&mut self, cx: &mut Cx) {
+        let feed = self.social_feed_view(ids!(main_feed));
+        if feed.post_count() == 0 {
+            feed.set_state(cx, FeedState::Loading);
+        }
+        // The grouping may have been changed on another device
+        feed.set_grouping(cx, feed_grouping());
+        self.loading_limit = Some(MAX_MAIN_FEED_POSTS);
+        submit_async_request(MatrixRequest::LoadMainFeed {
+            limit: MAX_MAIN_FEED_POSTS,
+        });
+        // The composer posts to the user's feeds, which may have been created since
+        submit_async_request(MatrixRequest::LoadOwnFeeds);
+        submit_async_request(MatrixRequest::LoadBirthdays);
+        submit_async_request(MatrixRequest::LoadFriendSuggestions);
+        self.redraw(cx);
+    }
+}
+
+impl SocialMainFeedPageRef {
+    /// See [`SocialMainFeedPage::show()`].
+    pub fn show(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show(cx);
+        }
+    }
+}
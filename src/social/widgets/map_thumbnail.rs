@@ -0,0 +1,158 @@
+//! Map thumbnail widget for event and post locations.
+//!
+//! Shows a small static map of a location, or a placeholder with the
+//! location's name if no map image is available (see [`maps`](crate::social::maps)).
+//! The parent widget opens the full map view when the thumbnail is tapped,
+//! see [`MapThumbnail::clicked()`].
+
+use makepad_widgets::*;
+use robrix_social_events::event::EventLocation;
+
+use crate::social::maps::{get_or_fetch_map_thumbnail, MapThumbnailStatus};
+use crate::utils;
+
+live_design! {
+    use link::theme::*;
+    use link::widgets::*;
+
+    /// A tappable map of a location, with a placeholder while offline.
+    pub MapThumbnail = {{MapThumbnail}} {
+        width: Fill,
+        height: Fit,
+        visible: false,
+
+        map_view = <View> {
+            width: Fill,
+            height: 120,
+            flow: Overlay,
+            cursor: Hand,
+            show_bg: true,
+            draw_bg: {
+                color: #e8eef2,
+                radius: 8.0,
+            }
+
+            map_image = <Image> {
+                width: Fill,
+                height: Fill,
+                fit: Cover,
+                visible: false,
+            }
+
+            placeholder = <View> {
+                width: Fill,
+                height: Fill,
+                flow: Down,
+                spacing: 4,
+                align: { x: 0.5, y: 0.5 },
+
+                placeholder_icon = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "🗺️",
+                    draw_text: {
+                        text_style: { font_size: 22.0 },
+                    }
+                }
+
+                placeholder_label = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 11.0 },
+                        color: #657786,
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct MapThumbnail {
+    #[deref]
+    view: View,
+
+    /// The location being shown.
+    #[rust]
+    location: Option<EventLocation>,
+
+    /// Whether the map image is still being fetched.
+    #[rust]
+    map_pending: bool,
+}
+
+impl Widget for MapThumbnail {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        // Map fetches signal the UI when they complete
+        if let Event::Signal = event {
+            if self.map_pending {
+                self.load_map(cx);
+            }
+        }
+        self.view.handle_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl MapThumbnail {
+    /// Show a map of the given location, or hide the thumbnail if `None`.
+    ///
+    /// Only locations with coordinates get a map image;
+    /// others show the placeholder with the location's name.
+    pub fn set_location(&mut self, cx: &mut Cx, location: Option<&EventLocation>) {
+        self.location = location.cloned();
+        if let Some(location) = location {
+            self.label(ids!(placeholder_label))
+                .set_text(cx, &location.name);
+        }
+        self.view.set_visible(cx, location.is_some());
+        self.load_map(cx);
+    }
+
+    /// Returns the shown location if the thumbnail was tapped.
+    pub fn clicked(&self, actions: &Actions) -> Option<&EventLocation> {
+        self.view(ids!(map_view))
+            .finger_up(actions)
+            .and(self.location.as_ref())
+    }
+
+    /// Show the map image for the current location, or the placeholder
+    /// while it is being fetched or if it can't be.
+    fn load_map(&mut self, cx: &mut Cx) {
+        let status = match &self.location {
+            Some(location) => get_or_fetch_map_thumbnail(location),
+            None => MapThumbnailStatus::Placeholder,
+        };
+        self.map_pending = matches!(status, MapThumbnailStatus::Pending);
+
+        let map_image = self.image(ids!(map_image));
+        let loaded = match &status {
+            MapThumbnailStatus::Ready(data) => utils::load_png_or_jpg(&map_image, cx, data)
+                .inspect_err(|e| error!("Failed to load map thumbnail image: {e:?}"))
+                .is_ok(),
+            _ => false,
+        };
+        map_image.set_visible(cx, loaded);
+        self.view(ids!(placeholder)).set_visible(cx, !loaded);
+        self.redraw(cx);
+    }
+}
+
+impl MapThumbnailRef {
+    /// See [`MapThumbnail::set_location()`].
+    pub fn set_location(&self, cx: &mut Cx, location: Option<&EventLocation>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_location(cx, location);
+        }
+    }
+
+    /// See [`MapThumbnail::clicked()`].
+    pub fn clicked(&self, actions: &Actions) -> Option<EventLocation> {
+        self.borrow()?.clicked(actions).cloned()
+    }
+}
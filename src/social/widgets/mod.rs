@@ -6,26 +6,125 @@
 
 use makepad_widgets::*;
 
+pub mod activity_log_view;
+pub mod audio_waveform;
+pub mod birthdays_card;
+pub mod comment_thread;
+pub mod discover_view;
 pub mod event_card;
+pub mod event_detail;
+pub mod event_invite_sheet;
+pub mod events_calendar;
+pub mod feed_density_settings;
+pub mod feed_filter_settings;
+pub mod feed_owner_card;
 pub mod feed_view;
+pub mod follow_lists;
 pub mod friend_list;
+pub mod kiosk_view;
+pub mod main_feed_page;
+pub mod map_thumbnail;
+pub mod people_suggestions_card;
+pub mod poll_view;
 pub mod post_card;
 pub mod post_composer;
+pub mod post_detail;
+pub mod post_group_card;
+pub mod profile_editor;
 pub mod profile_page;
+pub mod profile_share_sheet;
+pub mod profile_tabs;
+pub mod push_rule_settings;
+pub mod retention_settings;
+pub mod share_to_chat_sheet;
+pub mod social_gate;
+pub mod social_settings;
+pub mod storage_usage_view;
+pub mod theme_dark;
+pub mod theme_light;
+pub mod translation_settings;
+pub mod trending_view;
 
+pub use activity_log_view::*;
+pub use audio_waveform::*;
+pub use birthdays_card::*;
+pub use comment_thread::*;
+pub use discover_view::*;
 pub use event_card::*;
+pub use event_detail::*;
+pub use event_invite_sheet::*;
+pub use events_calendar::*;
+pub use feed_density_settings::*;
+pub use feed_filter_settings::*;
+pub use feed_owner_card::*;
 pub use feed_view::*;
+pub use follow_lists::*;
 pub use friend_list::*;
+pub use kiosk_view::*;
+pub use main_feed_page::*;
+pub use map_thumbnail::*;
+pub use people_suggestions_card::*;
+pub use poll_view::*;
 pub use post_card::*;
 pub use post_composer::*;
+pub use post_detail::*;
+pub use post_group_card::*;
+pub use profile_editor::*;
 pub use profile_page::*;
+pub use profile_share_sheet::*;
+pub use profile_tabs::*;
+pub use push_rule_settings::*;
+pub use retention_settings::*;
+pub use share_to_chat_sheet::*;
+pub use social_gate::*;
+pub use social_settings::*;
+pub use storage_usage_view::*;
+pub use translation_settings::*;
+pub use trending_view::*;
 
 /// Register all social widget designs with the Makepad live system.
 pub fn live_design(cx: &mut Cx) {
+    // The color palettes are used by all other widgets, so they must be registered first
+    theme_light::live_design(cx);
+    theme_dark::live_design(cx);
+    // Used by the event and post cards, so it must be registered first
+    map_thumbnail::live_design(cx);
+    // Used by the post card
+    audio_waveform::live_design(cx);
+    poll_view::live_design(cx);
+    activity_log_view::live_design(cx);
+    birthdays_card::live_design(cx);
+    discover_view::live_design(cx);
     event_card::live_design(cx);
+    event_detail::live_design(cx);
+    event_invite_sheet::live_design(cx);
+    events_calendar::live_design(cx);
+    feed_density_settings::live_design(cx);
+    feed_filter_settings::live_design(cx);
+    feed_owner_card::live_design(cx);
     feed_view::live_design(cx);
+    follow_lists::live_design(cx);
     friend_list::live_design(cx);
+    kiosk_view::live_design(cx);
+    main_feed_page::live_design(cx);
+    people_suggestions_card::live_design(cx);
     post_card::live_design(cx);
+    // Uses the post card's reactions row
+    comment_thread::live_design(cx);
     post_composer::live_design(cx);
+    post_detail::live_design(cx);
+    post_group_card::live_design(cx);
+    profile_editor::live_design(cx);
+    profile_tabs::live_design(cx);
+    profile_share_sheet::live_design(cx);
     profile_page::live_design(cx);
+    push_rule_settings::live_design(cx);
+    retention_settings::live_design(cx);
+    share_to_chat_sheet::live_design(cx);
+    social_gate::live_design(cx);
+    storage_usage_view::live_design(cx);
+    translation_settings::live_design(cx);
+    trending_view::live_design(cx);
+    // Uses the settings widgets above
+    social_settings::live_design(cx);
 }
@@ -0,0 +1,234 @@
+//! "People you may know" card widget.
+//!
+//! This card is shown among the posts in the newsfeed with the top few
+//! friends of friends from
+//! [`FriendSuggestionEngine::get_friend_suggestions()`], letting the user
+//! follow them right away or see all suggestions.
+//!
+//! [`FriendSuggestionEngine::get_friend_suggestions()`]: crate::social::discovery::FriendSuggestionEngine::get_friend_suggestions
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::OwnedUserId;
+use std::collections::BTreeSet;
+
+use crate::shared::avatar::AvatarWidgetExt;
+use crate::social::discovery::SuggestedUser;
+
+/// The rows of people shown in the card, in order.
+const PERSON_ROWS: [&[LiveId]; 3] = [ids!(person_0), ids!(person_1), ids!(person_2)];
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use crate::shared::avatar::Avatar;
+
+    /// A single suggested person in the card.
+    SuggestedPersonRow = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        cursor: Hand,
+
+        avatar = <Avatar> {
+            width: 36,
+            height: 36,
+        }
+
+        person_info = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 2,
+
+            name_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: #000,
+                }
+            }
+
+            detail_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 11.0 },
+                    color: #666,
+                }
+            }
+        }
+
+        follow_button = <Button> {
+            width: Fit,
+            height: 28,
+            text: "Follow",
+            draw_bg: {
+                color: #1d9bf0,
+                radius: 14.0,
+            }
+            draw_text: {
+                color: #fff,
+                text_style: { font_size: 11.0 },
+            }
+        }
+    }
+
+    /// Card suggesting friends of friends within the feed.
+    pub SocialPeopleSuggestionsCard = {{SocialPeopleSuggestionsCard}} {
+        width: Fill,
+        height: Fit,
+        padding: 16,
+        flow: Down,
+        spacing: 12,
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            align: { y: 0.5 },
+
+            title_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "People you may know",
+                draw_text: {
+                    text_style: { font_size: 15.0 },
+                    color: #000,
+                }
+            }
+
+            see_all_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "See all",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: #1d9bf0,
+                    text_style: { font_size: 12.0 },
+                }
+            }
+        }
+
+        person_0 = <SuggestedPersonRow> {}
+        person_1 = <SuggestedPersonRow> {}
+        person_2 = <SuggestedPersonRow> {}
+    }
+}
+
+/// Actions that can be triggered from the people suggestions card.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialPeopleSuggestionsAction {
+    /// User tapped a suggested person.
+    OpenProfile(OwnedUserId),
+    /// User clicked Follow next to a suggested person.
+    /// The follow should be made with
+    /// [`FollowService::follow()`](crate::social::FollowService::follow).
+    Follow(OwnedUserId),
+    /// User wants to see all suggestions,
+    /// e.g., in [`SocialDiscoverView`](crate::social::widgets::discover_view::SocialDiscoverView).
+    SeeAll,
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialPeopleSuggestionsCard {
+    #[deref]
+    view: View,
+
+    /// The people shown in the card, one per row.
+    #[rust]
+    people: Vec<OwnedUserId>,
+
+    /// People the user followed from this card.
+    #[rust]
+    followed: BTreeSet<OwnedUserId>,
+}
+
+impl Widget for SocialPeopleSuggestionsCard {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialPeopleSuggestionsCard {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.button(ids!(see_all_button)).clicked(actions) {
+            cx.action(SocialPeopleSuggestionsAction::SeeAll);
+        }
+
+        for (row_ids, user_id) in PERSON_ROWS.iter().zip(&self.people) {
+            let row = self.view(row_ids);
+            let follow_button = row.button(ids!(follow_button));
+            if follow_button.clicked(actions) {
+                cx.action(SocialPeopleSuggestionsAction::Follow(user_id.clone()));
+                follow_button.set_text(cx, "Following");
+                follow_button.set_enabled(cx, false);
+                self.followed.insert(user_id.clone());
+            } else if row.finger_up(actions).is_some() {
+                cx.action(SocialPeopleSuggestionsAction::OpenProfile(user_id.clone()));
+            }
+        }
+    }
+}
+
+impl SocialPeopleSuggestionsCard {
+    /// Set the suggested people, of which only the first few are shown.
+    pub fn set_suggestions(&mut self, cx: &mut Cx, suggestions: &[SuggestedUser]) {
+        self.people = suggestions
+            .iter()
+            .take(PERSON_ROWS.len())
+            .map(|user| user.user_id.clone())
+            .collect();
+
+        for (index, row_ids) in PERSON_ROWS.iter().enumerate() {
+            let row = self.view(row_ids);
+            let Some(user) = suggestions.get(index) else {
+                row.set_visible(cx, false);
+                continue;
+            };
+            row.set_visible(cx, true);
+            let name = user.user_id.localpart();
+            row.avatar(ids!(avatar)).set_text(cx, name);
+            row.label(ids!(name_label)).set_text(cx, name);
+            let detail = match user.mutual_friends.len() {
+                1 => "1 mutual friend".to_string(),
+                count => format!("{count} mutual friends"),
+            };
+            row.label(ids!(detail_label)).set_text(cx, &detail);
+            let is_followed = self.followed.contains(&user.user_id);
+            let follow_button = row.button(ids!(follow_button));
+            follow_button.set_text(cx, if is_followed { "Following" } else { "Follow" });
+            follow_button.set_enabled(cx, !is_followed);
+        }
+        self.redraw(cx);
+    }
+}
+
+impl SocialPeopleSuggestionsCardRef {
+    /// See [`SocialPeopleSuggestionsCard::set_suggestions()`].
+    pub fn set_suggestions(&self, cx: &mut Cx, suggestions: &[SuggestedUser]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_suggestions(cx, suggestions);
+        }
+    }
+}
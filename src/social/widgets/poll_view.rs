@@ -0,0 +1,317 @@
+//! Poll widget for poll posts.
+//!
+//! Shows the answers of a poll as buttons filled in proportion to their
+//! votes, with the answers the current user picked highlighted. Tapping an
+//! answer votes for it, or withdraws the vote for it (see
+//! [`PollResults::toggle_vote()`]), until the poll's author ends the poll.
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::OwnedUserId;
+
+use crate::sliding_sync::current_user_id;
+use crate::social::poll::PollResults;
+use crate::social::widgets::post_card::SocialPostCardAction;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use link::social_theme::*;
+
+    /// Button template for a single answer of a poll.
+    PollAnswerButton = <Button> {
+        width: Fill,
+        height: Fit,
+        padding: { top: 10, bottom: 10, left: 12, right: 12 },
+        margin: { bottom: 6 },
+        align: { x: 0.0, y: 0.5 },
+
+        draw_bg: {
+            // The share of voters who picked the answer, from 0.0 to 1.0
+            instance fill: 0.0
+            // 1.0 if the current user picked the answer
+            instance selected: 0.0
+            border_radius: 8.0
+            border_size: 1.0
+
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(
+                    self.border_size,
+                    self.border_size,
+                    self.rect_size.x - self.border_size * 2.0,
+                    self.rect_size.y - self.border_size * 2.0,
+                    self.border_radius
+                );
+                sdf.fill((SOCIAL_SURFACE));
+                // The bar showing the answer's share of the votes
+                sdf.box(
+                    self.border_size,
+                    self.border_size,
+                    (self.rect_size.x - self.border_size * 2.0) * self.fill,
+                    self.rect_size.y - self.border_size * 2.0,
+                    self.border_radius
+                );
+                sdf.fill((SOCIAL_ACCENT_BG));
+                sdf.box(
+                    self.border_size,
+                    self.border_size,
+                    self.rect_size.x - self.border_size * 2.0,
+                    self.rect_size.y - self.border_size * 2.0,
+                    self.border_radius
+                );
+                sdf.stroke(mix((SOCIAL_BORDER), (SOCIAL_ACCENT), self.selected), self.border_size);
+                return sdf.result;
+            }
+        }
+        draw_text: {
+            text_style: { font_size: 13.0 },
+            color: (SOCIAL_TEXT),
+            wrap: Word,
+        }
+    }
+
+    /// The answers of a poll, as buttons that vote for them.
+    pub SocialPollAnswers = {{SocialPollAnswers}} {
+        width: Fill,
+        height: Fit,
+        flow: Down,
+        answer_template: <PollAnswerButton> {}
+    }
+
+    /// A poll post's answers with their votes, and a button for its author to end it.
+    pub SocialPollView = {{SocialPollView}} {
+        width: Fill,
+        height: Fit,
+        visible: false,
+        flow: Down,
+        spacing: 4,
+
+        poll_answers = <SocialPollAnswers> {}
+
+        poll_footer = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            align: { y: 0.5 },
+
+            poll_summary_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                }
+            }
+
+            end_poll_button = <Button> {
+                width: Fit,
+                height: Fit,
+                visible: false,
+                padding: { top: 4, bottom: 4, left: 8, right: 8 },
+                text: "End poll",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_ACCENT),
+                }
+            }
+        }
+    }
+}
+
+/// Widget for displaying the answers of a poll as buttons.
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialPollAnswers {
+    #[redraw]
+    #[rust]
+    area: Area,
+
+    /// Template for answer buttons.
+    #[live]
+    answer_template: Option<LivePtr>,
+
+    /// Created answer buttons with the IDs of their answers.
+    #[rust]
+    answer_buttons: Vec<(ButtonRef, String)>,
+
+    /// Layout for the widget.
+    #[layout]
+    layout: Layout,
+
+    /// Walk for the widget.
+    #[walk]
+    walk: Walk,
+
+    /// The poll being shown.
+    #[rust]
+    results: Option<PollResults>,
+
+    /// The current user, who votes by tapping answers.
+    #[rust]
+    user_id: Option<OwnedUserId>,
+}
+
+impl Widget for SocialPollAnswers {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, _scope: &mut Scope) {
+        let Some(results) = self.results.as_ref().filter(|results| !results.ended) else {
+            return;
+        };
+        let Some(user_id) = &self.user_id else {
+            return;
+        };
+        // Handle click events on answer buttons
+        for (button_ref, answer_id) in &self.answer_buttons {
+            if let Hit::FingerUp(fue) = event.hits(cx, button_ref.area()) {
+                if fue.is_over && fue.is_primary_hit() && fue.was_tap() {
+                    cx.action(SocialPostCardAction::VotePoll {
+                        event_id: results.poll_id.clone(),
+                        answers: results.toggle_vote(user_id, answer_id),
+                    });
+                }
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        cx.begin_turtle(walk, self.layout);
+        for (button, _) in self.answer_buttons.iter_mut() {
+            let _ = button.draw(cx, scope);
+        }
+        cx.end_turtle();
+        DrawStep::done()
+    }
+}
+
+impl SocialPollAnswers {
+    /// Set the poll whose answers to display.
+    pub fn set_results(&mut self, cx: &mut Cx, results: &PollResults) {
+        self.user_id = current_user_id();
+        self.answer_buttons.clear();
+
+        let Some(template) = self.answer_template else {
+            return;
+        };
+
+        let own_votes = self
+            .user_id
+            .as_deref()
+            .map(|user_id| results.votes_of(user_id))
+            .unwrap_or_default();
+        let voter_count = results.voter_count();
+        for answer in &results.answers {
+            let button = WidgetRef::new_from_ptr(cx, Some(template)).as_button();
+            let is_selected = own_votes.contains(&answer.answer.id);
+            let percentage = results.percentage(answer);
+            let text = if voter_count == 0 {
+                answer.answer.text.clone()
+            } else {
+                format!("{}  ·  {percentage}%", answer.answer.text)
+            };
+            if is_selected {
+                button.set_text(cx, &format!("✓ {text}"));
+            } else {
+                button.set_text(cx, &text);
+            }
+
+            let selected = if is_selected { 1.0 } else { 0.0 };
+            button.apply_over(
+                cx,
+                live! {
+                    draw_bg: { fill: (percentage as f64 / 100.0), selected: (selected) }
+                },
+            );
+
+            self.answer_buttons.push((button, answer.answer.id.clone()));
+        }
+        self.results = Some(results.clone());
+        self.redraw(cx);
+    }
+}
+
+impl SocialPollAnswersRef {
+    /// See [`SocialPollAnswers::set_results()`].
+    pub fn set_results(&self, cx: &mut Cx, results: &PollResults) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_results(cx, results);
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialPollView {
+    #[deref]
+    view: View,
+
+    /// The poll being shown.
+    #[rust]
+    results: Option<PollResults>,
+}
+
+impl Widget for SocialPollView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialPollView {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        let Some(results) = &self.results else {
+            return;
+        };
+        if self.button(ids!(end_poll_button)).clicked(actions) {
+            cx.action(SocialPostCardAction::EndPoll(results.poll_id.clone()));
+        }
+    }
+}
+
+impl SocialPollView {
+    /// Show the given poll with its current votes, or hide the view if `None`.
+    ///
+    /// The button to end the poll is only shown to its author while it's open.
+    pub fn set_results(&mut self, cx: &mut Cx, results: Option<&PollResults>) {
+        self.results = results.cloned();
+        self.view.set_visible(cx, results.is_some());
+        let Some(results) = results else {
+            return;
+        };
+        self.social_poll_answers(ids!(poll_answers))
+            .set_results(cx, results);
+
+        let voters = match results.voter_count() {
+            1 => "1 vote".to_string(),
+            count => format!("{count} votes"),
+        };
+        let summary = if results.ended {
+            format!("{voters} · Final results")
+        } else if results.max_selections > 1 {
+            format!("{voters} · Pick up to {}", results.max_selections)
+        } else {
+            voters
+        };
+        self.label(ids!(poll_summary_label)).set_text(cx, &summary);
+
+        let is_author = current_user_id().is_some_and(|user_id| user_id == results.author);
+        self.button(ids!(end_poll_button))
+            .set_visible(cx, is_author && !results.ended);
+        self.redraw(cx);
+    }
+}
+
+impl SocialPollViewRef {
+    /// See [`SocialPollView::set_results()`].
+    pub fn set_results(&self, cx: &mut Cx, results: Option<&PollResults>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_results(cx, results);
+        }
+    }
+}
@@ -1,31 +1,81 @@
 //! Post card widget for displaying social posts.
 //!
 //! This widget renders a single post in a feed, including author info,
-//! content, media, reactions, and interaction buttons.
+//! content, media, reactions, and interaction buttons. Collaborative posts
+//! show both of their authors, and posts tagged with a location show a map of it.
+//! Posts that couldn't be deserialized are shown as an "Unsupported post"
+//! notice instead, with the raw event type in developer mode, and posts in
+//! encrypted feeds that couldn't be decrypted yet as a notice that they're
+//! waiting for their keys. Posts whose media breaks the feed's media
+//! restrictions are pointed out to the feed's moderators. Images with alt text
+//! have an "ALT" chip that shows the description below them. Audio posts, e.g.,
+//! voice notes, show a play button with the clip's waveform and duration;
+//! the parent widget plays the clip and reports its progress with
+//! [`SocialPostCard::set_audio_progress()`]. Poll posts show their answers with
+//! live vote counts (see [`SocialPollView`](crate::social::widgets::poll_view::SocialPollView)).
+//! The more options button opens the post's options, e.g., to send it to a chat.
+//! Authors whose identity the current user has verified get a verified badge,
+//! which can be tapped to verify them again.
 
 use makepad_widgets::*;
-use matrix_sdk::ruma::{MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId};
-
+use matrix_sdk::ruma::{
+    matrix_uri::MatrixId, EventId, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedMxcUri,
+    OwnedRoomId, OwnedUserId, UserId,
+};
+use robrix_social_events::{
+    event::EventLocation, feed_config::SocialFeedConfigEventContent, license::ContentLicense,
+    settings::FeedDensity,
+};
+
+use crate::avatar_cache;
 use crate::shared::avatar::AvatarWidgetExt;
-use crate::social::reactions::{reactions_for_display, ReactionSummary};
+use crate::shared::callout_tooltip::{CalloutTooltipOptions, TooltipAction, TooltipPosition};
+use crate::shared::html_or_plaintext::{HtmlOrPlaintextWidgetExt, RobrixHtmlLinkAction};
+use crate::sliding_sync::{current_user_id, submit_async_request, MatrixRequest};
+use crate::social::audio_clip::AudioDetails;
+use crate::social::feed_moderation::ModerationPermissions;
+use crate::social::media_policy::MediaViolation;
+use crate::social::poll::PollResults;
+use crate::social::post_links::{post_text_html, PostLink};
+use crate::social::translation::{Translation, TranslationAction};
+use crate::social::user_verification::{user_verification, UserVerificationAction};
+use crate::social::newsfeed::{
+    FeedItem, GroupableItem, Language, OrderedFeedItem, SpamSignal, UndecryptablePost,
+    UnsupportedPost,
+};
+use crate::social::optimistic::LIKE_REACTION;
+use crate::social::post::PostContent;
+use crate::social::post_watch::is_post_watched;
+use crate::social::reactions::{
+    quick_reactions, reactions_for_display, ReactionDisplay, ReactionSummary,
+};
+use crate::social::widgets::audio_waveform::AudioWaveformWidgetExt;
+use crate::social::widgets::map_thumbnail::MapThumbnailWidgetExt;
+use crate::social::widgets::poll_view::SocialPollViewWidgetExt;
 
 live_design! {
     use link::theme::*;
     use link::shaders::*;
     use link::widgets::*;
+    use link::social_theme::*;
 
     use crate::shared::styles::*;
     use crate::shared::avatar::Avatar;
+    use crate::shared::html_or_plaintext::*;
     use crate::shared::icon_button::RobrixIconButton;
-
-    CARD_BG_COLOR = #fff
-    CARD_BORDER_COLOR = #e0e0e0
-    ICON_COLOR = #666
-    ICON_HOVER_COLOR = #1d9bf0
-    REACTION_SELECTED_BG = #e8f5fd
-    REACTION_NORMAL_BG = #f0f2f5
-    REACTION_SELECTED_BORDER = #1d9bf0
-    REACTION_NORMAL_BORDER = #e0e0e0
+    use crate::social::widgets::audio_waveform::AudioWaveform;
+    use crate::social::widgets::map_thumbnail::MapThumbnail;
+    use crate::social::widgets::poll_view::SocialPollView;
+
+    CARD_BG_COLOR = (SOCIAL_BG)
+    CARD_BORDER_COLOR = (SOCIAL_BORDER)
+    ICON_COLOR = (SOCIAL_TEXT_SECONDARY)
+    ICON_HOVER_COLOR = (SOCIAL_ACCENT)
+    FOCUS_RING_COLOR = (SOCIAL_ACCENT)
+    REACTION_SELECTED_BG = (SOCIAL_ACCENT_BG)
+    REACTION_NORMAL_BG = (SOCIAL_SURFACE)
+    REACTION_SELECTED_BORDER = (SOCIAL_ACCENT)
+    REACTION_NORMAL_BORDER = (SOCIAL_BORDER)
 
     /// Button template for displaying a single reaction.
     ReactionButton = <Button> {
@@ -35,8 +85,8 @@ live_design! {
         margin: { right: 4 },
 
         draw_bg: {
-            instance reaction_bg_color: (REACTION_NORMAL_BG)
-            instance reaction_border_color: (REACTION_NORMAL_BORDER)
+            // 1.0 if the user has selected this reaction
+            instance selected: 0.0
             border_radius: 12.0
             border_size: 1.0
 
@@ -49,14 +99,32 @@ live_design! {
                     self.rect_size.y - self.border_size * 2.0,
                     self.border_radius
                 );
-                sdf.fill_keep(self.reaction_bg_color);
-                sdf.stroke(self.reaction_border_color, self.border_size);
+                sdf.fill_keep(mix((REACTION_NORMAL_BG), (REACTION_SELECTED_BG), self.selected));
+                sdf.stroke(
+                    mix((REACTION_NORMAL_BORDER), (REACTION_SELECTED_BORDER), self.selected),
+                    self.border_size
+                );
                 return sdf.result;
             }
         }
         draw_text: {
             text_style: { font_size: 12.0 },
-            color: #333,
+            color: (SOCIAL_TEXT),
+        }
+    }
+
+    /// Button template for an option in a post's more options menu.
+    PostOptionButton = <Button> {
+        width: Fit,
+        height: Fit,
+        padding: { top: 6, bottom: 6, left: 12, right: 12 },
+        draw_bg: {
+            color: (SOCIAL_SURFACE),
+            radius: 6.0,
+        }
+        draw_text: {
+            text_style: { font_size: 13.0 },
+            color: (SOCIAL_TEXT),
         }
     }
 
@@ -70,6 +138,8 @@ live_design! {
         show_bg: true,
         draw_bg: {
             color: (CARD_BG_COLOR),
+            // 1.0 while the card has keyboard focus in the feed
+            instance focused: 0.0,
             fn pixel(self) -> vec4 {
                 let sdf = Sdf2d::viewport(self.pos * self.rect_size);
                 sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 0.);
@@ -77,6 +147,11 @@ live_design! {
                 // Bottom border
                 sdf.rect(0., self.rect_size.y - 1., self.rect_size.x, 1.);
                 sdf.fill((CARD_BORDER_COLOR));
+                // Focus ring
+                if self.focused > 0.5 {
+                    sdf.box(1., 1., self.rect_size.x - 2., self.rect_size.y - 2., 2.);
+                    sdf.stroke((FOCUS_RING_COLOR), 2.);
+                }
                 return sdf.result;
             }
         }
@@ -94,12 +169,32 @@ live_design! {
                 height: 48,
             }
 
+            // The other author of a collaborative post, overlapping the author's avatar
+            co_author_avatar = <Avatar> {
+                width: 28,
+                height: 28,
+                margin: { left: -28, top: 24 },
+                visible: false,
+            }
+
             author_info = <View> {
                 width: Fill,
                 height: Fit,
                 flow: Down,
                 spacing: 2,
 
+                // Shown on posts pinned to the top of their owner's feed
+                pinned_badge = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    visible: false,
+                    text: "📌 Pinned",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                    }
+                }
+
                 name_row = <View> {
                     width: Fill,
                     height: Fit,
@@ -113,7 +208,23 @@ live_design! {
                         text: "",
                         draw_text: {
                             text_style: { font_size: 14.0 },
-                            color: #000,
+                            color: (SOCIAL_TEXT_PRIMARY),
+                        }
+                    }
+
+                    // Shown if the current user verified the author's identity
+                    verified_badge = <Button> {
+                        width: Fit,
+                        height: Fit,
+                        padding: 0,
+                        visible: false,
+                        text: "✓",
+                        draw_bg: {
+                            color: #0000,
+                        }
+                        draw_text: {
+                            text_style: { font_size: 14.0 },
+                            color: (SOCIAL_ACCENT),
                         }
                     }
 
@@ -123,7 +234,18 @@ live_design! {
                         text: "",
                         draw_text: {
                             text_style: { font_size: 14.0 },
-                            color: #666,
+                            color: (SOCIAL_TEXT_SECONDARY),
+                        }
+                    }
+
+                    co_author_label = <Label> {
+                        width: Fit,
+                        height: Fit,
+                        visible: false,
+                        text: "",
+                        draw_text: {
+                            text_style: { font_size: 14.0 },
+                            color: (SOCIAL_TEXT_PRIMARY),
                         }
                     }
 
@@ -133,7 +255,7 @@ live_design! {
                         text: "",
                         draw_text: {
                             text_style: { font_size: 14.0 },
-                            color: #666,
+                            color: (SOCIAL_TEXT_SECONDARY),
                         }
                     }
                 }
@@ -145,7 +267,24 @@ live_design! {
                     text: "(edited)",
                     draw_text: {
                         text_style: { font_size: 12.0 },
-                        color: #999,
+                        color: (SOCIAL_TEXT_MUTED),
+                    }
+                }
+
+                // Shown while the user's changes to the post are being sent,
+                // or if the post failed to send, in which case tapping it retries
+                pending_indicator = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    visible: false,
+                    padding: 0,
+                    text: "Sending…",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_MUTED),
                     }
                 }
             }
@@ -159,36 +298,270 @@ live_design! {
                     color: #0000,
                 }
                 draw_text: {
-                    color: #666,
+                    color: (SOCIAL_TEXT_SECONDARY),
                     text_style: { font_size: 18.0 }
                 }
             }
         }
 
-        // Content section
-        content_section = <View> {
+        // Options for the post, shown when the more options button is tapped
+        more_options_menu = <View> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            flow: Right,
+            spacing: 6,
+            align: { x: 1.0 },
+
+            send_to_chat_button = <PostOptionButton> {
+                text: "Send to chat",
+            }
+
+            translate_post_button = <PostOptionButton> {
+                text: "Translate",
+            }
+
+            // Moderation options, shown to the feed's moderators
+            pin_post_button = <PostOptionButton> {
+                visible: false,
+                text: "Pin post",
+            }
+
+            remove_post_button = <PostOptionButton> {
+                visible: false,
+                text: "Remove post",
+                draw_text: {
+                    color: (SOCIAL_DANGER),
+                }
+            }
+
+            ban_author_button = <PostOptionButton> {
+                visible: false,
+                text: "Ban from feed",
+                draw_text: {
+                    color: (SOCIAL_DANGER),
+                }
+            }
+        }
+
+        // Shown instead of the post while it is hidden as likely spam
+        spam_interstitial = <View> {
             width: Fill,
             height: Fit,
             flow: Down,
-            spacing: 12,
+            spacing: 8,
             margin: { left: 60 },
+            padding: 12,
+            visible: false,
+            show_bg: true,
+            draw_bg: {
+                color: (SOCIAL_SURFACE),
+                radius: 8.0,
+            }
 
-            // Text content
-            text_content = <Label> {
+            spam_reason_label = <Label> {
                 width: Fill,
                 height: Fit,
                 text: "",
                 draw_text: {
-                    text_style: { font_size: 14.0 },
-                    color: #333,
+                    text_style: { font_size: 13.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                    wrap: Word,
+                }
+            }
+
+            spam_buttons = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+
+                show_anyway_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 4, bottom: 4, left: 8, right: 8 },
+                    text: "Show anyway",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (ICON_HOVER_COLOR),
+                        text_style: { font_size: 12.0 }
+                    }
+                }
+
+                not_spam_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 4, bottom: 4, left: 8, right: 8 },
+                    text: "Not spam",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (ICON_COLOR),
+                        text_style: { font_size: 12.0 }
+                    }
+                }
+            }
+        }
+
+        // Shown instead of the post if it couldn't be deserialized or decrypted
+        unsupported_notice = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 4,
+            margin: { left: 60 },
+            padding: 12,
+            visible: false,
+            show_bg: true,
+            draw_bg: {
+                color: (SOCIAL_SURFACE),
+                radius: 8.0,
+            }
+
+            unsupported_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Unsupported post: this version of Robrix can't show it.",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
                     wrap: Word,
                 }
             }
 
+            unsupported_details_label = <Label> {
+                width: Fill,
+                height: Fit,
+                visible: false,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 11.0 },
+                    color: (SOCIAL_TEXT_MUTED),
+                    wrap: Word,
+                }
+            }
+        }
+
+        // Content section
+        content_section = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 12,
+            margin: { left: 60 },
+
+            // Text content, formatted if the post has an HTML body
+            text_content = <HtmlOrPlaintext> { }
+
+            // The post's text translated into the user's language, shown instead of it
+            translation_view = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                spacing: 4,
+                visible: false,
+
+                translated_text = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 14.0 },
+                        color: (SOCIAL_TEXT),
+                        wrap: Word,
+                    }
+                }
+
+                translation_row = <View> {
+                    width: Fill,
+                    height: Fit,
+                    flow: Right,
+                    spacing: 8,
+                    align: { y: 0.5 },
+
+                    translation_label = <Label> {
+                        width: Fit,
+                        height: Fit,
+                        text: "",
+                        draw_text: {
+                            text_style: { font_size: 12.0 },
+                            color: (SOCIAL_TEXT_MUTED),
+                        }
+                    }
+
+                    show_original_button = <Button> {
+                        width: Fit,
+                        height: Fit,
+                        padding: { top: 2, bottom: 2, left: 4, right: 4 },
+                        text: "Show original",
+                        draw_bg: {
+                            color: #0000,
+                        }
+                        draw_text: {
+                            color: (ICON_HOVER_COLOR),
+                            text_style: { font_size: 12.0 }
+                        }
+                    }
+                }
+            }
+
+            // Shown for posts written in a language other than the user's own
+            language_row = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+                align: { y: 0.5 },
+                visible: false,
+
+                language_label = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_MUTED),
+                    }
+                }
+
+                translate_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 2, bottom: 2, left: 4, right: 4 },
+                    text: "Translate",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (ICON_HOVER_COLOR),
+                        text_style: { font_size: 12.0 }
+                    }
+                }
+
+                hide_language_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 2, bottom: 2, left: 4, right: 4 },
+                    text: "Hide this language",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (ICON_HOVER_COLOR),
+                        text_style: { font_size: 12.0 }
+                    }
+                }
+            }
+
             // Media content (image/video)
             media_container = <View> {
                 width: Fill,
                 height: Fit,
+                flow: Down,
+                spacing: 4,
                 visible: false,
 
                 media_image = <Image> {
@@ -205,8 +578,92 @@ live_design! {
                         }
                     }
                 }
+
+                // Toggles the description of the image, if it has one
+                alt_text_chip = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    visible: false,
+                    padding: { top: 2, bottom: 2, left: 8, right: 8 },
+                    text: "ALT",
+                    draw_bg: {
+                        color: (SOCIAL_SURFACE),
+                        border_radius: 8.0,
+                    }
+                    draw_text: {
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        text_style: { font_size: 10.0 },
+                    }
+                }
+
+                alt_text_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    visible: false,
+                    text: "",
+                    draw_text: {
+                        wrap: Word,
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        text_style: { font_size: 11.0 },
+                    }
+                }
             }
 
+            // Audio clip player
+            audio_player = <View> {
+                width: Fill,
+                height: Fit,
+                visible: false,
+                flow: Right,
+                spacing: 8,
+                padding: { top: 6, bottom: 6, left: 6, right: 12 },
+                align: { y: 0.5 },
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_SURFACE),
+                    fn pixel(self) -> vec4 {
+                        let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                        sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 18.);
+                        sdf.fill(self.color);
+                        return sdf.result;
+                    }
+                }
+
+                play_audio_button = <Button> {
+                    width: 32,
+                    height: 32,
+                    text: "▶",
+                    draw_bg: {
+                        color: (ICON_HOVER_COLOR),
+                        fn pixel(self) -> vec4 {
+                            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                            sdf.circle(self.rect_size.x / 2., self.rect_size.y / 2., self.rect_size.x / 2.);
+                            sdf.fill(self.color);
+                            return sdf.result;
+                        }
+                    }
+                    draw_text: {
+                        color: (SOCIAL_ON_ACCENT),
+                        text_style: { font_size: 12.0 }
+                    }
+                }
+
+                audio_waveform = <AudioWaveform> {}
+
+                audio_duration_label = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        text_style: { font_size: 11.0 },
+                    }
+                }
+            }
+
+            // Poll answers and votes
+            poll_view = <SocialPollView> {}
+
             // Link preview
             link_preview = <View> {
                 width: Fill,
@@ -215,7 +672,7 @@ live_design! {
                 padding: 12,
                 show_bg: true,
                 draw_bg: {
-                    color: #f8f8f8,
+                    color: (SOCIAL_SURFACE),
                     fn pixel(self) -> vec4 {
                         let sdf = Sdf2d::viewport(self.pos * self.rect_size);
                         sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 8.);
@@ -237,7 +694,7 @@ live_design! {
                         text: "",
                         draw_text: {
                             text_style: { font_size: 14.0 },
-                            color: #333,
+                            color: (SOCIAL_TEXT),
                         }
                     }
 
@@ -247,7 +704,7 @@ live_design! {
                         text: "",
                         draw_text: {
                             text_style: { font_size: 12.0 },
-                            color: #666,
+                            color: (SOCIAL_TEXT_SECONDARY),
                             wrap: Word,
                         }
                     }
@@ -258,11 +715,44 @@ live_design! {
                         text: "",
                         draw_text: {
                             text_style: { font_size: 11.0 },
-                            color: #1d9bf0,
+                            color: (SOCIAL_ACCENT),
                         }
                     }
                 }
             }
+
+            // Map of the post's location, tap to open the full map view
+            map_thumbnail = <MapThumbnail> {}
+
+            // License chip, shown if the post has a license
+            license_chip = <Button> {
+                width: Fit,
+                height: Fit,
+                visible: false,
+                padding: { top: 2, bottom: 2, left: 8, right: 8 },
+                text: "",
+                draw_bg: {
+                    color: (SOCIAL_SURFACE),
+                    border_radius: 8.0,
+                }
+                draw_text: {
+                    color: (SOCIAL_TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 },
+                }
+            }
+
+            // Shown to the feed's moderators if the post's media breaks the feed's restrictions
+            media_violation_label = <Label> {
+                width: Fill,
+                height: Fit,
+                visible: false,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 11.0 },
+                    color: (SOCIAL_WARNING_TEXT),
+                    wrap: Word,
+                }
+            }
         }
 
         // Reactions row
@@ -278,7 +768,20 @@ live_design! {
             reaction_template: <ReactionButton> {}
         }
 
-        // Action bar: Comment, Share, Like, Bookmark
+        // Quick reactions row, shown by the react button
+        quick_reactions_row = {{SocialReactionsRow}} {
+            width: Fill,
+            height: Fit,
+            flow: RightWrap,
+            spacing: 4,
+            margin: { left: 60, top: 4 },
+            visible: false,
+            show_counts: false,
+
+            reaction_template: <ReactionButton> {}
+        }
+
+        // Action bar: Comment, Reply, Share, Like, Bookmark
         action_bar = <View> {
             width: Fill,
             height: Fit,
@@ -301,10 +804,40 @@ live_design! {
 
             <View> { width: 40, height: 1 }
 
-            share_button = <RobrixIconButton> {
+            reply_button = <RobrixIconButton> {
+                width: Fit,
+                height: 32,
+                text: "↩️",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (ICON_COLOR),
+                    text_style: { font_size: 13.0 }
+                }
+            }
+
+            <View> { width: 40, height: 1 }
+
+            share_button = <RobrixIconButton> {
+                width: Fit,
+                height: 32,
+                text: "🔄 0",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (ICON_COLOR),
+                    text_style: { font_size: 13.0 }
+                }
+            }
+
+            <View> { width: 40, height: 1 }
+
+            like_button = <RobrixIconButton> {
                 width: Fit,
                 height: 32,
-                text: "🔄 0",
+                text: "❤️ 0",
                 draw_bg: {
                     color: #0000,
                 }
@@ -316,10 +849,10 @@ live_design! {
 
             <View> { width: 40, height: 1 }
 
-            like_button = <RobrixIconButton> {
+            react_button = <RobrixIconButton> {
                 width: Fit,
                 height: 32,
-                text: "❤️ 0",
+                text: "😀+",
                 draw_bg: {
                     color: #0000,
                 }
@@ -331,6 +864,20 @@ live_design! {
 
             <View> { width: Fill, height: 1 }
 
+            // Hidden on the user's own posts, whose comments they're notified about anyway
+            watch_button = <RobrixIconButton> {
+                width: Fit,
+                height: 32,
+                text: "🔕",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (ICON_COLOR),
+                    text_style: { font_size: 13.0 }
+                }
+            }
+
             bookmark_button = <RobrixIconButton> {
                 width: Fit,
                 height: 32,
@@ -358,18 +905,44 @@ pub struct PostCardData {
     pub author_id: OwnedUserId,
     /// Author's display name.
     pub author_name: Option<String>,
+    /// Author's avatar, shown once its thumbnail has been fetched.
+    pub author_avatar_url: Option<OwnedMxcUri>,
+    /// The other author of a collaborative post, shown next to the author
+    /// (see [`displayed_co_author()`](crate::social::displayed_co_author)).
+    pub co_author: Option<OwnedUserId>,
+    /// The other author's avatar, if this is a collaborative post.
+    pub co_author_avatar_url: Option<OwnedMxcUri>,
     /// Post timestamp.
     pub timestamp: MilliSecondsSinceUnixEpoch,
     /// Text content of the post.
     pub text: String,
+    /// HTML formatting of the post's text, shown instead of it if present
+    /// (see [`formatted_body_from_content()`](crate::social::formatted_body_from_content)).
+    /// Otherwise, the text is rendered as Markdown (see [`post_text_html()`]).
+    pub formatted_text: Option<String>,
     /// Whether the post has been edited.
     pub is_edited: bool,
     /// Media URL if the post has media.
     pub media_url: Option<String>,
+    /// Description of the post's image for people who can't see it
+    /// (see [`alt_text_from_content()`](crate::social::alt_text_from_content)).
+    pub media_alt_text: Option<String>,
+    /// Duration and waveform of the post's audio clip, if it's an audio post
+    /// (see [`audio_details_from_content()`](crate::social::audio_details_from_content)).
+    pub audio: Option<AudioDetails>,
+    /// The question and answers of the post's poll with their current votes,
+    /// if it's a poll post.
+    pub poll: Option<PollResults>,
     /// Link preview data.
     pub link_preview: Option<LinkPreviewData>,
     /// Reaction summary.
     pub reactions: ReactionSummary,
+    /// Configuration of the feed the post is in, e.g., its allowed reactions.
+    pub feed_config: SocialFeedConfigEventContent,
+    /// License the post's content is shared under.
+    pub license: Option<ContentLicense>,
+    /// Location the post is tagged with.
+    pub location: Option<EventLocation>,
     /// Comment count.
     pub comment_count: u32,
     /// Share/repost count.
@@ -378,6 +951,161 @@ pub struct PostCardData {
     pub is_liked: bool,
     /// Whether the current user has bookmarked this post.
     pub is_bookmarked: bool,
+    /// Whether the current user watches this post for new comments
+    /// (see [`is_post_watched()`](crate::social::is_post_watched)).
+    pub is_watched: bool,
+    /// Whether the current user wrote this post, in which case it can't be watched.
+    pub is_own_post: bool,
+    /// Detected language of the post text.
+    pub language: Option<Language>,
+    /// Why the post was hidden as likely spam (see [`SpamScorer`](crate::social::SpamScorer)).
+    ///
+    /// If non-empty, the post is hidden behind an interstitial
+    /// until the user chooses to show it anyway.
+    pub hidden_as_spam: Vec<SpamSignal>,
+    /// Why the post can't be shown, if it couldn't be deserialized,
+    /// in which case an "Unsupported post" notice is shown instead.
+    pub unsupported: Option<UnsupportedPost>,
+    /// Why the post can't be shown, if it's in an encrypted feed and couldn't be decrypted,
+    /// in which case a notice that it's waiting for its keys is shown instead.
+    pub undecryptable: Option<UndecryptablePost>,
+    /// How the post's media breaks the feed's media restrictions, for the feed's moderators
+    /// (see [`FeedItem::media_violation`](crate::social::FeedItem::media_violation)).
+    pub media_violation: Option<MediaViolation>,
+    /// Whether the post, or the user's reactions to it, are still being sent
+    /// (see [`OptimisticUpdates`](crate::social::OptimisticUpdates)).
+    pub is_pending: bool,
+    /// Whether the post failed to send and is waiting for the user to retry it
+    /// (see [`Outbox`](crate::social::Outbox)).
+    pub send_failed: bool,
+    /// What the current user may do to moderate the post's feed, offered in the post's options
+    /// (see [`FeedModerationService`](crate::social::FeedModerationService)).
+    pub moderation: ModerationPermissions,
+    /// Whether the post is pinned to its feed, in which case a "Pinned" badge is shown
+    /// (see [`FeedItem::is_pinned`](crate::social::FeedItem::is_pinned)).
+    pub is_pinned: bool,
+}
+
+impl GroupableItem for PostCardData {
+    fn author(&self) -> &UserId {
+        &self.author_id
+    }
+
+    fn timestamp_ms(&self) -> u64 {
+        self.timestamp.get().into()
+    }
+}
+
+impl OrderedFeedItem for PostCardData {
+    fn event_id(&self) -> &EventId {
+        &self.event_id
+    }
+}
+
+impl PostCardData {
+    /// Create the data shown for an aggregated feed item, with its loaded reactions.
+    ///
+    /// The author's name and avatar, and the feed's configuration, aren't known
+    /// from the feed item and are left for the post card to fall back on.
+    pub fn from_feed_item(
+        item: FeedItem,
+        reactions: ReactionSummary,
+        own_user_id: Option<&UserId>,
+    ) -> Self {
+        let is_own_post = own_user_id == Some(&*item.sender);
+        let is_liked = own_user_id
+            .is_some_and(|user_id| reactions.has_user_reacted(LIKE_REACTION, &user_id.to_owned()));
+        let is_watched = !is_own_post && is_post_watched(&item.room_id, &item.event_id);
+        let text = item.content.text().unwrap_or_default().to_owned();
+        let mut formatted_text = None;
+        let mut media_url = None;
+        let mut media_alt_text = None;
+        let mut audio = None;
+        let mut poll = None;
+        let mut link_preview = None;
+        match item.content {
+            PostContent::Text { formatted_body, .. } => formatted_text = formatted_body,
+            PostContent::Image {
+                mxc_uri, alt_text, ..
+            } => {
+                media_url = Some(mxc_uri.to_string());
+                media_alt_text = alt_text;
+            }
+            PostContent::Video { mxc_uri, .. } => media_url = Some(mxc_uri.to_string()),
+            PostContent::Audio {
+                duration_ms,
+                waveform,
+                ..
+            } => {
+                audio = Some(AudioDetails {
+                    duration: duration_ms,
+                    waveform,
+                })
+            }
+            PostContent::Link { url, preview, .. } => {
+                let preview = (*preview).as_ref();
+                link_preview = Some(LinkPreviewData {
+                    title: preview.and_then(|preview| preview.title.clone()),
+                    description: preview.and_then(|preview| preview.description.clone()),
+                    url: url.to_string(),
+                    image_url: preview
+                        .and_then(|preview| preview.image.as_ref())
+                        .map(ToString::to_string),
+                });
+            }
+            PostContent::Poll {
+                question,
+                answers,
+                max_selections,
+            } => {
+                poll = Some(PollResults::new(
+                    item.event_id.clone(),
+                    item.sender.clone(),
+                    question,
+                    answers,
+                    max_selections,
+                ))
+            }
+        }
+
+        Self {
+            event_id: item.event_id,
+            room_id: item.room_id,
+            author_id: item.sender,
+            author_name: None,
+            author_avatar_url: None,
+            co_author: None,
+            co_author_avatar_url: None,
+            timestamp: item.origin_server_ts,
+            text,
+            formatted_text,
+            is_edited: false,
+            media_url,
+            media_alt_text,
+            audio,
+            poll,
+            link_preview,
+            reactions,
+            feed_config: SocialFeedConfigEventContent::default(),
+            license: None,
+            location: None,
+            comment_count: item.comment_count,
+            share_count: 0,
+            is_liked,
+            is_bookmarked: false,
+            is_watched,
+            is_own_post,
+            language: item.language,
+            hidden_as_spam: Vec::new(),
+            unsupported: item.unsupported,
+            undecryptable: item.undecryptable,
+            media_violation: item.media_violation,
+            is_pending: false,
+            send_failed: false,
+            moderation: ModerationPermissions::default(),
+            is_pinned: item.is_pinned,
+        }
+    }
 }
 
 /// Link preview data for display.
@@ -402,6 +1130,10 @@ pub enum SocialPostCardAction {
     ViewAuthorProfile(OwnedUserId),
     /// User tapped to comment on the post.
     Comment(OwnedEventId),
+    /// User tapped to reply to the post from the composer, quoting it.
+    /// A feed showing its composer passes the post to
+    /// [`SocialPostComposer::start_reply()`](crate::social::widgets::post_composer::SocialPostComposer::start_reply).
+    Reply(OwnedEventId),
     /// User tapped to share/repost.
     Share(OwnedEventId),
     /// User tapped to like the post.
@@ -412,55 +1144,117 @@ pub enum SocialPostCardAction {
     Bookmark(OwnedEventId),
     /// User tapped to remove bookmark.
     RemoveBookmark(OwnedEventId),
-    /// User tapped the more options button.
+    /// User turned on notifications about new comments on someone else's post,
+    /// e.g., to be passed to [`watch_post()`](crate::social::watch_post).
+    Watch(OwnedEventId),
+    /// User turned off notifications about new comments on the post.
+    Unwatch(OwnedEventId),
+    /// User tapped the more options button, opening the post's options.
     ShowMoreOptions(OwnedEventId),
-    /// User tapped on a link preview.
+    /// User tapped "Send to chat" in the post's options, to forward the post
+    /// into a regular room (see [`ShareToChatService`](crate::social::ShareToChatService)).
+    SendToChat(OwnedEventId),
+    /// A moderator of the post's feed tapped to remove the post.
+    RemovePost(OwnedEventId),
+    /// A moderator of the post's feed tapped to ban the post's author from the feed.
+    BanAuthor(OwnedEventId),
+    /// A moderator of the post's feed tapped to pin the post, or to unpin it if `pinned` is false.
+    PinPost {
+        event_id: OwnedEventId,
+        pinned: bool,
+    },
+    /// User tapped on a link preview or on a link in the post's text.
     OpenLink(String),
+    /// User tapped a hashtag in the post's text.
+    /// The hashtag is lowercased and without its leading `#`.
+    ViewHashtag(String),
     /// User tapped on media to view full size.
     ViewMedia(OwnedEventId),
+    /// User tapped to play the post's audio clip, or to resume it if it was paused.
+    /// The progress of playing it should be reported with
+    /// [`SocialPostCard::set_audio_progress()`].
+    PlayAudio(OwnedEventId),
+    /// User tapped to pause the post's audio clip.
+    PauseAudio(OwnedEventId),
+    /// User tapped the map of the post's location to view the full map.
+    ViewLocation(EventLocation),
     /// User tapped a reaction to add/remove it.
     ToggleReaction {
         event_id: OwnedEventId,
         emoji: String,
     },
+    /// User asked to translate a post, e.g., one written in another language.
+    ///
+    /// The card requests the translation itself and shows it in place of the post's text.
+    TranslatePost(OwnedEventId),
+    /// User asked to hide all posts written in this language.
+    HideLanguage(Language),
+    /// User reported a post hidden as likely spam as a false positive.
+    /// It should be passed to
+    /// [`SpamScorer::mark_not_spam()`](crate::social::SpamScorer::mark_not_spam);
+    /// the feed view saves the sender itself.
+    ReportNotSpam {
+        event_id: OwnedEventId,
+        sender: OwnedUserId,
+    },
+    /// User tapped to retry sending a post that failed to send.
+    /// The ID is the post's local event ID (see [`local_event_id()`](crate::social::local_event_id)).
+    RetrySend(OwnedEventId),
+    /// User tapped an answer of a poll post, changing their vote to the given answers.
+    /// No answers withdraws the user's vote.
+    VotePoll {
+        event_id: OwnedEventId,
+        answers: Vec<String>,
+    },
+    /// The author of a poll post tapped to end the poll.
+    EndPoll(OwnedEventId),
+    /// The current votes of a poll post were loaded,
+    /// e.g., after the user voted or someone else did.
+    PollResultsLoaded(PollResults),
     /// No action.
     None,
 }
 
+/// The notice shown instead of a post that couldn't be deserialized.
+const UNSUPPORTED_POST_TEXT: &str = "Unsupported post: this version of Robrix can't show it.";
+
+/// The notice shown instead of a post in an encrypted feed that couldn't be decrypted.
+const UNDECRYPTABLE_POST_TEXT: &str =
+    "🔒 Waiting for this post: it will be shown once it can be decrypted.";
+
+/// The sections of a post card indented past the author's avatar,
+/// to line up with the author's name.
+const INDENTED_SECTIONS: [&[LiveId]; 6] = [
+    ids!(spam_interstitial),
+    ids!(unsupported_notice),
+    ids!(content_section),
+    ids!(reactions_row),
+    ids!(quick_reactions_row),
+    ids!(action_bar),
+];
+
+/// Space between the author's avatar and their name.
+const AVATAR_SPACING: f64 = 12.0;
+
+/// Get the padding, avatar size and media height of a post card in a feed density.
+fn density_dimensions(density: FeedDensity) -> (f64, f64, f64) {
+    match density {
+        FeedDensity::Comfortable => (16.0, 48.0, 300.0),
+        FeedDensity::Compact => (8.0, 36.0, 180.0),
+    }
+}
+
 /// Reaction button data for tracking click events.
 #[derive(Clone, Debug)]
 struct ReactionButtonData {
     emoji: String,
     #[allow(dead_code)] // Reserved for future use in reaction toggle UI
     is_selected: bool,
+    /// Whether the button groups reactions the feed doesn't allow,
+    /// which can't be toggled.
+    is_other: bool,
 }
 
-// Color constants for reaction buttons
-const REACTION_BG_SELECTED: Vec4 = Vec4 {
-    x: 0.91,
-    y: 0.96,
-    z: 0.99,
-    w: 1.0,
-}; // #e8f5fd
-const REACTION_BG_NORMAL: Vec4 = Vec4 {
-    x: 0.94,
-    y: 0.95,
-    z: 0.96,
-    w: 1.0,
-}; // #f0f2f5
-const REACTION_BORDER_SELECTED: Vec4 = Vec4 {
-    x: 0.11,
-    y: 0.61,
-    z: 0.94,
-    w: 1.0,
-}; // #1d9bf0
-const REACTION_BORDER_NORMAL: Vec4 = Vec4 {
-    x: 0.88,
-    y: 0.88,
-    z: 0.88,
-    w: 1.0,
-}; // #e0e0e0
-
 /// Widget for displaying a row of reaction buttons.
 #[derive(Live, LiveHook, Widget)]
 pub struct SocialReactionsRow {
@@ -476,6 +1270,10 @@ pub struct SocialReactionsRow {
     #[rust]
     reaction_buttons: Vec<(ButtonRef, ReactionButtonData)>,
 
+    /// Whether to show reaction counts, or only the emoji.
+    #[live(true)]
+    show_counts: bool,
+
     /// Layout for the widget.
     #[layout]
     layout: Layout,
@@ -493,6 +1291,9 @@ impl Widget for SocialReactionsRow {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, _scope: &mut Scope) {
         // Handle click events on reaction buttons
         for (button_ref, data) in &self.reaction_buttons {
+            if data.is_other {
+                continue;
+            }
             if let Hit::FingerUp(fue) = event.hits(cx, button_ref.area()) {
                 if fue.is_over && fue.is_primary_hit() && fue.was_tap() {
                     if let Some(event_id) = &self.event_id {
@@ -521,7 +1322,7 @@ impl SocialReactionsRow {
     pub fn set_reactions(
         &mut self,
         cx: &mut Cx,
-        reactions: &[crate::social::reactions::ReactionDisplay],
+        reactions: &[ReactionDisplay],
         event_id: OwnedEventId,
     ) {
         self.event_id = Some(event_id);
@@ -533,27 +1334,22 @@ impl SocialReactionsRow {
 
         for reaction in reactions {
             let button = WidgetRef::new_from_ptr(cx, Some(template)).as_button();
-            button.set_text(cx, &format!("{} {}", reaction.emoji, reaction.count));
-
-            // Apply styling based on whether the user has selected this reaction
-            let (bg_color, border_color) = if reaction.is_selected {
-                (REACTION_BG_SELECTED, REACTION_BORDER_SELECTED)
+            if self.show_counts {
+                button.set_text(cx, &format!("{} {}", reaction.emoji, reaction.count));
             } else {
-                (REACTION_BG_NORMAL, REACTION_BORDER_NORMAL)
-            };
+                button.set_text(cx, &reaction.emoji);
+            }
 
-            button.apply_over(
-                cx,
-                live! {
-                    draw_bg: { reaction_bg_color: (bg_color), reaction_border_color: (border_color) }
-                },
-            );
+            // Apply styling based on whether the user has selected this reaction
+            let selected = if reaction.is_selected { 1.0 } else { 0.0 };
+            button.apply_over(cx, live! { draw_bg: { selected: (selected) } });
 
             self.reaction_buttons.push((
                 button,
                 ReactionButtonData {
                     emoji: reaction.emoji.clone(),
                     is_selected: reaction.is_selected,
+                    is_other: reaction.is_other,
                 },
             ));
         }
@@ -571,7 +1367,7 @@ impl SocialReactionsRowRef {
     pub fn set_reactions(
         &self,
         cx: &mut Cx,
-        reactions: &[crate::social::reactions::ReactionDisplay],
+        reactions: &[ReactionDisplay],
         event_id: OwnedEventId,
     ) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -600,6 +1396,22 @@ pub struct SocialPostCard {
     #[rust]
     author_id: Option<OwnedUserId>,
 
+    /// The other author's user ID, if this is a collaborative post.
+    #[rust]
+    co_author_id: Option<OwnedUserId>,
+
+    /// The author's name and avatar, kept to show the avatar image once it's been fetched.
+    #[rust]
+    author_avatar: (String, Option<OwnedMxcUri>),
+
+    /// The other author's name and avatar, if this is a collaborative post.
+    #[rust]
+    co_author_avatar: Option<(String, Option<OwnedMxcUri>)>,
+
+    /// Whether any of the authors' avatar images are still being fetched.
+    #[rust]
+    avatars_pending: bool,
+
     /// Whether the current user has liked this post.
     #[rust]
     is_liked: bool,
@@ -608,14 +1420,105 @@ pub struct SocialPostCard {
     #[rust]
     is_bookmarked: bool,
 
+    /// Whether the current user watches this post for new comments.
+    #[rust]
+    is_watched: bool,
+
     /// Link URL if the post contains a link.
     #[rust]
     link_url: Option<String>,
+
+    /// Detected language of the post text.
+    #[rust]
+    language: Option<Language>,
+
+    /// License the post's content is shared under.
+    #[rust]
+    license: Option<ContentLicense>,
+
+    /// Description of the post's image for people who can't see it.
+    #[rust]
+    media_alt_text: Option<String>,
+
+    /// Whether the post's audio clip is playing.
+    #[rust]
+    is_audio_playing: bool,
+
+    /// Whether the post has any reactions to show.
+    #[rust]
+    has_reactions: bool,
+
+    /// Whether to show details meant for developers, e.g., the raw type of unsupported posts.
+    #[rust]
+    show_developer_details: bool,
+
+    /// Whether the post failed to send and can be retried.
+    #[rust]
+    send_failed: bool,
+
+    /// Whether the post is pinned to its feed.
+    #[rust]
+    is_pinned: bool,
+
+    /// Text content of the post, as sent for translation.
+    #[rust]
+    text: String,
+
+    /// The post's text translated into the user's language, once it has been.
+    #[rust]
+    translation: Option<Translation>,
+
+    /// Whether the translation is shown instead of the post's text.
+    #[rust]
+    is_showing_translation: bool,
+
+    /// The feed density the card is laid out for.
+    #[rust]
+    density: FeedDensity,
 }
 
 impl Widget for SocialPostCard {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
-        self.view.handle_event(cx, event, scope);
+        // Show the authors' avatar images once they've been fetched
+        if self.avatars_pending {
+            if let Event::Signal = event {
+                avatar_cache::process_avatar_updates(cx);
+                self.show_avatars(cx);
+                if !self.avatars_pending {
+                    self.redraw(cx);
+                }
+            }
+        }
+
+        // Handle the links tapped in this post's text here, so that other post cards don't
+        let mut actions = cx.capture_actions(|cx| self.view.handle_event(cx, event, scope));
+        actions.retain(|action| !self.handle_text_link(cx, action));
+        cx.extend_actions(actions);
+
+        // Describe the icon-only action buttons when they're hovered
+        for (button_id, label) in self.action_button_labels() {
+            let area = self.button(button_id).area();
+            match event.hits_with_capture_overload(cx, area, true) {
+                Hit::FingerHoverIn(_) | Hit::FingerLongPress(_) => {
+                    cx.widget_action(
+                        self.widget_uid(),
+                        &scope.path,
+                        TooltipAction::HoverIn {
+                            text: label.to_owned(),
+                            widget_rect: area.rect(cx),
+                            options: CalloutTooltipOptions {
+                                position: TooltipPosition::Top,
+                                ..Default::default()
+                            },
+                        },
+                    );
+                }
+                Hit::FingerHoverOut(_) => {
+                    cx.widget_action(self.widget_uid(), &scope.path, TooltipAction::HoverOut);
+                }
+                _ => {}
+            }
+        }
         self.widget_match_event(cx, event, scope);
     }
 
@@ -626,15 +1529,27 @@ impl Widget for SocialPostCard {
 
 impl WidgetMatchEvent for SocialPostCard {
     fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
-        let Some(event_id) = &self.event_id else {
+        // Owned, as the card itself changes while handling the actions
+        let Some(event_id) = self.event_id.clone() else {
             return;
         };
+        let event_id = &event_id;
+
+        // Handle retrying a post that failed to send
+        if self.send_failed && self.button(ids!(pending_indicator)).clicked(actions) {
+            cx.action(SocialPostCardAction::RetrySend(event_id.clone()));
+        }
 
         // Handle comment button
         if self.button(ids!(comment_button)).clicked(actions) {
             cx.action(SocialPostCardAction::Comment(event_id.clone()));
         }
 
+        // Handle reply button
+        if self.button(ids!(reply_button)).clicked(actions) {
+            cx.action(SocialPostCardAction::Reply(event_id.clone()));
+        }
+
         // Handle share button
         if self.button(ids!(share_button)).clicked(actions) {
             cx.action(SocialPostCardAction::Share(event_id.clone()));
@@ -649,6 +1564,23 @@ impl WidgetMatchEvent for SocialPostCard {
             }
         }
 
+        // Show or hide the quick reactions, which are hidden again once one is picked
+        let quick_reactions_row = self.view(ids!(quick_reactions_row));
+        if self.button(ids!(react_button)).clicked(actions) {
+            quick_reactions_row.set_visible(cx, !quick_reactions_row.visible());
+            self.redraw(cx);
+        }
+        let picked_quick_reaction = actions.iter().any(|action| {
+            matches!(
+                action.downcast_ref::<SocialPostCardAction>(),
+                Some(SocialPostCardAction::ToggleReaction { event_id: id, .. }) if id == event_id
+            )
+        });
+        if picked_quick_reaction && quick_reactions_row.visible() {
+            quick_reactions_row.set_visible(cx, false);
+            self.redraw(cx);
+        }
+
         // Handle bookmark button
         if self.button(ids!(bookmark_button)).clicked(actions) {
             if self.is_bookmarked {
@@ -658,9 +1590,68 @@ impl WidgetMatchEvent for SocialPostCard {
             }
         }
 
-        // Handle more options button
+        // Handle watch button
+        if self.button(ids!(watch_button)).clicked(actions) {
+            if self.is_watched {
+                cx.action(SocialPostCardAction::Unwatch(event_id.clone()));
+            } else {
+                cx.action(SocialPostCardAction::Watch(event_id.clone()));
+            }
+        }
+
+        // Handle more options button and the options it shows
+        let more_options_menu = self.view(ids!(more_options_menu));
         if self.button(ids!(more_button)).clicked(actions) {
-            cx.action(SocialPostCardAction::ShowMoreOptions(event_id.clone()));
+            let show_options = !more_options_menu.visible();
+            more_options_menu.set_visible(cx, show_options);
+            if show_options {
+                cx.action(SocialPostCardAction::ShowMoreOptions(event_id.clone()));
+            }
+            self.redraw(cx);
+        }
+        if self.button(ids!(send_to_chat_button)).clicked(actions) {
+            more_options_menu.set_visible(cx, false);
+            cx.action(SocialPostCardAction::SendToChat(event_id.clone()));
+            self.redraw(cx);
+        }
+        if self.button(ids!(translate_post_button)).clicked(actions) {
+            more_options_menu.set_visible(cx, false);
+            self.translate(cx);
+        }
+        if self.button(ids!(pin_post_button)).clicked(actions) {
+            more_options_menu.set_visible(cx, false);
+            cx.action(SocialPostCardAction::PinPost {
+                event_id: event_id.clone(),
+                pinned: !self.is_pinned,
+            });
+            self.redraw(cx);
+        }
+        if self.button(ids!(remove_post_button)).clicked(actions) {
+            more_options_menu.set_visible(cx, false);
+            cx.action(SocialPostCardAction::RemovePost(event_id.clone()));
+            self.redraw(cx);
+        }
+        if self.button(ids!(ban_author_button)).clicked(actions) {
+            more_options_menu.set_visible(cx, false);
+            cx.action(SocialPostCardAction::BanAuthor(event_id.clone()));
+            self.redraw(cx);
+        }
+
+        // Tapping the verified badge goes through the verification flow again
+        if self.button(ids!(verified_badge)).clicked(actions) {
+            if let Some(author_id) = &self.author_id {
+                submit_async_request(MatrixRequest::VerifyUser(author_id.clone()));
+            }
+        }
+        for action in actions {
+            if let Some(UserVerificationAction::Checked { user_id, verified }) =
+                action.downcast_ref()
+            {
+                if self.author_id.as_ref() == Some(user_id) {
+                    self.show_verified_badge(cx, *verified);
+                    self.redraw(cx);
+                }
+            }
         }
 
         // Handle author avatar click
@@ -669,6 +1660,17 @@ impl WidgetMatchEvent for SocialPostCard {
                 cx.action(SocialPostCardAction::ViewAuthorProfile(author_id.clone()));
             }
         }
+        if self
+            .view(ids!(co_author_avatar))
+            .finger_up(actions)
+            .is_some()
+        {
+            if let Some(co_author_id) = &self.co_author_id {
+                cx.action(SocialPostCardAction::ViewAuthorProfile(
+                    co_author_id.clone(),
+                ));
+            }
+        }
 
         // Handle media click
         if self
@@ -685,26 +1687,267 @@ impl WidgetMatchEvent for SocialPostCard {
                 cx.action(SocialPostCardAction::OpenLink(url.clone()));
             }
         }
+
+        // Handle map thumbnail click
+        if let Some(location) = self.map_thumbnail(ids!(map_thumbnail)).clicked(actions) {
+            cx.action(SocialPostCardAction::ViewLocation(location));
+        }
+
+        // Open the terms of the post's license
+        if self.button(ids!(license_chip)).clicked(actions) {
+            if let Some(url) = self.license.and_then(|license| license.url()) {
+                cx.action(SocialPostCardAction::OpenLink(url.to_string()));
+            }
+        }
+
+        // Play or pause the audio clip
+        if self.button(ids!(play_audio_button)).clicked(actions) {
+            if self.is_audio_playing {
+                cx.action(SocialPostCardAction::PauseAudio(event_id.clone()));
+            } else {
+                cx.action(SocialPostCardAction::PlayAudio(event_id.clone()));
+            }
+        }
+
+        // Show or hide the description of the image
+        if self.button(ids!(alt_text_chip)).clicked(actions) {
+            let alt_text_label = self.label(ids!(alt_text_label));
+            alt_text_label.set_visible(cx, !alt_text_label.visible());
+        }
+
+        // Handle language row buttons
+        if self.button(ids!(translate_button)).clicked(actions) {
+            self.translate(cx);
+        }
+
+        // Switch between the translation and the original text
+        if self.button(ids!(show_original_button)).clicked(actions) {
+            self.is_showing_translation = !self.is_showing_translation;
+            self.show_translation(cx);
+        }
+        for action in actions {
+            match action.downcast_ref() {
+                Some(TranslationAction::Translated {
+                    event_id: translated_id,
+                    translation,
+                }) if translated_id == event_id => {
+                    self.translation = Some(translation.clone());
+                    self.is_showing_translation = true;
+                    self.show_translation(cx);
+                }
+                Some(TranslationAction::Failed {
+                    event_id: failed_id,
+                    error,
+                }) if failed_id == event_id => {
+                    self.label(ids!(translation_label))
+                        .set_text(cx, &format!("Couldn't translate: {error}"));
+                    self.redraw(cx);
+                }
+                _ => {}
+            }
+        }
+        if self.button(ids!(hide_language_button)).clicked(actions) {
+            if let Some(language) = self.language {
+                cx.action(SocialPostCardAction::HideLanguage(language));
+            }
+        }
+
+        // Handle the likely spam interstitial
+        if self.button(ids!(show_anyway_button)).clicked(actions) {
+            self.set_hidden_as_spam(cx, false);
+        }
+        if self.button(ids!(not_spam_button)).clicked(actions) {
+            if let Some(author_id) = &self.author_id {
+                cx.action(SocialPostCardAction::ReportNotSpam {
+                    event_id: event_id.clone(),
+                    sender: author_id.clone(),
+                });
+            }
+            self.set_hidden_as_spam(cx, false);
+        }
     }
 }
 
 impl SocialPostCard {
+    /// Get the accessible labels of the icon-only action buttons, describing what they do.
+    fn action_button_labels(&self) -> [(&'static [LiveId], &'static str); 8] {
+        [
+            (ids!(comment_button), "Comment"),
+            (ids!(reply_button), "Reply"),
+            (ids!(share_button), "Share"),
+            (
+                ids!(like_button),
+                if self.is_liked { "Unlike" } else { "Like" },
+            ),
+            (ids!(react_button), "Add a reaction"),
+            (
+                ids!(watch_button),
+                if self.is_watched {
+                    "Stop notifications about new comments"
+                } else {
+                    "Notify me about new comments"
+                },
+            ),
+            (
+                ids!(bookmark_button),
+                if self.is_bookmarked {
+                    "Remove bookmark"
+                } else {
+                    "Bookmark"
+                },
+            ),
+            (ids!(more_button), "More options"),
+        ]
+    }
+
+    /// Translate the post into the user's language, or show its translation if it has one already.
+    fn translate(&mut self, cx: &mut Cx) {
+        let Some(event_id) = self.event_id.clone() else {
+            return;
+        };
+        cx.action(SocialPostCardAction::TranslatePost(event_id.clone()));
+        if self.translation.is_some() {
+            self.is_showing_translation = true;
+            self.show_translation(cx);
+            return;
+        }
+        self.view(ids!(translation_view)).set_visible(cx, true);
+        self.label(ids!(translated_text)).set_visible(cx, false);
+        self.button(ids!(show_original_button))
+            .set_visible(cx, false);
+        self.label(ids!(translation_label))
+            .set_text(cx, "Translating…");
+        submit_async_request(MatrixRequest::TranslatePost {
+            event_id,
+            text: self.text.clone(),
+        });
+        self.redraw(cx);
+    }
+
+    /// Show the post's translation in place of its text, or its original text
+    /// with the option to show the translation again, if it has been translated.
+    fn show_translation(&mut self, cx: &mut Cx) {
+        let translation_view = self.view(ids!(translation_view));
+        let Some(translation) = &self.translation else {
+            translation_view.set_visible(cx, false);
+            self.html_or_plaintext(ids!(text_content))
+                .set_visible(cx, true);
+            return;
+        };
+        let showing = self.is_showing_translation;
+        translation_view.set_visible(cx, true);
+        self.html_or_plaintext(ids!(text_content))
+            .set_visible(cx, !showing);
+        let translated_text = self.label(ids!(translated_text));
+        translated_text.set_visible(cx, showing);
+        translated_text.set_text(cx, &translation.text);
+        let source = translation.source_language_name().map_or_else(
+            || "Translated".to_owned(),
+            |name| format!("Translated from {name}"),
+        );
+        self.label(ids!(translation_label)).set_text(cx, &source);
+        let show_original_button = self.button(ids!(show_original_button));
+        show_original_button.set_visible(cx, true);
+        show_original_button.set_text(
+            cx,
+            if showing {
+                "Show original"
+            } else {
+                "Show translation"
+            },
+        );
+        self.redraw(cx);
+    }
+
+    /// Handle a link, mention or hashtag tapped in the post's text.
+    ///
+    /// Returns whether `action` was such a tap.
+    fn handle_text_link(&mut self, cx: &mut Cx, action: &Action) -> bool {
+        let link = if let HtmlLinkAction::Clicked { url, .. } = action.as_widget_action().cast() {
+            PostLink::from_url(&url)
+        } else if let RobrixHtmlLinkAction::ClickedMatrixLink { url, matrix_id, .. } =
+            action.as_widget_action().cast()
+        {
+            match matrix_id {
+                MatrixId::User(user_id) => PostLink::Mention(user_id),
+                _ => PostLink::Url(url),
+            }
+        } else {
+            return false;
+        };
+        cx.action(match link {
+            PostLink::Url(url) => SocialPostCardAction::OpenLink(url),
+            PostLink::Mention(user_id) => SocialPostCardAction::ViewAuthorProfile(user_id),
+            PostLink::Hashtag(hashtag) => SocialPostCardAction::ViewHashtag(hashtag),
+        });
+        true
+    }
+
+    /// Show or hide the author's verified badge.
+    fn show_verified_badge(&mut self, cx: &mut Cx, is_verified: bool) {
+        self.button(ids!(verified_badge))
+            .set_visible(cx, is_verified);
+    }
+
+    /// Show the authors' avatar images, or the first letters of their names
+    /// while the images are being fetched.
+    fn show_avatars(&mut self, cx: &mut Cx) {
+        let (author_name, author_avatar_url) = &self.author_avatar;
+        let mut drawn = self.avatar(ids!(author_avatar)).show_avatar_uri(
+            cx,
+            author_name,
+            author_avatar_url.as_deref(),
+        );
+        if let Some((co_author_name, co_author_avatar_url)) = &self.co_author_avatar {
+            drawn &= self.avatar(ids!(co_author_avatar)).show_avatar_uri(
+                cx,
+                co_author_name,
+                co_author_avatar_url.as_deref(),
+            );
+        }
+        self.avatars_pending = !drawn;
+    }
+
     /// Set the post data to display.
     pub fn set_post(&mut self, cx: &mut Cx, data: &PostCardData) {
+        let is_new_post = self.event_id.as_ref() != Some(&data.event_id);
         self.event_id = Some(data.event_id.clone());
         self.author_id = Some(data.author_id.clone());
         self.is_liked = data.is_liked;
         self.is_bookmarked = data.is_bookmarked;
+        self.is_watched = data.is_watched;
 
         // Set author info
         let display_name = data
             .author_name
             .as_deref()
             .unwrap_or_else(|| data.author_id.localpart());
-        self.avatar(ids!(author_avatar)).set_text(cx, display_name);
+        self.author_avatar = (display_name.to_owned(), data.author_avatar_url.clone());
         self.label(ids!(author_name)).set_text(cx, display_name);
         self.label(ids!(author_username))
             .set_text(cx, &format!("@{}", data.author_id.localpart()));
+        let is_own_post = current_user_id().is_some_and(|own| own == data.author_id);
+        let is_verified = !is_own_post && user_verification(&data.author_id) == Some(true);
+        self.show_verified_badge(cx, is_verified);
+
+        // Show the other author of a collaborative post
+        self.co_author_id = data.co_author.clone();
+        self.co_author_avatar = data.co_author.as_ref().map(|co_author| {
+            (
+                co_author.localpart().to_owned(),
+                data.co_author_avatar_url.clone(),
+            )
+        });
+        if let Some(co_author) = &data.co_author {
+            let co_author_name = co_author.localpart();
+            self.label(ids!(co_author_label))
+                .set_text(cx, &format!("with {}", co_author_name));
+        }
+        self.widget(ids!(co_author_avatar))
+            .set_visible(cx, data.co_author.is_some());
+        self.label(ids!(co_author_label))
+            .set_visible(cx, data.co_author.is_some());
+        self.show_avatars(cx);
 
         // Set timestamp
         let timestamp_text = format_timestamp(data.timestamp);
@@ -713,9 +1956,41 @@ impl SocialPostCard {
         // Set edited indicator
         self.label(ids!(edited_indicator))
             .set_visible(cx, data.is_edited);
+        self.send_failed = data.send_failed;
+        let pending_indicator = self.button(ids!(pending_indicator));
+        pending_indicator.set_visible(cx, data.is_pending || data.send_failed);
+        pending_indicator.set_text(
+            cx,
+            if data.send_failed {
+                "Failed, tap to retry"
+            } else {
+                "Sending…"
+            },
+        );
+
+        // Set text content, rendering Markdown in posts sent without an HTML body,
+        // e.g., links, code, blockquotes and spoilers that are revealed when tapped,
+        // and making its links, mentions and hashtags tappable
+        self.text = data.text.clone();
+        let text_content = self.html_or_plaintext(ids!(text_content));
+        match post_text_html(&data.text, data.formatted_text.as_deref()) {
+            Some(formatted_text) => text_content.show_html(cx, formatted_text),
+            None => text_content.show_plaintext(cx, &data.text),
+        }
 
-        // Set text content
-        self.label(ids!(text_content)).set_text(cx, &data.text);
+        // Offer translate/hide for posts not in the user's own language
+        self.language = data.language;
+        let foreign_language = data
+            .language
+            .filter(|language| *language != Language::system());
+        if let Some(language) = foreign_language {
+            self.label(ids!(language_label))
+                .set_text(cx, &format!("Written in {}", language));
+            self.button(ids!(hide_language_button))
+                .set_text(cx, &format!("Hide {}", language));
+        }
+        self.view(ids!(language_row))
+            .set_visible(cx, foreign_language.is_some());
 
         // Set media if present
         if data.media_url.is_some() {
@@ -741,6 +2016,55 @@ impl SocialPostCard {
             self.view(ids!(link_preview)).set_visible(cx, false);
         }
 
+        // Show a map of the post's location, if it has one
+        self.map_thumbnail(ids!(map_thumbnail))
+            .set_location(cx, data.location.as_ref());
+
+        // Show the license as a chip below the content
+        self.license = data.license;
+        let license_chip = self.button(ids!(license_chip));
+        if let Some(license) = data.license {
+            license_chip.set_text(cx, license.name());
+        }
+        license_chip.set_visible(cx, data.license.is_some());
+
+        // Offer the image's alt text, hidden until the "ALT" chip is clicked
+        self.media_alt_text = data.media_alt_text.clone();
+        let alt_text_label = self.label(ids!(alt_text_label));
+        alt_text_label.set_text(cx, data.media_alt_text.as_deref().unwrap_or_default());
+        alt_text_label.set_visible(cx, false);
+        self.button(ids!(alt_text_chip))
+            .set_visible(cx, data.media_alt_text.is_some());
+
+        // Show the player of an audio clip, stopped when another post is shown
+        if is_new_post {
+            self.set_audio_progress(cx, None);
+            if let Some(audio) = &data.audio {
+                self.audio_waveform(ids!(audio_waveform))
+                    .set_audio(cx, audio);
+            }
+        }
+        if let Some(audio) = &data.audio {
+            self.label(ids!(audio_duration_label))
+                .set_text(cx, &audio.duration_text().unwrap_or_default());
+        }
+        self.view(ids!(audio_player))
+            .set_visible(cx, data.audio.is_some());
+
+        // Show the answers and votes of a poll post
+        self.social_poll_view(ids!(poll_view))
+            .set_results(cx, data.poll.as_ref());
+
+        // Point out media that breaks the feed's restrictions to its moderators
+        let media_violation_label = self.label(ids!(media_violation_label));
+        if let Some(violation) = &data.media_violation {
+            media_violation_label.set_text(
+                cx,
+                &format!("This post breaks the feed's media rules: {violation}."),
+            );
+        }
+        media_violation_label.set_visible(cx, data.media_violation.is_some());
+
         // Set action button counts
         self.button(ids!(comment_button))
             .set_text(cx, &format!("💬 {}", data.comment_count));
@@ -761,12 +2085,18 @@ impl SocialPostCard {
         self.button(ids!(bookmark_button))
             .set_text(cx, bookmark_text);
 
+        // Set watch button state
+        let watch_button = self.button(ids!(watch_button));
+        watch_button.set_visible(cx, !data.is_own_post);
+        watch_button.set_text(cx, if self.is_watched { "🔔" } else { "🔕" });
+
         // Populate and show reactions row if there are reactions
         let has_reactions = !data.reactions.is_empty();
+        self.has_reactions = has_reactions;
         if has_reactions {
             // Convert reactions to display format (using None for current user since
             // we track liked state separately via is_liked)
-            let display_reactions = reactions_for_display(&data.reactions, None);
+            let display_reactions = reactions_for_display(&data.reactions, None, &data.feed_config);
             if let Some(mut reactions_row) = self
                 .view
                 .widget(ids!(reactions_row))
@@ -775,8 +2105,157 @@ impl SocialPostCard {
                 reactions_row.set_reactions(cx, &display_reactions, data.event_id.clone());
             }
         }
+
+        // Offer only the reactions the feed allows
+        let quick_reactions: Vec<_> = quick_reactions(&data.feed_config)
+            .into_iter()
+            .map(|emoji| ReactionDisplay::new(emoji, 0, false))
+            .collect();
+        if let Some(mut quick_reactions_row) = self
+            .view
+            .widget(ids!(quick_reactions_row))
+            .borrow_mut::<SocialReactionsRow>()
+        {
+            quick_reactions_row.set_reactions(cx, &quick_reactions, data.event_id.clone());
+        }
+        if is_new_post {
+            self.view(ids!(quick_reactions_row)).set_visible(cx, false);
+            self.view(ids!(more_options_menu)).set_visible(cx, false);
+            self.translation = None;
+            self.is_showing_translation = false;
+            self.show_translation(cx);
+        }
+
+        // Offer the moderation options the user has in the post's feed
+        self.is_pinned = data.is_pinned;
+        self.label(ids!(pinned_badge))
+            .set_visible(cx, data.is_pinned);
+        let pin_post_button = self.button(ids!(pin_post_button));
+        pin_post_button.set_visible(cx, data.moderation.can_pin);
+        pin_post_button.set_text(
+            cx,
+            if data.is_pinned {
+                "Unpin post"
+            } else {
+                "Pin post"
+            },
+        );
+        self.button(ids!(remove_post_button))
+            .set_visible(cx, data.moderation.can_remove_posts);
+        self.button(ids!(ban_author_button))
+            .set_visible(cx, data.moderation.can_ban && !data.is_own_post);
+
+        // Hide likely spam until the user chooses to show it
+        if !data.hidden_as_spam.is_empty() {
+            let reasons: Vec<_> = data.hidden_as_spam.iter().map(ToString::to_string).collect();
+            self.label(ids!(spam_reason_label)).set_text(
+                cx,
+                &format!("This post may be spam: {}.", reasons.join(", ")),
+            );
+        }
+        self.set_hidden_as_spam(cx, !data.hidden_as_spam.is_empty());
+
+        // Show a notice instead of a post that couldn't be deserialized or decrypted
+        let notice = match (&data.unsupported, &data.undecryptable) {
+            (Some(unsupported), _) => Some((
+                UNSUPPORTED_POST_TEXT,
+                format!("{}: {}", unsupported.event_type, unsupported.reason),
+            )),
+            (None, Some(undecryptable)) => {
+                Some((UNDECRYPTABLE_POST_TEXT, undecryptable.to_string()))
+            }
+            (None, None) => None,
+        };
+        self.view(ids!(unsupported_notice))
+            .set_visible(cx, notice.is_some());
+        if let Some((text, details)) = notice {
+            self.label(ids!(unsupported_label)).set_text(cx, text);
+            let details_label = self.label(ids!(unsupported_details_label));
+            details_label.set_visible(cx, self.show_developer_details);
+            details_label.set_text(cx, &details);
+            self.view(ids!(spam_interstitial)).set_visible(cx, false);
+            self.view(ids!(content_section)).set_visible(cx, false);
+            self.view(ids!(reactions_row)).set_visible(cx, false);
+            self.view(ids!(quick_reactions_row)).set_visible(cx, false);
+            self.view(ids!(action_bar)).set_visible(cx, false);
+        }
+    }
+
+    /// Set whether to show details meant for developers,
+    /// e.g., the raw event type of unsupported posts.
+    ///
+    /// This applies from the next call to [`Self::set_post()`].
+    pub fn set_show_developer_details(&mut self, show: bool) {
+        self.show_developer_details = show;
+    }
+
+    /// Highlight the card while it has keyboard focus in the feed.
+    pub fn set_focused(&mut self, cx: &mut Cx, focused: bool) {
+        let focused = if focused { 1.0 } else { 0.0 };
+        self.view
+            .apply_over(cx, live! { draw_bg: { focused: (focused) } });
+        self.redraw(cx);
+    }
+
+    /// Lay out the card for a feed density, changing its padding,
+    /// the size of the authors' avatars and the height of its media.
+    pub fn set_density(&mut self, cx: &mut Cx, density: FeedDensity) {
+        if self.density == density {
+            return;
+        }
+        self.density = density;
+        let (padding, avatar_size, media_height) = density_dimensions(density);
+        self.view.apply_over(
+            cx,
+            live! {
+                padding: { left: (padding), right: (padding), top: (padding), bottom: (padding) }
+            },
+        );
+        self.view(ids!(author_avatar))
+            .apply_over(cx, live! { width: (avatar_size), height: (avatar_size) });
+        // The other author's avatar overlaps the bottom of the author's avatar
+        self.view(ids!(co_author_avatar))
+            .apply_over(cx, live! { margin: { top: (avatar_size / 2.0) } });
+        self.view
+            .image(ids!(media_image))
+            .apply_over(cx, live! { height: (media_height) });
+        let indent = avatar_size + AVATAR_SPACING;
+        for section in INDENTED_SECTIONS {
+            self.widget(section)
+                .apply_over(cx, live! { margin: { left: (indent) } });
+        }
+        self.redraw(cx);
+    }
+
+    /// Show how much of the post's audio clip was played, from 0.0 to 1.0,
+    /// while it's playing, or `None` once it's paused or stopped.
+    pub fn set_audio_progress(&mut self, cx: &mut Cx, progress: Option<f64>) {
+        self.is_audio_playing = progress.is_some();
+        self.button(ids!(play_audio_button))
+            .set_text(cx, if self.is_audio_playing { "⏸" } else { "▶" });
+        if let Some(progress) = progress {
+            self.audio_waveform(ids!(audio_waveform))
+                .set_progress(cx, progress);
+        }
+    }
+
+    /// Get the description of the post's image, for screen readers and other
+    /// assistive technology that can't see the image itself.
+    pub fn media_alt_text(&self) -> Option<&str> {
+        self.media_alt_text.as_deref()
+    }
+
+    /// Show the likely spam interstitial in place of the post, or show the post itself.
+    fn set_hidden_as_spam(&mut self, cx: &mut Cx, hidden: bool) {
+        self.view(ids!(spam_interstitial)).set_visible(cx, hidden);
+        self.view(ids!(content_section)).set_visible(cx, !hidden);
         self.view(ids!(reactions_row))
-            .set_visible(cx, has_reactions);
+            .set_visible(cx, !hidden && self.has_reactions);
+        self.view(ids!(action_bar)).set_visible(cx, !hidden);
+        if hidden {
+            self.view(ids!(quick_reactions_row)).set_visible(cx, false);
+        }
+        self.redraw(cx);
     }
 
     /// Update the like state.
@@ -798,6 +2277,13 @@ impl SocialPostCard {
             .set_text(cx, bookmark_text);
     }
 
+    /// Update whether the current user watches the post for new comments.
+    pub fn set_watched(&mut self, cx: &mut Cx, is_watched: bool) {
+        self.is_watched = is_watched;
+        self.button(ids!(watch_button))
+            .set_text(cx, if is_watched { "🔔" } else { "🔕" });
+    }
+
     /// Set the media texture for displaying an image in the post.
     ///
     /// This method should be called when the media image has been loaded
@@ -844,6 +2330,40 @@ impl SocialPostCardRef {
         }
     }
 
+    /// See [`SocialPostCard::set_show_developer_details()`].
+    pub fn set_show_developer_details(&self, show: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_show_developer_details(show);
+        }
+    }
+
+    /// See [`SocialPostCard::set_focused()`].
+    pub fn set_focused(&self, cx: &mut Cx, focused: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_focused(cx, focused);
+        }
+    }
+
+    /// See [`SocialPostCard::set_density()`].
+    pub fn set_density(&self, cx: &mut Cx, density: FeedDensity) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_density(cx, density);
+        }
+    }
+
+    /// See [`SocialPostCard::set_audio_progress()`].
+    pub fn set_audio_progress(&self, cx: &mut Cx, progress: Option<f64>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_audio_progress(cx, progress);
+        }
+    }
+
+    /// See [`SocialPostCard::media_alt_text()`].
+    pub fn media_alt_text(&self) -> Option<String> {
+        self.borrow()
+            .and_then(|inner| inner.media_alt_text().map(str::to_owned))
+    }
+
     /// See [`SocialPostCard::set_liked()`].
     pub fn set_liked(&self, cx: &mut Cx, is_liked: bool, count: u32) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -858,6 +2378,13 @@ impl SocialPostCardRef {
         }
     }
 
+    /// See [`SocialPostCard::set_watched()`].
+    pub fn set_watched(&self, cx: &mut Cx, is_watched: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_watched(cx, is_watched);
+        }
+    }
+
     /// See [`SocialPostCard::set_media_texture()`].
     pub fn set_media_texture(&self, cx: &mut Cx, texture: Option<Texture>) {
         if let Some(mut inner) = self.borrow_mut() {
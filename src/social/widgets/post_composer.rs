@@ -1,28 +1,107 @@
 //! Post composer widget for creating new posts.
 //!
 //! This widget provides a UI for composing social media posts with
-//! text input, media attachments, and audience/privacy selection.
+//! text input, media attachments (photos, videos and audio clips such as
+//! voice notes), an optional co-author, and the license the post is shared
+//! under. Attached photos can be given alt text for people who can't see them.
+//! The user picks one or more of their feeds to post to; posting to several
+//! cross-posts the same post to each, once [`SharingGuard`] has validated
+//! that the combination of audiences doesn't leak a more private post.
+//! The composer can also reply to a post, quoting it above the text input
+//! (see [`SocialPostComposer::start_reply()`]); the reply is sent to the
+//! post's thread instead of the user's feeds.
+//! Before a post whose text the user already posted in the last day is
+//! sent, the composer asks for confirmation
+//! (see [`SocialPostComposer::set_recent_posts()`]).
+//! The configuration of each feed posted to is fetched when its audience is
+//! selected, and posting is blocked while the attached media breaks the feed's
+//! media restrictions (see [`media_policy`](crate::social::media_policy)).
+//! Instead of attaching media, the user can turn the post into a poll, whose
+//! question is the post's text (see [`poll`](crate::social::poll)).
+//! Posts and replies are written in Markdown; the formatting bar inserts the
+//! Markdown for bold, italic, strikethrough, code, spoilers and bullet lists at the cursor.
 
-use makepad_widgets::*;
-use std::path::PathBuf;
+use makepad_widgets::{text::selection::Cursor, *};
+use matrix_sdk::ruma::{MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId};
+use robrix_social_events::{
+    feed_config::{MediaKind, SocialFeedConfigEventContent},
+    license::ContentLicense,
+};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::shared::avatar::AvatarWidgetExt;
-use crate::social::feed_room::FeedPrivacy;
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::social::duplicate_media::UploadedImage;
+use crate::social::duplicate_posts::RecentPostIndex;
+use crate::social::feed_room::{FeedPrivacy, UserFeeds};
+use crate::social::media_policy::{check_media, MediaViolation};
+use crate::social::outbox::OutboxMedia;
+use crate::social::privacy::{PrivacyLevel, ShareValidation, SharingGuard};
+use crate::social::widgets::post_card::PostCardData;
+
+/// The audience checkboxes and the feeds they post to.
+const AUDIENCE_TARGETS: [(&[LiveId], FeedPrivacy); 3] = [
+    (ids!(public_target), FeedPrivacy::Public),
+    (ids!(friends_target), FeedPrivacy::Friends),
+    (ids!(close_friends_target), FeedPrivacy::CloseFriends),
+];
+
+/// The inputs for the answers of a poll, of which at least two must be filled in.
+const POLL_ANSWER_INPUTS: [&[LiveId]; 4] = [
+    ids!(poll_answer_1),
+    ids!(poll_answer_2),
+    ids!(poll_answer_3),
+    ids!(poll_answer_4),
+];
+
+/// The maximum number of characters of a post quoted when replying to it.
+const REPLY_PREVIEW_CHARS: usize = 200;
+
+/// The formatting bar's buttons and the Markdown they insert before and after the cursor.
+///
+/// Bullet list items are started on a new line (see [`SocialPostComposer::insert_formatting()`]).
+const FORMAT_BUTTONS: [(&[LiveId], &str, &str); 6] = [
+    (ids!(bold_button), "**", "**"),
+    (ids!(italic_button), "_", "_"),
+    (ids!(strikethrough_button), "~~", "~~"),
+    (ids!(code_button), "`", "`"),
+    (ids!(spoiler_button), "||", "||"),
+    (ids!(bullet_list_button), "- ", ""),
+];
 
 live_design! {
     use link::theme::*;
     use link::shaders::*;
     use link::widgets::*;
+    use link::social_theme::*;
 
     use crate::shared::styles::*;
     use crate::shared::avatar::Avatar;
     use crate::shared::icon_button::RobrixIconButton;
 
-    COMPOSER_BG_COLOR = #fff
-    COMPOSER_BORDER_COLOR = #e0e0e0
-    INPUT_BG_COLOR = #f5f5f5
-    BUTTON_PRIMARY_COLOR = #1d9bf0
-    BUTTON_DISABLED_COLOR = #87ceeb
+    COMPOSER_BG_COLOR = (SOCIAL_BG)
+    COMPOSER_BORDER_COLOR = (SOCIAL_BORDER)
+    INPUT_BG_COLOR = (SOCIAL_SURFACE)
+    BUTTON_PRIMARY_COLOR = (SOCIAL_ACCENT)
+    BUTTON_DISABLED_COLOR = (SOCIAL_ACCENT_DISABLED)
+
+    /// A button of the formatting bar.
+    FormatButton = <Button> {
+        width: 32,
+        height: 28,
+        draw_bg: {
+            color: #0000,
+            border_size: 0.0,
+        }
+        draw_text: {
+            color: (SOCIAL_TEXT_SECONDARY),
+            text_style: { font_size: 13.0 }
+        }
+    }
 
     /// Post composer widget for creating new social posts.
     pub SocialPostComposer = {{SocialPostComposer}} {
@@ -45,7 +124,7 @@ live_design! {
             }
         }
 
-        // Header row with avatar and audience selector
+        // Header row with avatar and the feeds to post to
         header_row = <View> {
             width: Fill,
             height: Fit,
@@ -58,10 +137,109 @@ live_design! {
                 height: 40,
             }
 
-            audience_dropdown = <DropDown> {
+            // Checking several audiences cross-posts to each of their feeds
+            audience_targets = <View> {
+                width: Fit,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+                align: { y: 0.5 },
+
+                public_target = <CheckBoxFlat> {
+                    text: "Public",
+                    active: true,
+                }
+                friends_target = <CheckBoxFlat> {
+                    text: "Friends",
+                    active: false,
+                }
+                close_friends_target = <CheckBoxFlat> {
+                    text: "Close Friends",
+                    active: false,
+                }
+            }
+
+            <View> { width: Fill, height: 1 }
+
+            // Labels follow the order of `ContentLicense::ALL`
+            license_dropdown = <DropDown> {
                 width: Fit,
                 height: Fit,
-                labels: ["Public", "Friends", "Close Friends"],
+                labels: [
+                    "No license",
+                    "All rights reserved",
+                    "CC0 1.0",
+                    "CC BY 4.0",
+                    "CC BY-SA 4.0",
+                    "CC BY-NC 4.0",
+                    "CC BY-NC-SA 4.0",
+                    "CC BY-ND 4.0",
+                    "CC BY-NC-ND 4.0",
+                ],
+            }
+        }
+
+        // The post being replied to (shown in reply mode)
+        reply_preview = <View> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            flow: Right,
+            spacing: 8,
+            padding: 12,
+            show_bg: true,
+            draw_bg: {
+                color: (INPUT_BG_COLOR),
+                fn pixel(self) -> vec4 {
+                    let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                    sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 8.);
+                    sdf.fill(self.color);
+                    // Quote bar
+                    sdf.rect(0., 0., 3., self.rect_size.y);
+                    sdf.fill((BUTTON_PRIMARY_COLOR));
+                    return sdf.result;
+                }
+            }
+
+            reply_quote = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                spacing: 4,
+
+                reply_author_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: <THEME_FONT_BOLD> { font_size: 12.0 },
+                        color: (BUTTON_PRIMARY_COLOR),
+                    }
+                }
+
+                reply_text_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        wrap: Word,
+                    }
+                }
+            }
+
+            cancel_reply_button = <Button> {
+                width: 24,
+                height: 24,
+                text: "×",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (SOCIAL_TEXT_SECONDARY),
+                    text_style: { font_size: 16.0 }
+                }
             }
         }
 
@@ -90,7 +268,7 @@ live_design! {
                 }
                 draw_text: {
                     text_style: { font_size: 14.0 },
-                    color: #333,
+                    color: (SOCIAL_TEXT),
                     fn get_color(self) -> vec4 {
                         return self.color;
                     }
@@ -98,6 +276,75 @@ live_design! {
             }
         }
 
+        // Markdown formatting of the text
+        format_bar = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 4,
+            align: { y: 0.5 },
+
+            bold_button = <FormatButton> {
+                text: "B",
+                draw_text: {
+                    text_style: <THEME_FONT_BOLD> { font_size: 13.0 }
+                }
+            }
+            italic_button = <FormatButton> {
+                text: "I",
+                draw_text: {
+                    text_style: <THEME_FONT_ITALIC> { font_size: 13.0 }
+                }
+            }
+            strikethrough_button = <FormatButton> { text: "S̶" }
+            code_button = <FormatButton> { text: "</>" }
+            spoiler_button = <FormatButton> { text: "||" }
+            bullet_list_button = <FormatButton> { text: "•" }
+        }
+
+        // Co-author of a collaborative post (shown when adding one)
+        co_author_row = <View> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            flow: Down,
+            spacing: 4,
+
+            co_author_input = <SimpleTextInput> {
+                empty_message: "Co-author's user ID, e.g., @bob:example.org"
+            }
+
+            co_author_error = <Label> {
+                width: Fill,
+                height: Fit,
+                visible: false,
+                text: "Not a valid user ID",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_DANGER),
+                }
+            }
+        }
+
+        // Answers of a poll (shown when making a poll)
+        poll_editor = <View> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            flow: Down,
+            spacing: 4,
+
+            poll_answer_1 = <SimpleTextInput> { empty_message: "Answer 1" }
+            poll_answer_2 = <SimpleTextInput> { empty_message: "Answer 2" }
+            poll_answer_3 = <SimpleTextInput> { empty_message: "Answer 3 (optional)" }
+            poll_answer_4 = <SimpleTextInput> { empty_message: "Answer 4 (optional)" }
+
+            poll_multiple_choice = <CheckBoxFlat> {
+                text: "Voters can pick several answers",
+                active: false,
+            }
+        }
+
         // Media preview area (shown when media attached)
         media_preview = <View> {
             width: Fill,
@@ -106,7 +353,7 @@ live_design! {
             padding: 8,
             show_bg: true,
             draw_bg: {
-                color: #f0f0f0,
+                color: (SOCIAL_SURFACE),
                 fn pixel(self) -> vec4 {
                     let sdf = Sdf2d::viewport(self.pos * self.rect_size);
                     sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 8.);
@@ -121,6 +368,19 @@ live_design! {
                 fit: Contain,
             }
 
+            // Shown instead of the image for audio clips
+            preview_audio_label = <Label> {
+                width: Fill,
+                height: Fill,
+                visible: false,
+                align: { x: 0.5, y: 0.5 },
+                text: "",
+                draw_text: {
+                    color: (SOCIAL_TEXT_SECONDARY),
+                    text_style: { font_size: 13.0 }
+                }
+            }
+
             remove_media_button = <Button> {
                 width: 24,
                 height: 24,
@@ -136,12 +396,208 @@ live_design! {
                     }
                 }
                 draw_text: {
-                    color: #fff,
+                    color: (SOCIAL_ON_ACCENT),
                     text_style: { font_size: 16.0 }
                 }
             }
         }
 
+        // Description of the attached photo for people who can't see it
+        alt_text_input = <SimpleTextInput> {
+            visible: false,
+            empty_message: "Describe this photo for people who can't see it (alt text)"
+        }
+
+        // Shown when the selected audiences would leak a more private post
+        audience_warning = <View> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            flow: Down,
+            spacing: 4,
+            padding: 12,
+            show_bg: true,
+            draw_bg: {
+                color: (SOCIAL_WARNING_BG),
+                fn pixel(self) -> vec4 {
+                    let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                    sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 8.);
+                    sdf.fill(self.color);
+                    return sdf.result;
+                }
+            }
+
+            audience_warning_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_WARNING_TEXT),
+                    wrap: Word,
+                }
+            }
+
+            confirm_audiences_button = <Button> {
+                width: Fit,
+                height: Fit,
+                padding: { top: 4, bottom: 4, left: 8, right: 8 },
+                text: "Post to all of them anyway",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (BUTTON_PRIMARY_COLOR),
+                    text_style: { font_size: 12.0 }
+                }
+            }
+        }
+
+        // Shown when the text was already posted recently, before posting it again
+        duplicate_post_warning = <View> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            flow: Down,
+            spacing: 4,
+            padding: 12,
+            show_bg: true,
+            draw_bg: {
+                color: (SOCIAL_WARNING_BG),
+                fn pixel(self) -> vec4 {
+                    let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                    sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 8.);
+                    sdf.fill(self.color);
+                    return sdf.result;
+                }
+            }
+
+            duplicate_post_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_WARNING_TEXT),
+                    wrap: Word,
+                }
+            }
+
+            duplicate_post_buttons = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+
+                post_again_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 4, bottom: 4, left: 8, right: 8 },
+                    text: "Post anyway",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (BUTTON_PRIMARY_COLOR),
+                        text_style: { font_size: 12.0 }
+                    }
+                }
+
+                keep_editing_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 4, bottom: 4, left: 8, right: 8 },
+                    text: "Keep editing",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        text_style: { font_size: 12.0 }
+                    }
+                }
+            }
+        }
+
+        // Shown when the attached photo was already posted recently
+        duplicate_warning = <View> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            flow: Down,
+            spacing: 4,
+            padding: 12,
+            show_bg: true,
+            draw_bg: {
+                color: (SOCIAL_WARNING_BG),
+                fn pixel(self) -> vec4 {
+                    let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                    sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 8.);
+                    sdf.fill(self.color);
+                    return sdf.result;
+                }
+            }
+
+            duplicate_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_WARNING_TEXT),
+                    wrap: Word,
+                }
+            }
+
+            duplicate_buttons = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+
+                link_previous_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 4, bottom: 4, left: 8, right: 8 },
+                    text: "Link previous post",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (BUTTON_PRIMARY_COLOR),
+                        text_style: { font_size: 12.0 }
+                    }
+                }
+
+                upload_anyway_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 4, bottom: 4, left: 8, right: 8 },
+                    text: "Upload anyway",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (SOCIAL_TEXT_SECONDARY),
+                        text_style: { font_size: 12.0 }
+                    }
+                }
+            }
+        }
+
+        // Shown when the attached media breaks the restrictions of a feed posted to
+        media_restriction_label = <Label> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 12.0 },
+                color: (SOCIAL_DANGER),
+                wrap: Word,
+            }
+        }
+
         // Link preview (shown when URL detected)
         link_preview_container = <View> {
             width: Fill,
@@ -151,7 +607,7 @@ live_design! {
             margin: { top: 8 },
             show_bg: true,
             draw_bg: {
-                color: #f8f8f8,
+                color: (SOCIAL_SURFACE),
                 fn pixel(self) -> vec4 {
                     let sdf = Sdf2d::viewport(self.pos * self.rect_size);
                     sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 8.);
@@ -175,7 +631,7 @@ live_design! {
                     text: "",
                     draw_text: {
                         text_style: { font_size: 14.0 },
-                        color: #333,
+                        color: (SOCIAL_TEXT),
                     }
                 }
 
@@ -185,7 +641,7 @@ live_design! {
                     text: "",
                     draw_text: {
                         text_style: { font_size: 12.0 },
-                        color: #666,
+                        color: (SOCIAL_TEXT_SECONDARY),
                         wrap: Word,
                     }
                 }
@@ -196,26 +652,50 @@ live_design! {
                     text: "",
                     draw_text: {
                         text_style: { font_size: 11.0 },
-                        color: #999,
+                        color: (SOCIAL_TEXT_MUTED),
                     }
                 }
             }
         }
 
-        // Character count
+        // Best time to post hint and character count
         char_count_row = <View> {
             width: Fill,
             height: Fit,
             flow: Right,
-            align: { x: 1.0 },
+            spacing: 8,
+            align: { x: 1.0, y: 0.5 },
+
+            best_time_hint = <Label> {
+                width: Fill,
+                height: Fit,
+                visible: false,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: (SOCIAL_TEXT_SECONDARY),
+                    wrap: Word,
+                }
+            }
 
             char_count_label = <Label> {
                 width: Fit,
                 height: Fit,
                 text: "0/500",
                 draw_text: {
+                    // 1.0 once few characters are left
+                    instance near_limit: 0.0
+                    // 1.0 once the post is too long
+                    instance over_limit: 0.0
+
                     text_style: { font_size: 12.0 },
-                    color: #999,
+                    fn get_color(self) -> vec4 {
+                        return mix(
+                            mix((SOCIAL_TEXT_MUTED), (SOCIAL_WARNING), self.near_limit),
+                            (SOCIAL_DANGER),
+                            self.over_limit
+                        );
+                    }
                 }
             }
         }
@@ -235,7 +715,7 @@ live_design! {
                 draw_bg: {
                     color: #0000,
                     border_size: 1.0,
-                    border_color: #ddd,
+                    border_color: (SOCIAL_BORDER),
                 }
             }
 
@@ -246,7 +726,18 @@ live_design! {
                 draw_bg: {
                     color: #0000,
                     border_size: 1.0,
-                    border_color: #ddd,
+                    border_color: (SOCIAL_BORDER),
+                }
+            }
+
+            attach_audio_button = <RobrixIconButton> {
+                width: 36,
+                height: 36,
+                text: "🎙",
+                draw_bg: {
+                    color: #0000,
+                    border_size: 1.0,
+                    border_color: (SOCIAL_BORDER),
                 }
             }
 
@@ -257,7 +748,29 @@ live_design! {
                 draw_bg: {
                     color: #0000,
                     border_size: 1.0,
-                    border_color: #ddd,
+                    border_color: (SOCIAL_BORDER),
+                }
+            }
+
+            co_author_button = <RobrixIconButton> {
+                width: 36,
+                height: 36,
+                text: "👥",
+                draw_bg: {
+                    color: #0000,
+                    border_size: 1.0,
+                    border_color: (SOCIAL_BORDER),
+                }
+            }
+
+            poll_button = <RobrixIconButton> {
+                width: 36,
+                height: 36,
+                text: "📊",
+                draw_bg: {
+                    color: #0000,
+                    border_size: 1.0,
+                    border_color: (SOCIAL_BORDER),
                 }
             }
 
@@ -268,16 +781,22 @@ live_design! {
                 height: 36,
                 text: "Post",
                 draw_bg: {
-                    color: (BUTTON_DISABLED_COLOR),
+                    // 1.0 while the post can be sent
+                    instance can_post: 0.0
+
                     fn pixel(self) -> vec4 {
                         let sdf = Sdf2d::viewport(self.pos * self.rect_size);
                         sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 18.);
-                        sdf.fill(self.color);
+                        sdf.fill(mix(
+                            (BUTTON_DISABLED_COLOR),
+                            (BUTTON_PRIMARY_COLOR),
+                            self.can_post
+                        ));
                         return sdf.result;
                     }
                 }
                 draw_text: {
-                    color: #fff,
+                    color: (SOCIAL_ON_ACCENT),
                     text_style: { font_size: 14.0 }
                 }
             }
@@ -294,6 +813,8 @@ pub enum AttachedMedia {
         path: PathBuf,
         /// MXC URI after upload (if uploaded).
         mxc_uri: Option<matrix_sdk::ruma::OwnedMxcUri>,
+        /// Description of the photo for people who can't see it, as entered in the composer.
+        alt_text: Option<String>,
     },
     /// A video file to be uploaded.
     Video {
@@ -302,30 +823,133 @@ pub enum AttachedMedia {
         /// MXC URI after upload (if uploaded).
         mxc_uri: Option<matrix_sdk::ruma::OwnedMxcUri>,
     },
+    /// An audio clip to be uploaded, e.g., a recorded voice note.
+    Audio {
+        /// Local file path.
+        path: PathBuf,
+        /// MXC URI after upload (if uploaded).
+        mxc_uri: Option<matrix_sdk::ruma::OwnedMxcUri>,
+    },
+}
+
+impl AttachedMedia {
+    /// Get the local path of the attached file.
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Photo { path, .. } | Self::Video { path, .. } | Self::Audio { path, .. } => path,
+        }
+    }
+
+    /// Get the kind of the attached media.
+    pub fn kind(&self) -> MediaKind {
+        match self {
+            Self::Photo { .. } => MediaKind::Image,
+            Self::Video { .. } => MediaKind::Video,
+            Self::Audio { .. } => MediaKind::Audio,
+        }
+    }
+}
+
+impl From<&AttachedMedia> for OutboxMedia {
+    fn from(media: &AttachedMedia) -> Self {
+        let alt_text = match media {
+            AttachedMedia::Photo { alt_text, .. } => alt_text.clone(),
+            AttachedMedia::Video { .. } | AttachedMedia::Audio { .. } => None,
+        };
+        OutboxMedia::from_path(media.path().to_owned()).with_alt_text(alt_text)
+    }
 }
 
 /// Actions that can be triggered from the post composer.
 #[derive(Clone, Debug, DefaultNone)]
 pub enum SocialPostComposerAction {
     /// User submitted a post.
+    ///
+    /// The post should be sent to each of the `targets`, e.g., as
+    /// [`Post::with_targets()`](crate::social::Post::with_targets).
     SubmitPost {
-        /// Text content of the post.
+        /// Text content of the post, in Markdown. Text posts should be sent with
+        /// its HTML formatting, e.g., as [`Post::markdown()`](crate::social::Post::markdown).
         text: String,
-        /// Selected privacy/audience level.
-        privacy: FeedPrivacy,
+        /// Feed rooms to post to, one for each selected audience.
+        targets: Vec<OwnedRoomId>,
+        /// Selected privacy/audience levels, in the same order as `targets`.
+        privacy_levels: Vec<FeedPrivacy>,
+        /// Attached media, if any. A photo's alt text should be sent with it, e.g., with
+        /// [`OutboxMedia::with_alt_text()`](crate::social::OutboxMedia::with_alt_text).
+        media: Option<AttachedMedia>,
+        /// License the post is shared under, if any.
+        license: Option<ContentLicense>,
+        /// User invited to co-author the post, if any. Such a post should be sent with
+        /// [`CoAuthorService::post_with_co_author()`](crate::social::CoAuthorService::post_with_co_author).
+        co_author: Option<OwnedUserId>,
+    },
+    /// User submitted a poll.
+    ///
+    /// The poll should be sent to each of the `targets`, e.g., as
+    /// [`Post::poll()`](crate::social::Post::poll).
+    SubmitPoll {
+        /// The question asked.
+        question: String,
+        /// The possible answers, in the order they're shown.
+        answers: Vec<String>,
+        /// Whether voters can pick several answers.
+        allow_multiple: bool,
+        /// Feed rooms to post to, one for each selected audience.
+        targets: Vec<OwnedRoomId>,
+        /// Selected privacy/audience levels, in the same order as `targets`.
+        privacy_levels: Vec<FeedPrivacy>,
+        /// License the poll is shared under, if any.
+        license: Option<ContentLicense>,
+    },
+    /// User submitted a reply to a post.
+    ///
+    /// The reply should be sent to the post's room as a reply in the post's thread,
+    /// e.g., as [`PostContent::into_thread_reply()`](crate::social::PostContent::into_thread_reply).
+    SubmitReply {
+        /// Text content of the reply, in Markdown (see [`SubmitPost::text`](Self::SubmitPost)).
+        text: String,
+        /// Room the post is in.
+        room_id: OwnedRoomId,
+        /// Event ID of the post being replied to.
+        post_id: OwnedEventId,
         /// Attached media, if any.
         media: Option<AttachedMedia>,
     },
+    /// User cancelled replying to a post; the composer makes new posts again.
+    ReplyCancelled,
     /// User wants to attach a photo.
     AttachPhoto,
     /// User wants to attach a video.
     AttachVideo,
+    /// User wants to record a voice note or attach an audio file,
+    /// which should then be passed to [`SocialPostComposer::attach_media()`].
+    AttachAudio,
     /// User wants to attach a link.
     AttachLink,
     /// User changed the audience selection.
-    AudienceChanged(FeedPrivacy),
+    AudienceChanged(Vec<FeedPrivacy>),
     /// User removed attached media.
     RemoveMedia,
+    /// The attached photo was already posted recently.
+    /// Sent from the background task that checked it.
+    DuplicateImage {
+        /// The local path of the attached photo.
+        path: PathBuf,
+        /// The earlier post of the same photo.
+        previous: UploadedImage,
+    },
+    /// The configuration of a feed posted to was fetched.
+    /// Sent from the background task that fetched it.
+    FeedConfigLoaded {
+        /// The feed room.
+        room_id: OwnedRoomId,
+        /// The feed's configuration.
+        config: SocialFeedConfigEventContent,
+    },
+    /// The user's feed rooms were found, see [`SocialPostComposer::set_user_feeds()`].
+    /// Sent from the background task that found them.
+    FeedsLoaded(UserFeeds),
     /// No action.
     None,
 }
@@ -333,19 +957,82 @@ pub enum SocialPostComposerAction {
 /// Maximum character count for posts.
 const MAX_POST_LENGTH: usize = 500;
 
+/// The character count turns to a warning once this few characters are left.
+const CHAR_COUNT_WARNING_REMAINING: usize = 50;
+
+/// Get the length of a post's text as counted against [`MAX_POST_LENGTH`].
+///
+/// Characters are counted as the user perceives them (grapheme clusters),
+/// so an emoji or a CJK character counts once, however many bytes it takes.
+pub fn post_length(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// A post being replied to from the composer.
+#[derive(Clone, Debug)]
+pub struct ReplyTarget {
+    /// Room the post is in, to which the reply is sent.
+    pub room_id: OwnedRoomId,
+    /// Event ID of the post.
+    pub event_id: OwnedEventId,
+    /// Name of the post's author.
+    pub author_name: String,
+    /// Text of the post, quoted above the reply.
+    pub text: String,
+}
+
+impl From<&PostCardData> for ReplyTarget {
+    fn from(post: &PostCardData) -> Self {
+        Self {
+            room_id: post.room_id.clone(),
+            event_id: post.event_id.clone(),
+            author_name: post
+                .author_name
+                .clone()
+                .unwrap_or_else(|| post.author_id.to_string()),
+            text: post.text.clone(),
+        }
+    }
+}
+
 #[derive(Live, LiveHook, Widget)]
 pub struct SocialPostComposer {
     #[deref]
     view: View,
 
-    /// Currently selected audience/privacy level.
+    /// Currently selected audiences, from least to most private.
+    #[rust(vec![FeedPrivacy::Public])]
+    selected_audiences: Vec<FeedPrivacy>,
+
+    /// The user's feed rooms, which the post can be sent to.
     #[rust]
-    selected_audience: FeedPrivacy,
+    feeds: UserFeeds,
+
+    /// Whether the user confirmed posting to the selected audiences
+    /// despite the warning from [`SharingGuard::validate_cross_post()`].
+    #[rust]
+    audiences_confirmed: bool,
 
     /// Attached media, if any.
     #[rust]
     attached_media: Option<AttachedMedia>,
 
+    /// License the post will be shared under.
+    #[rust]
+    selected_license: Option<ContentLicense>,
+
+    /// The user's default license, which is selected again after posting.
+    #[rust]
+    default_license: Option<ContentLicense>,
+
+    /// User to invite to co-author the post, if a valid user ID was entered.
+    #[rust]
+    co_author: Option<OwnedUserId>,
+
+    /// Whether the entered co-author isn't a valid user ID.
+    #[rust]
+    invalid_co_author: bool,
+
     /// Detected link URL in the text.
     #[rust]
     detected_link: Option<url::Url>,
@@ -357,6 +1044,35 @@ pub struct SocialPostComposer {
     /// Whether the post button should be enabled.
     #[rust]
     can_post: bool,
+
+    /// The earlier post of the attached photo, if it was already posted recently.
+    #[rust]
+    duplicate_of: Option<UploadedImage>,
+
+    /// The post being replied to, if the composer is in reply mode.
+    #[rust]
+    reply_to: Option<ReplyTarget>,
+
+    /// Texts of the posts the user recently sent, to catch posting the same text twice.
+    #[rust]
+    recent_posts: RecentPostIndex,
+
+    /// Whether the user confirmed posting a text they already posted recently.
+    #[rust]
+    duplicate_post_confirmed: bool,
+
+    /// The configurations of the feeds posted to, as last fetched.
+    #[rust]
+    feed_configs: BTreeMap<OwnedRoomId, SocialFeedConfigEventContent>,
+
+    /// How the attached media breaks the restrictions of a feed posted to, if it does.
+    #[rust]
+    media_violation: Option<MediaViolation>,
+
+    /// The answers entered for the poll, one for each answer input,
+    /// if the post is a poll.
+    #[rust]
+    poll_answers: Option<Vec<String>>,
 }
 
 impl Widget for SocialPostComposer {
@@ -375,23 +1091,49 @@ impl WidgetMatchEvent for SocialPostComposer {
         // Handle text input changes
         if let Some(text) = self.text_input(ids!(text_input)).changed(actions) {
             self.current_text = text;
-            self.update_can_post();
+            self.set_duplicate_post_warning(cx, None);
+            self.update_can_post(cx);
             self.update_char_count(cx);
             self.detect_links();
         }
 
-        // Handle audience dropdown
-        if let Some(selected) = self.drop_down(ids!(audience_dropdown)).selected(actions) {
-            self.selected_audience = match selected {
-                0 => FeedPrivacy::Public,
-                1 => FeedPrivacy::Friends,
-                2 => FeedPrivacy::CloseFriends,
-                _ => FeedPrivacy::Public,
-            };
+        for (button_ids, before, after) in FORMAT_BUTTONS {
+            if self.button(button_ids).clicked(actions) {
+                self.insert_formatting(cx, before, after);
+            }
+        }
+
+        // Handle audience checkboxes
+        let audiences_changed = AUDIENCE_TARGETS
+            .iter()
+            .any(|(target_ids, _)| self.check_box(target_ids).changed(actions).is_some());
+        if audiences_changed {
+            self.selected_audiences = AUDIENCE_TARGETS
+                .iter()
+                .filter(|(target_ids, _)| self.check_box(target_ids).active(cx))
+                .map(|(_, privacy)| *privacy)
+                .collect();
+            self.audiences_confirmed = false;
+            self.update_audience_warning(cx);
+            self.fetch_feed_configs();
+            self.check_attached_media(cx);
+            self.update_can_post(cx);
             cx.action(SocialPostComposerAction::AudienceChanged(
-                self.selected_audience,
+                self.selected_audiences.clone(),
             ));
         }
+        if self.button(ids!(confirm_audiences_button)).clicked(actions) {
+            self.audiences_confirmed = true;
+            self.update_audience_warning(cx);
+            self.update_can_post(cx);
+        }
+
+        // Handle license dropdown, whose first entry is "No license"
+        if let Some(selected) = self.drop_down(ids!(license_dropdown)).selected(actions) {
+            self.selected_license = selected
+                .checked_sub(1)
+                .and_then(|index| ContentLicense::ALL.get(index).copied());
+        }
 
         // Handle button clicks
         if self.button(ids!(attach_photo_button)).clicked(actions) {
@@ -402,40 +1144,317 @@ impl WidgetMatchEvent for SocialPostComposer {
             cx.action(SocialPostComposerAction::AttachVideo);
         }
 
+        if self.button(ids!(attach_audio_button)).clicked(actions) {
+            cx.action(SocialPostComposerAction::AttachAudio);
+        }
+
         if self.button(ids!(attach_link_button)).clicked(actions) {
             cx.action(SocialPostComposerAction::AttachLink);
         }
 
+        // Show or hide the co-author input; hiding it removes the co-author
+        if self.button(ids!(co_author_button)).clicked(actions) {
+            let co_author_row = self.view(ids!(co_author_row));
+            let show = !co_author_row.visible();
+            co_author_row.set_visible(cx, show);
+            if !show {
+                self.set_co_author_text(cx, "");
+                self.text_input(ids!(co_author_input)).set_text(cx, "");
+            }
+            self.redraw(cx);
+        }
+        if let Some(text) = self.text_input(ids!(co_author_input)).changed(actions) {
+            self.set_co_author_text(cx, &text);
+        }
+
+        // Turn the post into a poll or back; a poll can't have media
+        if self.button(ids!(poll_button)).clicked(actions) {
+            let is_poll = self.poll_answers.is_none();
+            if is_poll && self.attached_media.is_some() {
+                self.remove_media(cx);
+            }
+            self.set_poll_mode(cx, is_poll);
+        }
+        for (index, answer_ids) in POLL_ANSWER_INPUTS.iter().enumerate() {
+            if let Some(text) = self.text_input(answer_ids).changed(actions) {
+                if let Some(answer) = self
+                    .poll_answers
+                    .as_mut()
+                    .and_then(|answers| answers.get_mut(index))
+                {
+                    *answer = text;
+                }
+                self.update_can_post(cx);
+            }
+        }
+
         if self.button(ids!(remove_media_button)).clicked(actions) {
-            self.attached_media = None;
-            self.view(ids!(media_preview)).set_visible(cx, false);
-            self.update_can_post();
-            cx.action(SocialPostComposerAction::RemoveMedia);
+            self.remove_media(cx);
+        }
+        if let Some(text) = self.text_input(ids!(alt_text_input)).changed(actions) {
+            if let Some(AttachedMedia::Photo { alt_text, .. }) = &mut self.attached_media {
+                let text = text.trim();
+                *alt_text = (!text.is_empty()).then(|| text.to_string());
+            }
+        }
+
+        // Offer to link the earlier post instead of uploading the same photo again
+        if self.button(ids!(link_previous_button)).clicked(actions) {
+            if let Some(previous) = self.duplicate_of.take() {
+                let permalink = previous.room_id.matrix_to_event_uri(previous.event_id);
+                let text = if self.current_text.trim().is_empty() {
+                    permalink.to_string()
+                } else {
+                    format!("{} {}", self.current_text.trim_end(), permalink)
+                };
+                self.text_input(ids!(text_input)).set_text(cx, &text);
+                self.current_text = text;
+                self.update_char_count(cx);
+                self.detect_links();
+                self.remove_media(cx);
+            }
+        }
+        if self.button(ids!(upload_anyway_button)).clicked(actions) {
+            self.set_duplicate_of(cx, None);
+        }
+
+        for action in actions {
+            if let Some(SocialPostComposerAction::DuplicateImage { path, previous }) =
+                action.downcast_ref()
+            {
+                let is_attached = matches!(
+                    &self.attached_media,
+                    Some(AttachedMedia::Photo { path: attached, .. }) if attached == path
+                );
+                if is_attached {
+                    self.set_duplicate_of(cx, Some(previous.clone()));
+                }
+            }
+            if let Some(SocialPostComposerAction::FeedConfigLoaded { room_id, config }) =
+                action.downcast_ref()
+            {
+                self.feed_configs.insert(room_id.clone(), config.clone());
+                self.check_attached_media(cx);
+                self.update_can_post(cx);
+            }
+            if let Some(SocialPostComposerAction::FeedsLoaded(feeds)) = action.downcast_ref() {
+                self.set_user_feeds(cx, feeds.clone());
+            }
+        }
+
+        if self.button(ids!(cancel_reply_button)).clicked(actions) {
+            self.cancel_reply(cx);
+            cx.action(SocialPostComposerAction::ReplyCancelled);
+        }
+
+        if self.button(ids!(keep_editing_button)).clicked(actions) {
+            self.set_duplicate_post_warning(cx, None);
+        }
+        if self.button(ids!(post_again_button)).clicked(actions) && self.can_post {
+            self.duplicate_post_confirmed = true;
+            self.submit(cx);
         }
 
         if self.button(ids!(post_button)).clicked(actions) && self.can_post {
-            cx.action(SocialPostComposerAction::SubmitPost {
-                text: self.current_text.clone(),
-                privacy: self.selected_audience,
-                media: self.attached_media.clone(),
-            });
-            // Clear after posting
-            self.clear(cx);
+            self.submit(cx);
         }
     }
 }
 
 impl SocialPostComposer {
+    /// Send the post or reply, unless its text was already posted recently
+    /// and the user hasn't confirmed posting it again.
+    fn submit(&mut self, cx: &mut Cx) {
+        if self.reply_to.is_none() && !self.duplicate_post_confirmed {
+            let now = MilliSecondsSinceUnixEpoch::now();
+            if let Some(previous) = self.recent_posts.find_duplicate(&self.current_text, now) {
+                let posted_at = previous.posted_at;
+                self.set_duplicate_post_warning(cx, Some(posted_at));
+                return;
+            }
+        }
+        match (&self.reply_to, &self.poll_answers) {
+            (Some(target), _) => cx.action(SocialPostComposerAction::SubmitReply {
+                text: self.current_text.clone(),
+                room_id: target.room_id.clone(),
+                post_id: target.event_id.clone(),
+                media: self.attached_media.clone(),
+            }),
+            (None, Some(_)) => cx.action(SocialPostComposerAction::SubmitPoll {
+                question: self.current_text.trim().to_string(),
+                answers: self.filled_poll_answers(),
+                allow_multiple: self.check_box(ids!(poll_multiple_choice)).active(cx),
+                targets: self.targets(),
+                privacy_levels: self.selected_audiences.clone(),
+                license: self.selected_license,
+            }),
+            (None, None) => cx.action(SocialPostComposerAction::SubmitPost {
+                text: self.current_text.clone(),
+                targets: self.targets(),
+                privacy_levels: self.selected_audiences.clone(),
+                media: self.attached_media.clone(),
+                license: self.selected_license,
+                co_author: self.co_author.clone(),
+            }),
+        }
+        if self.reply_to.is_none() {
+            self.recent_posts
+                .record(&self.current_text, MilliSecondsSinceUnixEpoch::now());
+        }
+        // Clear after posting, which also leaves reply mode
+        self.clear(cx);
+    }
+
     /// Set the user's avatar for display.
     pub fn set_user_avatar(&mut self, cx: &mut Cx, display_name: &str) {
         self.avatar(ids!(user_avatar)).set_text(cx, display_name);
     }
 
     /// Attach media to the post.
+    ///
+    /// Attached photos are checked in the background against the user's
+    /// recent uploads, and a warning is shown if one was already posted.
+    /// Posting is blocked while the media breaks the restrictions of a feed posted to.
+    /// The user can describe attached photos for people who can't see them.
+    /// Audio clips are previewed by their file name.
+    /// A poll can't have media, so attaching media turns a poll back into a post.
     pub fn attach_media(&mut self, cx: &mut Cx, media: AttachedMedia) {
+        self.set_poll_mode(cx, false);
+        self.set_duplicate_of(cx, None);
+        let alt_text_input = self.text_input(ids!(alt_text_input));
+        match &media {
+            AttachedMedia::Photo { path, alt_text, .. } => {
+                submit_async_request(MatrixRequest::FindDuplicateImage { path: path.clone() });
+                alt_text_input.set_text(cx, alt_text.as_deref().unwrap_or_default());
+                alt_text_input.set_visible(cx, true);
+            }
+            AttachedMedia::Video { .. } | AttachedMedia::Audio { .. } => {
+                alt_text_input.set_visible(cx, false);
+            }
+        }
+        let is_audio = matches!(media, AttachedMedia::Audio { .. });
+        let preview_audio_label = self.label(ids!(preview_audio_label));
+        if is_audio {
+            let file_name = media.path().file_name().unwrap_or_default();
+            preview_audio_label.set_text(cx, &format!("🎙 {}", file_name.to_string_lossy()));
+        }
+        preview_audio_label.set_visible(cx, is_audio);
+        self.image(ids!(preview_image)).set_visible(cx, !is_audio);
         self.attached_media = Some(media);
         self.view(ids!(media_preview)).set_visible(cx, true);
-        self.update_can_post();
+        self.check_attached_media(cx);
+        self.update_can_post(cx);
+    }
+
+    /// Set the co-author from the text entered for them, which may be empty.
+    fn set_co_author_text(&mut self, cx: &mut Cx, text: &str) {
+        let text = text.trim();
+        self.co_author = OwnedUserId::try_from(text).ok();
+        self.invalid_co_author = !text.is_empty() && self.co_author.is_none();
+        self.label(ids!(co_author_error))
+            .set_visible(cx, self.invalid_co_author);
+        self.update_can_post(cx);
+    }
+
+    /// Show or hide the inputs for the answers of a poll, making the post a poll or not.
+    ///
+    /// Leaving poll mode discards the entered answers.
+    fn set_poll_mode(&mut self, cx: &mut Cx, is_poll: bool) {
+        if is_poll == self.poll_answers.is_some() {
+            return;
+        }
+        if is_poll {
+            self.poll_answers = Some(vec![String::new(); POLL_ANSWER_INPUTS.len()]);
+        } else {
+            self.poll_answers = None;
+            for answer_ids in POLL_ANSWER_INPUTS {
+                self.text_input(answer_ids).set_text(cx, "");
+            }
+            self.check_box(ids!(poll_multiple_choice))
+                .set_active(cx, false);
+        }
+        self.view(ids!(poll_editor)).set_visible(cx, is_poll);
+        self.update_can_post(cx);
+        self.redraw(cx);
+    }
+
+    /// Get the answers entered for the poll, leaving out empty inputs.
+    fn filled_poll_answers(&self) -> Vec<String> {
+        self.poll_answers
+            .iter()
+            .flatten()
+            .map(|answer| answer.trim())
+            .filter(|answer| !answer.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Remove the attached media.
+    fn remove_media(&mut self, cx: &mut Cx) {
+        self.attached_media = None;
+        self.view(ids!(media_preview)).set_visible(cx, false);
+        self.text_input(ids!(alt_text_input)).set_visible(cx, false);
+        self.set_duplicate_of(cx, None);
+        self.check_attached_media(cx);
+        self.update_can_post(cx);
+        cx.action(SocialPostComposerAction::RemoveMedia);
+    }
+
+    /// Set the texts of the posts the user recently sent, e.g., as loaded with
+    /// [`RecentPostIndex::load()`], to ask for confirmation before posting one of them again.
+    ///
+    /// Posts sent from the composer are added to the index, but the index should also
+    /// be saved with [`record_post()`](crate::social::duplicate_posts::record_post)
+    /// once a post has been sent.
+    pub fn set_recent_posts(&mut self, recent_posts: RecentPostIndex) {
+        self.recent_posts = recent_posts;
+    }
+
+    /// Ask the user to confirm posting a text they already posted at the given time,
+    /// or hide the question if `None`.
+    fn set_duplicate_post_warning(
+        &mut self,
+        cx: &mut Cx,
+        posted_at: Option<MilliSecondsSinceUnixEpoch>,
+    ) {
+        if let Some(posted_at) = posted_at {
+            let posted_at: u64 = posted_at.get().into();
+            let when = chrono::DateTime::from_timestamp_millis(posted_at as i64)
+                .map(|dt| {
+                    dt.with_timezone(&chrono::Local)
+                        .format(" at %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            self.label(ids!(duplicate_post_label)).set_text(
+                cx,
+                &format!("You already posted this{when}. Post it again?"),
+            );
+        }
+        self.view(ids!(duplicate_post_warning))
+            .set_visible(cx, posted_at.is_some());
+        self.duplicate_post_confirmed = false;
+        self.redraw(cx);
+    }
+
+    /// Show a warning that the attached photo was already posted, or hide it if `None`.
+    fn set_duplicate_of(&mut self, cx: &mut Cx, previous: Option<UploadedImage>) {
+        if let Some(previous) = &previous {
+            let uploaded_at: u64 = previous.uploaded_at.get().into();
+            let posted_at = chrono::DateTime::from_timestamp_millis(uploaded_at as i64)
+                .map(|dt| dt.with_timezone(&chrono::Local).format(" on %b %d").to_string())
+                .unwrap_or_default();
+            self.label(ids!(duplicate_label)).set_text(
+                cx,
+                &format!(
+                    "You already posted this photo{posted_at}. \
+                     Link to that post instead of uploading it again?"
+                ),
+            );
+        }
+        self.view(ids!(duplicate_warning))
+            .set_visible(cx, previous.is_some());
+        self.duplicate_of = previous;
     }
 
     /// Set the link preview data.
@@ -457,32 +1476,318 @@ impl SocialPostComposer {
             .set_visible(cx, true);
     }
 
-    /// Clear the composer state.
+    /// Show a hint about when the user's friends are usually active,
+    /// or hide it if `hint` is `None`.
+    ///
+    /// The hint is kept when the composer is cleared. It should come from
+    /// [`ActiveWindow::hint_text()`](crate::social::ActiveWindow::hint_text).
+    pub fn set_best_time_hint(&mut self, cx: &mut Cx, hint: Option<&str>) {
+        let label = self.label(ids!(best_time_hint));
+        label.set_text(cx, hint.unwrap_or_default());
+        label.set_visible(cx, hint.is_some());
+    }
+
+    /// Set the user's feed rooms, which the post can be sent to.
+    ///
+    /// Audiences without a feed are hidden. This must be called before posting.
+    pub fn set_user_feeds(&mut self, cx: &mut Cx, feeds: UserFeeds) {
+        for (target_ids, privacy) in AUDIENCE_TARGETS {
+            let has_feed = feeds.get(privacy).is_some();
+            let check_box = self.check_box(target_ids);
+            check_box.set_visible(cx, has_feed);
+            if !has_feed {
+                check_box.set_active(cx, false);
+            }
+        }
+        self.selected_audiences
+            .retain(|privacy| feeds.get(*privacy).is_some());
+        self.feeds = feeds;
+        self.update_audience_warning(cx);
+        self.fetch_feed_configs();
+        self.check_attached_media(cx);
+        self.update_can_post(cx);
+    }
+
+    /// Get the feed rooms the post or reply is sent to, with how to refer to each feed.
+    fn destination_feeds(&self) -> Vec<(OwnedRoomId, String)> {
+        match &self.reply_to {
+            Some(target) => vec![(target.room_id.clone(), "this feed".to_string())],
+            None => self
+                .selected_audiences
+                .iter()
+                .filter_map(|privacy| {
+                    let room_id = self.feeds.get(*privacy)?.clone();
+                    Some((room_id, format!("the {}", privacy.feed_name())))
+                })
+                .collect(),
+        }
+    }
+
+    /// Fetch the configurations of the feeds posted to in the background,
+    /// to check the attached media against their media restrictions.
+    fn fetch_feed_configs(&self) {
+        for (room_id, _) in self.destination_feeds() {
+            submit_async_request(MatrixRequest::FetchFeedConfig { room_id });
+        }
+    }
+
+    /// Check the attached media against the restrictions of the feeds posted to,
+    /// showing why it can't be posted if it breaks them.
+    ///
+    /// Feeds whose configuration hasn't been fetched yet aren't checked.
+    fn check_attached_media(&mut self, cx: &mut Cx) {
+        let violation = self.attached_media.as_ref().and_then(|media| {
+            let size = std::fs::metadata(media.path()).ok().map(|m| m.len());
+            self.destination_feeds()
+                .into_iter()
+                .find_map(|(room_id, feed_name)| {
+                    let config = self.feed_configs.get(&room_id)?;
+                    Some((check_media(config, media.kind(), size)?, feed_name))
+                })
+        });
+        let label = self.label(ids!(media_restriction_label));
+        if let Some((violation, feed_name)) = &violation {
+            label.set_text(
+                cx,
+                &format!("{violation} in {feed_name}. Remove the attachment to post."),
+            );
+        }
+        label.set_visible(cx, violation.is_some());
+        self.media_violation = violation.map(|(violation, _)| violation);
+        self.redraw(cx);
+    }
+
+    /// Get the feed rooms of the selected audiences.
+    fn targets(&self) -> Vec<OwnedRoomId> {
+        self.feeds
+            .targets(&self.selected_audiences)
+            .unwrap_or_default()
+    }
+
+    /// Validate posting to all of the selected audiences at once.
+    fn audience_validation(&self) -> ShareValidation {
+        let targets: Vec<_> = self
+            .selected_audiences
+            .iter()
+            .filter_map(|privacy| {
+                let room_id: &RoomId = self.feeds.get(*privacy)?;
+                Some((room_id, PrivacyLevel::from(*privacy)))
+            })
+            .collect();
+        SharingGuard::validate_cross_post(&targets)
+    }
+
+    /// Show why the selected audiences can't be posted to together, if they can't.
+    fn update_audience_warning(&mut self, cx: &mut Cx) {
+        let (warning, can_confirm) = match self.audience_validation() {
+            ShareValidation::RequiresConfirmation { warning } if !self.audiences_confirmed => {
+                (Some(warning), true)
+            }
+            ShareValidation::BlockedPrivacyLeak { message, .. } => (
+                Some(format!("{message}. Choose fewer audiences to post to.")),
+                false,
+            ),
+            _ => (None, false),
+        };
+        self.label(ids!(audience_warning_label))
+            .set_text(cx, warning.as_deref().unwrap_or_default());
+        self.button(ids!(confirm_audiences_button))
+            .set_visible(cx, can_confirm);
+        self.view(ids!(audience_warning))
+            .set_visible(cx, warning.is_some());
+    }
+
+    /// Set the user's default license, e.g., from
+    /// [`load_default_license()`](crate::social::load_default_license),
+    /// and select it for the post being composed.
+    pub fn set_default_license(&mut self, cx: &mut Cx, license: Option<ContentLicense>) {
+        self.default_license = license;
+        self.select_license(cx, license);
+    }
+
+    /// Select the license the post will be shared under.
+    fn select_license(&mut self, cx: &mut Cx, license: Option<ContentLicense>) {
+        let index = license
+            .and_then(|license| ContentLicense::ALL.iter().position(|l| *l == license))
+            .map_or(0, |index| index + 1);
+        self.drop_down(ids!(license_dropdown))
+            .set_selected_item(cx, index);
+        self.selected_license = license;
+    }
+
+    /// Switch to reply mode, quoting the given post above the text input.
+    ///
+    /// In reply mode, the audiences, license and co-author don't apply,
+    /// since the reply is sent to the post's thread rather than the user's feeds,
+    /// and replies can't be polls.
+    /// Any text entered so far is kept.
+    pub fn start_reply(&mut self, cx: &mut Cx, target: ReplyTarget) {
+        self.set_poll_mode(cx, false);
+        self.label(ids!(reply_author_label))
+            .set_text(cx, &format!("Replying to {}", target.author_name));
+        self.label(ids!(reply_text_label))
+            .set_text(cx, &reply_preview(&target.text));
+        self.text_input(ids!(co_author_input)).set_text(cx, "");
+        self.set_co_author_text(cx, "");
+        self.view(ids!(co_author_row)).set_visible(cx, false);
+        self.reply_to = Some(target);
+        self.show_reply_mode(cx);
+    }
+
+    /// Leave reply mode, going back to making new posts.
+    ///
+    /// Any text entered so far is kept.
+    pub fn cancel_reply(&mut self, cx: &mut Cx) {
+        self.reply_to = None;
+        self.show_reply_mode(cx);
+    }
+
+    /// Get the post being replied to, if the composer is in reply mode.
+    pub fn reply_to(&self) -> Option<&ReplyTarget> {
+        self.reply_to.as_ref()
+    }
+
+    /// Show or hide the parts of the composer that depend on whether it's in reply mode.
+    fn show_reply_mode(&mut self, cx: &mut Cx) {
+        let replying = self.reply_to.is_some();
+        self.view(ids!(reply_preview)).set_visible(cx, replying);
+        self.view(ids!(audience_targets)).set_visible(cx, !replying);
+        self.widget(ids!(license_dropdown))
+            .set_visible(cx, !replying);
+        self.button(ids!(co_author_button))
+            .set_visible(cx, !replying);
+        self.button(ids!(poll_button)).set_visible(cx, !replying);
+        self.button(ids!(post_button))
+            .set_text(cx, if replying { "Reply" } else { "Post" });
+        if replying {
+            self.view(ids!(audience_warning)).set_visible(cx, false);
+        } else {
+            self.update_audience_warning(cx);
+        }
+        self.fetch_feed_configs();
+        self.check_attached_media(cx);
+        self.update_can_post(cx);
+        self.redraw(cx);
+    }
+
+    /// Clear the composer state and leave reply mode.
+    ///
+    /// The selected audiences are kept, but posting to them
+    /// must be confirmed again if needed.
+    /// The user's default license is selected again.
     pub fn clear(&mut self, cx: &mut Cx) {
         self.current_text.clear();
         self.attached_media = None;
         self.detected_link = None;
         self.can_post = false;
+        self.audiences_confirmed = false;
+        self.update_audience_warning(cx);
 
         self.text_input(ids!(text_input)).set_text(cx, "");
+        self.text_input(ids!(co_author_input)).set_text(cx, "");
+        self.set_co_author_text(cx, "");
+        self.view(ids!(co_author_row)).set_visible(cx, false);
+        self.set_poll_mode(cx, false);
         self.view(ids!(media_preview)).set_visible(cx, false);
+        self.text_input(ids!(alt_text_input)).set_visible(cx, false);
+        self.set_duplicate_of(cx, None);
+        self.set_duplicate_post_warning(cx, None);
         self.view(ids!(link_preview_container))
             .set_visible(cx, false);
+        self.select_license(cx, self.default_license);
         self.update_char_count(cx);
+        self.cancel_reply(cx);
     }
 
-    /// Check if the post button should be enabled.
-    fn update_can_post(&mut self) {
-        let has_content = !self.current_text.trim().is_empty() || self.attached_media.is_some();
-        let within_limit = self.current_text.len() <= MAX_POST_LENGTH;
-        self.can_post = has_content && within_limit;
+    /// Check if the post button should be enabled, and show it accordingly.
+    fn update_can_post(&mut self, cx: &mut Cx) {
+        let has_content = if self.poll_answers.is_some() {
+            // A poll needs a question and at least two answers
+            !self.current_text.trim().is_empty() && self.filled_poll_answers().len() >= 2
+        } else {
+            !self.current_text.trim().is_empty() || self.attached_media.is_some()
+        };
+        let within_limit = post_length(&self.current_text) <= MAX_POST_LENGTH;
+        let media_allowed = self.media_violation.is_none();
+        // Replies go to the post's thread, so the audiences don't matter
+        self.can_post = if self.reply_to.is_some() {
+            has_content && within_limit && media_allowed
+        } else {
+            let audiences_allowed = match self.audience_validation() {
+                ShareValidation::Allowed => true,
+                ShareValidation::RequiresConfirmation { .. } => self.audiences_confirmed,
+                _ => false,
+            };
+            has_content
+                && within_limit
+                && media_allowed
+                && !self.invalid_co_author
+                && !self.targets().is_empty()
+                && audiences_allowed
+        };
+
+        let can_post = if self.can_post { 1.0 } else { 0.0 };
+        let post_button = self.button(ids!(post_button));
+        post_button.apply_over(cx, live! { draw_bg: { can_post: (can_post) } });
+        post_button.set_enabled(cx, self.can_post);
     }
 
-    /// Update the character count display.
+    /// Update the character count display,
+    /// turning it yellow near the limit and red over it.
     fn update_char_count(&mut self, cx: &mut Cx) {
-        let count = self.current_text.len();
+        let count = post_length(&self.current_text);
         let text = format!("{}/{}", count, MAX_POST_LENGTH);
-        self.label(ids!(char_count_label)).set_text(cx, &text);
+        let over_limit = if count > MAX_POST_LENGTH { 1.0 } else { 0.0 };
+        let near_limit = if count + CHAR_COUNT_WARNING_REMAINING >= MAX_POST_LENGTH {
+            1.0
+        } else {
+            0.0
+        };
+        let char_count_label = self.label(ids!(char_count_label));
+        char_count_label.set_text(cx, &text);
+        char_count_label.apply_over(
+            cx,
+            live! { draw_text: { near_limit: (near_limit), over_limit: (over_limit) } },
+        );
+    }
+
+    /// Insert Markdown formatting at the cursor of the text input,
+    /// leaving the cursor between `before` and `after`.
+    ///
+    /// Bullet list items are started on a new line unless the cursor is at the start of one.
+    fn insert_formatting(&mut self, cx: &mut Cx, before: &str, after: &str) {
+        let text_input = self.text_input(ids!(text_input));
+        let cursor = text_input
+            .borrow()
+            .map_or(self.current_text.len(), |input| input.cursor().index)
+            .min(self.current_text.len());
+        let cursor = (0..=cursor)
+            .rev()
+            .find(|index| self.current_text.is_char_boundary(*index))
+            .unwrap_or(0);
+        let (head, tail) = self.current_text.split_at(cursor);
+        let line_break = if before == "- " && !head.is_empty() && !head.ends_with('\n') {
+            "\n"
+        } else {
+            ""
+        };
+        let text = format!("{head}{line_break}{before}{after}{tail}");
+        let new_cursor = cursor + line_break.len() + before.len();
+
+        text_input.set_text(cx, &text);
+        text_input.set_cursor(
+            cx,
+            Cursor {
+                index: new_cursor,
+                prefer_next_row: false,
+            },
+            false,
+        );
+        text_input.set_key_focus(cx);
+        self.current_text = text;
+        self.set_duplicate_post_warning(cx, None);
+        self.update_can_post(cx);
+        self.update_char_count(cx);
     }
 
     /// Detect URLs in the current text.
@@ -504,18 +1809,35 @@ impl SocialPostComposer {
         &self.current_text
     }
 
-    /// Get the selected privacy level.
-    pub fn privacy(&self) -> FeedPrivacy {
-        self.selected_audience
+    /// Get the selected privacy levels.
+    pub fn privacy_levels(&self) -> &[FeedPrivacy] {
+        &self.selected_audiences
     }
 
     /// Get the attached media, if any.
     pub fn attached_media(&self) -> Option<&AttachedMedia> {
         self.attached_media.as_ref()
     }
+
+    /// Get the user invited to co-author the post, if any.
+    pub fn co_author(&self) -> Option<&OwnedUserId> {
+        self.co_author.as_ref()
+    }
+
+    /// Get the selected license, if any.
+    pub fn license(&self) -> Option<ContentLicense> {
+        self.selected_license
+    }
 }
 
 impl SocialPostComposerRef {
+    /// See [`SocialPostComposer::set_recent_posts()`].
+    pub fn set_recent_posts(&self, recent_posts: RecentPostIndex) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_recent_posts(recent_posts);
+        }
+    }
+
     /// See [`SocialPostComposer::set_user_avatar()`].
     pub fn set_user_avatar(&self, cx: &mut Cx, display_name: &str) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -530,6 +1852,41 @@ impl SocialPostComposerRef {
         }
     }
 
+    /// See [`SocialPostComposer::set_user_feeds()`].
+    pub fn set_user_feeds(&self, cx: &mut Cx, feeds: UserFeeds) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_user_feeds(cx, feeds);
+        }
+    }
+
+    /// See [`SocialPostComposer::set_default_license()`].
+    pub fn set_default_license(&self, cx: &mut Cx, license: Option<ContentLicense>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_default_license(cx, license);
+        }
+    }
+
+    /// See [`SocialPostComposer::set_best_time_hint()`].
+    pub fn set_best_time_hint(&self, cx: &mut Cx, hint: Option<&str>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_best_time_hint(cx, hint);
+        }
+    }
+
+    /// See [`SocialPostComposer::start_reply()`].
+    pub fn start_reply(&self, cx: &mut Cx, target: ReplyTarget) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.start_reply(cx, target);
+        }
+    }
+
+    /// See [`SocialPostComposer::cancel_reply()`].
+    pub fn cancel_reply(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.cancel_reply(cx);
+        }
+    }
+
     /// See [`SocialPostComposer::clear()`].
     pub fn clear(&self, cx: &mut Cx) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -537,3 +1894,13 @@ impl SocialPostComposerRef {
         }
     }
 }
+
+/// Shorten the text of a post for quoting it above a reply.
+fn reply_preview(text: &str) -> String {
+    if text.chars().count() <= REPLY_PREVIEW_CHARS {
+        return text.to_string();
+    }
+    let mut preview: String = text.chars().take(REPLY_PREVIEW_CHARS).collect();
+    preview.push('…');
+    preview
+}
@@ -0,0 +1,325 @@
+//! Post detail view widget.
+//!
+//! This widget renders the full view of a single post: the post card
+//! itself and a breakdown of when people reacted to it relative to when
+//! it was posted, giving authors a sense of when their audience is active.
+//!
+//! It's shown in a modal when viewing one of the posts on a profile page
+//! (see [`SocialProfilePage`]), while the post's reactions are loaded through
+//! [`MatrixRequest::LoadReactionTiming`].
+//!
+//! [`SocialProfilePage`]: crate::social::widgets::profile_page::SocialProfilePage
+//! [`MatrixRequest::LoadReactionTiming`]: crate::sliding_sync::MatrixRequest::LoadReactionTiming
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::OwnedEventId;
+
+use crate::social::reactions::ReactionTimingBucket;
+use crate::social::widgets::post_card::{PostCardData, SocialPostCardWidgetExt};
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use crate::social::widgets::post_card::SocialPostCard;
+
+    /// A single time bucket in the reaction timing chart.
+    ReactionTimingColumn = <View> {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        spacing: 4,
+        align: { x: 0.5, y: 1.0 },
+
+        count_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 10.0 },
+                color: #666,
+            }
+        }
+
+        bar = <View> {
+            width: 16,
+            height: 0,
+            show_bg: true,
+            draw_bg: {
+                color: #1d9bf0,
+                radius: 2.0,
+            }
+        }
+
+        bucket_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 10.0 },
+                color: #666,
+            }
+        }
+    }
+
+    /// Full detail view for a single post.
+    pub PostDetailView = {{PostDetailView}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: #f0f2f5
+        }
+
+        content = <ScrollYView> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+            padding: 12,
+            spacing: 12,
+
+            post_card = <SocialPostCard> {}
+
+            // Reaction timing (only shown once the post has reactions)
+            reaction_timing = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                padding: 16,
+                spacing: 8,
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    color: #fff,
+                    radius: 8.0,
+                }
+
+                reaction_timing_title = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "When people reacted",
+                    draw_text: {
+                        text_style: { font_size: 16.0 },
+                        color: #000,
+                    }
+                }
+
+                timing_chart = {{ReactionTimingChart}} {
+                    width: Fill,
+                    height: 110,
+                    flow: Right,
+                    spacing: 4,
+
+                    column_template: <ReactionTimingColumn> {}
+                }
+
+                reaction_timing_summary = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: #666,
+                        wrap: Word,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Actions emitted when the details of a post were loaded.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum PostDetailAction {
+    /// The reactions to the post with the given ID were counted by how long
+    /// after the post they arrived, see [`PostDetailView::set_reaction_timing()`].
+    ReactionTimingLoaded {
+        event_id: OwnedEventId,
+        buckets: Vec<ReactionTimingBucket>,
+    },
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct PostDetailView {
+    #[deref]
+    view: View,
+}
+
+impl Widget for PostDetailView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl PostDetailView {
+    /// Set the post to display.
+    pub fn set_post(&mut self, cx: &mut Cx, data: &PostCardData) {
+        self.social_post_card(ids!(post_card)).set_post(cx, data);
+        self.redraw(cx);
+    }
+
+    /// Show when people reacted to the post.
+    ///
+    /// This should be called with the result of
+    /// [`reaction_timing()`](crate::social::reactions::reaction_timing)
+    /// for the post's reaction timestamps, e.g., from
+    /// [`load_reaction_timestamps()`](crate::social::reactions::load_reaction_timestamps).
+    pub fn set_reaction_timing(&mut self, cx: &mut Cx, buckets: &[ReactionTimingBucket]) {
+        let total: u32 = buckets.iter().map(|b| b.count).sum();
+        self.view(ids!(reaction_timing)).set_visible(cx, total > 0);
+        if total == 0 {
+            return;
+        }
+
+        if let Some(mut chart) = self
+            .view
+            .widget(ids!(timing_chart))
+            .borrow_mut::<ReactionTimingChart>()
+        {
+            chart.set_buckets(cx, buckets);
+        }
+
+        // The first bucket wins ties, favoring the earliest reactions
+        let summary = buckets
+            .iter()
+            .rev()
+            .max_by_key(|b| b.count)
+            .map(|peak| {
+                format!(
+                    "{} of {} reactions arrived {} after posting.",
+                    peak.count,
+                    total,
+                    peak_description(peak.label)
+                )
+            })
+            .unwrap_or_default();
+        self.label(ids!(reaction_timing_summary))
+            .set_text(cx, &summary);
+        self.redraw(cx);
+    }
+
+    /// Clear the post data.
+    pub fn clear(&mut self, cx: &mut Cx) {
+        self.view(ids!(reaction_timing)).set_visible(cx, false);
+        if let Some(mut chart) = self
+            .view
+            .widget(ids!(timing_chart))
+            .borrow_mut::<ReactionTimingChart>()
+        {
+            chart.set_buckets(cx, &[]);
+        }
+        self.label(ids!(reaction_timing_summary)).set_text(cx, "");
+    }
+}
+
+/// Describe a reaction timing bucket label in a sentence, e.g., "within 1h".
+fn peak_description(label: &str) -> String {
+    if let Some(upper) = label.strip_prefix('<') {
+        format!("within {}", upper)
+    } else if let Some(lower) = label.strip_prefix('>') {
+        format!("more than {}", lower)
+    } else {
+        label.to_string()
+    }
+}
+
+impl PostDetailViewRef {
+    /// See [`PostDetailView::set_post()`].
+    pub fn set_post(&self, cx: &mut Cx, data: &PostCardData) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_post(cx, data);
+        }
+    }
+
+    /// See [`PostDetailView::set_reaction_timing()`].
+    pub fn set_reaction_timing(&self, cx: &mut Cx, buckets: &[ReactionTimingBucket]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_reaction_timing(cx, buckets);
+        }
+    }
+
+    /// See [`PostDetailView::clear()`].
+    pub fn clear(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.clear(cx);
+        }
+    }
+}
+
+/// Maximum height of a bar in the reaction timing chart.
+const TIMING_CHART_MAX_BAR_HEIGHT: f64 = 70.0;
+
+/// Widget for displaying reaction counts per time bucket as a bar chart.
+#[derive(Live, LiveHook, Widget)]
+pub struct ReactionTimingChart {
+    #[redraw]
+    #[rust]
+    area: Area,
+
+    /// Template for a chart column (count, bar and bucket label).
+    #[live]
+    column_template: Option<LivePtr>,
+
+    /// Created columns, one per bucket.
+    #[rust]
+    columns: Vec<WidgetRef>,
+
+    /// Layout for the widget.
+    #[layout]
+    layout: Layout,
+
+    /// Walk for the widget.
+    #[walk]
+    walk: Walk,
+}
+
+impl Widget for ReactionTimingChart {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        cx.begin_turtle(walk, self.layout);
+        for column in self.columns.iter_mut() {
+            let _ = column.draw(cx, scope);
+        }
+        cx.end_turtle_with_area(&mut self.area);
+        DrawStep::done()
+    }
+}
+
+impl ReactionTimingChart {
+    /// Set the buckets to display.
+    ///
+    /// Each bar's height is proportional to the bucket's share of the
+    /// busiest bucket.
+    pub fn set_buckets(&mut self, cx: &mut Cx, buckets: &[ReactionTimingBucket]) {
+        self.columns.clear();
+        let Some(template) = self.column_template else {
+            return;
+        };
+
+        let max = buckets.iter().map(|b| b.count).max().unwrap_or(0).max(1);
+        for bucket in buckets {
+            let column = WidgetRef::new_from_ptr(cx, Some(template));
+            let height = TIMING_CHART_MAX_BAR_HEIGHT * bucket.count as f64 / max as f64;
+            column
+                .widget(ids!(bar))
+                .apply_over(cx, live! { height: (height) });
+            let count_text = if bucket.count > 0 {
+                bucket.count.to_string()
+            } else {
+                String::new()
+            };
+            column.label(ids!(count_label)).set_text(cx, &count_text);
+            column.label(ids!(bucket_label)).set_text(cx, bucket.label);
+            self.columns.push(column);
+        }
+        self.area.redraw(cx);
+    }
+}
@@ -0,0 +1,219 @@
+//! Collapsed post group card widget.
+//!
+//! This widget stands in for a burst of posts from a single author
+//! ("Alice posted 5 times"), showing a preview of the latest post
+//! and letting the user expand the group.
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::{OwnedEventId, OwnedMxcUri, OwnedUserId};
+
+use crate::avatar_cache;
+use crate::shared::avatar::AvatarWidgetExt;
+use crate::social::widgets::post_card::PostCardData;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use crate::shared::avatar::Avatar;
+
+    GROUP_LINK_COLOR = #1d9bf0
+
+    /// Card representing a collapsed group of posts from one author.
+    pub SocialPostGroupCard = {{SocialPostGroupCard}} {
+        width: Fill,
+        height: Fit,
+        padding: 16,
+        flow: Down,
+        spacing: 8,
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 12,
+            align: { y: 0.5 },
+
+            author_avatar = <Avatar> {
+                width: 36,
+                height: 36,
+            }
+
+            summary_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: #000,
+                }
+            }
+        }
+
+        latest_preview = <Label> {
+            width: Fill,
+            height: Fit,
+            margin: { left: 48 },
+            text: "",
+            draw_text: {
+                text_style: { font_size: 13.0 },
+                color: #666,
+                wrap: Word,
+            }
+        }
+
+        buttons = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 8,
+            margin: { left: 48 },
+
+            expand_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Show all",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (GROUP_LINK_COLOR),
+                    text_style: { font_size: 12.0 }
+                }
+            }
+
+            always_expand_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Always show all from this person",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (GROUP_LINK_COLOR),
+                    text_style: { font_size: 12.0 }
+                }
+            }
+        }
+    }
+}
+
+/// Actions that can be triggered from a post group card.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialPostGroupAction {
+    /// User expanded the group, identified by its first post.
+    Expand(OwnedEventId),
+    /// User asked to never group this author's posts.
+    AlwaysExpand(OwnedUserId),
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialPostGroupCard {
+    #[deref]
+    view: View,
+
+    /// Event ID of the first post in the group.
+    #[rust]
+    first_event_id: Option<OwnedEventId>,
+
+    /// The author of the grouped posts.
+    #[rust]
+    author_id: Option<OwnedUserId>,
+
+    /// The author's name and avatar, kept to show the avatar image once it's been fetched.
+    #[rust]
+    author_avatar: (String, Option<OwnedMxcUri>),
+
+    /// Whether the author's avatar image is still being fetched.
+    #[rust]
+    avatar_pending: bool,
+}
+
+impl Widget for SocialPostGroupCard {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        // Show the author's avatar image once it's been fetched
+        if self.avatar_pending {
+            if let Event::Signal = event {
+                avatar_cache::process_avatar_updates(cx);
+                self.show_avatar(cx);
+                if !self.avatar_pending {
+                    self.redraw(cx);
+                }
+            }
+        }
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialPostGroupCard {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.button(ids!(expand_button)).clicked(actions) {
+            if let Some(event_id) = &self.first_event_id {
+                cx.action(SocialPostGroupAction::Expand(event_id.clone()));
+            }
+        }
+
+        if self.button(ids!(always_expand_button)).clicked(actions) {
+            if let Some(author_id) = &self.author_id {
+                cx.action(SocialPostGroupAction::AlwaysExpand(author_id.clone()));
+            }
+        }
+    }
+}
+
+impl SocialPostGroupCard {
+    /// Set the posts in this group, in feed order.
+    pub fn set_posts(&mut self, cx: &mut Cx, posts: &[&PostCardData]) {
+        let Some(first) = posts.first() else {
+            return;
+        };
+        self.first_event_id = Some(first.event_id.clone());
+        self.author_id = Some(first.author_id.clone());
+
+        let display_name = first
+            .author_name
+            .as_deref()
+            .unwrap_or_else(|| first.author_id.localpart());
+        self.author_avatar = (display_name.to_owned(), first.author_avatar_url.clone());
+        self.show_avatar(cx);
+        self.label(ids!(summary_label)).set_text(
+            cx,
+            &format!("{} posted {} times", display_name, posts.len()),
+        );
+        self.label(ids!(latest_preview)).set_text(cx, &first.text);
+    }
+
+    /// Show the author's avatar image, or the first letter of their name
+    /// while the image is being fetched.
+    fn show_avatar(&mut self, cx: &mut Cx) {
+        let (author_name, author_avatar_url) = &self.author_avatar;
+        let drawn = self.avatar(ids!(author_avatar)).show_avatar_uri(
+            cx,
+            author_name,
+            author_avatar_url.as_deref(),
+        );
+        self.avatar_pending = !drawn;
+    }
+}
+
+impl SocialPostGroupCardRef {
+    /// See [`SocialPostGroupCard::set_posts()`].
+    pub fn set_posts(&self, cx: &mut Cx, posts: &[&PostCardData]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_posts(cx, posts);
+        }
+    }
+}
@@ -0,0 +1,378 @@
+//! Profile editor sheet widget.
+//!
+//! This widget lets the user edit the text details of their own social
+//! profile: display name, bio, location, website, pronouns, and birthday.
+//! Input is validated with [`ProfileDraft`] before anything is saved, and
+//! invalid fields are marked with an error below them.
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::OwnedRoomId;
+use robrix_social_events::profile::SocialProfileEventContent;
+
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::social::profile_edit::{ProfileDraft, ProfileField, ProfileFieldError};
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+
+    PROFILE_EDITOR_ERROR_COLOR = #e0245e
+
+    /// A labeled text field with a validation error below it.
+    ProfileEditorField = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Down,
+        spacing: 4,
+
+        field_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 12.0 },
+                color: #666,
+            }
+        }
+
+        field_input = <SimpleTextInput> {}
+
+        field_error = <Label> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 11.0 },
+                color: (PROFILE_EDITOR_ERROR_COLOR),
+                wrap: Word,
+            }
+        }
+    }
+
+    /// Sheet for editing the details of one's own social profile.
+    pub SocialProfileEditor = {{SocialProfileEditor}} {
+        width: 440,
+        height: Fit,
+        flow: Down,
+        padding: 20,
+        spacing: 12,
+        show_bg: true,
+        draw_bg: {
+            color: #fff,
+            radius: 8.0,
+        }
+
+        title = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "Edit profile",
+            draw_text: {
+                text_style: { font_size: 18.0 },
+                color: #000,
+            }
+        }
+
+        fields = <ScrollYView> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 12,
+
+            display_name_field = <ProfileEditorField> {
+                field_label = { text: "Display name" }
+                field_input = { empty_message: "Your name" }
+            }
+
+            bio_field = <ProfileEditorField> {
+                field_label = { text: "Bio" }
+                field_input = { empty_message: "Tell people about yourself" }
+            }
+
+            location_field = <ProfileEditorField> {
+                field_label = { text: "Location" }
+                field_input = { empty_message: "City, country" }
+            }
+
+            website_field = <ProfileEditorField> {
+                field_label = { text: "Website" }
+                field_input = { empty_message: "https://example.org" }
+            }
+
+            pronouns_field = <ProfileEditorField> {
+                field_label = { text: "Pronouns" }
+                field_input = { empty_message: "e.g., she/her" }
+            }
+
+            birthday_field = <ProfileEditorField> {
+                field_label = { text: "Birthday" }
+                field_input = { empty_message: "YYYY-MM-DD" }
+            }
+        }
+
+        status_label = <Label> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 12.0 },
+                color: #666,
+                wrap: Word,
+            }
+        }
+
+        buttons = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 8,
+            align: { x: 1.0, y: 0.5 },
+
+            cancel_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Cancel",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: #666,
+                }
+            }
+
+            save_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Save",
+                draw_bg: {
+                    color: #1d9bf0,
+                    radius: 4.0,
+                }
+                draw_text: {
+                    color: #fff,
+                }
+            }
+        }
+    }
+}
+
+/// All editable fields, along with the ID of the view that holds each one.
+const FIELDS: [(ProfileField, &[LiveId]); 6] = [
+    (ProfileField::DisplayName, ids!(display_name_field)),
+    (ProfileField::Bio, ids!(bio_field)),
+    (ProfileField::Location, ids!(location_field)),
+    (ProfileField::Website, ids!(website_field)),
+    (ProfileField::Pronouns, ids!(pronouns_field)),
+    (ProfileField::Birthday, ids!(birthday_field)),
+];
+
+/// Actions emitted by or sent to the profile editor.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialProfileEditorAction {
+    /// The profile was saved. Sent from the background task that saved it.
+    Saved {
+        /// The new display name, if it was changed.
+        display_name: Option<String>,
+        /// The saved profile.
+        profile: SocialProfileEventContent,
+    },
+    /// Saving the profile failed. Sent from the background task that saved it.
+    SaveFailed(String),
+    /// The user closed the editor without saving.
+    Cancelled,
+    /// No action
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialProfileEditor {
+    #[deref]
+    view: View,
+
+    /// The profile room being edited.
+    #[rust]
+    room_id: Option<OwnedRoomId>,
+
+    /// The display name when editing started.
+    #[rust]
+    original_display_name: String,
+
+    /// The profile being edited, which keeps the fields that aren't editable here.
+    #[rust]
+    profile: SocialProfileEventContent,
+
+    /// Whether a save request is in progress.
+    #[rust]
+    saving: bool,
+}
+
+impl Widget for SocialProfileEditor {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialProfileEditor {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.button(ids!(cancel_button)).clicked(actions) && !self.saving {
+            cx.action(SocialProfileEditorAction::Cancelled);
+        }
+
+        if self.button(ids!(save_button)).clicked(actions) && !self.saving {
+            self.save(cx);
+        }
+
+        if !self.saving {
+            return;
+        }
+        for action in actions {
+            match action.downcast_ref() {
+                Some(SocialProfileEditorAction::Saved { profile, .. }) => {
+                    self.profile = profile.clone();
+                    self.set_saving(cx, false);
+                    self.set_status(cx, None);
+                }
+                Some(SocialProfileEditorAction::SaveFailed(error)) => {
+                    self.set_saving(cx, false);
+                    self.set_status(cx, Some(error.as_str()));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl SocialProfileEditor {
+    /// Start editing the given profile.
+    ///
+    /// `room_id` is the user's profile room, which the edited profile
+    /// is saved to with
+    /// [`ProfileRoomService::update_profile()`](crate::social::ProfileRoomService::update_profile).
+    pub fn set_profile(
+        &mut self,
+        cx: &mut Cx,
+        room_id: OwnedRoomId,
+        display_name: Option<&str>,
+        profile: SocialProfileEventContent,
+    ) {
+        let draft = ProfileDraft::new(display_name, Some(&profile));
+        for (field, path) in FIELDS {
+            let text = match field {
+                ProfileField::DisplayName => &draft.display_name,
+                ProfileField::Bio => &draft.bio,
+                ProfileField::Location => &draft.location,
+                ProfileField::Website => &draft.website,
+                ProfileField::Pronouns => &draft.pronouns,
+                ProfileField::Birthday => &draft.birthday,
+            };
+            self.view(path)
+                .text_input(ids!(field_input))
+                .set_text(cx, text);
+        }
+        self.show_errors(cx, &[]);
+        self.set_status(cx, None);
+        self.set_saving(cx, false);
+
+        self.room_id = Some(room_id);
+        self.original_display_name = draft.display_name;
+        self.profile = profile;
+        self.redraw(cx);
+    }
+
+    /// Collect the current contents of the fields.
+    fn draft(&self) -> ProfileDraft {
+        let text = |path: &[LiveId]| self.view(path).text_input(ids!(field_input)).text();
+        ProfileDraft {
+            display_name: text(ids!(display_name_field)),
+            bio: text(ids!(bio_field)),
+            location: text(ids!(location_field)),
+            website: text(ids!(website_field)),
+            pronouns: text(ids!(pronouns_field)),
+            birthday: text(ids!(birthday_field)),
+        }
+    }
+
+    /// Validate the fields and, if they are all valid, save the profile.
+    fn save(&mut self, cx: &mut Cx) {
+        let Some(room_id) = self.room_id.clone() else {
+            return;
+        };
+        let draft = self.draft();
+        let profile = match draft.apply_to_now(self.profile.clone()) {
+            Ok(profile) => profile,
+            Err(errors) => {
+                self.show_errors(cx, &errors);
+                self.set_status(cx, Some("Please fix the errors above."));
+                return;
+            }
+        };
+        self.show_errors(cx, &[]);
+
+        // Only change the account's display name if it was edited
+        let display_name = draft.display_name.trim().to_string();
+        let display_name = (display_name != self.original_display_name).then_some(display_name);
+        submit_async_request(MatrixRequest::UpdateSocialProfile {
+            room_id,
+            display_name,
+            profile,
+        });
+        self.set_saving(cx, true);
+        self.set_status(cx, None);
+    }
+
+    /// Show the given validation errors below their fields, hiding all others.
+    fn show_errors(&mut self, cx: &mut Cx, errors: &[ProfileFieldError]) {
+        for (field, path) in FIELDS {
+            let error_label = self.view(path).label(ids!(field_error));
+            match errors.iter().find(|e| e.field() == field) {
+                Some(error) => {
+                    error_label.set_text(cx, &error.to_string());
+                    error_label.set_visible(cx, true);
+                }
+                None => error_label.set_visible(cx, false),
+            }
+        }
+        self.redraw(cx);
+    }
+
+    /// Show a status message below the fields, or hide it if `None`.
+    fn set_status(&mut self, cx: &mut Cx, status: Option<&str>) {
+        let status_label = self.label(ids!(status_label));
+        status_label.set_text(cx, status.unwrap_or_default());
+        status_label.set_visible(cx, status.is_some());
+        self.redraw(cx);
+    }
+
+    /// Enable or disable the buttons while a save request is in progress.
+    fn set_saving(&mut self, cx: &mut Cx, saving: bool) {
+        self.saving = saving;
+        self.button(ids!(save_button))
+            .set_text(cx, if saving { "Saving…" } else { "Save" });
+        self.button(ids!(cancel_button)).set_enabled(cx, !saving);
+        self.button(ids!(save_button)).set_enabled(cx, !saving);
+    }
+}
+
+impl SocialProfileEditorRef {
+    /// See [`SocialProfileEditor::set_profile()`].
+    pub fn set_profile(
+        &self,
+        cx: &mut Cx,
+        room_id: OwnedRoomId,
+        display_name: Option<&str>,
+        profile: SocialProfileEventContent,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_profile(cx, room_id, display_name, profile);
+        }
+    }
+}
@@ -1,26 +1,202 @@
 //! Profile page widget displaying user's social profile.
 //!
 //! This widget renders the full social profile page, including cover photo,
-//! avatar, user information, and action buttons for social interactions.
+//! avatar, user information, follower/following/friend counts, and action
+//! buttons for social interactions.
+//!
+//! The page can be opened for any user from the rest of the app with
+//! [`SocialProfilePage::show_user()`], e.g., from the chat-side user profile pane.
+//! Its DSL lives in the `social_enabled` namespace, so that it can be used
+//! through the `social_link` namespace (see `App::live_register()`).
+//!
+//! On other users' profiles, the friends the current user shares with them are
+//! shown as "N mutual friends" with a few of their avatars, which can be tapped
+//! to list them all (see [`FriendSuggestionEngine::mutual_friends()`]).
+//! On one's own profile, tapping the follower or following count lists them
+//! instead (see [`crate::social::widgets::follow_lists`]), and followers can
+//! be removed or banned from there. Tapping the friend count lists one's
+//! friends and friend requests in a [`FriendListView`].
+//!
+//! Any profile can be shared from its Share button, which shows its link
+//! and a QR code of it (see [`crate::social::profile_links`]).
+//!
+//! Users whose identity the current user has verified get a verified badge
+//! next to their name; other users can be verified from their profile,
+//! and tapping the badge verifies them again.
+//!
+//! Below the profile details, tabs switch between the owner's posts,
+//! their photos and videos, and, on one's own profile, the posts one liked
+//! and a calendar of one's events (see [`crate::social::profile_tabs`]).
+//! Tapping an event in the calendar opens it in an [`EventDetailView`], and
+//! friends can be invited to the events one joined from an [`EventInviteSheet`].
+//! The owner's posts are shown in a [`SocialFeedView`] without its composer,
+//! from the owner's feeds that the current user has joined. Each tab is loaded when it's first shown for a profile.
+//! Viewing one of the owner's posts opens it in a [`PostDetailView`],
+//! along with when people reacted to it.
+//!
+//! [`FriendSuggestionEngine::mutual_friends()`]: crate::social::discovery::FriendSuggestionEngine::mutual_friends
+//! [`EventDetailView`]: crate::social::widgets::event_detail::EventDetailView
+//! [`EventInviteSheet`]: crate::social::widgets::event_invite_sheet::EventInviteSheet
+//! [`FriendListView`]: crate::social::widgets::friend_list::FriendListView
+//! [`PostDetailView`]: crate::social::widgets::post_detail::PostDetailView
+//! [`SocialFeedView`]: crate::social::widgets::feed_view::SocialFeedView
 
 use makepad_widgets::*;
-use matrix_sdk::ruma::OwnedUserId;
-use robrix_social_events::profile::SocialProfileEventContent;
+use matrix_sdk::ruma::{OwnedEventId, OwnedRoomId, OwnedUserId};
+use robrix_social_events::profile::{ProfileCountsPrivacy, SocialProfileEventContent};
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::shared::avatar::AvatarWidgetExt;
+use crate::shared::confirmation_modal::{ConfirmationModalContent, ConfirmationModalWidgetExt};
+use crate::home::navigation_tab_bar::NavigationBarAction;
+use crate::shared::popup_list::{enqueue_popup_notification, PopupItem, PopupKind};
+use crate::social::discovery::MutualFriendsAction;
+use crate::social::event_bus::{subscribe_social_events, SocialEvent, SocialSubscription, SocialTopic};
+use crate::social::follow::FollowListsAction;
+use crate::social::newsfeed::feed_grouping;
+use crate::social::widgets::friend_list::{FriendListAction, FriendListViewWidgetExt};
+use crate::social::widgets::follow_lists::{
+    FollowListAction, FollowersListViewWidgetExt, FollowingListViewWidgetExt,
+};
+use crate::social::profile_media::ProfileImageKind;
+use crate::social::widgets::profile_editor::{
+    SocialProfileEditorAction, SocialProfileEditorWidgetExt,
+};
+use crate::social::profile_stats::ProfileCounts;
+use crate::social::widgets::profile_share_sheet::{
+    SocialProfileShareSheetAction, SocialProfileShareSheetWidgetExt,
+};
+use crate::social::profile_tabs::{ProfileTab, ProfileTabAction, MAX_PROFILE_POSTS};
+use crate::social::widgets::event_detail::{
+    EventDetailAction, EventDetailLoadAction, EventDetailViewWidgetExt,
+};
+use crate::social::widgets::event_invite_sheet::{
+    EventInviteSheetAction, EventInviteSheetWidgetExt, EventInvitesAction,
+};
+use crate::social::widgets::events_calendar::{
+    SocialEventsCalendarAction, SocialEventsCalendarWidgetExt,
+};
+use crate::social::widgets::feed_view::{FeedState, SocialFeedViewAction, SocialFeedViewWidgetExt};
+use crate::social::widgets::post_card::SocialPostCardAction;
+use crate::social::widgets::post_detail::{PostDetailAction, PostDetailViewWidgetExt};
+use crate::social::widgets::profile_tabs::{
+    SocialProfileLikesListWidgetExt, SocialProfileMediaGridWidgetExt,
+};
+use crate::social::user_verification::{
+    refresh_user_verification, user_verification, UserVerificationAction,
+};
+use crate::sliding_sync::{current_user_id, submit_async_request, MatrixRequest};
+use crate::utils;
+use crate::verification::VerificationAction;
 
 live_design! {
+    link social_enabled
+
     use link::theme::*;
     use link::shaders::*;
     use link::widgets::*;
+    use link::social_theme::*;
 
     use crate::shared::styles::*;
     use crate::shared::avatar::Avatar;
+    use crate::shared::confirmation_modal::*;
+    use crate::social::widgets::profile_editor::SocialProfileEditor;
+    use crate::social::widgets::profile_share_sheet::SocialProfileShareSheet;
+    use crate::social::widgets::feed_view::SocialFeedView;
+    use crate::social::widgets::follow_lists::FollowersListView;
+    use crate::social::widgets::follow_lists::FollowingListView;
+    use crate::social::widgets::friend_list::FriendListView;
+    use crate::social::widgets::post_detail::PostDetailView;
+    use crate::social::widgets::profile_tabs::SocialProfileMediaGrid;
+    use crate::social::widgets::profile_tabs::SocialProfileLikesList;
+    use crate::social::widgets::events_calendar::SocialEventsCalendar;
+    use crate::social::widgets::event_invite_sheet::EventInviteSheet;
+    use crate::social::widgets::event_detail::EventDetailView;
 
     // Default cover image placeholder
     IMG_DEFAULT_COVER = dep("crate://self/resources/img/default_avatar.png")
 
+    /// A tab of the profile page, e.g., "Media".
+    ProfileTabButton = <Button> {
+        width: Fit,
+        height: Fill,
+        text: "",
+        draw_bg: {
+            color: #0000
+        }
+        draw_text: {
+            // 1.0 if this tab is shown
+            instance selected: 0.0
+
+            fn get_color(self) -> vec4 {
+                return mix((SOCIAL_TEXT_SECONDARY), (SOCIAL_ACCENT), self.selected);
+            }
+        }
+    }
+
+    /// A tappable profile count, e.g., "12 Followers".
+    ProfileCountButton = <Button> {
+        width: Fit,
+        height: Fit,
+        padding: 0,
+        text: "",
+        draw_bg: {
+            color: #0000,
+        }
+        draw_text: {
+            text_style: { font_size: 13.0 },
+            color: (SOCIAL_TEXT),
+        }
+    }
+
+    /// A small button for editing the profile's images.
+    ProfileImageButton = <Button> {
+        width: Fit,
+        height: Fit,
+        visible: false,
+        text: "",
+        draw_bg: {
+            color: (SOCIAL_BG),
+            border_width: 1.0,
+            border_color: (SOCIAL_BORDER),
+            radius: 4.0,
+        }
+        draw_text: {
+            color: (SOCIAL_TEXT),
+        }
+    }
+
+    /// A single user in the mutual friends list.
+    ProfilePersonItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 10, bottom: 10 },
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        cursor: Hand,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_BG)
+        }
+
+        avatar = <Avatar> {
+            width: 36,
+            height: 36,
+        }
+
+        user_id_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: (SOCIAL_TEXT_PRIMARY),
+            }
+        }
+    }
+
     /// Social profile page layout displaying user's extended profile information.
     pub SocialProfilePage = {{SocialProfilePage}} {
         width: Fill,
@@ -28,13 +204,15 @@ live_design! {
         flow: Down,
         show_bg: true,
         draw_bg: {
-            color: #fff
+            color: (SOCIAL_BG)
         }
 
         // Cover photo banner
         cover_container = <View> {
             width: Fill,
             height: 200,
+            flow: Overlay,
+            align: { x: 1.0, y: 1.0 },
 
             cover_image = <Image> {
                 width: Fill,
@@ -50,6 +228,19 @@ live_design! {
                     }
                 }
             }
+
+            // Preview of a new cover photo before it is saved
+            cover_preview = <Image> {
+                width: Fill,
+                height: Fill,
+                fit: Cover,
+                visible: false,
+            }
+
+            change_cover_button = <ProfileImageButton> {
+                margin: 12,
+                text: "Change cover",
+            }
         }
 
         // Profile info section
@@ -72,8 +263,42 @@ live_design! {
                     height: 100,
                 }
 
+                // Preview of a new avatar before it is saved
+                avatar_preview = <Avatar> {
+                    width: 100,
+                    height: 100,
+                    visible: false,
+                }
+
                 <View> { width: Fill }
 
+                change_avatar_button = <ProfileImageButton> {
+                    margin: { top: 54, right: 8 },
+                    text: "Change photo",
+                }
+
+                edit_details_button = <ProfileImageButton> {
+                    margin: { top: 54, right: 8 },
+                    text: "Edit details",
+                }
+
+                // Shows the profile's link and QR code
+                share_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    margin: { top: 54, right: 8 },
+                    text: "Share",
+                    draw_bg: {
+                        color: (SOCIAL_BG),
+                        border_width: 1.0,
+                        border_color: (SOCIAL_BORDER),
+                        radius: 4.0,
+                    }
+                    draw_text: {
+                        color: (SOCIAL_TEXT),
+                    }
+                }
+
                 // Edit button (for own profile)
                 edit_button = <Button> {
                     width: Fit,
@@ -81,13 +306,65 @@ live_design! {
                     margin: { top: 54 },
                     text: "Edit Profile",
                     draw_bg: {
-                        color: #fff,
+                        color: (SOCIAL_BG),
                         border_width: 1.0,
-                        border_color: #ccc,
+                        border_color: (SOCIAL_BORDER),
+                        radius: 4.0,
+                    }
+                    draw_text: {
+                        color: (SOCIAL_TEXT),
+                    }
+                }
+            }
+
+            // Save or discard a previewed cover photo or avatar
+            image_edit_bar = <View> {
+                width: Fill,
+                height: Fit,
+                padding: 8,
+                flow: Right,
+                spacing: 8,
+                align: { y: 0.5 },
+                visible: false,
+                show_bg: true,
+                draw_bg: {
+                    color: (SOCIAL_ACCENT_BG),
+                    radius: 4.0,
+                }
+
+                image_edit_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 12.0 },
+                        color: (SOCIAL_TEXT),
+                        wrap: Word,
+                    }
+                }
+
+                cancel_image_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Cancel",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (SOCIAL_TEXT_SECONDARY),
+                    }
+                }
+
+                save_image_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Save",
+                    draw_bg: {
+                        color: (SOCIAL_ACCENT),
                         radius: 4.0,
                     }
                     draw_text: {
-                        color: #333,
+                        color: (SOCIAL_ON_ACCENT),
                     }
                 }
             }
@@ -99,14 +376,55 @@ live_design! {
                 flow: Down,
                 spacing: 4,
 
-                name_label = <Label> {
+                name_row = <View> {
                     width: Fill,
                     height: Fit,
-                    text: "",
-                    draw_text: {
-                        text_style: { font_size: 20.0, },
-                        color: #000,
-                        font_scale: 1.0,
+                    flow: Right,
+                    spacing: 6,
+                    align: { y: 0.5 },
+
+                    name_label = <Label> {
+                        width: Fit,
+                        height: Fit,
+                        text: "",
+                        draw_text: {
+                            text_style: { font_size: 20.0, },
+                            color: (SOCIAL_TEXT_PRIMARY),
+                            font_scale: 1.0,
+                        }
+                    }
+
+                    // Shown if the current user verified the owner's identity
+                    verified_badge = <Button> {
+                        width: Fit,
+                        height: Fit,
+                        padding: 0,
+                        visible: false,
+                        text: "✓ Verified",
+                        draw_bg: {
+                            color: #0000,
+                        }
+                        draw_text: {
+                            text_style: { font_size: 13.0 },
+                            color: (SOCIAL_ACCENT),
+                        }
+                    }
+
+                    // Shown for other users' profiles until they're verified
+                    verify_button = <Button> {
+                        width: Fit,
+                        height: Fit,
+                        visible: false,
+                        text: "Verify",
+                        draw_bg: {
+                            color: (SOCIAL_BG),
+                            border_width: 1.0,
+                            border_color: (SOCIAL_BORDER),
+                            radius: 4.0,
+                        }
+                        draw_text: {
+                            color: (SOCIAL_TEXT),
+                        }
                     }
                 }
 
@@ -116,7 +434,7 @@ live_design! {
                     text: "",
                     draw_text: {
                         text_style: { font_size: 14.0, },
-                        color: #666,
+                        color: (SOCIAL_TEXT_SECONDARY),
                     }
                 }
             }
@@ -128,7 +446,7 @@ live_design! {
                 text: "",
                 draw_text: {
                     text_style: { font_size: 14.0, },
-                    color: #333,
+                    color: (SOCIAL_TEXT),
                     wrap: Word,
                 }
             }
@@ -153,7 +471,7 @@ live_design! {
                         text: "Location:",
                         draw_text: {
                             text_style: { font_size: 12.0 },
-                            color: #666,
+                            color: (SOCIAL_TEXT_SECONDARY),
                         }
                     }
 
@@ -163,7 +481,7 @@ live_design! {
                         text: "",
                         draw_text: {
                             text_style: { font_size: 12.0 },
-                            color: #666,
+                            color: (SOCIAL_TEXT_SECONDARY),
                         }
                     }
                 }
@@ -181,7 +499,7 @@ live_design! {
                         text: "Website:",
                         draw_text: {
                             text_style: { font_size: 12.0 },
-                            color: #666,
+                            color: (SOCIAL_TEXT_SECONDARY),
                         }
                     }
 
@@ -191,12 +509,90 @@ live_design! {
                         text: "",
                         draw_text: {
                             text_style: { font_size: 12.0 },
-                            color: #1a0dab,
+                            color: (SOCIAL_ACCENT),
                         }
                     }
                 }
             }
 
+            // Follower, following, and friend counts
+            counts_row = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 16,
+
+                followers_button = <ProfileCountButton> {}
+                following_button = <ProfileCountButton> {}
+                friends_button = <ProfileCountButton> {}
+            }
+
+            // Friends shared with the current user (others' profiles only)
+            mutual_friends_row = <View> {
+                width: Fit,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+                align: { y: 0.5 },
+                cursor: Hand,
+                visible: false,
+
+                mutual_friend_avatars = <View> {
+                    width: Fit,
+                    height: Fit,
+                    flow: Right,
+
+                    mutual_friend_0 = <Avatar> {
+                        width: 24,
+                        height: 24,
+                    }
+                    mutual_friend_1 = <Avatar> {
+                        width: 24,
+                        height: 24,
+                        margin: { left: -8 },
+                    }
+                    mutual_friend_2 = <Avatar> {
+                        width: 24,
+                        height: 24,
+                        margin: { left: -8 },
+                    }
+                }
+
+                mutual_friends_label = <Label> {
+                    width: Fit,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 13.0 },
+                        color: (SOCIAL_TEXT_SECONDARY),
+                    }
+                }
+            }
+
+            // Count privacy settings (own profile only)
+            counts_privacy_settings = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Down,
+                spacing: 4,
+                visible: false,
+
+                hide_followers_toggle = <CheckBoxFlat> {
+                    text: "Hide my follower count from others",
+                    active: false,
+                }
+
+                hide_following_toggle = <CheckBoxFlat> {
+                    text: "Hide my following count from others",
+                    active: false,
+                }
+
+                hide_friends_toggle = <CheckBoxFlat> {
+                    text: "Hide my friend count from others",
+                    active: false,
+                }
+            }
+
             // Action buttons row
             action_row = <View> {
                 width: Fill,
@@ -210,11 +606,11 @@ live_design! {
                     height: Fit,
                     text: "Follow",
                     draw_bg: {
-                        color: #1d9bf0,
+                        color: (SOCIAL_ACCENT),
                         radius: 20.0,
                     }
                     draw_text: {
-                        color: #fff,
+                        color: (SOCIAL_ON_ACCENT),
                     }
                 }
 
@@ -223,13 +619,13 @@ live_design! {
                     height: Fit,
                     text: "Add Friend",
                     draw_bg: {
-                        color: #fff,
+                        color: (SOCIAL_BG),
                         border_width: 1.0,
-                        border_color: #ccc,
+                        border_color: (SOCIAL_BORDER),
                         radius: 20.0,
                     }
                     draw_text: {
-                        color: #333,
+                        color: (SOCIAL_TEXT),
                     }
                 }
 
@@ -238,13 +634,13 @@ live_design! {
                     height: Fit,
                     text: "Message",
                     draw_bg: {
-                        color: #fff,
+                        color: (SOCIAL_BG),
                         border_width: 1.0,
-                        border_color: #ccc,
+                        border_color: (SOCIAL_BORDER),
                         radius: 20.0,
                     }
                     draw_text: {
-                        color: #333,
+                        color: (SOCIAL_TEXT),
                     }
                 }
             }
@@ -256,7 +652,7 @@ live_design! {
             height: 1,
             show_bg: true,
             draw_bg: {
-                color: #eee
+                color: (SOCIAL_DIVIDER)
             }
         }
 
@@ -267,61 +663,181 @@ live_design! {
             flow: Right,
             padding: { left: 16, right: 16 },
 
-            posts_tab = <Button> {
-                width: Fit,
-                height: Fill,
+            posts_tab = <ProfileTabButton> {
                 text: "Posts",
-                draw_bg: {
-                    color: #0000
-                }
-                draw_text: {
-                    color: #1d9bf0,
-                }
+                draw_text: { selected: 1.0 }
             }
 
-            media_tab = <Button> {
-                width: Fit,
-                height: Fill,
+            media_tab = <ProfileTabButton> {
                 text: "Media",
-                draw_bg: {
-                    color: #0000
-                }
-                draw_text: {
-                    color: #666,
-                }
             }
 
-            likes_tab = <Button> {
-                width: Fit,
-                height: Fill,
+            // Only shown on one's own profile
+            likes_tab = <ProfileTabButton> {
                 text: "Likes",
-                draw_bg: {
-                    color: #0000
-                }
-                draw_text: {
-                    color: #666,
-                }
+                visible: false,
+            }
+
+            // Only shown on one's own profile
+            events_tab = <ProfileTabButton> {
+                text: "Events",
+                visible: false,
             }
         }
 
-        // Posts feed section (placeholder)
+        // The owner's posts, in the feeds the current user can read
         posts_section = <View> {
             width: Fill,
             height: Fill,
 
-            posts_placeholder = <Label> {
+            profile_feed = <SocialFeedView> {
+                composer_section = { visible: false }
+            }
+        }
+
+        // The owner's photos, videos and audio clips
+        media_section = <SocialProfileMediaGrid> {
+            visible: false,
+        }
+
+        // Posts the current user reacted to (own profile only)
+        likes_section = <SocialProfileLikesList> {
+            visible: false,
+        }
+
+        // Events the current user joined or was invited to (own profile only)
+        events_section = <SocialEventsCalendar> {
+            visible: false,
+        }
+
+        // Mutual friends, replaces the tab sections
+        people_section = <View> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+            visible: false,
+
+            people_header = <View> {
+                width: Fill,
+                height: Fit,
+                padding: { left: 16, right: 16, top: 8, bottom: 8 },
+                flow: Right,
+                align: { y: 0.5 },
+
+                people_title = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 16.0 },
+                        color: (SOCIAL_TEXT_PRIMARY),
+                    }
+                }
+
+                close_people_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Close",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: (SOCIAL_TEXT_SECONDARY),
+                    }
+                }
+            }
+
+            people_list = <PortalList> {
                 width: Fill,
                 height: Fill,
-                padding: 32,
-                text: "Posts will appear here...",
-                draw_text: {
-                    text_style: { font_size: 14.0 },
-                    color: #999,
-                    wrap: Word,
+                flow: Down,
+
+                person_item = <ProfilePersonItem> {}
+
+                empty_people = <View> {
+                    width: Fill,
+                    height: Fit,
+                    padding: 32,
+                    align: { x: 0.5 },
+
+                    <Label> {
+                        text: "Nobody here yet.",
+                        draw_text: {
+                            text_style: { font_size: 14.0 },
+                            color: (SOCIAL_TEXT_MUTED),
+                        }
+                    }
+                }
+            }
+        }
+
+        // The owner's followers or followed users (own profile only),
+        // replaces the tab sections
+        followers_section = <FollowersListView> {
+            visible: false,
+        }
+
+        following_section = <FollowingListView> {
+            visible: false,
+        }
+
+        // The owner's friends and friend requests (own profile only)
+        friends_section = <FriendListView> {
+            visible: false,
+            header = {
+                add_friend_button = { visible: false }
+            }
+        }
+
+        // Confirmation for removing or banning a follower
+        follower_moderation_modal = <Modal> {
+            content: {
+                follower_moderation_modal_inner = <NegativeConfirmationModal> {}
+            }
+        }
+
+        // Editor for the profile's text details (own profile only)
+        profile_editor_modal = <Modal> {
+            content: {
+                profile_editor = <SocialProfileEditor> {}
+            }
+        }
+
+        // The profile's link and QR code
+        profile_share_modal = <Modal> {
+            content: {
+                profile_share_sheet = <SocialProfileShareSheet> {}
+            }
+        }
+
+        // Friends to invite to one of the current user's events
+        event_invite_modal = <Modal> {
+            content: {
+                event_invite_sheet = <EventInviteSheet> {
+                    width: 400,
+                    height: 500,
+                }
+            }
+        }
+
+        // One of the current user's events, from the events calendar
+        event_detail_modal = <Modal> {
+            content: {
+                event_detail = <EventDetailView> {
+                    width: 500,
+                    height: 600,
                 }
             }
+        }
 
-            // Will embed SocialFeedView in Phase 3
+        // One of the owner's posts, with when people reacted to it
+        post_detail_modal = <Modal> {
+            content: {
+                post_detail = <PostDetailView> {
+                    width: 500,
+                    height: 600,
+                }
+            }
         }
     }
 }
@@ -331,6 +847,8 @@ live_design! {
 pub struct LoadedProfile {
     /// User ID of the profile owner
     pub user_id: OwnedUserId,
+    /// Room ID of the profile room, if known
+    pub room_id: Option<OwnedRoomId>,
     /// Display name from Matrix profile
     pub display_name: Option<String>,
     /// Extended social profile data
@@ -344,16 +862,35 @@ pub struct LoadedProfile {
 /// Actions that can be triggered from the profile page.
 #[derive(Clone, Debug, DefaultNone)]
 pub enum SocialProfileAction {
-    /// User clicked the Follow button
-    Follow(OwnedUserId),
+    /// A [`MatrixRequest::Follow`] or [`MatrixRequest::Unfollow`] completed,
+    /// leaving the user followed or not, whether or not it succeeded.
+    FollowChanged {
+        user_id: OwnedUserId,
+        following: bool,
+    },
     /// User clicked the Add Friend button
     SendFriendRequest(OwnedUserId),
     /// User clicked the Message button
     OpenDirectMessage(OwnedUserId),
-    /// User clicked the Edit Profile button
+    /// User opened the editor for their profile details
     EditProfile,
+    /// User wants to pick a new cover photo or avatar.
+    /// The picked image should be prepared with
+    /// [`prepare_profile_image()`](crate::social::prepare_profile_image)
+    /// and shown with [`SocialProfilePage::preview_profile_image()`].
+    ChangeProfileImage(ProfileImageKind),
     /// User clicked on the website link
     OpenWebsite(String),
+    /// User changed which counts are hidden from others
+    SetCountsPrivacy(ProfileCountsPrivacy),
+    /// User tapped someone in the mutual friends list
+    OpenProfile(OwnedUserId),
+    /// A follower was removed from the current user's public feed or banned
+    /// from all of their feeds, after a [`MatrixRequest::RemoveFollower`]
+    /// or [`MatrixRequest::BanFromFeeds`].
+    FollowerRemoved(OwnedUserId),
+    /// A profile requested with [`SocialProfilePage::show_user()`] was loaded
+    Loaded(LoadedProfile),
     /// No action
     None,
 }
@@ -374,16 +911,116 @@ pub struct SocialProfilePage {
     /// Whether this is the current user's own profile.
     #[rust]
     is_own_profile: bool,
-}
 
-impl Widget for SocialProfilePage {
-    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
-        self.view.handle_event(cx, event, scope);
-        self.widget_match_event(cx, event, scope);
-    }
+    /// Whether the current user follows the profile owner.
+    #[rust]
+    is_following: bool,
+
+    /// Which list of people is currently shown, if any.
+    #[rust]
+    people_list: Option<ProfilePeopleList>,
+
+    /// Whether the owner is editing their profile.
+    #[rust]
+    editing: bool,
+
+    /// A previewed cover photo or avatar that hasn't been saved yet.
+    #[rust]
+    pending_image: Option<(ProfileImageKind, Arc<[u8]>)>,
+
+    /// The tab shown below the profile details.
+    #[rust]
+    tab: ProfileTab,
+
+    /// Tabs whose posts were requested since the profile was shown.
+    #[rust]
+    loaded_tabs: Vec<ProfileTab>,
+
+    /// The friends the current user shares with the profile owner.
+    #[rust]
+    mutual_friends: Vec<OwnedUserId>,
+
+    /// The post shown in the post detail modal, if any.
+    #[rust]
+    detail_post: Option<OwnedEventId>,
+
+    /// The event friends are being invited to, while the invites sheet is open.
+    #[rust]
+    invite_event: Option<OwnedRoomId>,
+
+    /// The event shown in the event detail modal, if any.
+    #[rust]
+    detail_event: Option<OwnedRoomId>,
+
+    /// Saved profile changes, shown if they're about the displayed profile.
+    #[rust(subscribe_social_events(&[SocialTopic::ProfileChanges]))]
+    profile_changes: SocialSubscription,
+}
+
+/// The avatars of the first few mutual friends, in order.
+const MUTUAL_FRIEND_AVATARS: [&[LiveId]; 3] = [
+    ids!(mutual_friend_0),
+    ids!(mutual_friend_1),
+    ids!(mutual_friend_2),
+];
+
+/// Which list of people is shown on the profile page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfilePeopleList {
+    /// Users following the profile owner.
+    Followers,
+    /// Users the profile owner follows.
+    Following,
+    /// The current user's friends and friend requests, on their own profile.
+    Friends,
+    /// Friends the current user shares with the profile owner.
+    MutualFriends,
+}
+
+impl ProfilePeopleList {
+    /// Get the title shown above the list.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Followers => "Followers",
+            Self::Following => "Following",
+            Self::Friends => "Friends",
+            Self::MutualFriends => "Mutual friends",
+        }
+    }
+}
+
+impl Widget for SocialProfilePage {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if let Event::Signal = event {
+            self.apply_profile_changes(cx);
+        }
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
-        self.view.draw_walk(cx, scope, walk)
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, self.mutual_friends.len().max(1));
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let item = match self.mutual_friends.get(item_id) {
+                    Some(user_id) => {
+                        let item = list.item(cx, item_id, live_id!(person_item));
+                        item.avatar(ids!(avatar)).set_text(cx, user_id.localpart());
+                        item.label(ids!(user_id_label))
+                            .set_text(cx, user_id.as_str());
+                        item
+                    }
+                    None => list.item(cx, item_id, live_id!(empty_people)),
+                };
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
     }
 }
 
@@ -396,8 +1033,14 @@ impl WidgetMatchEvent for SocialProfilePage {
 
         // Handle button clicks
         if follow_button.clicked(actions) {
-            if let Some(user_id) = &self.user_id {
-                cx.action(SocialProfileAction::Follow(user_id.clone()));
+            if let Some(user_id) = self.user_id.clone() {
+                if self.is_following {
+                    submit_async_request(MatrixRequest::Unfollow(user_id));
+                } else {
+                    submit_async_request(MatrixRequest::Follow(user_id));
+                }
+                // Show the change right away; it's reverted if the request fails
+                self.set_following(cx, !self.is_following);
             }
         }
 
@@ -414,7 +1057,674 @@ impl WidgetMatchEvent for SocialProfilePage {
         }
 
         if edit_button.clicked(actions) {
-            cx.action(SocialProfileAction::EditProfile);
+            self.set_editing(cx, !self.editing);
+        }
+
+        if self.button(ids!(share_button)).clicked(actions) {
+            if let Some(user_id) = self.user_id.clone() {
+                self.social_profile_share_sheet(ids!(profile_share_sheet))
+                    .show(cx, user_id);
+                self.modal(ids!(profile_share_modal)).open(cx);
+            }
+        }
+
+        if self.editing {
+            if self.button(ids!(edit_details_button)).clicked(actions) {
+                self.open_profile_editor(cx);
+                cx.action(SocialProfileAction::EditProfile);
+            }
+            if self.button(ids!(change_cover_button)).clicked(actions) {
+                cx.action(SocialProfileAction::ChangeProfileImage(
+                    ProfileImageKind::CoverPhoto,
+                ));
+            }
+            if self.button(ids!(change_avatar_button)).clicked(actions) {
+                cx.action(SocialProfileAction::ChangeProfileImage(ProfileImageKind::Avatar));
+            }
+        }
+
+        if self.button(ids!(save_image_button)).clicked(actions) {
+            if let Some((kind, data)) = self.pending_image.take() {
+                self.show_profile_image(cx, kind, &data);
+                let mut room_id = None;
+                if let Some(profile) = self.profile.as_mut() {
+                    match kind {
+                        ProfileImageKind::CoverPhoto => profile.cover_data = Some(data.clone()),
+                        ProfileImageKind::Avatar => profile.avatar_data = Some(data.clone()),
+                    }
+                    room_id = profile.room_id.clone();
+                }
+                self.hide_image_preview(cx);
+                match room_id {
+                    Some(room_id) => {
+                        submit_async_request(MatrixRequest::SaveProfileImage {
+                            room_id,
+                            kind,
+                            data,
+                        });
+                    }
+                    None => enqueue_popup_notification(PopupItem {
+                        message: "Your profile has no room to save the image to.".into(),
+                        kind: PopupKind::Error,
+                        auto_dismissal_duration: None,
+                    }),
+                }
+            }
+        }
+
+        if self.button(ids!(cancel_image_button)).clicked(actions) {
+            self.pending_image = None;
+            self.hide_image_preview(cx);
+        }
+
+        // Only the owner can see who follows them and who they follow
+        if self.is_own_profile {
+            if self.button(ids!(followers_button)).clicked(actions) {
+                self.show_people_list(cx, ProfilePeopleList::Followers);
+            }
+            if self.button(ids!(following_button)).clicked(actions) {
+                self.show_people_list(cx, ProfilePeopleList::Following);
+            }
+            if self.button(ids!(friends_button)).clicked(actions) {
+                self.show_people_list(cx, ProfilePeopleList::Friends);
+            }
+
+            let hide_followers = self.check_box(ids!(hide_followers_toggle));
+            let hide_following = self.check_box(ids!(hide_following_toggle));
+            let hide_friends = self.check_box(ids!(hide_friends_toggle));
+            if hide_followers.changed(actions).is_some()
+                || hide_following.changed(actions).is_some()
+                || hide_friends.changed(actions).is_some()
+            {
+                cx.action(SocialProfileAction::SetCountsPrivacy(ProfileCountsPrivacy {
+                    hide_followers: hide_followers.active(cx),
+                    hide_following: hide_following.active(cx),
+                    hide_friends: hide_friends.active(cx),
+                }));
+            }
+        }
+
+        if self
+            .view(ids!(mutual_friends_row))
+            .finger_up(actions)
+            .is_some()
+        {
+            self.show_people_list(cx, ProfilePeopleList::MutualFriends);
+        }
+
+        if self.button(ids!(close_people_button)).clicked(actions) {
+            self.hide_people_list(cx);
+        }
+
+        if self.button(ids!(posts_tab)).clicked(actions) {
+            self.show_tab(cx, ProfileTab::Posts);
+        }
+        if self.button(ids!(media_tab)).clicked(actions) {
+            self.show_tab(cx, ProfileTab::Media);
+        }
+        if self.button(ids!(likes_tab)).clicked(actions) {
+            self.show_tab(cx, ProfileTab::Likes);
+        }
+        if self.button(ids!(events_tab)).clicked(actions) {
+            self.show_tab(cx, ProfileTab::Events);
+        }
+
+        for action in actions {
+            if let Some(SocialFeedViewAction::LoadMore) = action.downcast_ref()
+                && let Some(user_id) = self.user_id.clone()
+            {
+                let loaded = self.social_feed_view(ids!(profile_feed)).post_count();
+                submit_async_request(MatrixRequest::LoadMoreProfilePosts {
+                    user_id,
+                    limit: loaded + MAX_PROFILE_POSTS,
+                });
+            }
+
+            if let Some(SocialFeedViewAction::PostAction(SocialPostCardAction::ViewPost(
+                event_id,
+            ))) = action.downcast_ref()
+            {
+                self.show_post_detail(cx, event_id);
+            }
+            if let Some(SocialFeedViewAction::GroupingChanged(grouping)) = action.downcast_ref() {
+                submit_async_request(MatrixRequest::SaveFeedGrouping(grouping.clone()));
+            }
+
+            match action.downcast_ref() {
+                Some(SocialEventsCalendarAction::OpenEvent(room_id)) => {
+                    self.show_event_detail(cx, room_id.clone());
+                }
+                Some(SocialEventsCalendarAction::InviteFriends(room_id)) => {
+                    self.show_event_invites(cx, room_id.clone());
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(EventInviteSheetAction::SendInvites { room_id, user_ids }) => {
+                    submit_async_request(MatrixRequest::InviteEventGuests {
+                        room_id: room_id.clone(),
+                        user_ids: user_ids.clone(),
+                    });
+                }
+                Some(EventInviteSheetAction::Close) => {
+                    self.invite_event = None;
+                    self.modal(ids!(event_invite_modal)).close(cx);
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(EventInvitesAction::FriendsLoaded {
+                    room_id,
+                    friends,
+                    already_invited,
+                }) if self.invite_event.as_ref() == Some(room_id) => {
+                    self.event_invite_sheet(ids!(event_invite_sheet))
+                        .set_friends(cx, room_id.clone(), friends.clone(), already_invited);
+                }
+                Some(EventInvitesAction::Sent { room_id, results })
+                    if self.invite_event.as_ref() == Some(room_id) =>
+                {
+                    self.event_invite_sheet(ids!(event_invite_sheet))
+                        .set_results(cx, results);
+                }
+                Some(EventInvitesAction::Failed(error)) if self.invite_event.is_some() => {
+                    enqueue_popup_notification(PopupItem {
+                        message: error.clone(),
+                        kind: PopupKind::Error,
+                        auto_dismissal_duration: None,
+                    });
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(EventDetailAction::SetAttendQuietly { room_id, quiet }) => {
+                    submit_async_request(MatrixRequest::SetAttendQuietly {
+                        room_id: room_id.clone(),
+                        quiet: *quiet,
+                    });
+                }
+                Some(EventDetailAction::AddCohost { room_id, user_id }) => {
+                    submit_async_request(MatrixRequest::AddEventCohost {
+                        room_id: room_id.clone(),
+                        user_id: user_id.clone(),
+                    });
+                }
+                Some(EventDetailAction::RemoveCohost { room_id, user_id }) => {
+                    submit_async_request(MatrixRequest::RemoveEventCohost {
+                        room_id: room_id.clone(),
+                        user_id: user_id.clone(),
+                    });
+                }
+                Some(EventDetailAction::CheckIn { room_id, message }) => {
+                    submit_async_request(MatrixRequest::CheckInToEvent {
+                        room_id: room_id.clone(),
+                        message: message.clone(),
+                    });
+                }
+                Some(EventDetailAction::AddChecklistItem {
+                    room_id,
+                    title,
+                    kind,
+                    quantity,
+                }) => {
+                    submit_async_request(MatrixRequest::AddChecklistItem {
+                        room_id: room_id.clone(),
+                        title: title.clone(),
+                        kind: *kind,
+                        quantity: *quantity,
+                    });
+                }
+                Some(EventDetailAction::SetChecklistClaim {
+                    room_id,
+                    item_id,
+                    quantity,
+                }) => {
+                    submit_async_request(MatrixRequest::SetChecklistClaim {
+                        room_id: room_id.clone(),
+                        item_id: item_id.clone(),
+                        quantity: *quantity,
+                    });
+                }
+                Some(EventDetailAction::SetChecklistItemDone {
+                    room_id,
+                    item_id,
+                    done,
+                }) => {
+                    submit_async_request(MatrixRequest::SetChecklistItemDone {
+                        room_id: room_id.clone(),
+                        item_id: item_id.clone(),
+                        done: *done,
+                    });
+                }
+                Some(EventDetailAction::OfferRide {
+                    room_id,
+                    seats,
+                    area,
+                }) => {
+                    submit_async_request(MatrixRequest::OfferRide {
+                        room_id: room_id.clone(),
+                        seats: *seats,
+                        area: area.clone(),
+                    });
+                }
+                Some(EventDetailAction::RequestRide {
+                    room_id,
+                    seats,
+                    area,
+                }) => {
+                    submit_async_request(MatrixRequest::RequestRide {
+                        room_id: room_id.clone(),
+                        seats: *seats,
+                        area: area.clone(),
+                    });
+                }
+                Some(EventDetailAction::WithdrawRide(room_id)) => {
+                    submit_async_request(MatrixRequest::WithdrawRide {
+                        room_id: room_id.clone(),
+                    });
+                }
+                Some(EventDetailAction::MessageAttendee(user_id)) => {
+                    cx.action(SocialProfileAction::OpenDirectMessage(user_id.clone()));
+                }
+                Some(EventDetailAction::VoteSchedulingPoll {
+                    room_id,
+                    start_times,
+                }) => {
+                    submit_async_request(MatrixRequest::VoteSchedulingPoll {
+                        room_id: room_id.clone(),
+                        start_times: start_times.clone(),
+                    });
+                }
+                Some(EventDetailAction::FinalizeSchedulingPoll(room_id)) => {
+                    submit_async_request(MatrixRequest::FinalizeSchedulingPoll {
+                        room_id: room_id.clone(),
+                    });
+                }
+                Some(EventDetailAction::KeepEventOpen(room_id)) => {
+                    submit_async_request(MatrixRequest::KeepEventOpen {
+                        room_id: room_id.clone(),
+                    });
+                }
+                Some(EventDetailAction::ArchiveEvent(room_id)) => {
+                    submit_async_request(MatrixRequest::ArchiveEvent {
+                        room_id: room_id.clone(),
+                    });
+                }
+                Some(EventDetailAction::CloseEvent(room_id)) => {
+                    submit_async_request(MatrixRequest::CloseEvent {
+                        room_id: room_id.clone(),
+                    });
+                }
+                Some(EventDetailAction::ShareSummary(summary)) => {
+                    cx.copy_to_clipboard(summary);
+                    enqueue_popup_notification(PopupItem {
+                        message: String::from("Copied the event summary to the clipboard."),
+                        auto_dismissal_duration: Some(3.0),
+                        kind: PopupKind::Success,
+                    });
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(EventDetailLoadAction::Loaded(event))
+                    if self.detail_event.as_ref() == Some(&event.room_id) =>
+                {
+                    self.event_detail_view(ids!(event_detail))
+                        .set_event(cx, event.clone());
+                }
+                Some(EventDetailLoadAction::Insights {
+                    room_id,
+                    insights,
+                    summary,
+                }) if self.detail_event.as_ref() == Some(room_id) => {
+                    self.event_detail_view(ids!(event_detail)).set_insights(
+                        cx,
+                        insights,
+                        summary.clone(),
+                    );
+                }
+                Some(EventDetailLoadAction::Roles {
+                    room_id,
+                    roles,
+                    viewer_role,
+                }) if self.detail_event.as_ref() == Some(room_id) => {
+                    self.event_detail_view(ids!(event_detail))
+                        .set_roles(cx, roles, *viewer_role);
+                }
+                Some(EventDetailLoadAction::AttendQuietly { room_id, quiet })
+                    if self.detail_event.as_ref() == Some(room_id) =>
+                {
+                    self.event_detail_view(ids!(event_detail))
+                        .set_attend_quietly(cx, *quiet);
+                }
+                Some(EventDetailLoadAction::Checkins { room_id, checkins })
+                    if self.detail_event.as_ref() == Some(room_id) =>
+                {
+                    self.event_detail_view(ids!(event_detail))
+                        .set_checkins(cx, checkins.clone());
+                }
+                Some(EventDetailLoadAction::Checklist {
+                    room_id,
+                    items,
+                    is_host,
+                }) if self.detail_event.as_ref() == Some(room_id) => {
+                    self.event_detail_view(ids!(event_detail)).set_checklist(
+                        cx,
+                        items.clone(),
+                        *is_host,
+                    );
+                }
+                Some(EventDetailLoadAction::RideBoard { room_id, board })
+                    if self.detail_event.as_ref() == Some(room_id) =>
+                {
+                    self.event_detail_view(ids!(event_detail))
+                        .set_ride_board(cx, board);
+                }
+                Some(EventDetailLoadAction::SchedulingPoll {
+                    room_id,
+                    poll,
+                    is_host,
+                }) if self.detail_event.as_ref() == Some(room_id) => {
+                    self.event_detail_view(ids!(event_detail))
+                        .set_scheduling_poll(cx, poll.clone(), *is_host);
+                }
+                Some(EventDetailLoadAction::Failed { room_id, error })
+                    if self.detail_event.as_ref() == Some(room_id) =>
+                {
+                    self.detail_event = None;
+                    self.modal(ids!(event_detail_modal)).close(cx);
+                    enqueue_popup_notification(PopupItem {
+                        message: error.clone(),
+                        kind: PopupKind::Error,
+                        auto_dismissal_duration: None,
+                    });
+                }
+                _ => {}
+            }
+
+            if let Some(PostDetailAction::ReactionTimingLoaded { event_id, buckets }) =
+                action.downcast_ref()
+                && self.detail_post.as_ref() == Some(event_id)
+            {
+                self.post_detail_view(ids!(post_detail))
+                    .set_reaction_timing(cx, buckets);
+            }
+        }
+
+        for action in actions {
+            match action.downcast_ref() {
+                Some(ProfileTabAction::PostsLoaded { user_id, posts }) => {
+                    if self.user_id.as_ref() == Some(user_id) {
+                        self.social_feed_view(ids!(profile_feed))
+                            .set_posts(cx, posts.clone());
+                        self.redraw(cx);
+                    }
+                }
+                Some(ProfileTabAction::MorePostsLoaded { user_id, posts }) => {
+                    if self.user_id.as_ref() == Some(user_id) {
+                        self.social_feed_view(ids!(profile_feed))
+                            .append_posts(cx, posts.clone());
+                        self.redraw(cx);
+                    }
+                }
+                Some(ProfileTabAction::MediaLoaded { user_id, posts }) => {
+                    if self.user_id.as_ref() == Some(user_id) {
+                        self.social_profile_media_grid(ids!(media_section))
+                            .set_posts(cx, posts.clone());
+                    }
+                }
+                Some(ProfileTabAction::LikesLoaded(posts)) => {
+                    if self.is_own_profile {
+                        self.social_profile_likes_list(ids!(likes_section))
+                            .set_posts(cx, posts.clone());
+                    }
+                }
+                Some(ProfileTabAction::EventsLoaded(events)) => {
+                    if self.is_own_profile {
+                        self.social_events_calendar(ids!(events_section))
+                            .set_events(cx, events.clone());
+                    }
+                }
+                Some(ProfileTabAction::Failed { tab, error }) => {
+                    // Allow trying again by switching back to the tab
+                    self.loaded_tabs.retain(|loaded| loaded != tab);
+                    match tab {
+                        ProfileTab::Media => self
+                            .social_profile_media_grid(ids!(media_section))
+                            .show_error(cx, error),
+                        ProfileTab::Likes => self
+                            .social_profile_likes_list(ids!(likes_section))
+                            .show_error(cx, error),
+                        ProfileTab::Posts => {
+                            let feed = self.social_feed_view(ids!(profile_feed));
+                            // Keep showing the posts if only loading older ones failed
+                            if feed.post_count() > 0 {
+                                feed.set_state(cx, FeedState::Loaded);
+                                enqueue_popup_notification(PopupItem {
+                                    message: format!("Failed to load more posts: {error}"),
+                                    kind: PopupKind::Error,
+                                    auto_dismissal_duration: None,
+                                });
+                            } else {
+                                feed.set_state(cx, FeedState::Error);
+                            }
+                        }
+                        ProfileTab::Events => enqueue_popup_notification(PopupItem {
+                            message: format!("Failed to find your events: {error}"),
+                            kind: PopupKind::Error,
+                            auto_dismissal_duration: None,
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let people_list = self.portal_list(ids!(people_list));
+        for (index, item) in people_list.items_with_actions(actions) {
+            let Some(user_id) = self.mutual_friends.get(index).cloned() else {
+                continue;
+            };
+            if item.as_view().finger_up(actions).is_some() {
+                cx.action(SocialProfileAction::OpenProfile(user_id));
+            }
+        }
+
+        for action in actions {
+            match action.downcast_ref() {
+                Some(FollowListAction::OpenProfile(user_id)) => {
+                    cx.action(NavigationBarAction::GoToSocialProfile {
+                        user_id: user_id.clone(),
+                    });
+                }
+                Some(FollowListAction::Follow(user_id)) => {
+                    submit_async_request(MatrixRequest::Follow(user_id.clone()));
+                }
+                Some(FollowListAction::Unfollow(user_id)) => {
+                    submit_async_request(MatrixRequest::Unfollow(user_id.clone()));
+                }
+                Some(FollowListAction::RemoveFollower(user_id)) => {
+                    let user_id = user_id.clone();
+                    self.confirm_follower_moderation(
+                        cx,
+                        ConfirmationModalContent {
+                            title_text: "Remove follower".into(),
+                            body_text: format!(
+                                "Remove {user_id} from your public feed? \
+                                They can follow you again later."
+                            )
+                            .into(),
+                            accept_button_text: Some("Remove".into()),
+                            on_accept_clicked: Some(Box::new(move |_cx| {
+                                submit_async_request(MatrixRequest::RemoveFollower(user_id));
+                            })),
+                            ..Default::default()
+                        },
+                    );
+                }
+                Some(FollowListAction::BanFromFeeds(user_id)) => {
+                    let user_id = user_id.clone();
+                    self.confirm_follower_moderation(
+                        cx,
+                        ConfirmationModalContent {
+                            title_text: "Ban from my feeds".into(),
+                            body_text: format!(
+                                "Ban {user_id} from all of your feeds? \
+                                They will be removed and can't follow you again until unbanned."
+                            )
+                            .into(),
+                            accept_button_text: Some("Ban".into()),
+                            on_accept_clicked: Some(Box::new(move |_cx| {
+                                submit_async_request(MatrixRequest::BanFromFeeds(user_id));
+                            })),
+                            ..Default::default()
+                        },
+                    );
+                }
+                Some(FollowListAction::Close) => self.hide_people_list(cx),
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(FriendListAction::Loaded {
+                    friends,
+                    pending_requests,
+                    outgoing_requests,
+                }) if self.people_list == Some(ProfilePeopleList::Friends) => {
+                    let friend_list = self.friend_list_view(ids!(friends_section));
+                    friend_list.set_friends(cx, friends.clone());
+                    friend_list.set_pending_requests(cx, pending_requests.clone());
+                    friend_list.set_outgoing_requests(cx, outgoing_requests.clone());
+                }
+                Some(FriendListAction::Failed(error))
+                    if self.people_list == Some(ProfilePeopleList::Friends) =>
+                {
+                    self.hide_people_list(cx);
+                    enqueue_popup_notification(PopupItem {
+                        message: error.clone(),
+                        kind: PopupKind::Error,
+                        auto_dismissal_duration: None,
+                    });
+                }
+                Some(FriendListAction::ViewProfile(user_id)) => {
+                    cx.action(NavigationBarAction::GoToSocialProfile {
+                        user_id: user_id.clone(),
+                    });
+                }
+                Some(FriendListAction::MessageFriend(user_id)) => {
+                    cx.action(SocialProfileAction::OpenDirectMessage(user_id.clone()));
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(FollowListsAction::FollowersLoaded(users))
+                    if self.people_list == Some(ProfilePeopleList::Followers) =>
+                {
+                    self.followers_list_view(ids!(followers_section))
+                        .set_users(cx, users.clone());
+                }
+                Some(FollowListsAction::FollowingLoaded(users))
+                    if self.people_list == Some(ProfilePeopleList::Following) =>
+                {
+                    self.following_list_view(ids!(following_section))
+                        .set_users(cx, users.clone());
+                }
+                Some(FollowListsAction::Failed(error))
+                    if matches!(
+                        self.people_list,
+                        Some(ProfilePeopleList::Followers | ProfilePeopleList::Following)
+                    ) =>
+                {
+                    self.hide_people_list(cx);
+                    enqueue_popup_notification(PopupItem {
+                        message: error.clone(),
+                        kind: PopupKind::Error,
+                        auto_dismissal_duration: None,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        for action in actions {
+            match action.downcast_ref() {
+                // The saved profile is shown once its change is published
+                Some(SocialProfileEditorAction::Saved { .. })
+                | Some(SocialProfileEditorAction::Cancelled) => {
+                    self.modal(ids!(profile_editor_modal)).close(cx);
+                }
+                _ => {}
+            }
+            if let Some(SocialProfileShareSheetAction::Close) = action.downcast_ref() {
+                self.modal(ids!(profile_share_modal)).close(cx);
+            }
+            match action.downcast_ref() {
+                Some(SocialProfileAction::Loaded(profile))
+                    if self.user_id.as_ref() == Some(&profile.user_id) =>
+                {
+                    self.set_profile(cx, profile.clone());
+                }
+                Some(SocialProfileAction::FollowChanged { user_id, following }) => {
+                    if self.user_id.as_ref() == Some(user_id) {
+                        self.set_following(cx, *following);
+                    }
+                    self.followers_list_view(ids!(followers_section))
+                        .set_followed(cx, user_id, *following);
+                    self.following_list_view(ids!(following_section))
+                        .set_followed(cx, user_id, *following);
+                }
+                Some(SocialProfileAction::FollowerRemoved(user_id)) => {
+                    self.followers_list_view(ids!(followers_section))
+                        .remove_user(cx, user_id);
+                }
+                _ => {}
+            }
+            if let Some(MutualFriendsAction::Loaded {
+                user_id,
+                mutual_friends,
+            }) = action.downcast_ref()
+            {
+                if self.user_id.as_ref() == Some(user_id) {
+                    self.set_mutual_friends(cx, mutual_friends.clone().unwrap_or_default());
+                }
+            }
+        }
+
+        if self.button(ids!(verify_button)).clicked(actions)
+            || self.button(ids!(verified_badge)).clicked(actions)
+        {
+            if let Some(user_id) = &self.user_id {
+                submit_async_request(MatrixRequest::VerifyUser(user_id.clone()));
+            }
+        }
+
+        for action in actions {
+            if let Some(UserVerificationAction::Checked { user_id, verified }) =
+                action.downcast_ref()
+            {
+                if self.user_id.as_ref() == Some(user_id) {
+                    self.show_verification(cx, Some(*verified));
+                }
+            }
+            // The owner may just have been verified from this page
+            if let Some(VerificationAction::RequestCompleted) = action.downcast_ref() {
+                if let Some(user_id) = self.user_id.as_ref().filter(|_| !self.is_own_profile) {
+                    refresh_user_verification(user_id);
+                }
+            }
+        }
+
+        if self
+            .confirmation_modal(ids!(follower_moderation_modal_inner))
+            .closed(actions)
+            .is_some()
+        {
+            self.modal(ids!(follower_moderation_modal)).close(cx);
         }
     }
 }
@@ -426,6 +1736,69 @@ impl SocialProfilePage {
         self.is_own_profile = is_own_profile;
     }
 
+    /// Show the profile of the given user, clearing the previous one.
+    ///
+    /// The user's display name and social profile are loaded in the background
+    /// and shown once they arrive.
+    pub fn show_user(&mut self, cx: &mut Cx, user_id: OwnedUserId) {
+        if self.user_id.as_ref() == Some(&user_id) && self.profile.is_some() {
+            return;
+        }
+        self.clear(cx);
+        let is_own_profile = current_user_id().is_some_and(|own| own == user_id);
+        self.label(ids!(name_label))
+            .set_text(cx, user_id.localpart());
+        self.label(ids!(username_label))
+            .set_text(cx, user_id.as_str());
+        self.set_user_id(user_id.clone(), is_own_profile);
+        self.social_feed_view(ids!(profile_feed))
+            .set_grouping(cx, feed_grouping());
+        if !is_own_profile {
+            self.show_verification(cx, user_verification(&user_id));
+            submit_async_request(MatrixRequest::LoadMutualFriends {
+                user_id: user_id.clone(),
+            });
+        }
+        self.show_tab(cx, ProfileTab::Posts);
+        submit_async_request(MatrixRequest::LoadSocialProfile { user_id });
+        self.redraw(cx);
+    }
+
+    /// Show whether the current user verified the owner's identity,
+    /// or neither the badge nor the verify button if that isn't known yet.
+    fn show_verification(&mut self, cx: &mut Cx, verified: Option<bool>) {
+        self.button(ids!(verified_badge))
+            .set_visible(cx, verified == Some(true));
+        self.button(ids!(verify_button))
+            .set_visible(cx, verified == Some(false));
+        self.redraw(cx);
+    }
+
+    /// Show the published changes to the displayed profile.
+    fn apply_profile_changes(&mut self, cx: &mut Cx) {
+        for event in self.profile_changes.drain() {
+            let SocialEvent::ProfileChanged {
+                user_id,
+                display_name,
+                profile,
+            } = event
+            else {
+                continue;
+            };
+            let Some(mut loaded) = self.profile.clone() else {
+                continue;
+            };
+            if loaded.user_id != user_id {
+                continue;
+            }
+            if let Some(display_name) = display_name {
+                loaded.display_name = (!display_name.is_empty()).then_some(display_name);
+            }
+            loaded.social_profile = Some(profile);
+            self.set_profile(cx, loaded);
+        }
+    }
+
     /// Set the loaded profile data and update the UI.
     pub fn set_profile(&mut self, cx: &mut Cx, profile: LoadedProfile) {
         // Update name label
@@ -435,15 +1808,21 @@ impl SocialProfilePage {
             .unwrap_or_else(|| profile.user_id.localpart().to_string());
         self.label(ids!(name_label)).set_text(cx, &name);
 
-        // Update username label
-        self.label(ids!(username_label))
-            .set_text(cx, profile.user_id.as_ref());
+        // Update username label, followed by the pronouns if available
+        let pronouns = profile
+            .social_profile
+            .as_ref()
+            .and_then(|social| social.pronouns.as_deref());
+        let username = match pronouns {
+            Some(pronouns) => format!("{} · {}", profile.user_id, pronouns),
+            None => profile.user_id.to_string(),
+        };
+        self.label(ids!(username_label)).set_text(cx, &username);
 
         // Update bio if available
         if let Some(ref social) = profile.social_profile {
-            if let Some(ref bio) = social.bio {
-                self.label(ids!(bio_label)).set_text(cx, bio);
-            }
+            self.label(ids!(bio_label))
+                .set_text(cx, social.bio.as_deref().unwrap_or_default());
 
             // Update location if available
             if let Some(ref location) = social.location {
@@ -463,29 +1842,398 @@ impl SocialProfilePage {
             }
         }
 
-        // Update avatar with first letter of name
+        // Update avatar with the profile image, or the first letter of name
         self.avatar(ids!(avatar)).set_text(cx, &name);
+        if let Some(avatar_data) = profile.avatar_data.clone() {
+            self.show_profile_image(cx, ProfileImageKind::Avatar, &avatar_data);
+        }
+        if let Some(cover_data) = profile.cover_data.clone() {
+            self.show_profile_image(cx, ProfileImageKind::CoverPhoto, &cover_data);
+        }
 
         // Show/hide action buttons based on whether this is own profile
         self.view(ids!(action_row))
             .set_visible(cx, !self.is_own_profile);
         self.button(ids!(edit_button))
             .set_visible(cx, self.is_own_profile);
+        if !self.is_own_profile {
+            self.set_editing(cx, false);
+        }
+
+        // Count privacy settings are only editable on one's own profile
+        let counts_privacy = profile
+            .social_profile
+            .as_ref()
+            .and_then(|social| social.counts_privacy)
+            .unwrap_or_default();
+        self.check_box(ids!(hide_followers_toggle))
+            .set_active(cx, counts_privacy.hide_followers);
+        self.check_box(ids!(hide_following_toggle))
+            .set_active(cx, counts_privacy.hide_following);
+        self.check_box(ids!(hide_friends_toggle))
+            .set_active(cx, counts_privacy.hide_friends);
+        self.view(ids!(counts_privacy_settings))
+            .set_visible(cx, self.is_own_profile);
+
+        // Only the owner can see what they liked and their events
+        self.button(ids!(likes_tab))
+            .set_visible(cx, self.is_own_profile);
+        self.button(ids!(events_tab))
+            .set_visible(cx, self.is_own_profile);
+
+        // Introduce the owner above their posts. The page doesn't know whether
+        // they're a friend, so the friend button is shown like the page's own.
+        let profile_feed = self.social_feed_view(ids!(profile_feed));
+        profile_feed.show_owner(cx, profile.clone(), self.is_own_profile, false);
 
         self.profile = Some(profile);
     }
 
+    /// Set the follower, following, and friend counts.
+    ///
+    /// This should be called with the result of
+    /// [`ProfileStatsService::get_counts()`](crate::social::ProfileStatsService::get_counts).
+    /// Hidden or unknown counts are not shown.
+    pub fn set_counts(&mut self, cx: &mut Cx, counts: ProfileCounts) {
+        let set_count = |cx: &mut Cx, button: ButtonRef, count: Option<u32>, noun: &str| {
+            if let Some(count) = count {
+                button.set_text(cx, &format!("{} {}", count, noun));
+            }
+            button.set_visible(cx, count.is_some());
+        };
+        set_count(cx, self.button(ids!(followers_button)), counts.followers, "Followers");
+        set_count(cx, self.button(ids!(following_button)), counts.following, "Following");
+        set_count(cx, self.button(ids!(friends_button)), counts.friends, "Friends");
+    }
+
+    /// Set whether the current user follows the profile owner,
+    /// e.g., from [`FollowService::is_following()`](crate::social::FollowService::is_following).
+    pub fn set_following(&mut self, cx: &mut Cx, following: bool) {
+        self.is_following = following;
+        self.button(ids!(follow_button))
+            .set_text(cx, if following { "Unfollow" } else { "Follow" });
+        self.social_feed_view(ids!(profile_feed))
+            .set_owner_following(cx, following);
+        self.redraw(cx);
+    }
+
+    /// Show a list of people in place of the current tab.
+    ///
+    /// Followers and followed users are only listed on one's own profile,
+    /// and are loaded again each time they're shown; see
+    /// [`FollowService::list_followers()`](crate::social::FollowService::list_followers)
+    /// and [`FollowService::list_following()`](crate::social::FollowService::list_following).
+    /// So are one's friends and friend requests, see [`MatrixRequest::LoadFriendList`].
+    pub fn show_people_list(&mut self, cx: &mut Cx, kind: ProfilePeopleList) {
+        match kind {
+            ProfilePeopleList::Followers if self.is_own_profile => {
+                submit_async_request(MatrixRequest::LoadFollowers);
+            }
+            ProfilePeopleList::Following if self.is_own_profile => {
+                submit_async_request(MatrixRequest::LoadFollowing);
+            }
+            ProfilePeopleList::Friends if self.is_own_profile => {
+                submit_async_request(MatrixRequest::LoadFriendList);
+            }
+            ProfilePeopleList::MutualFriends => {
+                self.label(ids!(people_title)).set_text(cx, kind.title());
+            }
+            _ => return,
+        }
+        if let Some(shown) = self.people_list {
+            self.widget(people_section(shown)).set_visible(cx, false);
+        }
+        self.people_list = Some(kind);
+        self.widget(tab_section(self.tab)).set_visible(cx, false);
+        self.widget(people_section(kind)).set_visible(cx, true);
+        self.redraw(cx);
+    }
+
+    /// Show the given tab below the profile details,
+    /// loading its posts if they weren't loaded for this profile yet.
+    pub fn show_tab(&mut self, cx: &mut Cx, tab: ProfileTab) {
+        if tab == ProfileTab::Likes && !self.is_own_profile {
+            return;
+        }
+        if self.people_list.is_some() {
+            self.hide_people_list(cx);
+        }
+        for (other_tab, tab_ids) in [
+            (ProfileTab::Posts, ids!(posts_tab)),
+            (ProfileTab::Media, ids!(media_tab)),
+            (ProfileTab::Likes, ids!(likes_tab)),
+            (ProfileTab::Events, ids!(events_tab)),
+        ] {
+            let selected = if other_tab == tab { 1.0 } else { 0.0 };
+            self.button(tab_ids)
+                .apply_over(cx, live! { draw_text: { selected: (selected) } });
+            self.widget(tab_section(other_tab))
+                .set_visible(cx, other_tab == tab);
+        }
+        self.tab = tab;
+
+        if let Some(user_id) = self.user_id.clone() {
+            if !self.loaded_tabs.contains(&tab) {
+                match tab {
+                    ProfileTab::Posts => {
+                        self.social_feed_view(ids!(profile_feed))
+                            .set_state(cx, FeedState::Loading);
+                        submit_async_request(MatrixRequest::LoadProfilePosts { user_id });
+                    }
+                    ProfileTab::Media => {
+                        self.social_profile_media_grid(ids!(media_section))
+                            .show_loading(cx);
+                        submit_async_request(MatrixRequest::LoadProfileMedia { user_id });
+                    }
+                    ProfileTab::Likes => {
+                        self.social_profile_likes_list(ids!(likes_section))
+                            .show_loading(cx);
+                        submit_async_request(MatrixRequest::LoadLikedPosts);
+                    }
+                    ProfileTab::Events => {
+                        submit_async_request(MatrixRequest::LoadEventsCalendar);
+                    }
+                }
+                self.loaded_tabs.push(tab);
+            }
+        }
+        self.redraw(cx);
+    }
+
+    /// Set the friends the current user shares with the profile owner,
+    /// e.g., from [`FriendSuggestionEngine::mutual_friends()`](crate::social::discovery::FriendSuggestionEngine::mutual_friends).
+    ///
+    /// Nothing is shown if there are none.
+    pub fn set_mutual_friends(&mut self, cx: &mut Cx, mutual_friends: Vec<OwnedUserId>) {
+        for (index, avatar_ids) in MUTUAL_FRIEND_AVATARS.iter().enumerate() {
+            let avatar = self.avatar(avatar_ids);
+            match mutual_friends.get(index) {
+                Some(friend) => {
+                    avatar.set_text(cx, friend.localpart());
+                    avatar.set_visible(cx, true);
+                }
+                None => avatar.set_visible(cx, false),
+            }
+        }
+        let text = match mutual_friends.len() {
+            1 => "1 mutual friend".to_owned(),
+            count => format!("{count} mutual friends"),
+        };
+        self.label(ids!(mutual_friends_label)).set_text(cx, &text);
+        self.view(ids!(mutual_friends_row))
+            .set_visible(cx, !mutual_friends.is_empty());
+        self.mutual_friends = mutual_friends;
+        self.redraw(cx);
+    }
+
+    /// Enter or leave edit mode, which shows the buttons for changing
+    /// the cover photo, the avatar, and the profile details.
+    fn set_editing(&mut self, cx: &mut Cx, editing: bool) {
+        self.editing = editing && self.is_own_profile;
+        self.button(ids!(edit_button))
+            .set_text(cx, if self.editing { "Done" } else { "Edit Profile" });
+        for button in [
+            self.button(ids!(change_cover_button)),
+            self.button(ids!(change_avatar_button)),
+            self.button(ids!(edit_details_button)),
+        ] {
+            button.set_visible(cx, self.editing);
+        }
+        if !self.editing {
+            self.pending_image = None;
+            self.hide_image_preview(cx);
+        }
+        self.redraw(cx);
+    }
+
+    /// Open the editor for the profile's text details.
+    fn open_profile_editor(&mut self, cx: &mut Cx) {
+        let Some(profile) = self.profile.as_ref() else {
+            return;
+        };
+        let Some(room_id) = profile.room_id.clone() else {
+            return;
+        };
+        self.social_profile_editor(ids!(profile_editor)).set_profile(
+            cx,
+            room_id,
+            profile.display_name.as_deref(),
+            profile.social_profile.clone().unwrap_or_default(),
+        );
+        self.modal(ids!(profile_editor_modal)).open(cx);
+    }
+
+    /// Preview a new cover photo or avatar in place of the current one,
+    /// letting the owner save or discard it.
+    ///
+    /// The image data should already be cropped with
+    /// [`prepare_profile_image()`](crate::social::prepare_profile_image),
+    /// so the preview shows exactly what will be uploaded.
+    pub fn preview_profile_image(&mut self, cx: &mut Cx, kind: ProfileImageKind, data: Arc<[u8]>) {
+        if !self.editing {
+            return;
+        }
+        self.hide_image_preview(cx);
+        let loaded = match kind {
+            ProfileImageKind::CoverPhoto => {
+                let cover_preview = self.image(ids!(cover_preview));
+                let loaded = utils::load_png_or_jpg(&cover_preview, cx, &data).is_ok();
+                cover_preview.set_visible(cx, loaded);
+                loaded
+            }
+            ProfileImageKind::Avatar => {
+                let avatar_preview = self.avatar(ids!(avatar_preview));
+                let loaded = avatar_preview
+                    .show_image(cx, None, |cx, img| utils::load_png_or_jpg(&img, cx, &data))
+                    .is_ok();
+                avatar_preview.set_visible(cx, loaded);
+                self.avatar(ids!(avatar)).set_visible(cx, !loaded);
+                loaded
+            }
+        };
+        if !loaded {
+            return;
+        }
+
+        let prompt = match kind {
+            ProfileImageKind::CoverPhoto => "Save this as your new cover photo?",
+            ProfileImageKind::Avatar => "Save this as your new profile photo?",
+        };
+        self.label(ids!(image_edit_label)).set_text(cx, prompt);
+        self.view(ids!(image_edit_bar)).set_visible(cx, true);
+        self.pending_image = Some((kind, data));
+        self.redraw(cx);
+    }
+
+    /// Show a cover photo or avatar as the profile's current image.
+    fn show_profile_image(&mut self, cx: &mut Cx, kind: ProfileImageKind, data: &[u8]) {
+        match kind {
+            ProfileImageKind::CoverPhoto => {
+                let _ = utils::load_png_or_jpg(&self.image(ids!(cover_image)), cx, data);
+            }
+            ProfileImageKind::Avatar => {
+                let _ = self
+                    .avatar(ids!(avatar))
+                    .show_image(cx, None, |cx, img| utils::load_png_or_jpg(&img, cx, data));
+            }
+        }
+    }
+
+    /// Hide any previewed cover photo or avatar, showing the current ones again.
+    fn hide_image_preview(&mut self, cx: &mut Cx) {
+        self.image(ids!(cover_preview)).set_visible(cx, false);
+        self.avatar(ids!(avatar_preview)).set_visible(cx, false);
+        self.avatar(ids!(avatar)).set_visible(cx, true);
+        self.view(ids!(image_edit_bar)).set_visible(cx, false);
+    }
+
+    /// Show a confirmation dialog before removing or banning a follower.
+    fn confirm_follower_moderation(&mut self, cx: &mut Cx, content: ConfirmationModalContent) {
+        self.confirmation_modal(ids!(follower_moderation_modal_inner))
+            .show(cx, content);
+        self.modal(ids!(follower_moderation_modal)).open(cx);
+    }
+
+    /// Hide the shown list of people and show the current tab again.
+    /// Show one of the owner's posts in the post detail modal,
+    /// loading when people reacted to it.
+    fn show_post_detail(&mut self, cx: &mut Cx, event_id: &OwnedEventId) {
+        let Some(post) = self
+            .social_feed_view(ids!(profile_feed))
+            .find_post(event_id)
+        else {
+            return;
+        };
+        let post_detail = self.post_detail_view(ids!(post_detail));
+        post_detail.clear(cx);
+        post_detail.set_post(cx, &post);
+        self.detail_post = Some(post.event_id.clone());
+        submit_async_request(MatrixRequest::LoadReactionTiming {
+            room_id: post.room_id,
+            event_id: post.event_id,
+            posted_at: post.timestamp,
+        });
+        self.modal(ids!(post_detail_modal)).open(cx);
+    }
+
+    /// Show one of the current user's events in the event detail modal, loading it.
+    fn show_event_detail(&mut self, cx: &mut Cx, room_id: OwnedRoomId) {
+        self.event_detail_view(ids!(event_detail)).clear(cx);
+        self.detail_event = Some(room_id.clone());
+        submit_async_request(MatrixRequest::LoadEventDetail { room_id });
+        self.modal(ids!(event_detail_modal)).open(cx);
+    }
+
+    /// Open the invites sheet for one of the current user's events,
+    /// loading the friends that can be invited to it.
+    fn show_event_invites(&mut self, cx: &mut Cx, room_id: OwnedRoomId) {
+        self.event_invite_sheet(ids!(event_invite_sheet))
+            .set_friends(cx, room_id.clone(), Vec::new(), &HashSet::new());
+        self.invite_event = Some(room_id.clone());
+        submit_async_request(MatrixRequest::LoadEventInviteFriends { room_id });
+        self.modal(ids!(event_invite_modal)).open(cx);
+    }
+
+    pub fn hide_people_list(&mut self, cx: &mut Cx) {
+        if let Some(shown) = self.people_list.take() {
+            self.widget(people_section(shown)).set_visible(cx, false);
+        }
+        self.widget(tab_section(self.tab)).set_visible(cx, true);
+        self.redraw(cx);
+    }
+
     /// Clear the profile data.
     pub fn clear(&mut self, cx: &mut Cx) {
+        self.set_editing(cx, false);
         self.user_id = None;
         self.profile = None;
         self.is_own_profile = false;
+        self.set_following(cx, false);
 
         self.label(ids!(name_label)).set_text(cx, "");
+        self.show_verification(cx, None);
         self.label(ids!(username_label)).set_text(cx, "");
         self.label(ids!(bio_label)).set_text(cx, "");
         self.view(ids!(location_row)).set_visible(cx, false);
         self.view(ids!(website_row)).set_visible(cx, false);
+        self.set_counts(cx, ProfileCounts::default());
+        self.view(ids!(counts_privacy_settings))
+            .set_visible(cx, false);
+        self.set_mutual_friends(cx, Vec::new());
+        self.hide_people_list(cx);
+        let profile_feed = self.social_feed_view(ids!(profile_feed));
+        profile_feed.clear(cx);
+        profile_feed.hide_owner(cx);
+        self.detail_post = None;
+        self.modal(ids!(post_detail_modal)).close(cx);
+        self.invite_event = None;
+        self.modal(ids!(event_invite_modal)).close(cx);
+        self.detail_event = None;
+        self.modal(ids!(event_detail_modal)).close(cx);
+        self.loaded_tabs.clear();
+        self.show_tab(cx, ProfileTab::Posts);
+        self.button(ids!(likes_tab)).set_visible(cx, false);
+        self.button(ids!(events_tab)).set_visible(cx, false);
+    }
+}
+
+/// Get the section of the profile page showing the given tab.
+fn tab_section(tab: ProfileTab) -> &'static [LiveId] {
+    match tab {
+        ProfileTab::Posts => ids!(posts_section),
+        ProfileTab::Media => ids!(media_section),
+        ProfileTab::Likes => ids!(likes_section),
+        ProfileTab::Events => ids!(events_section),
+    }
+}
+
+/// Get the section of the profile page showing the given list of people.
+fn people_section(kind: ProfilePeopleList) -> &'static [LiveId] {
+    match kind {
+        ProfilePeopleList::Followers => ids!(followers_section),
+        ProfilePeopleList::Following => ids!(following_section),
+        ProfilePeopleList::Friends => ids!(friends_section),
+        ProfilePeopleList::MutualFriends => ids!(people_section),
     }
 }
 
@@ -497,6 +2245,13 @@ impl SocialProfilePageRef {
         }
     }
 
+    /// See [`SocialProfilePage::show_user()`].
+    pub fn show_user(&self, cx: &mut Cx, user_id: OwnedUserId) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_user(cx, user_id);
+        }
+    }
+
     /// See [`SocialProfilePage::set_profile()`].
     pub fn set_profile(&self, cx: &mut Cx, profile: LoadedProfile) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -504,6 +2259,41 @@ impl SocialProfilePageRef {
         }
     }
 
+    /// See [`SocialProfilePage::set_counts()`].
+    pub fn set_counts(&self, cx: &mut Cx, counts: ProfileCounts) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_counts(cx, counts);
+        }
+    }
+
+    /// See [`SocialProfilePage::set_following()`].
+    pub fn set_following(&self, cx: &mut Cx, following: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_following(cx, following);
+        }
+    }
+
+    /// See [`SocialProfilePage::show_people_list()`].
+    pub fn show_people_list(&self, cx: &mut Cx, kind: ProfilePeopleList) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_people_list(cx, kind);
+        }
+    }
+
+    /// See [`SocialProfilePage::preview_profile_image()`].
+    pub fn preview_profile_image(&self, cx: &mut Cx, kind: ProfileImageKind, data: Arc<[u8]>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.preview_profile_image(cx, kind, data);
+        }
+    }
+
+    /// See [`SocialProfilePage::hide_people_list()`].
+    pub fn hide_people_list(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.hide_people_list(cx);
+        }
+    }
+
     /// See [`SocialProfilePage::clear()`].
     pub fn clear(&self, cx: &mut Cx) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -0,0 +1,280 @@
+//! Sheet for sharing a social profile.
+//!
+//! The sheet shows a link to the profile as text and as a QR code, which can be
+//! switched between the `matrix.to` link and the link that opens the profile
+//! in Robrix (see [`crate::social::profile_links`]). The shown link can be
+//! copied to the clipboard.
+
+use makepad_widgets::{image_cache::ImageBuffer, *};
+use matrix_sdk::ruma::OwnedUserId;
+
+use crate::shared::popup_list::{enqueue_popup_notification, PopupItem, PopupKind};
+use crate::social::profile_links::{profile_qr_code, ProfileLinkKind};
+
+/// The buttons switching between the kinds of link, and the kind each one shows.
+const LINK_KIND_BUTTONS: [(&[LiveId], ProfileLinkKind); 2] = [
+    (ids!(matrix_to_button), ProfileLinkKind::MatrixTo),
+    (ids!(robrix_link_button), ProfileLinkKind::Robrix),
+];
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use link::social_theme::*;
+
+    use crate::shared::styles::*;
+
+    /// A button picking the kind of link that is shared.
+    ShareLinkKindButton = <Button> {
+        width: Fit,
+        height: Fit,
+        padding: { top: 6, bottom: 6, left: 12, right: 12 },
+        text: "",
+        draw_bg: {
+            color: #0000
+        }
+        draw_text: {
+            // 1.0 if this kind of link is shown
+            instance selected: 0.0
+
+            fn get_color(self) -> vec4 {
+                return mix((SOCIAL_TEXT_SECONDARY), (SOCIAL_ACCENT), self.selected);
+            }
+        }
+    }
+
+    /// Sheet showing a profile's link and its QR code.
+    pub SocialProfileShareSheet = {{SocialProfileShareSheet}} {
+        width: 360,
+        height: Fit,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_BG)
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            flow: Right,
+            spacing: 8,
+            align: { y: 0.5 },
+
+            title_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Share profile",
+                draw_text: {
+                    text_style: { font_size: 18.0 },
+                    color: (SOCIAL_TEXT_PRIMARY),
+                }
+            }
+
+            close_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Close",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: (SOCIAL_TEXT_SECONDARY),
+                }
+            }
+        }
+
+        link_kind_row = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Right,
+            spacing: 8,
+            align: { x: 0.5 },
+
+            matrix_to_button = <ShareLinkKindButton> {
+                text: "matrix.to link",
+                draw_text: { selected: 1.0 }
+            }
+
+            robrix_link_button = <ShareLinkKindButton> {
+                text: "Robrix link",
+            }
+        }
+
+        qr_section = <View> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            align: { x: 0.5 },
+
+            // Drawn dark on light whatever the theme, so that it can be scanned
+            qr_code = <Image> {
+                width: 240,
+                height: 240,
+                fit: Stretch,
+            }
+
+            qr_error_label = <Label> {
+                width: Fill,
+                height: Fit,
+                visible: false,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: (SOCIAL_DANGER),
+                    wrap: Word,
+                }
+            }
+        }
+
+        link_label = <Label> {
+            width: Fill,
+            height: Fit,
+            padding: { left: 16, right: 16 },
+            text: "",
+            draw_text: {
+                text_style: { font_size: 13.0 },
+                color: (SOCIAL_TEXT_SECONDARY),
+                wrap: Word,
+            }
+        }
+
+        footer = <View> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            align: { x: 1.0 },
+
+            copy_link_button = <Button> {
+                width: Fit,
+                height: Fit,
+                padding: { top: 6, bottom: 6, left: 12, right: 12 },
+                text: "Copy link",
+                draw_bg: {
+                    color: (SOCIAL_ACCENT),
+                    radius: 4.0,
+                }
+                draw_text: {
+                    color: (SOCIAL_ON_ACCENT),
+                }
+            }
+        }
+    }
+}
+
+/// Actions that can be triggered from the profile share sheet.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialProfileShareSheetAction {
+    /// User closed the sheet.
+    Close,
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialProfileShareSheet {
+    #[deref]
+    view: View,
+
+    /// The user whose profile is shared.
+    #[rust]
+    user_id: Option<OwnedUserId>,
+
+    /// The kind of link that is shown.
+    #[rust]
+    link_kind: ProfileLinkKind,
+}
+
+impl Widget for SocialProfileShareSheet {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialProfileShareSheet {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.button(ids!(close_button)).clicked(actions) {
+            cx.action(SocialProfileShareSheetAction::Close);
+        }
+
+        for (button, link_kind) in LINK_KIND_BUTTONS {
+            if self.button(button).clicked(actions) && self.link_kind != link_kind {
+                self.link_kind = link_kind;
+                self.show_link(cx);
+            }
+        }
+
+        if self.button(ids!(copy_link_button)).clicked(actions) {
+            if let Some(user_id) = &self.user_id {
+                cx.copy_to_clipboard(&self.link_kind.link(user_id));
+                enqueue_popup_notification(PopupItem {
+                    message: String::from("Copied the profile link to the clipboard."),
+                    auto_dismissal_duration: Some(3.0),
+                    kind: PopupKind::Success,
+                });
+            }
+        }
+    }
+}
+
+impl SocialProfileShareSheet {
+    /// Show the links to the given user's profile, starting with the `matrix.to` link.
+    pub fn show(&mut self, cx: &mut Cx, user_id: OwnedUserId) {
+        self.user_id = Some(user_id);
+        self.link_kind = ProfileLinkKind::default();
+        self.show_link(cx);
+    }
+
+    /// Show the link of the selected kind and its QR code.
+    fn show_link(&mut self, cx: &mut Cx) {
+        let Some(user_id) = &self.user_id else {
+            return;
+        };
+        let link = self.link_kind.link(user_id);
+        self.label(ids!(link_label)).set_text(cx, &link);
+        for (button, link_kind) in LINK_KIND_BUTTONS {
+            let selected = if link_kind == self.link_kind {
+                1.0
+            } else {
+                0.0
+            };
+            self.button(button)
+                .apply_over(cx, live! { draw_text: { selected: (selected) } });
+        }
+
+        let qr_code = self.image(ids!(qr_code));
+        let shown = profile_qr_code(&link)
+            .map_err(|e| e.to_string())
+            .and_then(|image| {
+                ImageBuffer::new(&image.rgba, image.size, image.size)
+                    .map_err(|e| format!("Cannot show the QR code: {e:?}"))
+            })
+            .map(|buffer| {
+                let texture = Some(buffer.into_new_texture(cx));
+                qr_code.set_texture(cx, texture);
+            });
+        if let Err(error) = &shown {
+            error!("Failed to draw the QR code of {link}: {error}");
+            self.label(ids!(qr_error_label)).set_text(cx, error);
+        }
+        qr_code.set_visible(cx, shown.is_ok());
+        self.label(ids!(qr_error_label))
+            .set_visible(cx, shown.is_err());
+        self.redraw(cx);
+    }
+}
+
+impl SocialProfileShareSheetRef {
+    /// See [`SocialProfileShareSheet::show()`].
+    pub fn show(&self, cx: &mut Cx, user_id: OwnedUserId) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show(cx, user_id);
+        }
+    }
+}
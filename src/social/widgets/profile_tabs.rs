@@ -0,0 +1,539 @@
+//! Media and Likes tabs of the profile page.
+//!
+//! [`SocialProfileMediaGrid`] shows a user's image, video and audio posts in
+//! a grid of thumbnails, fetched through the media cache as they're shown.
+//! [`SocialProfileLikesList`] lists the posts the current user reacted to.
+//! Both are filled in from a [`ProfileTabAction`] (see
+//! [`crate::social::profile_tabs`]), and tapping a post emits
+//! [`SocialProfileTabsAction::OpenPost`].
+
+use makepad_widgets::*;
+use matrix_sdk::{
+    media::MediaFormat,
+    ruma::{OwnedEventId, OwnedMxcUri, OwnedRoomId},
+};
+use rangemap::RangeSet;
+
+use crate::media_cache::{MediaCache, MediaCacheEntry};
+use crate::shared::avatar::AvatarWidgetExt;
+use crate::social::newsfeed::FeedItem;
+use crate::social::post::PostContent;
+use crate::utils;
+
+/// The cells of each row of the media grid, in order.
+const MEDIA_CELLS: [&[LiveId]; 3] = [ids!(media_0), ids!(media_1), ids!(media_2)];
+
+/// Maximum number of characters of a post's text shown in the likes list.
+const POST_PREVIEW_CHARS: usize = 200;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+    use link::social_theme::*;
+
+    use crate::shared::styles::*;
+    use crate::shared::avatar::Avatar;
+
+    /// A single media post in the grid, showing its thumbnail.
+    ProfileMediaCell = <View> {
+        width: Fill,
+        height: 120,
+        flow: Overlay,
+        align: { x: 0.5, y: 0.5 },
+        cursor: Hand,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_SURFACE)
+        }
+
+        media_image = <Image> {
+            width: Fill,
+            height: Fill,
+            fit: Biggest,
+        }
+
+        // Marks videos and audio clips, and stands in for missing thumbnails
+        kind_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 20.0 },
+                color: (SOCIAL_TEXT_SECONDARY),
+            }
+        }
+    }
+
+    /// A row of the media grid.
+    ProfileMediaRow = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 2,
+        margin: { bottom: 2 },
+
+        media_0 = <ProfileMediaCell> {}
+        media_1 = <ProfileMediaCell> {}
+        media_2 = <ProfileMediaCell> {}
+    }
+
+    /// A single liked post in the list.
+    ProfileLikedPostItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 10, bottom: 10 },
+        flow: Right,
+        spacing: 12,
+        cursor: Hand,
+        show_bg: true,
+        draw_bg: {
+            color: (SOCIAL_BG)
+        }
+
+        avatar = <Avatar> {
+            width: 40,
+            height: 40,
+        }
+
+        post_info = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 4,
+
+            sender_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: (SOCIAL_TEXT_PRIMARY),
+                }
+            }
+
+            text_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: (SOCIAL_TEXT),
+                    wrap: Word,
+                }
+            }
+        }
+    }
+
+    /// Shown in place of a tab's posts while they're loading or if there are none.
+    ProfileTabEmpty = <View> {
+        width: Fill,
+        height: Fit,
+        padding: 32,
+        align: { x: 0.5 },
+
+        empty_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: (SOCIAL_TEXT_MUTED),
+                wrap: Word,
+            }
+        }
+    }
+
+    /// Grid of a user's media posts.
+    pub SocialProfileMediaGrid = {{SocialProfileMediaGrid}} {
+        width: Fill,
+        height: Fill,
+
+        media_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            media_row = <ProfileMediaRow> {}
+            empty_item = <ProfileTabEmpty> {}
+        }
+    }
+
+    /// List of the posts the current user reacted to.
+    pub SocialProfileLikesList = {{SocialProfileLikesList}} {
+        width: Fill,
+        height: Fill,
+
+        likes_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            post_item = <ProfileLikedPostItem> {}
+            empty_item = <ProfileTabEmpty> {}
+        }
+    }
+}
+
+/// Actions that can be triggered from the Media and Likes tabs.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialProfileTabsAction {
+    /// User tapped a post.
+    OpenPost {
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+    },
+    /// No action.
+    None,
+}
+
+/// The posts of a tab, or why there are none to show.
+#[derive(Clone, Debug, Default)]
+enum TabPosts {
+    /// The posts are being loaded.
+    #[default]
+    Loading,
+    /// The posts were loaded.
+    Loaded(Vec<FeedItem>),
+    /// Loading the posts failed.
+    Failed(String),
+}
+
+impl TabPosts {
+    /// Get the loaded posts, if any.
+    fn posts(&self) -> &[FeedItem] {
+        match self {
+            Self::Loaded(posts) => posts,
+            Self::Loading | Self::Failed(_) => &[],
+        }
+    }
+
+    /// Get the text shown in place of the posts if there are none.
+    fn empty_text<'a>(&'a self, no_posts_text: &'a str) -> &'a str {
+        match self {
+            Self::Loading => "Loading…",
+            Self::Loaded(_) => no_posts_text,
+            Self::Failed(error) => error,
+        }
+    }
+}
+
+/// Get the image shown in a post's grid cell, if it has one.
+fn thumbnail_uri(content: &PostContent) -> Option<&OwnedMxcUri> {
+    match content {
+        PostContent::Image {
+            mxc_uri,
+            thumbnail_uri,
+            ..
+        } => Some(thumbnail_uri.as_ref().unwrap_or(mxc_uri)),
+        PostContent::Video { thumbnail_uri, .. } => thumbnail_uri.as_ref(),
+        _ => None,
+    }
+}
+
+/// Get the symbol marking a post's kind of media in its grid cell.
+fn media_kind_symbol(content: &PostContent) -> &'static str {
+    match content {
+        PostContent::Video { .. } => "▶",
+        PostContent::Audio { .. } => "♪",
+        _ => "",
+    }
+}
+
+/// Get the text shown for a post in the likes list,
+/// describing its media if it has no text.
+fn post_preview(content: &PostContent) -> String {
+    let text = content.text().unwrap_or(match content {
+        PostContent::Image { .. } => "Photo",
+        PostContent::Video { .. } => "Video",
+        PostContent::Audio { .. } => "Voice note",
+        PostContent::Link { url, .. } => url.as_str(),
+        _ => "",
+    });
+    match text.char_indices().nth(POST_PREVIEW_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialProfileMediaGrid {
+    #[deref]
+    view: View,
+
+    /// The media posts shown in the grid.
+    #[rust]
+    posts: TabPosts,
+
+    /// Fetches the thumbnails shown in the grid.
+    #[rust(MediaCache::new(None))]
+    media_cache: MediaCache,
+
+    /// Rows whose thumbnails were all shown since the posts last changed,
+    /// which don't need to be filled in again when they're redrawn.
+    #[rust]
+    rows_drawn: RangeSet<usize>,
+
+    /// Whether some thumbnails are still being fetched.
+    #[rust]
+    thumbnails_pending: bool,
+}
+
+impl Widget for SocialProfileMediaGrid {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        // The media cache signals the UI once a thumbnail was fetched
+        if let Event::Signal = event {
+            if self.thumbnails_pending {
+                self.redraw(cx);
+            }
+        }
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            self.thumbnails_pending = false;
+            let posts = self.posts.posts();
+            let row_count = posts.len().div_ceil(MEDIA_CELLS.len());
+            list.set_item_range(cx, 0, row_count.max(1));
+            while let Some(row) = list.next_visible_item(cx) {
+                let Some(row_posts) = posts.chunks(MEDIA_CELLS.len()).nth(row) else {
+                    let item = list.item(cx, row, live_id!(empty_item));
+                    item.label(ids!(empty_label))
+                        .set_text(cx, self.posts.empty_text("No photos or videos yet."));
+                    item.draw_all(cx, scope);
+                    continue;
+                };
+
+                let (item, existed) = list.item_with_existed(cx, row, live_id!(media_row));
+                if !existed || !self.rows_drawn.contains(&row) {
+                    let mut all_shown = true;
+                    for (index, cell_ids) in MEDIA_CELLS.iter().enumerate() {
+                        let cell = item.view(cell_ids);
+                        let post = row_posts.get(index);
+                        // Cells past the last post are left blank rather than hidden,
+                        // so that the last row's cells keep their width
+                        cell.apply_over(cx, live! { show_bg: (post.is_some()) });
+                        let image = cell.image(ids!(media_image));
+                        image.set_texture(cx, None);
+                        let Some(post) = post else {
+                            cell.label(ids!(kind_label)).set_text(cx, "");
+                            continue;
+                        };
+                        let mut symbol = media_kind_symbol(&post.content);
+                        if let Some(mxc_uri) = thumbnail_uri(&post.content) {
+                            match self
+                                .media_cache
+                                .try_get_media_or_fetch(mxc_uri.clone(), MediaFormat::File)
+                            {
+                                (MediaCacheEntry::Loaded(data), _) => {
+                                    if utils::load_png_or_jpg(&image, cx, &data).is_err() {
+                                        symbol = "🖼";
+                                    }
+                                }
+                                (MediaCacheEntry::Requested, _) => all_shown = false,
+                                (MediaCacheEntry::Failed(status), _) => {
+                                    error!("Failed to fetch media thumbnail {mxc_uri}: {status}");
+                                    symbol = "🖼";
+                                }
+                            }
+                        }
+                        cell.label(ids!(kind_label)).set_text(cx, symbol);
+                    }
+                    if all_shown {
+                        self.rows_drawn.insert(row..row + 1);
+                    } else {
+                        self.thumbnails_pending = true;
+                    }
+                }
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for SocialProfileMediaGrid {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        let media_list = self.portal_list(ids!(media_list));
+        for (row, item) in media_list.items_with_actions(actions) {
+            for (index, cell_ids) in MEDIA_CELLS.iter().enumerate() {
+                let Some(post) = self.posts.posts().get(row * MEDIA_CELLS.len() + index) else {
+                    continue;
+                };
+                if item.view(cell_ids).finger_up(actions).is_some() {
+                    cx.action(SocialProfileTabsAction::OpenPost {
+                        room_id: post.room_id.clone(),
+                        event_id: post.event_id.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl SocialProfileMediaGrid {
+    /// Show that the media posts are being loaded, clearing the current ones.
+    pub fn show_loading(&mut self, cx: &mut Cx) {
+        self.set_tab_posts(cx, TabPosts::Loading);
+    }
+
+    /// Set the media posts to show in the grid.
+    pub fn set_posts(&mut self, cx: &mut Cx, posts: Vec<FeedItem>) {
+        self.set_tab_posts(cx, TabPosts::Loaded(posts));
+    }
+
+    /// Show why the media posts couldn't be loaded.
+    pub fn show_error(&mut self, cx: &mut Cx, error: &str) {
+        self.set_tab_posts(
+            cx,
+            TabPosts::Failed(format!("Failed to load media: {error}")),
+        );
+    }
+
+    fn set_tab_posts(&mut self, cx: &mut Cx, posts: TabPosts) {
+        self.posts = posts;
+        self.rows_drawn.clear();
+        self.redraw(cx);
+    }
+}
+
+impl SocialProfileMediaGridRef {
+    /// See [`SocialProfileMediaGrid::show_loading()`].
+    pub fn show_loading(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_loading(cx);
+        }
+    }
+
+    /// See [`SocialProfileMediaGrid::set_posts()`].
+    pub fn set_posts(&self, cx: &mut Cx, posts: Vec<FeedItem>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_posts(cx, posts);
+        }
+    }
+
+    /// See [`SocialProfileMediaGrid::show_error()`].
+    pub fn show_error(&self, cx: &mut Cx, error: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_error(cx, error);
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialProfileLikesList {
+    #[deref]
+    view: View,
+
+    /// The liked posts shown in the list.
+    #[rust]
+    posts: TabPosts,
+}
+
+impl Widget for SocialProfileLikesList {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        // Borrow the field directly, since the view is drawn at the same time
+        let posts = &self.posts;
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, posts.posts().len().max(1));
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let item = match posts.posts().get(item_id) {
+                    Some(post) => {
+                        let item = list.item(cx, item_id, live_id!(post_item));
+                        let name = post.sender.localpart();
+                        item.avatar(ids!(avatar)).set_text(cx, name);
+                        item.label(ids!(sender_label)).set_text(cx, name);
+                        item.label(ids!(text_label))
+                            .set_text(cx, &post_preview(&post.content));
+                        item
+                    }
+                    None => {
+                        let item = list.item(cx, item_id, live_id!(empty_item));
+                        item.label(ids!(empty_label))
+                            .set_text(cx, posts.empty_text("Posts you react to will appear here."));
+                        item
+                    }
+                };
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for SocialProfileLikesList {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        let likes_list = self.portal_list(ids!(likes_list));
+        for (index, item) in likes_list.items_with_actions(actions) {
+            let Some(post) = self.posts.posts().get(index) else {
+                continue;
+            };
+            if item.as_view().finger_up(actions).is_some() {
+                cx.action(SocialProfileTabsAction::OpenPost {
+                    room_id: post.room_id.clone(),
+                    event_id: post.event_id.clone(),
+                });
+            }
+        }
+    }
+}
+
+impl SocialProfileLikesList {
+    /// Show that the liked posts are being loaded, clearing the current ones.
+    pub fn show_loading(&mut self, cx: &mut Cx) {
+        self.posts = TabPosts::Loading;
+        self.redraw(cx);
+    }
+
+    /// Set the liked posts to show in the list.
+    pub fn set_posts(&mut self, cx: &mut Cx, posts: Vec<FeedItem>) {
+        self.posts = TabPosts::Loaded(posts);
+        self.redraw(cx);
+    }
+
+    /// Show why the liked posts couldn't be loaded.
+    pub fn show_error(&mut self, cx: &mut Cx, error: &str) {
+        self.posts = TabPosts::Failed(format!("Failed to load your likes: {error}"));
+        self.redraw(cx);
+    }
+}
+
+impl SocialProfileLikesListRef {
+    /// See [`SocialProfileLikesList::show_loading()`].
+    pub fn show_loading(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_loading(cx);
+        }
+    }
+
+    /// See [`SocialProfileLikesList::set_posts()`].
+    pub fn set_posts(&self, cx: &mut Cx, posts: Vec<FeedItem>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_posts(cx, posts);
+        }
+    }
+
+    /// See [`SocialProfileLikesList::show_error()`].
+    pub fn show_error(&self, cx: &mut Cx, error: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_error(cx, error);
+        }
+    }
+}
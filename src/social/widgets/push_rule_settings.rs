@@ -0,0 +1,173 @@
+//! Settings for push notifications about social interactions.
+//!
+//! Each category of social interactions has a toggle that installs or removes
+//! its push rules in the user's account (see [`push_rules`]), so the setting
+//! applies to every device the user receives push notifications on.
+//!
+//! [`push_rules`]: crate::social::push_rules
+
+use makepad_widgets::*;
+use std::collections::BTreeSet;
+
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::social::push_rules::{SocialPushCategory, SocialPushRulesAction};
+
+/// The toggles of the categories, in the order they're shown.
+const CATEGORY_TOGGLES: [(&[LiveId], SocialPushCategory); 3] = [
+    (ids!(rsvps_toggle), SocialPushCategory::Rsvps),
+    (ids!(friend_knocks_toggle), SocialPushCategory::FriendKnocks),
+    (ids!(reactions_toggle), SocialPushCategory::Reactions),
+];
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+
+    /// A hint shown below a category's toggle.
+    PushCategoryHint = <Label> {
+        width: Fill,
+        height: Fit,
+        margin: { bottom: 8 },
+        text: "",
+        draw_text: {
+            text_style: { font_size: 12.0 },
+            color: #666,
+            wrap: Word,
+        }
+    }
+
+    /// Toggles for push notifications about social interactions.
+    pub SocialPushRuleSettings = {{SocialPushRuleSettings}} {
+        width: Fill,
+        height: Fit,
+        padding: 16,
+        flow: Down,
+        spacing: 4,
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        title_label = <Label> {
+            width: Fill,
+            height: Fit,
+            margin: { bottom: 8 },
+            text: "Social notifications",
+            draw_text: {
+                text_style: { font_size: 18.0 },
+                color: #000,
+            }
+        }
+
+        rsvps_toggle = <CheckBoxFlat> {
+            text: "Event RSVPs",
+            active: false,
+        }
+        <PushCategoryHint> {
+            text: "When someone RSVPs to an event.",
+        }
+
+        friend_knocks_toggle = <CheckBoxFlat> {
+            text: "Friend requests",
+            active: false,
+        }
+        <PushCategoryHint> {
+            text: "When someone asks to join your friends feed.",
+        }
+
+        reactions_toggle = <CheckBoxFlat> {
+            text: "Reactions",
+            active: false,
+        }
+        <PushCategoryHint> {
+            text: "When someone reacts to your posts or to comments on them.",
+        }
+
+        status_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 12.0 },
+                color: #e0245e,
+                wrap: Word,
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialPushRuleSettings {
+    #[deref]
+    view: View,
+}
+
+impl Widget for SocialPushRuleSettings {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialPushRuleSettings {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        for (toggle_id, category) in CATEGORY_TOGGLES {
+            if let Some(enabled) = self.check_box(toggle_id).changed(actions) {
+                self.set_status(cx, "");
+                submit_async_request(MatrixRequest::SetSocialPushCategory { category, enabled });
+            }
+        }
+
+        for action in actions {
+            match action.downcast_ref() {
+                Some(SocialPushRulesAction::Loaded(enabled)) => {
+                    self.set_enabled_categories(cx, enabled);
+                }
+                Some(SocialPushRulesAction::Failed(error)) => {
+                    self.set_status(cx, error);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl SocialPushRuleSettings {
+    /// Load which categories are turned on from the user's push rules.
+    pub fn refresh(&mut self, cx: &mut Cx) {
+        self.set_status(cx, "");
+        submit_async_request(MatrixRequest::LoadSocialPushRules);
+    }
+
+    /// Show which categories are turned on.
+    fn set_enabled_categories(&mut self, cx: &mut Cx, enabled: &BTreeSet<SocialPushCategory>) {
+        for (toggle_id, category) in CATEGORY_TOGGLES {
+            self.check_box(toggle_id)
+                .set_active(cx, enabled.contains(&category));
+        }
+        self.redraw(cx);
+    }
+
+    /// Show an error, or hide it if `text` is empty.
+    fn set_status(&mut self, cx: &mut Cx, text: &str) {
+        let status_label = self.label(ids!(status_label));
+        status_label.set_visible(cx, !text.is_empty());
+        status_label.set_text(cx, text);
+    }
+}
+
+impl SocialPushRuleSettingsRef {
+    /// See [`SocialPushRuleSettings::refresh()`].
+    pub fn refresh(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.refresh(cx);
+        }
+    }
+}
@@ -0,0 +1,441 @@
+//! Settings panel for post retention in the user's own feeds.
+//!
+//! The owner picks a feed and a maximum post age, previews which posts
+//! would be deleted, keeps individual posts by pinning them, and can run
+//! the deletion immediately instead of waiting for the background job.
+//! It's shown in the social settings, which carry out its actions
+//! (see [`SocialSettingsScreen`](super::social_settings::SocialSettingsScreen)).
+
+use chrono::DateTime;
+use makepad_widgets::*;
+use matrix_sdk::ruma::OwnedEventId;
+use robrix_social_events::retention::SocialRetentionEventContent;
+use std::collections::BTreeSet;
+
+use crate::social::feed_room::FeedPrivacy;
+use crate::social::retention::{OwnPost, RetentionReport};
+
+/// Maximum ages offered in the age dropdown, in the order of its labels.
+const MAX_AGE_OPTIONS: [Option<u32>; 5] = [None, Some(3), Some(6), Some(12), Some(24)];
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+
+    RETENTION_DANGER_COLOR = #e0245e
+
+    /// A post that would be deleted by the retention policy.
+    ExpiredPostItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 10, bottom: 10 },
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        info_column = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 2,
+
+            date_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                }
+            }
+
+            preview_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: #000,
+                    wrap: Word,
+                }
+            }
+        }
+
+        keep_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "Keep",
+        }
+    }
+
+    /// Retention settings for the user's own feeds.
+    pub SocialRetentionSettings = {{SocialRetentionSettings}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: #f5f5f5
+        }
+
+        settings = <View> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            flow: Down,
+            spacing: 12,
+            show_bg: true,
+            draw_bg: {
+                color: #fff
+            }
+
+            title_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Post retention",
+                draw_text: {
+                    text_style: { font_size: 18.0 },
+                    color: #000,
+                }
+            }
+
+            description_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Automatically delete your own posts once they reach a certain age. Kept posts are never deleted.",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                    wrap: Word,
+                }
+            }
+
+            feed_row = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+                align: { y: 0.5 },
+
+                <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "Feed",
+                    draw_text: {
+                        text_style: { font_size: 14.0 },
+                        color: #000,
+                    }
+                }
+
+                feed_dropdown = <DropDown> {
+                    width: Fit,
+                    height: Fit,
+                    labels: ["Public", "Friends", "Close Friends"],
+                }
+            }
+
+            max_age_row = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+                align: { y: 0.5 },
+
+                <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "Delete posts older than",
+                    draw_text: {
+                        text_style: { font_size: 14.0 },
+                        color: #000,
+                    }
+                }
+
+                max_age_dropdown = <DropDown> {
+                    width: Fit,
+                    height: Fit,
+                    labels: ["Never", "3 months", "6 months", "1 year", "2 years"],
+                }
+            }
+
+            actions_row = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+
+                preview_button = <Button> {
+                    width: Fill,
+                    height: Fit,
+                    padding: 10,
+                    text: "Preview",
+                }
+
+                apply_button = <Button> {
+                    width: Fill,
+                    height: Fit,
+                    padding: 10,
+                    text: "Delete now",
+                    draw_bg: {
+                        color: (RETENTION_DANGER_COLOR),
+                        radius: 4.0,
+                    }
+                    draw_text: {
+                        color: #fff,
+                    }
+                }
+            }
+
+            summary_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                    wrap: Word,
+                }
+            }
+        }
+
+        expired_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            expired_item = <ExpiredPostItem> {}
+        }
+    }
+}
+
+/// Actions that can be triggered from the retention settings.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialRetentionSettingsAction {
+    /// User switched to another feed; its policy should be loaded.
+    FeedSelected(FeedPrivacy),
+    /// User changed the maximum post age of a feed.
+    SetMaxAge {
+        feed: FeedPrivacy,
+        max_age_months: Option<u32>,
+    },
+    /// User wants to see which posts would be deleted.
+    Preview(FeedPrivacy),
+    /// User wants to keep a post regardless of its age.
+    PinPost {
+        feed: FeedPrivacy,
+        event_id: OwnedEventId,
+    },
+    /// User wants to delete expired posts now.
+    ApplyNow(FeedPrivacy),
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialRetentionSettings {
+    #[deref]
+    view: View,
+
+    /// The feed whose policy is being edited.
+    #[rust(FeedPrivacy::Public)]
+    feed: FeedPrivacy,
+
+    /// Posts that would be deleted, from the latest preview.
+    #[rust]
+    expired: Vec<OwnPost>,
+
+    /// Posts pinned since the latest preview.
+    #[rust]
+    pinned: BTreeSet<OwnedEventId>,
+}
+
+impl Widget for SocialRetentionSettings {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, self.expired.len());
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let Some(post) = self.expired.get(item_id) else {
+                    continue;
+                };
+                let item = list.item(cx, item_id, live_id!(expired_item));
+                self.populate_post_item(cx, &item, post);
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for SocialRetentionSettings {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if let Some(selected) = self.drop_down(ids!(feed_dropdown)).selected(actions) {
+            self.feed = match selected {
+                0 => FeedPrivacy::Public,
+                1 => FeedPrivacy::Friends,
+                2 => FeedPrivacy::CloseFriends,
+                _ => FeedPrivacy::Public,
+            };
+            self.clear_preview(cx);
+            cx.action(SocialRetentionSettingsAction::FeedSelected(self.feed));
+        }
+
+        if let Some(selected) = self.drop_down(ids!(max_age_dropdown)).selected(actions) {
+            let max_age_months = MAX_AGE_OPTIONS.get(selected).copied().flatten();
+            self.clear_preview(cx);
+            cx.action(SocialRetentionSettingsAction::SetMaxAge {
+                feed: self.feed,
+                max_age_months,
+            });
+        }
+
+        if self.button(ids!(preview_button)).clicked(actions) {
+            cx.action(SocialRetentionSettingsAction::Preview(self.feed));
+        }
+
+        if self.button(ids!(apply_button)).clicked(actions) {
+            cx.action(SocialRetentionSettingsAction::ApplyNow(self.feed));
+        }
+
+        let expired_list = self.portal_list(ids!(expired_list));
+        for (index, item) in expired_list.items_with_actions(actions) {
+            if !item.button(ids!(keep_button)).clicked(actions) {
+                continue;
+            }
+            let Some(post) = self.expired.get(index) else {
+                continue;
+            };
+            if self.pinned.insert(post.event_id.clone()) {
+                cx.action(SocialRetentionSettingsAction::PinPost {
+                    feed: self.feed,
+                    event_id: post.event_id.clone(),
+                });
+                self.redraw(cx);
+            }
+        }
+    }
+}
+
+impl SocialRetentionSettings {
+    /// Show the stored policy of a feed.
+    pub fn set_policy(
+        &mut self,
+        cx: &mut Cx,
+        feed: FeedPrivacy,
+        policy: &SocialRetentionEventContent,
+    ) {
+        let feed_index = match feed {
+            FeedPrivacy::Public => 0,
+            FeedPrivacy::Friends => 1,
+            FeedPrivacy::CloseFriends => 2,
+        };
+        self.feed = feed;
+        self.drop_down(ids!(feed_dropdown))
+            .set_selected_item(cx, feed_index);
+
+        // Ages that aren't offered in the dropdown fall back to "Never"
+        let age_index = MAX_AGE_OPTIONS
+            .iter()
+            .position(|age| *age == policy.max_age_months)
+            .unwrap_or(0);
+        self.drop_down(ids!(max_age_dropdown))
+            .set_selected_item(cx, age_index);
+
+        self.clear_preview(cx);
+    }
+
+    /// Show the result of a retention run for the current feed.
+    ///
+    /// A dry run lists the posts that would be deleted; a real run
+    /// summarizes how many posts were deleted.
+    pub fn set_report(&mut self, cx: &mut Cx, report: RetentionReport) {
+        if report.feed != self.feed {
+            return;
+        }
+
+        let summary = if report.dry_run {
+            match report.expired.len() {
+                0 => "No posts would be deleted.".to_string(),
+                1 => "1 post would be deleted. Tap Keep to exclude it.".to_string(),
+                n => format!("{} posts would be deleted. Tap Keep to exclude a post.", n),
+            }
+        } else if report.failed.is_empty() {
+            format!("Deleted {} posts.", report.redacted.len())
+        } else {
+            format!(
+                "Deleted {} posts, {} could not be deleted.",
+                report.redacted.len(),
+                report.failed.len()
+            )
+        };
+        self.label(ids!(summary_label)).set_text(cx, &summary);
+
+        self.pinned.clear();
+        self.expired = if report.dry_run {
+            report.expired
+        } else {
+            Vec::new()
+        };
+        self.redraw(cx);
+    }
+
+    /// Clear the preview after the feed or policy changed.
+    fn clear_preview(&mut self, cx: &mut Cx) {
+        self.expired.clear();
+        self.pinned.clear();
+        self.label(ids!(summary_label)).set_text(cx, "");
+        self.redraw(cx);
+    }
+
+    /// Fill in an expired post row.
+    fn populate_post_item(&self, cx: &mut Cx, item: &WidgetRef, post: &OwnPost) {
+        let date = DateTime::from_timestamp_millis(u64::from(post.origin_server_ts.get()) as i64)
+            .map(|dt| dt.format("%b %-d, %Y").to_string())
+            .unwrap_or_default();
+        item.label(ids!(date_label)).set_text(cx, &date);
+        item.label(ids!(preview_label)).set_text(cx, &post.preview);
+
+        let pinned = self.pinned.contains(&post.event_id);
+        let keep_button = item.button(ids!(keep_button));
+        keep_button.set_text(cx, if pinned { "Kept" } else { "Keep" });
+        keep_button.set_enabled(cx, !pinned);
+    }
+}
+
+impl SocialRetentionSettingsRef {
+    /// Get the feed whose policy is shown.
+    pub fn feed(&self) -> FeedPrivacy {
+        self.borrow()
+            .map_or(FeedPrivacy::Public, |inner| inner.feed)
+    }
+
+    /// See [`SocialRetentionSettings::set_policy()`].
+    pub fn set_policy(&self, cx: &mut Cx, feed: FeedPrivacy, policy: &SocialRetentionEventContent) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_policy(cx, feed, policy);
+        }
+    }
+
+    /// See [`SocialRetentionSettings::set_report()`].
+    pub fn set_report(&self, cx: &mut Cx, report: RetentionReport) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_report(cx, report);
+        }
+    }
+}
@@ -0,0 +1,417 @@
+//! Room picker for sending a post to a chat.
+//!
+//! The sheet lists the regular rooms the user has joined, each with a button
+//! that forwards the post into it (see [`ShareToChatService`]). Forwards are
+//! checked by the sharing guard first; if one needs the user's confirmation,
+//! its warning is shown above the list with a button to send the post anyway.
+//! After a forward, the room's row shows whether it succeeded.
+//!
+//! [`ShareToChatService`]: crate::social::share_to_chat::ShareToChatService
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::{OwnedEventId, OwnedRoomId};
+use std::collections::HashMap;
+
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::social::share_to_chat::{ChatTarget, ShareToChatAction};
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+
+    SHARE_ACCENT_COLOR = #1d9bf0
+
+    /// A room the post can be sent to.
+    ShareChatItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 10, bottom: 10 },
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        info_column = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 2,
+
+            name_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: #000,
+                }
+            }
+
+            status_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                    wrap: Word,
+                }
+            }
+        }
+
+        send_button = <Button> {
+            width: Fit,
+            height: Fit,
+            padding: { top: 6, bottom: 6, left: 12, right: 12 },
+            text: "Send",
+            draw_bg: {
+                color: (SHARE_ACCENT_COLOR),
+                radius: 4.0,
+            }
+            draw_text: {
+                color: #fff,
+            }
+        }
+    }
+
+    /// Sheet for picking the chat to send a post to.
+    pub SocialShareToChatSheet = {{SocialShareToChatSheet}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: #f5f5f5
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            flow: Right,
+            spacing: 8,
+            align: { y: 0.5 },
+            show_bg: true,
+            draw_bg: {
+                color: #fff
+            }
+
+            title_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Send to chat",
+                draw_text: {
+                    text_style: { font_size: 18.0 },
+                    color: #000,
+                }
+            }
+
+            close_button = <Button> {
+                width: Fit,
+                height: Fit,
+                text: "Close",
+                draw_bg: {
+                    color: #0000,
+                }
+                draw_text: {
+                    color: #666,
+                }
+            }
+        }
+
+        // Shown when sending to a room needs the user's confirmation
+        confirmation_bar = <View> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            padding: 16,
+            flow: Down,
+            spacing: 8,
+            show_bg: true,
+            draw_bg: {
+                color: #fff4d6
+            }
+
+            warning_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: #6b5200,
+                    wrap: Word,
+                }
+            }
+
+            confirmation_buttons = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                spacing: 8,
+                align: { x: 1.0 },
+
+                cancel_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Cancel",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: #6b5200,
+                    }
+                }
+
+                send_anyway_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    padding: { top: 6, bottom: 6, left: 12, right: 12 },
+                    text: "Send anyway",
+                    draw_bg: {
+                        color: (SHARE_ACCENT_COLOR),
+                        radius: 4.0,
+                    }
+                    draw_text: {
+                        color: #fff,
+                    }
+                }
+            }
+        }
+
+        chat_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            chat_item = <ShareChatItem> {}
+
+            empty_item = <View> {
+                width: Fill,
+                height: Fit,
+                padding: 32,
+                align: { x: 0.5 },
+
+                empty_label = <Label> {
+                    text: "",
+                    draw_text: {
+                        text_style: { font_size: 14.0 },
+                        color: #666,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The state of sending the post to a single room.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChatShareStatus {
+    /// The post is being sent.
+    Sending,
+    /// The post was sent.
+    Sent,
+    /// Sending the post failed or was blocked.
+    Failed(String),
+}
+
+/// Actions that can be triggered from the share-to-chat sheet.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialShareToChatSheetAction {
+    /// User closed the sheet.
+    Close,
+    /// No action.
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialShareToChatSheet {
+    #[deref]
+    view: View,
+
+    /// The feed room of the post being sent, and the post.
+    #[rust]
+    post: Option<(OwnedRoomId, OwnedEventId)>,
+
+    /// Rooms the post can be sent to, or `None` while they're loading.
+    #[rust]
+    targets: Option<Vec<ChatTarget>>,
+
+    /// Sending state of the rooms the post was sent to.
+    #[rust]
+    statuses: HashMap<OwnedRoomId, ChatShareStatus>,
+
+    /// The room whose forward is waiting for the user's confirmation.
+    #[rust]
+    pending_confirmation: Option<OwnedRoomId>,
+}
+
+impl Widget for SocialShareToChatSheet {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            let targets = self.targets.as_deref().unwrap_or_default();
+            list.set_item_range(cx, 0, targets.len().max(1));
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let item = match targets.get(item_id) {
+                    Some(target) => {
+                        let item = list.item(cx, item_id, live_id!(chat_item));
+                        self.populate_chat_item(cx, &item, target);
+                        item
+                    }
+                    None => {
+                        let item = list.item(cx, item_id, live_id!(empty_item));
+                        let text = if self.targets.is_some() {
+                            "No chats to send to."
+                        } else {
+                            "Loading chats…"
+                        };
+                        item.label(ids!(empty_label)).set_text(cx, text);
+                        item
+                    }
+                };
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for SocialShareToChatSheet {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.button(ids!(close_button)).clicked(actions) {
+            cx.action(SocialShareToChatSheetAction::Close);
+        }
+
+        if self.button(ids!(cancel_button)).clicked(actions) {
+            self.set_pending_confirmation(cx, None);
+        }
+        if self.button(ids!(send_anyway_button)).clicked(actions) {
+            if let Some(room_id) = self.pending_confirmation.clone() {
+                self.set_pending_confirmation(cx, None);
+                self.send(cx, room_id, true);
+            }
+        }
+
+        let chat_list = self.portal_list(ids!(chat_list));
+        for (index, item) in chat_list.items_with_actions(actions) {
+            if !item.button(ids!(send_button)).clicked(actions) {
+                continue;
+            }
+            let Some(target) = self.targets.as_ref().and_then(|targets| targets.get(index)) else {
+                continue;
+            };
+            let room_id = target.room_id.clone();
+            self.send(cx, room_id, false);
+        }
+
+        for action in actions {
+            match action.downcast_ref() {
+                Some(ShareToChatAction::TargetsLoaded(targets)) => {
+                    self.targets = Some(targets.clone());
+                    self.redraw(cx);
+                }
+                Some(ShareToChatAction::Sent(room_id)) => {
+                    self.set_status(cx, room_id, ChatShareStatus::Sent);
+                }
+                Some(ShareToChatAction::NeedsConfirmation { room_id, warning }) => {
+                    self.statuses.remove(room_id);
+                    self.label(ids!(warning_label)).set_text(cx, warning);
+                    self.set_pending_confirmation(cx, Some(room_id.clone()));
+                }
+                Some(ShareToChatAction::Failed { room_id, error }) => {
+                    self.set_status(cx, room_id, ChatShareStatus::Failed(error.clone()));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl SocialShareToChatSheet {
+    /// Show the rooms the given post can be sent to, loading them anew.
+    pub fn open(&mut self, cx: &mut Cx, room_id: OwnedRoomId, post_id: OwnedEventId) {
+        self.post = Some((room_id, post_id));
+        self.targets = None;
+        self.statuses.clear();
+        self.set_pending_confirmation(cx, None);
+        submit_async_request(MatrixRequest::LoadChatTargets);
+        self.redraw(cx);
+    }
+
+    /// Send the post to a room, unless it's already being sent there or was sent.
+    fn send(&mut self, cx: &mut Cx, target_room_id: OwnedRoomId, confirmed: bool) {
+        let Some((room_id, post_id)) = &self.post else {
+            return;
+        };
+        if matches!(
+            self.statuses.get(&target_room_id),
+            Some(ChatShareStatus::Sending | ChatShareStatus::Sent)
+        ) {
+            return;
+        }
+        submit_async_request(MatrixRequest::SendPostToChat {
+            room_id: room_id.clone(),
+            post_id: post_id.clone(),
+            target_room_id: target_room_id.clone(),
+            confirmed,
+        });
+        self.set_status(cx, &target_room_id, ChatShareStatus::Sending);
+    }
+
+    /// Update the sending state of a room the post was sent to.
+    fn set_status(&mut self, cx: &mut Cx, room_id: &OwnedRoomId, status: ChatShareStatus) {
+        self.statuses.insert(room_id.clone(), status);
+        self.redraw(cx);
+    }
+
+    /// Show or hide the warning of a forward that needs the user's confirmation.
+    fn set_pending_confirmation(&mut self, cx: &mut Cx, room_id: Option<OwnedRoomId>) {
+        self.view(ids!(confirmation_bar))
+            .set_visible(cx, room_id.is_some());
+        self.pending_confirmation = room_id;
+        self.redraw(cx);
+    }
+
+    /// Fill in a room row from the room and the post's sending state there.
+    fn populate_chat_item(&self, cx: &mut Cx, item: &WidgetRef, target: &ChatTarget) {
+        item.label(ids!(name_label)).set_text(cx, &target.name);
+
+        let status = self.statuses.get(&target.room_id);
+        let status_text = match status {
+            Some(ChatShareStatus::Sending) => "Sending…".to_string(),
+            Some(ChatShareStatus::Sent) => "Sent".to_string(),
+            Some(ChatShareStatus::Failed(error)) => error.clone(),
+            None => String::new(),
+        };
+        let status_label = item.label(ids!(status_label));
+        status_label.set_visible(cx, status.is_some());
+        status_label.set_text(cx, &status_text);
+
+        let can_send = matches!(status, None | Some(ChatShareStatus::Failed(_)));
+        item.button(ids!(send_button)).set_visible(cx, can_send);
+    }
+}
+
+impl SocialShareToChatSheetRef {
+    /// See [`SocialShareToChatSheet::open()`].
+    pub fn open(&self, cx: &mut Cx, room_id: OwnedRoomId, post_id: OwnedEventId) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.open(cx, room_id, post_id);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! A container that only shows social UI while social features are enabled.
+//!
+//! Social features can be turned off per account at runtime (see
+//! [`availability`](crate::social::availability)). All social UI should be
+//! placed inside a `SocialGate`, which hides its content and stops
+//! forwarding events to it while social features are disabled.
+
+use makepad_widgets::*;
+
+use crate::social::availability::{is_social_enabled, SocialAvailabilityAction};
+
+live_design! {
+    link social_enabled
+
+    use link::theme::*;
+    use link::widgets::*;
+
+    /// Container for social UI, hidden while social features are disabled.
+    pub SocialGate = {{SocialGate}} {
+        width: Fill,
+        height: Fill,
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialGate {
+    #[deref]
+    view: View,
+}
+
+impl Widget for SocialGate {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if let Event::Actions(actions) = event {
+            let changed = actions.iter().any(|action| {
+                matches!(
+                    action.downcast_ref(),
+                    Some(SocialAvailabilityAction::Changed(_))
+                )
+            });
+            if changed {
+                self.redraw(cx);
+            }
+        }
+        if is_social_enabled() {
+            self.view.handle_event(cx, event, scope);
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        if !is_social_enabled() {
+            return DrawStep::done();
+        }
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
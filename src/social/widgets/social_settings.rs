@@ -0,0 +1,222 @@
+//! The section of the settings screen holding the social settings.
+//!
+//! It's shown in the settings screen through the `social_link` namespace
+//! (see `App::live_register()`), inside a
+//! [`SocialGate`](crate::social::widgets::social_gate::SocialGate),
+//! so it's hidden while social features are disabled.
+
+use makepad_widgets::*;
+
+use crate::app::AppStateAction;
+use crate::room::BasicRoomDetails;
+use crate::shared::popup_list::{enqueue_popup_notification, PopupItem, PopupKind};
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::social::activity_log::ActivityLogAction;
+use crate::social::retention::RetentionJobAction;
+use crate::social::widgets::activity_log_view::{
+    SocialActivityLogViewAction, SocialActivityLogViewWidgetExt,
+};
+use crate::social::widgets::feed_density_settings::SocialFeedDensitySettingsWidgetExt;
+use crate::social::widgets::feed_filter_settings::{
+    SocialFeedFilterSettingsAction, SocialFeedFilterSettingsWidgetExt,
+};
+use crate::social::widgets::push_rule_settings::SocialPushRuleSettingsWidgetExt;
+use crate::social::widgets::retention_settings::{
+    SocialRetentionSettingsAction, SocialRetentionSettingsWidgetExt,
+};
+use crate::social::widgets::storage_usage_view::SocialStorageUsageViewWidgetExt;
+use crate::social::widgets::translation_settings::SocialTranslationSettingsWidgetExt;
+use crate::utils::RoomNameId;
+
+live_design! {
+    link social_enabled
+
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::helpers::*;
+    use crate::shared::styles::*;
+    use crate::social::widgets::activity_log_view::SocialActivityLogView;
+    use crate::social::widgets::feed_density_settings::SocialFeedDensitySettings;
+    use crate::social::widgets::feed_filter_settings::SocialFeedFilterSettings;
+    use crate::social::widgets::push_rule_settings::SocialPushRuleSettings;
+    use crate::social::widgets::retention_settings::SocialRetentionSettings;
+    use crate::social::widgets::storage_usage_view::SocialStorageUsageView;
+    use crate::social::widgets::translation_settings::SocialTranslationSettings;
+
+    /// The view containing all social settings.
+    pub SocialSettingsScreen = {{SocialSettingsScreen}} {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 10
+
+        <TitleLabel> {
+            text: "Social Settings"
+        }
+
+        feed_filter_settings = <SocialFeedFilterSettings> {
+            height: Fit,
+            language_list = { height: 300 }
+        }
+
+        feed_density_settings = <SocialFeedDensitySettings> {}
+
+        translation_settings = <SocialTranslationSettings> {}
+
+        push_rule_settings = <SocialPushRuleSettings> {}
+
+        retention_settings = <SocialRetentionSettings> {
+            height: Fit,
+            expired_list = { height: 300 }
+        }
+
+        storage_usage = <SocialStorageUsageView> {
+            height: Fit
+        }
+
+        activity_log = <SocialActivityLogView> {
+            height: Fit,
+            activity_list = { height: 300 }
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialSettingsScreen {
+    #[deref]
+    view: View,
+}
+
+impl Widget for SocialSettingsScreen {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialSettingsScreen {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        let retention_settings = self.social_retention_settings(ids!(retention_settings));
+        let activity_log = self.social_activity_log_view(ids!(activity_log));
+        for action in actions {
+            if let Some(SocialFeedFilterSettingsAction::Changed(filter)) = action.downcast_ref() {
+                submit_async_request(MatrixRequest::SaveFeedFilter(filter.clone()));
+            }
+
+            match action.downcast_ref() {
+                Some(SocialRetentionSettingsAction::FeedSelected(feed)) => {
+                    submit_async_request(MatrixRequest::LoadRetentionPolicy(*feed));
+                }
+                Some(SocialRetentionSettingsAction::SetMaxAge {
+                    feed,
+                    max_age_months,
+                }) => submit_async_request(MatrixRequest::SetRetentionMaxAge {
+                    feed: *feed,
+                    max_age_months: *max_age_months,
+                }),
+                Some(SocialRetentionSettingsAction::Preview(feed)) => {
+                    submit_async_request(MatrixRequest::RunRetentionPolicy {
+                        feed: *feed,
+                        dry_run: true,
+                    });
+                }
+                Some(SocialRetentionSettingsAction::PinPost { feed, event_id }) => {
+                    submit_async_request(MatrixRequest::PinRetainedPost {
+                        feed: *feed,
+                        event_id: event_id.clone(),
+                    });
+                }
+                Some(SocialRetentionSettingsAction::ApplyNow(feed)) => {
+                    submit_async_request(MatrixRequest::RunRetentionPolicy {
+                        feed: *feed,
+                        dry_run: false,
+                    });
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(RetentionJobAction::PolicyLoaded { feed, policy }) => {
+                    retention_settings.set_policy(cx, *feed, policy);
+                }
+                Some(RetentionJobAction::Completed(report)) => {
+                    retention_settings.set_report(cx, report.clone());
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(SocialActivityLogViewAction::RangeSelected(range)) => {
+                    submit_async_request(MatrixRequest::LoadActivityLog(*range));
+                }
+                Some(SocialActivityLogViewAction::Undo(entries)) => {
+                    submit_async_request(MatrixRequest::UndoActivities(entries.clone()));
+                }
+                // Posts are shown in their feed room's timeline
+                Some(SocialActivityLogViewAction::OpenPost { room_id, .. }) => {
+                    cx.action(AppStateAction::NavigateToRoom {
+                        room_to_close: None,
+                        destination_room: BasicRoomDetails::RoomId(RoomNameId::empty(
+                            room_id.clone(),
+                        )),
+                    });
+                }
+                _ => {}
+            }
+
+            match action.downcast_ref() {
+                Some(ActivityLogAction::Loaded(entries)) => {
+                    activity_log.set_entries(cx, entries.clone());
+                }
+                Some(ActivityLogAction::Undone(report)) => {
+                    activity_log.set_undo_report(cx, report.clone());
+                }
+                Some(ActivityLogAction::Failed(error)) => {
+                    enqueue_popup_notification(PopupItem {
+                        message: format!("Failed to load your activity: {error}"),
+                        kind: PopupKind::Error,
+                        auto_dismissal_duration: None,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl SocialSettingsScreen {
+    /// Show the current social settings.
+    pub fn refresh(&mut self, cx: &mut Cx) {
+        self.social_feed_filter_settings(ids!(feed_filter_settings))
+            .refresh(cx);
+        self.social_feed_density_settings(ids!(feed_density_settings))
+            .refresh(cx);
+        self.social_translation_settings(ids!(translation_settings))
+            .refresh(cx);
+        self.social_push_rule_settings(ids!(push_rule_settings))
+            .refresh(cx);
+        let feed = self
+            .social_retention_settings(ids!(retention_settings))
+            .feed();
+        submit_async_request(MatrixRequest::LoadRetentionPolicy(feed));
+        self.social_storage_usage_view(ids!(storage_usage))
+            .refresh(cx);
+        if let Some(range) = self.social_activity_log_view(ids!(activity_log)).range() {
+            submit_async_request(MatrixRequest::LoadActivityLog(range));
+        }
+    }
+}
+
+impl SocialSettingsScreenRef {
+    /// See [`SocialSettingsScreen::refresh()`].
+    pub fn refresh(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.refresh(cx);
+        }
+    }
+}
@@ -0,0 +1,336 @@
+//! Dashboard of the local storage used by social features.
+//!
+//! Lists how much space cached media, the feed cache and the search indexes
+//! take up on this device, each with a button to clear it, and lets the user
+//! limit the size of the caches (see [`storage_usage`]). The limits are saved
+//! to the account's social settings, so they apply on every device.
+//!
+//! [`storage_usage`]: crate::social::storage_usage
+
+use bytesize::ByteSize;
+use makepad_widgets::*;
+use robrix_social_events::settings::CacheLimits;
+
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::social::storage_usage::{StorageCategory, StorageUsage, StorageUsageAction};
+
+/// Cache size limits offered in the limit dropdowns, in bytes, in the order of their labels.
+const LIMIT_OPTIONS: [Option<u64>; 5] = [
+    None,
+    Some(10_000_000),
+    Some(50_000_000),
+    Some(100_000_000),
+    Some(500_000_000),
+];
+
+/// The rows showing the categories of stored data, in the order they're shown.
+const CATEGORY_ROWS: [(&[LiveId], StorageCategory); 3] = [
+    (ids!(media_row), StorageCategory::Media),
+    (ids!(feed_cache_row), StorageCategory::FeedCache),
+    (ids!(search_index_row), StorageCategory::SearchIndex),
+];
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+
+    /// A category of stored data with its size and a button to clear it.
+    StorageCategoryRow = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 10, bottom: 10 },
+        flow: Right,
+        spacing: 12,
+        align: { y: 0.5 },
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        info_column = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 2,
+
+            name_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: #000,
+                }
+            }
+
+            size_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                }
+            }
+        }
+
+        clear_button = <Button> {
+            width: Fit,
+            height: Fit,
+            text: "Clear",
+        }
+    }
+
+    /// A dropdown limiting the size of a cache.
+    CacheLimitRow = <View> {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 8,
+        align: { y: 0.5 },
+
+        limit_label = <Label> {
+            width: Fill,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: #000,
+            }
+        }
+
+        limit_dropdown = <DropDown> {
+            width: Fit,
+            height: Fit,
+            labels: ["No limit", "10 MB", "50 MB", "100 MB", "500 MB"],
+        }
+    }
+
+    /// Local storage used by social features, with per-category clear buttons.
+    pub SocialStorageUsageView = {{SocialStorageUsageView}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        spacing: 1,
+        show_bg: true,
+        draw_bg: {
+            color: #f5f5f5
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            flow: Down,
+            spacing: 4,
+            show_bg: true,
+            draw_bg: {
+                color: #fff
+            }
+
+            title_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Data usage",
+                draw_text: {
+                    text_style: { font_size: 18.0 },
+                    color: #000,
+                }
+            }
+
+            total_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                    wrap: Word,
+                }
+            }
+        }
+
+        media_row = <StorageCategoryRow> {}
+        feed_cache_row = <StorageCategoryRow> {}
+        search_index_row = <StorageCategoryRow> {}
+
+        limits = <View> {
+            width: Fill,
+            height: Fit,
+            margin: { top: 12 },
+            padding: 16,
+            flow: Down,
+            spacing: 12,
+            show_bg: true,
+            draw_bg: {
+                color: #fff
+            }
+
+            limits_description_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Once a cache grows past its limit, its oldest data is removed.",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                    wrap: Word,
+                }
+            }
+
+            media_limit_row = <CacheLimitRow> {
+                limit_label = { text: "Cached media limit" }
+            }
+
+            feed_cache_limit_row = <CacheLimitRow> {
+                limit_label = { text: "Feed cache limit" }
+            }
+        }
+
+        status_label = <Label> {
+            width: Fill,
+            height: Fit,
+            padding: 16,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 12.0 },
+                color: #e0245e,
+                wrap: Word,
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialStorageUsageView {
+    #[deref]
+    view: View,
+
+    /// The cache limits last loaded.
+    #[rust]
+    limits: CacheLimits,
+}
+
+impl Widget for SocialStorageUsageView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialStorageUsageView {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        for (row_id, category) in CATEGORY_ROWS {
+            let row = self.view(row_id);
+            if row.button(ids!(clear_button)).clicked(actions) {
+                self.set_status(cx, "");
+                submit_async_request(MatrixRequest::ClearSocialStorage(category));
+            }
+        }
+
+        let media_limit = self
+            .view(ids!(media_limit_row))
+            .drop_down(ids!(limit_dropdown))
+            .selected(actions);
+        let feed_cache_limit = self
+            .view(ids!(feed_cache_limit_row))
+            .drop_down(ids!(limit_dropdown))
+            .selected(actions);
+        if media_limit.is_some() || feed_cache_limit.is_some() {
+            let mut limits = self.limits;
+            if let Some(selected) = media_limit {
+                limits.max_media_bytes = LIMIT_OPTIONS.get(selected).copied().flatten();
+            }
+            if let Some(selected) = feed_cache_limit {
+                limits.max_feed_cache_bytes = LIMIT_OPTIONS.get(selected).copied().flatten();
+            }
+            self.limits = limits;
+            self.set_status(cx, "");
+            submit_async_request(MatrixRequest::SetCacheLimits(limits));
+        }
+
+        for action in actions {
+            match action.downcast_ref() {
+                Some(StorageUsageAction::Loaded { usage, limits }) => {
+                    self.set_usage(cx, *usage, *limits);
+                }
+                Some(StorageUsageAction::Failed(error)) => {
+                    self.set_status(cx, error);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl SocialStorageUsageView {
+    /// Measure the storage usage anew and show it.
+    pub fn refresh(&mut self, cx: &mut Cx) {
+        self.set_status(cx, "");
+        self.label(ids!(total_label)).set_text(cx, "Calculating…");
+        submit_async_request(MatrixRequest::LoadStorageUsage);
+    }
+
+    /// Show the measured storage usage and the current cache limits.
+    fn set_usage(&mut self, cx: &mut Cx, usage: StorageUsage, limits: CacheLimits) {
+        self.label(ids!(total_label)).set_text(
+            cx,
+            &format!(
+                "Social features use {} on this device.",
+                ByteSize::b(usage.total())
+            ),
+        );
+
+        for (row_id, category) in CATEGORY_ROWS {
+            let row = self.view(row_id);
+            row.label(ids!(name_label)).set_text(cx, category.label());
+            let size = ByteSize::b(usage.get(category));
+            let size_text = match category.limit(&limits) {
+                Some(limit) => format!("{size} of {}", ByteSize::b(limit)),
+                None => size.to_string(),
+            };
+            row.label(ids!(size_label)).set_text(cx, &size_text);
+            row.button(ids!(clear_button))
+                .set_enabled(cx, usage.get(category) > 0);
+        }
+
+        // Limits that aren't offered in the dropdowns fall back to "No limit"
+        for (row_id, limit) in [
+            (ids!(media_limit_row), limits.max_media_bytes),
+            (ids!(feed_cache_limit_row), limits.max_feed_cache_bytes),
+        ] {
+            let index = LIMIT_OPTIONS
+                .iter()
+                .position(|option| *option == limit)
+                .unwrap_or(0);
+            self.view(row_id)
+                .drop_down(ids!(limit_dropdown))
+                .set_selected_item(cx, index);
+        }
+
+        self.limits = limits;
+        self.redraw(cx);
+    }
+
+    /// Show an error, or hide it if `text` is empty.
+    fn set_status(&mut self, cx: &mut Cx, text: &str) {
+        let status_label = self.label(ids!(status_label));
+        status_label.set_visible(cx, !text.is_empty());
+        status_label.set_text(cx, text);
+    }
+}
+
+impl SocialStorageUsageViewRef {
+    /// See [`SocialStorageUsageView::refresh()`].
+    pub fn refresh(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.refresh(cx);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! The dark color palette of social widgets.
+//!
+//! Defines the `social_theme_dark` DSL namespace, which `App::live_register()`
+//! connects to the `social_theme` namespace while the app uses its dark theme.
+//! It defines the same colors as [`theme_light`](super::theme_light).
+
+use makepad_widgets::*;
+
+live_design! {
+    link social_theme_dark
+
+    /// Background of cards, pages and sheets.
+    pub SOCIAL_BG = #1e1f22
+    /// Background of chips, inputs and secondary panels.
+    pub SOCIAL_SURFACE = #2b2d31
+    /// Borders of cards, inputs and outlined buttons.
+    pub SOCIAL_BORDER = #3f4147
+    /// Lines separating sections and list items.
+    pub SOCIAL_DIVIDER = #2f3136
+
+    /// Titles and names.
+    pub SOCIAL_TEXT_PRIMARY = #f2f3f5
+    /// Body text.
+    pub SOCIAL_TEXT = #dbdee1
+    /// Secondary text and icons, e.g., timestamps and handles.
+    pub SOCIAL_TEXT_SECONDARY = #a3a6aa
+    /// Hints and placeholders.
+    pub SOCIAL_TEXT_MUTED = #80848e
+
+    /// Links, selections and primary buttons.
+    pub SOCIAL_ACCENT = #1d9bf0
+    /// Background of selected and highlighted items.
+    pub SOCIAL_ACCENT_BG = #16334a
+    /// Text and icons on top of the accent color.
+    pub SOCIAL_ON_ACCENT = #fff
+    /// Primary buttons that can't be pressed yet, e.g., posting an empty post.
+    pub SOCIAL_ACCENT_DISABLED = #2a5f85
+
+    /// Errors and destructive actions.
+    pub SOCIAL_DANGER = #f4577f
+    /// Background of errors.
+    pub SOCIAL_DANGER_BG = #3d2229
+    /// Text of warnings and notices.
+    pub SOCIAL_WARNING_TEXT = #f0c674
+    /// Background of warnings and notices.
+    pub SOCIAL_WARNING_BG = #3a321c
+    /// Counts and indicators nearing a limit, e.g., of a post's length.
+    pub SOCIAL_WARNING = #f0b429
+    /// Confirmations, e.g., going to an event or checking in.
+    pub SOCIAL_SUCCESS = #3fb950
+
+    /// Presence dot of users who are online.
+    pub SOCIAL_PRESENCE_ONLINE = #3fbf5f
+    /// Presence dot of users who are idle.
+    pub SOCIAL_PRESENCE_IDLE = #f0b54a
+    /// Presence dot of users who are offline.
+    pub SOCIAL_PRESENCE_OFFLINE = #666
+}
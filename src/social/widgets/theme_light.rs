@@ -0,0 +1,58 @@
+//! The light color palette of social widgets.
+//!
+//! Defines the `social_theme_light` DSL namespace, which `App::live_register()`
+//! connects to the `social_theme` namespace while the app uses its light theme.
+//! [`theme_dark`](super::theme_dark) defines the same colors for the dark theme.
+
+use makepad_widgets::*;
+
+live_design! {
+    link social_theme_light
+
+    /// Background of cards, pages and sheets.
+    pub SOCIAL_BG = #fff
+    /// Background of chips, inputs and secondary panels.
+    pub SOCIAL_SURFACE = #f0f2f5
+    /// Borders of cards, inputs and outlined buttons.
+    pub SOCIAL_BORDER = #e0e0e0
+    /// Lines separating sections and list items.
+    pub SOCIAL_DIVIDER = #eee
+
+    /// Titles and names.
+    pub SOCIAL_TEXT_PRIMARY = #000
+    /// Body text.
+    pub SOCIAL_TEXT = #333
+    /// Secondary text and icons, e.g., timestamps and handles.
+    pub SOCIAL_TEXT_SECONDARY = #666
+    /// Hints and placeholders.
+    pub SOCIAL_TEXT_MUTED = #999
+
+    /// Links, selections and primary buttons.
+    pub SOCIAL_ACCENT = #1d9bf0
+    /// Background of selected and highlighted items.
+    pub SOCIAL_ACCENT_BG = #e8f5fd
+    /// Text and icons on top of the accent color.
+    pub SOCIAL_ON_ACCENT = #fff
+    /// Primary buttons that can't be pressed yet, e.g., posting an empty post.
+    pub SOCIAL_ACCENT_DISABLED = #87ceeb
+
+    /// Errors and destructive actions.
+    pub SOCIAL_DANGER = #e0245e
+    /// Background of errors.
+    pub SOCIAL_DANGER_BG = #fff0f0
+    /// Text of warnings and notices.
+    pub SOCIAL_WARNING_TEXT = #5d4037
+    /// Background of warnings and notices.
+    pub SOCIAL_WARNING_BG = #fff8e1
+    /// Counts and indicators nearing a limit, e.g., of a post's length.
+    pub SOCIAL_WARNING = #e6a700
+    /// Confirmations, e.g., going to an event or checking in.
+    pub SOCIAL_SUCCESS = #22c55e
+
+    /// Presence dot of users who are online.
+    pub SOCIAL_PRESENCE_ONLINE = #31a24c
+    /// Presence dot of users who are idle.
+    pub SOCIAL_PRESENCE_IDLE = #f5a623
+    /// Presence dot of users who are offline.
+    pub SOCIAL_PRESENCE_OFFLINE = #bbb
+}
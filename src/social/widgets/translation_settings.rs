@@ -0,0 +1,173 @@
+//! Setting for the service posts are translated with.
+//!
+//! The endpoint of a LibreTranslate-compatible service, and its API key if it
+//! needs one, are saved in the account's social settings
+//! (see [`set_translation_settings()`]). Saving an empty endpoint turns
+//! translation off.
+//!
+//! [`set_translation_settings()`]: crate::social::translation::set_translation_settings
+
+use makepad_widgets::*;
+use robrix_social_events::settings::TranslationSettings;
+use url::Url;
+
+use crate::sliding_sync::{submit_async_request, MatrixRequest};
+use crate::social::translation::translation_settings;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::helpers::*;
+    use crate::shared::styles::*;
+    use crate::shared::icon_button::*;
+
+    /// Translation service setting.
+    pub SocialTranslationSettings = {{SocialTranslationSettings}} {
+        width: Fill,
+        height: Fit,
+        flow: Down,
+        spacing: 8,
+
+        <SubsectionLabel> {
+            text: "Translation service:"
+        }
+
+        <Label> {
+            width: Fill,
+            height: Fit,
+            text: "Posts are translated by a LibreTranslate-compatible service. Leave the address empty to turn translation off.",
+            draw_text: {
+                wrap: Word,
+                color: (COLOR_TEXT),
+                text_style: <REGULAR_TEXT>{ font_size: 10 },
+            }
+        }
+
+        endpoint_input = <RobrixTextInput> {
+            width: 400,
+            height: Fit,
+            empty_text: "https://libretranslate.example.org/"
+        }
+
+        api_key_input = <RobrixTextInput> {
+            width: 400,
+            height: Fit,
+            empty_text: "API key (optional)"
+            is_password: true,
+        }
+
+        error_label = <Label> {
+            width: Fill,
+            height: Fit,
+            visible: false,
+            text: "",
+            draw_text: {
+                wrap: Word,
+                color: (COLOR_FG_DANGER_RED),
+                text_style: <REGULAR_TEXT>{ font_size: 10 },
+            }
+        }
+
+        save_button = <RobrixIconButton> {
+            width: Fit,
+            height: Fit,
+            padding: 10,
+            margin: {left: 5},
+            draw_bg: {
+                color: (COLOR_ACTIVE_PRIMARY)
+            }
+            draw_icon: {
+                svg_file: (ICON_CHECKMARK)
+                color: (COLOR_PRIMARY)
+            }
+            icon_walk: {width: 16, height: 16, margin: 0}
+            draw_text: {
+                color: (COLOR_PRIMARY)
+                text_style: <REGULAR_TEXT> {}
+            }
+            text: "Save"
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialTranslationSettings {
+    #[deref]
+    view: View,
+}
+
+impl Widget for SocialTranslationSettings {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for SocialTranslationSettings {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if !self.button(ids!(save_button)).clicked(actions) {
+            return;
+        }
+        let endpoint = self
+            .text_input(ids!(endpoint_input))
+            .text()
+            .trim()
+            .to_owned();
+        let api_key = self
+            .text_input(ids!(api_key_input))
+            .text()
+            .trim()
+            .to_owned();
+        let error_label = self.label(ids!(error_label));
+        let settings = if endpoint.is_empty() {
+            None
+        } else if let Err(e) = Url::parse(&endpoint) {
+            error_label.set_text(cx, &format!("Invalid address: {e}"));
+            error_label.set_visible(cx, true);
+            return;
+        } else {
+            Some(TranslationSettings {
+                endpoint,
+                api_key: (!api_key.is_empty()).then_some(api_key),
+            })
+        };
+        error_label.set_visible(cx, false);
+        submit_async_request(MatrixRequest::SetTranslationSettings(settings));
+    }
+}
+
+impl SocialTranslationSettings {
+    /// Show the current translation service.
+    pub fn refresh(&mut self, cx: &mut Cx) {
+        let settings = translation_settings();
+        self.text_input(ids!(endpoint_input)).set_text(
+            cx,
+            settings
+                .as_ref()
+                .map_or("", |settings| settings.endpoint.as_str()),
+        );
+        self.text_input(ids!(api_key_input)).set_text(
+            cx,
+            settings
+                .as_ref()
+                .and_then(|settings| settings.api_key.as_deref())
+                .unwrap_or_default(),
+        );
+        self.label(ids!(error_label)).set_visible(cx, false);
+    }
+}
+
+impl SocialTranslationSettingsRef {
+    /// See [`SocialTranslationSettings::refresh()`].
+    pub fn refresh(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.refresh(cx);
+        }
+    }
+}
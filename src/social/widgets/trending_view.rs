@@ -0,0 +1,385 @@
+//! Trending posts and topics widget.
+//!
+//! Shown as the Trending tab of the
+//! [`SocialDiscoverView`](crate::social::widgets::discover_view::SocialDiscoverView),
+//! this lists the trending hashtags and posts found by
+//! [`TrendingService::get_trending()`].
+//!
+//! [`TrendingService::get_trending()`]: crate::social::discovery::TrendingService::get_trending
+
+use makepad_widgets::*;
+use matrix_sdk::ruma::{OwnedEventId, OwnedRoomId};
+
+use crate::shared::avatar::AvatarWidgetExt;
+use crate::social::discovery::{Trending, TrendingPost};
+
+/// The topic chips shown above the posts, in order.
+const TOPIC_CHIPS: [&[LiveId]; 5] = [
+    ids!(topic_0),
+    ids!(topic_1),
+    ids!(topic_2),
+    ids!(topic_3),
+    ids!(topic_4),
+];
+
+/// Maximum number of characters of a post's text shown in the list.
+const POST_PREVIEW_CHARS: usize = 200;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::shared::styles::*;
+    use crate::shared::avatar::Avatar;
+
+    /// A trending hashtag.
+    TopicChip = <Button> {
+        width: Fit,
+        height: 28,
+        text: "",
+        draw_bg: {
+            color: #e8f5fe,
+            radius: 14.0,
+        }
+        draw_text: {
+            color: #1d9bf0,
+            text_style: { font_size: 12.0 },
+        }
+    }
+
+    /// A single trending post in the list.
+    TrendingPostItem = <View> {
+        width: Fill,
+        height: Fit,
+        padding: { left: 16, right: 16, top: 10, bottom: 10 },
+        flow: Right,
+        spacing: 12,
+        cursor: Hand,
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        avatar = <Avatar> {
+            width: 40,
+            height: 40,
+        }
+
+        post_info = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 4,
+
+            sender_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: #000,
+                }
+            }
+
+            text_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 14.0 },
+                    color: #000,
+                    wrap: Word,
+                }
+            }
+
+            engagement_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "",
+                draw_text: {
+                    text_style: { font_size: 12.0 },
+                    color: #666,
+                }
+            }
+        }
+    }
+
+    /// Shown in place of the list when there are no trending posts.
+    TrendingEmpty = <View> {
+        width: Fill,
+        height: Fit,
+        padding: 32,
+        align: { x: 0.5 },
+
+        empty_label = <Label> {
+            width: Fit,
+            height: Fit,
+            text: "",
+            draw_text: {
+                text_style: { font_size: 14.0 },
+                color: #999,
+            }
+        }
+    }
+
+    /// View of the trending posts and topics in followed public feeds.
+    pub SocialTrendingView = {{SocialTrendingView}} {
+        width: Fill,
+        height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {
+            color: #fff
+        }
+
+        header = <View> {
+            width: Fill,
+            height: Fit,
+            flow: Down,
+            spacing: 12,
+            padding: 16,
+
+            title_row = <View> {
+                width: Fill,
+                height: Fit,
+                flow: Right,
+                align: { y: 0.5 },
+
+                title_label = <Label> {
+                    width: Fill,
+                    height: Fit,
+                    text: "Trending",
+                    draw_text: {
+                        text_style: { font_size: 20.0 },
+                        color: #000,
+                    }
+                }
+
+                refresh_button = <Button> {
+                    width: Fit,
+                    height: Fit,
+                    text: "Refresh",
+                    draw_bg: {
+                        color: #0000,
+                    }
+                    draw_text: {
+                        color: #1d9bf0,
+                        text_style: { font_size: 12.0 },
+                    }
+                }
+            }
+
+            topics = <View> {
+                width: Fill,
+                height: Fit,
+                flow: RightWrap,
+                spacing: 8,
+                visible: false,
+
+                topic_0 = <TopicChip> {}
+                topic_1 = <TopicChip> {}
+                topic_2 = <TopicChip> {}
+                topic_3 = <TopicChip> {}
+                topic_4 = <TopicChip> {}
+            }
+
+            section_label = <Label> {
+                width: Fill,
+                height: Fit,
+                text: "Popular posts",
+                draw_text: {
+                    text_style: { font_size: 13.0 },
+                    color: #666,
+                }
+            }
+        }
+
+        posts_list = <PortalList> {
+            width: Fill,
+            height: Fill,
+            flow: Down,
+
+            post_item = <TrendingPostItem> {}
+            empty_item = <TrendingEmpty> {}
+        }
+    }
+}
+
+/// Actions that can be triggered from the trending view.
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SocialTrendingAction {
+    /// User tapped a trending post.
+    OpenPost {
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+    },
+    /// User tapped a trending hashtag, given without the leading `#`.
+    OpenHashtag(String),
+    /// Trending posts and topics should be (re)loaded,
+    /// and then set with [`SocialTrendingView::set_trending()`].
+    Refresh,
+    /// No action
+    None,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialTrendingView {
+    #[deref]
+    view: View,
+
+    /// The trending hashtags shown in the topic chips.
+    #[rust]
+    topics: Vec<String>,
+
+    /// The trending posts, or `None` until they've been loaded.
+    #[rust]
+    posts: Option<Vec<TrendingPost>>,
+}
+
+impl Widget for SocialTrendingView {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        // Borrow the field directly, since the view is drawn at the same time
+        let posts = self.posts.as_deref().unwrap_or_default();
+        let empty_text = if self.posts.is_none() {
+            "Loading…"
+        } else {
+            "Nothing is trending right now. Check back later."
+        };
+
+        while let Some(widget_to_draw) = self.view.draw_walk(cx, scope, walk).step() {
+            let portal_list_ref = widget_to_draw.as_portal_list();
+            let Some(mut list) = portal_list_ref.borrow_mut() else {
+                continue;
+            };
+
+            list.set_item_range(cx, 0, posts.len().max(1));
+            while let Some(item_id) = list.next_visible_item(cx) {
+                let item = match posts.get(item_id) {
+                    Some(post) => {
+                        let item = list.item(cx, item_id, live_id!(post_item));
+                        let name = post.sender.localpart();
+                        item.avatar(ids!(avatar)).set_text(cx, name);
+                        item.label(ids!(sender_label)).set_text(cx, name);
+                        item.label(ids!(text_label))
+                            .set_text(cx, &post_preview(&post.text));
+                        item.label(ids!(engagement_label))
+                            .set_text(cx, &engagement_text(post));
+                        item
+                    }
+                    None => {
+                        let item = list.item(cx, item_id, live_id!(empty_item));
+                        item.label(ids!(empty_label)).set_text(cx, empty_text);
+                        item
+                    }
+                };
+                item.draw_all(cx, scope);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for SocialTrendingView {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self.button(ids!(refresh_button)).clicked(actions) {
+            cx.action(SocialTrendingAction::Refresh);
+        }
+
+        for (chip_ids, hashtag) in TOPIC_CHIPS.iter().zip(&self.topics) {
+            if self.button(chip_ids).clicked(actions) {
+                cx.action(SocialTrendingAction::OpenHashtag(hashtag.clone()));
+            }
+        }
+
+        let posts_list = self.portal_list(ids!(posts_list));
+        for (index, item) in posts_list.items_with_actions(actions) {
+            let Some(post) = self.posts.as_ref().and_then(|posts| posts.get(index)) else {
+                continue;
+            };
+            if item.as_view().finger_up(actions).is_some() {
+                cx.action(SocialTrendingAction::OpenPost {
+                    room_id: post.room_id.clone(),
+                    event_id: post.event_id.clone(),
+                });
+            }
+        }
+    }
+}
+
+impl SocialTrendingView {
+    /// Set the trending posts and topics,
+    /// e.g., from [`TrendingService::get_trending()`](crate::social::discovery::TrendingService::get_trending).
+    ///
+    /// Only the first few topics are shown.
+    pub fn set_trending(&mut self, cx: &mut Cx, trending: &Trending) {
+        self.topics = trending
+            .topics
+            .iter()
+            .take(TOPIC_CHIPS.len())
+            .map(|topic| topic.hashtag.clone())
+            .collect();
+        for (index, chip_ids) in TOPIC_CHIPS.iter().enumerate() {
+            let chip = self.button(chip_ids);
+            match self.topics.get(index) {
+                Some(hashtag) => {
+                    chip.set_text(cx, &format!("#{hashtag}"));
+                    chip.set_visible(cx, true);
+                }
+                None => chip.set_visible(cx, false),
+            }
+        }
+        self.view(ids!(topics))
+            .set_visible(cx, !self.topics.is_empty());
+
+        self.posts = Some(trending.posts.clone());
+        self.redraw(cx);
+    }
+
+    /// Returns whether trending posts and topics have been set yet.
+    pub fn is_loaded(&self) -> bool {
+        self.posts.is_some()
+    }
+}
+
+impl SocialTrendingViewRef {
+    /// See [`SocialTrendingView::set_trending()`].
+    pub fn set_trending(&self, cx: &mut Cx, trending: &Trending) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_trending(cx, trending);
+        }
+    }
+
+    /// See [`SocialTrendingView::is_loaded()`].
+    pub fn is_loaded(&self) -> bool {
+        self.borrow().is_some_and(|inner| inner.is_loaded())
+    }
+}
+
+/// Shorten a post's text for the list.
+fn post_preview(text: &str) -> String {
+    if text.chars().count() <= POST_PREVIEW_CHARS {
+        return text.to_string();
+    }
+    let mut preview: String = text.chars().take(POST_PREVIEW_CHARS).collect();
+    preview.push('…');
+    preview
+}
+
+/// Describe the engagement with a trending post.
+fn engagement_text(post: &TrendingPost) -> String {
+    let reactions = match post.reactions {
+        1 => "1 reaction".to_string(),
+        count => format!("{count} reactions"),
+    };
+    let comments = match post.comments {
+        1 => "1 comment".to_string(),
+        count => format!("{count} comments"),
+    };
+    format!("{reactions} · {comments}")
+}
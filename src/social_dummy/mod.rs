@@ -1,17 +1,28 @@
 //! Placeholder module when social features are disabled.
+//!
+//! Like the `tsp_dummy` module, this defines the placeholder widgets in the
+//! `social_disabled` DSL namespace, which `App::live_register()` connects to the
+//! `social_link` namespace when the `social` feature is not enabled.
+//! The rest of the app's DSL can then use social widgets through `social_link`.
 
 use makepad_widgets::*;
+use matrix_sdk::ruma::OwnedUserId;
 
 live_design! {
+    link social_disabled
+
     use link::theme::*;
     use link::widgets::*;
 
     // Empty placeholder widgets that render nothing
     pub SocialFeedView = {{SocialFeedView}} {}
     pub SocialProfilePage = {{SocialProfilePage}} {}
+    pub SocialMainFeedPage = {{SocialMainFeedPage}} {}
     pub SocialPostComposer = {{SocialPostComposer}} {}
     pub SocialEventCard = {{SocialEventCard}} {}
     pub SocialFriendList = {{SocialFriendList}} {}
+    pub SocialGate = {{SocialGate}} {}
+    pub SocialSettingsScreen = {{SocialSettingsScreen}} {}
 }
 
 #[derive(Live, LiveHook, Widget)]
@@ -38,6 +49,28 @@ impl Widget for SocialProfilePage {
     }
 }
 
+impl SocialProfilePageRef {
+    /// Does nothing, as there are no social profiles to show.
+    pub fn show_user(&self, _cx: &mut Cx, _user_id: OwnedUserId) {}
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialMainFeedPage {
+    #[deref]
+    view: View,
+}
+
+impl Widget for SocialMainFeedPage {
+    fn draw_walk(&mut self, _cx: &mut Cx2d, _scope: &mut Scope, _walk: Walk) -> DrawStep {
+        DrawStep::done()
+    }
+}
+
+impl SocialMainFeedPageRef {
+    /// Does nothing, as there is no newsfeed to show.
+    pub fn show(&self, _cx: &mut Cx) {}
+}
+
 #[derive(Live, LiveHook, Widget)]
 pub struct SocialPostComposer {
     #[deref]
@@ -73,3 +106,32 @@ impl Widget for SocialFriendList {
         DrawStep::done()
     }
 }
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialGate {
+    #[deref]
+    view: View,
+}
+
+impl Widget for SocialGate {
+    fn draw_walk(&mut self, _cx: &mut Cx2d, _scope: &mut Scope, _walk: Walk) -> DrawStep {
+        DrawStep::done()
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SocialSettingsScreen {
+    #[deref]
+    view: View,
+}
+
+impl Widget for SocialSettingsScreen {
+    fn draw_walk(&mut self, _cx: &mut Cx2d, _scope: &mut Scope, _walk: Walk) -> DrawStep {
+        DrawStep::done()
+    }
+}
+
+impl SocialSettingsScreenRef {
+    /// Does nothing, as there are no social settings to show.
+    pub fn refresh(&self, _cx: &mut Cx) {}
+}
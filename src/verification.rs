@@ -14,7 +14,7 @@ use matrix_sdk::{
             key::verification::{request::ToDeviceKeyVerificationRequestEvent, VerificationMethod},
             room::message::{MessageType, OriginalSyncRoomMessageEvent},
         },
-        UserId,
+        OwnedUserId, UserId,
     },
     Client,
 };
@@ -217,6 +217,24 @@ async fn sas_verification_handler(
     }
 }
 
+/// Sends a verification request to another user.
+///
+/// The request is then handled like the ones received from others,
+/// so its progress is shown in the verification modal.
+pub async fn request_user_verification(client: Client, user_id: OwnedUserId) -> anyhow::Result<()> {
+    let identity = client
+        .encryption()
+        .get_user_identity(&user_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("{user_id} has not set up cross-signing"))?;
+    // We currently only support SAS verification.
+    let request = identity
+        .request_verification_with_methods(vec![VerificationMethod::SasV1])
+        .await?;
+    Handle::current().spawn(request_verification_handler(client, request));
+    Ok(())
+}
+
 async fn request_verification_handler(client: Client, request: VerificationRequest) {
     log!(
         "Received a verification request in room {:?}: {:?}",
@@ -237,6 +255,8 @@ async fn request_verification_handler(client: Client, request: VerificationReque
     // We currently only support SAS verification.
     let supported_methods = vec![VerificationMethod::SasV1];
     match response_receiver.recv().await {
+        // We sent this request ourselves, so only the other side needs to accept it.
+        Some(VerificationUserResponse::Accept) if request.we_started() => {}
         Some(VerificationUserResponse::Accept) => {
             match request.accept_with_methods(supported_methods).await {
                 Ok(()) => {
@@ -260,6 +280,14 @@ async fn request_verification_handler(client: Client, request: VerificationReque
 
     while let Some(state) = stream.next().await {
         match state {
+            // Once the other side accepted our request, we start SAS verification with them.
+            VerificationRequestState::Ready { .. } if request.we_started() => {
+                Cx::post_action(VerificationAction::RequestAccepted);
+                if let Err(e) = request.start_sas().await {
+                    Cx::post_action(VerificationAction::RequestAcceptError(Arc::new(e)));
+                    return;
+                }
+            }
             VerificationRequestState::Created { .. }
             | VerificationRequestState::Requested { .. }
             | VerificationRequestState::Ready { .. } => {}
@@ -174,6 +174,17 @@ impl WidgetMatchEvent for VerificationModal {
             } else {
                 if let Some(state) = self.state.as_ref() {
                     let _ = state.response_sender.send(VerificationUserResponse::Accept);
+                    if state.request.we_started() {
+                        self.label(ids!(prompt)).set_text(
+                            cx,
+                            &format!(
+                                "Waiting for {} to accept the verification request...",
+                                state.request.other_user_id(),
+                            ),
+                        );
+                        accept_button.set_enabled(cx, false);
+                        accept_button.set_text(cx, "Waiting...");
+                    }
                 }
             }
         }
@@ -199,10 +210,19 @@ impl WidgetMatchEvent for VerificationModal {
                     }
 
                     VerificationAction::RequestAccepted => {
+                        let we_started = self
+                            .state
+                            .as_ref()
+                            .is_some_and(|state| state.request.we_started());
                         self.label(ids!(prompt)).set_text(
                             cx,
-                            "You successfully accepted the verification request.\n\n\
-                            Waiting for the other device to agree on verification methods...",
+                            if we_started {
+                                "The other user accepted the verification request.\n\n\
+                                Waiting for both devices to agree on verification methods..."
+                            } else {
+                                "You successfully accepted the verification request.\n\n\
+                                Waiting for the other device to agree on verification methods..."
+                            },
                         );
                         accept_button.set_enabled(cx, false);
                         accept_button.set_text(cx, "Waiting...");
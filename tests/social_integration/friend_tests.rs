@@ -2,7 +2,10 @@
 //!
 //! Tests for friend relationships, friend requests, and friends space management.
 
-use robrix::social::friends::{FriendRequestError, FriendRequestState, FriendsError};
+use robrix::social::{
+    friends::{FriendRequestError, FriendRequestState, FriendsError},
+    BlockListError,
+};
 
 /// Test FriendRequestState equality.
 #[test]
@@ -64,6 +67,22 @@ fn test_friend_request_error_display() {
     );
 }
 
+/// Test BlockListError display messages.
+#[test]
+fn test_block_list_error_display() {
+    assert_eq!(BlockListError::NotLoggedIn.to_string(), "Not logged in");
+    assert_eq!(
+        BlockListError::CannotBlockSelf.to_string(),
+        "Cannot block yourself"
+    );
+
+    let blocked: FriendRequestError = BlockListError::CannotBlockSelf.into();
+    assert_eq!(
+        blocked.to_string(),
+        "Block list error: Cannot block yourself"
+    );
+}
+
 /// Test FriendsError display messages.
 #[test]
 fn test_friends_error_display() {
@@ -271,3 +271,168 @@ fn test_sharing_guard_missing_mentions() {
     );
     assert!(matches!(result, ShareValidation::Allowed));
 }
+
+/// Test validation of cross-posting to several feeds at once.
+///
+/// The post is treated as written for the most private target,
+/// so posting it to a less private feed too is validated as a share.
+#[test]
+fn test_sharing_guard_validate_cross_post() {
+    use matrix_sdk::ruma::OwnedRoomId;
+
+    let public: OwnedRoomId = "!public:example.org".try_into().unwrap();
+    let friends: OwnedRoomId = "!friends:example.org".try_into().unwrap();
+    let close: OwnedRoomId = "!close:example.org".try_into().unwrap();
+
+    // A single target is always allowed
+    let result = SharingGuard::validate_cross_post(&[(&close, PrivacyLevel::CloseFriends)]);
+    assert!(matches!(result, ShareValidation::Allowed));
+
+    // Public + Friends requires confirmation
+    let result = SharingGuard::validate_cross_post(&[
+        (&public, PrivacyLevel::Public),
+        (&friends, PrivacyLevel::Friends),
+    ]);
+    assert!(matches!(
+        result,
+        ShareValidation::RequiresConfirmation { .. }
+    ));
+
+    // A blocked target takes precedence over one requiring confirmation
+    let result = SharingGuard::validate_cross_post(&[
+        (&public, PrivacyLevel::Public),
+        (&friends, PrivacyLevel::Friends),
+        (&close, PrivacyLevel::CloseFriends),
+    ]);
+    assert!(matches!(result, ShareValidation::BlockedPrivacyLeak { .. }));
+}
+
+/// Test that feed privacy levels map to the matching sharing privacy levels.
+#[test]
+fn test_privacy_level_from_feed_privacy() {
+    use robrix::social::feed_room::FeedPrivacy;
+
+    assert_eq!(
+        PrivacyLevel::from(FeedPrivacy::Public),
+        PrivacyLevel::Public
+    );
+    assert_eq!(
+        PrivacyLevel::from(FeedPrivacy::Friends),
+        PrivacyLevel::Friends
+    );
+    assert_eq!(
+        PrivacyLevel::from(FeedPrivacy::CloseFriends),
+        PrivacyLevel::CloseFriends
+    );
+}
+
+/// Test that room privacy levels are inferred from feeds and room state.
+#[test]
+fn test_privacy_level_from_room_state() {
+    use matrix_sdk::ruma::events::room::{history_visibility::HistoryVisibility, join_rules::JoinRule};
+    use robrix::social::feed_room::FeedPrivacy;
+
+    // Feeds get the level of their feed, since friends and close friends feeds
+    // use the same join rules
+    assert_eq!(
+        PrivacyLevel::from_room_state(
+            Some(FeedPrivacy::Friends),
+            false,
+            Some(&JoinRule::Invite),
+            Some(&HistoryVisibility::Shared),
+        ),
+        PrivacyLevel::Friends
+    );
+    assert_eq!(
+        PrivacyLevel::from_room_state(
+            Some(FeedPrivacy::CloseFriends),
+            false,
+            Some(&JoinRule::Invite),
+            Some(&HistoryVisibility::Shared),
+        ),
+        PrivacyLevel::CloseFriends
+    );
+
+    // ...unless their state makes them more public
+    assert_eq!(
+        PrivacyLevel::from_room_state(
+            Some(FeedPrivacy::CloseFriends),
+            false,
+            Some(&JoinRule::Public),
+            Some(&HistoryVisibility::Shared),
+        ),
+        PrivacyLevel::Public
+    );
+    assert_eq!(
+        PrivacyLevel::from_room_state(
+            Some(FeedPrivacy::Friends),
+            false,
+            Some(&JoinRule::Invite),
+            Some(&HistoryVisibility::WorldReadable),
+        ),
+        PrivacyLevel::Public
+    );
+
+    // Direct chats are private
+    assert_eq!(
+        PrivacyLevel::from_room_state(None, true, Some(&JoinRule::Invite), None),
+        PrivacyLevel::Private
+    );
+
+    // Anyone can read a world-readable room, even if it's invite-only
+    assert_eq!(
+        PrivacyLevel::from_room_state(
+            None,
+            false,
+            Some(&JoinRule::Invite),
+            Some(&HistoryVisibility::WorldReadable),
+        ),
+        PrivacyLevel::Public
+    );
+
+    assert_eq!(
+        PrivacyLevel::from_room_state(None, false, Some(&JoinRule::Public), None),
+        PrivacyLevel::Public
+    );
+
+    // Invite-only group chats are private
+    assert_eq!(
+        PrivacyLevel::from_room_state(
+            None,
+            false,
+            Some(&JoinRule::Invite),
+            Some(&HistoryVisibility::Shared),
+        ),
+        PrivacyLevel::Private
+    );
+
+    // Rooms with unknown join rules are treated as private
+    assert_eq!(
+        PrivacyLevel::from_room_state(None, false, None, None),
+        PrivacyLevel::Private
+    );
+}
+
+/// Test that a post keeps its intended audience when it is in a wider room.
+#[test]
+fn test_privacy_level_for_post() {
+    use robrix_social_events::visibility::PostVisibility;
+
+    assert_eq!(
+        PrivacyLevel::for_post(PrivacyLevel::Friends, None),
+        PrivacyLevel::Friends
+    );
+    assert_eq!(
+        PrivacyLevel::for_post(PrivacyLevel::Friends, Some(PostVisibility::CloseFriends)),
+        PrivacyLevel::CloseFriends
+    );
+    // A post can't be made more public than its room
+    assert_eq!(
+        PrivacyLevel::for_post(PrivacyLevel::Friends, Some(PostVisibility::Public)),
+        PrivacyLevel::Friends
+    );
+
+    // Sharing a close friends post found in a friends feed is blocked
+    let source = PrivacyLevel::for_post(PrivacyLevel::Friends, Some(PostVisibility::CloseFriends));
+    assert!(!source.can_share_to(PrivacyLevel::Friends));
+}